@@ -0,0 +1,203 @@
+//! Wallets traded from one process
+//!
+//! Every account has its own client, executor and risk limits; trades and
+//! snapshots are tagged with the account ID. A config without
+//! `[[polymarket.accounts]]` is a single account named `default`.
+
+use crate::client::PolymarketClient;
+use crate::config::{AccountConfig, PolymarketConfig, RiskConfig, StrategyConfig};
+use crate::error::Result;
+use crate::executor::{Executor, ExecutorSettings};
+use crate::storage::Database;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// One wallet with its executor
+pub struct Account {
+    pub id: String,
+    pub client: Arc<PolymarketClient>,
+    pub executor: Arc<Executor>,
+    /// Capital allotted to the bot, if less than the whole wallet
+    pub capital: Option<Decimal>,
+}
+
+impl Account {
+    /// Build the client and executor of one configured account
+    pub async fn connect(
+        polymarket: &PolymarketConfig,
+        risk: &RiskConfig,
+        account: &AccountConfig,
+        db: Arc<Database>,
+        settings: &ExecutorSettings,
+    ) -> Result<Self> {
+        let client = Arc::new(PolymarketClient::new(polymarket.for_account(account)).await?);
+        let executor = Executor::new(client.clob.clone(), account.risk.apply(risk))
+            .with_executed_signals(db)
+            .with_account(account.id.clone())
+            .with_settings(settings);
+
+        Ok(Self {
+            id: account.id.clone(),
+            client,
+            executor: Arc::new(executor),
+            capital: account.capital,
+        })
+    }
+
+    /// Balance positions are sized off: the wallet balance capped at the
+    /// allotted capital
+    pub fn trading_balance(&self, wallet_balance: Decimal) -> Decimal {
+        match self.capital {
+            Some(capital) => wallet_balance.min(capital),
+            None => wallet_balance,
+        }
+    }
+}
+
+/// All configured accounts, in config order
+pub struct Accounts {
+    accounts: Vec<Account>,
+}
+
+impl Accounts {
+    /// Connect every configured account; `risk` is the global `[risk]`
+    pub async fn connect(
+        polymarket: &PolymarketConfig,
+        risk: &RiskConfig,
+        db: Arc<Database>,
+        settings: &ExecutorSettings,
+    ) -> Result<Self> {
+        let mut accounts = Vec::new();
+        for account in polymarket.account_configs() {
+            accounts.push(Account::connect(polymarket, risk, &account, db.clone(), settings).await?);
+        }
+        Ok(Self::new(accounts))
+    }
+
+    /// Panics without any account
+    pub fn new(accounts: Vec<Account>) -> Self {
+        assert!(!accounts.is_empty(), "at least one account is required");
+        Self { accounts }
+    }
+
+    /// First account, also used for market data
+    pub fn primary(&self) -> &Account {
+        &self.accounts[0]
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Account> {
+        self.accounts.iter().find(|a| a.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Never true; there is always a primary account
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.accounts.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    pub fn executors(&self) -> Vec<Arc<Executor>> {
+        self.accounts.iter().map(|a| a.executor.clone()).collect()
+    }
+
+    /// Accounts `strategy` trades on under the `[strategy.accounts]` allowlists
+    pub fn for_strategy<'a>(
+        &'a self,
+        config: &'a StrategyConfig,
+        strategy: &'a str,
+    ) -> impl Iterator<Item = &'a Account> + 'a {
+        self.accounts.iter().filter(move |a| config.trades_on(strategy, &a.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RiskOverrides;
+    use rust_decimal_macros::dec;
+
+    /// Well-formed throwaway key; nothing is signed in these tests
+    const TEST_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn polymarket(accounts: Vec<AccountConfig>) -> PolymarketConfig {
+        PolymarketConfig {
+            clob_url: "http://localhost:1".to_string(),
+            gamma_url: "http://localhost:1".to_string(),
+            data_api_url: "http://localhost:1".to_string(),
+            private_key: Arc::new(TEST_KEY.into()),
+            key_source: Default::default(),
+            key_file: None,
+            keyring_service: "polymarket-bot".to_string(),
+            funder_address: None,
+            chain_id: 137,
+            signature_type: 0,
+            accounts,
+        }
+    }
+
+    fn account(id: &str, capital: Option<Decimal>) -> AccountConfig {
+        AccountConfig {
+            id: id.to_string(),
+            private_key: Arc::new(TEST_KEY.into()),
+            key_file: None,
+            funder_address: None,
+            signature_type: None,
+            capital,
+            risk: RiskOverrides::default(),
+        }
+    }
+
+    async fn db() -> (Arc<Database>, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::connect(dir.path().join("test.db")).await.unwrap());
+        (db, dir)
+    }
+
+    #[tokio::test]
+    async fn test_single_account_config_unchanged() {
+        let (db, _dir) = db().await;
+        let accounts = Accounts::connect(&polymarket(Vec::new()), &RiskConfig::default(), db, &ExecutorSettings::default())
+            .await
+            .unwrap();
+
+        assert_eq!(accounts.ids(), vec!["default"]);
+        let primary = accounts.primary();
+        assert_eq!(primary.executor.account_id(), Some("default"));
+        assert_eq!(primary.trading_balance(dec!(1234)), dec!(1234));
+    }
+
+    #[tokio::test]
+    async fn test_strategy_allowlists_route_to_accounts() {
+        let (db, _dir) = db().await;
+        let config = polymarket(vec![account("personal", None), account("fund", Some(dec!(500)))]);
+        let accounts = Accounts::connect(&config, &RiskConfig::default(), db, &ExecutorSettings::default()).await.unwrap();
+        let mut strategy = StrategyConfig::default();
+        strategy
+            .accounts
+            .insert("crypto_hf".to_string(), vec!["personal".to_string()]);
+
+        let ids = |name| {
+            accounts
+                .for_strategy(&strategy, name)
+                .map(|a| a.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("crypto_hf"), vec!["personal"]);
+        assert_eq!(ids("llm"), vec!["personal", "fund"]);
+
+        let fund = accounts.get("FUND").unwrap();
+        assert_eq!(fund.trading_balance(dec!(2000)), dec!(500));
+        assert_eq!(fund.trading_balance(dec!(300)), dec!(300));
+        assert!(accounts.get("other").is_none());
+    }
+}
@@ -0,0 +1,638 @@
+//! Everything known about one market, in one place
+//!
+//! A [`MarketDossier`] gathers a market's book depth and spread, daily price
+//! history and realized volatility, volume trend, time to resolution,
+//! category, our positions and resting orders on it, comparable markets
+//! (the rest of its event, or questions worded alike) and the edge left
+//! after slippage and fees at a few order sizes. [`MarketDossierBuilder`]
+//! takes each source separately, so callers add what they have;
+//! [`gather`] fetches the usual ones.
+
+use super::minhash::MinHasher;
+use crate::client::mock::ClobClientTrait;
+use crate::client::{GammaClient, OrderBook};
+use crate::config::FeeSchedule;
+use crate::error::Result;
+use crate::fees::{FeeModel, FeeRole, PolymarketFees};
+use crate::orderbook::{OrderBookAnalyzer, OrderBookSnapshot, OrderType, TradeSide};
+use crate::storage::{Database, OpenPosition};
+use crate::types::{Market, Side, Trade};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Order sizes, in USDC, the net edge is worked out at
+pub const EDGE_SIZES_USDC: [Decimal; 3] = [dec!(100), dec!(500), dec!(2000)];
+
+/// Question similarity from which another market counts as comparable
+pub const COMPARABLE_MIN_SIMILARITY: f64 = 0.3;
+
+/// Comparable markets listed at most
+const MAX_COMPARABLES: usize = 5;
+
+/// Depth is counted within this distance of the best price
+const DEPTH_BAND: Decimal = dec!(0.05);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutcomePrice {
+    pub outcome: String,
+    pub price: Decimal,
+}
+
+/// YES book at the time of the dossier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BookSummary {
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    pub spread: Option<Decimal>,
+    /// Shares bid within 5c of the best bid
+    pub bid_depth: Decimal,
+    /// Shares offered within 5c of the best ask
+    pub ask_depth: Decimal,
+    pub bid_levels: usize,
+    pub ask_levels: usize,
+}
+
+/// Daily closing YES prices, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PriceHistory {
+    pub last_7d: Vec<Decimal>,
+    pub last_30d: Vec<Decimal>,
+    /// Standard deviation of the daily changes over 30 days, in
+    /// probability points; needs at least three days
+    pub realized_volatility: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolumeTrend {
+    pub volume_24h: Decimal,
+    pub volume_1wk: Decimal,
+    /// Last day's volume over the week's daily average; above 1 is picking up
+    pub ratio: Option<Decimal>,
+}
+
+/// Shares we hold of one of the market's tokens
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeldPosition {
+    pub account_id: Option<String>,
+    pub outcome: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+}
+
+/// One of our orders resting on the market
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestingOrderView {
+    pub order_id: String,
+    pub account_id: Option<String>,
+    pub outcome: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Another market to check this one's price against
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparableMarket {
+    pub id: String,
+    pub question: String,
+    pub yes_price: Option<Decimal>,
+    /// Part of the same event
+    pub same_event: bool,
+    /// Estimated Jaccard similarity of the questions
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FairValue {
+    pub probability: Decimal,
+    pub confidence: Decimal,
+}
+
+/// Cost of taking `size_usdc` of YES off the book, and the edge left if a
+/// fair value is known. Buys when the fair value is above the mid (or
+/// unknown), sells otherwise.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SizedEdge {
+    pub size_usdc: Decimal,
+    pub side: Side,
+    /// Average fill price; `None` when the book is empty on that side
+    pub avg_price: Option<Decimal>,
+    /// Fraction of the order the book could fill
+    pub filled_pct: Decimal,
+    /// Slippage from the best price plus the taker fee, per share
+    pub cost_per_share: Option<Decimal>,
+    /// Fair value minus the average price (the other way for sells), less fees
+    pub net_edge: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketDossier {
+    pub market_id: String,
+    pub question: String,
+    pub category: String,
+    pub outcomes: Vec<OutcomePrice>,
+    pub volume: Decimal,
+    pub liquidity: Decimal,
+    pub end_date: Option<DateTime<Utc>>,
+    pub hours_to_resolution: Option<i64>,
+    pub book: Option<BookSummary>,
+    pub history: PriceHistory,
+    pub volume_trend: Option<VolumeTrend>,
+    pub positions: Vec<HeldPosition>,
+    pub open_orders: Vec<RestingOrderView>,
+    pub comparables: Vec<ComparableMarket>,
+    pub fair_value: Option<FairValue>,
+    pub edge_at_size: Vec<SizedEdge>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl MarketDossier {
+    pub fn builder(market: Market) -> MarketDossierBuilder {
+        MarketDossierBuilder {
+            market,
+            book: None,
+            daily_prices: Vec::new(),
+            volumes: None,
+            positions: Vec::new(),
+            open_orders: Vec::new(),
+            candidates: Vec::new(),
+            fair_value: None,
+            fees: Arc::new(PolymarketFees::new(FeeSchedule::default())),
+        }
+    }
+}
+
+/// Collects a market's data sources for a [`MarketDossier`]
+pub struct MarketDossierBuilder {
+    market: Market,
+    book: Option<OrderBook>,
+    daily_prices: Vec<(DateTime<Utc>, Decimal)>,
+    volumes: Option<(Decimal, Decimal)>,
+    positions: Vec<OpenPosition>,
+    open_orders: Vec<Trade>,
+    candidates: Vec<Market>,
+    fair_value: Option<FairValue>,
+    fees: Arc<dyn FeeModel>,
+}
+
+impl MarketDossierBuilder {
+    pub fn market(&self) -> &Market {
+        &self.market
+    }
+
+    /// The YES token's order book
+    pub fn book(mut self, book: OrderBook) -> Self {
+        self.book = Some(book);
+        self
+    }
+
+    /// Timed YES prices, e.g. daily snapshots or hourly history; the last
+    /// price of each UTC day is its close
+    pub fn prices(mut self, prices: impl IntoIterator<Item = (DateTime<Utc>, Decimal)>) -> Self {
+        self.daily_prices.extend(prices);
+        self
+    }
+
+    /// Volume over the last 24 hours and the last week
+    pub fn volumes(mut self, volume_24h: Decimal, volume_1wk: Decimal) -> Self {
+        self.volumes = Some((volume_24h, volume_1wk));
+        self
+    }
+
+    /// Open positions; those on other markets are ignored
+    pub fn positions(mut self, positions: &[OpenPosition]) -> Self {
+        let market_id = &self.market.id;
+        self.positions.extend(positions.iter().filter(|p| &p.market_id == market_id).cloned());
+        self
+    }
+
+    /// Resting orders; those on other markets are ignored
+    pub fn open_orders(mut self, orders: &[Trade]) -> Self {
+        let market_id = &self.market.id;
+        self.open_orders.extend(orders.iter().filter(|t| &t.market_id == market_id).cloned());
+        self
+    }
+
+    /// Markets to pick comparables from: the rest of the event, and any
+    /// whose question is similar enough
+    pub fn comparables(mut self, candidates: impl IntoIterator<Item = Market>) -> Self {
+        self.candidates.extend(candidates);
+        self
+    }
+
+    /// Model probability of YES, for the net edge
+    pub fn fair_value(mut self, probability: Decimal, confidence: Decimal) -> Self {
+        self.fair_value = Some(FairValue { probability, confidence });
+        self
+    }
+
+    pub fn fees(mut self, fees: Arc<dyn FeeModel>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn build(self, now: DateTime<Utc>) -> MarketDossier {
+        let market = &self.market;
+        let outcome_of = |token_id: &str| {
+            market
+                .outcomes
+                .iter()
+                .find(|o| o.token_id == token_id)
+                .map_or_else(|| token_id.to_string(), |o| o.outcome.clone())
+        };
+
+        let analyzer = self.book.as_ref().map(|book| {
+            let mut analyzer = OrderBookAnalyzer::new();
+            analyzer.process_snapshot(OrderBookSnapshot::from_book(book, now.timestamp_millis().max(0) as u64));
+            analyzer
+        });
+        let edge_at_size = match (&self.book, &analyzer) {
+            (Some(book), Some(analyzer)) => EDGE_SIZES_USDC
+                .iter()
+                .map(|&size| sized_edge(&market.id, book, analyzer, size, self.fair_value, self.fees.as_ref()))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        MarketDossier {
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            category: market.market_category().name().to_string(),
+            outcomes: market
+                .outcomes
+                .iter()
+                .map(|o| OutcomePrice { outcome: o.outcome.clone(), price: o.price })
+                .collect(),
+            volume: market.volume,
+            liquidity: market.liquidity,
+            end_date: market.end_date,
+            hours_to_resolution: market.end_date.map(|end| (end - now).num_hours().max(0)),
+            book: self.book.as_ref().map(book_summary),
+            history: price_history(&self.daily_prices, now),
+            volume_trend: self.volumes.map(|(day, week)| VolumeTrend {
+                volume_24h: day,
+                volume_1wk: week,
+                ratio: (week > Decimal::ZERO).then(|| day / (week / dec!(7))),
+            }),
+            positions: self
+                .positions
+                .iter()
+                .map(|p| HeldPosition {
+                    account_id: p.account_id.clone(),
+                    outcome: outcome_of(&p.token_id),
+                    size: p.size,
+                    avg_price: p.avg_price,
+                })
+                .collect(),
+            open_orders: self
+                .open_orders
+                .iter()
+                .map(|t| RestingOrderView {
+                    order_id: t.order_id.clone(),
+                    account_id: t.account_id.clone(),
+                    outcome: outcome_of(&t.token_id),
+                    side: t.side,
+                    price: t.price,
+                    size: t.size,
+                })
+                .collect(),
+            comparables: comparables(market, &self.candidates),
+            fair_value: self.fair_value,
+            edge_at_size,
+            generated_at: now,
+        }
+    }
+}
+
+fn book_summary(book: &OrderBook) -> BookSummary {
+    let (best_bid, best_ask) = (book.best_bid(), book.best_ask());
+    let depth = |levels: &[crate::client::OrderBookLevel], best: Option<Decimal>| -> Decimal {
+        let Some(best) = best else {
+            return Decimal::ZERO;
+        };
+        levels.iter().filter(|l| (l.price - best).abs() <= DEPTH_BAND).map(|l| l.size).sum()
+    };
+    BookSummary {
+        best_bid,
+        best_ask,
+        spread: best_bid.zip(best_ask).map(|(bid, ask)| ask - bid),
+        bid_depth: depth(&book.bids, best_bid),
+        ask_depth: depth(&book.asks, best_ask),
+        bid_levels: book.bids.len(),
+        ask_levels: book.asks.len(),
+    }
+}
+
+/// Closing prices of the last 7 and 30 days, and the volatility of the latter
+fn price_history(prices: &[(DateTime<Utc>, Decimal)], now: DateTime<Utc>) -> PriceHistory {
+    let mut closes: BTreeMap<NaiveDate, (DateTime<Utc>, Decimal)> = BTreeMap::new();
+    for &(at, price) in prices.iter().filter(|(at, _)| *at <= now) {
+        let close = closes.entry(at.date_naive()).or_insert((at, price));
+        if at >= close.0 {
+            *close = (at, price);
+        }
+    }
+    let since = |days: i64| -> Vec<Decimal> {
+        let from = (now - Duration::days(days)).date_naive();
+        closes.range(from..).map(|(_, (_, price))| *price).collect()
+    };
+    let last_30d = since(30);
+    let changes: Vec<Decimal> = last_30d.windows(2).map(|w| w[1] - w[0]).collect();
+    let realized_volatility = (changes.len() >= 2).then(|| {
+        let n = Decimal::from(changes.len());
+        let mean = changes.iter().sum::<Decimal>() / n;
+        let variance = changes.iter().map(|c| (c - mean) * (c - mean)).sum::<Decimal>() / (n - Decimal::ONE);
+        variance.sqrt().unwrap_or_default()
+    });
+    PriceHistory { last_7d: since(7), last_30d, realized_volatility }
+}
+
+fn sized_edge(
+    market_id: &str,
+    book: &OrderBook,
+    analyzer: &OrderBookAnalyzer,
+    size_usdc: Decimal,
+    fair_value: Option<FairValue>,
+    fees: &dyn FeeModel,
+) -> SizedEdge {
+    let buy = match (fair_value, book.midpoint()) {
+        (Some(fair), Some(mid)) => fair.probability >= mid,
+        _ => true,
+    };
+    let (side, trade_side, best) = if buy {
+        (Side::Buy, TradeSide::Buy, book.best_ask())
+    } else {
+        (Side::Sell, TradeSide::Sell, book.best_bid())
+    };
+    let unfilled = SizedEdge {
+        size_usdc,
+        side,
+        avg_price: None,
+        filled_pct: Decimal::ZERO,
+        cost_per_share: None,
+        net_edge: None,
+    };
+    let Some(best) = best.filter(|p| *p > Decimal::ZERO) else {
+        return unfilled;
+    };
+
+    let shares = size_usdc / best;
+    let fill = analyzer.simulate_fill(trade_side, shares, OrderType::Market);
+    let Some(avg_price) = fill.avg_price else {
+        return unfilled;
+    };
+    let fee = fees.fee_per_share(market_id, side, avg_price, FeeRole::Taker);
+    SizedEdge {
+        avg_price: Some(avg_price),
+        filled_pct: fill.filled / shares * Decimal::ONE_HUNDRED,
+        cost_per_share: Some((avg_price - best).abs() + fee),
+        net_edge: fair_value.map(|fair| match side {
+            Side::Buy => fair.probability - avg_price - fee,
+            Side::Sell => avg_price - fair.probability - fee,
+        }),
+        ..unfilled
+    }
+}
+
+/// The rest of `market`'s event first, then the most similar questions
+fn comparables(market: &Market, candidates: &[Market]) -> Vec<ComparableMarket> {
+    let hasher = MinHasher::new(128);
+    let signature = hasher.signature(&market.question);
+    let mut seen = std::collections::HashSet::new();
+    let mut found: Vec<ComparableMarket> = candidates
+        .iter()
+        .filter(|c| c.id != market.id && seen.insert(c.id.clone()))
+        .filter_map(|c| {
+            let same_event = market.event_id.is_some() && c.event_id == market.event_id;
+            let similarity = MinHasher::estimate_similarity(&signature, &hasher.signature(&c.question));
+            (same_event || similarity >= COMPARABLE_MIN_SIMILARITY).then(|| ComparableMarket {
+                id: c.id.clone(),
+                question: c.question.clone(),
+                yes_price: c.yes_price(),
+                same_event,
+                similarity,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| b.same_event.cmp(&a.same_event).then(b.similarity.total_cmp(&a.similarity)));
+    found.truncate(MAX_COMPARABLES);
+    found
+}
+
+/// Start a dossier on `market_id` from the exchange and, if given, our
+/// database: the YES book, daily price snapshots (hourly history when there
+/// are none), volumes, the event's other markets and the top markets as
+/// comparables, and our positions and resting orders
+pub async fn gather(
+    market_id: &str,
+    gamma: &GammaClient,
+    clob: &impl ClobClientTrait,
+    db: Option<&Database>,
+    now: DateTime<Utc>,
+) -> Result<MarketDossierBuilder> {
+    let market = gamma.get_market(market_id).await?;
+    let yes_token = market
+        .outcomes
+        .iter()
+        .find(|o| o.outcome.eq_ignore_ascii_case("yes"))
+        .map(|o| o.token_id.clone());
+    let mut builder = MarketDossier::builder(market.clone());
+
+    if let Some(token) = &yes_token {
+        match clob.get_order_book(token).await {
+            Ok(book) => builder = builder.book(book),
+            Err(e) => tracing::debug!("No book for {}: {}", market_id, e),
+        }
+    }
+
+    let mut snapshots = Vec::new();
+    if let Some(db) = db {
+        for snapshot in db.get_price_snapshots(now - Duration::days(31), now).await? {
+            if snapshot.market_id == market.id {
+                snapshots.push((snapshot.taken_at, snapshot.yes_price));
+            }
+        }
+        builder = builder
+            .positions(&db.get_open_positions().await?)
+            .open_orders(&db.get_resting_trades().await?);
+    }
+    if snapshots.len() < 2 {
+        if let Some(token) = &yes_token {
+            snapshots = gamma.get_hourly_price_history(token).await.unwrap_or_default();
+        }
+    }
+    builder = builder.prices(snapshots);
+
+    match gamma.get_market_volumes(market_id).await {
+        Ok((day, week)) => builder = builder.volumes(day, week),
+        Err(e) => tracing::debug!("No volumes for {}: {}", market_id, e),
+    }
+
+    let mut candidates = match &market.event_id {
+        Some(event_id) => gamma.get_event(event_id).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+    candidates.extend(gamma.get_top_markets(200).await.unwrap_or_default());
+    Ok(builder.comparables(candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::OrderBookLevel;
+    use crate::types::{Outcome, TradeStatus};
+
+    fn market(id: &str, question: &str, event_id: Option<&str>, yes: Decimal) -> Market {
+        Market {
+            id: id.to_string(),
+            question: question.to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(50000),
+            liquidity: dec!(10000),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: yes },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: Decimal::ONE - yes },
+            ],
+            active: true,
+            closed: false,
+            event_id: event_id.map(str::to_string),
+            neg_risk: false,
+            category: Some("Politics".to_string()),
+            order_constraints: None,
+        }
+    }
+
+    fn level(price: Decimal, size: Decimal) -> OrderBookLevel {
+        OrderBookLevel { price, size }
+    }
+
+    /// Bids 0.48 x 500, 0.45 x 1000; asks 0.50 x 400, 0.52 x 600, 0.60 x 5000
+    fn book() -> OrderBook {
+        OrderBook {
+            bids: vec![level(dec!(0.48), dec!(500)), level(dec!(0.45), dec!(1000))],
+            asks: vec![level(dec!(0.50), dec!(400)), level(dec!(0.52), dec!(600)), level(dec!(0.60), dec!(5000))],
+        }
+    }
+
+    #[test]
+    fn test_dossier_prices_edge_net_of_slippage_and_fees() {
+        let now = Utc::now();
+        let fees = Arc::new(PolymarketFees::new(FeeSchedule { taker_fee_bps: dec!(100), maker_fee_bps: Decimal::ZERO }));
+        let dossier = MarketDossier::builder(market("m1", "Will the Fed cut rates in June?", None, dec!(0.49)))
+            .book(book())
+            .fair_value(dec!(0.60), dec!(0.8))
+            .fees(fees)
+            .build(now);
+
+        let summary = dossier.book.as_ref().unwrap();
+        assert_eq!(summary.spread, Some(dec!(0.02)));
+        assert_eq!((summary.bid_depth, summary.ask_depth), (dec!(1500), dec!(1000)));
+        assert_eq!(dossier.category, "politics");
+
+        let edges = &dossier.edge_at_size;
+        assert_eq!(edges.len(), 3);
+        // $100 is 200 shares, all at the best ask; 1% of 0.50 in fees
+        assert_eq!(edges[0].side, Side::Buy);
+        assert_eq!(edges[0].avg_price, Some(dec!(0.50)));
+        assert_eq!(edges[0].net_edge, Some(dec!(0.095)));
+        // Larger orders walk the book and keep less of the edge
+        assert!(edges[1].net_edge.unwrap() < edges[0].net_edge.unwrap());
+        assert!(edges[2].net_edge.unwrap() < edges[1].net_edge.unwrap());
+        assert_eq!(edges[2].filled_pct, dec!(100));
+
+        // Below the mid the edge is in selling into the bids
+        let short = MarketDossier::builder(market("m1", "Will the Fed cut rates in June?", None, dec!(0.49)))
+            .book(crate::client::OrderBook { bids: vec![level(dec!(0.48), dec!(100))], asks: Vec::new() })
+            .fair_value(dec!(0.30), dec!(0.8))
+            .build(now);
+        assert_eq!(short.edge_at_size[0].side, Side::Buy);
+        let short = MarketDossier::builder(market("m1", "Will the Fed cut rates in June?", None, dec!(0.49)))
+            .book(book())
+            .fair_value(dec!(0.30), dec!(0.8))
+            .build(now);
+        assert_eq!(short.edge_at_size[0].side, Side::Sell);
+        assert_eq!(short.edge_at_size[0].net_edge, Some(dec!(0.18)));
+        // $2000 at 0.48 is more than the bids hold
+        assert!(short.edge_at_size[2].filled_pct < dec!(100));
+    }
+
+    #[test]
+    fn test_dossier_history_positions_and_comparables() {
+        let now = "2026-06-30T18:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let day = |days_ago: i64, hour: u32, price: Decimal| {
+            let date = (now - Duration::days(days_ago)).date_naive();
+            (date.and_hms_opt(hour, 0, 0).unwrap().and_utc(), price)
+        };
+        let prices = vec![
+            day(40, 12, dec!(0.10)),
+            day(20, 12, dec!(0.40)),
+            day(3, 9, dec!(0.49)),
+            day(3, 12, dec!(0.50)),
+            day(2, 12, dec!(0.52)),
+            day(0, 12, dec!(0.51)),
+        ];
+        let target = market("m1", "Will Biden win the 2024 US presidential election?", Some("e1"), dec!(0.51));
+        let held = OpenPosition {
+            account_id: None,
+            market_id: "m1".to_string(),
+            token_id: "m1-no".to_string(),
+            size: dec!(100),
+            avg_price: dec!(0.45),
+            strategy_name: None,
+        };
+        let elsewhere = OpenPosition { market_id: "m9".to_string(), ..held.clone() };
+        let order = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "m1-yes".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.47),
+            size: dec!(50),
+            fee: Decimal::ZERO,
+            timestamp: now,
+            status: TradeStatus::Resting,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        let candidates = vec![
+            market("m2", "Will Trump win the 2024 US presidential election?", Some("e1"), dec!(0.47)),
+            market("m3", "Will Kamala Harris win the 2024 US presidential election?", None, dec!(0.05)),
+            market("m4", "Will it snow in Lisbon this winter?", None, dec!(0.02)),
+            market("m5", "Which party wins the House?", Some("e1"), dec!(0.60)),
+            target.clone(),
+        ];
+        let dossier = MarketDossier::builder(target)
+            .prices(prices)
+            .volumes(dec!(14000), dec!(49000))
+            .positions(&[held, elsewhere])
+            .open_orders(&[order])
+            .comparables(candidates)
+            .build(now);
+
+        assert_eq!(dossier.history.last_7d, vec![dec!(0.50), dec!(0.52), dec!(0.51)]);
+        assert_eq!(dossier.history.last_30d, vec![dec!(0.40), dec!(0.50), dec!(0.52), dec!(0.51)]);
+        assert!(dossier.history.realized_volatility.unwrap() > Decimal::ZERO);
+        assert_eq!(dossier.volume_trend.as_ref().unwrap().ratio, Some(dec!(2)));
+        assert_eq!(dossier.positions.len(), 1);
+        assert_eq!(dossier.positions[0].outcome, "No");
+        assert_eq!(dossier.open_orders[0].outcome, "Yes");
+        assert!(dossier.book.is_none() && dossier.edge_at_size.is_empty());
+
+        let ids: Vec<&str> = dossier.comparables.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(&ids[..2], ["m2", "m5"]);
+        assert!(ids.contains(&"m3"));
+        assert!(!ids.contains(&"m4") && !ids.contains(&"m1"));
+
+        let json = serde_json::to_value(&dossier).unwrap();
+        assert_eq!(json["comparables"][0]["same_event"], true);
+    }
+}
@@ -0,0 +1,334 @@
+//! Per-market microstructure profile
+//!
+//! Execution tactics should differ by market: some have tight books kept by
+//! fast market makers, others are deserts. Book samples taken while quoting
+//! and the fill latency of our resting orders are aggregated once a day into
+//! a [`MarketProfile`] per market, stored in `market_profiles`. The executor
+//! asks [`MarketProfiles`] how to work an order and how large its slices may
+//! be; the signal generator asks how much edge a market needs.
+
+use crate::client::OrderBook;
+use crate::config::MarketProfileConfig;
+use crate::error::Result;
+use crate::orderbook::MmActivityLevel;
+use crate::storage::Database;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A market's top of book at one moment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookSample {
+    pub market_id: String,
+    pub taken_at: DateTime<Utc>,
+    pub spread: Decimal,
+    /// Mean size of the best bid and best ask
+    pub top_depth: Decimal,
+    /// Market maker activity the order book analyzer saw, when it ran
+    pub mm_activity: Option<MmActivityLevel>,
+}
+
+impl BookSample {
+    /// Sample of a book with both sides; `None` for a one-sided book
+    pub fn from_book(
+        market_id: &str,
+        book: &OrderBook,
+        mm_activity: Option<MmActivityLevel>,
+        taken_at: DateTime<Utc>,
+    ) -> Option<Self> {
+        let (bid, ask) = (book.bids.first()?, book.asks.first()?);
+        Some(Self {
+            market_id: market_id.to_string(),
+            taken_at,
+            spread: ask.price - bid.price,
+            top_depth: (bid.size + ask.size) / Decimal::TWO,
+            mm_activity,
+        })
+    }
+}
+
+/// How an order at market is worked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStyle {
+    /// Take the best opposing price right away
+    Taker,
+    /// Join the near touch for a while, then take what didn't fill
+    MakerThenTaker,
+}
+
+impl std::fmt::Display for ExecutionStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Taker => "taker",
+            Self::MakerThenTaker => "maker then taker",
+        })
+    }
+}
+
+/// Microstructure statistics of one market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketProfile {
+    pub market_id: String,
+    pub median_spread: Decimal,
+    pub median_top_depth: Decimal,
+    /// Top-of-book changes per minute between consecutive samples
+    pub quote_updates_per_min: Decimal,
+    /// Median time our resting orders took to fill
+    pub median_fill_latency_secs: Option<i64>,
+    /// Most often seen market maker activity
+    pub mm_activity: Option<MmActivityLevel>,
+    pub samples: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn median(mut values: Vec<Decimal>) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / Decimal::TWO
+    } else {
+        values[mid]
+    })
+}
+
+impl MarketProfile {
+    /// Profile of `samples` (oldest first) and the fill latencies of our
+    /// resting orders; `None` without samples
+    pub fn aggregate(
+        market_id: &str,
+        samples: &[BookSample],
+        fill_latencies_secs: &[i64],
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let median_spread = median(samples.iter().map(|s| s.spread).collect())?;
+        let median_top_depth = median(samples.iter().map(|s| s.top_depth).collect())?;
+
+        let changes = samples
+            .windows(2)
+            .filter(|w| (w[0].spread, w[0].top_depth) != (w[1].spread, w[1].top_depth))
+            .count();
+        let minutes = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) => Decimal::from((last.taken_at - first.taken_at).num_seconds()) / Decimal::from(60),
+            _ => Decimal::ZERO,
+        };
+        let quote_updates_per_min = if minutes > Decimal::ZERO {
+            (Decimal::from(changes) / minutes).round_dp(2)
+        } else {
+            Decimal::ZERO
+        };
+
+        let mut latencies = fill_latencies_secs.to_vec();
+        latencies.sort_unstable();
+        let median_fill_latency_secs = latencies.get(latencies.len() / 2).copied();
+
+        let mut activity: Vec<(MmActivityLevel, usize)> = Vec::new();
+        for level in samples.iter().filter_map(|s| s.mm_activity) {
+            match activity.iter_mut().find(|(l, _)| *l == level) {
+                Some((_, count)) => *count += 1,
+                None => activity.push((level, 1)),
+            }
+        }
+        let mm_activity = activity.into_iter().max_by_key(|(_, count)| *count).map(|(level, _)| level);
+
+        Some(Self {
+            market_id: market_id.to_string(),
+            median_spread,
+            median_top_depth,
+            quote_updates_per_min,
+            median_fill_latency_secs,
+            mm_activity,
+            samples: samples.len() as u32,
+            updated_at: now,
+        })
+    }
+
+    fn is_wide(&self, config: &MarketProfileConfig) -> bool {
+        self.median_spread >= config.wide_spread
+    }
+
+    /// Maker first on wide-spread markets, where crossing gives away the edge
+    pub fn execution_style(&self, config: &MarketProfileConfig) -> ExecutionStyle {
+        if self.is_wide(config) {
+            ExecutionStyle::MakerThenTaker
+        } else {
+            ExecutionStyle::Taker
+        }
+    }
+
+    /// Largest order (shares) to send at once: a share of the usual
+    /// top-of-book depth, but no less than the smallest slice
+    pub fn max_slice(&self, config: &MarketProfileConfig) -> Decimal {
+        (self.median_top_depth * config.max_slice_depth_fraction).max(config.min_slice_shares)
+    }
+
+    /// `base` plus a share of the median spread on wide-spread markets
+    pub fn min_edge(&self, base: Decimal, config: &MarketProfileConfig) -> Decimal {
+        if self.is_wide(config) {
+            base + self.median_spread * config.spread_edge_fraction
+        } else {
+            base
+        }
+    }
+}
+
+/// The latest profile of every market, shared by the executor and signal
+/// generator. Profiles built from fewer than `min_samples` samples are kept
+/// but not acted on.
+#[derive(Debug, Default)]
+pub struct MarketProfiles {
+    config: MarketProfileConfig,
+    profiles: RwLock<HashMap<String, MarketProfile>>,
+}
+
+impl MarketProfiles {
+    pub fn new(config: MarketProfileConfig) -> Self {
+        Self {
+            config,
+            profiles: RwLock::default(),
+        }
+    }
+
+    pub fn config(&self) -> &MarketProfileConfig {
+        &self.config
+    }
+
+    /// Replace the profiles of the markets in `profiles`
+    pub fn set(&self, profiles: impl IntoIterator<Item = MarketProfile>) {
+        let mut current = self.profiles.write();
+        for profile in profiles {
+            current.insert(profile.market_id.clone(), profile);
+        }
+    }
+
+    /// Profile of `market_id`, enough samples or not
+    pub fn get(&self, market_id: &str) -> Option<MarketProfile> {
+        self.profiles.read().get(market_id).cloned()
+    }
+
+    fn usable(&self, market_id: &str) -> Option<MarketProfile> {
+        self.get(market_id).filter(|p| p.samples >= self.config.min_samples)
+    }
+
+    /// How to work an order at market; taker without a usable profile
+    pub fn execution_style(&self, market_id: &str) -> ExecutionStyle {
+        self.usable(market_id)
+            .map_or(ExecutionStyle::Taker, |p| p.execution_style(&self.config))
+    }
+
+    /// Largest order to send at once, when the market has a usable profile
+    pub fn max_slice(&self, market_id: &str) -> Option<Decimal> {
+        self.usable(market_id).map(|p| p.max_slice(&self.config))
+    }
+
+    /// Edge a signal on `market_id` needs, given `base`
+    pub fn min_edge(&self, market_id: &str, base: Decimal) -> Decimal {
+        self.usable(market_id).map_or(base, |p| p.min_edge(base, &self.config))
+    }
+
+    /// Pick up the profiles stored by earlier runs
+    pub async fn load(&self, db: &Database) -> Result<usize> {
+        let profiles = db.load_market_profiles().await?;
+        let count = profiles.len();
+        self.set(profiles);
+        Ok(count)
+    }
+
+    /// Rebuild every sampled market's profile from the last `window_days`,
+    /// store them and drop older samples; returns how many were built
+    pub async fn refresh(&self, db: &Database, now: DateTime<Utc>) -> Result<usize> {
+        let since = now - Duration::days(self.config.window_days);
+        let mut profiles = Vec::new();
+        for market_id in db.sampled_markets(since).await? {
+            let samples = db.get_book_samples(&market_id, since).await?;
+            let latencies = db.get_fill_latencies(&market_id, since).await?;
+            if let Some(profile) = MarketProfile::aggregate(&market_id, &samples, &latencies, now) {
+                db.save_market_profile(&profile).await?;
+                profiles.push(profile);
+            }
+        }
+        db.prune_book_samples(since).await?;
+
+        let count = profiles.len();
+        self.set(profiles);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::OrderBookLevel;
+    use rust_decimal_macros::dec;
+
+    fn sample(secs: i64, spread: Decimal, top_depth: Decimal) -> BookSample {
+        BookSample {
+            market_id: "m1".to_string(),
+            taken_at: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            spread,
+            top_depth,
+            mm_activity: Some(MmActivityLevel::Normal),
+        }
+    }
+
+    #[test]
+    fn test_sample_of_book() {
+        let book = OrderBook {
+            bids: vec![OrderBookLevel { price: dec!(0.45), size: dec!(100) }],
+            asks: vec![OrderBookLevel { price: dec!(0.48), size: dec!(300) }],
+        };
+        let sample = BookSample::from_book("m1", &book, None, Utc::now()).unwrap();
+        assert_eq!((sample.spread, sample.top_depth), (dec!(0.03), dec!(200)));
+        assert!(BookSample::from_book("m1", &OrderBook { bids: vec![], asks: book.asks }, None, Utc::now()).is_none());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        // Four samples a minute apart, the book changing twice
+        let samples = [
+            sample(0, dec!(0.02), dec!(100)),
+            sample(60, dec!(0.02), dec!(100)),
+            sample(120, dec!(0.06), dec!(40)),
+            sample(180, dec!(0.04), dec!(40)),
+        ];
+        let profile = MarketProfile::aggregate("m1", &samples, &[30, 10, 90], Utc::now()).unwrap();
+        assert_eq!(profile.median_spread, dec!(0.03));
+        assert_eq!(profile.median_top_depth, dec!(70));
+        assert_eq!(profile.quote_updates_per_min, dec!(0.67));
+        assert_eq!(profile.median_fill_latency_secs, Some(30));
+        assert_eq!(profile.mm_activity, Some(MmActivityLevel::Normal));
+        assert_eq!(profile.samples, 4);
+        assert!(MarketProfile::aggregate("m1", &[], &[], Utc::now()).is_none());
+    }
+
+    #[test]
+    fn test_wide_spread_markets_are_worked_maker_first() {
+        let config = MarketProfileConfig { min_samples: 2, ..Default::default() };
+        let profiles = MarketProfiles::new(config);
+        let tight = MarketProfile::aggregate("m1", &[sample(0, dec!(0.01), dec!(400)), sample(60, dec!(0.01), dec!(400))], &[], Utc::now()).unwrap();
+        let wide = MarketProfile { market_id: "m2".to_string(), median_spread: dec!(0.08), median_top_depth: dec!(4), ..tight.clone() };
+        let thin = MarketProfile { market_id: "m3".to_string(), samples: 1, ..wide.clone() };
+        profiles.set([tight, wide, thin]);
+
+        assert_eq!(profiles.execution_style("m1"), ExecutionStyle::Taker);
+        assert_eq!(profiles.max_slice("m1"), Some(dec!(200)));
+        assert_eq!(profiles.min_edge("m1", dec!(0.05)), dec!(0.05));
+
+        assert_eq!(profiles.execution_style("m2"), ExecutionStyle::MakerThenTaker);
+        // Half of 4 shares is below the smallest slice
+        assert_eq!(profiles.max_slice("m2"), Some(dec!(5)));
+        assert_eq!(profiles.min_edge("m2", dec!(0.05)), dec!(0.09));
+
+        // Too few samples, and no profile at all
+        for market_id in ["m3", "m4"] {
+            assert_eq!(profiles.execution_style(market_id), ExecutionStyle::Taker);
+            assert_eq!(profiles.max_slice(market_id), None);
+            assert_eq!(profiles.min_edge(market_id, dec!(0.05)), dec!(0.05));
+        }
+    }
+}
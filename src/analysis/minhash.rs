@@ -0,0 +1,154 @@
+//! MinHash signatures for fuzzy text similarity
+//!
+//! A signature is the minimum of each of `num_hashes` random hash functions
+//! over a text's shingles. The fraction of positions where two signatures
+//! agree estimates the Jaccard similarity of their shingle sets.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Mersenne prime 2^61 - 1 for the universal hash family
+const PRIME: u64 = (1 << 61) - 1;
+
+/// Texts with fewer words than this are shingled by character 3-grams
+const MIN_WORDS_FOR_WORD_SHINGLES: usize = 4;
+
+/// Fixed seed so signatures are comparable across restarts
+const SEED: u64 = 0x5EED_1591;
+
+/// Computes MinHash signatures
+#[derive(Debug, Clone)]
+pub struct MinHasher {
+    pub num_hashes: usize,
+    /// `(a, b)` coefficients of each `(a * x + b) mod p` projection
+    coefficients: Vec<(u64, u64)>,
+}
+
+impl MinHasher {
+    pub fn new(num_hashes: usize) -> Self {
+        let num_hashes = num_hashes.max(1);
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let coefficients = (0..num_hashes)
+            .map(|_| (rng.random_range(1..PRIME), rng.random_range(0..PRIME)))
+            .collect();
+        Self {
+            num_hashes,
+            coefficients,
+        }
+    }
+
+    /// Signature of `text`; texts without any shingles get all `u32::MAX`
+    pub fn signature(&self, text: &str) -> Vec<u32> {
+        let shingles = shingles(text);
+        let mut signature = vec![u32::MAX; self.num_hashes];
+
+        for shingle in &shingles {
+            let x = hash_str(shingle) % PRIME;
+            for (slot, &(a, b)) in signature.iter_mut().zip(&self.coefficients) {
+                let h = ((a as u128 * x as u128 + b as u128) % PRIME as u128) as u32;
+                if h < *slot {
+                    *slot = h;
+                }
+            }
+        }
+
+        signature
+    }
+
+    /// Fraction of matching positions, an estimate of Jaccard similarity
+    pub fn estimate_similarity(a: &[u32], b: &[u32]) -> f64 {
+        let len = a.len().min(b.len());
+        if len == 0 {
+            return 0.0;
+        }
+        let matching = a.iter().zip(b).filter(|(x, y)| x == y).count();
+        matching as f64 / len as f64
+    }
+}
+
+impl Default for MinHasher {
+    fn default() -> Self {
+        Self::new(128)
+    }
+}
+
+/// 2-word shingles of the normalized text, or character 3-grams for short texts
+fn shingles(text: &str) -> HashSet<String> {
+    let normalized = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>();
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+
+    if words.len() >= MIN_WORDS_FOR_WORD_SHINGLES {
+        return words.windows(2).map(|w| w.join(" ")).collect();
+    }
+
+    let chars: Vec<char> = words.join(" ").chars().collect();
+    if chars.is_empty() {
+        return HashSet::new();
+    }
+    if chars.len() < 3 {
+        return HashSet::from([chars.iter().collect()]);
+    }
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+fn hash_str(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts_match_exactly() {
+        let hasher = MinHasher::new(64);
+        let a = hasher.signature("BTC breaking out above 70k, targets 75k next");
+        let b = hasher.signature("BTC breaking out above 70k, targets 75k next");
+        assert_eq!(a.len(), 64);
+        assert_eq!(MinHasher::estimate_similarity(&a, &b), 1.0);
+    }
+
+    #[test]
+    fn test_rephrased_text_is_similar_and_unrelated_is_not() {
+        let hasher = MinHasher::new(256);
+        let original = hasher.signature(
+            "SEC approves spot ethereum ETF applications from BlackRock and Fidelity today",
+        );
+        let rephrased = hasher.signature(
+            "BREAKING: SEC approves spot ethereum ETF applications from BlackRock and Fidelity",
+        );
+        let unrelated = hasher.signature("Solana network halted again after validator bug in latest release");
+
+        let similar = MinHasher::estimate_similarity(&original, &rephrased);
+        let different = MinHasher::estimate_similarity(&original, &unrelated);
+        assert!(similar > 0.6, "similar = {}", similar);
+        assert!(different < 0.1, "different = {}", different);
+    }
+
+    #[test]
+    fn test_short_texts_use_character_shingles() {
+        let hasher = MinHasher::new(128);
+        let a = hasher.signature("BTC pump");
+        let b = hasher.signature("btc pump!");
+        let c = hasher.signature("ETH dump");
+        assert_eq!(MinHasher::estimate_similarity(&a, &b), 1.0);
+        assert!(MinHasher::estimate_similarity(&a, &c) < 0.5);
+    }
+
+    #[test]
+    fn test_signatures_are_deterministic_across_hashers() {
+        let a = MinHasher::new(32).signature("whales accumulating sol on every dip");
+        let b = MinHasher::new(32).signature("whales accumulating sol on every dip");
+        assert_eq!(a, b);
+        assert_eq!(MinHasher::estimate_similarity(&[], &a), 0.0);
+    }
+}
@@ -5,8 +5,18 @@
 //! - Position sizing patterns
 //! - Exit strategies
 //! - Market selection criteria
-
+//! - Intraday and day-of-week volatility ([`seasonality`])
+//! - Correlation networks and clusters of markets ([`network`])
+//! - Everything known about a single market ([`dossier`])
+//! - Per-market microstructure profiles for execution ([`market_profile`])
+
+pub mod dossier;
+pub mod market_profile;
+pub mod minhash;
+pub mod network;
 pub mod pattern;
+pub mod seasonality;
+pub mod sentiment;
 pub mod trader_profile;
 
 #[cfg(test)]
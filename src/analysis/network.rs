@@ -0,0 +1,206 @@
+//! Correlation network of the markets in a portfolio
+//!
+//! Markets are nodes and correlations stronger than a threshold are edges.
+//! [`MarketCorrelationNetwork::to_d3_json`] exports the graph for a
+//! force-directed view; [`MarketCorrelationNetwork::find_clusters`] groups
+//! markets connected through such edges, which
+//! [`MarketCorrelationNetwork::cluster_constraints`] turns into sectors the
+//! optimizer caps, so one cluster of markets moving together cannot carry
+//! the whole portfolio.
+
+use crate::portfolio::{PortfolioConstraints, PortfolioOptimizer};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::json;
+
+/// Edges weaker than this are left out of the exported graph by default
+pub const DEFAULT_THRESHOLD: Decimal = dec!(0.5);
+
+/// Pairwise correlations of a set of markets
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketCorrelationNetwork {
+    pub symbols: Vec<String>,
+    pub correlation_matrix: Vec<Vec<Decimal>>,
+    /// Display name per symbol; the symbol itself by default
+    pub labels: Vec<String>,
+    /// Market category per symbol; "other" by default
+    pub categories: Vec<String>,
+    /// |correlation| above which [`Self::to_d3_json`] draws an edge
+    pub threshold: Decimal,
+}
+
+impl MarketCorrelationNetwork {
+    pub fn new(symbols: Vec<String>, correlation_matrix: Vec<Vec<Decimal>>) -> Self {
+        Self {
+            labels: symbols.clone(),
+            categories: vec!["other".to_string(); symbols.len()],
+            symbols,
+            correlation_matrix,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// The network of the optimizer's assets and their correlations
+    pub fn from_portfolio_optimizer(optimizer: &PortfolioOptimizer) -> Self {
+        Self::new(optimizer.symbols().to_vec(), optimizer.correlation_matrix().to_vec())
+    }
+
+    pub fn with_threshold(mut self, threshold: Decimal) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Name and categorize the node of `symbol`, if it is in the network
+    pub fn with_node_info(mut self, symbol: &str, label: &str, category: &str) -> Self {
+        if let Some(i) = self.symbols.iter().position(|s| s == symbol) {
+            self.labels[i] = label.to_string();
+            self.categories[i] = category.to_string();
+        }
+        self
+    }
+
+    /// Pairs `(i, j, correlation)` with `i < j` and |correlation| above
+    /// `threshold`
+    pub fn edges(&self, threshold: Decimal) -> Vec<(usize, usize, Decimal)> {
+        let n = self.symbols.len();
+        let mut edges = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                let correlation = self.correlation(i, j);
+                if correlation.abs() > threshold {
+                    edges.push((i, j, correlation));
+                }
+            }
+        }
+        edges
+    }
+
+    fn correlation(&self, i: usize, j: usize) -> Decimal {
+        self.correlation_matrix
+            .get(i)
+            .and_then(|row| row.get(j))
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// `{ nodes: [{id, label, category}], links: [{source, target, weight}] }`
+    /// with links for correlations stronger than [`Self::threshold`]
+    pub fn to_d3_json(&self) -> serde_json::Value {
+        let nodes: Vec<_> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(i, symbol)| json!({ "id": symbol, "label": self.labels[i], "category": self.categories[i] }))
+            .collect();
+        let links: Vec<_> = self
+            .edges(self.threshold)
+            .into_iter()
+            .map(|(i, j, correlation)| {
+                json!({ "source": self.symbols[i], "target": self.symbols[j], "weight": correlation })
+            })
+            .collect();
+        json!({ "nodes": nodes, "links": links })
+    }
+
+    /// Groups of markets connected by correlations stronger than
+    /// `threshold`, every market in exactly one; each group is in index
+    /// order and groups are ordered by their first market
+    pub fn find_clusters(&self, threshold: Decimal) -> Vec<Vec<usize>> {
+        let n = self.symbols.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for (i, j, _) in self.edges(threshold) {
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut clusters: Vec<Vec<usize>> = Vec::new();
+        let mut cluster_of_root = vec![usize::MAX; n];
+        for i in 0..n {
+            let r = root(&mut parent, i);
+            if cluster_of_root[r] == usize::MAX {
+                cluster_of_root[r] = clusters.len();
+                clusters.push(Vec::new());
+            }
+            clusters[cluster_of_root[r]].push(i);
+        }
+        clusters
+    }
+
+    /// `constraints` with each cluster at `threshold` as a sector capped at
+    /// `1 / n_clusters` of the portfolio
+    pub fn cluster_constraints(&self, threshold: Decimal, mut constraints: PortfolioConstraints) -> PortfolioConstraints {
+        let clusters = self.find_clusters(threshold);
+        if clusters.is_empty() {
+            return constraints;
+        }
+        let cap = Decimal::ONE / Decimal::from(clusters.len());
+        for (k, members) in clusters.iter().enumerate() {
+            let sector = format!("cluster_{}", k);
+            for &i in members {
+                constraints.asset_sectors.insert(self.symbols[i].clone(), sector.clone());
+            }
+            constraints.sector_limits.insert(sector, cap);
+        }
+        constraints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a and b move together, c against a, d on its own
+    fn network() -> MarketCorrelationNetwork {
+        let symbols = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let correlations = vec![
+            vec![dec!(1), dec!(0.9), dec!(-0.75), dec!(0.1)],
+            vec![dec!(0.9), dec!(1), dec!(-0.6), dec!(0.2)],
+            vec![dec!(-0.75), dec!(-0.6), dec!(1), dec!(0)],
+            vec![dec!(0.1), dec!(0.2), dec!(0), dec!(1)],
+        ];
+        MarketCorrelationNetwork::new(symbols, correlations)
+    }
+
+    #[test]
+    fn test_d3_export_keeps_strong_links() {
+        let graph = network()
+            .with_threshold(dec!(0.7))
+            .with_node_info("a", "Will BTC hit $100k?", "crypto")
+            .with_node_info("missing", "ignored", "other")
+            .to_d3_json();
+
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 4);
+        assert_eq!(graph["nodes"][0], json!({"id": "a", "label": "Will BTC hit $100k?", "category": "crypto"}));
+        assert_eq!(graph["nodes"][3], json!({"id": "d", "label": "d", "category": "other"}));
+        let links = graph["links"].as_array().unwrap();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0]["source"], "a");
+        assert_eq!(links[0]["target"], "b");
+        assert_eq!(links[1]["target"], "c");
+        assert_eq!(links[1]["weight"], json!(dec!(-0.75)));
+    }
+
+    #[test]
+    fn test_clusters_follow_strong_correlations_either_way() {
+        let network = network();
+        assert_eq!(network.find_clusters(dec!(0.5)), vec![vec![0, 1, 2], vec![3]]);
+        assert_eq!(network.find_clusters(dec!(0.8)), vec![vec![0, 1], vec![2], vec![3]]);
+        assert_eq!(network.find_clusters(dec!(0.95)).len(), 4);
+        assert_eq!(network.find_clusters(dec!(0.05)), vec![vec![0, 1, 2, 3]]);
+
+        let constraints = network.cluster_constraints(dec!(0.8), PortfolioConstraints::default());
+        assert_eq!(constraints.sector_limits.len(), 3);
+        assert!(constraints.sector_limits.values().all(|&cap| cap == Decimal::ONE / dec!(3)));
+        assert_eq!(constraints.asset_sectors["a"], constraints.asset_sectors["b"]);
+        assert_ne!(constraints.asset_sectors["a"], constraints.asset_sectors["c"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Intraday and day-of-week volatility patterns
+//!
+//! Prediction market prices move more during US market hours and less at
+//! weekends. [`SeasonalityModel`] learns how much more from price history,
+//! as realized volatility per hour of day and per day of week relative to
+//! the average, so positions can be sized for constant risk through the
+//! day: [`SeasonalityModel::size_factor`] is the inverse of the expected
+//! volatility at a time.
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Price changes below which [`SeasonalityModel::train_pooled`] gives up:
+/// a week of hourly prices
+pub const MIN_OBSERVATIONS: usize = 24 * 7;
+
+/// Volatility multiplier below which sizing stops scaling up
+const MIN_VOL_MULTIPLIER: Decimal = dec!(0.25);
+
+/// Realized volatility per hour of day (UTC) and day of week (Monday
+/// first), as multiples of the average; each array averages 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonalityModel {
+    pub hourly_vol_multipliers: [Decimal; 24],
+    pub dow_vol_multipliers: [Decimal; 7],
+}
+
+impl Default for SeasonalityModel {
+    /// No seasonality: every multiplier 1
+    fn default() -> Self {
+        Self {
+            hourly_vol_multipliers: [Decimal::ONE; 24],
+            dow_vol_multipliers: [Decimal::ONE; 7],
+        }
+    }
+}
+
+impl SeasonalityModel {
+    /// Pattern of Polymarket as a whole, for markets without enough
+    /// history: busiest from the US open to the evening, quietest in the
+    /// US night and at weekends
+    pub fn global() -> Self {
+        Self {
+            hourly_vol_multipliers: [
+                dec!(0.95), dec!(0.90), dec!(0.85), dec!(0.80), dec!(0.78), dec!(0.76),
+                dec!(0.76), dec!(0.78), dec!(0.82), dec!(0.85), dec!(0.90), dec!(0.95),
+                dec!(1.00), dec!(1.15), dec!(1.30), dec!(1.30), dec!(1.25), dec!(1.20),
+                dec!(1.20), dec!(1.20), dec!(1.15), dec!(1.10), dec!(1.05), dec!(1.00),
+            ],
+            dow_vol_multipliers: [
+                dec!(1.05), dec!(1.10), dec!(1.10), dec!(1.10), dec!(1.05), dec!(0.80), dec!(0.80),
+            ],
+        }
+    }
+
+    /// Learn the pattern from one evenly sampled price series
+    pub fn train(price_history: &[(DateTime<Utc>, Decimal)]) -> Self {
+        Self::fit(&price_changes(price_history))
+    }
+
+    /// Learn the pattern from several markets' price series together, or
+    /// `None` with fewer than [`MIN_OBSERVATIONS`] price changes among them
+    pub fn train_pooled(histories: &[Vec<(DateTime<Utc>, Decimal)>]) -> Option<Self> {
+        let changes: Vec<_> = histories.iter().flat_map(|history| price_changes(history)).collect();
+        (changes.len() >= MIN_OBSERVATIONS).then(|| Self::fit(&changes))
+    }
+
+    /// Per-bucket RMS of `changes`, over the mean of the buckets with any;
+    /// empty buckets are average
+    fn fit(changes: &[(DateTime<Utc>, Decimal)]) -> Self {
+        let mut hourly = [(Decimal::ZERO, 0u32); 24];
+        let mut daily = [(Decimal::ZERO, 0u32); 7];
+        for (at, change) in changes {
+            let squared = change * change;
+            let hour = &mut hourly[at.hour() as usize];
+            hour.0 += squared;
+            hour.1 += 1;
+            let day = &mut daily[at.weekday().num_days_from_monday() as usize];
+            day.0 += squared;
+            day.1 += 1;
+        }
+        Self {
+            hourly_vol_multipliers: normalized(hourly),
+            dow_vol_multipliers: normalized(daily),
+        }
+    }
+
+    /// Expected volatility at `timestamp` relative to the average
+    pub fn vol_multiplier(&self, timestamp: DateTime<Utc>) -> Decimal {
+        self.hourly_vol_multipliers[timestamp.hour() as usize]
+            * self.dow_vol_multipliers[timestamp.weekday().num_days_from_monday() as usize]
+    }
+
+    /// Position size multiplier for constant risk at `timestamp`: the
+    /// inverse of [`Self::vol_multiplier`], which is floored so quiet hours
+    /// size up at most fourfold
+    pub fn size_factor(&self, timestamp: DateTime<Utc>) -> Decimal {
+        Decimal::ONE / self.vol_multiplier(timestamp).max(MIN_VOL_MULTIPLIER)
+    }
+}
+
+/// Each price change in a series, at the time of its later price
+fn price_changes(history: &[(DateTime<Utc>, Decimal)]) -> Vec<(DateTime<Utc>, Decimal)> {
+    let mut history = history.to_vec();
+    history.sort_by_key(|(at, _)| *at);
+    history.windows(2).map(|w| (w[1].0, w[1].1 - w[0].1)).collect()
+}
+
+fn normalized<const N: usize>(buckets: [(Decimal, u32); N]) -> [Decimal; N] {
+    let vols = buckets.map(|(sum, count)| {
+        (count > 0).then(|| (sum / Decimal::from(count)).sqrt().unwrap_or(Decimal::ZERO))
+    });
+    let observed: Vec<Decimal> = vols.iter().flatten().copied().collect();
+    let mean = observed.iter().sum::<Decimal>() / Decimal::from(observed.len().max(1));
+    if mean.is_zero() {
+        return [Decimal::ONE; N];
+    }
+    vols.map(|vol| vol.map_or(Decimal::ONE, |vol| vol / mean))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// Monday 2024-06-03 00:00 UTC
+    fn monday() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap()
+    }
+
+    fn mean(values: &[Decimal]) -> Decimal {
+        values.iter().sum::<Decimal>() / Decimal::from(values.len())
+    }
+
+    #[test]
+    fn test_train_finds_the_busy_hours() {
+        // Four weeks of hourly prices, swinging 3¢ an hour from 14:00 to
+        // 20:00 UTC and 1¢ otherwise
+        let mut price = dec!(0.50);
+        let mut up = true;
+        let history: Vec<_> = (0..24 * 28)
+            .map(|h| {
+                let at = monday() + Duration::hours(h);
+                let step = if (14..20).contains(&at.hour()) { dec!(0.03) } else { dec!(0.01) };
+                price += if up { step } else { -step };
+                up = !up;
+                (at, price)
+            })
+            .collect();
+
+        let model = SeasonalityModel::train(&history);
+
+        assert_eq!(mean(&model.hourly_vol_multipliers).round_dp(6), Decimal::ONE);
+        assert_eq!(mean(&model.dow_vol_multipliers).round_dp(6), Decimal::ONE);
+        let busy = model.hourly_vol_multipliers[15];
+        let quiet = model.hourly_vol_multipliers[3];
+        assert_eq!((busy / quiet).round_dp(6), dec!(3));
+        // Every day looks the same
+        assert!(model.dow_vol_multipliers.iter().all(|m| (m - Decimal::ONE).abs() < dec!(0.01)));
+
+        let tuesday_afternoon = monday() + Duration::hours(24 + 15);
+        assert_eq!(model.vol_multiplier(tuesday_afternoon), busy * model.dow_vol_multipliers[1]);
+        assert!(model.size_factor(tuesday_afternoon) < Decimal::ONE);
+        assert!(model.size_factor(monday() + Duration::hours(3)) > Decimal::ONE);
+    }
+
+    #[test]
+    fn test_global_model_averages_one() {
+        let model = SeasonalityModel::global();
+        assert_eq!(mean(&model.hourly_vol_multipliers), Decimal::ONE);
+        assert_eq!(mean(&model.dow_vol_multipliers), Decimal::ONE);
+        // Saturday night is quieter than a weekday afternoon
+        let saturday_night = monday() + Duration::hours(5 * 24 + 5);
+        let wednesday_afternoon = monday() + Duration::hours(2 * 24 + 15);
+        assert!(model.vol_multiplier(saturday_night) < model.vol_multiplier(wednesday_afternoon));
+    }
+
+    #[test]
+    fn test_flat_or_short_history() {
+        let flat: Vec<_> = (0..48).map(|h| (monday() + Duration::hours(h), dec!(0.5))).collect();
+        assert_eq!(SeasonalityModel::train(&flat), SeasonalityModel::default());
+        assert_eq!(SeasonalityModel::default().size_factor(monday()), Decimal::ONE);
+
+        // Two days of two markets: too little to learn from
+        let swinging: Vec<_> = (0..48)
+            .map(|h| (monday() + Duration::hours(h), dec!(0.5) + Decimal::new(h % 2, 2)))
+            .collect();
+        assert!(SeasonalityModel::train_pooled(&[swinging.clone(), swinging.clone()]).is_none());
+        let weeks = vec![swinging; 4];
+        assert!(SeasonalityModel::train_pooled(&weeks).is_some());
+    }
+}
@@ -0,0 +1,265 @@
+//! Social sentiment per token from ingested signals
+//!
+//! The ingester turns chatter into [`ParsedSignal`]s about tokens, but the
+//! market models only see market data. [`SentimentIndex`] keeps the recent
+//! signals per token and scores them as bullish minus bearish, each weighted
+//! by its confidence and its authors' trust and halving every
+//! `half_life_mins`, so the prompt can say what the crowd thinks and the
+//! ensemble can lean on it a little.
+
+use crate::config::SentimentConfig;
+use crate::ingester::{ParsedSignal, SignalDirection};
+use crate::types::Market;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Names a market may use for a token instead of its symbol
+const ALIASES: &[(&str, &str)] = &[
+    ("BTC", "bitcoin"),
+    ("ETH", "ethereum"),
+    ("SOL", "solana"),
+    ("XRP", "ripple"),
+    ("DOGE", "dogecoin"),
+];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// +1 bullish, -1 bearish, 0 neutral
+    direction: f64,
+    weight: f64,
+    sources: usize,
+    timestamp: DateTime<Utc>,
+}
+
+/// Decayed sentiment on a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentimentScore {
+    /// From -1 (all bearish) to +1 (all bullish)
+    pub score: Decimal,
+    /// Raw messages behind the signals
+    pub sources: usize,
+    /// Oldest signal still counted
+    pub oldest: DateTime<Utc>,
+}
+
+impl SentimentScore {
+    /// e.g. "+0.60 (12 sources, last 2h)" as of `now`
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let age = now - self.oldest;
+        let span = if age < Duration::hours(1) {
+            format!("{}m", age.num_minutes().max(1))
+        } else {
+            format!("{}h", (age.num_minutes() + 59) / 60)
+        };
+        format!("{:+.2} ({} sources, last {})", self.score, self.sources, span)
+    }
+
+    /// `probability` moved toward the sentiment by at most `max_adjustment`
+    pub fn adjust(&self, probability: Decimal, max_adjustment: Decimal) -> Decimal {
+        (probability + self.score * max_adjustment).clamp(Decimal::new(1, 2), Decimal::new(99, 2))
+    }
+}
+
+/// Recent signals per token, scored with exponential decay
+#[derive(Debug, Default)]
+pub struct SentimentIndex {
+    config: SentimentConfig,
+    by_token: RwLock<HashMap<String, Vec<Entry>>>,
+}
+
+impl SentimentIndex {
+    pub fn new(config: SentimentConfig) -> Self {
+        Self { config, by_token: RwLock::default() }
+    }
+
+    /// Most a full-strength score moves a probability
+    pub fn max_adjustment(&self) -> Decimal {
+        Decimal::try_from(self.config.max_adjustment).unwrap_or_default().max(Decimal::ZERO)
+    }
+
+    pub fn record(&self, signal: &ParsedSignal) {
+        let direction = match signal.direction {
+            SignalDirection::Bullish => 1.0,
+            SignalDirection::Bearish => -1.0,
+            SignalDirection::Neutral => 0.0,
+        };
+        let trust = if signal.sources.is_empty() {
+            1.0
+        } else {
+            signal.sources.iter().map(|s| s.author_trust).sum::<f64>() / signal.sources.len() as f64
+        };
+        let entry = Entry {
+            direction,
+            weight: (signal.confidence * trust).max(0.0),
+            sources: signal.sources.len().max(1),
+            timestamp: signal.timestamp,
+        };
+        self.by_token.write().entry(signal.token.to_uppercase()).or_default().push(entry);
+    }
+
+    /// Sentiment on `token` as of `now`; `None` without recent signals.
+    /// Signals past `max_age_hours` are forgotten. The score is the decayed
+    /// net direction over the undecayed weight, so it fades toward zero as
+    /// the signals age.
+    pub fn score(&self, token: &str, now: DateTime<Utc>) -> Option<SentimentScore> {
+        let max_age = Duration::seconds((self.config.max_age_hours * 3600.0) as i64);
+        let mut by_token = self.by_token.write();
+        let entries = by_token.get_mut(&token.to_uppercase())?;
+        entries.retain(|e| now - e.timestamp <= max_age);
+
+        let total_weight: f64 = entries.iter().map(|e| e.weight).sum();
+        if entries.is_empty() || total_weight <= 0.0 {
+            return None;
+        }
+        let half_life_secs = (self.config.half_life_mins * 60.0).max(1.0);
+        let net: f64 = entries
+            .iter()
+            .map(|e| {
+                let age_secs = (now - e.timestamp).num_seconds().max(0) as f64;
+                e.direction * e.weight * 0.5f64.powf(age_secs / half_life_secs)
+            })
+            .sum();
+
+        Some(SentimentScore {
+            score: Decimal::try_from(net / total_weight).unwrap_or_default().round_dp(4),
+            sources: entries.iter().map(|e| e.sources).sum(),
+            oldest: entries.iter().map(|e| e.timestamp).min()?,
+        })
+    }
+
+    /// Tokens with signals that `market`'s question mentions, by symbol or
+    /// by name
+    pub fn tokens_for(&self, market: &Market) -> Vec<String> {
+        let question = market.question.to_lowercase();
+        let words: Vec<&str> = question.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+        let mut tokens: Vec<String> = self
+            .by_token
+            .read()
+            .keys()
+            .filter(|token| {
+                let symbol = token.to_lowercase();
+                words.contains(&symbol.as_str())
+                    || ALIASES
+                        .iter()
+                        .any(|(t, name)| t.eq_ignore_ascii_case(token) && words.contains(name))
+            })
+            .cloned()
+            .collect();
+        tokens.sort();
+        tokens
+    }
+
+    /// Sentiment on the token `market` is about; with several, the one with
+    /// the most sources
+    pub fn for_market(&self, market: &Market, now: DateTime<Utc>) -> Option<SentimentScore> {
+        self.tokens_for(market)
+            .iter()
+            .filter_map(|token| self.score(token, now))
+            .max_by_key(|s| s.sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingester::{ActionType, RawSignal};
+    use crate::types::TraceId;
+    use rust_decimal_macros::dec;
+
+    fn signal(token: &str, direction: SignalDirection, timestamp: DateTime<Utc>, trust: &[f64]) -> ParsedSignal {
+        ParsedSignal {
+            token: token.to_string(),
+            direction,
+            timeframe: "1h".to_string(),
+            confidence: 1.0,
+            reasoning: String::new(),
+            action_type: ActionType::Entry,
+            sources: trust
+                .iter()
+                .map(|&author_trust| RawSignal {
+                    source: "telegram".to_string(),
+                    source_id: "1".to_string(),
+                    content: String::new(),
+                    author: "a".to_string(),
+                    author_trust,
+                    timestamp,
+                    metadata: None,
+                    trace_id: TraceId::new(),
+                })
+                .collect(),
+            agg_score: 1.0,
+            timestamp,
+            trace_id: TraceId::new(),
+        }
+    }
+
+    fn market(question: &str) -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: question.to_string(),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: Vec::new(),
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn index() -> SentimentIndex {
+        SentimentIndex::new(SentimentConfig { half_life_mins: 60.0, max_age_hours: 6.0, max_adjustment: 0.05 })
+    }
+
+    #[test]
+    fn test_score_decays_with_age() {
+        let now = Utc::now();
+        let index = index();
+        index.record(&signal("btc", SignalDirection::Bullish, now, &[1.0, 1.0]));
+
+        assert_eq!(index.score("BTC", now).unwrap().score, dec!(1));
+        assert_eq!(index.score("BTC", now + Duration::hours(1)).unwrap().score, dec!(0.5));
+        assert_eq!(index.score("BTC", now + Duration::hours(2)).unwrap().score, dec!(0.25));
+        // Forgotten past the max age
+        assert!(index.score("BTC", now + Duration::hours(7)).is_none());
+    }
+
+    #[test]
+    fn test_score_weighs_direction_by_trust() {
+        let now = Utc::now();
+        let index = index();
+        index.record(&signal("ETH", SignalDirection::Bullish, now, &[0.9, 0.7]));
+        index.record(&signal("ETH", SignalDirection::Bearish, now, &[0.4]));
+        index.record(&signal("ETH", SignalDirection::Neutral, now - Duration::hours(2), &[0.4]));
+
+        let score = index.score("eth", now).unwrap();
+        // (0.8 - 0.4) / (0.8 + 0.4 + 0.4)
+        assert_eq!(score.score, dec!(0.25));
+        assert_eq!(score.sources, 4);
+        assert_eq!(score.describe(now), "+0.25 (4 sources, last 2h)");
+    }
+
+    #[test]
+    fn test_markets_resolve_tokens_by_symbol_or_name() {
+        let now = Utc::now();
+        let index = index();
+        index.record(&signal("BTC", SignalDirection::Bearish, now, &[1.0]));
+        index.record(&signal("SOL", SignalDirection::Bullish, now, &[1.0, 1.0]));
+
+        assert_eq!(index.tokens_for(&market("Will Bitcoin reach $100k?")), ["BTC"]);
+        assert_eq!(index.tokens_for(&market("BTC or SOL: which is up more?")), ["BTC", "SOL"]);
+        // Not a substring match
+        assert!(index.tokens_for(&market("Will the solstice parade happen?")).is_empty());
+        assert_eq!(index.for_market(&market("BTC or SOL: which is up more?"), now).unwrap().sources, 2);
+
+        let score = index.for_market(&market("Bitcoin above 100k?"), now).unwrap();
+        assert_eq!(score.adjust(dec!(0.5), index.max_adjustment()), dec!(0.45));
+        assert_eq!(score.adjust(dec!(0.02), index.max_adjustment()), dec!(0.01));
+    }
+}
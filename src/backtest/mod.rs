@@ -15,10 +15,12 @@
 //! println!("Sharpe: {:.2}", result.metrics.sharpe_ratio);
 //! ```
 
+use crate::clock::{Clock, SystemClock};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration};
 
 /// Backtest configuration
@@ -262,6 +264,8 @@ pub struct BacktestEngine {
     drawdown_curve: Vec<(DateTime<Utc>, Decimal)>,
     daily_returns: Vec<Decimal>,
     prev_equity: Decimal,
+    /// Time source for results not derived from price bars
+    clock: Arc<dyn Clock>,
 }
 
 impl BacktestEngine {
@@ -277,9 +281,16 @@ impl BacktestEngine {
             drawdown_curve: Vec::new(),
             daily_returns: Vec::new(),
             prev_equity: Decimal::ZERO,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Use a custom clock so runs are reproducible
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Calculate slippage based on model
     pub fn calculate_slippage(&self, order_size: Decimal, price: Decimal, volume: Decimal) -> Decimal {
         let order_value = order_size * price;
@@ -703,6 +714,7 @@ impl BacktestEngine {
         F: FnMut(&[PriceBar], usize, &HashMap<String, Position>) -> (Signal, Decimal),
     {
         if price_bars.is_empty() {
+            let now = self.clock.now();
             return BacktestResult {
                 config: self.config.clone(),
                 metrics: PerformanceMetrics::default(),
@@ -711,8 +723,8 @@ impl BacktestEngine {
                 drawdown_curve: Vec::new(),
                 daily_returns: Vec::new(),
                 final_portfolio_value: self.config.initial_capital,
-                start_date: Utc::now(),
-                end_date: Utc::now(),
+                start_date: now,
+                end_date: now,
                 trading_days: 0,
             };
         }
@@ -1220,6 +1232,20 @@ mod tests {
         assert_eq!(result.final_portfolio_value, config.initial_capital);
     }
 
+    #[test]
+    fn test_empty_backtest_uses_injected_clock() {
+        use crate::clock::ManualClock;
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut engine = BacktestEngine::new(BacktestConfig::default())
+            .with_clock(Arc::new(ManualClock::new(start)));
+
+        let result = engine.run("TEST", &[], |_, _, _| (Signal::Hold, dec!(0)));
+        assert_eq!(result.start_date, start);
+        assert_eq!(result.end_date, start);
+    }
+
     #[test]
     fn test_engine_reset() {
         let config = BacktestConfig::default();
@@ -32,6 +32,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(100000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "eth-5k".to_string(),
@@ -54,6 +58,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(80000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "fed-rate".to_string(),
@@ -76,6 +84,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(50000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "trump-approval".to_string(),
@@ -98,6 +110,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(120000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "sp500-6k".to_string(),
@@ -120,6 +136,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(45000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
     ]
 }
@@ -46,6 +46,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(100000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "eth-5k".to_string(),
@@ -68,6 +72,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(80000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "fed-rate".to_string(),
@@ -90,6 +98,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(50000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "trump-approval".to_string(),
@@ -112,6 +124,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(120000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "sp500-6k".to_string(),
@@ -134,6 +150,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(45000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         // Additional markets for more diverse testing
         Market {
@@ -157,6 +177,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(35000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "sol-500".to_string(),
@@ -179,6 +203,10 @@ fn generate_test_markets() -> Vec<Market> {
             liquidity: dec!(55000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
     ]
 }
@@ -193,6 +221,18 @@ fn optimized_strategy_config() -> StrategyConfig {
         model_update_interval_secs: 900,
         compound_enabled: true,
         compound_sqrt_scaling: true,
+        modes: std::collections::HashMap::new(),
+        high_epistemic_threshold: Decimal::new(15, 2),
+        accounts: std::collections::HashMap::new(),
+        experiment: None,
+        experiments: Default::default(),
+        bayesian_prior_strength: None,
+        regime_map: Default::default(),
+        regime_confidence_threshold: dec!(0.60),
+        max_concurrent_analyses: 4,
+        analysis_timeout_secs: 60,
+        seasonality: Default::default(),
+        scoring: Default::default(),
     }
 }
 
@@ -204,6 +244,18 @@ fn optimized_risk_config() -> RiskConfig {
         max_daily_loss_pct: dec!(0.05),   // 5% (was 10%)
         min_balance_reserve: dec!(100),
         max_open_positions: 5,            // Reduced from default
+        signal_dedup_ttl_secs: 900,
+        max_total_kelly: Decimal::ONE,
+        max_consecutive_api_failures: 5,
+        api_failure_pause_secs: 300,
+        max_positions_per_strategy: std::collections::HashMap::new(),
+        queued_signal_ttl_secs: 1800,
+        max_queued_signals: 20,
+        loss_cooldown_mins: 30,
+        loss_cooldown_override_edge: None,
+        regime_rotation_min_edge_gain: None,
+        max_heat_budget: Decimal::ZERO,
+        regime_consensus_sizing: Default::default(),
     }
 }
 
@@ -217,6 +269,18 @@ fn original_strategy_config() -> StrategyConfig {
         model_update_interval_secs: 900,
         compound_enabled: true,
         compound_sqrt_scaling: true,
+        modes: std::collections::HashMap::new(),
+        high_epistemic_threshold: Decimal::new(15, 2),
+        accounts: std::collections::HashMap::new(),
+        experiment: None,
+        experiments: Default::default(),
+        bayesian_prior_strength: None,
+        regime_map: Default::default(),
+        regime_confidence_threshold: dec!(0.60),
+        max_concurrent_analyses: 4,
+        analysis_timeout_secs: 60,
+        seasonality: Default::default(),
+        scoring: Default::default(),
     }
 }
 
@@ -227,6 +291,18 @@ fn original_risk_config() -> RiskConfig {
         max_daily_loss_pct: dec!(0.10),   // 10%
         min_balance_reserve: dec!(100),
         max_open_positions: 10,
+        signal_dedup_ttl_secs: 900,
+        max_total_kelly: Decimal::ONE,
+        max_consecutive_api_failures: 5,
+        api_failure_pause_secs: 300,
+        max_positions_per_strategy: std::collections::HashMap::new(),
+        queued_signal_ttl_secs: 1800,
+        max_queued_signals: 20,
+        loss_cooldown_mins: 30,
+        loss_cooldown_override_edge: None,
+        regime_rotation_min_edge_gain: None,
+        max_heat_budget: Decimal::ZERO,
+        regime_consensus_sizing: Default::default(),
     }
 }
 
@@ -704,7 +704,7 @@ mod tests {
     fn create_test_trader() -> LiveTrader {
         LiveTrader {
             gamma: GammaClient::new("http://test").unwrap(),
-            http: Client::new(),
+            http: polymarket_bot::utils::http_client(polymarket_bot::utils::HTTP_TIMEOUT),
             capital: 100.0,
             trades: vec![],
             hourly_trade_count: 0,
@@ -812,6 +812,10 @@ mod tests {
             end_date: Some(Utc::now() + Duration::hours(1)),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
 
         // Run ML prediction
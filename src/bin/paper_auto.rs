@@ -285,7 +285,7 @@ async fn trade_loop(
     prices: &HashMap<String, f64>,
     last_slot: &mut u64,
 ) -> anyhow::Result<()> {
-    let client = reqwest::Client::new();
+    let client = polymarket_bot::utils::http_client(polymarket_bot::utils::HTTP_TIMEOUT);
     
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)?
@@ -529,6 +529,10 @@ async fn buy_position(
         end_date: None,
         active: true,
         closed: false,
+        event_id: None,
+        neg_risk: false,
+        category: None,
+        order_constraints: None,
     };
     
     match trader.buy(&mock_market, PositionSide::Yes, amount,
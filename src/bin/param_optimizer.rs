@@ -54,6 +54,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "btc-100k-no".to_string(), price: dec!(0.55) },
             ],
             volume: dec!(500000), liquidity: dec!(100000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "eth-5k".to_string(),
@@ -65,6 +69,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "eth-5k-no".to_string(), price: dec!(0.65) },
             ],
             volume: dec!(300000), liquidity: dec!(80000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "fed-rate".to_string(),
@@ -76,6 +84,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "fed-rate-no".to_string(), price: dec!(0.40) },
             ],
             volume: dec!(200000), liquidity: dec!(50000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "trump-approval".to_string(),
@@ -87,6 +99,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "trump-approval-no".to_string(), price: dec!(0.68) },
             ],
             volume: dec!(450000), liquidity: dec!(120000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "sp500-6k".to_string(),
@@ -98,6 +114,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "sp500-6k-no".to_string(), price: dec!(0.28) },
             ],
             volume: dec!(180000), liquidity: dec!(45000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "ai-regulation".to_string(),
@@ -109,6 +129,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "ai-reg-no".to_string(), price: dec!(0.60) },
             ],
             volume: dec!(150000), liquidity: dec!(35000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "sol-500".to_string(),
@@ -120,6 +144,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "sol-500-no".to_string(), price: dec!(0.75) },
             ],
             volume: dec!(220000), liquidity: dec!(55000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
         Market {
             id: "gold-3k".to_string(),
@@ -131,6 +159,10 @@ fn generate_test_markets() -> Vec<Market> {
                 Outcome { outcome: "No".to_string(), token_id: "gold-3k-no".to_string(), price: dec!(0.45) },
             ],
             volume: dec!(280000), liquidity: dec!(70000), active: true, closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         },
     ]
 }
@@ -156,6 +188,18 @@ async fn run_simulation(params: &ParamSet, markets: &[Market], seed: u64) -> any
         model_update_interval_secs: 900,
         compound_enabled: true,
         compound_sqrt_scaling: true,
+        modes: std::collections::HashMap::new(),
+        high_epistemic_threshold: Decimal::new(15, 2),
+        accounts: std::collections::HashMap::new(),
+        experiment: None,
+        experiments: Default::default(),
+        bayesian_prior_strength: None,
+        regime_map: Default::default(),
+        regime_confidence_threshold: dec!(0.60),
+        max_concurrent_analyses: 4,
+        analysis_timeout_secs: 60,
+        seasonality: Default::default(),
+        scoring: Default::default(),
     };
     
     let risk = RiskConfig {
@@ -164,6 +208,18 @@ async fn run_simulation(params: &ParamSet, markets: &[Market], seed: u64) -> any
         max_daily_loss_pct: dec!(0.05),  // Fixed at 5%
         min_balance_reserve: dec!(100),
         max_open_positions: 5,
+        signal_dedup_ttl_secs: 900,
+        max_total_kelly: Decimal::ONE,
+        max_consecutive_api_failures: 5,
+        api_failure_pause_secs: 300,
+        max_positions_per_strategy: std::collections::HashMap::new(),
+        queued_signal_ttl_secs: 1800,
+        max_queued_signals: 20,
+        loss_cooldown_mins: 30,
+        loss_cooldown_override_edge: None,
+        regime_rotation_min_edge_gain: None,
+        max_heat_budget: Decimal::ZERO,
+        regime_consensus_sizing: Default::default(),
     };
     
     let mut sim = EnhancedDryRunSimulator::new(dec!(1000), strategy, risk)
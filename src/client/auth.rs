@@ -4,6 +4,7 @@
 //! Supports both Level 1 (EIP-712) and Level 2 (HMAC) authentication.
 
 use crate::error::{BotError, Result};
+use crate::keys::PrivateKey;
 use ethers::signers::{LocalWallet, Signer};
 use ethers::types::{Address, H256, U256};
 use ethers::utils::keccak256;
@@ -21,14 +22,9 @@ pub struct PolySigner {
 }
 
 impl PolySigner {
-    /// Create a new signer from a private key (hex string, with or without 0x prefix)
-    pub fn from_private_key(private_key: &str, chain_id: u64) -> Result<Self> {
-        let key_hex = private_key.trim_start_matches("0x");
-        let wallet: LocalWallet = key_hex
-            .parse()
-            .map_err(|e| BotError::Auth(format!("Invalid private key: {}", e)))?;
-        
-        let wallet = wallet.with_chain_id(chain_id);
+    /// Create a new signer from a private key
+    pub fn from_private_key(private_key: &PrivateKey, chain_id: u64) -> Result<Self> {
+        let wallet = private_key.wallet()?.with_chain_id(chain_id);
 
         Ok(Self { wallet, chain_id })
     }
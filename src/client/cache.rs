@@ -0,0 +1,188 @@
+//! In-memory cache of Gamma market data
+//!
+//! The scan loop, the discovery watchlist and the CLI ask Gamma for the
+//! same markets over and over. [`MarketCache`] keeps the latest copy of
+//! each market and price history for a TTL, dropping the least recently
+//! used entries beyond its capacity; see [`GammaClient::with_cache`].
+//!
+//! [`GammaClient::with_cache`]: super::GammaClient::with_cache
+
+use crate::types::Market;
+use lru::LruCache;
+use rust_decimal::Decimal;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+/// How long a cached market is served (its prices move)
+pub const DEFAULT_MARKET_TTL: Duration = Duration::from_secs(60);
+
+/// How long a cached price history is served
+pub const DEFAULT_HISTORY_TTL: Duration = Duration::from_secs(300);
+
+/// Markets (and, separately, price histories) kept at most
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// Cache lookups and evictions since the cache was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped to make room, not counting expired ones replaced
+    pub evictions: u64,
+    /// Entries currently cached
+    pub size: usize,
+}
+
+impl CacheStats {
+    /// Share of lookups served from the cache; `None` before any lookup
+    pub fn hit_rate(&self) -> Option<Decimal> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| Decimal::from(self.hits) / Decimal::from(lookups))
+    }
+}
+
+/// Markets by ID and price histories by token ID, with when they were fetched
+pub struct MarketCache {
+    pub markets: LruCache<String, (Market, Instant)>,
+    pub price_histories: LruCache<String, (Vec<Decimal>, Instant)>,
+    market_ttl: Duration,
+    history_ttl: Duration,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl MarketCache {
+    pub fn new(market_ttl: Duration, history_ttl: Duration, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            markets: LruCache::new(capacity),
+            price_histories: LruCache::new(capacity),
+            market_ttl,
+            history_ttl,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// The cached market if fetched within the TTL
+    pub fn market(&mut self, market_id: &str, now: Instant) -> Option<Market> {
+        let fresh = match self.markets.get(market_id) {
+            Some((market, fetched_at)) if now.duration_since(*fetched_at) < self.market_ttl => Some(market.clone()),
+            _ => None,
+        };
+        self.count(fresh.is_some());
+        fresh
+    }
+
+    pub fn insert_market(&mut self, market: Market, now: Instant) {
+        let id = market.id.clone();
+        if let Some((evicted, _)) = self.markets.push(id.clone(), (market, now)) {
+            self.evictions += u64::from(evicted != id);
+        }
+    }
+
+    /// The cached price history of `token_id` if fetched within the TTL
+    pub fn price_history(&mut self, token_id: &str, now: Instant) -> Option<Vec<Decimal>> {
+        let fresh = match self.price_histories.get(token_id) {
+            Some((history, fetched_at)) if now.duration_since(*fetched_at) < self.history_ttl => Some(history.clone()),
+            _ => None,
+        };
+        self.count(fresh.is_some());
+        fresh
+    }
+
+    pub fn insert_price_history(&mut self, token_id: &str, history: Vec<Decimal>, now: Instant) {
+        if let Some((evicted, _)) = self.price_histories.push(token_id.to_string(), (history, now)) {
+            self.evictions += u64::from(evicted != token_id);
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            size: self.markets.len() + self.price_histories.len(),
+        }
+    }
+
+    fn count(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(id: &str) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: Vec::new(),
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    #[test]
+    fn test_markets_served_within_ttl() {
+        let mut cache = MarketCache::new(Duration::from_secs(60), Duration::from_secs(300), 10);
+        let start = Instant::now();
+        assert!(cache.market("a", start).is_none());
+        cache.insert_market(market("a"), start);
+
+        assert_eq!(cache.market("a", start + Duration::from_secs(59)).unwrap().id, "a");
+        assert!(cache.market("a", start + Duration::from_secs(60)).is_none());
+        // Refetching resets the clock without counting as an eviction
+        cache.insert_market(market("a"), start + Duration::from_secs(60));
+        assert!(cache.market("a", start + Duration::from_secs(61)).is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions, stats.size), (2, 2, 0, 1));
+        assert_eq!(stats.hit_rate(), Some(Decimal::new(5, 1)));
+        assert_eq!(CacheStats::default().hit_rate(), None);
+    }
+
+    #[test]
+    fn test_least_recently_used_entries_are_evicted() {
+        let mut cache = MarketCache::new(Duration::from_secs(60), Duration::from_secs(300), 2);
+        let now = Instant::now();
+        cache.insert_market(market("a"), now);
+        cache.insert_market(market("b"), now);
+        // Reading "a" makes "b" the least recently used
+        assert!(cache.market("a", now).is_some());
+        cache.insert_market(market("c"), now);
+
+        assert!(cache.market("b", now).is_none());
+        assert!(cache.market("a", now).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().size, 2);
+    }
+
+    #[test]
+    fn test_price_histories_have_their_own_ttl() {
+        let mut cache = MarketCache::new(Duration::from_secs(60), Duration::from_secs(300), 10);
+        let start = Instant::now();
+        let history = vec![Decimal::new(45, 2), Decimal::new(47, 2)];
+        cache.insert_price_history("t1", history.clone(), start);
+
+        assert_eq!(cache.price_history("t1", start + Duration::from_secs(299)), Some(history));
+        assert!(cache.price_history("t1", start + Duration::from_secs(300)).is_none());
+        assert!(cache.price_history("t2", start).is_none());
+    }
+}
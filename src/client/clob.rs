@@ -4,6 +4,7 @@
 //! Implements Polymarket's Level 1 (EIP-712) and Level 2 (HMAC) authentication.
 
 use crate::client::auth::{ApiCredentials, PolySigner};
+use crate::client::mock::ClobClientTrait;
 use crate::error::{BotError, Result};
 use crate::types::{Order, OrderStatus, OrderType, Side};
 use reqwest::Client;
@@ -68,6 +69,12 @@ struct OrderResponse {
     #[serde(rename = "orderID")]
     order_id: String,
     status: String,
+    /// What the order gave up on matching (USDC for buys, shares for sells)
+    #[serde(rename = "makingAmount", default)]
+    making_amount: Option<String>,
+    /// What the order received on matching (shares for buys, USDC for sells)
+    #[serde(rename = "takingAmount", default)]
+    taking_amount: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -238,6 +245,15 @@ impl ClobClient {
 
     /// Place a limit order
     pub async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        self.submit_order(order, None).await
+    }
+
+    /// Place a GTD order that expires at the given unix timestamp
+    pub async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        self.submit_order(order, Some(expiration.max(0) as u64)).await
+    }
+
+    async fn submit_order(&self, order: &Order, expiration: Option<u64>) -> Result<OrderStatus> {
         let creds = self.credentials.read().await;
         let creds = creds
             .as_ref()
@@ -256,7 +272,7 @@ impl ClobClient {
                 OrderType::FOK => "FOK".to_string(),
                 OrderType::GTD => "GTD".to_string(),
             },
-            expiration: None,
+            expiration,
         };
 
         let path = "/order";
@@ -278,12 +294,25 @@ impl ClobClient {
             .json()
             .await?;
 
+        // A matched order matched in full; a partial match rests as live
+        let (filled_size, avg_price) = if resp.status.eq_ignore_ascii_case("matched") {
+            let amount = |a: &Option<String>| a.as_deref().and_then(|s| s.parse::<Decimal>().ok());
+            let (shares, usdc) = match order.side {
+                Side::Buy => (amount(&resp.taking_amount), amount(&resp.making_amount)),
+                Side::Sell => (amount(&resp.making_amount), amount(&resp.taking_amount)),
+            };
+            let shares = shares.filter(|s| *s > Decimal::ZERO).unwrap_or(order.size);
+            (shares, usdc.map(|usdc| usdc / shares))
+        } else {
+            (Decimal::ZERO, None)
+        };
+
         Ok(OrderStatus {
             order_id: resp.order_id,
             status: resp.status,
-            filled_size: Decimal::ZERO,
-            remaining_size: order.size,
-            avg_price: None,
+            filled_size,
+            remaining_size: order.size - filled_size,
+            avg_price,
         })
     }
 
@@ -307,6 +336,36 @@ impl ClobClient {
         Ok(())
     }
 
+    /// Cancel an order and place its replacement
+    pub async fn replace_order(&self, order_id: &str, order: &Order) -> Result<OrderStatus> {
+        self.cancel_order(order_id).await?;
+        self.place_order(order).await
+    }
+
+    /// Cancel all open orders, returning the number cancelled
+    pub async fn cancel_all_orders(&self) -> Result<u32> {
+        let creds = self.credentials.read().await;
+        let creds = creds
+            .as_ref()
+            .ok_or_else(|| BotError::Auth("Not authenticated".into()))?;
+
+        let path = "/cancel-all";
+        let url = format!("{}{}", self.base_url, path);
+        let headers = self.create_l2_headers(creds, "DELETE", path, None)?;
+
+        let mut req = self.http.delete(&url);
+        for (key, value) in headers {
+            req = req.header(&key, &value);
+        }
+
+        let resp: serde_json::Value = req.send().await?.json().await?;
+
+        Ok(resp["canceled"]
+            .as_array()
+            .map(|ids| ids.len() as u32)
+            .unwrap_or(0))
+    }
+
     /// Get order status
     pub async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
         let creds = self.credentials.read().await;
@@ -483,6 +542,49 @@ impl ClobClient {
     }
 }
 
+#[async_trait::async_trait]
+impl ClobClientTrait for ClobClient {
+    async fn get_balance(&self) -> Result<Decimal> {
+        ClobClient::get_balance(self).await
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        ClobClient::get_order_book(self, token_id).await
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        ClobClient::place_order(self, order).await
+    }
+
+    async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        ClobClient::place_order_until(self, order, expiration).await
+    }
+
+    fn supports_gtd(&self) -> bool {
+        true
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        ClobClient::cancel_order(self, order_id).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        ClobClient::get_open_orders(self).await
+    }
+
+    async fn cancel_all_orders(&self) -> Result<u32> {
+        ClobClient::cancel_all_orders(self).await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        ClobClient::get_order(self, order_id).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<crate::types::Position>> {
+        ClobClient::get_positions(self).await
+    }
+}
+
 /// Order book data
 #[derive(Debug, Clone)]
 pub struct OrderBook {
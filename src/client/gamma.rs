@@ -2,14 +2,27 @@
 //!
 //! Fetches market information, prices, and metadata.
 
+use super::cache::{CacheStats, MarketCache};
 use crate::error::{BotError, Result};
-use crate::types::{Market, Outcome};
+use crate::types::{Market, OrderConstraints, Outcome};
 use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::debug;
 
+/// API serving price histories (the CLOB's, Gamma has none)
+pub const DEFAULT_PRICE_HISTORY_URL: &str = "https://clob.polymarket.com";
+
+/// Markets per page of a paginated listing, Gamma's cap per request
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Pause between the pages of a listing, to stay under Gamma's rate limit
+pub const DEFAULT_PAGE_DELAY: Duration = Duration::from_millis(200);
+
 /// Known crypto series IDs
 pub const CRYPTO_SERIES: &[(&str, &str, u64)] = &[
     ("BTC 15m", "btc-up-or-down-15m", 10192),
@@ -34,6 +47,42 @@ pub const CRYPTO_SEARCH_QUERIES: &[&str] = &[
 pub struct GammaClient {
     http: Client,
     base_url: String,
+    history_url: String,
+    /// Shared by clones; `None` until [`GammaClient::with_cache`]
+    cache: Option<Arc<Mutex<MarketCache>>>,
+    /// Markets per page of [`GammaClient::get_all_markets`]
+    page_size: usize,
+    /// Pause between its pages
+    page_delay: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceHistoryResponse {
+    history: Vec<PricePoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PricePoint {
+    /// Unix seconds
+    #[serde(default)]
+    t: i64,
+    p: f64,
+}
+
+/// Rolling volumes of a market, in USDC
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct GammaVolumes {
+    #[serde(rename = "volume24hr", default)]
+    day: Option<f64>,
+    #[serde(rename = "volume1wk", default)]
+    week: Option<f64>,
+}
+
+/// On-chain condition of a market, redeemed against once it resolves
+#[derive(Debug, Clone, Deserialize)]
+struct GammaCondition {
+    #[serde(rename = "conditionId")]
+    condition_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -52,6 +101,73 @@ struct GammaMarket {
     outcome_prices: Option<String>, // JSON string "[0.55, 0.45]"
     #[serde(rename = "clobTokenIds")]
     clob_token_ids: Option<String>, // JSON string
+    #[serde(rename = "negRisk", default)]
+    neg_risk: bool,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<String>,
+    #[serde(rename = "orderPriceMinTickSize", default)]
+    order_price_min_tick_size: Option<f64>,
+    #[serde(rename = "orderMinSize", default)]
+    order_min_size: Option<f64>,
+    /// Parent events (only present on /markets responses)
+    #[serde(default)]
+    events: Vec<EventRef>,
+}
+
+/// Which markets to scan
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketFilter {
+    /// Gamma categories to keep, case-insensitive (empty = any)
+    pub categories: Vec<String>,
+    pub min_liquidity: Decimal,
+    /// Only markets resolving within this many days
+    pub max_days_to_resolution: Option<i64>,
+    pub min_volume: Decimal,
+}
+
+impl MarketFilter {
+    /// Whether a market passes every criterion
+    pub fn matches(&self, market: &Market, now: DateTime<Utc>) -> bool {
+        let category_ok = self.categories.is_empty()
+            || market
+                .category
+                .as_deref()
+                .is_some_and(|c| self.categories.iter().any(|want| want.eq_ignore_ascii_case(c)));
+        let resolution_ok = match self.max_days_to_resolution {
+            Some(days) => market
+                .end_date
+                .is_some_and(|end| end > now && end <= now + chrono::Duration::days(days)),
+            None => true,
+        };
+
+        category_ok
+            && resolution_ok
+            && market.liquidity >= self.min_liquidity
+            && market.volume >= self.min_volume
+    }
+
+    /// Query parameters for the criteria Gamma can filter on itself
+    fn query(&self, now: DateTime<Utc>) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if self.min_liquidity > Decimal::ZERO {
+            query.push(("liquidity_num_min", self.min_liquidity.to_string()));
+        }
+        if self.min_volume > Decimal::ZERO {
+            query.push(("volume_num_min", self.min_volume.to_string()));
+        }
+        if let Some(days) = self.max_days_to_resolution {
+            query.push(("end_date_min", now.to_rfc3339()));
+            query.push(("end_date_max", (now + chrono::Duration::days(days)).to_rfc3339()));
+        }
+        query
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EventRef {
+    id: String,
 }
 
 impl GammaClient {
@@ -64,9 +180,51 @@ impl GammaClient {
         Ok(Self {
             http,
             base_url: base_url.trim_end_matches('/').to_string(),
+            history_url: DEFAULT_PRICE_HISTORY_URL.to_string(),
+            cache: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            page_delay: DEFAULT_PAGE_DELAY,
         })
     }
 
+    /// Page through listings `page_size` markets at a time (at least 1),
+    /// pausing `page_delay` between pages
+    pub fn with_paging(mut self, page_size: usize, page_delay: Duration) -> Self {
+        self.page_size = page_size.max(1);
+        self.page_delay = page_delay;
+        self
+    }
+
+    /// Fetch price histories from `url` instead of the public CLOB
+    pub fn with_history_url(mut self, url: &str) -> Self {
+        self.history_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Serve markets fetched within `market_ttl` and price histories fetched
+    /// within `history_ttl` from memory, keeping up to `capacity` of each.
+    /// Market lists fetched by the client refresh the cached markets too.
+    pub fn with_cache(mut self, market_ttl: Duration, history_ttl: Duration, capacity: usize) -> Self {
+        self.cache = Some(Arc::new(Mutex::new(MarketCache::new(market_ttl, history_ttl, capacity))));
+        self
+    }
+
+    /// Lookups served by the cache so far; all zero without one
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.as_ref().map(|c| c.lock().stats()).unwrap_or_default()
+    }
+
+    /// Refresh the cached copies of `markets`
+    fn cache_markets(&self, markets: &[Market]) {
+        if let Some(cache) = &self.cache {
+            let now = Instant::now();
+            let mut cache = cache.lock();
+            for market in markets {
+                cache.insert_market(market.clone(), now);
+            }
+        }
+    }
+
     /// Get all active markets
     pub async fn get_markets(&self) -> Result<Vec<Market>> {
         let url = format!("{}/markets", self.base_url);
@@ -84,13 +242,80 @@ impl GammaClient {
 
     /// Get a specific market by ID
     pub async fn get_market(&self, market_id: &str) -> Result<Market> {
+        if let Some(market) = self.cache.as_ref().and_then(|c| c.lock().market(market_id, Instant::now())) {
+            return Ok(market);
+        }
         let url = format!("{}/markets/{}", self.base_url, market_id);
         let resp: GammaMarket = self.http.get(&url).send().await?.json().await?;
 
-        self.parse_market(resp)
+        let market = self
+            .parse_market(resp)
+            .ok_or_else(|| BotError::MarketNotFound(market_id.to_string()))?;
+        self.cache_markets(std::slice::from_ref(&market));
+        Ok(market)
+    }
+
+    /// Volume of a market over the last 24 hours and the last week, in
+    /// USDC; not cached
+    pub async fn get_market_volumes(&self, market_id: &str) -> Result<(Decimal, Decimal)> {
+        let url = format!("{}/markets/{}", self.base_url, market_id);
+        let resp: GammaVolumes = self.http.get(&url).send().await?.json().await?;
+        let volume = |v: Option<f64>| v.and_then(|v| Decimal::try_from(v).ok()).unwrap_or(Decimal::ZERO);
+        Ok((volume(resp.day), volume(resp.week)))
+    }
+
+    /// Conditional Tokens condition ID of a market (0x-prefixed hex); not
+    /// cached
+    pub async fn get_condition_id(&self, market_id: &str) -> Result<String> {
+        let url = format!("{}/markets/{}", self.base_url, market_id);
+        let resp: GammaCondition = self.http.get(&url).send().await?.json().await?;
+        resp.condition_id
+            .filter(|id| !id.is_empty())
             .ok_or_else(|| BotError::MarketNotFound(market_id.to_string()))
     }
 
+    /// Hourly prices of a token over the last week, oldest first
+    pub async fn get_price_history(&self, token_id: &str) -> Result<Vec<Decimal>> {
+        if let Some(history) = self.cache.as_ref().and_then(|c| c.lock().price_history(token_id, Instant::now())) {
+            return Ok(history);
+        }
+        let url = format!("{}/prices-history", self.history_url);
+        let resp: PriceHistoryResponse = self
+            .http
+            .get(&url)
+            .query(&[("market", token_id), ("interval", "1w"), ("fidelity", "60")])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let history: Vec<Decimal> = resp.history.iter().filter_map(|point| Decimal::try_from(point.p).ok()).collect();
+        if let Some(cache) = &self.cache {
+            cache.lock().insert_price_history(token_id, history.clone(), Instant::now());
+        }
+        Ok(history)
+    }
+
+    /// Hourly prices of a token over its whole history with their times,
+    /// oldest first; not cached
+    pub async fn get_hourly_price_history(&self, token_id: &str) -> Result<Vec<(DateTime<Utc>, Decimal)>> {
+        let url = format!("{}/prices-history", self.history_url);
+        let resp: PriceHistoryResponse = self
+            .http
+            .get(&url)
+            .query(&[("market", token_id), ("interval", "max"), ("fidelity", "60")])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        Ok(resp
+            .history
+            .iter()
+            .filter_map(|point| Some((DateTime::from_timestamp(point.t, 0)?, Decimal::try_from(point.p).ok()?)))
+            .collect())
+    }
+
     /// Search markets by keyword (fetches all active markets and filters locally)
     pub async fn search_markets(&self, query: &str) -> Result<Vec<Market>> {
         let url = format!("{}/markets", self.base_url);
@@ -134,7 +359,165 @@ impl GammaClient {
             .json()
             .await?;
 
-        Ok(resp.into_iter().filter_map(|m| self.parse_market(m)).collect())
+        let markets: Vec<Market> = resp.into_iter().filter_map(|m| self.parse_market(m)).collect();
+        self.cache_markets(&markets);
+        Ok(markets)
+    }
+
+    /// Get markets matching a filter, highest volume first. Liquidity, volume
+    /// and end date are filtered server-side; categories (which Gamma only
+    /// exposes as tag IDs) client-side. Every criterion is re-checked locally.
+    pub async fn get_markets_filtered(&self, filter: &MarketFilter, limit: usize) -> Result<Vec<Market>> {
+        let now = Utc::now();
+        let url = format!("{}/markets", self.base_url);
+        let mut query: Vec<(&str, String)> = vec![
+            ("active", "true".to_string()),
+            ("closed", "false".to_string()),
+            ("_sort", "volume:desc".to_string()),
+            ("_limit", "500".to_string()),
+        ];
+        query.extend(filter.query(now));
+
+        let resp: Vec<GammaMarket> = self.http.get(&url).query(&query).send().await?.json().await?;
+
+        let markets: Vec<Market> = resp
+            .into_iter()
+            .filter_map(|m| self.parse_market(m))
+            .filter(|m| filter.matches(m, now))
+            .take(limit)
+            .collect();
+        self.cache_markets(&markets);
+        Ok(markets)
+    }
+
+    /// Markets matching `filter` over up to `max_pages` pages, highest
+    /// volume first, each market once; at most `max_pages` pages' worth.
+    /// Paging stops early at a short or empty page, or at one that brings
+    /// no new market (the listing is exhausted).
+    pub async fn get_all_markets(&self, filter: &MarketFilter, max_pages: usize) -> Result<Vec<Market>> {
+        let now = Utc::now();
+        let url = format!("{}/markets", self.base_url);
+        let mut seen = std::collections::HashSet::new();
+        let mut markets = Vec::new();
+
+        for page in 0..max_pages {
+            if page > 0 {
+                tokio::time::sleep(self.page_delay).await;
+            }
+            let mut query: Vec<(&str, String)> = vec![
+                ("active", "true".to_string()),
+                ("closed", "false".to_string()),
+                ("_sort", "volume:desc".to_string()),
+                ("_limit", self.page_size.to_string()),
+                ("_offset", (page * self.page_size).to_string()),
+            ];
+            query.extend(filter.query(now));
+
+            let resp: Vec<GammaMarket> = self.http.get(&url).query(&query).send().await?.json().await?;
+            let fetched = resp.len();
+            let mut new = 0;
+            for gm in resp {
+                if !seen.insert(gm.id.clone()) {
+                    continue;
+                }
+                new += 1;
+                if let Some(market) = self.parse_market(gm).filter(|m| filter.matches(m, now)) {
+                    markets.push(market);
+                }
+            }
+            debug!("Gamma page {}: {} markets, {} new", page + 1, fetched, new);
+            if fetched < self.page_size || new == 0 {
+                break;
+            }
+        }
+
+        self.cache_markets(&markets);
+        Ok(markets)
+    }
+
+    /// Markets created at or after `since` that match `filter`, newest
+    /// first; markets without a creation time are skipped
+    pub async fn get_markets_created_since(
+        &self,
+        since: DateTime<Utc>,
+        filter: &MarketFilter,
+        limit: usize,
+    ) -> Result<Vec<Market>> {
+        let now = Utc::now();
+        let url = format!("{}/markets", self.base_url);
+        let mut query: Vec<(&str, String)> = vec![
+            ("active", "true".to_string()),
+            ("closed", "false".to_string()),
+            ("_sort", "createdAt:desc".to_string()),
+            ("_limit", "500".to_string()),
+        ];
+        query.extend(filter.query(now));
+
+        let resp: Vec<GammaMarket> = self.http.get(&url).query(&query).send().await?.json().await?;
+
+        Ok(resp
+            .into_iter()
+            .filter(|gm| {
+                gm.created_at
+                    .as_deref()
+                    .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+                    .is_some_and(|created| created >= since)
+            })
+            .filter_map(|m| self.parse_market(m))
+            .filter(|m| filter.matches(m, now))
+            .take(limit)
+            .collect())
+    }
+
+    /// Get all markets of an event, tagged with the event ID
+    pub async fn get_event(&self, event_id: &str) -> Result<Vec<Market>> {
+        let url = format!("{}/events/{}", self.base_url, event_id);
+        let resp: EventResponse = self.http.get(&url).send().await?.json().await?;
+
+        Ok(self.parse_event(resp))
+    }
+
+    /// Get the markets of all active negRisk events
+    pub async fn get_neg_risk_markets(&self) -> Result<Vec<Market>> {
+        let url = format!("{}/events", self.base_url);
+        let resp: Vec<EventResponse> = self
+            .http
+            .get(&url)
+            .query(&[
+                ("active", "true"),
+                ("closed", "false"),
+                ("neg_risk", "true"),
+                ("limit", "100"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        Ok(resp
+            .into_iter()
+            .filter(|e| e.neg_risk)
+            .flat_map(|e| self.parse_event(e))
+            .collect())
+    }
+
+    /// Parse an event's markets; event-level flags win over per-market ones
+    fn parse_event(&self, event: EventResponse) -> Vec<Market> {
+        let event_id = event.id;
+        let neg_risk = event.neg_risk;
+        event
+            .markets
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|gm| self.parse_market(gm))
+            .map(|mut market| {
+                if event_id.is_some() {
+                    market.event_id = event_id.clone();
+                }
+                market.neg_risk |= neg_risk;
+                market
+            })
+            .collect()
     }
 
     fn parse_market(&self, gm: GammaMarket) -> Option<Market> {
@@ -204,6 +587,16 @@ impl GammaClient {
             outcomes,
             active: gm.active,
             closed: gm.closed,
+            event_id: gm.events.first().map(|e| e.id.clone()),
+            neg_risk: gm.neg_risk,
+            category: gm.category,
+            order_constraints: match (gm.order_price_min_tick_size, gm.order_min_size) {
+                (Some(tick), Some(min_size)) => Some(OrderConstraints {
+                    tick_size: Decimal::try_from(tick).ok()?,
+                    min_order_size: Decimal::try_from(min_size).ok()?,
+                }),
+                _ => None,
+            },
         })
     }
 
@@ -471,7 +864,293 @@ struct SeriesEvent {
 /// Response structure for event endpoint
 #[derive(Debug, Deserialize)]
 struct EventResponse {
+    #[serde(default)]
+    id: Option<String>,
     #[allow(dead_code)]
     title: String,
+    #[serde(rename = "negRisk", default)]
+    neg_risk: bool,
     markets: Option<Vec<GammaMarket>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const NEG_RISK_EVENT: &str = include_str!("fixtures/neg_risk_event.json");
+
+    fn client() -> GammaClient {
+        GammaClient::new("https://gamma-api.polymarket.com").unwrap()
+    }
+
+    #[test]
+    fn test_parse_neg_risk_event_fixture() {
+        let event: EventResponse = serde_json::from_str(NEG_RISK_EVENT).unwrap();
+        assert!(event.neg_risk);
+
+        let markets = client().parse_event(event);
+        assert_eq!(markets.len(), 4);
+        assert!(markets.iter().all(|m| m.neg_risk && m.event_id.as_deref() == Some("10977")));
+
+        let cut = &markets[1];
+        assert_eq!(cut.id, "502518");
+        assert_eq!(cut.outcomes.len(), 2);
+        assert_eq!(cut.outcomes[0].outcome, "Yes");
+        assert_eq!(cut.outcomes[0].price, dec!(0.955));
+        assert_eq!(cut.outcomes[1].price, dec!(0.045));
+        assert!(cut.outcomes[0].token_id.starts_with("4325803837"));
+
+        // Mutually exclusive outcomes: YES prices sum to about 1
+        let yes_sum: Decimal = markets.iter().map(|m| m.outcomes[0].price).sum();
+        assert_eq!(yes_sum, dec!(1.005));
+    }
+
+    #[test]
+    fn test_parse_market_event_ref() {
+        let json = r#"{
+            "id": "502519",
+            "question": "No change in Fed interest rates after December 2024 meeting?",
+            "active": true,
+            "closed": false,
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.041\", \"0.959\"]",
+            "clobTokenIds": "[\"1\", \"2\"]",
+            "negRisk": true,
+            "events": [{ "id": "10977", "title": "Fed decision in December?" }]
+        }"#;
+        let gm: GammaMarket = serde_json::from_str(json).unwrap();
+        let market = client().parse_market(gm).unwrap();
+        assert_eq!(market.event_id.as_deref(), Some("10977"));
+        assert!(market.neg_risk);
+        assert_eq!(market.order_constraints, None);
+    }
+
+    #[test]
+    fn test_parse_order_constraints() {
+        let json = r#"{ "id": "1", "question": "Q?", "active": true, "closed": false,
+            "orderPriceMinTickSize": 0.001, "orderMinSize": 5 }"#;
+        let gm: GammaMarket = serde_json::from_str(json).unwrap();
+        let constraints = client().parse_market(gm).unwrap().order_constraints.unwrap();
+        assert_eq!(constraints.tick_size, dec!(0.001));
+        assert_eq!(constraints.min_order_size, dec!(5));
+    }
+
+    #[test]
+    fn test_standalone_market_has_no_event() {
+        let json = r#"{ "id": "1", "question": "Q?", "active": true, "closed": false }"#;
+        let gm: GammaMarket = serde_json::from_str(json).unwrap();
+        let market = client().parse_market(gm).unwrap();
+        assert_eq!(market.event_id, None);
+        assert!(!market.neg_risk);
+    }
+    /// Serve `body` for `/markets` on a local port and return the base URL
+    /// plus the query strings received
+    async fn mock_gamma(body: String) -> (String, std::sync::Arc<parking_lot::Mutex<Vec<String>>>) {
+        use axum::{extract::RawQuery, routing::get, Router};
+
+        let queries = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = queries.clone();
+        let app = Router::new().route(
+            "/markets",
+            get(move |RawQuery(query): RawQuery| {
+                seen.lock().push(query.unwrap_or_default());
+                let body = body.clone();
+                async move { ([("content-type", "application/json")], body) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), queries)
+    }
+
+    fn gamma_market(id: &str, category: &str, liquidity: &str, volume: &str, days_to_end: i64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "question": format!("Market {}?", id),
+            "category": category,
+            "liquidity": liquidity,
+            "volume": volume,
+            "endDate": (Utc::now() + chrono::Duration::days(days_to_end)).to_rfc3339(),
+            "active": true,
+            "closed": false,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_filtered() {
+        // The mock ignores query parameters, so everything comes back and the
+        // client-side pass has to drop the non-matching markets
+        let body = serde_json::json!([
+            gamma_market("keep-crypto", "Crypto", "20000", "50000", 3),
+            gamma_market("keep-politics", "politics", "15000", "9000", 6),
+            gamma_market("sports", "Sports", "20000", "50000", 3),
+            gamma_market("illiquid", "Crypto", "500", "50000", 3),
+            gamma_market("far-off", "Politics", "20000", "50000", 30),
+            gamma_market("low-volume", "Crypto", "20000", "100", 3),
+        ]);
+        let (url, queries) = mock_gamma(body.to_string()).await;
+
+        let filter = MarketFilter {
+            categories: vec!["Crypto".to_string(), "Politics".to_string()],
+            min_liquidity: dec!(10000),
+            max_days_to_resolution: Some(7),
+            min_volume: dec!(1000),
+        };
+        let markets = GammaClient::new(&url).unwrap().get_markets_filtered(&filter, 20).await.unwrap();
+
+        let ids: Vec<&str> = markets.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["keep-crypto", "keep-politics"]);
+        assert_eq!(markets[1].category.as_deref(), Some("politics"));
+
+        // Numeric criteria are pushed to the server
+        let query = queries.lock()[0].clone();
+        assert!(query.contains("liquidity_num_min=10000"));
+        assert!(query.contains("volume_num_min=1000"));
+        assert!(query.contains("end_date_max="));
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_filtered_respects_limit() {
+        let body = serde_json::json!([
+            gamma_market("a", "Crypto", "20000", "50000", 3),
+            gamma_market("b", "Crypto", "20000", "50000", 3),
+        ]);
+        let (url, _) = mock_gamma(body.to_string()).await;
+
+        let markets = GammaClient::new(&url)
+            .unwrap()
+            .get_markets_filtered(&MarketFilter::default(), 1)
+            .await
+            .unwrap();
+        assert_eq!(markets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_markets_pages_and_deduplicates() {
+        use axum::{extract::Query, routing::get, Router};
+        use std::collections::HashMap;
+
+        // Two full pages of two (the second repeating a market that moved up
+        // the volume ranking), then an empty page
+        let pages = [
+            serde_json::json!([
+                gamma_market("a", "Crypto", "20000", "50000", 3),
+                gamma_market("b", "Crypto", "20000", "40000", 3),
+            ]),
+            serde_json::json!([
+                gamma_market("b", "Crypto", "20000", "40000", 3),
+                gamma_market("c", "Crypto", "20000", "30000", 3),
+            ]),
+            serde_json::json!([]),
+        ];
+        let offsets = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = offsets.clone();
+        let app = Router::new().route(
+            "/markets",
+            get(move |Query(query): Query<HashMap<String, String>>| {
+                let offset: usize = query.get("_offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+                seen.lock().push(offset);
+                let body = pages.get(offset / 2).cloned().unwrap_or_else(|| serde_json::json!([]));
+                async move { axum::Json(body) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let gamma = GammaClient::new(&url).unwrap().with_paging(2, Duration::ZERO);
+
+        let markets = gamma.get_all_markets(&MarketFilter::default(), 10).await.unwrap();
+        let ids: Vec<&str> = markets.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(*offsets.lock(), [0, 2, 4]);
+
+        // Capped at max_pages
+        offsets.lock().clear();
+        let markets = gamma.get_all_markets(&MarketFilter::default(), 1).await.unwrap();
+        assert_eq!(markets.len(), 2);
+        assert_eq!(*offsets.lock(), [0]);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_markets_stops_when_offset_is_ignored() {
+        // A server that ignores the offset serves the same page forever
+        let body = serde_json::json!([
+            gamma_market("a", "Crypto", "20000", "50000", 3),
+            gamma_market("b", "Crypto", "20000", "40000", 3),
+        ]);
+        let (url, queries) = mock_gamma(body.to_string()).await;
+        let gamma = GammaClient::new(&url).unwrap().with_paging(2, Duration::ZERO);
+
+        let markets = gamma.get_all_markets(&MarketFilter::default(), 10).await.unwrap();
+        assert_eq!(markets.len(), 2);
+        assert_eq!(queries.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_created_since() {
+        let created = |mut market: serde_json::Value, hours_ago: i64| {
+            market["createdAt"] = (Utc::now() - chrono::Duration::hours(hours_ago)).to_rfc3339().into();
+            market
+        };
+        let body = serde_json::json!([
+            created(gamma_market("fresh", "Crypto", "20000", "50000", 3), 2),
+            created(gamma_market("fresh-thin", "Crypto", "500", "50000", 3), 1),
+            created(gamma_market("old", "Crypto", "20000", "50000", 3), 48),
+            gamma_market("undated", "Crypto", "20000", "50000", 3),
+        ]);
+        let (url, queries) = mock_gamma(body.to_string()).await;
+
+        let filter = MarketFilter { min_liquidity: dec!(10000), ..MarketFilter::default() };
+        let since = Utc::now() - chrono::Duration::hours(6);
+        let markets = GammaClient::new(&url).unwrap().get_markets_created_since(since, &filter, 20).await.unwrap();
+
+        let ids: Vec<&str> = markets.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["fresh"]);
+        assert!(queries.lock()[0].contains("_sort=createdAt%3Adesc"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_serves_listed_markets_and_price_histories() {
+        use axum::{routing::get, Router};
+
+        let body = serde_json::json!([gamma_market("a", "Crypto", "20000", "50000", 3)]);
+        let (url, _) = mock_gamma(body.to_string()).await;
+        let history_requests = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let seen = history_requests.clone();
+        let app = Router::new().route(
+            "/prices-history",
+            get(move || {
+                seen.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { axum::Json(serde_json::json!({"history": [{"t": 1, "p": 0.45}, {"t": 2, "p": 0.5}]})) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let history_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let gamma = GammaClient::new(&url)
+            .unwrap()
+            .with_history_url(&history_url)
+            .with_cache(Duration::from_secs(60), Duration::from_secs(300), 100);
+        gamma.get_markets_filtered(&MarketFilter::default(), 20).await.unwrap();
+
+        // The mock has no /markets/{id} route: this only succeeds from the cache
+        assert_eq!(gamma.get_market("a").await.unwrap().id, "a");
+        assert!(gamma.get_market("b").await.is_err());
+
+        assert_eq!(gamma.get_price_history("t1").await.unwrap(), vec![dec!(0.45), dec!(0.5)]);
+        assert_eq!(gamma.clone().get_price_history("t1").await.unwrap().len(), 2);
+        assert_eq!(history_requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let stats = gamma.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (2, 2, 2));
+        assert_eq!(GammaClient::new(&url).unwrap().cache_stats(), CacheStats::default());
+
+        // Timed history bypasses the cache
+        let timed = gamma.get_hourly_price_history("t1").await.unwrap();
+        assert_eq!(timed, vec![(DateTime::from_timestamp(1, 0).unwrap(), dec!(0.45)), (DateTime::from_timestamp(2, 0).unwrap(), dec!(0.5))]);
+        assert_eq!(history_requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+}
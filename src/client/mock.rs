@@ -7,7 +7,7 @@
 
 use crate::client::{OrderBook, OrderBookLevel};
 use crate::error::Result;
-use crate::types::{Market, Order, OrderStatus, Position, Side, Outcome};
+use crate::types::{Market, Order, OrderStatus, OrderType, Position, Side, Outcome};
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -21,9 +21,96 @@ pub trait ClobClientTrait: Send + Sync {
     async fn get_balance(&self) -> Result<Decimal>;
     async fn get_order_book(&self, token_id: &str) -> Result<OrderBook>;
     async fn place_order(&self, order: &Order) -> Result<OrderStatus>;
+    /// Place a GTD order; clients without expiry support treat it as GTC
+    async fn place_order_until(&self, order: &Order, _expiration: i64) -> Result<OrderStatus> {
+        self.place_order(order).await
+    }
+    /// Whether the venue expires GTD orders itself; without it the executor
+    /// cancels expired orders
+    fn supports_gtd(&self) -> bool {
+        false
+    }
     async fn cancel_order(&self, order_id: &str) -> Result<()>;
+    /// Cancel an order and place its replacement
+    async fn replace_order(&self, order_id: &str, order: &Order) -> Result<OrderStatus> {
+        self.cancel_order(order_id).await?;
+        self.place_order(order).await
+    }
     async fn get_open_orders(&self) -> Result<Vec<OrderStatus>>;
+    /// Cancel every open order, returning how many were cancelled
+    async fn cancel_all_orders(&self) -> Result<u32> {
+        let mut cancelled = 0;
+        for order in self.get_open_orders().await? {
+            self.cancel_order(&order.order_id).await?;
+            cancelled += 1;
+        }
+        Ok(cancelled)
+    }
+    /// Status and matched size of one order, open or not; clients that
+    /// don't keep closed orders only find open ones
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.get_open_orders()
+            .await?
+            .into_iter()
+            .find(|o| o.order_id == order_id)
+            .ok_or_else(|| crate::error::BotError::Execution(format!("Unknown order {}", order_id)))
+    }
     async fn get_positions(&self) -> Result<Vec<Position>>;
+    /// Net shares held of a single outcome token (negative when short)
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        Ok(self
+            .get_positions()
+            .await?
+            .iter()
+            .filter(|p| p.token_id == token_id)
+            .map(|p| match p.side {
+                Side::Buy => p.size,
+                Side::Sell => -p.size,
+            })
+            .sum())
+    }
+}
+
+/// Shared clients trade through the client they point to, so an executor can
+/// hold an `Arc<dyn ClobClientTrait>` chosen at runtime
+#[async_trait]
+impl<T: ClobClientTrait + ?Sized> ClobClientTrait for Arc<T> {
+    async fn get_balance(&self) -> Result<Decimal> {
+        (**self).get_balance().await
+    }
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        (**self).get_order_book(token_id).await
+    }
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        (**self).place_order(order).await
+    }
+    async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        (**self).place_order_until(order, expiration).await
+    }
+    fn supports_gtd(&self) -> bool {
+        (**self).supports_gtd()
+    }
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        (**self).cancel_order(order_id).await
+    }
+    async fn replace_order(&self, order_id: &str, order: &Order) -> Result<OrderStatus> {
+        (**self).replace_order(order_id, order).await
+    }
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        (**self).get_open_orders().await
+    }
+    async fn cancel_all_orders(&self) -> Result<u32> {
+        (**self).cancel_all_orders().await
+    }
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        (**self).get_order(order_id).await
+    }
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        (**self).get_positions().await
+    }
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        (**self).get_token_holdings(token_id).await
+    }
 }
 
 /// Trait for Gamma operations (allows mocking)
@@ -73,6 +160,7 @@ pub struct MockClobClient {
     order_books: HashMap<String, OrderBook>,
     simulate_failures: bool,
     latency_ms: u64,
+    match_orders: bool,
 }
 
 impl MockClobClient {
@@ -82,6 +170,7 @@ impl MockClobClient {
             order_books: Self::default_order_books(),
             simulate_failures: false,
             latency_ms: 0,
+            match_orders: false,
         }
     }
 
@@ -100,6 +189,30 @@ impl MockClobClient {
         self
     }
 
+    /// Match orders against the order book instead of filling everything:
+    /// non-crossing GTC/GTD orders rest, FOK orders that can't fully fill are killed
+    pub fn with_order_matching(mut self) -> Self {
+        self.match_orders = true;
+        self
+    }
+
+    /// Seed a held position in an outcome token
+    pub fn with_position(self, token_id: &str, size: Decimal) -> Self {
+        self.state.write().unwrap().positions.insert(
+            token_id.to_string(),
+            Position {
+                token_id: token_id.to_string(),
+                market_id: "mock_market".to_string(),
+                side: Side::Buy,
+                size,
+                avg_entry_price: dec!(0.50),
+                current_price: dec!(0.50),
+                unrealized_pnl: Decimal::ZERO,
+            },
+        );
+        self
+    }
+
     pub fn state(&self) -> Arc<RwLock<MockState>> {
         self.state.clone()
     }
@@ -129,6 +242,26 @@ impl MockClobClient {
         self.order_books.insert(token_id.to_string(), book);
     }
 
+    fn book_for(&self, token_id: &str) -> OrderBook {
+        self.order_books
+            .get(token_id)
+            .or(self.order_books.get("default"))
+            .cloned()
+            .unwrap_or_else(|| OrderBook {
+                bids: vec![OrderBookLevel { price: dec!(0.50), size: dec!(1000) }],
+                asks: vec![OrderBookLevel { price: dec!(0.52), size: dec!(1000) }],
+            })
+    }
+
+    /// Size available to an order at its limit price or better
+    fn fillable_size(&self, order: &Order) -> Decimal {
+        let book = self.book_for(&order.token_id);
+        match order.side {
+            Side::Buy => book.asks.iter().filter(|l| l.price <= order.price).map(|l| l.size).sum(),
+            Side::Sell => book.bids.iter().filter(|l| l.price >= order.price).map(|l| l.size).sum(),
+        }
+    }
+
     async fn simulate_latency(&self) {
         if self.latency_ms > 0 {
             tokio::time::sleep(std::time::Duration::from_millis(self.latency_ms)).await;
@@ -157,14 +290,7 @@ impl ClobClientTrait for MockClobClient {
         if self.simulate_failures {
             return Err(crate::error::BotError::Api("Mock failure".into()));
         }
-        Ok(self.order_books
-            .get(token_id)
-            .or(self.order_books.get("default"))
-            .cloned()
-            .unwrap_or_else(|| OrderBook {
-                bids: vec![OrderBookLevel { price: dec!(0.50), size: dec!(1000) }],
-                asks: vec![OrderBookLevel { price: dec!(0.52), size: dec!(1000) }],
-            }))
+        Ok(self.book_for(token_id))
     }
 
     async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
@@ -173,9 +299,44 @@ impl ClobClientTrait for MockClobClient {
             return Err(crate::error::BotError::Api("Mock failure".into()));
         }
 
+        let fillable = if self.match_orders {
+            self.fillable_size(order)
+        } else {
+            order.size
+        };
+
         let mut state = self.state.write().unwrap();
-        let order_id = format!("mock_order_{}", state.trades_executed + 1);
-        
+        let order_id = format!("mock_order_{}", state.orders.len() + 1);
+
+        if fillable < order.size && order.order_type == OrderType::FOK {
+            return Ok(OrderStatus {
+                order_id,
+                status: "CANCELLED".to_string(),
+                filled_size: Decimal::ZERO,
+                remaining_size: order.size,
+                avg_price: None,
+            });
+        }
+
+        if fillable.is_zero() {
+            state.orders.push(MockOrder {
+                order_id: order_id.clone(),
+                token_id: order.token_id.clone(),
+                side: order.side,
+                price: order.price,
+                size: order.size,
+                status: "OPEN".to_string(),
+                timestamp: Utc::now(),
+            });
+            return Ok(OrderStatus {
+                order_id,
+                status: "OPEN".to_string(),
+                filled_size: Decimal::ZERO,
+                remaining_size: order.size,
+                avg_price: None,
+            });
+        }
+
         // Update balance (simulate immediate fill)
         let cost = order.price * order.size;
         match order.side {
@@ -238,6 +399,24 @@ impl ClobClientTrait for MockClobClient {
             .collect())
     }
 
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.simulate_latency().await;
+        let state = self.state.read().unwrap();
+        let o = state
+            .orders
+            .iter()
+            .find(|o| o.order_id == order_id)
+            .ok_or_else(|| crate::error::BotError::Execution(format!("Unknown order {}", order_id)))?;
+        let filled = if o.status == "FILLED" { o.size } else { Decimal::ZERO };
+        Ok(OrderStatus {
+            order_id: o.order_id.clone(),
+            status: o.status.clone(),
+            filled_size: filled,
+            remaining_size: o.size - filled,
+            avg_price: (!filled.is_zero()).then_some(o.price),
+        })
+    }
+
     async fn get_positions(&self) -> Result<Vec<Position>> {
         self.simulate_latency().await;
         let state = self.state.read().unwrap();
@@ -292,6 +471,10 @@ impl MockGammaClient {
                 ],
                 active: true,
                 closed: false,
+                event_id: None,
+                neg_risk: false,
+                category: None,
+                order_constraints: None,
             },
             Market {
                 id: "eth_5k_2026".to_string(),
@@ -314,6 +497,10 @@ impl MockGammaClient {
                 ],
                 active: true,
                 closed: false,
+                event_id: None,
+                neg_risk: false,
+                category: None,
+                order_constraints: None,
             },
             Market {
                 id: "btc_up_24h".to_string(),
@@ -336,6 +523,10 @@ impl MockGammaClient {
                 ],
                 active: true,
                 closed: false,
+                event_id: None,
+                neg_risk: false,
+                category: None,
+                order_constraints: None,
             },
         ]
     }
@@ -6,6 +6,7 @@
 //! - WebSocket: Real-time price updates via polymarket_ws
 //! - Mock: Test clients for offline testing
 
+pub mod cache;
 pub mod clob;
 pub mod gamma;
 mod auth;
@@ -15,8 +16,9 @@ pub mod orderbook_stream;
 #[cfg(test)]
 mod tests;
 
+pub use cache::{CacheStats, MarketCache};
 pub use clob::{ClobClient, OrderBook, OrderBookLevel};
-pub use gamma::{GammaClient, CRYPTO_SERIES, CRYPTO_SEARCH_QUERIES};
+pub use gamma::{GammaClient, MarketFilter, CRYPTO_SERIES, CRYPTO_SEARCH_QUERIES};
 pub use auth::PolySigner;
 pub use orderbook_stream::{OrderBookManager, OrderBookUpdate, LocalOrderBook};
 
@@ -43,11 +45,18 @@ impl PolymarketClient {
     pub async fn new(config: PolymarketConfig) -> Result<Self> {
         let signer = PolySigner::from_private_key(&config.private_key, config.chain_id)?;
         let clob = ClobClient::new(&config.clob_url, signer, config.funder_address.clone())?;
-        let gamma = GammaClient::new(&config.gamma_url)?;
+        let gamma = GammaClient::new(&config.gamma_url)?
+            .with_history_url(&config.clob_url)
+            .with_cache(cache::DEFAULT_MARKET_TTL, cache::DEFAULT_HISTORY_TTL, cache::DEFAULT_CAPACITY);
 
         Ok(Self { clob, gamma, config })
     }
 
+    /// Data API endpoint (leaderboard, user positions)
+    pub fn data_api_url(&self) -> &str {
+        self.config.data_api_url.trim_end_matches('/')
+    }
+
     /// Create a WebSocket stream for real-time market data
     ///
     /// Uses the official Polymarket WebSocket protocol with:
@@ -0,0 +1,207 @@
+//! Injectable time and id sources
+//!
+//! Components that stamp ids or timestamps onto their output (order routing,
+//! backtests) or act on elapsed time (cooldowns, the daily P&L rollover, the
+//! daily report) take these as trait objects so tests and backtest runs can
+//! be made reproducible. Production defaults are the system clock and v4
+//! UUIDs.
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Source of wall-clock and monotonic time
+#[async_trait]
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Milliseconds since the Unix epoch
+    fn now_millis(&self) -> u64 {
+        self.now().timestamp_millis().max(0) as u64
+    }
+
+    /// Monotonic time, for durations
+    fn now_instant(&self) -> Instant;
+
+    /// Wait until `duration` has passed on this clock
+    async fn sleep(&self, duration: Duration);
+}
+
+/// The real clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Deterministic clock: starts at a fixed instant and advances by a fixed
+/// step on every read, or when moved with [`advance`](Self::advance) and
+/// [`set`](Self::set). Sleepers wake once the clock has been moved past
+/// their deadline.
+#[derive(Debug)]
+pub struct ManualClock {
+    millis: AtomicI64,
+    step_ms: i64,
+    /// Start in milliseconds and the real instant standing for it
+    origin: (i64, Instant),
+    moved: Notify,
+}
+
+impl ManualClock {
+    /// Clock frozen at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self::stepping(start, 0)
+    }
+
+    /// Clock starting at `start` that advances `step_ms` after each read
+    pub fn stepping(start: DateTime<Utc>, step_ms: i64) -> Self {
+        Self {
+            millis: AtomicI64::new(start.timestamp_millis()),
+            step_ms,
+            origin: (start.timestamp_millis(), Instant::now()),
+            moved: Notify::new(),
+        }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, ms: i64) {
+        self.millis.fetch_add(ms, Ordering::SeqCst);
+        self.moved.notify_waiters();
+    }
+
+    /// Move the clock to `at`
+    pub fn set(&self, at: DateTime<Utc>) {
+        self.millis.store(at.timestamp_millis(), Ordering::SeqCst);
+        self.moved.notify_waiters();
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        let millis = self.millis.fetch_add(self.step_ms, Ordering::SeqCst);
+        Utc.timestamp_millis_opt(millis).single().unwrap_or_default()
+    }
+
+    fn now_instant(&self) -> Instant {
+        let (start, instant) = self.origin;
+        let elapsed = self.millis.load(Ordering::SeqCst) - start;
+        let offset = Duration::from_millis(elapsed.unsigned_abs());
+        if elapsed >= 0 {
+            instant + offset
+        } else {
+            instant.checked_sub(offset).unwrap_or(instant)
+        }
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let deadline = self.millis.load(Ordering::SeqCst).saturating_add(duration.as_millis() as i64);
+        loop {
+            let moved = self.moved.notified();
+            tokio::pin!(moved);
+            // Register before checking so a move in between isn't missed
+            moved.as_mut().enable();
+            if self.millis.load(Ordering::SeqCst) >= deadline {
+                return;
+            }
+            moved.await;
+        }
+    }
+}
+
+/// Source of unique ids
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+/// Random v4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl IdGenerator for UuidGenerator {
+    fn next_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+/// Deterministic ids: `<prefix>-1`, `<prefix>-2`, ...
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> String {
+        format!("{}-{}", self.prefix, self.next.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_steps() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::stepping(start, 1000);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now_millis(), start.timestamp_millis() as u64 + 1000);
+
+        clock.advance(500);
+        assert_eq!(clock.now_millis(), start.timestamp_millis() as u64 + 2500);
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[tokio::test]
+    async fn test_manual_clock_sleep_waits_for_advance() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = std::sync::Arc::new(ManualClock::new(start));
+        let began = clock.now_instant();
+
+        let sleeper = {
+            let clock = clock.clone();
+            tokio::spawn(async move { clock.sleep(Duration::from_secs(60)).await })
+        };
+        tokio::task::yield_now().await;
+        clock.advance(59_000);
+        tokio::task::yield_now().await;
+        assert!(!sleeper.is_finished());
+
+        clock.advance(1_000);
+        tokio::time::timeout(Duration::from_secs(5), sleeper).await.unwrap().unwrap();
+        assert_eq!(clock.now_instant() - began, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_sequential_ids() {
+        let ids = SequentialIdGenerator::new("order");
+        assert_eq!(ids.next_id(), "order-1");
+        assert_eq!(ids.next_id(), "order-2");
+    }
+}
@@ -1,8 +1,10 @@
 //! Configuration management
 
+use crate::keys::PrivateKey;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -12,8 +14,977 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub llm: Option<LlmConfig>,
     pub telegram: Option<TelegramConfig>,
+    pub slack: Option<SlackConfig>,
     pub ingester: Option<IngesterConfig>,
     pub copy_trade: Option<CopyTradeConfig>,
+    pub shutdown: Option<ShutdownConfig>,
+    pub market_maker: Option<MarketMakerConfig>,
+    pub resting_orders: Option<RestingOrderConfig>,
+    pub rebalance: Option<RebalanceConfig>,
+    pub simulation: Option<SimulationConfig>,
+    pub markets: Option<MarketScanConfig>,
+    pub alerts: Option<AlertsConfig>,
+    pub health: Option<HealthConfig>,
+    pub fees: Option<FeeConfig>,
+    pub recovery: Option<RecoveryConfig>,
+    pub executor: Option<ExecutorConfig>,
+    pub discovery: Option<DiscoveryConfig>,
+    pub fast_stop: Option<FastStopConfig>,
+    pub events: Option<EventSinkConfig>,
+    pub watchdog: Option<WatchdogConfig>,
+    pub position_news: Option<PositionNewsConfig>,
+    pub ramp: Option<RampConfig>,
+    pub market_profile: Option<MarketProfileConfig>,
+    pub redeem: Option<RedeemConfig>,
+    pub metrics: Option<MetricsConfig>,
+}
+
+/// Prometheus scrape endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsConfig {
+    /// Port `/metrics` is served on
+    pub listen_port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketMakerConfig {
+    /// Enable market making
+    #[serde(default)]
+    pub enabled: bool,
+    /// Market IDs to quote (allowlist)
+    #[serde(default)]
+    pub markets: Vec<String>,
+    /// Full quoted spread around fair value (e.g., 0.04 = 4 cents)
+    #[serde(default = "default_mm_spread")]
+    pub spread: Decimal,
+    /// Shares per quote
+    #[serde(default = "default_mm_quote_size")]
+    pub quote_size: Decimal,
+    /// Maximum absolute inventory per market (shares)
+    #[serde(default = "default_mm_max_inventory")]
+    pub max_inventory: Decimal,
+    /// Fraction of the half-spread to shift quotes at max inventory
+    #[serde(default = "default_mm_skew_factor")]
+    pub skew_factor: Decimal,
+    /// Weight of the model prediction vs mid in fair value (scaled by confidence)
+    #[serde(default = "default_mm_model_weight")]
+    pub model_weight: Decimal,
+    /// Re-quote when fair value or quotes move more than this
+    #[serde(default = "default_mm_requote_tolerance")]
+    pub requote_tolerance: Decimal,
+    /// Re-quote loop interval (milliseconds)
+    #[serde(default = "default_mm_requote_interval_ms")]
+    pub requote_interval_ms: u64,
+    /// Stop quoting this many minutes before market close
+    #[serde(default = "default_mm_min_minutes_to_close")]
+    pub min_minutes_to_close: i64,
+    /// Stop quoting when VPIN exceeds this
+    #[serde(default = "default_mm_max_vpin")]
+    pub max_vpin: Decimal,
+    /// Fill VPIN buckets by volume or, for quiet markets, by time
+    #[serde(default)]
+    pub vpin_mode: crate::orderbook::VpinMode,
+    /// Duration of a VPIN bucket in time mode (seconds)
+    #[serde(default = "default_mm_vpin_bucket_secs")]
+    pub vpin_bucket_secs: u64,
+}
+
+fn default_mm_spread() -> Decimal {
+    Decimal::new(4, 2)
+}
+
+fn default_mm_quote_size() -> Decimal {
+    Decimal::new(50, 0)
+}
+
+fn default_mm_max_inventory() -> Decimal {
+    Decimal::new(500, 0)
+}
+
+fn default_mm_skew_factor() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_mm_model_weight() -> Decimal {
+    Decimal::new(30, 2)
+}
+
+fn default_mm_requote_tolerance() -> Decimal {
+    Decimal::new(1, 2)
+}
+
+fn default_mm_requote_interval_ms() -> u64 {
+    2000
+}
+
+fn default_mm_min_minutes_to_close() -> i64 {
+    60
+}
+
+fn default_mm_max_vpin() -> Decimal {
+    Decimal::new(70, 2)
+}
+
+fn default_mm_vpin_bucket_secs() -> u64 {
+    300
+}
+
+impl Default for MarketMakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            markets: Vec::new(),
+            spread: default_mm_spread(),
+            quote_size: default_mm_quote_size(),
+            max_inventory: default_mm_max_inventory(),
+            skew_factor: default_mm_skew_factor(),
+            model_weight: default_mm_model_weight(),
+            requote_tolerance: default_mm_requote_tolerance(),
+            requote_interval_ms: default_mm_requote_interval_ms(),
+            min_minutes_to_close: default_mm_min_minutes_to_close(),
+            max_vpin: default_mm_max_vpin(),
+            vpin_mode: Default::default(),
+            vpin_bucket_secs: default_mm_vpin_bucket_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestingOrderConfig {
+    /// Rest limit orders instead of crossing the spread on illiquid markets
+    #[serde(default)]
+    pub enabled: bool,
+    /// Rest instead of taking when the spread is wider than this
+    #[serde(default = "default_resting_min_spread")]
+    pub min_spread: Decimal,
+    /// Distance of the resting price from fair value, on the edge's side
+    #[serde(default = "default_resting_margin")]
+    pub margin: Decimal,
+    /// Reprice when the target price moves more than this
+    #[serde(default = "default_resting_reprice_tolerance")]
+    pub reprice_tolerance: Decimal,
+    /// Cancel orders resting longer than this (seconds)
+    #[serde(default = "default_resting_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+fn default_resting_min_spread() -> Decimal {
+    Decimal::new(5, 2)
+}
+
+fn default_resting_margin() -> Decimal {
+    Decimal::new(2, 2)
+}
+
+fn default_resting_reprice_tolerance() -> Decimal {
+    Decimal::new(1, 2)
+}
+
+fn default_resting_max_age_secs() -> u64 {
+    24 * 3600
+}
+
+impl Default for RestingOrderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_spread: default_resting_min_spread(),
+            margin: default_resting_margin(),
+            reprice_tolerance: default_resting_reprice_tolerance(),
+            max_age_secs: default_resting_max_age_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RebalanceConfig {
+    /// Target portfolio weight per market ID
+    #[serde(default)]
+    pub target_weights: std::collections::HashMap<String, Decimal>,
+    /// Rebalance a market when |actual - target| weight exceeds this
+    #[serde(default = "default_drift_threshold")]
+    pub drift_threshold: Decimal,
+    /// Skip rebalance trades smaller than this (USDC)
+    #[serde(default = "default_min_rebalance_size")]
+    pub min_rebalance_size_usdc: Decimal,
+    /// Minimum hours between rebalances
+    #[serde(default = "default_rebalance_cooldown")]
+    pub cooldown_hours: u32,
+}
+
+fn default_drift_threshold() -> Decimal {
+    Decimal::new(5, 2)
+}
+
+fn default_min_rebalance_size() -> Decimal {
+    Decimal::new(10, 0)
+}
+
+fn default_rebalance_cooldown() -> u32 {
+    24
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            target_weights: std::collections::HashMap::new(),
+            drift_threshold: default_drift_threshold(),
+            min_rebalance_size_usdc: default_min_rebalance_size(),
+            cooldown_hours: default_rebalance_cooldown(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShutdownConfig {
+    /// Close open positions at market on shutdown
+    #[serde(default = "default_true")]
+    pub close_positions: bool,
+    /// Maximum time to wait for closing orders to fill (seconds)
+    #[serde(default = "default_shutdown_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_shutdown_timeout() -> u64 {
+    30
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            close_positions: true,
+            timeout_secs: default_shutdown_timeout(),
+        }
+    }
+}
+
+/// Dependency health checks (`/health`, `doctor`, background alerts)
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthConfig {
+    /// Check in the background and alert when a dependency degrades
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Background check interval (seconds)
+    #[serde(default = "default_health_interval_secs")]
+    pub interval_secs: u64,
+    /// Probes slower than this report Degraded (milliseconds)
+    #[serde(default = "default_health_slow_ms")]
+    pub slow_ms: u64,
+    /// Price feed older than this reports Degraded (seconds)
+    #[serde(default = "default_health_feed_stale_secs")]
+    pub feed_stale_secs: u64,
+    /// Free disk space below this reports Degraded, below a tenth of it Down (MB)
+    #[serde(default = "default_health_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+    /// Consecutive checks a new status must persist before it is alerted
+    #[serde(default = "default_health_confirmations")]
+    pub confirmations: u32,
+}
+
+fn default_health_interval_secs() -> u64 {
+    300
+}
+
+fn default_health_slow_ms() -> u64 {
+    2000
+}
+
+fn default_health_feed_stale_secs() -> u64 {
+    300
+}
+
+fn default_health_min_free_disk_mb() -> u64 {
+    500
+}
+
+fn default_health_confirmations() -> u32 {
+    2
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_health_interval_secs(),
+            slow_ms: default_health_slow_ms(),
+            feed_stale_secs: default_health_feed_stale_secs(),
+            min_free_disk_mb: default_health_min_free_disk_mb(),
+            confirmations: default_health_confirmations(),
+        }
+    }
+}
+
+/// Order execution
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExecutorConfig {
+    /// Probe liquidity with a slice of large orders before sending the rest
+    pub two_phase: Option<TwoPhaseConfig>,
+    /// Normalize order sizes to exchange constraints and book depth
+    pub sizing: Option<OrderSizingConfig>,
+    /// How long a signal stays tradeable after it is generated (seconds,
+    /// 0 = no expiry). Its resting orders are sent GTD with the same
+    /// expiry, or cancelled once it passes on venues without GTD
+    #[serde(default)]
+    pub signal_ttl_secs: u64,
+}
+
+/// Two-phase execution of large orders
+#[derive(Debug, Clone, Deserialize)]
+pub struct TwoPhaseConfig {
+    /// Share of the order sent as the probe
+    #[serde(default = "default_probe_fraction")]
+    pub probe_fraction: Decimal,
+    /// How long a resting probe may take to fill before it is cancelled
+    #[serde(default = "default_probe_timeout_secs")]
+    pub probe_timeout_secs: u64,
+    /// Probe slippage against the signal's price above which the rest of
+    /// the order is not sent
+    #[serde(default = "default_max_acceptable_slippage_bps")]
+    pub max_acceptable_slippage_bps: Decimal,
+    /// Orders smaller than this (USDC) execute in one go
+    #[serde(default = "default_min_size_for_probe_usdc")]
+    pub min_size_for_probe_usdc: Decimal,
+}
+
+fn default_probe_fraction() -> Decimal {
+    Decimal::new(1, 1)
+}
+
+fn default_probe_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_acceptable_slippage_bps() -> Decimal {
+    Decimal::from(50)
+}
+
+fn default_min_size_for_probe_usdc() -> Decimal {
+    Decimal::from(1000)
+}
+
+impl Default for TwoPhaseConfig {
+    fn default() -> Self {
+        Self {
+            probe_fraction: default_probe_fraction(),
+            probe_timeout_secs: default_probe_timeout_secs(),
+            max_acceptable_slippage_bps: default_max_acceptable_slippage_bps(),
+            min_size_for_probe_usdc: default_min_size_for_probe_usdc(),
+        }
+    }
+}
+
+/// What to do with an order below the minimum notional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubMinimumPolicy {
+    /// Drop it
+    #[default]
+    Skip,
+    /// Carry its size over to the market's next signals until the sum
+    /// reaches the minimum
+    Accumulate,
+}
+
+/// Pre-trade order size normalization
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderSizingConfig {
+    /// Smallest order sent (USDC), on top of each market's minimum size
+    #[serde(default = "default_min_order_notional_usdc")]
+    pub min_order_notional_usdc: Decimal,
+    #[serde(default)]
+    pub sub_minimum: SubMinimumPolicy,
+    /// Largest share of the visible depth on the taking side one order may take
+    #[serde(default = "default_max_book_depth_fraction")]
+    pub max_book_depth_fraction: Decimal,
+}
+
+fn default_min_order_notional_usdc() -> Decimal {
+    Decimal::from(5)
+}
+
+fn default_max_book_depth_fraction() -> Decimal {
+    Decimal::new(5, 1)
+}
+
+impl Default for OrderSizingConfig {
+    fn default() -> Self {
+        Self {
+            min_order_notional_usdc: default_min_order_notional_usdc(),
+            sub_minimum: SubMinimumPolicy::default(),
+            max_book_depth_fraction: default_max_book_depth_fraction(),
+        }
+    }
+}
+
+/// First look at newly created markets, which often misprice early
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoveryConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds between polls for new markets
+    #[serde(default = "default_discovery_interval_secs")]
+    pub interval_secs: u64,
+    /// Only markets created within this many hours count as new
+    #[serde(default = "default_discovery_max_age_hours")]
+    pub max_age_hours: i64,
+    #[serde(default = "default_discovery_min_volume")]
+    pub min_volume: Decimal,
+    #[serde(default = "default_discovery_min_liquidity")]
+    pub min_liquidity: Decimal,
+    /// Markets analyzed per poll at most
+    #[serde(default = "default_discovery_max_markets")]
+    pub max_markets: usize,
+    /// Markets in each Telegram digest, largest absolute edge first
+    #[serde(default = "default_discovery_digest_size")]
+    pub digest_size: usize,
+    /// Add markets whose first-look edge reaches this to the scan set
+    /// (unset = alert only)
+    #[serde(default)]
+    pub auto_add_edge: Option<Decimal>,
+}
+
+fn default_discovery_interval_secs() -> u64 {
+    1800
+}
+
+fn default_discovery_max_age_hours() -> i64 {
+    6
+}
+
+fn default_discovery_min_volume() -> Decimal {
+    Decimal::from(10_000)
+}
+
+fn default_discovery_min_liquidity() -> Decimal {
+    Decimal::from(5_000)
+}
+
+fn default_discovery_max_markets() -> usize {
+    10
+}
+
+fn default_discovery_digest_size() -> usize {
+    5
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_discovery_interval_secs(),
+            max_age_hours: default_discovery_max_age_hours(),
+            min_volume: default_discovery_min_volume(),
+            min_liquidity: default_discovery_min_liquidity(),
+            max_markets: default_discovery_max_markets(),
+            digest_size: default_discovery_digest_size(),
+            auto_add_edge: None,
+        }
+    }
+}
+
+/// Immediate exits of crypto Up/Down positions on adverse Binance ticks,
+/// without waiting for the next scan
+#[derive(Debug, Clone, Deserialize)]
+pub struct FastStopConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Exit once the estimated chance of the position resolving in our
+    /// favour falls below this
+    #[serde(default = "default_fast_stop_min_probability")]
+    pub min_probability: Decimal,
+    /// Exit once the underlying moves this many basis points against the
+    /// position...
+    #[serde(default = "default_fast_stop_adverse_move_bps")]
+    pub adverse_move_bps: Decimal,
+    /// ...within this many seconds
+    #[serde(default = "default_fast_stop_adverse_window_secs")]
+    pub adverse_window_secs: u64,
+}
+
+fn default_fast_stop_min_probability() -> Decimal {
+    Decimal::new(20, 2)
+}
+
+fn default_fast_stop_adverse_move_bps() -> Decimal {
+    Decimal::from(30)
+}
+
+fn default_fast_stop_adverse_window_secs() -> u64 {
+    30
+}
+
+impl Default for FastStopConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_probability: default_fast_stop_min_probability(),
+            adverse_move_bps: default_fast_stop_adverse_move_bps(),
+            adverse_window_secs: default_fast_stop_adverse_window_secs(),
+        }
+    }
+}
+
+/// Smaller positions for strategies and market categories without a track
+/// record (see `risk::RampPolicy`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RampConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Fraction of the full size cap a new strategy or category starts at
+    #[serde(default = "default_ramp_initial_fraction")]
+    pub initial_fraction: Decimal,
+    /// Added to the fraction after every `trades_per_step` closed trades...
+    #[serde(default = "default_ramp_step")]
+    pub step: Decimal,
+    #[serde(default = "default_ramp_trades_per_step")]
+    pub trades_per_step: u32,
+    /// ...won at least this often
+    #[serde(default = "default_ramp_min_win_rate")]
+    pub min_win_rate: Decimal,
+    /// Losing trades in a row that set the fraction back (0 never does)
+    #[serde(default = "default_ramp_losing_streak")]
+    pub losing_streak: u32,
+    /// How far a losing streak sets the fraction back
+    #[serde(default)]
+    pub on_losing_streak: RampSetback,
+}
+
+/// What a losing streak does to a ramp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RampSetback {
+    /// Back to `initial_fraction`
+    #[default]
+    Reset,
+    /// One `step` down, not below `initial_fraction`
+    StepDown,
+}
+
+fn default_ramp_initial_fraction() -> Decimal {
+    Decimal::new(25, 2)
+}
+
+fn default_ramp_step() -> Decimal {
+    Decimal::new(10, 2)
+}
+
+fn default_ramp_trades_per_step() -> u32 {
+    10
+}
+
+fn default_ramp_min_win_rate() -> Decimal {
+    Decimal::new(45, 2)
+}
+
+fn default_ramp_losing_streak() -> u32 {
+    5
+}
+
+impl Default for RampConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_fraction: default_ramp_initial_fraction(),
+            step: default_ramp_step(),
+            trades_per_step: default_ramp_trades_per_step(),
+            min_win_rate: default_ramp_min_win_rate(),
+            losing_streak: default_ramp_losing_streak(),
+            on_losing_streak: RampSetback::default(),
+        }
+    }
+}
+
+/// Per-market microstructure profiles steering execution and signal edge
+/// (see `analysis::market_profile`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketProfileConfig {
+    /// Days of book samples and fills a profile is built from
+    #[serde(default = "default_profile_window_days")]
+    pub window_days: i64,
+    /// Samples a profile needs before it's acted on
+    #[serde(default = "default_profile_min_samples")]
+    pub min_samples: u32,
+    /// Median spread from which a market counts as wide
+    #[serde(default = "default_profile_wide_spread")]
+    pub wide_spread: Decimal,
+    /// Share of a wide market's median spread added to `min_edge`
+    #[serde(default = "default_profile_spread_edge_fraction")]
+    pub spread_edge_fraction: Decimal,
+    /// How long a maker order on a wide market rests before the rest is taken
+    #[serde(default = "default_profile_maker_wait_secs")]
+    pub maker_wait_secs: u64,
+    /// Largest slice, as a fraction of the median top-of-book depth
+    #[serde(default = "default_profile_max_slice_depth_fraction")]
+    pub max_slice_depth_fraction: Decimal,
+    /// Slices are never smaller than this (shares)
+    #[serde(default = "default_profile_min_slice_shares")]
+    pub min_slice_shares: Decimal,
+}
+
+fn default_profile_window_days() -> i64 {
+    7
+}
+
+fn default_profile_min_samples() -> u32 {
+    30
+}
+
+fn default_profile_wide_spread() -> Decimal {
+    Decimal::new(4, 2)
+}
+
+fn default_profile_spread_edge_fraction() -> Decimal {
+    Decimal::new(5, 1)
+}
+
+fn default_profile_maker_wait_secs() -> u64 {
+    30
+}
+
+fn default_profile_max_slice_depth_fraction() -> Decimal {
+    Decimal::new(5, 1)
+}
+
+fn default_profile_min_slice_shares() -> Decimal {
+    Decimal::from(5)
+}
+
+impl Default for MarketProfileConfig {
+    fn default() -> Self {
+        Self {
+            window_days: default_profile_window_days(),
+            min_samples: default_profile_min_samples(),
+            wide_spread: default_profile_wide_spread(),
+            spread_edge_fraction: default_profile_spread_edge_fraction(),
+            maker_wait_secs: default_profile_maker_wait_secs(),
+            max_slice_depth_fraction: default_profile_max_slice_depth_fraction(),
+            min_slice_shares: default_profile_min_slice_shares(),
+        }
+    }
+}
+
+/// Redemption of winning shares in resolved markets (see
+/// `portfolio::redeem`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedeemConfig {
+    /// Send the redeem transaction on its own; otherwise resolved winnings
+    /// are only tracked as pending redemption
+    #[serde(default)]
+    pub auto_redeem: bool,
+    /// Polygon JSON-RPC endpoint the transaction is sent through
+    #[serde(default = "default_redeem_rpc_url")]
+    pub rpc_url: String,
+    /// POL price in USDC, for the gas cost booked against a redemption
+    #[serde(default = "default_redeem_pol_price")]
+    pub pol_price_usdc: Decimal,
+    /// Attempts at a redemption before it's left for the next check
+    #[serde(default = "default_redeem_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait between attempts
+    #[serde(default = "default_redeem_retry_delay_secs")]
+    pub retry_delay_secs: u64,
+    /// How often open positions are checked for resolution
+    #[serde(default = "default_redeem_check_interval_secs")]
+    pub check_interval_secs: u64,
+}
+
+fn default_redeem_rpc_url() -> String {
+    "https://polygon-rpc.com".to_string()
+}
+
+fn default_redeem_pol_price() -> Decimal {
+    Decimal::new(5, 1)
+}
+
+fn default_redeem_max_attempts() -> u32 {
+    3
+}
+
+fn default_redeem_retry_delay_secs() -> u64 {
+    30
+}
+
+fn default_redeem_check_interval_secs() -> u64 {
+    600
+}
+
+impl Default for RedeemConfig {
+    fn default() -> Self {
+        Self {
+            auto_redeem: false,
+            rpc_url: default_redeem_rpc_url(),
+            pol_price_usdc: default_redeem_pol_price(),
+            max_attempts: default_redeem_max_attempts(),
+            retry_delay_secs: default_redeem_retry_delay_secs(),
+            check_interval_secs: default_redeem_check_interval_secs(),
+        }
+    }
+}
+
+/// Structured events for external systems (see `events::sink`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventSinkConfig {
+    /// Print each event as a line of JSON on stdout
+    #[serde(default)]
+    pub stdout: bool,
+    /// Append each event as a line of JSON to this file
+    pub file: Option<String>,
+}
+
+/// NewsAPI alerts on headlines about the markets of our open positions
+#[derive(Debug, Clone, Deserialize)]
+pub struct PositionNewsConfig {
+    /// NewsAPI key
+    pub api_key: String,
+    /// Seconds between news polls
+    #[serde(default = "default_position_news_poll_secs")]
+    pub poll_secs: u64,
+    /// Minutes between reloads of the positions' keywords
+    #[serde(default = "default_position_news_keyword_refresh_mins")]
+    pub keyword_refresh_mins: u64,
+}
+
+fn default_position_news_poll_secs() -> u64 {
+    300
+}
+
+fn default_position_news_keyword_refresh_mins() -> u64 {
+    30
+}
+
+/// Heartbeats of the long-running tasks, checked by `monitor::watchdog`
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchdogConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds between checks
+    #[serde(default = "default_watchdog_check_interval_secs")]
+    pub check_interval_secs: u64,
+    /// Seconds without a scan before the main loop counts as stalled;
+    /// never less than three scan intervals
+    #[serde(default = "default_watchdog_main_loop_stale_secs")]
+    pub main_loop_stale_secs: u64,
+    /// Seconds without progress before a feed, ingester source, the
+    /// copy-trade loop or notification delivery counts as stalled
+    #[serde(default = "default_watchdog_task_stale_secs")]
+    pub task_stale_secs: u64,
+    /// Exit the process when the main loop stalls, for systemd to restart it
+    #[serde(default)]
+    pub exit_on_main_loop_stall: bool,
+}
+
+fn default_watchdog_check_interval_secs() -> u64 {
+    30
+}
+
+fn default_watchdog_main_loop_stale_secs() -> u64 {
+    600
+}
+
+fn default_watchdog_task_stale_secs() -> u64 {
+    300
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: default_watchdog_check_interval_secs(),
+            main_loop_stale_secs: default_watchdog_main_loop_stale_secs(),
+            task_stale_secs: default_watchdog_task_stale_secs(),
+            exit_on_main_loop_stall: false,
+        }
+    }
+}
+
+/// Runtime state snapshots restored after a crash or restart
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecoveryConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Snapshot file, replaced atomically on every write
+    #[serde(default = "default_recovery_path")]
+    pub path: String,
+    /// Seconds between snapshots
+    #[serde(default = "default_recovery_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_recovery_path() -> String {
+    "data/state_snapshot.json".to_string()
+}
+
+fn default_recovery_interval_secs() -> u64 {
+    60
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: default_recovery_path(),
+            interval_secs: default_recovery_interval_secs(),
+        }
+    }
+}
+
+/// Fee rates of one market, in basis points of `min(price, 1 - price)` per
+/// share; a negative maker rate is a rebate
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub struct FeeSchedule {
+    #[serde(default)]
+    pub taker_fee_bps: Decimal,
+    #[serde(default)]
+    pub maker_fee_bps: Decimal,
+}
+
+/// Polymarket fee schedule used for edge, fills and P&L alike
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeConfig {
+    /// Rates of markets without an override
+    #[serde(default)]
+    pub taker_fee_bps: Decimal,
+    #[serde(default)]
+    pub maker_fee_bps: Decimal,
+    /// Flat cost of every fill in USDC on top of the rates: gas, or the
+    /// withdrawal cost it's amortized to
+    #[serde(default)]
+    pub gas_per_order_usdc: Decimal,
+    /// Rates of crypto Up/Down markets, which are created too often to
+    /// list by ID
+    #[serde(default = "default_crypto_updown_fees")]
+    pub crypto_updown: FeeSchedule,
+    /// Per-market overrides, by market ID
+    #[serde(default)]
+    pub markets: std::collections::HashMap<String, FeeSchedule>,
+}
+
+fn default_crypto_updown_fees() -> FeeSchedule {
+    // 2% of notional at 50c
+    FeeSchedule {
+        taker_fee_bps: Decimal::new(400, 0),
+        maker_fee_bps: Decimal::ZERO,
+    }
+}
+
+impl FeeConfig {
+    /// Rates of markets without an override
+    pub fn default_schedule(&self) -> FeeSchedule {
+        FeeSchedule {
+            taker_fee_bps: self.taker_fee_bps,
+            maker_fee_bps: self.maker_fee_bps,
+        }
+    }
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            taker_fee_bps: Decimal::ZERO,
+            maker_fee_bps: Decimal::ZERO,
+            gas_per_order_usdc: Decimal::ZERO,
+            crypto_updown: default_crypto_updown_fees(),
+            markets: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// Which markets the main loop scans
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketScanConfig {
+    /// Gamma categories to scan, e.g. ["Crypto", "Politics"] (empty = all)
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Minimum liquidity for regular markets (USDC)
+    #[serde(default = "default_min_liquidity")]
+    pub min_liquidity: Decimal,
+    /// Minimum liquidity for crypto Up/Down markets (USDC)
+    #[serde(default = "default_min_crypto_liquidity")]
+    pub min_crypto_liquidity: Decimal,
+    /// Only markets resolving within this many days
+    #[serde(default)]
+    pub max_days_to_resolution: Option<i64>,
+    /// Minimum traded volume (USDC)
+    #[serde(default)]
+    pub min_volume: Decimal,
+    /// Regular markets fetched per scan
+    #[serde(default = "default_scan_limit")]
+    pub limit: usize,
+    /// Pages of 500 markets paged through to find them, so that markets
+    /// past the first page are found when filters drop many
+    #[serde(default = "default_scan_max_pages")]
+    pub max_pages: usize,
+    /// Market IDs scanned every cycle whatever the filters above say;
+    /// changed at runtime with /watch and /unwatch
+    #[serde(default)]
+    pub watchlist: Vec<String>,
+}
+
+fn default_min_liquidity() -> Decimal {
+    Decimal::new(10000, 0)
+}
+
+fn default_min_crypto_liquidity() -> Decimal {
+    Decimal::new(1000, 0)
+}
+
+fn default_scan_limit() -> usize {
+    20
+}
+
+fn default_scan_max_pages() -> usize {
+    1
+}
+
+impl Default for MarketScanConfig {
+    fn default() -> Self {
+        Self {
+            categories: Vec::new(),
+            min_liquidity: default_min_liquidity(),
+            min_crypto_liquidity: default_min_crypto_liquidity(),
+            max_days_to_resolution: None,
+            min_volume: Decimal::ZERO,
+            limit: default_scan_limit(),
+            max_pages: default_scan_max_pages(),
+            watchlist: Vec::new(),
+        }
+    }
+}
+
+/// Dry-run account simulation
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationConfig {
+    /// Starting balance of a new simulated account (USDC)
+    #[serde(default = "default_sim_balance")]
+    pub initial_balance: Decimal,
+    /// Slippage applied to every simulated fill, in basis points
+    #[serde(default = "default_sim_slippage_bps")]
+    pub slippage_bps: Decimal,
+    /// SQLite file holding the simulated account
+    #[serde(default = "default_sim_db_path")]
+    pub db_path: String,
+}
+
+fn default_sim_balance() -> Decimal {
+    Decimal::new(1000, 0)
+}
+
+fn default_sim_slippage_bps() -> Decimal {
+    Decimal::new(20, 0)
+}
+
+fn default_sim_db_path() -> String {
+    "simulation.db".to_string()
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            initial_balance: default_sim_balance(),
+            slippage_bps: default_sim_slippage_bps(),
+            db_path: default_sim_db_path(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -33,29 +1004,261 @@ pub struct CopyTradeConfig {
     /// Delay before copying (seconds)
     #[serde(default)]
     pub delay_secs: u64,
+    /// Discover traders from the leaderboard and update the follow list weekly
+    #[serde(default)]
+    pub auto_discover: bool,
+    /// Leaderboard discovery thresholds
+    #[serde(default)]
+    pub discovery: TraderDiscoveryConfig,
+    /// Settled copied trades needed before a trader's weight is recalibrated
+    #[serde(default = "default_recalibrate_min_trades")]
+    pub recalibrate_min_trades: u32,
+    /// Stop copying traders whose recalibrated weight falls below this
+    #[serde(default = "default_min_leader_weight")]
+    pub min_leader_weight: f64,
+}
+
+fn default_copy_ratio() -> f64 {
+    0.5
+}
+
+fn default_recalibrate_min_trades() -> u32 {
+    5
+}
+
+fn default_min_leader_weight() -> f64 {
+    0.3
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraderDiscoveryConfig {
+    /// Follow leaderboard traders scoring above this
+    #[serde(default = "default_add_threshold")]
+    pub add_threshold: f64,
+    /// Unfollow traders scoring below this
+    #[serde(default = "default_remove_threshold")]
+    pub remove_threshold: f64,
+    /// Minimum closed positions in the lookback window
+    #[serde(default = "default_min_trades")]
+    pub min_trades: u32,
+    /// Minimum traded volume (USDC)
+    #[serde(default = "default_min_volume_usdc")]
+    pub min_volume_usdc: Decimal,
+    /// Lookback window for trader stats (days)
+    #[serde(default = "default_lookback_days")]
+    pub lookback_days: u32,
+}
+
+fn default_add_threshold() -> f64 {
+    0.6
+}
+
+fn default_remove_threshold() -> f64 {
+    0.3
+}
+
+fn default_min_trades() -> u32 {
+    20
+}
+
+fn default_min_volume_usdc() -> Decimal {
+    Decimal::new(10_000, 0)
+}
+
+fn default_lookback_days() -> u32 {
+    30
+}
+
+impl Default for TraderDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            add_threshold: default_add_threshold(),
+            remove_threshold: default_remove_threshold(),
+            min_trades: default_min_trades(),
+            min_volume_usdc: default_min_volume_usdc(),
+            lookback_days: default_lookback_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IngesterConfig {
+    /// Enable signal ingestion
+    #[serde(default)]
+    pub enabled: bool,
+    /// Telegram userbot configuration
+    pub telegram_userbot: Option<TelegramUserbotConfig>,
+    /// Telegram bot configuration (for public channels)
+    pub telegram_bot: Option<TelegramBotIngesterConfig>,
+    /// Twitter/X configuration
+    pub twitter: Option<TwitterConfig>,
+    /// Signal processing settings
+    #[serde(default)]
+    pub processing: ProcessingConfig,
+    /// Channel capacities and load-shedding policy
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+    /// Author trust scores (priors for the learned trust)
+    #[serde(default)]
+    pub author_trust: std::collections::HashMap<String, f64>,
+    /// Learning author trust from signal outcomes
+    #[serde(default)]
+    pub trust: AuthorTrustConfig,
+    /// MinHash similarity above which a message is a near-duplicate of a
+    /// recent one and skipped
+    #[serde(default = "default_dedup_threshold")]
+    pub dedup_threshold: f64,
+    /// Social sentiment from ingested signals shown to the model
+    #[serde(default)]
+    pub sentiment: SentimentConfig,
+    /// Rules mapping signal tokens to Polymarket markets
+    #[serde(default)]
+    pub mapping: SignalMappingConfig,
+    /// Seconds without a message after which a source is reported as
+    /// silent, once per silence
+    #[serde(default = "default_stale_source_secs")]
+    pub stale_source_secs: u64,
+}
+
+fn default_dedup_threshold() -> f64 {
+    0.8
+}
+
+fn default_stale_source_secs() -> u64 {
+    3 * 3600
+}
+
+/// Rules mapping the tokens of external signals to Polymarket markets
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SignalMappingConfig {
+    /// Tried in order; the first whose pattern matches the token is used
+    #[serde(default)]
+    pub rules: Vec<MappingRuleConfig>,
+}
+
+/// One `[[ingester.mapping.rules]]` entry: markets whose question contains
+/// every keyword, or the listed markets
+#[derive(Debug, Clone, Deserialize)]
+pub struct MappingRuleConfig {
+    /// Regex matched against the signal's token, case-insensitively
+    pub pattern: String,
+    /// Words every mapped market's question must contain
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Map straight to these markets instead of searching
+    #[serde(default)]
+    pub market_ids: Vec<String>,
+    /// Scales the signal's confidence on the mapped markets
+    #[serde(default = "default_confidence_boost")]
+    pub confidence_boost: f64,
+    /// Most markets mapped, by volume
+    #[serde(default = "default_mapping_max_markets")]
+    pub max_markets: usize,
+}
+
+fn default_confidence_boost() -> f64 {
+    1.0
+}
+
+fn default_mapping_max_markets() -> usize {
+    3
+}
+
+/// Decaying per-token sentiment built from ingested signals
+#[derive(Debug, Clone, Deserialize)]
+pub struct SentimentConfig {
+    /// Age at which a signal counts half (minutes)
+    #[serde(default = "default_sentiment_half_life_mins")]
+    pub half_life_mins: f64,
+    /// Signals older than this are forgotten (hours)
+    #[serde(default = "default_sentiment_max_age_hours")]
+    pub max_age_hours: f64,
+    /// Most the ensemble probability is moved by a full-strength sentiment
+    /// score; 0 leaves predictions alone and only shows it in the prompt
+    #[serde(default)]
+    pub max_adjustment: f64,
+}
+
+fn default_sentiment_half_life_mins() -> f64 {
+    60.0
+}
+
+fn default_sentiment_max_age_hours() -> f64 {
+    6.0
+}
+
+impl Default for SentimentConfig {
+    fn default() -> Self {
+        Self {
+            half_life_mins: default_sentiment_half_life_mins(),
+            max_age_hours: default_sentiment_max_age_hours(),
+            max_adjustment: 0.0,
+        }
+    }
+}
+
+/// Author trust learned as a smoothed, decaying hit rate
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthorTrustConfig {
+    /// Trust of authors without an `author_trust` entry before any outcomes
+    #[serde(default = "default_prior_trust")]
+    pub prior_trust: f64,
+    /// How many observations the prior is worth
+    #[serde(default = "default_prior_weight")]
+    pub prior_weight: f64,
+    /// Age at which an outcome counts half (days)
+    #[serde(default = "default_trust_half_life_days")]
+    pub half_life_days: f64,
+    /// Authors whose trust falls below this are muted
+    #[serde(default = "default_mute_below")]
+    pub mute_below: f64,
+    /// Outcomes needed before an author can be muted
+    #[serde(default = "default_mute_min_samples")]
+    pub mute_min_samples: usize,
+    /// How often outcomes are resolved and trust recomputed (seconds)
+    #[serde(default = "default_trust_recompute_secs")]
+    pub recompute_interval_secs: u64,
+    /// Fixed trust for these authors, never learned or muted
+    #[serde(default)]
+    pub pinned: std::collections::HashMap<String, f64>,
+}
+
+fn default_prior_trust() -> f64 {
+    0.3
+}
+
+fn default_prior_weight() -> f64 {
+    10.0
+}
+
+fn default_trust_half_life_days() -> f64 {
+    14.0
+}
+
+fn default_mute_below() -> f64 {
+    0.35
+}
+
+fn default_mute_min_samples() -> usize {
+    20
 }
 
-fn default_copy_ratio() -> f64 {
-    0.5
+fn default_trust_recompute_secs() -> u64 {
+    3600
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct IngesterConfig {
-    /// Enable signal ingestion
-    #[serde(default)]
-    pub enabled: bool,
-    /// Telegram userbot configuration
-    pub telegram_userbot: Option<TelegramUserbotConfig>,
-    /// Telegram bot configuration (for public channels)
-    pub telegram_bot: Option<TelegramBotIngesterConfig>,
-    /// Twitter/X configuration
-    pub twitter: Option<TwitterConfig>,
-    /// Signal processing settings
-    #[serde(default)]
-    pub processing: ProcessingConfig,
-    /// Author trust scores
-    #[serde(default)]
-    pub author_trust: std::collections::HashMap<String, f64>,
+impl Default for AuthorTrustConfig {
+    fn default() -> Self {
+        Self {
+            prior_trust: default_prior_trust(),
+            prior_weight: default_prior_weight(),
+            half_life_days: default_trust_half_life_days(),
+            mute_below: default_mute_below(),
+            mute_min_samples: default_mute_min_samples(),
+            recompute_interval_secs: default_trust_recompute_secs(),
+            pinned: std::collections::HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -91,7 +1294,59 @@ pub struct TwitterConfig {
     pub nitter_instance: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+/// What to do when a signal queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Wait for space (sources stall under load)
+    #[default]
+    Block,
+    /// Evict queued signals, lowest trust first
+    DropOldest,
+    /// Admit one in `sample_every` arrivals while full
+    Sample,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackpressureConfig {
+    /// Policy applied to both the raw and parsed signal queues
+    #[serde(default)]
+    pub policy: BackpressurePolicy,
+    /// Sampling rate for the `sample` policy
+    #[serde(default = "default_sample_every")]
+    pub sample_every: u32,
+    /// Raw signal queue capacity
+    #[serde(default = "default_raw_capacity")]
+    pub raw_capacity: usize,
+    /// Parsed signal queue capacity
+    #[serde(default = "default_parsed_capacity")]
+    pub parsed_capacity: usize,
+}
+
+fn default_sample_every() -> u32 {
+    4
+}
+
+fn default_raw_capacity() -> usize {
+    500
+}
+
+fn default_parsed_capacity() -> usize {
+    100
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            policy: BackpressurePolicy::default(),
+            sample_every: default_sample_every(),
+            raw_capacity: default_raw_capacity(),
+            parsed_capacity: default_parsed_capacity(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct ProcessingConfig {
     /// Aggregation window in seconds
     #[serde(default = "default_aggregation_window")]
@@ -116,20 +1371,160 @@ fn default_min_agg_score() -> f64 {
     0.6
 }
 
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            aggregation_window_secs: default_aggregation_window(),
+            min_confidence: default_min_confidence(),
+            min_agg_score: default_min_agg_score(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct PolymarketConfig {
     /// CLOB API endpoint
     pub clob_url: String,
     /// Gamma API endpoint (market data)
     pub gamma_url: String,
-    /// Private key for signing (hex, without 0x prefix)
-    pub private_key: String,
+    /// Data API endpoint (leaderboard, user positions)
+    #[serde(default = "default_data_api_url")]
+    pub data_api_url: String,
+    /// Private key for signing (hex, without 0x prefix); unused when
+    /// `accounts` is set or the key comes from elsewhere
+    #[serde(default, deserialize_with = "crate::keys::deserialize_shared")]
+    pub private_key: Arc<PrivateKey>,
+    /// Where the private keys come from
+    #[serde(default)]
+    pub key_source: KeySource,
+    /// Encrypted key file, with `key_source = "encrypted_file"`; unused
+    /// when `accounts` is set
+    pub key_file: Option<String>,
+    /// OS keyring service the keys are stored under, with
+    /// `key_source = "keyring"`; each account's under its ID
+    #[serde(default = "default_keyring_service")]
+    pub keyring_service: String,
     /// Funder address (for proxy wallets)
     pub funder_address: Option<String>,
     /// Chain ID (137 for Polygon mainnet)
     pub chain_id: u64,
     /// Signature type (0=EOA, 1=Magic, 2=Proxy)
     pub signature_type: u8,
+    /// Wallets traded from one process; the first is also used for market
+    /// data. Empty means the single account above.
+    #[serde(default)]
+    pub accounts: Vec<AccountConfig>,
+}
+
+fn default_data_api_url() -> String {
+    "https://data-api.polymarket.com".to_string()
+}
+
+fn default_keyring_service() -> String {
+    "polymarket-bot".to_string()
+}
+
+/// Where the wallet private keys are kept; see [`crate::keys`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// `private_key` in the config
+    #[default]
+    Plaintext,
+    /// A passphrase-encrypted `key_file`
+    EncryptedFile,
+    /// The OS keyring
+    Keyring,
+}
+
+/// Account ID of the top-level credentials in single-account configs
+pub const DEFAULT_ACCOUNT_ID: &str = "default";
+
+impl PolymarketConfig {
+    /// Accounts to trade: `accounts`, or the top-level credentials as a
+    /// single account with the global risk limits
+    pub fn account_configs(&self) -> Vec<AccountConfig> {
+        if !self.accounts.is_empty() {
+            return self.accounts.clone();
+        }
+        vec![AccountConfig {
+            id: DEFAULT_ACCOUNT_ID.to_string(),
+            private_key: self.private_key.clone(),
+            key_file: self.key_file.clone(),
+            funder_address: self.funder_address.clone(),
+            signature_type: None,
+            capital: None,
+            risk: RiskOverrides::default(),
+        }]
+    }
+
+    /// Client settings of the first account, used for market data
+    pub fn primary_account(&self) -> PolymarketConfig {
+        let accounts = self.account_configs();
+        self.for_account(&accounts[0])
+    }
+
+    /// Client settings for one account: the shared endpoints with the
+    /// account's credentials
+    pub fn for_account(&self, account: &AccountConfig) -> PolymarketConfig {
+        PolymarketConfig {
+            private_key: account.private_key.clone(),
+            funder_address: account.funder_address.clone(),
+            signature_type: account.signature_type.unwrap_or(self.signature_type),
+            accounts: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// One wallet of a multi-account setup
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountConfig {
+    /// Short name used in storage, reports and Telegram (e.g. "fund")
+    pub id: String,
+    /// Private key for signing (hex, without 0x prefix), with
+    /// `key_source = "plaintext"`
+    #[serde(default, deserialize_with = "crate::keys::deserialize_shared")]
+    pub private_key: Arc<PrivateKey>,
+    /// Encrypted key file, with `key_source = "encrypted_file"`
+    pub key_file: Option<String>,
+    /// Funder address (for proxy wallets)
+    pub funder_address: Option<String>,
+    /// Signature type; defaults to the top-level one
+    pub signature_type: Option<u8>,
+    /// Capital allotted to the bot; positions are sized off the lower of
+    /// this and the wallet balance
+    pub capital: Option<Decimal>,
+    /// Overrides of the global `[risk]` limits
+    #[serde(default)]
+    pub risk: RiskOverrides,
+}
+
+/// Per-account replacements for `[risk]` values; unset fields keep the
+/// global value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RiskOverrides {
+    pub max_position_pct: Option<Decimal>,
+    pub max_exposure_pct: Option<Decimal>,
+    pub max_daily_loss_pct: Option<Decimal>,
+    pub min_balance_reserve: Option<Decimal>,
+    pub max_open_positions: Option<usize>,
+    pub max_total_kelly: Option<Decimal>,
+}
+
+impl RiskOverrides {
+    /// `base` with the overridden fields replaced
+    pub fn apply(&self, base: &RiskConfig) -> RiskConfig {
+        RiskConfig {
+            max_position_pct: self.max_position_pct.unwrap_or(base.max_position_pct),
+            max_exposure_pct: self.max_exposure_pct.unwrap_or(base.max_exposure_pct),
+            max_daily_loss_pct: self.max_daily_loss_pct.unwrap_or(base.max_daily_loss_pct),
+            min_balance_reserve: self.min_balance_reserve.unwrap_or(base.min_balance_reserve),
+            max_open_positions: self.max_open_positions.unwrap_or(base.max_open_positions),
+            max_total_kelly: self.max_total_kelly.unwrap_or(base.max_total_kelly),
+            ..base.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -150,6 +1545,199 @@ pub struct StrategyConfig {
     /// Use sqrt scaling for compound growth (safer)
     #[serde(default = "default_true")]
     pub compound_sqrt_scaling: bool,
+    /// Model disagreement (std dev of ensemble probabilities) above which
+    /// signals are sized at half
+    #[serde(default = "default_high_epistemic_threshold")]
+    pub high_epistemic_threshold: Decimal,
+    /// Per-strategy mode (`llm`, `realtime`, `crypto_hf`); unlisted
+    /// strategies trade live
+    #[serde(default)]
+    pub modes: std::collections::HashMap<String, StrategyMode>,
+    /// Per-strategy account allowlists; unlisted strategies trade on every
+    /// account
+    #[serde(default)]
+    pub accounts: std::collections::HashMap<String, Vec<String>>,
+    /// Experiment label tagged onto every trade for attribution
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// Per-strategy experiment labels, overriding `experiment`
+    #[serde(default)]
+    pub experiments: std::collections::HashMap<String, String>,
+    /// When set, trade on a per-market Bayesian posterior in which a
+    /// full-confidence model prediction counts as this many observations
+    #[serde(default)]
+    pub bayesian_prior_strength: Option<Decimal>,
+    /// Strategy to switch to in each detected BTC regime, e.g.
+    /// `BullishTrend = "momentum"`
+    #[serde(default)]
+    pub regime_map: std::collections::HashMap<crate::regime::MarketRegime, String>,
+    /// Regime confidence above which `regime_map` applies; below it the
+    /// default strategy is active
+    #[serde(default = "default_regime_confidence_threshold")]
+    pub regime_confidence_threshold: Decimal,
+    /// Markets whose model analysis runs at once during a scan
+    #[serde(default = "default_max_concurrent_analyses")]
+    pub max_concurrent_analyses: usize,
+    /// Seconds one market's analysis (or one batch request covering it)
+    /// may take before it is skipped for the scan
+    #[serde(default = "default_analysis_timeout_secs")]
+    pub analysis_timeout_secs: u64,
+    /// Hour-of-day and day-of-week position sizing
+    #[serde(default)]
+    pub seasonality: SeasonalityConfig,
+    /// Multi-factor grading of signals before execution
+    #[serde(default)]
+    pub scoring: SignalScoringConfig,
+}
+
+/// Intraday and day-of-week volatility patterns (see
+/// `analysis::seasonality`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SeasonalityConfig {
+    /// Scale positions by the inverse of the expected volatility at the
+    /// time of the signal, for constant risk through the day and week
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Multi-factor signal grading (see `strategy::signal_scorer`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SignalScoringConfig {
+    /// Lowest grade executed; `F` executes every signal
+    #[serde(default = "default_min_grade")]
+    pub min_grade: crate::strategy::signal_scorer::Grade,
+    /// Weight of each score component, scaled to sum to 1
+    #[serde(default)]
+    pub weights: crate::strategy::signal_scorer::ScorerWeights,
+}
+
+fn default_min_grade() -> crate::strategy::signal_scorer::Grade {
+    crate::strategy::signal_scorer::Grade::B
+}
+
+impl Default for SignalScoringConfig {
+    fn default() -> Self {
+        Self {
+            min_grade: default_min_grade(),
+            weights: Default::default(),
+        }
+    }
+}
+
+impl StrategyConfig {
+    /// Experiment label of `strategy`'s trades, if any
+    pub fn experiment_for(&self, strategy: &str) -> Option<&str> {
+        self.experiments
+            .get(strategy)
+            .or(self.experiment.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Whether `strategy`'s signals are traded on `account`
+    pub fn trades_on(&self, strategy: &str, account: &str) -> bool {
+        self.accounts
+            .get(strategy)
+            .is_none_or(|allowed| allowed.iter().any(|a| a == account))
+    }
+}
+
+fn default_high_epistemic_threshold() -> Decimal {
+    Decimal::new(15, 2)
+}
+
+fn default_regime_confidence_threshold() -> Decimal {
+    Decimal::new(60, 2)
+}
+
+fn default_max_concurrent_analyses() -> usize {
+    crate::scanner::DEFAULT_MAX_CONCURRENT_ANALYSES
+}
+
+fn default_analysis_timeout_secs() -> u64 {
+    crate::scanner::DEFAULT_ANALYSIS_TIMEOUT_SECS
+}
+
+/// Whether a strategy's signals are traded, only recorded, or dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyMode {
+    /// Signals go to the real executor
+    #[default]
+    Live,
+    /// Signals are paper-filled into `shadow_trades` and tracked for
+    /// counterfactual P&L
+    Shadow,
+    /// Signals are discarded
+    Off,
+}
+
+impl std::str::FromStr for StrategyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "live" => Ok(Self::Live),
+            "shadow" => Ok(Self::Shadow),
+            "off" => Ok(Self::Off),
+            other => Err(format!("unknown strategy mode: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for StrategyMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Live => "live",
+            Self::Shadow => "shadow",
+            Self::Off => "off",
+        })
+    }
+}
+
+/// User-defined alert rules, checked every scan
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AlertsConfig {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+/// Notify when `expression` holds over the bot's metrics, e.g.
+/// `daily_pnl < -50 && open_positions > 3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub expression: String,
+    #[serde(default)]
+    pub severity: AlertSeverity,
+    /// Minimum time between two notifications of this rule
+    #[serde(default = "default_alert_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// Notification text; `{metric}` placeholders are replaced by current
+    /// values. Defaults to the expression.
+    #[serde(default)]
+    pub message_template: String,
+}
+
+fn default_alert_cooldown_secs() -> u64 {
+    3600
+}
+
+/// Severity of a user-defined alert rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl std::fmt::Display for AlertSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -164,6 +1752,80 @@ pub struct RiskConfig {
     pub min_balance_reserve: Decimal,
     /// Maximum number of open positions
     pub max_open_positions: usize,
+    /// How long an executed signal blocks an identical one (seconds)
+    #[serde(default = "default_signal_dedup_ttl_secs")]
+    pub signal_dedup_ttl_secs: u64,
+    /// Cap on the summed Kelly fractions committed to open positions
+    /// (1.0 = full Kelly); new positions are clipped to stay under it
+    #[serde(default = "default_max_total_kelly")]
+    pub max_total_kelly: Decimal,
+    /// Pause trading after this many API failures in a row (0 = never)
+    #[serde(default = "default_max_consecutive_api_failures")]
+    pub max_consecutive_api_failures: u32,
+    /// How long such a pause lasts before trading is retried (seconds)
+    #[serde(default = "default_api_failure_pause_secs")]
+    pub api_failure_pause_secs: u64,
+    /// Open position limit per strategy name; strategies not listed are
+    /// only bound by `max_open_positions`
+    #[serde(default)]
+    pub max_positions_per_strategy: std::collections::HashMap<String, usize>,
+    /// How long a signal held back by a position limit stays eligible to
+    /// trade once a slot frees up (seconds)
+    #[serde(default = "default_queued_signal_ttl_secs")]
+    pub queued_signal_ttl_secs: u64,
+    /// Signals held back at most; the lowest net edge is dropped beyond it
+    #[serde(default = "default_max_queued_signals")]
+    pub max_queued_signals: usize,
+    /// How long a realized loss on a market blocks new entries there
+    /// (minutes, 0 = never)
+    #[serde(default = "default_loss_cooldown_mins")]
+    pub loss_cooldown_mins: u64,
+    /// Edge at which a signal may re-enter a market despite its cooldown;
+    /// unset keeps the market blocked until the cooldown expires
+    #[serde(default)]
+    pub loss_cooldown_override_edge: Option<Decimal>,
+    /// At the regime's open position cap, a signal whose edge beats the
+    /// weakest open position's entry edge by this much closes that position
+    /// to take its place; unset blocks new entries at the cap
+    #[serde(default)]
+    pub regime_rotation_min_edge_gain: Option<Decimal>,
+    /// Portfolio heat allowed (USDC of `size * |edge|` summed over open
+    /// positions); past 80% new signals get half Kelly, past 100% only
+    /// hedges trade. 0 = unlimited
+    #[serde(default)]
+    pub max_heat_budget: Decimal,
+    /// Size multipliers per multi-timeframe BTC regime consensus, applied
+    /// on top of the detected regime's own multiplier
+    #[serde(default)]
+    pub regime_consensus_sizing: crate::regime::ConsensusSizingConfig,
+}
+
+fn default_signal_dedup_ttl_secs() -> u64 {
+    900
+}
+
+fn default_max_total_kelly() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_max_consecutive_api_failures() -> u32 {
+    5
+}
+
+fn default_api_failure_pause_secs() -> u64 {
+    300
+}
+
+fn default_queued_signal_ttl_secs() -> u64 {
+    1800
+}
+
+fn default_max_queued_signals() -> usize {
+    20
+}
+
+fn default_loss_cooldown_mins() -> u64 {
+    30
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -183,6 +1845,87 @@ pub struct LlmConfig {
     pub model: Option<String>,
     /// Base URL for OpenAI-compatible APIs
     pub base_url: Option<String>,
+    /// Consecutive failed predictions before falling back to the market
+    /// price (default: 3)
+    #[serde(default = "default_llm_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long to stay on the fallback before retrying the LLM (default: 300)
+    #[serde(default = "default_llm_breaker_cooldown_secs")]
+    pub breaker_cooldown_secs: u64,
+    /// USD per million input tokens, for the monthly report's LLM spend
+    #[serde(default)]
+    pub input_cost_per_mtok: Option<Decimal>,
+    /// USD per million output tokens
+    #[serde(default)]
+    pub output_cost_per_mtok: Option<Decimal>,
+    /// Prediction prompt templates by market category (`crypto`,
+    /// `politics`, `sports`, `economics`, `culture`, `other`) or `default`
+    /// for the rest; the built-in prompt when empty
+    #[serde(default)]
+    pub prompts: std::collections::HashMap<String, PromptSource>,
+    /// Providers tried in order when the primary fails or times out; a
+    /// `market` provider answers with the market price
+    #[serde(default)]
+    pub fallback: Vec<LlmFallbackConfig>,
+    /// Seconds each provider of the fallback chain gets (default: 30)
+    #[serde(default = "default_llm_provider_timeout_secs")]
+    pub provider_timeout_secs: u64,
+    /// Markets predicted at once by a provider without a batch API;
+    /// `strategy.max_concurrent_analyses` when unset
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Markets per OpenAI batch request, larger batches being split
+    /// (default: 20)
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+/// A provider of the LLM fallback chain; the prompts and costs are the
+/// primary's
+#[derive(Debug, Clone, Deserialize)]
+pub struct LlmFallbackConfig {
+    /// deepseek, anthropic, openai, ollama, compatible or market
+    pub provider: String,
+    #[serde(default)]
+    pub api_key: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl LlmConfig {
+    /// This config with `fallback`'s provider
+    pub fn with_provider(&self, fallback: &LlmFallbackConfig) -> LlmConfig {
+        LlmConfig {
+            provider: fallback.provider.clone(),
+            api_key: fallback.api_key.clone(),
+            model: fallback.model.clone(),
+            base_url: fallback.base_url.clone(),
+            fallback: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Where a prompt template comes from
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptSource {
+    /// A file, reloaded when it changes
+    File(std::path::PathBuf),
+    /// The template itself
+    Text(String),
+}
+
+fn default_llm_failure_threshold() -> u32 {
+    3
+}
+
+fn default_llm_breaker_cooldown_secs() -> u64 {
+    300
+}
+
+fn default_llm_provider_timeout_secs() -> u64 {
+    30
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -203,6 +1946,46 @@ pub struct TelegramConfig {
     /// Send daily reports (default: true)
     #[serde(default = "default_true")]
     pub notify_daily: bool,
+    /// How commands are received (default: long-polling)
+    #[serde(default)]
+    pub mode: TelegramBotMode,
+}
+
+/// Slack notifications, sent alongside Telegram's
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackConfig {
+    /// Incoming-webhook URL - KEEP SECRET!
+    pub webhook_url: String,
+}
+
+/// How the command listener receives Telegram updates
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelegramBotMode {
+    /// Long-poll `getUpdates`, each request held open up to `timeout_secs`
+    Polling {
+        #[serde(default = "default_polling_timeout_secs")]
+        timeout_secs: u64,
+    },
+    /// Telegram pushes updates to `{public_url}/webhook`, served on
+    /// `listen_port`; requests must carry `secret_token`
+    Webhook {
+        public_url: String,
+        listen_port: u16,
+        secret_token: String,
+    },
+}
+
+fn default_polling_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for TelegramBotMode {
+    fn default() -> Self {
+        Self::Polling {
+            timeout_secs: default_polling_timeout_secs(),
+        }
+    }
 }
 
 fn default_true() -> bool {
@@ -218,9 +2001,152 @@ impl Config {
             .build()?;
 
         let config: Config = settings.try_deserialize()?;
+        let errors = config.validate();
+        if !errors.is_empty() {
+            return Err(ConfigValidationErrors(errors).into());
+        }
         Ok(config)
     }
 
+    /// Every setting out of its sensible range, so that a typo like
+    /// `max_position_pct = 5.0` (500%) fails at startup instead of sizing
+    /// positions off it
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut v = Validator::default();
+
+        let strategy = &self.strategy;
+        v.fraction("strategy.min_edge", strategy.min_edge, "0.06");
+        v.fraction("strategy.min_confidence", strategy.min_confidence, "0.60");
+        v.fraction("strategy.high_epistemic_threshold", strategy.high_epistemic_threshold, "0.15");
+        v.fraction("strategy.regime_confidence_threshold", strategy.regime_confidence_threshold, "0.6");
+        v.check(
+            "strategy.kelly_fraction",
+            strategy.kelly_fraction,
+            strategy.kelly_fraction >= Decimal::new(5, 2) && strategy.kelly_fraction <= Decimal::ONE,
+            "in [0.05, 1.0]",
+            "0.25 for quarter Kelly; over 1.0 bets more than full Kelly",
+        );
+        v.check(
+            "strategy.scan_interval_secs",
+            strategy.scan_interval_secs,
+            strategy.scan_interval_secs >= 10,
+            ">= 10",
+            "180; faster scans hit the API rate limits",
+        );
+
+        let risk = &self.risk;
+        v.fraction("risk.max_position_pct", risk.max_position_pct, "0.05 for 5% of the portfolio");
+        v.fraction("risk.max_exposure_pct", risk.max_exposure_pct, "0.50 for 50% of the portfolio");
+        v.check(
+            "risk.max_daily_loss_pct",
+            risk.max_daily_loss_pct,
+            risk.max_daily_loss_pct > Decimal::ZERO && risk.max_daily_loss_pct <= Decimal::new(5, 1),
+            "in (0, 0.5]",
+            "0.10 to stop after losing 10% in a day",
+        );
+        if let Some(edge) = risk.loss_cooldown_override_edge {
+            v.fraction("risk.loss_cooldown_override_edge", edge, "0.15, or remove it");
+        }
+        v.max_total_kelly("risk.max_total_kelly", risk.max_total_kelly);
+        for (i, account) in self.polymarket.accounts.iter().enumerate() {
+            if let Some(kelly) = account.risk.max_total_kelly {
+                v.max_total_kelly(&format!("polymarket.accounts[{}].risk.max_total_kelly", i), kelly);
+            }
+        }
+        if let Some(ramp) = &self.ramp {
+            v.positive_fraction("ramp.initial_fraction", ramp.initial_fraction, "0.25 to start at a quarter size");
+        }
+
+        if let Some(executor) = &self.executor {
+            if let Some(two_phase) = &executor.two_phase {
+                let probe = two_phase.probe_fraction;
+                v.check(
+                    "executor.two_phase.probe_fraction",
+                    probe,
+                    probe > Decimal::ZERO && probe < Decimal::ONE,
+                    "in (0, 1)",
+                    "0.1 to probe with a tenth of the order",
+                );
+            }
+            if let Some(sizing) = &executor.sizing {
+                v.positive_fraction("executor.sizing.max_book_depth_fraction", sizing.max_book_depth_fraction, "0.5");
+            }
+        }
+        if let Some(fast_stop) = &self.fast_stop {
+            v.fraction("fast_stop.min_probability", fast_stop.min_probability, "0.20");
+        }
+        if let Some(profile) = &self.market_profile {
+            v.fraction("market_profile.spread_edge_fraction", profile.spread_edge_fraction, "0.5");
+            v.positive_fraction("market_profile.max_slice_depth_fraction", profile.max_slice_depth_fraction, "0.5");
+        }
+        if let Some(rebalance) = &self.rebalance {
+            v.positive_fraction("rebalance.drift_threshold", rebalance.drift_threshold, "0.05 for 5 points of weight");
+        }
+
+        if let Some(copy_trade) = &self.copy_trade {
+            v.check(
+                "copy_trade.copy_ratio",
+                copy_trade.copy_ratio,
+                copy_trade.copy_ratio > 0.0 && copy_trade.copy_ratio <= 2.0,
+                "in (0, 2.0]",
+                "0.5 to copy at half the leader's size",
+            );
+            let discovery = &copy_trade.discovery;
+            v.score("copy_trade.discovery.add_threshold", discovery.add_threshold, "0.6");
+            v.score("copy_trade.discovery.remove_threshold", discovery.remove_threshold, "0.3");
+            v.check(
+                "copy_trade.discovery.remove_threshold",
+                discovery.remove_threshold,
+                discovery.remove_threshold <= discovery.add_threshold,
+                "<= copy_trade.discovery.add_threshold",
+                "0.3, below add_threshold so followed traders aren't dropped as soon as they're added",
+            );
+        }
+        if let Some(markets) = &self.markets {
+            v.min_liquidity("markets.min_liquidity", markets.min_liquidity);
+        }
+        if let Some(discovery) = &self.discovery {
+            v.min_liquidity("discovery.min_liquidity", discovery.min_liquidity);
+        }
+        if let Some(ingester) = &self.ingester {
+            let confidence = ingester.processing.min_confidence;
+            v.check(
+                "ingester.processing.min_confidence",
+                confidence,
+                (0.0..=1.0).contains(&confidence),
+                "in [0, 1]",
+                "0.5",
+            );
+            let dedup = ingester.dedup_threshold;
+            v.check(
+                "ingester.dedup_threshold",
+                dedup,
+                dedup > 0.0 && dedup <= 1.0,
+                "in (0, 1]",
+                "0.8; lower values skip messages that merely share words",
+            );
+        }
+
+        if let Some(llm) = &self.llm {
+            v.llm_model("llm", &llm.provider, llm.model.as_deref());
+            for (i, fallback) in llm.fallback.iter().enumerate() {
+                v.llm_model(&format!("llm.fallback[{}]", i), &fallback.provider, fallback.model.as_deref());
+            }
+        }
+
+        let db_dir = Path::new(&self.database.path).parent().filter(|dir| !dir.as_os_str().is_empty());
+        if let Some(dir) = db_dir.filter(|dir| !dir.is_dir()) {
+            v.errors.push(ConfigError {
+                field: "database.path".into(),
+                value: self.database.path.clone(),
+                constraint: "parent directory must exist".into(),
+                suggestion: format!("mkdir -p {}", dir.display()),
+            });
+        }
+
+        v.errors
+    }
+
     /// Load from default locations
     pub fn load_default() -> anyhow::Result<Self> {
         // Try loading from current directory or user config
@@ -237,6 +2163,125 @@ impl Config {
     }
 }
 
+/// A setting that failed [`Config::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// Dotted path of the setting, e.g. `risk.max_position_pct`
+    pub field: String,
+    pub value: String,
+    pub constraint: String,
+    pub suggestion: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} = {}: must be {} (try {})",
+            self.field, self.value, self.constraint, self.suggestion
+        )
+    }
+}
+
+/// Every failure of [`Config::validate`], reported at once
+#[derive(Debug, Clone)]
+pub struct ConfigValidationErrors(pub Vec<ConfigError>);
+
+impl std::fmt::Display for ConfigValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid configuration ({} errors):", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigValidationErrors {}
+
+/// Model name prefixes each hosted provider serves; `ollama` and
+/// `compatible` take any model
+const KNOWN_LLM_MODELS: &[(&str, &[&str])] = &[
+    ("deepseek", &["deepseek-"]),
+    ("anthropic", &["claude-"]),
+    ("claude", &["claude-"]),
+    ("openai", &["gpt-", "o1", "o3", "o4", "chatgpt-"]),
+    ("gpt", &["gpt-", "o1", "o3", "o4", "chatgpt-"]),
+];
+
+/// LLM providers `model::llm` knows, plus `market` for fallback chains
+const KNOWN_LLM_PROVIDERS: &[&str] = &["deepseek", "anthropic", "claude", "openai", "gpt", "ollama", "compatible", "custom", "market"];
+
+/// Collects the failures of [`Config::validate`]
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ConfigError>,
+}
+
+impl Validator {
+    fn check(&mut self, field: &str, value: impl std::fmt::Display, ok: bool, constraint: &str, suggestion: &str) {
+        if !ok {
+            self.errors.push(ConfigError {
+                field: field.to_string(),
+                value: value.to_string(),
+                constraint: constraint.to_string(),
+                suggestion: suggestion.to_string(),
+            });
+        }
+    }
+
+    /// A probability or fraction of the portfolio
+    fn fraction(&mut self, field: &str, value: Decimal, suggestion: &str) {
+        let ok = value >= Decimal::ZERO && value <= Decimal::ONE;
+        self.check(field, value, ok, "in [0, 1]", suggestion);
+    }
+
+    /// A share of something that must be more than nothing
+    fn positive_fraction(&mut self, field: &str, value: Decimal, suggestion: &str) {
+        let ok = value > Decimal::ZERO && value <= Decimal::ONE;
+        self.check(field, value, ok, "in (0, 1]", suggestion);
+    }
+
+    /// A trader score
+    fn score(&mut self, field: &str, value: f64, suggestion: &str) {
+        self.check(field, value, (0.0..=1.0).contains(&value), "in [0, 1]", suggestion);
+    }
+
+    /// At 0 no position can be opened
+    fn max_total_kelly(&mut self, field: &str, value: Decimal) {
+        self.check(field, value, value > Decimal::ZERO, "> 0", "1.0 for full Kelly across open positions");
+    }
+
+    /// Thinner markets can't absorb an order without moving the price
+    fn min_liquidity(&mut self, field: &str, value: Decimal) {
+        self.check(field, value, value >= Decimal::from(100), ">= 100", "10000");
+    }
+
+    fn llm_model(&mut self, field: &str, provider: &str, model: Option<&str>) {
+        let provider = provider.to_lowercase();
+        if !KNOWN_LLM_PROVIDERS.contains(&provider.as_str()) {
+            self.check(
+                &format!("{}.provider", field),
+                &provider,
+                false,
+                "a known provider",
+                &KNOWN_LLM_PROVIDERS.join(", "),
+            );
+            return;
+        }
+        let (Some(model), Some((_, prefixes))) = (model, KNOWN_LLM_MODELS.iter().find(|(p, _)| *p == provider)) else {
+            return;
+        };
+        self.check(
+            &format!("{}.model", field),
+            model,
+            prefixes.iter().any(|prefix| model.starts_with(prefix)),
+            &format!("a {} model", provider),
+            &format!("a model starting with {}, or provider = \"compatible\"", prefixes.join(" / ")),
+        );
+    }
+}
+
 impl Default for StrategyConfig {
     fn default() -> Self {
         Self {
@@ -247,6 +2292,18 @@ impl Default for StrategyConfig {
             model_update_interval_secs: 900,
             compound_enabled: true,
             compound_sqrt_scaling: true,
+            high_epistemic_threshold: default_high_epistemic_threshold(),
+            modes: std::collections::HashMap::new(),
+            accounts: std::collections::HashMap::new(),
+            experiment: None,
+            experiments: Default::default(),
+            bayesian_prior_strength: None,
+            regime_map: Default::default(),
+            regime_confidence_threshold: default_regime_confidence_threshold(),
+            max_concurrent_analyses: default_max_concurrent_analyses(),
+            analysis_timeout_secs: default_analysis_timeout_secs(),
+            seasonality: SeasonalityConfig::default(),
+            scoring: SignalScoringConfig::default(),
         }
     }
 }
@@ -259,6 +2316,18 @@ impl Default for RiskConfig {
             max_daily_loss_pct: Decimal::new(10, 2), // 10%
             min_balance_reserve: Decimal::new(100, 0), // $100
             max_open_positions: 10,
+            signal_dedup_ttl_secs: default_signal_dedup_ttl_secs(),
+            max_total_kelly: default_max_total_kelly(),
+            max_consecutive_api_failures: default_max_consecutive_api_failures(),
+            api_failure_pause_secs: default_api_failure_pause_secs(),
+            max_positions_per_strategy: std::collections::HashMap::new(),
+            queued_signal_ttl_secs: default_queued_signal_ttl_secs(),
+            max_queued_signals: default_max_queued_signals(),
+            loss_cooldown_mins: default_loss_cooldown_mins(),
+            loss_cooldown_override_edge: None,
+            regime_rotation_min_edge_gain: None,
+            max_heat_budget: Decimal::ZERO,
+            regime_consensus_sizing: Default::default(),
         }
     }
 }
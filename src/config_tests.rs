@@ -4,6 +4,7 @@
 mod tests {
     use super::super::config::*;
     use rust_decimal_macros::dec;
+    use rust_decimal::Decimal;
 
     #[test]
     fn test_strategy_config_default() {
@@ -95,6 +96,31 @@ notify_trades = false
         assert!(config.notify_errors); // defaults to true
     }
 
+    #[test]
+    fn test_telegram_bot_mode() {
+        let config: TelegramConfig = toml::from_str("bot_token = \"t\"\nchat_id = \"1\"").unwrap();
+        assert_eq!(config.mode, TelegramBotMode::Polling { timeout_secs: 30 });
+
+        let toml_str = r#"
+bot_token = "123:abc"
+chat_id = "12345"
+[mode]
+type = "webhook"
+public_url = "https://bot.example.com"
+listen_port = 8443
+secret_token = "s3cret"
+"#;
+        let config: TelegramConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.mode,
+            TelegramBotMode::Webhook {
+                public_url: "https://bot.example.com".to_string(),
+                listen_port: 8443,
+                secret_token: "s3cret".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_polymarket_config() {
         let toml_str = r#"
@@ -107,7 +133,7 @@ signature_type = 0
         let config: PolymarketConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.clob_url, "https://clob.polymarket.com");
         assert_eq!(config.gamma_url, "https://gamma-api.polymarket.com");
-        assert_eq!(config.private_key, "abc123");
+        assert_eq!(*config.private_key, "abc123");
         assert_eq!(config.chain_id, 137);
         assert_eq!(config.signature_type, 0);
         assert!(config.funder_address.is_none());
@@ -128,6 +154,39 @@ signature_type = 1
         assert_eq!(config.signature_type, 1);
     }
 
+    #[test]
+    fn test_polymarket_config_key_source() {
+        use crate::config::KeySource;
+
+        let config: PolymarketConfig = toml::from_str(r#"
+clob_url = "https://clob.polymarket.com"
+gamma_url = "https://gamma-api.polymarket.com"
+chain_id = 137
+signature_type = 0
+"#).unwrap();
+        assert_eq!(config.key_source, KeySource::Plaintext);
+        assert_eq!(config.keyring_service, "polymarket-bot");
+
+        let config: PolymarketConfig = toml::from_str(r#"
+clob_url = "https://clob.polymarket.com"
+gamma_url = "https://gamma-api.polymarket.com"
+private_key = "abc123"
+key_source = "encrypted_file"
+key_file = "data/wallet.key"
+chain_id = 137
+signature_type = 0
+
+[[accounts]]
+id = "fund"
+key_file = "data/fund.key"
+"#).unwrap();
+        assert_eq!(config.key_source, KeySource::EncryptedFile);
+        assert_eq!(config.account_configs()[0].key_file.as_deref(), Some("data/fund.key"));
+        assert!(config.account_configs()[0].private_key.is_empty());
+        // The key stays out of debug output
+        assert!(!format!("{:?}", config).contains("abc123"));
+    }
+
     #[test]
     fn test_llm_config_minimal() {
         let toml_str = r#"
@@ -266,4 +325,248 @@ channels = ["@channel1", "@channel2"]
         assert_eq!(config.bot_token, "123:abc");
         assert_eq!(config.channels.len(), 2);
     }
+
+    #[test]
+    fn test_single_account_config_is_default_account() {
+        let toml_str = r#"
+clob_url = "https://clob.polymarket.com"
+gamma_url = "https://gamma-api.polymarket.com"
+private_key = "abc123"
+funder_address = "0x123456"
+chain_id = 137
+signature_type = 1
+"#;
+        let config: PolymarketConfig = toml::from_str(toml_str).unwrap();
+        let accounts = config.account_configs();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].id, DEFAULT_ACCOUNT_ID);
+
+        let client = config.for_account(&accounts[0]);
+        assert_eq!(*client.private_key, "abc123");
+        assert_eq!(client.funder_address, Some("0x123456".to_string()));
+        assert_eq!(client.signature_type, 1);
+
+        let risk = accounts[0].risk.apply(&RiskConfig::default());
+        assert_eq!(risk.max_position_pct, RiskConfig::default().max_position_pct);
+    }
+
+    #[test]
+    fn test_multi_account_config() {
+        let toml_str = r#"
+clob_url = "https://clob.polymarket.com"
+gamma_url = "https://gamma-api.polymarket.com"
+chain_id = 137
+signature_type = 0
+
+[[accounts]]
+id = "personal"
+private_key = "aaa"
+
+[[accounts]]
+id = "fund"
+private_key = "bbb"
+funder_address = "0xfund"
+signature_type = 2
+capital = 5000
+risk = { max_position_pct = 0.02, max_open_positions = 4 }
+"#;
+        let config: PolymarketConfig = toml::from_str(toml_str).unwrap();
+        let accounts = config.account_configs();
+        let ids: Vec<_> = accounts.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["personal", "fund"]);
+
+        let personal = config.for_account(&accounts[0]);
+        assert_eq!(*personal.private_key, "aaa");
+        assert_eq!(personal.signature_type, 0);
+        let fund = config.for_account(&accounts[1]);
+        assert_eq!(*fund.private_key, "bbb");
+        assert_eq!(fund.funder_address, Some("0xfund".to_string()));
+        assert_eq!(fund.signature_type, 2);
+        assert_eq!(fund.clob_url, config.clob_url);
+        assert_eq!(accounts[1].capital, Some(dec!(5000)));
+
+        let base = RiskConfig::default();
+        let risk = accounts[1].risk.apply(&base);
+        assert_eq!(risk.max_position_pct, dec!(0.02));
+        assert_eq!(risk.max_open_positions, 4);
+        assert_eq!(risk.max_daily_loss_pct, base.max_daily_loss_pct);
+    }
+
+    #[test]
+    fn test_strategy_account_allowlist() {
+        let mut config = StrategyConfig::default();
+        config.accounts.insert("crypto_hf".to_string(), vec!["personal".to_string()]);
+
+        assert!(config.trades_on("crypto_hf", "personal"));
+        assert!(!config.trades_on("crypto_hf", "fund"));
+        // Unlisted strategies trade everywhere
+        assert!(config.trades_on("llm", "fund"));
+    }
+
+    #[test]
+    fn test_alert_rules_config() {
+        let toml_str = r#"
+            [[rules]]
+            name = "Losing day"
+            expression = "daily_pnl < -50 && open_positions > 3"
+            severity = "critical"
+            cooldown_secs = 600
+            message_template = "P&L {daily_pnl}"
+
+            [[rules]]
+            name = "Streak"
+            expression = "consecutive_losses >= 5"
+        "#;
+        let config: AlertsConfig = toml::from_str(toml_str).unwrap();
+
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].severity, AlertSeverity::Critical);
+        assert_eq!(config.rules[0].cooldown_secs, 600);
+        assert_eq!(config.rules[1].severity, AlertSeverity::Warning);
+        assert_eq!(config.rules[1].cooldown_secs, 3600);
+        assert!(config.rules[1].message_template.is_empty());
+    }
+
+    /// Smallest config that deserializes, with every range at its default
+    const MINIMAL_CONFIG: &str = r#"
+        [polymarket]
+        clob_url = "https://clob.polymarket.com"
+        gamma_url = "https://gamma-api.polymarket.com"
+        chain_id = 137
+        signature_type = 0
+
+        [strategy]
+        min_edge = 0.06
+        min_confidence = 0.6
+        kelly_fraction = 0.25
+        scan_interval_secs = 180
+        model_update_interval_secs = 900
+
+        [risk]
+        max_position_pct = 0.05
+        max_exposure_pct = 0.5
+        max_daily_loss_pct = 0.1
+        min_balance_reserve = 100
+        max_open_positions = 10
+
+        [database]
+        path = "polymarket.db"
+    "#;
+
+    #[test]
+    fn test_valid_config_passes_validation() {
+        let mut config: Config = toml::from_str(MINIMAL_CONFIG).unwrap();
+        assert!(config.validate().is_empty());
+
+        config.llm = Some(toml::from_str("provider = \"anthropic\"\nmodel = \"claude-3-5-haiku-latest\"").unwrap());
+        config.copy_trade = Some(toml::from_str("copy_ratio = 1.5").unwrap());
+        config.markets = Some(MarketScanConfig::default());
+        assert!(config.validate().is_empty(), "{:?}", config.validate());
+    }
+
+    #[test]
+    fn test_validation_reports_every_invalid_field() {
+        let mut config: Config = toml::from_str(MINIMAL_CONFIG).unwrap();
+        config.risk.max_position_pct = dec!(5.0);
+        config.strategy.kelly_fraction = dec!(2.0);
+        config.strategy.scan_interval_secs = 5;
+        config.risk.max_daily_loss_pct = Decimal::ZERO;
+        config.copy_trade = Some(toml::from_str("copy_ratio = 0.0").unwrap());
+        config.markets = Some(MarketScanConfig { min_liquidity: dec!(50), ..Default::default() });
+        config.llm = Some(toml::from_str("provider = \"openai\"\nmodel = \"claude-3\"").unwrap());
+        config.database.path = "no/such/dir/polymarket.db".to_string();
+
+        let errors = config.validate();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "strategy.kelly_fraction",
+                "strategy.scan_interval_secs",
+                "risk.max_position_pct",
+                "risk.max_daily_loss_pct",
+                "copy_trade.copy_ratio",
+                "markets.min_liquidity",
+                "llm.model",
+                "database.path",
+            ]
+        );
+        let position = &errors[2];
+        assert_eq!((position.value.as_str(), position.constraint.as_str()), ("5.0", "in [0, 1]"));
+        assert_eq!(errors[7].suggestion, "mkdir -p no/such/dir");
+
+        let report = ConfigValidationErrors(errors).to_string();
+        assert!(report.starts_with("Invalid configuration (8 errors):"));
+        assert!(report.contains("\n  - strategy.kelly_fraction = 2.0: must be in [0.05, 1.0] (try 0.25"));
+    }
+
+    #[test]
+    fn test_validation_checks_fraction_and_threshold_ranges() {
+        let mut config: Config = toml::from_str(MINIMAL_CONFIG).unwrap();
+        config.rebalance = Some(RebalanceConfig::default());
+        config.executor = Some(toml::from_str("[two_phase]\n[sizing]").unwrap());
+        config.fast_stop = Some(FastStopConfig::default());
+        config.ramp = Some(RampConfig::default());
+        config.market_profile = Some(MarketProfileConfig::default());
+        config.copy_trade = Some(toml::from_str("").unwrap());
+        config.ingester = Some(toml::from_str("").unwrap());
+        config.polymarket.accounts = vec![toml::from_str("id = \"fund\"\n[risk]\nmax_total_kelly = 0.5").unwrap()];
+        // The defaults are in range
+        assert!(config.validate().is_empty(), "{:?}", config.validate());
+
+        config.risk.max_total_kelly = Decimal::ZERO;
+        config.polymarket.accounts[0].risk.max_total_kelly = Some(dec!(-1));
+        config.ramp.as_mut().unwrap().initial_fraction = Decimal::ZERO;
+        let executor = config.executor.as_mut().unwrap();
+        executor.two_phase.as_mut().unwrap().probe_fraction = Decimal::ONE;
+        executor.sizing.as_mut().unwrap().max_book_depth_fraction = dec!(1.5);
+        config.fast_stop.as_mut().unwrap().min_probability = dec!(20);
+        let profile = config.market_profile.as_mut().unwrap();
+        profile.spread_edge_fraction = dec!(-0.5);
+        profile.max_slice_depth_fraction = Decimal::ZERO;
+        config.rebalance.as_mut().unwrap().drift_threshold = dec!(5);
+        let discovery = &mut config.copy_trade.as_mut().unwrap().discovery;
+        discovery.add_threshold = 0.2;
+        discovery.remove_threshold = 0.4;
+        config.ingester.as_mut().unwrap().dedup_threshold = 80.0;
+
+        let errors = config.validate();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "risk.max_total_kelly",
+                "polymarket.accounts[0].risk.max_total_kelly",
+                "ramp.initial_fraction",
+                "executor.two_phase.probe_fraction",
+                "executor.sizing.max_book_depth_fraction",
+                "fast_stop.min_probability",
+                "market_profile.spread_edge_fraction",
+                "market_profile.max_slice_depth_fraction",
+                "rebalance.drift_threshold",
+                "copy_trade.discovery.remove_threshold",
+                "ingester.dedup_threshold",
+            ]
+        );
+        assert_eq!(errors[9].constraint, "<= copy_trade.discovery.add_threshold");
+
+        // Scores are between 0 and 1
+        let discovery = &mut config.copy_trade.as_mut().unwrap().discovery;
+        discovery.add_threshold = 60.0;
+        let errors = config.validate();
+        assert!(errors.iter().any(|e| e.field == "copy_trade.discovery.add_threshold" && e.constraint == "in [0, 1]"));
+    }
+
+    #[test]
+    fn test_validation_rejects_unknown_llm_provider() {
+        let mut config: Config = toml::from_str(MINIMAL_CONFIG).unwrap();
+        config.llm = Some(toml::from_str("provider = \"openai\"\n[[fallback]]\nprovider = \"gemini\"").unwrap());
+        let errors = config.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "llm.fallback[0].provider");
+
+        // Any model goes with a self-hosted provider
+        config.llm = Some(toml::from_str("provider = \"ollama\"\nmodel = \"llama3\"").unwrap());
+        assert!(config.validate().is_empty());
+    }
 }
@@ -53,4 +53,20 @@ pub enum BotError {
     Internal(String),
 }
 
+impl BotError {
+    /// Whether the error points at an unreachable or failing dependency
+    /// rather than at the request itself
+    pub fn is_api_failure(&self) -> bool {
+        matches!(
+            self,
+            BotError::Api(_)
+                | BotError::Auth(_)
+                | BotError::Network(_)
+                | BotError::WebSocket(_)
+                | BotError::Database(_)
+                | BotError::RateLimited { .. }
+        )
+    }
+}
+
 pub type Result<T> = std::result::Result<T, BotError>;
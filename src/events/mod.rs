@@ -23,6 +23,12 @@
 //! - `TimerEvent`: Scheduled tasks
 //! - `RiskEvent`: Risk limit breaches, margin calls
 //! - `SystemEvent`: Start, stop, heartbeat
+//!
+//! Events for systems outside the bot go out through [`sink::EventSink`]s.
+
+pub mod sink;
+
+pub use sink::{BotEvent, EventSink, FileSink, StdoutJsonSink};
 
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
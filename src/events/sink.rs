@@ -0,0 +1,249 @@
+//! Structured bot events for external systems
+//!
+//! Telegram messages are written for people. An [`EventSink`] gets every
+//! signal, order, fill, risk block and regime change as a [`BotEvent`]
+//! that serializes to one JSON object, for a log pipeline or a message bus.
+//! Sinks are configured under `[events]`; [`StdoutJsonSink`] and
+//! [`FileSink`] write one event per line.
+
+use crate::config::EventSinkConfig;
+use crate::regime::MarketRegime;
+use crate::types::{Side, Signal, Trade, TradeStatus, TraceId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Something the bot did, as external systems see it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotEvent {
+    SignalGenerated {
+        market_id: String,
+        token_id: String,
+        side: Side,
+        model_probability: Decimal,
+        market_probability: Decimal,
+        edge: Decimal,
+        confidence: Decimal,
+        strategy: Option<String>,
+        trace_id: TraceId,
+        timestamp: DateTime<Utc>,
+    },
+    /// An order accepted by the exchange and resting on the book
+    OrderPlaced {
+        order_id: String,
+        market_id: String,
+        token_id: String,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+        account_id: Option<String>,
+        trace_id: TraceId,
+        timestamp: DateTime<Utc>,
+    },
+    /// An order matched on arrival
+    OrderFilled {
+        order_id: String,
+        market_id: String,
+        token_id: String,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+        fee: Decimal,
+        strategy: Option<String>,
+        account_id: Option<String>,
+        trace_id: TraceId,
+        timestamp: DateTime<Utc>,
+    },
+    /// An order the exchange refused or killed, or one too small to send
+    OrderRejected {
+        market_id: String,
+        token_id: String,
+        reason: String,
+        account_id: Option<String>,
+        trace_id: TraceId,
+        timestamp: DateTime<Utc>,
+    },
+    /// A signal stopped by a risk limit
+    RiskBlocked {
+        market_id: String,
+        reason: String,
+        account_id: Option<String>,
+        trace_id: TraceId,
+        timestamp: DateTime<Utc>,
+    },
+    RegimeChanged {
+        symbol: String,
+        from: MarketRegime,
+        to: MarketRegime,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl BotEvent {
+    pub fn signal_generated(signal: &Signal) -> Self {
+        Self::SignalGenerated {
+            market_id: signal.market_id.clone(),
+            token_id: signal.token_id.clone(),
+            side: signal.side,
+            model_probability: signal.model_probability,
+            market_probability: signal.market_probability,
+            edge: signal.edge,
+            confidence: signal.confidence,
+            strategy: signal.strategy_name.clone(),
+            trace_id: signal.trace_id,
+            timestamp: signal.timestamp,
+        }
+    }
+
+    /// `OrderFilled` for a filled trade, `OrderPlaced` for a resting one
+    pub fn from_trade(trade: &Trade) -> Self {
+        match trade.status {
+            TradeStatus::Filled => Self::OrderFilled {
+                order_id: trade.order_id.clone(),
+                market_id: trade.market_id.clone(),
+                token_id: trade.token_id.clone(),
+                side: trade.side,
+                price: trade.price,
+                size: trade.size,
+                fee: trade.fee,
+                strategy: trade.strategy_name.clone(),
+                account_id: trade.account_id.clone(),
+                trace_id: trade.trace_id,
+                timestamp: trade.timestamp,
+            },
+            TradeStatus::Resting => Self::OrderPlaced {
+                order_id: trade.order_id.clone(),
+                market_id: trade.market_id.clone(),
+                token_id: trade.token_id.clone(),
+                side: trade.side,
+                price: trade.price,
+                size: trade.size,
+                account_id: trade.account_id.clone(),
+                trace_id: trade.trace_id,
+                timestamp: trade.timestamp,
+            },
+        }
+    }
+}
+
+/// Destination for [`BotEvent`]s. Emitting must not fail the trading path:
+/// sinks log their own errors.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    async fn emit(&self, event: BotEvent);
+}
+
+/// Send `event` to every sink
+pub async fn emit_all(sinks: &[Arc<dyn EventSink>], event: BotEvent) {
+    for sink in sinks {
+        sink.emit(event.clone()).await;
+    }
+}
+
+/// Prints each event as a line of JSON on stdout
+#[derive(Debug, Default)]
+pub struct StdoutJsonSink;
+
+#[async_trait]
+impl EventSink for StdoutJsonSink {
+    async fn emit(&self, event: BotEvent) {
+        match serde_json::to_string(&event) {
+            Ok(line) => println!("{}", line),
+            Err(e) => tracing::warn!("Failed to serialize bot event: {}", e),
+        }
+    }
+}
+
+/// Appends each event as a line of JSON to a file
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<tokio::fs::File>,
+}
+
+impl FileSink {
+    /// Append to `path`, creating it and its directory
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(tokio::fs::File::from_std(file)) })
+    }
+}
+
+#[async_trait]
+impl EventSink for FileSink {
+    async fn emit(&self, event: BotEvent) {
+        let mut line = match serde_json::to_vec(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize bot event: {}", e);
+                return;
+            }
+        };
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        if let Err(e) = file.write_all(&line).await.and(file.flush().await) {
+            tracing::warn!("Failed to write bot event: {}", e);
+        }
+    }
+}
+
+/// The sinks `[events]` configures
+pub fn sinks_from_config(config: &EventSinkConfig) -> std::io::Result<Vec<Arc<dyn EventSink>>> {
+    let mut sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    if config.stdout {
+        sinks.push(Arc::new(StdoutJsonSink));
+    }
+    if let Some(path) = &config.file {
+        sinks.push(Arc::new(FileSink::open(path)?));
+    }
+    Ok(sinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sink_appends_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events/bot.jsonl");
+        let sink = FileSink::open(&path).unwrap();
+        let now = Utc::now();
+
+        sink.emit(BotEvent::RegimeChanged {
+            symbol: "BTC".to_string(),
+            from: MarketRegime::Ranging,
+            to: MarketRegime::Volatile,
+            timestamp: now,
+        })
+        .await;
+        sink.emit(BotEvent::RiskBlocked {
+            market_id: "m1".to_string(),
+            reason: "Daily loss limit exceeded".to_string(),
+            account_id: None,
+            trace_id: TraceId::default(),
+            timestamp: now,
+        })
+        .await;
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "regime_changed");
+        assert_eq!(lines[0]["to"], "Volatile");
+        assert_eq!(lines[1]["type"], "risk_blocked");
+        assert_eq!(lines[1]["market_id"], "m1");
+
+        let parsed: BotEvent = serde_json::from_str(text.lines().nth(1).unwrap()).unwrap();
+        assert!(matches!(parsed, BotEvent::RiskBlocked { .. }));
+    }
+}
@@ -36,6 +36,7 @@
 //! println!("Average slippage: {} bps", metrics.avg_slippage_bps);
 //! ```
 
+use crate::types::{Side, Trade};
 use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -82,6 +83,32 @@ pub struct ExecutionRecord {
     pub twap_benchmark: Option<Decimal>,
 }
 
+impl ExecutionRecord {
+    /// Record of a bot trade, keeping the fee the executor charged it so
+    /// cost attribution matches realized P&L
+    pub fn from_trade(trade: &Trade, decision_price: Decimal, decision_time: DateTime<Utc>) -> Self {
+        Self {
+            order_id: trade.order_id.clone(),
+            symbol: trade.token_id.clone(),
+            side: match trade.side {
+                Side::Buy => OrderSide::Buy,
+                Side::Sell => OrderSide::Sell,
+            },
+            decision_price,
+            arrival_price: trade.price,
+            execution_price: trade.price,
+            quantity_ordered: trade.size,
+            quantity_filled: trade.size,
+            decision_time,
+            arrival_time: trade.timestamp,
+            execution_time: trade.timestamp,
+            fees: trade.fee,
+            vwap_benchmark: None,
+            twap_benchmark: None,
+        }
+    }
+}
+
 /// Breakdown of execution costs
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CostAttribution {
@@ -838,6 +865,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_record_from_trade_carries_fee() {
+        let trade = Trade {
+            id: "t".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "tok".to_string(),
+            market_id: "m".to_string(),
+            side: Side::Buy,
+            price: dec!(0.99),
+            size: dec!(1000),
+            fee: dec!(0.2),
+            timestamp: Utc::now(),
+            status: crate::types::TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        let record = ExecutionRecord::from_trade(&trade, dec!(0.99), trade.timestamp);
+        assert_eq!(record.fees, dec!(0.2));
+
+        let mut analyzer = ExecutionAnalyzer::new();
+        analyzer.record_execution(record);
+        let metrics = analyzer.compute_metrics();
+        // 0.2 on 990 of notional
+        assert!((metrics.cost_attribution.fee_cost_bps - dec!(2.02)).abs() < dec!(0.01));
+    }
+
     #[test]
     fn test_analyzer_creation() {
         let analyzer = ExecutionAnalyzer::new();
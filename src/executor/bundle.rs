@@ -0,0 +1,249 @@
+//! All-or-nothing execution of multi-leg orders
+//!
+//! Arbitrage legs only pay off together: if one fills and the next has moved
+//! away, the filled leg is naked exposure (leg risk). A bundle places each
+//! leg as a fill-or-kill order limited to the signal's price plus a
+//! tolerance. If any leg fails, every leg already filled is sold back (or
+//! bought back) at market so the bundle leaves no net position.
+
+use super::{classify_order_status, Executor};
+use crate::client::mock::ClobClientTrait;
+use crate::error::Result;
+use crate::types::{Order, OrderType, Side, Signal, Trade, TradeStatus};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Legs to execute together
+#[derive(Debug, Clone)]
+pub struct OrderBundle {
+    /// Placed in order; each sized like [`Executor::execute`] sizes a signal
+    pub legs: Vec<Signal>,
+    /// How far past `market_probability` a leg may fill (0.01 = 1 cent)
+    pub price_tolerance: Decimal,
+}
+
+impl OrderBundle {
+    pub fn new(legs: Vec<Signal>) -> Self {
+        Self {
+            legs,
+            price_tolerance: dec!(0.01),
+        }
+    }
+
+    pub fn with_price_tolerance(mut self, tolerance: Decimal) -> Self {
+        self.price_tolerance = tolerance;
+        self
+    }
+}
+
+/// What happened to one leg
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegStatus {
+    /// Filled and kept
+    Filled,
+    /// Did not fill within tolerance (or the order errored)
+    Failed(String),
+    /// Filled, then closed at market after another leg failed
+    Unwound,
+    /// Filled, but closing it failed: the position is still open
+    UnwindFailed(String),
+    /// Not placed because an earlier leg failed
+    Skipped,
+}
+
+/// Outcome of one leg of a bundle
+#[derive(Debug, Clone)]
+pub struct LegReport {
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub status: LegStatus,
+    /// The leg's fill, if it filled
+    pub fill: Option<Trade>,
+    /// The closing trade, if it was unwound
+    pub unwind: Option<Trade>,
+}
+
+/// Outcome of a bundle
+#[derive(Debug, Clone, Default)]
+pub struct BundleResult {
+    pub legs: Vec<LegReport>,
+}
+
+impl BundleResult {
+    /// Every leg filled and is held
+    pub fn is_complete(&self) -> bool {
+        !self.legs.is_empty() && self.legs.iter().all(|l| l.status == LegStatus::Filled)
+    }
+
+    /// Some filled leg could not be unwound and is still held
+    pub fn has_naked_legs(&self) -> bool {
+        self.legs.iter().any(|l| matches!(l.status, LegStatus::UnwindFailed(_)))
+    }
+
+    /// All trades placed, fills and unwinds, in order
+    pub fn trades(&self) -> Vec<Trade> {
+        self.legs
+            .iter()
+            .flat_map(|l| l.fill.iter().chain(l.unwind.iter()))
+            .cloned()
+            .collect()
+    }
+
+    /// One line per leg, e.g. for a notification
+    pub fn summary(&self) -> String {
+        self.legs
+            .iter()
+            .map(|l| {
+                let status = match &l.status {
+                    LegStatus::Filled => "filled".to_string(),
+                    LegStatus::Failed(reason) => format!("failed ({})", reason),
+                    LegStatus::Unwound => "unwound".to_string(),
+                    LegStatus::UnwindFailed(reason) => format!("UNWIND FAILED ({})", reason),
+                    LegStatus::Skipped => "skipped".to_string(),
+                };
+                format!("{:?} {} on {}: {}", l.side, l.token_id, l.market_id, status)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<C: ClobClientTrait> Executor<C> {
+    /// Execute every leg or none. Risk limits are checked for all legs
+    /// before anything is placed; on a failed leg the filled ones are
+    /// unwound at market and the remaining legs are skipped.
+    pub async fn execute_bundle(&self, bundle: &OrderBundle, portfolio_value: Decimal) -> Result<BundleResult> {
+        for signal in &bundle.legs {
+            self.check_risk_limits(signal, signal.suggested_size * portfolio_value, portfolio_value).await?;
+        }
+
+        let mut result = BundleResult::default();
+        let mut failed = false;
+
+        for signal in &bundle.legs {
+            let mut report = LegReport {
+                market_id: signal.market_id.clone(),
+                token_id: signal.token_id.clone(),
+                side: signal.side,
+                status: LegStatus::Skipped,
+                fill: None,
+                unwind: None,
+            };
+
+            if !failed {
+                match self.fill_bundle_leg(signal, portfolio_value, bundle.price_tolerance).await {
+                    Ok(Some(trade)) => {
+                        report.status = LegStatus::Filled;
+                        report.fill = Some(trade);
+                    }
+                    Ok(None) => {
+                        report.status = LegStatus::Failed("not filled within tolerance".to_string());
+                        failed = true;
+                    }
+                    Err(e) => {
+                        report.status = LegStatus::Failed(e.to_string());
+                        failed = true;
+                    }
+                }
+            }
+
+            result.legs.push(report);
+        }
+
+        if failed {
+            // Unwind newest first
+            for report in result.legs.iter_mut().rev() {
+                let Some(fill) = &report.fill else { continue };
+                match self.unwind_bundle_leg(fill).await {
+                    Ok(trade) => {
+                        report.status = LegStatus::Unwound;
+                        report.unwind = Some(trade);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to unwind bundle leg {}: {}", fill.token_id, e);
+                        report.status = LegStatus::UnwindFailed(e.to_string());
+                    }
+                }
+            }
+            tracing::warn!("Bundle failed:\n{}", result.summary());
+        }
+
+        Ok(result)
+    }
+
+    /// Place one leg as FOK limited to its price plus tolerance; `None` if killed
+    async fn fill_bundle_leg(
+        &self,
+        signal: &Signal,
+        portfolio_value: Decimal,
+        tolerance: Decimal,
+    ) -> Result<Option<Trade>> {
+        let size = signal.suggested_size * portfolio_value / signal.market_probability;
+        let price = match signal.side {
+            Side::Buy => signal.market_probability + tolerance,
+            Side::Sell => signal.market_probability - tolerance,
+        };
+        let order = Order {
+            token_id: signal.token_id.clone(),
+            side: signal.side,
+            price,
+            size,
+            order_type: OrderType::FOK,
+        };
+
+        self.place_bundle_order(&order, &signal.market_id, signal.strategy_name.clone())
+            .await
+    }
+
+    /// Close a filled leg by crossing the spread
+    async fn unwind_bundle_leg(&self, fill: &Trade) -> Result<Trade> {
+        let side = match fill.side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let order = Order {
+            token_id: fill.token_id.clone(),
+            side,
+            price: self.touch_price(&fill.token_id, side, true).await?,
+            size: fill.size,
+            order_type: OrderType::FOK,
+        };
+
+        self.place_bundle_order(&order, &fill.market_id, fill.strategy_name.clone())
+            .await?
+            .ok_or_else(|| crate::error::BotError::Execution("unwind order not filled".into()))
+    }
+
+    async fn place_bundle_order(
+        &self,
+        order: &Order,
+        market_id: &str,
+        strategy_name: Option<String>,
+    ) -> Result<Option<Trade>> {
+        let status = self.clob.place_order(order).await?;
+        // FOK either fills completely or not at all
+        if classify_order_status(&status) != Some(TradeStatus::Filled) {
+            return Ok(None);
+        }
+
+        self.update_position(&order.token_id, order.side, status.filled_size).await;
+        Ok(Some(Trade {
+            id: uuid::Uuid::new_v4().to_string(),
+            order_id: status.order_id,
+            token_id: order.token_id.clone(),
+            market_id: market_id.to_string(),
+            side: order.side,
+            price: status.avg_price.unwrap_or(order.price),
+            size: status.filled_size,
+            fee: Decimal::ZERO,
+            timestamp: chrono::Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        }))
+    }
+}
@@ -9,7 +9,7 @@
 
 use crate::client::clob::{ClobClient, OrderBook};
 use crate::error::Result;
-use crate::types::{Side, Trade};
+use crate::types::{Side, Trade, TradeStatus};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
@@ -411,6 +411,12 @@ impl GradualExitExecutor {
             size: action.sell_size,
             fee: Decimal::ZERO,
             timestamp: chrono::Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
 
         Ok(Some(trade))
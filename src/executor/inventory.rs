@@ -0,0 +1,107 @@
+//! Inventory-aware order planning
+//!
+//! A market's YES and NO tokens redeem for $1 together, so N NO shares carry
+//! the same exposure as being short N YES shares. Before opening anything
+//! new, a signal is netted against what we already hold:
+//! - Bearish on a token we hold: sell the held shares first, then buy the
+//!   complement only for the remainder
+//! - Bullish on a token whose complement we hold: sell the complement first,
+//!   then buy the token for the remainder
+//!
+//! This avoids holding both sides of a market, which locks up collateral and
+//! pays fees twice for zero net exposure.
+
+use crate::types::Side;
+use rust_decimal::Decimal;
+
+/// A single order produced by the planner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedLeg {
+    pub token_id: String,
+    pub side: Side,
+    /// Shares
+    pub size: Decimal,
+    /// Whether the leg trades the complement of the signal's token
+    /// (prices must be mirrored as `1 - p`)
+    pub on_complement: bool,
+}
+
+/// Exposure change split into reducing held inventory and opening the remainder
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderPlan {
+    /// Sell of inventory that already points the other way
+    pub reduce: Option<PlannedLeg>,
+    /// New position for whatever the reduction didn't cover
+    pub open: Option<PlannedLeg>,
+}
+
+impl OrderPlan {
+    /// Legs in execution order (reduce first)
+    pub fn legs(&self) -> impl Iterator<Item = &PlannedLeg> {
+        self.reduce.iter().chain(self.open.iter())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reduce.is_none() && self.open.is_none()
+    }
+}
+
+/// Current holdings of a token and its complement (shares)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Holdings {
+    pub token: Decimal,
+    pub complement: Decimal,
+}
+
+/// Net a `side` order for `size` shares of `token_id` against `holdings`.
+///
+/// Without a known complement, a sell beyond what is held stays a sell of the
+/// token itself, as it was before netting.
+pub fn plan_order(
+    token_id: &str,
+    complement_id: Option<&str>,
+    side: Side,
+    size: Decimal,
+    holdings: Holdings,
+) -> OrderPlan {
+    if size <= Decimal::ZERO {
+        return OrderPlan::default();
+    }
+
+    let leg = |token_id: &str, side, size, on_complement| PlannedLeg {
+        token_id: token_id.to_string(),
+        side,
+        size,
+        on_complement,
+    };
+
+    match side {
+        Side::Buy => {
+            let (reduce, remainder) = match complement_id {
+                Some(complement) => {
+                    let held = holdings.complement.max(Decimal::ZERO).min(size);
+                    let reduce = (held > Decimal::ZERO)
+                        .then(|| leg(complement, Side::Sell, held, true));
+                    (reduce, size - held)
+                }
+                None => (None, size),
+            };
+            OrderPlan {
+                reduce,
+                open: (remainder > Decimal::ZERO)
+                    .then(|| leg(token_id, Side::Buy, remainder, false)),
+            }
+        }
+        Side::Sell => {
+            let held = holdings.token.max(Decimal::ZERO).min(size);
+            let remainder = size - held;
+            OrderPlan {
+                reduce: (held > Decimal::ZERO).then(|| leg(token_id, Side::Sell, held, false)),
+                open: (remainder > Decimal::ZERO).then(|| match complement_id {
+                    Some(complement) => leg(complement, Side::Buy, remainder, true),
+                    None => leg(token_id, Side::Sell, remainder, false),
+                }),
+            }
+        }
+    }
+}
@@ -7,6 +7,9 @@ pub mod slippage_predictor;
 pub mod price_optimizer;
 pub mod twap_vwap;
 pub mod latency_optimizer;
+pub mod inventory;
+pub mod simulation;
+pub mod bundle;
 
 pub use arbitrage_executor::{ArbitrageExecutor, ArbitrageExecutorConfig, ArbitrageResult, ArbitrageMetrics};
 pub use slippage_predictor::{SlippagePredictor, SlippageConfig, SlippagePrediction, OrderBook, OrderSide};
@@ -18,6 +21,9 @@ pub use latency_optimizer::{
     BatchSubmitter, BatchConfig, BatchableOrder, BatchOrderResult,
     ConnectionPool, TemplateCache, PreSignedTemplate,
 };
+pub use inventory::{Holdings, OrderPlan, PlannedLeg};
+pub use bundle::{BundleResult, LegReport, LegStatus, OrderBundle};
+pub use simulation::{SimOrder, SimPosition, SimStats, SimulatedClobClient};
 
 #[cfg(test)]
 mod tests;
@@ -26,93 +32,1053 @@ mod smart_executor_tests;
 #[cfg(test)]
 mod gradual_exit_tests;
 
+use crate::analysis::market_profile::{ExecutionStyle, MarketProfiles};
 use crate::client::ClobClient;
-use crate::config::RiskConfig;
+use crate::client::mock::ClobClientTrait;
+use crate::config::{OrderSizingConfig, RiskConfig, SubMinimumPolicy, TwoPhaseConfig};
 use crate::error::{BotError, Result};
-use crate::types::{Order, OrderType, Signal, Trade};
-use rust_decimal::Decimal;
-use std::collections::HashMap;
+use crate::events::sink::{emit_all, BotEvent, EventSink};
+use crate::fees::{FeeModel, FeeRole, PolymarketFees};
+use crate::notify::Notifier;
+use crate::portfolio::EventPositionView;
+use crate::storage::Database;
+use crate::types::{Market, Order, OrderConstraints, OrderStatus, OrderType as ClobOrderType, Side, Signal, Trade, TradeStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tracing::Instrument;
+
+/// Poll interval while waiting for a resting probe to fill
+const PROBE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Decimal places of order sizes accepted by the exchange
+const SIZE_DECIMALS: u32 = 2;
+
+/// How the executor should work a signal on the CLOB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderType {
+    /// Take the best opposing price (GTC at the touch)
+    #[default]
+    Market,
+    /// Rest at the given price until filled or cancelled (GTC)
+    Limit { price: Decimal },
+    /// Take the best opposing price; cancel unless fully filled (FOK)
+    FillOrKill,
+    /// Join the near touch until the expiry (GTD, or cancelled by
+    /// [`Executor::cancel_expired_orders`] on venues without it)
+    GoodTilTime { expiry: DateTime<Utc> },
+}
 
 /// Trade executor with risk management
-pub struct Executor {
-    pub clob: ClobClient,
+pub struct Executor<C: ClobClientTrait = ClobClient> {
+    pub clob: C,
     risk_config: RiskConfig,
     positions: RwLock<HashMap<String, Decimal>>, // token_id -> size
+    committed_kelly: RwLock<HashMap<String, Decimal>>, // token_id -> bankroll fraction opened
+    complements: RwLock<HashMap<String, String>>, // token_id -> other outcome's token_id
+    constraints: RwLock<HashMap<String, OrderConstraints>>, // token_id -> exchange order constraints
+    pending_open: RwLock<HashMap<String, PlannedLeg>>, // market_id -> sub-minimum opening carried over
+    events: RwLock<HashMap<String, Vec<Market>>>, // negRisk event_id -> its markets
     daily_pnl: RwLock<Decimal>,
+    /// Persisted fingerprints of executed signals
+    executed_signals: Option<Arc<Database>>,
+    /// Account trades are tagged with in multi-account setups
+    account_id: Option<String>,
+    /// Fees charged on fills
+    fees: Arc<dyn FeeModel>,
+    /// Liquidity probing of large orders (defaults when unset)
+    two_phase: Option<TwoPhaseConfig>,
+    /// Opening size normalization (sizes are sent as planned when unset)
+    sizing: Option<OrderSizingConfig>,
+    /// Warns when a probe stops a large order
+    notifier: Option<Notifier>,
+    /// Large orders abandoned after their probe slipped too far
+    probe_cancellations_total: AtomicU64,
+    /// Slippage paid on those probes (USDC)
+    probe_slippage_cost_usdc: RwLock<Decimal>,
+    /// Resting orders to cancel at their signal's expiry, on venues
+    /// without GTD: order_id -> expiry
+    expiring_orders: RwLock<HashMap<String, DateTime<Utc>>>,
+    /// Signals that expired before trading, or whose orders expired unfilled
+    expired_signals_total: AtomicU64,
+    /// Receive order, fill, rejection and risk block events
+    sinks: Vec<Arc<dyn EventSink>>,
+    /// Per-market profiles steering how orders at market are worked
+    profiles: Option<Arc<MarketProfiles>>,
+}
+
+/// Options every executor of the bot is built with
+#[derive(Clone)]
+pub struct ExecutorSettings {
+    pub fees: Arc<dyn FeeModel>,
+    pub two_phase: Option<TwoPhaseConfig>,
+    pub sizing: Option<OrderSizingConfig>,
+    pub notifier: Option<Notifier>,
+    pub sinks: Vec<Arc<dyn EventSink>>,
+    pub profiles: Option<Arc<MarketProfiles>>,
+}
+
+impl Default for ExecutorSettings {
+    fn default() -> Self {
+        Self {
+            fees: Arc::new(PolymarketFees::default()),
+            two_phase: None,
+            sizing: None,
+            notifier: None,
+            sinks: Vec::new(),
+            profiles: None,
+        }
+    }
 }
 
-impl Executor {
-    pub fn new(clob: ClobClient, risk_config: RiskConfig) -> Self {
+/// Stable identity of a signal for execution dedup: market, side, edge to
+/// the cent and the `bucket_secs`-long time bucket it falls in
+pub fn signal_fingerprint(signal: &Signal, bucket_secs: u64) -> String {
+    let bucket = signal.timestamp.timestamp().div_euclid(bucket_secs.max(1) as i64);
+    format!(
+        "{}:{:?}:{}:{}",
+        signal.market_id,
+        signal.side,
+        signal.edge.round_dp(2).normalize(),
+        bucket
+    )
+}
+
+impl<C: ClobClientTrait> Executor<C> {
+    pub fn new(clob: C, risk_config: RiskConfig) -> Self {
         Self {
             clob,
             risk_config,
             positions: RwLock::new(HashMap::new()),
+            committed_kelly: RwLock::new(HashMap::new()),
+            complements: RwLock::new(HashMap::new()),
+            constraints: RwLock::new(HashMap::new()),
+            pending_open: RwLock::new(HashMap::new()),
+            events: RwLock::new(HashMap::new()),
             daily_pnl: RwLock::new(Decimal::ZERO),
+            executed_signals: None,
+            account_id: None,
+            fees: Arc::new(PolymarketFees::default()),
+            two_phase: None,
+            sizing: None,
+            notifier: None,
+            probe_cancellations_total: AtomicU64::new(0),
+            probe_slippage_cost_usdc: RwLock::new(Decimal::ZERO),
+            expiring_orders: RwLock::new(HashMap::new()),
+            expired_signals_total: AtomicU64::new(0),
+            sinks: Vec::new(),
+            profiles: None,
+        }
+    }
+
+    /// Apply the bot-wide executor options
+    pub fn with_settings(self, settings: &ExecutorSettings) -> Self {
+        let mut executor = self.with_fees(settings.fees.clone());
+        executor.two_phase = settings.two_phase.clone();
+        executor.sizing = settings.sizing.clone();
+        executor.notifier = settings.notifier.clone();
+        executor.sinks = settings.sinks.clone();
+        executor.profiles = settings.profiles.clone();
+        executor
+    }
+
+    /// Probe large orders as configured in `two_phase` (see
+    /// [`execute_two_phase`](Self::execute_two_phase))
+    pub fn with_two_phase(mut self, two_phase: TwoPhaseConfig) -> Self {
+        self.two_phase = Some(two_phase);
+        self
+    }
+
+    /// Normalize opening sizes as configured in `sizing`: capped at a share
+    /// of the visible depth, rounded to the exchange's lot and skipped or
+    /// accumulated below the minimum order
+    pub fn with_sizing(mut self, sizing: OrderSizingConfig) -> Self {
+        self.sizing = Some(sizing);
+        self
+    }
+
+    /// Record fees on trades from `fees` (no fees by default)
+    pub fn with_fees(mut self, fees: Arc<dyn FeeModel>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Skip signals already executed within `risk.signal_dedup_ttl_secs`,
+    /// as recorded in `db`
+    pub fn with_executed_signals(mut self, db: Arc<Database>) -> Self {
+        self.executed_signals = Some(db);
+        self
+    }
+
+    /// Send order, fill, rejection and risk block events to `sink` too
+    pub fn with_event_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Work opening orders at market by their market's profile: maker
+    /// first on wide-spread markets, and in slices no larger than a share of
+    /// the usual top-of-book depth
+    pub fn with_market_profiles(mut self, profiles: Arc<MarketProfiles>) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Tag trades with `account_id` and keep its executed signals apart
+    /// from other accounts
+    pub fn with_account(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    pub fn account_id(&self) -> Option<&str> {
+        self.account_id.as_deref()
+    }
+
+    /// Record the YES/NO token pair of a binary market so signals on it can be
+    /// netted against inventory in either outcome. Markets of negRisk events
+    /// are also grouped so risk limits see the event as one position, and
+    /// the market's tick and minimum size apply to orders on its tokens.
+    pub async fn register_market(&self, market: &Market) {
+        if let Some(order_constraints) = market.order_constraints {
+            let mut constraints = self.constraints.write().await;
+            for outcome in &market.outcomes {
+                constraints.insert(outcome.token_id.clone(), order_constraints);
+            }
+        }
+
+        if let [a, b] = market.outcomes.as_slice() {
+            let mut complements = self.complements.write().await;
+            complements.insert(a.token_id.clone(), b.token_id.clone());
+            complements.insert(b.token_id.clone(), a.token_id.clone());
+        }
+
+        if let (true, Some(event_id)) = (market.neg_risk, &market.event_id) {
+            let mut events = self.events.write().await;
+            let markets = events.entry(event_id.clone()).or_default();
+            match markets.iter_mut().find(|m| m.id == market.id) {
+                Some(existing) => *existing = market.clone(),
+                None => markets.push(market.clone()),
+            }
+        }
+    }
+
+    /// Aggregated position across a registered negRisk event
+    pub async fn event_view(&self, event_id: &str) -> Option<EventPositionView> {
+        let events = self.events.read().await;
+        let markets = events.get(event_id)?;
+        let positions = self.positions.read().await;
+        Some(EventPositionView::from_holdings(event_id, markets, &positions))
+    }
+
+    /// Net a signal's desired exposure change against current holdings
+    pub async fn plan_order(&self, signal: &Signal, size_shares: Decimal) -> Result<OrderPlan> {
+        let complement = self.complements.read().await.get(&signal.token_id).cloned();
+        let holdings = Holdings {
+            token: self.clob.get_token_holdings(&signal.token_id).await?,
+            complement: match &complement {
+                Some(token_id) => self.clob.get_token_holdings(token_id).await?,
+                None => Decimal::ZERO,
+            },
+        };
+
+        Ok(inventory::plan_order(
+            &signal.token_id,
+            complement.as_deref(),
+            signal.side,
+            size_shares,
+            holdings,
+        ))
+    }
+
+    /// Execute a trading signal at market
+    pub async fn execute(&self, signal: &Signal, portfolio_value: Decimal) -> Result<Vec<Trade>> {
+        self.execute_with_type(signal, portfolio_value, OrderType::Market).await
+    }
+
+    /// Execute a trading signal with an explicit order type.
+    ///
+    /// The signal is first netted against inventory (see [`inventory`]), so it
+    /// may place up to two orders: a sell of held shares pointing the other way,
+    /// then a new position for the remainder. Returns one trade per order the
+    /// exchange accepted: `Filled` when it matched, `Resting` with the exchange
+    /// order ID when it is working on the book. Killed orders (e.g. a FOK that
+    /// could not fill) produce no trade; if the reducing order is killed the
+    /// opening order is not placed.
+    ///
+    /// A signal past its `valid_until` places nothing; orders it places that
+    /// may rest expire at that time.
+    pub async fn execute_with_type(
+        &self,
+        signal: &Signal,
+        portfolio_value: Decimal,
+        order_type: OrderType,
+    ) -> Result<Vec<Trade>> {
+        let span = tracing::info_span!("execute_trade", trace_id = %signal.trace_id);
+        self.execute_traced(signal, portfolio_value, order_type).instrument(span).await
+    }
+
+    async fn execute_traced(&self, signal: &Signal, portfolio_value: Decimal, order_type: OrderType) -> Result<Vec<Trade>> {
+        if self.skip_expired(signal) {
+            return Ok(Vec::new());
+        }
+        let fingerprint = self.fingerprint(signal);
+        if self.already_executed(&fingerprint).await? {
+            return Ok(Vec::new());
+        }
+        let plan = self.checked_plan(signal, portfolio_value).await?;
+
+        let mut trades = Vec::new();
+        for leg in plan.legs() {
+            let reducing = plan.reduce.as_ref() == Some(leg);
+            let placed = match (&self.profiles, order_type) {
+                (Some(profiles), OrderType::Market) if !reducing => self.place_profiled(signal, leg, profiles).await?,
+                _ => self.place_leg(signal, leg, order_type).await?.into_iter().collect(),
+            };
+            if placed.is_empty() && reducing {
+                tracing::info!(
+                    "Reducing order for {} not filled, skipping new position",
+                    leg.token_id
+                );
+                break;
+            }
+            for trade in placed {
+                if !reducing && trade.status == TradeStatus::Filled {
+                    self.commit_kelly(signal, trade.size, &leg.token_id, portfolio_value).await;
+                }
+                trades.push(trade);
+            }
+        }
+
+        if !trades.is_empty() {
+            self.record_executed(&fingerprint).await;
+        }
+
+        Ok(trades)
+    }
+
+    /// Execute a large signal in two phases: a `probe_fraction` slice at
+    /// market first, then the rest only if the slice filled within
+    /// `max_acceptable_slippage_bps` of the price the signal was priced at.
+    /// Orders under `min_size_for_probe_usdc` are sent in one go. Returns
+    /// the opening trade, probe and remainder reported together; `None`
+    /// when nothing was traded.
+    ///
+    /// Only signals that open positions can be probed; one that would first
+    /// reduce inventory held the other way is rejected (see
+    /// [`plan_order`](Self::plan_order)) and should go through [`execute`](Self::execute).
+    pub async fn execute_two_phase(&self, signal: &Signal, portfolio_value: Decimal) -> Result<Option<Trade>> {
+        let span = tracing::info_span!("execute_trade", trace_id = %signal.trace_id);
+        self.execute_two_phase_traced(signal, portfolio_value).instrument(span).await
+    }
+
+    async fn execute_two_phase_traced(&self, signal: &Signal, portfolio_value: Decimal) -> Result<Option<Trade>> {
+        let config = self.two_phase.clone().unwrap_or_default();
+        if self.skip_expired(signal) {
+            return Ok(None);
+        }
+        let fingerprint = self.fingerprint(signal);
+        if self.already_executed(&fingerprint).await? {
+            return Ok(None);
+        }
+        let plan = self.checked_plan(signal, portfolio_value).await?;
+        if let Some(reduce) = &plan.reduce {
+            return Err(BotError::Execution(format!(
+                "Two-phase execution only opens positions; signal would first sell {:.2} shares of {}",
+                reduce.size, reduce.token_id
+            )));
+        }
+        let Some(open) = plan.open else {
+            return Ok(None);
+        };
+
+        let expected_price = if open.on_complement {
+            Decimal::ONE - signal.market_probability
+        } else {
+            signal.market_probability
+        };
+        let trade = if open.size * expected_price < config.min_size_for_probe_usdc {
+            self.place_opening(signal, &open, portfolio_value).await?
+        } else {
+            self.probe_then_fill(signal, &open, expected_price, portfolio_value, &config).await?
+        };
+
+        if trade.is_some() {
+            self.record_executed(&fingerprint).await;
+        }
+        Ok(trade)
+    }
+
+    /// Send a `probe_fraction` slice of `open`, then the rest if the slice
+    /// filled close enough to `expected_price`
+    async fn probe_then_fill(
+        &self,
+        signal: &Signal,
+        open: &PlannedLeg,
+        expected_price: Decimal,
+        portfolio_value: Decimal,
+        config: &TwoPhaseConfig,
+    ) -> Result<Option<Trade>> {
+        let probe_leg = PlannedLeg {
+            size: open.size * config.probe_fraction,
+            ..open.clone()
+        };
+        let Some(mut probe) = self.place_leg(signal, &probe_leg, OrderType::Market).await? else {
+            return Ok(None);
+        };
+        if probe.status == TradeStatus::Resting {
+            if !self.await_fill(&probe.order_id, config.probe_timeout_secs).await? {
+                self.clob.cancel_order(&probe.order_id).await?;
+                tracing::info!(
+                    "Probe {} for {} not filled within {}s, abandoning order",
+                    probe.order_id,
+                    signal.market_id,
+                    config.probe_timeout_secs
+                );
+                return Ok(None);
+            }
+            self.update_position(&probe.token_id, probe.side, probe.size).await;
+            probe.status = TradeStatus::Filled;
+        }
+        self.commit_kelly(signal, probe.size, &probe.token_id, portfolio_value).await;
+
+        // Slippage against the signal's price, positive when adverse
+        let adverse = match probe.side {
+            Side::Buy => probe.price - expected_price,
+            Side::Sell => expected_price - probe.price,
+        };
+        let slippage_bps = if expected_price > Decimal::ZERO {
+            adverse / expected_price * Decimal::from(10_000)
+        } else {
+            Decimal::ZERO
+        };
+        if slippage_bps >= config.max_acceptable_slippage_bps {
+            self.probe_cancellations_total.fetch_add(1, Ordering::Relaxed);
+            *self.probe_slippage_cost_usdc.write().await += (adverse * probe.size).max(Decimal::ZERO);
+            tracing::warn!(
+                "Probe for {} slipped {:.1}bps (limit {:.1}bps), not sending the remaining {:.2} shares",
+                signal.market_id,
+                slippage_bps,
+                config.max_acceptable_slippage_bps,
+                open.size - probe.size
+            );
+            if let Some(notifier) = &self.notifier {
+                let sent = notifier
+                    .probe_slippage_too_high(&signal.market_id, slippage_bps, config.max_acceptable_slippage_bps)
+                    .await;
+                if let Err(e) = sent {
+                    tracing::warn!("Failed to send slippage warning: {}", e);
+                }
+            }
+            return Ok(Some(probe));
+        }
+
+        let rest_leg = PlannedLeg {
+            size: open.size - probe.size,
+            ..open.clone()
+        };
+        let Some(rest) = self.place_opening(signal, &rest_leg, portfolio_value).await? else {
+            return Ok(Some(probe));
+        };
+        let size = probe.size + rest.size;
+        Ok(Some(Trade {
+            price: (probe.price * probe.size + rest.price * rest.size) / size,
+            size,
+            fee: probe.fee + rest.fee,
+            ..rest
+        }))
+    }
+
+    /// Place an opening leg at market and commit its Kelly fraction once filled
+    async fn place_opening(&self, signal: &Signal, leg: &PlannedLeg, portfolio_value: Decimal) -> Result<Option<Trade>> {
+        let trade = self.place_leg(signal, leg, OrderType::Market).await?;
+        if trade.as_ref().is_some_and(|t| t.status == TradeStatus::Filled) {
+            self.commit_kelly(signal, leg.size, &leg.token_id, portfolio_value).await;
+        }
+        Ok(trade)
+    }
+
+    /// Work an opening leg at market as its market's profile suggests, in
+    /// slices of at most the profile's slice size. Returns the filled slices
+    /// as one trade, followed by the slice left resting if one didn't fill;
+    /// no slices follow a resting or killed one.
+    async fn place_profiled(&self, signal: &Signal, leg: &PlannedLeg, profiles: &MarketProfiles) -> Result<Vec<Trade>> {
+        let style = profiles.execution_style(&signal.market_id);
+        let max_slice = profiles.max_slice(&signal.market_id).unwrap_or(leg.size);
+        let wait_secs = profiles.config().maker_wait_secs;
+
+        let mut filled: Option<Trade> = None;
+        let mut resting = None;
+        let mut remaining = leg.size;
+        while remaining > Decimal::ZERO {
+            let slice = PlannedLeg {
+                size: remaining.min(max_slice),
+                ..leg.clone()
+            };
+            let placed = match style {
+                ExecutionStyle::Taker => self.place_leg(signal, &slice, OrderType::Market).await?,
+                ExecutionStyle::MakerThenTaker => self.place_maker_then_taker(signal, &slice, wait_secs).await?,
+            };
+            let Some(trade) = placed else { break };
+            if trade.status == TradeStatus::Resting {
+                resting = Some(trade);
+                break;
+            }
+            remaining -= trade.size;
+            filled = Some(match filled {
+                Some(so_far) => {
+                    let size = so_far.size + trade.size;
+                    Trade {
+                        price: (so_far.price * so_far.size + trade.price * trade.size) / size,
+                        size,
+                        fee: so_far.fee + trade.fee,
+                        ..trade
+                    }
+                }
+                None => trade,
+            });
+        }
+        Ok(filled.into_iter().chain(resting).collect())
+    }
+
+    /// Join the near touch with `leg` for `wait_secs`, then cancel and take
+    /// the best opposing price if it hasn't filled
+    async fn place_maker_then_taker(&self, signal: &Signal, leg: &PlannedLeg, wait_secs: u64) -> Result<Option<Trade>> {
+        // Expires well after we stop waiting, so leaving the book means filled
+        let expiry = Utc::now() + chrono::Duration::seconds(wait_secs as i64 * 2 + 60);
+        let Some(maker) = self.place_leg(signal, leg, OrderType::GoodTilTime { expiry }).await? else {
+            return self.place_leg(signal, leg, OrderType::Market).await;
+        };
+        if maker.status == TradeStatus::Filled {
+            return Ok(Some(maker));
+        }
+        if self.await_fill(&maker.order_id, wait_secs).await? {
+            self.expiring_orders.write().await.remove(&maker.order_id);
+            return Ok(Some(self.record_resting_fill(&maker).await));
+        }
+        self.cancel_order(&maker.order_id).await?;
+        tracing::info!(
+            "Maker order {} for {} not filled within {}s, taking",
+            maker.order_id,
+            signal.market_id,
+            wait_secs
+        );
+        self.place_leg(signal, leg, OrderType::Market).await
+    }
+
+    /// Wait until `order_id` leaves the open orders; `false` if it is still
+    /// open after `timeout_secs`
+    async fn await_fill(&self, order_id: &str, timeout_secs: u64) -> Result<bool> {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_secs);
+        loop {
+            let open = self.clob.get_open_orders().await?;
+            if !open.iter().any(|o| o.order_id == order_id) {
+                return Ok(true);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(PROBE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Whether `signal` is past its `valid_until`, counting it if so
+    fn skip_expired(&self, signal: &Signal) -> bool {
+        let Some(valid_until) = signal.valid_until.filter(|v| *v <= Utc::now()) else {
+            return false;
+        };
+        self.expired_signals_total.fetch_add(1, Ordering::Relaxed);
+        tracing::info!(
+            "Signal on {} expired at {} before trading, skipping",
+            signal.market_id,
+            valid_until.format("%H:%M:%S")
+        );
+        true
+    }
+
+    /// Cancel the resting orders whose signal has expired, on venues that
+    /// don't expire GTD orders themselves; returns how many were cancelled.
+    /// Orders that have filled meanwhile are just forgotten.
+    pub async fn cancel_expired_orders(&self) -> Result<usize> {
+        let now = Utc::now();
+        let due: Vec<String> = self
+            .expiring_orders
+            .read()
+            .await
+            .iter()
+            .filter(|(_, expiry)| **expiry <= now)
+            .map(|(order_id, _)| order_id.clone())
+            .collect();
+        if due.is_empty() {
+            return Ok(0);
         }
+
+        let open: HashSet<String> = self.clob.get_open_orders().await?.into_iter().map(|o| o.order_id).collect();
+        let mut cancelled = 0;
+        for order_id in due {
+            if open.contains(&order_id) {
+                self.clob.cancel_order(&order_id).await?;
+                self.expired_signals_total.fetch_add(1, Ordering::Relaxed);
+                tracing::info!("Cancelled order {}: its signal expired unfilled", order_id);
+                cancelled += 1;
+            }
+            self.expiring_orders.write().await.remove(&order_id);
+        }
+        Ok(cancelled)
+    }
+
+    /// IDs of this account's orders working on the book
+    pub async fn open_order_ids(&self) -> Result<HashSet<String>> {
+        Ok(self.clob.get_open_orders().await?.into_iter().map(|o| o.order_id).collect())
+    }
+
+    /// Cancel a resting order
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.clob.cancel_order(order_id).await?;
+        self.expiring_orders.write().await.remove(order_id);
+        Ok(())
     }
 
-    /// Execute a trading signal
-    pub async fn execute(&self, signal: &Signal, portfolio_value: Decimal) -> Result<Option<Trade>> {
-        // Pre-trade risk checks
-        self.check_risk_limits(signal, portfolio_value).await?;
+    /// Move the resting order `trade` placed to `price`, keeping its token,
+    /// side and size. Returns the replacement (`Filled` if it matched on
+    /// arrival), or `None` when the exchange killed it.
+    pub async fn replace_order(&self, trade: &Trade, price: Decimal) -> Result<Option<Trade>> {
+        let price = self.round_to_tick(&trade.token_id, trade.side, price).await;
+        let order = Order {
+            token_id: trade.token_id.clone(),
+            side: trade.side,
+            price,
+            size: trade.size,
+            order_type: ClobOrderType::GTC,
+        };
+        let order_status = self.clob.replace_order(&trade.order_id, &order).await?;
+        self.expiring_orders.write().await.remove(&trade.order_id);
+        let Some(status) = classify_order_status(&order_status) else {
+            tracing::info!("Replacement of order {} not accepted ({})", trade.order_id, order_status.status);
+            return Ok(None);
+        };
 
-        // Calculate actual order size
+        let size = if status == TradeStatus::Filled {
+            self.update_position(&trade.token_id, trade.side, order_status.filled_size).await;
+            order_status.filled_size
+        } else {
+            trade.size
+        };
+        let role = if status == TradeStatus::Filled { FeeRole::Taker } else { FeeRole::Maker };
+        let replacement = Trade {
+            id: uuid::Uuid::new_v4().to_string(),
+            order_id: order_status.order_id,
+            price,
+            size,
+            fee: self.fees.order_fee(&trade.market_id, trade.side, price, size, role),
+            timestamp: Utc::now(),
+            status,
+            ..trade.clone()
+        };
+        emit_all(&self.sinks, BotEvent::from_trade(&replacement)).await;
+        Ok(Some(replacement))
+    }
+
+    /// Book the fill of the resting order `trade` placed, once it has left
+    /// the book; returns the trade as filled
+    pub async fn record_resting_fill(&self, trade: &Trade) -> Trade {
+        self.update_position(&trade.token_id, trade.side, trade.size).await;
+        let filled = Trade { status: TradeStatus::Filled, ..trade.clone() };
+        emit_all(&self.sinks, BotEvent::from_trade(&filled)).await;
+        filled
+    }
+
+    /// Signals dropped after their `valid_until`, and orders cancelled when
+    /// it passed
+    pub fn expired_signals_total(&self) -> u64 {
+        self.expired_signals_total.load(Ordering::Relaxed)
+    }
+
+    /// Large orders abandoned after their probe slipped too far
+    pub fn probe_cancellations_total(&self) -> u64 {
+        self.probe_cancellations_total.load(Ordering::Relaxed)
+    }
+
+    /// Slippage paid on the probes of abandoned orders (USDC)
+    pub async fn probe_slippage_cost_usdc(&self) -> Decimal {
+        *self.probe_slippage_cost_usdc.read().await
+    }
+
+    /// Dedup key of `signal` on this executor's account
+    fn fingerprint(&self, signal: &Signal) -> String {
+        let ttl_secs = self.risk_config.signal_dedup_ttl_secs;
+        match &self.account_id {
+            Some(account) => format!("{}:{}", account, signal_fingerprint(signal, ttl_secs)),
+            None => signal_fingerprint(signal, ttl_secs),
+        }
+    }
+
+    async fn already_executed(&self, fingerprint: &str) -> Result<bool> {
+        let Some(db) = &self.executed_signals else {
+            return Ok(false);
+        };
+        let executed = db.is_signal_executed(fingerprint, Utc::now()).await?;
+        if executed {
+            tracing::info!("Signal {} already executed, skipping", fingerprint);
+        }
+        Ok(executed)
+    }
+
+    async fn record_executed(&self, fingerprint: &str) {
+        let Some(db) = &self.executed_signals else {
+            return;
+        };
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::seconds(self.risk_config.signal_dedup_ttl_secs as i64);
+        if let Err(e) = db.record_executed_signal(fingerprint, now, expires_at).await {
+            tracing::warn!("Failed to record executed signal {}: {}", fingerprint, e);
+        }
+    }
+
+    /// Size the signal, net it against inventory and apply the pre-trade
+    /// risk checks (reducing inventory is always allowed)
+    async fn checked_plan(&self, signal: &Signal, portfolio_value: Decimal) -> Result<OrderPlan> {
+        let plan = self.plan_within_limits(signal, portfolio_value).await;
+        let event = match &plan {
+            Err(BotError::RiskLimit(reason)) => BotEvent::RiskBlocked {
+                market_id: signal.market_id.clone(),
+                reason: reason.clone(),
+                account_id: self.account_id.clone(),
+                trace_id: signal.trace_id,
+                timestamp: Utc::now(),
+            },
+            Err(BotError::OrderRejected(reason)) => self.rejection(signal, &signal.token_id, reason.clone()),
+            _ => return plan,
+        };
+        emit_all(&self.sinks, event).await;
+        plan
+    }
+
+    fn rejection(&self, signal: &Signal, token_id: &str, reason: String) -> BotEvent {
+        BotEvent::OrderRejected {
+            market_id: signal.market_id.clone(),
+            token_id: token_id.to_string(),
+            reason,
+            account_id: self.account_id.clone(),
+            trace_id: signal.trace_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    async fn plan_within_limits(&self, signal: &Signal, portfolio_value: Decimal) -> Result<OrderPlan> {
         let size_usd = signal.suggested_size * portfolio_value;
         let size_shares = size_usd / signal.market_probability;
 
-        // Get current market price for limit order
-        let book = self.clob.get_order_book(&signal.token_id).await?;
-        let limit_price = match signal.side {
-            crate::types::Side::Buy => book
-                .best_ask()
-                .ok_or_else(|| BotError::Execution("No asks available".into()))?,
-            crate::types::Side::Sell => book
-                .best_bid()
-                .ok_or_else(|| BotError::Execution("No bids available".into()))?,
+        let mut plan = self.plan_order(signal, size_shares).await?;
+        if plan.open.is_some() {
+            self.carry_over_pending(signal, &mut plan).await;
+            self.clip_to_kelly_cap(signal, &mut plan, portfolio_value).await?;
+            self.normalize_open(signal, &mut plan).await?;
+        }
+        if let Some(open) = &plan.open {
+            let open_usd = open.size * signal.market_probability;
+            self.check_risk_limits(signal, open_usd, portfolio_value).await?;
+        }
+        Ok(plan)
+    }
+
+    /// Add the size accumulated below the minimum on the signal's market to
+    /// its opening leg; accumulation on another token of the market (the
+    /// signal flipped sides) is dropped
+    async fn carry_over_pending(&self, signal: &Signal, plan: &mut OrderPlan) {
+        let Some(open) = plan.open.as_mut() else {
+            return;
+        };
+        let Some(pending) = self.pending_open.write().await.remove(&signal.market_id) else {
+            return;
+        };
+        if pending.token_id == open.token_id && pending.side == open.side {
+            open.size += pending.size;
+        } else {
+            tracing::info!(
+                "Dropping {:.2} shares of {} accumulated on {}, signal now opens {}",
+                pending.size,
+                pending.token_id,
+                signal.market_id,
+                open.token_id
+            );
+        }
+    }
+
+    /// Cap the opening leg at `max_book_depth_fraction` of the visible depth
+    /// it would take, round it down to the exchange's lot and hold it to the
+    /// minimum order (the larger of `min_order_notional_usdc` and the
+    /// market's minimum size). A sub-minimum opening is skipped, rejecting a
+    /// signal with nothing to reduce, or under
+    /// [`SubMinimumPolicy::Accumulate`] kept for the market's next signal.
+    async fn normalize_open(&self, signal: &Signal, plan: &mut OrderPlan) -> Result<()> {
+        let (Some(sizing), Some(open)) = (&self.sizing, plan.open.as_mut()) else {
+            return Ok(());
+        };
+
+        let depth = self.visible_depth(&open.token_id, open.side).await?;
+        let depth_cap = depth * sizing.max_book_depth_fraction;
+        if open.size > depth_cap {
+            tracing::info!(
+                "Capping {} from {:.2} to {:.2} shares ({} of {:.2} visible)",
+                open.token_id,
+                open.size,
+                depth_cap,
+                sizing.max_book_depth_fraction,
+                depth
+            );
+            open.size = depth_cap;
+        }
+        open.size = open.size.round_dp_with_strategy(SIZE_DECIMALS, RoundingStrategy::ToZero);
+
+        let price = if open.on_complement {
+            Decimal::ONE - signal.market_probability
+        } else {
+            signal.market_probability
+        };
+        let min_size = self
+            .constraints
+            .read()
+            .await
+            .get(&open.token_id)
+            .map_or(Decimal::ZERO, |c| c.min_order_size);
+        let notional = open.size * price;
+        if open.size >= min_size && notional >= sizing.min_order_notional_usdc && !open.size.is_zero() {
+            return Ok(());
+        }
+
+        let reason = format!(
+            "Order of {:.2} shares ({:.2} USDC) of {} below the minimum of {} shares and {} USDC",
+            open.size, notional, open.token_id, min_size, sizing.min_order_notional_usdc
+        );
+        match sizing.sub_minimum {
+            SubMinimumPolicy::Skip => {
+                if plan.reduce.is_none() {
+                    return Err(BotError::OrderRejected(reason));
+                }
+                tracing::info!("{}, only reducing {}", reason, signal.token_id);
+            }
+            SubMinimumPolicy::Accumulate => {
+                tracing::info!("{}, carrying it over to the next signal on {}", reason, signal.market_id);
+                self.pending_open.write().await.insert(signal.market_id.clone(), open.clone());
+            }
+        }
+        plan.open = None;
+        Ok(())
+    }
+
+    /// Opening size accumulated below the minimum order on `market_id`
+    pub async fn pending_open_size(&self, market_id: &str) -> Decimal {
+        self.pending_open.read().await.get(market_id).map_or(Decimal::ZERO, |leg| leg.size)
+    }
+
+    /// Shares on the side of the book an order on `token_id` would take
+    async fn visible_depth(&self, token_id: &str, side: Side) -> Result<Decimal> {
+        let book = self.clob.get_order_book(token_id).await?;
+        let levels = match side {
+            Side::Buy => &book.asks,
+            Side::Sell => &book.bids,
         };
+        Ok(levels.iter().map(|l| l.size).sum())
+    }
+
+    /// Count a filled opening leg against the total Kelly cap
+    async fn commit_kelly(&self, signal: &Signal, size: Decimal, token_id: &str, portfolio_value: Decimal) {
+        if portfolio_value > Decimal::ZERO {
+            let fraction = size * signal.market_probability / portfolio_value;
+            *self.committed_kelly.write().await.entry(token_id.to_string()).or_default() += fraction;
+        }
+    }
+
+    /// Clip the opening leg so the Kelly fractions committed to open
+    /// positions stay within `risk.max_total_kelly`. Once the cap is reached
+    /// the opening leg is dropped, and a signal with nothing to reduce is
+    /// rejected.
+    async fn clip_to_kelly_cap(&self, signal: &Signal, plan: &mut OrderPlan, portfolio_value: Decimal) -> Result<()> {
+        let Some(open) = plan.open.as_mut() else {
+            return Ok(());
+        };
+        if portfolio_value <= Decimal::ZERO || signal.market_probability <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let cap = self.risk_config.max_total_kelly;
+        let committed = self.committed_kelly().await;
+        let budget = (cap - committed).max(Decimal::ZERO);
+        let fraction = open.size * signal.market_probability / portfolio_value;
+        if fraction <= budget {
+            return Ok(());
+        }
+
+        if budget.is_zero() {
+            let reason = format!(
+                "Total Kelly cap reached: {:.4} of {:.4} committed to open positions",
+                committed, cap
+            );
+            if plan.reduce.is_none() {
+                return Err(BotError::RiskLimit(reason));
+            }
+            tracing::info!("{}, only reducing {}", reason, signal.token_id);
+            plan.open = None;
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Clipping {} from {:.4} to {:.4} of bankroll (total Kelly cap {:.4}, {:.4} committed)",
+            open.token_id,
+            fraction,
+            budget,
+            cap,
+            committed
+        );
+        open.size = budget * portfolio_value / signal.market_probability;
+        Ok(())
+    }
+
+    /// Sum of the bankroll fractions opened into positions still held
+    pub async fn committed_kelly(&self) -> Decimal {
+        self.committed_kelly.read().await.values().sum()
+    }
+
+    /// Place one leg of an order plan; `None` when the exchange killed it
+    async fn place_leg(
+        &self,
+        signal: &Signal,
+        leg: &PlannedLeg,
+        order_type: OrderType,
+    ) -> Result<Option<Trade>> {
+        // Orders that may rest expire with the signal that placed them
+        let expiry = match order_type {
+            OrderType::FillOrKill => None,
+            OrderType::GoodTilTime { expiry } => Some(signal.valid_until.map_or(expiry, |v| v.min(expiry))),
+            OrderType::Market | OrderType::Limit { .. } => signal.valid_until,
+        };
+        let (limit_price, clob_type) = match order_type {
+            OrderType::Limit { price } if leg.on_complement => (Decimal::ONE - price, ClobOrderType::GTC),
+            OrderType::Limit { price } => (price, ClobOrderType::GTC),
+            OrderType::Market => (self.touch_price(&leg.token_id, leg.side, true).await?, ClobOrderType::GTC),
+            OrderType::FillOrKill => (self.touch_price(&leg.token_id, leg.side, true).await?, ClobOrderType::FOK),
+            OrderType::GoodTilTime { .. } => (self.touch_price(&leg.token_id, leg.side, false).await?, ClobOrderType::GTC),
+        };
+        let limit_price = self.round_to_tick(&leg.token_id, leg.side, limit_price).await;
+        let gtd = expiry.is_some() && self.clob.supports_gtd();
 
-        // Create and place order
         let order = Order {
-            token_id: signal.token_id.clone(),
-            side: signal.side,
+            token_id: leg.token_id.clone(),
+            side: leg.side,
             price: limit_price,
-            size: size_shares,
-            order_type: OrderType::GTC,
+            size: leg.size,
+            order_type: if gtd { ClobOrderType::GTD } else { clob_type },
         };
 
         tracing::info!(
-            "Placing order: {} {:.2} shares of {} @ {:.4}",
-            match signal.side {
-                crate::types::Side::Buy => "BUY",
-                crate::types::Side::Sell => "SELL",
+            "Placing {:?} order: {} {:.2} shares of {} @ {:.4}",
+            order_type,
+            match leg.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
             },
-            size_shares,
-            signal.token_id,
+            leg.size,
+            leg.token_id,
             limit_price
         );
 
-        let order_status = self.clob.place_order(&order).await?;
+        let placed = match expiry {
+            Some(expiry) if gtd => self.clob.place_order_until(&order, expiry.timestamp()).await,
+            _ => self.clob.place_order(&order).await,
+        };
+        let order_status = match placed {
+            Ok(status) => status,
+            Err(e) => {
+                emit_all(&self.sinks, self.rejection(signal, &leg.token_id, e.to_string())).await;
+                return Err(e);
+            }
+        };
+
+        let Some(status) = classify_order_status(&order_status) else {
+            tracing::info!(
+                "Order {} not filled ({}), no position taken",
+                order_status.order_id,
+                order_status.status
+            );
+            let reason = format!("Order {} not filled ({})", order_status.order_id, order_status.status);
+            emit_all(&self.sinks, self.rejection(signal, &leg.token_id, reason)).await;
+            return Ok(None);
+        };
 
-        // Update positions
-        self.update_position(&signal.token_id, signal.side, size_shares)
-            .await;
+        // Only matched orders change the position, by what they matched;
+        // resting ones are tracked by order ID
+        let size = if status == TradeStatus::Filled {
+            self.update_position(&leg.token_id, leg.side, order_status.filled_size).await;
+            order_status.filled_size
+        } else {
+            if let (Some(expiry), false) = (expiry, gtd) {
+                self.expiring_orders.write().await.insert(order_status.order_id.clone(), expiry);
+            }
+            leg.size
+        };
 
-        Ok(Some(Trade {
+        // Orders matched on arrival took liquidity; resting ones will be maker fills
+        let role = if status == TradeStatus::Filled { FeeRole::Taker } else { FeeRole::Maker };
+        let fee = self.fees.order_fee(&signal.market_id, leg.side, limit_price, size, role);
+
+        let trade = Trade {
             id: uuid::Uuid::new_v4().to_string(),
             order_id: order_status.order_id,
-            token_id: signal.token_id.clone(),
+            token_id: leg.token_id.clone(),
             market_id: signal.market_id.clone(),
-            side: signal.side,
+            side: leg.side,
             price: limit_price,
-            size: size_shares,
-            fee: Decimal::ZERO, // TODO: Calculate fee
+            size,
+            fee,
             timestamp: chrono::Utc::now(),
-        }))
+            status,
+            strategy_name: signal.strategy_name.clone(),
+            account_id: self.account_id.clone(),
+            trace_id: signal.trace_id,
+            tags: signal.tags.clone(),
+            edge: Some(signal.edge),
+        };
+        emit_all(&self.sinks, BotEvent::from_trade(&trade)).await;
+        Ok(Some(trade))
+    }
+
+    /// `price` on the tick of `token_id`'s market, rounded away from the
+    /// book: down for buys, up for sells
+    async fn round_to_tick(&self, token_id: &str, side: Side, price: Decimal) -> Decimal {
+        let Some(tick) = self.constraints.read().await.get(token_id).map(|c| c.tick_size) else {
+            return price;
+        };
+        if tick <= Decimal::ZERO {
+            return price;
+        }
+        let ticks = price / tick;
+        let ticks = match side {
+            Side::Buy => ticks.floor(),
+            Side::Sell => ticks.ceil(),
+        };
+        (ticks * tick).normalize()
+    }
+
+    /// Best price on the far (taking) or near (joining) side of the book
+    async fn touch_price(&self, token_id: &str, side: Side, take: bool) -> Result<Decimal> {
+        let book = self.clob.get_order_book(token_id).await?;
+        let use_asks = matches!((side, take), (Side::Buy, true) | (Side::Sell, false));
+        if use_asks {
+            book.best_ask()
+                .ok_or_else(|| BotError::Execution("No asks available".into()))
+        } else {
+            book.best_bid()
+                .ok_or_else(|| BotError::Execution("No bids available".into()))
+        }
     }
 
     /// Check all risk limits before trading
-    async fn check_risk_limits(&self, signal: &Signal, portfolio_value: Decimal) -> Result<()> {
+    async fn check_risk_limits(&self, signal: &Signal, open_usd: Decimal, portfolio_value: Decimal) -> Result<()> {
         // Check daily loss limit
         let daily_pnl = *self.daily_pnl.read().await;
         let max_loss = self.risk_config.max_daily_loss_pct * portfolio_value;
@@ -123,20 +1089,48 @@ impl Executor {
             )));
         }
 
-        // Check position limit
+        // Tokens of a negRisk event count as one position, keyed by event
         let positions = self.positions.read().await;
-        if positions.len() >= self.risk_config.max_open_positions {
-            if !positions.contains_key(&signal.token_id) {
-                return Err(BotError::RiskLimit(format!(
-                    "Max open positions ({}) reached",
-                    self.risk_config.max_open_positions
-                )));
-            }
+        let events = self.events.read().await;
+        let token_events: HashMap<&str, &str> = events
+            .iter()
+            .flat_map(|(event_id, markets)| {
+                markets
+                    .iter()
+                    .flat_map(|m| m.outcomes.iter())
+                    .map(move |o| (o.token_id.as_str(), event_id.as_str()))
+            })
+            .collect();
+        fn position_key<'a>(token_events: &HashMap<&'a str, &'a str>, token_id: &'a str) -> &'a str {
+            token_events.get(token_id).copied().unwrap_or(token_id)
         }
 
-        // Check total exposure
-        let total_exposure: Decimal = positions.values().sum();
-        let new_exposure = total_exposure + signal.suggested_size * portfolio_value;
+        // Check position limit
+        let open: HashSet<&str> = positions.keys().map(|t| position_key(&token_events, t)).collect();
+        if open.len() >= self.risk_config.max_open_positions
+            && !open.contains(position_key(&token_events, &signal.token_id))
+        {
+            return Err(BotError::RiskLimit(format!(
+                "Max open positions ({}) reached",
+                self.risk_config.max_open_positions
+            )));
+        }
+
+        // Check total exposure; negRisk events contribute only what isn't
+        // redeemable regardless of the outcome
+        let standalone: Decimal = positions
+            .iter()
+            .filter(|(token_id, _)| !token_events.contains_key(token_id.as_str()))
+            .map(|(_, size)| *size)
+            .sum();
+        let event_exposure: Decimal = events
+            .iter()
+            .map(|(event_id, markets)| {
+                EventPositionView::from_holdings(event_id, markets, &positions).net_directional_exposure()
+            })
+            .sum();
+        let total_exposure = standalone + event_exposure;
+        let new_exposure = total_exposure + open_usd;
         let max_exposure = self.risk_config.max_exposure_pct * portfolio_value;
         if new_exposure > max_exposure {
             return Err(BotError::RiskLimit(format!(
@@ -167,6 +1161,16 @@ impl Executor {
         } else {
             positions.insert(token_id.to_string(), new_size);
         }
+
+        // Selling releases the committed Kelly fraction pro rata
+        if current > Decimal::ZERO && new_size < current {
+            let mut committed = self.committed_kelly.write().await;
+            if new_size <= Decimal::ZERO {
+                committed.remove(token_id);
+            } else if let Some(fraction) = committed.get_mut(token_id) {
+                *fraction = *fraction * new_size / current;
+            }
+        }
     }
 
     /// Update daily P&L
@@ -185,4 +1189,116 @@ impl Executor {
     pub async fn get_positions(&self) -> HashMap<String, Decimal> {
         self.positions.read().await.clone()
     }
+
+    /// Replace the position cache, e.g. with one saved before a restart
+    pub async fn restore_positions(&self, positions: HashMap<String, Decimal>) {
+        *self.positions.write().await = positions;
+    }
+
+    /// Flatten every tracked position by crossing the spread with FOK orders.
+    ///
+    /// Returns the number of positions closed. Positions that fail to close
+    /// (empty book side, rejected order) are logged and left in place.
+    pub async fn close_all_positions_at_market(&self) -> Result<u32> {
+        let positions = self.get_positions().await;
+        let mut closed = 0;
+
+        for (token_id, size) in positions {
+            match self.close_at_market(&token_id, size).await {
+                Ok(Some(_)) => closed += 1,
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to close position in {}: {}", token_id, e);
+                }
+            }
+        }
+
+        Ok(closed)
+    }
+
+    /// Close the whole position in `token_id` of `market_id` with a FOK order
+    /// at the top of the book; `None` when there is no position or the order
+    /// didn't fill. The closing trade carries `tags`.
+    pub async fn close_position_at_market(
+        &self,
+        market_id: &str,
+        token_id: &str,
+        tags: Vec<String>,
+    ) -> Result<Option<Trade>> {
+        let size = self.positions.read().await.get(token_id).copied().unwrap_or_default();
+        if size.is_zero() {
+            return Ok(None);
+        }
+        let Some((order, status)) = self.close_at_market(token_id, size).await? else {
+            return Ok(None);
+        };
+        let fee = self.fees.order_fee(market_id, order.side, order.price, status.filled_size, FeeRole::Taker);
+        Ok(Some(Trade {
+            id: uuid::Uuid::new_v4().to_string(),
+            order_id: status.order_id,
+            token_id: order.token_id,
+            market_id: market_id.to_string(),
+            side: order.side,
+            price: order.price,
+            size: status.filled_size,
+            fee,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: self.account_id.clone(),
+            trace_id: Default::default(),
+            tags,
+            edge: None,
+        }))
+    }
+
+    /// FOK order against the best opposite quote for `size` shares of
+    /// `token_id` (positive means long); the order if it filled
+    async fn close_at_market(&self, token_id: &str, size: Decimal) -> Result<Option<(Order, OrderStatus)>> {
+        let side = if size > Decimal::ZERO {
+            crate::types::Side::Sell
+        } else {
+            crate::types::Side::Buy
+        };
+
+        let book = self.clob.get_order_book(token_id).await?;
+        let price = match side {
+            crate::types::Side::Sell => book.best_bid(),
+            crate::types::Side::Buy => book.best_ask(),
+        };
+        let Some(price) = price else {
+            tracing::warn!("No liquidity to close position in {}", token_id);
+            return Ok(None);
+        };
+
+        let order = Order {
+            token_id: token_id.to_string(),
+            side,
+            price,
+            size: size.abs(),
+            order_type: ClobOrderType::FOK,
+        };
+
+        let status = self.clob.place_order(&order).await?;
+        if classify_order_status(&status) != Some(TradeStatus::Filled) {
+            tracing::warn!("Close order for {} not filled: {}", token_id, status.status);
+            return Ok(None);
+        }
+        self.update_position(token_id, side, status.filled_size).await;
+        Ok(Some((order, status)))
+    }
+}
+
+/// Map an exchange order status to a trade status; `None` means it was killed
+/// or rejected without filling, or its status isn't one we know
+pub(crate) fn classify_order_status(status: &OrderStatus) -> Option<TradeStatus> {
+    match status.status.to_lowercase().as_str() {
+        "live" | "open" | "delayed" | "partial" => Some(TradeStatus::Resting),
+        "matched" | "filled" => Some(TradeStatus::Filled),
+        "unmatched" | "cancelled" | "canceled" | "killed" | "rejected" | "expired" => None,
+        other => {
+            tracing::warn!("Order {} has unknown status '{}'", status.order_id, other);
+            None
+        }
+    }
 }
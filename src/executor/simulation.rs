@@ -0,0 +1,667 @@
+//! Simulated CLOB account for dry-run trading
+//!
+//! Implements `ClobClientTrait` so the executor trades against it exactly as
+//! it would against the exchange. Every order fills immediately at its limit
+//! price moved against us by `slippage_bps`; positions are marked to market
+//! with [`SimulatedClobClient::advance_time`]. With [`SimulatedClobClient::open`]
+//! the account survives restarts in its own SQLite file.
+//!
+//! Order books come from an optional live data source; without one a single
+//! level at the last marked price is quoted on both sides.
+
+use crate::client::mock::ClobClientTrait;
+use crate::client::{OrderBook, OrderBookLevel};
+use crate::error::{BotError, Result};
+use crate::types::{Order, OrderStatus, Position, Side};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A simulated holding in one outcome token
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimPosition {
+    pub token_id: String,
+    /// Shares held
+    pub size: Decimal,
+    /// Average fill price, including slippage
+    pub avg_price: Decimal,
+    /// Last mark from `advance_time` (entry price until then)
+    pub mark_price: Decimal,
+}
+
+impl SimPosition {
+    pub fn unrealized_pnl(&self) -> Decimal {
+        (self.mark_price - self.avg_price) * self.size
+    }
+}
+
+/// A simulated fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimOrder {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    /// Price before slippage
+    pub limit_price: Decimal,
+    /// Price actually filled at
+    pub fill_price: Decimal,
+    pub size: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Performance of the simulated account
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimStats {
+    pub initial_balance: Decimal,
+    /// Cash
+    pub balance: Decimal,
+    /// Marked value of open positions
+    pub position_value: Decimal,
+    /// Cash plus position value
+    pub equity: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub realized_pnl: Decimal,
+    /// Equity return on the initial balance (0.05 = 5%)
+    pub total_return: Decimal,
+    /// Return from holding every token at its first entry price, sized as
+    /// first bought, to its current mark: what the picks made without the
+    /// trading in and out
+    pub buy_and_hold_return: Decimal,
+    pub trades: usize,
+    pub open_positions: usize,
+}
+
+#[derive(Debug, Default)]
+struct SimState {
+    balance: Decimal,
+    initial_balance: Decimal,
+    realized_pnl: Decimal,
+    positions: HashMap<String, SimPosition>,
+    orders: Vec<SimOrder>,
+    /// token_id -> (first entry price, first entry notional)
+    benchmark: HashMap<String, (Decimal, Decimal)>,
+    /// Latest price of every token passed to `advance_time`
+    marks: HashMap<String, Decimal>,
+}
+
+/// Simulated CLOB account with slippage and mark-to-market
+pub struct SimulatedClobClient {
+    slippage_bps: Decimal,
+    state: Mutex<SimState>,
+    books: Option<Arc<dyn ClobClientTrait>>,
+    pool: Option<SqlitePool>,
+}
+
+impl SimulatedClobClient {
+    /// In-memory account; state is lost on drop
+    pub fn new(initial_balance: Decimal, slippage_bps: Decimal) -> Self {
+        Self {
+            slippage_bps,
+            state: Mutex::new(SimState {
+                balance: initial_balance,
+                initial_balance,
+                ..Default::default()
+            }),
+            books: None,
+            pool: None,
+        }
+    }
+
+    /// Account persisted in the SQLite file at `path`, resuming its previous
+    /// state if there is one (`initial_balance` only seeds a new file)
+    pub async fn open<P: AsRef<Path>>(path: P, initial_balance: Decimal, slippage_bps: Decimal) -> Result<Self> {
+        let db_url = format!("sqlite:{}?mode=rwc", path.as_ref().display());
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&db_url)
+            .await?;
+
+        let mut sim = Self::new(initial_balance, slippage_bps);
+        Self::migrate(&pool).await?;
+        sim.load(&pool).await?;
+        sim.pool = Some(pool);
+        sim.persist_account().await?;
+
+        Ok(sim)
+    }
+
+    /// Quote order books from `source` instead of the marked prices
+    pub fn with_order_books(mut self, source: Arc<dyn ClobClientTrait>) -> Self {
+        self.books = Some(source);
+        self
+    }
+
+    /// Mark positions to `current_prices` (token_id -> price). Tokens not
+    /// listed keep their previous mark.
+    pub async fn advance_time(&self, current_prices: &HashMap<String, Decimal>) -> Result<()> {
+        let marked: Vec<SimPosition> = {
+            let mut state = self.state.lock();
+            state
+                .marks
+                .extend(current_prices.iter().map(|(k, v)| (k.clone(), *v)));
+            state
+                .positions
+                .values_mut()
+                .filter_map(|p| {
+                    let price = current_prices.get(&p.token_id)?;
+                    p.mark_price = *price;
+                    Some(p.clone())
+                })
+                .collect()
+        };
+
+        for position in &marked {
+            self.persist_position(position).await?;
+        }
+        Ok(())
+    }
+
+    /// Current balance, P&L and benchmark comparison
+    pub fn get_simulation_stats(&self) -> SimStats {
+        let state = self.state.lock();
+
+        let position_value: Decimal = state.positions.values().map(|p| p.mark_price * p.size).sum();
+        let unrealized_pnl: Decimal = state.positions.values().map(SimPosition::unrealized_pnl).sum();
+        let equity = state.balance + position_value;
+
+        let benchmark_pnl: Decimal = state
+            .benchmark
+            .iter()
+            .filter(|(_, (entry, _))| !entry.is_zero())
+            .map(|(token_id, (entry, notional))| {
+                let mark = state
+                    .marks
+                    .get(token_id)
+                    .or_else(|| state.positions.get(token_id).map(|p| &p.mark_price))
+                    .copied()
+                    .unwrap_or(*entry);
+                *notional * (mark / *entry - Decimal::ONE)
+            })
+            .sum();
+
+        let ratio = |x: Decimal| {
+            if state.initial_balance.is_zero() {
+                Decimal::ZERO
+            } else {
+                x / state.initial_balance
+            }
+        };
+
+        SimStats {
+            initial_balance: state.initial_balance,
+            balance: state.balance,
+            position_value,
+            equity,
+            unrealized_pnl,
+            realized_pnl: state.realized_pnl,
+            total_return: ratio(equity - state.initial_balance),
+            buy_and_hold_return: ratio(benchmark_pnl),
+            trades: state.orders.len(),
+            open_positions: state.positions.len(),
+        }
+    }
+
+    /// All simulated fills, oldest first
+    pub fn orders(&self) -> Vec<SimOrder> {
+        self.state.lock().orders.clone()
+    }
+
+    /// Simulated holding in a token
+    pub fn position(&self, token_id: &str) -> Option<SimPosition> {
+        self.state.lock().positions.get(token_id).cloned()
+    }
+
+    /// Limit price moved against the taker by the configured slippage
+    fn fill_price(&self, order: &Order) -> Decimal {
+        let slippage = order.price * self.slippage_bps / Decimal::from(10_000);
+        match order.side {
+            Side::Buy => order.price + slippage,
+            Side::Sell => order.price - slippage,
+        }
+    }
+
+    /// Apply a fill to the state; returns the fill and the position after it
+    fn apply_fill(&self, order: &Order) -> Result<(SimOrder, SimPosition)> {
+        let price = self.fill_price(order);
+        let notional = price * order.size;
+        let mut state = self.state.lock();
+
+        let held = state.positions.get(&order.token_id).map(|p| p.size).unwrap_or(Decimal::ZERO);
+        match order.side {
+            Side::Buy if state.balance < notional => {
+                return Err(BotError::InsufficientBalance {
+                    required: notional,
+                    available: state.balance,
+                });
+            }
+            Side::Sell if held < order.size => {
+                return Err(BotError::Execution(format!(
+                    "Cannot sell {} shares of {}, holding {}",
+                    order.size, order.token_id, held
+                )));
+            }
+            _ => {}
+        }
+
+        let position = state
+            .positions
+            .entry(order.token_id.clone())
+            .or_insert_with(|| SimPosition {
+                token_id: order.token_id.clone(),
+                size: Decimal::ZERO,
+                avg_price: price,
+                mark_price: price,
+            });
+        let mut realized = Decimal::ZERO;
+        match order.side {
+            Side::Buy => {
+                position.avg_price = (position.avg_price * position.size + notional) / (position.size + order.size);
+                position.size += order.size;
+            }
+            Side::Sell => {
+                realized = (price - position.avg_price) * order.size;
+                position.size -= order.size;
+            }
+        }
+        let position = position.clone();
+        if position.size.is_zero() {
+            state.positions.remove(&order.token_id);
+        }
+
+        match order.side {
+            Side::Buy => state.balance -= notional,
+            Side::Sell => state.balance += notional,
+        }
+        state.realized_pnl += realized;
+        if order.side == Side::Buy {
+            state.benchmark.entry(order.token_id.clone()).or_insert((price, notional));
+        }
+
+        let fill = SimOrder {
+            order_id: format!("sim_{}", state.orders.len() + 1),
+            token_id: order.token_id.clone(),
+            side: order.side,
+            limit_price: order.price,
+            fill_price: price,
+            size: order.size,
+            timestamp: Utc::now(),
+        };
+        state.orders.push(fill.clone());
+
+        Ok((fill, position))
+    }
+
+    async fn migrate(pool: &SqlitePool) -> Result<()> {
+        for ddl in [
+            r#"
+            CREATE TABLE IF NOT EXISTS sim_account (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                balance TEXT NOT NULL,
+                initial_balance TEXT NOT NULL,
+                realized_pnl TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS sim_positions (
+                token_id TEXT PRIMARY KEY,
+                size TEXT NOT NULL,
+                avg_price TEXT NOT NULL,
+                mark_price TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS sim_orders (
+                order_id TEXT PRIMARY KEY,
+                token_id TEXT NOT NULL,
+                side TEXT NOT NULL,
+                limit_price TEXT NOT NULL,
+                fill_price TEXT NOT NULL,
+                size TEXT NOT NULL,
+                timestamp TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS sim_benchmark (
+                token_id TEXT PRIMARY KEY,
+                entry_price TEXT NOT NULL,
+                notional TEXT NOT NULL
+            )
+            "#,
+        ] {
+            sqlx::query(ddl).execute(pool).await?;
+        }
+        Ok(())
+    }
+
+    async fn load(&mut self, pool: &SqlitePool) -> Result<()> {
+        let account: Option<(String, String, String)> =
+            sqlx::query_as("SELECT balance, initial_balance, realized_pnl FROM sim_account WHERE id = 1")
+                .fetch_optional(pool)
+                .await?;
+        let Some((balance, initial_balance, realized_pnl)) = account else {
+            return Ok(());
+        };
+
+        let positions: Vec<(String, String, String, String)> =
+            sqlx::query_as("SELECT token_id, size, avg_price, mark_price FROM sim_positions")
+                .fetch_all(pool)
+                .await?;
+        let orders: Vec<(String, String, String, String, String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT order_id, token_id, side, limit_price, fill_price, size, timestamp
+            FROM sim_orders
+            ORDER BY timestamp ASC, CAST(SUBSTR(order_id, 5) AS INTEGER) ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        let benchmark: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT token_id, entry_price, notional FROM sim_benchmark")
+                .fetch_all(pool)
+                .await?;
+
+        let state = self.state.get_mut();
+        state.balance = parse_decimal(&balance)?;
+        state.initial_balance = parse_decimal(&initial_balance)?;
+        state.realized_pnl = parse_decimal(&realized_pnl)?;
+        for (token_id, size, avg_price, mark_price) in positions {
+            state.positions.insert(
+                token_id.clone(),
+                SimPosition {
+                    token_id,
+                    size: parse_decimal(&size)?,
+                    avg_price: parse_decimal(&avg_price)?,
+                    mark_price: parse_decimal(&mark_price)?,
+                },
+            );
+        }
+        for (order_id, token_id, side, limit_price, fill_price, size, timestamp) in orders {
+            state.orders.push(SimOrder {
+                order_id,
+                token_id,
+                side: if side == "Buy" { Side::Buy } else { Side::Sell },
+                limit_price: parse_decimal(&limit_price)?,
+                fill_price: parse_decimal(&fill_price)?,
+                size: parse_decimal(&size)?,
+                timestamp: timestamp
+                    .parse()
+                    .map_err(|e| BotError::Internal(format!("Bad simulation timestamp: {}", e)))?,
+            });
+        }
+        for (token_id, entry_price, notional) in benchmark {
+            state
+                .benchmark
+                .insert(token_id, (parse_decimal(&entry_price)?, parse_decimal(&notional)?));
+        }
+
+        Ok(())
+    }
+
+    async fn persist_account(&self) -> Result<()> {
+        let Some(pool) = &self.pool else { return Ok(()) };
+        let (balance, initial_balance, realized_pnl) = {
+            let state = self.state.lock();
+            (state.balance, state.initial_balance, state.realized_pnl)
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO sim_account (id, balance, initial_balance, realized_pnl)
+            VALUES (1, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                balance = excluded.balance,
+                realized_pnl = excluded.realized_pnl
+            "#,
+        )
+        .bind(balance.to_string())
+        .bind(initial_balance.to_string())
+        .bind(realized_pnl.to_string())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn persist_position(&self, position: &SimPosition) -> Result<()> {
+        let Some(pool) = &self.pool else { return Ok(()) };
+
+        if position.size.is_zero() {
+            sqlx::query("DELETE FROM sim_positions WHERE token_id = ?")
+                .bind(&position.token_id)
+                .execute(pool)
+                .await?;
+        } else {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO sim_positions (token_id, size, avg_price, mark_price)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&position.token_id)
+            .bind(position.size.to_string())
+            .bind(position.avg_price.to_string())
+            .bind(position.mark_price.to_string())
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn persist_fill(&self, fill: &SimOrder, position: &SimPosition) -> Result<()> {
+        let Some(pool) = &self.pool else { return Ok(()) };
+
+        sqlx::query(
+            r#"
+            INSERT INTO sim_orders (order_id, token_id, side, limit_price, fill_price, size, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&fill.order_id)
+        .bind(&fill.token_id)
+        .bind(format!("{:?}", fill.side))
+        .bind(fill.limit_price.to_string())
+        .bind(fill.fill_price.to_string())
+        .bind(fill.size.to_string())
+        .bind(fill.timestamp.to_rfc3339())
+        .execute(pool)
+        .await?;
+
+        if fill.side == Side::Buy {
+            let notional = fill.fill_price * fill.size;
+            sqlx::query("INSERT OR IGNORE INTO sim_benchmark (token_id, entry_price, notional) VALUES (?, ?, ?)")
+                .bind(&fill.token_id)
+                .bind(fill.fill_price.to_string())
+                .bind(notional.to_string())
+                .execute(pool)
+                .await?;
+        }
+
+        self.persist_position(position).await?;
+        self.persist_account().await
+    }
+}
+
+fn parse_decimal(s: &str) -> Result<Decimal> {
+    s.parse()
+        .map_err(|e| BotError::Internal(format!("Bad simulation value '{}': {}", s, e)))
+}
+
+#[async_trait]
+impl ClobClientTrait for SimulatedClobClient {
+    async fn get_balance(&self) -> Result<Decimal> {
+        Ok(self.state.lock().balance)
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        if let Some(source) = &self.books {
+            return source.get_order_book(token_id).await;
+        }
+
+        let mark = self
+            .state
+            .lock()
+            .marks
+            .get(token_id)
+            .copied()
+            .ok_or_else(|| BotError::Execution(format!("No simulated price for {}", token_id)))?;
+        let level = || vec![OrderBookLevel { price: mark, size: Decimal::MAX }];
+        Ok(OrderBook { bids: level(), asks: level() })
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        let (fill, position) = self.apply_fill(order)?;
+        self.persist_fill(&fill, &position).await?;
+
+        tracing::info!(
+            "📝 SIMULATED: {:?} {:.2} shares of {} @ {:.4} (limit {:.4})",
+            fill.side,
+            fill.size,
+            fill.token_id,
+            fill.fill_price,
+            fill.limit_price
+        );
+
+        Ok(OrderStatus {
+            order_id: fill.order_id,
+            status: "FILLED".to_string(),
+            filled_size: fill.size,
+            remaining_size: Decimal::ZERO,
+            avg_price: Some(fill.fill_price),
+        })
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> Result<()> {
+        // Orders fill on placement, so nothing is ever resting
+        Ok(())
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        let state = self.state.lock();
+        Ok(state
+            .positions
+            .values()
+            .map(|p| Position {
+                token_id: p.token_id.clone(),
+                market_id: String::new(),
+                side: Side::Buy,
+                size: p.size,
+                avg_entry_price: p.avg_price,
+                current_price: p.mark_price,
+                unrealized_pnl: p.unrealized_pnl(),
+            })
+            .collect())
+    }
+
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        Ok(self.position(token_id).map(|p| p.size).unwrap_or(Decimal::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OrderType;
+    use rust_decimal_macros::dec;
+
+    fn order(token_id: &str, side: Side, price: Decimal, size: Decimal) -> Order {
+        Order {
+            token_id: token_id.to_string(),
+            side,
+            price,
+            size,
+            order_type: OrderType::GTC,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fills_with_slippage() {
+        let sim = SimulatedClobClient::new(dec!(1000), dec!(50));
+
+        let status = sim.place_order(&order("yes", Side::Buy, dec!(0.40), dec!(100))).await.unwrap();
+        assert_eq!(status.status, "FILLED");
+        // 50 bps worse than the limit
+        assert_eq!(status.avg_price, Some(dec!(0.402)));
+        assert_eq!(sim.get_balance().await.unwrap(), dec!(959.8));
+
+        let status = sim.place_order(&order("yes", Side::Sell, dec!(0.50), dec!(40))).await.unwrap();
+        assert_eq!(status.avg_price, Some(dec!(0.4975)));
+        assert_eq!(sim.get_token_holdings("yes").await.unwrap(), dec!(60));
+
+        let stats = sim.get_simulation_stats();
+        assert_eq!(stats.trades, 2);
+        assert_eq!(stats.realized_pnl, dec!(3.82));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_overspend_and_oversell() {
+        let sim = SimulatedClobClient::new(dec!(10), dec!(0));
+        assert!(sim.place_order(&order("yes", Side::Buy, dec!(0.5), dec!(100))).await.is_err());
+        assert!(sim.place_order(&order("yes", Side::Sell, dec!(0.5), dec!(1))).await.is_err());
+        assert_eq!(sim.get_simulation_stats().trades, 0);
+    }
+
+    #[tokio::test]
+    async fn test_mark_to_market_and_buy_and_hold() {
+        let sim = SimulatedClobClient::new(dec!(1000), dec!(0));
+        sim.place_order(&order("a", Side::Buy, dec!(0.50), dec!(200))).await.unwrap();
+        // Sold half at entry: the trading left money on the table versus holding
+        sim.place_order(&order("a", Side::Sell, dec!(0.50), dec!(100))).await.unwrap();
+
+        sim.advance_time(&HashMap::from([("a".to_string(), dec!(0.60))])).await.unwrap();
+
+        let stats = sim.get_simulation_stats();
+        assert_eq!(stats.unrealized_pnl, dec!(10));
+        assert_eq!(stats.equity, dec!(1010));
+        assert_eq!(stats.total_return, dec!(0.01));
+        // Holding the original 200 shares would have made $20
+        assert_eq!(stats.buy_and_hold_return, dec!(0.02));
+        assert_eq!(stats.open_positions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_quotes_marked_price_without_book_source() {
+        let sim = SimulatedClobClient::new(dec!(1000), dec!(0));
+        assert!(sim.get_order_book("a").await.is_err());
+
+        sim.advance_time(&HashMap::from([("a".to_string(), dec!(0.3))])).await.unwrap();
+        let book = sim.get_order_book("a").await.unwrap();
+        assert_eq!(book.best_ask(), Some(dec!(0.3)));
+        assert_eq!(book.best_bid(), Some(dec!(0.3)));
+    }
+
+    #[tokio::test]
+    async fn test_state_persists_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("simulation.db");
+
+        {
+            let sim = SimulatedClobClient::open(&path, dec!(500), dec!(100)).await.unwrap();
+            sim.place_order(&order("a", Side::Buy, dec!(0.50), dec!(100))).await.unwrap();
+            sim.advance_time(&HashMap::from([("a".to_string(), dec!(0.55))])).await.unwrap();
+        }
+
+        // Initial balance only seeds a fresh file
+        let sim = SimulatedClobClient::open(&path, dec!(9999), dec!(100)).await.unwrap();
+        let stats = sim.get_simulation_stats();
+        assert_eq!(stats.initial_balance, dec!(500));
+        assert_eq!(stats.balance, dec!(449.5));
+        assert_eq!(stats.trades, 1);
+        let position = sim.position("a").unwrap();
+        assert_eq!(position.avg_price, dec!(0.505));
+        assert_eq!(position.mark_price, dec!(0.55));
+
+        let status = sim.place_order(&order("a", Side::Sell, dec!(0.55), dec!(100))).await.unwrap();
+        assert_eq!(status.order_id, "sim_2");
+        assert!(sim.position("a").is_none());
+    }
+}
@@ -8,7 +8,7 @@
 
 use crate::client::clob::{ClobClient, OrderBook};
 use crate::error::{BotError, Result};
-use crate::types::{Order, OrderStatus, OrderType, Side, Trade};
+use crate::types::{Order, OrderStatus, OrderType, Side, Trade, TradeStatus};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::time::Duration;
@@ -286,6 +286,12 @@ impl SmartExecutor {
                 size: filled_status.filled_size,
                 fee: Decimal::ZERO,
                 timestamp: chrono::Utc::now(),
+                status: TradeStatus::Filled,
+                strategy_name: None,
+                account_id: None,
+                trace_id: Default::default(),
+                tags: Vec::new(),
+                edge: None,
             };
 
             Ok(ExecutionResult {
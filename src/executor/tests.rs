@@ -56,6 +56,13 @@ mod tests {
             confidence: dec!(0.80),
             suggested_size: dec!(0.05), // 5% of portfolio
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         let portfolio_value = dec!(1000);
@@ -161,6 +168,13 @@ mod tests {
             confidence: dec!(0.85),
             suggested_size: dec!(0.03),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         assert!(signal.edge > Decimal::ZERO);
@@ -178,6 +192,13 @@ mod tests {
             confidence: dec!(0.80),
             suggested_size: dec!(0.02),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         assert!(signal.edge < Decimal::ZERO);
@@ -198,4 +219,886 @@ mod tests {
         let current_positions = 10;
         assert!(current_positions >= config.max_open_positions);
     }
+
+    // ========== Order Type Selection ==========
+
+    fn buy_signal(suggested_size: Decimal) -> Signal {
+        Signal {
+            market_id: "m1".to_string(),
+            token_id: "t1".to_string(),
+            side: Side::Buy,
+            model_probability: dec!(0.70),
+            market_probability: dec!(0.55),
+            edge: dec!(0.15),
+            confidence: dec!(0.80),
+            suggested_size,
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    /// Exchange quoting 0.54/0.56 with 3500 shares on each side for every token
+    fn scenario() -> crate::testing::Scenario {
+        crate::testing::Scenario::new().balance(dec!(100000)).default_book(
+            &[(dec!(0.54), dec!(500)), (dec!(0.53), dec!(1000)), (dec!(0.52), dec!(2000))],
+            &[(dec!(0.56), dec!(500)), (dec!(0.57), dec!(1000)), (dec!(0.58), dec!(2000))],
+        )
+    }
+
+    fn matching_executor() -> crate::executor::Executor<crate::testing::MockClob> {
+        crate::executor::Executor::new(scenario().build(), RiskConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_executed_signal_is_not_repeated_within_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = std::sync::Arc::new(crate::storage::Database::connect(dir.path().join("test.db")).await.unwrap());
+        let signal = buy_signal(dec!(0.05));
+
+        let executor = matching_executor().with_executed_signals(db.clone());
+        assert_eq!(executor.execute(&signal, dec!(1000)).await.unwrap().len(), 1);
+
+        // A fresh executor (as after a restart) sees the persisted fingerprint
+        let restarted = matching_executor().with_executed_signals(db.clone());
+        let rescanned = Signal { edge: dec!(0.1512), ..signal.clone() };
+        assert!(restarted.execute(&rescanned, dec!(1000)).await.unwrap().is_empty());
+        assert!(restarted.clob.fills().is_empty());
+
+        // A different side is a different signal
+        let other = Signal { side: Side::Sell, token_id: "t2".to_string(), ..signal.clone() };
+        assert_ne!(
+            crate::executor::signal_fingerprint(&other, 900),
+            crate::executor::signal_fingerprint(&signal, 900)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_executed_signal_is_allowed_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = std::sync::Arc::new(crate::storage::Database::connect(dir.path().join("test.db")).await.unwrap());
+        let signal = buy_signal(dec!(0.05));
+        let fingerprint = crate::executor::signal_fingerprint(&signal, RiskConfig::default().signal_dedup_ttl_secs);
+
+        let executed_at = Utc::now() - chrono::Duration::hours(1);
+        db.record_executed_signal(&fingerprint, executed_at, executed_at + chrono::Duration::minutes(15))
+            .await
+            .unwrap();
+        assert!(!db.is_signal_executed(&fingerprint, Utc::now()).await.unwrap());
+
+        let executor = matching_executor().with_executed_signals(db.clone());
+        assert_eq!(executor.execute(&signal, dec!(1000)).await.unwrap().len(), 1);
+        assert!(db.is_signal_executed(&fingerprint, Utc::now()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_returns_resting_order_id() {
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::types::TradeStatus;
+
+        let executor = matching_executor();
+        let trade = executor
+            .execute_with_type(&buy_signal(dec!(0.05)), dec!(1000), ExecOrderType::Limit { price: dec!(0.50) })
+            .await
+            .unwrap()
+            .pop()
+            .expect("limit order should be accepted");
+
+        assert_eq!(trade.status, TradeStatus::Resting);
+        assert_eq!(trade.price, dec!(0.50));
+        assert!(!trade.order_id.is_empty());
+        // Resting orders don't open a position until they fill
+        assert!(executor.get_positions().await.is_empty());
+
+        let open = crate::client::mock::ClobClientTrait::get_open_orders(&executor.clob).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, trade.order_id);
+    }
+
+    #[tokio::test]
+    async fn test_fok_that_cannot_fill_returns_without_position() {
+        use crate::executor::OrderType as ExecOrderType;
+
+        let executor = matching_executor();
+        // 4000 USDC at 0.55 is far more than the 3500 shares offered
+        let result = executor
+            .execute_with_type(&buy_signal(dec!(0.40)), dec!(10000), ExecOrderType::FillOrKill)
+            .await
+            .unwrap();
+
+        assert!(result.is_empty());
+        assert!(executor.get_positions().await.is_empty());
+    }
+
+    /// Keeps every event it is sent
+    #[derive(Default)]
+    struct CapturingSink(parking_lot::Mutex<Vec<crate::events::BotEvent>>);
+
+    #[async_trait::async_trait]
+    impl crate::events::EventSink for CapturingSink {
+        async fn emit(&self, event: crate::events::BotEvent) {
+            self.0.lock().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_emits_order_filled_event() {
+        use crate::events::BotEvent;
+
+        let sink = std::sync::Arc::new(CapturingSink::default());
+        let executor = matching_executor().with_account("main").with_event_sink(sink.clone());
+        let signal = Signal { strategy_name: Some("value".to_string()), ..buy_signal(dec!(0.05)) };
+        let trade = executor.execute(&signal, dec!(1000)).await.unwrap().pop().unwrap();
+
+        let events = sink.0.lock().clone();
+        assert_eq!(
+            events,
+            [BotEvent::OrderFilled {
+                order_id: trade.order_id.clone(),
+                market_id: "m1".to_string(),
+                token_id: "t1".to_string(),
+                side: Side::Buy,
+                price: dec!(0.56),
+                size: trade.size,
+                fee: trade.fee,
+                strategy: Some("value".to_string()),
+                account_id: Some("main".to_string()),
+                trace_id: signal.trace_id,
+                timestamp: trade.timestamp,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_killed_orders_and_risk_blocks_emit_events() {
+        use crate::events::BotEvent;
+        use crate::executor::OrderType as ExecOrderType;
+
+        let sink = std::sync::Arc::new(CapturingSink::default());
+        let executor = matching_executor().with_event_sink(sink.clone());
+
+        // More than the book holds: the FOK is killed
+        executor
+            .execute_with_type(&buy_signal(dec!(0.40)), dec!(10000), ExecOrderType::FillOrKill)
+            .await
+            .unwrap();
+        executor.update_pnl(dec!(-500)).await;
+        assert!(executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.is_err());
+
+        let events = sink.0.lock().clone();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], BotEvent::OrderRejected { token_id, .. } if token_id == "t1"));
+        assert!(matches!(&events[1], BotEvent::RiskBlocked { reason, .. } if reason.contains("Daily loss limit")));
+    }
+
+    #[tokio::test]
+    async fn test_market_order_fills_and_tracks_position() {
+        use crate::types::TradeStatus;
+
+        let executor = matching_executor();
+        let trade = executor
+            .execute(&buy_signal(dec!(0.05)), dec!(1000))
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(trade.status, TradeStatus::Filled);
+        assert_eq!(trade.price, dec!(0.56)); // best ask
+        assert_eq!(executor.get_positions().await.get("t1"), Some(&trade.size));
+    }
+
+    #[tokio::test]
+    async fn test_trades_carry_signal_trace_id() {
+        let signal = Signal { trace_id: crate::types::TraceId::new(), ..buy_signal(dec!(0.05)) };
+        let trades = matching_executor().execute(&signal, dec!(1000)).await.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].trace_id, signal.trace_id);
+    }
+
+    #[tokio::test]
+    async fn test_fills_are_charged_taker_and_resting_orders_maker_fees() {
+        use crate::config::FeeSchedule;
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::fees::PolymarketFees;
+
+        let fees = std::sync::Arc::new(PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: dec!(200),
+            maker_fee_bps: dec!(-50),
+        }));
+        let executor = matching_executor().with_fees(fees);
+
+        let taken = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap().pop().unwrap();
+        assert_eq!(taken.fee, dec!(0.02) * dec!(0.44) * taken.size);
+
+        let rested = executor
+            .execute_with_type(&buy_signal(dec!(0.05)), dec!(1000), ExecOrderType::Limit { price: dec!(0.50) })
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(rested.fee, dec!(-0.005) * dec!(0.50) * rested.size);
+    }
+
+    #[tokio::test]
+    async fn test_realized_pnl_subtracts_modeled_fees_and_gas() {
+        use crate::config::FeeSchedule;
+        use crate::fees::PolymarketFees;
+
+        let fees = PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: dec!(200),
+            maker_fee_bps: Decimal::ZERO,
+        })
+        .with_gas_per_order(dec!(0.05));
+        let executor = matching_executor().with_fees(std::sync::Arc::new(fees));
+
+        let bought = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap().pop().unwrap();
+        assert_eq!(bought.fee, dec!(0.02) * dec!(0.44) * bought.size + dec!(0.05));
+        let sold = executor.close_position_at_market("m1", "t1", Vec::new()).await.unwrap().unwrap();
+        assert_eq!(sold.fee, dec!(0.02) * dec!(0.46) * sold.size + dec!(0.05));
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = crate::storage::Database::connect(dir.path().join("test.db")).await.unwrap();
+        db.save_trade(&bought).await.unwrap();
+        db.save_trade(&sold).await.unwrap();
+        let pnl = db.get_pnl_by_strategy().await.unwrap().pop().unwrap();
+        assert_eq!(pnl.fees, bought.fee + sold.fee);
+        assert_eq!(pnl.realized_pnl, (sold.price - bought.price) * sold.size - bought.fee - sold.fee);
+    }
+
+    // ========== Two-Phase Execution ==========
+
+    fn two_phase_executor(max_acceptable_slippage_bps: Decimal) -> crate::executor::Executor<crate::testing::MockClob> {
+        matching_executor().with_two_phase(crate::config::TwoPhaseConfig {
+            max_acceptable_slippage_bps,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_large_order_proceeds_after_acceptable_probe() {
+        let executor = two_phase_executor(dec!(200));
+        let trade = executor.execute_two_phase(&buy_signal(dec!(0.04)), dec!(30000)).await.unwrap().unwrap();
+
+        // The 0.56 probe is ~182bps over the 0.55 signal price, within limit,
+        // so the remainder follows at the touch; what it can't take there rests
+        let fills = executor.clob.fills();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].size * dec!(10), trade.size);
+        assert_eq!(fills[0].size + fills[1].size, dec!(500));
+        assert_eq!(trade.price, dec!(0.56));
+        assert_eq!(trade.status, crate::types::TradeStatus::Resting);
+        assert_eq!(executor.probe_cancellations_total(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_large_order_abandoned_when_probe_slips() {
+        let executor = two_phase_executor(dec!(100));
+        let probe = executor.execute_two_phase(&buy_signal(dec!(0.04)), dec!(30000)).await.unwrap().unwrap();
+
+        assert_eq!(executor.clob.fills().len(), 1);
+        assert_eq!(probe.price, dec!(0.56));
+        assert_eq!(executor.probe_cancellations_total(), 1);
+        assert_eq!(executor.probe_slippage_cost_usdc().await, dec!(0.01) * probe.size);
+    }
+
+    #[tokio::test]
+    async fn test_small_order_skips_probe() {
+        let executor = two_phase_executor(dec!(100));
+        let trade = executor.execute_two_phase(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap().unwrap();
+
+        assert_eq!(executor.clob.fills().len(), 1);
+        assert_eq!(executor.clob.fills()[0].size, trade.size);
+        assert_eq!(executor.probe_cancellations_total(), 0);
+    }
+
+    // ========== Market Profiles ==========
+
+    fn profiled_executor(median_spread: Decimal, median_top_depth: Decimal) -> crate::executor::Executor<crate::testing::MockClob> {
+        use crate::analysis::market_profile::{MarketProfile, MarketProfiles};
+
+        let profiles = MarketProfiles::new(crate::config::MarketProfileConfig {
+            maker_wait_secs: 0,
+            ..Default::default()
+        });
+        profiles.set([MarketProfile {
+            market_id: "m1".to_string(),
+            median_spread,
+            median_top_depth,
+            quote_updates_per_min: dec!(2),
+            median_fill_latency_secs: None,
+            mm_activity: None,
+            samples: 100,
+            updated_at: Utc::now(),
+        }]);
+        matching_executor().with_market_profiles(std::sync::Arc::new(profiles))
+    }
+
+    #[tokio::test]
+    async fn test_profile_slices_orders_by_top_of_book_depth() {
+        use crate::types::TradeStatus;
+
+        // ~90.9 shares in slices of half the usual 60-share top of book
+        let executor = profiled_executor(dec!(0.02), dec!(60));
+        let trades = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap();
+        assert_eq!(trades.len(), 1);
+        let fills = executor.clob.fills();
+        assert_eq!(fills.iter().map(|f| f.size.round()).collect::<Vec<_>>(), [dec!(30), dec!(30), dec!(30), dec!(1)]);
+        assert_eq!(trades[0].size, fills.iter().map(|f| f.size).sum::<Decimal>());
+        assert_eq!((trades[0].price, trades[0].status), (dec!(0.56), TradeStatus::Filled));
+
+        // Markets without a profile go in one order
+        let trades = executor.execute(&Signal { market_id: "m2".to_string(), ..buy_signal(dec!(0.05)) }, dec!(1000)).await.unwrap();
+        assert_eq!(executor.clob.fills().len(), 5);
+        assert_eq!(executor.clob.fills()[4].size, trades[0].size);
+    }
+
+    #[tokio::test]
+    async fn test_wide_spread_profile_joins_the_bid_before_taking() {
+        use crate::types::TradeStatus;
+
+        let executor = profiled_executor(dec!(0.06), dec!(1000));
+        let trades = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap();
+
+        // The maker order at the 0.54 bid didn't fill in time and was pulled
+        let maker = executor.clob.order("mock_1").unwrap();
+        assert_eq!(maker.status, "CANCELLED");
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].price, trades[0].status), (dec!(0.56), TradeStatus::Filled));
+        assert_eq!(executor.clob.fills().len(), 1);
+    }
+
+    fn sizing_executor(sizing: crate::config::OrderSizingConfig) -> crate::executor::Executor<crate::testing::MockClob> {
+        matching_executor().with_sizing(sizing)
+    }
+
+    fn yes_signal(suggested_size: Decimal) -> Signal {
+        Signal { token_id: "yes".to_string(), ..buy_signal(suggested_size) }
+    }
+
+    #[tokio::test]
+    async fn test_order_rounded_to_market_constraints() {
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::types::OrderConstraints;
+
+        let executor = sizing_executor(crate::config::OrderSizingConfig::default());
+        let market = crate::types::Market {
+            order_constraints: Some(OrderConstraints { tick_size: dec!(0.01), min_order_size: dec!(5) }),
+            ..binary_market()
+        };
+        executor.register_market(&market).await;
+
+        // $50 at 0.55 is 90.9090... shares; the bid is floored to the tick
+        let trade = executor
+            .execute_with_type(&yes_signal(dec!(0.05)), dec!(1000), ExecOrderType::Limit { price: dec!(0.505) })
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(trade.size, dec!(90.90));
+        assert_eq!(trade.price, dec!(0.50));
+    }
+
+    #[tokio::test]
+    async fn test_sub_minimum_order_is_skipped() {
+        use crate::error::BotError;
+        use crate::types::OrderConstraints;
+
+        let executor = sizing_executor(crate::config::OrderSizingConfig::default());
+        let err = executor.execute(&yes_signal(dec!(0.004)), dec!(1000)).await.unwrap_err();
+        assert!(matches!(err, BotError::OrderRejected(_)), "{}", err);
+
+        // $20 clears the notional minimum but not the market's 100 shares
+        let market = crate::types::Market {
+            order_constraints: Some(OrderConstraints { tick_size: dec!(0.01), min_order_size: dec!(100) }),
+            ..binary_market()
+        };
+        executor.register_market(&market).await;
+        let err = executor.execute(&yes_signal(dec!(0.02)), dec!(1000)).await.unwrap_err();
+        assert!(matches!(err, BotError::OrderRejected(_)), "{}", err);
+        assert!(executor.clob.fills().is_empty());
+        assert_eq!(executor.pending_open_size("m1").await, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_sub_minimum_orders_accumulate_until_minimum() {
+        use crate::config::{OrderSizingConfig, SubMinimumPolicy};
+
+        let executor = sizing_executor(OrderSizingConfig {
+            sub_minimum: SubMinimumPolicy::Accumulate,
+            ..OrderSizingConfig::default()
+        });
+
+        // $2 a signal against a $5 minimum: the third one crosses it
+        assert!(executor.execute(&yes_signal(dec!(0.002)), dec!(1000)).await.unwrap().is_empty());
+        assert_eq!(executor.pending_open_size("m1").await, dec!(3.63));
+        assert!(executor.execute(&yes_signal(dec!(0.002)), dec!(1000)).await.unwrap().is_empty());
+        assert_eq!(executor.pending_open_size("m1").await, dec!(7.26));
+
+        let trades = executor.execute(&yes_signal(dec!(0.002)), dec!(1000)).await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].size, dec!(10.89));
+        assert_eq!(executor.pending_open_size("m1").await, Decimal::ZERO);
+
+        // Accumulation on the other outcome is dropped when the signal flips
+        executor.execute(&yes_signal(dec!(0.002)), dec!(1000)).await.unwrap();
+        let no = Signal { token_id: "no".to_string(), ..buy_signal(dec!(0.002)) };
+        assert!(executor.execute(&no, dec!(1000)).await.unwrap().is_empty());
+        assert_eq!(executor.pending_open_size("m1").await, dec!(3.63));
+    }
+
+    #[tokio::test]
+    async fn test_order_capped_at_book_depth_fraction() {
+        let executor = sizing_executor(crate::config::OrderSizingConfig::default());
+
+        // 9090 shares wanted against 3500 on the asks: half of them are taken
+        let trades = executor.execute(&yes_signal(dec!(0.05)), dec!(100000)).await.unwrap();
+        assert_eq!(trades[0].size, dec!(1750));
+    }
+
+    #[tokio::test]
+    async fn test_good_til_time_joins_near_touch() {
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::types::TradeStatus;
+
+        let executor = matching_executor();
+        let expiry = Utc::now() + chrono::Duration::hours(1);
+        let trade = executor
+            .execute_with_type(&buy_signal(dec!(0.05)), dec!(1000), ExecOrderType::GoodTilTime { expiry })
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(trade.status, TradeStatus::Resting);
+        assert_eq!(trade.price, dec!(0.54)); // best bid
+    }
+
+    #[tokio::test]
+    async fn test_expired_signal_is_never_submitted() {
+        let executor = matching_executor();
+        let signal = Signal {
+            valid_until: Some(Utc::now() - chrono::Duration::seconds(1)),
+            ..buy_signal(dec!(0.05))
+        };
+
+        assert!(executor.execute(&signal, dec!(1000)).await.unwrap().is_empty());
+        assert!(executor.execute_two_phase(&signal, dec!(1000)).await.unwrap().is_none());
+        assert!(executor.clob.order("mock_1").is_none());
+        assert_eq!(executor.expired_signals_total(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resting_orders_are_sent_gtd_until_signal_expiry() {
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::types::TradeStatus;
+
+        let executor = matching_executor();
+        let valid_until = Utc::now() + chrono::Duration::minutes(10);
+        let signal = Signal { valid_until: Some(valid_until), ..buy_signal(dec!(0.05)) };
+
+        let limit = ExecOrderType::Limit { price: dec!(0.50) };
+        let trade = executor.execute_with_type(&signal, dec!(1000), limit).await.unwrap().pop().unwrap();
+        assert_eq!(trade.status, TradeStatus::Resting);
+        assert_eq!(executor.clob.expiration(&trade.order_id), Some(valid_until.timestamp()));
+
+        // A later order expiry is capped at the signal's
+        let later = ExecOrderType::GoodTilTime { expiry: valid_until + chrono::Duration::hours(1) };
+        let trade = executor.execute_with_type(&signal, dec!(1000), later).await.unwrap().pop().unwrap();
+        assert_eq!(executor.clob.expiration(&trade.order_id), Some(valid_until.timestamp()));
+
+        // Signals without an expiry rest GTC
+        let trade = executor.execute_with_type(&buy_signal(dec!(0.05)), dec!(1000), limit).await.unwrap().pop().unwrap();
+        assert_eq!(executor.clob.expiration(&trade.order_id), None);
+        assert_eq!(executor.cancel_expired_orders().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_orders_cancelled_at_signal_expiry_without_gtd() {
+        use crate::executor::OrderType as ExecOrderType;
+
+        let executor = crate::executor::Executor::new(scenario().without_gtd().build(), RiskConfig::default());
+        let signal = Signal {
+            valid_until: Some(Utc::now() + chrono::Duration::milliseconds(200)),
+            ..buy_signal(dec!(0.05))
+        };
+        let trade = executor
+            .execute_with_type(&signal, dec!(1000), ExecOrderType::Limit { price: dec!(0.50) })
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(executor.clob.expiration(&trade.order_id), None);
+        assert_eq!(executor.cancel_expired_orders().await.unwrap(), 0);
+
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert_eq!(executor.cancel_expired_orders().await.unwrap(), 1);
+        assert_eq!(executor.clob.order(&trade.order_id).unwrap().status, "CANCELLED");
+        assert_eq!(executor.expired_signals_total(), 1);
+        // Cancelled orders are forgotten
+        assert_eq!(executor.cancel_expired_orders().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_partially_fills_over_time() {
+        use crate::client::mock::ClobClientTrait;
+        use crate::executor::OrderType as ExecOrderType;
+        use crate::testing::OrderScript;
+        use crate::types::TradeStatus;
+
+        // Order rests for 2 ticks, fills 40%, then the book moves away
+        let clob = scenario()
+            .next_order(OrderScript::new().rests_for(2).fills(dec!(0.4)))
+            .book_at(3, "t1", &[(dec!(0.60), dec!(500))], &[(dec!(0.62), dec!(500))])
+            .build();
+        let executor = crate::executor::Executor::new(clob, RiskConfig::default());
+
+        let trade = executor
+            .execute_with_type(&buy_signal(dec!(0.05)), dec!(1000), ExecOrderType::Limit { price: dec!(0.50) })
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(trade.status, TradeStatus::Resting);
+
+        executor.clob.advance(2);
+        let status = executor.clob.order(&trade.order_id).unwrap();
+        assert_eq!(status.filled_size, trade.size * dec!(0.4));
+        assert_eq!(status.status, "OPEN");
+
+        executor.clob.advance(5);
+        let open = executor.clob.get_open_orders().await.unwrap();
+        assert_eq!(open[0].remaining_size, trade.size * dec!(0.6));
+    }
+
+    #[test]
+    fn test_unknown_order_status_is_not_a_fill() {
+        use crate::executor::classify_order_status;
+        use crate::types::{OrderStatus, TradeStatus};
+
+        let status = |s: &str| OrderStatus {
+            order_id: "o1".to_string(),
+            status: s.to_string(),
+            filled_size: dec!(0),
+            remaining_size: dec!(10),
+            avg_price: None,
+        };
+        assert_eq!(classify_order_status(&status("matched")), Some(TradeStatus::Filled));
+        assert_eq!(classify_order_status(&status("FILLED")), Some(TradeStatus::Filled));
+        assert_eq!(classify_order_status(&status("live")), Some(TradeStatus::Resting));
+        assert_eq!(classify_order_status(&status("EXPIRED")), None);
+        assert_eq!(classify_order_status(&status("ORDER_STATUS_INVALID")), None);
+        assert_eq!(classify_order_status(&status("")), None);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rejection_surfaces_as_error() {
+        use crate::testing::Rejection;
+
+        let clob = scenario().reject_next(Rejection::InsufficientBalance).build();
+        let executor = crate::executor::Executor::new(clob, RiskConfig::default());
+
+        let result = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await;
+        assert!(matches!(result, Err(crate::error::BotError::InsufficientBalance { .. })));
+        assert!(executor.get_positions().await.is_empty());
+    }
+
+    // ========== Inventory Netting ==========
+
+    use crate::executor::inventory::{plan_order, Holdings, PlannedLeg};
+
+    fn leg(token_id: &str, side: Side, size: Decimal, on_complement: bool) -> PlannedLeg {
+        PlannedLeg { token_id: token_id.to_string(), side, size, on_complement }
+    }
+
+    fn held(token: Decimal, complement: Decimal) -> Holdings {
+        Holdings { token, complement }
+    }
+
+    #[test]
+    fn test_plan_flip_sells_yes_then_buys_no() {
+        // Hold 60 YES, model wants 100 shares of bearish exposure
+        let plan = plan_order("yes", Some("no"), Side::Sell, dec!(100), held(dec!(60), dec!(0)));
+        assert_eq!(plan.reduce, Some(leg("yes", Side::Sell, dec!(60), false)));
+        assert_eq!(plan.open, Some(leg("no", Side::Buy, dec!(40), true)));
+    }
+
+    #[test]
+    fn test_plan_partial_reduction_opens_nothing() {
+        let plan = plan_order("yes", Some("no"), Side::Sell, dec!(30), held(dec!(60), dec!(0)));
+        assert_eq!(plan.reduce, Some(leg("yes", Side::Sell, dec!(30), false)));
+        assert_eq!(plan.open, None);
+    }
+
+    #[test]
+    fn test_plan_bullish_sells_held_no_first() {
+        let plan = plan_order("yes", Some("no"), Side::Buy, dec!(50), held(dec!(0), dec!(20)));
+        assert_eq!(plan.reduce, Some(leg("no", Side::Sell, dec!(20), true)));
+        assert_eq!(plan.open, Some(leg("yes", Side::Buy, dec!(30), false)));
+    }
+
+    #[test]
+    fn test_plan_flat_opens_full_size() {
+        let buy = plan_order("yes", Some("no"), Side::Buy, dec!(50), Holdings::default());
+        assert_eq!(buy.reduce, None);
+        assert_eq!(buy.open, Some(leg("yes", Side::Buy, dec!(50), false)));
+
+        // Flat and bearish: buy NO rather than shorting YES
+        let sell = plan_order("yes", Some("no"), Side::Sell, dec!(50), Holdings::default());
+        assert_eq!(sell.reduce, None);
+        assert_eq!(sell.open, Some(leg("no", Side::Buy, dec!(50), true)));
+    }
+
+    #[test]
+    fn test_plan_without_complement_keeps_selling_token() {
+        let plan = plan_order("yes", None, Side::Sell, dec!(100), held(dec!(60), dec!(0)));
+        assert_eq!(plan.reduce, Some(leg("yes", Side::Sell, dec!(60), false)));
+        assert_eq!(plan.open, Some(leg("yes", Side::Sell, dec!(40), false)));
+    }
+
+    #[test]
+    fn test_plan_zero_size_is_empty() {
+        let plan = plan_order("yes", Some("no"), Side::Buy, Decimal::ZERO, held(dec!(10), dec!(10)));
+        assert!(plan.is_empty());
+    }
+
+    fn binary_market() -> crate::types::Market {
+        use crate::types::{Market, Outcome};
+        Market {
+            id: "m1".to_string(),
+            question: "Will it happen?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(5000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: dec!(0.55) },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: dec!(0.45) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bearish_flip_sells_held_yes_before_buying_no() {
+        let clob = scenario().position("yes", dec!(50)).build();
+        let executor = crate::executor::Executor::new(clob, RiskConfig::default());
+        executor.register_market(&binary_market()).await;
+
+        let signal = Signal {
+            token_id: "yes".to_string(),
+            side: Side::Sell,
+            market_probability: dec!(0.50),
+            ..buy_signal(dec!(0.05))
+        };
+        // $50 at 0.50 = 100 shares of bearish exposure against 50 held YES
+        let trades = executor.execute(&signal, dec!(1000)).await.unwrap();
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!((trades[0].token_id.as_str(), trades[0].side, trades[0].size), ("yes", Side::Sell, dec!(50)));
+        assert_eq!((trades[1].token_id.as_str(), trades[1].side, trades[1].size), ("no", Side::Buy, dec!(50)));
+    }
+
+    #[tokio::test]
+    async fn test_reduce_only_skips_risk_limits() {
+        let clob = scenario().position("yes", dec!(500)).build();
+        let config = RiskConfig { max_open_positions: 0, ..RiskConfig::default() };
+        let executor = crate::executor::Executor::new(clob, config);
+        executor.register_market(&binary_market()).await;
+
+        let signal = Signal { token_id: "yes".to_string(), side: Side::Sell, ..buy_signal(dec!(0.05)) };
+        let trades = executor.execute(&signal, dec!(1000)).await.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].side, Side::Sell);
+        assert_eq!(trades[0].token_id, "yes");
+    }
+
+    #[tokio::test]
+    async fn test_total_kelly_cap_clips_simultaneous_positions() {
+        use crate::error::BotError;
+
+        let config = RiskConfig { max_total_kelly: dec!(0.12), ..RiskConfig::default() };
+        let executor = crate::executor::Executor::new(scenario().build(), config);
+        let max_sized = |token: &str| Signal {
+            market_id: format!("m-{}", token),
+            token_id: token.to_string(),
+            ..buy_signal(RiskConfig::default().max_position_pct)
+        };
+        let committed = || async { executor.committed_kelly().await.round_dp(6) };
+
+        // 5% each: the first two fit, the third is clipped to the 2% left
+        let trades = executor.execute(&max_sized("t1"), dec!(1000)).await.unwrap();
+        assert_eq!((trades[0].size * dec!(0.55)).round_dp(6), dec!(50));
+        executor.execute(&max_sized("t2"), dec!(1000)).await.unwrap();
+        assert_eq!(committed().await, dec!(0.10));
+
+        let trades = executor.execute(&max_sized("t3"), dec!(1000)).await.unwrap();
+        assert_eq!((trades[0].size * dec!(0.55)).round_dp(6), dec!(20));
+        assert_eq!(committed().await, dec!(0.12));
+
+        let err = executor.execute(&max_sized("t4"), dec!(1000)).await.unwrap_err();
+        assert!(matches!(err, BotError::RiskLimit(ref reason) if reason.contains("Total Kelly cap")), "{}", err);
+
+        // Closing a position frees its share of the budget
+        let close = Signal { side: Side::Sell, ..max_sized("t1") };
+        let trades = executor.execute(&close, dec!(1000)).await.unwrap();
+        assert_eq!(trades[0].side, Side::Sell);
+        assert!(!executor.get_positions().await.contains_key("t1"));
+        assert_eq!(committed().await, dec!(0.07));
+    }
+
+    fn neg_risk_market(id: &str) -> crate::types::Market {
+        crate::types::Market {
+            id: id.to_string(),
+            outcomes: vec![
+                crate::types::Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: dec!(0.55) },
+                crate::types::Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: dec!(0.45) },
+            ],
+            event_id: Some("e1".to_string()),
+            neg_risk: true,
+            ..binary_market()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_neg_risk_event_counts_as_one_position() {
+        let clob = scenario().build();
+        let config = RiskConfig { max_open_positions: 1, ..RiskConfig::default() };
+        let executor = crate::executor::Executor::new(clob, config);
+        executor.register_market(&neg_risk_market("a")).await;
+        executor.register_market(&neg_risk_market("b")).await;
+
+        let yes_on = |market_id: &str| Signal {
+            market_id: market_id.to_string(),
+            token_id: format!("{}-yes", market_id),
+            ..buy_signal(dec!(0.05))
+        };
+        executor.execute(&yes_on("a"), dec!(1000)).await.unwrap();
+        // Second market of the same event is not a new position
+        executor.execute(&yes_on("b"), dec!(1000)).await.unwrap();
+
+        // YES on every market of the event is fully redeemable
+        let view = executor.event_view("e1").await.unwrap();
+        assert_eq!(view.legs.len(), 2);
+        assert!(view.redeemable_value() > dec!(0));
+        assert_eq!(view.net_directional_exposure(), dec!(0));
+
+        // A standalone market still hits the limit
+        let other = Signal { market_id: "m9".to_string(), token_id: "m9-yes".to_string(), ..buy_signal(dec!(0.05)) };
+        assert!(executor.execute(&other, dec!(1000)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_executor_trades_through_simulated_client() {
+        use crate::client::mock::ClobClientTrait;
+        use crate::executor::SimulatedClobClient;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let sim = Arc::new(SimulatedClobClient::new(dec!(1000), dec!(100)));
+        sim.advance_time(&HashMap::from([("t1".to_string(), dec!(0.50))])).await.unwrap();
+        let clob: Arc<dyn ClobClientTrait> = sim.clone();
+        let executor = crate::executor::Executor::new(clob, RiskConfig::default());
+
+        let trades = executor.execute(&buy_signal(dec!(0.05)), dec!(1000)).await.unwrap();
+        assert_eq!(trades.len(), 1);
+
+        // Filled at the marked ask plus 100 bps of slippage
+        let position = sim.position("t1").unwrap();
+        assert_eq!(position.avg_price, dec!(0.505));
+        assert_eq!(position.size, trades[0].size);
+        assert_eq!(sim.get_balance().await.unwrap(), dec!(1000) - dec!(0.505) * trades[0].size);
+    }
+
+    // ========== Order Bundles ==========
+
+    fn bundle_executor(second_leg_ask: Decimal) -> crate::executor::Executor<crate::testing::MockClob> {
+        let clob = scenario()
+            .book("t2", &[(second_leg_ask - dec!(0.02), dec!(500))], &[(second_leg_ask, dec!(500))])
+            .build();
+        crate::executor::Executor::new(clob, RiskConfig::default())
+    }
+
+    fn bundle_legs() -> Vec<Signal> {
+        vec![
+            Signal { market_probability: dec!(0.56), ..buy_signal(dec!(0.05)) },
+            Signal {
+                market_id: "m2".to_string(),
+                token_id: "t2".to_string(),
+                market_probability: dec!(0.40),
+                ..buy_signal(dec!(0.05))
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_bundle_fills_every_leg() {
+        use crate::executor::{LegStatus, OrderBundle};
+
+        let executor = bundle_executor(dec!(0.40));
+        let result = executor
+            .execute_bundle(&OrderBundle::new(bundle_legs()), dec!(1000))
+            .await
+            .unwrap();
+
+        assert!(result.is_complete());
+        assert!(result.legs.iter().all(|l| l.status == LegStatus::Filled));
+        assert_eq!(executor.get_positions().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_unwinds_first_leg_when_second_fails() {
+        use crate::executor::{LegStatus, OrderBundle};
+
+        // The second leg's ask ran away from 0.40 to 0.45, past the 1c tolerance
+        let executor = bundle_executor(dec!(0.45));
+        let result = executor
+            .execute_bundle(&OrderBundle::new(bundle_legs()), dec!(1000))
+            .await
+            .unwrap();
+
+        assert!(!result.is_complete());
+        assert!(!result.has_naked_legs());
+        assert_eq!(result.legs[0].status, LegStatus::Unwound);
+        assert!(matches!(result.legs[1].status, LegStatus::Failed(_)));
+
+        // Bought at the ask, sold back at the bid
+        let fill = result.legs[0].fill.as_ref().unwrap();
+        let unwind = result.legs[0].unwind.as_ref().unwrap();
+        assert_eq!(fill.side, Side::Buy);
+        assert_eq!(unwind.side, Side::Sell);
+        assert_eq!(unwind.size, fill.size);
+        assert_eq!(unwind.price, dec!(0.54));
+        assert_eq!(result.trades().len(), 2);
+
+        // Flat again
+        assert!(executor.get_positions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bundle_skips_legs_after_failure() {
+        use crate::executor::{LegStatus, OrderBundle};
+
+        let mut legs = bundle_legs();
+        legs.swap(0, 1);
+        let executor = bundle_executor(dec!(0.45));
+        let result = executor.execute_bundle(&OrderBundle::new(legs), dec!(1000)).await.unwrap();
+
+        assert!(matches!(result.legs[0].status, LegStatus::Failed(_)));
+        assert_eq!(result.legs[1].status, LegStatus::Skipped);
+        assert!(result.trades().is_empty());
+        assert!(executor.get_positions().await.is_empty());
+    }
 }
@@ -0,0 +1,218 @@
+//! Trading fees
+//!
+//! Polymarket charges fees per share as `rate * min(price, 1 - price)`, so
+//! the fee shrinks towards the extremes: a share bought at 99c pays the same
+//! as one bought at 1c, a fiftieth of what a 50c share pays. Rates differ
+//! between takers and makers (a negative maker rate is a rebate) and from
+//! market to market.
+//!
+//! Every place that prices a trade (edge at signal time, the fee recorded
+//! on fills, and through it realized P&L and execution analytics) goes
+//! through one [`FeeModel`] so the numbers reconcile. A signal's edge has
+//! to clear the fee of the round trip, not just the entry, and each fill
+//! also pays a flat [`order_cost`](FeeModel::order_cost) for gas.
+
+use crate::config::{FeeConfig, FeeSchedule};
+use crate::types::Side;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Whether an order added or removed liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeRole {
+    Maker,
+    Taker,
+}
+
+/// Expected fee of a fill
+pub trait FeeModel: Send + Sync {
+    /// Fee in USDC for filling `size` shares at `price`; negative for a rebate
+    fn fee(&self, market_id: &str, side: Side, price: Decimal, size: Decimal, role: FeeRole) -> Decimal;
+
+    /// Fee of one share, in the same units as a signal's edge
+    fn fee_per_share(&self, market_id: &str, side: Side, price: Decimal, role: FeeRole) -> Decimal {
+        self.fee(market_id, side, price, Decimal::ONE, role)
+    }
+
+    /// Flat cost of an order in USDC (gas, withdrawal), whatever its size
+    fn order_cost(&self) -> Decimal {
+        Decimal::ZERO
+    }
+
+    /// Fee recorded on a fill: the rate plus the [`order_cost`](Self::order_cost)
+    fn order_fee(&self, market_id: &str, side: Side, price: Decimal, size: Decimal, role: FeeRole) -> Decimal {
+        self.fee(market_id, side, price, size, role) + self.order_cost()
+    }
+
+    /// Taker fee of one share bought or sold at `entry_price` and closed
+    /// again at `exit_price`
+    fn round_trip_per_share(&self, market_id: &str, side: Side, entry_price: Decimal, exit_price: Decimal) -> Decimal {
+        let exit_side = match side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        self.fee_per_share(market_id, side, entry_price, FeeRole::Taker)
+            + self.fee_per_share(market_id, exit_side, exit_price, FeeRole::Taker)
+    }
+}
+
+/// Polymarket's fee curve with per-market rates
+#[derive(Debug, Default)]
+pub struct PolymarketFees {
+    default: FeeSchedule,
+    markets: RwLock<HashMap<String, FeeSchedule>>,
+    gas_per_order: Decimal,
+}
+
+impl PolymarketFees {
+    /// The same rates on every market
+    pub fn new(default: FeeSchedule) -> Self {
+        Self {
+            default,
+            markets: RwLock::new(HashMap::new()),
+            gas_per_order: Decimal::ZERO,
+        }
+    }
+
+    /// Charge `gas` USDC on every fill
+    pub fn with_gas_per_order(mut self, gas: Decimal) -> Self {
+        self.gas_per_order = gas;
+        self
+    }
+
+    pub fn from_config(config: &FeeConfig) -> Self {
+        Self {
+            default: config.default_schedule(),
+            markets: RwLock::new(config.markets.clone()),
+            gas_per_order: config.gas_per_order_usdc,
+        }
+    }
+
+    /// Charge `market_id` at `schedule`, e.g. once a scan finds it to be a
+    /// market type with its own rates
+    pub fn set_market(&self, market_id: &str, schedule: FeeSchedule) {
+        self.markets.write().insert(market_id.to_string(), schedule);
+    }
+
+    /// Like [`set_market`](Self::set_market), unless the market already has rates
+    pub fn set_market_default(&self, market_id: &str, schedule: FeeSchedule) {
+        self.markets.write().entry(market_id.to_string()).or_insert(schedule);
+    }
+
+    /// Rates charged on `market_id`
+    pub fn schedule(&self, market_id: &str) -> FeeSchedule {
+        self.markets.read().get(market_id).copied().unwrap_or(self.default)
+    }
+}
+
+impl FeeModel for PolymarketFees {
+    fn fee(&self, market_id: &str, _side: Side, price: Decimal, size: Decimal, role: FeeRole) -> Decimal {
+        let schedule = self.schedule(market_id);
+        let bps = match role {
+            FeeRole::Maker => schedule.maker_fee_bps,
+            FeeRole::Taker => schedule.taker_fee_bps,
+        };
+        let curve = price.min(Decimal::ONE - price).max(Decimal::ZERO);
+        bps / Decimal::from(10_000) * curve * size
+    }
+
+    fn order_cost(&self) -> Decimal {
+        self.gas_per_order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fees(taker_bps: Decimal, maker_bps: Decimal) -> PolymarketFees {
+        PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: taker_bps,
+            maker_fee_bps: maker_bps,
+        })
+    }
+
+    #[test]
+    fn test_fee_curve_at_extreme_prices() {
+        let fees = fees(dec!(200), dec!(-50));
+
+        // 2% of notional at 50c, 0.02c a share at 1c and 99c
+        assert_eq!(fees.fee("m", Side::Buy, dec!(0.50), dec!(100), FeeRole::Taker), dec!(1));
+        let low = fees.fee_per_share("m", Side::Buy, dec!(0.01), FeeRole::Taker);
+        let high = fees.fee_per_share("m", Side::Buy, dec!(0.99), FeeRole::Taker);
+        assert_eq!(low, dec!(0.0002));
+        assert_eq!(high, low);
+        assert_eq!(fees.fee_per_share("m", Side::Sell, dec!(0.99), FeeRole::Taker), low);
+
+        // At 1c the fee is 2% of the cost of the share, at 99c about 0.02%
+        assert_eq!(low / dec!(0.01), dec!(0.02));
+        assert!(high / dec!(0.99) < dec!(0.0003));
+
+        // Maker rebates follow the same curve
+        assert_eq!(fees.fee("m", Side::Sell, dec!(0.99), dec!(1000), FeeRole::Maker), dec!(-0.05));
+        assert_eq!(fees.fee("m", Side::Buy, dec!(1), dec!(10), FeeRole::Taker), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_fee_dominates_thin_edge_at_extremes() {
+        // A crypto Up/Down market at the default crypto rates
+        let config = FeeConfig::default();
+        let fees = PolymarketFees::from_config(&config);
+        fees.set_market("updown", config.crypto_updown);
+
+        // Half a cent of edge on a 1c outcome survives the fee...
+        let fee = fees.fee_per_share("updown", Side::Buy, dec!(0.01), FeeRole::Taker);
+        assert_eq!(fee, dec!(0.0004));
+        assert!(dec!(0.005) > fee);
+        // ...but a few basis points of edge on a 99c favourite do not, and
+        // near 50c the fee eats two cents of edge
+        assert!(dec!(0.0003) < fees.fee_per_share("updown", Side::Buy, dec!(0.99), FeeRole::Taker));
+        assert_eq!(fees.fee_per_share("updown", Side::Buy, dec!(0.50), FeeRole::Taker), dec!(0.02));
+
+        // Other markets use the (zero) default
+        assert_eq!(fees.fee_per_share("other", Side::Buy, dec!(0.50), FeeRole::Taker), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_round_trip_and_order_cost() {
+        let fees = fees(dec!(400), Decimal::ZERO).with_gas_per_order(dec!(0.10));
+
+        // In at 50c and out at 60c: 2c + 1.6c a share
+        assert_eq!(fees.round_trip_per_share("m", Side::Buy, dec!(0.50), dec!(0.60)), dec!(0.036));
+        assert_eq!(fees.round_trip_per_share("m", Side::Sell, dec!(0.50), dec!(0.60)), dec!(0.036));
+
+        // Gas is charged once per fill, not per share
+        assert_eq!(fees.order_fee("m", Side::Buy, dec!(0.50), dec!(100), FeeRole::Taker), dec!(2.10));
+        assert_eq!(fees.fee_per_share("m", Side::Buy, dec!(0.50), FeeRole::Taker), dec!(0.02));
+
+        let config = FeeConfig {
+            gas_per_order_usdc: dec!(0.03),
+            ..Default::default()
+        };
+        assert_eq!(PolymarketFees::from_config(&config).order_cost(), dec!(0.03));
+    }
+
+    #[test]
+    fn test_per_market_overrides() {
+        let mut config = FeeConfig::default();
+        config.taker_fee_bps = dec!(100);
+        config.markets.insert(
+            "promo".to_string(),
+            FeeSchedule {
+                taker_fee_bps: dec!(0),
+                maker_fee_bps: dec!(-100),
+            },
+        );
+        let fees = PolymarketFees::from_config(&config);
+        assert_eq!(fees.schedule("promo").maker_fee_bps, dec!(-100));
+        assert_eq!(fees.schedule("plain").taker_fee_bps, dec!(100));
+
+        // A runtime default never replaces a configured override
+        fees.set_market_default("promo", config.crypto_updown);
+        assert_eq!(fees.schedule("promo").taker_fee_bps, Decimal::ZERO);
+        fees.set_market_default("plain", config.crypto_updown);
+        assert_eq!(fees.schedule("plain"), config.crypto_updown);
+    }
+}
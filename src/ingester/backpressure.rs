@@ -0,0 +1,295 @@
+//! Bounded signal queues with explicit backpressure
+//!
+//! Sources hand signals to an mpsc channel that is drained straight into a
+//! `SignalQueue`, so a news burst is handled by the queue's policy instead
+//! of blocking the source task:
+//! - `Block`: producers wait for space (no loss, sources may stall)
+//! - `DropOldest`: evict a queued signal to admit the new one
+//! - `Sample`: once full, admit only every Nth arriving signal
+//!
+//! When shedding, the lowest-priority signal (author trust for raw signals,
+//! aggregate score for parsed ones) goes first, oldest first among equals.
+//! Every shed signal increments `dropped()`.
+
+use super::{ParsedSignal, RawSignal};
+use crate::config::BackpressurePolicy;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify};
+
+/// Priority used when shedding load (higher is kept longer)
+pub trait SignalPriority {
+    fn priority(&self) -> f64;
+}
+
+impl SignalPriority for RawSignal {
+    fn priority(&self) -> f64 {
+        self.author_trust
+    }
+}
+
+impl SignalPriority for ParsedSignal {
+    fn priority(&self) -> f64 {
+        self.agg_score
+    }
+}
+
+/// Result of pushing into a `SignalQueue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// Queued without loss
+    Queued,
+    /// Queued after evicting a lower-priority signal
+    Evicted,
+    /// The pushed signal itself was shed
+    Dropped,
+    /// The queue is closed
+    Closed,
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+    /// Arrivals while full, for sampling
+    overflow_arrivals: u64,
+}
+
+/// Bounded multi-producer, single-consumer signal queue
+pub struct SignalQueue<T> {
+    state: Mutex<QueueState<T>>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    sample_every: u64,
+    dropped: AtomicU64,
+    not_empty: Notify,
+    not_full: Notify,
+}
+
+impl<T: SignalPriority> SignalQueue<T> {
+    pub fn new(capacity: usize, policy: BackpressurePolicy) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+                overflow_arrivals: 0,
+            }),
+            capacity: capacity.max(1),
+            policy,
+            sample_every: 4,
+            dropped: AtomicU64::new(0),
+            not_empty: Notify::new(),
+            not_full: Notify::new(),
+        }
+    }
+
+    /// Under `Sample`, admit one in `n` arrivals while full
+    pub fn with_sample_every(mut self, n: u32) -> Self {
+        self.sample_every = n.max(1) as u64;
+        self
+    }
+
+    /// Signals shed so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Stop accepting signals; `pop` drains what is left, then returns `None`
+    pub fn close(&self) {
+        self.state.lock().closed = true;
+        self.not_empty.notify_waiters();
+        self.not_full.notify_waiters();
+    }
+
+    /// Enqueue a signal according to the backpressure policy
+    pub async fn push(&self, item: T) -> PushOutcome {
+        let mut item = Some(item);
+        loop {
+            let wait = self.not_full.notified();
+            {
+                let mut state = self.state.lock();
+                if state.closed {
+                    return PushOutcome::Closed;
+                }
+
+                if state.items.len() < self.capacity {
+                    state.overflow_arrivals = 0;
+                    state.items.push_back(item.take().expect("item pushed once"));
+                    drop(state);
+                    self.not_empty.notify_one();
+                    return PushOutcome::Queued;
+                }
+
+                match self.policy {
+                    BackpressurePolicy::Block => {}
+                    BackpressurePolicy::DropOldest => {
+                        return self.shed(&mut state, item.take().expect("item pushed once"));
+                    }
+                    BackpressurePolicy::Sample => {
+                        state.overflow_arrivals += 1;
+                        let item = item.take().expect("item pushed once");
+                        if !state.overflow_arrivals.is_multiple_of(self.sample_every) {
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                            return PushOutcome::Dropped;
+                        }
+                        return self.shed(&mut state, item);
+                    }
+                }
+            }
+            wait.await;
+        }
+    }
+
+    /// Admit `item` into a full queue by evicting the lowest-priority signal,
+    /// or drop `item` if everything queued outranks it
+    fn shed(&self, state: &mut QueueState<T>, item: T) -> PushOutcome {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+
+        let victim = state
+            .items
+            .iter()
+            .enumerate()
+            .min_by(|(ia, a), (ib, b)| {
+                a.priority()
+                    .partial_cmp(&b.priority())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(ia.cmp(ib))
+            })
+            .map(|(i, q)| (i, q.priority()));
+
+        match victim {
+            Some((_, p)) if item.priority() < p => PushOutcome::Dropped,
+            Some((i, _)) => {
+                state.items.remove(i);
+                state.items.push_back(item);
+                self.not_empty.notify_one();
+                PushOutcome::Evicted
+            }
+            None => PushOutcome::Dropped,
+        }
+    }
+
+    /// Dequeue the next signal, waiting if empty; `None` once closed and drained
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            let wait = self.not_empty.notified();
+            {
+                let mut state = self.state.lock();
+                if let Some(item) = state.items.pop_front() {
+                    drop(state);
+                    self.not_full.notify_one();
+                    return Some(item);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            wait.await;
+        }
+    }
+}
+
+/// Drain an mpsc receiver into a queue, closing the queue when all senders drop
+pub async fn forward<T: SignalPriority>(mut rx: mpsc::Receiver<T>, queue: Arc<SignalQueue<T>>) {
+    while let Some(item) = rx.recv().await {
+        if queue.push(item).await == PushOutcome::Closed {
+            break;
+        }
+    }
+    queue.close();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn raw(id: usize, trust: f64) -> RawSignal {
+        RawSignal {
+            source: "test".to_string(),
+            source_id: id.to_string(),
+            content: String::new(),
+            author: "author".to_string(),
+            author_trust: trust,
+            timestamp: Utc::now(),
+            metadata: None,
+            trace_id: Default::default(),
+        }
+    }
+
+    async fn drain(queue: &SignalQueue<RawSignal>) -> Vec<String> {
+        queue.close();
+        let mut ids = Vec::new();
+        while let Some(s) = queue.pop().await {
+            ids.push(s.source_id);
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_newest() {
+        let (tx, rx) = mpsc::channel(4);
+        let queue = Arc::new(SignalQueue::new(3, BackpressurePolicy::DropOldest));
+        let fwd = tokio::spawn(forward(rx, queue.clone()));
+
+        for i in 0..10 {
+            tx.send(raw(i, 0.5)).await.unwrap();
+        }
+        drop(tx);
+        fwd.await.unwrap();
+
+        assert_eq!(queue.dropped(), 7);
+        assert_eq!(drain(&queue).await, vec!["7", "8", "9"]);
+    }
+
+    #[tokio::test]
+    async fn test_shedding_prefers_low_trust() {
+        let queue = SignalQueue::new(2, BackpressurePolicy::DropOldest);
+        queue.push(raw(0, 0.9)).await;
+        queue.push(raw(1, 0.2)).await;
+
+        // Evicts the low-trust signal, not the oldest
+        assert_eq!(queue.push(raw(2, 0.5)).await, PushOutcome::Evicted);
+        // Lower than everything queued: shed the newcomer
+        assert_eq!(queue.push(raw(3, 0.1)).await, PushOutcome::Dropped);
+
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(drain(&queue).await, vec!["0", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_sample_admits_one_in_n() {
+        let queue = SignalQueue::new(1, BackpressurePolicy::Sample).with_sample_every(3);
+        for i in 0..7 {
+            queue.push(raw(i, 0.5)).await;
+        }
+        // Arrivals 1..=6 overflow; the 3rd and 6th are admitted
+        assert_eq!(queue.dropped(), 6);
+        assert_eq!(drain(&queue).await, vec!["6"]);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let queue = Arc::new(SignalQueue::new(1, BackpressurePolicy::Block));
+        queue.push(raw(0, 0.5)).await;
+
+        let q = queue.clone();
+        let producer = tokio::spawn(async move { q.push(raw(1, 0.5)).await });
+        tokio::task::yield_now().await;
+        assert!(!producer.is_finished());
+
+        assert_eq!(queue.pop().await.unwrap().source_id, "0");
+        assert_eq!(producer.await.unwrap(), PushOutcome::Queued);
+        assert_eq!(queue.dropped(), 0);
+        assert_eq!(drain(&queue).await, vec!["1"]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Near-duplicate detection for raw signals
+//!
+//! The same story often arrives from several channels, slightly reworded.
+//! Each message's MinHash signature is split into bands of `rows_per_band`
+//! hashes and indexed by band (locality-sensitive hashing), so only messages
+//! sharing at least one band are compared instead of every recent message.
+
+use super::RawSignal;
+use crate::analysis::minhash::MinHasher;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
+
+/// Drops messages that are near-duplicates of one seen within the window
+pub struct SignalDeduplicator {
+    min_hasher: MinHasher,
+    rows_per_band: usize,
+    /// Band key -> signatures of recent messages with that band
+    buckets: HashMap<u32, Vec<(Uuid, Vec<u32>)>>,
+    window_secs: u64,
+    /// Recent messages oldest first, with their band keys for eviction
+    seen: VecDeque<(DateTime<Utc>, Uuid, Vec<u32>)>,
+    deduplicated_total: u64,
+}
+
+impl SignalDeduplicator {
+    pub fn new(num_hashes: usize, rows_per_band: usize, window_secs: u64) -> Self {
+        let rows_per_band = rows_per_band.clamp(1, num_hashes.max(1));
+        Self {
+            min_hasher: MinHasher::new(num_hashes),
+            rows_per_band,
+            buckets: HashMap::new(),
+            window_secs,
+            seen: VecDeque::new(),
+            deduplicated_total: 0,
+        }
+    }
+
+    /// Whether `signal` is a near-duplicate of a message seen in the window.
+    /// Messages that aren't are remembered for later comparisons.
+    pub fn is_duplicate(&mut self, signal: &RawSignal, threshold: f64) -> bool {
+        if signal.content.trim().is_empty() {
+            return false;
+        }
+        self.evict(signal.timestamp);
+
+        let signature = self.min_hasher.signature(&signal.content);
+        let keys = self.band_keys(&signature);
+
+        let duplicate = keys.iter().any(|key| {
+            self.buckets.get(key).is_some_and(|candidates| {
+                candidates
+                    .iter()
+                    .any(|(_, other)| MinHasher::estimate_similarity(&signature, other) >= threshold)
+            })
+        });
+
+        if duplicate {
+            self.deduplicated_total += 1;
+            return true;
+        }
+
+        let id = Uuid::new_v4();
+        for key in &keys {
+            self.buckets.entry(*key).or_default().push((id, signature.clone()));
+        }
+        self.seen.push_back((signal.timestamp, id, keys));
+        false
+    }
+
+    /// Messages dropped as near-duplicates since startup
+    pub fn signals_deduplicated_total(&self) -> u64 {
+        self.deduplicated_total
+    }
+
+    /// Messages currently remembered
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// One key per band, hashed together with the band index
+    fn band_keys(&self, signature: &[u32]) -> Vec<u32> {
+        signature
+            .chunks(self.rows_per_band)
+            .enumerate()
+            .map(|(band, rows)| {
+                let mut hasher = DefaultHasher::new();
+                band.hash(&mut hasher);
+                rows.hash(&mut hasher);
+                hasher.finish() as u32
+            })
+            .collect()
+    }
+
+    fn evict(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::seconds(self.window_secs as i64);
+        while let Some((at, _, _)) = self.seen.front() {
+            if *at >= cutoff {
+                break;
+            }
+            let Some((_, id, keys)) = self.seen.pop_front() else {
+                break;
+            };
+            for key in keys {
+                if let Some(bucket) = self.buckets.get_mut(&key) {
+                    bucket.retain(|(other, _)| *other != id);
+                    if bucket.is_empty() {
+                        self.buckets.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for SignalDeduplicator {
+    /// 128 hashes in 32 bands of 4 over a 5-minute window
+    fn default() -> Self {
+        Self::new(128, 4, 300)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(content: &str, timestamp: DateTime<Utc>) -> RawSignal {
+        RawSignal {
+            source: "telegram".to_string(),
+            source_id: Uuid::new_v4().to_string(),
+            content: content.to_string(),
+            author: "alpha".to_string(),
+            author_trust: 0.5,
+            timestamp,
+            metadata: None,
+            trace_id: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_rephrased_story_is_duplicate() {
+        let mut dedup = SignalDeduplicator::default();
+        let now = Utc::now();
+
+        let original = signal(
+            "SEC approves spot ethereum ETF applications from BlackRock and Fidelity today",
+            now,
+        );
+        let rephrased = signal(
+            "BREAKING: SEC approves spot ethereum ETF applications from BlackRock and Fidelity",
+            now + Duration::seconds(30),
+        );
+        let unrelated = signal(
+            "Solana network halted again after validator bug in latest release",
+            now + Duration::seconds(40),
+        );
+
+        assert!(!dedup.is_duplicate(&original, 0.6));
+        assert!(dedup.is_duplicate(&rephrased, 0.6));
+        assert!(!dedup.is_duplicate(&unrelated, 0.6));
+        assert_eq!(dedup.signals_deduplicated_total(), 1);
+        assert_eq!(dedup.len(), 2);
+    }
+
+    #[test]
+    fn test_threshold_controls_strictness() {
+        let mut dedup = SignalDeduplicator::default();
+        let now = Utc::now();
+
+        dedup.is_duplicate(&signal("whales are buying btc on every single dip this week", now), 0.99);
+        let similar = signal("whales are buying btc on every single dip this month", now);
+        assert!(!dedup.is_duplicate(&similar, 0.99));
+        let exact = signal("whales are buying btc on every single dip this week", now);
+        assert!(dedup.is_duplicate(&exact, 0.99));
+    }
+
+    #[test]
+    fn test_messages_expire_after_window() {
+        let mut dedup = SignalDeduplicator::new(64, 4, 60);
+        let now = Utc::now();
+        let text = "ETH looks ready to break 4000 with volume picking up";
+
+        assert!(!dedup.is_duplicate(&signal(text, now), 0.8));
+        assert!(dedup.is_duplicate(&signal(text, now + Duration::seconds(59)), 0.8));
+        assert!(!dedup.is_duplicate(&signal(text, now + Duration::seconds(120)), 0.8));
+        assert_eq!(dedup.len(), 1);
+        assert_eq!(dedup.buckets.len(), 16);
+    }
+
+    #[test]
+    fn test_empty_content_never_duplicate() {
+        let mut dedup = SignalDeduplicator::default();
+        let now = Utc::now();
+        assert!(!dedup.is_duplicate(&signal("  ", now), 0.8));
+        assert!(!dedup.is_duplicate(&signal("  ", now), 0.8));
+        assert!(dedup.is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+//! Mapping external signals to Polymarket markets
+//!
+//! Signals name tokens ("BTC", "TRUMP"), while orders need market IDs.
+//! [`SignalMapper`] tries its rules in order and uses the first whose
+//! pattern matches the token: either a keyword search over active markets,
+//! ranked by volume, or a fixed list of markets. Signals no rule matches map
+//! to nothing and are skipped. Mappings are cached per token and direction
+//! for [`CACHE_TTL`].
+
+use super::ParsedSignal;
+use crate::client::GammaClient;
+use crate::config::{MappingRuleConfig, SignalMappingConfig};
+use crate::error::{BotError, Result};
+use crate::types::Market;
+use parking_lot::{Mutex, RwLock};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a token's mapping is reused before searching again
+pub const CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Markets mapped by a rule added with `/addmapping`
+const LITERAL_MAX_MARKETS: usize = 1;
+
+/// Where a rule looks for markets
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchQuery {
+    /// Active markets whose question contains every keyword
+    Keywords(Vec<String>),
+    /// These markets, as long as they are open
+    Markets(Vec<String>),
+}
+
+/// Tokens matching `pattern` map to the markets found by `query`
+#[derive(Debug, Clone)]
+pub struct MappingRule {
+    pub pattern: Regex,
+    pub query: SearchQuery,
+    /// Scales the signal's confidence on the mapped markets
+    pub confidence_boost: f64,
+    /// Most markets mapped, by volume
+    pub max_markets: usize,
+}
+
+impl MappingRule {
+    pub fn from_config(config: &MappingRuleConfig) -> Result<Self> {
+        let pattern = RegexBuilder::new(&config.pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| BotError::Config(format!("Invalid mapping pattern '{}': {}", config.pattern, e)))?;
+        let query = match (config.market_ids.is_empty(), config.keywords.is_empty()) {
+            (false, _) => SearchQuery::Markets(config.market_ids.clone()),
+            (true, false) => SearchQuery::Keywords(config.keywords.clone()),
+            (true, true) => {
+                return Err(BotError::Config(format!(
+                    "Mapping rule '{}' needs keywords or market_ids",
+                    config.pattern
+                )))
+            }
+        };
+        Ok(Self {
+            pattern,
+            query,
+            confidence_boost: config.confidence_boost,
+            max_markets: config.max_markets,
+        })
+    }
+
+    /// Map exactly `token` to `market_id`
+    pub fn literal(token: &str, market_id: &str) -> Self {
+        let pattern = RegexBuilder::new(&format!("^{}$", regex::escape(token)))
+            .case_insensitive(true)
+            .build()
+            .expect("escaped token is a valid regex");
+        Self {
+            pattern,
+            query: SearchQuery::Markets(vec![market_id.to_string()]),
+            confidence_boost: 1.0,
+            max_markets: LITERAL_MAX_MARKETS,
+        }
+    }
+}
+
+/// Maps signal tokens to market IDs with configured and runtime rules
+#[derive(Debug, Default)]
+pub struct SignalMapper {
+    rules: RwLock<Vec<MappingRule>>,
+    /// Market IDs by "TOKEN:Direction", with when they were found
+    cache: Mutex<HashMap<String, (Instant, Vec<String>)>>,
+}
+
+impl SignalMapper {
+    pub fn new(rules: Vec<MappingRule>) -> Self {
+        Self { rules: RwLock::new(rules), cache: Mutex::default() }
+    }
+
+    pub fn from_config(config: &SignalMappingConfig) -> Result<Self> {
+        let rules = config.rules.iter().map(MappingRule::from_config).collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(rules))
+    }
+
+    /// Map `token` to `market_id` ahead of every other rule
+    pub fn add_literal(&self, token: &str, market_id: &str) {
+        self.rules.write().insert(0, MappingRule::literal(token, market_id));
+        self.cache.lock().clear();
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.read().len()
+    }
+
+    /// Markets `signal` is about, with the signal's confidence scaled by the
+    /// rule's boost; empty when no rule matches or no market is found
+    pub async fn map(&self, signal: &ParsedSignal, gamma: &GammaClient) -> Vec<(String, f64)> {
+        let Some(rule) = self.rules.read().iter().find(|r| r.pattern.is_match(&signal.token)).cloned() else {
+            tracing::debug!(trace_id = %signal.trace_id, "No mapping rule matches token {}", signal.token);
+            return Vec::new();
+        };
+        let confidence = (signal.confidence * rule.confidence_boost).clamp(0.0, 1.0);
+        let key = format!("{}:{:?}", signal.token.to_uppercase(), signal.direction);
+
+        let cached = self
+            .cache
+            .lock()
+            .get(&key)
+            .filter(|(found, _)| found.elapsed() < CACHE_TTL)
+            .map(|(_, ids)| ids.clone());
+        let market_ids = match cached {
+            Some(ids) => {
+                tracing::debug!(trace_id = %signal.trace_id, "Mapped {} from cache to {:?}", key, ids);
+                ids
+            }
+            None => match Self::search(&rule, gamma).await {
+                Ok(ids) => {
+                    tracing::debug!(
+                        trace_id = %signal.trace_id,
+                        "Mapped {} by rule '{}' to {:?}",
+                        key,
+                        rule.pattern.as_str(),
+                        ids
+                    );
+                    self.cache.lock().insert(key, (Instant::now(), ids.clone()));
+                    ids
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to map {}: {}", key, e);
+                    Vec::new()
+                }
+            },
+        };
+        market_ids.into_iter().map(|id| (id, confidence)).collect()
+    }
+
+    /// IDs of the open markets `rule` finds, by volume
+    async fn search(rule: &MappingRule, gamma: &GammaClient) -> Result<Vec<String>> {
+        let mut markets: Vec<Market> = match &rule.query {
+            SearchQuery::Keywords(keywords) => {
+                let Some(first) = keywords.first() else {
+                    return Ok(Vec::new());
+                };
+                let keywords: Vec<String> = keywords.iter().map(|k| k.to_lowercase()).collect();
+                gamma
+                    .search_markets(first)
+                    .await?
+                    .into_iter()
+                    .filter(|m| {
+                        let question = m.question.to_lowercase();
+                        keywords.iter().all(|k| question.contains(k.as_str()))
+                    })
+                    .collect()
+            }
+            SearchQuery::Markets(ids) => {
+                let mut markets = Vec::new();
+                for id in ids {
+                    match gamma.get_market(id).await {
+                        Ok(market) => markets.push(market),
+                        Err(e) => tracing::debug!("Skipping mapped market {}: {}", id, e),
+                    }
+                }
+                markets
+            }
+        };
+        markets.retain(|m| !m.closed);
+        markets.sort_by_key(|m| std::cmp::Reverse(m.volume));
+        Ok(markets.into_iter().take(rule.max_markets).map(|m| m.id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingester::{ActionType, SignalDirection};
+    use crate::types::TraceId;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn signal(token: &str, direction: SignalDirection) -> ParsedSignal {
+        ParsedSignal {
+            token: token.to_string(),
+            direction,
+            timeframe: "1d".to_string(),
+            confidence: 0.8,
+            reasoning: String::new(),
+            action_type: ActionType::Entry,
+            sources: Vec::new(),
+            agg_score: 0.8,
+            timestamp: chrono::Utc::now(),
+            trace_id: TraceId::new(),
+        }
+    }
+
+    fn gamma_market(id: &str, question: &str, volume: &str, closed: bool) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "question": question,
+            "liquidity": "10000",
+            "volume": volume,
+            "active": true,
+            "closed": closed,
+        })
+    }
+
+    /// Gamma serving `markets`; returns its URL and how many requests it got
+    async fn mock_gamma(markets: Vec<serde_json::Value>) -> (String, Arc<AtomicUsize>) {
+        use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let (list, list_requests, by_id_requests) = (markets.clone(), requests.clone(), requests.clone());
+        let app = Router::new()
+            .route(
+                "/markets",
+                get(move || async move {
+                    list_requests.fetch_add(1, Ordering::SeqCst);
+                    Json(serde_json::Value::Array(list))
+                }),
+            )
+            .route(
+                "/markets/{id}",
+                get(move |Path(id): Path<String>| async move {
+                    by_id_requests.fetch_add(1, Ordering::SeqCst);
+                    markets
+                        .iter()
+                        .find(|m| m["id"] == id.as_str())
+                        .map(|m| Json(m.clone()))
+                        .ok_or(StatusCode::NOT_FOUND)
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn rule(pattern: &str, keywords: &[&str], market_ids: &[&str]) -> MappingRuleConfig {
+        MappingRuleConfig {
+            pattern: pattern.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            market_ids: market_ids.iter().map(|k| k.to_string()).collect(),
+            confidence_boost: 0.5,
+            max_markets: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keyword_rule_maps_to_markets_by_volume_and_caches() {
+        let (url, requests) = mock_gamma(vec![
+            gamma_market("btc-100k", "Will Bitcoin hit $100k?", "50000", false),
+            gamma_market("btc-etf", "Bitcoin ETF approved?", "90000", false),
+            gamma_market("btc-low", "Will Bitcoin dip below $50k?", "1000", false),
+            gamma_market("btc-old", "Bitcoin above $10k in 2020?", "999999", true),
+            gamma_market("eth", "Will Ethereum flip Bitcoin?", "70000", false),
+        ])
+        .await;
+        let gamma = GammaClient::new(&url).unwrap();
+        let config = SignalMappingConfig { rules: vec![rule("^(btc|bitcoin)$", &["bitcoin"], &[])] };
+        let mapper = SignalMapper::from_config(&config).unwrap();
+
+        // The two busiest open markets mentioning Bitcoin, "eth" included
+        let mapped = mapper.map(&signal("BTC", SignalDirection::Bullish), &gamma).await;
+        assert_eq!(mapped, [("btc-etf".to_string(), 0.4), ("eth".to_string(), 0.4)]);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Cached per token and direction
+        mapper.map(&signal("btc", SignalDirection::Bullish), &gamma).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        mapper.map(&signal("BTC", SignalDirection::Bearish), &gamma).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_tokens_map_to_nothing_until_a_literal_rule() {
+        let (url, requests) = mock_gamma(vec![
+            gamma_market("trump-2028", "Will Trump run in 2028?", "1000", false),
+            gamma_market("closed", "Trump closed market", "1000", true),
+        ])
+        .await;
+        let gamma = GammaClient::new(&url).unwrap();
+        let config = SignalMappingConfig { rules: vec![rule("^BTC$", &["bitcoin"], &[]), rule("^TRUMP$", &[], &["closed"])] };
+        let mapper = SignalMapper::from_config(&config).unwrap();
+
+        assert!(mapper.map(&signal("DOGE", SignalDirection::Bullish), &gamma).await.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+        // Closed markets are never mapped
+        assert!(mapper.map(&signal("TRUMP", SignalDirection::Bullish), &gamma).await.is_empty());
+
+        // A literal rule takes precedence over the configured ones
+        mapper.add_literal("trump", "trump-2028");
+        assert_eq!(mapper.rule_count(), 3);
+        let mapped = mapper.map(&signal("TRUMP", SignalDirection::Bullish), &gamma).await;
+        assert_eq!(mapped, [("trump-2028".to_string(), 0.8)]);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        assert!(MappingRule::from_config(&rule("(", &["x"], &[])).is_err());
+        assert!(MappingRule::from_config(&rule("^X$", &[], &[])).is_err());
+        assert!(MappingRule::literal("a.b", "m").pattern.is_match("A.B"));
+        assert!(!MappingRule::literal("a.b", "m").pattern.is_match("axb"));
+    }
+}
@@ -4,11 +4,20 @@
 //! - Telegram groups (alpha channels)
 //! - Twitter/X (KOL accounts)
 //! - On-chain data (whale movements)
+//! - NewsAPI headlines, e.g. about the markets we hold ([`portfolio_news`])
 
 pub mod source;
 pub mod telegram;
 pub mod twitter;
+pub mod news;
+pub mod portfolio_news;
 pub mod processor;
+pub mod backpressure;
+pub mod trust;
+pub mod dedup;
+pub mod mapping;
+pub mod replay;
+pub mod stats;
 
 #[cfg(test)]
 mod tests;
@@ -16,15 +25,24 @@ mod tests;
 mod processor_tests;
 
 use crate::error::Result;
+use crate::types::TraceId;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+pub use backpressure::{PushOutcome, SignalPriority, SignalQueue};
+pub use news::{NewsApiSource, NewsArticle};
+pub use portfolio_news::NewsPortfolioMonitor;
+pub use trust::{AuthorStanding, AuthorTrust};
+pub use dedup::SignalDeduplicator;
+pub use mapping::SignalMapper;
+pub use stats::{IngestStats, SourceStats, SourceStatsHandle};
+
 /// Raw signal from any source
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawSignal {
-    /// Source type: "telegram", "twitter", "chain"
+    /// Source type: "telegram", "twitter", "twitter_rss", "chain"
     pub source: String,
     /// Original message/event ID
     pub source_id: String,
@@ -38,6 +56,9 @@ pub struct RawSignal {
     pub timestamp: DateTime<Utc>,
     /// Additional metadata
     pub metadata: Option<serde_json::Value>,
+    /// Assigned by the [`SignalProcessor`](processor::SignalProcessor) on ingestion
+    #[serde(default)]
+    pub trace_id: TraceId,
 }
 
 /// Parsed signal after LLM extraction
@@ -61,6 +82,9 @@ pub struct ParsedSignal {
     pub agg_score: f64,
     /// Timestamp
     pub timestamp: DateTime<Utc>,
+    /// Trace of the raw signal that completed the aggregation
+    #[serde(default)]
+    pub trace_id: TraceId,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -0,0 +1,245 @@
+//! NewsAPI headline collection
+//!
+//! Polls the NewsAPI `everything` endpoint for articles mentioning any of a
+//! set of keywords. The keywords can be swapped while the source runs, e.g.
+//! to follow the markets we hold.
+
+use super::{RawSignal, SignalSource, SourceStatsHandle};
+use crate::error::{BotError, Result};
+use crate::monitor::Heartbeat;
+use crate::utils::{http_client, HTTP_TIMEOUT};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const NEWSAPI_URL: &str = "https://newsapi.org/v2/everything";
+
+/// NewsAPI caps the query at 500 characters
+const MAX_QUERY_LEN: usize = 500;
+
+/// Default time between polls
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(300);
+
+/// A headline from NewsAPI
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewsArticle {
+    pub title: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub source: String,
+    pub published_at: DateTime<Utc>,
+}
+
+impl NewsArticle {
+    /// Title and description, what keywords are matched against
+    pub fn text(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{}. {}", self.title, description),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// NewsAPI source
+pub struct NewsApiSource {
+    api_key: String,
+    keywords: RwLock<Vec<String>>,
+    poll_interval: Duration,
+    http: reqwest::Client,
+    /// Beaten on every poll, for the watchdog
+    heartbeat: Option<Arc<Heartbeat>>,
+    stats: Option<SourceStatsHandle>,
+}
+
+impl NewsApiSource {
+    pub fn new(api_key: String, keywords: Vec<String>) -> Self {
+        Self {
+            api_key,
+            keywords: RwLock::new(keywords),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            http: http_client(HTTP_TIMEOUT),
+            heartbeat: None,
+            stats: None,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_heartbeat(mut self, heartbeat: Arc<Heartbeat>) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub fn with_stats(mut self, stats: SourceStatsHandle) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    pub fn keywords(&self) -> Vec<String> {
+        self.keywords.read().clone()
+    }
+
+    /// Replace the keywords searched for from the next poll on
+    pub fn set_keywords(&self, keywords: Vec<String>) {
+        *self.keywords.write() = keywords;
+    }
+
+    /// Articles published since `since` mentioning any keyword, newest first;
+    /// none without keywords
+    pub async fn fetch_articles(&self, since: DateTime<Utc>) -> Result<Vec<NewsArticle>> {
+        let Some(query) = search_query(&self.keywords()) else {
+            return Ok(Vec::new());
+        };
+        let from = since.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let resp = self
+            .http
+            .get(NEWSAPI_URL)
+            .query(&[
+                ("q", query.as_str()),
+                ("from", from.as_str()),
+                ("sortBy", "publishedAt"),
+                ("language", "en"),
+                ("pageSize", "100"),
+            ])
+            .header("X-Api-Key", &self.api_key)
+            .send()
+            .await?;
+
+        let data: NewsApiResponse = resp.json().await?;
+        if data.status != "ok" {
+            return Err(BotError::Api(format!(
+                "NewsAPI: {}",
+                data.message.unwrap_or(data.status)
+            )));
+        }
+        Ok(data
+            .articles
+            .into_iter()
+            .filter_map(|a| {
+                Some(NewsArticle {
+                    title: a.title?,
+                    description: a.description,
+                    url: a.url?,
+                    source: a.source.and_then(|s| s.name).unwrap_or_default(),
+                    published_at: a.published_at?,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Keywords OR-ed into a NewsAPI query, phrases quoted, as many as fit
+pub(crate) fn search_query(keywords: &[String]) -> Option<String> {
+    let mut query = String::new();
+    for keyword in keywords.iter().map(|k| k.replace('"', "")).filter(|k| !k.trim().is_empty()) {
+        let term = if keyword.contains(' ') { format!("\"{}\"", keyword) } else { keyword };
+        let sep = if query.is_empty() { "" } else { " OR " };
+        if query.len() + sep.len() + term.len() > MAX_QUERY_LEN {
+            break;
+        }
+        query.push_str(sep);
+        query.push_str(&term);
+    }
+    (!query.is_empty()).then_some(query)
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsApiResponse {
+    status: String,
+    message: Option<String>,
+    #[serde(default)]
+    articles: Vec<NewsApiArticle>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewsApiArticle {
+    source: Option<NewsApiArticleSource>,
+    title: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsApiArticleSource {
+    name: Option<String>,
+}
+
+#[async_trait]
+impl SignalSource for NewsApiSource {
+    fn name(&self) -> &str {
+        "news"
+    }
+
+    async fn run(&self, tx: mpsc::Sender<RawSignal>) -> Result<()> {
+        tracing::info!("NewsAPI source starting, {} keywords", self.keywords.read().len());
+
+        let mut seen_urls: HashSet<String> = HashSet::new();
+        let mut since = Utc::now();
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
+
+            let polled_at = Utc::now();
+            match self.fetch_articles(since).await {
+                Ok(articles) => {
+                    since = polled_at;
+                    for article in articles {
+                        if !seen_urls.insert(article.url.clone()) {
+                            continue;
+                        }
+                        if let Some(stats) = &self.stats {
+                            stats.received();
+                            stats.passed_prefilter();
+                        }
+                        let signal = RawSignal {
+                            source: "news".to_string(),
+                            source_id: article.url.clone(),
+                            content: article.text(),
+                            author: article.source.clone(),
+                            author_trust: 0.3,
+                            timestamp: Utc::now(),
+                            metadata: Some(serde_json::json!({
+                                "url": article.url,
+                                "published_at": article.published_at,
+                            })),
+                            trace_id: Default::default(),
+                        };
+                        if tx.send(signal).await.is_err() {
+                            tracing::warn!("Failed to send signal, channel closed");
+                            return Ok(());
+                        }
+                    }
+                }
+                Err(e) => {
+                    if let Some(stats) = &self.stats {
+                        stats.error();
+                    }
+                    tracing::warn!("Failed to fetch news: {}", e);
+                }
+            }
+
+            // Limit seen cache size
+            if seen_urls.len() > 10000 {
+                seen_urls.clear();
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! News about the markets we hold
+//!
+//! [`NewsPortfolioMonitor`] keeps a [`NewsApiSource`] searching for the
+//! people, organisations and other names in the questions of our open
+//! positions' markets, and alerts as soon as a strongly worded headline
+//! mentions one of them.
+
+use super::news::{NewsApiSource, NewsArticle};
+use crate::client::PolymarketClient;
+use crate::notify::Notifier;
+use crate::sentiment::SentimentAnalyzer;
+use crate::types::{Market, Trade};
+use chrono::Utc;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+/// Absolute compound sentiment from which a matching headline is alerted
+pub const NEWS_SENTIMENT_THRESHOLD: f64 = 0.3;
+
+/// Two or more capitalised words in a row: "Donald Trump", "NBA Finals"
+static NAMED_ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Z][A-Za-z]+(?: [A-Z][A-Za-z]+)+\b").expect("valid regex"));
+
+/// A single capitalised word or acronym: "Bitcoin", "NBA"
+static PROPER_NOUN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:[A-Z][a-z]{2,}|[A-Z]{2,6})\b").expect("valid regex"));
+
+/// Capitalised words that name nothing worth searching for
+const STOPWORDS: &[&str] = &[
+    "will", "would", "does", "did", "is", "are", "was", "can", "could", "should", "has", "have", "who", "what",
+    "which", "when", "where", "how", "why", "the", "this", "that", "these", "any", "yes", "no", "before", "after",
+    "by", "in", "on", "at", "of", "for", "and", "or", "between", "above", "below", "over", "under", "more", "less",
+    "than", "win", "reach", "end", "up", "down", "january", "february", "march", "april", "may", "june", "july",
+    "august", "september", "october", "november", "december", "jan", "feb", "mar", "apr", "jun", "jul", "aug",
+    "sep", "sept", "oct", "nov", "dec", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday",
+    "sunday", "et", "utc", "pm", "am", "usd",
+];
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word.to_lowercase().as_str())
+}
+
+/// Names worth searching the news for in a market question: runs of
+/// capitalised words as named entities, then single proper nouns and
+/// acronyms not already part of one. Question words, months and the like
+/// are dropped from either end of a run.
+pub fn extract_keywords(question: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    let mut covered: Vec<(usize, usize)> = Vec::new();
+    for m in NAMED_ENTITY.find_iter(question) {
+        let words: Vec<&str> = m.as_str().split(' ').collect();
+        let start = words.iter().position(|w| !is_stopword(w)).unwrap_or(words.len());
+        let end = words.iter().rposition(|w| !is_stopword(w)).map_or(start, |i| i + 1);
+        if end - start >= 2 {
+            covered.push((m.start(), m.end()));
+            keywords.push(words[start..end].join(" "));
+        }
+    }
+    for m in PROPER_NOUN.find_iter(question) {
+        let inside_entity = covered.iter().any(|&(start, end)| m.start() >= start && m.end() <= end);
+        if !inside_entity && !is_stopword(m.as_str()) {
+            keywords.push(m.as_str().to_string());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    keywords.retain(|k| seen.insert(k.to_lowercase()));
+    keywords
+}
+
+/// Lowercased words of `text` separated and surrounded by single spaces,
+/// so keywords only match whole words
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+/// An open position's market and what to look for in the news about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedMarket {
+    pub market_id: String,
+    pub question: String,
+    pub keywords: Vec<String>,
+}
+
+impl WatchedMarket {
+    /// `None` for resolved markets and those without anything to search for
+    pub fn from_market(market: &Market) -> Option<Self> {
+        if market.closed || !market.active {
+            return None;
+        }
+        let keywords = extract_keywords(&market.question);
+        (!keywords.is_empty()).then(|| Self {
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            keywords,
+        })
+    }
+
+    /// Whether `article` mentions any of the keywords
+    pub fn matches(&self, article: &NewsArticle) -> bool {
+        let text = normalize(&article.text());
+        self.keywords.iter().any(|k| text.contains(&normalize(k)))
+    }
+}
+
+/// Watches the news for the markets of our open positions
+pub struct NewsPortfolioMonitor {
+    pub news_source: NewsApiSource,
+    /// How often the open positions, and so the keywords, are reloaded
+    pub keyword_refresh_interval: Duration,
+}
+
+impl NewsPortfolioMonitor {
+    pub fn new(news_source: NewsApiSource, keyword_refresh_interval: Duration) -> Self {
+        Self { news_source, keyword_refresh_interval }
+    }
+
+    /// Markets of `positions` still open, with their keywords
+    async fn watched_markets(positions: &[Trade], client: &PolymarketClient) -> Vec<WatchedMarket> {
+        let mut market_ids: Vec<&str> = positions.iter().map(|p| p.market_id.as_str()).collect();
+        market_ids.sort_unstable();
+        market_ids.dedup();
+
+        let mut watched = Vec::new();
+        for market_id in market_ids {
+            match client.gamma.get_market(market_id).await {
+                Ok(market) => watched.extend(WatchedMarket::from_market(&market)),
+                Err(e) => tracing::debug!("No market {} for position news: {}", market_id, e),
+            }
+        }
+        watched
+    }
+
+    /// Poll the news for the open positions' markets forever, alerting on
+    /// headlines about them with `|sentiment|` above
+    /// [`NEWS_SENTIMENT_THRESHOLD`]
+    pub async fn run(
+        &self,
+        positions_fn: Arc<dyn Fn() -> Vec<Trade> + Send + Sync>,
+        client: Arc<PolymarketClient>,
+        notifier: Arc<Notifier>,
+    ) {
+        let analyzer = SentimentAnalyzer::new();
+        let mut watched: Vec<WatchedMarket> = Vec::new();
+        let mut refreshed_at: Option<Instant> = None;
+        let mut seen_urls: HashSet<String> = HashSet::new();
+        let mut since = Utc::now();
+        let mut interval = tokio::time::interval(self.news_source.poll_interval());
+
+        loop {
+            interval.tick().await;
+
+            // Without positions there is nothing to fetch, so look again every poll
+            if watched.is_empty() || refreshed_at.is_none_or(|at| at.elapsed() >= self.keyword_refresh_interval) {
+                watched = Self::watched_markets(&positions_fn(), &client).await;
+                let mut keywords: Vec<String> = watched.iter().flat_map(|w| w.keywords.clone()).collect();
+                let mut seen = HashSet::new();
+                keywords.retain(|k| seen.insert(k.to_lowercase()));
+                tracing::debug!("Position news: {} markets, {} keywords", watched.len(), keywords.len());
+                self.news_source.set_keywords(keywords);
+                refreshed_at = Some(Instant::now());
+            }
+            if watched.is_empty() {
+                since = Utc::now();
+                continue;
+            }
+
+            let polled_at = Utc::now();
+            let articles = match self.news_source.fetch_articles(since).await {
+                Ok(articles) => articles,
+                Err(e) => {
+                    tracing::warn!("Failed to fetch position news: {}", e);
+                    continue;
+                }
+            };
+            since = polled_at;
+
+            for article in articles {
+                if !seen_urls.insert(article.url.clone()) {
+                    continue;
+                }
+                let sentiment = analyzer.analyze(&article.text()).compound;
+                if sentiment.abs() <= NEWS_SENTIMENT_THRESHOLD {
+                    continue;
+                }
+                for market in watched.iter().filter(|w| w.matches(&article)) {
+                    tracing::info!(
+                        "Position news for {} (sentiment {:+.2}): {}",
+                        market.market_id,
+                        sentiment,
+                        article.title
+                    );
+                    if let Err(e) = notifier.position_news(&market.question, &article.title).await {
+                        tracing::warn!("Failed to send position news: {}", e);
+                    }
+                }
+            }
+
+            // Limit seen cache size
+            if seen_urls.len() > 10000 {
+                seen_urls.clear();
+            }
+        }
+    }
+}
@@ -2,14 +2,75 @@
 //!
 //! Uses LLM to extract structured signals from raw messages,
 //! then aggregates multi-source signals for validation.
-
+//!
+//! Time comes from an injected [`Clock`] and LLM extractions can be kept in
+//! an [`ExtractionCache`] keyed by message content, so that a recorded
+//! message stream can be replayed offline with other thresholds (see
+//! [`replay`](super::replay)) without paying for inference again.
+
+use super::backpressure::{PushOutcome, SignalQueue};
+use super::dedup::SignalDeduplicator;
+use super::stats::IngestStats;
+use super::trust::AuthorTrust;
 use super::{ActionType, ParsedSignal, RawSignal, SignalDirection};
+use crate::clock::{Clock, SystemClock};
 use crate::config::LlmConfig;
 use crate::error::{BotError, Result};
-use chrono::{Duration, Utc};
+use crate::storage::Database;
+use crate::types::TraceId;
+use crate::utils::{http_client, LLM_HTTP_TIMEOUT};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
 use reqwest::Client;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use tokio::sync::mpsc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// LLM responses to messages, by [`content_hash`]
+#[async_trait]
+pub trait ExtractionCache: Send + Sync {
+    async fn get(&self, content_hash: &str) -> Option<String>;
+    async fn put(&self, content_hash: &str, response: &str, now: DateTime<Utc>);
+}
+
+#[async_trait]
+impl ExtractionCache for Database {
+    async fn get(&self, content_hash: &str) -> Option<String> {
+        self.get_llm_extraction(content_hash).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to read cached extraction: {}", e);
+            None
+        })
+    }
+
+    async fn put(&self, content_hash: &str, response: &str, now: DateTime<Utc>) {
+        if let Err(e) = self.save_llm_extraction(content_hash, response, now).await {
+            tracing::warn!("Failed to cache extraction: {}", e);
+        }
+    }
+}
+
+/// Key of a message in an [`ExtractionCache`]
+pub fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Extracted signals waiting to be aggregated, by token
+#[derive(Debug, Default)]
+pub struct AggregationBuffer {
+    signals: HashMap<String, Vec<ExtractedSignal>>,
+}
+
+impl AggregationBuffer {
+    /// Forget signals extracted at or before `cutoff`
+    pub fn prune(&mut self, cutoff: DateTime<Utc>) {
+        for signals in self.signals.values_mut() {
+            signals.retain(|s| s.timestamp > cutoff);
+        }
+        self.signals.retain(|_, v| !v.is_empty());
+    }
+}
 
 /// Signal processor using LLM for extraction
 pub struct SignalProcessor {
@@ -21,16 +82,39 @@ pub struct SignalProcessor {
     min_confidence: f64,
     /// Minimum aggregate score to emit
     min_agg_score: f64,
+    /// Learned author trust; sources' static scores are used without it
+    trust: Option<Arc<AuthorTrust>>,
+    /// Near-duplicate filter and its similarity threshold
+    dedup: Option<(Mutex<SignalDeduplicator>, f64)>,
+    clock: Arc<dyn Clock>,
+    extraction_cache: Option<Arc<dyn ExtractionCache>>,
+    /// Skip messages without a cached extraction instead of calling the LLM
+    cache_only: bool,
+    /// Where every incoming message is recorded for replays
+    raw_log: Option<Arc<Database>>,
+    extraction_cache_hits: AtomicU64,
+    extraction_cache_misses: AtomicU64,
+    /// Per-source counts of LLM calls and aggregated contributions
+    stats: Option<Arc<IngestStats>>,
 }
 
 impl SignalProcessor {
     pub fn new(llm_config: LlmConfig) -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(LLM_HTTP_TIMEOUT),
             llm_config,
             aggregation_window: 300, // 5 minutes
             min_confidence: 0.5,
             min_agg_score: 0.6,
+            trust: None,
+            dedup: None,
+            clock: Arc::new(SystemClock),
+            extraction_cache: None,
+            cache_only: false,
+            raw_log: None,
+            extraction_cache_hits: AtomicU64::new(0),
+            extraction_cache_misses: AtomicU64::new(0),
+            stats: None,
         }
     }
 
@@ -45,81 +129,188 @@ impl SignalProcessor {
         self
     }
 
+    pub fn with_author_trust(mut self, trust: Arc<AuthorTrust>) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    /// Skip messages whose text is at least `threshold` similar to one seen
+    /// within the aggregation window
+    pub fn with_dedup_threshold(mut self, threshold: f64) -> Self {
+        let window = self.aggregation_window.max(0) as u64;
+        let dedup = SignalDeduplicator::new(128, 4, window);
+        self.dedup = Some((Mutex::new(dedup), threshold));
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Reuse LLM extractions of messages seen before
+    pub fn with_extraction_cache(mut self, cache: Arc<dyn ExtractionCache>) -> Self {
+        self.extraction_cache = Some(cache);
+        self
+    }
+
+    /// Skip messages without a cached extraction instead of calling the LLM
+    pub fn with_cache_only(mut self, cache_only: bool) -> Self {
+        self.cache_only = cache_only;
+        self
+    }
+
+    /// Record every incoming message in `db` for replays
+    pub fn with_raw_signal_log(mut self, db: Arc<Database>) -> Self {
+        self.raw_log = Some(db);
+        self
+    }
+
+    /// Count, per source, the messages sent to the LLM and those that end
+    /// up in aggregated signals
+    pub fn with_ingest_stats(mut self, stats: Arc<IngestStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Seconds within which signals on a token are aggregated
+    pub fn aggregation_window(&self) -> i64 {
+        self.aggregation_window
+    }
+
+    /// Extractions served from and missing from the cache so far
+    pub fn extraction_cache_stats(&self) -> (u64, u64) {
+        (
+            self.extraction_cache_hits.load(Ordering::Relaxed),
+            self.extraction_cache_misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Whether a message is a rephrasing of a recent one
+    pub fn is_duplicate(&self, raw: &RawSignal) -> bool {
+        self.dedup
+            .as_ref()
+            .is_some_and(|(dedup, threshold)| dedup.lock().is_duplicate(raw, *threshold))
+    }
+
+    /// Messages skipped as near-duplicates so far
+    pub fn signals_deduplicated_total(&self) -> u64 {
+        self.dedup
+            .as_ref()
+            .map_or(0, |(dedup, _)| dedup.lock().signals_deduplicated_total())
+    }
+
+    /// Re-weight a message by its author's learned trust; `None` if the
+    /// author is muted
+    pub fn apply_trust(&self, mut raw: RawSignal) -> Option<RawSignal> {
+        if let Some(trust) = &self.trust {
+            if trust.is_muted(&raw.author) {
+                return None;
+            }
+            raw.author_trust = trust.trust(&raw.author);
+        }
+        Some(raw)
+    }
+
     /// Run the processing pipeline
     pub async fn run(
         &self,
-        mut raw_rx: mpsc::Receiver<RawSignal>,
-        parsed_tx: mpsc::Sender<ParsedSignal>,
+        raw_queue: Arc<SignalQueue<RawSignal>>,
+        parsed_queue: Arc<SignalQueue<ParsedSignal>>,
     ) -> Result<()> {
-        // Buffer for aggregation
-        let mut signal_buffer: HashMap<String, Vec<ExtractedSignal>> = HashMap::new();
+        let mut buffer = AggregationBuffer::default();
         let mut cleanup_interval = tokio::time::interval(std::time::Duration::from_secs(60));
 
         loop {
             tokio::select! {
-                Some(raw) = raw_rx.recv() => {
-                    // Extract signal using LLM
-                    match self.extract_signal(&raw).await {
-                        Ok(Some(extracted)) => {
-                            tracing::debug!(
-                                "Extracted signal: {} {} (conf: {:.2})",
-                                extracted.token,
-                                match extracted.direction {
-                                    SignalDirection::Bullish => "📈",
-                                    SignalDirection::Bearish => "📉",
-                                    SignalDirection::Neutral => "➡️",
-                                },
-                                extracted.confidence
-                            );
-
-                            // Add to buffer
-                            let key = extracted.token.clone();
-                            signal_buffer
-                                .entry(key.clone())
-                                .or_default()
-                                .push(extracted);
-
-                            // Try to aggregate
-                            if let Some(aggregated) = self.try_aggregate(&key, &mut signal_buffer) {
-                                if aggregated.agg_score >= self.min_agg_score {
-                                    tracing::info!(
-                                        "🎯 Aggregated signal: {} {} score={:.2}",
-                                        aggregated.token,
-                                        match aggregated.direction {
-                                            SignalDirection::Bullish => "BULLISH",
-                                            SignalDirection::Bearish => "BEARISH",
-                                            SignalDirection::Neutral => "NEUTRAL",
-                                        },
-                                        aggregated.agg_score
-                                    );
-
-                                    if parsed_tx.send(aggregated).await.is_err() {
-                                        tracing::warn!("Parsed signal channel closed");
-                                        return Ok(());
-                                    }
-                                }
-                            }
-                        }
-                        Ok(None) => {
-                            tracing::debug!("No signal extracted from: {}", raw.source_id);
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to extract signal: {}", e);
+                Some(raw) = raw_queue.pop() => {
+                    if let Some(db) = &self.raw_log {
+                        if let Err(e) = db.record_raw_signal(&raw).await {
+                            tracing::warn!("Failed to record raw signal: {}", e);
                         }
                     }
+                    let Some(aggregated) = self.process(raw, &mut buffer).await else {
+                        continue;
+                    };
+                    if parsed_queue.push(aggregated).await == PushOutcome::Closed {
+                        tracing::warn!("Parsed signal queue closed");
+                        return Ok(());
+                    }
                 }
                 _ = cleanup_interval.tick() => {
                     // Remove old signals from buffer
-                    let cutoff = Utc::now() - Duration::seconds(self.aggregation_window * 2);
-                    for signals in signal_buffer.values_mut() {
-                        signals.retain(|s| s.timestamp > cutoff);
-                    }
-                    signal_buffer.retain(|_, v| !v.is_empty());
+                    buffer.prune(self.clock.now() - Duration::seconds(self.aggregation_window * 2));
                 }
             }
         }
     }
 
+    /// Take one message through trust, deduplication, extraction and
+    /// aggregation; the aggregated signal it completes, if any
+    pub async fn process(&self, raw: RawSignal, buffer: &mut AggregationBuffer) -> Option<ParsedSignal> {
+        let mut raw = self.apply_trust(raw)?;
+        raw.trace_id = TraceId::new();
+        if self.is_duplicate(&raw) {
+            tracing::debug!(
+                "Skipping near-duplicate {} ({} deduplicated)",
+                raw.source_id,
+                self.signals_deduplicated_total()
+            );
+            return None;
+        }
+
+        // Extract signal using LLM
+        let extracted = match self.extract_signal(&raw).await {
+            Ok(Some(extracted)) => extracted,
+            Ok(None) => {
+                tracing::debug!("No signal extracted from: {}", raw.source_id);
+                return None;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to extract signal: {}", e);
+                return None;
+            }
+        };
+        tracing::debug!(
+            trace_id = %raw.trace_id,
+            "Extracted signal: {} {} (conf: {:.2})",
+            extracted.token,
+            match extracted.direction {
+                SignalDirection::Bullish => "📈",
+                SignalDirection::Bearish => "📉",
+                SignalDirection::Neutral => "➡️",
+            },
+            extracted.confidence
+        );
+
+        // Add to buffer
+        let key = extracted.token.clone();
+        buffer.signals.entry(key.clone()).or_default().push(extracted);
+
+        // Try to aggregate
+        let aggregated = self.try_aggregate(&key, &mut buffer.signals)?;
+        if aggregated.agg_score < self.min_agg_score {
+            return None;
+        }
+        if let Some(stats) = &self.stats {
+            for source in &aggregated.sources {
+                stats.record_contributed(&source.source, self.clock.now());
+            }
+        }
+        tracing::info!(
+            trace_id = %aggregated.trace_id,
+            "🎯 Aggregated signal: {} {} score={:.2}",
+            aggregated.token,
+            match aggregated.direction {
+                SignalDirection::Bullish => "BULLISH",
+                SignalDirection::Bearish => "BEARISH",
+                SignalDirection::Neutral => "NEUTRAL",
+            },
+            aggregated.agg_score
+        );
+        Some(aggregated)
+    }
+
     /// Extract structured signal from raw message using LLM
     async fn extract_signal(&self, raw: &RawSignal) -> Result<Option<ExtractedSignal>> {
         let prompt = format!(
@@ -148,7 +339,31 @@ Only valid JSON, no other text."#,
             raw.content
         );
 
-        let response = self.call_llm(&prompt).await?;
+        let hash = content_hash(&raw.content);
+        let cached = match &self.extraction_cache {
+            Some(cache) => cache.get(&hash).await,
+            None => None,
+        };
+        let response = match cached {
+            Some(response) => {
+                self.extraction_cache_hits.fetch_add(1, Ordering::Relaxed);
+                response
+            }
+            None => {
+                self.extraction_cache_misses.fetch_add(1, Ordering::Relaxed);
+                if self.cache_only {
+                    return Ok(None);
+                }
+                if let Some(stats) = &self.stats {
+                    stats.record_sent_to_llm(&raw.source, self.clock.now());
+                }
+                let response = self.call_llm(&prompt).await?;
+                if let Some(cache) = &self.extraction_cache {
+                    cache.put(&hash, &response, self.clock.now()).await;
+                }
+                response
+            }
+        };
         let parsed = self.parse_llm_response(&response, raw)?;
         Ok(parsed)
     }
@@ -240,7 +455,7 @@ Only valid JSON, no other text."#,
             action,
             reasoning: parsed["reasoning"].as_str().unwrap_or("").to_string(),
             raw: raw.clone(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
         }))
     }
 
@@ -253,7 +468,7 @@ Only valid JSON, no other text."#,
         // Take ownership of signals to avoid borrow issues
         let signals = buffer.remove(token)?;
         
-        let cutoff = Utc::now() - Duration::seconds(self.aggregation_window);
+        let cutoff = self.clock.now() - Duration::seconds(self.aggregation_window);
         let recent: Vec<_> = signals.into_iter().filter(|s| s.timestamp > cutoff).collect();
 
         if recent.is_empty() {
@@ -315,6 +530,8 @@ Only valid JSON, no other text."#,
             sources.iter().map(|s| &s.author).collect();
         let multi_source_bonus = (unique_authors.len() as f64 - 1.0) * 0.1;
         let final_score = (agg_score + multi_source_bonus).min(1.0);
+        // The newest source completed the aggregation; its trace carries on
+        let trace_id = sources.last().map(|s| s.trace_id).unwrap_or_default();
 
         Some(ParsedSignal {
             token: token.to_string(),
@@ -325,7 +542,8 @@ Only valid JSON, no other text."#,
             action_type: best_action,
             sources,
             agg_score: final_score,
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
+            trace_id,
         })
     }
 }
@@ -13,6 +13,15 @@ mod tests {
             api_key: "test-key".to_string(),
             model: Some("deepseek-chat".to_string()),
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         }
     }
 
@@ -48,6 +57,70 @@ mod tests {
         let _ = processor;
     }
 
+    #[test]
+    fn test_apply_learned_trust() {
+        use crate::config::AuthorTrustConfig;
+        use crate::ingester::AuthorTrust;
+        use crate::storage::SignalOutcome;
+        use rust_decimal_macros::dec;
+        use std::sync::Arc;
+
+        let raw = |author: &str| RawSignal {
+            source: "twitter".to_string(),
+            source_id: "tweet".to_string(),
+            content: "BTC up".to_string(),
+            author: author.to_string(),
+            author_trust: 0.3,
+            timestamp: Utc::now(),
+            metadata: None,
+            trace_id: Default::default(),
+        };
+
+        let config = AuthorTrustConfig { mute_min_samples: 5, ..AuthorTrustConfig::default() };
+        let trust = Arc::new(AuthorTrust::new(config, [("@good".to_string(), 0.8)].into()));
+        let misses: Vec<SignalOutcome> = (0..20)
+            .map(|_| {
+                let mut o = SignalOutcome::pending("@bad", "BTC", SignalDirection::Bullish, "1h", dec!(100), Utc::now());
+                o.resolve(dec!(90));
+                o
+            })
+            .collect();
+        trust.recompute(&misses, Utc::now());
+
+        let processor = SignalProcessor::new(create_llm_config()).with_author_trust(trust);
+        assert_eq!(processor.apply_trust(raw("@good")).unwrap().author_trust, 0.8);
+        assert!(processor.apply_trust(raw("@bad")).is_none());
+
+        // Without learned trust the source's score is kept
+        let plain = SignalProcessor::new(create_llm_config());
+        assert_eq!(plain.apply_trust(raw("@bad")).unwrap().author_trust, 0.3);
+    }
+
+    #[test]
+    fn test_processor_skips_near_duplicates() {
+        let message = |content: &str| RawSignal {
+            source: "telegram".to_string(),
+            source_id: "msg".to_string(),
+            content: content.to_string(),
+            author: "@alpha".to_string(),
+            author_trust: 0.5,
+            timestamp: Utc::now(),
+            metadata: None,
+            trace_id: Default::default(),
+        };
+
+        let processor = SignalProcessor::new(create_llm_config()).with_dedup_threshold(0.6);
+        assert!(!processor.is_duplicate(&message("Binance lists new SOL perpetual pairs with 50x leverage from Monday")));
+        assert!(processor.is_duplicate(&message("JUST IN: Binance lists new SOL perpetual pairs with 50x leverage from Monday")));
+        assert_eq!(processor.signals_deduplicated_total(), 1);
+
+        // Disabled without a threshold
+        let plain = SignalProcessor::new(create_llm_config());
+        assert!(!plain.is_duplicate(&message("same text twice over here")));
+        assert!(!plain.is_duplicate(&message("same text twice over here")));
+        assert_eq!(plain.signals_deduplicated_total(), 0);
+    }
+
     #[test]
     fn test_raw_signal_for_processing() {
         let signal = RawSignal {
@@ -58,6 +131,7 @@ mod tests {
             author_trust: 0.8,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
         
         assert!(signal.content.contains("bullish"));
@@ -74,6 +148,7 @@ mod tests {
             author_trust: 0.75,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
         
         assert!(signal.content.contains("breakdown") || signal.content.contains("short"));
@@ -91,6 +166,7 @@ mod tests {
             sources: vec![],
             agg_score: 0.90,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
         
         assert_eq!(parsed.direction, SignalDirection::Bullish);
@@ -109,6 +185,7 @@ mod tests {
             sources: vec![],
             agg_score: 0.75,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
         
         assert_eq!(parsed.direction, SignalDirection::Bearish);
@@ -127,6 +204,7 @@ mod tests {
             sources: vec![],
             agg_score: 0.55,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
         
         assert_eq!(parsed.direction, SignalDirection::Neutral);
@@ -189,6 +267,7 @@ mod tests {
                 author_trust: 0.8,
                 timestamp: Utc::now(),
                 metadata: None,
+                trace_id: Default::default(),
             },
             RawSignal {
                 source: "telegram".to_string(),
@@ -198,6 +277,7 @@ mod tests {
                 author_trust: 0.7,
                 timestamp: Utc::now(),
                 metadata: None,
+                trace_id: Default::default(),
             },
         ];
         
@@ -220,6 +300,7 @@ mod tests {
             sources: vec![],
             agg_score: 0.65,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
         
         assert_eq!(parsed.action_type, ActionType::Warning);
@@ -0,0 +1,249 @@
+//! Offline replay of the signal processor over recorded messages
+//!
+//! The live processor records every message it receives in `raw_signals`
+//! and every LLM extraction by content hash. [`replay`] feeds a recorded
+//! stream through a [`SignalProcessor`] built with other thresholds or
+//! another aggregation window, with the clock following the messages'
+//! timestamps, and reports the aggregated signals it would have produced.
+//! With a cache-only processor no message is sent to the LLM again.
+
+use super::processor::{AggregationBuffer, SignalProcessor};
+use super::{ParsedSignal, RawSignal};
+use crate::clock::ManualClock;
+use crate::storage::PriceSnapshot;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+/// What a replay produced
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    /// Messages replayed
+    pub messages: usize,
+    /// Messages whose extraction came from the cache
+    pub cache_hits: u64,
+    /// Messages without a cached extraction
+    pub cache_misses: u64,
+    /// Aggregated signals, oldest first
+    pub signals: Vec<ParsedSignal>,
+}
+
+/// A mapped market's YES price around a signal
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketMove {
+    pub market_id: String,
+    /// First snapshot at or after the signal
+    pub price_before: Decimal,
+    /// Last snapshot within the horizon
+    pub price_after: Decimal,
+}
+
+impl MarketMove {
+    pub fn change(&self) -> Decimal {
+        self.price_after - self.price_before
+    }
+}
+
+/// Run `messages` through `processor` in order, moving `clock` (the
+/// processor's clock) to each message's timestamp first
+pub async fn replay(processor: &SignalProcessor, clock: &ManualClock, mut messages: Vec<RawSignal>) -> ReplayReport {
+    messages.sort_by_key(|m| m.timestamp);
+    let (hits_before, misses_before) = processor.extraction_cache_stats();
+    let mut buffer = AggregationBuffer::default();
+    let mut report = ReplayReport { messages: messages.len(), ..ReplayReport::default() };
+    for message in messages {
+        clock.set(message.timestamp);
+        buffer.prune(message.timestamp - Duration::seconds(processor.aggregation_window() * 2));
+        if let Some(signal) = processor.process(message, &mut buffer).await {
+            report.signals.push(signal);
+        }
+    }
+    let (hits, misses) = processor.extraction_cache_stats();
+    report.cache_hits = hits - hits_before;
+    report.cache_misses = misses - misses_before;
+    report
+}
+
+/// How `market_id` moved over `horizon` after `at`, from `snapshots`;
+/// `None` without two snapshots in that span
+pub fn market_move(snapshots: &[PriceSnapshot], market_id: &str, at: DateTime<Utc>, horizon: Duration) -> Option<MarketMove> {
+    let mut span = snapshots
+        .iter()
+        .filter(|s| s.market_id == market_id && s.taken_at >= at && s.taken_at <= at + horizon);
+    let first = span.next()?;
+    let last = span.next_back()?;
+    Some(MarketMove {
+        market_id: market_id.to_string(),
+        price_before: first.yes_price,
+        price_after: last.yes_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LlmConfig;
+    use crate::ingester::processor::{content_hash, ExtractionCache};
+    use crate::ingester::{IngestStats, SignalDirection};
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use parking_lot::Mutex;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, String>>);
+
+    #[async_trait]
+    impl ExtractionCache for MemoryCache {
+        async fn get(&self, content_hash: &str) -> Option<String> {
+            self.0.lock().get(content_hash).cloned()
+        }
+
+        async fn put(&self, content_hash: &str, response: &str, _now: DateTime<Utc>) {
+            self.0.lock().insert(content_hash.to_string(), response.to_string());
+        }
+    }
+
+    fn llm_config() -> LlmConfig {
+        LlmConfig {
+            provider: "deepseek".to_string(),
+            api_key: String::new(),
+            model: None,
+            // Nothing listens here: any LLM call fails
+            base_url: Some("http://127.0.0.1:9".to_string()),
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
+        }
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()
+    }
+
+    fn message(author: &str, content: &str, secs: i64) -> RawSignal {
+        RawSignal {
+            source: "telegram".to_string(),
+            source_id: format!("{}-{}", author, secs),
+            content: content.to_string(),
+            author: author.to_string(),
+            author_trust: 0.6,
+            timestamp: start() + Duration::seconds(secs),
+            metadata: None,
+            trace_id: Default::default(),
+        }
+    }
+
+    /// Two bullish BTC calls 4 minutes apart and an unrelated message,
+    /// all with cached extractions
+    async fn recorded() -> (Vec<RawSignal>, Arc<MemoryCache>) {
+        let cache = Arc::new(MemoryCache::default());
+        let extractions = [
+            ("BTC breaking out, loading longs", r#"{"token": "BTC", "direction": "bullish", "confidence": 0.7, "action": "entry"}"#),
+            ("Bitcoin looks strong into the weekly close", r#"{"token": "BTC", "direction": "bullish", "confidence": 0.65}"#),
+            ("gm everyone", r#"{"token": null}"#),
+        ];
+        for (content, response) in extractions {
+            cache.put(&content_hash(content), response, start()).await;
+        }
+        let messages = vec![
+            message("@bob", "Bitcoin looks strong into the weekly close", 240),
+            message("@alice", "BTC breaking out, loading longs", 0),
+            message("@carol", "gm everyone", 120),
+        ];
+        (messages, cache)
+    }
+
+    fn processor(clock: &Arc<ManualClock>, cache: Arc<MemoryCache>, min_confidence: f64, window: i64) -> SignalProcessor {
+        SignalProcessor::new(llm_config())
+            .with_thresholds(min_confidence, 0.6)
+            .with_window(window)
+            .with_clock(clock.clone())
+            .with_extraction_cache(cache)
+            .with_cache_only(true)
+    }
+
+    #[tokio::test]
+    async fn test_replay_aggregates_recorded_messages_from_the_cache() {
+        let (messages, cache) = recorded().await;
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+
+        let report = replay(&processor(&clock, cache, 0.6, 300), &clock, messages).await;
+
+        assert_eq!(report.messages, 3);
+        assert_eq!((report.cache_hits, report.cache_misses), (3, 0));
+        assert_eq!(report.signals.len(), 1);
+        let signal = &report.signals[0];
+        assert_eq!(signal.token, "BTC");
+        assert_eq!(signal.direction, SignalDirection::Bullish);
+        assert_eq!(signal.sources.len(), 2);
+        // Timestamped by the recorded stream, not the wall clock
+        assert_eq!(signal.timestamp, start() + Duration::seconds(240));
+    }
+
+    #[tokio::test]
+    async fn test_processor_counts_llm_calls_and_contributions_per_source() {
+        let (messages, cache) = recorded().await;
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let stats = Arc::new(IngestStats::new());
+        let processor = processor(&clock, cache, 0.6, 300).with_cache_only(false).with_ingest_stats(stats.clone());
+
+        let mut messages = messages;
+        messages.push(message("@dave", "never seen before", 60));
+        replay(&processor, &clock, messages).await;
+
+        let telegram = &stats.snapshot()[0];
+        assert_eq!(telegram.source, "telegram");
+        // Only the uncached message went to the (unreachable) LLM
+        assert_eq!(telegram.sent_to_llm, 1);
+        assert_eq!(telegram.contributed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_replay_with_other_parameters() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+
+        // A window shorter than the gap between the two calls
+        let (messages, cache) = recorded().await;
+        assert!(replay(&processor(&clock, cache, 0.6, 120), &clock, messages).await.signals.is_empty());
+
+        // A confidence floor above the second call
+        let (messages, cache) = recorded().await;
+        assert!(replay(&processor(&clock, cache, 0.68, 300), &clock, messages).await.signals.is_empty());
+
+        // Uncached messages are skipped, not sent to the LLM
+        let (mut messages, cache) = recorded().await;
+        messages.push(message("@dave", "never seen before", 60));
+        let report = replay(&processor(&clock, cache, 0.6, 300), &clock, messages).await;
+        assert_eq!((report.cache_hits, report.cache_misses), (3, 1));
+        assert_eq!(report.signals.len(), 1);
+    }
+
+    #[test]
+    fn test_market_move_over_the_horizon() {
+        let snapshot = |market_id: &str, price: Decimal, hours: i64| PriceSnapshot {
+            market_id: market_id.to_string(),
+            yes_price: price,
+            taken_at: start() + Duration::hours(hours),
+        };
+        let snapshots = vec![
+            snapshot("m1", dec!(0.40), -1),
+            snapshot("m1", dec!(0.42), 1),
+            snapshot("m2", dec!(0.90), 2),
+            snapshot("m1", dec!(0.47), 20),
+            snapshot("m1", dec!(0.60), 30),
+        ];
+
+        let moved = market_move(&snapshots, "m1", start(), Duration::hours(24)).unwrap();
+        assert_eq!((moved.price_before, moved.price_after), (dec!(0.42), dec!(0.47)));
+        assert_eq!(moved.change(), dec!(0.05));
+        assert!(market_move(&snapshots, "m2", start(), Duration::hours(24)).is_none());
+    }
+}
@@ -0,0 +1,271 @@
+//! Per-source ingestion counters
+//!
+//! Each [`SignalSource`](super::SignalSource) counts the messages it
+//! receives and lets through its own filters via a [`SourceStatsHandle`];
+//! the [`SignalProcessor`](super::processor::SignalProcessor) counts, by
+//! [`RawSignal::source`](super::RawSignal), the messages it sends to the LLM
+//! and those that end up in an aggregated signal. Together they show which
+//! sources are actually producing anything.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Counters of one source since it was first seen
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub source: String,
+    /// New messages fetched
+    pub received: u64,
+    /// Messages through the source's own keyword filter
+    pub passed_prefilter: u64,
+    /// Messages the processor asked the LLM to extract a signal from
+    pub sent_to_llm: u64,
+    /// Messages among the sources of an aggregated signal
+    pub contributed: u64,
+    /// Failed fetches
+    pub errors: u64,
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct Tracked {
+    stats: SourceStats,
+    /// Last message, or when tracking started if later
+    heard_from_at: DateTime<Utc>,
+    /// Whether the current silence has been warned about
+    stale_warned: bool,
+}
+
+/// Counters of every source, shared between the sources, the processor and
+/// whoever reports on them
+#[derive(Debug, Default)]
+pub struct IngestStats {
+    sources: Mutex<BTreeMap<String, Tracked>>,
+}
+
+impl IngestStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carry on from counters persisted before a restart
+    pub fn restore(stats: Vec<SourceStats>, now: DateTime<Utc>) -> Self {
+        let sources = stats
+            .into_iter()
+            .map(|stats| {
+                let tracked = Tracked { stats, heard_from_at: now, stale_warned: false };
+                (tracked.stats.source.clone(), tracked)
+            })
+            .collect();
+        Self { sources: Mutex::new(sources) }
+    }
+
+    /// Handle through which `source` updates its counters; its silence is
+    /// timed from `now`
+    pub fn handle(self: &Arc<Self>, source: &str, now: DateTime<Utc>) -> SourceStatsHandle {
+        self.update(source, now, |_| {});
+        SourceStatsHandle { stats: self.clone(), source: source.to_string() }
+    }
+
+    fn update(&self, source: &str, now: DateTime<Utc>, f: impl FnOnce(&mut Tracked)) {
+        let mut sources = self.sources.lock();
+        let tracked = sources.entry(source.to_string()).or_insert_with(|| Tracked {
+            stats: SourceStats { source: source.to_string(), ..Default::default() },
+            heard_from_at: now,
+            stale_warned: false,
+        });
+        f(tracked);
+    }
+
+    pub fn record_received(&self, source: &str, at: DateTime<Utc>) {
+        self.update(source, at, |t| {
+            t.stats.received += 1;
+            t.stats.last_message_at = Some(at);
+            t.heard_from_at = at;
+            t.stale_warned = false;
+        });
+    }
+
+    pub fn record_passed_prefilter(&self, source: &str, now: DateTime<Utc>) {
+        self.update(source, now, |t| t.stats.passed_prefilter += 1);
+    }
+
+    pub fn record_sent_to_llm(&self, source: &str, now: DateTime<Utc>) {
+        self.update(source, now, |t| t.stats.sent_to_llm += 1);
+    }
+
+    pub fn record_contributed(&self, source: &str, now: DateTime<Utc>) {
+        self.update(source, now, |t| t.stats.contributed += 1);
+    }
+
+    pub fn record_error(&self, source: &str, now: DateTime<Utc>) {
+        self.update(source, now, |t| t.stats.errors += 1);
+    }
+
+    /// Every source's counters, by name
+    pub fn snapshot(&self) -> Vec<SourceStats> {
+        self.sources.lock().values().map(|t| t.stats.clone()).collect()
+    }
+
+    /// Sources silent for longer than `threshold` that have not been warned
+    /// about yet; each silence is reported once, until messages arrive again
+    pub fn newly_stale(&self, threshold: Duration, now: DateTime<Utc>) -> Vec<SourceStats> {
+        let mut sources = self.sources.lock();
+        sources
+            .values_mut()
+            .filter(|t| !t.stale_warned && now - t.heard_from_at > threshold)
+            .map(|t| {
+                t.stale_warned = true;
+                t.stats.clone()
+            })
+            .collect()
+    }
+
+    /// Counters and last message times in Prometheus text format
+    pub fn prometheus_counters(&self) -> String {
+        let messages = "polymarket_bot_ingest_messages_total";
+        let errors = "polymarket_bot_ingest_errors_total";
+        let last = "polymarket_bot_ingest_last_message_timestamp_seconds";
+        let stats = self.snapshot();
+        let mut text = format!(
+            "# HELP {messages} Ingested messages by source and pipeline stage\n# TYPE {messages} counter\n"
+        );
+        for s in &stats {
+            for (stage, count) in [
+                ("received", s.received),
+                ("passed_prefilter", s.passed_prefilter),
+                ("sent_to_llm", s.sent_to_llm),
+                ("contributed", s.contributed),
+            ] {
+                text += &format!("{messages}{{source=\"{}\",stage=\"{stage}\"}} {count}\n", s.source);
+            }
+        }
+        text += &format!("# HELP {errors} Failed fetches by source\n# TYPE {errors} counter\n");
+        for s in &stats {
+            text += &format!("{errors}{{source=\"{}\"}} {}\n", s.source, s.errors);
+        }
+        text += &format!("# HELP {last} When a source last received a message\n# TYPE {last} gauge\n");
+        for s in &stats {
+            if let Some(at) = s.last_message_at {
+                text += &format!("{last}{{source=\"{}\"}} {}\n", s.source, at.timestamp());
+            }
+        }
+        text
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::monitor::MetricsSource for IngestStats {
+    async fn prometheus_text(&self) -> String {
+        self.prometheus_counters()
+    }
+}
+
+/// One source's view of the [`IngestStats`]
+#[derive(Debug, Clone)]
+pub struct SourceStatsHandle {
+    stats: Arc<IngestStats>,
+    source: String,
+}
+
+impl SourceStatsHandle {
+    pub fn received(&self) {
+        self.stats.record_received(&self.source, Utc::now());
+    }
+
+    pub fn passed_prefilter(&self) {
+        self.stats.record_passed_prefilter(&self.source, Utc::now());
+    }
+
+    pub fn error(&self) {
+        self.stats.record_error(&self.source, Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 3, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_counts_by_source() {
+        let stats = Arc::new(IngestStats::new());
+        stats.handle("twitter_rss", at(0));
+        stats.record_received("telegram", at(1));
+        stats.record_passed_prefilter("telegram", at(1));
+        stats.record_sent_to_llm("telegram", at(1));
+        stats.record_received("telegram", at(2));
+        stats.record_contributed("telegram", at(2));
+        stats.record_error("twitter_rss", at(3));
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(
+            snapshot[0],
+            SourceStats {
+                source: "telegram".to_string(),
+                received: 2,
+                passed_prefilter: 1,
+                sent_to_llm: 1,
+                contributed: 1,
+                errors: 0,
+                last_message_at: Some(at(2)),
+            }
+        );
+        assert_eq!(snapshot[1].errors, 1);
+        assert_eq!(snapshot[1].last_message_at, None);
+
+        let metrics = stats.prometheus_counters();
+        assert!(metrics.contains("polymarket_bot_ingest_messages_total{source=\"telegram\",stage=\"received\"} 2\n"));
+        assert!(metrics.contains("polymarket_bot_ingest_errors_total{source=\"twitter_rss\"} 1\n"));
+        assert!(metrics.contains(&format!(
+            "polymarket_bot_ingest_last_message_timestamp_seconds{{source=\"telegram\"}} {}\n",
+            at(2).timestamp()
+        )));
+        assert!(!metrics.contains("last_message_timestamp_seconds{source=\"twitter_rss\"}"));
+    }
+
+    #[test]
+    fn test_staleness_is_warned_once_per_silence() {
+        let stats = Arc::new(IngestStats::new());
+        stats.handle("twitter_rss", at(0));
+        stats.record_received("telegram", at(0));
+        let threshold = Duration::minutes(60);
+
+        assert!(stats.newly_stale(threshold, at(60)).is_empty());
+        stats.record_received("telegram", at(50));
+        let stale = stats.newly_stale(threshold, at(61));
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].source, "twitter_rss");
+        assert!(stats.newly_stale(threshold, at(100)).is_empty());
+
+        // Telegram goes quiet too; the RSS source is warned about again
+        // only after it has recovered and gone quiet once more
+        assert_eq!(stats.newly_stale(threshold, at(111))[0].source, "telegram");
+        stats.record_received("twitter_rss", at(130));
+        assert!(stats.newly_stale(threshold, at(150)).is_empty());
+        assert_eq!(stats.newly_stale(threshold, at(191))[0].source, "twitter_rss");
+    }
+
+    #[test]
+    fn test_restored_sources_are_timed_from_the_restart() {
+        let persisted = vec![SourceStats {
+            source: "telegram".to_string(),
+            received: 40,
+            last_message_at: Some(at(-600)),
+            ..Default::default()
+        }];
+        let stats = IngestStats::restore(persisted, at(0));
+        assert!(stats.newly_stale(Duration::minutes(60), at(30)).is_empty());
+        stats.record_received("telegram", at(31));
+        assert_eq!(stats.snapshot()[0].received, 41);
+        assert_eq!(stats.newly_stale(Duration::minutes(60), at(92)).len(), 1);
+    }
+}
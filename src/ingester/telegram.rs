@@ -3,10 +3,13 @@
 //! Uses grammers (MTProto) to monitor Telegram groups for trading signals.
 //! Requires user authentication (not bot API) to access group messages.
 
-use super::{RawSignal, SignalSource, TelegramIngesterConfig};
+use super::{RawSignal, SignalSource, SourceStatsHandle, TelegramIngesterConfig};
 use crate::error::Result;
+use crate::monitor::Heartbeat;
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use async_trait::async_trait;
 use chrono::Utc;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 /// Telegram group monitor
@@ -86,6 +89,9 @@ pub struct TelegramBotSource {
     bot_token: String,
     channel_usernames: Vec<String>,
     http: reqwest::Client,
+    /// Beaten on every poll, for the watchdog
+    heartbeat: Option<Arc<Heartbeat>>,
+    stats: Option<SourceStatsHandle>,
 }
 
 impl TelegramBotSource {
@@ -93,9 +99,21 @@ impl TelegramBotSource {
         Self {
             bot_token,
             channel_usernames,
-            http: reqwest::Client::new(),
+            http: http_client(HTTP_TIMEOUT),
+            heartbeat: None,
+            stats: None,
         }
     }
+
+    pub fn with_heartbeat(mut self, heartbeat: Arc<Heartbeat>) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub fn with_stats(mut self, stats: SourceStatsHandle) -> Self {
+        self.stats = Some(stats);
+        self
+    }
 }
 
 #[async_trait]
@@ -115,6 +133,9 @@ impl SignalSource for TelegramBotSource {
 
         loop {
             interval.tick().await;
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
 
             let url = format!(
                 "https://api.telegram.org/bot{}/getUpdates?offset={}&timeout=30",
@@ -122,7 +143,9 @@ impl SignalSource for TelegramBotSource {
                 last_update_id + 1
             );
 
-            match self.http.get(&url).send().await {
+            // Long poll: allow for the 30s Telegram holds the request open
+            let request = self.http.get(&url).timeout(std::time::Duration::from_secs(30) + HTTP_TIMEOUT);
+            match request.send().await {
                 Ok(resp) => {
                     if let Ok(data) = resp.json::<serde_json::Value>().await {
                         if let Some(updates) = data["result"].as_array() {
@@ -136,6 +159,11 @@ impl SignalSource for TelegramBotSource {
                                     if let Some(text) = post["text"].as_str() {
                                         let chat_id = post["chat"]["id"].as_i64().unwrap_or(0);
                                         let msg_id = post["message_id"].as_i64().unwrap_or(0);
+                                        // Channel posts are not filtered
+                                        if let Some(stats) = &self.stats {
+                                            stats.received();
+                                            stats.passed_prefilter();
+                                        }
 
                                         let signal = RawSignal {
                                             source: "telegram".to_string(),
@@ -151,6 +179,7 @@ impl SignalSource for TelegramBotSource {
                                                 "chat_id": chat_id,
                                                 "message_id": msg_id
                                             })),
+                                            trace_id: Default::default(),
                                         };
 
                                         if tx.send(signal).await.is_err() {
@@ -164,6 +193,9 @@ impl SignalSource for TelegramBotSource {
                     }
                 }
                 Err(e) => {
+                    if let Some(stats) = &self.stats {
+                        stats.error();
+                    }
                     tracing::warn!("Telegram API error: {}", e);
                 }
             }
@@ -15,6 +15,7 @@ mod tests {
             author_trust: 0.7,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
         
         assert_eq!(signal.source, "twitter");
@@ -36,6 +37,7 @@ mod tests {
             author_trust: 0.8,
             timestamp: Utc::now(),
             metadata: Some(metadata),
+            trace_id: Default::default(),
         };
         
         let meta = signal.metadata.unwrap();
@@ -119,6 +121,7 @@ mod tests {
             author_trust: 0.8,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
 
         let parsed = ParsedSignal {
@@ -131,6 +134,7 @@ mod tests {
             sources: vec![raw],
             agg_score: 0.8,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
 
         assert_eq!(parsed.token, "ETH");
@@ -148,6 +152,7 @@ mod tests {
             author_trust: 0.8,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
 
         let raw2 = RawSignal {
@@ -158,6 +163,7 @@ mod tests {
             author_trust: 0.7,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
 
         let parsed = ParsedSignal {
@@ -170,6 +176,7 @@ mod tests {
             sources: vec![raw1, raw2],
             agg_score: 0.9,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
 
         assert_eq!(parsed.sources.len(), 2);
@@ -188,6 +195,7 @@ mod tests {
             sources: vec![],
             agg_score: 0.65,
             timestamp: Utc::now(),
+            trace_id: Default::default(),
         };
 
         assert_eq!(parsed.direction, SignalDirection::Bearish);
@@ -231,6 +239,7 @@ mod tests {
             author_trust: 0.5,
             timestamp: Utc::now(),
             metadata: None,
+            trace_id: Default::default(),
         };
         
         let cloned = signal.clone();
@@ -251,4 +260,54 @@ mod tests {
         let cloned = action.clone();
         assert_eq!(action, cloned);
     }
+
+    #[test]
+    fn test_position_news_keywords() {
+        use super::super::portfolio_news::extract_keywords;
+
+        assert_eq!(
+            extract_keywords("Will Donald Trump win the 2028 Republican presidential nomination?"),
+            vec!["Donald Trump", "Republican"]
+        );
+        assert_eq!(
+            extract_keywords("Will the Federal Reserve cut rates in June?"),
+            vec!["Federal Reserve"]
+        );
+        assert_eq!(
+            extract_keywords("Bitcoin above $100k on December 31? Will the NBA Finals go to Game 7?"),
+            vec!["NBA Finals", "Bitcoin", "Game"]
+        );
+        assert!(extract_keywords("will it rain tomorrow?").is_empty());
+    }
+
+    #[test]
+    fn test_position_news_matching() {
+        use super::super::news::{search_query, NewsArticle};
+        use super::super::portfolio_news::WatchedMarket;
+
+        let watched = WatchedMarket {
+            market_id: "m1".to_string(),
+            question: "Will the Fed cut rates?".to_string(),
+            keywords: vec!["Fed".to_string(), "Jerome Powell".to_string()],
+        };
+        let article = |title: &str| NewsArticle {
+            title: title.to_string(),
+            description: None,
+            url: "https://example.com".to_string(),
+            source: "Reuters".to_string(),
+            published_at: Utc::now(),
+        };
+        assert!(watched.matches(&article("Fed signals a surprise cut")));
+        assert!(watched.matches(&article("JEROME POWELL: inflation is beaten")));
+        // Whole words only
+        assert!(!watched.matches(&article("Federal budget talks stall")));
+
+        assert_eq!(
+            search_query(&watched.keywords).as_deref(),
+            Some("Fed OR \"Jerome Powell\"")
+        );
+        assert_eq!(search_query(&[]), None);
+        let many: Vec<String> = (0..200).map(|i| format!("Keyword{}", i)).collect();
+        assert!(search_query(&many).unwrap().len() <= 500);
+    }
 }
@@ -0,0 +1,320 @@
+//! Author trust learned from signal outcomes
+//!
+//! An author's trust is their hit rate smoothed toward a prior:
+//!
+//! `trust = (prior * prior_weight + Σ wᵢ·hitᵢ) / (prior_weight + Σ wᵢ)`
+//!
+//! where each outcome's weight `wᵢ` halves every `half_life_days`, so recent
+//! calls count most and a new author starts at the prior. The prior is the
+//! author's configured `author_trust` entry, or `prior_trust` for unknown
+//! authors. Pinned authors keep a fixed trust and are never muted.
+
+use super::{ParsedSignal, SignalDirection};
+use crate::config::AuthorTrustConfig;
+use crate::error::Result;
+use crate::storage::{Database, SignalOutcome};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+/// An author's current trust
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorStanding {
+    pub author: String,
+    pub trust: f64,
+    /// Resolved outcomes behind the score
+    pub samples: usize,
+    pub muted: bool,
+    pub pinned: bool,
+}
+
+/// Shared, periodically recomputed trust scores
+pub struct AuthorTrust {
+    config: AuthorTrustConfig,
+    /// Configured per-author priors
+    priors: HashMap<String, f64>,
+    learned: RwLock<HashMap<String, AuthorStanding>>,
+}
+
+impl AuthorTrust {
+    pub fn new(config: AuthorTrustConfig, priors: HashMap<String, f64>) -> Self {
+        Self {
+            config,
+            priors,
+            learned: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn prior(&self, author: &str) -> f64 {
+        self.priors.get(author).copied().unwrap_or(self.config.prior_trust)
+    }
+
+    /// Trust to weight an author's messages by
+    pub fn trust(&self, author: &str) -> f64 {
+        if let Some(&pinned) = self.config.pinned.get(author) {
+            return pinned;
+        }
+        self.learned
+            .read()
+            .get(author)
+            .map(|s| s.trust)
+            .unwrap_or_else(|| self.prior(author))
+    }
+
+    /// Whether an author's messages are currently ignored
+    pub fn is_muted(&self, author: &str) -> bool {
+        self.learned.read().get(author).is_some_and(|s| s.muted)
+    }
+
+    /// Rescore every author from their resolved outcomes; returns the
+    /// authors muted by this update
+    pub fn recompute(&self, outcomes: &[SignalOutcome], now: DateTime<Utc>) -> Vec<AuthorStanding> {
+        // author -> (Σ w·hit, Σ w, samples)
+        let mut tallies: HashMap<&str, (f64, f64, usize)> = HashMap::new();
+        for outcome in outcomes {
+            let Some(hit) = outcome.hit else { continue };
+            let age_days = (now - outcome.signal_at).num_seconds().max(0) as f64 / 86_400.0;
+            let weight = 0.5_f64.powf(age_days / self.config.half_life_days);
+            let tally = tallies.entry(outcome.author.as_str()).or_default();
+            tally.0 += if hit { weight } else { 0.0 };
+            tally.1 += weight;
+            tally.2 += 1;
+        }
+
+        let mut learned = self.learned.write();
+        let mut newly_muted = Vec::new();
+        for (author, (hits, weight, samples)) in tallies {
+            let pinned = self.config.pinned.contains_key(author);
+            let prior = self.prior(author);
+            let trust = (prior * self.config.prior_weight + hits) / (self.config.prior_weight + weight);
+            let muted = !pinned && samples >= self.config.mute_min_samples && trust < self.config.mute_below;
+
+            let was_muted = learned.get(author).is_some_and(|s| s.muted);
+            let standing = AuthorStanding {
+                author: author.to_string(),
+                trust: self.config.pinned.get(author).copied().unwrap_or(trust),
+                samples,
+                muted,
+                pinned,
+            };
+            if muted && !was_muted {
+                newly_muted.push(standing.clone());
+            }
+            learned.insert(author.to_string(), standing);
+        }
+        newly_muted.sort_by(|a, b| a.author.cmp(&b.author));
+        newly_muted
+    }
+
+    /// Every known author (learned, configured or pinned), most trusted first
+    pub fn roster(&self) -> Vec<AuthorStanding> {
+        let learned = self.learned.read();
+        let mut roster: HashMap<&str, AuthorStanding> = learned
+            .iter()
+            .map(|(author, standing)| (author.as_str(), standing.clone()))
+            .collect();
+        for author in self.priors.keys().chain(self.config.pinned.keys()) {
+            roster.entry(author.as_str()).or_insert_with(|| AuthorStanding {
+                author: author.clone(),
+                trust: self.trust(author),
+                samples: 0,
+                muted: false,
+                pinned: self.config.pinned.contains_key(author),
+            });
+        }
+
+        let mut roster: Vec<AuthorStanding> = roster.into_values().collect();
+        roster.sort_by(|a, b| b.trust.total_cmp(&a.trust).then_with(|| a.author.cmp(&b.author)));
+        roster
+    }
+}
+
+/// One pending outcome per distinct author behind a directional signal
+pub fn pending_outcomes(signal: &ParsedSignal, entry_price: Decimal, now: DateTime<Utc>) -> Vec<SignalOutcome> {
+    if signal.direction == SignalDirection::Neutral {
+        return Vec::new();
+    }
+    let mut seen = HashSet::new();
+    signal
+        .sources
+        .iter()
+        .filter(|raw| seen.insert(raw.author.as_str()))
+        .map(|raw| {
+            SignalOutcome::pending(&raw.author, &signal.token, signal.direction, &signal.timeframe, entry_price, now)
+        })
+        .collect()
+}
+
+/// Resolve outcomes whose timeframe has ended at the current `price` of
+/// their token, then rescore every author. Returns newly muted authors.
+pub async fn refresh_author_trust(
+    db: &Database,
+    trust: &AuthorTrust,
+    price: impl Fn(&str) -> Option<Decimal>,
+    now: DateTime<Utc>,
+) -> Result<Vec<AuthorStanding>> {
+    for mut outcome in db.get_due_signal_outcomes(now).await? {
+        // Tokens without a price stay pending until one is available
+        let Some(exit_price) = price(&outcome.token) else { continue };
+        outcome.resolve(exit_price);
+        db.resolve_signal_outcome(&outcome).await?;
+    }
+
+    // Older outcomes weigh under 0.1%
+    let horizon = chrono::Duration::seconds((trust.config.half_life_days * 10.0 * 86_400.0) as i64);
+    let outcomes = db.get_resolved_signal_outcomes(now - horizon).await?;
+    Ok(trust.recompute(&outcomes, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingester::SignalDirection;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn outcome(author: &str, hit: bool, age_days: i64, now: DateTime<Utc>) -> SignalOutcome {
+        let mut o = SignalOutcome::pending(author, "BTC", SignalDirection::Bullish, "1h", dec!(100), now - Duration::days(age_days));
+        o.resolve(if hit { dec!(101) } else { dec!(99) });
+        o
+    }
+
+    fn config() -> AuthorTrustConfig {
+        AuthorTrustConfig {
+            prior_trust: 0.5,
+            prior_weight: 10.0,
+            half_life_days: 14.0,
+            mute_below: 0.35,
+            mute_min_samples: 20,
+            ..AuthorTrustConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_new_author_starts_at_prior() {
+        let trust = AuthorTrust::new(config(), HashMap::from([("known".to_string(), 0.8)]));
+        assert_eq!(trust.trust("stranger"), 0.5);
+        assert_eq!(trust.trust("known"), 0.8);
+    }
+
+    #[test]
+    fn test_hit_rate_is_smoothed_toward_prior() {
+        let now = Utc::now();
+        let trust = AuthorTrust::new(config(), HashMap::new());
+        // 10 fresh hits against a prior worth 10 observations at 0.5
+        let outcomes: Vec<_> = (0..10).map(|_| outcome("alice", true, 0, now)).collect();
+        trust.recompute(&outcomes, now);
+        assert!((trust.trust("alice") - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_old_outcomes_decay() {
+        let now = Utc::now();
+        let trust = AuthorTrust::new(config(), HashMap::new());
+        // Old misses, recent hits: recent form dominates
+        let mut outcomes: Vec<_> = (0..10).map(|_| outcome("alice", false, 56, now)).collect();
+        outcomes.extend((0..10).map(|_| outcome("alice", true, 0, now)));
+        trust.recompute(&outcomes, now);
+        // Misses at four half-lives weigh 1/16 each
+        let expected = (5.0 + 10.0) / (10.0 + 10.0 + 10.0 / 16.0);
+        assert!((trust.trust("alice") - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_poor_author_is_muted_once() {
+        let now = Utc::now();
+        let trust = AuthorTrust::new(config(), HashMap::new());
+
+        // Not enough evidence yet
+        let few: Vec<_> = (0..10).map(|_| outcome("bob", false, 0, now)).collect();
+        assert!(trust.recompute(&few, now).is_empty());
+        assert!(!trust.is_muted("bob"));
+
+        let many: Vec<_> = (0..30).map(|_| outcome("bob", false, 0, now)).collect();
+        let muted = trust.recompute(&many, now);
+        assert_eq!(muted.len(), 1);
+        assert_eq!(muted[0].author, "bob");
+        assert!(trust.is_muted("bob"));
+
+        // Already muted: no repeat notification
+        assert!(trust.recompute(&many, now).is_empty());
+    }
+
+    #[test]
+    fn test_pinned_author_is_fixed() {
+        let now = Utc::now();
+        let mut config = config();
+        config.pinned.insert("carol".to_string(), 0.9);
+        let trust = AuthorTrust::new(config, HashMap::new());
+
+        let misses: Vec<_> = (0..30).map(|_| outcome("carol", false, 0, now)).collect();
+        assert!(trust.recompute(&misses, now).is_empty());
+        assert_eq!(trust.trust("carol"), 0.9);
+        assert!(!trust.is_muted("carol"));
+
+        let roster = trust.roster();
+        assert_eq!(roster[0].author, "carol");
+        assert!(roster[0].pinned);
+        assert_eq!(roster[0].samples, 30);
+    }
+    #[test]
+    fn test_pending_outcomes_one_per_author() {
+        use crate::ingester::{ActionType, RawSignal};
+
+        let raw = |author: &str| RawSignal {
+            source: "twitter".to_string(),
+            source_id: "1".to_string(),
+            content: String::new(),
+            author: author.to_string(),
+            author_trust: 0.5,
+            timestamp: Utc::now(),
+            metadata: None,
+            trace_id: Default::default(),
+        };
+        let mut signal = ParsedSignal {
+            token: "ETH".to_string(),
+            direction: SignalDirection::Bearish,
+            timeframe: "4h".to_string(),
+            confidence: 0.8,
+            reasoning: String::new(),
+            action_type: ActionType::Entry,
+            sources: vec![raw("a"), raw("b"), raw("a")],
+            agg_score: 0.9,
+            timestamp: Utc::now(),
+            trace_id: Default::default(),
+        };
+
+        let now = Utc::now();
+        let outcomes = pending_outcomes(&signal, dec!(3000), now);
+        let authors: Vec<&str> = outcomes.iter().map(|o| o.author.as_str()).collect();
+        assert_eq!(authors, ["a", "b"]);
+        assert_eq!(outcomes[0].resolve_at, now + Duration::hours(4));
+
+        signal.direction = SignalDirection::Neutral;
+        assert!(pending_outcomes(&signal, dec!(3000), now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_resolves_due_outcomes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let now = Utc::now();
+        let trust = AuthorTrust::new(config(), HashMap::new());
+
+        let called = |author: &str, token: &str| {
+            SignalOutcome::pending(author, token, SignalDirection::Bullish, "1h", dec!(100), now - Duration::hours(2))
+        };
+        db.record_signal_outcome(&called("alice", "BTC")).await.unwrap();
+        db.record_signal_outcome(&called("bob", "DOGE")).await.unwrap();
+
+        let price = |token: &str| (token == "BTC").then_some(dec!(105));
+        refresh_author_trust(&db, &trust, price, now).await.unwrap();
+
+        // One (two-hour-old) hit on top of a 0.5 prior worth 10
+        assert!((trust.trust("alice") - 6.0 / 11.0).abs() < 1e-3);
+        // No DOGE price: still pending, still at the prior
+        assert_eq!(trust.trust("bob"), 0.5);
+        assert_eq!(db.get_due_signal_outcomes(now).await.unwrap().len(), 1);
+    }
+}
@@ -3,11 +3,14 @@
 //! Monitors KOL accounts for trading signals.
 //! Supports both API v2 and RSS fallback.
 
-use super::{RawSignal, SignalSource, TwitterIngesterConfig};
+use super::{RawSignal, SignalSource, SourceStatsHandle, TwitterIngesterConfig};
 use crate::error::Result;
+use crate::monitor::Heartbeat;
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use async_trait::async_trait;
 use chrono::Utc;
 use serde::Deserialize;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 /// Twitter API v2 source
@@ -15,6 +18,9 @@ pub struct TwitterSource {
     config: TwitterIngesterConfig,
     http: reqwest::Client,
     author_trust: std::collections::HashMap<String, f64>,
+    /// Beaten on every poll, for the watchdog
+    heartbeat: Option<Arc<Heartbeat>>,
+    stats: Option<SourceStatsHandle>,
 }
 
 impl TwitterSource {
@@ -24,11 +30,23 @@ impl TwitterSource {
     ) -> Self {
         Self {
             config,
-            http: reqwest::Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             author_trust,
+            heartbeat: None,
+            stats: None,
         }
     }
 
+    pub fn with_heartbeat(mut self, heartbeat: Arc<Heartbeat>) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub fn with_stats(mut self, stats: SourceStatsHandle) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
     fn get_trust(&self, author: &str) -> f64 {
         self.author_trust.get(author).copied().unwrap_or(0.3)
     }
@@ -83,6 +101,9 @@ impl SignalSource for TwitterSource {
 
         loop {
             interval.tick().await;
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
 
             for user_id in &self.config.watch_users {
                 match self.fetch_user_tweets(user_id).await {
@@ -93,6 +114,9 @@ impl SignalSource for TwitterSource {
                                 continue;
                             }
                             seen_ids.insert(tweet.id.clone());
+                            if let Some(stats) = &self.stats {
+                                stats.received();
+                            }
 
                             // Filter by keywords if configured
                             if !self.config.keywords.is_empty() {
@@ -104,6 +128,9 @@ impl SignalSource for TwitterSource {
                                     continue;
                                 }
                             }
+                            if let Some(stats) = &self.stats {
+                                stats.passed_prefilter();
+                            }
 
                             let author = tweet.author_id.clone().unwrap_or_else(|| user_id.clone());
                             let signal = RawSignal {
@@ -117,6 +144,7 @@ impl SignalSource for TwitterSource {
                                     "user_id": user_id,
                                     "created_at": tweet.created_at
                                 })),
+                                trace_id: Default::default(),
                             };
 
                             if tx.send(signal).await.is_err() {
@@ -126,6 +154,9 @@ impl SignalSource for TwitterSource {
                         }
                     }
                     Err(e) => {
+                        if let Some(stats) = &self.stats {
+                            stats.error();
+                        }
                         tracing::warn!("Failed to fetch tweets for {}: {}", user_id, e);
                     }
                 }
@@ -145,6 +176,9 @@ pub struct TwitterRssSource {
     usernames: Vec<String>,
     keywords: Vec<String>,
     http: reqwest::Client,
+    /// Beaten on every poll, for the watchdog
+    heartbeat: Option<Arc<Heartbeat>>,
+    stats: Option<SourceStatsHandle>,
 }
 
 impl TwitterRssSource {
@@ -153,9 +187,21 @@ impl TwitterRssSource {
             nitter_instance,
             usernames,
             keywords,
-            http: reqwest::Client::new(),
+            http: http_client(HTTP_TIMEOUT),
+            heartbeat: None,
+            stats: None,
         }
     }
+
+    pub fn with_heartbeat(mut self, heartbeat: Arc<Heartbeat>) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub fn with_stats(mut self, stats: SourceStatsHandle) -> Self {
+        self.stats = Some(stats);
+        self
+    }
 }
 
 #[async_trait]
@@ -176,6 +222,9 @@ impl SignalSource for TwitterRssSource {
 
         loop {
             interval.tick().await;
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
 
             for username in &self.usernames {
                 let url = format!("{}/{}/rss", self.nitter_instance, username);
@@ -190,6 +239,9 @@ impl SignalSource for TwitterRssSource {
                                     continue;
                                 }
                                 seen_ids.insert(item.guid.clone());
+                                if let Some(stats) = &self.stats {
+                                    stats.received();
+                                }
 
                                 // Keyword filter
                                 if !self.keywords.is_empty() {
@@ -201,9 +253,12 @@ impl SignalSource for TwitterRssSource {
                                         continue;
                                     }
                                 }
+                                if let Some(stats) = &self.stats {
+                                    stats.passed_prefilter();
+                                }
 
                                 let signal = RawSignal {
-                                    source: "twitter".to_string(),
+                                    source: "twitter_rss".to_string(),
                                     source_id: item.guid,
                                     content: item.description,
                                     author: username.clone(),
@@ -213,6 +268,7 @@ impl SignalSource for TwitterRssSource {
                                         "username": username,
                                         "link": item.link
                                     })),
+                                    trace_id: Default::default(),
                                 };
 
                                 if tx.send(signal).await.is_err() {
@@ -222,6 +278,9 @@ impl SignalSource for TwitterRssSource {
                         }
                     }
                     Err(e) => {
+                        if let Some(stats) = &self.stats {
+                            stats.error();
+                        }
                         tracing::warn!("Failed to fetch RSS for {}: {}", username, e);
                     }
                 }
@@ -3,6 +3,7 @@
 //! Monitors large traders' on-chain activity and positions.
 
 use crate::error::Result;
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rust_decimal::Decimal;
@@ -103,7 +104,7 @@ impl Default for WhaleTracker {
 impl WhaleTracker {
     pub fn new() -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             whales: Arc::new(RwLock::new(HashMap::new())),
             change_threshold: Decimal::new(100, 0),       // $100
             large_trade_threshold: Decimal::new(1000, 0), // $1000
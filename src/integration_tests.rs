@@ -6,7 +6,7 @@ mod tests {
         Config, PolymarketConfig, StrategyConfig, RiskConfig, DatabaseConfig,
         LlmConfig, TelegramConfig, CopyTradeConfig,
     };
-    use crate::types::{Market, Outcome, Signal, Side, Order, OrderType, Trade, Position, OrderStatus};
+    use crate::types::{Market, Outcome, Signal, Side, Order, OrderType, Trade, TradeStatus, Position, OrderStatus};
     use crate::model::Prediction;
     use crate::strategy::SignalGenerator;
     use crate::analysis::{TradeAnalyzer, TradeRecord, TradeOutcome, TradingPattern};
@@ -24,10 +24,15 @@ mod tests {
             polymarket: PolymarketConfig {
                 clob_url: "https://clob.polymarket.com".to_string(),
                 gamma_url: "https://gamma-api.polymarket.com".to_string(),
-                private_key: "test_key".to_string(),
+                data_api_url: "https://data-api.polymarket.com".to_string(),
+                private_key: std::sync::Arc::new("test_key".into()),
+                key_source: Default::default(),
+                key_file: None,
+                keyring_service: "polymarket-bot".to_string(),
                 funder_address: None,
                 chain_id: 137,
                 signature_type: 0,
+                accounts: Vec::new(),
             },
             strategy: StrategyConfig::default(),
             risk: RiskConfig::default(),
@@ -37,7 +42,17 @@ mod tests {
                 api_key: "sk-test".to_string(),
                 model: None,
                 base_url: None,
+                failure_threshold: 3,
+                breaker_cooldown_secs: 300,
+                input_cost_per_mtok: None,
+                output_cost_per_mtok: None,
+                prompts: Default::default(),
+                fallback: Vec::new(),
+                provider_timeout_secs: 30,
+                max_concurrent: None,
+                batch_size: None,
             }),
+            slack: None,
             telegram: Some(TelegramConfig {
                 bot_token: "123:abc".to_string(),
                 chat_id: "12345".to_string(),
@@ -45,9 +60,30 @@ mod tests {
                 notify_trades: true,
                 notify_errors: true,
                 notify_daily: true,
+                mode: Default::default(),
             }),
             ingester: None,
             copy_trade: None,
+            shutdown: None,
+            market_maker: None,
+            resting_orders: None,
+            rebalance: None,
+            simulation: None,
+            markets: None,
+            alerts: None,
+            health: None,
+            fees: None,
+            recovery: None,
+            executor: None,
+            discovery: None,
+            fast_stop: None,
+            events: None,
+            watchdog: None,
+            position_news: None,
+            ramp: None,
+            market_profile: None,
+            redeem: None,
+            metrics: None,
         };
         
         assert_eq!(config.polymarket.chain_id, 137);
@@ -63,6 +99,10 @@ mod tests {
             follow_addresses: vec!["0x123".to_string()],
             copy_ratio: 0.5,
             delay_secs: 30,
+            auto_discover: false,
+            discovery: Default::default(),
+            recalibrate_min_trades: 5,
+            min_leader_weight: 0.3,
         };
         
         assert!(copy_trade.enabled);
@@ -94,12 +134,17 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let prediction = Prediction {
             probability: dec!(0.70),
             confidence: dec!(0.80),
             reasoning: "Strong momentum".to_string(),
+            fallback_used: None,
         };
         
         let generator = SignalGenerator::new(
@@ -127,6 +172,13 @@ mod tests {
             confidence: dec!(0.80),
             suggested_size: dec!(0.05),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         // Convert signal to order
@@ -176,6 +228,12 @@ mod tests {
             size: order_status.filled_size,
             fee: dec!(0.25),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
         
         assert_eq!(trade.size, dec!(100));
@@ -331,4 +389,382 @@ mod tests {
         assert!(pattern.avg_win > pattern.avg_loss);
         assert!(pattern.expected_value > Decimal::ZERO);
     }
+
+    // ========== Order Routing Tests ==========
+
+    #[tokio::test]
+    async fn test_routed_child_becomes_polymarket_order() {
+        use crate::client::mock::MockClobClient;
+        use crate::fees::PolymarketFees;
+        use crate::routing::{
+            OrderType as RouteOrderType, PolymarketVenue, RoutingConfig, Side as RouteSide, SmartOrderRouter,
+            VenueExecutor, POLYMARKET_VENUE_ID,
+        };
+        use std::sync::Arc;
+
+        let clob = MockClobClient::new().with_balance(dec!(1000)).with_order_matching();
+        let state = clob.state();
+        let venue = PolymarketVenue::new(clob, Arc::new(PolymarketFees::default()));
+
+        let mut router = SmartOrderRouter::new(RoutingConfig::default());
+        router.register_venue(venue.venue());
+        router.update_liquidity(venue.liquidity("yes_token").await.unwrap());
+
+        // 100 shares fit inside the 500 offered at the 56c best ask
+        let parent = router.new_parent_order("yes_token", RouteSide::Buy, dec!(100), RouteOrderType::Market);
+        let decision = router.route(&parent);
+        assert_eq!(decision.child_orders.len(), 1);
+        let child = &decision.child_orders[0];
+        assert_eq!(child.venue_id, POLYMARKET_VENUE_ID);
+
+        let feedback = venue.execute(child).await;
+        {
+            let state = state.read().unwrap();
+            let placed = state.orders.last().expect("child should be placed on the CLOB");
+            assert_eq!(placed.token_id, "yes_token");
+            assert_eq!(placed.side, Side::Buy);
+            assert_eq!(placed.size, dec!(100));
+            assert_eq!(placed.price, child.price);
+            assert_eq!(placed.status, "FILLED");
+        }
+        assert!(feedback.success, "{:?}", feedback.error);
+        assert_eq!(feedback.child_id, child.id);
+        assert_eq!(feedback.filled_qty, dec!(100));
+        assert_eq!(feedback.actual_slippage_bps, Decimal::ZERO);
+
+        router.record_feedback(feedback);
+        let metrics = router.get_metrics(POLYMARKET_VENUE_ID).unwrap();
+        assert_eq!((metrics.total_orders, metrics.successful_fills), (1, 1));
+    }
+
+    // ========== Fault Injection Tests ==========
+
+    use crate::client::mock::ClobClientTrait;
+    use crate::executor::Executor;
+    use crate::risk::{RiskCheckResult, RiskManager};
+    use crate::testing::{ChaosInjector, ChaosModel, ChaosPolymarketClient, ErrorType, MockClob, Scenario};
+
+    const CHAOS_TOKENS: [&str; 4] = ["t0", "t1", "t2", "t3"];
+
+    fn chaos_executor(failure_rate: f64, seed: u64) -> Executor<ChaosPolymarketClient<MockClob>> {
+        let clob = Scenario::new().balance(dec!(100000)).default_book(
+            &[(dec!(0.54), dec!(100000))],
+            &[(dec!(0.56), dec!(100000))],
+        );
+        let injector = ChaosInjector::new(
+            failure_rate,
+            0,
+            vec![
+                ErrorType::NetworkTimeout,
+                ErrorType::ApiError(503),
+                ErrorType::DatabaseLock,
+                ErrorType::RateLimited { retry_after_secs: 1 },
+            ],
+        )
+        .with_seed(seed);
+        let risk = RiskConfig {
+            max_open_positions: CHAOS_TOKENS.len(),
+            ..RiskConfig::default()
+        };
+        Executor::new(ChaosPolymarketClient::new(clob.build(), injector), risk)
+    }
+
+    fn chaos_signal(i: usize) -> Signal {
+        let token = CHAOS_TOKENS[i % CHAOS_TOKENS.len()];
+        Signal {
+            market_id: format!("m{}", token),
+            token_id: token.to_string(),
+            side: if i % 3 == 2 { Side::Sell } else { Side::Buy },
+            model_probability: dec!(0.70),
+            market_probability: dec!(0.55),
+            edge: dec!(0.15),
+            confidence: dec!(0.80),
+            suggested_size: dec!(0.005),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    /// Run signals through the executor the way the scan loop does, feeding
+    /// outcomes to the risk manager; returns how many failed
+    async fn run_chaos(
+        executor: &Executor<ChaosPolymarketClient<MockClob>>,
+        risk: &mut RiskManager,
+        signals: usize,
+    ) -> usize {
+        let mut failed = 0;
+        for i in 0..signals {
+            if let RiskCheckResult::Blocked { .. } = risk.can_trade() {
+                continue;
+            }
+            match executor.execute(&chaos_signal(i), dec!(1000)).await {
+                Ok(_) => risk.record_api_success(),
+                Err(e) => {
+                    risk.record_api_failure(&e);
+                    failed += 1;
+                }
+            }
+        }
+        failed
+    }
+
+    /// The executor's book must match what the exchange actually filled
+    async fn assert_positions_match_exchange(executor: &Executor<ChaosPolymarketClient<MockClob>>) {
+        let positions = executor.get_positions().await;
+        for token in CHAOS_TOKENS {
+            let tracked = positions.get(token).copied().unwrap_or(Decimal::ZERO);
+            assert_eq!(tracked, executor.clob.inner.holdings(token), "position in {}", token);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bot_survives_10pct_api_failures() {
+        let executor = chaos_executor(0.10, 7);
+        let mut risk = RiskManager::new(RiskConfig::default());
+
+        let failed = run_chaos(&executor, &mut risk, 200).await;
+
+        assert!(executor.clob.injector.injected() > 0);
+        assert!(failed > 0, "some executions should have failed");
+        assert!(failed < 100, "{} of 200 executions failed", failed);
+        assert!(!executor.clob.inner.fills().is_empty());
+        assert_positions_match_exchange(&executor).await;
+    }
+
+    #[tokio::test]
+    async fn test_bot_pauses_on_consecutive_failures() {
+        let executor = chaos_executor(1.0, 7);
+        let config = RiskConfig::default();
+        let max_failures = config.max_consecutive_api_failures as usize;
+        let mut risk = RiskManager::new(config);
+
+        let failed = run_chaos(&executor, &mut risk, 20).await;
+
+        // Trading stops once the run of failures hits the limit
+        assert_eq!(failed, max_failures);
+        assert!(matches!(risk.can_trade(), RiskCheckResult::Blocked { .. }));
+        assert!(executor.clob.inner.fills().is_empty());
+        assert!(executor.get_positions().await.is_empty());
+
+        // A successful call once the exchange is back resumes trading
+        assert!(executor.clob.inner.get_balance().await.is_ok());
+        risk.record_api_success();
+        assert!(matches!(risk.can_trade(), RiskCheckResult::Allowed));
+    }
+
+    struct FixedModel {
+        name: &'static str,
+        probability: Decimal,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::model::ProbabilityModel for FixedModel {
+        async fn predict(&self, _market: &Market) -> crate::error::Result<Prediction> {
+            Ok(Prediction {
+                probability: self.probability,
+                confidence: dec!(0.8),
+                reasoning: "fixed".to_string(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graceful_degradation_llm_unavailable() {
+        let llm = ChaosModel::new(
+            FixedModel { name: "llm", probability: dec!(0.90) },
+            ChaosInjector::new(1.0, 0, vec![ErrorType::ApiError(503), ErrorType::NetworkTimeout]).with_seed(3),
+        );
+        let mut ensemble = crate::model::EnsembleModel::new();
+        ensemble.add_model(Box::new(llm), dec!(0.7));
+        ensemble.add_model(Box::new(FixedModel { name: "sentiment", probability: dec!(0.60) }), dec!(0.3));
+
+        let market = Market {
+            id: "m1".to_string(),
+            question: "Will it happen?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(5000),
+            outcomes: vec![
+                Outcome { token_id: "t1".to_string(), outcome: "Yes".to_string(), price: dec!(0.5) },
+                Outcome { token_id: "t2".to_string(), outcome: "No".to_string(), price: dec!(0.5) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+
+        // The remaining model carries the prediction on its own
+        let prediction = ensemble.predict(&market).await.unwrap();
+        assert_eq!(prediction.probability, dec!(0.60));
+        assert!(!prediction.reasoning.contains("llm"));
+    }
+
+    // ========== Scripted Outage Tests ==========
+
+    use crate::clock::{Clock, ManualClock};
+    use crate::notify::Notifier;
+    use crate::scanner::{execute_or_rest, fetch_balances, record_execution};
+    use crate::strategy::realtime::{BinanceTradeFeed, RealtimeEngine};
+    use crate::testing::{Fault, FaultScript, FaultyBinanceFeed, FaultyClob, RecordingNotifier};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn outage_start() -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn minutes(n: i64) -> chrono::Duration {
+        chrono::Duration::minutes(n)
+    }
+
+    /// One pass of the scan loop's balance and execution steps, skipping
+    /// the signals whose token is already held
+    async fn scan_once(
+        executor: &Executor<FaultyClob<MockClob>>,
+        risk: &tokio::sync::Mutex<RiskManager>,
+        notifier: &Notifier,
+        signals: &[Signal],
+    ) {
+        let balances = fetch_balances(&[("default", &executor.clob)], risk, Some(notifier)).await;
+        let Some(&balance) = balances.get("default") else {
+            return;
+        };
+        for signal in signals {
+            if let RiskCheckResult::Blocked { .. } = risk.lock().await.can_trade() {
+                return;
+            }
+            if executor.get_positions().await.contains_key(&signal.token_id) {
+                continue;
+            }
+            let execution = execute_or_rest(executor, None, None, signal, balance);
+            let _ = record_execution(execution, risk, Some(notifier)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clob_down_for_10_minutes_mid_position() {
+        let clock = Arc::new(ManualClock::new(outage_start()));
+        // A burst of 502s, then requests that never come back
+        let script = FaultScript::new(clock.clone())
+            .during(Fault::ServerError(502), outage_start() + minutes(5), minutes(5))
+            .during(Fault::Timeout, outage_start() + minutes(10), minutes(5));
+        let clob = Scenario::new().balance(dec!(1000)).default_book(
+            &[(dec!(0.54), dec!(100000))],
+            &[(dec!(0.56), dec!(100000))],
+        );
+        let executor = Executor::new(FaultyClob::new(clob.build(), script), RiskConfig::default());
+        let risk = tokio::sync::Mutex::new(RiskManager::new(RiskConfig::default()));
+        let alerts = Arc::new(RecordingNotifier::new());
+        let notifier = Notifier::disabled().with_backend(alerts.clone());
+
+        let mut alerts_at_recovery = 0;
+        let mut held_during_outage = false;
+        for minute in 0..=20 {
+            // t0 is signalled all along; t1 first shows up mid-outage
+            let mut signals = vec![chaos_signal(0)];
+            if minute >= 7 {
+                signals.push(chaos_signal(1));
+            }
+            scan_once(&executor, &risk, &notifier, &signals).await;
+
+            if minute == 14 {
+                alerts_at_recovery = alerts.errors().len();
+                held_during_outage = executor.get_positions().await.contains_key("t1");
+            }
+            clock.advance(60_000);
+        }
+
+        // One alert per failed scan, and none once the CLOB is back
+        assert_eq!(alerts_at_recovery, 10);
+        assert_eq!(alerts.errors().len(), 10);
+        assert!(alerts.errors().iter().all(|(context, _)| context == "Balance fetch"));
+        assert_eq!(executor.clob.script.injected(), 10);
+
+        // The open position is neither doubled nor lost, and the signal
+        // that arrived during the outage trades once it clears
+        let fills = executor.clob.inner.fills();
+        assert_eq!(fills.iter().filter(|f| f.token_id == "t0").count(), 1);
+        assert_eq!(fills.iter().filter(|f| f.token_id == "t1").count(), 1);
+        assert!(!held_during_outage);
+        assert!(matches!(risk.lock().await.can_trade(), RiskCheckResult::Allowed));
+        let positions = executor.get_positions().await;
+        for token in ["t0", "t1"] {
+            assert_eq!(positions.get(token).copied(), Some(executor.clob.inner.holdings(token)));
+        }
+    }
+
+    fn binance_trade(symbol: &str, price: Decimal, at_millis: u64) -> String {
+        serde_json::json!({
+            "stream": format!("{}@trade", symbol.to_lowercase()),
+            "data": { "e": "trade", "s": symbol, "p": price.to_string(), "T": at_millis },
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_binance_feed_returns_garbage_ticks() {
+        let clock = Arc::new(ManualClock::new(outage_start()));
+        let script = FaultScript::new(clock.clone())
+            .during(Fault::MalformedJson, outage_start() + minutes(5), minutes(5));
+        let feed = FaultyBinanceFeed::new(script);
+        let (signal_tx, _signal_rx) = tokio::sync::mpsc::channel(16);
+        let engine = Arc::new(RealtimeEngine::new(signal_tx));
+        let alerts = Arc::new(RecordingNotifier::new());
+        let notifier = Notifier::disabled().with_backend(alerts.clone());
+        let mut trade_feed = BinanceTradeFeed::new(engine.clone()).with_notifier(Arc::new(notifier));
+
+        // A tick every 10 seconds for 15 minutes, rising a dollar a second
+        let mut last_good = Decimal::ZERO;
+        for second in (0..15 * 60).step_by(10) {
+            let price = dec!(60000) + Decimal::from(second);
+            let sent = binance_trade("BTCUSDT", price, clock.now_millis());
+            let received = feed.deliver(&sent).expect("garbage, not dropped");
+            if let Some((_, price)) = trade_feed.handle_message(&received).await {
+                last_good = price;
+            }
+
+            // Garbage never reaches the engine's price
+            let current = engine.get_price("BTCUSDT").await.expect("first tick is good").price;
+            assert_eq!(current, last_good);
+            clock.advance(10_000);
+        }
+
+        assert_eq!(feed.script.injected(), 30);
+        // Ticks after the garbage clears move the price again
+        assert_eq!(last_good, dec!(60000) + Decimal::from(15 * 60 - 10));
+        assert_eq!(alerts.errors(), vec![(
+            "Binance feed".to_string(),
+            "3 unparseable trades in a row".to_string(),
+        )]);
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(24))]
+
+        #[test]
+        fn prop_positions_consistent_under_chaos(failure_rate in 0.0f64..0.6, seed in proptest::prelude::any::<u64>()) {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async {
+                let executor = chaos_executor(failure_rate, seed);
+                let mut risk = RiskManager::new(RiskConfig::default());
+                run_chaos(&executor, &mut risk, 40).await;
+                assert_positions_match_exchange(&executor).await;
+            });
+        }
+    }
 }
@@ -0,0 +1,447 @@
+//! Wallet private keys: where they are kept and how they are unlocked
+//!
+//! `polymarket.key_source` selects where the signing keys come from:
+//! - `plaintext`: `private_key` in the config, as before
+//! - `encrypted_file`: a key file written by `polymarket-bot key encrypt`,
+//!   AES-256-GCM under a key derived from a passphrase with PBKDF2. The
+//!   passphrase is read from `POLYMARKET_KEY_PASSPHRASE`, or asked for at
+//!   startup.
+//! - `keyring`: the OS keyring (`security` on macOS, `secret-tool` on
+//!   Linux), each account's key stored under its ID
+//!
+//! Keys are unlocked once at startup into [`PrivateKey`]s, which print as
+//! `<redacted>`, can't be cloned and are wiped when dropped; configs share
+//! one through an `Arc`. Nothing reads the key back out except the signer,
+//! through [`PrivateKey::wallet`].
+
+use crate::config::{KeySource, PolymarketConfig, DEFAULT_ACCOUNT_ID};
+use crate::error::{BotError, Result};
+use ethers::signers::LocalWallet;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Environment variable holding the key file passphrase
+pub const PASSPHRASE_ENV: &str = "POLYMARKET_KEY_PASSPHRASE";
+
+/// PBKDF2-HMAC-SHA256 rounds for new key files
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const KEY_FILE_VERSION: u8 = 1;
+const KDF_NAME: &str = "pbkdf2-sha256";
+const SALT_LEN: usize = 16;
+/// Binds the ciphertext to its purpose
+const KEY_FILE_AAD: &[u8] = b"polymarket-bot private key";
+
+/// A wallet private key (hex, with or without 0x). Never printed, never
+/// copied and zeroed on drop.
+#[derive(Default, Deserialize)]
+#[serde(transparent)]
+pub struct PrivateKey(String);
+
+impl PrivateKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.trim().is_empty()
+    }
+
+    /// The signing wallet for this key; the raw bytes are wiped once it is
+    /// built
+    pub fn wallet(&self) -> Result<LocalWallet> {
+        let hex_key = self.0.trim().trim_start_matches("0x");
+        let bytes = Zeroizing::new(
+            hex::decode(hex_key).map_err(|_| BotError::Auth("Invalid private key: not hex".into()))?,
+        );
+        LocalWallet::from_bytes(&bytes).map_err(|_| BotError::Auth("Invalid private key".into()))
+    }
+}
+
+impl From<&str> for PrivateKey {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for PrivateKey {
+    fn from(key: String) -> Self {
+        Self::new(key)
+    }
+}
+
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if self.is_empty() { "PrivateKey(<empty>)" } else { "PrivateKey(<redacted>)" })
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Deserializes a config's key into the `Arc` its clones share
+pub fn deserialize_shared<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Arc<PrivateKey>, D::Error> {
+    PrivateKey::deserialize(deserializer).map(Arc::new)
+}
+
+#[cfg(test)]
+impl PartialEq<&str> for PrivateKey {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// On-disk format of an encrypted key, hex-encoded fields
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKeyFile {
+    version: u8,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(|| BotError::Config("Key file has 0 iterations".into()))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    ring::pbkdf2::derive(ring::pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), key.as_mut());
+    let key = UnboundKey::new(&AES_256_GCM, key.as_ref()).map_err(|_| BotError::Internal("AES key setup failed".into()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// `key` encrypted under `passphrase`, as the contents of a key file
+pub fn encrypt_key(key: &PrivateKey, passphrase: &str) -> Result<String> {
+    encrypt_key_with_iterations(key, passphrase, PBKDF2_ITERATIONS)
+}
+
+fn encrypt_key_with_iterations(key: &PrivateKey, passphrase: &str, iterations: u32) -> Result<String> {
+    if key.is_empty() {
+        return Err(BotError::Config("No private key to encrypt".into()));
+    }
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| BotError::Internal("No system randomness".into()))?;
+
+    let mut buffer = Zeroizing::new(key.0.trim().as_bytes().to_vec());
+    derive_key(passphrase, &salt, iterations)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(KEY_FILE_AAD), &mut *buffer)
+        .map_err(|_| BotError::Internal("Key encryption failed".into()))?;
+
+    let file = EncryptedKeyFile {
+        version: KEY_FILE_VERSION,
+        kdf: KDF_NAME.to_string(),
+        iterations,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(&*buffer),
+    };
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// The key in a key file's `contents`; fails on a wrong passphrase or a
+/// tampered file alike
+pub fn decrypt_key(contents: &str, passphrase: &str) -> Result<PrivateKey> {
+    let file: EncryptedKeyFile =
+        serde_json::from_str(contents).map_err(|e| BotError::Config(format!("Not a key file: {}", e)))?;
+    if file.version != KEY_FILE_VERSION || file.kdf != KDF_NAME {
+        return Err(BotError::Config(format!(
+            "Unsupported key file (version {}, {})",
+            file.version, file.kdf
+        )));
+    }
+    let field = |name: &str, value: &str| {
+        hex::decode(value).map_err(|_| BotError::Config(format!("Key file {} is not hex", name)))
+    };
+    let salt = field("salt", &file.salt)?;
+    let nonce = Nonce::try_assume_unique_for_key(&field("nonce", &file.nonce)?)
+        .map_err(|_| BotError::Config("Key file nonce has the wrong length".into()))?;
+    let mut buffer = Zeroizing::new(field("ciphertext", &file.ciphertext)?);
+
+    let plaintext = derive_key(passphrase, &salt, file.iterations)?
+        .open_in_place(nonce, Aad::from(KEY_FILE_AAD), &mut buffer)
+        .map_err(|_| BotError::Auth("Wrong passphrase or corrupted key file".into()))?;
+    let key = std::str::from_utf8(plaintext).map_err(|_| BotError::Auth("Key file holds no key".into()))?;
+    Ok(PrivateKey::new(key))
+}
+
+/// Write an encrypted key, readable by the owner only; never overwrites
+pub fn write_key_file(path: &Path, contents: &str) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| BotError::Config(format!("{}: {}", dir.display(), e)))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| BotError::Config(format!("{}: {}", path.display(), e)))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| BotError::Config(format!("{}: {}", path.display(), e)))
+}
+
+fn read_key_file(path: &str, passphrase: &str) -> Result<PrivateKey> {
+    let path = shellexpand::tilde(path);
+    let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| BotError::Config(format!("{}: {}", path, e)))?;
+    decrypt_key(&contents, passphrase)
+}
+
+/// Key stored in the OS keyring under `service` and `account`
+fn keyring_key(service: &str, account: &str) -> Result<PrivateKey> {
+    let output = if cfg!(target_os = "macos") {
+        std::process::Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", account, "-w"])
+            .output()
+    } else if cfg!(target_os = "linux") {
+        std::process::Command::new("secret-tool")
+            .args(["lookup", "service", service, "account", account])
+            .output()
+    } else {
+        return Err(BotError::Config("The OS keyring is only supported on macOS and Linux".into()));
+    };
+    let mut output = output.map_err(|e| BotError::Config(format!("OS keyring unavailable: {}", e)))?;
+    let key = match std::str::from_utf8(&output.stdout) {
+        Ok(key) if output.status.success() && !key.trim().is_empty() => Ok(PrivateKey::new(key.trim())),
+        _ => Err(BotError::Auth(format!(
+            "No key for account {} in keyring service {}: {}",
+            account,
+            service,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+    };
+    output.stdout.zeroize();
+    key
+}
+
+/// Read a line from the terminal without echoing it
+fn prompt_hidden(prompt: &str) -> Result<Zeroizing<String>> {
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return Err(BotError::Config(format!("No passphrase: set {} or run in a terminal", PASSPHRASE_ENV)));
+    }
+    eprint!("{}", prompt);
+    let _ = std::io::stderr().flush();
+    let echo_off = set_echo(false);
+    let mut line = Zeroizing::new(String::new());
+    let read = std::io::stdin().read_line(&mut line);
+    if echo_off {
+        set_echo(true);
+    }
+    eprintln!();
+    read.map_err(|e| BotError::Config(format!("Failed to read passphrase: {}", e)))?;
+    Ok(Zeroizing::new(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Turn terminal echo on or off; false when that is not possible
+fn set_echo(on: bool) -> bool {
+    cfg!(unix)
+        && std::process::Command::new("stty")
+            .arg(if on { "echo" } else { "-echo" })
+            .stdin(std::process::Stdio::inherit())
+            .status()
+            .is_ok_and(|s| s.success())
+}
+
+/// The passphrase [`take_env_passphrase`] moved out of the environment
+static ENV_PASSPHRASE: Mutex<Option<Zeroizing<String>>> = Mutex::new(None);
+
+/// Move the passphrase out of [`PASSPHRASE_ENV`], so it isn't left in the
+/// environment for child processes. Call it before any other thread
+/// starts: changing the environment while another thread reads it is
+/// undefined behavior.
+pub fn take_env_passphrase() {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        std::env::remove_var(PASSPHRASE_ENV);
+        *ENV_PASSPHRASE.lock().unwrap_or_else(PoisonError::into_inner) = Some(Zeroizing::new(passphrase));
+    }
+}
+
+/// The passphrase taken from the environment, if any; read only once
+fn env_passphrase() -> Option<Zeroizing<String>> {
+    let taken = ENV_PASSPHRASE.lock().unwrap_or_else(PoisonError::into_inner).take();
+    taken.or_else(|| std::env::var(PASSPHRASE_ENV).ok().map(Zeroizing::new))
+}
+
+/// Passphrase from [`PASSPHRASE_ENV`], or else asked for
+pub fn read_passphrase() -> Result<Zeroizing<String>> {
+    if let Some(passphrase) = env_passphrase() {
+        return Ok(passphrase);
+    }
+    prompt_hidden("Key file passphrase: ")
+}
+
+/// A passphrase for a new key file: from [`PASSPHRASE_ENV`], or asked for
+/// twice
+pub fn read_new_passphrase() -> Result<Zeroizing<String>> {
+    if let Some(passphrase) = env_passphrase() {
+        return Ok(passphrase);
+    }
+    let passphrase = prompt_hidden("New passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(BotError::Config("The passphrase must not be empty".into()));
+    }
+    if *prompt_hidden("Repeat passphrase: ")? != *passphrase {
+        return Err(BotError::Config("Passphrases do not match".into()));
+    }
+    Ok(passphrase)
+}
+
+/// Replace every account's key with the one from `key_source`, asking for
+/// the passphrase at most once
+pub fn unlock_keys(config: &mut PolymarketConfig) -> Result<()> {
+    unlock_keys_with(config, read_passphrase)
+}
+
+/// [`unlock_keys`] with the passphrase from `passphrase`
+pub fn unlock_keys_with(
+    config: &mut PolymarketConfig,
+    passphrase: impl FnOnce() -> Result<Zeroizing<String>>,
+) -> Result<()> {
+    let source = config.key_source;
+    if source == KeySource::Plaintext {
+        return Ok(());
+    }
+    if !config.private_key.is_empty() || config.accounts.iter().any(|a| !a.private_key.is_empty()) {
+        tracing::warn!("private_key in the config is ignored with key_source = {:?}; remove it", source);
+    }
+
+    // The top-level credentials are the account when none are listed
+    let mut slots: Vec<(String, Option<String>, &mut Arc<PrivateKey>)> = if config.accounts.is_empty() {
+        vec![(DEFAULT_ACCOUNT_ID.to_string(), config.key_file.clone(), &mut config.private_key)]
+    } else {
+        config
+            .accounts
+            .iter_mut()
+            .map(|a| (a.id.clone(), a.key_file.clone(), &mut a.private_key))
+            .collect()
+    };
+
+    match source {
+        KeySource::Plaintext => {}
+        KeySource::EncryptedFile => {
+            let passphrase = passphrase()?;
+            for (id, key_file, key) in slots.iter_mut() {
+                let path = key_file
+                    .as_deref()
+                    .ok_or_else(|| BotError::Config(format!("No key_file for account {}", id)))?;
+                **key = Arc::new(read_key_file(path, &passphrase)?);
+            }
+        }
+        KeySource::Keyring => {
+            for (id, _, key) in slots.iter_mut() {
+                **key = Arc::new(keyring_key(&config.keyring_service, id)?);
+            }
+        }
+    }
+    tracing::info!("Unlocked {} wallet key(s) from {:?}", slots.len(), source);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AccountConfig;
+    use ethers::signers::Signer;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn config(accounts: Vec<AccountConfig>, key_file: Option<String>) -> PolymarketConfig {
+        PolymarketConfig {
+            clob_url: "https://clob.polymarket.com".to_string(),
+            gamma_url: "https://gamma-api.polymarket.com".to_string(),
+            data_api_url: "https://data-api.polymarket.com".to_string(),
+            private_key: Default::default(),
+            key_source: KeySource::EncryptedFile,
+            key_file,
+            keyring_service: "polymarket-bot".to_string(),
+            funder_address: None,
+            chain_id: 137,
+            signature_type: 0,
+            accounts,
+        }
+    }
+
+    #[test]
+    fn test_key_file_round_trip() {
+        let key = PrivateKey::new(KEY);
+        let contents = encrypt_key_with_iterations(&key, "hunter2", 1000).unwrap();
+        assert!(!contents.contains(&KEY[2..]));
+
+        let decrypted = decrypt_key(&contents, "hunter2").unwrap();
+        assert_eq!(decrypted, KEY);
+        assert_eq!(decrypted.wallet().unwrap().address(), key.wallet().unwrap().address());
+        assert!(matches!(decrypt_key(&contents, "hunter3"), Err(BotError::Auth(_))));
+
+        // Any change to the ciphertext is caught
+        let mut file: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        let ciphertext = file["ciphertext"].as_str().unwrap().to_string();
+        let flipped = if ciphertext.starts_with('0') { "1" } else { "0" };
+        file["ciphertext"] = format!("{}{}", flipped, &ciphertext[1..]).into();
+        assert!(decrypt_key(&file.to_string(), "hunter2").is_err());
+
+        assert_eq!(format!("{:?}", key), "PrivateKey(<redacted>)");
+        assert!(encrypt_key(&PrivateKey::default(), "hunter2").is_err());
+    }
+
+    #[test]
+    fn test_unlock_keys_from_encrypted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("fund.key");
+        let contents = encrypt_key_with_iterations(&PrivateKey::new(KEY), "hunter2", 1000).unwrap();
+        write_key_file(&path, &contents).unwrap();
+        assert!(write_key_file(&path, &contents).is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let path = path.to_str().unwrap().to_string();
+        let mut single = config(Vec::new(), Some(path.clone()));
+        unlock_keys_with(&mut single, || Ok(Zeroizing::new("hunter2".to_string()))).unwrap();
+        assert_eq!(*single.private_key, KEY);
+        assert!(!format!("{:?}", single).contains(&KEY[2..]));
+
+        let account = AccountConfig {
+            id: "fund".to_string(),
+            private_key: Default::default(),
+            key_file: Some(path),
+            funder_address: None,
+            signature_type: None,
+            capital: None,
+            risk: Default::default(),
+        };
+        let mut multi = config(vec![account.clone(), AccountConfig { id: "other".to_string(), key_file: None, ..account }], None);
+        let err = unlock_keys_with(&mut multi, || Ok(Zeroizing::new("hunter2".to_string()))).unwrap_err();
+        assert!(err.to_string().contains("No key_file for account other"));
+        assert_eq!(*multi.accounts[0].private_key, KEY);
+
+        let mut wrong = config(Vec::new(), single.key_file.clone());
+        assert!(unlock_keys_with(&mut wrong, || Ok(Zeroizing::new("nope".to_string()))).is_err());
+
+        // Plaintext configs are left alone, without asking for a passphrase
+        let mut plaintext = config(Vec::new(), None);
+        plaintext.key_source = KeySource::Plaintext;
+        plaintext.private_key = Arc::new(PrivateKey::new(KEY));
+        unlock_keys_with(&mut plaintext, || panic!("no passphrase needed")).unwrap();
+        assert_eq!(*plaintext.private_key, KEY);
+    }
+}
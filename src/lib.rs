@@ -13,18 +13,22 @@
 //!                                    Risk Management (Daily P&L, Volatility, Correlation)
 //! ```
 
+pub mod accounts;
 pub mod analysis;
 pub mod arbitrage;
 pub mod backtest;
 pub mod client;
+pub mod clock;
 pub mod config;
 pub mod data;
 pub mod error;
 pub mod events;
 pub mod execution_quality;
 pub mod executor;
+pub mod fees;
 pub mod fusion;
 pub mod ingester;
+pub mod keys;
 pub mod ml;
 pub mod model;
 pub mod monitor;
@@ -33,11 +37,14 @@ pub mod onchain;
 pub mod paper;
 pub mod orderbook;
 pub mod portfolio;
+pub mod recovery;
 pub mod regime;
+pub mod report;
 pub mod risk;
 pub mod routing;
 pub mod scanner;
 pub mod sentiment;
+pub mod shutdown;
 pub mod signal_decay;
 pub mod stat_arb;
 pub mod storage;
@@ -2,32 +2,51 @@
 //!
 //! An automated trading system for Polymarket prediction markets.
 
-use chrono::Timelike;
+use chrono::{Datelike, Timelike};
 use clap::{Parser, Subcommand};
 use polymarket_bot::{
-    client::PolymarketClient,
-    config::Config,
-    executor::Executor,
+    accounts::Accounts,
+    analysis::{dossier::{self, MarketDossier}, market_profile::MarketProfiles, network::MarketCorrelationNetwork, seasonality::SeasonalityModel, sentiment::SentimentIndex},
+    client::{mock::ClobClientTrait, GammaClient, MarketFilter, PolymarketClient},
+    clock::SystemClock,
+    config::{Config, ConfigValidationErrors, KeySource},
+    events::sink::{emit_all, sinks_from_config, BotEvent},
+    executor::{Executor, ExecutorSettings, SimulatedClobClient},
+    fees::PolymarketFees,
     ingester::{
         processor::SignalProcessor,
         telegram::TelegramBotSource,
         twitter::{TwitterSource, TwitterRssSource},
-        ParsedSignal, RawSignal, SignalSource,
+        self as ingester, AuthorTrust, IngestStats, NewsApiSource, NewsPortfolioMonitor, ParsedSignal, RawSignal, SignalMapper, SignalQueue, SignalSource,
     },
-    model::{EnsembleModel, LlmModel, ProbabilityModel},
-    monitor::Monitor,
-    notify::Notifier,
-    risk::RiskManager,
-    storage::Database,
+    keys,
+    ml::BayesianUpdater,
+    model::{CircuitBreakerModel, EnsembleModel, LlmFallbackChain, LlmModel, MarketImpliedModel, ProbabilityModel, PromptTemplate, MARKET_FALLBACK_NAME},
+    monitor::{evaluate_market_alerts, AlertEngine, AlertMetric, BotMetrics, MarketSnapshot, ExpressionEvaluator, FeedHeartbeat, HealthChecker, HealthStatus, HealthTransitions, MetricsRegistry, Monitor, Watchdog},
+    notify::{Notifier, SlackNotifier},
+    orderbook::{OrderBookAnalyzer, OrderBookSnapshot},
+    risk::{FastStopMonitor, LiquidationReport, LiquidityStressTester, LIQUIDATION_ALERT_PCT, OpenPositionCounts, PositionLimitCheck, PositionLimits, PositionQueue, RampPolicy, RiskManager, StressPortfolio, StressResult, StressScenario},
+    shutdown::GracefulShutdown,
+    paper::PaperBroker,
+    portfolio::{heat::HEAT_WARNING, ladder::refresh_position_markets, CapitalLadder, CtfRedeemer, PortfolioHeatMonitor, PortfolioRebalancer, Redeemer},
+    recovery::{SnapshotWriter, StateSnapshot, SIMULATED_ACCOUNT_ID, STATE_SAVE_EVERY_CYCLES},
+    regime::{MultiTimeframeRegime, PriceBar, RegimeConfig, RegimeDetector},
+    report::{DailySchedule, MonthlyReport, REPORT_DIR},
+    scanner::{execute_or_rest, fetch_balances, record_execution, MarketDiscovery, PinnedMarkets},
+    storage::{CopiedTrade, Database, JournalEntry, JournalKind, LeaderboardSnapshot, StoredPrediction, TraceEvent, TraceStage, DEFAULT_STRATEGY_NAME},
     strategy::{
-        SignalGenerator,
-        copy_trade::{CopyTrader, TopTrader},
+        AutoStrategySelector, DynamicKellyConfig, RestingOrderStrategy, RestingUpdate, Routing, ShadowRouter,
+        SignalGenerator, SignalScorer, StrategyModes,
+        copy_trade::{plan_follow_update, CopyTrader, TopTrader, TraderDiscovery},
         crypto_hf::{CryptoHfStrategy, CryptoPriceTracker},
+        market_maker::{MarketMakerEngine, MarketMakerInputs, MmMarketInput},
         realtime::{RealtimeEngine, start_binance_feed},
         signal_filter::SignalFilter,
     },
     telegram::{TelegramBot, CommandHandler, BotCommand},
+    utils::ascii_chart::sparkline,
 };
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::sync::Arc;
 use std::time::Duration;
@@ -60,21 +79,149 @@ enum Commands {
         #[arg(short, long, default_value = "10")]
         limit: usize,
     },
-    /// Analyze a specific market
+    /// Analyze a specific market, or a symbol's regime history
     Analyze {
         /// Market ID to analyze
-        market_id: String,
+        #[arg(required_unless_present = "regime_history")]
+        market_id: Option<String>,
+        /// Classify historical Binance bars by market regime instead
+        #[arg(long)]
+        regime_history: bool,
+        /// First day of history, e.g. 2024-01-01 (default: a year ago)
+        #[arg(long)]
+        from: Option<String>,
+        /// Binance symbol, e.g. BTC or ETHUSDT
+        #[arg(long, default_value = "BTC")]
+        symbol: String,
+        /// Bar interval, e.g. 1h or 1d
+        #[arg(long, default_value = "1h")]
+        interval: String,
+        /// Print the market dossier as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Show account status
     Status,
-    /// Send status report to Telegram
-    Report,
+    /// Send status report to Telegram, or with --period the monthly report
+    Report {
+        /// Month to report on instead, e.g. 2024-06; also writes the JSON report
+        #[arg(long)]
+        period: Option<String>,
+        /// Directory for the JSON report
+        #[arg(long, default_value = REPORT_DIR)]
+        dir: String,
+    },
     /// Test Telegram notification
     TestNotify,
+    /// Print journal entries interleaved with trades
+    Journal {
+        /// How far back to look, e.g. 7d, 12h, 30m
+        #[arg(long, default_value = "7d")]
+        since: String,
+    },
+    /// Check every dependency and suggest fixes
+    Doctor,
+    /// Export the portfolio value history as CSV
+    EquityCurve {
+        /// First day to export, e.g. 2024-01-01 (default: everything)
+        #[arg(long)]
+        from: Option<String>,
+        /// CSV file to write
+        #[arg(long, default_value = "equity.csv")]
+        output: String,
+    },
+    /// Score model predictions against how their markets resolved
+    Accuracy {
+        /// Predictions made within this lookback, e.g. 30d, 12h
+        #[arg(long, default_value = "30d")]
+        since: String,
+    },
+    /// Break trading performance down by trade tag (strategy, model, prompt, experiment)
+    Attribution {
+        /// Trades made within this lookback, e.g. 30d, 12h
+        #[arg(long, default_value = "30d")]
+        since: String,
+    },
+    /// Render a market's prediction prompt and run it once
+    PromptTest {
+        /// Market ID to predict
+        market_id: String,
+        /// Template file to try instead of the one configured for the market's category
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Re-run the signal processor over recorded messages with other parameters
+    ReplaySignals {
+        /// First day to replay, e.g. 2024-06-01
+        #[arg(long)]
+        from: String,
+        /// Day to stop before (default: now)
+        #[arg(long)]
+        to: Option<String>,
+        /// Minimum extraction confidence (default: [ingester.processing])
+        #[arg(long)]
+        min_confidence: Option<f64>,
+        /// Minimum aggregate score (default: [ingester.processing])
+        #[arg(long)]
+        min_agg_score: Option<f64>,
+        /// Aggregation window in seconds (default: [ingester.processing])
+        #[arg(long)]
+        window: Option<i64>,
+        /// How long after a signal to measure its markets' price move, e.g. 24h
+        #[arg(long, default_value = "24h")]
+        horizon: String,
+        /// Skip messages without a cached extraction instead of calling the LLM
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Run the built-in crisis scenarios against the open positions
+    StressTest {
+        /// Daily price history to estimate volatility from, e.g. 60d
+        #[arg(long, default_value = "60d")]
+        history: String,
+    },
+    /// Export the correlation network of the open positions as D3 JSON
+    CorrelationNetwork {
+        /// Minimum |correlation| for two markets to be linked
+        #[arg(long, default_value = "0.5")]
+        threshold: Decimal,
+        /// Output JSON file
+        #[arg(long, default_value = "network.json")]
+        output: String,
+        /// Daily price history to estimate correlations from, e.g. 60d
+        #[arg(long, default_value = "60d")]
+        history: String,
+    },
+    /// Check the config file and list every invalid setting
+    ValidateConfig,
+    /// Manage the wallet private keys
+    Key {
+        #[command(subcommand)]
+        action: KeyCommand,
+    },
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+#[derive(Subcommand)]
+enum KeyCommand {
+    /// Encrypt an account's plaintext private_key into a key file
+    Encrypt {
+        /// Account whose key to encrypt (default: the first)
+        #[arg(long)]
+        account: Option<String>,
+        /// Key file to write; never overwritten
+        #[arg(long, default_value = "data/wallet.key")]
+        output: String,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    // While the process is still single-threaded, as clearing an
+    // environment variable must be
+    keys::take_env_passphrase();
+    tokio::runtime::Builder::new_multi_thread().enable_all().build()?.block_on(run())
+}
+
+async fn run() -> anyhow::Result<()> {
     // Initialize logging
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
@@ -83,17 +230,193 @@ async fn main() -> anyhow::Result<()> {
 
     let cli = Cli::parse();
 
-    // Load configuration
-    let config = Config::load(&cli.config)?;
+    // Validation lists the config's errors rather than failing on them
+    if let Commands::ValidateConfig = cli.command {
+        return validate_config(&cli.config);
+    }
+
+    // Load configuration, and the wallet keys unless converting them
+    let mut config = Config::load(&cli.config)?;
+    if !matches!(cli.command, Commands::Key { .. }) {
+        keys::unlock_keys(&mut config.polymarket)?;
+    }
 
     match cli.command {
         Commands::Run { dry_run } => run_bot(config, dry_run).await,
         Commands::Markets { limit } => show_markets(config, limit).await,
-        Commands::Analyze { market_id } => analyze_market(config, &market_id).await,
+        Commands::Analyze { regime_history: true, from, symbol, interval, .. } => {
+            show_regime_history(&symbol, &interval, from.as_deref()).await
+        }
+        Commands::Analyze { market_id, json, .. } => analyze_market(config, &market_id.unwrap_or_default(), json).await,
         Commands::Status => show_status(config).await,
-        Commands::Report => send_report(config).await,
+        Commands::Report { period: Some(period), dir } => send_monthly_report(config, &period, &dir).await,
+        Commands::Report { period: None, .. } => send_report(config).await,
         Commands::TestNotify => test_notify(config).await,
+        Commands::Journal { since } => show_journal(config, &since).await,
+        Commands::Doctor => doctor(config).await,
+        Commands::EquityCurve { from, output } => export_equity_curve(config, from.as_deref(), &output).await,
+        Commands::Accuracy { since } => show_accuracy(config, &since).await,
+        Commands::Attribution { since } => show_attribution(config, &since).await,
+        Commands::PromptTest { market_id, template } => prompt_test(config, &market_id, template.as_deref()).await,
+        Commands::ReplaySignals { from, to, min_confidence, min_agg_score, window, horizon, offline } => {
+            let params = ReplayParams { min_confidence, min_agg_score, window, offline };
+            replay_signals(config, &from, to.as_deref(), &horizon, params).await
+        }
+        Commands::StressTest { history } => stress_test(config, &history).await,
+        Commands::CorrelationNetwork { threshold, output, history } => {
+            export_correlation_network(config, threshold, &output, &history).await
+        }
+        Commands::Key { action: KeyCommand::Encrypt { account, output } } => {
+            encrypt_key_file(&config, account.as_deref(), &output)
+        }
+        Commands::ValidateConfig => unreachable!("validated before loading"),
+    }
+}
+
+/// Run the market maker re-quote loop in the background
+fn spawn_market_maker<C: ClobClientTrait + 'static>(
+    clob: C,
+    config: polymarket_bot::config::MarketMakerConfig,
+    inputs: Arc<MarketMakerInputs>,
+    db: Arc<Database>,
+    notifier: Arc<Notifier>,
+) {
+    let engine = Arc::new(
+        MarketMakerEngine::new(clob, config, inputs)
+            .with_database(db)
+            .with_notifier(notifier),
+    );
+    tokio::spawn(engine.run());
+}
+
+/// Log the changes a review made to resting orders, notifying their fills
+async fn report_resting_updates(
+    updates: Vec<RestingUpdate>,
+    markets: &[polymarket_bot::types::Market],
+    notifier: &Notifier,
+    notify_trades: bool,
+) {
+    for update in updates {
+        match update {
+            RestingUpdate::Filled { order, trade } => {
+                tracing::info!("📌 Resting order {} filled on {} @ {:.2}", trade.order_id, trade.market_id, order.price);
+                if notify_trades {
+                    let question = markets
+                        .iter()
+                        .find(|m| m.id == trade.market_id)
+                        .map_or(trade.market_id.as_str(), |m| m.question.as_str());
+                    let _ = notifier.resting_fill(&order, &trade, question).await;
+                }
+            }
+            RestingUpdate::Repriced { from, order } => {
+                tracing::info!(
+                    "📌 Repriced resting order on {}: {:.2} -> {:.2}",
+                    order.signal.market_id,
+                    from,
+                    order.price
+                );
+            }
+            RestingUpdate::Cancelled { order, reason } => {
+                tracing::info!("📌 Cancelled resting order on {}: {}", order.signal.market_id, reason);
+            }
+        }
+    }
+}
+
+/// Close watched crypto positions on every account as soon as a tick trips
+/// their fast stop
+async fn run_fast_stop(
+    fast_stop: Arc<parking_lot::Mutex<FastStopMonitor>>,
+    mut ticks: tokio::sync::broadcast::Receiver<(String, Decimal, chrono::DateTime<chrono::Utc>)>,
+    accounts: Arc<Accounts>,
+    db: Arc<Database>,
+    monitor: Arc<Monitor>,
+    notifier: Option<Arc<Notifier>>,
+) {
+    use tokio::sync::broadcast::error::RecvError;
+
+    loop {
+        let (symbol, price, at) = match ticks.recv().await {
+            Ok(tick) => tick,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Fast stop skipped {} ticks", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let exits = fast_stop.lock().on_tick(&symbol, price, at);
+        for exit in exits {
+            tracing::warn!(
+                "Fast stop on {} ({} ${:.2}, win probability {:.1}%): {:?}",
+                exit.position.market_id,
+                exit.position.asset,
+                exit.underlying,
+                exit.probability * Decimal::ONE_HUNDRED,
+                exit.reason
+            );
+            for account in accounts.iter() {
+                let tags = vec!["exit:fast_stop".to_string()];
+                let trade = match account
+                    .executor
+                    .close_position_at_market(&exit.position.market_id, &exit.position.token_id, tags)
+                    .await
+                {
+                    Ok(Some(trade)) => trade,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::error!("Fast stop exit failed on {}: {}", account.id, e);
+                        continue;
+                    }
+                };
+                let latency = (chrono::Utc::now() - at).to_std().unwrap_or_default();
+                tracing::info!("Fast stop filled {} in {}ms", trade.id, latency.as_millis());
+                monitor.record_fast_stop(latency).await;
+                if let Err(e) = db.save_trade(&trade).await {
+                    tracing::error!("Failed to save fast stop trade {}: {}", trade.id, e);
+                }
+                if let Some(notifier) = &notifier {
+                    let _ = notifier.fast_stop_executed(&exit, &trade, latency).await;
+                }
+            }
+        }
+    }
+}
+
+/// Pass the latest prediction for an allowlisted market to the market maker
+async fn feed_market_maker(
+    inputs: &MarketMakerInputs,
+    config: &Config,
+    market: &polymarket_bot::types::Market,
+    prediction: &polymarket_bot::model::Prediction,
+) {
+    let allowed = config
+        .market_maker
+        .as_ref()
+        .is_some_and(|mm| mm.markets.contains(&market.id));
+    if !allowed {
+        return;
     }
+
+    let token = |name: &str| {
+        market
+            .outcomes
+            .iter()
+            .find(|o| o.outcome.eq_ignore_ascii_case(name))
+            .map(|o| o.token_id.clone())
+    };
+    let Some(yes_token) = token("yes") else {
+        return;
+    };
+
+    inputs
+        .update_market(MmMarketInput {
+            market_id: market.id.clone(),
+            yes_token,
+            no_token: token("no"),
+            end_date: market.end_date,
+            model: Some((prediction.probability, prediction.confidence)),
+        })
+        .await;
 }
 
 async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
@@ -103,60 +426,276 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
         tracing::warn!("Running in DRY RUN mode - no actual trades will be executed");
     }
 
-    // Initialize Telegram notifier
-    let notifier = if let Some(tg) = &config.telegram {
+    // Initialize components
+    let db = Arc::new(Database::connect(&config.database.path).await?);
+
+    // Initialize Telegram notifier; messages go through the db outbox so a
+    // Telegram outage delays them instead of dropping them
+    let mut notifier = if let Some(tg) = &config.telegram {
         Notifier::new(tg.bot_token.clone(), tg.chat_id.clone())
+            .with_outbox(db.clone())
+            .await?
     } else {
         tracing::warn!("Telegram not configured, notifications disabled");
         Notifier::disabled()
     };
+    if let Some(slack) = &config.slack {
+        notifier = notifier.with_backend(Arc::new(SlackNotifier::new(&slack.webhook_url)));
+    }
+
+    // Heartbeats of the long-running tasks; stalled feeds, ingester sources
+    // and notification delivery are restarted
+    let watchdog_config = config.watchdog.clone().unwrap_or_default();
+    let watchdog = Arc::new(Watchdog::new());
+    let task_stale_after = Duration::from_secs(watchdog_config.task_stale_secs);
+    if notifier.has_outbox() {
+        let delivery = notifier.clone();
+        watchdog.spawn_restartable("notification_delivery", task_stale_after, move |heartbeat| {
+            delivery.clone().deliver_outbox_every(Duration::from_secs(5), Some(heartbeat))
+        });
+    }
+    if watchdog_config.enabled {
+        watchdog.clone().spawn_supervisor(
+            Duration::from_secs(watchdog_config.check_interval_secs.max(1)),
+            notifier.clone(),
+            watchdog_config.exit_on_main_loop_stall,
+        );
+    }
 
     // Send startup notification
     if let Err(e) = notifier.startup(dry_run).await {
         tracing::warn!("Failed to send startup notification: {}", e);
     }
 
-    // Initialize components
-    let client = Arc::new(PolymarketClient::new(config.polymarket.clone()).await?);
+    let monitor = Arc::new(Monitor::new(1000));
+
+    // Prometheus metrics of every component that registers, at /metrics
+    let metrics = Arc::new(MetricsRegistry::new());
+    metrics.register(monitor.clone());
+    if let Some(metrics_config) = &config.metrics {
+        if let Err(e) = metrics.clone().serve(metrics_config.listen_port).await {
+            tracing::warn!("Metrics endpoint not started: {}", e);
+        }
+    }
+
+    // One fee model prices signals, fills and P&L alike
+    let fee_config = config.fees.clone().unwrap_or_default();
+    let fees = Arc::new(PolymarketFees::from_config(&fee_config));
+
+    let two_phase = config.executor.as_ref().and_then(|e| e.two_phase.clone());
+    let signal_ttl_secs = config.executor.as_ref().map_or(0, |e| e.signal_ttl_secs);
+    // Structured events for external systems, alongside Telegram
+    let event_sinks = match &config.events {
+        Some(events_config) => sinks_from_config(events_config)?,
+        None => Vec::new(),
+    };
+    // Per-market microstructure profiles, rebuilt daily from the market
+    // maker's book samples
+    let market_profiles = match &config.market_profile {
+        Some(profile_config) => {
+            let profiles = Arc::new(MarketProfiles::new(profile_config.clone()));
+            match profiles.load(&db).await {
+                Ok(count) => tracing::info!("Loaded {} market profiles", count),
+                Err(e) => tracing::warn!("Failed to load market profiles: {}", e),
+            }
+            Some(profiles)
+        }
+        None => None,
+    };
+    let executor_settings = ExecutorSettings {
+        fees: fees.clone(),
+        two_phase: two_phase.clone(),
+        sizing: config.executor.as_ref().and_then(|e| e.sizing.clone()),
+        notifier: Some(notifier.clone()),
+        sinks: event_sinks.clone(),
+        profiles: market_profiles.clone(),
+    };
+
+    // One client and executor per wallet; the first also serves market data
+    let accounts = Arc::new(Accounts::connect(&config.polymarket, &config.risk, db.clone(), &executor_settings).await?);
+    let client = accounts.primary().client.clone();
+    let executor = accounts.primary().executor.clone();
     
     // Skip CLOB auth in dry-run mode (not needed for reading markets)
     if !dry_run {
-        client.clob.initialize().await?;
+        for account in accounts.iter() {
+            account.client.clob.initialize().await?;
+        }
     } else {
         tracing::info!("Skipping CLOB authentication in dry-run mode");
     }
+    if accounts.len() > 1 {
+        tracing::info!("Trading {} accounts: {}", accounts.len(), accounts.ids().join(", "));
+    }
 
-    let db = Arc::new(Database::connect(&config.database.path).await?);
-    let monitor = Monitor::new(1000);
+    // Dry runs trade against a simulated account priced off live order books
+    let simulation = if dry_run {
+        let sim_config = config.simulation.clone().unwrap_or_default();
+        let sim = SimulatedClobClient::open(&sim_config.db_path, sim_config.initial_balance, sim_config.slippage_bps)
+            .await?
+            .with_order_books(Arc::new(client.clob.clone()));
+        let sim = Arc::new(sim);
+        tracing::info!(
+            "Simulated account loaded from {} (balance ${:.2}, slippage {} bps)",
+            sim_config.db_path,
+            sim.get_balance().await?,
+            sim_config.slippage_bps
+        );
+        let clob: Arc<dyn ClobClientTrait> = sim.clone();
+        let executor = Executor::new(clob, config.risk.clone())
+            .with_executed_signals(db.clone())
+            .with_settings(&executor_settings);
+        Some((sim, executor))
+    } else {
+        None
+    };
+
+    // Dependency health, on demand via /health and in the background
+    let feed_heartbeat = Arc::new(FeedHeartbeat::default());
+    let health_checker = Arc::new(HealthChecker::from_config(
+        &config,
+        (!dry_run).then(|| client.clob.clone()),
+        client.gamma.clone(),
+        db.clone(),
+        &notifier,
+        Some(feed_heartbeat.clone()),
+    ));
+    metrics.register(health_checker.clone());
+    let health_config = config.health.clone().unwrap_or_default();
+    if health_config.enabled {
+        let checker = health_checker.clone();
+        let notifier = notifier.clone();
+        tokio::spawn(async move {
+            let mut transitions = HealthTransitions::new(health_config.confirmations);
+            let mut interval = tokio::time::interval(Duration::from_secs(health_config.interval_secs.max(1)));
+            loop {
+                interval.tick().await;
+                let changes: Vec<_> = transitions
+                    .observe(&checker.check_all().await)
+                    .into_iter()
+                    .filter(|t| t.to != HealthStatus::Ok)
+                    .collect();
+                for t in &changes {
+                    tracing::warn!("Health: {} {} → {} ({})", t.component, t.from, t.to, t.detail);
+                }
+                if !changes.is_empty() {
+                    if let Err(e) = notifier.health_changed(&changes).await {
+                        tracing::warn!("Failed to send health alert: {}", e);
+                    }
+                }
+            }
+        });
+    }
 
     // Initialize command handler for Telegram
-    let cmd_handler = Arc::new(CommandHandler::new(config.clone(), notifier.clone()));
+    let mut cmd_handler = CommandHandler::new(config.clone(), notifier.clone());
+    if let Some(rebalance_config) = &config.rebalance {
+        cmd_handler = cmd_handler.with_rebalancer(
+            PortfolioRebalancer::from_config(rebalance_config),
+            executor.clone(),
+        );
+    }
+    // Shadow-mode strategies paper-trade beside the live ones
+    let strategy_modes = Arc::new(StrategyModes::new(config.strategy.modes.clone()));
+    let shadow_router = ShadowRouter::new(strategy_modes.clone(), client.clob.clone(), config.risk.clone(), db.clone());
+    cmd_handler = cmd_handler.with_strategy_modes(strategy_modes.clone());
+    cmd_handler = cmd_handler.with_accounts(accounts.clone());
+    cmd_handler = cmd_handler.with_health(health_checker.clone());
+    // Markets scanned whatever the scan filters say, changed with /watch
+    let pinned_markets = PinnedMarkets::new(&config.markets.clone().unwrap_or_default().watchlist);
+    cmd_handler = cmd_handler.with_pinned_markets(pinned_markets.clone());
+    // Author trust learned from ingested signal outcomes
+    let author_trust = config
+        .ingester
+        .as_ref()
+        .filter(|c| c.enabled)
+        .map(|c| Arc::new(AuthorTrust::new(c.trust.clone(), c.author_trust.clone())));
+    if let Some(trust) = &author_trust {
+        cmd_handler = cmd_handler.with_author_trust(trust.clone());
+    }
+    // Per-source ingestion counts, carried on from the last hourly snapshot
+    let ingest_stats = match config.ingester.as_ref().filter(|c| c.enabled) {
+        Some(_) => {
+            let persisted = db.get_latest_ingest_stats().await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to load ingestion stats: {}", e);
+                Vec::new()
+            });
+            Some(Arc::new(IngestStats::restore(persisted, chrono::Utc::now())))
+        }
+        None => None,
+    };
+    if let Some(stats) = &ingest_stats {
+        cmd_handler = cmd_handler.with_ingest_stats(stats.clone());
+        metrics.register(stats.clone());
+    }
+    // Signal tokens to markets, extended with /addmapping
+    let signal_mapper = Arc::new(match &config.ingester {
+        Some(ingester_config) => SignalMapper::from_config(&ingester_config.mapping)?,
+        None => SignalMapper::default(),
+    });
+    cmd_handler = cmd_handler.with_signal_mapper(signal_mapper.clone());
+    let cmd_handler = Arc::new(cmd_handler);
 
     // Create command channel
     let (cmd_tx, mut cmd_rx) = mpsc::channel::<BotCommand>(100);
 
     // Start Telegram command listener if configured
-    if let Some(tg) = &config.telegram {
-        let telegram_bot = Arc::new(TelegramBot::new(
-            tg.bot_token.clone(),
-            tg.chat_id.clone(),
-            cmd_tx,
-        ));
-        
+    let telegram_bot = config.telegram.as_ref().map(|tg| {
+        let telegram_bot = Arc::new(
+            TelegramBot::new(tg.bot_token.clone(), tg.chat_id.clone(), cmd_tx).with_mode(tg.mode.clone()),
+        );
+
         let bot_clone = telegram_bot.clone();
         tokio::spawn(async move {
-            bot_clone.start_polling().await;
+            if let Err(e) = bot_clone.start().await {
+                tracing::error!("Failed to start Telegram command listener: {}", e);
+            }
         });
-        
+
         tracing::info!("Telegram command listener started");
-    }
+        metrics.register(telegram_bot.clone());
+        telegram_bot
+    });
 
     // Initialize model
-    let mut model = EnsembleModel::new();
+    let sentiment = Arc::new(SentimentIndex::new(
+        config.ingester.as_ref().map(|c| c.sentiment.clone()).unwrap_or_default(),
+    ));
+    let max_concurrent_predictions = config
+        .llm
+        .as_ref()
+        .and_then(|c| c.max_concurrent)
+        .unwrap_or(config.strategy.max_concurrent_analyses)
+        .max(1);
+    let mut model = EnsembleModel::new()
+        .with_sentiment(sentiment.clone())
+        .with_max_concurrent(max_concurrent_predictions)
+        .with_analysis_timeout(Duration::from_secs(config.strategy.analysis_timeout_secs));
+    let mut llm_usage = None;
+    let mut recent_signals = None;
     if let Some(llm_config) = &config.llm {
         match LlmModel::from_config(llm_config) {
             Ok(llm) => {
+                let llm = llm.with_sentiment(sentiment.clone());
                 tracing::info!("LLM model initialized: {}", llm.name());
+                llm_usage = Some(llm.usage());
+                recent_signals = Some(llm.recent_signals());
+                let llm: Box<dyn ProbabilityModel> = if llm_config.fallback.is_empty() {
+                    Box::new(llm)
+                } else {
+                    let chain = Arc::new(
+                        llm_fallback_chain(llm, llm_config, &sentiment).with_notifier(Arc::new(notifier.clone())),
+                    );
+                    metrics.register(chain.clone());
+                    Box::new(chain)
+                };
+                let llm = CircuitBreakerModel::new(
+                    llm,
+                    Box::new(MarketImpliedModel),
+                    llm_config.failure_threshold,
+                    Duration::from_secs(llm_config.breaker_cooldown_secs),
+                )
+                .with_notifier(Arc::new(notifier.clone()));
                 model.add_model(Box::new(llm), Decimal::new(70, 2)); // 70% weight
             }
             Err(e) => {
@@ -164,23 +703,99 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             }
         }
     }
+    metrics.register(model.batch_metrics());
+    let model = Arc::new(model);
 
     // Initialize strategy
-    let signal_gen = SignalGenerator::new(config.strategy.clone(), config.risk.clone());
-    let crypto_strategy = CryptoHfStrategy::default();
+    let mut signal_gen = SignalGenerator::new(config.strategy.clone(), config.risk.clone()).with_fees(fees.clone());
+    if let Some(prior_strength) = config.strategy.bayesian_prior_strength {
+        signal_gen = signal_gen.with_bayesian(BayesianUpdater::new(prior_strength));
+    }
+    if config.strategy.seasonality.enabled {
+        signal_gen = signal_gen.with_seasonality(seasonality_model(&client.gamma).await);
+    }
+    if let Some(profiles) = &market_profiles {
+        signal_gen = signal_gen.with_market_profiles(profiles.clone());
+    }
+    let crypto_strategy = CryptoHfStrategy {
+        fees: fees.clone(),
+        ..CryptoHfStrategy::default()
+    };
     let mut crypto_tracker = CryptoPriceTracker::new();
     let signal_filter = SignalFilter::new();
     tracing::info!("Signal filter initialized (15-min dedup, fusion required)");
+    let signal_scorer = SignalScorer::new(config.strategy.scoring.weights.clone());
     
     // Initialize advanced risk manager
     let risk_manager = Arc::new(tokio::sync::Mutex::new(
         RiskManager::new(config.risk.clone())
     ));
     tracing::info!("Risk manager initialized (daily P&L tracking, volatility sizing, correlation detection)");
+    if let Some(ramp_config) = config.ramp.clone().filter(|r| r.enabled) {
+        let states = db.load_ramp_states().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load size ramp state: {}", e);
+            Default::default()
+        });
+        let ramp = RampPolicy::new(ramp_config).with_states(states);
+        cmd_handler.state.write().await.ramp_levels = ramp.levels();
+        risk_manager.lock().await.set_ramp(ramp);
+        tracing::info!("Size ramp-up enabled for new strategies and market categories");
+    }
     
+    // Resume warm from the last state snapshot, if any
+    let recovery_config = config.recovery.clone().unwrap_or_default();
+    let restored = match recovery_config.enabled {
+        true => StateSnapshot::load(std::path::Path::new(&recovery_config.path)).await,
+        false => None,
+    };
+    let snapshot_writer = recovery_config.enabled.then(|| SnapshotWriter::spawn(&recovery_config.path));
+    let mut regime_detector = RegimeDetector::new(RegimeConfig::default());
+    // The same BTC bars rolled up into longer timeframes, whose consensus scales sizing
+    let mut multi_regime = MultiTimeframeRegime::new();
+    // Follows the BTC regime with the strategy mapped to it in [strategy.regime_map]
+    let mut strategy_selector = (!config.strategy.regime_map.is_empty()).then(|| {
+        AutoStrategySelector::new(
+            config.strategy.regime_map.clone(),
+            config.strategy.regime_confidence_threshold,
+        )
+    });
+    let mut heat_monitor = PortfolioHeatMonitor::new(config.risk.max_heat_budget);
+    let mut restored_vpin = None;
+    if let Some(snapshot) = restored {
+        tracing::info!("Restoring runtime state saved at {}", snapshot.taken_at);
+        crypto_tracker.restore(snapshot.crypto);
+        if let Some(state) = snapshot.regime {
+            regime_detector = RegimeDetector::from_state(RegimeConfig::default(), state);
+        }
+        for account in accounts.iter() {
+            if let Some(positions) = snapshot.positions.get(&account.id) {
+                account.executor.restore_positions(positions.clone()).await;
+            }
+        }
+        if let (Some((_, executor)), Some(positions)) = (&simulation, snapshot.positions.get(SIMULATED_ACCOUNT_ID)) {
+            executor.restore_positions(positions.clone()).await;
+        }
+        restored_vpin = Some(snapshot.vpin);
+    }
+    // Database checkpoints are usually newer than the snapshot
+    if recovery_config.enabled {
+        match db.load_state().await {
+            Ok(Some(state)) => crypto_tracker.restore(state),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load crypto tracker state: {}", e),
+        }
+        match db.load_state().await {
+            Ok(Some(state)) => regime_detector = RegimeDetector::from_state(RegimeConfig::default(), state),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load regime detector state: {}", e),
+        }
+    }
+
     // Initialize crypto price history from Binance klines
-    if let Err(e) = crypto_tracker.init_history().await {
-        tracing::warn!("Failed to initialize crypto price history: {}", e);
+    if crypto_tracker.history_len("BTC") == 0 {
+        if let Err(e) = crypto_tracker.init_history().await {
+            tracing::warn!("Failed to initialize crypto price history: {}", e);
+        }
     }
 
     // Initialize real-time engine with WebSocket feed
@@ -189,39 +804,194 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
     
     // Start Binance WebSocket feed in background
     let rt_engine_clone = realtime_engine.clone();
-    tokio::spawn(async move {
-        if let Err(e) = start_binance_feed(rt_engine_clone).await {
-            tracing::error!("Binance WebSocket feed error: {}", e);
+    let feed_notifier = config.telegram.as_ref().is_some_and(|c| c.notify_errors).then(|| Arc::new(notifier.clone()));
+    watchdog.spawn_restartable("binance_feed", task_stale_after, move |heartbeat| {
+        let engine = rt_engine_clone.clone();
+        let notifier = feed_notifier.clone();
+        async move {
+            if let Err(e) = start_binance_feed(engine, Some(heartbeat), notifier).await {
+                tracing::error!("Binance WebSocket feed error: {}", e);
+            }
         }
     });
     
-    let executor = Arc::new(Executor::new(client.clob.clone(), config.risk.clone()));
     let tg_config = config.telegram.clone();
     let notifier = Arc::new(notifier);
 
+    // Exit crypto Up/Down positions on adverse Binance ticks, between scans
+    let fast_stop = match config.fast_stop.clone().filter(|f| f.enabled && !dry_run) {
+        Some(fast_stop_config) => {
+            let fast_stop = Arc::new(parking_lot::Mutex::new(FastStopMonitor::new(fast_stop_config)));
+            let notify = tg_config.as_ref().is_some_and(|c| c.notify_trades);
+            tokio::spawn(run_fast_stop(
+                fast_stop.clone(),
+                realtime_engine.subscribe_ticks(),
+                accounts.clone(),
+                db.clone(),
+                monitor.clone(),
+                notify.then(|| notifier.clone()),
+            ));
+            tracing::info!("Fast stop enabled for crypto Up/Down positions");
+            Some(fast_stop)
+        }
+        None => None,
+    };
+
+    // First looks at new high-volume markets, off the scan loop
+    let discovery_watchlist = match config.discovery.clone().filter(|d| d.enabled) {
+        Some(discovery_config) => {
+            let discovery = MarketDiscovery::new(client.gamma.clone(), model.clone(), db.clone(), discovery_config);
+            let discovery = if tg_config.is_some() {
+                discovery.with_notifier(notifier.clone())
+            } else {
+                discovery
+            };
+            let watchlist = discovery.watchlist();
+            discovery.spawn();
+            tracing::info!("Market discovery enabled");
+            Some(watchlist)
+        }
+        None => None,
+    };
+
+    // Alerts on headlines about the markets we hold; the main loop keeps
+    // the open positions current
+    let news_positions: Arc<parking_lot::RwLock<Vec<polymarket_bot::types::Trade>>> = Default::default();
+    if let Some(news_config) = &config.position_news {
+        let source = NewsApiSource::new(news_config.api_key.clone(), Vec::new())
+            .with_poll_interval(Duration::from_secs(news_config.poll_secs));
+        let monitor = NewsPortfolioMonitor::new(source, Duration::from_secs(news_config.keyword_refresh_mins * 60));
+        let positions = news_positions.clone();
+        let positions_fn: Arc<dyn Fn() -> Vec<polymarket_bot::types::Trade> + Send + Sync> =
+            Arc::new(move || positions.read().clone());
+        let (client, notifier) = (client.clone(), notifier.clone());
+        tokio::spawn(async move { monitor.run(positions_fn, client, notifier).await });
+        tracing::info!("Position news alerts enabled");
+    }
+
     tracing::info!("Bot initialized with real-time WebSocket feed...");
 
+    // ========== Graceful Shutdown ==========
+    // Cancel orders, flatten positions and checkpoint on SIGINT/SIGTERM
+    {
+        let shutdown = GracefulShutdown::from_config(&config.shutdown.clone().unwrap_or_default());
+        let state = cmd_handler.state.clone();
+        let executors = accounts.executors();
+        let notifier = notifier.clone();
+        let db = db.clone();
+        let telegram_bot = telegram_bot.clone();
+
+        tokio::spawn(async move {
+            let signal = GracefulShutdown::wait_for_signal().await;
+            tracing::warn!("Received {}, shutting down", signal);
+            if let Some(bot) = &telegram_bot {
+                bot.shutdown().await;
+            }
+            if dry_run {
+                state.write().await.shutdown_requested = true;
+                let _ = notifier.shutdown(signal).await;
+            } else {
+                shutdown.run(&state, &executors, &notifier, &db).await;
+            }
+            // Last chance for the shutdown notifications; undelivered
+            // critical ones go out on the next start
+            if let Err(e) = notifier.deliver_outbox(chrono::Utc::now()).await {
+                tracing::warn!("Failed to deliver notifications: {}", e);
+            }
+            std::process::exit(0);
+        });
+    }
+
+    // ========== Market Making ==========
+    // Quotes allowlisted markets on a fast loop; paper broker in dry-run
+    let mm_inputs = match config.market_maker.clone().filter(|c| c.enabled) {
+        Some(mm_config) => {
+            let inputs = Arc::new(MarketMakerInputs::new());
+            tracing::info!("Market maker enabled on {} markets", mm_config.markets.len());
+            if dry_run {
+                let broker = PaperBroker::new(client.clob.clone(), Decimal::new(1000, 0));
+                spawn_market_maker(broker, mm_config, inputs.clone(), db.clone(), notifier.clone());
+            } else {
+                spawn_market_maker(client.clob.clone(), mm_config, inputs.clone(), db.clone(), notifier.clone());
+            }
+            if let Some(vpin) = restored_vpin {
+                inputs.restore_vpin(vpin).await;
+            }
+            Some(inputs)
+        }
+        None => None,
+    };
+
     // ========== Signal Ingester Pipeline ==========
     // Spawn the external signal ingestion system if configured
-    let (parsed_signal_tx, mut parsed_signal_rx) = mpsc::channel::<ParsedSignal>(100);
+    let backpressure = config
+        .ingester
+        .as_ref()
+        .map(|c| c.backpressure.clone())
+        .unwrap_or_default();
+    let parsed_queue = Arc::new(
+        SignalQueue::<ParsedSignal>::new(backpressure.parsed_capacity, backpressure.policy)
+            .with_sample_every(backpressure.sample_every),
+    );
     
     if let Some(ingester_config) = &config.ingester {
         if ingester_config.enabled {
-            tracing::info!("Starting signal ingester pipeline...");
+            tracing::info!(
+                "Starting signal ingester pipeline (backpressure: {:?})...",
+                backpressure.policy
+            );
             
-            // Raw signal channel
-            let (raw_tx, raw_rx) = mpsc::channel::<RawSignal>(500);
+            // Raw signals: sources hand off through a small channel that is
+            // drained into the bounded queue, where the backpressure policy applies
+            let (raw_tx, raw_rx) = mpsc::channel::<RawSignal>(32);
+            let raw_queue = Arc::new(
+                SignalQueue::<RawSignal>::new(backpressure.raw_capacity, backpressure.policy)
+                    .with_sample_every(backpressure.sample_every),
+            );
+            tokio::spawn(ingester::backpressure::forward(raw_rx, raw_queue.clone()));
+
+            // Warn when ingestion is saturated
+            {
+                let raw_queue = raw_queue.clone();
+                let parsed_queue = parsed_queue.clone();
+                tokio::spawn(async move {
+                    let mut reported = (0, 0);
+                    loop {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        let dropped = (raw_queue.dropped(), parsed_queue.dropped());
+                        if dropped != reported {
+                            tracing::warn!(
+                                "Signal ingestion saturated: {} raw / {} parsed signals dropped (+{} / +{} in last minute)",
+                                dropped.0,
+                                dropped.1,
+                                dropped.0 - reported.0,
+                                dropped.1 - reported.1,
+                            );
+                            reported = dropped;
+                        }
+                    }
+                });
+            }
             
-            // Start signal sources
+            // Start signal sources, each counting what it receives under
+            // the source name its messages carry
+            let ingest_stats = ingest_stats.clone().unwrap_or_default();
             if let Some(tg_bot_config) = &ingester_config.telegram_bot {
-                let source = TelegramBotSource::new(
-                    tg_bot_config.bot_token.clone(),
-                    tg_bot_config.channels.clone(),
-                );
+                let tg_bot_config = tg_bot_config.clone();
                 let tx = raw_tx.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = source.run(tx).await {
-                        tracing::error!("Telegram bot source error: {}", e);
+                let stats = ingest_stats.handle("telegram", chrono::Utc::now());
+                watchdog.spawn_restartable("telegram_bot_source", task_stale_after, move |heartbeat| {
+                    let source = TelegramBotSource::new(
+                        tg_bot_config.bot_token.clone(),
+                        tg_bot_config.channels.clone(),
+                    )
+                    .with_heartbeat(heartbeat)
+                    .with_stats(stats.clone());
+                    let tx = tx.clone();
+                    async move {
+                        if let Err(e) = source.run(tx).await {
+                            tracing::error!("Telegram bot source error: {}", e);
+                        }
                     }
                 });
                 tracing::info!("Telegram bot source started");
@@ -229,32 +999,41 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             
             if let Some(twitter_config) = &ingester_config.twitter {
                 if twitter_config.bearer_token.is_some() {
-                    let source = TwitterSource::new(
-                        polymarket_bot::ingester::TwitterIngesterConfig {
-                            bearer_token: twitter_config.bearer_token.clone(),
-                            watch_users: twitter_config.user_ids.clone(),
-                            keywords: twitter_config.keywords.clone(),
-                        },
-                        ingester_config.author_trust.clone(),
-                    );
+                    let source_config = polymarket_bot::ingester::TwitterIngesterConfig {
+                        bearer_token: twitter_config.bearer_token.clone(),
+                        watch_users: twitter_config.user_ids.clone(),
+                        keywords: twitter_config.keywords.clone(),
+                    };
+                    let author_trust = ingester_config.author_trust.clone();
                     let tx = raw_tx.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = source.run(tx).await {
-                            tracing::error!("Twitter source error: {}", e);
+                    let stats = ingest_stats.handle("twitter", chrono::Utc::now());
+                    watchdog.spawn_restartable("twitter_source", task_stale_after, move |heartbeat| {
+                        let source = TwitterSource::new(source_config.clone(), author_trust.clone())
+                            .with_heartbeat(heartbeat)
+                            .with_stats(stats.clone());
+                        let tx = tx.clone();
+                        async move {
+                            if let Err(e) = source.run(tx).await {
+                                tracing::error!("Twitter source error: {}", e);
+                            }
                         }
                     });
                     tracing::info!("Twitter API source started");
                 } else if let Some(nitter) = &twitter_config.nitter_instance {
                     // Use RSS fallback
-                    let source = TwitterRssSource::new(
-                        nitter.clone(),
-                        twitter_config.user_ids.clone(),
-                        twitter_config.keywords.clone(),
-                    );
+                    let (instance, user_ids, keywords) =
+                        (nitter.clone(), twitter_config.user_ids.clone(), twitter_config.keywords.clone());
                     let tx = raw_tx.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = source.run(tx).await {
-                            tracing::error!("Twitter RSS source error: {}", e);
+                    let stats = ingest_stats.handle("twitter_rss", chrono::Utc::now());
+                    watchdog.spawn_restartable("twitter_rss_source", task_stale_after, move |heartbeat| {
+                        let source = TwitterRssSource::new(instance.clone(), user_ids.clone(), keywords.clone())
+                            .with_heartbeat(heartbeat)
+                            .with_stats(stats.clone());
+                        let tx = tx.clone();
+                        async move {
+                            if let Err(e) = source.run(tx).await {
+                                tracing::error!("Twitter RSS source error: {}", e);
+                            }
                         }
                     });
                     tracing::info!("Twitter RSS source started (via {})", nitter);
@@ -263,21 +1042,84 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             
             // Start signal processor
             if let Some(llm_config) = &config.llm {
-                let processor = SignalProcessor::new(llm_config.clone())
+                let mut processor = SignalProcessor::new(llm_config.clone())
                     .with_thresholds(
                         ingester_config.processing.min_confidence,
                         ingester_config.processing.min_agg_score,
                     )
-                    .with_window(ingester_config.processing.aggregation_window_secs);
+                    .with_window(ingester_config.processing.aggregation_window_secs)
+                    .with_dedup_threshold(ingester_config.dedup_threshold)
+                    .with_raw_signal_log(db.clone())
+                    .with_extraction_cache(db.clone())
+                    .with_ingest_stats(ingest_stats.clone());
+                if let Some(trust) = &author_trust {
+                    processor = processor.with_author_trust(trust.clone());
+                }
                 
-                let parsed_tx = parsed_signal_tx.clone();
+                let parsed_queue = parsed_queue.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = processor.run(raw_rx, parsed_tx).await {
+                    if let Err(e) = processor.run(raw_queue, parsed_queue).await {
                         tracing::error!("Signal processor error: {}", e);
                     }
                 });
                 tracing::info!("Signal processor started");
             }
+
+            // Warn once about each source gone silent; snapshot the counts
+            // every hour so they survive restarts
+            {
+                let db = db.clone();
+                let notifier = notifier.clone();
+                let threshold = chrono::Duration::seconds(ingester_config.stale_source_secs as i64);
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(Duration::from_secs(60));
+                    let mut saved_hour = None;
+                    loop {
+                        interval.tick().await;
+                        let now = chrono::Utc::now();
+                        let stale = ingest_stats.newly_stale(threshold, now);
+                        if !stale.is_empty() {
+                            let names: Vec<&str> = stale.iter().map(|s| s.source.as_str()).collect();
+                            tracing::warn!("Signal sources silent for over {}s: {}", threshold.num_seconds(), names.join(", "));
+                            if let Err(e) = notifier.sources_stale(&stale, threshold).await {
+                                tracing::warn!("Failed to send silent source warning: {}", e);
+                            }
+                        }
+                        if saved_hour != Some(now.hour()) {
+                            match db.save_ingest_stats(&ingest_stats.snapshot(), now).await {
+                                Ok(()) => saved_hour = Some(now.hour()),
+                                Err(e) => tracing::warn!("Failed to save ingestion stats: {}", e),
+                            }
+                        }
+                    }
+                });
+            }
+
+            // Periodically score matured signals and relearn author trust
+            if let Some(trust) = author_trust.clone() {
+                let db = db.clone();
+                let notifier = notifier.clone();
+                let interval_secs = ingester_config.trust.recompute_interval_secs.max(60);
+                tokio::spawn(async move {
+                    let mut prices = CryptoPriceTracker::new();
+                    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+                    loop {
+                        interval.tick().await;
+                        if let Err(e) = prices.update_prices().await {
+                            tracing::debug!("Failed to update prices for signal outcomes: {}", e);
+                        }
+                        let now = chrono::Utc::now();
+                        match ingester::trust::refresh_author_trust(&db, &trust, |t| prices.current_price(t), now).await {
+                            Ok(muted) if !muted.is_empty() => {
+                                tracing::warn!("Muted {} signal authors for poor accuracy", muted.len());
+                                let _ = notifier.authors_muted(&muted).await;
+                            }
+                            Ok(_) => {}
+                            Err(e) => tracing::warn!("Failed to refresh author trust: {}", e),
+                        }
+                    }
+                });
+            }
         }
     }
     
@@ -285,21 +1127,61 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
     {
         let notifier_for_signals = notifier.clone();
         let _executor_for_signals = executor.clone();
-        let _db_for_signals = db.clone();
+        let db_for_signals = db.clone();
         let _dry_run_mode = dry_run;
+        let learn_trust = author_trust.is_some();
+        let sentiment = sentiment.clone();
+        let signal_mapper = signal_mapper.clone();
+        let gamma_for_signals = client.gamma.clone();
         
         tokio::spawn(async move {
-            while let Some(signal) = parsed_signal_rx.recv().await {
+            let mut prices = CryptoPriceTracker::new();
+            while let Some(signal) = parsed_queue.pop().await {
                 tracing::info!(
+                    trace_id = %signal.trace_id,
                     "📊 Received aggregated signal: {} {:?} (score: {:.2}, conf: {:.2})",
                     signal.token,
                     signal.direction,
                     signal.agg_score,
                     signal.confidence
                 );
+                for source in &signal.sources {
+                    let detail = format!(
+                        "{} {} by {}: {}",
+                        source.source,
+                        source.source_id,
+                        source.author,
+                        source.content.chars().take(200).collect::<String>()
+                    );
+                    let event = TraceEvent { timestamp: source.timestamp, ..TraceEvent::new(signal.trace_id, TraceStage::RawSignal, detail) };
+                    record_trace(&db_for_signals, event).await;
+                }
+                let detail = format!(
+                    "{} {:?} over {} (score: {:.2}, conf: {:.2}, {} sources)",
+                    signal.token,
+                    signal.direction,
+                    signal.timeframe,
+                    signal.agg_score,
+                    signal.confidence,
+                    signal.sources.len()
+                );
+                record_trace(&db_for_signals, TraceEvent::new(signal.trace_id, TraceStage::ParsedSignal, detail)).await;
+                sentiment.record(&signal);
+
+                // Shadow-track every signal's outcome to learn author trust
+                if learn_trust {
+                    let _ = prices.update_prices().await;
+                    if let Some(entry_price) = prices.current_price(&signal.token) {
+                        let now = chrono::Utc::now();
+                        for outcome in ingester::trust::pending_outcomes(&signal, entry_price, now) {
+                            if let Err(e) = db_for_signals.record_signal_outcome(&outcome).await {
+                                tracing::warn!("Failed to record signal outcome: {}", e);
+                            }
+                        }
+                    }
+                }
                 
-                // TODO: Map external signals to Polymarket markets
-                // For now, just notify about high-confidence signals
+                let mapped = signal_mapper.map(&signal, &gamma_for_signals).await;
                 if signal.agg_score >= 0.7 {
                     let msg = format!(
                         "🎯 *High Confidence Signal*\n\n\
@@ -308,7 +1190,8 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                         Score: {:.0}%\n\
                         Confidence: {:.0}%\n\
                         Timeframe: {}\n\
-                        Sources: {}\n\n\
+                        Sources: {}\n\
+                        Markets: {}\n\n\
                         Reasoning: {}",
                         signal.token,
                         signal.direction,
@@ -316,12 +1199,17 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                         signal.confidence * 100.0,
                         signal.timeframe,
                         signal.sources.len(),
+                        if mapped.is_empty() {
+                            "none".to_string()
+                        } else {
+                            mapped.iter().map(|(id, conf)| format!("`{}` ({:.0}%)", id, conf * 100.0)).collect::<Vec<_>>().join(", ")
+                        },
                         signal.reasoning
                     );
                     
                     let _ = notifier_for_signals.send_raw(&msg).await;
                     
-                    // TODO: Execute trade when we can map signals to markets
+                    // TODO: Execute trades on the mapped markets
                     // if !dry_run_mode {
                     //     if let Ok(Some(trade)) = executor_for_signals.execute_external_signal(&signal).await {
                     //         let _ = notifier_for_signals.trade_executed(&trade, &signal.token).await;
@@ -339,7 +1227,8 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             tracing::info!("Starting copy trading module...");
             
             let mut copy_trader = CopyTrader::new()
-                .with_copy_ratio(copy_config.copy_ratio);
+                .with_copy_ratio(copy_config.copy_ratio)
+                .with_min_weight(copy_config.min_leader_weight);
             
             // Add traders to follow
             for username in &copy_config.follow_users {
@@ -350,6 +1239,10 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                     total_profit: Decimal::ZERO,
                     weight: 1.0,
                     updated_at: chrono::Utc::now(),
+                    trades: 0,
+                    volume_usdc: Decimal::ZERO,
+                    sharpe_30d: 0.0,
+                    performance_score: 0.0,
                 };
                 copy_trader.add_trader(trader);
                 tracing::info!("Following trader: @{}", username);
@@ -364,6 +1257,10 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                     total_profit: Decimal::ZERO,
                     weight: 1.0,
                     updated_at: chrono::Utc::now(),
+                    trades: 0,
+                    volume_usdc: Decimal::ZERO,
+                    sharpe_30d: 0.0,
+                    performance_score: 0.0,
                 };
                 copy_trader.add_trader(trader);
                 tracing::info!("Following address: {}", address);
@@ -373,12 +1270,92 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             let notifier_for_copy = notifier.clone();
             let delay_secs = copy_config.delay_secs;
             let _dry_run_copy = dry_run;
+            let auto_discover = copy_config.auto_discover;
+            let discovery = copy_config.discovery.clone();
+            let client_for_copy = client.clone();
+            let db_for_copy = db.clone();
+            let copy_heartbeat = watchdog.watch("copy_trade", task_stale_after);
+            let recalibrate_min_trades = copy_config.recalibrate_min_trades;
             
             tokio::spawn(async move {
                 let mut interval = tokio::time::interval(Duration::from_secs(30));
+                let mut last_recalibration: Option<chrono::DateTime<chrono::Utc>> = None;
+                let mut last_discovery = match db_for_copy.latest_leaderboard_snapshot().await {
+                    Ok(snapshot) => snapshot.map(|s| s.timestamp),
+                    Err(e) => {
+                        tracing::warn!("Failed to load leaderboard snapshot: {}", e);
+                        None
+                    }
+                };
                 
                 loop {
                     interval.tick().await;
+                    copy_heartbeat.beat();
+
+                    // Refresh the follow list from the leaderboard weekly
+                    let now = chrono::Utc::now();
+                    let discovery_due = last_discovery
+                        .map(|t| now - t >= chrono::Duration::days(7))
+                        .unwrap_or(true);
+                    if auto_discover && discovery_due {
+                        last_discovery = Some(now);
+                        match TraderDiscovery::fetch_leaderboard(
+                            &client_for_copy,
+                            discovery.min_trades,
+                            discovery.min_volume_usdc,
+                            discovery.lookback_days,
+                        )
+                        .await
+                        {
+                            Ok(leaderboard) => {
+                                let update = plan_follow_update(
+                                    copy_trader.traders(),
+                                    &leaderboard,
+                                    discovery.add_threshold,
+                                    discovery.remove_threshold,
+                                );
+                                copy_trader.apply_follow_update(&update);
+                                if !update.is_empty() {
+                                    let added: Vec<_> = update.added.iter().map(|t| t.username.clone()).collect();
+                                    let removed: Vec<_> = update.removed.iter().map(|t| t.username.clone()).collect();
+                                    tracing::info!("Copy trader list updated: +{:?} -{:?}", added, removed);
+                                    let _ = notifier_for_copy.copy_traders_updated(&added, &removed).await;
+                                }
+                                let snapshot = LeaderboardSnapshot { timestamp: now, traders: leaderboard };
+                                if let Err(e) = db_for_copy.save_leaderboard_snapshot(&snapshot).await {
+                                    tracing::warn!("Failed to save leaderboard snapshot: {}", e);
+                                }
+                            }
+                            Err(e) => tracing::warn!("Trader discovery failed: {}", e),
+                        }
+                    }
+
+                    // Reweight traders from how their copies turned out, hourly
+                    let recalibration_due = last_recalibration
+                        .map(|t| now - t >= chrono::Duration::hours(1))
+                        .unwrap_or(true);
+                    if recalibration_due {
+                        last_recalibration = Some(now);
+                        if let Err(e) = settle_copied_trades(&db_for_copy, &copy_trader, client_for_copy.data_api_url()).await {
+                            tracing::warn!("Failed to settle copied trades: {}", e);
+                        }
+                        match db_for_copy.get_copy_leader_outcomes().await {
+                            Ok(outcomes) => {
+                                for trader in copy_trader.recalibrate(&outcomes, recalibrate_min_trades) {
+                                    tracing::warn!(
+                                        "Pausing copy trading of @{}: win rate {:.0}%, P&L ${:.2}",
+                                        trader.username,
+                                        trader.win_rate * 100.0,
+                                        trader.total_profit
+                                    );
+                                    let _ = notifier_for_copy
+                                        .copy_trader_paused(&trader.username, trader.win_rate, trader.total_profit)
+                                        .await;
+                                }
+                            }
+                            Err(e) => tracing::warn!("Failed to load copy leader outcomes: {}", e),
+                        }
+                    }
                     
                     match copy_trader.check_for_signals().await {
                         Ok(signals) => {
@@ -393,6 +1370,10 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                                     signal.market_id
                                 );
                                 
+                                if let Err(e) = db_for_copy.record_copied_trade(&CopiedTrade::from_signal(&signal)).await {
+                                    tracing::warn!("Failed to record copied trade: {}", e);
+                                }
+
                                 // Delay before copying
                                 if delay_secs > 0 {
                                     tokio::time::sleep(Duration::from_secs(delay_secs)).await;
@@ -437,47 +1418,281 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
         }
     }
 
-    // Spawn daily report task
-    if tg_config.as_ref().map(|c| c.notify_daily).unwrap_or(false) {
-        let notifier_clone = notifier.clone();
-        let db_clone = db.clone();
-        let client_clone = client.clone();
-        let risk_manager_clone = risk_manager.clone();
-        
+    // Rebuild market profiles at midnight UTC
+    if let Some(profiles) = market_profiles.clone() {
+        let db = db.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // Check hourly
-            // Wait for first tick (starts immediately)
-            interval.tick().await;
-            
+            let mut schedule = DailySchedule::new(Arc::new(SystemClock), Duration::from_secs(60));
             loop {
-                interval.tick().await;
+                let now = schedule.next().await;
+                match profiles.refresh(&db, now).await {
+                    Ok(count) => tracing::info!("Refreshed {} market profiles", count),
+                    Err(e) => tracing::warn!("Failed to refresh market profiles: {}", e),
+                }
+            }
+        });
+    }
+
+    // Watch open positions for resolved markets and redeem the winnings
+    let mut redeemer_handle: Option<Arc<Redeemer>> = None;
+    if let Some(redeem_config) = config.redeem.clone().filter(|_| !dry_run) {
+        let primary = accounts.primary();
+        let mut redeemer = Redeemer::new(db.clone(), notifier.clone(), redeem_config.clone()).with_account(primary.id.clone());
+        if redeem_config.auto_redeem {
+            let wallet = config
+                .polymarket
+                .account_configs()
+                .into_iter()
+                .find(|a| a.id == primary.id)
+                .map(|a| config.polymarket.for_account(&a));
+            match wallet.map(|w| CtfRedeemer::new(&w, &redeem_config, primary.client.gamma.clone())) {
+                Some(Ok(client)) => redeemer = redeemer.with_client(Arc::new(client)),
+                Some(Err(e)) => tracing::warn!("Auto-redeem disabled: {}", e),
+                None => tracing::warn!("Auto-redeem disabled: no wallet for account {}", primary.id),
+            }
+        }
+        let redeemer = Arc::new(redeemer);
+        redeemer_handle = Some(redeemer.clone());
+        let gamma = primary.client.gamma.clone();
+        let db = db.clone();
+        let state = cmd_handler.state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(redeem_config.check_interval_secs));
+            loop {
+                interval.tick().await;
+                let mut markets = std::collections::HashMap::new();
+                for market_id in db.get_open_position_markets().await.unwrap_or_default() {
+                    match gamma.get_market(&market_id).await {
+                        Ok(market) => {
+                            markets.insert(market_id, market);
+                        }
+                        Err(e) => tracing::warn!("Failed to check resolution of {}: {}", market_id, e),
+                    }
+                }
+                match redeemer.run_once(&markets).await {
+                    Ok(redemptions) if !redemptions.is_empty() => tracing::info!("Redeemed {} positions", redemptions.len()),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Redemption check failed: {}", e),
+                }
+                state.write().await.pending_redemption = redeemer.pending_value();
+            }
+        });
+    }
+
+    // Spawn daily report task
+    if tg_config.as_ref().map(|c| c.notify_daily).unwrap_or(false) {
+        let notifier_clone = notifier.clone();
+        let db_clone = db.clone();
+        let accounts_clone = accounts.clone();
+        let risk_manager_clone = risk_manager.clone();
+        let sim_clone = simulation.as_ref().map(|(sim, _)| sim.clone());
+        let monitor_clone = monitor.clone();
+        let fees_clone = fees.clone();
+        let ingest_stats_clone = ingest_stats.clone();
+        let llm_prices = llm_prices(&config);
+        
+        tokio::spawn(async move {
+            // Send daily report at midnight UTC and reset risk tracker
+            let mut schedule = DailySchedule::new(Arc::new(SystemClock), Duration::from_secs(60));
+            loop {
+                let now = schedule.next().await;
+                // Reset daily risk tracker
+                {
+                    let mut rm = risk_manager_clone.lock().await;
+                    let daily_pnl = rm.daily_pnl();
+                    let win_rate = rm.pnl_tracker.win_rate();
+                    tracing::info!(
+                        "📊 Daily risk reset - P&L: ${:.2}, Win rate: {:.1}%",
+                        daily_pnl,
+                        win_rate.unwrap_or(0.0)
+                    );
+                    rm.reset_daily();
+                }
                 
-                // Send daily report at midnight UTC and reset risk tracker
-                let now = chrono::Utc::now();
-                if now.hour() == 0 && now.minute() < 5 {
-                    // Reset daily risk tracker
-                    {
-                        let mut rm = risk_manager_clone.lock().await;
-                        let daily_pnl = rm.daily_pnl();
-                        let win_rate = rm.pnl_tracker.win_rate();
-                        tracing::info!(
-                            "📊 Daily risk reset - P&L: ${:.2}, Win rate: {:.1}%",
-                            daily_pnl,
-                            win_rate.unwrap_or(0.0)
-                        );
-                        rm.reset_daily();
+                let balance = match &sim_clone {
+                    Some(sim) => {
+                        let _ = notifier_clone.simulation_report(&sim.get_simulation_stats()).await;
+                        sim.get_balance().await.unwrap_or(Decimal::ZERO)
+                    }
+                    None => {
+                        let mut total = Decimal::ZERO;
+                        for account in accounts_clone.iter() {
+                            total += account.client.clob.get_balance().await.unwrap_or(Decimal::ZERO);
+                        }
+                        total
+                    }
+                };
+                let stats = db_clone.get_daily_stats().await.unwrap_or_default();
+                let by_strategy = db_clone.get_pnl_by_strategy().await.unwrap_or_default();
+                let by_account = db_clone.get_pnl_by_account().await.unwrap_or_default();
+                let shadow = db_clone.get_shadow_pnl().await.unwrap_or_default();
+                let performance = monitor_clone.rolling_performance().await;
+
+                // Pick up moved end dates before laddering open positions
+                if let Err(e) = refresh_position_markets(&db_clone, &accounts_clone.primary().client.gamma).await {
+                    tracing::warn!("Failed to refresh position markets: {}", e);
+                }
+                if let Err(e) = resolve_predictions(&db_clone, &accounts_clone.primary().client.gamma).await {
+                    tracing::warn!("Failed to resolve predictions: {}", e);
+                }
+                let ladder = db_clone
+                    .get_ladder_positions()
+                    .await
+                    .map(|positions| CapitalLadder::build(&positions, now))
+                    .ok();
+                let sources = ingest_stats_clone.as_ref().map(|s| s.snapshot()).unwrap_or_default();
+                let _ = notifier_clone
+                    .daily_report(
+                        &stats,
+                        balance,
+                        &by_strategy,
+                        &by_account,
+                        &shadow,
+                        Some(&performance),
+                        ladder.as_ref(),
+                        &sources,
+                    )
+                    .await;
+
+                let liquidation = match simulate_forced_liquidation(&db_clone, &accounts_clone.primary().client.clob).await {
+                    Ok(report) => {
+                        if report.liquidation_cost_pct > LIQUIDATION_ALERT_PCT {
+                            let _ = notifier_clone.liquidation_cost_high(&report).await;
+                        }
+                        Some(report)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to simulate forced liquidation: {}", e);
+                        None
+                    }
+                };
+
+                // Weekly report on Monday covering the previous seven days
+                if now.weekday() == chrono::Weekday::Mon {
+                    let week_ago = now - chrono::Duration::days(7);
+                    let trades = db_clone.get_trades_since(week_ago).await.unwrap_or_default();
+                    let interventions = db_clone.count_manual_interventions(week_ago).await.unwrap_or(0);
+                    let equity_curve: Vec<Decimal> = db_clone
+                        .get_portfolio_history(week_ago, now)
+                        .await
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|s| s.value)
+                        .collect();
+                    let stress = stress_test_positions(&db_clone, now, chrono::Duration::days(STRESS_HISTORY_DAYS))
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::debug!("Skipping weekly stress tests: {}", e);
+                            Vec::new()
+                        });
+                    let _ = notifier_clone
+                        .weekly_report(balance, &trades, interventions, &equity_curve, &stress, liquidation.as_ref())
+                        .await;
+                }
+
+                // Tag attribution on the 1st covering the previous month
+                if now.day() == 1 {
+                    let month_ago = now.checked_sub_months(chrono::Months::new(1)).unwrap_or(now);
+                    match db_clone.performance_by_tag(month_ago, now).await {
+                        Ok(by_tag) => {
+                            let month = month_ago.format("%Y-%m").to_string();
+                            let _ = notifier_clone.monthly_attribution(&month, &by_tag).await;
+                        }
+                        Err(e) => tracing::warn!("Failed to compute tag attribution: {}", e),
+                    }
+
+                    let month = month_ago.format("%Y-%m").to_string();
+                    match MonthlyReport::build(&db_clone, &month, fees_clone.as_ref(), llm_prices).await {
+                        Ok(report) => {
+                            if let Err(e) = report.write_json(std::path::Path::new(REPORT_DIR)) {
+                                tracing::warn!("{}", e);
+                            }
+                            let _ = notifier_clone.monthly_report(&report).await;
+                        }
+                        Err(e) => tracing::warn!("Failed to build the monthly report: {}", e),
                     }
-                    
-                    let balance = client_clone.clob.get_balance().await.unwrap_or(Decimal::ZERO);
-                    let stats = db_clone.get_daily_stats().await.unwrap_or_default();
-                    let _ = notifier_clone.daily_report(&stats, balance).await;
                 }
             }
         });
     }
 
+    let drawdown_tiers = DynamicKellyConfig::default();
+    let mut drawdown_tier = polymarket_bot::strategy::DrawdownTier::Normal;
+    let mut peak_balance = Decimal::ZERO;
+    let mut last_equity: Option<Decimal> = None;
+    let mut account_balances: std::collections::HashMap<String, Decimal> = std::collections::HashMap::new();
+    // Balance fetch and execution failures are reported when error notifications are on
+    let error_notifier = tg_config.as_ref().is_some_and(|c| c.notify_errors).then_some(notifier.as_ref());
+    let alert_rules = config.alerts.clone().unwrap_or_default().rules;
+    ExpressionEvaluator::validate(&alert_rules)?;
+    let alert_engine = Arc::new(parking_lot::Mutex::new(AlertEngine::new()));
+    metrics.register(alert_engine.clone());
+    let mut alerted_volumes: std::collections::HashMap<String, Decimal> = std::collections::HashMap::new();
+
+    // Signals held back by position limits survive restarts
+    let mut position_queue = PositionQueue::from_config(&config.risk);
+    match db.load_position_queue().await {
+        Ok(entries) => position_queue.restore(entries),
+        Err(e) => tracing::warn!("Failed to load signal queue: {}", e),
+    }
+
+    // Market scan filters; crypto Up/Down markets carry no category, so they
+    // are fetched separately when the category list allows them
+    let scan_config = config.markets.clone().unwrap_or_default();
+    let market_filter = MarketFilter {
+        categories: scan_config.categories.clone(),
+        min_liquidity: scan_config.min_liquidity,
+        max_days_to_resolution: scan_config.max_days_to_resolution,
+        min_volume: scan_config.min_volume,
+    };
+    let crypto_filter = MarketFilter {
+        categories: Vec::new(),
+        min_liquidity: scan_config.min_crypto_liquidity,
+        ..market_filter.clone()
+    };
+    let scan_crypto = scan_config.categories.is_empty()
+        || scan_config.categories.iter().any(|c| c.eq_ignore_ascii_case("crypto"));
+
+    // Limit orders worked on wide books, re-adopted from before a restart
+    let mut resting = match config.resting_orders.clone().filter(|c| c.enabled) {
+        Some(resting_config) => {
+            let mut strategy = RestingOrderStrategy::new(resting_config).with_database(db.clone());
+            match db.load_resting_orders().await {
+                Ok(orders) => {
+                    if !orders.is_empty() {
+                        tracing::info!("Re-adopted {} resting orders", orders.len());
+                    }
+                    strategy.restore(orders);
+                }
+                Err(e) => tracing::warn!("Failed to load resting orders: {}", e),
+            }
+            Some(strategy)
+        }
+        None => None,
+    };
+
+    let mut last_btc_price: Option<Decimal> = None;
+    // Last regime position limit alerted, so each is sent once
+    let mut last_limit_alert: Option<String> = None;
+    let mut last_state_snapshot = std::time::Instant::now();
+    let mut scan_cycles: u64 = 0;
+
+    // A stalled main loop stops trading altogether
+    let main_loop_heartbeat = watchdog.watch_critical(
+        "main_loop",
+        Duration::from_secs(watchdog_config.main_loop_stale_secs.max(3 * config.strategy.scan_interval_secs)),
+    );
+
     // Main trading loop
     loop {
+        main_loop_heartbeat.beat();
+
+        // Stop trading while shutdown cleanup runs
+        if cmd_handler.is_shutting_down().await {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+
         // Process any pending Telegram commands
         while let Ok(cmd) = cmd_rx.try_recv() {
             cmd_handler.handle(cmd, &client, &db).await;
@@ -490,28 +1705,50 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             continue;
         }
 
-        // Get portfolio value (use simulated balance in dry-run mode)
-        let balance = if dry_run {
-            Decimal::new(1000, 0)  // $1000 simulated balance
+        // Get portfolio value, summed over accounts (simulated account in
+        // dry-run mode); accounts whose balance can't be fetched sit out the scan
+        let balance = if let Some((sim, _)) = &simulation {
+            sim.get_balance().await?
         } else {
-            match executor.clob.get_balance().await {
-                Ok(b) => b,
-                Err(e) => {
-                    tracing::error!("Failed to get balance: {}", e);
-                    if tg_config.as_ref().map(|c| c.notify_errors).unwrap_or(false) {
-                        let _ = notifier.error("Balance fetch", &e.to_string()).await;
-                    }
-                    tokio::time::sleep(Duration::from_secs(60)).await;
-                    continue;
+            let clients: Vec<_> = accounts.iter().map(|a| (a.id.as_str(), &a.client.clob)).collect();
+            account_balances = fetch_balances(&clients, &risk_manager, error_notifier).await;
+            // Winnings awaiting redemption are sized off like cash
+            if let Some(redeemer) = &redeemer_handle {
+                if let Some(balance) = account_balances.get_mut(&accounts.primary().id) {
+                    *balance += redeemer.pending_value();
                 }
             }
+            if account_balances.is_empty() {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                continue;
+            }
+            account_balances.values().sum()
         };
 
         tracing::info!("Current balance: ${:.2}", balance);
 
+        // Journal drawdown tier changes
+        peak_balance = peak_balance.max(balance);
+        if peak_balance > Decimal::ZERO {
+            let tier = drawdown_tiers.drawdown_tier((peak_balance - balance) / peak_balance);
+            if tier != drawdown_tier {
+                let entry = JournalEntry::auto(
+                    JournalKind::DrawdownTier,
+                    format!("Drawdown tier {:?} → {:?} (balance ${:.2}, peak ${:.2})", drawdown_tier, tier, balance, peak_balance),
+                );
+                if let Err(e) = db.add_journal_entry(&entry).await {
+                    tracing::warn!("Failed to journal drawdown tier: {}", e);
+                }
+                drawdown_tier = tier;
+            }
+        }
+
         // Get top markets + crypto markets
-        let mut markets = match client.gamma.get_top_markets(20).await {
-            Ok(m) => m,
+        let mut markets = match client.gamma.get_all_markets(&market_filter, scan_config.max_pages).await {
+            Ok(mut m) => {
+                m.truncate(scan_config.limit);
+                m
+            }
             Err(e) => {
                 tracing::error!("Failed to get markets: {}", e);
                 if tg_config.as_ref().map(|c| c.notify_errors).unwrap_or(false) {
@@ -523,23 +1760,252 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
         };
 
         // Also fetch crypto markets (BTC/ETH Up/Down)
-        match client.gamma.get_crypto_markets().await {
-            Ok(crypto_markets) => {
-                tracing::info!("Found {} crypto markets", crypto_markets.len());
-                markets.extend(crypto_markets);
+        if scan_crypto {
+            match client.gamma.get_crypto_markets().await {
+                Ok(crypto_markets) => {
+                    tracing::info!("Found {} crypto markets", crypto_markets.len());
+                    let now = chrono::Utc::now();
+                    markets.extend(crypto_markets.into_iter().filter(|m| crypto_filter.matches(m, now)));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch crypto markets: {}", e);
+                }
             }
-            Err(e) => {
-                tracing::warn!("Failed to fetch crypto markets: {}", e);
+        }
+
+        // Plus pinned markets, below the liquidity floor too
+        let pinned = pinned_markets.fetch(&client.gamma, &markets).await;
+        markets.extend(pinned);
+
+        // Plus newly discovered markets whose first look showed a large edge
+        if let Some(watchlist) = &discovery_watchlist {
+            let discovered = watchlist.fetch(&client.gamma, &markets).await;
+            markets.extend(discovered);
+        }
+        monitor.record_cache_stats(client.gamma.cache_stats()).await;
+        let outbox = notifier.outbox_stats();
+        monitor.record_notification_outbox(outbox.depth, outbox.delivery_failures).await;
+
+        // Mark the simulated account to the latest market prices
+        if let Some((sim, _)) = &simulation {
+            let prices = markets
+                .iter()
+                .flat_map(|m| &m.outcomes)
+                .map(|o| (o.token_id.clone(), o.price))
+                .collect();
+            if let Err(e) = sim.advance_time(&prices).await {
+                tracing::warn!("Failed to mark simulated positions: {}", e);
+            }
+        }
+
+        // Mark shadow fills for counterfactual P&L
+        if let Err(e) = shadow_router.mark(&markets).await {
+            tracing::warn!("Failed to mark shadow trades: {}", e);
+        }
+
+        // Feed the rolling Sharpe/Sortino with this cycle's mark-to-market return
+        let equity = match &simulation {
+            Some((sim, _)) => sim.get_simulation_stats().equity,
+            None => {
+                let prices: std::collections::HashMap<&str, Decimal> = markets
+                    .iter()
+                    .flat_map(|m| &m.outcomes)
+                    .map(|o| (o.token_id.as_str(), o.price))
+                    .collect();
+                let mut equity = balance;
+                for account in accounts.iter() {
+                    let positions = account.executor.get_positions().await;
+                    equity += positions
+                        .iter()
+                        .filter_map(|(token, size)| prices.get(token.as_str()).map(|p| p * size))
+                        .sum::<Decimal>();
+                }
+                equity
+            }
+        };
+        if let Some(last) = last_equity.filter(|e| !e.is_zero()) {
+            monitor.record_scan_return(chrono::Utc::now(), (equity - last) / last).await;
+        }
+        last_equity = Some(equity);
+
+        // Portfolio value time series for the equity curve
+        monitor.record_portfolio_value(equity).await;
+        let mut probe_cancellations = 0;
+        let mut probe_cost = Decimal::ZERO;
+        let mut expired_signals = 0;
+        for executor in accounts.iter().map(|a| a.executor.as_ref()) {
+            probe_cancellations += executor.probe_cancellations_total();
+            probe_cost += executor.probe_slippage_cost_usdc().await;
+            if let Err(e) = executor.cancel_expired_orders().await {
+                tracing::warn!("Failed to cancel expired orders: {}", e);
+            }
+            expired_signals += executor.expired_signals_total();
+        }
+        if let Some((_, executor)) = &simulation {
+            probe_cancellations += executor.probe_cancellations_total();
+            probe_cost += executor.probe_slippage_cost_usdc().await;
+            if let Err(e) = executor.cancel_expired_orders().await {
+                tracing::warn!("Failed to cancel expired orders: {}", e);
+            }
+            expired_signals += executor.expired_signals_total();
+        }
+        monitor.record_probe_stats(probe_cancellations, probe_cost).await;
+        monitor.record_expired_signals(expired_signals).await;
+        let snapshot = match &simulation {
+            Some((sim, _)) => {
+                let stats = sim.get_simulation_stats();
+                Ok((stats.realized_pnl, stats.unrealized_pnl, stats.open_positions as u32))
+            }
+            None => position_pnl(&db, &markets).await,
+        };
+        match snapshot {
+            Ok((realized, unrealized, open)) => {
+                if let Err(e) = db.save_portfolio_snapshot(equity, realized, unrealized, open, balance).await {
+                    tracing::warn!("Failed to save portfolio snapshot: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to value open positions: {}", e),
+        }
+        // Daily prices the monthly report's benchmark replays
+        let now = chrono::Utc::now();
+        if let Err(e) = db.save_price_snapshots(&markets, now).await {
+            tracing::warn!("Failed to save price snapshots: {}", e);
+        }
+        if let Some(usage) = &llm_usage {
+            if let Err(e) = db.record_llm_usage(now.date_naive(), usage.take()).await {
+                tracing::warn!("Failed to record LLM usage: {}", e);
             }
         }
 
+        // User-defined alert rules
+        if !alert_rules.is_empty() {
+            let mut open_positions = 0;
+            for account in accounts.iter() {
+                open_positions += account.executor.get_positions().await.values().filter(|s| !s.is_zero()).count();
+            }
+            let consecutive_losses = match db.get_consecutive_losses().await {
+                Ok(n) => n,
+                Err(e) => {
+                    tracing::warn!("Failed to count consecutive losses: {}", e);
+                    0
+                }
+            };
+            let performance = monitor.rolling_performance().await;
+            let to_f64 = |d: Option<Decimal>| d.and_then(|d| d.to_f64()).unwrap_or(f64::NAN);
+            let metrics = BotMetrics {
+                daily_pnl: to_f64(Some(risk_manager.lock().await.daily_pnl())),
+                balance: to_f64(Some(balance)),
+                equity: to_f64(Some(equity)),
+                drawdown: to_f64((peak_balance > Decimal::ZERO).then(|| (peak_balance - balance) / peak_balance)),
+                open_positions: open_positions as f64,
+                consecutive_losses: consecutive_losses as f64,
+                rolling_sharpe: to_f64(performance.sharpe()),
+                rolling_sortino: to_f64(performance.sortino()),
+            };
+            let fired = alert_engine.lock().evaluate_all(&alert_rules, &metrics);
+            for alert in fired {
+                tracing::warn!("Alert {} fired: {}", alert.rule, alert.message);
+                if let Err(e) = notifier.alert_fired(&alert).await {
+                    tracing::warn!("Failed to send alert: {}", e);
+                }
+            }
+        }
+
+        // Per-market alerts set from Telegram
+        check_market_alerts(&db, &client, &notifier, &markets, &mut alerted_volumes).await;
+
         // Update crypto prices for HF strategy
-        if let Err(e) = crypto_tracker.update_prices().await {
-            tracing::debug!("Failed to update crypto prices: {}", e);
+        match crypto_tracker.update_prices().await {
+            Ok(()) => feed_heartbeat.beat(),
+            Err(e) => tracing::debug!("Failed to update crypto prices: {}", e),
+        }
+
+        // BTC's move since the previous scan is one bar of the regime detector
+        if let Some(price) = crypto_tracker.current_price("BTC") {
+            let open = last_btc_price.replace(price).unwrap_or(price);
+            let bar = PriceBar {
+                timestamp: chrono::Utc::now(),
+                open,
+                high: open.max(price),
+                low: open.min(price),
+                close: price,
+                volume: Decimal::ZERO,
+            };
+            multi_regime.update(bar.clone());
+            risk_manager.lock().await.set_regime_consensus(multi_regime.consensus_regime());
+            let detection = regime_detector.update(bar);
+            if let Some((from, to)) = regime_detector.get_transition() {
+                tracing::info!("BTC regime changed: {:?} → {:?}", from, to);
+                let event = BotEvent::RegimeChanged { symbol: "BTC".to_string(), from, to, timestamp: chrono::Utc::now() };
+                emit_all(&event_sinks, event).await;
+            }
+            if let Some(detection) = &detection {
+                risk_manager.lock().await.set_regime(detection.regime);
+            }
+            let switch = strategy_selector.as_mut().zip(detection.as_ref()).and_then(|(s, d)| s.update(d));
+            if let Some(switch) = switch {
+                tracing::info!(
+                    "Active strategy {} → {} ({:?}, confidence {:.0}%)",
+                    switch.from,
+                    switch.to,
+                    switch.regime,
+                    switch.confidence * Decimal::ONE_HUNDRED
+                );
+                monitor.record_strategy_switch(&switch.from, &switch.to).await;
+                if let Err(e) = notifier.strategy_switched(&switch).await {
+                    tracing::warn!("Failed to send strategy switch notification: {}", e);
+                }
+            }
+        }
+
+        // Checkpoint warm state for crash recovery; written in the background
+        if let Some(writer) = &snapshot_writer {
+            if last_state_snapshot.elapsed() >= Duration::from_secs(recovery_config.interval_secs) {
+                let mut snapshot = StateSnapshot::new(chrono::Utc::now());
+                snapshot.crypto = crypto_tracker.state();
+                snapshot.regime = Some(regime_detector.state());
+                for account in accounts.iter() {
+                    snapshot.positions.insert(account.id.clone(), account.executor.get_positions().await);
+                }
+                if let Some((_, executor)) = &simulation {
+                    snapshot.positions.insert(SIMULATED_ACCOUNT_ID.to_string(), executor.get_positions().await);
+                }
+                if let Some(inputs) = &mm_inputs {
+                    snapshot.vpin = inputs.vpin_states().await;
+                }
+                writer.submit(snapshot);
+                last_state_snapshot = std::time::Instant::now();
+            }
+        }
+        scan_cycles += 1;
+        if recovery_config.enabled && scan_cycles.is_multiple_of(STATE_SAVE_EVERY_CYCLES) {
+            if let Err(e) = db.save_state(&crypto_tracker.state()).await {
+                tracing::warn!("Failed to save crypto tracker state: {}", e);
+            }
+            if let Err(e) = db.save_state(&regime_detector.state()).await {
+                tracing::warn!("Failed to save regime detector state: {}", e);
+            }
+        }
+
+        // Crypto Up/Down markets carry their own fee rates
+        for market in markets.iter() {
+            if CryptoHfStrategy::is_crypto_hf_market(market).is_some() {
+                fees.set_market_default(&market.id, fee_config.crypto_updown);
+            }
+        }
+
+        // Group negRisk markets by event so exposure is judged per event
+        for market in markets.iter().filter(|m| m.neg_risk) {
+            for account in accounts.iter() {
+                account.executor.register_market(market).await;
+            }
+            if let Err(e) = db.save_market_event(market).await {
+                tracing::debug!("Failed to save event for {}: {}", market.id, e);
+            }
         }
 
         tracing::info!("Scanning {} markets...", markets.len());
-        
+
         // Check risk limits before trading
         {
             let rm = risk_manager.lock().await;
@@ -556,6 +2022,102 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
             }
         }
 
+        // Position slots for this scan, fewer in a drawdown. Slots freed
+        // since the last scan go to queued signals first, best net edge
+        // first, if their markets are in the scan and the edge is still there.
+        let position_limits = PositionLimits::new(&config.risk, None).for_drawdown(drawdown_tier);
+        let mut open_counts = match db.get_open_positions().await {
+            Ok(positions) => OpenPositionCounts::from_positions(&positions),
+            Err(e) => {
+                tracing::warn!("Failed to count open positions: {}", e);
+                OpenPositionCounts::default()
+            }
+        };
+
+        // Portfolio heat at the open positions' entry edges: half Kelly
+        // near the budget, hedges only past it
+        for market in &markets {
+            heat_monitor.register_market(market);
+        }
+        let heat_positions = db.get_open_position_trades().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load open positions for heat: {}", e);
+            Vec::new()
+        });
+        let heat = heat_monitor.calculate(&heat_positions, &std::collections::HashMap::new());
+        *news_positions.write() = heat_positions.clone();
+        // Open positions against the regime's cap, kept current through the scan
+        let mut regime_positions = heat_positions.clone();
+        monitor.record_portfolio_heat(&heat).await;
+        if heat_monitor.max_heat_budget > Decimal::ZERO {
+            cmd_handler.state.write().await.heat_utilization = Some(heat.heat_utilization);
+        }
+        if heat.heat_utilization > HEAT_WARNING {
+            tracing::warn!(
+                "🌡️ Portfolio heat ${:.2} is {:.0}% of budget (hottest: {})",
+                heat.total_heat_usdc,
+                heat.heat_utilization * Decimal::ONE_HUNDRED,
+                heat.hottest_position.as_deref().unwrap_or("-")
+            );
+        }
+
+        let mut released: std::collections::HashMap<String, polymarket_bot::types::Signal> = Default::default();
+        {
+            let queued_before = position_queue.len();
+            let mut projected = open_counts.clone();
+            let current_price = |signal: &polymarket_bot::types::Signal| {
+                markets
+                    .iter()
+                    .find(|m| m.id == signal.market_id)
+                    .and_then(|m| m.outcomes.iter().find(|o| o.token_id == signal.token_id))
+                    .map(|o| o.price)
+            };
+            while let Some(queued) = position_queue.pop_ready(
+                chrono::Utc::now(),
+                &position_limits,
+                &projected,
+                config.strategy.min_edge,
+                current_price,
+            ) {
+                tracing::info!(
+                    "⏳ Releasing queued signal for {} (net edge {:.1}%)",
+                    queued.signal.market_id,
+                    queued.net_edge() * Decimal::ONE_HUNDRED
+                );
+                let strategy = queued.signal.strategy_name.as_deref().unwrap_or(DEFAULT_STRATEGY_NAME);
+                projected.add(strategy, &queued.signal.token_id);
+                released.insert(queued.signal.market_id.clone(), queued.signal);
+            }
+            if position_queue.len() != queued_before {
+                if let Err(e) = db.save_position_queue(position_queue.entries()).await {
+                    tracing::warn!("Failed to save signal queue: {}", e);
+                }
+            }
+        }
+
+        // All markets are predicted in one batch, each market (or batch
+        // request) failing on its own after `analysis_timeout_secs`; the
+        // results are applied below one market at a time, in scan order
+        let llm_markets: Vec<_> = markets
+            .iter()
+            .filter(|m| !released.contains_key(&m.id) && CryptoHfStrategy::is_crypto_hf_market(m).is_none())
+            .cloned()
+            .collect();
+        let mut predictions: std::collections::HashMap<String, _> =
+            match model.predict_batch_with_uncertainty(&llm_markets).await {
+                Ok(batch) => llm_markets
+                    .iter()
+                    .zip(batch)
+                    .filter_map(|(market, prediction)| Some((market.id.clone(), prediction?)))
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!("Batch prediction of {} markets failed: {}", llm_markets.len(), e);
+                    Default::default()
+                }
+            };
+
+        // Latest model probability per market, for reviewing resting orders
+        let mut fair_values: std::collections::HashMap<String, Decimal> = Default::default();
+
         // Analyze each market
         for market in &markets {
             // Check if this is a crypto Up/Down market
@@ -568,47 +2130,137 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                 rm.update_correlation(&market.id, price, chrono::Utc::now().timestamp());
             }
             
-            // Skip low liquidity markets (lower threshold for crypto markets)
-            let min_liquidity = if is_crypto_market {
-                Decimal::new(1000, 0)  // $1,000 for crypto markets
-            } else {
-                Decimal::new(10000, 0) // $10,000 for regular markets
+            // Generate signal: use real-time engine for crypto markets, LLM for others.
+            // Signals are tagged with their strategy for attribution and shadow routing.
+            let tagged = |mut signal: polymarket_bot::types::Signal, strategy: &str| {
+                if signal_ttl_secs > 0 {
+                    signal.valid_until = Some(signal.timestamp + chrono::Duration::seconds(signal_ttl_secs as i64));
+                }
+                signal.strategy_name = Some(strategy.to_string());
+                signal.tags.push(format!("strategy:{}", strategy));
+                if let Some(experiment) = config.strategy.experiment_for(strategy) {
+                    signal.tags.push(format!("experiment:{}", experiment));
+                }
+                signal
             };
-            
-            if market.liquidity < min_liquidity {
-                continue;
-            }
-
-            // Generate signal: use real-time engine for crypto markets, LLM for others
-            let signal = if is_crypto_market {
+            let queued = released.remove(&market.id);
+            let from_queue = queued.is_some();
+            let signal = if queued.is_some() {
+                queued
+            } else if is_crypto_market {
                 // Use real-time WebSocket data for crypto markets
                 realtime_engine.generate_signal(market).await
-                    .or_else(|| crypto_strategy.generate_signal(market, &crypto_tracker))
+                    .map(|s| tagged(s, "realtime"))
+                    .or_else(|| crypto_strategy.generate_signal(market, &crypto_tracker).map(|s| tagged(s, "crypto_hf")))
             } else {
                 // Use LLM prediction for regular markets
-                let prediction = match model.predict(market).await {
-                    Ok(p) => p,
-                    Err(e) => {
-                        tracing::debug!("Model failed for {}: {}", market.id, e);
-                        continue;
-                    }
+                // Every model failed on it, or a duplicate listing of a
+                // market already analyzed
+                let Some(prediction) = predictions.remove(&market.id) else {
+                    tracing::debug!("No prediction for {}", market.id);
+                    continue;
                 };
-                signal_gen.generate(market, &prediction)
+                let point = prediction.prediction();
+                fair_values.insert(market.id.clone(), point.probability);
+                if let Some(inputs) = &mm_inputs {
+                    feed_market_maker(inputs, &config, market, &point).await;
+                }
+                // Model version and the prompt used for the market's category,
+                // stamped onto LLM trades
+                let model_tags = model.tags_for(market);
+                if let (Some(market_price), false) = (market.yes_price(), point.confidence.is_zero()) {
+                    let stored = StoredPrediction {
+                        market_id: market.id.clone(),
+                        predicted_at: chrono::Utc::now(),
+                        probability: point.probability,
+                        market_price,
+                        confidence: point.confidence,
+                        prompt_hash: model_tags.iter().find_map(|t| t.strip_prefix("prompt:")).map(str::to_string),
+                    };
+                    if let Err(e) = db.save_prediction(&stored).await {
+                        tracing::warn!("Failed to store prediction for {}: {}", market.id, e);
+                    }
+                }
+                signal_gen.generate_with_uncertainty(market, &prediction).map(|s| {
+                    let mut signal = tagged(s, "llm");
+                    signal.tags.extend(model_tags);
+                    if let Some(fallback) = &prediction.fallback_used {
+                        signal.tags.push(format!("fallback:{}", fallback));
+                    }
+                    signal
+                })
             };
 
-            if let Some(signal) = signal {
+            if let Some(mut signal) = signal {
                 // Apply signal filter with dynamic cooldown
                 // Crypto markets: 2 min cooldown (fast trading)
                 // Other markets: 15 min cooldown
-                if !signal_filter.deduplicator.can_trade_dynamic(&market.id, is_crypto_market) {
+                // Queued signals already passed it when they were queued
+                if !from_queue && !signal_filter.deduplicator.can_trade_dynamic(&market.id, is_crypto_market) {
                     tracing::debug!("Skipping {} - cooldown active", market.id);
                     continue;
                 }
+                if !from_queue {
+                    let detail = format!(
+                        "{} {} on {} | Model: {:.1}% vs Market: {:.1}% | Edge: {:.1}% ({})",
+                        match signal.side {
+                            polymarket_bot::types::Side::Buy => "BUY",
+                            polymarket_bot::types::Side::Sell => "SELL",
+                        },
+                        signal.token_id,
+                        signal.market_id,
+                        signal.model_probability * Decimal::ONE_HUNDRED,
+                        signal.market_probability * Decimal::ONE_HUNDRED,
+                        signal.edge * Decimal::ONE_HUNDRED,
+                        signal.strategy_name.as_deref().unwrap_or(DEFAULT_STRATEGY_NAME)
+                    );
+                    record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Signal, detail)).await;
+                    if let Some(recent) = &recent_signals {
+                        recent.record(&signal);
+                    }
+                }
+                if risk_manager.lock().await.in_loss_cooldown(&signal) {
+                    tracing::info!(trace_id = %signal.trace_id, "Skipping {} - cooling down after a loss", market.id);
+                    record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, "Skipped: cooling down after a loss")).await;
+                    continue;
+                }
+                // Larger when the BTC regime is confirmed across timeframes,
+                // smaller when they conflict
+                signal.suggested_size *= risk_manager.lock().await.regime_size_multiplier();
+                // Smaller while the strategy or market category is new or
+                // coming off a losing streak
+                let ramp = risk_manager.lock().await.ramp_multiplier(&signal, market);
+                if ramp < Decimal::ONE {
+                    signal.suggested_size *= ramp;
+                    record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, format!("Size ramp: {}%", (ramp * Decimal::ONE_HUNDRED).round_dp(0)))).await;
+                }
+                match heat.kelly_scale(&signal, &heat_positions) {
+                    Some(scale) => signal.suggested_size *= scale,
+                    None => {
+                        tracing::info!(trace_id = %signal.trace_id, "Skipping {} - portfolio heat over budget", market.id);
+                        record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, "Skipped: portfolio heat over budget")).await;
+                        continue;
+                    }
+                }
+                
+                // Graded on more than its edge; weak signals aren't traded
+                let regime = if is_crypto_market { regime_detector.current_regime() } else { None };
+                let market_sentiment = sentiment.for_market(market, chrono::Utc::now());
+                let score = signal_scorer.score(&signal, market, regime, market_sentiment.as_ref(), None);
+                signal.signal_score = Some(score.total);
+                let (grade, total) = (score.grade, score.total);
+                cmd_handler.state.write().await.signal_scores.insert(market.id.clone(), score);
+                if !grade.at_least(config.strategy.scoring.min_grade) {
+                    tracing::info!(trace_id = %signal.trace_id, "Skipping {} - signal graded {} ({:.2})", market.id, grade, total);
+                    record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, format!("Skipped: graded {} ({:.2})", grade, total))).await;
+                    continue;
+                }
                 
                 // Mark as traded to prevent duplicates
                 signal_filter.deduplicator.mark_traded(&market.id);
                 
                 tracing::info!(
+                    trace_id = %signal.trace_id,
                     "Signal: {} {} | Model: {:.1}% vs Market: {:.1}% | Edge: {:.1}%",
                     match signal.side {
                         polymarket_bot::types::Side::Buy => "BUY",
@@ -620,77 +2272,255 @@ async fn run_bot(config: Config, dry_run: bool) -> anyhow::Result<()> {
                     signal.edge * Decimal::ONE_HUNDRED
                 );
 
+                if !from_queue {
+                    emit_all(&event_sinks, BotEvent::signal_generated(&signal)).await;
+                }
+
                 // Send signal notification
-                if tg_config.as_ref().map(|c| c.notify_signals).unwrap_or(false) {
-                    let _ = notifier.signal_found(&signal, &market.question).await;
+                if !from_queue && tg_config.as_ref().map(|c| c.notify_signals).unwrap_or(false) {
+                    let _ = notifier
+                        .signal_found(&signal, &market.question, signal_gen.is_high_uncertainty(&signal))
+                        .await;
                 }
 
-                if dry_run {
-                    // Simulate trade in dry-run mode
-                    let sim_size = signal.suggested_size * balance;
-                    let potential_profit = sim_size * signal.edge;
-                    tracing::info!(
-                        "📝 SIMULATED: Would {} ${:.2} on {} @ {:.1}% (potential: ${:.2})",
-                        match signal.side {
-                            polymarket_bot::types::Side::Buy => "BUY",
-                            polymarket_bot::types::Side::Sell => "SELL",
-                        },
-                        sim_size,
-                        market.question.chars().take(40).collect::<String>(),
-                        signal.market_probability * Decimal::ONE_HUNDRED,
-                        potential_profit
-                    );
-                } else {
-                    match executor.execute(&signal, balance).await {
-                        Ok(Some(trade)) => {
-                            tracing::info!("Trade executed: {}", trade.id);
-                            db.save_trade(&trade).await?;
-
-                            // Update PnL tracking for risk management
-                            {
-                                let mut rm = risk_manager.lock().await;
-                                rm.pnl_tracker.set_starting_balance(balance);
-                                // Record trade fee as immediate cost (actual P&L comes later on close)
-                                rm.record_trade(-trade.fee);
-                                tracing::debug!(
-                                    "Risk: Daily P&L = ${:.2}, remaining budget = ${:.2}",
-                                    rm.daily_pnl(),
-                                    rm.pnl_tracker.remaining_loss_budget().unwrap_or(Decimal::ZERO)
-                                );
+                if cmd_handler.is_shutting_down().await {
+                    break;
+                }
+
+                // Shadow and disabled strategies never reach an executor that trades
+                match shadow_router.route(&signal, market, balance).await {
+                    Ok(Routing::Live) => {}
+                    Ok(Routing::Shadowed(fills)) => {
+                        tracing::info!("👻 Shadow {:?}: {} paper fills", signal.strategy_name, fills.len());
+                        continue;
+                    }
+                    Ok(Routing::Dropped) => continue,
+                    Err(e) => {
+                        tracing::warn!("Shadow execution failed: {}", e);
+                        continue;
+                    }
+                }
+
+                // The regime caps open positions; a much stronger signal
+                // rotates the weakest one out instead of being skipped, once
+                // it has opened
+                let limit_check = risk_manager.lock().await.check_position_limit(&signal, &regime_positions);
+                let rotation = match limit_check {
+                    PositionLimitCheck::Allowed => None,
+                    PositionLimitCheck::Blocked { reason } => {
+                        tracing::info!(trace_id = %signal.trace_id, "Skipping {} - {}", market.id, reason);
+                        record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, format!("Skipped: {}", reason))).await;
+                        if last_limit_alert.as_ref() != Some(&reason) {
+                            if let Err(e) = notifier.risk_alert("Position limit", &reason).await {
+                                tracing::warn!("Failed to send position limit alert: {}", e);
                             }
-                            let _ = cmd_handler.check_risk_limits(Decimal::ZERO).await;
+                            last_limit_alert = Some(reason);
+                        }
+                        continue;
+                    }
+                    PositionLimitCheck::Rotate { market_id, token_id, edge } => Some((market_id, token_id, edge)),
+                };
+
+                // At a position limit the signal waits for a slot instead
+                if !position_limits.has_slot(&open_counts, &signal) {
+                    if position_queue.push(signal.clone(), &market.question, chrono::Utc::now()) {
+                        tracing::info!("⏳ Queued {} ({} waiting): position limit reached", market.id, position_queue.len());
+                        record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, "Queued: position limit reached")).await;
+                    }
+                    if let Err(e) = db.save_position_queue(position_queue.entries()).await {
+                        tracing::warn!("Failed to save signal queue: {}", e);
+                    }
+                    continue;
+                }
+                let strategy = signal.strategy_name.as_deref().unwrap_or(DEFAULT_STRATEGY_NAME);
 
-                            // Send trade notification
-                            if tg_config.as_ref().map(|c| c.notify_trades).unwrap_or(false) {
-                                let _ = notifier.trade_executed(&trade, &market.question).await;
+                let mut opened = false;
+                if let Some((_, sim_executor)) = &simulation {
+                    // Trade against the simulated account; fills stay in the simulation db
+                    sim_executor.register_market(market).await;
+                    match execute_or_rest(sim_executor, two_phase.as_ref(), resting.as_mut(), &signal, balance).await {
+                        Ok(trades) => {
+                            if !trades.is_empty() {
+                                opened = true;
+                                open_counts.add(strategy, &signal.token_id);
+                            }
+                            regime_positions.extend(trades.iter().cloned());
+                            for trade in trades {
+                                if tg_config.as_ref().map(|c| c.notify_trades).unwrap_or(false) {
+                                    let _ = notifier.trade_executed(&trade, &market.question).await;
+                                }
                             }
                         }
-                        Ok(None) => {}
-                        Err(e) => {
-                            tracing::error!("Execution failed: {}", e);
-                            if tg_config.as_ref().map(|c| c.notify_errors).unwrap_or(false) {
-                                let _ = notifier.error("Trade execution", &e.to_string()).await;
+                        Err(e) => tracing::warn!("Simulated execution failed: {}", e),
+                    }
+                } else {
+                    // Every account the strategy is allowed on trades the signal,
+                    // sized off its own balance and limits
+                    for account in accounts.for_strategy(&config.strategy, strategy) {
+                        let Some(wallet_balance) = account_balances.get(&account.id) else {
+                            continue;
+                        };
+                        let executor = &account.executor;
+                        executor.register_market(market).await;
+                        let trading_balance = account.trading_balance(*wallet_balance);
+                        let execution = execute_or_rest(executor, two_phase.as_ref(), resting.as_mut(), &signal, trading_balance);
+                        match record_execution(execution, &risk_manager, error_notifier).await {
+                            Ok(trades) => {
+                                if !trades.is_empty() {
+                                    opened = true;
+                                    open_counts.add(strategy, &signal.token_id);
+                                    if let Err(e) = db.cache_market(market).await {
+                                        tracing::debug!("Failed to cache market {}: {}", market.id, e);
+                                    }
+                                    if let Some(fast_stop) = &fast_stop {
+                                        let now = chrono::Utc::now();
+                                        if let Some(position) = crypto_strategy.fast_stop_position(market, &signal, &crypto_tracker, now) {
+                                            fast_stop.lock().watch(position);
+                                        }
+                                    }
+                                }
+                                regime_positions.extend(trades.iter().cloned());
+                                for trade in trades {
+                                    tracing::info!("Trade executed: {}", trade.id);
+                                    db.save_trade(&trade).await?;
+                                    match db.get_closing_pnl(&trade.id).await {
+                                        Ok(Some(pnl)) => {
+                                            risk_manager.lock().await.record_close(&trade.market_id, pnl);
+                                            record_ramp_close(&db, &risk_manager, &cmd_handler, strategy, market, pnl).await;
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => tracing::warn!("Failed to compute closing P&L of {}: {}", trade.id, e),
+                                    }
+
+                                    // Update PnL tracking for risk management
+                                    {
+                                        let mut rm = risk_manager.lock().await;
+                                        rm.pnl_tracker.set_starting_balance(balance);
+                                        // Record trade fee as immediate cost (actual P&L comes later on close)
+                                        rm.record_trade(-trade.fee);
+                                        tracing::debug!(
+                                            "Risk: Daily P&L = ${:.2}, remaining budget = ${:.2}",
+                                            rm.daily_pnl(),
+                                            rm.pnl_tracker.remaining_loss_budget().unwrap_or(Decimal::ZERO)
+                                        );
+                                    }
+                                    if cmd_handler.check_risk_limits(Decimal::ZERO).await {
+                                        let entry = JournalEntry::auto(JournalKind::Pause, "Daily loss limit hit, trading paused");
+                                        let _ = db.add_journal_entry(&entry).await;
+                                    }
+
+                                    // Send trade notification
+                                    if tg_config.as_ref().map(|c| c.notify_trades).unwrap_or(false) {
+                                        let _ = notifier.trade_executed(&trade, &market.question).await;
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                tracing::error!(trace_id = %signal.trace_id, "Execution failed on {}: {}", account.id, e);
+                                let detail = format!("Not executed on {}: {}", account.id, e);
+                                record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, detail)).await;
                             }
                         }
                     }
                 }
-            }
-        }
-
-        // Log stats periodically
-        monitor.log_stats().await;
 
-        // Wait before next scan
-        tracing::info!(
-            "Sleeping for {} seconds...",
-            config.strategy.scan_interval_secs
-        );
-        tokio::time::sleep(Duration::from_secs(config.strategy.scan_interval_secs)).await;
+                // Rotate only once the stronger signal's entry is placed, on
+                // the account it traded on: the simulated one in dry runs
+                if let Some((market_id, token_id, edge)) = rotation.filter(|_| opened) {
+                    tracing::info!(
+                        "🔄 Rotating out of {} (edge {:.1}%) for {} (edge {:.1}%)",
+                        market_id,
+                        edge * Decimal::ONE_HUNDRED,
+                        market.id,
+                        signal.edge * Decimal::ONE_HUNDRED
+                    );
+                    let tags = vec!["exit:rotation".to_string()];
+                    let closes = match &simulation {
+                        Some((_, sim_executor)) => {
+                            vec![("simulation", sim_executor.close_position_at_market(&market_id, &token_id, tags).await)]
+                        }
+                        None => {
+                            let mut closes = Vec::new();
+                            for account in accounts.iter() {
+                                let close = account.executor.close_position_at_market(&market_id, &token_id, tags.clone()).await;
+                                closes.push((account.id.as_str(), close));
+                            }
+                            closes
+                        }
+                    };
+                    let mut rotated = false;
+                    for (account, close) in closes {
+                        match close {
+                            Ok(Some(trade)) => {
+                                rotated = true;
+                                // Simulated fills stay in the simulation db
+                                if simulation.is_none() {
+                                    if let Err(e) = db.save_trade(&trade).await {
+                                        tracing::error!("Failed to save rotation exit {}: {}", trade.id, e);
+                                    }
+                                }
+                                if tg_config.as_ref().map(|c| c.notify_trades).unwrap_or(false) {
+                                    let _ = notifier.trade_executed(&trade, &market_id).await;
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => tracing::error!("Rotation exit failed on {}: {}", account, e),
+                        }
+                    }
+                    if rotated {
+                        regime_positions.retain(|trade| trade.token_id != token_id);
+                    } else {
+                        // Still held, so it keeps counting against the cap
+                        let reason = format!("could not rotate out of {}", market_id);
+                        tracing::warn!(trace_id = %signal.trace_id, "Position limit: {}", reason);
+                        record_trace(&db, TraceEvent::new(signal.trace_id, TraceStage::Risk, format!("Blocked: {}", reason))).await;
+                        if last_limit_alert.as_ref() != Some(&reason) {
+                            if let Err(e) = notifier.risk_alert("Position limit", &reason).await {
+                                tracing::warn!("Failed to send position limit alert: {}", e);
+                            }
+                            last_limit_alert = Some(reason);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Reprice, cancel or book the fills of resting orders
+        if let Some(resting) = resting.as_mut() {
+            let notify_trades = tg_config.as_ref().is_some_and(|c| c.notify_trades);
+            let now = chrono::Utc::now();
+            let reviews = match &simulation {
+                Some((_, sim_executor)) => vec![("simulation", resting.review(sim_executor, &fair_values, now).await)],
+                None => {
+                    let mut reviews = Vec::new();
+                    for account in accounts.iter() {
+                        reviews.push((account.id.as_str(), resting.review(&account.executor, &fair_values, now).await));
+                    }
+                    reviews
+                }
+            };
+            for (account, review) in reviews {
+                match review {
+                    Ok(updates) => report_resting_updates(updates, &markets, &notifier, notify_trades).await,
+                    Err(e) => tracing::warn!("Failed to review resting orders on {}: {}", account, e),
+                }
+            }
+        }
+
+        // Log stats periodically
+        monitor.log_stats().await;
+
+        // Wait before next scan
+        tracing::info!(
+            "Sleeping for {} seconds...",
+            config.strategy.scan_interval_secs
+        );
+        tokio::time::sleep(Duration::from_secs(config.strategy.scan_interval_secs)).await;
     }
 }
 
 async fn show_markets(config: Config, limit: usize) -> anyhow::Result<()> {
-    let client = PolymarketClient::new(config.polymarket).await?;
+    let client = PolymarketClient::new(config.polymarket.primary_account()).await?;
     let markets = client.gamma.get_top_markets(limit).await?;
 
     println!("\n📊 Top {} Polymarket Markets:\n", limit);
@@ -719,70 +2549,471 @@ async fn show_markets(config: Config, limit: usize) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn analyze_market(config: Config, market_id: &str) -> anyhow::Result<()> {
-    let client = PolymarketClient::new(config.polymarket.clone()).await?;
-    let market = client.gamma.get_market(market_id).await?;
+/// Load the config file and print each setting that fails validation
+fn validate_config(path: &str) -> anyhow::Result<()> {
+    let errors = match Config::load(path) {
+        Ok(_) => {
+            println!("✅ {} is valid", path);
+            return Ok(());
+        }
+        Err(e) => match e.downcast::<ConfigValidationErrors>() {
+            Ok(errors) => errors.0,
+            Err(e) => return Err(e),
+        },
+    };
 
-    println!("\n📈 Market Analysis\n");
-    println!("Question: {}", market.question);
-    if let Some(desc) = &market.description {
-        println!("Description: {}", desc);
+    println!("❌ {} has {} invalid settings\n", path, errors.len());
+    for error in &errors {
+        println!("{} = {}", error.field, error.value);
+        println!("  must be {}", error.constraint);
+        println!("  try {}\n", error.suggestion);
     }
-    println!("\nCurrent Prices:");
-    for outcome in &market.outcomes {
-        println!(
-            "  {} = {:.1}%",
-            outcome.outcome,
-            outcome.price * Decimal::ONE_HUNDRED
-        );
+    anyhow::bail!("Invalid configuration")
+}
+
+/// Write an account's plaintext key to a passphrase-encrypted key file
+fn encrypt_key_file(config: &Config, account: Option<&str>, output: &str) -> anyhow::Result<()> {
+    if config.polymarket.key_source != KeySource::Plaintext {
+        anyhow::bail!("key_source is already {:?}; set it to plaintext to encrypt a key", config.polymarket.key_source);
     }
-    println!("\nVolume: ${:.0}", market.volume);
-    println!("Liquidity: ${:.0}", market.liquidity);
+    let accounts = config.polymarket.account_configs();
+    let account = match account {
+        Some(id) => accounts.iter().find(|a| a.id == id).ok_or_else(|| anyhow::anyhow!("No account {}", id))?,
+        None => &accounts[0],
+    };
+    if account.private_key.is_empty() {
+        anyhow::bail!("Account {} has no private_key to encrypt", account.id);
+    }
+    // Fail on a malformed key now rather than at the next startup
+    account.private_key.wallet()?;
+
+    let passphrase = keys::read_new_passphrase()?;
+    let contents = keys::encrypt_key(&account.private_key, &passphrase)?;
+    keys::write_key_file(std::path::Path::new(output), &contents)?;
+
+    println!("🔐 Key of account {} encrypted to {}", account.id, output);
+    println!("\nIn [polymarket] set");
+    println!("  key_source = \"encrypted_file\"");
+    if config.polymarket.accounts.is_empty() {
+        println!("  key_file = \"{}\"", output);
+    } else {
+        println!("and key_file = \"{}\" in the [[polymarket.accounts]] entry of {}", output, account.id);
+    }
+    println!("then delete the plaintext private_key. Supply the passphrase at startup");
+    println!("through {} or at the prompt.", keys::PASSPHRASE_ENV);
+    Ok(())
+}
+
+async fn analyze_market(config: Config, market_id: &str, json: bool) -> anyhow::Result<()> {
+    let client = PolymarketClient::new(config.polymarket.primary_account()).await?;
+    // Positions and resting orders come from the database when there is one
+    let db = match Database::connect(&config.database.path).await {
+        Ok(db) => Some(db),
+        Err(e) => {
+            tracing::warn!("No database for positions and orders: {}", e);
+            None
+        }
+    };
+    let now = chrono::Utc::now();
+    let mut builder = dossier::gather(market_id, &client.gamma, &client.clob, db.as_ref(), now)
+        .await?
+        .fees(Arc::new(PolymarketFees::from_config(&config.fees.clone().unwrap_or_default())));
+    let market = builder.market().clone();
 
     // Run model if configured
+    let mut reasoning = None;
     if let Some(llm_config) = &config.llm {
-        println!("\n🤖 Running LLM analysis...\n");
+        if !json {
+            println!("\n🤖 Running LLM analysis...");
+        }
         let llm = LlmModel::from_config(llm_config)?;
         match llm.predict(&market).await {
             Ok(pred) => {
-                println!("Model Probability: {:.1}%", pred.probability * Decimal::ONE_HUNDRED);
-                println!("Confidence: {:.1}%", pred.confidence * Decimal::ONE_HUNDRED);
-                println!("Reasoning: {}", pred.reasoning);
-
-                let market_prob = market.yes_price().unwrap_or(Decimal::ZERO);
-                let edge = pred.probability - market_prob;
-                println!("\nEdge: {:.1}%", edge * Decimal::ONE_HUNDRED);
-            }
-            Err(e) => {
-                println!("Model error: {}", e);
+                builder = builder.fair_value(pred.probability, pred.confidence);
+                reasoning = Some(pred.reasoning);
             }
+            Err(e) => eprintln!("Model error: {}", e),
         }
     }
+    let dossier = builder.build(now);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&dossier)?);
+        return Ok(());
+    }
+
+    print_dossier(&dossier, market.description.as_deref(), reasoning.as_deref());
+
+    // Volatility by hour and weekday, from this market's own hourly prices
+    // when it has enough of them
+    let history = match market.outcomes.iter().find(|o| o.outcome.eq_ignore_ascii_case("yes")) {
+        Some(yes) => client.gamma.get_hourly_price_history(&yes.token_id).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let (seasonality, source) = match SeasonalityModel::train_pooled(&[history]) {
+        Some(model) => (model, "this market"),
+        None => (SeasonalityModel::global(), "Polymarket-wide"),
+    };
+    println!("\nSeasonality ({}):", source);
+    println!("  Hourly (UTC 0-23): {}", sparkline(&seasonality.hourly_vol_multipliers, 24));
+    let busiest = (0..24).max_by_key(|&h| seasonality.hourly_vol_multipliers[h]).unwrap_or_default();
+    let quietest = (0..24).min_by_key(|&h| seasonality.hourly_vol_multipliers[h]).unwrap_or_default();
+    println!(
+        "  Busiest {:02}:00 ({:.2}x), quietest {:02}:00 ({:.2}x)",
+        busiest,
+        seasonality.hourly_vol_multipliers[busiest],
+        quietest,
+        seasonality.hourly_vol_multipliers[quietest]
+    );
+    let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let weekdays: Vec<String> = days
+        .iter()
+        .zip(seasonality.dow_vol_multipliers)
+        .map(|(day, m)| format!("{} {:.2}x", day, m))
+        .collect();
+    println!("  Weekdays: {}", weekdays.join(", "));
 
     Ok(())
 }
 
-async fn show_status(config: Config) -> anyhow::Result<()> {
-    let client = PolymarketClient::new(config.polymarket).await?;
-    client.clob.initialize().await?;
+fn print_dossier(dossier: &MarketDossier, description: Option<&str>, reasoning: Option<&str>) {
+    let pct = |p: Decimal| (p * Decimal::ONE_HUNDRED).round_dp(1);
+    println!("\n📈 Market Analysis\n");
+    println!("Question: {}", dossier.question);
+    if let Some(desc) = description {
+        println!("Description: {}", desc);
+    }
+    println!("Category: {}", dossier.category);
+    match (dossier.end_date, dossier.hours_to_resolution) {
+        (Some(end), Some(hours)) => {
+            println!("Resolves: {} (in {}d {}h)", end.format("%Y-%m-%d %H:%M UTC"), hours / 24, hours % 24)
+        }
+        _ => println!("Resolves: unknown"),
+    }
+    println!("\nCurrent Prices:");
+    for outcome in &dossier.outcomes {
+        println!("  {} = {}%", outcome.outcome, pct(outcome.price));
+    }
+    println!("\nVolume: ${:.0}", dossier.volume);
+    println!("Liquidity: ${:.0}", dossier.liquidity);
+    if let Some(trend) = &dossier.volume_trend {
+        let ratio = trend.ratio.map_or_else(|| "n/a".to_string(), |r| format!("{}x", r.round_dp(2).normalize()));
+        println!(
+            "Volume 24h: ${:.0}, 1wk: ${:.0} (24h vs daily average: {})",
+            trend.volume_24h, trend.volume_1wk, ratio
+        );
+    }
 
-    let balance = client.clob.get_balance().await?;
-    let open_orders = client.clob.get_open_orders().await?;
+    if let Some(book) = &dossier.book {
+        println!("\nOrder Book (YES):");
+        let price = |p: Option<Decimal>| p.map_or_else(|| "-".to_string(), |p| p.to_string());
+        println!(
+            "  Bid {} / Ask {} (spread {})",
+            price(book.best_bid),
+            price(book.best_ask),
+            price(book.spread)
+        );
+        println!(
+            "  Depth within 5c: {:.0} bid ({} levels), {:.0} ask ({} levels)",
+            book.bid_depth, book.bid_levels, book.ask_depth, book.ask_levels
+        );
+    }
+
+    let history = &dossier.history;
+    println!("\nPrice History (daily closes):");
+    for (label, closes) in [("7d", &history.last_7d), ("30d", &history.last_30d)] {
+        match (closes.first(), closes.last()) {
+            (Some(first), Some(last)) => {
+                println!("  {:>3}: {} {}% -> {}%", label, sparkline(closes, 30), pct(*first), pct(*last))
+            }
+            _ => println!("  {:>3}: no history", label),
+        }
+    }
+    if let Some(vol) = history.realized_volatility {
+        println!("  Realized volatility: {} pts/day", pct(vol));
+    }
+
+    if !dossier.positions.is_empty() || !dossier.open_orders.is_empty() {
+        println!("\nOur Book:");
+        for position in &dossier.positions {
+            println!(
+                "  Holding {:.2} {} @ {} ({})",
+                position.size,
+                position.outcome,
+                position.avg_price,
+                position.account_id.as_deref().unwrap_or("primary")
+            );
+        }
+        for order in &dossier.open_orders {
+            println!(
+                "  Resting {:?} {:.2} {} @ {} ({})",
+                order.side,
+                order.size,
+                order.outcome,
+                order.price,
+                order.account_id.as_deref().unwrap_or("primary")
+            );
+        }
+    }
 
-    println!("\n💰 Account Status\n");
-    println!("Balance: ${:.2} USDC", balance);
-    println!("Open Orders: {}", open_orders.len());
+    if !dossier.comparables.is_empty() {
+        println!("\nComparable Markets:");
+        for comparable in &dossier.comparables {
+            let price = comparable.yes_price.map_or_else(|| "-".to_string(), |p| format!("{}%", pct(p)));
+            let why = if comparable.same_event {
+                "same event".to_string()
+            } else {
+                format!("{:.0}% similar", comparable.similarity * 100.0)
+            };
+            println!("  {:>6}  {} [{}] ({})", price, comparable.question, comparable.id, why);
+        }
+    }
 
-    if !open_orders.is_empty() {
-        println!("\nOpen Orders:");
-        for order in &open_orders {
+    if let Some(fair) = dossier.fair_value {
+        println!("\nModel Probability: {}%", pct(fair.probability));
+        println!("Confidence: {}%", pct(fair.confidence));
+        if let Some(reasoning) = reasoning {
+            println!("Reasoning: {}", reasoning);
+        }
+    }
+    if !dossier.edge_at_size.is_empty() {
+        println!("\nEdge by Size (net of slippage and fees):");
+        for edge in &dossier.edge_at_size {
+            let Some(avg) = edge.avg_price else {
+                println!("  ${:>5}: no liquidity", edge.size_usdc);
+                continue;
+            };
+            let net = edge.net_edge.map_or_else(|| "n/a".to_string(), |e| format!("{}%", pct(e)));
             println!(
-                "  {} - Status: {}, Filled: {:.2}, Remaining: {:.2}",
-                order.order_id, order.status, order.filled_size, order.remaining_size
+                "  ${:>5}: {:?} @ {} ({}% filled), net edge {}",
+                edge.size_usdc,
+                edge.side,
+                avg.round_dp(4).normalize(),
+                edge.filled_pct.round_dp(0),
+                net
             );
         }
     }
+}
+
+/// Markets whose hourly price history trains the seasonality model
+const SEASONALITY_MARKETS: usize = 10;
+
+/// Volatility pattern of the highest-volume markets' hourly prices, or the
+/// Polymarket-wide one without enough history
+async fn seasonality_model(gamma: &GammaClient) -> SeasonalityModel {
+    let markets = gamma
+        .get_markets_filtered(&MarketFilter::default(), SEASONALITY_MARKETS)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to list markets for seasonality: {}", e);
+            Vec::new()
+        });
+    let mut histories = Vec::new();
+    for market in &markets {
+        let Some(yes) = market.outcomes.iter().find(|o| o.outcome.eq_ignore_ascii_case("yes")) else {
+            continue;
+        };
+        match gamma.get_hourly_price_history(&yes.token_id).await {
+            Ok(history) => histories.push(history),
+            Err(e) => tracing::debug!("No price history for {}: {}", market.id, e),
+        }
+    }
+    match SeasonalityModel::train_pooled(&histories) {
+        Some(model) => {
+            tracing::info!("Seasonality trained on {} markets' hourly prices", histories.len());
+            model
+        }
+        None => {
+            tracing::info!("Not enough price history for seasonality, using the Polymarket-wide pattern");
+            SeasonalityModel::global()
+        }
+    }
+}
+
+/// Print the prompt `market_id` would get, from `template` or the one
+/// configured for its category, and the model's answer to it
+async fn prompt_test(config: Config, market_id: &str, template: Option<&str>) -> anyhow::Result<()> {
+    let llm_config = config.llm.as_ref().ok_or_else(|| anyhow::anyhow!("No [llm] section in the config"))?;
+    let llm = LlmModel::from_config(llm_config)?;
+    let client = PolymarketClient::new(config.polymarket.primary_account()).await?;
+    let market = client.gamma.get_market(market_id).await?;
+
+    let template = match template {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+            PromptTemplate::parse(path, &text)?
+        }
+        None => llm.template_for(&market),
+    };
+
+    println!("\n🧪 Prompt Test: {} ({:?})\n", market.question, market.market_category());
+    println!("Template: {} (hash {})\n", template.name, template.hash());
+    println!("{}", llm.render_prompt(&market, &template));
+    println!("\n🤖 Running {}...\n", llm.name());
+    let pred = llm.predict_with_template(&market, &template).await?;
+    println!("Model Probability: {:.1}%", pred.probability * Decimal::ONE_HUNDRED);
+    println!("Confidence: {:.1}%", pred.confidence * Decimal::ONE_HUNDRED);
+    println!("Reasoning: {}", pred.reasoning);
+    if let Some(market_prob) = market.yes_price() {
+        println!("\nEdge: {:.1}%", (pred.probability - market_prob) * Decimal::ONE_HUNDRED);
+    }
+
+    Ok(())
+}
+
+/// Print how long `symbol` spent in each market regime since `from`
+async fn show_regime_history(symbol: &str, interval: &str, from: Option<&str>) -> anyhow::Result<()> {
+    let from = match from {
+        Some(day) => chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .map_err(|e| anyhow::anyhow!("Invalid --from date {:?}: {}", day, e))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc(),
+        None => chrono::Utc::now() - chrono::Duration::days(365),
+    };
+    let symbol = symbol.to_uppercase();
+    let symbol = if symbol.ends_with("USDT") { symbol } else { format!("{}USDT", symbol) };
+
+    println!("\n📊 Regime History: {} ({} bars since {})\n", symbol, interval, from.format("%Y-%m-%d"));
+    let bars = fetch_binance_bars(&symbol, interval, from).await?;
+    let history = RegimeDetector::new(RegimeConfig::default()).analyze_historical(&bars)?;
+
+    let counts = history.bar_counts();
+    let total_time = history.total_time().num_seconds().max(1) as f64;
+    let mut regimes: Vec<_> = history.time_in_regime.iter().collect();
+    regimes.sort_by(|a, b| b.1.cmp(a.1));
+
+    println!("{:<14} {:>12} {:>8} {:>8}", "Regime", "Duration", "Share", "Bars");
+    for (regime, time) in regimes {
+        println!(
+            "{:<14} {:>12} {:>7.1}% {:>8}",
+            format!("{:?}", regime),
+            format!("{}d {}h", time.num_days(), time.num_hours() % 24),
+            time.num_seconds() as f64 / total_time * 100.0,
+            counts.get(regime).copied().unwrap_or(0),
+        );
+    }
+    println!(
+        "\n{} bars classified, {} transitions, most common: {:?}",
+        history.detections.len(),
+        history.transitions.len(),
+        history.most_common_regime
+    );
+
+    Ok(())
+}
+
+/// Binance klines of `symbol` from `from` to now, oldest first
+async fn fetch_binance_bars(
+    symbol: &str,
+    interval: &str,
+    from: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<Vec<PriceBar>> {
+    const PAGE: usize = 1000;
+    let http = polymarket_bot::utils::http_client(polymarket_bot::utils::HTTP_TIMEOUT);
+    let mut bars: Vec<PriceBar> = Vec::new();
+    let mut start_ms = from.timestamp_millis();
+
+    loop {
+        let url = format!(
+            "https://api.binance.com/api/v3/klines?symbol={}&interval={}&startTime={}&limit={}",
+            symbol, interval, start_ms, PAGE
+        );
+        let page: Vec<Vec<serde_json::Value>> = http.get(&url).send().await?.error_for_status()?.json().await?;
+
+        let field = |kline: &[serde_json::Value], i: usize| -> Decimal {
+            kline[i].as_str().and_then(|v| v.parse().ok()).unwrap_or_default()
+        };
+        // [open_time, open, high, low, close, volume, ...]
+        for kline in page.iter().filter(|k| k.len() >= 6) {
+            let open_ms = kline[0].as_i64().unwrap_or(0);
+            let Some(timestamp) = chrono::DateTime::from_timestamp_millis(open_ms) else {
+                continue;
+            };
+            bars.push(PriceBar {
+                timestamp,
+                open: field(kline, 1),
+                high: field(kline, 2),
+                low: field(kline, 3),
+                close: field(kline, 4),
+                volume: field(kline, 5),
+            });
+            start_ms = open_ms + 1;
+        }
+        if page.len() < PAGE {
+            break;
+        }
+    }
+
+    Ok(bars)
+}
+
+async fn show_status(config: Config) -> anyhow::Result<()> {
+    let accounts = config.polymarket.account_configs();
+
+    for account in &accounts {
+        let client = PolymarketClient::new(config.polymarket.for_account(account)).await?;
+        client.clob.initialize().await?;
+
+        let balance = client.clob.get_balance().await?;
+        let open_orders = client.clob.get_open_orders().await?;
+
+        if accounts.len() > 1 {
+            println!("\n💰 Account Status: {}\n", account.id);
+        } else {
+            println!("\n💰 Account Status\n");
+        }
+        println!("Balance: ${:.2} USDC", balance);
+        println!("Open Orders: {}", open_orders.len());
+
+        if !open_orders.is_empty() {
+            println!("\nOpen Orders:");
+            for order in &open_orders {
+                println!(
+                    "  {} - Status: {}, Filled: {:.2}, Remaining: {:.2}",
+                    order.order_id, order.status, order.filled_size, order.remaining_size
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn doctor(config: Config) -> anyhow::Result<()> {
+    let client = PolymarketClient::new(config.polymarket.primary_account()).await?;
+    if let Err(e) = client.clob.initialize().await {
+        println!("CLOB authentication failed: {}", e);
+    }
+    let db = Arc::new(Database::connect(&config.database.path).await?);
+    let notifier = match &config.telegram {
+        Some(tg) => Notifier::new(tg.bot_token.clone(), tg.chat_id.clone()),
+        None => Notifier::disabled(),
+    };
+
+    let checker = HealthChecker::from_config(&config, Some(client.clob.clone()), client.gamma.clone(), db, &notifier, None);
+    let results = checker.check_all().await;
+
+    println!("\n🩺 Health Check\n");
+    for c in &results {
+        let icon = match c.status {
+            HealthStatus::Ok => "🟢",
+            HealthStatus::Degraded => "🟡",
+            HealthStatus::Down => "🔴",
+        };
+        let latency = c.latency.map(|l| format!("{} ms", l.as_millis())).unwrap_or_else(|| "-".to_string());
+        println!("{} {:<10} {:<9} {:>8}  {}", icon, c.component, c.status.to_string(), latency, c.detail);
+        if let Some(hint) = &c.hint {
+            println!("   → {}", hint);
+        }
+    }
 
+    let down = results.iter().filter(|c| c.status == HealthStatus::Down).count();
+    let degraded = results.iter().filter(|c| c.status == HealthStatus::Degraded).count();
+    println!("\n{} ok, {} degraded, {} down", results.len() - down - degraded, degraded, down);
+    if down > 0 {
+        anyhow::bail!("{} dependencies down", down);
+    }
     Ok(())
 }
 
@@ -792,22 +3023,632 @@ async fn send_report(config: Config) -> anyhow::Result<()> {
     
     let notifier = Notifier::new(tg_config.bot_token.clone(), tg_config.chat_id.clone());
     
-    // Get account status
-    let client = PolymarketClient::new(config.polymarket).await?;
-    client.clob.initialize().await?;
-    let balance = client.clob.get_balance().await?;
+    // Get account status, summed over accounts
+    let mut balance = Decimal::ZERO;
+    for account in config.polymarket.account_configs() {
+        let client = PolymarketClient::new(config.polymarket.for_account(&account)).await?;
+        client.clob.initialize().await?;
+        balance += client.clob.get_balance().await?;
+    }
     
     // Get stats from database
     let db = Database::connect(&config.database.path).await?;
     let stats = db.get_daily_stats().await.unwrap_or_default();
+    let by_strategy = db.get_pnl_by_strategy().await.unwrap_or_default();
+    let by_account = db.get_pnl_by_account().await.unwrap_or_default();
+    let shadow = db.get_shadow_pnl().await.unwrap_or_default();
+    let ladder = db
+        .get_ladder_positions()
+        .await
+        .map(|positions| CapitalLadder::build(&positions, chrono::Utc::now()))
+        .ok();
+    
+    // Source counts as of the running bot's last hourly snapshot
+    let sources = db.get_latest_ingest_stats().await.unwrap_or_default();
     
     // Send report
-    notifier.daily_report(&stats, balance).await?;
+    // Rolling ratios live in the running bot's monitor
+    notifier
+        .daily_report(&stats, balance, &by_strategy, &by_account, &shadow, None, ladder.as_ref(), &sources)
+        .await?;
     
     println!("✅ Report sent to Telegram");
     Ok(())
 }
 
+/// Build the monthly report for `period`, write it to `dir` and send the
+/// summary to Telegram when configured
+async fn send_monthly_report(config: Config, period: &str, dir: &str) -> anyhow::Result<()> {
+    let db = Database::connect(&config.database.path).await?;
+    let fees = PolymarketFees::from_config(&config.fees.clone().unwrap_or_default());
+    let report = MonthlyReport::build(&db, period, &fees, llm_prices(&config)).await?;
+    let path = report.write_json(std::path::Path::new(dir))?;
+    println!("Wrote {}", path.display());
+
+    match &config.telegram {
+        Some(tg) => {
+            Notifier::new(tg.bot_token.clone(), tg.chat_id.clone()).monthly_report(&report).await?;
+            println!("✅ Report sent to Telegram");
+        }
+        None => println!("Telegram not configured, report not sent"),
+    }
+    Ok(())
+}
+
+/// Configured LLM token prices, input and output per million tokens
+fn llm_prices(config: &Config) -> Option<(Decimal, Decimal)> {
+    let llm = config.llm.as_ref()?;
+    Some((llm.input_cost_per_mtok?, llm.output_cost_per_mtok?))
+}
+
+async fn show_journal(config: Config, since: &str) -> anyhow::Result<()> {
+    let window = parse_lookback(since)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --since '{}', expected e.g. 7d, 12h, 30m", since))?;
+    let since_ts = chrono::Utc::now() - window;
+
+    let db = Database::connect(&config.database.path).await?;
+    let timeline = db.get_timeline(since_ts).await?;
+    let interventions = db.count_manual_interventions(since_ts).await?;
+
+    println!("\n📓 Journal since {}\n", since_ts.format("%Y-%m-%d %H:%M UTC"));
+    if timeline.is_empty() {
+        println!("No trades or journal entries.");
+    }
+    for entry in &timeline {
+        println!("{}", entry.describe());
+    }
+    println!("\nManual interventions: {}", interventions);
+
+    Ok(())
+}
+
+/// Realized P&L of all fills, mark-to-market P&L of the open positions at
+/// the scanned markets' prices (entry price when not scanned), and how many
+/// positions are open
+async fn position_pnl(db: &Database, markets: &[polymarket_bot::types::Market]) -> polymarket_bot::error::Result<(Decimal, Decimal, u32)> {
+    let realized = db.get_pnl_by_strategy().await?.iter().map(|p| p.realized_pnl).sum();
+    let positions = db.get_open_positions().await?;
+    let prices: std::collections::HashMap<&str, Decimal> = markets
+        .iter()
+        .flat_map(|m| &m.outcomes)
+        .map(|o| (o.token_id.as_str(), o.price))
+        .collect();
+    let unrealized = positions
+        .iter()
+        .map(|p| {
+            let price = prices.get(p.token_id.as_str()).copied().unwrap_or(p.avg_price);
+            (price - p.avg_price) * p.size
+        })
+        .sum();
+    Ok((realized, unrealized, positions.len() as u32))
+}
+
+/// Check the per-market alert rules against this scan's markets, fetching
+/// alerted markets the scan didn't cover, and notify the ones that fire.
+/// `volumes` holds each alerted market's volume at the previous check.
+async fn check_market_alerts(
+    db: &Database,
+    client: &PolymarketClient,
+    notifier: &Notifier,
+    markets: &[polymarket_bot::types::Market],
+    volumes: &mut std::collections::HashMap<String, Decimal>,
+) {
+    let alerts = match db.get_market_alerts().await {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            tracing::warn!("Failed to load market alerts: {}", e);
+            return;
+        }
+    };
+    let market_ids: std::collections::BTreeSet<&str> = alerts.iter().map(|a| a.rule.market_id.as_str()).collect();
+    volumes.retain(|id, _| market_ids.contains(id.as_str()));
+    if alerts.is_empty() {
+        return;
+    }
+    let watches = |market_id: &str, metric: AlertMetric| {
+        alerts.iter().any(|a| a.rule.market_id == market_id && a.rule.metric == metric)
+    };
+    let positions = if alerts.iter().any(|a| a.rule.metric == AlertMetric::Pnl) {
+        db.get_open_positions().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load positions for market alerts: {}", e);
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+
+    let now = chrono::Utc::now();
+    let mut snapshots = std::collections::HashMap::new();
+    for market_id in market_ids {
+        let market = match markets.iter().find(|m| m.id == market_id) {
+            Some(market) => market.clone(),
+            None => match client.gamma.get_market(market_id).await {
+                Ok(market) => market,
+                Err(e) => {
+                    tracing::debug!("Skipping alerts on {}: {}", market_id, e);
+                    continue;
+                }
+            },
+        };
+        let yes_token = market.outcomes.iter().find(|o| o.outcome.eq_ignore_ascii_case("yes"));
+        let spread = match yes_token.filter(|_| watches(market_id, AlertMetric::Spread)) {
+            Some(yes) => match client.clob.get_order_book(&yes.token_id).await {
+                Ok(book) => book.spread(),
+                Err(e) => {
+                    tracing::debug!("No spread for alerts on {}: {}", market_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let held: Vec<_> = positions.iter().filter(|p| p.market_id == market_id).collect();
+        let unrealized_pnl = (!held.is_empty()).then(|| {
+            held.iter()
+                .map(|p| {
+                    let price = market
+                        .outcomes
+                        .iter()
+                        .find(|o| o.token_id == p.token_id)
+                        .map_or(p.avg_price, |o| o.price);
+                    (price - p.avg_price) * p.size
+                })
+                .sum()
+        });
+        let snapshot = MarketSnapshot {
+            question: market.question.clone(),
+            yes_price: market.yes_price(),
+            spread,
+            volume_delta: volumes.insert(market.id.clone(), market.volume).map(|previous| market.volume - previous),
+            unrealized_pnl,
+            hours_left: market.end_date.map(|end| Decimal::from((end - now).num_minutes()) / Decimal::from(60)),
+        };
+        snapshots.insert(market.id, snapshot);
+    }
+
+    for fired in evaluate_market_alerts(&alerts, &snapshots, now) {
+        tracing::info!("Market alert #{} fired: {} = {}", fired.alert.id, fired.alert.rule.condition(), fired.value);
+        if let Err(e) = notifier.market_alert_fired(&fired).await {
+            tracing::warn!("Failed to send market alert: {}", e);
+        }
+        if let Err(e) = db.mark_market_alert_fired(fired.alert.id, now).await {
+            tracing::warn!("Failed to record market alert #{}: {}", fired.alert.id, e);
+        }
+    }
+}
+
+/// Write the portfolio value history since `from` to a CSV file
+async fn export_equity_curve(config: Config, from: Option<&str>, output: &str) -> anyhow::Result<()> {
+    let from = match from {
+        Some(day) => chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .map_err(|e| anyhow::anyhow!("Invalid --from date {:?}: {}", day, e))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc(),
+        None => chrono::DateTime::<chrono::Utc>::MIN_UTC,
+    };
+
+    let db = Database::connect(&config.database.path).await?;
+    let history = db.get_portfolio_history(from, chrono::Utc::now()).await?;
+
+    let mut csv = String::from("timestamp,value,balance,realized_pnl,unrealized_pnl,open_positions\n");
+    for s in &history {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            s.created_at.to_rfc3339(),
+            s.value,
+            s.balance,
+            s.realized_pnl,
+            s.unrealized_pnl,
+            s.n_open_positions
+        ));
+    }
+    std::fs::write(output, csv)?;
+
+    let values: Vec<Decimal> = history.iter().map(|s| s.value).collect();
+    println!("Wrote {} snapshots to {}", history.len(), output);
+    if let (Some(first), Some(last)) = (values.first(), values.last()) {
+        println!("{} ${:.2} → ${:.2}", sparkline(&values, 60), first, last);
+    }
+
+    Ok(())
+}
+
+/// Store a pipeline trace event; a failure only costs the trace
+async fn record_trace(db: &Database, event: TraceEvent) {
+    if let Err(e) = db.record_trace_event(&event).await {
+        tracing::warn!("Failed to record trace event: {}", e);
+    }
+}
+
+/// Record the outcome of every predicted market that has since resolved;
+/// returns how many were resolved
+async fn resolve_predictions(db: &Database, gamma: &GammaClient) -> polymarket_bot::error::Result<usize> {
+    let mut resolved = 0;
+    for market_id in db.get_unresolved_prediction_markets().await? {
+        match gamma.get_market(&market_id).await {
+            Ok(market) => {
+                if let Some(yes_won) = market.resolved_yes() {
+                    db.resolve_prediction(&market_id, yes_won).await?;
+                    resolved += 1;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to check resolution of {}: {}", market_id, e),
+        }
+    }
+    Ok(resolved)
+}
+
+/// `primary` followed by the configured fallback providers; those that
+/// fail to build are left out
+fn llm_fallback_chain(
+    primary: LlmModel,
+    config: &polymarket_bot::config::LlmConfig,
+    sentiment: &Arc<SentimentIndex>,
+) -> LlmFallbackChain {
+    let mut providers: Vec<(Box<dyn ProbabilityModel>, String)> = Vec::new();
+    let name = primary.name().to_string();
+    providers.push((Box::new(primary), name));
+    for fallback in &config.fallback {
+        if fallback.provider.eq_ignore_ascii_case(MARKET_FALLBACK_NAME) {
+            providers.push((Box::new(MarketImpliedModel), MARKET_FALLBACK_NAME.to_string()));
+            continue;
+        }
+        match LlmModel::from_config(&config.with_provider(fallback)) {
+            Ok(llm) => {
+                let name = llm.name().to_string();
+                providers.push((Box::new(llm.with_sentiment(sentiment.clone())), name));
+            }
+            Err(e) => tracing::warn!("Failed to initialize LLM fallback {}: {}", fallback.provider, e),
+        }
+    }
+    tracing::info!(
+        "LLM fallback chain: {}",
+        providers.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>().join(" → ")
+    );
+    LlmFallbackChain::from_models(providers, Duration::from_secs(config.provider_timeout_secs))
+}
+
+/// Settle open copied trades whose leader has since closed the position;
+/// returns how many were settled
+async fn settle_copied_trades(
+    db: &Database,
+    copy_trader: &CopyTrader,
+    data_api_url: &str,
+) -> polymarket_bot::error::Result<usize> {
+    let mut by_leader: std::collections::HashMap<String, Vec<CopiedTrade>> = std::collections::HashMap::new();
+    for trade in db.get_open_copied_trades().await? {
+        by_leader.entry(trade.trader_key.clone()).or_default().push(trade);
+    }
+
+    let mut settled = 0;
+    for (leader, trades) in by_leader {
+        let closed = match copy_trader.get_closed_positions(data_api_url, &leader).await {
+            Ok(closed) => closed,
+            Err(e) => {
+                tracing::warn!("Failed to fetch closed positions of {}: {}", leader, e);
+                continue;
+            }
+        };
+        for mut trade in trades {
+            if trade.settle(&closed) {
+                db.settle_copied_trade(&trade).await?;
+                settled += 1;
+            }
+        }
+    }
+    Ok(settled)
+}
+
+/// Count a close towards its strategy's and market category's size ramps,
+/// storing their state and journaling level changes
+async fn record_ramp_close(
+    db: &Database,
+    risk_manager: &tokio::sync::Mutex<RiskManager>,
+    cmd_handler: &CommandHandler,
+    strategy: &str,
+    market: &polymarket_bot::types::Market,
+    pnl: Decimal,
+) {
+    let (updates, config, levels) = {
+        let mut rm = risk_manager.lock().await;
+        let updates = rm.record_ramp_close(strategy, market.market_category().name(), pnl);
+        let Some(ramp) = rm.ramp() else {
+            return;
+        };
+        (updates, ramp.config.clone(), ramp.levels())
+    };
+    cmd_handler.state.write().await.ramp_levels = levels;
+    for update in updates {
+        if let Err(e) = db.save_ramp_state(&update.key, &update.state).await {
+            tracing::warn!("Failed to save size ramp of {}: {}", update.key, e);
+        }
+        if let Some(text) = update.describe(&config) {
+            tracing::info!("{}", text);
+            let _ = db.add_journal_entry(&JournalEntry::auto(JournalKind::Ramp, text)).await;
+        }
+    }
+}
+
+async fn show_accuracy(config: Config, since: &str) -> anyhow::Result<()> {
+    let window = parse_lookback(since)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --since '{}', expected e.g. 30d, 12h", since))?;
+    let now = chrono::Utc::now();
+
+    let db = Database::connect(&config.database.path).await?;
+    let gamma = GammaClient::new(&config.polymarket.gamma_url)?;
+    let newly_resolved = resolve_predictions(&db, &gamma).await?;
+    let report = db.accuracy_report(now - window..now).await?;
+
+    println!("\n🎯 Prediction accuracy since {}\n", (now - window).format("%Y-%m-%d %H:%M UTC"));
+    if report.markets == 0 {
+        println!("No resolved markets with predictions ({} newly resolved).", newly_resolved);
+        return Ok(());
+    }
+    println!("Resolved markets: {} ({} newly resolved)", report.markets, newly_resolved);
+    println!("Brier score:      {:.4} (market {:.4})", report.brier_score, report.market_brier_score);
+    println!("Log-loss:         {:.4}", report.log_loss);
+    println!("\n{:<12} {:>6} {:>10} {:>10}", "Predicted", "Count", "Mean", "Realized");
+    println!("{}", "-".repeat(41));
+    for bin in &report.calibration {
+        println!(
+            "{:<12} {:>6} {:>9.1}% {:>9.1}%",
+            format!("{:.0}-{:.0}%", bin.lower * Decimal::ONE_HUNDRED, bin.upper * Decimal::ONE_HUNDRED),
+            bin.count,
+            (bin.mean_predicted * Decimal::ONE_HUNDRED).round_dp(1),
+            (bin.realized_frequency * Decimal::ONE_HUNDRED).round_dp(1)
+        );
+    }
+
+    Ok(())
+}
+
+async fn show_attribution(config: Config, since: &str) -> anyhow::Result<()> {
+    let window = parse_lookback(since)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --since '{}', expected e.g. 30d, 12h", since))?;
+    let now = chrono::Utc::now();
+
+    let db = Database::connect(&config.database.path).await?;
+    let report = db.performance_by_tag(now - window, now).await?;
+
+    println!("\n🏷️  Performance by tag since {}\n", (now - window).format("%Y-%m-%d %H:%M UTC"));
+    if report.is_empty() {
+        println!("No tagged trades.");
+        return Ok(());
+    }
+    println!("{:<32} {:>7} {:>7} {:>9} {:>12} {:>9}", "Tag", "Trades", "Closes", "Win rate", "P&L", "Avg edge");
+    println!("{}", "-".repeat(81));
+    for tag in &report {
+        println!(
+            "{:<32} {:>7} {:>7} {:>8.1}% {:>12} {:>9}",
+            tag.tag,
+            tag.trades,
+            tag.closes,
+            (tag.win_rate() * Decimal::ONE_HUNDRED).round_dp(1),
+            format!("${:.2}", tag.realized_pnl),
+            tag.avg_edge
+                .map(|edge| format!("{:.1}%", edge * Decimal::ONE_HUNDRED))
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    Ok(())
+}
+
+/// Days of price history behind the weekly stress tests
+const STRESS_HISTORY_DAYS: i64 = 60;
+
+/// Built-in stress scenarios run against the open positions, with their
+/// covariance from the daily price snapshots of the last `history`
+async fn stress_test_positions(db: &Database, now: chrono::DateTime<chrono::Utc>, history: chrono::Duration) -> anyhow::Result<Vec<StressResult>> {
+    let positions = db.get_ladder_positions().await?;
+    let snapshots = db.get_price_snapshots(now - history, now).await?;
+    let portfolio = StressPortfolio::from_positions(&positions, &snapshots)?;
+    Ok(portfolio.run(&StressScenario::builtin()))
+}
+
+/// Urgency of the daily forced liquidation simulation: every position in
+/// a single order
+const FORCED_LIQUIDATION_URGENCY: f64 = 1.0;
+
+/// Cost of dumping the open positions into their current books; markets
+/// whose book can't be fetched are judged by their cached liquidity
+async fn simulate_forced_liquidation(db: &Database, clob: &impl ClobClientTrait) -> anyhow::Result<LiquidationReport> {
+    let positions = db.get_open_position_trades().await?;
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let mut books = std::collections::HashMap::new();
+    let mut liquidity = std::collections::HashMap::new();
+    for position in &positions {
+        let book = match clob.get_order_book(&position.token_id).await {
+            Ok(book) => {
+                let mut analyzer = OrderBookAnalyzer::new();
+                analyzer.process_snapshot(OrderBookSnapshot::from_book(&book, now_ms));
+                Some(analyzer)
+            }
+            Err(e) => {
+                tracing::debug!("No book for {}: {}", position.token_id, e);
+                None
+            }
+        };
+        books.insert(position.token_id.clone(), book);
+        if let Some(market) = db.get_cached_market(&position.market_id).await? {
+            liquidity.insert(market.id.clone(), market.liquidity);
+        }
+    }
+    Ok(LiquidityStressTester::new(liquidity).simulate_liquidation(&positions, &books, FORCED_LIQUIDATION_URGENCY))
+}
+
+async fn stress_test(config: Config, history: &str) -> anyhow::Result<()> {
+    let history = parse_lookback(history)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --history '{}', expected e.g. 60d", history))?;
+
+    let db = Database::connect(&config.database.path).await?;
+    let results = stress_test_positions(&db, chrono::Utc::now(), history).await?;
+
+    println!("\n📊 Stress tests of the open positions (daily VaR)\n");
+    println!("{:<20} {:>10} {:>10} {:>15}", "Scenario", "Loss", "VaR 99%", "Worst position");
+    println!("{}", "-".repeat(58));
+    for r in &results {
+        println!(
+            "{:<20} {:>9.1}% {:>9.1}% {:>14.1}%",
+            r.scenario_name, r.portfolio_loss_pct, r.var_99, r.max_single_loss
+        );
+    }
+
+    Ok(())
+}
+
+/// Write the correlation network of the open positions, over the daily
+/// price snapshots of the last `history`, to `output` and list its clusters
+async fn export_correlation_network(config: Config, threshold: Decimal, output: &str, history: &str) -> anyhow::Result<()> {
+    let history = parse_lookback(history)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --history '{}', expected e.g. 60d", history))?;
+
+    let db = Database::connect(&config.database.path).await?;
+    let now = chrono::Utc::now();
+    let positions = db.get_ladder_positions().await?;
+    let snapshots = db.get_price_snapshots(now - history, now).await?;
+    let portfolio = StressPortfolio::from_positions(&positions, &snapshots)?;
+
+    let mut network = MarketCorrelationNetwork::from_portfolio_optimizer(&portfolio.optimizer).with_threshold(threshold);
+    for p in &positions {
+        let Some(market) = &p.market else { continue };
+        let category = market.market_category().name();
+        for symbol in [p.position.market_id.clone(), format!("{}:no", p.position.market_id)] {
+            network = network.with_node_info(&symbol, &market.question, category);
+        }
+    }
+
+    std::fs::write(output, serde_json::to_string_pretty(&network.to_d3_json())?)?;
+    println!("Wrote {} markets, {} links to {}", network.symbols.len(), network.edges(threshold).len(), output);
+
+    let clusters = network.find_clusters(threshold);
+    println!("\n🔗 {} clusters at |correlation| > {}\n", clusters.len(), threshold);
+    for (k, members) in clusters.iter().enumerate() {
+        println!("Cluster {} (max {:.0}% of the portfolio):", k, 100.0 / clusters.len() as f64);
+        for &i in members {
+            println!("  {}", network.labels[i]);
+        }
+    }
+
+    Ok(())
+}
+
+/// Processor settings a replay overrides
+struct ReplayParams {
+    min_confidence: Option<f64>,
+    min_agg_score: Option<f64>,
+    window: Option<i64>,
+    offline: bool,
+}
+
+/// Replay the recorded messages of `[from, to)` through the signal
+/// processor and report the aggregated signals, with the price move of
+/// their mapped markets over `horizon`
+async fn replay_signals(config: Config, from: &str, to: Option<&str>, horizon: &str, params: ReplayParams) -> anyhow::Result<()> {
+    let parse_day = |day: &str| {
+        chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+            .map_err(|e| anyhow::anyhow!("Invalid date {:?}: {}", day, e))
+    };
+    let from = parse_day(from)?;
+    let to = match to {
+        Some(day) => parse_day(day)?,
+        None => chrono::Utc::now(),
+    };
+    let horizon = parse_lookback(horizon)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --horizon '{}', expected e.g. 24h, 7d", horizon))?;
+    let llm_config = config.llm.as_ref().ok_or_else(|| anyhow::anyhow!("[llm] is not configured"))?;
+    let ingester_config = config.ingester.as_ref();
+    let processing = ingester_config.map(|c| c.processing.clone()).unwrap_or_default();
+
+    let db = Arc::new(Database::connect(&config.database.path).await?);
+    let messages = db.get_raw_signals(from, to).await?;
+    let clock = Arc::new(polymarket_bot::clock::ManualClock::new(from));
+    let mut processor = SignalProcessor::new(llm_config.clone())
+        .with_thresholds(
+            params.min_confidence.unwrap_or(processing.min_confidence),
+            params.min_agg_score.unwrap_or(processing.min_agg_score),
+        )
+        .with_window(params.window.unwrap_or(processing.aggregation_window_secs))
+        .with_clock(clock.clone())
+        .with_extraction_cache(db.clone())
+        .with_cache_only(params.offline);
+    if let Some(c) = ingester_config {
+        processor = processor.with_dedup_threshold(c.dedup_threshold);
+    }
+    let report = ingester::replay::replay(&processor, &clock, messages).await;
+
+    println!(
+        "\n🔁 Signal replay {} → {} (confidence ≥ {:.2}, score ≥ {:.2}, window {}s)\n",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d %H:%M UTC"),
+        params.min_confidence.unwrap_or(processing.min_confidence),
+        params.min_agg_score.unwrap_or(processing.min_agg_score),
+        processor.aggregation_window()
+    );
+    println!(
+        "Messages: {} ({} cached extractions, {} {})",
+        report.messages,
+        report.cache_hits,
+        report.cache_misses,
+        if params.offline { "skipped" } else { "sent to the LLM" }
+    );
+    println!("Aggregated signals: {}", report.signals.len());
+    if report.signals.is_empty() {
+        return Ok(());
+    }
+
+    let mapper = match ingester_config {
+        Some(c) => SignalMapper::from_config(&c.mapping)?,
+        None => SignalMapper::default(),
+    };
+    let gamma = GammaClient::new(&config.polymarket.gamma_url)?;
+    let snapshots = db.get_price_snapshots(from, to + horizon).await?;
+    println!("\n{:<17} {:<8} {:<8} {:>6} {:>8}  Market moves", "Time", "Token", "Dir", "Score", "Sources");
+    println!("{}", "-".repeat(72));
+    for signal in &report.signals {
+        let moves: Vec<String> = mapper
+            .map(signal, &gamma)
+            .await
+            .iter()
+            .map(|(market_id, _)| match ingester::replay::market_move(&snapshots, market_id, signal.timestamp, horizon) {
+                Some(moved) => format!(
+                    "{} {:.0}%→{:.0}% ({:+.1}pt)",
+                    market_id,
+                    moved.price_before * Decimal::ONE_HUNDRED,
+                    moved.price_after * Decimal::ONE_HUNDRED,
+                    moved.change() * Decimal::ONE_HUNDRED
+                ),
+                None => format!("{} (no prices)", market_id),
+            })
+            .collect();
+        println!(
+            "{:<17} {:<8} {:<8} {:>6.2} {:>8}  {}",
+            signal.timestamp.format("%Y-%m-%d %H:%M"),
+            signal.token,
+            format!("{:?}", signal.direction),
+            signal.agg_score,
+            signal.sources.len(),
+            if moves.is_empty() { "unmapped".to_string() } else { moves.join(", ") }
+        );
+    }
+
+    Ok(())
+}
+
+/// Parse a lookback like `7d`, `12h` or `30m`
+fn parse_lookback(s: &str) -> Option<chrono::Duration> {
+    let s = s.trim();
+    let unit = s.chars().last()?;
+    let amount: i64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'd' => Some(chrono::Duration::days(amount)),
+        'h' => Some(chrono::Duration::hours(amount)),
+        'm' => Some(chrono::Duration::minutes(amount)),
+        _ => None,
+    }
+}
+
 async fn test_notify(config: Config) -> anyhow::Result<()> {
     let tg_config = config.telegram.as_ref()
         .ok_or_else(|| anyhow::anyhow!("Telegram not configured in config.toml"))?;
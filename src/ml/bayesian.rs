@@ -0,0 +1,222 @@
+//! Bayesian posterior over each market's YES probability
+//!
+//! Rather than trusting a single point estimate, each tracked market keeps a
+//! Beta(α, β) posterior over its true YES probability. Model predictions
+//! move it with a weight proportional to their confidence, market prices
+//! with the weight of one observation. The posterior mean is the probability
+//! to trade on; its spread says how much to trust it.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::HashMap;
+
+/// Beta distribution over a probability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetaDistribution {
+    pub alpha: Decimal,
+    pub beta: Decimal,
+}
+
+impl Default for BetaDistribution {
+    /// Uniform prior, Beta(1, 1)
+    fn default() -> Self {
+        Self::new(Decimal::ONE, Decimal::ONE)
+    }
+}
+
+impl BetaDistribution {
+    pub fn new(alpha: Decimal, beta: Decimal) -> Self {
+        Self { alpha, beta }
+    }
+
+    pub fn mean(&self) -> Decimal {
+        self.alpha / (self.alpha + self.beta)
+    }
+
+    pub fn variance(&self) -> Decimal {
+        let total = self.alpha + self.beta;
+        self.alpha * self.beta / (total * total * (total + Decimal::ONE))
+    }
+
+    /// Standard deviation; at most 0.5 for any Beta distribution
+    pub fn std_dev(&self) -> Decimal {
+        self.variance().sqrt().unwrap_or(Decimal::ZERO)
+    }
+
+    /// Central interval holding `level` (e.g. 0.95) of the probability mass,
+    /// using the normal approximation and clamped to [0, 1]
+    pub fn credible_interval(&self, level: Decimal) -> (Decimal, Decimal) {
+        let level = level.to_f64().unwrap_or(0.95).clamp(0.0, 0.999_999);
+        let z = Decimal::from_f64(standard_normal_quantile(0.5 + level / 2.0)).unwrap_or(Decimal::ZERO);
+        let half_width = z * self.std_dev();
+        let mean = self.mean();
+        (
+            (mean - half_width).max(Decimal::ZERO),
+            (mean + half_width).min(Decimal::ONE),
+        )
+    }
+
+    /// Differential entropy in nats: zero for the uniform prior, more
+    /// negative the more concentrated the posterior
+    pub fn entropy(&self) -> Decimal {
+        let (a, b) = (self.alpha.to_f64().unwrap_or(1.0), self.beta.to_f64().unwrap_or(1.0));
+        let ln_beta = ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b);
+        let entropy = ln_beta - (a - 1.0) * digamma(a) - (b - 1.0) * digamma(b) + (a + b - 2.0) * digamma(a + b);
+        Decimal::from_f64(entropy).unwrap_or(Decimal::ZERO)
+    }
+}
+
+/// Per-market Beta posteriors, starting from a uniform prior
+#[derive(Debug, Clone)]
+pub struct BayesianUpdater {
+    pub posteriors: HashMap<String, BetaDistribution>,
+    /// Observations a full-confidence model prediction is worth
+    pub prior_strength: Decimal,
+}
+
+impl BayesianUpdater {
+    pub fn new(prior_strength: Decimal) -> Self {
+        Self {
+            posteriors: HashMap::new(),
+            prior_strength,
+        }
+    }
+
+    /// Current posterior of `market_id`, if it has been updated
+    pub fn posterior(&self, market_id: &str) -> Option<&BetaDistribution> {
+        self.posteriors.get(market_id)
+    }
+
+    /// Shift the posterior toward `model_prob` with weight
+    /// `confidence * prior_strength`
+    pub fn update_from_model(&mut self, market_id: &str, model_prob: Decimal, confidence: Decimal) -> BetaDistribution {
+        let weight = confidence.clamp(Decimal::ZERO, Decimal::ONE) * self.prior_strength;
+        self.observe(market_id, model_prob, weight)
+    }
+
+    /// Count the market price as one observation
+    pub fn update_from_price(&mut self, market_id: &str, market_price: Decimal) -> BetaDistribution {
+        self.observe(market_id, market_price, Decimal::ONE)
+    }
+
+    /// Drop the posterior of a market that resolved or left the scan
+    pub fn forget(&mut self, market_id: &str) {
+        self.posteriors.remove(market_id);
+    }
+
+    fn observe(&mut self, market_id: &str, probability: Decimal, weight: Decimal) -> BetaDistribution {
+        let probability = probability.clamp(Decimal::ZERO, Decimal::ONE);
+        let posterior = self.posteriors.entry(market_id.to_string()).or_default();
+        posterior.alpha += weight * probability;
+        posterior.beta += weight * (Decimal::ONE - probability);
+        *posterior
+    }
+}
+
+/// Inverse CDF of the standard normal for `p` in (0, 1) (Abramowitz and
+/// Stegun 26.2.23, absolute error below 4.5e-4)
+fn standard_normal_quantile(p: f64) -> f64 {
+    if p < 0.5 {
+        return -standard_normal_quantile(1.0 - p);
+    }
+    let t = (-2.0 * (1.0 - p).max(f64::MIN_POSITIVE).ln()).sqrt();
+    t - (2.515517 + 0.802853 * t + 0.010328 * t * t) / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+}
+
+/// ln Γ(x) for x > 0 (Lanczos approximation, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection: Γ(x)Γ(1 - x) = π / sin(πx)
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// ψ(x) = d/dx ln Γ(x) for x > 0
+fn digamma(mut x: f64) -> f64 {
+    let mut result = 0.0;
+    // ψ(x) = ψ(x + 1) - 1/x until the asymptotic series is accurate
+    while x < 6.0 {
+        result -= 1.0 / x;
+        x += 1.0;
+    }
+    let inv2 = 1.0 / (x * x);
+    result + x.ln() - 0.5 / x - inv2 * (1.0 / 12.0 - inv2 * (1.0 / 120.0 - inv2 / 252.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_beta_moments() {
+        let uniform = BetaDistribution::default();
+        assert_eq!(uniform.mean(), dec!(0.5));
+        assert!((uniform.variance() - Decimal::ONE / dec!(12)).abs() < dec!(0.000001));
+        assert!(uniform.entropy().abs() < dec!(0.0001));
+
+        let beta = BetaDistribution::new(dec!(2), dec!(6));
+        assert_eq!(beta.mean(), dec!(0.25));
+        assert_eq!(beta.variance(), dec!(12) / dec!(576));
+        // Concentrated posteriors carry less entropy than the uniform prior
+        assert!(beta.entropy() < Decimal::ZERO);
+        assert!(BetaDistribution::new(dec!(20), dec!(60)).entropy() < beta.entropy());
+    }
+
+    #[test]
+    fn test_credible_interval() {
+        let beta = BetaDistribution::new(dec!(50), dec!(50));
+        let (low, high) = beta.credible_interval(dec!(0.95));
+        // mean 0.5, sd ≈ 0.0498 -> 0.5 ± 1.96 sd
+        assert!((low - dec!(0.4025)).abs() < dec!(0.001), "{}", low);
+        assert!((high - dec!(0.5975)).abs() < dec!(0.001), "{}", high);
+        let (narrow_low, narrow_high) = beta.credible_interval(dec!(0.5));
+        assert!(narrow_low > low && narrow_high < high);
+
+        let (low, high) = BetaDistribution::new(dec!(1), dec!(20)).credible_interval(dec!(0.99));
+        assert_eq!(low, Decimal::ZERO);
+        assert!(high < Decimal::ONE);
+    }
+
+    #[test]
+    fn test_updates_weight_model_by_confidence() {
+        let mut updater = BayesianUpdater::new(dec!(10));
+        assert!(updater.posterior("m1").is_none());
+
+        let posterior = updater.update_from_price("m1", dec!(0.40));
+        assert_eq!(posterior, BetaDistribution::new(dec!(1.40), dec!(1.60)));
+
+        // Confidence 0.8 with prior strength 10 counts as 8 observations
+        let posterior = updater.update_from_model("m1", dec!(0.70), dec!(0.8));
+        assert_eq!(posterior, BetaDistribution::new(dec!(7.00), dec!(4.00)));
+        assert!(posterior.mean() > dec!(0.60) && posterior.mean() < dec!(0.70));
+
+        // A confident model moves the posterior further than a hesitant one
+        let mut hesitant = BayesianUpdater::new(dec!(10));
+        hesitant.update_from_price("m1", dec!(0.40));
+        let weak = hesitant.update_from_model("m1", dec!(0.70), dec!(0.2));
+        assert!(weak.mean() < posterior.mean());
+        assert!(weak.variance() > posterior.variance());
+
+        updater.forget("m1");
+        assert!(updater.posterior("m1").is_none());
+    }
+}
@@ -6,12 +6,14 @@
 //! - Ensemble prediction combining multiple models
 //! - Multi-factor fusion with dynamic weighting
 //! - Unified predictor interface for live trading
+//! - Bayesian Beta posteriors over each market's probability
 
 pub mod features;
 pub mod calibration;
 pub mod ensemble;
 pub mod factors;
 pub mod predictor;
+pub mod bayesian;
 
 #[cfg(test)]
 mod tests;
@@ -20,4 +22,5 @@ pub use features::{FeatureExtractor, MarketFeatures, FeatureConfig};
 pub use calibration::{ProbabilityCalibrator, CalibrationMethod, CalibrationResult};
 pub use ensemble::{EnsemblePredictor, ModelPrediction, EnsembleConfig, EnsembleMethod};
 pub use factors::{MultiFactorFusion, Factor, FactorWeight, FusionConfig, FusionResult, FactorCategory};
+pub use bayesian::{BayesianUpdater, BetaDistribution};
 pub use predictor::{MLPredictor, MLPredictorConfig, MLPredictionResult, MarketDataInput, KlineData, FeatureSummary};
@@ -0,0 +1,147 @@
+//! Predicting many markets in one pass
+//!
+//! Models without a batch API answer a batch with one prediction per
+//! market, [`predict_concurrently`] at most `max_concurrent` at a time and
+//! each within `timeout` (see [`analyze_markets`]), so one slow market
+//! doesn't cost the rest theirs. [`BatchMetrics`] records how large the ensemble's batches
+//! are and how long they take, served at `/metrics`.
+
+use super::{Prediction, ProbabilityModel};
+use crate::error::Result;
+use crate::scanner::{analyze_markets, DEFAULT_ANALYSIS_TIMEOUT_SECS, DEFAULT_MAX_CONCURRENT_ANALYSES};
+use crate::types::Market;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How a batch is predicted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+    /// Markets predicted at once by a model without a batch API
+    pub max_concurrent: usize,
+    /// Time one market's prediction (or one batch request) may take
+    pub timeout: Duration,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT_ANALYSES,
+            timeout: Duration::from_secs(DEFAULT_ANALYSIS_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// Predictions of `markets` by `model`, at most `limits.max_concurrent` in
+/// flight at once and each failed after `limits.timeout`; index `i` of the
+/// result is `markets[i]`'s
+pub async fn predict_concurrently<M>(model: &M, markets: &[Market], limits: BatchLimits) -> Vec<Result<Prediction>>
+where
+    M: ProbabilityModel + ?Sized,
+{
+    analyze_markets(markets, limits.max_concurrent, limits.timeout, |market| model.predict(market))
+        .await
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect()
+}
+
+/// Counts of recorded values at or below each bound, the last bucket
+/// catching the rest
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [u64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [u64]) -> Self {
+        Self {
+            bounds,
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, value: u64) {
+        let bucket = self.bounds.iter().position(|&bound| value <= bound).unwrap_or(self.bounds.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> u64 {
+        self.sum.load(Ordering::Relaxed)
+    }
+
+    /// Upper bound of each bucket (`None` for the overflow bucket) with
+    /// its count
+    pub fn buckets(&self) -> Vec<(Option<u64>, u64)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (self.bounds.get(i).copied(), count.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// The histogram in Prometheus text format, with cumulative buckets
+    pub fn prometheus_text(&self, name: &str, help: &str) -> String {
+        let mut text = format!("# HELP {name} {help}\n# TYPE {name} histogram\n");
+        let mut cumulative = 0;
+        for (bound, count) in self.buckets() {
+            cumulative += count;
+            let le = bound.map_or_else(|| "+Inf".to_string(), |bound| bound.to_string());
+            text += &format!("{name}_bucket{{le=\"{le}\"}} {cumulative}\n");
+        }
+        text += &format!("{name}_sum {}\n{name}_count {}\n", self.sum(), self.count());
+        text
+    }
+}
+
+const BATCH_SIZE_BOUNDS: &[u64] = &[1, 5, 10, 25, 50, 100];
+const BATCH_LATENCY_MS_BOUNDS: &[u64] = &[100, 500, 1_000, 5_000, 10_000, 30_000, 60_000];
+
+/// Sizes and durations of the ensemble's batch predictions
+#[derive(Debug)]
+pub struct BatchMetrics {
+    /// Markets per batch
+    pub batch_size: Histogram,
+    /// Milliseconds from a batch's start to its last prediction
+    pub batch_latency_ms: Histogram,
+}
+
+impl BatchMetrics {
+    pub fn new() -> Self {
+        Self {
+            batch_size: Histogram::new(BATCH_SIZE_BOUNDS),
+            batch_latency_ms: Histogram::new(BATCH_LATENCY_MS_BOUNDS),
+        }
+    }
+
+    pub fn record(&self, size: usize, latency: Duration) {
+        self.batch_size.record(size as u64);
+        self.batch_latency_ms.record(latency.as_millis() as u64);
+    }
+}
+
+impl Default for BatchMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::monitor::MetricsSource for BatchMetrics {
+    async fn prometheus_text(&self) -> String {
+        self.batch_size.prometheus_text("polymarket_bot_prediction_batch_size", "Markets per batch prediction")
+            + &self
+                .batch_latency_ms
+                .prometheus_text("polymarket_bot_prediction_batch_latency_ms", "Duration of batch predictions in milliseconds")
+    }
+}
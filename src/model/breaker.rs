@@ -0,0 +1,400 @@
+//! Circuit breaker around an unreliable model
+//!
+//! When the LLM provider is rate limiting or down, every market's prediction
+//! fails and the bot goes blind on non-crypto markets. [`CircuitBreakerModel`]
+//! counts consecutive failures of its primary model; after
+//! `failure_threshold` of them it opens and answers from a fallback model for
+//! `cooldown`. The first call after the cooldown probes the primary again
+//! (half-open): success closes the breaker, failure reopens it. The operator
+//! hears once when the breaker trips and once when it recovers.
+
+use super::{predict_concurrently, BatchLimits, Prediction, ProbabilityModel};
+use crate::error::Result;
+use crate::notify::Notifier;
+use crate::types::Market;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Where a breaker routes calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Primary model in use
+    Closed,
+    /// Fallback in use until the cooldown ends
+    Open,
+    /// Cooldown over; the next call probes the primary
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    consecutive_failures: u32,
+    /// When the cooldown of an open breaker ends
+    open_until: Option<Instant>,
+    /// A probe of the primary is in flight
+    probing: bool,
+}
+
+/// Which model answers a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Primary,
+    Probe,
+    Fallback,
+}
+
+/// State change worth telling the operator about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    Tripped,
+    Recovered,
+}
+
+/// Wraps a primary model, answering from `fallback` while the primary keeps
+/// failing
+pub struct CircuitBreakerModel {
+    primary: Box<dyn ProbabilityModel>,
+    fallback: Box<dyn ProbabilityModel>,
+    failure_threshold: u32,
+    cooldown: Duration,
+    breaker: Mutex<Breaker>,
+    notifier: Option<Arc<Notifier>>,
+    name: String,
+}
+
+impl CircuitBreakerModel {
+    /// A zero `failure_threshold` is treated as 1
+    pub fn new(
+        primary: Box<dyn ProbabilityModel>,
+        fallback: Box<dyn ProbabilityModel>,
+        failure_threshold: u32,
+        cooldown: Duration,
+    ) -> Self {
+        let name = primary.name().to_string();
+        Self {
+            primary,
+            fallback,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            breaker: Mutex::new(Breaker {
+                consecutive_failures: 0,
+                open_until: None,
+                probing: false,
+            }),
+            notifier: None,
+            name,
+        }
+    }
+
+    /// Tell the operator when the breaker trips and when it recovers
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state_at(Instant::now())
+    }
+
+    fn state_at(&self, now: Instant) -> BreakerState {
+        let breaker = self.breaker.lock();
+        match breaker.open_until {
+            None => BreakerState::Closed,
+            Some(until) if now < until && !breaker.probing => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    fn route(&self, now: Instant) -> Route {
+        let mut breaker = self.breaker.lock();
+        match breaker.open_until {
+            None => Route::Primary,
+            Some(until) if now >= until && !breaker.probing => {
+                breaker.probing = true;
+                Route::Probe
+            }
+            Some(_) => Route::Fallback,
+        }
+    }
+
+    fn record_success(&self) -> Option<Transition> {
+        let mut breaker = self.breaker.lock();
+        let was_open = breaker.open_until.is_some();
+        *breaker = Breaker {
+            consecutive_failures: 0,
+            open_until: None,
+            probing: false,
+        };
+        was_open.then_some(Transition::Recovered)
+    }
+
+    fn record_failure(&self, now: Instant) -> Option<Transition> {
+        let mut breaker = self.breaker.lock();
+        breaker.consecutive_failures += 1;
+        if breaker.probing {
+            // Failed probe: another cooldown, without a second notification
+            breaker.probing = false;
+            breaker.open_until = Some(now + self.cooldown);
+            return None;
+        }
+        if breaker.open_until.is_none() && breaker.consecutive_failures >= self.failure_threshold {
+            breaker.open_until = Some(now + self.cooldown);
+            return Some(Transition::Tripped);
+        }
+        None
+    }
+
+    async fn notify(&self, transition: Transition, error: Option<&str>) {
+        let text = match transition {
+            Transition::Tripped => {
+                tracing::warn!(
+                    "Model {} failed {} times in a row, using {} for {}s",
+                    self.name,
+                    self.failure_threshold,
+                    self.fallback.name(),
+                    self.cooldown.as_secs()
+                );
+                crate::notify::model_breaker_tripped(
+                    &self.name,
+                    self.fallback.name(),
+                    self.failure_threshold,
+                    self.cooldown,
+                    error.unwrap_or_default(),
+                )
+            }
+            Transition::Recovered => {
+                tracing::info!("Model {} recovered", self.name);
+                crate::notify::model_breaker_recovered(&self.name)
+            }
+        };
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send(&text).await {
+                tracing::warn!("Failed to send model breaker notification: {}", e);
+            }
+        }
+    }
+
+    async fn predict_at(&self, market: &Market, now: Instant) -> Result<Prediction> {
+        if self.route(now) == Route::Fallback {
+            return self.fallback.predict(market).await;
+        }
+        match self.primary.predict(market).await {
+            Ok(prediction) => {
+                if let Some(transition) = self.record_success() {
+                    self.notify(transition, None).await;
+                }
+                Ok(prediction)
+            }
+            Err(e) => {
+                tracing::debug!("Model {} failed for {}: {}", self.name, market.id, e);
+                if let Some(transition) = self.record_failure(now) {
+                    self.notify(transition, Some(&e.to_string())).await;
+                }
+                self.fallback.predict(market).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ProbabilityModel for CircuitBreakerModel {
+    async fn predict(&self, market: &Market) -> Result<Prediction> {
+        self.predict_at(market, Instant::now()).await
+    }
+
+    /// A closed breaker hands the whole batch to the primary, counting each
+    /// market's result and answering failures from the fallback; otherwise
+    /// each market is routed on its own
+    async fn predict_batch(&self, markets: &[Market], limits: BatchLimits) -> Vec<Result<Prediction>> {
+        let now = Instant::now();
+        if self.state_at(now) != BreakerState::Closed {
+            return predict_concurrently(self, markets, limits).await;
+        }
+        let mut predictions = Vec::with_capacity(markets.len());
+        for (market, result) in markets.iter().zip(self.primary.predict_batch(markets, limits).await) {
+            let prediction = match result {
+                Ok(prediction) => {
+                    if let Some(transition) = self.record_success() {
+                        self.notify(transition, None).await;
+                    }
+                    Ok(prediction)
+                }
+                Err(e) => {
+                    tracing::debug!("Model {} failed for {}: {}", self.name, market.id, e);
+                    if let Some(transition) = self.record_failure(now) {
+                        self.notify(transition, Some(&e.to_string())).await;
+                    }
+                    self.fallback.predict(market).await
+                }
+            };
+            predictions.push(prediction);
+        }
+        predictions
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The primary's tags: fallback answers carry no confidence and never
+    /// become trades
+    fn tags(&self) -> Vec<String> {
+        self.primary.tags()
+    }
+
+    fn tags_for(&self, market: &Market) -> Vec<String> {
+        self.primary.tags_for(market)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BotError;
+    use crate::model::MarketImpliedModel;
+    use crate::types::Outcome;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Fails while `down` is set, counting every call
+    struct FlakyModel {
+        down: Arc<AtomicBool>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ProbabilityModel for FlakyModel {
+        async fn predict(&self, _market: &Market) -> Result<Prediction> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                return Err(BotError::RateLimited { retry_after_secs: 60 });
+            }
+            Ok(Prediction {
+                probability: dec!(0.80),
+                confidence: dec!(0.70),
+                reasoning: "llm".to_string(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    fn market() -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: "Will it?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(50000),
+            liquidity: dec!(10000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: dec!(0.40) },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: dec!(0.60) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn breaker() -> (CircuitBreakerModel, Arc<AtomicBool>, Arc<AtomicUsize>) {
+        let down = Arc::new(AtomicBool::new(true));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let primary = FlakyModel { down: down.clone(), calls: calls.clone() };
+        let model = CircuitBreakerModel::new(
+            Box::new(primary),
+            Box::new(MarketImpliedModel),
+            3,
+            Duration::from_secs(300),
+        );
+        (model, down, calls)
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failures_trip_the_breaker() {
+        let (model, _, calls) = breaker();
+        let start = Instant::now();
+
+        for i in 1..=3 {
+            // Failures fall back to the market price right away
+            let prediction = model.predict_at(&market(), start).await.unwrap();
+            assert_eq!(prediction.probability, dec!(0.40));
+            assert_eq!(prediction.confidence, Decimal::ZERO);
+            assert_eq!(calls.load(Ordering::SeqCst), i);
+        }
+        assert_eq!(model.state_at(start), BreakerState::Open);
+
+        // Open: the primary is left alone until the cooldown ends
+        model.predict_at(&market(), start + Duration::from_secs(299)).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(model.state_at(start + Duration::from_secs(300)), BreakerState::HalfOpen);
+    }
+
+    #[tokio::test]
+    async fn test_success_resets_the_failure_count() {
+        let (model, down, _) = breaker();
+        let start = Instant::now();
+
+        model.predict_at(&market(), start).await.unwrap();
+        model.predict_at(&market(), start).await.unwrap();
+        down.store(false, Ordering::SeqCst);
+        model.predict_at(&market(), start).await.unwrap();
+        down.store(true, Ordering::SeqCst);
+        model.predict_at(&market(), start).await.unwrap();
+        model.predict_at(&market(), start).await.unwrap();
+        assert_eq!(model.state_at(start), BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe_closes_or_reopens() {
+        let (model, down, calls) = breaker();
+        let start = Instant::now();
+        for _ in 0..3 {
+            model.predict_at(&market(), start).await.unwrap();
+        }
+
+        // A failed probe opens the breaker for another cooldown
+        let probe = start + Duration::from_secs(300);
+        model.predict_at(&market(), probe).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(model.state_at(probe), BreakerState::Open);
+        model.predict_at(&market(), probe + Duration::from_secs(299)).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // A successful one closes it and the primary answers again
+        down.store(false, Ordering::SeqCst);
+        let probe = probe + Duration::from_secs(300);
+        let prediction = model.predict_at(&market(), probe).await.unwrap();
+        assert_eq!(prediction.probability, dec!(0.80));
+        assert_eq!(model.state_at(probe), BreakerState::Closed);
+        model.predict_at(&market(), probe).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_batch_failures_trip_the_breaker() {
+        let (model, _, calls) = breaker();
+        let markets = vec![market(); 5];
+        let limits = BatchLimits { max_concurrent: 2, ..Default::default() };
+
+        // Every failed market is answered from the fallback, in place
+        let predictions = model.predict_batch(&markets, limits).await;
+        assert_eq!(predictions.len(), 5);
+        assert!(predictions.iter().all(|p| p.as_ref().unwrap().probability == dec!(0.40)));
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert_eq!(model.state(), BreakerState::Open);
+
+        // Once open, the next batch goes straight to the fallback
+        model.predict_batch(&markets, limits).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+}
@@ -0,0 +1,299 @@
+//! Fallback chain of LLM providers
+//!
+//! One provider being down shouldn't stop predictions. [`LlmFallbackChain`]
+//! asks its providers in order, each within `timeout_per_provider`, and
+//! answers with the first that succeeds; a market-price provider at the end
+//! never fails. The operator hears when a fallback takes over from the
+//! primary and when the primary answers again.
+
+use super::{LlmModel, LlmProvider, MarketImpliedModel, Prediction, ProbabilityModel};
+use crate::error::{BotError, Result};
+use crate::notify::Notifier;
+use crate::types::Market;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Display name of the market-price provider
+pub const MARKET_FALLBACK_NAME: &str = "market";
+
+#[derive(Debug, Default)]
+struct ChainStats {
+    /// Index of the fallback answering since the primary failed
+    active_fallback: Option<usize>,
+    /// Failed or timed-out calls by provider
+    failures: BTreeMap<String, u64>,
+    /// Predictions answered by a fallback, by that provider
+    activations: BTreeMap<String, u64>,
+}
+
+/// Providers asked in order until one answers
+pub struct LlmFallbackChain {
+    /// Models with their display names, primary first
+    pub providers: Vec<(Box<dyn ProbabilityModel>, String)>,
+    pub timeout_per_provider: Duration,
+    stats: Mutex<ChainStats>,
+    notifier: Option<Arc<Notifier>>,
+}
+
+impl LlmFallbackChain {
+    /// Chain of `providers` with their display names, primary first
+    pub fn new(providers: Vec<(LlmProvider, String)>, timeout_per_provider: Duration) -> Self {
+        let models = providers
+            .into_iter()
+            .map(|(provider, name)| (Box::new(LlmModel::new(provider)) as Box<dyn ProbabilityModel>, name))
+            .collect();
+        Self::from_models(models, timeout_per_provider)
+    }
+
+    /// Chain of already built models, e.g. with their prompts configured
+    pub fn from_models(providers: Vec<(Box<dyn ProbabilityModel>, String)>, timeout_per_provider: Duration) -> Self {
+        Self {
+            providers,
+            timeout_per_provider,
+            stats: Mutex::default(),
+            notifier: None,
+        }
+    }
+
+    /// Answer with the market price when every provider fails
+    pub fn with_market_fallback(mut self) -> Self {
+        self.providers.push((Box::new(MarketImpliedModel), MARKET_FALLBACK_NAME.to_string()));
+        self
+    }
+
+    /// Tell the operator when a fallback takes over and when the primary
+    /// is back
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    fn primary_name(&self) -> &str {
+        self.providers.first().map_or("", |(_, name)| name)
+    }
+
+    /// The first prediction any provider makes in time, with that
+    /// provider's name; `fallback_used` is set unless it's the primary
+    pub async fn predict(&self, market: &Market) -> Result<(Prediction, String)> {
+        let mut last_error = BotError::Config("LLM fallback chain has no providers".into());
+        for (index, (model, name)) in self.providers.iter().enumerate() {
+            let error = match tokio::time::timeout(self.timeout_per_provider, model.predict(market)).await {
+                Ok(Ok(mut prediction)) => {
+                    if index > 0 {
+                        prediction.fallback_used = Some(name.clone());
+                    }
+                    self.record_success(index, name).await;
+                    return Ok((prediction, name.clone()));
+                }
+                Ok(Err(e)) => e,
+                Err(_) => BotError::Api(format!("{} timed out after {}s", name, self.timeout_per_provider.as_secs_f64())),
+            };
+            tracing::debug!("LLM provider {} failed for {}: {}", name, market.id, error);
+            *self.stats.lock().failures.entry(name.clone()).or_default() += 1;
+            last_error = error;
+        }
+        Err(last_error)
+    }
+
+    /// Count the answer of provider `index`, notifying when the fallback in
+    /// use changes or the primary recovers
+    async fn record_success(&self, index: usize, name: &str) {
+        let text = {
+            let mut stats = self.stats.lock();
+            if index > 0 {
+                *stats.activations.entry(name.to_string()).or_default() += 1;
+            }
+            let previous = std::mem::replace(&mut stats.active_fallback, (index > 0).then_some(index));
+            match (previous, index) {
+                (Some(_), 0) => Some(crate::notify::llm_primary_restored(self.primary_name())),
+                (previous, index) if index > 0 && previous != Some(index) => {
+                    Some(crate::notify::llm_fallback(self.primary_name(), name))
+                }
+                _ => None,
+            }
+        };
+        let Some(text) = text else {
+            return;
+        };
+        tracing::warn!("{}", text);
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send(&text).await {
+                tracing::warn!("Failed to send LLM fallback notification: {}", e);
+            }
+        }
+    }
+
+    /// Provider failures and fallback activations in Prometheus text format
+    pub fn prometheus_counters(&self) -> String {
+        let failures = "polymarket_bot_llm_provider_failures_total";
+        let activations = "polymarket_bot_llm_fallback_activations_total";
+        let stats = self.stats.lock();
+        let mut text =
+            format!("# HELP {failures} Failed or timed-out LLM calls by provider\n# TYPE {failures} counter\n");
+        for (provider, count) in &stats.failures {
+            text += &format!("{failures}{{provider=\"{provider}\"}} {count}\n");
+        }
+        text += &format!(
+            "# HELP {activations} Predictions answered by a fallback provider\n# TYPE {activations} counter\n"
+        );
+        for (provider, count) in &stats.activations {
+            text += &format!("{activations}{{to_provider=\"{provider}\"}} {count}\n");
+        }
+        text
+    }
+}
+
+#[async_trait]
+impl crate::monitor::MetricsSource for LlmFallbackChain {
+    async fn prometheus_text(&self) -> String {
+        self.prometheus_counters()
+    }
+}
+
+#[async_trait]
+impl ProbabilityModel for LlmFallbackChain {
+    async fn predict(&self, market: &Market) -> Result<Prediction> {
+        LlmFallbackChain::predict(self, market).await.map(|(prediction, _)| prediction)
+    }
+
+    fn name(&self) -> &str {
+        self.primary_name()
+    }
+
+    /// The primary's tags
+    fn tags(&self) -> Vec<String> {
+        self.providers.first().map(|(model, _)| model.tags()).unwrap_or_default()
+    }
+
+    fn tags_for(&self, market: &Market) -> Vec<String> {
+        self.providers.first().map(|(model, _)| model.tags_for(market)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Fails while `down` is set; sleeps `delay` before answering
+    struct StubModel {
+        down: Arc<AtomicBool>,
+        delay: Duration,
+        probability: Decimal,
+    }
+
+    #[async_trait]
+    impl ProbabilityModel for StubModel {
+        async fn predict(&self, _market: &Market) -> Result<Prediction> {
+            tokio::time::sleep(self.delay).await;
+            if self.down.load(Ordering::SeqCst) {
+                return Err(BotError::Api("HTTP 503".into()));
+            }
+            Ok(Prediction {
+                probability: self.probability,
+                confidence: dec!(0.70),
+                reasoning: "llm".to_string(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "stub"
+        }
+    }
+
+    fn stub(down: &Arc<AtomicBool>, delay: Duration, probability: Decimal) -> Box<dyn ProbabilityModel> {
+        Box::new(StubModel { down: down.clone(), delay, probability })
+    }
+
+    fn market() -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: "Will it?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(1000),
+            liquidity: dec!(1000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: dec!(0.42) },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: dec!(0.58) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_on_error_and_restores_primary() {
+        let primary_down = Arc::new(AtomicBool::new(false));
+        let up = Arc::new(AtomicBool::new(false));
+        let chain = LlmFallbackChain::from_models(
+            vec![
+                (stub(&primary_down, Duration::ZERO, dec!(0.60)), "openai".to_string()),
+                (stub(&up, Duration::ZERO, dec!(0.55)), "deepseek".to_string()),
+            ],
+            Duration::from_secs(5),
+        );
+
+        let (prediction, provider) = chain.predict(&market()).await.unwrap();
+        assert_eq!((prediction.probability, provider.as_str()), (dec!(0.60), "openai"));
+        assert_eq!(prediction.fallback_used, None);
+
+        primary_down.store(true, Ordering::SeqCst);
+        let (prediction, provider) = chain.predict(&market()).await.unwrap();
+        assert_eq!((prediction.probability, provider.as_str()), (dec!(0.55), "deepseek"));
+        assert_eq!(prediction.fallback_used.as_deref(), Some("deepseek"));
+        assert_eq!(chain.stats.lock().active_fallback, Some(1));
+
+        primary_down.store(false, Ordering::SeqCst);
+        let (_, provider) = chain.predict(&market()).await.unwrap();
+        assert_eq!(provider, "openai");
+        assert_eq!(chain.stats.lock().active_fallback, None);
+
+        let counters = chain.prometheus_counters();
+        assert!(counters.contains("polymarket_bot_llm_provider_failures_total{provider=\"openai\"} 1\n"));
+        assert!(counters.contains("polymarket_bot_llm_fallback_activations_total{to_provider=\"deepseek\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_moves_on_to_market_price() {
+        let up = Arc::new(AtomicBool::new(false));
+        let down = Arc::new(AtomicBool::new(true));
+        let chain = LlmFallbackChain::from_models(
+            vec![
+                (stub(&up, Duration::from_secs(5), dec!(0.60)), "openai".to_string()),
+                (stub(&down, Duration::ZERO, dec!(0.55)), "deepseek".to_string()),
+            ],
+            Duration::from_millis(20),
+        )
+        .with_market_fallback();
+
+        let (prediction, provider) = chain.predict(&market()).await.unwrap();
+        assert_eq!(provider, MARKET_FALLBACK_NAME);
+        assert_eq!(prediction.probability, dec!(0.42));
+        assert_eq!(prediction.fallback_used.as_deref(), Some(MARKET_FALLBACK_NAME));
+        let failures = &chain.stats.lock().failures;
+        assert_eq!((failures["openai"], failures["deepseek"]), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_every_provider_failing_is_an_error() {
+        let down = Arc::new(AtomicBool::new(true));
+        let chain = LlmFallbackChain::from_models(
+            vec![(stub(&down, Duration::ZERO, dec!(0.60)), "openai".to_string())],
+            Duration::from_secs(5),
+        );
+        assert!(ProbabilityModel::predict(&chain, &market()).await.is_err());
+        assert!(LlmFallbackChain::from_models(Vec::new(), Duration::from_secs(5)).predict(&market()).await.is_err());
+    }
+}
@@ -1,19 +1,56 @@
 //! LLM-based probability model
 //!
 //! Supports multiple LLM providers: DeepSeek, Anthropic, OpenAI, and OpenAI-compatible APIs.
+//! Prompts come from the [`PromptTemplate`]s configured per market category.
 
-use super::{Prediction, ProbabilityModel};
+use super::prompt::{parse_batch_predictions, parse_prediction, PromptContext, PromptLibrary, PromptTemplate, RecentSignals};
+use super::{predict_concurrently, BatchLimits, Prediction, ProbabilityModel};
+use crate::analysis::sentiment::SentimentIndex;
 use crate::error::{BotError, Result};
-use crate::types::Market;
+use crate::types::{Market, MarketCategory};
+use crate::utils::{http_client, LLM_HTTP_TIMEOUT};
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use reqwest::Client;
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// LLM model for probability estimation
 pub struct LlmModel {
     http: Client,
     provider: LlmProvider,
+    usage: Arc<LlmUsage>,
+    prompts: Mutex<PromptLibrary>,
+    recent_signals: Arc<RecentSignals>,
+    sentiment: Option<Arc<SentimentIndex>>,
+    /// Markets per batch request
+    batch_size: usize,
+}
+
+/// Calls and tokens of an [`LlmModel`] since they were last taken
+#[derive(Debug, Default)]
+pub struct LlmUsage {
+    calls: AtomicU64,
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+impl LlmUsage {
+    fn record(&self, input_tokens: u64, output_tokens: u64) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.input_tokens.fetch_add(input_tokens, Ordering::Relaxed);
+        self.output_tokens.fetch_add(output_tokens, Ordering::Relaxed);
+    }
+
+    /// Usage so far, resetting the counters
+    pub fn take(&self) -> crate::storage::LlmUsageTotals {
+        crate::storage::LlmUsageTotals {
+            calls: self.calls.swap(0, Ordering::Relaxed),
+            input_tokens: self.input_tokens.swap(0, Ordering::Relaxed),
+            output_tokens: self.output_tokens.swap(0, Ordering::Relaxed),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +76,16 @@ pub enum LlmProvider {
     },
 }
 
+/// Leads an OpenAI batch request; each market follows as a system message
+/// numbering it and a user message with its prompt
+/// Markets per batch request by default, small enough to stay well
+/// inside the context and output limits
+const DEFAULT_BATCH_SIZE: usize = 20;
+
+const BATCH_INSTRUCTIONS: &str = "You will be asked about several prediction markets, each introduced \
+    by a system message numbering it. Answer each as its prompt asks, and reply with a single JSON \
+    object {\"predictions\": [...]} holding one prediction object per market, in the order given.";
+
 // ============ Request/Response types ============
 
 #[derive(Debug, Serialize)]
@@ -63,6 +110,16 @@ struct ResponseFormat {
 #[derive(Debug, Deserialize)]
 struct OpenAIResponse {
     choices: Vec<OpenAIChoice>,
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -91,6 +148,16 @@ struct AnthropicMessage {
 #[derive(Debug, Deserialize)]
 struct AnthropicResponse {
     content: Vec<AnthropicContent>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -101,11 +168,21 @@ struct AnthropicContent {
 impl LlmModel {
     pub fn new(provider: LlmProvider) -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(LLM_HTTP_TIMEOUT),
             provider,
+            usage: Arc::default(),
+            prompts: Mutex::default(),
+            recent_signals: Arc::default(),
+            sentiment: None,
+            batch_size: DEFAULT_BATCH_SIZE,
         }
     }
 
+    /// Counters of the calls made and tokens used, shared with the model
+    pub fn usage(&self) -> Arc<LlmUsage> {
+        self.usage.clone()
+    }
+
     /// Create from config
     pub fn from_config(config: &crate::config::LlmConfig) -> Result<Self> {
         let provider = match config.provider.to_lowercase().as_str() {
@@ -135,7 +212,18 @@ impl LlmModel {
             _ => return Err(BotError::Config(format!("Unknown LLM provider: {}", config.provider))),
         };
 
-        Ok(Self::new(provider))
+        let model = Self::new(provider).with_batch_size(config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE));
+        *model.prompts.lock() = PromptLibrary::from_config(&config.prompts)?;
+        Ok(model)
+    }
+
+    /// Base URL of the provider's API
+    pub fn endpoint(&self) -> &str {
+        match &self.provider {
+            LlmProvider::DeepSeek { .. } => "https://api.deepseek.com",
+            LlmProvider::Anthropic { .. } => "https://api.anthropic.com",
+            LlmProvider::OpenAI { base_url, .. } | LlmProvider::Compatible { base_url, .. } => base_url,
+        }
     }
 
     /// Convenience constructors
@@ -161,34 +249,62 @@ impl LlmModel {
         })
     }
 
-    fn build_prompt(&self, market: &Market) -> String {
-        let yes_price = market.yes_price().unwrap_or(Decimal::new(50, 2));
+    /// Model the provider is asked for
+    pub fn model_id(&self) -> &str {
+        match &self.provider {
+            LlmProvider::DeepSeek { model, .. }
+            | LlmProvider::Anthropic { model, .. }
+            | LlmProvider::OpenAI { model, .. }
+            | LlmProvider::Compatible { model, .. } => model,
+        }
+    }
 
-        format!(
-            r#"You are an expert prediction market analyst. Analyze the following market and estimate the probability of the "Yes" outcome.
+    /// Hash of the built-in prompt template, so that edits to the prompt
+    /// show up in trade attribution
+    pub fn prompt_hash() -> String {
+        PromptTemplate::builtin().hash().to_string()
+    }
 
-Market Question: {}
+    /// Signals shown to the model in the `{signals}` placeholder, shared
+    /// with the model
+    pub fn recent_signals(&self) -> Arc<RecentSignals> {
+        self.recent_signals.clone()
+    }
 
-Description: {}
+    /// Ask about at most `batch_size` markets per batch request; 0 is
+    /// treated as 1
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
 
-Current Market Price: Yes = {:.2}% / No = {:.2}%
+    /// Show the social sentiment on each market's token in the
+    /// `{sentiment}` placeholder
+    pub fn with_sentiment(mut self, sentiment: Arc<SentimentIndex>) -> Self {
+        self.sentiment = Some(sentiment);
+        self
+    }
 
-Instructions:
-1. Consider all relevant factors, news, and historical precedents
-2. Be objective and avoid cognitive biases
-3. If you're uncertain, reflect that in your confidence score
+    /// The template for `market`'s category
+    pub fn template_for(&self, market: &Market) -> PromptTemplate {
+        self.prompts.lock().select(market.market_category())
+    }
 
-Respond with ONLY a JSON object in this exact format:
-{{"probability": <number 0-100>, "confidence": <number 0-100>, "reasoning": "<brief explanation>"}}
+    /// `market`'s prompt from `template`
+    pub fn render_prompt(&self, market: &Market, template: &PromptTemplate) -> String {
+        let mut context = PromptContext::new(market, &self.recent_signals.for_market(&market.id));
+        let now = chrono::Utc::now();
+        if let Some(score) = self.sentiment.as_ref().and_then(|index| index.for_market(market, now)) {
+            context = context.with_sentiment(score.describe(now));
+        }
+        template.render(&context)
+    }
 
-Example response:
-{{"probability": 65, "confidence": 70, "reasoning": "Based on recent polling data and historical trends..."}}
-"#,
-            market.question,
-            market.description.as_deref().unwrap_or("No description"),
-            yes_price * Decimal::ONE_HUNDRED,
-            (Decimal::ONE - yes_price) * Decimal::ONE_HUNDRED,
-        )
+    /// Predict `market` with a given template rather than its category's
+    pub async fn predict_with_template(&self, market: &Market, template: &PromptTemplate) -> Result<Prediction> {
+        let prompt = self.render_prompt(market, template);
+        let response = self.call_llm(&prompt).await?;
+        parse_prediction(&response)
     }
 
     async fn call_openai_compatible(
@@ -222,6 +338,8 @@ Example response:
         
         let response: OpenAIResponse = serde_json::from_str(&text)
             .map_err(|e| BotError::Api(format!("JSON parse error: {} - response: {}", e, &text[..text.len().min(200)])))?;
+        let (input, output) = response.usage.as_ref().map_or((0, 0), |u| (u.prompt_tokens, u.completion_tokens));
+        self.usage.record(input, output);
 
         response
             .choices
@@ -230,6 +348,94 @@ Example response:
             .ok_or_else(|| BotError::Api("Empty response from LLM".into()))
     }
 
+    /// Predict every market in one OpenAI request: a system+user message
+    /// pair per market, answered with one prediction each
+    async fn predict_openai_batch(
+        &self,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        markets: &[Market],
+    ) -> Result<Vec<Result<Prediction>>> {
+        let mut messages = vec![OpenAIMessage {
+            role: "system".to_string(),
+            content: BATCH_INSTRUCTIONS.to_string(),
+        }];
+        for (i, market) in markets.iter().enumerate() {
+            let template = self.template_for(market);
+            messages.push(OpenAIMessage {
+                role: "system".to_string(),
+                content: format!("Market {} of {}", i + 1, markets.len()),
+            });
+            messages.push(OpenAIMessage {
+                role: "user".to_string(),
+                content: self.render_prompt(market, &template),
+            });
+        }
+        let request = OpenAIRequest {
+            model: model.to_string(),
+            messages,
+            response_format: Some(ResponseFormat {
+                r#type: "json_object".to_string(),
+            }),
+        };
+
+        let response: OpenAIResponse = self
+            .http
+            .post(format!("{}/v1/chat/completions", base_url))
+            .header("content-type", "application/json")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let (input, output) = response.usage.as_ref().map_or((0, 0), |u| (u.prompt_tokens, u.completion_tokens));
+        self.usage.record(input, output);
+
+        let content = response
+            .choices
+            .first()
+            .map(|c| c.message.content.as_str())
+            .ok_or_else(|| BotError::Api("Empty response from LLM".into()))?;
+        parse_batch_predictions(content, markets.len())
+    }
+
+    /// One batch request for `markets`, falling back to one request per
+    /// market if it fails; a request that times out fails its markets
+    async fn predict_openai_chunk(
+        &self,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        markets: &[Market],
+        limits: BatchLimits,
+    ) -> Vec<Result<Prediction>> {
+        if markets.len() < 2 {
+            return predict_concurrently(self, markets, limits).await;
+        }
+        let request = self.predict_openai_batch(base_url, api_key, model, markets);
+        match tokio::time::timeout(limits.timeout, request).await {
+            Ok(Ok(predictions)) => return predictions,
+            Ok(Err(e)) => tracing::warn!(
+                "Batch prediction of {} markets failed, predicting one at a time: {}",
+                markets.len(),
+                e
+            ),
+            Err(_) => {
+                let error = format!(
+                    "batch prediction of {} markets timed out after {}s",
+                    markets.len(),
+                    limits.timeout.as_secs_f64()
+                );
+                tracing::warn!("{}", error);
+                return markets.iter().map(|_| Err(BotError::Api(error.clone()))).collect();
+            }
+        }
+        predict_concurrently(self, markets, limits).await
+    }
+
     async fn call_anthropic(&self, api_key: &str, model: &str, prompt: &str) -> Result<String> {
         let request = AnthropicRequest {
             model: model.to_string(),
@@ -251,6 +457,8 @@ Example response:
             .await?
             .json()
             .await?;
+        let (input, output) = response.usage.as_ref().map_or((0, 0), |u| (u.input_tokens, u.output_tokens));
+        self.usage.record(input, output);
 
         response
             .content
@@ -278,47 +486,35 @@ Example response:
             }
         }
     }
-
-    fn parse_response(&self, response: &str) -> Result<Prediction> {
-        // Try to extract JSON from the response
-        let json_str = if response.contains('{') {
-            let start = response.find('{').unwrap();
-            let end = response.rfind('}').unwrap_or(response.len() - 1) + 1;
-            &response[start..end]
-        } else {
-            response
-        };
-
-        let parsed: serde_json::Value = serde_json::from_str(json_str)
-            .map_err(|e| BotError::Api(format!("Failed to parse LLM response: {}", e)))?;
-
-        let probability = parsed["probability"]
-            .as_f64()
-            .ok_or_else(|| BotError::Api("Missing probability in response".into()))?;
-
-        let confidence = parsed["confidence"]
-            .as_f64()
-            .ok_or_else(|| BotError::Api("Missing confidence in response".into()))?;
-
-        let reasoning = parsed["reasoning"]
-            .as_str()
-            .unwrap_or("No reasoning provided")
-            .to_string();
-
-        Ok(Prediction {
-            probability: Decimal::try_from(probability / 100.0).unwrap_or(Decimal::new(50, 2)),
-            confidence: Decimal::try_from(confidence / 100.0).unwrap_or(Decimal::new(50, 2)),
-            reasoning,
-        })
-    }
 }
 
 #[async_trait]
 impl ProbabilityModel for LlmModel {
     async fn predict(&self, market: &Market) -> Result<Prediction> {
-        let prompt = self.build_prompt(market);
-        let response = self.call_llm(&prompt).await?;
-        self.parse_response(&response)
+        let template = self.template_for(market);
+        self.predict_with_template(market, &template).await
+    }
+
+    /// OpenAI answers the batch in requests of up to `batch_size`
+    /// markets each; other providers always ask one market at a time
+    async fn predict_batch(&self, markets: &[Market], limits: BatchLimits) -> Vec<Result<Prediction>> {
+        if let (LlmProvider::OpenAI { api_key, model, base_url }, 2..) = (&self.provider, markets.len()) {
+            let chunks = markets
+                .chunks(self.batch_size)
+                .map(|chunk| self.predict_openai_chunk(base_url, api_key, model, chunk, limits));
+            return futures_util::future::join_all(chunks).await.into_iter().flatten().collect();
+        }
+        predict_concurrently(self, markets, limits).await
+    }
+
+    /// Tags with the prompt of uncategorized markets
+    fn tags(&self) -> Vec<String> {
+        let template = self.prompts.lock().select(MarketCategory::Other);
+        vec![format!("model:{}", self.model_id()), format!("prompt:{}", template.hash())]
+    }
+
+    fn tags_for(&self, market: &Market) -> Vec<String> {
+        vec![format!("model:{}", self.model_id()), format!("prompt:{}", self.template_for(market).hash())]
     }
 
     fn name(&self) -> &str {
@@ -3,18 +3,28 @@
 //! This module contains various models to estimate the "true" probability
 //! of market outcomes, which can be compared to market prices to find edge.
 
+mod batch;
+mod breaker;
+mod fallback;
 mod llm;
+mod prompt;
 mod sentiment;
 #[cfg(test)]
 mod tests;
 
-pub use llm::{LlmModel, LlmProvider};
+pub use batch::{predict_concurrently, BatchLimits, BatchMetrics, Histogram};
+pub use breaker::{BreakerState, CircuitBreakerModel};
+pub use fallback::{LlmFallbackChain, MARKET_FALLBACK_NAME};
+pub use llm::{LlmModel, LlmProvider, LlmUsage};
+pub use prompt::{parse_batch_predictions, parse_prediction, PromptContext, PromptLibrary, PromptTemplate, RecentSignals};
 pub use sentiment::SentimentModel;
 
+use crate::analysis::sentiment::SentimentIndex;
 use crate::error::Result;
 use crate::types::Market;
 use async_trait::async_trait;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, MathematicalOps};
+use std::sync::Arc;
 
 /// Probability prediction result
 #[derive(Debug, Clone)]
@@ -25,6 +35,8 @@ pub struct Prediction {
     pub confidence: Decimal,
     /// Reasoning/explanation
     pub reasoning: String,
+    /// Provider that answered after the primary failed, if any
+    pub fallback_used: Option<String>,
 }
 
 /// Trait for probability models
@@ -32,66 +44,360 @@ pub struct Prediction {
 pub trait ProbabilityModel: Send + Sync {
     /// Predict the probability of the "Yes" outcome
     async fn predict(&self, market: &Market) -> Result<Prediction>;
+
+    /// Predict every market, index `i` of the result answering
+    /// `markets[i]`; one call per market within `limits`, unless the model
+    /// has a batch API
+    async fn predict_batch(&self, markets: &[Market], limits: BatchLimits) -> Vec<Result<Prediction>> {
+        predict_concurrently(self, markets, limits).await
+    }
     
     /// Model name for logging
     fn name(&self) -> &str;
+
+    /// Attribution tags stamped onto the trades of this model's signals,
+    /// such as its version and prompt hash
+    fn tags(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Tags for the signals on `market`, for models whose tags vary by
+    /// market (such as the prompt chosen for its category)
+    fn tags_for(&self, _market: &Market) -> Vec<String> {
+        self.tags()
+    }
+}
+
+/// Lets a model be shared, e.g. with the metrics endpoint, while the
+/// ensemble owns it
+#[async_trait]
+impl<T: ProbabilityModel + ?Sized> ProbabilityModel for Arc<T> {
+    async fn predict(&self, market: &Market) -> Result<Prediction> {
+        (**self).predict(market).await
+    }
+    async fn predict_batch(&self, markets: &[Market], limits: BatchLimits) -> Vec<Result<Prediction>> {
+        (**self).predict_batch(markets, limits).await
+    }
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+    fn tags(&self) -> Vec<String> {
+        (**self).tags()
+    }
+    fn tags_for(&self, market: &Market) -> Vec<String> {
+        (**self).tags_for(market)
+    }
+}
+
+/// The market's own YES price, with zero confidence: a stand-in that keeps
+/// scans running without producing edge
+pub struct MarketImpliedModel;
+
+#[async_trait]
+impl ProbabilityModel for MarketImpliedModel {
+    async fn predict(&self, market: &Market) -> Result<Prediction> {
+        Ok(Prediction {
+            probability: market.yes_price().unwrap_or(Decimal::new(50, 2)),
+            confidence: Decimal::ZERO,
+            reasoning: "Market-implied probability".to_string(),
+            fallback_used: None,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "MarketImplied"
+    }
+}
+
+/// `tags` in order, each once
+fn dedup_tags(tags: impl Iterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for tag in tags {
+        if !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    unique
 }
 
 /// Ensemble model combining multiple models
 pub struct EnsembleModel {
     models: Vec<(Box<dyn ProbabilityModel>, Decimal)>, // (model, weight)
+    sentiment: Option<Arc<SentimentIndex>>,
+    /// How each model predicts a batch
+    limits: BatchLimits,
+    batch_metrics: Arc<BatchMetrics>,
 }
 
 impl EnsembleModel {
     pub fn new() -> Self {
-        Self { models: Vec::new() }
+        Self {
+            models: Vec::new(),
+            sentiment: None,
+            limits: BatchLimits::default(),
+            batch_metrics: Arc::default(),
+        }
+    }
+
+    /// Let each model predict up to `max_concurrent` markets at once in a
+    /// batch; 0 is treated as 1
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.limits.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Fail each market's prediction in a batch after `timeout`, per model
+    pub fn with_analysis_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.limits.timeout = timeout;
+        self
+    }
+
+    /// Sizes and latencies of the batches predicted, shared with the model
+    pub fn batch_metrics(&self) -> Arc<BatchMetrics> {
+        self.batch_metrics.clone()
+    }
+
+    /// Nudge the combined probability toward the social sentiment on the
+    /// market's token, by at most the index's `max_adjustment`
+    pub fn with_sentiment(mut self, sentiment: Arc<SentimentIndex>) -> Self {
+        self.sentiment = Some(sentiment);
+        self
     }
 
     pub fn add_model(&mut self, model: Box<dyn ProbabilityModel>, weight: Decimal) {
         self.models.push((model, weight));
     }
 
-    pub async fn predict(&self, market: &Market) -> Result<Prediction> {
-        if self.models.is_empty() {
-            return Ok(Prediction {
-                probability: Decimal::new(50, 2),
-                confidence: Decimal::ZERO,
-                reasoning: "No models configured".to_string(),
-            });
-        }
+    /// Tags of every model in the ensemble, without duplicates
+    pub fn tags(&self) -> Vec<String> {
+        dedup_tags(self.models.iter().flat_map(|(model, _)| model.tags()))
+    }
 
-        let mut total_weight = Decimal::ZERO;
-        let mut weighted_prob = Decimal::ZERO;
-        let mut weighted_conf = Decimal::ZERO;
-        let mut reasons = Vec::new();
+    /// Tags of every model in the ensemble for `market`, without duplicates
+    pub fn tags_for(&self, market: &Market) -> Vec<String> {
+        dedup_tags(self.models.iter().flat_map(|(model, _)| model.tags_for(market)))
+    }
 
+    /// Predictions of every model that succeeded, with their weights
+    async fn predict_each(&self, market: &Market) -> Vec<(Prediction, Decimal, &str)> {
+        let mut predictions = Vec::new();
         for (model, weight) in &self.models {
             match model.predict(market).await {
-                Ok(pred) => {
-                    weighted_prob += pred.probability * weight;
-                    weighted_conf += pred.confidence * weight;
-                    total_weight += weight;
-                    reasons.push(format!("{}: {:.0}%", model.name(), pred.probability * Decimal::ONE_HUNDRED));
-                }
+                Ok(pred) => predictions.push((pred, *weight, model.name())),
                 Err(e) => {
                     tracing::warn!("Model {} failed: {}", model.name(), e);
                 }
             }
         }
+        predictions
+    }
+
+    /// Predictions of every model that succeeded on each market, with their
+    /// weights; each model gets the whole batch in one call
+    async fn predict_each_batch(&self, markets: &[Market]) -> Vec<Vec<(Prediction, Decimal, &str)>> {
+        let started = std::time::Instant::now();
+        let batches = futures_util::future::join_all(
+            self.models.iter().map(|(model, _)| model.predict_batch(markets, self.limits)),
+        )
+        .await;
+
+        let mut predictions: Vec<Vec<_>> = markets.iter().map(|_| Vec::new()).collect();
+        for ((model, weight), results) in self.models.iter().zip(batches) {
+            for ((market, result), each) in markets.iter().zip(results).zip(predictions.iter_mut()) {
+                match result {
+                    Ok(pred) => each.push((pred, *weight, model.name())),
+                    Err(e) => tracing::warn!("Model {} failed for {}: {}", model.name(), market.id, e),
+                }
+            }
+        }
+
+        let elapsed = started.elapsed();
+        self.batch_metrics.record(markets.len(), elapsed);
+        tracing::debug!("Predicted a batch of {} markets in {}ms", markets.len(), elapsed.as_millis());
+        predictions
+    }
+
+    /// Predict all `markets` in a single pass: index `i` of the result is
+    /// `markets[i]`'s prediction, `None` when every model failed on it
+    pub async fn predict_batch(&self, markets: &[Market]) -> Result<Vec<Option<Prediction>>> {
+        Ok(self
+            .predict_batch_with_uncertainty(markets)
+            .await?
+            .into_iter()
+            .map(|prediction| prediction.map(|p| p.prediction()))
+            .collect())
+    }
+
+    /// [`predict_batch`](Self::predict_batch) with each prediction's
+    /// uncertainty, as [`predict_with_uncertainty`](Self::predict_with_uncertainty)
+    pub async fn predict_batch_with_uncertainty(&self, markets: &[Market]) -> Result<Vec<Option<UncertainPrediction>>> {
+        if self.models.is_empty() {
+            return Ok(markets
+                .iter()
+                .map(|_| Some(UncertainPrediction::from_weighted(Self::no_models(), &[])))
+                .collect());
+        }
+        let batch = self.predict_each_batch(markets).await;
+        Ok(markets
+            .iter()
+            .zip(batch)
+            .map(|(market, predictions)| {
+                if predictions.is_empty() {
+                    return None;
+                }
+                let mut prediction = Self::combine(&predictions);
+                self.apply_sentiment(market, &mut prediction);
+                Some(UncertainPrediction::from_weighted(prediction, &predictions))
+            })
+            .collect())
+    }
+
+    pub async fn predict(&self, market: &Market) -> Result<Prediction> {
+        if self.models.is_empty() {
+            return Ok(Self::no_models());
+        }
+        let mut prediction = Self::combine(&self.predict_each(market).await);
+        self.apply_sentiment(market, &mut prediction);
+        Ok(prediction)
+    }
+
+    /// Ensemble prediction with its uncertainty: how much the models
+    /// disagree (epistemic) and how unsure they each are (aleatoric)
+    pub async fn predict_with_uncertainty(&self, market: &Market) -> Result<UncertainPrediction> {
+        if self.models.is_empty() {
+            return Ok(UncertainPrediction::from_weighted(Self::no_models(), &[]));
+        }
+        let predictions = self.predict_each(market).await;
+        let mut prediction = Self::combine(&predictions);
+        self.apply_sentiment(market, &mut prediction);
+        Ok(UncertainPrediction::from_weighted(prediction, &predictions))
+    }
 
+    /// Bounded sentiment adjustment of a combined prediction; nothing
+    /// without an index, recent signals or a `max_adjustment`
+    fn apply_sentiment(&self, market: &Market, prediction: &mut Prediction) {
+        let Some(index) = &self.sentiment else {
+            return;
+        };
+        let max_adjustment = index.max_adjustment();
+        if max_adjustment.is_zero() {
+            return;
+        }
+        let now = chrono::Utc::now();
+        if let Some(score) = index.for_market(market, now) {
+            prediction.probability = score.adjust(prediction.probability, max_adjustment);
+            prediction.reasoning.push_str(&format!("; sentiment {}", score.describe(now)));
+        }
+    }
+
+    fn no_models() -> Prediction {
+        Prediction {
+            probability: Decimal::new(50, 2),
+            confidence: Decimal::ZERO,
+            reasoning: "No models configured".to_string(),
+            fallback_used: None,
+        }
+    }
+
+    /// Weighted average of the individual predictions
+    fn combine(predictions: &[(Prediction, Decimal, &str)]) -> Prediction {
+        let total_weight: Decimal = predictions.iter().map(|(_, w, _)| *w).sum();
         if total_weight == Decimal::ZERO {
-            return Ok(Prediction {
+            return Prediction {
                 probability: Decimal::new(50, 2),
                 confidence: Decimal::ZERO,
                 reasoning: "All models failed".to_string(),
-            });
+                fallback_used: None,
+            };
         }
 
-        Ok(Prediction {
+        let weighted_prob: Decimal = predictions.iter().map(|(p, w, _)| p.probability * w).sum();
+        let weighted_conf: Decimal = predictions.iter().map(|(p, w, _)| p.confidence * w).sum();
+        let reasons: Vec<String> = predictions
+            .iter()
+            .map(|(p, _, name)| format!("{}: {:.0}%", name, p.probability * Decimal::ONE_HUNDRED))
+            .collect();
+
+        Prediction {
             probability: weighted_prob / total_weight,
             confidence: weighted_conf / total_weight,
             reasoning: reasons.join("; "),
-        })
+            fallback_used: predictions.iter().find_map(|(p, _, _)| p.fallback_used.clone()),
+        }
+    }
+}
+
+/// An ensemble prediction with a decomposed uncertainty estimate
+#[derive(Debug, Clone)]
+pub struct UncertainPrediction {
+    /// Weighted mean of the models' probabilities
+    pub mean: Decimal,
+    /// Weighted standard deviation of the models' probabilities
+    pub std_dev: Decimal,
+    /// Uncertainty from model disagreement (equal to `std_dev`)
+    pub epistemic: Decimal,
+    /// Uncertainty the models themselves report, from their confidence:
+    /// a Bernoulli spread `sqrt(p(1-p))` scaled by `1 - confidence`
+    pub aleatoric: Decimal,
+    /// `mean ± 1.96 σ` over both uncertainties, clamped to [0, 1]
+    pub credible_interval_95: (Decimal, Decimal),
+    /// Weighted confidence of the models
+    pub confidence: Decimal,
+    pub reasoning: String,
+    /// Provider that answered after the primary LLM failed, if any
+    pub fallback_used: Option<String>,
+}
+
+impl UncertainPrediction {
+    fn from_weighted(prediction: Prediction, predictions: &[(Prediction, Decimal, &str)]) -> Self {
+        let total_weight: Decimal = predictions.iter().map(|(_, w, _)| *w).sum();
+        let mean = prediction.probability;
+
+        let (epistemic, aleatoric) = if total_weight > Decimal::ZERO {
+            let variance = predictions
+                .iter()
+                .map(|(p, w, _)| (p.probability - mean) * (p.probability - mean) * w)
+                .sum::<Decimal>()
+                / total_weight;
+            let aleatoric = predictions
+                .iter()
+                .map(|(p, w, _)| {
+                    let spread = (p.probability * (Decimal::ONE - p.probability)).sqrt().unwrap_or_default();
+                    spread * (Decimal::ONE - p.confidence.clamp(Decimal::ZERO, Decimal::ONE)) * w
+                })
+                .sum::<Decimal>()
+                / total_weight;
+            (variance.sqrt().unwrap_or_default(), aleatoric)
+        } else {
+            (Decimal::ZERO, Decimal::ZERO)
+        };
+
+        let total = (epistemic * epistemic + aleatoric * aleatoric).sqrt().unwrap_or_default();
+        let half_width = Decimal::new(196, 2) * total;
+
+        Self {
+            mean,
+            std_dev: epistemic,
+            epistemic,
+            aleatoric,
+            credible_interval_95: (
+                (mean - half_width).max(Decimal::ZERO),
+                (mean + half_width).min(Decimal::ONE),
+            ),
+            confidence: prediction.confidence,
+            reasoning: prediction.reasoning,
+            fallback_used: prediction.fallback_used,
+        }
+    }
+
+    /// The point estimate
+    pub fn prediction(&self) -> Prediction {
+        Prediction {
+            probability: self.mean,
+            confidence: self.confidence,
+            reasoning: self.reasoning.clone(),
+            fallback_used: self.fallback_used.clone(),
+        }
     }
 }
 
@@ -0,0 +1,530 @@
+//! Prediction prompt templates
+//!
+//! A template is plain text with `{placeholder}`s for the market being
+//! predicted (see [`PLACEHOLDERS`]); `{{` and `}}` are literal braces.
+//! Substitution is a single pass over the parsed template, so braces in a
+//! market's question or description are inserted as they are and never
+//! read as placeholders.
+//!
+//! [`PromptLibrary`] picks a template per market category, falling back to
+//! the `default` one and then to the built-in prompt, and reloads template
+//! files when they change. Whatever the template, the model must answer
+//! with the JSON object read by [`parse_prediction`].
+
+use super::Prediction;
+use crate::config::PromptSource;
+use crate::error::{BotError, Result};
+use crate::types::{Market, MarketCategory, Side, Signal};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+
+/// Placeholders a template may use
+pub const PLACEHOLDERS: &[&str] = &["question", "description", "outcomes", "prices", "end_date", "signals", "sentiment"];
+
+/// Config key of the template used for categories without their own
+pub const DEFAULT_KEY: &str = "default";
+
+/// Recent signals kept per market for the `{signals}` placeholder
+const RECENT_SIGNALS: usize = 5;
+
+/// The built-in prediction prompt
+pub const BUILTIN_TEMPLATE: &str = r#"You are an expert prediction market analyst. Analyze the following market and estimate the probability of the "Yes" outcome.
+
+Market Question: {question}
+
+Description: {description}
+
+Outcomes: {outcomes}
+Current Market Price: {prices}
+Resolution Date: {end_date}
+
+Recent signals on this market:
+{signals}
+
+Recent social sentiment: {sentiment}
+
+Instructions:
+1. Consider all relevant factors, news, and historical precedents
+2. Be objective and avoid cognitive biases
+3. If you're uncertain, reflect that in your confidence score
+
+Respond with ONLY a JSON object in this exact format:
+{{"probability": <number 0-100>, "confidence": <number 0-100>, "reasoning": "<brief explanation>"}}
+
+Example response:
+{{"probability": 65, "confidence": 70, "reasoning": "Based on recent polling data and historical trends..."}}
+"#;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    /// Index into [`PLACEHOLDERS`]
+    Placeholder(usize),
+}
+
+/// A parsed prompt template
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    /// Where the template came from, for logs
+    pub name: String,
+    pieces: Vec<Piece>,
+    hash: String,
+}
+
+impl PromptTemplate {
+    /// Parse `text`, rejecting unknown placeholders and unmatched braces
+    pub fn parse(name: &str, text: &str) -> Result<Self> {
+        let invalid = |reason: String| BotError::Config(format!("Prompt template '{}': {}", name, reason));
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(invalid(format!("unclosed '{{{}'", placeholder))),
+                        }
+                    }
+                    let index = PLACEHOLDERS
+                        .iter()
+                        .position(|p| *p == placeholder.trim())
+                        .ok_or_else(|| invalid(format!("unknown placeholder '{{{}}}'", placeholder)))?;
+                    if !literal.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut literal)));
+                    }
+                    pieces.push(Piece::Placeholder(index));
+                }
+                '}' => return Err(invalid("unmatched '}', write '}}' for a literal brace".to_string())),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Text(literal));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            pieces,
+            hash: hex::encode(&Sha256::digest(text.as_bytes())[..4]),
+        })
+    }
+
+    /// The built-in prompt
+    pub fn builtin() -> Self {
+        Self::parse("built-in", BUILTIN_TEMPLATE).expect("built-in prompt template is valid")
+    }
+
+    /// First 8 hex digits of the SHA-256 of the template text, so that
+    /// edits to a prompt show up in attribution
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// The prompt for `context`
+    pub fn render(&self, context: &PromptContext) -> String {
+        let mut prompt = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => prompt.push_str(text),
+                Piece::Placeholder(index) => prompt.push_str(context.value(PLACEHOLDERS[*index])),
+            }
+        }
+        prompt
+    }
+}
+
+/// Values of the placeholders for one market
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptContext {
+    pub question: String,
+    pub description: String,
+    /// e.g. "Yes, No"
+    pub outcomes: String,
+    /// e.g. "Yes = 45.00% / No = 55.00%"
+    pub prices: String,
+    pub end_date: String,
+    /// One recent signal per line, or "None"
+    pub signals: String,
+    /// e.g. "+0.60 (12 sources, last 2h)", or "None"
+    pub sentiment: String,
+}
+
+impl PromptContext {
+    pub fn new(market: &Market, signals: &[String]) -> Self {
+        let prices = if market.outcomes.is_empty() {
+            "Unknown".to_string()
+        } else {
+            market
+                .outcomes
+                .iter()
+                .map(|o| format!("{} = {:.2}%", o.outcome, o.price * Decimal::ONE_HUNDRED))
+                .collect::<Vec<_>>()
+                .join(" / ")
+        };
+        Self {
+            question: market.question.clone(),
+            description: market.description.clone().unwrap_or_else(|| "No description".to_string()),
+            outcomes: market.outcomes.iter().map(|o| o.outcome.as_str()).collect::<Vec<_>>().join(", "),
+            prices,
+            end_date: market
+                .end_date
+                .map_or_else(|| "Unknown".to_string(), |d| d.format("%Y-%m-%d %H:%M UTC").to_string()),
+            signals: if signals.is_empty() { "None".to_string() } else { signals.join("\n") },
+            sentiment: "None".to_string(),
+        }
+    }
+
+    /// Show `sentiment` in the `{sentiment}` placeholder
+    pub fn with_sentiment(mut self, sentiment: String) -> Self {
+        self.sentiment = sentiment;
+        self
+    }
+
+    fn value(&self, placeholder: &str) -> &str {
+        match placeholder {
+            "question" => &self.question,
+            "description" => &self.description,
+            "outcomes" => &self.outcomes,
+            "prices" => &self.prices,
+            "end_date" => &self.end_date,
+            "signals" => &self.signals,
+            "sentiment" => &self.sentiment,
+            _ => "",
+        }
+    }
+}
+
+/// A configured template and the file version it was read from
+#[derive(Debug)]
+struct Loaded {
+    source: PromptSource,
+    template: PromptTemplate,
+    modified: Option<SystemTime>,
+}
+
+fn modified(source: &PromptSource) -> Option<SystemTime> {
+    match source {
+        PromptSource::File(path) => std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+        PromptSource::Text(_) => None,
+    }
+}
+
+fn load(key: &str, source: &PromptSource) -> Result<Loaded> {
+    let template = match source {
+        PromptSource::File(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| BotError::Config(format!("Failed to read prompt template {}: {}", path.display(), e)))?;
+            PromptTemplate::parse(&path.display().to_string(), &text)?
+        }
+        PromptSource::Text(text) => PromptTemplate::parse(key, text)?,
+    };
+    Ok(Loaded {
+        source: source.clone(),
+        template,
+        modified: modified(source),
+    })
+}
+
+/// Templates by market category
+#[derive(Debug, Default)]
+pub struct PromptLibrary {
+    templates: HashMap<String, Loaded>,
+}
+
+impl PromptLibrary {
+    /// Load `prompts`, keyed by category name or [`DEFAULT_KEY`]
+    pub fn from_config(prompts: &HashMap<String, PromptSource>) -> Result<Self> {
+        let categories = [
+            MarketCategory::Crypto,
+            MarketCategory::Politics,
+            MarketCategory::Sports,
+            MarketCategory::Economics,
+            MarketCategory::Culture,
+            MarketCategory::Other,
+        ];
+        let mut templates = HashMap::new();
+        for (key, source) in prompts {
+            let key = key.to_lowercase();
+            if key != DEFAULT_KEY && !categories.iter().any(|c| c.name() == key) {
+                return Err(BotError::Config(format!("Unknown prompt category '{}'", key)));
+            }
+            let loaded = load(&key, source)?;
+            templates.insert(key, loaded);
+        }
+        Ok(Self { templates })
+    }
+
+    /// Template for markets in `category`, rereading its file first if it
+    /// changed. A template that no longer parses keeps its last version.
+    pub fn select(&mut self, category: MarketCategory) -> PromptTemplate {
+        for key in [category.name(), DEFAULT_KEY] {
+            let Some(loaded) = self.templates.get_mut(key) else {
+                continue;
+            };
+            let current = modified(&loaded.source);
+            if current != loaded.modified {
+                match load(key, &loaded.source) {
+                    Ok(reloaded) => {
+                        tracing::info!("Reloaded prompt template {} ({})", reloaded.template.name, reloaded.template.hash());
+                        *loaded = reloaded;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Keeping the previous prompt template: {}", e);
+                        loaded.modified = current;
+                    }
+                }
+            }
+            return loaded.template.clone();
+        }
+        PromptTemplate::builtin()
+    }
+}
+
+/// The latest signals on each market, for the `{signals}` placeholder
+#[derive(Debug, Default)]
+pub struct RecentSignals {
+    by_market: Mutex<HashMap<String, VecDeque<String>>>,
+}
+
+impl RecentSignals {
+    pub fn record(&self, signal: &Signal) {
+        let line = format!(
+            "{} {} at {:.1}% (model {:.1}%, edge {:+.1}%)",
+            signal.timestamp.format("%Y-%m-%d %H:%M UTC"),
+            match signal.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            },
+            signal.market_probability * Decimal::ONE_HUNDRED,
+            signal.model_probability * Decimal::ONE_HUNDRED,
+            signal.edge * Decimal::ONE_HUNDRED,
+        );
+        let mut by_market = self.by_market.lock();
+        let lines = by_market.entry(signal.market_id.clone()).or_default();
+        if lines.len() == RECENT_SIGNALS {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Signals on `market_id`, oldest first
+    pub fn for_market(&self, market_id: &str) -> Vec<String> {
+        self.by_market
+            .lock()
+            .get(market_id)
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Read the model's answer, whatever the prompt: the first JSON object in
+/// `response` with a `probability` and `confidence`, both in percent
+pub fn parse_prediction(response: &str) -> Result<Prediction> {
+    let parsed = response
+        .match_indices('{')
+        .filter_map(|(start, _)| {
+            serde_json::Deserializer::from_str(&response[start..])
+                .into_iter::<serde_json::Value>()
+                .next()?
+                .ok()
+        })
+        .find(|value| value.get("probability").is_some())
+        .ok_or_else(|| BotError::Api("Failed to parse LLM response: no prediction object".into()))?;
+
+    let probability = parsed["probability"]
+        .as_f64()
+        .ok_or_else(|| BotError::Api("Missing probability in response".into()))?;
+
+    let confidence = parsed["confidence"]
+        .as_f64()
+        .ok_or_else(|| BotError::Api("Missing confidence in response".into()))?;
+
+    let reasoning = parsed["reasoning"]
+        .as_str()
+        .unwrap_or("No reasoning provided")
+        .to_string();
+
+    Ok(Prediction {
+        probability: Decimal::try_from(probability / 100.0).unwrap_or(Decimal::new(50, 2)),
+        confidence: Decimal::try_from(confidence / 100.0).unwrap_or(Decimal::new(50, 2)),
+        reasoning,
+        fallback_used: None,
+    })
+}
+
+/// Read a batch answer: a JSON object whose `predictions` array holds one
+/// prediction object per market, in the order the markets were asked.
+/// Fails when the array is missing or its length isn't `expected`; a
+/// single unreadable entry fails only its own market.
+pub fn parse_batch_predictions(response: &str, expected: usize) -> Result<Vec<Result<Prediction>>> {
+    let parsed = response
+        .match_indices('{')
+        .filter_map(|(start, _)| {
+            serde_json::Deserializer::from_str(&response[start..])
+                .into_iter::<serde_json::Value>()
+                .next()?
+                .ok()
+        })
+        .find(|value| value.get("predictions").is_some_and(|p| p.is_array()))
+        .ok_or_else(|| BotError::Api("Failed to parse LLM batch response: no predictions array".into()))?;
+
+    let entries = parsed["predictions"].as_array().map(Vec::as_slice).unwrap_or_default();
+    if entries.len() != expected {
+        return Err(BotError::Api(format!(
+            "LLM batch response has {} predictions for {} markets",
+            entries.len(),
+            expected
+        )));
+    }
+    Ok(entries.iter().map(|entry| parse_prediction(&entry.to_string())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn market(question: &str) -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: question.to_string(),
+            description: Some("Resolves per {the} rules".to_string()),
+            end_date: Some(Utc.with_ymd_and_hms(2024, 11, 5, 23, 0, 0).unwrap()),
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: vec![
+                Outcome {
+                    token_id: "yes".to_string(),
+                    outcome: "Yes".to_string(),
+                    price: dec!(0.45),
+                },
+                Outcome {
+                    token_id: "no".to_string(),
+                    outcome: "No".to_string(),
+                    price: dec!(0.55),
+                },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: Some("Politics".to_string()),
+            order_constraints: None,
+        }
+    }
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let template =
+            PromptTemplate::parse("t", "Q: {question}\nO: {outcomes}\nP: {prices}\nE: {end_date}\nS: {signals}").unwrap();
+        let context = PromptContext::new(&market("Will X win?"), &["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            template.render(&context),
+            "Q: Will X win?\nO: Yes, No\nP: Yes = 45.00% / No = 55.00%\nE: 2024-11-05 23:00 UTC\nS: a\nb"
+        );
+        assert_eq!(PromptContext::new(&market("?"), &[]).signals, "None");
+
+        let template = PromptTemplate::parse("t", "Sentiment: {sentiment}").unwrap();
+        assert_eq!(template.render(&PromptContext::new(&market("?"), &[])), "Sentiment: None");
+        let context = PromptContext::new(&market("?"), &[]).with_sentiment("+0.60 (12 sources, last 2h)".to_string());
+        assert_eq!(template.render(&context), "Sentiment: +0.60 (12 sources, last 2h)");
+    }
+
+    #[test]
+    fn test_braces_in_market_text_are_not_placeholders() {
+        let template = PromptTemplate::parse("t", "{question} | {description} | {{\"probability\": 1}}").unwrap();
+        let rendered = template.render(&PromptContext::new(&market("Will {signals} trend on {{X}}?"), &[]));
+        assert_eq!(rendered, "Will {signals} trend on {{X}}? | Resolves per {the} rules | {\"probability\": 1}");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_templates() {
+        assert!(PromptTemplate::parse("t", "{question} {volume}").is_err());
+        assert!(PromptTemplate::parse("t", "{question").is_err());
+        assert!(PromptTemplate::parse("t", "JSON: {\"p\": 1}").is_err());
+        assert!(PromptTemplate::parse("t", "}").is_err());
+        assert_eq!(PromptTemplate::builtin().hash().len(), 8);
+        assert_ne!(PromptTemplate::parse("t", "{question}").unwrap().hash(), PromptTemplate::builtin().hash());
+    }
+
+    #[test]
+    fn test_library_selects_by_category_and_reloads_files() {
+        let path = std::env::temp_dir().join(format!("prompt-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "Election: {question}").unwrap();
+        let prompts = HashMap::from([
+            ("politics".to_string(), PromptSource::File(path.clone())),
+            ("default".to_string(), PromptSource::Text("Generic: {question}".to_string())),
+        ]);
+        let mut library = PromptLibrary::from_config(&prompts).unwrap();
+        let context = PromptContext::new(&market("Q?"), &[]);
+
+        let politics = library.select(MarketCategory::Politics);
+        assert_eq!(politics.render(&context), "Election: Q?");
+        assert_eq!(library.select(MarketCategory::Sports).render(&context), "Generic: Q?");
+
+        // Force a different modification time in case the rewrite lands in
+        // the same filesystem tick
+        library.templates.get_mut("politics").unwrap().modified = None;
+        std::fs::write(&path, "Race: {question}").unwrap();
+        let reloaded = library.select(MarketCategory::Politics);
+        assert_eq!(reloaded.render(&context), "Race: Q?");
+        assert_ne!(reloaded.hash(), politics.hash());
+
+        // A broken edit keeps the last good version
+        library.templates.get_mut("politics").unwrap().modified = None;
+        std::fs::write(&path, "Race: {nope}").unwrap();
+        assert_eq!(library.select(MarketCategory::Politics).render(&context), "Race: Q?");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(PromptLibrary::from_config(&HashMap::from([(
+            "weather".to_string(),
+            PromptSource::Text("{question}".to_string())
+        )]))
+        .is_err());
+        assert_eq!(PromptLibrary::default().select(MarketCategory::Crypto), PromptTemplate::builtin());
+    }
+
+    #[test]
+    fn test_parse_prediction_skips_echoed_braces() {
+        let response = r#"The market "{X} by 2025?" looks mispriced. {"probability": 62, "confidence": 70, "reasoning": "r"}"#;
+        let prediction = parse_prediction(response).unwrap();
+        assert_eq!(prediction.probability, dec!(0.62));
+        assert_eq!(prediction.confidence, dec!(0.7));
+        assert!(parse_prediction("no json here").is_err());
+        assert!(parse_prediction(r#"{"probability": 50}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_predictions_keeps_market_order() {
+        let response = r#"{"predictions": [
+            {"probability": 30, "confidence": 80, "reasoning": "a"},
+            {"probability": 55},
+            {"probability": 90, "confidence": 60, "reasoning": "c"}
+        ]}"#;
+        let predictions = parse_batch_predictions(response, 3).unwrap();
+        assert_eq!(predictions[0].as_ref().unwrap().probability, dec!(0.3));
+        // A bad entry fails only its own market
+        assert!(predictions[1].is_err());
+        assert_eq!(predictions[2].as_ref().unwrap().probability, dec!(0.9));
+
+        assert!(parse_batch_predictions(response, 2).is_err());
+        assert!(parse_batch_predictions(r#"{"probability": 50, "confidence": 50}"#, 1).is_err());
+    }
+}
@@ -33,6 +33,7 @@ impl ProbabilityModel for SentimentModel {
             probability,
             confidence: Decimal::new(30, 2), // Low confidence for placeholder
             reasoning: "Sentiment analysis not yet implemented".to_string(),
+            fallback_used: None,
         })
     }
 
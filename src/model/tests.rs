@@ -101,6 +101,24 @@ mod tests {
         let _ = model;
     }
 
+    #[test]
+    fn test_llm_model_tags_stamp_model_and_prompt_hash() {
+        use crate::model::ProbabilityModel;
+
+        let hash = LlmModel::prompt_hash();
+        assert_eq!(hash.len(), 8);
+        assert_eq!(hash, LlmModel::prompt_hash());
+
+        let model = LlmModel::deepseek("sk-test".to_string());
+        assert_eq!(model.tags(), vec!["model:deepseek-chat".to_string(), format!("prompt:{}", hash)]);
+
+        // Ensembles report each tag once
+        let mut ensemble = crate::model::EnsembleModel::new();
+        ensemble.add_model(Box::new(LlmModel::deepseek("a".to_string())), dec!(1));
+        ensemble.add_model(Box::new(LlmModel::deepseek("b".to_string())), dec!(1));
+        assert_eq!(ensemble.tags(), model.tags());
+    }
+
     #[test]
     fn test_llm_model_from_config_deepseek() {
         let config = LlmConfig {
@@ -108,6 +126,15 @@ mod tests {
             api_key: "sk-test".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -120,6 +147,15 @@ mod tests {
             api_key: "sk-test".to_string(),
             model: Some("claude-3".to_string()),
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -132,6 +168,15 @@ mod tests {
             api_key: "sk-test".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -144,6 +189,15 @@ mod tests {
             api_key: "sk-test".to_string(),
             model: Some("gpt-4".to_string()),
             base_url: Some("https://api.openai.com".to_string()),
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -156,6 +210,15 @@ mod tests {
             api_key: "sk-test".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -168,6 +231,15 @@ mod tests {
             api_key: "".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -180,6 +252,15 @@ mod tests {
             api_key: "".to_string(),
             model: Some("custom-model".to_string()),
             base_url: Some("http://localhost:8000".to_string()),
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -192,6 +273,15 @@ mod tests {
             api_key: "test-key".to_string(),
             model: Some("custom-model".to_string()),
             base_url: Some("http://localhost:8000".to_string()),
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let model = LlmModel::from_config(&config).unwrap();
         let _ = model;
@@ -204,6 +294,15 @@ mod tests {
             api_key: "test".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let result = LlmModel::from_config(&config);
         assert!(result.is_err());
@@ -216,6 +315,15 @@ mod tests {
             api_key: "".to_string(),
             model: None,
             base_url: Some("http://localhost:8000".to_string()),
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let result = LlmModel::from_config(&config);
         assert!(result.is_err());
@@ -228,6 +336,15 @@ mod tests {
             api_key: "".to_string(),
             model: Some("model".to_string()),
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let result = LlmModel::from_config(&config);
         assert!(result.is_err());
@@ -240,6 +357,15 @@ mod tests {
             api_key: "test".to_string(),
             model: None,
             base_url: None,
+            failure_threshold: 3,
+            breaker_cooldown_secs: 300,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+            prompts: Default::default(),
+            fallback: Vec::new(),
+            provider_timeout_secs: 30,
+            max_concurrent: None,
+            batch_size: None,
         };
         let result = LlmModel::from_config(&config);
         assert!(result.is_ok());
@@ -268,6 +394,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -277,4 +407,323 @@ mod tests {
         assert_eq!(market.yes_price(), Some(dec!(0.65)));
         assert_eq!(market.no_price(), Some(dec!(0.35)));
     }
+
+    struct FixedModel {
+        name: &'static str,
+        probability: rust_decimal::Decimal,
+        confidence: rust_decimal::Decimal,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::model::ProbabilityModel for FixedModel {
+        async fn predict(&self, _market: &Market) -> crate::error::Result<crate::model::Prediction> {
+            Ok(crate::model::Prediction {
+                probability: self.probability,
+                confidence: self.confidence,
+                reasoning: String::new(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    fn ensemble(models: &[(rust_decimal::Decimal, rust_decimal::Decimal, rust_decimal::Decimal)]) -> crate::model::EnsembleModel {
+        let mut ensemble = crate::model::EnsembleModel::new();
+        for &(probability, confidence, weight) in models {
+            ensemble.add_model(Box::new(FixedModel { name: "fixed", probability, confidence }), weight);
+        }
+        ensemble
+    }
+
+    #[tokio::test]
+    async fn test_uncertainty_from_model_disagreement() {
+        let market = create_test_market();
+
+        // Two fully confident models 40 points apart: σ = 0.2, no aleatoric part
+        let split = ensemble(&[(dec!(0.3), dec!(1), dec!(1)), (dec!(0.7), dec!(1), dec!(1))]);
+        let u = split.predict_with_uncertainty(&market).await.unwrap();
+        assert_eq!(u.mean, dec!(0.5));
+        assert_eq!(u.std_dev, dec!(0.2));
+        assert_eq!(u.epistemic, u.std_dev);
+        assert_eq!(u.aleatoric, dec!(0));
+        assert_eq!(u.credible_interval_95, (dec!(0.108), dec!(0.892)));
+        assert_eq!(u.prediction().probability, split.predict(&market).await.unwrap().probability);
+
+        // Agreeing but unsure models: only aleatoric uncertainty
+        let unsure = ensemble(&[(dec!(0.5), dec!(0.5), dec!(0.7)), (dec!(0.5), dec!(0.5), dec!(0.3))]);
+        let u = unsure.predict_with_uncertainty(&market).await.unwrap();
+        assert_eq!(u.epistemic, dec!(0));
+        assert_eq!(u.aleatoric, dec!(0.25));
+        assert_eq!(u.credible_interval_95, (dec!(0.01), dec!(0.99)));
+    }
+
+    #[tokio::test]
+    async fn test_weighted_disagreement_and_empty_ensemble() {
+        let market = create_test_market();
+
+        // 3:1 weights pull the mean toward the heavier model
+        let weighted = ensemble(&[(dec!(0.2), dec!(1), dec!(3)), (dec!(0.6), dec!(1), dec!(1))]);
+        let u = weighted.predict_with_uncertainty(&market).await.unwrap();
+        assert_eq!(u.mean, dec!(0.3));
+        // sqrt((3 * 0.01 + 0.09) / 4) = sqrt(0.03)
+        assert!((u.epistemic - dec!(0.1732)).abs() < dec!(0.0001));
+
+        let empty = crate::model::EnsembleModel::new().predict_with_uncertainty(&market).await.unwrap();
+        assert_eq!(empty.mean, dec!(0.5));
+        assert_eq!(empty.epistemic, dec!(0));
+        assert_eq!(empty.credible_interval_95, (dec!(0.5), dec!(0.5)));
+    }
+
+    #[tokio::test]
+    async fn test_sentiment_adjusts_ensemble_within_bounds() {
+        use crate::analysis::sentiment::SentimentIndex;
+        use crate::config::SentimentConfig;
+        use crate::ingester::{ActionType, ParsedSignal, SignalDirection};
+        use std::sync::Arc;
+
+        let mut market = create_test_market();
+        market.question = "Will Bitcoin close above $100k?".to_string();
+        let config = SentimentConfig { max_adjustment: 0.05, ..SentimentConfig::default() };
+
+        // An empty index leaves predictions untouched
+        let empty = ensemble(&[(dec!(0.6), dec!(1), dec!(1))]).with_sentiment(Arc::new(SentimentIndex::new(config.clone())));
+        let prediction = empty.predict(&market).await.unwrap();
+        assert_eq!(prediction.probability, dec!(0.6));
+        assert_eq!(empty.predict_with_uncertainty(&market).await.unwrap().mean, dec!(0.6));
+
+        let index = Arc::new(SentimentIndex::new(config));
+        index.record(&ParsedSignal {
+            token: "BTC".to_string(),
+            direction: SignalDirection::Bearish,
+            timeframe: "1h".to_string(),
+            confidence: 1.0,
+            reasoning: String::new(),
+            action_type: ActionType::Entry,
+            sources: Vec::new(),
+            agg_score: 1.0,
+            timestamp: Utc::now(),
+            trace_id: Default::default(),
+        });
+        let bearish = ensemble(&[(dec!(0.6), dec!(1), dec!(1))]).with_sentiment(index);
+        let prediction = bearish.predict(&market).await.unwrap();
+        // Moved by at most max_adjustment
+        assert!(prediction.probability < dec!(0.6) && prediction.probability >= dec!(0.55));
+        assert!(prediction.reasoning.contains("sentiment -"));
+    }
+
+    /// Fails on markets whose id starts with "bad" and hangs on those
+    /// starting with "slow", tracking how many of its predictions run at once
+    struct ConcurrencyModel {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::model::ProbabilityModel for ConcurrencyModel {
+        async fn predict(&self, market: &Market) -> crate::error::Result<crate::model::Prediction> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            let delay = if market.id.starts_with("slow") { 60_000 } else { 5 };
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if market.id.starts_with("bad") {
+                return Err(crate::error::BotError::Api("HTTP 500".into()));
+            }
+            Ok(crate::model::Prediction {
+                probability: market.yes_price().unwrap(),
+                confidence: dec!(0.8),
+                reasoning: market.id.clone(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "concurrency"
+        }
+    }
+
+    fn priced_market(id: &str, yes: rust_decimal::Decimal) -> Market {
+        let mut market = create_test_market();
+        market.id = id.to_string();
+        market.outcomes[0].price = yes;
+        market.outcomes[1].price = rust_decimal::Decimal::ONE - yes;
+        market
+    }
+
+    #[tokio::test]
+    async fn test_predict_batch_is_aligned_with_markets() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let model = ConcurrencyModel { in_flight: AtomicUsize::new(0), max_in_flight: max_in_flight.clone() };
+        let mut ensemble = crate::model::EnsembleModel::new().with_max_concurrent(3);
+        ensemble.add_model(Box::new(model), dec!(1));
+
+        let markets: Vec<Market> = (0..10)
+            .map(|i| {
+                let id = if i == 4 { "bad-4".to_string() } else { format!("m{}", i) };
+                priced_market(&id, dec!(0.05) * rust_decimal::Decimal::from(i + 1))
+            })
+            .collect();
+        let predictions = ensemble.predict_batch(&markets).await.unwrap();
+
+        assert_eq!(predictions.len(), markets.len());
+        for (market, prediction) in markets.iter().zip(&predictions) {
+            match prediction {
+                // Every model failed on it
+                None => assert_eq!(market.id, "bad-4"),
+                Some(p) => assert_eq!(p.probability, market.yes_price().unwrap()),
+            }
+        }
+        assert!(predictions[4].is_none());
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+
+        let metrics = ensemble.batch_metrics();
+        assert_eq!(metrics.batch_size.count(), 1);
+        assert_eq!(metrics.batch_size.sum(), 10);
+        assert_eq!(metrics.batch_latency_ms.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_metrics_render_cumulative_histograms() {
+        use crate::monitor::MetricsSource;
+
+        let metrics = crate::model::BatchMetrics::new();
+        metrics.record(3, std::time::Duration::from_millis(200));
+        metrics.record(30, std::time::Duration::from_secs(90));
+        let text = metrics.prometheus_text().await;
+
+        assert!(text.contains("# TYPE polymarket_bot_prediction_batch_size histogram\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_size_bucket{le=\"1\"} 0\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_size_bucket{le=\"5\"} 1\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_size_bucket{le=\"50\"} 2\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_size_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_size_sum 33\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_latency_ms_bucket{le=\"60000\"} 1\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_latency_ms_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("polymarket_bot_prediction_batch_latency_ms_count 2\n"));
+    }
+
+    #[tokio::test]
+    async fn test_slow_market_times_out_without_the_batch() {
+        let model = ConcurrencyModel {
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            max_in_flight: Default::default(),
+        };
+        let mut ensemble = crate::model::EnsembleModel::new()
+            .with_max_concurrent(2)
+            .with_analysis_timeout(std::time::Duration::from_millis(100));
+        ensemble.add_model(Box::new(model), dec!(1));
+
+        let markets: Vec<Market> =
+            ["m0", "slow-1", "m2", "m3"].iter().map(|id| priced_market(id, dec!(0.3))).collect();
+        let started = std::time::Instant::now();
+        let predictions = ensemble.predict_batch(&markets).await.unwrap();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(predictions[1].is_none());
+        assert!([0, 2, 3].iter().all(|&i| predictions[i].is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_openai_predicts_a_batch_in_one_request() {
+        use crate::model::ProbabilityModel;
+        use axum::{routing::post, Json, Router};
+        use parking_lot::Mutex;
+        use serde_json::{json, Value};
+        use std::sync::Arc;
+
+        let requests: Arc<Mutex<Vec<Value>>> = Arc::default();
+        let seen = requests.clone();
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post(move |Json(body): Json<Value>| {
+                let seen = seen.clone();
+                async move {
+                    seen.lock().push(body);
+                    let content = json!({"predictions": [
+                        {"probability": 20, "confidence": 70, "reasoning": "a"},
+                        {"probability": 50, "confidence": 60, "reasoning": "b"},
+                        {"probability": 80, "confidence": 90, "reasoning": "c"},
+                    ]});
+                    Json(json!({
+                        "choices": [{"message": {"content": content.to_string()}}],
+                        "usage": {"prompt_tokens": 900, "completion_tokens": 120},
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let model = LlmModel::new(LlmProvider::OpenAI {
+            api_key: "sk-test".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            base_url: format!("http://{}", addr),
+        });
+        let markets: Vec<Market> = ["a", "b", "c"].iter().map(|id| priced_market(id, dec!(0.5))).collect();
+        let predictions = model.predict_batch(&markets, crate::model::BatchLimits::default()).await;
+
+        let probabilities: Vec<_> = predictions.iter().map(|p| p.as_ref().unwrap().probability).collect();
+        assert_eq!(probabilities, [dec!(0.2), dec!(0.5), dec!(0.8)]);
+
+        // One request: the instructions, then a system+user pair per market
+        let requests = requests.lock();
+        assert_eq!(requests.len(), 1);
+        let roles: Vec<_> = requests[0]["messages"].as_array().unwrap().iter().map(|m| m["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, ["system", "system", "user", "system", "user", "system", "user"]);
+        assert_eq!(requests[0]["messages"][5]["content"], "Market 3 of 3");
+        assert_eq!(model.usage().take().calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_openai_batches_are_split_by_batch_size() {
+        use crate::model::ProbabilityModel;
+        use axum::{routing::post, Json, Router};
+        use serde_json::{json, Value};
+        use std::sync::Arc;
+
+        // Answers each request with one prediction per market asked about
+        let sizes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = sizes.clone();
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post(move |Json(body): Json<Value>| {
+                let seen = seen.clone();
+                async move {
+                    let asked = body["messages"].as_array().unwrap().iter().filter(|m| m["role"] == "user").count();
+                    seen.lock().push(asked);
+                    let predictions: Vec<_> = (0..asked)
+                        .map(|_| json!({"probability": 40, "confidence": 70, "reasoning": "x"}))
+                        .collect();
+                    let content = json!({ "predictions": predictions });
+                    Json(json!({"choices": [{"message": {"content": content.to_string()}}]}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let model = LlmModel::new(LlmProvider::OpenAI {
+            api_key: "sk-test".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            base_url: format!("http://{}", addr),
+        })
+        .with_batch_size(2);
+        let markets: Vec<Market> = (0..5).map(|i| priced_market(&format!("m{}", i), dec!(0.5))).collect();
+        let predictions = model.predict_batch(&markets, crate::model::BatchLimits::default()).await;
+
+        assert_eq!(predictions.len(), 5);
+        assert!(predictions.iter().all(|p| p.as_ref().unwrap().probability == dec!(0.4)));
+        let mut sizes = sizes.lock().clone();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [1, 2, 2]);
+    }
 }
@@ -0,0 +1,456 @@
+//! User-defined alert rules
+//!
+//! Each `[[alerts.rules]]` entry has an expression over [`BotMetrics`], such
+//! as `daily_pnl < -50 && open_positions > 3`. Comparisons (`<`, `<=`, `>`,
+//! `>=`, `==`, `!=`) take metric names or numbers and are joined by `&&`,
+//! which binds tighter than `||`. The [`AlertEngine`] checks every rule each
+//! scan and holds back rules still in their cooldown.
+
+use crate::config::{AlertRule, AlertSeverity};
+use crate::error::{BotError, Result};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Names usable in alert expressions and message templates
+pub const METRIC_NAMES: &[&str] = &[
+    "daily_pnl",
+    "balance",
+    "equity",
+    "drawdown",
+    "open_positions",
+    "consecutive_losses",
+    "rolling_sharpe",
+    "rolling_sortino",
+];
+
+/// Current values of the metrics alert rules can refer to. Ratios that
+/// aren't available yet are NaN; comparisons with NaN never hold.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BotMetrics {
+    /// Realized P&L today (USDC)
+    pub daily_pnl: f64,
+    /// Cash balance over all accounts (USDC)
+    pub balance: f64,
+    /// Balance plus open positions at market prices (USDC)
+    pub equity: f64,
+    /// Fraction below the peak balance (0.1 = 10% down)
+    pub drawdown: f64,
+    pub open_positions: f64,
+    /// Closing fills in a row that lost money
+    pub consecutive_losses: f64,
+    pub rolling_sharpe: f64,
+    pub rolling_sortino: f64,
+}
+
+impl BotMetrics {
+    /// Value of the metric called `name`
+    pub fn get(&self, name: &str) -> Option<f64> {
+        Some(match name {
+            "daily_pnl" => self.daily_pnl,
+            "balance" => self.balance,
+            "equity" => self.equity,
+            "drawdown" => self.drawdown,
+            "open_positions" => self.open_positions,
+            "consecutive_losses" => self.consecutive_losses,
+            "rolling_sharpe" => self.rolling_sharpe,
+            "rolling_sortino" => self.rolling_sortino,
+            _ => return None,
+        })
+    }
+
+    /// Replace `{metric}` placeholders in `template` by current values
+    pub fn render(&self, template: &str) -> String {
+        METRIC_NAMES.iter().fold(template.to_string(), |text, name| {
+            let placeholder = format!("{{{}}}", name);
+            if !text.contains(&placeholder) {
+                return text;
+            }
+            let value = self.get(name).unwrap_or(f64::NAN);
+            let value = if value.is_nan() {
+                "n/a".to_string()
+            } else if value.fract() == 0.0 {
+                format!("{}", value)
+            } else {
+                format!("{:.2}", value)
+            };
+            text.replace(&placeholder, &value)
+        })
+    }
+}
+
+/// Evaluates alert expressions against [`BotMetrics`]
+pub struct ExpressionEvaluator;
+
+impl ExpressionEvaluator {
+    /// Whether `expr` holds. Malformed expressions and unknown metrics are
+    /// configuration errors.
+    pub fn eval(expr: &str, metrics: &BotMetrics) -> Result<bool> {
+        let tokens = tokenize(expr)?;
+        let mut parser = Parser {
+            expr,
+            tokens: &tokens,
+            pos: 0,
+            metrics,
+        };
+        let holds = parser.or()?;
+        if parser.pos < tokens.len() {
+            return Err(parser.error("unexpected input after expression"));
+        }
+        Ok(holds)
+    }
+
+    /// Check every rule's expression parses and only names known metrics
+    pub fn validate(rules: &[AlertRule]) -> Result<()> {
+        for rule in rules {
+            Self::eval(&rule.expression, &BotMetrics::default())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Metric(String),
+    Number(f64),
+    Cmp(CmpOp),
+    And,
+    Or,
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let invalid = |msg: String| BotError::Config(format!("alert expression `{}`: {}", expr, msg));
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                name.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Metric(name));
+        } else if c.is_ascii_digit() || c == '.' || c == '-' {
+            let mut number = String::from(c);
+            chars.next();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+            let value = number
+                .parse()
+                .map_err(|_| invalid(format!("invalid number `{}`", number)))?;
+            tokens.push(Token::Number(value));
+        } else {
+            chars.next();
+            let next_is = |chars: &mut std::iter::Peekable<std::str::Chars>, expected| {
+                chars.next_if_eq(&expected).is_some()
+            };
+            let token = match c {
+                '<' if next_is(&mut chars, '=') => Token::Cmp(CmpOp::Le),
+                '<' => Token::Cmp(CmpOp::Lt),
+                '>' if next_is(&mut chars, '=') => Token::Cmp(CmpOp::Ge),
+                '>' => Token::Cmp(CmpOp::Gt),
+                '=' if next_is(&mut chars, '=') => Token::Cmp(CmpOp::Eq),
+                '!' if next_is(&mut chars, '=') => Token::Cmp(CmpOp::Ne),
+                '&' if next_is(&mut chars, '&') => Token::And,
+                '|' if next_is(&mut chars, '|') => Token::Or,
+                other => return Err(invalid(format!("unexpected `{}`", other))),
+            };
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent over `or := and ('||' and)*`, `and := cmp ('&&' cmp)*`,
+/// `cmp := operand op operand`. Every comparison is evaluated, so errors in
+/// either branch surface regardless of values.
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+    metrics: &'a BotMetrics,
+}
+
+impl Parser<'_> {
+    fn or(&mut self) -> Result<bool> {
+        let mut holds = self.and()?;
+        while self.eat(&Token::Or) {
+            let rhs = self.and()?;
+            holds = holds || rhs;
+        }
+        Ok(holds)
+    }
+
+    fn and(&mut self) -> Result<bool> {
+        let mut holds = self.comparison()?;
+        while self.eat(&Token::And) {
+            let rhs = self.comparison()?;
+            holds = holds && rhs;
+        }
+        Ok(holds)
+    }
+
+    fn comparison(&mut self) -> Result<bool> {
+        let lhs = self.operand()?;
+        let op = match self.tokens.get(self.pos) {
+            Some(Token::Cmp(op)) => *op,
+            _ => return Err(self.error("expected a comparison operator")),
+        };
+        self.pos += 1;
+        let rhs = self.operand()?;
+
+        if lhs.is_nan() || rhs.is_nan() {
+            return Ok(false);
+        }
+        Ok(match op {
+            CmpOp::Lt => lhs < rhs,
+            CmpOp::Le => lhs <= rhs,
+            CmpOp::Gt => lhs > rhs,
+            CmpOp::Ge => lhs >= rhs,
+            CmpOp::Eq => lhs == rhs,
+            CmpOp::Ne => lhs != rhs,
+        })
+    }
+
+    fn operand(&mut self) -> Result<f64> {
+        let value = match self.tokens.get(self.pos) {
+            Some(Token::Number(value)) => *value,
+            Some(Token::Metric(name)) => self
+                .metrics
+                .get(name)
+                .ok_or_else(|| self.error(&format!("unknown metric `{}`", name)))?,
+            _ => return Err(self.error("expected a metric or number")),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.tokens.get(self.pos) == Some(token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn error(&self, msg: &str) -> BotError {
+        BotError::Config(format!("alert expression `{}`: {}", self.expr, msg))
+    }
+}
+
+/// A rule whose condition held
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredAlert {
+    pub rule: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+}
+
+/// Checks alert rules, enforcing each rule's cooldown
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    /// Rule name -> when it last fired
+    last_fired: HashMap<String, Instant>,
+    fired_total: HashMap<AlertSeverity, u64>,
+}
+
+impl AlertEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules whose expression holds and that haven't fired within their
+    /// cooldown. Rules that fail to evaluate are logged and skipped.
+    pub fn evaluate_all(&mut self, rules: &[AlertRule], metrics: &BotMetrics) -> Vec<FiredAlert> {
+        self.evaluate_all_at(rules, metrics, Instant::now())
+    }
+
+    fn evaluate_all_at(&mut self, rules: &[AlertRule], metrics: &BotMetrics, now: Instant) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+        for rule in rules {
+            let cooling_down = self
+                .last_fired
+                .get(&rule.name)
+                .is_some_and(|at| now.duration_since(*at) < Duration::from_secs(rule.cooldown_secs));
+            if cooling_down {
+                continue;
+            }
+
+            match ExpressionEvaluator::eval(&rule.expression, metrics) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("Skipping alert rule {}: {}", rule.name, e);
+                    continue;
+                }
+            }
+
+            self.last_fired.insert(rule.name.clone(), now);
+            *self.fired_total.entry(rule.severity).or_default() += 1;
+            let template = if rule.message_template.is_empty() {
+                &rule.expression
+            } else {
+                &rule.message_template
+            };
+            fired.push(FiredAlert {
+                rule: rule.name.clone(),
+                severity: rule.severity,
+                message: metrics.render(template),
+            });
+        }
+        fired
+    }
+
+    /// Alerts of `severity` fired since startup
+    pub fn alerts_fired_total(&self, severity: AlertSeverity) -> u64 {
+        self.fired_total.get(&severity).copied().unwrap_or(0)
+    }
+
+    /// Fired alert counts as a Prometheus text-format counter
+    pub fn prometheus_counters(&self) -> String {
+        let name = "polymarket_bot_alerts_fired_total";
+        let mut text = format!("# HELP {name} Alert rules fired, by severity\n# TYPE {name} counter\n");
+        for severity in [AlertSeverity::Warning, AlertSeverity::Critical] {
+            text += &format!(
+                "{name}{{severity=\"{severity}\"}} {}\n",
+                self.alerts_fired_total(severity)
+            );
+        }
+        text
+    }
+}
+
+/// The engine shared between the scan loop evaluating rules and `/metrics`
+#[async_trait::async_trait]
+impl super::MetricsSource for parking_lot::Mutex<AlertEngine> {
+    async fn prometheus_text(&self) -> String {
+        self.lock().prometheus_counters()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> BotMetrics {
+        BotMetrics {
+            daily_pnl: -60.0,
+            balance: 940.0,
+            equity: 1010.5,
+            drawdown: 0.06,
+            open_positions: 4.0,
+            consecutive_losses: 2.0,
+            rolling_sharpe: f64::NAN,
+            rolling_sortino: f64::NAN,
+        }
+    }
+
+    fn rule(name: &str, expression: &str, severity: AlertSeverity, cooldown_secs: u64) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            severity,
+            cooldown_secs,
+            message_template: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_expressions() {
+        let m = metrics();
+        let eval = |expr| ExpressionEvaluator::eval(expr, &m).unwrap();
+
+        assert!(eval("daily_pnl < -50 && open_positions > 3"));
+        assert!(!eval("daily_pnl < -50 && open_positions > 4"));
+        assert!(eval("open_positions >= 4 && balance<=940"));
+        assert!(eval("consecutive_losses == 2"));
+        assert!(!eval("consecutive_losses != 2"));
+        // && binds tighter than ||
+        assert!(eval("drawdown > 0.5 && balance > 0 || open_positions == 4"));
+        assert!(!eval("open_positions == 0 || drawdown > 0.5 && balance > 0"));
+        assert!(eval("-100 < daily_pnl"));
+
+        // Unavailable ratios never match
+        assert!(!eval("rolling_sharpe < 0.5"));
+        assert!(!eval("rolling_sharpe != 0"));
+    }
+
+    #[test]
+    fn test_invalid_expressions_are_errors() {
+        let m = metrics();
+        for expr in [
+            "",
+            "daily_pnl",
+            "daily_pnl < ",
+            "pnl < 0",
+            "daily_pnl = 0",
+            "daily_pnl < 0 &&",
+            "daily_pnl < 0 balance > 0",
+            "daily_pnl < 1.2.3",
+            "(daily_pnl < 0)",
+        ] {
+            assert!(ExpressionEvaluator::eval(expr, &m).is_err(), "{:?} should not parse", expr);
+        }
+
+        let rules = vec![rule("ok", "balance < 100", AlertSeverity::Warning, 0)];
+        assert!(ExpressionEvaluator::validate(&rules).is_ok());
+        let rules = vec![rule("typo", "balanc < 100", AlertSeverity::Warning, 0)];
+        assert!(ExpressionEvaluator::validate(&rules).is_err());
+    }
+
+    #[test]
+    fn test_cooldown_and_counters() {
+        let mut engine = AlertEngine::new();
+        let rules = vec![
+            rule("losing day", "daily_pnl < -50", AlertSeverity::Warning, 600),
+            rule("deep drawdown", "drawdown > 0.05", AlertSeverity::Critical, 0),
+            rule("quiet", "open_positions == 0", AlertSeverity::Critical, 0),
+        ];
+        let start = Instant::now();
+        let names = |fired: Vec<FiredAlert>| fired.into_iter().map(|a| a.rule).collect::<Vec<_>>();
+
+        let fired = engine.evaluate_all_at(&rules, &metrics(), start);
+        assert_eq!(names(fired), vec!["losing day", "deep drawdown"]);
+
+        let fired = engine.evaluate_all_at(&rules, &metrics(), start + Duration::from_secs(300));
+        assert_eq!(names(fired), vec!["deep drawdown"]);
+
+        let fired = engine.evaluate_all_at(&rules, &metrics(), start + Duration::from_secs(600));
+        assert_eq!(names(fired), vec!["losing day", "deep drawdown"]);
+
+        assert_eq!(engine.alerts_fired_total(AlertSeverity::Warning), 2);
+        assert_eq!(engine.alerts_fired_total(AlertSeverity::Critical), 3);
+        let counters = engine.prometheus_counters();
+        assert!(counters.contains("# TYPE polymarket_bot_alerts_fired_total counter"));
+        assert!(counters.contains("polymarket_bot_alerts_fired_total{severity=\"warning\"} 2"));
+        assert!(counters.contains("polymarket_bot_alerts_fired_total{severity=\"critical\"} 3"));
+    }
+
+    #[test]
+    fn test_message_template() {
+        let mut engine = AlertEngine::new();
+        let mut templated = rule("losing day", "daily_pnl < -50", AlertSeverity::Warning, 0);
+        templated.message_template =
+            "P&L {daily_pnl} with {open_positions} open, equity {equity}, sharpe {rolling_sharpe}".to_string();
+        let plain = rule("positions", "open_positions > 3", AlertSeverity::Warning, 0);
+
+        let fired = engine.evaluate_all(&[templated, plain], &metrics());
+        assert_eq!(fired[0].message, "P&L -60 with 4 open, equity 1010.50, sharpe n/a");
+        assert_eq!(fired[1].message, "open_positions > 3");
+    }
+}
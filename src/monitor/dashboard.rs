@@ -4,12 +4,13 @@
 //! market state, and risk metrics.
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::Json,
     routing::get,
     Router,
 };
+use crate::analysis::network::MarketCorrelationNetwork;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -22,6 +23,8 @@ pub struct DashboardState {
     pub trades: RwLock<Vec<TradeEntry>>,
     pub positions: RwLock<Vec<PositionEntry>>,
     pub alerts: RwLock<Vec<AlertEntry>>,
+    /// Latest correlation network of the held markets, if computed
+    pub correlation_network: RwLock<Option<MarketCorrelationNetwork>>,
 }
 
 /// Core metrics displayed on dashboard
@@ -147,9 +150,15 @@ impl DashboardState {
             trades: RwLock::new(Vec::new()),
             positions: RwLock::new(Vec::new()),
             alerts: RwLock::new(Vec::new()),
+            correlation_network: RwLock::new(None),
         }
     }
     
+    /// Replace the correlation network served by the API
+    pub async fn set_correlation_network(&self, network: MarketCorrelationNetwork) {
+        *self.correlation_network.write().await = Some(network);
+    }
+    
     /// Record a new trade
     pub async fn record_trade(&self, trade: TradeEntry) {
         let mut trades = self.trades.write().await;
@@ -379,6 +388,29 @@ async fn get_alerts(
     Json(alerts.clone())
 }
 
+/// Query of the correlation network endpoint
+#[derive(Debug, Deserialize)]
+struct CorrelationNetworkQuery {
+    /// Minimum |correlation| of exported links; the network's own by default
+    threshold: Option<Decimal>,
+    /// Only "json" (D3 nodes and links) is supported
+    format: Option<String>,
+}
+
+/// Get the correlation network as D3 nodes and links
+async fn get_correlation_network(
+    State(state): State<Arc<DashboardState>>,
+    Query(query): Query<CorrelationNetworkQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if query.format.as_deref().is_some_and(|f| f != "json") {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let network = state.correlation_network.read().await;
+    let network = network.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let threshold = query.threshold.unwrap_or(network.threshold);
+    Ok(Json(network.clone().with_threshold(threshold).to_d3_json()))
+}
+
 /// Health check
 async fn health_check() -> &'static str {
     "OK"
@@ -433,6 +465,7 @@ pub fn create_router(state: Arc<DashboardState>) -> Router {
         .route("/positions", get(get_positions))
         .route("/alerts", get(get_alerts))
         .route("/summary", get(get_summary))
+        .route("/api/v1/correlation-network", get(get_correlation_network))
         .with_state(state)
 }
 
@@ -706,4 +739,34 @@ mod tests {
         state.close_position("m1", "Up").await;
         assert_eq!(state.positions.read().await.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_correlation_network_endpoint() {
+        let state = Arc::new(DashboardState::new(Decimal::from(1000)));
+        let query = |threshold: Option<&str>, format: Option<&str>| {
+            Query(CorrelationNetworkQuery {
+                threshold: threshold.map(|t| t.parse().unwrap()),
+                format: format.map(String::from),
+            })
+        };
+        
+        let missing = get_correlation_network(State(state.clone()), query(None, None)).await;
+        assert_eq!(missing.unwrap_err(), StatusCode::NOT_FOUND);
+        
+        let symbols = vec!["a".to_string(), "b".to_string()];
+        let correlations = vec![
+            vec![Decimal::ONE, Decimal::new(6, 1)],
+            vec![Decimal::new(6, 1), Decimal::ONE],
+        ];
+        state.set_correlation_network(MarketCorrelationNetwork::new(symbols, correlations)).await;
+        
+        let Json(graph) = get_correlation_network(State(state.clone()), query(None, Some("json"))).await.unwrap();
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(graph["links"].as_array().unwrap().len(), 1);
+        let Json(graph) = get_correlation_network(State(state.clone()), query(Some("0.7"), None)).await.unwrap();
+        assert!(graph["links"].as_array().unwrap().is_empty());
+        
+        let csv = get_correlation_network(State(state), query(None, Some("csv"))).await;
+        assert_eq!(csv.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -0,0 +1,721 @@
+//! Dependency health checks
+//!
+//! A [`HealthChecker`] runs one [`HealthProbe`] per dependency (Gamma, CLOB
+//! auth, database, Telegram, LLM, Binance feed, disk) and reports each as
+//! Ok, Degraded or Down with the measured latency. Checks run on demand
+//! (`/health`, `doctor`) and in the background, where
+//! [`HealthTransitions`] only reports a status change once it has held for
+//! several checks, so a single slow response doesn't page anyone.
+
+use crate::client::{ClobClient, GammaClient};
+use crate::config::Config;
+use crate::error::BotError;
+use crate::model::LlmModel;
+use crate::notify::Notifier;
+use crate::storage::Database;
+use crate::utils::{http_client, HTTP_TIMEOUT};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Probes taking longer than this are reported Down
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+    Down,
+}
+
+impl HealthStatus {
+    /// Prometheus gauge value
+    pub fn level(self) -> u8 {
+        match self {
+            Self::Ok => 0,
+            Self::Degraded => 1,
+            Self::Down => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ok => "ok",
+            Self::Degraded => "degraded",
+            Self::Down => "down",
+        })
+    }
+}
+
+/// What a probe found, before latency is taken into account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeResult {
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+impl ProbeResult {
+    pub fn ok(detail: impl Into<String>) -> Self {
+        Self { status: HealthStatus::Ok, detail: detail.into() }
+    }
+
+    pub fn degraded(detail: impl Into<String>) -> Self {
+        Self { status: HealthStatus::Degraded, detail: detail.into() }
+    }
+
+    pub fn down(detail: impl Into<String>) -> Self {
+        Self { status: HealthStatus::Down, detail: detail.into() }
+    }
+}
+
+/// Health of one dependency
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentHealth {
+    pub component: String,
+    pub status: HealthStatus,
+    /// Round trip of the probe; `None` for local checks
+    pub latency: Option<Duration>,
+    pub detail: String,
+    /// What to try when the component isn't Ok
+    pub hint: Option<String>,
+}
+
+/// Checks one dependency
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    fn component(&self) -> &str;
+
+    /// Remediation hint shown when the component isn't Ok
+    fn hint(&self) -> &str;
+
+    /// Whether the probe's latency is meaningful (a network round trip)
+    fn timed(&self) -> bool {
+        true
+    }
+
+    async fn probe(&self) -> ProbeResult;
+}
+
+/// Runs every probe and keeps the latest results
+pub struct HealthChecker {
+    probes: Vec<Box<dyn HealthProbe>>,
+    slow_after: Duration,
+    latest: RwLock<Vec<ComponentHealth>>,
+}
+
+impl HealthChecker {
+    /// Timed probes slower than `slow_after` are reported Degraded
+    pub fn new(slow_after: Duration) -> Self {
+        Self {
+            probes: Vec::new(),
+            slow_after,
+            latest: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn with_probe(mut self, probe: impl HealthProbe + 'static) -> Self {
+        self.probes.push(Box::new(probe));
+        self
+    }
+
+    /// Probe every dependency concurrently, in registration order
+    pub async fn check_all(&self) -> Vec<ComponentHealth> {
+        let results = join_all(self.probes.iter().map(|probe| self.check(probe.as_ref()))).await;
+        *self.latest.write() = results.clone();
+        results
+    }
+
+    /// Results of the last check
+    pub fn latest(&self) -> Vec<ComponentHealth> {
+        self.latest.read().clone()
+    }
+
+    async fn check(&self, probe: &dyn HealthProbe) -> ComponentHealth {
+        let start = Instant::now();
+        let mut result = tokio::time::timeout(PROBE_TIMEOUT, probe.probe())
+            .await
+            .unwrap_or_else(|_| ProbeResult::down(format!("no answer within {}s", PROBE_TIMEOUT.as_secs())));
+        let latency = probe.timed().then(|| start.elapsed());
+
+        if let Some(latency) = latency.filter(|l| *l > self.slow_after) {
+            if result.status == HealthStatus::Ok {
+                result = ProbeResult::degraded(format!(
+                    "slow: {} ms (threshold {} ms)",
+                    latency.as_millis(),
+                    self.slow_after.as_millis()
+                ));
+            }
+        }
+
+        ComponentHealth {
+            component: probe.component().to_string(),
+            status: result.status,
+            latency,
+            hint: (result.status != HealthStatus::Ok).then(|| probe.hint().to_string()),
+            detail: result.detail,
+        }
+    }
+
+    /// Latest results as Prometheus text-format gauges
+    pub fn prometheus_gauges(&self) -> String {
+        let latest = self.latest.read();
+        let status = "polymarket_bot_health_status";
+        let latency = "polymarket_bot_health_latency_seconds";
+        let mut text = format!(
+            "# HELP {status} Dependency health (0 ok, 1 degraded, 2 down)\n# TYPE {status} gauge\n"
+        );
+        for c in latest.iter() {
+            text += &format!("{status}{{component=\"{}\"}} {}\n", c.component, c.status.level());
+        }
+        text += &format!("# HELP {latency} Round trip of the last health probe\n# TYPE {latency} gauge\n");
+        for c in latest.iter() {
+            if let Some(l) = c.latency {
+                text += &format!("{latency}{{component=\"{}\"}} {}\n", c.component, l.as_secs_f64());
+            }
+        }
+        text
+    }
+}
+
+#[async_trait]
+impl super::MetricsSource for HealthChecker {
+    async fn prometheus_text(&self) -> String {
+        self.prometheus_gauges()
+    }
+}
+
+/// A component's status changing between checks
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthTransition {
+    pub component: String,
+    pub from: HealthStatus,
+    pub to: HealthStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone)]
+struct Tracked {
+    reported: HealthStatus,
+    candidate: HealthStatus,
+    streak: u32,
+}
+
+/// Turns successive checks into status changes, suppressing flaps: a new
+/// status is only reported once it has been seen `confirmations` checks in
+/// a row. Components start out Ok.
+#[derive(Debug)]
+pub struct HealthTransitions {
+    confirmations: u32,
+    components: HashMap<String, Tracked>,
+}
+
+impl HealthTransitions {
+    pub fn new(confirmations: u32) -> Self {
+        Self {
+            confirmations: confirmations.max(1),
+            components: HashMap::new(),
+        }
+    }
+
+    /// Record a check, returning the confirmed status changes
+    pub fn observe(&mut self, results: &[ComponentHealth]) -> Vec<HealthTransition> {
+        let mut transitions = Vec::new();
+        for result in results {
+            let tracked = self.components.entry(result.component.clone()).or_insert(Tracked {
+                reported: HealthStatus::Ok,
+                candidate: HealthStatus::Ok,
+                streak: 0,
+            });
+
+            if result.status == tracked.reported {
+                tracked.candidate = tracked.reported;
+                tracked.streak = 0;
+                continue;
+            }
+            if result.status == tracked.candidate {
+                tracked.streak += 1;
+            } else {
+                tracked.candidate = result.status;
+                tracked.streak = 1;
+            }
+
+            if tracked.streak >= self.confirmations {
+                transitions.push(HealthTransition {
+                    component: result.component.clone(),
+                    from: tracked.reported,
+                    to: result.status,
+                    detail: result.detail.clone(),
+                });
+                tracked.reported = result.status;
+                tracked.streak = 0;
+            }
+        }
+        transitions
+    }
+}
+
+/// Time of the last successful price feed update, shared with the feed probe
+#[derive(Debug, Default)]
+pub struct FeedHeartbeat {
+    /// Unix milliseconds; 0 before the first update
+    last_update_ms: AtomicI64,
+}
+
+impl FeedHeartbeat {
+    pub fn beat(&self) {
+        self.last_update_ms
+            .store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    /// Time since the last update, `None` before the first
+    pub fn age(&self) -> Option<Duration> {
+        let last = self.last_update_ms.load(Ordering::Relaxed);
+        (last > 0).then(|| {
+            let elapsed = chrono::Utc::now().timestamp_millis() - last;
+            Duration::from_millis(elapsed.max(0) as u64)
+        })
+    }
+}
+
+/// Gamma market data API
+pub struct GammaProbe(pub GammaClient);
+
+#[async_trait]
+impl HealthProbe for GammaProbe {
+    fn component(&self) -> &str {
+        "gamma"
+    }
+
+    fn hint(&self) -> &str {
+        "Check network access to polymarket.gamma_url; Gamma outages are usually short"
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        match self.0.get_top_markets(1).await {
+            Ok(markets) if markets.is_empty() => ProbeResult::degraded("no active markets returned"),
+            Ok(_) => ProbeResult::ok("markets listed"),
+            Err(e) => ProbeResult::down(e.to_string()),
+        }
+    }
+}
+
+/// CLOB API credentials, checked with an authenticated balance request
+pub struct ClobAuthProbe(pub ClobClient);
+
+#[async_trait]
+impl HealthProbe for ClobAuthProbe {
+    fn component(&self) -> &str {
+        "clob_auth"
+    }
+
+    fn hint(&self) -> &str {
+        "Check polymarket.private_key, funder_address and signature_type; restart to re-derive API keys"
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        match self.0.get_balance().await {
+            Ok(balance) => ProbeResult::ok(format!("authenticated, balance ${:.2}", balance)),
+            Err(e) => ProbeResult::down(e.to_string()),
+        }
+    }
+}
+
+/// SQLite write/read round trip
+pub struct DatabaseProbe(pub Arc<Database>);
+
+#[async_trait]
+impl HealthProbe for DatabaseProbe {
+    fn component(&self) -> &str {
+        "database"
+    }
+
+    fn hint(&self) -> &str {
+        "Check database.path is writable and not locked by another process"
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        match self.0.health_round_trip().await {
+            Ok(()) => ProbeResult::ok("write/read round trip"),
+            Err(e) => ProbeResult::down(e.to_string()),
+        }
+    }
+}
+
+/// Telegram Bot API and bot token
+pub struct TelegramProbe(pub Notifier);
+
+#[async_trait]
+impl HealthProbe for TelegramProbe {
+    fn component(&self) -> &str {
+        "telegram"
+    }
+
+    fn hint(&self) -> &str {
+        "Check telegram.bot_token with @BotFather and network access to api.telegram.org"
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        match self.0.ping().await {
+            Ok(()) => ProbeResult::ok("bot token accepted"),
+            Err(e) => ProbeResult::down(e.to_string()),
+        }
+    }
+}
+
+/// Reachability of an HTTP endpoint; any response counts, server errors
+/// are Degraded
+pub struct HttpProbe {
+    component: String,
+    url: String,
+    hint: String,
+    http: reqwest::Client,
+}
+
+impl HttpProbe {
+    pub fn new(component: &str, url: &str, hint: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            url: url.to_string(),
+            hint: hint.to_string(),
+            http: http_client(HTTP_TIMEOUT),
+        }
+    }
+
+    /// The configured LLM provider's API
+    pub fn llm(endpoint: &str) -> Self {
+        Self::new(
+            "llm",
+            endpoint,
+            "Check llm.provider, llm.base_url and the provider's status page; signals fall back to other models",
+        )
+    }
+}
+
+#[async_trait]
+impl HealthProbe for HttpProbe {
+    fn component(&self) -> &str {
+        &self.component
+    }
+
+    fn hint(&self) -> &str {
+        &self.hint
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        match self.http.get(&self.url).send().await {
+            Ok(resp) if resp.status().is_server_error() => {
+                ProbeResult::degraded(format!("{} returned {}", self.url, resp.status()))
+            }
+            Ok(resp) => ProbeResult::ok(format!("{} returned {}", self.url, resp.status())),
+            Err(e) => ProbeResult::down(e.to_string()),
+        }
+    }
+}
+
+/// Binance REST API, and the age of the last price update when the bot is
+/// running
+pub struct BinanceFeedProbe {
+    http: reqwest::Client,
+    heartbeat: Option<Arc<FeedHeartbeat>>,
+    stale_after: Duration,
+}
+
+impl BinanceFeedProbe {
+    pub fn new(heartbeat: Option<Arc<FeedHeartbeat>>, stale_after: Duration) -> Self {
+        Self {
+            http: http_client(HTTP_TIMEOUT),
+            heartbeat,
+            stale_after,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for BinanceFeedProbe {
+    fn component(&self) -> &str {
+        "binance"
+    }
+
+    fn hint(&self) -> &str {
+        "Check network access to api.binance.com (blocked in some regions); crypto_hf signals pause while prices are stale"
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        let reachable = self
+            .http
+            .get("https://api.binance.com/api/v3/ping")
+            .send()
+            .await
+            .map_err(BotError::from)
+            .and_then(|resp| match resp.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(BotError::Api(format!("ping returned {}", status))),
+            });
+        if let Err(e) = reachable {
+            return ProbeResult::down(e.to_string());
+        }
+
+        let Some(heartbeat) = &self.heartbeat else {
+            return ProbeResult::ok("reachable");
+        };
+        match heartbeat.age() {
+            None => ProbeResult::degraded("reachable, no price update yet"),
+            Some(age) if age > self.stale_after => {
+                ProbeResult::degraded(format!("prices stale: last update {}s ago", age.as_secs()))
+            }
+            Some(age) => ProbeResult::ok(format!("last price update {}s ago", age.as_secs())),
+        }
+    }
+}
+
+/// Free space on the filesystems holding the bot's files
+pub struct DiskProbe {
+    paths: Vec<PathBuf>,
+    min_free_mb: u64,
+}
+
+impl DiskProbe {
+    pub fn new(paths: Vec<PathBuf>, min_free_mb: u64) -> Self {
+        Self { paths, min_free_mb }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for DiskProbe {
+    fn component(&self) -> &str {
+        "disk"
+    }
+
+    fn hint(&self) -> &str {
+        "Free up space or prune old logs; SQLite writes fail on a full disk"
+    }
+
+    fn timed(&self) -> bool {
+        false
+    }
+
+    async fn probe(&self) -> ProbeResult {
+        let mut worst: Option<(u64, &PathBuf)> = None;
+        for path in &self.paths {
+            match free_disk_mb(path).await {
+                Ok(free) => {
+                    if worst.is_none_or(|(least, _)| free < least) {
+                        worst = Some((free, path));
+                    }
+                }
+                Err(e) => return ProbeResult::degraded(format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        let Some((free, path)) = worst else {
+            return ProbeResult::ok("no paths to check");
+        };
+        let detail = format!("{} MB free at {}", free, path.display());
+        if free < self.min_free_mb / 10 {
+            ProbeResult::down(detail)
+        } else if free < self.min_free_mb {
+            ProbeResult::degraded(detail)
+        } else {
+            ProbeResult::ok(detail)
+        }
+    }
+}
+
+/// Free space of the filesystem holding `path`, via POSIX `df`
+async fn free_disk_mb(path: &std::path::Path) -> std::result::Result<u64, String> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| format!("df failed: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    // Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+        .ok_or_else(|| format!("unexpected df output: {}", stdout.trim()))
+}
+
+/// Directory a file lives in, for disk checks
+pub fn parent_dir(file: &str) -> PathBuf {
+    match std::path::Path::new(file).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+impl HealthChecker {
+    /// Probes for every configured dependency. `clob` is left out when not
+    /// authenticated (dry runs); `heartbeat` is the running bot's price feed.
+    pub fn from_config(
+        config: &Config,
+        clob: Option<ClobClient>,
+        gamma: GammaClient,
+        db: Arc<Database>,
+        notifier: &Notifier,
+        heartbeat: Option<Arc<FeedHeartbeat>>,
+    ) -> Self {
+        let health = config.health.clone().unwrap_or_default();
+        let mut checker = Self::new(Duration::from_millis(health.slow_ms))
+            .with_probe(GammaProbe(gamma));
+        if let Some(clob) = clob {
+            checker = checker.with_probe(ClobAuthProbe(clob));
+        }
+        checker = checker.with_probe(DatabaseProbe(db));
+        if notifier.is_enabled() {
+            checker = checker.with_probe(TelegramProbe(notifier.clone()));
+        }
+        if let Some(llm) = config.llm.as_ref().and_then(|c| LlmModel::from_config(c).ok()) {
+            checker = checker.with_probe(HttpProbe::llm(llm.endpoint()));
+        }
+
+        let mut disk_paths = vec![parent_dir(&config.database.path)];
+        if let Some(sim) = &config.simulation {
+            disk_paths.push(parent_dir(&sim.db_path));
+        }
+        disk_paths.dedup();
+
+        checker
+            .with_probe(BinanceFeedProbe::new(heartbeat, Duration::from_secs(health.feed_stale_secs)))
+            .with_probe(DiskProbe::new(disk_paths, health.min_free_disk_mb))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeProbe {
+        name: &'static str,
+        result: ProbeResult,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl HealthProbe for FakeProbe {
+        fn component(&self) -> &str {
+            self.name
+        }
+
+        fn hint(&self) -> &str {
+            "try turning it off and on again"
+        }
+
+        async fn probe(&self) -> ProbeResult {
+            tokio::time::sleep(self.delay).await;
+            self.result.clone()
+        }
+    }
+
+    fn fake(name: &'static str, result: ProbeResult, delay_ms: u64) -> FakeProbe {
+        FakeProbe { name, result, delay: Duration::from_millis(delay_ms) }
+    }
+
+    fn health(component: &str, status: HealthStatus) -> ComponentHealth {
+        ComponentHealth {
+            component: component.to_string(),
+            status,
+            latency: None,
+            detail: String::new(),
+            hint: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_all_grades_each_component() {
+        let checker = HealthChecker::new(Duration::from_millis(50))
+            .with_probe(fake("gamma", ProbeResult::ok("fine"), 0))
+            .with_probe(fake("llm", ProbeResult::ok("fine"), 120))
+            .with_probe(fake("database", ProbeResult::down("locked"), 0));
+
+        let results = checker.check_all().await;
+        let statuses: Vec<_> = results.iter().map(|r| (r.component.as_str(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("gamma", HealthStatus::Ok),
+                ("llm", HealthStatus::Degraded),
+                ("database", HealthStatus::Down),
+            ]
+        );
+        assert!(results[1].detail.starts_with("slow: "));
+        assert!(results[1].latency.unwrap() >= Duration::from_millis(120));
+        assert_eq!(results[0].hint, None);
+        assert_eq!(results[2].hint.as_deref(), Some("try turning it off and on again"));
+        assert_eq!(checker.latest(), results);
+
+        let gauges = checker.prometheus_gauges();
+        assert!(gauges.contains("# TYPE polymarket_bot_health_status gauge"));
+        assert!(gauges.contains("polymarket_bot_health_status{component=\"gamma\"} 0"));
+        assert!(gauges.contains("polymarket_bot_health_status{component=\"llm\"} 1"));
+        assert!(gauges.contains("polymarket_bot_health_status{component=\"database\"} 2"));
+        assert!(gauges.contains("polymarket_bot_health_latency_seconds{component=\"llm\"} 0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_disk_probe_reports_free_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = DiskProbe::new(vec![dir.path().to_path_buf()], 0).probe().await;
+        assert_eq!(ok.status, HealthStatus::Ok, "{}", ok.detail);
+        assert!(ok.detail.contains("MB free at"));
+
+        let full = DiskProbe::new(vec![dir.path().to_path_buf()], u64::MAX).probe().await;
+        assert_eq!(full.status, HealthStatus::Down);
+
+        let missing = DiskProbe::new(vec![dir.path().join("missing")], 0).probe().await;
+        assert_eq!(missing.status, HealthStatus::Degraded);
+    }
+
+    #[test]
+    fn test_transitions_suppress_flaps() {
+        let mut transitions = HealthTransitions::new(2);
+        let observe = |t: &mut HealthTransitions, status| t.observe(&[health("gamma", status)]);
+
+        // A single blip is not reported
+        assert!(observe(&mut transitions, HealthStatus::Down).is_empty());
+        assert!(observe(&mut transitions, HealthStatus::Ok).is_empty());
+
+        // A status that holds for two checks is
+        assert!(observe(&mut transitions, HealthStatus::Degraded).is_empty());
+        let changed = observe(&mut transitions, HealthStatus::Degraded);
+        assert_eq!(changed.len(), 1);
+        assert_eq!((changed[0].from, changed[0].to), (HealthStatus::Ok, HealthStatus::Degraded));
+        assert!(observe(&mut transitions, HealthStatus::Degraded).is_empty());
+
+        // Alternating statuses restart the count
+        assert!(observe(&mut transitions, HealthStatus::Down).is_empty());
+        assert!(observe(&mut transitions, HealthStatus::Ok).is_empty());
+        assert!(observe(&mut transitions, HealthStatus::Down).is_empty());
+        let changed = observe(&mut transitions, HealthStatus::Down);
+        assert_eq!((changed[0].from, changed[0].to), (HealthStatus::Degraded, HealthStatus::Down));
+    }
+
+    #[test]
+    fn test_feed_heartbeat_age() {
+        let heartbeat = FeedHeartbeat::default();
+        assert_eq!(heartbeat.age(), None);
+        heartbeat.beat();
+        assert!(heartbeat.age().unwrap() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_parent_dir() {
+        assert_eq!(parent_dir("data/bot.db"), PathBuf::from("data"));
+        assert_eq!(parent_dir("bot.db"), PathBuf::from("."));
+    }
+}
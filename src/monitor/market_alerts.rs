@@ -0,0 +1,371 @@
+//! Per-market alerts set from Telegram
+//!
+//! Unlike the configured [`AlertRule`](crate::config::AlertRule)s over bot
+//! metrics, these watch one market each and are managed at runtime with
+//! `/alert add <market_id> <metric> <comparator> <value> [cooldown]`, e.g.
+//! `/alert add 517310 price > 0.70 30m`. Rules are stored in the database and
+//! checked every scan against a [`MarketSnapshot`]; a rule that holds fires at
+//! most once per cooldown.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Cooldown of rules added without one
+pub const DEFAULT_MARKET_ALERT_COOLDOWN_SECS: u64 = 3600;
+
+/// What a market alert watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// YES price
+    Price,
+    /// Best ask minus best bid of the YES token
+    Spread,
+    /// Volume traded since the previous scan (USDC)
+    VolumeDelta,
+    /// Unrealized P&L of our position in the market (USDC)
+    Pnl,
+    /// Hours until the market's end date
+    HoursLeft,
+}
+
+impl AlertMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMetric::Price => "price",
+            AlertMetric::Spread => "spread",
+            AlertMetric::VolumeDelta => "volume_delta",
+            AlertMetric::Pnl => "pnl",
+            AlertMetric::HoursLeft => "hours_left",
+        }
+    }
+}
+
+impl FromStr for AlertMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "price" => Ok(AlertMetric::Price),
+            "spread" => Ok(AlertMetric::Spread),
+            "volume_delta" | "volume" => Ok(AlertMetric::VolumeDelta),
+            "pnl" => Ok(AlertMetric::Pnl),
+            "hours_left" | "ttr" => Ok(AlertMetric::HoursLeft),
+            _ => Err(format!(
+                "unknown metric `{}` (expected price, spread, volume_delta, pnl or hours_left)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparator {
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+}
+
+impl Comparator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+        }
+    }
+
+    fn holds(&self, lhs: Decimal, rhs: Decimal) -> bool {
+        match self {
+            Comparator::Lt => lhs < rhs,
+            Comparator::Le => lhs <= rhs,
+            Comparator::Gt => lhs > rhs,
+            Comparator::Ge => lhs >= rhs,
+        }
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "<" => Ok(Comparator::Lt),
+            "<=" => Ok(Comparator::Le),
+            ">" => Ok(Comparator::Gt),
+            ">=" => Ok(Comparator::Ge),
+            _ => Err(format!("unknown comparator `{}` (expected <, <=, > or >=)", s)),
+        }
+    }
+}
+
+/// `metric comparator threshold` on one market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketAlertRule {
+    pub market_id: String,
+    pub metric: AlertMetric,
+    pub comparator: Comparator,
+    pub threshold: Decimal,
+    /// Minimum time between two notifications of this rule
+    pub cooldown_secs: u64,
+}
+
+impl MarketAlertRule {
+    /// The rule's condition, e.g. `price > 0.70`
+    pub fn condition(&self) -> String {
+        format!("{} {} {}", self.metric.as_str(), self.comparator.as_str(), self.threshold)
+    }
+}
+
+/// `<market_id> <metric> <comparator> <value> [cooldown]`. Values may end in
+/// `%` or `c` (both hundredths, so `70%` and `5c` are 0.70 and 0.05);
+/// cooldowns are a number of `s`, `m`, `h` or `d`.
+impl FromStr for MarketAlertRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let [market_id, metric, comparator, value, rest @ ..] = parts.as_slice() else {
+            return Err("expected <market_id> <metric> <comparator> <value> [cooldown]".to_string());
+        };
+        let cooldown_secs = match rest {
+            [] => DEFAULT_MARKET_ALERT_COOLDOWN_SECS,
+            [cooldown] => parse_cooldown(cooldown)?,
+            _ => return Err(format!("unexpected `{}` after the cooldown", rest[1..].join(" "))),
+        };
+
+        Ok(Self {
+            market_id: market_id.to_string(),
+            metric: metric.parse()?,
+            comparator: comparator.parse()?,
+            threshold: parse_threshold(value)?,
+            cooldown_secs,
+        })
+    }
+}
+
+fn parse_threshold(s: &str) -> Result<Decimal, String> {
+    let invalid = || format!("invalid value `{}`", s);
+    let (number, scale) = match s.strip_suffix('%').or_else(|| s.strip_suffix('c')).or_else(|| s.strip_suffix('¢')) {
+        Some(number) => (number, Decimal::ONE_HUNDRED),
+        None => (s, Decimal::ONE),
+    };
+    let value = Decimal::from_str(number).map_err(|_| invalid())?;
+    Ok(value / scale)
+}
+
+fn parse_cooldown(s: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid cooldown `{}` (expected e.g. 90s, 30m, 2h, 1d)", s);
+    let unit = s.chars().last().ok_or_else(invalid)?;
+    let amount: u64 = s[..s.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        _ => return Err(invalid()),
+    };
+    amount.checked_mul(unit_secs).ok_or_else(invalid)
+}
+
+/// A stored rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketAlert {
+    pub id: i64,
+    pub rule: MarketAlertRule,
+    pub last_fired: Option<DateTime<Utc>>,
+}
+
+impl MarketAlert {
+    fn cooling_down(&self, now: DateTime<Utc>) -> bool {
+        let cooldown = Duration::seconds(self.rule.cooldown_secs.min(i64::MAX as u64) as i64);
+        self.last_fired.is_some_and(|at| now - at < cooldown)
+    }
+}
+
+/// Current values of a market's alert metrics; `None` where unknown (no
+/// position, no end date, first sighting for the volume delta)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketSnapshot {
+    pub question: String,
+    pub yes_price: Option<Decimal>,
+    pub spread: Option<Decimal>,
+    pub volume_delta: Option<Decimal>,
+    pub unrealized_pnl: Option<Decimal>,
+    pub hours_left: Option<Decimal>,
+}
+
+impl MarketSnapshot {
+    pub fn get(&self, metric: AlertMetric) -> Option<Decimal> {
+        match metric {
+            AlertMetric::Price => self.yes_price,
+            AlertMetric::Spread => self.spread,
+            AlertMetric::VolumeDelta => self.volume_delta,
+            AlertMetric::Pnl => self.unrealized_pnl,
+            AlertMetric::HoursLeft => self.hours_left,
+        }
+    }
+}
+
+/// A rule whose condition held, with the value that triggered it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredMarketAlert {
+    pub alert: MarketAlert,
+    pub question: String,
+    pub value: Decimal,
+}
+
+/// Alerts whose condition holds on their market's snapshot and that are out
+/// of their cooldown. Markets without a snapshot and unknown metric values
+/// never fire.
+pub fn evaluate_market_alerts(
+    alerts: &[MarketAlert],
+    snapshots: &HashMap<String, MarketSnapshot>,
+    now: DateTime<Utc>,
+) -> Vec<FiredMarketAlert> {
+    alerts
+        .iter()
+        .filter(|alert| !alert.cooling_down(now))
+        .filter_map(|alert| {
+            let snapshot = snapshots.get(&alert.rule.market_id)?;
+            let value = snapshot.get(alert.rule.metric)?;
+            alert.rule.comparator.holds(value, alert.rule.threshold).then(|| FiredMarketAlert {
+                alert: alert.clone(),
+                question: snapshot.question.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn alert(id: i64, rule: &str) -> MarketAlert {
+        MarketAlert {
+            id,
+            rule: rule.parse().unwrap(),
+            last_fired: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rule: MarketAlertRule = "517310 price > 0.70".parse().unwrap();
+        assert_eq!(
+            rule,
+            MarketAlertRule {
+                market_id: "517310".to_string(),
+                metric: AlertMetric::Price,
+                comparator: Comparator::Gt,
+                threshold: dec!(0.70),
+                cooldown_secs: DEFAULT_MARKET_ALERT_COOLDOWN_SECS,
+            }
+        );
+        assert_eq!(rule.condition(), "price > 0.70");
+
+        let rule: MarketAlertRule = "btc-hourly SPREAD >= 5c 15m".parse().unwrap();
+        assert_eq!(rule.metric, AlertMetric::Spread);
+        assert_eq!(rule.comparator, Comparator::Ge);
+        assert_eq!(rule.threshold, dec!(0.05));
+        assert_eq!(rule.cooldown_secs, 900);
+
+        let rule: MarketAlertRule = "m1 price < 30% 1d".parse().unwrap();
+        assert_eq!((rule.threshold, rule.cooldown_secs), (dec!(0.30), 86400));
+        let rule: MarketAlertRule = "m1 pnl <= -25 90s".parse().unwrap();
+        assert_eq!((rule.metric, rule.threshold, rule.cooldown_secs), (AlertMetric::Pnl, dec!(-25), 90));
+        let rule: MarketAlertRule = "m1 hours_left < 6".parse().unwrap();
+        assert_eq!(rule.metric, AlertMetric::HoursLeft);
+        let rule: MarketAlertRule = "m1 volume_delta > 10000 0m".parse().unwrap();
+        assert_eq!((rule.metric, rule.cooldown_secs), (AlertMetric::VolumeDelta, 0));
+    }
+
+    #[test]
+    fn test_malformed_rules_are_rejected() {
+        for input in [
+            "",
+            "517310",
+            "517310 price >",
+            "517310 price > 0.70 30m extra",
+            "517310 odds > 0.70",
+            "517310 price = 0.70",
+            "517310 price => 0.70",
+            "517310 price > seventy",
+            "517310 price > 0.7.0",
+            "517310 price > %",
+            "517310 price > 0.70 30",
+            "517310 price > 0.70 30w",
+            "517310 price > 0.70 -5m",
+            "517310 price > 0.70 m",
+            "517310 price > 0.70 99999999999999999999d",
+        ] {
+            assert!(input.parse::<MarketAlertRule>().is_err(), "{:?} should not parse", input);
+        }
+
+        let err = "517310 odds > 0.70".parse::<MarketAlertRule>().unwrap_err();
+        assert!(err.contains("unknown metric `odds`"));
+        let err = "517310 price > 0.70 30w".parse::<MarketAlertRule>().unwrap_err();
+        assert!(err.contains("invalid cooldown `30w`"));
+    }
+
+    #[test]
+    fn test_rules_fire_on_their_market_when_condition_holds() {
+        let alerts = vec![
+            alert(1, "m1 price > 0.70"),
+            alert(2, "m1 spread >= 5c"),
+            alert(3, "m2 price > 0.70"),
+            alert(4, "m1 pnl < -10"),
+            alert(5, "m3 price > 0.10"),
+        ];
+        let snapshots = HashMap::from([
+            (
+                "m1".to_string(),
+                MarketSnapshot {
+                    question: "Will it?".to_string(),
+                    yes_price: Some(dec!(0.72)),
+                    spread: Some(dec!(0.05)),
+                    ..MarketSnapshot::default()
+                },
+            ),
+            (
+                "m2".to_string(),
+                MarketSnapshot { yes_price: Some(dec!(0.70)), ..MarketSnapshot::default() },
+            ),
+        ]);
+
+        let fired = evaluate_market_alerts(&alerts, &snapshots, Utc::now());
+        let ids: Vec<i64> = fired.iter().map(|f| f.alert.id).collect();
+        // m2 sits at the threshold, m1 has no position, m3 wasn't scanned
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(fired[0].value, dec!(0.72));
+        assert_eq!(fired[0].question, "Will it?");
+    }
+
+    #[test]
+    fn test_cooldown_holds_back_rules() {
+        let now = Utc::now();
+        let snapshots = HashMap::from([(
+            "m1".to_string(),
+            MarketSnapshot { yes_price: Some(dec!(0.80)), ..MarketSnapshot::default() },
+        )]);
+        let mut fired_recently = alert(1, "m1 price > 0.70 30m");
+        fired_recently.last_fired = Some(now - Duration::minutes(29));
+        let mut fired_long_ago = alert(2, "m1 price > 0.70 30m");
+        fired_long_ago.last_fired = Some(now - Duration::minutes(30));
+
+        let fired = evaluate_market_alerts(&[fired_recently, fired_long_ago], &snapshots, now);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].alert.id, 2);
+    }
+}
@@ -0,0 +1,102 @@
+//! Prometheus metrics endpoint
+//!
+//! Components render their own counters and gauges in Prometheus text
+//! format; [`MetricsRegistry`] collects the registered ones and serves them
+//! together at `GET /metrics` for scraping.
+
+use crate::error::{BotError, Result};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// A component whose metrics are served at `/metrics`
+#[async_trait]
+pub trait MetricsSource: Send + Sync {
+    /// Current metrics in Prometheus text format
+    async fn prometheus_text(&self) -> String;
+}
+
+/// Metrics sources served together; sources can be added while serving
+#[derive(Default)]
+pub struct MetricsRegistry {
+    sources: RwLock<Vec<Arc<dyn MetricsSource>>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, source: Arc<dyn MetricsSource>) {
+        self.sources.write().push(source);
+    }
+
+    /// Every source's metrics, in registration order
+    pub async fn render(&self) -> String {
+        let sources = self.sources.read().clone();
+        let mut text = String::new();
+        for source in sources {
+            text += &source.prometheus_text().await;
+        }
+        text
+    }
+
+    /// `GET /metrics`
+    pub fn router(self: Arc<Self>) -> axum::Router {
+        use axum::{extract::State, http::header, routing::get};
+
+        let handler = |State(registry): State<Arc<Self>>| async move {
+            ([(header::CONTENT_TYPE, CONTENT_TYPE)], registry.render().await)
+        };
+        axum::Router::new().route("/metrics", get(handler)).with_state(self)
+    }
+
+    /// Serve `/metrics` on `listen_port` in the background
+    pub async fn serve(self: Arc<Self>, listen_port: u16) -> Result<()> {
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], listen_port));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| BotError::Internal(format!("Failed to bind metrics port {}: {}", listen_port, e)))?;
+        let app = self.router();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("Metrics server stopped: {}", e);
+            }
+        });
+        tracing::info!("Serving metrics on http://{}/metrics", addr);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(&'static str);
+
+    #[async_trait]
+    impl MetricsSource for Fixed {
+        async fn prometheus_text(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_every_source() {
+        let registry = Arc::new(MetricsRegistry::new());
+        registry.register(Arc::new(Fixed("# TYPE a counter\na 1\n")));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = registry.clone().router();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // Registered after the server started
+        registry.register(Arc::new(Fixed("# TYPE b gauge\nb 2\n")));
+        let response = reqwest::get(format!("http://{}/metrics", addr)).await.unwrap();
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+        assert_eq!(response.text().await.unwrap(), "# TYPE a counter\na 1\n# TYPE b gauge\nb 2\n");
+    }
+}
@@ -1,7 +1,13 @@
 //! Monitoring and alerting
 
+pub mod alerts;
 pub mod dashboard;
+pub mod health;
+pub mod market_alerts;
 pub mod market_state;
+pub mod metrics;
+pub mod performance;
+pub mod watchdog;
 
 pub use dashboard::{
     DashboardState, DashboardMetrics, TradeEntry, PositionEntry, AlertEntry,
@@ -12,18 +18,48 @@ pub use market_state::{
     MarketStateMonitor, MarketStateConfig, MarketState, VolatilityRegime,
     TradingRecommendation, Alert, AlertType, AlertSeverity, Anomaly, AnomalyType
 };
+pub use alerts::{AlertEngine, BotMetrics, ExpressionEvaluator, FiredAlert};
+pub use market_alerts::{
+    evaluate_market_alerts, AlertMetric, Comparator, FiredMarketAlert, MarketAlert, MarketAlertRule, MarketSnapshot,
+};
+pub use health::{ComponentHealth, FeedHeartbeat, HealthChecker, HealthStatus, HealthTransition, HealthTransitions};
+pub use metrics::{MetricsRegistry, MetricsSource};
+pub use performance::RollingPerformance;
+pub use watchdog::{Heartbeat, Stall, StallAction, Watchdog};
 
 #[cfg(test)]
 mod tests;
 
+use crate::client::CacheStats;
+use crate::portfolio::HeatMetrics;
 use rust_decimal::Decimal;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use tokio::sync::RwLock;
 
 /// Performance monitor
 pub struct Monitor {
     trades: RwLock<VecDeque<TradeRecord>>,
     max_history: usize,
+    performance: RwLock<RollingPerformance>,
+    /// Latest total portfolio value in USDC
+    portfolio_value: RwLock<Option<Decimal>>,
+    /// Large orders abandoned after their liquidity probe, and the slippage
+    /// those probes paid (USDC), summed over executors
+    probe_stats: RwLock<(u64, Decimal)>,
+    /// Fast stops executed, and the tick-to-fill latency of the last one (ms)
+    fast_stops: RwLock<(u64, Option<Decimal>)>,
+    /// Strategy the regime selector made active
+    active_strategy: RwLock<Option<String>>,
+    /// Regime-driven strategy switches by (from, to)
+    strategy_switches: RwLock<BTreeMap<(String, String), u64>>,
+    /// Latest portfolio heat (USDC) and its share of the heat budget
+    portfolio_heat: RwLock<Option<(Decimal, Decimal)>>,
+    /// Signals that expired unfilled, summed over executors
+    expired_signals: RwLock<u64>,
+    /// Latest Gamma market cache counters
+    cache_stats: RwLock<CacheStats>,
+    /// Notifications waiting in the outbox and failed deliveries
+    notification_outbox: RwLock<(u64, u64)>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,7 +88,165 @@ impl Monitor {
         Self {
             trades: RwLock::new(VecDeque::with_capacity(max_history)),
             max_history,
+            performance: RwLock::new(RollingPerformance::default()),
+            portfolio_value: RwLock::new(None),
+            probe_stats: RwLock::new((0, Decimal::ZERO)),
+            fast_stops: RwLock::new((0, None)),
+            active_strategy: RwLock::new(None),
+            strategy_switches: RwLock::new(BTreeMap::new()),
+            portfolio_heat: RwLock::new(None),
+            expired_signals: RwLock::new(0),
+            cache_stats: RwLock::new(CacheStats::default()),
+            notification_outbox: RwLock::new((0, 0)),
+        }
+    }
+
+    /// Record a scan cycle's mark-to-market return; the ratios see it as
+    /// part of its UTC day's return
+    pub async fn record_scan_return(&self, at: chrono::DateTime<chrono::Utc>, scan_return: Decimal) {
+        self.performance.write().await.record_scan(at.date_naive(), scan_return);
+    }
+
+    /// Record this scan cycle's total portfolio value
+    pub async fn record_portfolio_value(&self, value: Decimal) {
+        *self.portfolio_value.write().await = Some(value);
+    }
+
+    /// Record the executors' running two-phase probe totals
+    pub async fn record_probe_stats(&self, cancellations: u64, slippage_cost: Decimal) {
+        *self.probe_stats.write().await = (cancellations, slippage_cost);
+    }
+
+    /// Record an executed fast stop and how long it took from the triggering
+    /// tick to the fill
+    pub async fn record_fast_stop(&self, latency: std::time::Duration) {
+        let mut fast_stops = self.fast_stops.write().await;
+        fast_stops.0 += 1;
+        fast_stops.1 = Some(Decimal::from(latency.as_millis() as u64));
+    }
+
+    /// Record that the regime selector switched from `from` to `to`
+    pub async fn record_strategy_switch(&self, from: &str, to: &str) {
+        *self.active_strategy.write().await = Some(to.to_string());
+        *self
+            .strategy_switches
+            .write()
+            .await
+            .entry((from.to_string(), to.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Record the executors' running total of signals that expired unfilled
+    pub async fn record_expired_signals(&self, total: u64) {
+        *self.expired_signals.write().await = total;
+    }
+
+    /// Record the Gamma client's cache counters
+    pub async fn record_cache_stats(&self, stats: CacheStats) {
+        *self.cache_stats.write().await = stats;
+    }
+
+    /// Record the notifier's outbox depth and running delivery failures
+    pub async fn record_notification_outbox(&self, depth: u64, delivery_failures: u64) {
+        *self.notification_outbox.write().await = (depth, delivery_failures);
+    }
+
+    /// Record this scan cycle's portfolio heat
+    pub async fn record_portfolio_heat(&self, heat: &HeatMetrics) {
+        *self.portfolio_heat.write().await = Some((heat.total_heat_usdc, heat.heat_utilization));
+    }
+
+    /// Strategy the regime selector made active, if it has switched yet
+    pub async fn active_strategy(&self) -> Option<String> {
+        self.active_strategy.read().await.clone()
+    }
+
+    /// Snapshot of the rolling Sharpe/Sortino window
+    pub async fn rolling_performance(&self) -> RollingPerformance {
+        self.performance.read().await.clone()
+    }
+
+    /// Rolling ratios, portfolio value and heat, probe, fast stop, expired
+    /// signal, strategy switch and notification delivery failure totals,
+    /// the notification queue depth and the market cache hit rate in
+    /// Prometheus text format; absent values are NaN
+    pub async fn prometheus_gauges(&self) -> String {
+        let performance = self.performance.read().await;
+        let (probe_cancellations, probe_cost) = *self.probe_stats.read().await;
+        let (fast_stops, fast_stop_latency) = *self.fast_stops.read().await;
+        let heat = *self.portfolio_heat.read().await;
+        let expired_signals = *self.expired_signals.read().await;
+        let (queued_notifications, delivery_failures) = *self.notification_outbox.read().await;
+        let mut strategy_switches = "# HELP polymarket_bot_strategy_switches_total Regime-driven switches of the active strategy\n\
+             # TYPE polymarket_bot_strategy_switches_total counter\n"
+            .to_string();
+        for ((from, to), count) in self.strategy_switches.read().await.iter() {
+            strategy_switches.push_str(&format!(
+                "polymarket_bot_strategy_switches_total{{from=\"{from}\",to=\"{to}\"}} {count}\n"
+            ));
         }
+        let gauge = |name: &str, help: &str, value: Option<Decimal>| {
+            let value = value.map(|v| v.to_string()).unwrap_or_else(|| "NaN".to_string());
+            format!("# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n")
+        };
+        gauge("polymarket_bot_rolling_sharpe_ratio", "Annualized rolling Sharpe ratio", performance.sharpe())
+            + &gauge("polymarket_bot_rolling_sortino_ratio", "Annualized rolling Sortino ratio", performance.sortino())
+            + &gauge(
+                "polymarket_bot_portfolio_value_usdc",
+                "Cash plus open positions at market",
+                *self.portfolio_value.read().await,
+            )
+            + &gauge(
+                "polymarket_bot_portfolio_heat_usdc",
+                "Edge-weighted size of the open positions",
+                heat.map(|(usdc, _)| usdc),
+            )
+            + &gauge(
+                "polymarket_bot_heat_utilization",
+                "Portfolio heat as a fraction of the heat budget",
+                heat.map(|(_, utilization)| utilization),
+            )
+            + &format!(
+                "# HELP polymarket_bot_probe_cancellations_total Large orders abandoned after their probe slipped too far\n\
+                 # TYPE polymarket_bot_probe_cancellations_total counter\n\
+                 polymarket_bot_probe_cancellations_total {probe_cancellations}\n"
+            )
+            + &gauge(
+                "polymarket_bot_probe_slippage_cost_usdc",
+                "Slippage paid by probes of abandoned orders",
+                Some(probe_cost),
+            )
+            + &format!(
+                "# HELP polymarket_bot_fast_stops_total Crypto positions exited by the fast stop\n\
+                 # TYPE polymarket_bot_fast_stops_total counter\n\
+                 polymarket_bot_fast_stops_total {fast_stops}\n"
+            )
+            + &gauge(
+                "polymarket_bot_fast_stop_latency_ms",
+                "Milliseconds from the tick that triggered the last fast stop to its fill",
+                fast_stop_latency,
+            )
+            + &format!(
+                "# HELP polymarket_bot_expired_signals_total Signals that expired before trading or whose orders expired unfilled\n\
+                 # TYPE polymarket_bot_expired_signals_total counter\n\
+                 polymarket_bot_expired_signals_total {expired_signals}\n"
+            )
+            + &strategy_switches
+            + &gauge(
+                "polymarket_bot_cache_hit_rate",
+                "Share of Gamma market and price history lookups served from the cache",
+                self.cache_stats.read().await.hit_rate(),
+            )
+            + &gauge(
+                "polymarket_bot_notification_queue_depth",
+                "Notifications waiting in the outbox",
+                Some(Decimal::from(queued_notifications)),
+            )
+            + &format!(
+                "# HELP polymarket_bot_notification_delivery_failures_total Failed attempts to deliver a notification\n\
+                 # TYPE polymarket_bot_notification_delivery_failures_total counter\n\
+                 polymarket_bot_notification_delivery_failures_total {delivery_failures}\n"
+            )
     }
 
     pub async fn record_trade(&self, record: TradeRecord) {
@@ -101,7 +295,7 @@ impl Monitor {
             win_rate,
             total_pnl,
             avg_pnl_per_trade: avg_pnl,
-            sharpe_ratio: None, // TODO: Calculate Sharpe ratio
+            sharpe_ratio: self.performance.read().await.sharpe(),
         }
     }
 
@@ -115,3 +309,10 @@ impl Monitor {
         );
     }
 }
+
+#[async_trait::async_trait]
+impl MetricsSource for Monitor {
+    async fn prometheus_text(&self) -> String {
+        self.prometheus_gauges().await
+    }
+}
@@ -0,0 +1,241 @@
+//! Realized rolling performance ratios
+
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::VecDeque;
+
+/// Fewer observations than this give no ratio
+pub const MIN_OBSERVATIONS: usize = 20;
+
+/// Annualization factor of the daily returns (trading days per year)
+const TRADING_DAYS_PER_YEAR: u32 = 252;
+
+/// Sharpe, Sortino and Calmar ratios over the last `window` daily returns
+#[derive(Debug, Clone)]
+pub struct RollingPerformance {
+    pub window: usize,
+    pub returns: VecDeque<Decimal>,
+    /// Per-period risk-free return
+    pub risk_free_rate: Decimal,
+    /// Day the scan returns are compounded into, with its growth so far;
+    /// recorded once a later day's scan arrives
+    pub open_day: Option<(NaiveDate, Decimal)>,
+}
+
+impl Default for RollingPerformance {
+    fn default() -> Self {
+        Self::new(30, Decimal::ZERO)
+    }
+}
+
+impl RollingPerformance {
+    pub fn new(window: usize, risk_free_rate: Decimal) -> Self {
+        Self {
+            window,
+            returns: VecDeque::with_capacity(window),
+            risk_free_rate,
+            open_day: None,
+        }
+    }
+
+    /// Compound one scan cycle's return into `day`'s; the first scan of a
+    /// later day records the finished day's return
+    pub fn record_scan(&mut self, day: NaiveDate, scan_return: Decimal) {
+        match &mut self.open_day {
+            Some((open, growth)) if *open == day => *growth *= Decimal::ONE + scan_return,
+            _ => {
+                if let Some((_, growth)) = self.open_day.take() {
+                    self.update(growth - Decimal::ONE);
+                }
+                self.open_day = Some((day, Decimal::ONE + scan_return));
+            }
+        }
+    }
+
+    /// Record one period's return (0.01 = +1%)
+    pub fn update(&mut self, period_return: Decimal) {
+        if self.returns.len() >= self.window {
+            self.returns.pop_front();
+        }
+        self.returns.push_back(period_return);
+    }
+
+    /// Annualized `mean(returns - rf) / std(returns)`
+    pub fn sharpe(&self) -> Option<Decimal> {
+        let n = self.observations()?;
+        let mean = self.returns.iter().sum::<Decimal>() / n;
+        let variance = self.returns.iter().map(|r| (r - mean) * (r - mean)).sum::<Decimal>() / (n - Decimal::ONE);
+        self.annualize(self.mean_excess(n), variance.sqrt()?)
+    }
+
+    /// Annualized `mean(returns - rf) / downside deviation`, penalizing only
+    /// returns below the risk-free rate
+    pub fn sortino(&self) -> Option<Decimal> {
+        let n = self.observations()?;
+        let downside = self
+            .returns
+            .iter()
+            .map(|r| (r - self.risk_free_rate).min(Decimal::ZERO))
+            .map(|d| d * d)
+            .sum::<Decimal>()
+            / n;
+        self.annualize(self.mean_excess(n), downside.sqrt()?)
+    }
+
+    /// Largest peak-to-trough fall of the compounded returns, as a fraction
+    /// of the peak
+    pub fn max_drawdown(&self) -> Decimal {
+        let mut equity = Decimal::ONE;
+        let mut peak = Decimal::ONE;
+        let mut max_drawdown = Decimal::ZERO;
+        for r in &self.returns {
+            equity *= Decimal::ONE + r;
+            peak = peak.max(equity);
+            max_drawdown = max_drawdown.max((peak - equity) / peak);
+        }
+        max_drawdown
+    }
+
+    /// Annualized mean daily return over the max drawdown between daily
+    /// closes; `None` without a drawdown to divide by
+    pub fn calmar(&self) -> Option<Decimal> {
+        let n = self.observations()?;
+        let max_drawdown = self.max_drawdown();
+        if max_drawdown.is_zero() {
+            return None;
+        }
+        let annual_return = self.returns.iter().sum::<Decimal>() / n * Decimal::from(TRADING_DAYS_PER_YEAR);
+        Some(annual_return / max_drawdown)
+    }
+
+    fn observations(&self) -> Option<Decimal> {
+        (self.returns.len() >= MIN_OBSERVATIONS).then(|| Decimal::from(self.returns.len()))
+    }
+
+    fn mean_excess(&self, n: Decimal) -> Decimal {
+        self.returns.iter().map(|r| r - self.risk_free_rate).sum::<Decimal>() / n
+    }
+
+    fn annualize(&self, excess: Decimal, deviation: Decimal) -> Option<Decimal> {
+        if deviation.is_zero() {
+            return None;
+        }
+        Some(excess / deviation * Decimal::from(TRADING_DAYS_PER_YEAR).sqrt()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn filled(returns: &[Decimal]) -> RollingPerformance {
+        let mut perf = RollingPerformance::new(30, Decimal::ZERO);
+        for r in returns {
+            perf.update(*r);
+        }
+        perf
+    }
+
+    #[test]
+    fn test_needs_min_observations() {
+        let perf = filled(&[dec!(0.01); MIN_OBSERVATIONS - 1]);
+        assert_eq!(perf.sharpe(), None);
+        assert_eq!(perf.sortino(), None);
+    }
+
+    #[test]
+    fn test_scans_are_recorded_as_daily_returns() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        let mut perf = RollingPerformance::default();
+        perf.record_scan(day(1), dec!(0.1));
+        perf.record_scan(day(1), dec!(0.1));
+        assert!(perf.returns.is_empty());
+
+        // 1.1 * 1.1 compounded, recorded when the next day starts
+        perf.record_scan(day(2), dec!(-0.5));
+        assert_eq!(perf.returns, [dec!(0.21)]);
+        perf.record_scan(day(4), dec!(0));
+        assert_eq!(perf.returns, [dec!(0.21), dec!(-0.5)]);
+    }
+
+    #[test]
+    fn test_window_drops_oldest() {
+        let perf = filled(&[dec!(0.01); 40]);
+        assert_eq!(perf.returns.len(), 30);
+    }
+
+    #[test]
+    fn test_sharpe_alternating_returns() {
+        // Mean 0.005, sample std of ±0.015 around it ≈ 0.01539
+        let returns: Vec<Decimal> = (0..20).map(|i| if i % 2 == 0 { dec!(0.02) } else { dec!(-0.01) }).collect();
+        let sharpe = filled(&returns).sharpe().unwrap();
+        // 0.005 / 0.01539 * sqrt(252) ≈ 5.16
+        assert!(sharpe > dec!(5.1) && sharpe < dec!(5.2), "{}", sharpe);
+    }
+
+    #[test]
+    fn test_sortino_ignores_upside() {
+        let returns: Vec<Decimal> = (0..20).map(|i| if i % 2 == 0 { dec!(0.02) } else { dec!(-0.01) }).collect();
+        let perf = filled(&returns);
+        // Downside deviation sqrt(10 * 0.0001 / 20) ≈ 0.00707 < std, so Sortino > Sharpe
+        let sortino = perf.sortino().unwrap();
+        assert!(sortino > perf.sharpe().unwrap());
+        // 0.005 / 0.00707 * sqrt(252) ≈ 11.22
+        assert!(sortino > dec!(11.1) && sortino < dec!(11.3), "{}", sortino);
+    }
+
+    #[test]
+    fn test_no_ratio_without_variation() {
+        let perf = filled(&[dec!(0.01); 25]);
+        assert_eq!(perf.sharpe(), None);
+        assert_eq!(perf.sortino(), None);
+        // Never down: no drawdown to measure Calmar against
+        assert_eq!(perf.max_drawdown(), Decimal::ZERO);
+        assert_eq!(perf.calmar(), None);
+    }
+
+    #[test]
+    fn test_sortino_and_calmar_over_scripted_history() {
+        // Same losses and mean return, upside spread differently
+        let steady: Vec<Decimal> = [vec![dec!(0.01); 10], vec![dec!(-0.05); 2], vec![dec!(0.02); 8]].concat();
+        let lumpy: Vec<Decimal> =
+            [vec![dec!(0); 5], vec![dec!(0.02); 5], vec![dec!(-0.05); 2], vec![dec!(0.02); 8]].concat();
+        let (steady, lumpy) = (filled(&steady), filled(&lumpy));
+
+        // Sortino only sees the two -5% periods: sqrt(2 * 0.0025 / 20)
+        assert_ne!(steady.sharpe(), lumpy.sharpe());
+        assert_eq!(steady.sortino(), lumpy.sortino());
+        let sortino = steady.sortino().unwrap();
+        // 0.008 / 0.01581 * sqrt(252) ≈ 8.03
+        assert!(sortino > dec!(8.0) && sortino < dec!(8.1), "{}", sortino);
+
+        // Two -5% periods in a row from the peak: 1 - 0.95^2
+        for perf in [&steady, &lumpy] {
+            assert!((perf.max_drawdown() - dec!(0.0975)).abs() < dec!(0.000001), "{}", perf.max_drawdown());
+        }
+        // 0.008 * 252 / 0.0975 ≈ 20.68
+        let calmar = steady.calmar().unwrap();
+        assert!(calmar > dec!(20.6) && calmar < dec!(20.7), "{}", calmar);
+    }
+
+    #[test]
+    fn test_calmar_over_daily_returns_of_many_scans() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, 1).unwrap() + chrono::Days::new(d);
+        let mut perf = RollingPerformance::default();
+        for d in 0..21 {
+            // A dip and recovery within each day, then one losing day
+            let scans = if d == 10 { vec![dec!(-0.05)] } else { vec![dec!(-0.02), dec!(0.0308)] };
+            for r in scans {
+                perf.record_scan(day(d), r);
+            }
+        }
+        assert_eq!(perf.returns.len(), 20);
+
+        // Intraday dips don't count, only the -5% day
+        assert!((perf.max_drawdown() - dec!(0.05)).abs() < dec!(0.000001), "{}", perf.max_drawdown());
+        // Mean daily return (19 * 0.010184 - 0.05) / 20 ≈ 0.007175, * 252 / 0.05 ≈ 36.16
+        let calmar = perf.calmar().unwrap();
+        assert!(calmar > dec!(36.1) && calmar < dec!(36.2), "{}", calmar);
+    }
+}
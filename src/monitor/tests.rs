@@ -237,4 +237,77 @@ mod tests {
         assert_eq!(stats.total_pnl, dec!(30));
         assert_eq!(stats.avg_pnl_per_trade, dec!(10));
     }
+    #[tokio::test]
+    async fn test_rolling_ratios_and_prometheus_gauges() {
+        let monitor = Monitor::new(10);
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("# TYPE polymarket_bot_rolling_sharpe_ratio gauge"));
+        assert!(gauges.contains("polymarket_bot_rolling_sortino_ratio NaN"));
+        assert!(gauges.contains("polymarket_bot_portfolio_value_usdc NaN"));
+
+        monitor.record_portfolio_value(dec!(1234.5)).await;
+        assert!(monitor.prometheus_gauges().await.contains("polymarket_bot_portfolio_value_usdc 1234.5"));
+
+        assert!(gauges.contains("# TYPE polymarket_bot_probe_cancellations_total counter"));
+        assert!(gauges.contains("polymarket_bot_probe_cancellations_total 0\n"));
+        monitor.record_probe_stats(2, dec!(3.25)).await;
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("polymarket_bot_probe_cancellations_total 2\n"));
+        assert!(gauges.contains("polymarket_bot_probe_slippage_cost_usdc 3.25"));
+        assert!(gauges.contains("polymarket_bot_fast_stops_total 0\n"));
+        assert!(gauges.contains("polymarket_bot_expired_signals_total 0\n"));
+        monitor.record_expired_signals(4).await;
+        assert!(monitor.prometheus_gauges().await.contains("polymarket_bot_expired_signals_total 4\n"));
+        assert!(gauges.contains("polymarket_bot_cache_hit_rate NaN"));
+        let stats = crate::client::CacheStats { hits: 3, misses: 1, evictions: 0, size: 2 };
+        monitor.record_cache_stats(stats).await;
+        assert!(monitor.prometheus_gauges().await.contains("polymarket_bot_cache_hit_rate 0.75\n"));
+        assert!(gauges.contains("polymarket_bot_notification_queue_depth 0\n"));
+        monitor.record_notification_outbox(3, 7).await;
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("polymarket_bot_notification_queue_depth 3\n"));
+        assert!(gauges.contains("polymarket_bot_notification_delivery_failures_total 7\n"));
+        assert!(gauges.contains("polymarket_bot_fast_stop_latency_ms NaN"));
+        monitor.record_fast_stop(std::time::Duration::from_millis(180)).await;
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("polymarket_bot_fast_stops_total 1\n"));
+        assert!(gauges.contains("polymarket_bot_fast_stop_latency_ms 180"));
+
+        assert!(gauges.contains("polymarket_bot_heat_utilization NaN"));
+        let heat = crate::portfolio::HeatMetrics {
+            total_heat_usdc: dec!(34),
+            heat_utilization: dec!(0.68),
+            ..Default::default()
+        };
+        monitor.record_portfolio_heat(&heat).await;
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("polymarket_bot_portfolio_heat_usdc 34\n"));
+        assert!(gauges.contains("polymarket_bot_heat_utilization 0.68\n"));
+
+        assert!(gauges.contains("# TYPE polymarket_bot_strategy_switches_total counter"));
+        assert_eq!(monitor.active_strategy().await, None);
+        monitor.record_strategy_switch("default", "momentum").await;
+        monitor.record_strategy_switch("momentum", "default").await;
+        monitor.record_strategy_switch("default", "momentum").await;
+        assert_eq!(monitor.active_strategy().await.as_deref(), Some("momentum"));
+        let gauges = monitor.prometheus_gauges().await;
+        assert!(gauges.contains("polymarket_bot_strategy_switches_total{from=\"default\",to=\"momentum\"} 2\n"));
+        assert!(gauges.contains("polymarket_bot_strategy_switches_total{from=\"momentum\",to=\"default\"} 1\n"));
+
+        // Two scans a day; each day is recorded once the next one starts
+        let start = Utc::now();
+        for i in 0..21 {
+            let day = start + chrono::Duration::days(i);
+            let r = if i % 2 == 0 { dec!(0.02) } else { dec!(-0.01) };
+            monitor.record_scan_return(day, r).await;
+            monitor.record_scan_return(day, Decimal::ZERO).await;
+        }
+        assert_eq!(monitor.rolling_performance().await.returns.len(), 20);
+        let sharpe = monitor.get_stats().await.sharpe_ratio.unwrap();
+        assert_eq!(Some(sharpe), monitor.rolling_performance().await.sharpe());
+        assert!(monitor
+            .prometheus_gauges()
+            .await
+            .contains(&format!("polymarket_bot_rolling_sharpe_ratio {}", sharpe)));
+    }
 }
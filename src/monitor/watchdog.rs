@@ -0,0 +1,258 @@
+//! Heartbeat watchdog over the bot's long-running tasks
+//!
+//! A task can look alive (process up, Telegram answering) while it sits on
+//! a call that never returns. Each watched task beats a [`Heartbeat`] as it
+//! makes progress, and [`Watchdog::check`] flags the ones whose last beat
+//! is older than their threshold. Restartable tasks (feeds, ingester
+//! sources, notification delivery) are aborted and spawned afresh; the
+//! others are reported, and a stalled main loop can exit the process so
+//! systemd restarts the bot.
+
+use crate::notify::Notifier;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Progress of one task
+#[derive(Debug)]
+pub struct Heartbeat {
+    started: Instant,
+    /// Milliseconds after `started` of the last beat
+    last_ms: AtomicU64,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self { started: Instant::now(), last_ms: AtomicU64::new(0) }
+    }
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        self.last_ms.store(self.started.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Time since the last beat, or since the heartbeat was created
+    pub fn age(&self) -> Duration {
+        self.started
+            .elapsed()
+            .saturating_sub(Duration::from_millis(self.last_ms.load(Ordering::Relaxed)))
+    }
+}
+
+type Respawn = Box<dyn Fn(Arc<Heartbeat>) -> JoinHandle<()> + Send + Sync>;
+
+enum OnStall {
+    Report,
+    Restart { respawn: Respawn, handle: JoinHandle<()> },
+    Critical,
+}
+
+struct WatchedTask {
+    heartbeat: Arc<Heartbeat>,
+    stale_after: Duration,
+    on_stall: OnStall,
+    /// Already reported for the current stall
+    reported: bool,
+}
+
+/// What the watchdog did about a stalled task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallAction {
+    Reported,
+    Restarted,
+    /// The main loop stopped: trading has stopped with it
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stall {
+    pub task: String,
+    /// Age of the task's last heartbeat
+    pub silent_for: Duration,
+    pub action: StallAction,
+}
+
+/// Registry of watched tasks and their heartbeats
+#[derive(Default)]
+pub struct Watchdog {
+    tasks: Mutex<BTreeMap<String, WatchedTask>>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watch a task whose stalls are only reported
+    pub fn watch(&self, name: &str, stale_after: Duration) -> Arc<Heartbeat> {
+        self.insert(name, stale_after, OnStall::Report)
+    }
+
+    /// Watch the main loop: its stalls are critical
+    pub fn watch_critical(&self, name: &str, stale_after: Duration) -> Arc<Heartbeat> {
+        self.insert(name, stale_after, OnStall::Critical)
+    }
+
+    /// Spawn `task` with a fresh heartbeat, and abort and spawn it again
+    /// whenever it stalls
+    pub fn spawn_restartable<F, Fut>(&self, name: &str, stale_after: Duration, task: F)
+    where
+        F: Fn(Arc<Heartbeat>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let respawn: Respawn = Box::new(move |heartbeat| tokio::spawn(task(heartbeat)));
+        let heartbeat = Arc::new(Heartbeat::default());
+        let handle = respawn(heartbeat.clone());
+        self.tasks.lock().insert(
+            name.to_string(),
+            WatchedTask { heartbeat, stale_after, on_stall: OnStall::Restart { respawn, handle }, reported: false },
+        );
+    }
+
+    fn insert(&self, name: &str, stale_after: Duration, on_stall: OnStall) -> Arc<Heartbeat> {
+        let heartbeat = Arc::new(Heartbeat::default());
+        self.tasks.lock().insert(
+            name.to_string(),
+            WatchedTask { heartbeat: heartbeat.clone(), stale_after, on_stall, reported: false },
+        );
+        heartbeat
+    }
+
+    /// Tasks stalled since the last check. Restartable ones are respawned,
+    /// and reported again each time; the others are reported once per
+    /// stall.
+    pub fn check(&self) -> Vec<Stall> {
+        let mut stalls = Vec::new();
+        for (name, task) in self.tasks.lock().iter_mut() {
+            let silent_for = task.heartbeat.age();
+            if silent_for <= task.stale_after {
+                task.reported = false;
+                continue;
+            }
+            let action = match &mut task.on_stall {
+                OnStall::Restart { respawn, handle } => {
+                    handle.abort();
+                    task.heartbeat.beat();
+                    *handle = respawn(task.heartbeat.clone());
+                    StallAction::Restarted
+                }
+                _ if task.reported => continue,
+                OnStall::Report => StallAction::Reported,
+                OnStall::Critical => StallAction::Critical,
+            };
+            task.reported = true;
+            stalls.push(Stall { task: name.clone(), silent_for, action });
+        }
+        stalls
+    }
+
+    /// Check every `interval` in the background, logging stalls and
+    /// sending them to `notifier`. With `exit_on_critical`, a critical
+    /// stall exits the process once reported, for a supervisor such as
+    /// systemd to restart the bot.
+    pub fn spawn_supervisor(self: Arc<Self>, interval: Duration, notifier: Notifier, exit_on_critical: bool) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                let stalls = self.check();
+                if stalls.is_empty() {
+                    continue;
+                }
+                for stall in &stalls {
+                    match stall.action {
+                        StallAction::Critical => {
+                            tracing::error!("Watchdog: {} silent for {}s", stall.task, stall.silent_for.as_secs())
+                        }
+                        _ => tracing::warn!("Watchdog: {} silent for {}s ({:?})", stall.task, stall.silent_for.as_secs(), stall.action),
+                    }
+                }
+                if let Err(e) = notifier.tasks_stalled(&stalls, exit_on_critical).await {
+                    tracing::warn!("Failed to send watchdog alert: {}", e);
+                }
+                if exit_on_critical && stalls.iter().any(|s| s.action == StallAction::Critical) {
+                    // One delivery attempt before exiting; undelivered
+                    // critical alerts survive in the outbox
+                    let _ = tokio::time::timeout(Duration::from_secs(10), notifier.deliver_outbox(chrono::Utc::now())).await;
+                    tracing::error!("Watchdog: exiting for a restart");
+                    std::process::exit(1);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stuck_task_is_detected_and_respawned() {
+        let watchdog = Watchdog::new();
+        let spawns = Arc::new(AtomicUsize::new(0));
+        let counter = spawns.clone();
+        // Beats once, then hangs like a request without a timeout
+        watchdog.spawn_restartable("feed", Duration::from_secs(30), move |heartbeat| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                heartbeat.beat();
+                std::future::pending::<()>().await;
+            }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(spawns.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert!(watchdog.check().is_empty());
+
+        tokio::time::advance(Duration::from_secs(15)).await;
+        let stalls = watchdog.check();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].task, "feed");
+        assert_eq!(stalls[0].action, StallAction::Restarted);
+        assert!(stalls[0].silent_for >= Duration::from_secs(30));
+        tokio::task::yield_now().await;
+        assert_eq!(spawns.load(Ordering::SeqCst), 2);
+
+        // The new task gets a full threshold before it counts as stuck
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert!(watchdog.check().is_empty());
+        tokio::time::advance(Duration::from_secs(15)).await;
+        assert_eq!(watchdog.check().len(), 1);
+        tokio::task::yield_now().await;
+        assert_eq!(spawns.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stalls_are_reported_once_until_the_task_recovers() {
+        let watchdog = Watchdog::new();
+        let main_loop = watchdog.watch_critical("main_loop", Duration::from_secs(60));
+        let copy_trade = watchdog.watch("copy_trade", Duration::from_secs(90));
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        copy_trade.beat();
+        let stalls = watchdog.check();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!((stalls[0].task.as_str(), stalls[0].action), ("main_loop", StallAction::Critical));
+
+        // Still stalled: not reported again
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(watchdog.check().is_empty());
+
+        main_loop.beat();
+        assert!(watchdog.check().is_empty());
+        tokio::time::advance(Duration::from_secs(65)).await;
+        let stalls = watchdog.check();
+        assert_eq!(stalls.len(), 2);
+        assert_eq!((stalls[0].task.as_str(), stalls[0].action), ("copy_trade", StallAction::Reported));
+        assert_eq!(stalls[1].action, StallAction::Critical);
+    }
+}
@@ -1,16 +1,42 @@
 //! Telegram notification module
 //!
-//! Sends trading signals, executions, and alerts to Telegram.
+//! Sends trading signals, executions, and alerts to Telegram, and the main
+//! events to any other [`NotifierBackend`]s (e.g. [`SlackNotifier`]).
 
+mod outbox;
+mod slack;
 #[cfg(test)]
 mod tests;
 
-use crate::error::Result;
-use crate::types::{Signal, Side, Trade};
-use crate::monitor::PerformanceStats;
+pub use outbox::{backoff, OutboxStats, Priority, DEDUP_WINDOW_SECS, MAX_ATTEMPTS};
+pub use slack::SlackNotifier;
+
+use crate::analysis::dossier::MarketDossier;
+use crate::analysis::market_profile::MarketProfile;
+use crate::clock::{Clock, SystemClock};
+use crate::config::AlertSeverity;
+use crate::error::{BotError, Result};
+use crate::executor::SimStats;
+use crate::ingester::{AuthorStanding, SourceStats};
+use crate::types::{Signal, Side, Trade, TradeStatus};
+use crate::utils::ascii_chart::sparkline;
+use crate::utils::{http_client, HTTP_TIMEOUT};
+use crate::monitor::{ComponentHealth, FiredAlert, FiredMarketAlert, MarketAlert, HealthStatus, HealthTransition, PerformanceStats, RollingPerformance, Stall, StallAction};
+use crate::orderbook::{MicrostructureEventType, OrderBookAnalysis, PredictedDirection, TradeSide};
+use crate::portfolio::redeem::Redemption;
+use crate::portfolio::CapitalLadder;
+use crate::report::MonthlyReport;
+use crate::risk::{FastStopExit, FastStopReason, LiquidationReport, QueuedSignal, StressResult};
+use crate::strategy::{RestingOrder, SignalScore, StrategySwitch};
+use crate::storage::{AccountPnl, DiscoveredMarket, ShadowPnl, StrategyPnl, TagPerformance, TraceEvent, TraceStage};
+use async_trait::async_trait;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Serialize;
+use std::sync::Arc;
+
+/// Telegram Bot API endpoint
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
 
 /// Telegram notifier
 #[derive(Clone)]
@@ -19,6 +45,29 @@ pub struct Notifier {
     bot_token: String,
     chat_id: String,
     enabled: bool,
+    api_url: String,
+    /// Queue messages are delivered from, when set up with
+    /// [`Notifier::with_outbox`]; shared by clones
+    outbox: Option<Arc<outbox::Outbox>>,
+    /// Stamps messages and times the outbox's dedup window and backoff
+    clock: Arc<dyn Clock>,
+    /// Other services trades, signals, errors and daily reports also go to
+    backends: Vec<Arc<dyn NotifierBackend>>,
+}
+
+/// A service besides Telegram that the main notifications are sent to.
+/// Failures are logged by the [`Notifier`], never returned to its caller.
+#[async_trait]
+pub trait NotifierBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn signal_found(&self, signal: &Signal, market_question: &str) -> Result<()>;
+
+    async fn trade_executed(&self, trade: &Trade, market_question: &str) -> Result<()>;
+
+    async fn daily_report(&self, stats: &PerformanceStats, balance: Decimal) -> Result<()>;
+
+    async fn error(&self, context: &str, error: &str) -> Result<()>;
 }
 
 #[derive(Debug, Serialize)]
@@ -31,23 +80,50 @@ struct TelegramMessage {
 impl Notifier {
     pub fn new(bot_token: String, chat_id: String) -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             bot_token,
             chat_id,
             enabled: true,
+            api_url: TELEGRAM_API_URL.to_string(),
+            outbox: None,
+            clock: Arc::new(SystemClock),
+            backends: Vec::new(),
         }
     }
 
     /// Create a disabled notifier (for when Telegram is not configured)
     pub fn disabled() -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             bot_token: String::new(),
             chat_id: String::new(),
             enabled: false,
+            api_url: TELEGRAM_API_URL.to_string(),
+            outbox: None,
+            clock: Arc::new(SystemClock),
+            backends: Vec::new(),
         }
     }
 
+    /// Read time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Also send trades, signals, errors and daily reports to `backend`,
+    /// whether or not Telegram is enabled
+    pub fn with_backend(mut self, backend: Arc<dyn NotifierBackend>) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    /// Talk to a Bot API server other than Telegram's
+    pub fn with_api_url(mut self, url: &str) -> Self {
+        self.api_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
     /// Send a raw message (HTML format)
     pub async fn send(&self, text: &str) -> Result<()> {
         self.send_with_format(text, "HTML").await
@@ -58,24 +134,47 @@ impl Notifier {
         self.send_with_format(text, "Markdown").await
     }
 
+    /// Send a message (HTML format) queued at `priority`
+    pub async fn send_with_priority(&self, text: &str, priority: Priority) -> Result<()> {
+        self.dispatch(text, "HTML", priority).await
+    }
+
+    /// Check the bot token against the Telegram API
+    pub async fn ping(&self) -> Result<()> {
+        let url = format!("{}/bot{}/getMe", self.api_url, self.bot_token);
+        let response = self.http.get(&url).send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::NOT_FOUND => {
+                Err(BotError::Auth("Telegram rejected the bot token".into()))
+            }
+            status => Err(BotError::Api(format!("Telegram getMe returned {}", status))),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     /// Send a message with specific parse mode
     async fn send_with_format(&self, text: &str, parse_mode: &str) -> Result<()> {
+        self.dispatch(text, parse_mode, Priority::Normal).await
+    }
+
+    /// Queue the message when there is an outbox, else post it right away
+    async fn dispatch(&self, text: &str, parse_mode: &str, priority: Priority) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
-        let url = format!(
-            "https://api.telegram.org/bot{}/sendMessage",
-            self.bot_token
-        );
-
-        let msg = TelegramMessage {
-            chat_id: self.chat_id.clone(),
-            text: text.to_string(),
-            parse_mode: parse_mode.to_string(),
-        };
+        if let Some(outbox) = &self.outbox {
+            match outbox.enqueue(text, parse_mode, priority, self.clock.now()).await {
+                Ok(_) => return Ok(()),
+                Err(e) => tracing::warn!("Failed to queue notification, sending it directly: {}", e),
+            }
+        }
 
-        let response = self.http.post(&url).json(&msg).send().await?;
+        let response = self.post(text, parse_mode).await?;
         
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
@@ -85,55 +184,56 @@ impl Notifier {
         Ok(())
     }
 
-    /// Notify about a trading signal found
-    pub async fn signal_found(&self, signal: &Signal, market_question: &str) -> Result<()> {
-        let side_emoji = match signal.side {
-            Side::Buy => "🟢",
-            Side::Sell => "🔴",
-        };
+    async fn post(&self, text: &str, parse_mode: &str) -> Result<reqwest::Response> {
+        let url = format!("{}/bot{}/sendMessage", self.api_url, self.bot_token);
 
-        let side_text = match signal.side {
-            Side::Buy => "BUY",
-            Side::Sell => "SELL",
+        let msg = TelegramMessage {
+            chat_id: self.chat_id.clone(),
+            text: text.to_string(),
+            parse_mode: parse_mode.to_string(),
         };
 
-        let text = format!(
-            "{} <b>Signal Found</b>\n\n\
-            📊 <b>{}</b>\n\n\
-            Direction: {} {}\n\
-            Model: <code>{:.1}%</code>\n\
-            Market: <code>{:.1}%</code>\n\
-            Edge: <code>{:+.1}%</code>\n\
-            Confidence: <code>{:.0}%</code>\n\
-            Size: <code>{:.1}%</code> of portfolio",
-            side_emoji,
-            truncate(market_question, 100),
-            side_emoji,
-            side_text,
-            signal.model_probability * Decimal::ONE_HUNDRED,
-            signal.market_probability * Decimal::ONE_HUNDRED,
-            signal.edge * Decimal::ONE_HUNDRED,
-            signal.confidence * Decimal::ONE_HUNDRED,
-            signal.suggested_size * Decimal::ONE_HUNDRED,
-        );
+        Ok(self.http.post(&url).json(&msg).send().await?)
+    }
 
-        self.send(&text).await
+    /// Notify about a trading signal found, flagging strong disagreement
+    /// between the models behind it
+    pub async fn signal_found(&self, signal: &Signal, market_question: &str, high_disagreement: bool) -> Result<()> {
+        for backend in &self.backends {
+            if let Err(e) = backend.signal_found(signal, market_question).await {
+                tracing::warn!("Failed to send signal to {}: {}", backend.name(), e);
+            }
+        }
+        self.send_with_priority(&signal_message(signal, market_question, high_disagreement), Priority::Low).await
     }
 
     /// Notify about trade execution
     pub async fn trade_executed(&self, trade: &Trade, market_question: &str) -> Result<()> {
+        for backend in &self.backends {
+            if let Err(e) = backend.trade_executed(trade, market_question).await {
+                tracing::warn!("Failed to send trade to {}: {}", backend.name(), e);
+            }
+        }
+
         let side_emoji = match trade.side {
             Side::Buy => "🟢",
             Side::Sell => "🔴",
         };
 
+        // Resting limit orders report their fill separately, see `resting_fill`
+        let header = if trade.status == TradeStatus::Resting {
+            "📌 <b>Order Resting</b>"
+        } else {
+            "✅ <b>Trade Executed</b>"
+        };
         let text = format!(
-            "✅ <b>Trade Executed</b>\n\n\
+            "{}\n\n\
             📊 {}\n\n\
             {} {} @ <code>${:.4}</code>\n\
             Size: <code>${:.2}</code>\n\
             Fee: <code>${:.4}</code>\n\
             Order ID: <code>{}</code>",
+            header,
             truncate(market_question, 80),
             side_emoji,
             match trade.side {
@@ -146,11 +246,24 @@ impl Notifier {
             &trade.order_id[..8],
         );
 
-        self.send(&text).await
+        self.send_with_priority(&text, Priority::Critical).await
+    }
+
+    /// Notify that a resting limit order was filled on the book, as opposed
+    /// to a taker trade
+    pub async fn resting_fill(&self, order: &RestingOrder, trade: &Trade, market_question: &str) -> Result<()> {
+        let text = resting_fill_message(order, trade, market_question, self.clock.now());
+        self.send_with_priority(&text, Priority::Critical).await
     }
 
     /// Notify about an error
     pub async fn error(&self, context: &str, error: &str) -> Result<()> {
+        for backend in &self.backends {
+            if let Err(e) = backend.error(context, error).await {
+                tracing::warn!("Failed to send error to {}: {}", backend.name(), e);
+            }
+        }
+
         let text = format!(
             "⚠️ <b>Error</b>\n\n\
             Context: {}\n\
@@ -159,14 +272,35 @@ impl Notifier {
             truncate(error, 200),
         );
 
-        self.send(&text).await
+        self.send_with_priority(&text, Priority::Critical).await
     }
 
-    /// Send daily performance report
-    pub async fn daily_report(&self, stats: &PerformanceStats, balance: Decimal) -> Result<()> {
+    /// Send daily performance report, with per-strategy and per-account
+    /// attribution when more than one has traded, a shadow-vs-live
+    /// comparison when any strategy runs in shadow, the capital ladder
+    /// when positions are open and signal source counts when ingesting.
+    /// `balance` is the total over all accounts.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn daily_report(
+        &self,
+        stats: &PerformanceStats,
+        balance: Decimal,
+        by_strategy: &[StrategyPnl],
+        by_account: &[AccountPnl],
+        shadow: &[ShadowPnl],
+        performance: Option<&RollingPerformance>,
+        ladder: Option<&CapitalLadder>,
+        sources: &[SourceStats],
+    ) -> Result<()> {
+        for backend in &self.backends {
+            if let Err(e) = backend.daily_report(stats, balance).await {
+                tracing::warn!("Failed to send daily report to {}: {}", backend.name(), e);
+            }
+        }
+
         let pnl_emoji = if stats.total_pnl >= Decimal::ZERO { "📈" } else { "📉" };
 
-        let text = format!(
+        let mut text = format!(
             "📊 <b>Daily Report</b>\n\n\
             💰 Balance: <code>${:.2}</code>\n\
             {} PnL: <code>{:+.2}</code>\n\n\
@@ -180,10 +314,133 @@ impl Notifier {
             stats.win_rate * Decimal::ONE_HUNDRED,
             stats.avg_pnl_per_trade,
         );
+        if let Some(performance) = performance {
+            text.push_str("\n\n");
+            text.push_str(&performance_line(performance));
+        }
+        text.push_str(&strategy_attribution(by_strategy));
+        text.push_str(&account_attribution(by_account));
+        if !shadow.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&shadow_comparison(by_strategy, shadow));
+        }
+        if let Some(ladder) = ladder.filter(|l| !l.is_empty()) {
+            text.push_str("\n\n");
+            text.push_str(&capital_ladder(ladder));
+        }
+        if !sources.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&ingest_sources(sources, self.clock.now()));
+        }
 
         self.send(&text).await
     }
 
+    /// Send weekly report
+    pub async fn weekly_report(
+        &self,
+        balance: Decimal,
+        trades: &[Trade],
+        manual_interventions: usize,
+        equity_curve: &[Decimal],
+        stress: &[StressResult],
+        liquidation: Option<&LiquidationReport>,
+    ) -> Result<()> {
+        self.send(&weekly_summary(balance, trades, manual_interventions, equity_curve, stress, liquidation)).await
+    }
+
+    /// Warn that a forced liquidation of the open positions would cost too
+    /// much of the portfolio
+    pub async fn liquidation_cost_high(&self, report: &LiquidationReport) -> Result<()> {
+        self.send_with_priority(&liquidation_alert(report), Priority::Critical).await
+    }
+
+    /// Alert a headline about the market of an open position
+    pub async fn position_news(&self, question: &str, headline: &str) -> Result<()> {
+        self.send_with_priority(&position_news_alert(question, headline), Priority::Critical).await
+    }
+
+    /// Send the monthly performance breakdown by trade tag
+    pub async fn monthly_attribution(&self, month: &str, by_tag: &[TagPerformance]) -> Result<()> {
+        self.send(&tag_attribution(month, by_tag)).await
+    }
+
+    /// Send the dry-run simulation report
+    pub async fn simulation_report(&self, stats: &SimStats) -> Result<()> {
+        self.send(&simulation_summary(stats)).await
+    }
+
+    /// Notify that authors were muted for poor signal accuracy
+    pub async fn authors_muted(&self, muted: &[AuthorStanding]) -> Result<()> {
+        self.send(&muted_authors(muted)).await
+    }
+
+    /// Warn that ingestion sources have received nothing for `threshold`
+    pub async fn sources_stale(&self, stale: &[SourceStats], threshold: chrono::Duration) -> Result<()> {
+        self.send(&stale_sources(stale, threshold)).await
+    }
+
+    /// Notify that a user-defined alert rule fired
+    pub async fn alert_fired(&self, alert: &FiredAlert) -> Result<()> {
+        self.send(&fired_alert(alert)).await
+    }
+
+    /// Notify that a per-market alert rule fired
+    pub async fn market_alert_fired(&self, fired: &FiredMarketAlert) -> Result<()> {
+        self.send(&fired_market_alert(fired)).await
+    }
+
+    /// Notify that dependencies degraded or went down
+    pub async fn health_changed(&self, transitions: &[HealthTransition]) -> Result<()> {
+        self.send(&health_changes(transitions)).await
+    }
+
+    /// Notify that watched tasks stopped making progress; critical when the
+    /// main loop is among them
+    pub async fn tasks_stalled(&self, stalls: &[Stall], exiting: bool) -> Result<()> {
+        let priority = if stalls.iter().any(|s| s.action == StallAction::Critical) {
+            Priority::Critical
+        } else {
+            Priority::Normal
+        };
+        self.send_with_priority(&stalled_tasks(stalls, exiting), priority).await
+    }
+
+    /// Notify that a large order was abandoned after its liquidity probe
+    pub async fn probe_slippage_too_high(&self, market: &str, actual_bps: Decimal, limit_bps: Decimal) -> Result<()> {
+        self.send(&probe_slippage(market, actual_bps, limit_bps)).await
+    }
+
+    /// Notify that the fast stop closed a crypto position
+    pub async fn fast_stop_executed(&self, exit: &FastStopExit, trade: &Trade, latency: std::time::Duration) -> Result<()> {
+        self.send_with_priority(&fast_stop_message(exit, trade, latency), Priority::Critical).await
+    }
+
+    /// Notify that the regime selector switched strategy
+    pub async fn strategy_switched(&self, switch: &StrategySwitch) -> Result<()> {
+        self.send(&strategy_switch(switch)).await
+    }
+
+    /// Send the monthly performance report
+    pub async fn monthly_report(&self, report: &MonthlyReport) -> Result<()> {
+        self.send(&monthly_summary(report)).await
+    }
+
+    /// Send the first looks at newly discovered markets
+    pub async fn markets_discovered(&self, markets: &[DiscoveredMarket], added: &[String]) -> Result<()> {
+        self.send(&discovery_digest(markets, added)).await
+    }
+
+    /// Notify about copy-trading follow list changes
+    pub async fn copy_traders_updated(&self, added: &[String], removed: &[String]) -> Result<()> {
+        self.send(&follow_list_update(added, removed)).await
+    }
+
+    /// Notify that copying a trader stopped because their copies underperformed
+    pub async fn copy_trader_paused(&self, username: &str, win_rate: f64, total_profit: Decimal) -> Result<()> {
+        self.send(&copy_trader_paused(username, win_rate, total_profit)).await
+    }
+
     /// Notify bot startup
     pub async fn startup(&self, dry_run: bool) -> Result<()> {
         let mode = if dry_run { "DRY RUN 🧪" } else { "LIVE 🔥" };
@@ -192,7 +449,7 @@ impl Notifier {
             Mode: {}\n\
             Time: {}",
             mode,
-            chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            self.clock.now().format("%Y-%m-%d %H:%M UTC"),
         );
 
         self.send(&text).await
@@ -205,7 +462,7 @@ impl Notifier {
             Reason: {}\n\
             Time: {}",
             reason,
-            chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            self.clock.now().format("%Y-%m-%d %H:%M UTC"),
         );
 
         self.send(&text).await
@@ -220,7 +477,7 @@ impl Notifier {
             message,
         );
 
-        self.send(&text).await
+        self.send_with_priority(&text, Priority::Critical).await
     }
 
     /// Notify about arbitrage opportunity found
@@ -311,9 +568,1018 @@ impl Notifier {
     }
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
+/// Signal notification body
+pub(crate) fn signal_message(signal: &Signal, market_question: &str, high_disagreement: bool) -> String {
+    let side_emoji = match signal.side {
+        Side::Buy => "🟢",
+        Side::Sell => "🔴",
+    };
+
+    let side_text = match signal.side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    };
+
+    let mut text = format!(
+        "{} <b>Signal Found</b>\n\n\
+        📊 <b>{}</b>\n\n\
+        Direction: {} {}\n\
+        Model: <code>{:.1}%</code>\n\
+        Market: <code>{:.1}%</code>\n\
+        Edge: <code>{:+.1}%</code>\n\
+        Confidence: <code>{:.0}%</code>\n\
+        Size: <code>{:.1}%</code> of portfolio",
+        side_emoji,
+        truncate(market_question, 100),
+        side_emoji,
+        side_text,
+        signal.model_probability * Decimal::ONE_HUNDRED,
+        signal.market_probability * Decimal::ONE_HUNDRED,
+        signal.edge * Decimal::ONE_HUNDRED,
+        signal.confidence * Decimal::ONE_HUNDRED,
+        signal.suggested_size * Decimal::ONE_HUNDRED,
+    );
+
+    if high_disagreement {
+        text.push_str(&format!(
+            "\n⚠️ High model disagreement (σ <code>{:.1}%</code>), size halved",
+            signal.epistemic_uncertainty * Decimal::ONE_HUNDRED
+        ));
+    }
+    text
+}
+
+/// Daily report section with realized P&L per strategy (empty for a single strategy)
+pub(crate) fn strategy_attribution(by_strategy: &[StrategyPnl]) -> String {
+    if by_strategy.len() < 2 {
+        return String::new();
+    }
+
+    let mut text = String::from("\n\n<b>By Strategy</b>");
+    for pnl in by_strategy {
+        text.push_str(&format!(
+            "\n{} {}: <code>{:+.2}</code> ({} trades)",
+            if pnl.realized_pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+            pnl.strategy,
+            pnl.realized_pnl,
+            pnl.trades,
+        ));
+    }
+    text
+}
+
+/// Realized P&L per account, when more than one account has traded; also
+/// appended to the `/pnl` reply
+pub(crate) fn account_attribution(by_account: &[AccountPnl]) -> String {
+    if by_account.len() < 2 {
+        return String::new();
+    }
+
+    let mut text = String::from("\n\n<b>By Account</b>");
+    for pnl in by_account {
+        text.push_str(&format!(
+            "\n{} {}: <code>{:+.2}</code> ({} trades)",
+            if pnl.realized_pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+            pnl.account,
+            pnl.realized_pnl,
+            pnl.trades,
+        ));
+    }
+    text
+}
+
+/// Shadow strategies' counterfactual P&L next to the live strategies'
+/// realized P&L; also the `/shadow` reply
+pub(crate) fn shadow_comparison(live: &[StrategyPnl], shadow: &[ShadowPnl]) -> String {
+    let mut text = String::from("👻 <b>Shadow vs Live</b>");
+    if shadow.is_empty() {
+        text.push_str("\nNo shadow trades yet");
+    }
+    for pnl in shadow {
+        text.push_str(&format!(
+            "\n{} {} (shadow): <code>{:+.2}</code> ({} fills, ${:.2})",
+            if pnl.pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+            pnl.strategy,
+            pnl.pnl,
+            pnl.trades,
+            pnl.volume,
+        ));
+    }
+    for pnl in live {
+        text.push_str(&format!(
+            "\n{} {} (live): <code>{:+.2}</code> ({} trades)",
+            if pnl.realized_pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+            pnl.strategy,
+            pnl.realized_pnl,
+            pnl.trades,
+        ));
+    }
+    text
+}
+
+/// `📊 Performance (30d): Sharpe 1.23, Sortino N/A, Calmar 4.56` over the
+/// window's daily returns; Calmar is N/A until the window has seen a
+/// drawdown
+pub(crate) fn performance_line(performance: &RollingPerformance) -> String {
+    let ratio = |r: Option<Decimal>| r.map(|r| format!("{:.2}", r)).unwrap_or_else(|| "N/A".to_string());
+    format!(
+        "📊 Performance ({}d): Sharpe {}, Sortino {}, Calmar {}",
+        performance.window,
+        ratio(performance.sharpe()),
+        ratio(performance.sortino()),
+        ratio(performance.calmar()),
+    )
+}
+
+/// Weekly report body: the week's trading activity, how often an operator
+/// stepped in (notes, pauses, risk changes), the week's equity curve and
+/// how the open positions fare under the stress scenarios
+pub(crate) fn weekly_summary(
+    balance: Decimal,
+    trades: &[Trade],
+    manual_interventions: usize,
+    equity_curve: &[Decimal],
+    stress: &[StressResult],
+    liquidation: Option<&LiquidationReport>,
+) -> String {
+    let volume: Decimal = trades.iter().map(|t| t.price * t.size).sum();
+    let fees: Decimal = trades.iter().map(|t| t.fee).sum();
+
+    let mut text = format!(
+        "🗓 <b>Weekly Report</b>\n\n\
+        💰 Balance: <code>${:.2}</code>\n\n\
+        Trades: {}\n\
+        Volume: <code>${:.2}</code>\n\
+        Fees: <code>${:.2}</code>\n\
+        Manual Interventions: {}",
+        balance,
+        trades.len(),
+        volume,
+        fees,
+        manual_interventions,
+    );
+    if let (Some(first), Some(last)) = (equity_curve.first(), equity_curve.last()) {
+        text.push_str(&format!(
+            "\n\n📈 Equity: <code>{}</code>\n<code>${:.2}</code> → <code>${:.2}</code>",
+            sparkline(equity_curve, 28),
+            first,
+            last,
+        ));
+    }
+    if !stress.is_empty() {
+        let scenarios: Vec<String> = stress
+            .iter()
+            .map(|r| format!("{}: {:+.1}%", escape_html(&r.scenario_name), -r.portfolio_loss_pct))
+            .collect();
+        text.push_str(&format!("\n\n📊 Stress Tests: {}", scenarios.join(", ")));
+    }
+    if let Some(report) = liquidation.filter(|r| !r.worst_market.is_empty()) {
+        text.push_str(&format!(
+            "\n💧 Forced liquidation: {:.1}% (${:.2}) over {}m, worst {}",
+            report.liquidation_cost_pct.round_dp(1),
+            report.total_slippage_usdc,
+            report.time_to_liquidate_mins,
+            escape_html(&report.worst_market)
+        ));
+    }
+    text
+}
+
+/// The open positions would lose too much to slippage in a forced liquidation
+pub(crate) fn position_news_alert(question: &str, headline: &str) -> String {
+    format!(
+        "⚠️ Position news: {}\n\n{}",
+        escape_html(&truncate(question, 60)),
+        escape_html(headline)
+    )
+}
+
+pub(crate) fn liquidation_alert(report: &LiquidationReport) -> String {
+    format!(
+        "⚠️ Forced liquidation cost: {:.1}% of portfolio\n\n\
+        Slippage: <code>${:.2}</code> over {}m\n\
+        Worst market: {}",
+        report.liquidation_cost_pct.round_dp(1),
+        report.total_slippage_usdc,
+        report.time_to_liquidate_mins,
+        escape_html(&report.worst_market)
+    )
+}
+
+/// Performance per trade tag over `period` (strategy, model, prompt hash,
+/// experiment), largest P&L first
+pub(crate) fn tag_attribution(period: &str, by_tag: &[TagPerformance]) -> String {
+    let mut text = format!("🏷 <b>Attribution: {}</b>", escape_html(period));
+    if by_tag.is_empty() {
+        text.push_str("
+No tagged trades");
+        return text;
+    }
+    let mut sorted: Vec<&TagPerformance> = by_tag.iter().collect();
+    sorted.sort_by_key(|tag| std::cmp::Reverse(tag.realized_pnl));
+    for tag in sorted {
+        text.push_str(&format!(
+            "\n{} {}: <code>{:+.2}</code> ({} trades, {:.0}% wins{})",
+            if tag.realized_pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+            escape_html(&tag.tag),
+            tag.realized_pnl,
+            tag.trades,
+            tag.win_rate() * Decimal::ONE_HUNDRED,
+            tag.avg_edge
+                .map(|edge| format!(", edge {:.1}%", edge * Decimal::ONE_HUNDRED))
+                .unwrap_or_default(),
+        ));
+    }
+    text
+}
+
+/// Simulation report body: simulated account vs. buy-and-hold
+pub(crate) fn simulation_summary(stats: &SimStats) -> String {
+    let pct = |x: Decimal| x * Decimal::ONE_HUNDRED;
+    let vs = stats.total_return - stats.buy_and_hold_return;
+
+    format!(
+        "🧪 <b>Simulation Report</b>\n\n\
+        💰 Balance: <code>${:.2}</code>\n\
+        Equity: <code>${:.2}</code> ({} open positions)\n\
+        Unrealized PnL: <code>{:+.2}</code>\n\
+        Realized PnL: <code>{:+.2}</code>\n\n\
+        Return: <code>{:+.2}%</code>\n\
+        Buy &amp; Hold: <code>{:+.2}%</code>\n\
+        {} vs. Buy &amp; Hold: <code>{:+.2}%</code>\n\n\
+        Trades: {}",
+        stats.balance,
+        stats.equity,
+        stats.open_positions,
+        stats.unrealized_pnl,
+        stats.realized_pnl,
+        pct(stats.total_return),
+        pct(stats.buy_and_hold_return),
+        if vs >= Decimal::ZERO { "🟢" } else { "🔴" },
+        pct(vs),
+        stats.trades,
+    )
+}
+
+/// `📋 Copy trader list updated: +@alice, -@bob`
+pub(crate) fn follow_list_update(added: &[String], removed: &[String]) -> String {
+    let changes: Vec<String> = added
+        .iter()
+        .map(|u| format!("+@{}", u))
+        .chain(removed.iter().map(|u| format!("-@{}", u)))
+        .collect();
+    format!("📋 Copy trader list updated: {}", changes.join(", "))
+}
+
+/// `⏸️ Stopped copying @alice: 20% of copied trades won, P&L $-42.50`
+pub(crate) fn copy_trader_paused(username: &str, win_rate: f64, total_profit: Decimal) -> String {
+    format!(
+        "⏸️ Stopped copying @{}: {:.0}% of copied trades won, P&L ${:.2}",
+        escape_html(username),
+        win_rate * 100.0,
+        total_profit
+    )
+}
+
+/// `/authors` reply: every author with trust and sample size
+pub(crate) fn author_roster(roster: &[AuthorStanding]) -> String {
+    if roster.is_empty() {
+        return "👥 No signal authors yet".to_string();
+    }
+
+    let mut text = String::from("👥 <b>Signal Authors</b>\n");
+    for a in roster {
+        let flag = if a.pinned {
+            " 📌"
+        } else if a.muted {
+            " 🔇"
+        } else {
+            ""
+        };
+        text.push_str(&format!(
+            "\n{}: <code>{:.2}</code> (n={}){}",
+            escape_html(&a.author),
+            a.trust,
+            a.samples,
+            flag,
+        ));
+    }
+    text
+}
+
+/// `/queue` reply: signals waiting for a free position slot, best first
+pub(crate) fn position_queue(entries: &[QueuedSignal], now: chrono::DateTime<chrono::Utc>) -> String {
+    if entries.is_empty() {
+        return "⏳ No signals waiting for a position slot".to_string();
+    }
+
+    let mut text = format!("⏳ <b>Signal Queue</b> ({})\n", entries.len());
+    for (i, q) in entries.iter().enumerate() {
+        let side = match q.signal.side {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        };
+        text.push_str(&format!(
+            "\n{}. {} {}\n   Net edge <code>{:.1}%</code> · {} · queued {}m ago",
+            i + 1,
+            side,
+            escape_html(&truncate(&q.question, 50)),
+            q.net_edge() * Decimal::ONE_HUNDRED,
+            escape_html(q.signal.strategy_name.as_deref().unwrap_or(crate::storage::DEFAULT_STRATEGY_NAME)),
+            (now - q.queued_at).num_minutes(),
+        ));
+    }
+    text
+}
+
+/// `🔇 Muted signal authors: @bob (0.21, n=34)`
+pub(crate) fn muted_authors(muted: &[AuthorStanding]) -> String {
+    let authors: Vec<String> = muted
+        .iter()
+        .map(|a| format!("{} ({:.2}, n={})", escape_html(&a.author), a.trust, a.samples))
+        .collect();
+    format!("🔇 Muted signal authors: {}", authors.join(", "))
+}
+
+/// `/sources` reply and daily report section: each ingestion source's
+/// messages through the pipeline and when it last heard anything
+pub(crate) fn ingest_sources(stats: &[SourceStats], now: chrono::DateTime<chrono::Utc>) -> String {
+    if stats.is_empty() {
+        return "📡 No signal sources running".to_string();
+    }
+
+    let mut text = "📡 <b>Signal Sources</b>\n".to_string();
+    for s in stats {
+        let last = match s.last_message_at {
+            Some(at) => format!("last message {} ago", elapsed(now - at)),
+            None => "no messages yet".to_string(),
+        };
+        let errors = if s.errors > 0 { format!(" · ⚠️ {} errors", s.errors) } else { String::new() };
+        text += &format!(
+            "\n<b>{}</b>: {} received → {} passed filters → {} to LLM → {} in signals\n   {}{}",
+            escape_html(&s.source),
+            s.received,
+            s.passed_prefilter,
+            s.sent_to_llm,
+            s.contributed,
+            last,
+            errors,
+        );
+    }
+    text
+}
+
+/// Sources that went silent for longer than `threshold`
+pub(crate) fn stale_sources(stale: &[SourceStats], threshold: chrono::Duration) -> String {
+    let names: Vec<String> = stale.iter().map(|s| escape_html(&s.source)).collect();
+    format!(
+        "📡 <b>Signal source silent</b>\n\nNo messages for over {} from: {}",
+        elapsed(threshold),
+        names.join(", ")
+    )
+}
+
+/// `45m`, `5h` or `3d`
+fn elapsed(duration: chrono::Duration) -> String {
+    if duration < chrono::Duration::hours(1) {
+        format!("{}m", duration.num_minutes().max(0))
+    } else if duration < chrono::Duration::hours(48) {
+        format!("{}h", duration.num_hours())
+    } else {
+        format!("{}d", duration.num_days())
+    }
+}
+
+/// A user-defined alert rule that fired
+pub(crate) fn fired_alert(alert: &FiredAlert) -> String {
+    let icon = match alert.severity {
+        AlertSeverity::Warning => "🟡",
+        AlertSeverity::Critical => "🔴",
+    };
+    format!(
+        "{} <b>{}</b>\n\n{}",
+        icon,
+        escape_html(&alert.rule),
+        escape_html(&alert.message)
+    )
+}
+
+pub(crate) fn fired_market_alert(fired: &FiredMarketAlert) -> String {
+    let rule = &fired.alert.rule;
+    let market = if fired.question.is_empty() { &rule.market_id } else { &fired.question };
+    format!(
+        "🔔 <b>Alert #{}</b>\n\n📊 {}\n<code>{}</code>: now <code>{}</code>",
+        fired.alert.id,
+        escape_html(&truncate(market, 60)),
+        escape_html(&rule.condition()),
+        fired.value.round_dp(4).normalize(),
+    )
+}
+
+/// `/alert list` reply
+pub(crate) fn market_alert_list(alerts: &[MarketAlert]) -> String {
+    if alerts.is_empty() {
+        return "🔔 No market alerts\nAdd one with /alert add &lt;market_id&gt; price &gt; 0.70".to_string();
+    }
+    let mut text = format!("🔔 <b>Market Alerts</b> ({})\n", alerts.len());
+    for alert in alerts {
+        text.push_str(&format!(
+            "\n#{} <code>{}</code> {} (cooldown {})",
+            alert.id,
+            escape_html(&alert.rule.market_id),
+            escape_html(&alert.rule.condition()),
+            cooldown(alert.rule.cooldown_secs),
+        ));
+    }
+    text
+}
+
+fn cooldown(secs: u64) -> String {
+    match secs {
+        s if s >= 86400 && s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s >= 3600 && s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s >= 60 && s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+fn health_icon(status: HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Ok => "🟢",
+        HealthStatus::Degraded => "🟡",
+        HealthStatus::Down => "🔴",
+    }
+}
+
+/// `/ladder` reply: locked capital by expected resolution date, and
+/// positions past their end date
+pub(crate) fn capital_ladder(ladder: &CapitalLadder) -> String {
+    let mut text = String::from("🪜 <b>Capital Ladder</b>");
+    if ladder.is_empty() {
+        text.push_str("\nNo open positions");
+        return text;
+    }
+    for rung in &ladder.rungs {
+        text.push_str(&format!(
+            "\n\n<b>{}</b> ({} positions)\n\
+            Locked <code>${:.2}</code> · EV <code>${:.2}</code>\n\
+            Worst <code>${:.2}</code> · Best <code>${:.2}</code>",
+            rung.bucket,
+            rung.positions,
+            rung.locked,
+            rung.expected_value,
+            rung.worst_case,
+            rung.best_case,
+        ));
+    }
+    text.push_str(&format!("\n\nTotal locked: <code>${:.2}</code>", ladder.total_locked()));
+    if !ladder.overdue.is_empty() {
+        text.push_str("\n\n⚠️ <b>Past end date, unresolved</b>");
+        for p in &ladder.overdue {
+            text.push_str(&format!(
+                "\n• {} (ended {}, <code>${:.2}</code>)",
+                escape_html(&truncate(&p.question, 50)),
+                p.end_date.format("%Y-%m-%d"),
+                p.locked,
+            ));
+        }
+    }
+    text
+}
+
+/// `/health` reply: one line per dependency, with details of those not Ok
+pub(crate) fn health_table(results: &[ComponentHealth]) -> String {
+    let mut text = "🩺 <b>Health</b>\n".to_string();
+    for c in results {
+        let latency = c
+            .latency
+            .map(|l| format!("{}ms", l.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        text += &format!(
+            "\n{} <code>{:<10} {:>7}</code>",
+            health_icon(c.status),
+            escape_html(&c.component),
+            latency
+        );
+        if c.status != HealthStatus::Ok {
+            text += &format!("\n    <i>{}</i>", escape_html(&truncate(&c.detail, 80)));
+        }
+    }
+    text
+}
+
+/// Background health check: dependencies whose status changed
+pub(crate) fn health_changes(transitions: &[HealthTransition]) -> String {
+    let mut text = "🩺 <b>Health Changed</b>\n".to_string();
+    for t in transitions {
+        text += &format!(
+            "\n{} {}: {} → {}",
+            health_icon(t.to),
+            escape_html(&t.component),
+            t.from,
+            t.to
+        );
+        if !t.detail.is_empty() {
+            text += &format!("\n    <i>{}</i>", escape_html(&truncate(&t.detail, 80)));
+        }
+    }
+    text
+}
+
+/// Watchdog: tasks whose heartbeat went quiet, and what was done about it
+pub(crate) fn stalled_tasks(stalls: &[Stall], exiting: bool) -> String {
+    let critical = stalls.iter().any(|s| s.action == StallAction::Critical);
+    let mut text = if critical {
+        "🚨 <b>Main loop stalled</b>\n".to_string()
+    } else {
+        "⏱ <b>Tasks stalled</b>\n".to_string()
+    };
+    for stall in stalls {
+        let action = match stall.action {
+            StallAction::Reported => "",
+            StallAction::Restarted => ", restarted",
+            StallAction::Critical => ", trading stopped",
+        };
+        text += &format!(
+            "\n• {}: silent for {}s{}",
+            escape_html(&stall.task),
+            stall.silent_for.as_secs(),
+            action
+        );
+    }
+    if critical && exiting {
+        text += "\n\nExiting for a restart";
+    }
+    text
+}
+
+/// Two-phase execution stopped after the probe slipped too far
+pub(crate) fn probe_slippage(market: &str, actual_bps: Decimal, limit_bps: Decimal) -> String {
+    format!(
+        "⚠️ Slippage too high for {}: {:.1}bps > {:.1}bps",
+        escape_html(market),
+        actual_bps.round_dp(1),
+        limit_bps.round_dp(1)
+    )
+}
+
+/// A crypto position closed between scans by the fast stop
+pub(crate) fn fast_stop_message(exit: &FastStopExit, trade: &Trade, latency: std::time::Duration) -> String {
+    let reason = match exit.reason {
+        FastStopReason::ProbabilityFloor => "win probability below the floor".to_string(),
+        FastStopReason::AdverseMove { bps } => format!("{:.1}bps adverse move", bps.round_dp(1)),
+    };
+    format!(
+        "🛑 <b>Fast stop</b> {} {}\n\n{}: {} at ${:.2}, win probability {:.1}%\nSold {:.2} @ {:.4} in {}ms",
+        escape_html(&exit.position.asset),
+        escape_html(&exit.position.market_id),
+        reason,
+        escape_html(&exit.position.asset),
+        exit.underlying,
+        exit.probability * Decimal::ONE_HUNDRED,
+        trade.size,
+        trade.price,
+        latency.as_millis()
+    )
+}
+
+/// A resting order filled as maker, in the signal token's terms
+pub(crate) fn resting_fill_message(
+    order: &RestingOrder,
+    trade: &Trade,
+    market_question: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let (emoji, action) = match order.signal.side {
+        Side::Buy => ("🟢", "BOUGHT"),
+        Side::Sell => ("🔴", "SOLD"),
+    };
+    format!(
+        "📌 <b>Resting Order Filled</b> (maker)\n\n📊 {}\n\n{} {} {:.2} @ <code>{:.2}</code>\n\
+        Fair value: {:.1}% | Fee: <code>${:.4}</code>\n\
+        Rested {} | Order ID: <code>{}</code>",
+        escape_html(&truncate(market_question, 80)),
+        emoji,
+        action,
+        trade.size,
+        order.price,
+        order.signal.model_probability * Decimal::ONE_HUNDRED,
+        trade.fee,
+        elapsed(now - order.placed_at),
+        escape_html(&trade.order_id),
+    )
+}
+
+/// The active strategy followed a regime change
+pub(crate) fn strategy_switch(switch: &StrategySwitch) -> String {
+    format!(
+        "🔄 Strategy: {} ({:?}, conf={:.0}%)",
+        escape_html(&switch.to),
+        switch.regime,
+        switch.confidence * Decimal::ONE_HUNDRED
+    )
+}
+
+/// Monthly report body: the month's numbers and how they compare with the
+/// benchmarks
+pub(crate) fn monthly_summary(report: &MonthlyReport) -> String {
+    let pct = |x: Decimal| x * Decimal::ONE_HUNDRED;
+    let ratio = |x: Option<Decimal>| x.map(|r| format!("{:.2}", r)).unwrap_or_else(|| "n/a".to_string());
+    let usd = |x: Option<Decimal>| x.map(|v| format!("{:+.2}", v)).unwrap_or_else(|| "-".to_string());
+
+    let mut text = format!(
+        "📅 <b>Monthly Report: {}</b>\n\n\
+        Equity: <code>${:.2}</code> → <code>${:.2}</code>\n\
+        Return: <code>{:+.2}%</code>\n\
+        Sharpe: <code>{}</code> · Sortino: <code>{}</code>\n\
+        Max drawdown: <code>{:.2}%</code>\n\n\
+        Closes: {} · Hit rate: <code>{:.0}%</code>\n\
+        Avg win: <code>{}</code> · Avg loss: <code>{}</code>\n\
+        Fees: <code>${:.2}</code>\n\
+        LLM: {} calls{}",
+        escape_html(&report.period),
+        report.starting_equity,
+        report.ending_equity,
+        pct(report.monthly_return),
+        ratio(report.sharpe),
+        ratio(report.sortino),
+        pct(report.max_drawdown),
+        report.closes,
+        pct(report.hit_rate),
+        usd(report.avg_win),
+        usd(report.avg_loss),
+        report.fees,
+        report.llm_usage.calls,
+        report.llm_spend.map(|spend| format!(", <code>${:.2}</code>", spend)).unwrap_or_default(),
+    );
+
+    text.push_str("\n\n<b>vs. benchmarks</b>");
+    for benchmark in &report.benchmarks {
+        text.push_str(&format!(
+            "\n{} {}: <code>{:+.2}%</code> (excess {:+.2}%)",
+            if report.monthly_return >= benchmark.total_return { "✅" } else { "❌" },
+            escape_html(&benchmark.name),
+            pct(benchmark.total_return),
+            pct(report.monthly_return - benchmark.total_return),
+        ));
+    }
+
+    for (title, lines) in [("By strategy", &report.by_strategy), ("By category", &report.by_category)] {
+        if lines.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\n\n<b>{}</b>", title));
+        for line in lines {
+            text.push_str(&format!(
+                "\n{} {}: <code>{:+.2}</code> ({} closes, {:.0}% wins)",
+                if line.realized_pnl >= Decimal::ZERO { "🟢" } else { "🔴" },
+                escape_html(&line.tag),
+                line.realized_pnl,
+                line.closes,
+                line.win_rate() * Decimal::ONE_HUNDRED,
+            ));
+        }
+    }
+    text
+}
+
+/// Digest of newly discovered markets in the given order, flagging those
+/// in `added` as joining the scan set
+pub(crate) fn discovery_digest(markets: &[DiscoveredMarket], added: &[String]) -> String {
+    let mut text = format!("🆕 <b>New Markets</b> ({})\n", markets.len());
+    for (i, m) in markets.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}. {}\n   Market <code>{:.1}%</code> · Model <code>{:.1}%</code> · Edge <code>{:+.1}%</code> · Conf <code>{:.0}%</code>\n   Vol <code>${:.0}</code> · Liq <code>${:.0}</code>",
+            i + 1,
+            escape_html(&truncate(&m.question, 80)),
+            m.market_price * Decimal::ONE_HUNDRED,
+            m.model_probability * Decimal::ONE_HUNDRED,
+            m.edge() * Decimal::ONE_HUNDRED,
+            m.confidence * Decimal::ONE_HUNDRED,
+            m.volume,
+            m.liquidity,
+        ));
+        if added.contains(&m.market_id) {
+            text.push_str(" · ➕ scanning");
+        }
+        if !m.reasoning.is_empty() {
+            text.push_str(&format!("\n   <i>{}</i>", escape_html(&truncate(&m.reasoning, 120))));
+        }
+    }
+    text
+}
+
+/// `/book` reply: imbalance, spread, trade flow toxicity and icebergs
+pub(crate) fn book_analysis(market_question: &str, analysis: &OrderBookAnalysis) -> String {
+    let mut text = format!(
+        "📖 <b>Order Book</b>\n\n📊 {}\n",
+        escape_html(&truncate(market_question, 60)),
+    );
+
+    match &analysis.imbalance {
+        Some(imbalance) => {
+            let direction = match imbalance.predicted_direction {
+                PredictedDirection::Up => "⬆️ buy pressure",
+                PredictedDirection::Down => "⬇️ sell pressure",
+                PredictedDirection::Neutral => "↔️ balanced",
+            };
+            text.push_str(&format!(
+                "\nImbalance: <code>{:+.2}</code> ({})\n\
+                Depth: <code>{:.0}</code> bid / <code>{:.0}</code> ask\n\
+                Spread: <code>{:.4}</code> / <code>{:.4}</code> ({:.0} bps)\n",
+                imbalance.simple_imbalance,
+                direction,
+                imbalance.total_bid_volume,
+                imbalance.total_ask_volume,
+                imbalance.best_bid,
+                imbalance.best_ask,
+                imbalance.spread_bps,
+            ));
+        }
+        None if analysis.invalid_snapshots > 0 => text.push_str("\nImbalance: n/a (crossed or locked book)\n"),
+        None => text.push_str("\nImbalance: n/a (one side of the book is empty)\n"),
+    }
+
+    match &analysis.vpin {
+        Some(vpin) => text.push_str(&format!(
+            "Toxicity: {:?} (VPIN <code>{:.2}</code>)\n",
+            vpin.toxicity_level, vpin.vpin
+        )),
+        None => text.push_str("Toxicity: n/a (no trade flow yet)\n"),
+    }
+
+    let icebergs = &analysis.iceberg_detection.icebergs;
+    if icebergs.is_empty() {
+        text.push_str("Icebergs: none detected");
+    } else {
+        text.push_str(&format!("Icebergs: {}", icebergs.len()));
+        for iceberg in icebergs {
+            let side = match iceberg.side {
+                TradeSide::Buy => "bid",
+                TradeSide::Sell => "ask",
+            };
+            text.push_str(&format!(
+                "\n  {} <code>{:.4}</code>: ~{:.0} hidden ({} refills)",
+                side, iceberg.price, iceberg.estimated_hidden, iceberg.refill_count,
+            ));
+        }
+    }
+
+    text
+}
+
+/// `/grade` reply: a signal's total score, grade and components
+pub(crate) fn signal_grade(market_id: &str, score: &SignalScore) -> String {
+    let mut text = format!(
+        "🎓 <b>Signal grade {}</b> ({:.2}) for <code>{}</code>\n",
+        score.grade,
+        score.total,
+        escape_html(market_id)
+    );
+    for (name, value) in score.breakdown() {
+        text.push_str(&format!("\n{}: <code>{:.2}</code>", name, value));
+    }
+    text
+}
+
+/// `/analyze` reply: the market dossier, edge by size included
+pub(crate) fn market_dossier(dossier: &MarketDossier) -> String {
+    let pct = |p: Decimal| (p * Decimal::ONE_HUNDRED).round_dp(1);
+    let prices: Vec<String> = dossier
+        .outcomes
+        .iter()
+        .map(|o| format!("{} <code>{}%</code>", escape_html(&o.outcome), pct(o.price)))
+        .collect();
+    let mut text = format!(
+        "🔎 <b>Market Dossier</b>\n\n📊 {}\n<code>{}</code> · {}\n\n{}\n💰 Volume <code>${:.0}</code> · liquidity <code>${:.0}</code>\n",
+        escape_html(&truncate(&dossier.question, 80)),
+        escape_html(&dossier.market_id),
+        dossier.category,
+        prices.join(" · "),
+        dossier.volume,
+        dossier.liquidity,
+    );
+    if let Some(hours) = dossier.hours_to_resolution {
+        text.push_str(&format!("⏳ Resolves in {}d {}h\n", hours / 24, hours % 24));
+    }
+    if let Some(trend) = &dossier.volume_trend {
+        text.push_str(&format!("📦 24h <code>${:.0}</code> / 1wk <code>${:.0}</code>", trend.volume_24h, trend.volume_1wk));
+        if let Some(ratio) = trend.ratio {
+            text.push_str(&format!(" ({}x the daily average)", ratio.round_dp(2).normalize()));
+        }
+        text.push('\n');
+    }
+    if let Some(book) = &dossier.book {
+        if let (Some(bid), Some(ask), Some(spread)) = (book.best_bid, book.best_ask, book.spread) {
+            text.push_str(&format!(
+                "📖 <code>{}</code> / <code>{}</code> (spread <code>{}</code>), depth <code>{:.0}</code> / <code>{:.0}</code>\n",
+                bid, ask, spread, book.bid_depth, book.ask_depth
+            ));
+        } else {
+            text.push_str("📖 One side of the book is empty\n");
+        }
+    }
+
+    let history = &dossier.history;
+    for (label, closes) in [("7d", &history.last_7d), ("30d", &history.last_30d)] {
+        if let (Some(first), Some(last)) = (closes.first(), closes.last()) {
+            text.push_str(&format!("📈 {} {} {}% → {}%\n", label, sparkline(closes, 30), pct(*first), pct(*last)));
+        }
+    }
+    if let Some(vol) = history.realized_volatility {
+        text.push_str(&format!("Realized vol: <code>{}</code> pts/day\n", pct(vol)));
+    }
+
+    for position in &dossier.positions {
+        text.push_str(&format!(
+            "\n💼 Holding <code>{:.2}</code> {} @ <code>{}</code>",
+            position.size,
+            escape_html(&position.outcome),
+            position.avg_price
+        ));
+    }
+    for order in &dossier.open_orders {
+        let side = match order.side {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        };
+        text.push_str(&format!(
+            "\n📌 Resting {} <code>{:.2}</code> {} @ <code>{}</code>",
+            side,
+            order.size,
+            escape_html(&order.outcome),
+            order.price
+        ));
+    }
+    if !dossier.positions.is_empty() || !dossier.open_orders.is_empty() {
+        text.push('\n');
+    }
+
+    if !dossier.comparables.is_empty() {
+        text.push_str("\n<b>Comparable</b>");
+        for comparable in &dossier.comparables {
+            let price = comparable.yes_price.map_or_else(|| "-".to_string(), |p| format!("{}%", pct(p)));
+            let marker = if comparable.same_event { "🔗" } else { "≈" };
+            text.push_str(&format!(
+                "\n{} <code>{}</code> {}",
+                marker,
+                price,
+                escape_html(&truncate(&comparable.question, 50))
+            ));
+        }
+        text.push('\n');
+    }
+
+    if let Some(fair) = dossier.fair_value {
+        text.push_str(&format!(
+            "\n🤖 Model <code>{}%</code> ({}% confidence)\n",
+            pct(fair.probability),
+            pct(fair.confidence)
+        ));
+    }
+    if !dossier.edge_at_size.is_empty() {
+        text.push_str("\n<b>Edge by size</b> (after slippage and fees)");
+        for edge in &dossier.edge_at_size {
+            let Some(avg) = edge.avg_price else {
+                text.push_str(&format!("\n${}: no liquidity", edge.size_usdc));
+                continue;
+            };
+            let net = edge.net_edge.map_or_else(|| "n/a".to_string(), |e| format!("{:+}%", pct(e)));
+            text.push_str(&format!(
+                "\n${}: avg <code>{}</code>, {}% filled, net <code>{}</code>",
+                edge.size_usdc,
+                avg.round_dp(4).normalize(),
+                edge.filled_pct.round_dp(0),
+                net
+            ));
+        }
+    }
+
+    text.trim_end().to_string()
+}
+
+pub(crate) fn model_breaker_tripped(
+    model: &str,
+    fallback: &str,
+    failures: u32,
+    cooldown: std::time::Duration,
+    error: &str,
+) -> String {
+    format!(
+        "🔌 <b>Model {} unavailable</b>\n\n{} failures in a row, last: {}\nUsing {} and retrying in {}s",
+        escape_html(model),
+        failures,
+        escape_html(&truncate(error, 200)),
+        escape_html(fallback),
+        cooldown.as_secs()
+    )
+}
+
+pub(crate) fn model_breaker_recovered(model: &str) -> String {
+    format!("✅ <b>Model {} recovered</b>\nBack to its predictions", escape_html(model))
+}
+
+pub(crate) fn llm_fallback(primary: &str, fallback: &str) -> String {
+    format!("⚠️ LLM fallback: {} failed, using {}", escape_html(primary), escape_html(fallback))
+}
+
+pub(crate) fn llm_primary_restored(provider: &str) -> String {
+    format!("✅ LLM primary restored: {}", escape_html(provider))
+}
+
+pub(crate) fn microstructure_event(event_type: MicrostructureEventType, market: &str) -> String {
+    format!("⚡ Microstructure event: {} at {}", event_type, escape_html(market))
+}
+
+/// `/trace` reply: every pipeline stage behind a trade, oldest first
+pub(crate) fn trace_report(trade_id: &str, events: &[TraceEvent]) -> String {
+    if events.is_empty() {
+        return format!("🔍 No trace recorded for trade <code>{}</code>", escape_html(trade_id));
+    }
+    let mut text = format!(
+        "🔍 <b>Trace</b> <code>{}</code>
+Trade <code>{}</code>
+",
+        events[0].trace_id,
+        escape_html(trade_id)
+    );
+    for event in events {
+        let icon = match event.stage {
+            TraceStage::RawSignal => "📡",
+            TraceStage::ParsedSignal => "🧩",
+            TraceStage::Signal => "🎯",
+            TraceStage::Risk => "🛡",
+            TraceStage::Trade => "💱",
+        };
+        text.push_str(&format!(
+            "
+{} <code>{}</code> {}
+   {}",
+            icon,
+            event.timestamp.format("%m-%d %H:%M:%S"),
+            event.stage.as_str(),
+            escape_html(&truncate(&event.detail, 160))
+        ));
+    }
+    text
+}
+
+/// `/profile` reply: a market's microstructure statistics
+pub(crate) fn market_profile_report(profile: &MarketProfile) -> String {
+    let latency = profile
+        .median_fill_latency_secs
+        .map_or("n/a".to_string(), |secs| format!("{}s", secs));
+    let mm_activity = profile.mm_activity.map_or("n/a".to_string(), |level| format!("{:?}", level));
+    format!(
+        "🧬 <b>Profile</b> <code>{}</code>
+Median spread: {:.4}
+Median top depth: {:.0} shares
+Quote updates: {}/min
+Fill latency: {}
+MM activity: {}
+<i>{} samples, updated {}</i>",
+        escape_html(&profile.market_id),
+        profile.median_spread,
+        profile.median_top_depth,
+        profile.quote_updates_per_min,
+        latency,
+        mm_activity,
+        profile.samples,
+        profile.updated_at.format("%Y-%m-%d %H:%M UTC")
+    )
+}
+
+/// Summary of a redemption of resolved winnings
+pub(crate) fn redemption_summary(redemption: &Redemption) -> String {
+    let receipt = &redemption.receipt;
+    format!(
+        "💵 <b>Redeemed</b>
+{}
+Shares: {:.2}
+Proceeds: <code>${:.2}</code>
+Gas: <code>${:.4}</code>
+Net: <code>${:.2}</code>
+<i>tx <code>{}</code></i>",
+        escape_html(&truncate(&redemption.question, 100)),
+        redemption.shares,
+        receipt.proceeds,
+        receipt.gas_cost,
+        (receipt.proceeds - receipt.gas_cost).round_dp(2),
+        escape_html(&receipt.tx_hash)
+    )
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub(super) fn truncate(s: &str, max_len: usize) -> String {
     if s.len() > max_len {
-        format!("{}...", &s[..max_len])
+        let mut end = max_len;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &s[..end])
     } else {
         s.to_string()
     }
@@ -0,0 +1,313 @@
+//! Persistent queue of outbound notifications
+//!
+//! With an outbox, [`Notifier`] queues each message in the `outbox` table
+//! instead of posting it, and a background task delivers the queue with
+//! retries and exponential backoff, so a brief Telegram outage delays
+//! messages instead of losing them. A message identical to one queued in
+//! the last [`DEDUP_WINDOW_SECS`] is dropped. Critical messages (trades,
+//! risk limits, errors) are retried until delivered, across restarts; the
+//! rest are given up after [`MAX_ATTEMPTS`] failures or on restart.
+
+use super::Notifier;
+use crate::error::Result;
+use crate::monitor::Heartbeat;
+use crate::storage::Database;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Identical messages queued within this many seconds are sent once
+pub const DEDUP_WINDOW_SECS: i64 = 300;
+
+/// Failed deliveries after which a non-critical message is dropped
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Messages posted per delivery pass
+const DELIVERY_BATCH: usize = 20;
+
+const BASE_BACKOFF_SECS: i64 = 5;
+const MAX_BACKOFF_SECS: i64 = 600;
+
+/// How much a notification matters; higher is delivered first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    /// Retried until delivered, even across restarts
+    Critical,
+}
+
+impl Priority {
+    /// Stored rank, ordered like the priorities
+    pub fn rank(self) -> i64 {
+        match self {
+            Self::Low => 0,
+            Self::Normal => 1,
+            Self::Critical => 2,
+        }
+    }
+
+    pub fn from_rank(rank: i64) -> Self {
+        match rank {
+            i64::MIN..=0 => Self::Low,
+            1 => Self::Normal,
+            _ => Self::Critical,
+        }
+    }
+}
+
+/// Delay before retrying a message that failed `attempts` times: 5s
+/// doubling per attempt, at most 10 minutes
+pub fn backoff(attempts: u32) -> Duration {
+    let secs = BASE_BACKOFF_SECS.saturating_mul(1 << attempts.saturating_sub(1).min(16));
+    Duration::seconds(secs.min(MAX_BACKOFF_SECS))
+}
+
+/// Outbox queue depth and failed deliveries since startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutboxStats {
+    pub depth: u64,
+    pub delivery_failures: u64,
+}
+
+pub(super) struct Outbox {
+    db: Arc<Database>,
+    depth: AtomicU64,
+    delivery_failures: AtomicU64,
+}
+
+impl Outbox {
+    /// Queue `text` unless the same message was queued within the dedup
+    /// window; returns whether it was queued
+    pub(super) async fn enqueue(&self, text: &str, parse_mode: &str, priority: Priority, now: DateTime<Utc>) -> Result<bool> {
+        let hash = hex::encode(Sha256::digest(format!("{}\n{}", parse_mode, text).as_bytes()));
+        let dedup_since = now - Duration::seconds(DEDUP_WINDOW_SECS);
+        let queued = self.db.enqueue_notification(&hash, priority, text, parse_mode, now, dedup_since).await?;
+        if queued {
+            self.depth.fetch_add(1, Ordering::Relaxed);
+        } else {
+            tracing::debug!("Dropping duplicate notification");
+        }
+        Ok(queued)
+    }
+}
+
+impl Notifier {
+    /// Queue messages in `db` and deliver them with retries from
+    /// [`Notifier::spawn_outbox_delivery`]. Non-critical messages left
+    /// undelivered by a previous run are dropped; critical ones are kept.
+    /// A disabled notifier is returned unchanged.
+    pub async fn with_outbox(mut self, db: Arc<Database>) -> Result<Self> {
+        if !self.enabled {
+            return Ok(self);
+        }
+        let stale = db.drop_notifications_below(Priority::Critical).await?;
+        if stale > 0 {
+            tracing::info!("Dropped {} stale notifications from before the restart", stale);
+        }
+        let depth = db.pending_notification_count().await?;
+        self.outbox = Some(Arc::new(Outbox {
+            db,
+            depth: AtomicU64::new(depth),
+            delivery_failures: AtomicU64::new(0),
+        }));
+        Ok(self)
+    }
+
+    /// Queue depth and delivery failures; zero without an outbox
+    pub fn outbox_stats(&self) -> OutboxStats {
+        self.outbox
+            .as_ref()
+            .map(|outbox| OutboxStats {
+                depth: outbox.depth.load(Ordering::Relaxed),
+                delivery_failures: outbox.delivery_failures.load(Ordering::Relaxed),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Post the queued messages due by `now`, most important first. A
+    /// transient failure reschedules the message with backoff and ends the
+    /// pass; Telegram rejecting a message outright drops it. Returns how
+    /// many were delivered.
+    pub async fn deliver_outbox(&self, now: DateTime<Utc>) -> Result<usize> {
+        let Some(outbox) = &self.outbox else {
+            return Ok(0);
+        };
+        let mut delivered = 0;
+        for message in outbox.db.due_notifications(now, DELIVERY_BATCH).await? {
+            let (error, permanent) = match self.post(&message.text, &message.parse_mode).await {
+                Ok(response) if response.status().is_success() => {
+                    outbox.db.mark_notification_delivered(message.id, now).await?;
+                    delivered += 1;
+                    continue;
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let permanent = status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS;
+                    let body = response.text().await.unwrap_or_default();
+                    (format!("Telegram returned {}: {}", status, body), permanent)
+                }
+                Err(e) => (e.to_string(), false),
+            };
+            outbox.delivery_failures.fetch_add(1, Ordering::Relaxed);
+            let attempts = message.attempts + 1;
+            if permanent || (message.priority < Priority::Critical && attempts >= MAX_ATTEMPTS) {
+                tracing::error!("Dropping notification after {} attempts: {}", attempts, error);
+                outbox.db.drop_notification(message.id).await?;
+                continue;
+            }
+            tracing::warn!("Notification delivery failed (attempt {}): {}", attempts, error);
+            outbox.db.reschedule_notification(message.id, attempts, now + backoff(attempts)).await?;
+            break;
+        }
+        outbox
+            .db
+            .prune_delivered_notifications(now - Duration::seconds(DEDUP_WINDOW_SECS))
+            .await?;
+        outbox.depth.store(outbox.db.pending_notification_count().await?, Ordering::Relaxed);
+        Ok(delivered)
+    }
+
+    /// Deliver the outbox every `interval` in the background; `None`
+    /// without an outbox
+    pub fn spawn_outbox_delivery(&self, interval: std::time::Duration) -> Option<tokio::task::JoinHandle<()>> {
+        self.outbox.as_ref()?;
+        Some(tokio::spawn(self.clone().deliver_outbox_every(interval, None)))
+    }
+
+    /// Deliver the outbox every `interval`, forever, beating `heartbeat`
+    /// after each pass
+    pub async fn deliver_outbox_every(self, interval: std::time::Duration, heartbeat: Option<Arc<Heartbeat>>) {
+        loop {
+            if let Err(e) = self.deliver_outbox(self.clock.now()).await {
+                tracing::warn!("Failed to deliver notifications: {}", e);
+            }
+            if let Some(heartbeat) = &heartbeat {
+                heartbeat.beat();
+            }
+            self.clock.sleep(interval).await;
+        }
+    }
+
+    /// Whether messages go through an outbox
+    pub fn has_outbox(&self) -> bool {
+        self.outbox.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Telegram API that fails the first `failures` sends with a 502, then
+    /// accepts; returns its URL and the texts it accepted
+    async fn mock_telegram(failures: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        use axum::{http::StatusCode, routing::post, Json, Router};
+
+        let accepted = Arc::new(Mutex::new(Vec::new()));
+        let seen = accepted.clone();
+        let remaining = Arc::new(Mutex::new(failures));
+        let app = Router::new().route(
+            "/bottoken/sendMessage",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let (seen, remaining) = (seen.clone(), remaining.clone());
+                async move {
+                    let mut remaining = remaining.lock();
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        return StatusCode::BAD_GATEWAY;
+                    }
+                    seen.lock().push(body["text"].as_str().unwrap_or_default().to_string());
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), accepted)
+    }
+
+    async fn notifier(url: &str, db: Arc<Database>) -> Notifier {
+        Notifier::new("token".to_string(), "chat".to_string())
+            .with_api_url(url)
+            .with_outbox(db)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_a_cap() {
+        assert_eq!(backoff(1), Duration::seconds(5));
+        assert_eq!(backoff(2), Duration::seconds(10));
+        assert_eq!(backoff(4), Duration::seconds(40));
+        assert_eq!(backoff(30), Duration::seconds(600));
+        assert_eq!(Priority::from_rank(Priority::Critical.rank()), Priority::Critical);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried_with_backoff() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::connect(dir.path().join("test.db")).await.unwrap());
+        let (url, accepted) = mock_telegram(2).await;
+        let notifier = notifier(&url, db).await;
+
+        notifier.send_with_priority("<b>filled</b>", Priority::Critical).await.unwrap();
+        // Identical messages within the window are sent once
+        notifier.send_with_priority("<b>filled</b>", Priority::Critical).await.unwrap();
+        assert_eq!(notifier.outbox_stats(), OutboxStats { depth: 1, delivery_failures: 0 });
+
+        let now = Utc::now();
+        assert_eq!(notifier.deliver_outbox(now).await.unwrap(), 0);
+        // Not due again until the backoff has passed
+        assert_eq!(notifier.deliver_outbox(now + Duration::seconds(4)).await.unwrap(), 0);
+        assert_eq!(notifier.deliver_outbox(now + Duration::seconds(5)).await.unwrap(), 0);
+        assert_eq!(notifier.outbox_stats(), OutboxStats { depth: 1, delivery_failures: 2 });
+
+        assert_eq!(notifier.deliver_outbox(now + Duration::seconds(15)).await.unwrap(), 1);
+        assert_eq!(*accepted.lock(), ["<b>filled</b>"]);
+        assert_eq!(notifier.outbox_stats().depth, 0);
+    }
+
+    #[tokio::test]
+    async fn test_critical_messages_survive_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        let (url, _) = mock_telegram(usize::MAX).await;
+        {
+            let notifier = notifier(&url, Arc::new(Database::connect(&path).await.unwrap())).await;
+            notifier.send_with_priority("trade executed", Priority::Critical).await.unwrap();
+            notifier.send("signal found").await.unwrap();
+            // Telegram is down for the rest of this run
+            notifier.deliver_outbox(Utc::now()).await.unwrap();
+            assert_eq!(notifier.outbox_stats().depth, 2);
+        }
+
+        let (url, accepted) = mock_telegram(0).await;
+        let restarted = notifier(&url, Arc::new(Database::connect(&path).await.unwrap())).await;
+        assert_eq!(restarted.outbox_stats().depth, 1);
+        assert_eq!(restarted.deliver_outbox(Utc::now() + Duration::minutes(1)).await.unwrap(), 1);
+        assert_eq!(*accepted.lock(), ["trade executed"]);
+        assert_eq!(restarted.outbox_stats().depth, 0);
+    }
+
+    #[tokio::test]
+    async fn test_non_critical_messages_are_dropped_after_max_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::connect(dir.path().join("test.db")).await.unwrap());
+        let (url, _) = mock_telegram(usize::MAX).await;
+        let notifier = notifier(&url, db).await;
+        notifier.send_with_priority("crypto status", Priority::Low).await.unwrap();
+
+        let mut now = Utc::now();
+        for _ in 0..MAX_ATTEMPTS {
+            notifier.deliver_outbox(now).await.unwrap();
+            now += Duration::minutes(10);
+        }
+
+        assert_eq!(notifier.outbox_stats(), OutboxStats { depth: 0, delivery_failures: MAX_ATTEMPTS as u64 });
+    }
+}
@@ -0,0 +1,233 @@
+//! Slack notifications through an incoming webhook
+//!
+//! Trades, signals and daily reports are posted as block-kit cards: a
+//! header, the market in a section and the numbers as section fields.
+//! Errors go out as plain text, so they still arrive if a card can't be
+//! built or rendered.
+
+use super::{truncate, NotifierBackend};
+use crate::error::{BotError, Result};
+use crate::monitor::PerformanceStats;
+use crate::types::{Side, Signal, Trade, TradeStatus};
+use crate::utils::{http_client, HTTP_TIMEOUT};
+use async_trait::async_trait;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+/// Posts to a Slack incoming-webhook URL
+#[derive(Debug, Clone)]
+pub struct SlackNotifier {
+    http: Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            http: http_client(HTTP_TIMEOUT),
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+
+    async fn post(&self, payload: &Value) -> Result<()> {
+        let response = self.http.post(&self.webhook_url).json(payload).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::Api(format!("Slack webhook returned {}: {}", status, body)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NotifierBackend for SlackNotifier {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn signal_found(&self, signal: &Signal, market_question: &str) -> Result<()> {
+        self.post(&signal_card(signal, market_question)).await
+    }
+
+    async fn trade_executed(&self, trade: &Trade, market_question: &str) -> Result<()> {
+        self.post(&trade_card(trade, market_question)).await
+    }
+
+    async fn daily_report(&self, stats: &PerformanceStats, balance: Decimal) -> Result<()> {
+        self.post(&daily_report_card(stats, balance)).await
+    }
+
+    async fn error(&self, context: &str, error: &str) -> Result<()> {
+        self.post(&json!({ "text": format!("⚠️ Error in {}: {}", context, truncate(error, 200)) })).await
+    }
+}
+
+fn side_label(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "🟢 BUY",
+        Side::Sell => "🔴 SELL",
+    }
+}
+
+/// Block-kit message: `title` as the header and notification text, the
+/// market question in a section, then `fields` as label/value pairs
+fn card(title: &str, market_question: Option<&str>, fields: &[(&str, String)], context: Option<String>) -> Value {
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": title, "emoji": true },
+    })];
+    if let Some(question) = market_question {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*{}*", truncate(question, 150)) },
+        }));
+    }
+    blocks.push(json!({
+        "type": "section",
+        "fields": fields
+            .iter()
+            .map(|(label, value)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", label, value) }))
+            .collect::<Vec<_>>(),
+    }));
+    if let Some(context) = context {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": context }],
+        }));
+    }
+    json!({ "text": title, "blocks": blocks })
+}
+
+fn trade_card(trade: &Trade, market_question: &str) -> Value {
+    let title = if trade.status == TradeStatus::Resting { "📌 Order Resting" } else { "✅ Trade Executed" };
+    card(
+        title,
+        Some(market_question),
+        &[
+            ("Side", side_label(trade.side).to_string()),
+            ("Price", format!("${:.4}", trade.price)),
+            ("Size", format!("${:.2}", trade.size)),
+            ("Fee", format!("${:.4}", trade.fee)),
+        ],
+        Some(format!("Order `{}`", trade.order_id)),
+    )
+}
+
+fn signal_card(signal: &Signal, market_question: &str) -> Value {
+    card(
+        "🎯 Signal Found",
+        Some(market_question),
+        &[
+            ("Side", side_label(signal.side).to_string()),
+            ("Edge", format!("{:+.1}%", signal.edge * Decimal::ONE_HUNDRED)),
+            ("Model", format!("{:.1}%", signal.model_probability * Decimal::ONE_HUNDRED)),
+            ("Market", format!("{:.1}%", signal.market_probability * Decimal::ONE_HUNDRED)),
+            ("Confidence", format!("{:.0}%", signal.confidence * Decimal::ONE_HUNDRED)),
+        ],
+        signal.strategy_name.as_ref().map(|s| format!("Strategy: {}", s)),
+    )
+}
+
+fn daily_report_card(stats: &PerformanceStats, balance: Decimal) -> Value {
+    card(
+        "📊 Daily Report",
+        None,
+        &[
+            ("Balance", format!("${:.2}", balance)),
+            ("PnL", format!("{:+.2}", stats.total_pnl)),
+            ("Trades", stats.total_trades.to_string()),
+            ("Win Rate", format!("{:.1}%", stats.win_rate * Decimal::ONE_HUNDRED)),
+            ("Avg PnL/Trade", format!("{:+.2}", stats.avg_pnl_per_trade)),
+        ],
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TraceId;
+    use chrono::Utc;
+    use parking_lot::Mutex;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    /// Slack webhook recording the payloads it receives; returns its URL
+    async fn mock_webhook() -> (String, Arc<Mutex<Vec<Value>>>) {
+        use axum::{routing::post, Json, Router};
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let seen = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<Value>| {
+                let seen = seen.clone();
+                async move {
+                    seen.lock().push(body);
+                    "ok"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), received)
+    }
+
+    fn trade() -> Trade {
+        Trade {
+            id: "t1".to_string(),
+            order_id: "0xabcdef0123456789".to_string(),
+            market_id: "m1".to_string(),
+            token_id: "yes".to_string(),
+            side: Side::Buy,
+            price: dec!(0.42),
+            size: dec!(25),
+            fee: dec!(0.05),
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            edge: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trade_executed_posts_block_kit_card() {
+        let (url, received) = mock_webhook().await;
+        let slack = SlackNotifier::new(&url);
+        slack.trade_executed(&trade(), "Will it rain?").await.unwrap();
+
+        let payload = received.lock()[0].clone();
+        assert_eq!(payload["text"], "✅ Trade Executed");
+        let blocks = payload["blocks"].as_array().unwrap();
+        let types: Vec<_> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+        assert_eq!(types, ["header", "section", "section", "context"]);
+        assert_eq!(blocks[0]["text"]["type"], "plain_text");
+        assert_eq!(blocks[1]["text"]["text"], "*Will it rain?*");
+        let fields: Vec<_> = blocks[2]["fields"].as_array().unwrap().iter().map(|f| f["text"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["*Side*\n🟢 BUY", "*Price*\n$0.4200", "*Size*\n$25.00", "*Fee*\n$0.0500"]);
+        assert!(blocks[2]["fields"].as_array().unwrap().iter().all(|f| f["type"] == "mrkdwn"));
+        assert_eq!(blocks[3]["elements"][0]["text"], "Order `0xabcdef0123456789`");
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_plain_text() {
+        let (url, received) = mock_webhook().await;
+        let slack = SlackNotifier::new(&url);
+        slack.error("order placement", "HTTP 503").await.unwrap();
+
+        let payload = received.lock()[0].clone();
+        assert_eq!(payload, json!({ "text": "⚠️ Error in order placement: HTTP 503" }));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_webhook_is_an_error() {
+        let slack = SlackNotifier::new("http://127.0.0.1:9/hook");
+        assert!(slack.error("x", "y").await.is_err());
+    }
+}
@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use super::super::Notifier;
-    use crate::types::{Signal, Side, Trade};
+    use crate::types::{Signal, Side, Trade, TradeStatus};
     use crate::monitor::PerformanceStats;
     use chrono::Utc;
     use rust_decimal_macros::dec;
@@ -41,6 +41,13 @@ mod tests {
             confidence: dec!(0.80),
             suggested_size: dec!(100),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
         };
         
         assert_eq!(signal.side, Side::Buy);
@@ -59,6 +66,13 @@ mod tests {
             confidence: dec!(0.75),
             suggested_size: dec!(50),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
         };
         
         assert_eq!(signal.side, Side::Sell);
@@ -76,6 +90,12 @@ mod tests {
             size: dec!(100),
             fee: dec!(0.50),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
         
         assert_eq!(trade.price, dec!(0.55));
@@ -152,6 +172,13 @@ mod tests {
             confidence: dec!(0.90),
             suggested_size: dec!(200),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
         };
         
         assert!(signal.confidence >= dec!(0.90));
@@ -169,6 +196,13 @@ mod tests {
             confidence: dec!(0.50),
             suggested_size: dec!(20),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
         };
         
         assert!(signal.confidence <= dec!(0.50));
@@ -188,4 +222,852 @@ mod tests {
         let result = notifier.send_raw("test message").await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_strategy_attribution() {
+        use crate::notify::strategy_attribution;
+        use crate::storage::StrategyPnl;
+
+        let pnl = |strategy: &str, realized_pnl, trades| StrategyPnl {
+            strategy: strategy.to_string(),
+            trades,
+            realized_pnl,
+            ..Default::default()
+        };
+
+        // Nothing to attribute with a single strategy
+        assert!(strategy_attribution(&[pnl("main", dec!(5), 3)]).is_empty());
+
+        let text = strategy_attribution(&[pnl("llm", dec!(12.5), 4), pnl("momentum", dec!(-3), 2)]);
+        assert!(text.contains("By Strategy"));
+        assert!(text.contains("llm: <code>+12.50</code> (4 trades)"));
+        assert!(text.contains("momentum: <code>-3.00</code> (2 trades)"));
+    }
+
+    #[test]
+    fn test_account_attribution() {
+        use crate::notify::account_attribution;
+        use crate::storage::AccountPnl;
+
+        let pnl = |account: &str, realized_pnl, trades| AccountPnl {
+            account: account.to_string(),
+            trades,
+            realized_pnl,
+            ..Default::default()
+        };
+
+        assert!(account_attribution(&[pnl("default", dec!(5), 3)]).is_empty());
+
+        let text = account_attribution(&[pnl("fund", dec!(-2), 1), pnl("personal", dec!(7.25), 5)]);
+        assert!(text.contains("By Account"));
+        assert!(text.contains("🔴 fund: <code>-2.00</code> (1 trades)"));
+        assert!(text.contains("🟢 personal: <code>+7.25</code> (5 trades)"));
+    }
+
+    #[test]
+    fn test_discovery_digest() {
+        use crate::notify::discovery_digest;
+        use crate::storage::DiscoveredMarket;
+
+        let market = |id: &str, price, model| DiscoveredMarket {
+            market_id: id.to_string(),
+            question: format!("Will {} happen?", id),
+            discovered_at: Utc::now(),
+            volume: dec!(52000),
+            liquidity: dec!(8000),
+            market_price: price,
+            model_probability: model,
+            confidence: dec!(0.7),
+            reasoning: "llm: 62%".to_string(),
+        };
+        let text = discovery_digest(
+            &[market("a&b", dec!(0.40), dec!(0.62)), market("c", dec!(0.80), dec!(0.71))],
+            &["a&b".to_string()],
+        );
+
+        assert!(text.starts_with("🆕 <b>New Markets</b> (2)"));
+        assert!(text.contains("1. Will a&amp;b happen?\n   Market <code>40.0%</code> · Model <code>62.0%</code> · Edge <code>+22.0%</code>"));
+        assert!(text.contains("Vol <code>$52000</code> · Liq <code>$8000</code> · ➕ scanning"));
+        assert!(text.contains("2. Will c happen?"));
+        assert!(text.contains("Edge <code>-9.0%</code>"));
+        assert_eq!(text.matches("➕ scanning").count(), 1);
+        assert!(text.contains("<i>llm: 62%</i>"));
+    }
+
+    #[test]
+    fn test_probe_slippage_warning() {
+        use crate::notify::probe_slippage;
+
+        assert_eq!(
+            probe_slippage("Will BTC hit 100k?", dec!(123.456), dec!(50)),
+            "⚠️ Slippage too high for Will BTC hit 100k?: 123.5bps > 50.0bps"
+        );
+    }
+
+    #[test]
+    fn test_fast_stop_message() {
+        use crate::notify::fast_stop_message;
+        use crate::risk::{FastStopExit, FastStopPosition, FastStopReason, UpDown};
+
+        let now = Utc::now();
+        let exit = FastStopExit {
+            position: FastStopPosition {
+                market_id: "btc-updown-15m".to_string(),
+                token_id: "up".to_string(),
+                asset: "BTC".to_string(),
+                direction: UpDown::Up,
+                strike: dec!(100000),
+                expires_at: now,
+                volatility: dec!(0.5),
+                opened_at: now,
+            },
+            reason: FastStopReason::AdverseMove { bps: dec!(34.99) },
+            probability: dec!(0.442),
+            underlying: dec!(99969),
+            triggered_at: now,
+        };
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "up".to_string(),
+            market_id: "btc-updown-15m".to_string(),
+            side: Side::Sell,
+            price: dec!(0.41),
+            size: dec!(25),
+            fee: dec!(0),
+            timestamp: now,
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        let text = fast_stop_message(&exit, &trade, std::time::Duration::from_millis(212));
+        assert!(text.starts_with("🛑 <b>Fast stop</b> BTC btc-updown-15m"));
+        assert!(text.contains("35.0bps adverse move: BTC at $99969.00, win probability 44.2%"));
+        assert!(text.ends_with("Sold 25.00 @ 0.4100 in 212ms"));
+    }
+
+    #[test]
+    fn test_resting_fill_message() {
+        use crate::notify::resting_fill_message;
+        use crate::strategy::RestingOrder;
+
+        let now = Utc::now();
+        let signal = Signal {
+            market_id: "m1".to_string(),
+            token_id: "yes".to_string(),
+            side: Side::Sell,
+            model_probability: dec!(0.35),
+            market_probability: dec!(0.45),
+            edge: dec!(-0.10),
+            confidence: dec!(0.80),
+            suggested_size: dec!(0.02),
+            timestamp: now,
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
+        };
+        // Sells rest as bids on the complement token
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "order-1234".to_string(),
+            token_id: "no".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.63),
+            size: dec!(40),
+            fee: dec!(0),
+            timestamp: now,
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        let order = RestingOrder {
+            signal,
+            trade: trade.clone(),
+            price: dec!(0.37),
+            placed_at: now - chrono::Duration::hours(5),
+        };
+        let text = resting_fill_message(&order, &trade, "Will <X> happen?", now);
+        assert!(text.starts_with("📌 <b>Resting Order Filled</b> (maker)"));
+        assert!(text.contains("Will &lt;X&gt; happen?"));
+        assert!(text.contains("🔴 SOLD 40.00 @ <code>0.37</code>"));
+        assert!(text.contains("Fair value: 35.0%"));
+        assert!(text.ends_with("Rested 5h | Order ID: <code>order-1234</code>"));
+    }
+
+    #[test]
+    fn test_strategy_switch_message() {
+        use crate::notify::strategy_switch;
+        use crate::regime::MarketRegime;
+        use crate::strategy::StrategySwitch;
+
+        let switch = StrategySwitch {
+            from: "default".to_string(),
+            to: "momentum".to_string(),
+            regime: MarketRegime::BullishTrend,
+            confidence: dec!(0.82),
+        };
+        assert_eq!(strategy_switch(&switch), "🔄 Strategy: momentum (BullishTrend, conf=82%)");
+    }
+
+    #[test]
+    fn test_monthly_summary() {
+        use crate::notify::monthly_summary;
+        use crate::report::{hold_usdc, BenchmarkResult, MonthlyReport};
+        use crate::storage::{LlmUsageTotals, TagPerformance};
+        use chrono::{TimeZone, Utc};
+
+        let favorite = BenchmarkResult {
+            name: "Buy favorite".to_string(),
+            total_return: dec!(0.05),
+            ..hold_usdc(dec!(1000))
+        };
+        let mut report = MonthlyReport {
+            period: "2024-06".to_string(),
+            from: Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+            to: Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap(),
+            starting_equity: dec!(1000),
+            ending_equity: dec!(1034),
+            monthly_return: dec!(0.034),
+            daily_returns: Vec::new(),
+            sharpe: Some(dec!(1.87)),
+            sortino: None,
+            max_drawdown: dec!(0.021),
+            closes: 8,
+            wins: 6,
+            hit_rate: dec!(0.75),
+            avg_win: Some(dec!(12.5)),
+            avg_loss: Some(dec!(-9.5)),
+            realized_pnl: dec!(34),
+            fees: dec!(3.2),
+            llm_usage: LlmUsageTotals { calls: 420, input_tokens: 0, output_tokens: 0 },
+            llm_spend: Some(dec!(4.18)),
+            by_strategy: vec![TagPerformance {
+                tag: "llm".to_string(),
+                trades: 10,
+                closes: 8,
+                wins: 6,
+                realized_pnl: dec!(34),
+                avg_edge: None,
+            }],
+            by_category: Vec::new(),
+            benchmarks: vec![hold_usdc(dec!(1000)), favorite],
+        };
+        let text = monthly_summary(&report);
+        assert!(text.starts_with("📅 <b>Monthly Report: 2024-06</b>"));
+        assert!(text.contains("Return: <code>+3.40%</code>"));
+        assert!(text.contains("Sharpe: <code>1.87</code> · Sortino: <code>n/a</code>"));
+        assert!(text.contains("Closes: 8 · Hit rate: <code>75%</code>"));
+        assert!(text.contains("Avg win: <code>+12.50</code> · Avg loss: <code>-9.50</code>"));
+        assert!(text.contains("LLM: 420 calls, <code>$4.18</code>"));
+        assert!(text.contains("✅ Hold USDC: <code>+0.00%</code> (excess +3.40%)"));
+        assert!(text.contains("❌ Buy favorite: <code>+5.00%</code> (excess -1.60%)"));
+        assert!(text.contains("🟢 llm: <code>+34.00</code> (8 closes, 75% wins)"));
+        assert!(!text.contains("By category"));
+
+        report.llm_spend = None;
+        assert!(monthly_summary(&report).contains("LLM: 420 calls\n"));
+    }
+
+    #[test]
+    fn test_fired_alert_severity_icons() {
+        use crate::config::AlertSeverity;
+        use crate::monitor::FiredAlert;
+        use crate::notify::fired_alert;
+
+        let alert = |severity| FiredAlert {
+            rule: "P&L < limit".to_string(),
+            severity,
+            message: "daily_pnl < -50".to_string(),
+        };
+
+        assert_eq!(
+            fired_alert(&alert(AlertSeverity::Warning)),
+            "🟡 <b>P&amp;L &lt; limit</b>\n\ndaily_pnl &lt; -50"
+        );
+        assert!(fired_alert(&alert(AlertSeverity::Critical)).starts_with("🔴 "));
+    }
+
+    #[test]
+    fn test_capital_ladder() {
+        use crate::notify::capital_ladder;
+        use crate::portfolio::{CapitalLadder, LadderBucket, LadderRung, OverduePosition};
+
+        assert!(capital_ladder(&CapitalLadder::default()).contains("No open positions"));
+
+        let ladder = CapitalLadder {
+            rungs: vec![LadderRung {
+                bucket: LadderBucket::ThisMonth,
+                positions: 2,
+                locked: dec!(30),
+                expected_value: dec!(31),
+                best_case: dec!(40),
+                worst_case: dec!(10),
+            }],
+            overdue: vec![OverduePosition {
+                market_id: "m1".to_string(),
+                question: "Will A & B sign?".to_string(),
+                end_date: "2026-10-01T00:00:00Z".parse().unwrap(),
+                locked: dec!(5),
+            }],
+        };
+        let text = capital_ladder(&ladder);
+        assert!(text.contains("<b>This month</b> (2 positions)"));
+        assert!(text.contains("Locked <code>$30.00</code> · EV <code>$31.00</code>"));
+        assert!(text.contains("Worst <code>$10.00</code> · Best <code>$40.00</code>"));
+        assert!(text.contains("• Will A &amp; B sign? (ended 2026-10-01, <code>$5.00</code>)"));
+    }
+
+    #[test]
+    fn test_position_queue() {
+        use crate::notify::position_queue;
+        use crate::risk::QueuedSignal;
+        use crate::types::{Side, Signal};
+
+        let now = chrono::Utc::now();
+        assert!(position_queue(&[], now).contains("No signals waiting"));
+
+        let queued = QueuedSignal {
+            signal: Signal {
+                market_id: "m1".to_string(),
+                token_id: "yes1".to_string(),
+                side: Side::Sell,
+                model_probability: dec!(0.30),
+                market_probability: dec!(0.45),
+                edge: dec!(-0.15),
+                confidence: dec!(0.8),
+                suggested_size: dec!(0.02),
+                timestamp: now,
+                strategy_name: Some("llm".to_string()),
+                epistemic_uncertainty: dec!(0.03),
+                trace_id: Default::default(),
+                tags: Vec::new(),
+                posterior_variance: dec!(0),
+                valid_until: None,
+                signal_score: None,
+            },
+            question: "Will A & B sign?".to_string(),
+            queued_at: now - chrono::Duration::minutes(12),
+        };
+        let text = position_queue(&[queued], now);
+        assert!(text.contains("<b>Signal Queue</b> (1)"));
+        assert!(text.contains("1. SELL Will A &amp; B sign?"));
+        assert!(text.contains("Net edge <code>12.0%</code> · llm · queued 12m ago"));
+    }
+
+    #[test]
+    fn test_health_table() {
+        use crate::monitor::{ComponentHealth, HealthStatus, HealthTransition};
+        use crate::notify::{health_changes, health_table};
+        use std::time::Duration;
+
+        let component = |name: &str, status, latency_ms: Option<u64>, detail: &str| ComponentHealth {
+            component: name.to_string(),
+            status,
+            latency: latency_ms.map(Duration::from_millis),
+            detail: detail.to_string(),
+            hint: None,
+        };
+        let text = health_table(&[
+            component("gamma", HealthStatus::Ok, Some(142), "markets listed"),
+            component("llm", HealthStatus::Degraded, Some(2310), "slow: 2310 ms"),
+            component("disk", HealthStatus::Down, None, "12 MB free at <data>"),
+        ]);
+
+        assert!(text.contains("🟢 <code>gamma        142ms</code>"));
+        assert!(!text.contains("markets listed"));
+        assert!(text.contains("🟡 <code>llm         2310ms</code>\n    <i>slow: 2310 ms</i>"));
+        assert!(text.contains("🔴 <code>disk             -</code>\n    <i>12 MB free at &lt;data&gt;</i>"));
+
+        let text = health_changes(&[HealthTransition {
+            component: "database".to_string(),
+            from: HealthStatus::Ok,
+            to: HealthStatus::Down,
+            detail: "database is locked".to_string(),
+        }]);
+        assert!(text.contains("🔴 database: ok → down"));
+        assert!(text.contains("<i>database is locked</i>"));
+    }
+
+    #[test]
+    fn test_stalled_tasks() {
+        use crate::monitor::{Stall, StallAction};
+        use crate::notify::stalled_tasks;
+        use std::time::Duration;
+
+        let stall = |task: &str, secs, action| Stall { task: task.to_string(), silent_for: Duration::from_secs(secs), action };
+        let text = stalled_tasks(&[stall("binance_feed", 312, StallAction::Restarted)], true);
+        assert!(text.starts_with("⏱ <b>Tasks stalled</b>"));
+        assert!(text.contains("• binance_feed: silent for 312s, restarted"));
+        assert!(!text.contains("Exiting"));
+
+        let stalls = [stall("copy_trade", 400, StallAction::Reported), stall("main_loop", 900, StallAction::Critical)];
+        let text = stalled_tasks(&stalls, true);
+        assert!(text.starts_with("🚨 <b>Main loop stalled</b>"));
+        assert!(text.contains("• copy_trade: silent for 400s\n"));
+        assert!(text.contains("• main_loop: silent for 900s, trading stopped"));
+        assert!(text.ends_with("Exiting for a restart"));
+        assert!(!stalled_tasks(&stalls, false).contains("Exiting"));
+    }
+
+    #[test]
+    fn test_ingest_sources() {
+        use crate::ingester::SourceStats;
+        use crate::notify::{ingest_sources, stale_sources};
+        use chrono::Duration;
+
+        let now = Utc::now();
+        assert_eq!(ingest_sources(&[], now), "📡 No signal sources running");
+
+        let stats = [
+            SourceStats {
+                source: "telegram".to_string(),
+                received: 120,
+                passed_prefilter: 120,
+                sent_to_llm: 95,
+                contributed: 12,
+                errors: 0,
+                last_message_at: Some(now - Duration::minutes(7)),
+            },
+            SourceStats { source: "twitter_rss".to_string(), errors: 31, ..Default::default() },
+        ];
+        let text = ingest_sources(&stats, now);
+        assert!(text.contains("<b>telegram</b>: 120 received → 120 passed filters → 95 to LLM → 12 in signals\n   last message 7m ago\n"));
+        assert!(text.ends_with("<b>twitter_rss</b>: 0 received → 0 passed filters → 0 to LLM → 0 in signals\n   no messages yet · ⚠️ 31 errors"));
+
+        let text = stale_sources(&stats[1..], Duration::hours(3));
+        assert!(text.ends_with("No messages for over 3h from: twitter_rss"));
+    }
+
+    #[test]
+    fn test_signal_message_flags_model_disagreement() {
+        use crate::notify::signal_message;
+
+        let signal = Signal {
+            market_id: "m1".to_string(),
+            token_id: "t1".to_string(),
+            side: Side::Buy,
+            model_probability: dec!(0.55),
+            market_probability: dec!(0.40),
+            edge: dec!(0.15),
+            confidence: dec!(0.70),
+            suggested_size: dec!(0.02),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: dec!(0.21),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
+        };
+
+        let text = signal_message(&signal, "Will it rain?", true);
+        assert!(text.contains("⚠️ High model disagreement (σ <code>21.0%</code>)"));
+        assert!(!signal_message(&signal, "Will it rain?", false).contains("disagreement"));
+    }
+
+    #[test]
+    fn test_shadow_comparison() {
+        use crate::notify::shadow_comparison;
+        use crate::storage::{ShadowPnl, StrategyPnl};
+
+        let live = StrategyPnl { strategy: "llm".to_string(), trades: 4, realized_pnl: dec!(12.5), ..Default::default() };
+        let shadow = ShadowPnl { strategy: "crypto_hf".to_string(), trades: 7, volume: dec!(350), pnl: dec!(-4.2) };
+
+        let text = shadow_comparison(&[live], &[shadow]);
+        assert!(text.contains("Shadow vs Live"));
+        assert!(text.contains("🔴 crypto_hf (shadow): <code>-4.20</code> (7 fills, $350.00)"));
+        assert!(text.contains("🟢 llm (live): <code>+12.50</code> (4 trades)"));
+
+        assert!(shadow_comparison(&[], &[]).contains("No shadow trades yet"));
+    }
+
+    #[test]
+    fn test_follow_list_update() {
+        use crate::notify::follow_list_update;
+
+        let text = follow_list_update(&["alice".to_string()], &["bob".to_string()]);
+        assert_eq!(text, "📋 Copy trader list updated: +@alice, -@bob");
+    }
+
+    #[test]
+    fn test_signal_grade_lists_components_in_order() {
+        use crate::notify::signal_grade;
+        use crate::strategy::{Grade, SignalScore};
+
+        let components = [("source_quality", 0.9), ("edge", 1.0), ("confidence", 0.7)]
+            .iter()
+            .map(|(k, v)| (k.to_string(), *v))
+            .collect();
+        let score = SignalScore { total: 0.74, components, grade: Grade::B };
+        let text = signal_grade("m<1>", &score);
+        assert_eq!(
+            text,
+            "🎓 <b>Signal grade B</b> (0.74) for <code>m&lt;1&gt;</code>\n\nedge: <code>1.00</code>\nconfidence: <code>0.70</code>\nsource_quality: <code>0.90</code>"
+        );
+    }
+
+    #[test]
+    fn test_copy_trader_paused() {
+        use crate::notify::copy_trader_paused;
+
+        let text = copy_trader_paused("alice", 0.2, dec!(-42.5));
+        assert_eq!(text, "⏸️ Stopped copying @alice: 20% of copied trades won, P&L $-42.50");
+    }
+
+    #[test]
+    fn test_weekly_summary_counts_interventions() {
+        use crate::notify::weekly_summary;
+
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "tok".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.40),
+            size: dec!(50),
+            fee: dec!(0.10),
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        let text = weekly_summary(dec!(1000), &[trade.clone(), trade], 3, &[], &[], None);
+        assert!(text.contains("Trades: 2"));
+        assert!(text.contains("Volume: <code>$40.00</code>"));
+        assert!(text.contains("Manual Interventions: 3"));
+        assert!(!text.contains("Equity"));
+        assert!(!text.contains("Stress"));
+
+        let text = weekly_summary(dec!(1000), &[], 0, &[dec!(980), dec!(950), dec!(1000)], &[], None);
+        assert!(text.contains("📈 Equity: <code>▅▁█</code>\n<code>$980.00</code> → <code>$1000.00</code>"));
+    }
+
+    #[test]
+    fn test_weekly_summary_stress_tests() {
+        use crate::notify::weekly_summary;
+        use crate::risk::StressResult;
+
+        let result = |name: &str, loss: rust_decimal::Decimal| StressResult {
+            scenario_name: name.to_string(),
+            portfolio_loss_pct: loss,
+            var_99: loss,
+            max_single_loss: loss,
+        };
+        let stress = [result("Covid scenario", dec!(12.3)), result("Crypto crash", dec!(28.14))];
+        let text = weekly_summary(dec!(1000), &[], 0, &[], &stress, None);
+        assert!(text.ends_with("📊 Stress Tests: Covid scenario: -12.3%, Crypto crash: -28.1%"));
+    }
+
+    #[test]
+    fn test_liquidation_cost_messages() {
+        use crate::notify::{liquidation_alert, weekly_summary};
+        use crate::risk::LiquidationReport;
+
+        let report = LiquidationReport {
+            total_slippage_usdc: dec!(61.5),
+            time_to_liquidate_mins: 20,
+            worst_market: "<thin>".to_string(),
+            liquidation_cost_pct: dec!(6.16),
+        };
+        let text = liquidation_alert(&report);
+        assert!(text.starts_with("⚠️ Forced liquidation cost: 6.2% of portfolio"));
+        assert!(text.ends_with("Slippage: <code>$61.50</code> over 20m\nWorst market: &lt;thin&gt;"));
+
+        let text = weekly_summary(dec!(1000), &[], 0, &[], &[], Some(&report));
+        assert!(text.ends_with("💧 Forced liquidation: 6.2% ($61.50) over 20m, worst &lt;thin&gt;"));
+        let nothing_open = LiquidationReport { worst_market: String::new(), ..report };
+        assert!(!weekly_summary(dec!(1000), &[], 0, &[], &[], Some(&nothing_open)).contains("liquidation"));
+    }
+
+    #[test]
+    fn test_performance_line() {
+        use crate::monitor::RollingPerformance;
+        use crate::notify::performance_line;
+
+        let mut performance = RollingPerformance::default();
+        assert_eq!(performance_line(&performance), "📊 Performance (30d): Sharpe N/A, Sortino N/A, Calmar N/A");
+
+        for i in 0..20 {
+            performance.update(if i % 2 == 0 { dec!(0.02) } else { dec!(-0.01) });
+        }
+        let line = performance_line(&performance);
+        assert!(line.contains("Sharpe 5.1"), "{}", line);
+        assert!(line.contains("Sortino 11.2"), "{}", line);
+        // Each -1% comes straight off a new peak: 0.005 * 252 / 0.01
+        assert!(line.contains("Calmar 126.00"), "{}", line);
+    }
+
+    #[test]
+    fn test_author_roster_and_mute_notice() {
+        use crate::ingester::AuthorStanding;
+        use crate::notify::{author_roster, muted_authors};
+
+        let standing = |author: &str, trust: f64, samples: usize, muted: bool, pinned: bool| AuthorStanding {
+            author: author.to_string(),
+            trust,
+            samples,
+            muted,
+            pinned,
+        };
+        let roster = [
+            standing("@alice", 0.9, 0, false, true),
+            standing("@carol", 0.62, 41, false, false),
+            standing("<bob>", 0.21, 34, true, false),
+        ];
+
+        let text = author_roster(&roster);
+        assert!(text.contains("@alice: <code>0.90</code> (n=0) 📌"));
+        assert!(text.contains("@carol: <code>0.62</code> (n=41)\n") || text.ends_with("(n=41)"));
+        assert!(text.contains("&lt;bob&gt;: <code>0.21</code> (n=34) 🔇"));
+        assert_eq!(author_roster(&[]), "👥 No signal authors yet");
+
+        assert_eq!(muted_authors(&roster[2..]), "🔇 Muted signal authors: &lt;bob&gt; (0.21, n=34)");
+    }
+
+    #[test]
+    fn test_simulation_summary() {
+        use crate::executor::SimStats;
+        use crate::notify::simulation_summary;
+
+        let stats = SimStats {
+            initial_balance: dec!(1000),
+            balance: dec!(900),
+            position_value: dec!(130),
+            equity: dec!(1030),
+            unrealized_pnl: dec!(25),
+            realized_pnl: dec!(5),
+            total_return: dec!(0.03),
+            buy_and_hold_return: dec!(0.05),
+            trades: 4,
+            open_positions: 2,
+        };
+        let text = simulation_summary(&stats);
+        assert!(text.contains("Unrealized PnL: <code>+25.00</code>"));
+        assert!(text.contains("Buy &amp; Hold: <code>+5.00%</code>"));
+        assert!(text.contains("🔴 vs. Buy &amp; Hold: <code>-2.00%</code>"));
+    }
+
+    #[test]
+    fn test_trace_report() {
+        use crate::notify::trace_report;
+        use crate::storage::{TraceEvent, TraceStage};
+        use crate::types::TraceId;
+
+        let trace_id = TraceId::new();
+        let events = vec![
+            TraceEvent::new(trace_id, TraceStage::Signal, "BUY yes on <m1>"),
+            TraceEvent::new(trace_id, TraceStage::Trade, "BUY 10.00 @ 0.4000"),
+        ];
+        let text = trace_report("t1", &events);
+        assert!(text.contains(&trace_id.to_string()));
+        assert!(text.contains("signal\n   BUY yes on &lt;m1&gt;"));
+        assert!(text.find("🎯").unwrap() < text.find("💱").unwrap());
+        assert_eq!(trace_report("t2", &[]), "🔍 No trace recorded for trade <code>t2</code>");
+    }
+
+    #[test]
+    fn test_market_profile_report() {
+        use crate::analysis::market_profile::MarketProfile;
+        use crate::notify::market_profile_report;
+        use crate::orderbook::MmActivityLevel;
+
+        let profile = MarketProfile {
+            market_id: "<m1>".to_string(),
+            median_spread: dec!(0.035),
+            median_top_depth: dec!(420),
+            quote_updates_per_min: dec!(1.5),
+            median_fill_latency_secs: Some(42),
+            mm_activity: Some(MmActivityLevel::Normal),
+            samples: 288,
+            updated_at: Utc::now(),
+        };
+        let text = market_profile_report(&profile);
+        assert!(text.contains("<code>&lt;m1&gt;</code>"));
+        assert!(text.contains("Median spread: 0.0350"));
+        assert!(text.contains("Fill latency: 42s"));
+        assert!(text.contains("MM activity: Normal"));
+        assert!(text.contains("288 samples"));
+
+        let text = market_profile_report(&MarketProfile { median_fill_latency_secs: None, mm_activity: None, ..profile });
+        assert!(text.contains("Fill latency: n/a") && text.contains("MM activity: n/a"));
+    }
+
+    #[test]
+    fn test_redemption_summary() {
+        use crate::notify::redemption_summary;
+        use crate::portfolio::redeem::{Redemption, RedemptionReceipt};
+
+        let redemption = Redemption {
+            market_id: "m1".to_string(),
+            question: "Will <it> rain?".to_string(),
+            shares: dec!(25),
+            receipt: RedemptionReceipt {
+                tx_hash: "0xfeed".to_string(),
+                proceeds: dec!(25),
+                gas_cost: dec!(0.0125),
+            },
+        };
+        let text = redemption_summary(&redemption);
+        assert!(text.contains("Will &lt;it&gt; rain?"));
+        assert!(text.contains("Proceeds: <code>$25.00</code>"));
+        assert!(text.contains("Gas: <code>$0.0125</code>"));
+        assert!(text.contains("Net: <code>$24.99</code>"));
+        assert!(text.contains("<code>0xfeed</code>"));
+    }
+
+    #[test]
+    fn test_tag_attribution() {
+        use crate::notify::tag_attribution;
+        use crate::storage::TagPerformance;
+
+        let by_tag = vec![
+            TagPerformance {
+                tag: "experiment:a<b".to_string(),
+                trades: 2,
+                closes: 1,
+                wins: 0,
+                realized_pnl: dec!(-3),
+                avg_edge: None,
+            },
+            TagPerformance {
+                tag: "prompt:1a2b3c4d".to_string(),
+                trades: 4,
+                closes: 4,
+                wins: 3,
+                realized_pnl: dec!(12.5),
+                avg_edge: Some(dec!(0.08)),
+            },
+        ];
+        let text = tag_attribution("2026-09", &by_tag);
+        assert!(text.contains("🟢 prompt:1a2b3c4d: <code>+12.50</code> (4 trades, 75% wins, edge 8.0%)"));
+        assert!(text.contains("🔴 experiment:a&lt;b: <code>-3.00</code> (2 trades, 0% wins)"));
+        assert!(text.find("prompt:").unwrap() < text.find("experiment:").unwrap());
+        assert!(tag_attribution("2026-09", &[]).contains("No tagged trades"));
+    }
+
+    #[test]
+    fn test_market_alert_messages() {
+        use crate::monitor::{FiredMarketAlert, MarketAlert};
+        use crate::notify::{fired_market_alert, market_alert_list};
+
+        let alert = |id, rule: &str| MarketAlert { id, rule: rule.parse().unwrap(), last_fired: None };
+        let fired = FiredMarketAlert {
+            alert: alert(3, "517310 price > 70%"),
+            question: "Will A & B merge?".to_string(),
+            value: dec!(0.7234),
+        };
+        assert_eq!(
+            fired_market_alert(&fired),
+            "🔔 <b>Alert #3</b>\n\n📊 Will A &amp; B merge?\n<code>price &gt; 0.70</code>: now <code>0.7234</code>"
+        );
+
+        let text = market_alert_list(&[alert(3, "517310 price > 0.70"), alert(4, "m2 spread >= 5c 90s")]);
+        assert!(text.contains("#3 <code>517310</code> price &gt; 0.70 (cooldown 1h)"));
+        assert!(text.contains("#4 <code>m2</code> spread &gt;= 0.05 (cooldown 90s)"));
+        assert!(market_alert_list(&[]).starts_with("🔔 No market alerts"));
+    }
+
+    #[test]
+    fn test_model_breaker_messages() {
+        use crate::notify::{model_breaker_recovered, model_breaker_tripped};
+
+        let text = model_breaker_tripped(
+            "anthropic",
+            "MarketImplied",
+            3,
+            std::time::Duration::from_secs(300),
+            "Rate limited: retry after 60 seconds",
+        );
+        assert!(text.starts_with("🔌 <b>Model anthropic unavailable</b>"));
+        assert!(text.contains("3 failures in a row, last: Rate limited"));
+        assert!(text.ends_with("Using MarketImplied and retrying in 300s"));
+        assert!(model_breaker_recovered("anthropic").starts_with("✅ <b>Model anthropic recovered</b>"));
+    }
+
+    #[test]
+    fn test_llm_fallback_messages() {
+        use crate::notify::{llm_fallback, llm_primary_restored};
+
+        assert_eq!(llm_fallback("GPT", "DeepSeek"), "⚠️ LLM fallback: GPT failed, using DeepSeek");
+        assert_eq!(llm_primary_restored("GPT"), "✅ LLM primary restored: GPT");
+    }
+
+    #[test]
+    fn test_market_dossier_message() {
+        use crate::analysis::dossier::MarketDossier;
+        use crate::client::{OrderBook, OrderBookLevel};
+        use crate::notify::market_dossier;
+        use crate::types::{Market, Outcome};
+
+        let market = |id: &str, question: &str, yes| Market {
+            id: id.to_string(),
+            question: question.to_string(),
+            description: None,
+            end_date: Some(Utc::now() + chrono::Duration::hours(50)),
+            volume: dec!(120000),
+            liquidity: dec!(15000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: yes },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: dec!(1) - yes },
+            ],
+            active: true,
+            closed: false,
+            event_id: Some("e1".to_string()),
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+        let book = OrderBook {
+            bids: vec![OrderBookLevel { price: dec!(0.40), size: dec!(1000) }],
+            asks: vec![OrderBookLevel { price: dec!(0.42), size: dec!(1000) }],
+        };
+        let dossier = MarketDossier::builder(market("m1", "Will <X> happen?", dec!(0.41)))
+            .book(book)
+            .volumes(dec!(7000), dec!(35000))
+            .comparables([market("m2", "Will Y happen instead?", dec!(0.30))])
+            .fair_value(dec!(0.55), dec!(0.7))
+            .build(Utc::now());
+
+        let text = market_dossier(&dossier);
+        assert!(text.starts_with("🔎 <b>Market Dossier</b>"));
+        assert!(text.contains("Will &lt;X&gt; happen?"));
+        assert!(text.contains("Yes <code>41.0%</code>"));
+        assert!(text.contains("⏳ Resolves in 2d 1h"));
+        assert!(text.contains("(1.4x the daily average)"));
+        assert!(text.contains("(spread <code>0.02</code>)"));
+        assert!(text.contains("🔗 <code>30.0%</code> Will Y happen instead?"));
+        assert!(text.contains("🤖 Model <code>55.0%</code> (70.0% confidence)"));
+        // $100 buys 238 shares at 0.42, all filled, 13c under fair value
+        assert!(text.contains("$100: avg <code>0.42</code>, 100% filled, net <code>+13.0%</code>"));
+        // $2000 is more than the 1000 shares offered
+        assert!(text.contains("$2000: avg <code>0.42</code>, 21% filled"));
+    }
+
+    #[test]
+    fn test_position_news_alert() {
+        use crate::notify::position_news_alert;
+
+        let text = position_news_alert(
+            "Will Donald Trump win the 2028 Republican presidential nomination?",
+            "Trump & allies face <new> indictment",
+        );
+        assert!(text.starts_with("⚠️ Position news: Will Donald Trump win the 2028 Republican presidenti"));
+        assert!(text.ends_with("\n\nTrump &amp; allies face &lt;new&gt; indictment"));
+    }
 }
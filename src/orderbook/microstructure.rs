@@ -0,0 +1,321 @@
+//! Microstructure event detection
+//!
+//! Flash crashes, quote stuffing and spoofing show up in the snapshot
+//! stream before they show up in fills. [`OrderBookAnalyzer::detect_microstructure_events`]
+//! looks for them over a recent window; a severity above
+//! [`SEVERE_EVENT_SEVERITY`] is worth pulling quotes for.
+//!
+//! There's no L3 (per-order) data, so spoofing is inferred from level
+//! sizes: a large size added at a price and taken away again within
+//! [`SPOOF_CANCEL_MS`] without trading there.
+
+use super::{BookLevel, OrderBookAnalyzer, OrderBookSnapshot};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Mid move between consecutive snapshots that counts as a flash crash
+pub const FLASH_CRASH_MOVE: Decimal = dec!(0.02);
+/// Consecutive snapshots further apart than this aren't a flash crash
+pub const FLASH_CRASH_MS: u64 = 100;
+/// Book updates per second that count as quote stuffing
+pub const QUOTE_STUFFING_RATE: f64 = 20.0;
+/// Period the quote update rate is measured over
+pub const QUOTE_STUFFING_MS: u64 = 5_000;
+/// A size added and removed again within this is a spoof
+pub const SPOOF_CANCEL_MS: u64 = 2_000;
+/// Period spoofs are counted over
+pub const SPOOF_WINDOW_MS: u64 = 30_000;
+/// Spoofs in [`SPOOF_WINDOW_MS`] beyond which it's an event
+pub const SPOOF_COUNT: usize = 3;
+/// Added size, as a multiple of the side's average level before, that's
+/// "large"
+pub const SPOOF_SIZE_MULTIPLE: Decimal = dec!(5);
+/// Severity above which an event calls for defensive action
+pub const SEVERE_EVENT_SEVERITY: f64 = 0.7;
+
+/// Kind of microstructure event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicrostructureEventType {
+    FlashCrash,
+    QuoteStuffing,
+    Spoofing,
+}
+
+impl std::fmt::Display for MicrostructureEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::FlashCrash => "flash crash",
+            Self::QuoteStuffing => "quote stuffing",
+            Self::Spoofing => "spoofing",
+        })
+    }
+}
+
+/// A detected microstructure event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MicrostructureEvent {
+    pub event_type: MicrostructureEventType,
+    /// 0 to 1; the threshold of the event is 0.5, twice it is 1
+    pub severity: f64,
+    /// Snapshot time the event was seen at
+    pub timestamp_ms: u64,
+}
+
+impl MicrostructureEvent {
+    pub fn is_severe(&self) -> bool {
+        self.severity > SEVERE_EVENT_SEVERITY
+    }
+}
+
+/// `measured / threshold` scaled so the threshold is 0.5, capped at 1
+fn severity(measured: f64, threshold: f64) -> f64 {
+    (measured / threshold / 2.0).min(1.0)
+}
+
+fn mid(snapshot: &OrderBookSnapshot) -> Option<Decimal> {
+    Some((snapshot.bids.first()?.price + snapshot.asks.first()?.price) / dec!(2))
+}
+
+fn same_levels(a: &[BookLevel], b: &[BookLevel]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.price == y.price && x.quantity == y.quantity)
+}
+
+/// Price -> size of one side
+fn sizes(levels: &[BookLevel]) -> HashMap<Decimal, Decimal> {
+    levels.iter().map(|l| (l.price, l.quantity)).collect()
+}
+
+fn average_size(levels: &[BookLevel]) -> Decimal {
+    if levels.is_empty() {
+        return Decimal::ZERO;
+    }
+    levels.iter().map(|l| l.quantity).sum::<Decimal>() / Decimal::from(levels.len())
+}
+
+/// A large size added at a level, waiting to be traded or pulled
+struct Placement {
+    at_ms: u64,
+    /// Size of the level before the addition
+    before: Decimal,
+    added: Decimal,
+}
+
+impl OrderBookAnalyzer {
+    /// Flash crashes, quote stuffing and spoofing seen in the snapshots of
+    /// the last `window_ms`, oldest first
+    pub fn detect_microstructure_events(&self, window_ms: u64) -> Vec<MicrostructureEvent> {
+        let Some(latest) = self.snapshots.back().map(|s| s.timestamp_ms) else {
+            return Vec::new();
+        };
+        let since = latest.saturating_sub(window_ms);
+        let start = self.snapshots.partition_point(|s| s.timestamp_ms < since);
+        let snapshots: Vec<&OrderBookSnapshot> = self.snapshots.range(start..).collect();
+
+        let mut events = self.flash_crashes(&snapshots);
+        events.extend(quote_stuffing(&snapshots, latest));
+        events.extend(self.spoofing(&snapshots, latest));
+        events.sort_by_key(|e| e.timestamp_ms);
+        events
+    }
+
+    /// Mid moves over [`FLASH_CRASH_MOVE`] between snapshots at most
+    /// [`FLASH_CRASH_MS`] apart
+    fn flash_crashes(&self, snapshots: &[&OrderBookSnapshot]) -> Vec<MicrostructureEvent> {
+        snapshots
+            .windows(2)
+            .filter(|pair| pair[1].timestamp_ms.saturating_sub(pair[0].timestamp_ms) <= FLASH_CRASH_MS)
+            .filter_map(|pair| {
+                let (before, after) = (mid(pair[0])?, mid(pair[1])?);
+                if before.is_zero() {
+                    return None;
+                }
+                let moved = ((after - before) / before).abs();
+                if moved <= FLASH_CRASH_MOVE {
+                    return None;
+                }
+                Some(MicrostructureEvent {
+                    event_type: MicrostructureEventType::FlashCrash,
+                    severity: severity(moved.to_f64()?, FLASH_CRASH_MOVE.to_f64()?),
+                    timestamp_ms: pair[1].timestamp_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Large sizes pulled within [`SPOOF_CANCEL_MS`] without a trade at
+    /// their price, counted over the last [`SPOOF_WINDOW_MS`]
+    fn spoofing(&self, snapshots: &[&OrderBookSnapshot], latest: u64) -> Option<MicrostructureEvent> {
+        let since = latest.saturating_sub(SPOOF_WINDOW_MS);
+        let start = snapshots.partition_point(|s| s.timestamp_ms < since);
+        let snapshots = &snapshots[start..];
+
+        let mut pending: HashMap<(bool, Decimal), Placement> = HashMap::new();
+        let mut spoofs = 0;
+        for pair in snapshots.windows(2) {
+            let (prev, cur) = (pair[0], pair[1]);
+            for (is_bid, prev_levels, levels) in [(true, &prev.bids, &cur.bids), (false, &prev.asks, &cur.asks)] {
+                let (prev_sizes, cur_sizes) = (sizes(prev_levels), sizes(levels));
+                let large = average_size(prev_levels) * SPOOF_SIZE_MULTIPLE;
+
+                pending.retain(|&(bid, price), placement| {
+                    if bid != is_bid {
+                        return true;
+                    }
+                    if cur.timestamp_ms.saturating_sub(placement.at_ms) > SPOOF_CANCEL_MS {
+                        return false;
+                    }
+                    let size = cur_sizes.get(&price).copied().unwrap_or_default();
+                    // Most of the added size gone
+                    if size > placement.before + placement.added * dec!(0.2) {
+                        return true;
+                    }
+                    if !self.traded_at(price, placement.at_ms, cur.timestamp_ms) {
+                        spoofs += 1;
+                    }
+                    false
+                });
+
+                for (&price, &size) in &cur_sizes {
+                    let before = prev_sizes.get(&price).copied().unwrap_or_default();
+                    if size - before >= large && !large.is_zero() {
+                        pending.insert((is_bid, price), Placement { at_ms: cur.timestamp_ms, before, added: size - before });
+                    }
+                }
+            }
+        }
+
+        (spoofs > SPOOF_COUNT).then(|| MicrostructureEvent {
+            event_type: MicrostructureEventType::Spoofing,
+            severity: severity(spoofs as f64, SPOOF_COUNT as f64),
+            timestamp_ms: latest,
+        })
+    }
+
+    /// Whether a trade printed at `price` between the two times
+    fn traded_at(&self, price: Decimal, from_ms: u64, to_ms: u64) -> bool {
+        self.trade_flows
+            .iter()
+            .any(|t| t.price == price && (from_ms..=to_ms).contains(&t.timestamp_ms))
+    }
+}
+
+/// Book updates per second over the last [`QUOTE_STUFFING_MS`] above
+/// [`QUOTE_STUFFING_RATE`]
+fn quote_stuffing(snapshots: &[&OrderBookSnapshot], latest: u64) -> Option<MicrostructureEvent> {
+    let since = latest.saturating_sub(QUOTE_STUFFING_MS);
+    let start = snapshots.partition_point(|s| s.timestamp_ms < since);
+    let updates = snapshots[start.saturating_sub(1)..]
+        .windows(2)
+        .filter(|pair| pair[1].timestamp_ms >= since)
+        .filter(|pair| !same_levels(&pair[0].bids, &pair[1].bids) || !same_levels(&pair[0].asks, &pair[1].asks))
+        .count();
+    let rate = updates as f64 / (QUOTE_STUFFING_MS as f64 / 1000.0);
+    (rate > QUOTE_STUFFING_RATE).then(|| MicrostructureEvent {
+        event_type: MicrostructureEventType::QuoteStuffing,
+        severity: severity(rate, QUOTE_STUFFING_RATE),
+        timestamp_ms: latest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::TradeFlow;
+
+    fn book(timestamp_ms: u64, bid: Decimal, ask: Decimal, bid_size: Decimal) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp_ms,
+            bids: vec![
+                BookLevel { price: bid, quantity: bid_size },
+                BookLevel { price: bid - dec!(0.01), quantity: dec!(100) },
+                BookLevel { price: bid - dec!(0.02), quantity: dec!(100) },
+            ],
+            asks: vec![
+                BookLevel { price: ask, quantity: dec!(100) },
+                BookLevel { price: ask + dec!(0.01), quantity: dec!(100) },
+            ],
+            last_trade_price: None,
+            last_trade_side: None,
+        }
+    }
+
+    fn types(events: &[MicrostructureEvent]) -> Vec<MicrostructureEventType> {
+        events.iter().map(|e| e.event_type).collect()
+    }
+
+    #[test]
+    fn test_flash_crash_needs_a_fast_move() {
+        let mut analyzer = OrderBookAnalyzer::new();
+        analyzer.process_snapshot(book(0, dec!(0.50), dec!(0.52), dec!(100)));
+        // 51 -> 46.5 mid (-8.8%) within 50ms
+        analyzer.process_snapshot(book(50, dec!(0.45), dec!(0.48), dec!(100)));
+        let events = analyzer.detect_microstructure_events(60_000);
+        assert_eq!(types(&events), [MicrostructureEventType::FlashCrash]);
+        assert_eq!((events[0].severity, events[0].timestamp_ms), (1.0, 50));
+        assert!(events[0].is_severe());
+
+        // The same move over a second is just a move
+        let mut analyzer = OrderBookAnalyzer::new();
+        analyzer.process_snapshot(book(0, dec!(0.50), dec!(0.52), dec!(100)));
+        analyzer.process_snapshot(book(1_000, dec!(0.45), dec!(0.48), dec!(100)));
+        assert!(analyzer.detect_microstructure_events(60_000).is_empty());
+    }
+
+    #[test]
+    fn test_quote_stuffing_rate() {
+        // 30 updates a second for 5 seconds: severity 30 / 20 / 2
+        let mut analyzer = OrderBookAnalyzer::new();
+        for i in 0..=150u64 {
+            let size = dec!(100) + Decimal::from(i % 2);
+            analyzer.process_snapshot(book(i * 33, dec!(0.50), dec!(0.52), size));
+        }
+        let events = analyzer.detect_microstructure_events(60_000);
+        assert_eq!(types(&events), [MicrostructureEventType::QuoteStuffing]);
+        assert!(events[0].severity > 0.7);
+
+        // Unchanged books aren't updates
+        let mut analyzer = OrderBookAnalyzer::new();
+        for i in 0..=150u64 {
+            analyzer.process_snapshot(book(i * 33, dec!(0.50), dec!(0.52), dec!(100)));
+        }
+        assert!(analyzer.detect_microstructure_events(60_000).is_empty());
+    }
+
+    #[test]
+    fn test_spoofing_counts_pulled_sizes() {
+        let mut analyzer = OrderBookAnalyzer::new();
+        // Every 5s a 2000-lot appears at the best bid and is gone a second later
+        for i in 0..5u64 {
+            analyzer.process_snapshot(book(i * 5_000, dec!(0.50), dec!(0.52), dec!(100)));
+            analyzer.process_snapshot(book(i * 5_000 + 500, dec!(0.50), dec!(0.52), dec!(2000)));
+            analyzer.process_snapshot(book(i * 5_000 + 1_500, dec!(0.50), dec!(0.52), dec!(100)));
+        }
+        let events = analyzer.detect_microstructure_events(60_000);
+        assert_eq!(types(&events), [MicrostructureEventType::Spoofing]);
+        assert!((events[0].severity - 5.0 / 6.0).abs() < 1e-9);
+
+        // Only three in a short window
+        assert!(analyzer.detect_microstructure_events(12_000).is_empty());
+    }
+
+    #[test]
+    fn test_traded_size_is_not_spoofing() {
+        let mut analyzer = OrderBookAnalyzer::new();
+        for i in 0..5u64 {
+            analyzer.process_snapshot(book(i * 5_000, dec!(0.50), dec!(0.52), dec!(100)));
+            analyzer.process_snapshot(book(i * 5_000 + 500, dec!(0.50), dec!(0.52), dec!(2000)));
+            analyzer.process_trade(TradeFlow {
+                timestamp_ms: i * 5_000 + 1_000,
+                price: dec!(0.50),
+                quantity: dec!(1900),
+                side: None,
+            });
+            analyzer.process_snapshot(book(i * 5_000 + 1_500, dec!(0.50), dec!(0.52), dec!(100)));
+        }
+        assert!(analyzer.detect_microstructure_events(60_000).is_empty());
+    }
+}
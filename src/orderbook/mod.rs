@@ -5,16 +5,31 @@
 //! - Volume-Weighted Imbalance across multiple levels
 //! - Iceberg order detection
 //! - Market maker behavior analysis
-//! - Trade flow toxicity (VPIN)
-//! - Price impact estimation
+//! - Trade flow toxicity (VPIN), from labeled trade sides or bulk-volume
+//!   classification when the feed doesn't label the aggressor
+//! - Price impact estimation and Market/IOC/FOK fill simulation
+//! - Flash crash, quote stuffing and spoofing detection ([`microstructure`])
+//!
+//! Crossed or locked snapshots (best bid at or above best ask) come from
+//! exchange glitches or stale merges; they're counted and kept out of the
+//! history, and the book-level analyses return `None` until a valid
+//! snapshot arrives.
 
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::MathematicalOps;
+use crate::utils::normal_cdf;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+pub mod microstructure;
+pub use microstructure::{MicrostructureEvent, MicrostructureEventType};
+
+/// Snapshot pairs [`OrderBookAnalyzer::fit_level_weights`] needs, per level
+pub const MIN_FIT_SAMPLES: usize = 30;
+
 /// Order book level (price + quantity)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookLevel {
@@ -32,6 +47,38 @@ pub struct OrderBookSnapshot {
     pub last_trade_side: Option<TradeSide>,
 }
 
+impl OrderBookSnapshot {
+    /// Snapshot of a CLOB book, best levels first
+    pub fn from_book(book: &crate::client::OrderBook, timestamp_ms: u64) -> Self {
+        let levels = |levels: &[crate::client::OrderBookLevel]| -> Vec<BookLevel> {
+            levels
+                .iter()
+                .map(|l| BookLevel { price: l.price, quantity: l.size })
+                .collect()
+        };
+        let mut bids = levels(&book.bids);
+        let mut asks = levels(&book.asks);
+        bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+        asks.sort_by_key(|l| l.price);
+
+        Self {
+            timestamp_ms,
+            bids,
+            asks,
+            last_trade_price: None,
+            last_trade_side: None,
+        }
+    }
+
+    /// Whether the best bid is at (locked) or above (crossed) the best ask
+    pub fn is_crossed(&self) -> bool {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => bid.price >= ask.price,
+            _ => false,
+        }
+    }
+}
+
 /// Trade side
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TradeSide {
@@ -39,6 +86,28 @@ pub enum TradeSide {
     Sell,
 }
 
+/// How a simulated taker order treats the depth it cannot reach
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Walk the whole book; whatever it cannot absorb is left unfilled
+    Market,
+    /// Immediate-or-cancel: take what is available at or better than the
+    /// limit, cancel the rest
+    Ioc { limit_price: Decimal },
+    /// Fill-or-kill: fill entirely at or better than the limit, or not at all
+    Fok { limit_price: Decimal },
+}
+
+/// Outcome of [`OrderBookAnalyzer::simulate_fill`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillResult {
+    pub filled: Decimal,
+    /// Volume-weighted price of the fill, `None` when nothing filled
+    pub avg_price: Option<Decimal>,
+    /// Quantity cancelled or left unfilled
+    pub remaining: Decimal,
+}
+
 /// Order Book Imbalance result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImbalanceResult {
@@ -91,7 +160,32 @@ pub struct TradeFlow {
     pub timestamp_ms: u64,
     pub price: Decimal,
     pub quantity: Decimal,
-    pub side: TradeSide,
+    /// Aggressor side, when the feed labels it
+    pub side: Option<TradeSide>,
+}
+
+/// How trades are grouped into VPIN buckets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VpinMode {
+    /// Buckets of `vpin_bucket_size` volume
+    #[default]
+    Volume,
+    /// Buckets of `vpin_bucket_secs`, for markets where volume buckets
+    /// would take hours to fill
+    Time,
+}
+
+/// How a VPIN's buy and sell volume was told apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VpinClassification {
+    /// Every trade carried its aggressor side
+    Labeled,
+    /// Bulk-volume classification: each bucket's volume split by the normal
+    /// CDF of its standardized price change
+    BulkVolume,
+    /// Labeled trades, plus bulk-volume classification of unlabeled ones
+    Mixed,
 }
 
 /// VPIN (Volume-synchronized Probability of Informed Trading) result
@@ -111,6 +205,9 @@ pub struct VpinResult {
     
     /// Toxicity level interpretation
     pub toxicity_level: ToxicityLevel,
+
+    /// How buys were told from sells
+    pub classification: VpinClassification,
 }
 
 /// Trade flow toxicity level
@@ -181,12 +278,23 @@ pub struct OrderBookAnalyzerConfig {
     
     /// Weight decay factor for depth-weighted imbalance
     pub depth_weight_decay: Decimal,
+
+    /// Per-level weights for depth-weighted imbalance, best level first,
+    /// replacing `depth_weight_decay^i` when set (levels past the end get
+    /// no weight). See [`OrderBookAnalyzer::fit_level_weights`].
+    pub level_weights: Option<Vec<Decimal>>,
     
     /// VPIN bucket size (in base currency volume)
     pub vpin_bucket_size: Decimal,
     
     /// Number of VPIN buckets to track
     pub vpin_bucket_count: usize,
+
+    /// Whether VPIN buckets fill by volume or by time
+    pub vpin_mode: VpinMode,
+
+    /// Duration of a VPIN bucket in [`VpinMode::Time`]
+    pub vpin_bucket_secs: u64,
     
     /// Threshold for iceberg detection (refill count)
     pub iceberg_refill_threshold: u32,
@@ -206,8 +314,11 @@ impl Default for OrderBookAnalyzerConfig {
         Self {
             imbalance_levels: 10,
             depth_weight_decay: dec!(0.8),
+            level_weights: None,
             vpin_bucket_size: dec!(1000),
             vpin_bucket_count: 50,
+            vpin_mode: VpinMode::Volume,
+            vpin_bucket_secs: 300,
             iceberg_refill_threshold: 3,
             mm_analysis_window_secs: 60,
             direction_threshold: dec!(0.15),
@@ -237,22 +348,64 @@ pub struct OrderBookAnalyzer {
     
     /// Last analysis time
     last_analysis: Option<Instant>,
+
+    /// Crossed or locked snapshots rejected so far
+    invalid_snapshots: u64,
+
+    /// Whether the latest snapshot was rejected
+    latest_invalid: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct VpinBucket {
     buy_volume: Decimal,
     sell_volume: Decimal,
     total_volume: Decimal,
+    /// Volume of trades without an aggressor side
+    #[serde(default)]
+    unlabeled_volume: Decimal,
+    /// Previous bucket's last price (or this one's first)
+    #[serde(default)]
+    open_price: Option<Decimal>,
+    /// Last traded price
+    #[serde(default)]
+    close_price: Option<Decimal>,
+    /// Time of the first trade
+    #[serde(default)]
+    start_ms: u64,
 }
 
-impl Default for VpinBucket {
-    fn default() -> Self {
-        Self {
-            buy_volume: Decimal::ZERO,
-            sell_volume: Decimal::ZERO,
-            total_volume: Decimal::ZERO,
+impl VpinBucket {
+    fn price_change(&self) -> f64 {
+        match (self.open_price, self.close_price) {
+            (Some(open), Some(close)) => (close - open).to_f64().unwrap_or(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Buy and sell volume, the unlabeled volume split by the normal CDF of
+    /// the price change over `sigma` (evenly without a spread)
+    fn classified(&self, sigma: f64) -> (Decimal, Decimal) {
+        if self.unlabeled_volume.is_zero() {
+            return (self.buy_volume, self.sell_volume);
         }
+        let buy_fraction = if sigma > 0.0 { normal_cdf(self.price_change() / sigma) } else { 0.5 };
+        let buys = self.unlabeled_volume * Decimal::from_f64(buy_fraction).unwrap_or(dec!(0.5));
+        (self.buy_volume + buys, self.sell_volume + self.unlabeled_volume - buys)
+    }
+}
+
+/// Completed and in-progress VPIN buckets of an [`OrderBookAnalyzer`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VpinState {
+    buckets: Vec<VpinBucket>,
+    current: VpinBucket,
+}
+
+impl VpinState {
+    /// Number of completed buckets
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
     }
 }
 
@@ -279,11 +432,26 @@ impl OrderBookAnalyzer {
             current_bucket: VpinBucket::default(),
             refill_tracker: std::collections::HashMap::new(),
             last_analysis: None,
+            invalid_snapshots: 0,
+            latest_invalid: false,
         }
     }
     
-    /// Process a new order book snapshot
+    /// Process a new order book snapshot; a crossed or locked one is
+    /// counted and dropped
     pub fn process_snapshot(&mut self, snapshot: OrderBookSnapshot) {
+        self.latest_invalid = snapshot.is_crossed();
+        if self.latest_invalid {
+            self.invalid_snapshots += 1;
+            tracing::debug!(
+                "Rejected crossed book at {}: bid {} >= ask {}",
+                snapshot.timestamp_ms,
+                snapshot.bids[0].price,
+                snapshot.asks[0].price
+            );
+            return;
+        }
+
         // Track refills for iceberg detection
         self.track_refills(&snapshot);
         
@@ -296,23 +464,62 @@ impl OrderBookAnalyzer {
         self.last_analysis = Some(Instant::now());
     }
     
+    /// Crossed or locked snapshots rejected so far
+    pub fn invalid_snapshot_count(&self) -> u64 {
+        self.invalid_snapshots
+    }
+
+    /// Latest valid snapshot, `None` while the latest one was rejected
+    fn current_snapshot(&self) -> Option<&OrderBookSnapshot> {
+        if self.latest_invalid {
+            return None;
+        }
+        self.snapshots.back()
+    }
+
+    /// VPIN buckets accumulated so far
+    pub fn vpin_state(&self) -> VpinState {
+        VpinState {
+            buckets: self.vpin_buckets.iter().cloned().collect(),
+            current: self.current_bucket.clone(),
+        }
+    }
+
+    /// Resume VPIN estimation from a [`vpin_state`](Self::vpin_state) snapshot
+    pub fn restore_vpin(&mut self, state: VpinState) {
+        let skip = state.buckets.len().saturating_sub(self.config.vpin_bucket_count);
+        self.vpin_buckets = state.buckets.into_iter().skip(skip).collect();
+        self.current_bucket = state.current;
+    }
+
     /// Process a trade for VPIN calculation
     pub fn process_trade(&mut self, trade: TradeFlow) {
+        // A time bucket ends with the first trade past its duration
+        let bucket_ms = self.config.vpin_bucket_secs.saturating_mul(1000);
+        if self.config.vpin_mode == VpinMode::Time
+            && self.current_bucket.total_volume > Decimal::ZERO
+            && trade.timestamp_ms >= self.current_bucket.start_ms.saturating_add(bucket_ms)
+        {
+            self.close_vpin_bucket();
+        }
+
         // Add to current bucket
+        if self.current_bucket.total_volume.is_zero() {
+            self.current_bucket.start_ms = trade.timestamp_ms;
+            self.current_bucket.open_price =
+                self.vpin_buckets.back().and_then(|b| b.close_price).or(Some(trade.price));
+        }
         match trade.side {
-            TradeSide::Buy => self.current_bucket.buy_volume += trade.quantity,
-            TradeSide::Sell => self.current_bucket.sell_volume += trade.quantity,
+            Some(TradeSide::Buy) => self.current_bucket.buy_volume += trade.quantity,
+            Some(TradeSide::Sell) => self.current_bucket.sell_volume += trade.quantity,
+            None => self.current_bucket.unlabeled_volume += trade.quantity,
         }
         self.current_bucket.total_volume += trade.quantity;
+        self.current_bucket.close_price = Some(trade.price);
         
         // Check if bucket is full
-        if self.current_bucket.total_volume >= self.config.vpin_bucket_size {
-            // Store completed bucket
-            if self.vpin_buckets.len() >= self.config.vpin_bucket_count {
-                self.vpin_buckets.pop_front();
-            }
-            self.vpin_buckets.push_back(self.current_bucket.clone());
-            self.current_bucket = VpinBucket::default();
+        if self.config.vpin_mode == VpinMode::Volume && self.current_bucket.total_volume >= self.config.vpin_bucket_size {
+            self.close_vpin_bucket();
         }
         
         // Store trade flow
@@ -322,9 +529,18 @@ impl OrderBookAnalyzer {
         self.trade_flows.push_back(trade);
     }
     
-    /// Calculate order book imbalance from latest snapshot
+    /// Store the current bucket as completed and start a new one
+    fn close_vpin_bucket(&mut self) {
+        if self.vpin_buckets.len() >= self.config.vpin_bucket_count {
+            self.vpin_buckets.pop_front();
+        }
+        self.vpin_buckets.push_back(std::mem::take(&mut self.current_bucket));
+    }
+
+    /// Calculate order book imbalance from latest snapshot; `None` when a
+    /// side is empty or the book is crossed
     pub fn calculate_imbalance(&self) -> Option<ImbalanceResult> {
-        let snapshot = self.snapshots.back()?;
+        let snapshot = self.current_snapshot()?;
         
         if snapshot.bids.is_empty() || snapshot.asks.is_empty() {
             return None;
@@ -373,8 +589,8 @@ impl OrderBookAnalyzer {
             weighted_bid += bid.quantity * bid_weight;
             weighted_ask += ask.quantity * ask_weight;
             
-            // Depth-weighted (exponential decay)
-            let depth_weight = self.config.depth_weight_decay.powi(i as i64);
+            // Depth-weighted (exponential decay unless weights are set)
+            let depth_weight = self.level_weight(i);
             depth_weighted_bid += bid.quantity * depth_weight;
             depth_weighted_ask += ask.quantity * depth_weight;
         }
@@ -429,21 +645,100 @@ impl OrderBookAnalyzer {
         })
     }
     
+    /// Weight of level `i` (0 = best) in the depth-weighted imbalance
+    fn level_weight(&self, i: usize) -> Decimal {
+        match &self.config.level_weights {
+            Some(weights) => weights.get(i).copied().unwrap_or(Decimal::ZERO),
+            None => self.config.depth_weight_decay.powi(i as i64),
+        }
+    }
+
+    /// Calibrate per-level weights for the depth-weighted imbalance from the
+    /// stored snapshots: each level is weighted by the correlation of its own
+    /// imbalance with the mid move to the next snapshot (levels that don't
+    /// predict the move get none), normalized to sum to one. Returns the new
+    /// weights; with fewer than [`MIN_FIT_SAMPLES`] usable snapshot pairs or
+    /// no predictive level the current weights are kept and `None` returned.
+    pub fn fit_level_weights(&mut self) -> Option<Vec<Decimal>> {
+        let levels = self.config.imbalance_levels;
+        let mut samples: Vec<(Vec<Option<f64>>, f64)> = Vec::new();
+        for (snapshot, next) in self.snapshots.iter().zip(self.snapshots.iter().skip(1)) {
+            let (Some(mid), Some(next_mid)) = (mid_price(snapshot), mid_price(next)) else {
+                continue;
+            };
+            let imbalances = (0..levels)
+                .map(|i| {
+                    let bid = snapshot.bids.get(i)?.quantity;
+                    let ask = snapshot.asks.get(i)?.quantity;
+                    let total = bid + ask;
+                    if total.is_zero() {
+                        return None;
+                    }
+                    ((bid - ask) / total).to_f64()
+                })
+                .collect();
+            samples.push((imbalances, (next_mid - mid).to_f64().unwrap_or(0.0)));
+        }
+        if samples.len() < MIN_FIT_SAMPLES {
+            return None;
+        }
+
+        let correlations: Vec<f64> = (0..levels)
+            .map(|i| {
+                let pairs: Vec<(f64, f64)> = samples
+                    .iter()
+                    .filter_map(|(imbalances, moved)| Some((imbalances[i]?, *moved)))
+                    .collect();
+                if pairs.len() < MIN_FIT_SAMPLES {
+                    return 0.0;
+                }
+                correlation(&pairs).max(0.0)
+            })
+            .collect();
+        let total: f64 = correlations.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let weights: Vec<Decimal> = correlations
+            .iter()
+            .map(|c| Decimal::from_f64(c / total).unwrap_or(Decimal::ZERO).round_dp(4))
+            .collect();
+        self.config.level_weights = Some(weights.clone());
+        Some(weights)
+    }
+
     /// Calculate VPIN (Volume-synchronized Probability of Informed Trading)
     pub fn calculate_vpin(&self) -> Option<VpinResult> {
         if self.vpin_buckets.is_empty() {
             return None;
         }
         
+        // Spread of the bucket price changes, standardizing them for
+        // bulk-volume classification
+        let changes: Vec<f64> = self.vpin_buckets.iter().map(VpinBucket::price_change).collect();
+        let mean = changes.iter().sum::<f64>() / changes.len() as f64;
+        let sigma = (changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / changes.len() as f64).sqrt();
+
         let mut total_buy = Decimal::ZERO;
         let mut total_sell = Decimal::ZERO;
         let mut total_imbalance = Decimal::ZERO;
+        let mut labeled = Decimal::ZERO;
+        let mut unlabeled = Decimal::ZERO;
         
         for bucket in &self.vpin_buckets {
-            total_buy += bucket.buy_volume;
-            total_sell += bucket.sell_volume;
-            total_imbalance += (bucket.buy_volume - bucket.sell_volume).abs();
+            let (buy, sell) = bucket.classified(sigma);
+            total_buy += buy;
+            total_sell += sell;
+            total_imbalance += (buy - sell).abs();
+            labeled += bucket.buy_volume + bucket.sell_volume;
+            unlabeled += bucket.unlabeled_volume;
         }
+        let classification = match (labeled > Decimal::ZERO, unlabeled > Decimal::ZERO) {
+            (_, false) => VpinClassification::Labeled,
+            (false, true) => VpinClassification::BulkVolume,
+            (true, true) => VpinClassification::Mixed,
+        };
         
         let total_volume = total_buy + total_sell;
         let vpin = if total_volume > Decimal::ZERO {
@@ -468,6 +763,7 @@ impl OrderBookAnalyzer {
             sell_volume: total_sell,
             bucket_count: self.vpin_buckets.len(),
             toxicity_level,
+            classification,
         })
     }
     
@@ -615,9 +911,10 @@ impl OrderBookAnalyzer {
         })
     }
     
-    /// Estimate price impact for a given order size
+    /// Estimate price impact for a given order size; `None` on an empty
+    /// side or a crossed book
     pub fn estimate_price_impact(&self, side: TradeSide, size: Decimal) -> Option<Decimal> {
-        let snapshot = self.snapshots.back()?;
+        let snapshot = self.current_snapshot()?;
         
         let levels = match side {
             TradeSide::Buy => &snapshot.asks,
@@ -657,6 +954,48 @@ impl OrderBookAnalyzer {
         Some(impact) // Returns impact in basis points
     }
     
+    /// Fill `quantity` against the latest snapshot as a taker order of
+    /// `order_type`, walking levels from the best price
+    pub fn simulate_fill(&self, side: TradeSide, quantity: Decimal, order_type: OrderType) -> FillResult {
+        let unfilled = FillResult { filled: Decimal::ZERO, avg_price: None, remaining: quantity };
+        let Some(snapshot) = self.current_snapshot() else {
+            return unfilled;
+        };
+        let levels = match side {
+            TradeSide::Buy => &snapshot.asks,
+            TradeSide::Sell => &snapshot.bids,
+        };
+        let limit_price = match order_type {
+            OrderType::Market => None,
+            OrderType::Ioc { limit_price } | OrderType::Fok { limit_price } => Some(limit_price),
+        };
+        let reachable = |price: Decimal| match (side, limit_price) {
+            (_, None) => true,
+            (TradeSide::Buy, Some(limit)) => price <= limit,
+            (TradeSide::Sell, Some(limit)) => price >= limit,
+        };
+
+        let mut filled = Decimal::ZERO;
+        let mut cost = Decimal::ZERO;
+        for level in levels.iter().take_while(|l| reachable(l.price)) {
+            if filled >= quantity {
+                break;
+            }
+            let fill_qty = (quantity - filled).min(level.quantity);
+            filled += fill_qty;
+            cost += fill_qty * level.price;
+        }
+
+        if filled <= Decimal::ZERO || (matches!(order_type, OrderType::Fok { .. }) && filled < quantity) {
+            return unfilled;
+        }
+        FillResult {
+            filled,
+            avg_price: Some(cost / filled),
+            remaining: quantity - filled,
+        }
+    }
+
     /// Get comprehensive analysis
     pub fn get_full_analysis(&self) -> OrderBookAnalysis {
         OrderBookAnalysis {
@@ -666,6 +1005,7 @@ impl OrderBookAnalyzer {
             market_maker: self.analyze_market_makers(),
             snapshot_count: self.snapshots.len(),
             trade_count: self.trade_flows.len(),
+            invalid_snapshots: self.invalid_snapshots,
         }
     }
     
@@ -732,6 +1072,28 @@ impl OrderBookAnalyzer {
     }
 }
 
+/// Mid of the best bid and ask
+fn mid_price(snapshot: &OrderBookSnapshot) -> Option<Decimal> {
+    Some((snapshot.bids.first()?.price + snapshot.asks.first()?.price) / dec!(2))
+}
+
+/// Pearson correlation of `(x, y)` pairs; zero when either is constant
+fn correlation(pairs: &[(f64, f64)]) -> f64 {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x <= f64::EPSILON || var_y <= f64::EPSILON {
+        return 0.0;
+    }
+    cov / (var_x * var_y).sqrt()
+}
+
 impl Default for OrderBookAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -747,6 +1109,9 @@ pub struct OrderBookAnalysis {
     pub market_maker: Option<MarketMakerAnalysis>,
     pub snapshot_count: usize,
     pub trade_count: usize,
+    /// Crossed or locked snapshots rejected
+    #[serde(default)]
+    pub invalid_snapshots: u64,
 }
 
 #[cfg(test)]
@@ -840,7 +1205,7 @@ mod tests {
                 timestamp_ms: i * 1000,
                 price: dec!(100),
                 quantity: dec!(50),
-                side,
+                side: Some(side),
             });
         }
         
@@ -851,6 +1216,90 @@ mod tests {
         assert!(vpin.buy_volume > vpin.sell_volume, "Should have more buys");
     }
     
+    /// Trades whose price follows the order flow, in regimes of mostly
+    /// buying, mostly selling and balanced flow; sides labeled or not
+    fn synthetic_flow(labeled: bool) -> Vec<TradeFlow> {
+        let mut seed: u64 = 42;
+        let mut price = dec!(0.50);
+        let mut trades = Vec::new();
+        for i in 0..2000u64 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let draw = (seed >> 33) % 100;
+            let buy_pct = [80, 20, 50, 70, 35][(i / 200 % 5) as usize];
+            let side = if draw < buy_pct { TradeSide::Buy } else { TradeSide::Sell };
+            // Each trade's price impact, plus noise of up to the same size
+            let noise = Decimal::from((seed >> 13) % 21) / dec!(10000) - dec!(0.001);
+            price += noise + match side {
+                TradeSide::Buy => dec!(0.001),
+                TradeSide::Sell => dec!(-0.001),
+            };
+            trades.push(TradeFlow {
+                timestamp_ms: i * 1000,
+                price,
+                quantity: dec!(10),
+                side: labeled.then_some(side),
+            });
+        }
+        trades
+    }
+
+    #[test]
+    fn test_bulk_volume_classification_agrees_with_labels() {
+        let config = OrderBookAnalyzerConfig { vpin_bucket_size: dec!(200), vpin_bucket_count: 100, ..Default::default() };
+        let vpin = |labeled: bool| {
+            let mut analyzer = OrderBookAnalyzer::with_config(config.clone());
+            for trade in synthetic_flow(labeled) {
+                analyzer.process_trade(trade);
+            }
+            analyzer.calculate_vpin().unwrap()
+        };
+        let (labeled, bulk) = (vpin(true), vpin(false));
+
+        assert_eq!(labeled.classification, VpinClassification::Labeled);
+        assert_eq!(bulk.classification, VpinClassification::BulkVolume);
+        assert_eq!(labeled.bucket_count, bulk.bucket_count);
+        assert!((labeled.vpin - bulk.vpin).abs() < dec!(0.15), "labeled {} vs bulk {}", labeled.vpin, bulk.vpin);
+        // Both see the net buying of the stream
+        assert!(labeled.buy_volume > labeled.sell_volume && bulk.buy_volume > bulk.sell_volume);
+        assert_eq!(labeled.buy_volume + labeled.sell_volume, bulk.buy_volume + bulk.sell_volume);
+    }
+
+    #[test]
+    fn test_unlabeled_volume_splits_evenly_without_price_moves() {
+        let mut analyzer = OrderBookAnalyzer::with_config(OrderBookAnalyzerConfig {
+            vpin_bucket_size: dec!(100),
+            ..Default::default()
+        });
+        for i in 0..10 {
+            analyzer.process_trade(TradeFlow { timestamp_ms: i, price: dec!(0.5), quantity: dec!(50), side: None });
+        }
+        analyzer.process_trade(TradeFlow { timestamp_ms: 10, price: dec!(0.5), quantity: dec!(100), side: Some(TradeSide::Buy) });
+
+        let result = analyzer.calculate_vpin().unwrap();
+        assert_eq!(result.classification, VpinClassification::Mixed);
+        assert_eq!((result.buy_volume, result.sell_volume), (dec!(350), dec!(250)));
+    }
+
+    #[test]
+    fn test_time_buckets_close_on_duration_not_volume() {
+        let mut analyzer = OrderBookAnalyzer::with_config(OrderBookAnalyzerConfig {
+            vpin_mode: VpinMode::Time,
+            vpin_bucket_secs: 60,
+            ..Default::default()
+        });
+        // A trade every 20s, far short of the volume bucket size
+        for i in 0..10u64 {
+            let side = Some(if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell });
+            analyzer.process_trade(TradeFlow { timestamp_ms: i * 20_000, price: dec!(0.5), quantity: dec!(5), side });
+        }
+
+        // 0-40s, 60-100s, 120-160s; 180s on is still filling
+        let result = analyzer.calculate_vpin().unwrap();
+        assert_eq!(result.bucket_count, 3);
+        assert_eq!(result.buy_volume + result.sell_volume, dec!(45));
+        assert_eq!(analyzer.vpin_state().current.total_volume, dec!(5));
+    }
+
     #[test]
     fn test_vpin_toxicity_levels() {
         // Low toxicity
@@ -902,6 +1351,63 @@ mod tests {
         assert!(impact_large > impact_small, "Large order should have more impact");
     }
     
+    /// Asks of 10 @ 101, 20 @ 102, 30 @ 103; bids mirror them below 100
+    fn three_level_book() -> OrderBookAnalyzer {
+        let mut analyzer = OrderBookAnalyzer::new();
+        let level = |price, quantity| BookLevel { price, quantity };
+        analyzer.process_snapshot(OrderBookSnapshot {
+            timestamp_ms: 1000,
+            bids: vec![level(dec!(100), dec!(10)), level(dec!(99), dec!(20)), level(dec!(98), dec!(30))],
+            asks: vec![level(dec!(101), dec!(10)), level(dec!(102), dec!(20)), level(dec!(103), dec!(30))],
+            last_trade_price: None,
+            last_trade_side: None,
+        });
+        analyzer
+    }
+
+    #[test]
+    fn test_ioc_fills_partially_within_limit() {
+        let analyzer = three_level_book();
+        let fill = analyzer.simulate_fill(TradeSide::Buy, dec!(50), OrderType::Ioc { limit_price: dec!(102) });
+        assert_eq!(fill.filled, dec!(30));
+        assert_eq!(fill.avg_price, Some((dec!(1010) + dec!(2040)) / dec!(30)));
+        assert_eq!(fill.remaining, dec!(20));
+
+        let sell = analyzer.simulate_fill(TradeSide::Sell, dec!(5), OrderType::Ioc { limit_price: dec!(100.5) });
+        assert_eq!(sell.filled, Decimal::ZERO);
+        assert_eq!(sell.avg_price, None);
+        assert_eq!(sell.remaining, dec!(5));
+    }
+
+    #[test]
+    fn test_fok_rejects_when_depth_is_insufficient() {
+        let analyzer = three_level_book();
+        let rejected = analyzer.simulate_fill(TradeSide::Buy, dec!(31), OrderType::Fok { limit_price: dec!(102) });
+        assert_eq!(rejected, FillResult { filled: Decimal::ZERO, avg_price: None, remaining: dec!(31) });
+
+        let filled = analyzer.simulate_fill(TradeSide::Sell, dec!(30), OrderType::Fok { limit_price: dec!(99) });
+        assert_eq!(filled.filled, dec!(30));
+        assert_eq!(filled.remaining, Decimal::ZERO);
+        assert_eq!(filled.avg_price, Some((dec!(1000) + dec!(1980)) / dec!(30)));
+    }
+
+    #[test]
+    fn test_market_fill_walks_levels() {
+        let analyzer = three_level_book();
+        let fill = analyzer.simulate_fill(TradeSide::Buy, dec!(45), OrderType::Market);
+        assert_eq!(fill.filled, dec!(45));
+        assert_eq!(fill.avg_price, Some((dec!(1010) + dec!(2040) + dec!(1545)) / dec!(45)));
+        assert_eq!(fill.remaining, Decimal::ZERO);
+
+        // More than the whole book: the excess is left unfilled
+        let exhausted = analyzer.simulate_fill(TradeSide::Sell, dec!(100), OrderType::Market);
+        assert_eq!(exhausted.filled, dec!(60));
+        assert_eq!(exhausted.remaining, dec!(40));
+
+        let empty = OrderBookAnalyzer::new().simulate_fill(TradeSide::Buy, dec!(1), OrderType::Market);
+        assert_eq!(empty.remaining, dec!(1));
+    }
+
     #[test]
     fn test_iceberg_detection() {
         let mut analyzer = OrderBookAnalyzer::with_config(OrderBookAnalyzerConfig {
@@ -974,7 +1480,7 @@ mod tests {
                 timestamp_ms: i * 1000,
                 price: dec!(100.5),
                 quantity: dec!(50),
-                side: if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell },
+                side: Some(if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell }),
             });
         }
         
@@ -994,13 +1500,45 @@ mod tests {
         assert!(analyzer.analyze_market_makers().is_none());
     }
     
+    #[test]
+    fn test_crossed_and_locked_books_are_rejected() {
+        let mut analyzer = OrderBookAnalyzer::new();
+        analyzer.process_snapshot(create_test_snapshot(dec!(100), dec!(101), dec!(1000), dec!(500)));
+        assert!(analyzer.calculate_imbalance().is_some());
+        assert_eq!(analyzer.invalid_snapshot_count(), 0);
+
+        // Crossed: no imbalance, impact or fill off it, and it isn't stored
+        let crossed = create_test_snapshot(dec!(102), dec!(101), dec!(1000), dec!(500));
+        assert!(crossed.is_crossed());
+        analyzer.process_snapshot(crossed);
+        assert!(analyzer.calculate_imbalance().is_none());
+        assert!(analyzer.estimate_price_impact(TradeSide::Buy, dec!(10)).is_none());
+        assert!(analyzer.simulate_fill(TradeSide::Buy, dec!(10), OrderType::Market).avg_price.is_none());
+        assert_eq!(analyzer.get_full_analysis().snapshot_count, 1);
+
+        // Locked
+        analyzer.process_snapshot(create_test_snapshot(dec!(101), dec!(101), dec!(1000), dec!(500)));
+        assert!(analyzer.calculate_imbalance().is_none());
+        assert_eq!(analyzer.get_full_analysis().invalid_snapshots, 2);
+
+        // A normal book passes again
+        analyzer.process_snapshot(create_test_snapshot(dec!(100), dec!(101), dec!(500), dec!(1000)));
+        let result = analyzer.calculate_imbalance().unwrap();
+        assert!(result.spread_bps > Decimal::ZERO);
+        assert_eq!(result.predicted_direction, PredictedDirection::Down);
+        assert_eq!(analyzer.invalid_snapshot_count(), 2);
+    }
+
     #[test]
     fn test_config_customization() {
         let config = OrderBookAnalyzerConfig {
             imbalance_levels: 20,
             depth_weight_decay: dec!(0.9),
+            level_weights: None,
             vpin_bucket_size: dec!(500),
             vpin_bucket_count: 100,
+            vpin_mode: VpinMode::Volume,
+            vpin_bucket_secs: 300,
             iceberg_refill_threshold: 5,
             mm_analysis_window_secs: 120,
             direction_threshold: dec!(0.2),
@@ -1061,4 +1599,75 @@ mod tests {
         assert!(result.depth_weighted_imbalance > result.simple_imbalance, 
             "Depth-weighted should show stronger buy signal when first level dominates");
     }
+
+    fn layered_snapshot() -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp_ms: 1000,
+            bids: vec![
+                BookLevel { price: dec!(100), quantity: dec!(1000) },
+                BookLevel { price: dec!(99), quantity: dec!(100) },
+                BookLevel { price: dec!(98), quantity: dec!(100) },
+            ],
+            asks: vec![
+                BookLevel { price: dec!(101), quantity: dec!(100) },
+                BookLevel { price: dec!(102), quantity: dec!(1000) },
+                BookLevel { price: dec!(103), quantity: dec!(1000) },
+            ],
+            last_trade_price: None,
+            last_trade_side: None,
+        }
+    }
+
+    #[test]
+    fn test_custom_level_weights_replace_geometric_decay() {
+        let imbalance = |level_weights: Option<Vec<Decimal>>| {
+            let mut analyzer = OrderBookAnalyzer::with_config(OrderBookAnalyzerConfig {
+                depth_weight_decay: dec!(0.5),
+                level_weights,
+                ..Default::default()
+            });
+            analyzer.process_snapshot(layered_snapshot());
+            analyzer.calculate_imbalance().unwrap().depth_weighted_imbalance
+        };
+
+        // Geometric: bids 1000 + 50 + 25, asks 100 + 500 + 250
+        assert_eq!(imbalance(None), dec!(225) / dec!(1925));
+        // Ignoring the best level flips the signal to the deeper sell wall
+        assert_eq!(imbalance(Some(vec![dec!(0), dec!(1), dec!(1)])), dec!(-1800) / dec!(2200));
+        // Levels past the end of the vector get no weight
+        assert_eq!(imbalance(Some(vec![dec!(1)])), dec!(900) / dec!(1100));
+    }
+
+    #[test]
+    fn test_fit_level_weights_favors_predictive_level() {
+        let mut analyzer = OrderBookAnalyzer::new();
+        assert!(analyzer.fit_level_weights().is_none());
+
+        // The second level's imbalance calls the next mid move; the best
+        // level alternates regardless of it
+        let mut mid = dec!(50);
+        for t in 0..60u64 {
+            let up = t % 3 != 0;
+            let (bid2, ask2) = if up { (dec!(300), dec!(100)) } else { (dec!(100), dec!(300)) };
+            let (bid1, ask1) = if t % 2 == 0 { (dec!(200), dec!(100)) } else { (dec!(100), dec!(200)) };
+            analyzer.process_snapshot(OrderBookSnapshot {
+                timestamp_ms: t * 1000,
+                bids: vec![
+                    BookLevel { price: mid - dec!(0.5), quantity: bid1 },
+                    BookLevel { price: mid - dec!(1.5), quantity: bid2 },
+                ],
+                asks: vec![
+                    BookLevel { price: mid + dec!(0.5), quantity: ask1 },
+                    BookLevel { price: mid + dec!(1.5), quantity: ask2 },
+                ],
+                last_trade_price: None,
+                last_trade_side: None,
+            });
+            mid += if up { dec!(0.5) } else { dec!(-0.5) };
+        }
+
+        let weights = analyzer.fit_level_weights().expect("enough snapshots to fit");
+        assert!(weights[1] > weights[0] * dec!(5), "{:?}", weights);
+        assert_eq!(analyzer.config.level_weights.as_ref(), Some(&weights));
+    }
 }
@@ -391,6 +391,10 @@ mod tests {
             liquidity: dec!(5000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -0,0 +1,328 @@
+//! Paper broker - simulated CLOB account backed by live order books
+//!
+//! Implements `ClobClientTrait` so anything that trades through the CLOB
+//! (executor, market maker) can run against it unchanged. Order books come
+//! from a real data source; orders, balance and positions are simulated.
+//!
+//! Fill model:
+//! - Orders that cross the book fill immediately at the touch
+//! - Non-crossing GTC/GTD orders rest and fill once the opposite touch
+//!   trades through their price on a later book fetch
+//! - FOK orders that don't cross are killed
+
+use crate::client::mock::ClobClientTrait;
+use crate::client::OrderBook;
+use crate::error::{BotError, Result};
+use crate::types::{Order, OrderStatus, OrderType, Position, Side};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A simulated fill
+#[derive(Debug, Clone)]
+pub struct PaperFill {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct BrokerState {
+    balance: Decimal,
+    positions: HashMap<String, Decimal>,
+    resting: Vec<Order>,
+    resting_ids: Vec<String>,
+    books: HashMap<String, OrderBook>,
+    fills: Vec<PaperFill>,
+    next_id: u64,
+}
+
+/// Simulated CLOB account
+pub struct PaperBroker<D: ClobClientTrait> {
+    data: D,
+    state: Mutex<BrokerState>,
+}
+
+impl<D: ClobClientTrait> PaperBroker<D> {
+    /// Create a paper broker reading order books from `data`
+    pub fn new(data: D, initial_balance: Decimal) -> Self {
+        Self {
+            data,
+            state: Mutex::new(BrokerState {
+                balance: initial_balance,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// All simulated fills so far
+    pub fn fills(&self) -> Vec<PaperFill> {
+        self.state.lock().fills.clone()
+    }
+
+    /// Simulated position in a token (shares)
+    pub fn position(&self, token_id: &str) -> Decimal {
+        self.state
+            .lock()
+            .positions
+            .get(token_id)
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Record a book and fill any resting orders it trades through
+    pub fn on_book(&self, token_id: &str, book: OrderBook) {
+        let mut state = self.state.lock();
+
+        let mut i = 0;
+        while i < state.resting.len() {
+            let order = &state.resting[i];
+            let traded_through = order.token_id == token_id
+                && match order.side {
+                    Side::Buy => book.best_ask().is_some_and(|a| a <= order.price),
+                    Side::Sell => book.best_bid().is_some_and(|b| b >= order.price),
+                };
+
+            if traded_through {
+                let order = state.resting.remove(i);
+                let order_id = state.resting_ids.remove(i);
+                Self::apply_fill(&mut state, &order_id, &order, order.price);
+            } else {
+                i += 1;
+            }
+        }
+
+        state.books.insert(token_id.to_string(), book);
+    }
+
+    fn apply_fill(state: &mut BrokerState, order_id: &str, order: &Order, price: Decimal) {
+        let notional = price * order.size;
+        let position = state.positions.entry(order.token_id.clone()).or_default();
+        match order.side {
+            Side::Buy => {
+                *position += order.size;
+                state.balance -= notional;
+            }
+            Side::Sell => {
+                *position -= order.size;
+                state.balance += notional;
+            }
+        }
+        state.fills.push(PaperFill {
+            order_id: order_id.to_string(),
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price,
+            size: order.size,
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+#[async_trait]
+impl<D: ClobClientTrait> ClobClientTrait for PaperBroker<D> {
+    async fn get_balance(&self) -> Result<Decimal> {
+        Ok(self.state.lock().balance)
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        let book = self.data.get_order_book(token_id).await?;
+        self.on_book(token_id, book.clone());
+        Ok(book)
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        let mut state = self.state.lock();
+        state.next_id += 1;
+        let order_id = format!("paper_{}", state.next_id);
+
+        if order.side == Side::Buy && state.balance < order.price * order.size {
+            return Err(BotError::InsufficientBalance {
+                required: order.price * order.size,
+                available: state.balance,
+            });
+        }
+
+        let touch = state.books.get(&order.token_id).and_then(|book| match order.side {
+            Side::Buy => book.best_ask().filter(|a| *a <= order.price),
+            Side::Sell => book.best_bid().filter(|b| *b >= order.price),
+        });
+
+        let (status, filled) = match (touch, order.order_type) {
+            (Some(price), _) => {
+                Self::apply_fill(&mut state, &order_id, order, price);
+                ("FILLED", order.size)
+            }
+            (None, OrderType::FOK) => ("CANCELLED", Decimal::ZERO),
+            (None, _) => {
+                state.resting.push(order.clone());
+                state.resting_ids.push(order_id.clone());
+                ("OPEN", Decimal::ZERO)
+            }
+        };
+
+        Ok(OrderStatus {
+            order_id,
+            status: status.to_string(),
+            filled_size: filled,
+            remaining_size: order.size - filled,
+            avg_price: touch,
+        })
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if let Some(i) = state.resting_ids.iter().position(|id| id == order_id) {
+            state.resting.remove(i);
+            state.resting_ids.remove(i);
+        }
+        Ok(())
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        let state = self.state.lock();
+        Ok(state
+            .resting
+            .iter()
+            .zip(&state.resting_ids)
+            .map(|(order, id)| OrderStatus {
+                order_id: id.clone(),
+                status: "OPEN".to_string(),
+                filled_size: Decimal::ZERO,
+                remaining_size: order.size,
+                avg_price: None,
+            })
+            .collect())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        let state = self.state.lock();
+        if let Some(i) = state.resting_ids.iter().position(|id| id == order_id) {
+            return Ok(OrderStatus {
+                order_id: order_id.to_string(),
+                status: "OPEN".to_string(),
+                filled_size: Decimal::ZERO,
+                remaining_size: state.resting[i].size,
+                avg_price: None,
+            });
+        }
+        // Paper orders fill whole or not at all, so anything else either
+        // has a fill or was cancelled
+        let fill = state.fills.iter().find(|f| f.order_id == order_id);
+        Ok(OrderStatus {
+            order_id: order_id.to_string(),
+            status: if fill.is_some() { "FILLED" } else { "CANCELLED" }.to_string(),
+            filled_size: fill.map_or(Decimal::ZERO, |f| f.size),
+            remaining_size: Decimal::ZERO,
+            avg_price: fill.map(|f| f.price),
+        })
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        let state = self.state.lock();
+        Ok(state
+            .positions
+            .iter()
+            .filter(|(_, size)| !size.is_zero())
+            .map(|(token_id, size)| {
+                let mark = state
+                    .books
+                    .get(token_id)
+                    .and_then(|b| b.midpoint())
+                    .unwrap_or(Decimal::ZERO);
+                Position {
+                    token_id: token_id.clone(),
+                    market_id: String::new(),
+                    side: if *size > Decimal::ZERO { Side::Buy } else { Side::Sell },
+                    size: size.abs(),
+                    avg_entry_price: Decimal::ZERO,
+                    current_price: mark,
+                    unrealized_pnl: Decimal::ZERO,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        Ok(self.position(token_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::MockClobClient;
+    use crate::client::OrderBookLevel;
+    use rust_decimal_macros::dec;
+
+    fn book(bid: Decimal, ask: Decimal) -> OrderBook {
+        OrderBook {
+            bids: vec![OrderBookLevel { price: bid, size: dec!(1000) }],
+            asks: vec![OrderBookLevel { price: ask, size: dec!(1000) }],
+        }
+    }
+
+    fn order(side: Side, price: Decimal, order_type: OrderType) -> Order {
+        Order {
+            token_id: "tok".to_string(),
+            side,
+            price,
+            size: dec!(10),
+            order_type,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resting_order_fills_when_traded_through() {
+        let broker = PaperBroker::new(MockClobClient::new(), dec!(100));
+        broker.on_book("tok", book(dec!(0.50), dec!(0.52)));
+
+        let status = broker.place_order(&order(Side::Buy, dec!(0.49), OrderType::GTC)).await.unwrap();
+        assert_eq!(status.status, "OPEN");
+        assert_eq!(broker.get_open_orders().await.unwrap().len(), 1);
+        assert_eq!(broker.get_balance().await.unwrap(), dec!(100));
+
+        // Ask drops to our bid
+        broker.on_book("tok", book(dec!(0.47), dec!(0.49)));
+        assert!(broker.get_open_orders().await.unwrap().is_empty());
+        assert_eq!(broker.position("tok"), dec!(10));
+        assert_eq!(broker.get_balance().await.unwrap(), dec!(95.1));
+        assert_eq!(broker.fills().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_crossing_order_fills_at_touch() {
+        let broker = PaperBroker::new(MockClobClient::new(), dec!(100));
+        broker.on_book("tok", book(dec!(0.50), dec!(0.52)));
+
+        let status = broker.place_order(&order(Side::Buy, dec!(0.60), OrderType::GTC)).await.unwrap();
+        assert_eq!(status.status, "FILLED");
+        assert_eq!(status.avg_price, Some(dec!(0.52)));
+        assert_eq!(broker.get_balance().await.unwrap(), dec!(94.8));
+    }
+
+    #[tokio::test]
+    async fn test_fok_killed_and_cancel() {
+        let broker = PaperBroker::new(MockClobClient::new(), dec!(100));
+        broker.on_book("tok", book(dec!(0.50), dec!(0.52)));
+
+        let killed = broker.place_order(&order(Side::Sell, dec!(0.55), OrderType::FOK)).await.unwrap();
+        assert_eq!(killed.status, "CANCELLED");
+
+        let resting = broker.place_order(&order(Side::Sell, dec!(0.55), OrderType::GTC)).await.unwrap();
+        broker.cancel_order(&resting.order_id).await.unwrap();
+        assert!(broker.get_open_orders().await.unwrap().is_empty());
+        assert!(broker.fills().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_balance() {
+        let broker = PaperBroker::new(MockClobClient::new(), dec!(1));
+        assert!(broker.place_order(&order(Side::Buy, dec!(0.50), OrderType::GTC)).await.is_err());
+    }
+}
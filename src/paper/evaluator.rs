@@ -513,6 +513,10 @@ mod tests {
             liquidity: dec!(10000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -615,6 +619,10 @@ mod tests {
             liquidity: dec!(0),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let characteristics = evaluator.analyze_characteristics(&market, dec!(0.5), dec!(0.5));
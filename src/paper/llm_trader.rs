@@ -6,6 +6,7 @@ use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error};
+use crate::utils::{http_client, LLM_HTTP_TIMEOUT};
 
 /// Trading decision from LLM
 #[derive(Debug, Clone, PartialEq)]
@@ -51,7 +52,7 @@ pub struct LlmTrader {
 impl LlmTrader {
     pub fn new(api_key: String) -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(LLM_HTTP_TIMEOUT),
             api_key,
             model: "deepseek-chat".to_string(),
         }
@@ -8,12 +8,14 @@ mod trader;
 mod evaluator;
 mod llm_trader;
 mod auto_trader;
+mod broker;
 
 pub use position::{Position, PositionSide, PositionStatus};
 pub use trader::{PaperTrader, PaperTraderConfig, TradeRecord, TradeAction};
 pub use evaluator::{MarketEvaluator, EvaluationResult, ConfidenceLevel};
 pub use llm_trader::{LlmTrader, TradeDecision, PositionContext, MarketContext};
 pub use auto_trader::{AutoTrader, AutoTraderConfig, AutoCloseResult, AutoCloseReason, PriceSnapshot, AuditEntry};
+pub use broker::{PaperBroker, PaperFill};
 
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
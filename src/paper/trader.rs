@@ -548,6 +548,10 @@ mod tests {
             liquidity: dec!(10000),
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
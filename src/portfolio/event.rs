@@ -0,0 +1,244 @@
+//! Event-level positions for negRisk events
+//!
+//! In a negRisk event exactly one market resolves YES, so positions across
+//! its markets are not independent: one YES on every market, or one NO on
+//! every market but one plus that market's YES, always redeem for $1. The
+//! view below values the whole event at once.
+//!
+//! With `y_i` YES and `n_i` NO shares on market `i`, if market `k` wins the
+//! payout is `y_k + Σ_{i≠k} n_i`. The minimum over `k` is locked in whatever
+//! happens (redeemable value); the rest depends on the winner (directional).
+//!
+//! The listed markets are assumed to cover every outcome.
+
+use crate::types::Market;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Holdings in one market of an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventLeg {
+    pub market_id: String,
+    /// YES shares held (negative if short)
+    pub yes: Decimal,
+    /// NO shares held (negative if short)
+    pub no: Decimal,
+    /// Current YES price
+    pub yes_price: Decimal,
+}
+
+/// Aggregated exposure across the markets of one negRisk event
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventPositionView {
+    pub event_id: String,
+    pub legs: Vec<EventLeg>,
+}
+
+impl EventPositionView {
+    pub fn new(event_id: &str, legs: Vec<EventLeg>) -> Self {
+        Self {
+            event_id: event_id.to_string(),
+            legs,
+        }
+    }
+
+    /// Build the view from an event's markets and token holdings
+    /// (token_id -> shares). Outcome 0 is YES and outcome 1 is NO, as Gamma
+    /// lists them.
+    pub fn from_holdings(event_id: &str, markets: &[Market], holdings: &HashMap<String, Decimal>) -> Self {
+        let held = |token_id: Option<&str>| {
+            token_id
+                .and_then(|t| holdings.get(t))
+                .copied()
+                .unwrap_or(Decimal::ZERO)
+        };
+
+        let legs = markets
+            .iter()
+            .map(|market| {
+                let yes = market.outcomes.first();
+                let no = market.outcomes.get(1);
+                EventLeg {
+                    market_id: market.id.clone(),
+                    yes: held(yes.map(|o| o.token_id.as_str())),
+                    no: held(no.map(|o| o.token_id.as_str())),
+                    yes_price: yes.map(|o| o.price).unwrap_or(Decimal::ZERO),
+                }
+            })
+            .collect();
+
+        Self::new(event_id, legs)
+    }
+
+    /// Whether no shares are held in the event
+    pub fn is_flat(&self) -> bool {
+        self.legs.iter().all(|l| l.yes.is_zero() && l.no.is_zero())
+    }
+
+    /// Payout if `market_id` is the market that resolves YES
+    pub fn payout_if(&self, market_id: &str) -> Decimal {
+        let total_no: Decimal = self.legs.iter().map(|l| l.no).sum();
+        self.legs
+            .iter()
+            .find(|l| l.market_id == market_id)
+            .map(|l| l.yes + total_no - l.no)
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Payout for each possible winner, in leg order
+    pub fn payouts(&self) -> Vec<(String, Decimal)> {
+        self.legs
+            .iter()
+            .map(|l| (l.market_id.clone(), self.payout_if(&l.market_id)))
+            .collect()
+    }
+
+    /// Value redeemable regardless of which market wins
+    pub fn redeemable_value(&self) -> Decimal {
+        self.payouts()
+            .into_iter()
+            .map(|(_, p)| p)
+            .min()
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Payout that still depends on the winner: best case minus the
+    /// redeemable floor. Zero for a fully hedged basket.
+    pub fn net_directional_exposure(&self) -> Decimal {
+        let best = self
+            .payouts()
+            .into_iter()
+            .map(|(_, p)| p)
+            .max()
+            .unwrap_or(Decimal::ZERO);
+        best - self.redeemable_value()
+    }
+
+    /// Per-market exposure above the redeemable floor
+    pub fn directional_by_market(&self) -> Vec<(String, Decimal)> {
+        let floor = self.redeemable_value();
+        self.payouts()
+            .into_iter()
+            .map(|(market_id, p)| (market_id, p - floor))
+            .collect()
+    }
+
+    /// Mark-to-market value at current YES prices
+    pub fn mark_value(&self) -> Decimal {
+        self.legs
+            .iter()
+            .map(|l| l.yes * l.yes_price + l.no * (Decimal::ONE - l.yes_price))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+    use rust_decimal_macros::dec;
+
+    fn leg(market_id: &str, yes: Decimal, no: Decimal, yes_price: Decimal) -> EventLeg {
+        EventLeg { market_id: market_id.to_string(), yes, no, yes_price }
+    }
+
+    #[test]
+    fn test_yes_basket_fully_redeemable() {
+        let view = EventPositionView::new(
+            "e",
+            vec![
+                leg("a", dec!(10), dec!(0), dec!(0.5)),
+                leg("b", dec!(10), dec!(0), dec!(0.3)),
+                leg("c", dec!(10), dec!(0), dec!(0.2)),
+            ],
+        );
+        assert_eq!(view.redeemable_value(), dec!(10));
+        assert_eq!(view.net_directional_exposure(), dec!(0));
+        assert_eq!(view.mark_value(), dec!(10));
+    }
+
+    #[test]
+    fn test_yes_plus_complementary_no_redeems() {
+        // a wins: every leg pays; b or c wins: only the other NO pays
+        let view = EventPositionView::new(
+            "e",
+            vec![
+                leg("a", dec!(5), dec!(0), dec!(0.5)),
+                leg("b", dec!(0), dec!(5), dec!(0.3)),
+                leg("c", dec!(0), dec!(5), dec!(0.2)),
+            ],
+        );
+        assert_eq!(view.payout_if("a"), dec!(15));
+        assert_eq!(view.payout_if("b"), dec!(5));
+        assert_eq!(view.payout_if("c"), dec!(5));
+        assert_eq!(view.redeemable_value(), dec!(5));
+        assert_eq!(view.net_directional_exposure(), dec!(10));
+    }
+
+    #[test]
+    fn test_no_basket_redeems_n_minus_one() {
+        let view = EventPositionView::new(
+            "e",
+            vec![
+                leg("a", dec!(0), dec!(4), dec!(0.5)),
+                leg("b", dec!(0), dec!(4), dec!(0.3)),
+                leg("c", dec!(0), dec!(4), dec!(0.2)),
+            ],
+        );
+        assert_eq!(view.redeemable_value(), dec!(8));
+        assert_eq!(view.net_directional_exposure(), dec!(0));
+    }
+
+    #[test]
+    fn test_single_yes_is_fully_directional() {
+        let view = EventPositionView::new(
+            "e",
+            vec![
+                leg("a", dec!(20), dec!(0), dec!(0.4)),
+                leg("b", dec!(0), dec!(0), dec!(0.6)),
+            ],
+        );
+        assert_eq!(view.redeemable_value(), dec!(0));
+        assert_eq!(view.net_directional_exposure(), dec!(20));
+        assert_eq!(
+            view.directional_by_market(),
+            vec![("a".to_string(), dec!(20)), ("b".to_string(), dec!(0))]
+        );
+        assert_eq!(view.mark_value(), dec!(8));
+    }
+
+    #[test]
+    fn test_from_holdings() {
+        let market = |id: &str, price| Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: dec!(0),
+            liquidity: dec!(0),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: dec!(1) - price },
+            ],
+            active: true,
+            closed: false,
+            event_id: Some("e".to_string()),
+            neg_risk: true,
+            category: None,
+            order_constraints: None,
+        };
+        let (a, b) = (market("a", dec!(0.7)), market("b", dec!(0.3)));
+        let holdings = HashMap::from([
+            ("a-yes".to_string(), dec!(3)),
+            ("b-no".to_string(), dec!(2)),
+        ]);
+
+        let view = EventPositionView::from_holdings("e", &[a, b], &holdings);
+        assert_eq!(view.legs[0], leg("a", dec!(3), dec!(0), dec!(0.7)));
+        assert_eq!(view.legs[1], leg("b", dec!(0), dec!(2), dec!(0.3)));
+        // a wins: 3 YES + 2 NO on b; b wins: nothing
+        assert_eq!(view.payout_if("a"), dec!(5));
+        assert_eq!(view.payout_if("b"), dec!(0));
+        assert!(!view.is_flat());
+    }
+}
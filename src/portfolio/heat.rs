@@ -0,0 +1,215 @@
+//! Portfolio heat: how much edge-weighted risk the open positions carry
+//!
+//! The heat of a position is what the bot thinks it knows about it in
+//! dollars, `size * |edge|`; a portfolio's heat is the sum over its
+//! positions. [`PortfolioHeatMonitor`] measures it against the
+//! `max_heat_budget` in `[risk]`: past [`HEAT_WARNING`] of the budget new
+//! signals are sized at half Kelly, and past the budget only hedges trade.
+
+use crate::types::{Market, MarketCategory, Side, Signal, Trade};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Utilization above which new signals are sized at half Kelly
+pub const HEAT_WARNING: Decimal = dec!(0.8);
+
+/// Utilization above which only hedges trade
+pub const HEAT_LIMIT: Decimal = Decimal::ONE;
+
+/// Heat of the open positions
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeatMetrics {
+    /// Sum of `size * |edge|` over the open positions
+    pub total_heat_usdc: Decimal,
+    /// Heat as a fraction of the budget; zero without a budget
+    pub heat_utilization: Decimal,
+    /// Token ID of the position with the most heat
+    pub hottest_position: Option<String>,
+    pub heat_by_category: HashMap<MarketCategory, Decimal>,
+}
+
+impl HeatMetrics {
+    /// Factor on the Kelly size of a new `signal` at this heat: halved past
+    /// the warning level, `None` (blocked) past the limit. Hedges of
+    /// `positions` always trade in full.
+    pub fn kelly_scale(&self, signal: &Signal, positions: &[Trade]) -> Option<Decimal> {
+        if is_hedge(signal, positions) {
+            Some(Decimal::ONE)
+        } else if self.heat_utilization > HEAT_LIMIT {
+            None
+        } else if self.heat_utilization > HEAT_WARNING {
+            Some(dec!(0.5))
+        } else {
+            Some(Decimal::ONE)
+        }
+    }
+}
+
+/// Whether `signal` takes risk off: selling a held token, or buying into a
+/// market held through another of its outcomes
+pub fn is_hedge(signal: &Signal, positions: &[Trade]) -> bool {
+    positions.iter().any(|p| match signal.side {
+        Side::Sell => p.token_id == signal.token_id,
+        Side::Buy => p.market_id == signal.market_id && p.token_id != signal.token_id,
+    })
+}
+
+/// Measures portfolio heat against a budget
+#[derive(Debug, Clone)]
+pub struct PortfolioHeatMonitor {
+    /// Heat allowed in USDC; zero or less leaves heat unlimited
+    pub max_heat_budget: Decimal,
+    /// market_id -> category, for the breakdown
+    categories: HashMap<String, MarketCategory>,
+}
+
+impl PortfolioHeatMonitor {
+    pub fn new(max_heat_budget: Decimal) -> Self {
+        Self {
+            max_heat_budget,
+            categories: HashMap::new(),
+        }
+    }
+
+    /// Break the heat of `market`'s positions down under its category;
+    /// positions on markets never registered count as `Other`
+    pub fn register_market(&mut self, market: &Market) {
+        self.categories.insert(market.id.clone(), market.market_category());
+    }
+
+    /// Heat of `positions`, one trade per open position holding its size.
+    /// `edges` holds the current edge by token ID; positions without one
+    /// fall back to the edge they were entered at.
+    pub fn calculate(&self, positions: &[Trade], edges: &HashMap<String, Decimal>) -> HeatMetrics {
+        let mut metrics = HeatMetrics::default();
+        let mut hottest = Decimal::ZERO;
+        for position in positions {
+            let edge = edges.get(&position.token_id).copied().or(position.edge).unwrap_or_default();
+            let heat = position.size.abs() * edge.abs();
+            metrics.total_heat_usdc += heat;
+            let category = self.categories.get(&position.market_id).copied().unwrap_or_default();
+            *metrics.heat_by_category.entry(category).or_default() += heat;
+            if heat > hottest {
+                hottest = heat;
+                metrics.hottest_position = Some(position.token_id.clone());
+            }
+        }
+        if self.max_heat_budget > Decimal::ZERO {
+            metrics.heat_utilization = metrics.total_heat_usdc / self.max_heat_budget;
+        }
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TradeStatus;
+    use chrono::Utc;
+
+    fn position(market_id: &str, token_id: &str, size: Decimal, edge: Option<Decimal>) -> Trade {
+        Trade {
+            id: format!("t-{}", token_id),
+            order_id: format!("o-{}", token_id),
+            token_id: token_id.to_string(),
+            market_id: market_id.to_string(),
+            side: Side::Buy,
+            price: dec!(0.5),
+            size,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge,
+        }
+    }
+
+    fn signal(market_id: &str, token_id: &str, side: Side) -> Signal {
+        Signal {
+            market_id: market_id.to_string(),
+            token_id: token_id.to_string(),
+            side,
+            model_probability: dec!(0.6),
+            market_probability: dec!(0.5),
+            edge: dec!(0.1),
+            confidence: dec!(0.8),
+            suggested_size: dec!(0.05),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            posterior_variance: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    fn market(id: &str, category: &str) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: Vec::new(),
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: Some(category.to_string()),
+            order_constraints: None,
+        }
+    }
+
+    #[test]
+    fn test_calculate_sums_edge_weighted_size() {
+        let mut monitor = PortfolioHeatMonitor::new(dec!(50));
+        monitor.register_market(&market("btc", "Crypto"));
+        monitor.register_market(&market("vote", "Politics"));
+        let positions = [
+            position("btc", "btc-yes", dec!(200), Some(dec!(0.05))),
+            position("vote", "vote-no", dec!(100), Some(dec!(0.10))),
+            position("unknown", "x-yes", dec!(50), None),
+        ];
+        // The current edge on btc-yes has turned negative
+        let edges = HashMap::from([("btc-yes".to_string(), dec!(-0.12))]);
+
+        let metrics = monitor.calculate(&positions, &edges);
+        assert_eq!(metrics.total_heat_usdc, dec!(34));
+        assert_eq!(metrics.heat_utilization, dec!(0.68));
+        assert_eq!(metrics.hottest_position.as_deref(), Some("btc-yes"));
+        assert_eq!(metrics.heat_by_category[&MarketCategory::Crypto], dec!(24));
+        assert_eq!(metrics.heat_by_category[&MarketCategory::Politics], dec!(10));
+        assert_eq!(metrics.heat_by_category[&MarketCategory::Other], Decimal::ZERO);
+        assert_eq!(metrics.kelly_scale(&signal("m2", "m2-yes", Side::Buy), &positions), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_heat_levels_scale_kelly_and_block_non_hedges() {
+        let positions = [position("m1", "m1-yes", dec!(100), Some(dec!(0.10)))];
+        let hot = PortfolioHeatMonitor::new(dec!(11)).calculate(&positions, &HashMap::new());
+        assert_eq!(hot.kelly_scale(&signal("m2", "m2-yes", Side::Buy), &positions), Some(dec!(0.5)));
+
+        let over = PortfolioHeatMonitor::new(dec!(9)).calculate(&positions, &HashMap::new());
+        assert_eq!(over.kelly_scale(&signal("m2", "m2-yes", Side::Buy), &positions), None);
+        assert_eq!(over.kelly_scale(&signal("m1", "m1-yes", Side::Buy), &positions), None);
+        // Selling the position or buying the other side takes risk off
+        assert_eq!(over.kelly_scale(&signal("m1", "m1-yes", Side::Sell), &positions), Some(Decimal::ONE));
+        assert_eq!(over.kelly_scale(&signal("m1", "m1-no", Side::Buy), &positions), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_no_budget_means_no_limit() {
+        let positions = [position("m1", "m1-yes", dec!(1000), Some(dec!(0.5)))];
+        let metrics = PortfolioHeatMonitor::new(Decimal::ZERO).calculate(&positions, &HashMap::new());
+        assert_eq!(metrics.total_heat_usdc, dec!(500));
+        assert_eq!(metrics.heat_utilization, Decimal::ZERO);
+        assert_eq!(metrics.kelly_scale(&signal("m2", "m2-yes", Side::Buy), &positions), Some(Decimal::ONE));
+    }
+}
@@ -0,0 +1,320 @@
+//! When locked capital frees up
+//!
+//! Open positions are grouped by how far away their market's end date is.
+//! Each rung shows the capital locked at cost, the value the market
+//! currently expects the positions to pay out, and the payout if every
+//! market in the rung resolves in our favour or against us. Payouts are
+//! per market: holding both outcomes of a market pays out whichever wins.
+//!
+//! Gamma moves end dates, so the cached metadata of markets with open
+//! positions is refreshed daily (see [`refresh_position_markets`]).
+
+use crate::client::GammaClient;
+use crate::storage::{Database, LadderPosition};
+use crate::types::Market;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// How soon a position's market is expected to resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LadderBucket {
+    /// End date passed without the market resolving
+    Overdue,
+    /// Within 7 days, or resolved and awaiting redemption
+    ThisWeek,
+    /// Within 30 days
+    ThisMonth,
+    /// Within 90 days
+    ThisQuarter,
+    Later,
+    /// End date unknown, e.g. the market was never cached
+    Undated,
+}
+
+impl LadderBucket {
+    /// Bucket of a market as of `now`; `None` for an uncached market
+    pub fn of(market: Option<&Market>, now: DateTime<Utc>) -> Self {
+        let Some(market) = market else {
+            return LadderBucket::Undated;
+        };
+        if market.closed {
+            return LadderBucket::ThisWeek;
+        }
+        match market.end_date {
+            None => LadderBucket::Undated,
+            Some(end) if end < now => LadderBucket::Overdue,
+            Some(end) if end - now <= Duration::days(7) => LadderBucket::ThisWeek,
+            Some(end) if end - now <= Duration::days(30) => LadderBucket::ThisMonth,
+            Some(end) if end - now <= Duration::days(90) => LadderBucket::ThisQuarter,
+            Some(_) => LadderBucket::Later,
+        }
+    }
+}
+
+impl fmt::Display for LadderBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            LadderBucket::Overdue => "Overdue",
+            LadderBucket::ThisWeek => "This week",
+            LadderBucket::ThisMonth => "This month",
+            LadderBucket::ThisQuarter => "This quarter",
+            LadderBucket::Later => "Later",
+            LadderBucket::Undated => "No end date",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Capital locked in one bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderRung {
+    pub bucket: LadderBucket,
+    /// Open positions (token and account pairs)
+    pub positions: usize,
+    /// Cost of the shares held
+    pub locked: Decimal,
+    /// Payout at current prices
+    pub expected_value: Decimal,
+    /// Payout if every market resolves our way
+    pub best_case: Decimal,
+    /// Payout if every market resolves against us
+    pub worst_case: Decimal,
+}
+
+/// A position whose market is past its end date but not resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverduePosition {
+    pub market_id: String,
+    pub question: String,
+    pub end_date: DateTime<Utc>,
+    pub locked: Decimal,
+}
+
+/// Open positions laddered by expected resolution date
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapitalLadder {
+    /// Non-empty buckets, soonest first
+    pub rungs: Vec<LadderRung>,
+    pub overdue: Vec<OverduePosition>,
+}
+
+impl CapitalLadder {
+    pub fn build(positions: &[LadderPosition], now: DateTime<Utc>) -> Self {
+        let mut by_market: BTreeMap<&str, Vec<&LadderPosition>> = BTreeMap::new();
+        for position in positions {
+            by_market.entry(&position.position.market_id).or_default().push(position);
+        }
+
+        let mut rungs: BTreeMap<LadderBucket, LadderRung> = BTreeMap::new();
+        let mut overdue = Vec::new();
+        for (market_id, held) in by_market {
+            let market = held[0].market.as_ref();
+            let bucket = LadderBucket::of(market, now);
+            let locked: Decimal = held.iter().map(|p| p.position.size * p.position.avg_price).sum();
+            let (worst, best) = payout_range(market, &held);
+
+            let rung = rungs.entry(bucket).or_insert_with(|| LadderRung {
+                bucket,
+                positions: 0,
+                locked: Decimal::ZERO,
+                expected_value: Decimal::ZERO,
+                best_case: Decimal::ZERO,
+                worst_case: Decimal::ZERO,
+            });
+            rung.positions += held.len();
+            rung.locked += locked;
+            rung.expected_value += held.iter().map(|p| p.position.size * mark_price(p)).sum::<Decimal>();
+            rung.best_case += best;
+            rung.worst_case += worst;
+
+            if let (LadderBucket::Overdue, Some(market)) = (bucket, market) {
+                overdue.push(OverduePosition {
+                    market_id: market_id.to_string(),
+                    question: market.question.clone(),
+                    end_date: market.end_date.unwrap_or(now),
+                    locked,
+                });
+            }
+        }
+
+        overdue.sort_by_key(|p| p.end_date);
+        Self {
+            rungs: rungs.into_values().collect(),
+            overdue,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rungs.is_empty()
+    }
+
+    /// Cost of all open positions
+    pub fn total_locked(&self) -> Decimal {
+        self.rungs.iter().map(|r| r.locked).sum()
+    }
+}
+
+/// Current price of the held token, falling back to the entry price
+fn mark_price(held: &LadderPosition) -> Decimal {
+    held.market
+        .as_ref()
+        .and_then(|m| m.outcomes.iter().find(|o| o.token_id == held.position.token_id))
+        .map(|o| o.price)
+        .unwrap_or(held.position.avg_price)
+}
+
+/// Lowest and highest payout of the holdings in one market over its
+/// outcomes. Without metadata each held token is taken as a separate
+/// outcome, plus one that is not held.
+fn payout_range(market: Option<&Market>, held: &[&LadderPosition]) -> (Decimal, Decimal) {
+    let shares = |token_id: &str| -> Decimal {
+        held.iter()
+            .filter(|p| p.position.token_id == token_id)
+            .map(|p| p.position.size)
+            .sum()
+    };
+    let payouts: Vec<Decimal> = match market {
+        Some(market) if !market.outcomes.is_empty() => {
+            market.outcomes.iter().map(|o| shares(&o.token_id)).collect()
+        }
+        _ => {
+            let mut payouts: Vec<Decimal> = held.iter().map(|p| shares(&p.position.token_id)).collect();
+            payouts.push(Decimal::ZERO);
+            payouts
+        }
+    };
+
+    let worst = payouts.iter().copied().min().unwrap_or(Decimal::ZERO);
+    let best = payouts.iter().copied().max().unwrap_or(Decimal::ZERO);
+    (worst, best)
+}
+
+/// Re-fetch the metadata of every market with an open position, picking up
+/// moved end dates and resolutions; returns how many markets were refreshed
+pub async fn refresh_position_markets(db: &Database, gamma: &GammaClient) -> crate::error::Result<usize> {
+    let mut refreshed = 0;
+    for market_id in db.get_open_position_markets().await? {
+        match gamma.get_market(&market_id).await {
+            Ok(market) => {
+                db.cache_market(&market).await?;
+                refreshed += 1;
+            }
+            Err(e) => tracing::warn!("Failed to refresh market {}: {}", market_id, e),
+        }
+    }
+    Ok(refreshed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::OpenPosition;
+    use crate::types::Outcome;
+    use rust_decimal_macros::dec;
+
+    fn market(id: &str, end_date: Option<DateTime<Utc>>, yes_price: Decimal) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("Question {}?", id),
+            description: None,
+            end_date,
+            volume: dec!(10000),
+            liquidity: dec!(5000),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn held(market: Option<&Market>, market_id: &str, token: &str, size: Decimal, avg_price: Decimal) -> LadderPosition {
+        LadderPosition {
+            position: OpenPosition {
+                account_id: None,
+                market_id: market_id.to_string(),
+                token_id: format!("{}-{}", market_id, token),
+                size,
+                avg_price,
+                strategy_name: None,
+            },
+            market: market.cloned(),
+        }
+    }
+
+    #[test]
+    fn test_positions_bucketed_by_end_date() {
+        let now = Utc::now();
+        let soon = market("a", Some(now + Duration::days(3)), dec!(0.60));
+        let month = market("b", Some(now + Duration::days(20)), dec!(0.30));
+        let late = market("c", Some(now + Duration::days(200)), dec!(0.50));
+        let overdue = market("d", Some(now - Duration::days(2)), dec!(0.90));
+
+        let ladder = CapitalLadder::build(
+            &[
+                held(Some(&soon), "a", "yes", dec!(100), dec!(0.50)),
+                // Both sides of b: one of them always pays
+                held(Some(&month), "b", "yes", dec!(10), dec!(0.20)),
+                held(Some(&month), "b", "no", dec!(40), dec!(0.70)),
+                held(Some(&late), "c", "yes", dec!(20), dec!(0.50)),
+                held(Some(&overdue), "d", "no", dec!(5), dec!(0.20)),
+                held(None, "e", "yes", dec!(8), dec!(0.25)),
+            ],
+            now,
+        );
+
+        let buckets: Vec<_> = ladder.rungs.iter().map(|r| r.bucket).collect();
+        assert_eq!(
+            buckets,
+            vec![
+                LadderBucket::Overdue,
+                LadderBucket::ThisWeek,
+                LadderBucket::ThisMonth,
+                LadderBucket::Later,
+                LadderBucket::Undated,
+            ]
+        );
+
+        let week = &ladder.rungs[1];
+        assert_eq!(week.locked, dec!(50));
+        assert_eq!(week.expected_value, dec!(60));
+        assert_eq!((week.worst_case, week.best_case), (Decimal::ZERO, dec!(100)));
+
+        let month = &ladder.rungs[2];
+        assert_eq!(month.positions, 2);
+        assert_eq!(month.locked, dec!(30));
+        assert_eq!(month.expected_value, dec!(3) + dec!(28));
+        assert_eq!((month.worst_case, month.best_case), (dec!(10), dec!(40)));
+
+        // Uncached market: priced at entry, may pay nothing
+        let undated = &ladder.rungs[4];
+        assert_eq!(undated.expected_value, dec!(2));
+        assert_eq!((undated.worst_case, undated.best_case), (Decimal::ZERO, dec!(8)));
+
+        assert_eq!(ladder.overdue.len(), 1);
+        assert_eq!(ladder.overdue[0].market_id, "d");
+        assert_eq!(ladder.overdue[0].locked, dec!(1));
+        assert_eq!(ladder.total_locked(), dec!(50) + dec!(30) + dec!(10) + dec!(1) + dec!(2));
+    }
+
+    #[test]
+    fn test_resolved_market_is_not_overdue() {
+        let now = Utc::now();
+        let mut resolved = market("a", Some(now - Duration::days(1)), dec!(1));
+        resolved.closed = true;
+        assert_eq!(LadderBucket::of(Some(&resolved), now), LadderBucket::ThisWeek);
+
+        resolved.closed = false;
+        assert_eq!(LadderBucket::of(Some(&resolved), now), LadderBucket::Overdue);
+        // A moved end date takes the market out of the overdue list
+        resolved.end_date = Some(now + Duration::days(45));
+        assert_eq!(LadderBucket::of(Some(&resolved), now), LadderBucket::ThisQuarter);
+    }
+}
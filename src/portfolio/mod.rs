@@ -8,6 +8,11 @@
 //! - Hierarchical Risk Parity (HRP)
 //! - Black-Litterman Model
 //! - Risk Budgeting
+//! - Drift-triggered rebalancing toward target weights
+//! - Event-level exposure for negRisk events
+//! - Capital ladder by expected resolution date
+//! - Portfolio heat: edge-weighted exposure against a budget
+//! - Redemption of winning shares once their market resolves
 //!
 //! ```rust,ignore
 //! use polymarket_bot::portfolio::{PortfolioOptimizer, OptimizationMethod};
@@ -16,6 +21,19 @@
 //! let weights = optimizer.optimize(OptimizationMethod::MaxSharpe)?;
 //! ```
 
+pub mod event;
+pub mod rebalance;
+pub mod ladder;
+pub mod heat;
+pub mod redeem;
+
+pub use event::{EventLeg, EventPositionView};
+pub use rebalance::{PortfolioRebalancer, RebalanceReport, RebalanceTrade};
+pub use ladder::{CapitalLadder, LadderBucket, LadderRung, OverduePosition};
+pub use heat::{HeatMetrics, PortfolioHeatMonitor};
+pub use redeem::{CtfRedeemer, Redeemer, RedemptionClient};
+
+use crate::regime::{MarketRegime, RegimeHistory};
 use rust_decimal::Decimal;
 #[allow(unused_imports)]
 use rust_decimal::prelude::*;
@@ -81,6 +99,8 @@ pub struct PortfolioConstraints {
     pub max_assets: usize,
     /// Sector constraints: (sector_name, max_weight)
     pub sector_limits: HashMap<String, Decimal>,
+    /// Sector of each asset, by symbol; assets without one are unconstrained
+    pub asset_sectors: HashMap<String, String>,
     /// Long-only constraint (no short selling)
     pub long_only: bool,
     /// Maximum turnover from current portfolio
@@ -97,6 +117,7 @@ impl Default for PortfolioConstraints {
             min_assets: 1,
             max_assets: 0, // unlimited
             sector_limits: HashMap::new(),
+            asset_sectors: HashMap::new(),
             long_only: true,
             max_turnover: None,
             current_weights: None,
@@ -167,6 +188,51 @@ impl PortfolioOptimizer {
         returns: &[Vec<Decimal>],
         risk_free_rate: Decimal,
         annualization_factor: u32,
+    ) -> Result<Self, PortfolioError> {
+        let weights = vec![Decimal::ONE; returns.len()];
+        Self::from_weighted_returns(symbols, returns, &weights, risk_free_rate, annualization_factor)
+    }
+
+    /// Create optimizer from returns matrix with exponentially weighted
+    /// (EWMA) mean and covariance
+    ///
+    /// Each period weighs half as much as the one `halflife_periods` later,
+    /// so recent correlations dominate after a regime shift. An infinite
+    /// half-life weights every period equally, as [`Self::from_returns`].
+    /// Arguments otherwise as in [`Self::from_returns`]; `returns` is
+    /// oldest first.
+    pub fn from_returns_ewm(
+        symbols: Vec<String>,
+        returns: &[Vec<Decimal>],
+        risk_free_rate: Decimal,
+        annualization_factor: u32,
+        halflife_periods: f64,
+    ) -> Result<Self, PortfolioError> {
+        if halflife_periods.is_nan() || halflife_periods <= 0.0 {
+            return Err(PortfolioError::InvalidConstraint(format!(
+                "EWMA half-life must be positive, got {}",
+                halflife_periods
+            )));
+        }
+        let decay = 0.5f64.powf(1.0 / halflife_periods);
+        let n_periods = returns.len();
+        let weights: Vec<Decimal> = (0..n_periods)
+            .map(|t| Decimal::from_f64(decay.powi((n_periods - 1 - t) as i32)).unwrap_or(Decimal::ZERO))
+            .collect();
+        Self::from_weighted_returns(symbols, returns, &weights, risk_free_rate, annualization_factor)
+    }
+
+    /// Create optimizer from returns weighted per period
+    ///
+    /// The mean and covariance are weighted by `weights` (one per row of
+    /// `returns`); the covariance is unbiased for reliability weights, so
+    /// equal weights give the sample covariance (divided by n-1).
+    fn from_weighted_returns(
+        symbols: Vec<String>,
+        returns: &[Vec<Decimal>],
+        weights: &[Decimal],
+        risk_free_rate: Decimal,
+        annualization_factor: u32,
     ) -> Result<Self, PortfolioError> {
         let n_assets = symbols.len();
         let n_periods = returns.len();
@@ -194,43 +260,52 @@ impl PortfolioOptimizer {
                 });
             }
         }
+        if weights.len() != n_periods {
+            return Err(PortfolioError::DimensionMismatch {
+                expected: n_periods,
+                actual: weights.len(),
+            });
+        }
+        
+        // Weight totals: V1 = sum(w), V2 = sum(w^2)
+        let weight_sum: Decimal = weights.iter().sum();
+        let weight_sq_sum: Decimal = weights.iter().map(|w| w * w).sum();
+        let divisor = weight_sum - weight_sq_sum / weight_sum;
+        if divisor <= Decimal::ZERO {
+            return Err(PortfolioError::InsufficientData {
+                required: 2,
+                actual: 1,
+            });
+        }
         
-        // Calculate expected returns (mean)
-        let mut expected_returns = vec![Decimal::ZERO; n_assets];
-        for row in returns {
+        // Calculate expected returns (weighted mean)
+        let mut mean_returns = vec![Decimal::ZERO; n_assets];
+        for (row, &w) in returns.iter().zip(weights) {
             for (j, &ret) in row.iter().enumerate() {
-                expected_returns[j] += ret;
+                mean_returns[j] += w * ret;
             }
         }
-        let n_dec = Decimal::from(n_periods as u32);
-        for ret in &mut expected_returns {
-            *ret /= n_dec;
+        for ret in &mut mean_returns {
+            *ret /= weight_sum;
         }
         
         // Annualize returns
         let ann_factor = Decimal::from(annualization_factor);
-        for ret in &mut expected_returns {
-            *ret *= ann_factor;
-        }
+        let expected_returns: Vec<Decimal> = mean_returns.iter().map(|r| *r * ann_factor).collect();
         
         // Calculate covariance matrix
         let mut covariance_matrix = vec![vec![Decimal::ZERO; n_assets]; n_assets];
-        let mean_returns: Vec<Decimal> = expected_returns.iter()
-            .map(|r| *r / ann_factor)
-            .collect();
-        
-        for row in returns {
+        for (row, &w) in returns.iter().zip(weights) {
             for i in 0..n_assets {
                 for j in 0..n_assets {
                     let dev_i = row[i] - mean_returns[i];
                     let dev_j = row[j] - mean_returns[j];
-                    covariance_matrix[i][j] += dev_i * dev_j;
+                    covariance_matrix[i][j] += w * dev_i * dev_j;
                 }
             }
         }
         
-        // Divide by (n-1) for sample covariance and annualize
-        let divisor = Decimal::from((n_periods - 1) as u32);
+        // Divide by V1 - V2/V1 (n-1 for equal weights) and annualize
         for i in 0..n_assets {
             for j in 0..n_assets {
                 covariance_matrix[i][j] = covariance_matrix[i][j] / divisor * ann_factor;
@@ -266,11 +341,32 @@ impl PortfolioOptimizer {
         })
     }
     
+    /// Create optimizer from the returns of the periods in one market regime
+    ///
+    /// `returns` has one row per bar analyzed into `history`; only the rows
+    /// of bars classified as `regime` are used, giving that regime's
+    /// covariance matrix.
+    pub fn from_regime_returns(
+        symbols: Vec<String>,
+        returns: &[Vec<Decimal>],
+        history: &RegimeHistory,
+        regime: MarketRegime,
+        risk_free_rate: Decimal,
+        annualization_factor: u32,
+    ) -> Result<Self, PortfolioError> {
+        let rows: Vec<Vec<Decimal>> = history
+            .filter_by_regime(regime)
+            .into_iter()
+            .filter_map(|i| returns.get(i).cloned())
+            .collect();
+        Self::from_returns(symbols, &rows, risk_free_rate, annualization_factor)
+    }
+
     /// Create optimizer from pre-computed statistics
     pub fn from_statistics(
         symbols: Vec<String>,
         expected_returns: Vec<Decimal>,
-        covariance_matrix: Vec<Vec<Decimal>>,
+        mut covariance_matrix: Vec<Vec<Decimal>>,
         risk_free_rate: Decimal,
     ) -> Result<Self, PortfolioError> {
         let n = symbols.len();
@@ -298,6 +394,15 @@ impl PortfolioOptimizer {
             }
         }
         
+        // Rounding noise below zero on the diagonal is a zero variance; a
+        // clearly negative one is a broken matrix
+        for (i, symbol) in symbols.iter().enumerate() {
+            if covariance_matrix[i][i] < -VARIANCE_EPSILON {
+                return Err(PortfolioError::NegativeVariance { asset: symbol.clone() });
+            }
+            covariance_matrix[i][i] = clamp_variance(covariance_matrix[i][i]);
+        }
+        
         // Calculate correlation matrix
         let mut correlation_matrix = vec![vec![Decimal::ZERO; n]; n];
         let volatilities: Vec<Decimal> = (0..n)
@@ -333,6 +438,14 @@ impl PortfolioOptimizer {
         self
     }
     
+    /// Cap each cluster of assets correlated above `threshold` at an equal
+    /// share of the portfolio, as sectors added to the constraints
+    pub fn with_correlation_clusters(mut self, threshold: Decimal) -> Self {
+        let network = crate::analysis::network::MarketCorrelationNetwork::from_portfolio_optimizer(&self);
+        self.constraints = network.cluster_constraints(threshold, std::mem::take(&mut self.constraints));
+        self
+    }
+
     /// Set max iterations
     pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
         self.max_iterations = max_iterations;
@@ -727,6 +840,64 @@ impl PortfolioOptimizer {
             }
         }
         
+        self.apply_sector_limits(weights)
+    }
+    
+    /// Scale sectors over their limit down to it, spreading the excess over
+    /// the assets in sectors with room, until every limit holds
+    fn apply_sector_limits(&self, weights: &mut [Decimal]) -> Result<(), PortfolioError> {
+        let limits = &self.constraints.sector_limits;
+        if limits.is_empty() {
+            return Ok(());
+        }
+        let sectors: Vec<Option<&String>> = self
+            .symbols
+            .iter()
+            .map(|s| self.constraints.asset_sectors.get(s).filter(|sector| limits.contains_key(*sector)))
+            .collect();
+        
+        for _ in 0..=self.symbols.len() {
+            let mut totals: HashMap<&String, Decimal> = HashMap::new();
+            for (i, sector) in sectors.iter().enumerate() {
+                if let Some(sector) = sector {
+                    *totals.entry(sector).or_default() += weights[i];
+                }
+            }
+            
+            let mut excess = Decimal::ZERO;
+            for (sector, &total) in &totals {
+                let limit = limits[*sector];
+                if total > limit + self.tolerance {
+                    for (i, s) in sectors.iter().enumerate() {
+                        if *s == Some(*sector) {
+                            weights[i] = weights[i] * limit / total;
+                        }
+                    }
+                    excess += total - limit;
+                }
+            }
+            if excess <= self.tolerance {
+                return Ok(());
+            }
+            
+            let has_room = |i: usize| match sectors[i] {
+                Some(sector) => totals[sector] < limits[sector] - self.tolerance,
+                None => true,
+            };
+            let open: Vec<usize> = (0..weights.len()).filter(|&i| has_room(i)).collect();
+            if open.is_empty() {
+                return Err(PortfolioError::NoFeasibleSolution);
+            }
+            let open_sum: Decimal = open.iter().map(|&i| weights[i]).sum();
+            for &i in &open {
+                weights[i] += if open_sum > Decimal::ZERO {
+                    excess * weights[i] / open_sum
+                } else {
+                    excess / Decimal::from(open.len())
+                };
+            }
+        }
+        
         Ok(())
     }
     
@@ -807,7 +978,7 @@ impl PortfolioOptimizer {
             }
         }
         
-        sqrt_decimal(variance.max(Decimal::ZERO))
+        sqrt_decimal(clamp_variance(variance))
     }
     
     /// Calculate risk contributions per asset
@@ -1021,7 +1192,7 @@ impl PortfolioOptimizer {
             return Decimal::ONE;
         }
         if indices.len() == 1 {
-            return self.covariance_matrix[indices[0]][indices[0]];
+            return clamp_variance(self.covariance_matrix[indices[0]][indices[0]]).max(Decimal::new(1, 10));
         }
         
         // Extract sub-covariance matrix
@@ -1276,26 +1447,66 @@ fn invert_small_matrix(matrix: &[Vec<Decimal>]) -> Result<Vec<Vec<Decimal>>, Por
     Ok(inverse)
 }
 
+/// Most negative a variance may be and still count as zero: sums of
+/// products leave that much rounding noise around a true zero
+const VARIANCE_EPSILON: Decimal = Decimal::from_parts(1, 0, 0, false, 12);
+
+/// `variance` with rounding noise below zero clamped to zero, so its root
+/// is zero rather than garbage. Anything more negative comes from a
+/// covariance matrix that isn't positive semi-definite; it is clamped too
+/// but logged.
+fn clamp_variance(variance: Decimal) -> Decimal {
+    if variance < -VARIANCE_EPSILON {
+        tracing::debug!("Clamping negative variance {} to zero", variance);
+    }
+    variance.max(Decimal::ZERO)
+}
+
 /// Newton's method square root for Decimal
+///
+/// The argument is first scaled by powers of 100 into [0.01, 100), where a
+/// guess of (m + 1) / 2 converges in a handful of iterations, and the root
+/// is scaled back by the matching power of 10. Iteration stops once a step
+/// is negligible relative to the root, so tiny and huge inputs (annualized
+/// covariances) get the same relative accuracy.
 fn sqrt_decimal(x: Decimal) -> Decimal {
     if x <= Decimal::ZERO {
         return Decimal::ZERO;
     }
-    
-    let mut guess = x / Decimal::TWO;
-    if guess == Decimal::ZERO {
-        guess = Decimal::ONE;
+
+    let hundred = Decimal::ONE_HUNDRED;
+    let lower = Decimal::new(1, 2);
+    let mut mantissa = x;
+    let mut exponent = 0i32;
+    while mantissa >= hundred {
+        mantissa /= hundred;
+        exponent += 1;
     }
-    
-    for _ in 0..50 {
-        let next = (guess + x / guess) / Decimal::TWO;
-        if (next - guess).abs() < Decimal::new(1, 12) {
-            return next;
+    while mantissa < lower {
+        mantissa *= hundred;
+        exponent -= 1;
+    }
+
+    let tolerance = Decimal::new(1, 20);
+    let mut root = (mantissa + Decimal::ONE) / Decimal::TWO;
+    for _ in 0..40 {
+        let next = (root + mantissa / root) / Decimal::TWO;
+        let converged = (next - root).abs() <= next * tolerance;
+        root = next;
+        if converged {
+            break;
         }
-        guess = next;
     }
-    
-    guess
+
+    let ten = Decimal::TEN;
+    for _ in 0..exponent.unsigned_abs() {
+        if exponent > 0 {
+            root *= ten;
+        } else {
+            root /= ten;
+        }
+    }
+    root
 }
 
 /// Risk Budgeting: allocate risk contributions to match target budgets
@@ -1406,7 +1617,7 @@ impl RiskBudgeter {
                 var += weights[i] * weights[j] * self.covariance[i][j];
             }
         }
-        var.max(Decimal::ZERO)
+        clamp_variance(var)
     }
 }
 
@@ -1554,6 +1765,108 @@ mod tests {
         assert!((sum - Decimal::ONE).abs() < dec("0.0001"));
     }
     
+    #[test]
+    fn test_from_regime_returns_uses_regime_rows() {
+        use crate::regime::{PriceBar, RegimeConfig, RegimeDetector};
+        use chrono::{Duration, Utc};
+
+        // Steady climb, then a wide choppy stretch
+        let start = Utc::now() - Duration::hours(200);
+        let mut bars = Vec::new();
+        for i in 0..120i64 {
+            let (close, half_range) = if i < 60 {
+                (Decimal::from(100 + i), dec("0.2"))
+            } else {
+                (Decimal::from(160 + (i % 2) * 8), dec("6"))
+            };
+            bars.push(PriceBar {
+                timestamp: start + Duration::hours(i),
+                open: close,
+                high: close + half_range,
+                low: close - half_range,
+                close,
+                volume: dec("1000"),
+            });
+        }
+        let history = RegimeDetector::new(RegimeConfig::default()).analyze_historical(&bars).unwrap();
+        let trending = history.filter_by_regime(MarketRegime::BullishTrend);
+        assert!(trending.len() >= 10);
+
+        // Asset A is calm in the trend and wild otherwise
+        let returns: Vec<Vec<Decimal>> = (0..bars.len())
+            .map(|i| {
+                let sign = if i % 2 == 0 { Decimal::ONE } else { -Decimal::ONE };
+                let a = if trending.contains(&i) { dec("0.001") } else { dec("0.05") };
+                vec![a * sign, dec("0.01") * sign]
+            })
+            .collect();
+
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        let optimizer = PortfolioOptimizer::from_regime_returns(
+            symbols.clone(), &returns, &history, MarketRegime::BullishTrend, dec("0.02"), 252,
+        ).unwrap();
+        let all = PortfolioOptimizer::from_returns(symbols.clone(), &returns, dec("0.02"), 252).unwrap();
+        assert!(optimizer.covariance_matrix()[0][0] < all.covariance_matrix()[0][0]);
+        assert!(optimizer.covariance_matrix()[0][0] < optimizer.covariance_matrix()[1][1]);
+
+        // A regime that never occurred has no data
+        let counts = history.bar_counts();
+        let absent = [MarketRegime::BearishTrend, MarketRegime::Ranging, MarketRegime::Unknown]
+            .into_iter()
+            .find(|r| !counts.contains_key(r))
+            .unwrap();
+        assert!(matches!(
+            PortfolioOptimizer::from_regime_returns(symbols, &returns, &history, absent, dec("0.02"), 252),
+            Err(PortfolioError::InsufficientData { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_returns_ewm_infinite_halflife_is_equal_weight() {
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        let returns: Vec<Vec<Decimal>> = (0..30)
+            .map(|i| vec![Decimal::from(i % 7) / dec("100") - dec("0.03"), Decimal::from(i % 5) / dec("200")])
+            .collect();
+
+        let equal = PortfolioOptimizer::from_returns(symbols.clone(), &returns, dec("0.02"), 252).unwrap();
+        let ewm = PortfolioOptimizer::from_returns_ewm(symbols.clone(), &returns, dec("0.02"), 252, f64::INFINITY).unwrap();
+        assert_eq!(ewm.expected_returns(), equal.expected_returns());
+        assert_eq!(ewm.covariance_matrix(), equal.covariance_matrix());
+        assert_eq!(ewm.correlation_matrix(), equal.correlation_matrix());
+
+        assert!(matches!(
+            PortfolioOptimizer::from_returns_ewm(symbols.clone(), &returns, dec("0.02"), 252, 0.0),
+            Err(PortfolioError::InvalidConstraint(_))
+        ));
+        assert!(matches!(
+            PortfolioOptimizer::from_returns_ewm(symbols, &returns[..5], dec("0.02"), 252, 10.0),
+            Err(PortfolioError::InsufficientData { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_returns_ewm_follows_structural_change() {
+        // A and B move against each other for 80 periods, then together
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        let returns: Vec<Vec<Decimal>> = (0..100)
+            .map(|i| {
+                let a = if i % 2 == 0 { dec("0.01") } else { dec("-0.01") };
+                let b = if i < 80 { -a } else { a };
+                vec![a, b]
+            })
+            .collect();
+
+        let before = PortfolioOptimizer::from_returns(symbols.clone(), &returns[..80], dec("0.02"), 252).unwrap();
+        let equal = PortfolioOptimizer::from_returns(symbols.clone(), &returns, dec("0.02"), 252).unwrap();
+        let ewm = PortfolioOptimizer::from_returns_ewm(symbols, &returns, dec("0.02"), 252, 5.0).unwrap();
+
+        let shift = |o: &PortfolioOptimizer| (o.covariance_matrix()[0][1] - before.covariance_matrix()[0][1]).abs();
+        assert!(shift(&ewm) > shift(&equal));
+        // The recent co-movement dominates the EWMA correlation
+        assert!(ewm.correlation_matrix()[0][1] > dec("0.8"));
+        assert!(equal.correlation_matrix()[0][1] < Decimal::ZERO);
+    }
+
     #[test]
     fn test_constraints_max_weight() {
         let symbols = vec!["A".to_string(), "B".to_string(), "C".to_string()];
@@ -1663,6 +1976,30 @@ mod tests {
         assert!((result.effective_n - dec("4")).abs() < dec("0.1"));
     }
     
+    #[test]
+    fn test_correlation_clusters_cap_sector_weight() {
+        let symbols = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let returns = vec![dec("0.10"); 3];
+        // A and B move together; C is independent but riskier, so min
+        // variance alone would put ~70% in the A/B cluster
+        let cov = vec![
+            vec![dec("0.04"), dec("0.038"), dec("0")],
+            vec![dec("0.038"), dec("0.04"), dec("0")],
+            vec![dec("0"), dec("0"), dec("0.09")],
+        ];
+        
+        let optimizer = PortfolioOptimizer::from_statistics(symbols, returns, cov, dec("0.02"))
+            .unwrap()
+            .with_correlation_clusters(dec("0.7"));
+        assert_eq!(optimizer.constraints.sector_limits.len(), 2);
+        
+        let result = optimizer.optimize(OptimizationMethod::MinVariance).unwrap();
+        let cluster = result.weights[0] + result.weights[1];
+        assert!((cluster - dec("0.5")).abs() < dec("0.001"), "cluster weight {}", cluster);
+        assert!((result.weights[2] - dec("0.5")).abs() < dec("0.001"));
+        assert!((result.weights[0] - result.weights[1]).abs() < dec("0.001"));
+    }
+    
     #[test]
     fn test_black_litterman_no_views() {
         let market_weights = vec![dec("0.6"), dec("0.4")];
@@ -1755,6 +2092,45 @@ mod tests {
         let three = sqrt_decimal(nine);
         assert!((three - Decimal::from(3)).abs() < dec("0.0001"));
     }
+
+    #[test]
+    fn test_sqrt_decimal_extreme_magnitudes() {
+        let root = sqrt_decimal(dec("1000000000000000000000000"));
+        assert!((root - dec("1000000000000")).abs() / dec("1000000000000") < dec("0.000000000001"));
+
+        let root = sqrt_decimal(dec("0.00000000000000000002"));
+        let expected = dec("0.000000000141421356237309505");
+        assert!((root - expected).abs() / expected < dec("0.000000001"));
+
+        let root = sqrt_decimal(dec("123456789.123456789"));
+        assert!((root * root - dec("123456789.123456789")).abs() < dec("0.0000001"));
+    }
+
+    #[test]
+    fn test_tiny_negative_variance_is_zero() {
+        let noise = dec("-0.000000000000000001");
+        assert_eq!(clamp_variance(noise), Decimal::ZERO);
+        assert_eq!(sqrt_decimal(clamp_variance(noise)), Decimal::ZERO);
+
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        let returns = vec![dec("0.1"), dec("0.05")];
+        let cov = vec![
+            vec![dec("0.04"), Decimal::ZERO],
+            vec![Decimal::ZERO, noise],
+        ];
+        let optimizer = PortfolioOptimizer::from_statistics(symbols.clone(), returns.clone(), cov, Decimal::ZERO).unwrap();
+        assert_eq!(optimizer.covariance_matrix[1][1], Decimal::ZERO);
+        assert_eq!(optimizer.correlation_matrix[1][1], Decimal::ONE);
+        assert_eq!(optimizer.portfolio_volatility(&[Decimal::ZERO, Decimal::ONE]), Decimal::ZERO);
+
+        // Clearly negative is an error, not noise
+        let cov = vec![
+            vec![dec("0.04"), Decimal::ZERO],
+            vec![Decimal::ZERO, dec("-0.01")],
+        ];
+        let result = PortfolioOptimizer::from_statistics(symbols, returns, cov, Decimal::ZERO);
+        assert!(matches!(result, Err(PortfolioError::NegativeVariance { asset }) if asset == "B"));
+    }
     
     #[test]
     fn test_matrix_inversion() {
@@ -0,0 +1,452 @@
+//! Drift detection and rebalancing toward target portfolio weights
+//!
+//! Target weights usually come from `PortfolioOptimizer`. As trades fill and
+//! prices move, actual weights drift; `PortfolioRebalancer` flags markets
+//! whose weight is more than `drift_threshold` away from target and works
+//! them back, smallest trades first so sells free capital for larger buys.
+//!
+//! Weights are tracked on the YES token of each market.
+
+use super::OptimizedPortfolio;
+use crate::client::PolymarketClient;
+use crate::config::RebalanceConfig;
+use crate::error::{BotError, Result};
+use crate::executor::Executor;
+use crate::types::{Position, Side, Signal, TraceId, Trade, TradeStatus};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+
+/// A trade needed to bring one market back to its target weight
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalanceTrade {
+    pub market_id: String,
+    pub current_weight: Decimal,
+    pub target_weight: Decimal,
+    pub trade_size_usdc: Decimal,
+    pub side: Side,
+}
+
+impl RebalanceTrade {
+    /// Signed drift from target (positive = overweight)
+    pub fn drift(&self) -> Decimal {
+        self.current_weight - self.target_weight
+    }
+}
+
+/// Outcome of a rebalance run
+#[derive(Debug, Clone, Default)]
+pub struct RebalanceReport {
+    pub executed: Vec<RebalanceTrade>,
+    pub failed: Vec<(RebalanceTrade, String)>,
+    /// Trades skipped for being below the minimum size
+    pub skipped: usize,
+    pub total_traded_usdc: Decimal,
+}
+
+impl RebalanceReport {
+    /// Telegram summary
+    pub fn message(&self) -> String {
+        let mut text = format!(
+            "⚖️ <b>Rebalance Complete</b>\n\n\
+            Executed: {} (${:.2})\n\
+            Failed: {}\n\
+            Skipped (below min size): {}\n",
+            self.executed.len(),
+            self.total_traded_usdc,
+            self.failed.len(),
+            self.skipped,
+        );
+        for (trade, reason) in &self.failed {
+            text.push_str(&format!("\n❌ <code>{}</code>: {}", trade.market_id, reason));
+        }
+        text
+    }
+}
+
+/// Detects weight drift and rebalances toward targets
+#[derive(Debug, Clone)]
+pub struct PortfolioRebalancer {
+    pub target_weights: HashMap<String, Decimal>,
+    pub drift_threshold: Decimal,
+    pub min_rebalance_size_usdc: Decimal,
+    pub cooldown_hours: u32,
+    last_rebalance: Option<DateTime<Utc>>,
+}
+
+impl PortfolioRebalancer {
+    pub fn new(
+        target_weights: HashMap<String, Decimal>,
+        drift_threshold: Decimal,
+        min_rebalance_size_usdc: Decimal,
+        cooldown_hours: u32,
+    ) -> Self {
+        Self {
+            target_weights,
+            drift_threshold,
+            min_rebalance_size_usdc,
+            cooldown_hours,
+            last_rebalance: None,
+        }
+    }
+
+    pub fn from_config(config: &RebalanceConfig) -> Self {
+        Self::new(
+            config.target_weights.clone(),
+            config.drift_threshold,
+            config.min_rebalance_size_usdc,
+            config.cooldown_hours,
+        )
+    }
+
+    /// Replace targets with optimizer output; `symbols` are the market IDs in
+    /// optimizer order (see `PortfolioOptimizer::symbols`)
+    pub fn set_target_weights(&mut self, symbols: &[String], portfolio: &OptimizedPortfolio) {
+        self.target_weights = symbols
+            .iter()
+            .cloned()
+            .zip(portfolio.weights.iter().copied())
+            .collect();
+    }
+
+    /// Markets whose weight drifted more than `drift_threshold` from target,
+    /// largest absolute drift first
+    pub fn check_drift(&self, positions: &[Trade], total_value: Decimal) -> Vec<RebalanceTrade> {
+        if total_value <= Decimal::ZERO {
+            return Vec::new();
+        }
+
+        let mut values: HashMap<&str, Decimal> = HashMap::new();
+        for trade in positions {
+            let notional = trade.price * trade.size;
+            let value = values.entry(trade.market_id.as_str()).or_default();
+            match trade.side {
+                Side::Buy => *value += notional,
+                Side::Sell => *value -= notional,
+            }
+        }
+
+        let markets: BTreeSet<&str> = values
+            .keys()
+            .copied()
+            .chain(self.target_weights.keys().map(String::as_str))
+            .collect();
+
+        let mut trades: Vec<RebalanceTrade> = markets
+            .into_iter()
+            .filter_map(|market_id| {
+                let current_weight = values.get(market_id).copied().unwrap_or(Decimal::ZERO) / total_value;
+                let target_weight = self.target_weights.get(market_id).copied().unwrap_or(Decimal::ZERO);
+                let diff = target_weight - current_weight;
+                if diff.abs() <= self.drift_threshold {
+                    return None;
+                }
+                Some(RebalanceTrade {
+                    market_id: market_id.to_string(),
+                    current_weight,
+                    target_weight,
+                    trade_size_usdc: diff.abs() * total_value,
+                    side: if diff > Decimal::ZERO { Side::Buy } else { Side::Sell },
+                })
+            })
+            .collect();
+
+        trades.sort_by_key(|t| std::cmp::Reverse(t.drift().abs()));
+        trades
+    }
+
+    /// Order drifted markets for execution: drop trades below the minimum
+    /// size, then smallest first. Returns the plan and the number skipped.
+    pub fn execution_plan(&self, mut trades: Vec<RebalanceTrade>) -> (Vec<RebalanceTrade>, usize) {
+        let before = trades.len();
+        trades.retain(|t| t.trade_size_usdc >= self.min_rebalance_size_usdc);
+        let skipped = before - trades.len();
+        trades.sort_by_key(|t| t.trade_size_usdc);
+        (trades, skipped)
+    }
+
+    /// Time left before another rebalance is allowed
+    pub fn cooldown_remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let next = self.last_rebalance? + Duration::hours(self.cooldown_hours as i64);
+        (next > now).then(|| next - now)
+    }
+
+    /// Current drift from live account positions, with the total account value
+    pub async fn preview(&self, client: &PolymarketClient) -> Result<(Vec<RebalanceTrade>, Decimal)> {
+        let positions = client.clob.get_positions().await?;
+        let balance = client.clob.get_balance().await?;
+        let total_value = balance
+            + positions
+                .iter()
+                .map(|p| p.size * p.current_price)
+                .sum::<Decimal>();
+
+        let holdings = positions_as_trades(&positions);
+        Ok((self.check_drift(&holdings, total_value), total_value))
+    }
+
+    /// Execute all drifted markets' trades, smallest first
+    pub async fn rebalance(&mut self, executor: &Executor, client: &PolymarketClient) -> Result<RebalanceReport> {
+        let now = Utc::now();
+        if let Some(remaining) = self.cooldown_remaining(now) {
+            return Err(BotError::RiskLimit(format!(
+                "Rebalance cooldown active for another {}h{}m",
+                remaining.num_hours(),
+                remaining.num_minutes() % 60
+            )));
+        }
+
+        let (trades, total_value) = self.preview(client).await?;
+        let (plan, skipped) = self.execution_plan(trades);
+        let mut report = RebalanceReport {
+            skipped,
+            ..Default::default()
+        };
+
+        for trade in plan {
+            match self.execute_trade(&trade, total_value, executor, client).await {
+                Ok(trades) if !trades.is_empty() => {
+                    report.total_traded_usdc += trade.trade_size_usdc;
+                    report.executed.push(trade);
+                }
+                Ok(_) => report.failed.push((trade, "not filled".to_string())),
+                Err(e) => {
+                    tracing::warn!("Rebalance trade for {} failed: {}", trade.market_id, e);
+                    report.failed.push((trade, e.to_string()));
+                }
+            }
+        }
+
+        if !report.executed.is_empty() {
+            self.last_rebalance = Some(now);
+        }
+
+        Ok(report)
+    }
+
+    async fn execute_trade(
+        &self,
+        trade: &RebalanceTrade,
+        total_value: Decimal,
+        executor: &Executor,
+        client: &PolymarketClient,
+    ) -> Result<Vec<Trade>> {
+        let market = client.gamma.get_market(&trade.market_id).await?;
+        executor.register_market(&market).await;
+        let yes = market
+            .outcomes
+            .iter()
+            .find(|o| o.outcome.eq_ignore_ascii_case("yes"))
+            .filter(|o| o.price > Decimal::ZERO)
+            .ok_or_else(|| BotError::MarketNotFound(format!("{} has no priced YES outcome", trade.market_id)))?;
+
+        let signal = Signal {
+            market_id: trade.market_id.clone(),
+            token_id: yes.token_id.clone(),
+            side: trade.side,
+            model_probability: yes.price,
+            market_probability: yes.price,
+            edge: Decimal::ZERO,
+            confidence: Decimal::ONE,
+            suggested_size: trade.trade_size_usdc / total_value,
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        };
+
+        executor.execute(&signal, total_value).await
+    }
+}
+
+impl Default for PortfolioRebalancer {
+    fn default() -> Self {
+        Self::from_config(&RebalanceConfig::default())
+    }
+}
+
+/// Telegram preview of proposed rebalance trades
+pub fn preview_message(trades: &[RebalanceTrade]) -> String {
+    if trades.is_empty() {
+        return "⚖️ Portfolio is within drift limits, nothing to rebalance".to_string();
+    }
+
+    let mut text = String::from("⚖️ <b>Rebalance Preview</b>\n\n");
+    for t in trades {
+        let side = match t.side {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        };
+        text.push_str(&format!(
+            "<code>{}</code>\n  {:.1}% → {:.1}% | {} ${:.2}\n",
+            t.market_id,
+            t.current_weight * Decimal::ONE_HUNDRED,
+            t.target_weight * Decimal::ONE_HUNDRED,
+            side,
+            t.trade_size_usdc,
+        ));
+    }
+    text.push_str("\nUse /rebalance confirm to execute");
+    text
+}
+
+/// Represent open positions as single fills at the current price so their
+/// market value can be fed to `check_drift`
+fn positions_as_trades(positions: &[Position]) -> Vec<Trade> {
+    positions
+        .iter()
+        .map(|p| Trade {
+            id: String::new(),
+            order_id: String::new(),
+            token_id: p.token_id.clone(),
+            market_id: p.market_id.clone(),
+            side: p.side,
+            price: p.current_price,
+            size: p.size,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fill(market_id: &str, side: Side, price: Decimal, size: Decimal) -> Trade {
+        Trade {
+            id: String::new(),
+            order_id: String::new(),
+            token_id: format!("{}-yes", market_id),
+            market_id: market_id.to_string(),
+            side,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        }
+    }
+
+    fn rebalancer() -> PortfolioRebalancer {
+        let targets = [("a", dec!(0.30)), ("b", dec!(0.20)), ("c", dec!(0.10))]
+            .into_iter()
+            .map(|(m, w)| (m.to_string(), w))
+            .collect();
+        PortfolioRebalancer::new(targets, dec!(0.05), dec!(10), 24)
+    }
+
+    #[test]
+    fn test_check_drift_sorted_by_drift() {
+        let r = rebalancer();
+        let positions = vec![
+            fill("a", Side::Buy, dec!(0.50), dec!(1000)), // 500 / 1000 = 50%
+            fill("b", Side::Buy, dec!(0.40), dec!(500)),  // 200 = 20%, on target
+            fill("d", Side::Buy, dec!(0.80), dec!(100)),  // 80 = 8%, untargeted
+        ];
+
+        let trades = r.check_drift(&positions, dec!(1000));
+        let ids: Vec<_> = trades.iter().map(|t| t.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d"]);
+
+        assert_eq!(trades[0].side, Side::Sell);
+        assert_eq!(trades[0].current_weight, dec!(0.5));
+        assert_eq!(trades[0].trade_size_usdc, dec!(200));
+
+        // Nothing held in c yet
+        assert_eq!(trades[1].side, Side::Buy);
+        assert_eq!(trades[1].trade_size_usdc, dec!(100));
+
+        assert_eq!(trades[2].target_weight, Decimal::ZERO);
+        assert_eq!(trades[2].side, Side::Sell);
+    }
+
+    #[test]
+    fn test_check_drift_nets_sells_and_respects_threshold() {
+        let r = rebalancer();
+        let positions = vec![
+            fill("a", Side::Buy, dec!(0.50), dec!(1000)),
+            fill("a", Side::Sell, dec!(0.50), dec!(440)), // 280 = 28%, within 5%
+            fill("b", Side::Buy, dec!(0.50), dec!(400)),
+            fill("c", Side::Buy, dec!(0.50), dec!(200)),
+        ];
+        assert!(r.check_drift(&positions, dec!(1000)).is_empty());
+        assert!(r.check_drift(&positions, Decimal::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_execution_plan_smallest_first() {
+        let r = rebalancer();
+        let positions = vec![fill("a", Side::Buy, dec!(0.50), dec!(1000))];
+        let mut trades = r.check_drift(&positions, dec!(1000));
+        trades.push(RebalanceTrade {
+            market_id: "tiny".to_string(),
+            current_weight: dec!(0.06),
+            target_weight: Decimal::ZERO,
+            trade_size_usdc: dec!(5),
+            side: Side::Sell,
+        });
+
+        let (plan, skipped) = r.execution_plan(trades);
+        assert_eq!(skipped, 1);
+        let sizes: Vec<_> = plan.iter().map(|t| t.trade_size_usdc).collect();
+        assert_eq!(sizes, vec![dec!(100), dec!(200), dec!(200)]);
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let mut r = rebalancer();
+        let now = Utc::now();
+        assert!(r.cooldown_remaining(now).is_none());
+
+        r.last_rebalance = Some(now - Duration::hours(23));
+        assert_eq!(r.cooldown_remaining(now), Some(Duration::hours(1)));
+
+        r.last_rebalance = Some(now - Duration::hours(25));
+        assert!(r.cooldown_remaining(now).is_none());
+    }
+
+    #[test]
+    fn test_set_target_weights_from_optimizer() {
+        let mut r = PortfolioRebalancer::default();
+        let portfolio = OptimizedPortfolio {
+            weights: vec![dec!(0.6), dec!(0.4)],
+            expected_return: Decimal::ZERO,
+            volatility: Decimal::ZERO,
+            sharpe_ratio: Decimal::ZERO,
+            diversification_ratio: Decimal::ZERO,
+            effective_n: Decimal::ZERO,
+            risk_contributions: vec![],
+            marginal_risk: vec![],
+        };
+        r.set_target_weights(&["x".to_string(), "y".to_string()], &portfolio);
+        assert_eq!(r.target_weights.get("y"), Some(&dec!(0.4)));
+    }
+
+    #[test]
+    fn test_preview_message() {
+        let r = rebalancer();
+        assert!(preview_message(&[]).contains("nothing to rebalance"));
+
+        let trades = r.check_drift(&[fill("a", Side::Buy, dec!(0.50), dec!(1000))], dec!(1000));
+        let text = preview_message(&trades);
+        assert!(text.contains("50.0% → 30.0% | SELL $200.00"));
+        assert!(text.contains("/rebalance confirm"));
+    }
+}
@@ -0,0 +1,608 @@
+//! Redemption of winning shares in resolved markets
+//!
+//! Once a market resolves, its winning shares are worth a dollar each, but
+//! only as conditional tokens: the USDC lands in the wallet when the
+//! position is redeemed on-chain. Until then the value is tracked as
+//! pending redemption, which positions are sized off together with the
+//! wallet balance. With `auto_redeem` the [`Redeemer`] sends the redeem
+//! transaction itself, retrying failures without resending a transaction
+//! that may still land, and books the payout less gas as a sale closing
+//! the position.
+
+use crate::client::GammaClient;
+use crate::config::{PolymarketConfig, RedeemConfig};
+use crate::error::{BotError, Result};
+use crate::notify::Notifier;
+use crate::storage::positions::OpenPosition;
+use crate::storage::Database;
+use crate::types::{Market, Side, TraceId, Trade, TradeStatus};
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::contract::abigen;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, PendingTransaction, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, TransactionReceipt, H256, U256};
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Polymarket's Conditional Tokens contract on Polygon
+const CTF_ADDRESS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+/// USDC.e, the collateral of every Polymarket condition
+const USDC_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+/// Redeems the positions of negRisk markets
+const NEG_RISK_ADAPTER_ADDRESS: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+
+/// Tag of the trades booking a redemption
+pub const REDEMPTION_TAG: &str = "redemption";
+
+abigen!(
+    ConditionalTokens,
+    r#"[
+        function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets) external
+        event PayoutRedemption(address indexed redeemer, address indexed collateralToken, bytes32 indexed parentCollectionId, bytes32 conditionId, uint256[] indexSets, uint256 payout)
+    ]"#
+);
+
+abigen!(
+    NegRiskAdapter,
+    r#"[
+        function redeemPositions(bytes32 conditionId, uint256[] amounts) external
+        event PayoutRedemption(address indexed redeemer, bytes32 indexed conditionId, uint256[] amounts, uint256 payout)
+    ]"#
+);
+
+/// Winning shares of one market to redeem
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedemptionRequest {
+    pub market_id: String,
+    pub token_id: String,
+    /// Outcome the shares are of: 0 for YES, 1 for NO
+    pub outcome_index: usize,
+    pub shares: Decimal,
+    pub neg_risk: bool,
+}
+
+/// A confirmed redemption
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedemptionReceipt {
+    pub tx_hash: String,
+    /// USDC paid out
+    pub proceeds: Decimal,
+    /// Gas paid, in USDC
+    pub gas_cost: Decimal,
+}
+
+/// Sends redemptions and confirms them
+#[async_trait]
+pub trait RedemptionClient: Send + Sync {
+    /// Send the redemption; returns its transaction hash
+    async fn submit(&self, request: &RedemptionRequest) -> Result<String>;
+
+    /// Wait for the transaction `tx_hash` to be mined; `None` if it was
+    /// dropped or reverted, so the redemption has to be sent again
+    async fn confirm(&self, request: &RedemptionRequest, tx_hash: &str) -> Result<Option<RedemptionReceipt>>;
+}
+
+type WalletClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+fn address(hex: &str) -> Address {
+    hex.parse().expect("valid contract address")
+}
+
+/// USDC has six decimals, and so do outcome shares
+fn base_units(amount: Decimal) -> U256 {
+    U256::from((amount * Decimal::from(1_000_000)).trunc().to_u128().unwrap_or(0))
+}
+
+fn from_base_units(amount: U256) -> Decimal {
+    Decimal::from_i128_with_scale(amount.low_u128() as i128, 6)
+}
+
+/// Redeems through the Conditional Tokens contract, or the negRisk adapter
+/// for negRisk markets, from an EOA wallet; proxy wallets hold their tokens
+/// in a contract this can't sign for
+pub struct CtfRedeemer {
+    wallet: Arc<WalletClient>,
+    gamma: GammaClient,
+    pol_price_usdc: Decimal,
+}
+
+impl CtfRedeemer {
+    pub fn new(polymarket: &PolymarketConfig, config: &RedeemConfig, gamma: GammaClient) -> Result<Self> {
+        if polymarket.signature_type != 0 {
+            return Err(BotError::Config("auto_redeem needs an EOA wallet (signature_type = 0)".into()));
+        }
+        let provider = Provider::<Http>::try_from(config.rpc_url.as_str())
+            .map_err(|e| BotError::Config(format!("Invalid redeem rpc_url: {}", e)))?;
+        let wallet = polymarket.private_key.wallet()?.with_chain_id(polymarket.chain_id);
+
+        Ok(Self {
+            wallet: Arc::new(SignerMiddleware::new(provider, wallet)),
+            gamma,
+            pol_price_usdc: config.pol_price_usdc,
+        })
+    }
+
+    async fn send(&self, request: &RedemptionRequest, condition_id: H256) -> std::result::Result<H256, String> {
+        if request.neg_risk {
+            let mut amounts = vec![U256::zero(); 2];
+            amounts[request.outcome_index.min(1)] = base_units(request.shares);
+            let adapter = NegRiskAdapter::new(address(NEG_RISK_ADAPTER_ADDRESS), self.wallet.clone());
+            let call = adapter.redeem_positions(condition_id.0, amounts);
+            let pending = call.send().await.map_err(|e| e.to_string())?;
+            Ok(pending.tx_hash())
+        } else {
+            let ctf = ConditionalTokens::new(address(CTF_ADDRESS), self.wallet.clone());
+            // Both outcomes' index sets; only the winning one pays
+            let call = ctf.redeem_positions(address(USDC_ADDRESS), [0; 32], condition_id.0, vec![U256::from(1), U256::from(2)]);
+            let pending = call.send().await.map_err(|e| e.to_string())?;
+            Ok(pending.tx_hash())
+        }
+    }
+
+    /// USDC paid to this wallet by the receipt's `PayoutRedemption` event
+    fn payout(&self, request: &RedemptionRequest, receipt: &TransactionReceipt) -> Option<Decimal> {
+        let ours = self.wallet.address();
+        let payouts: Vec<U256> = if request.neg_risk {
+            receipt
+                .logs
+                .iter()
+                .filter(|log| log.address == address(NEG_RISK_ADAPTER_ADDRESS))
+                .filter_map(|log| ethers::contract::parse_log::<neg_risk_adapter::PayoutRedemptionFilter>(log.clone()).ok())
+                .filter(|event| event.redeemer == ours)
+                .map(|event| event.payout)
+                .collect()
+        } else {
+            receipt
+                .logs
+                .iter()
+                .filter(|log| log.address == address(CTF_ADDRESS))
+                .filter_map(|log| ethers::contract::parse_log::<conditional_tokens::PayoutRedemptionFilter>(log.clone()).ok())
+                .filter(|event| event.redeemer == ours)
+                .map(|event| event.payout)
+                .collect()
+        };
+        (!payouts.is_empty()).then(|| payouts.into_iter().map(from_base_units).sum())
+    }
+}
+
+#[async_trait]
+impl RedemptionClient for CtfRedeemer {
+    async fn submit(&self, request: &RedemptionRequest) -> Result<String> {
+        let failed = |reason: String| BotError::Execution(format!("Redeeming {} failed: {}", request.market_id, reason));
+        let condition_id: H256 = self
+            .gamma
+            .get_condition_id(&request.market_id)
+            .await?
+            .parse()
+            .map_err(|_| failed("bad condition ID".into()))?;
+        let tx_hash = self.send(request, condition_id).await.map_err(failed)?;
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    async fn confirm(&self, request: &RedemptionRequest, tx_hash: &str) -> Result<Option<RedemptionReceipt>> {
+        let hash: H256 = tx_hash
+            .parse()
+            .map_err(|_| BotError::Internal(format!("Bad redemption transaction hash {}", tx_hash)))?;
+        // Checks the chain for the transaction, so one sent before a failed
+        // wait is found rather than sent again
+        let receipt = PendingTransaction::new(hash, self.wallet.provider())
+            .confirmations(1)
+            .await
+            .map_err(|e| BotError::Execution(format!("Confirming redemption {} failed: {}", tx_hash, e)))?;
+        let Some(receipt) = receipt else {
+            tracing::warn!("Redemption transaction {} of {} was dropped", tx_hash, request.market_id);
+            return Ok(None);
+        };
+        if receipt.status != Some(1.into()) {
+            tracing::warn!("Redemption transaction {} of {} reverted", tx_hash, request.market_id);
+            return Ok(None);
+        }
+
+        let proceeds = self.payout(request, &receipt).unwrap_or_else(|| {
+            tracing::warn!("No payout of {} in redemption transaction {}", request.market_id, tx_hash);
+            Decimal::ZERO
+        });
+        let gas_wei = receipt.gas_used.unwrap_or_default() * receipt.effective_gas_price.unwrap_or_default();
+        let gas_pol = Decimal::from_i128_with_scale(gas_wei.low_u128() as i128, 18);
+        Ok(Some(RedemptionReceipt {
+            tx_hash: tx_hash.to_string(),
+            proceeds,
+            gas_cost: (gas_pol * self.pol_price_usdc).round_dp(6),
+        }))
+    }
+}
+
+/// A redemption booked against a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redemption {
+    pub market_id: String,
+    pub question: String,
+    pub shares: Decimal,
+    pub receipt: RedemptionReceipt,
+}
+
+/// Finds winning positions in resolved markets and redeems them
+pub struct Redeemer {
+    /// Sends the redemptions; without one, winnings are only tracked
+    client: Option<Arc<dyn RedemptionClient>>,
+    db: Arc<Database>,
+    notifier: Arc<Notifier>,
+    config: RedeemConfig,
+    /// Only positions of this account (and those without one)
+    account_id: Option<String>,
+    /// Value of resolved winning shares not yet redeemed
+    pending: RwLock<Decimal>,
+    /// Transactions sent but not yet seen mined, by market; checked on the
+    /// next attempt before anything is sent again
+    unconfirmed: Mutex<HashMap<String, String>>,
+}
+
+impl Redeemer {
+    pub fn new(db: Arc<Database>, notifier: Arc<Notifier>, config: RedeemConfig) -> Self {
+        Self {
+            client: None,
+            db,
+            notifier,
+            config,
+            account_id: None,
+            pending: RwLock::new(Decimal::ZERO),
+            unconfirmed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Redeem through `client` when `auto_redeem` is set
+    pub fn with_client(mut self, client: Arc<dyn RedemptionClient>) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Only redeem the positions of `account_id`, the wallet the client
+    /// signs for
+    pub fn with_account(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    /// USDC owed by resolved markets that hasn't landed in the wallet
+    pub fn pending_value(&self) -> Decimal {
+        *self.pending.read()
+    }
+
+    /// Winning shares among the open positions, with `markets` holding
+    /// the latest state of their markets
+    fn winning(&self, positions: Vec<OpenPosition>, markets: &HashMap<String, Market>) -> Vec<(OpenPosition, RedemptionRequest)> {
+        positions
+            .into_iter()
+            .filter(|p| p.size > Decimal::ZERO)
+            .filter(|p| {
+                p.account_id
+                    .as_deref()
+                    .is_none_or(|account| self.account_id.as_deref().is_none_or(|ours| ours == account))
+            })
+            .filter_map(|position| {
+                let market = markets.get(&position.market_id)?;
+                let yes_won = market.resolved_yes()?;
+                let winner = if yes_won { "yes" } else { "no" };
+                let outcome_index = market
+                    .outcomes
+                    .iter()
+                    .position(|o| o.outcome.eq_ignore_ascii_case(winner) && o.token_id == position.token_id)?;
+                let request = RedemptionRequest {
+                    market_id: position.market_id.clone(),
+                    token_id: position.token_id.clone(),
+                    outcome_index,
+                    shares: position.size,
+                    neg_risk: market.neg_risk,
+                };
+                Some((position, request))
+            })
+            .collect()
+    }
+
+    /// Check the open positions against `markets`, and with `auto_redeem`
+    /// and a client redeem every winning one; returns the redemptions made. Winnings
+    /// whose redemption keeps failing stay pending for the next check.
+    pub async fn run_once(&self, markets: &HashMap<String, Market>) -> Result<Vec<Redemption>> {
+        let winning = self.winning(self.db.get_open_positions().await?, markets);
+        *self.pending.write() = winning.iter().map(|(_, r)| r.shares).sum();
+        let Some(client) = self.client.as_ref().filter(|_| self.config.auto_redeem) else {
+            return Ok(Vec::new());
+        };
+
+        let mut redemptions = Vec::new();
+        for (position, request) in winning {
+            let receipt = match self.redeem_with_retry(client.as_ref(), &request).await {
+                Ok(receipt) => receipt,
+                Err(e) => {
+                    tracing::warn!("Giving up on redeeming {} for now: {}", request.market_id, e);
+                    let _ = self.notifier.error("Redemption", &e.to_string()).await;
+                    continue;
+                }
+            };
+            self.book(&position, &request, &receipt).await?;
+            *self.pending.write() -= request.shares;
+
+            let question = markets.get(&request.market_id).map(|m| m.question.clone()).unwrap_or_default();
+            let redemption = Redemption {
+                market_id: request.market_id.clone(),
+                question,
+                shares: request.shares,
+                receipt,
+            };
+            let _ = self.notifier.send(&crate::notify::redemption_summary(&redemption)).await;
+            redemptions.push(redemption);
+        }
+        Ok(redemptions)
+    }
+
+    /// Send the redemption and confirm it, retrying failures. A transaction
+    /// already sent is confirmed again rather than resent, unless it was
+    /// dropped or reverted.
+    async fn redeem_with_retry(&self, client: &dyn RedemptionClient, request: &RedemptionRequest) -> Result<RedemptionReceipt> {
+        let mut attempt = 1;
+        loop {
+            let sent = self.unconfirmed.lock().get(&request.market_id).cloned();
+            let tx_hash = match sent {
+                Some(tx_hash) => Ok(tx_hash),
+                None => client.submit(request).await.inspect(|tx_hash| {
+                    self.unconfirmed.lock().insert(request.market_id.clone(), tx_hash.clone());
+                }),
+            };
+            let error = match tx_hash {
+                Ok(tx_hash) => match client.confirm(request, &tx_hash).await {
+                    Ok(Some(receipt)) => {
+                        self.unconfirmed.lock().remove(&request.market_id);
+                        return Ok(receipt);
+                    }
+                    Ok(None) => {
+                        self.unconfirmed.lock().remove(&request.market_id);
+                        BotError::Execution(format!("Redemption transaction {} didn't go through", tx_hash))
+                    }
+                    Err(e) => e,
+                },
+                Err(e) => e,
+            };
+            if attempt >= self.config.max_attempts {
+                return Err(error);
+            }
+            tracing::warn!("Redemption attempt {} of {} failed: {}", attempt, request.market_id, error);
+            attempt += 1;
+            tokio::time::sleep(Duration::from_secs(self.config.retry_delay_secs)).await;
+        }
+    }
+
+    /// Close the position with a sale at the proceeds per share, gas as the
+    /// fee
+    async fn book(&self, position: &OpenPosition, request: &RedemptionRequest, receipt: &RedemptionReceipt) -> Result<()> {
+        let trade = Trade {
+            id: uuid::Uuid::new_v4().to_string(),
+            order_id: receipt.tx_hash.clone(),
+            token_id: request.token_id.clone(),
+            market_id: request.market_id.clone(),
+            side: Side::Sell,
+            price: receipt.proceeds / request.shares,
+            size: request.shares,
+            fee: receipt.gas_cost,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: position.strategy_name.clone(),
+            account_id: position.account_id.clone(),
+            trace_id: TraceId::new(),
+            tags: vec![REDEMPTION_TAG.to_string()],
+            edge: None,
+        };
+        self.db.save_trade(&trade).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+    use rust_decimal_macros::dec;
+
+    /// Fails its first `failures` submissions, then redeems; the first
+    /// `lost_confirmations` waits fail though the transaction landed
+    struct MockRedemption {
+        failures: usize,
+        lost_confirmations: usize,
+        calls: Mutex<Vec<RedemptionRequest>>,
+        confirmations: Mutex<Vec<String>>,
+    }
+
+    impl MockRedemption {
+        fn new(failures: usize) -> Arc<Self> {
+            Self::losing_confirmations(failures, 0)
+        }
+
+        fn losing_confirmations(failures: usize, lost_confirmations: usize) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                lost_confirmations,
+                calls: Mutex::new(Vec::new()),
+                confirmations: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl RedemptionClient for MockRedemption {
+        async fn submit(&self, request: &RedemptionRequest) -> Result<String> {
+            let mut calls = self.calls.lock();
+            calls.push(request.clone());
+            if calls.len() <= self.failures {
+                return Err(BotError::Execution("nonce too low".into()));
+            }
+            Ok(format!("0xfeed{}", calls.len()))
+        }
+
+        async fn confirm(&self, request: &RedemptionRequest, tx_hash: &str) -> Result<Option<RedemptionReceipt>> {
+            let mut confirmations = self.confirmations.lock();
+            confirmations.push(tx_hash.to_string());
+            if confirmations.len() <= self.lost_confirmations {
+                return Err(BotError::Execution("request timed out".into()));
+            }
+            // Paid a dollar per share, less a cent of rounding
+            Ok(Some(RedemptionReceipt {
+                tx_hash: tx_hash.to_string(),
+                proceeds: request.shares - dec!(0.01),
+                gas_cost: dec!(0.02),
+            }))
+        }
+    }
+
+    fn market(id: &str, yes_price: Decimal, closed: bool) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("Market {}?", id),
+            description: None,
+            end_date: None,
+            volume: dec!(1000),
+            liquidity: dec!(1000),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: !closed,
+            closed,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn buy(market_id: &str, token_id: &str, size: Decimal) -> Trade {
+        Trade {
+            id: uuid::Uuid::new_v4().to_string(),
+            order_id: "o1".to_string(),
+            token_id: token_id.to_string(),
+            market_id: market_id.to_string(),
+            side: Side::Buy,
+            price: dec!(0.6),
+            size,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: Some("llm".to_string()),
+            account_id: None,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            edge: None,
+        }
+    }
+
+    /// YES won m1 (held), NO lost m2 (held), m3 still open (held)
+    async fn setup(dir: &tempfile::TempDir) -> (Arc<Database>, HashMap<String, Market>) {
+        let db = Arc::new(Database::connect(dir.path().join("redeem.db").to_str().unwrap()).await.unwrap());
+        db.save_trade(&buy("m1", "m1-yes", dec!(10))).await.unwrap();
+        db.save_trade(&buy("m2", "m2-no", dec!(5))).await.unwrap();
+        db.save_trade(&buy("m3", "m3-yes", dec!(8))).await.unwrap();
+        let markets = [market("m1", dec!(1), true), market("m2", dec!(1), true), market("m3", dec!(0.5), false)]
+            .into_iter()
+            .map(|m| (m.id.clone(), m))
+            .collect();
+        (db, markets)
+    }
+
+    fn config(auto_redeem: bool) -> RedeemConfig {
+        RedeemConfig { auto_redeem, retry_delay_secs: 0, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_failed_redemption_is_retried_and_booked() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, markets) = setup(&dir).await;
+        let client = MockRedemption::new(1);
+        let redeemer = Redeemer::new(db.clone(), Arc::new(Notifier::disabled()), config(true)).with_client(client.clone());
+
+        let redemptions = redeemer.run_once(&markets).await.unwrap();
+        assert_eq!(redemptions.len(), 1);
+        assert_eq!(redemptions[0].question, "Market m1?");
+        assert_eq!(redemptions[0].receipt.tx_hash, "0xfeed2");
+        // One failure, then the retry went through
+        let calls = client.calls.lock().clone();
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[1].token_id.as_str(), calls[1].outcome_index, calls[1].shares), ("m1-yes", 0, dec!(10)));
+        assert_eq!(redeemer.pending_value(), Decimal::ZERO);
+
+        // The position is closed by a sale at a dollar, gas as its fee
+        let open: Vec<String> = db.get_open_positions().await.unwrap().into_iter().map(|p| p.market_id).collect();
+        assert_eq!(open, ["m2", "m3"]);
+        let sale = db.get_recent_trades(10).await.unwrap().into_iter().find(|t| t.side == Side::Sell).unwrap();
+        // The receipt's payout, not the share count, prices the sale
+        assert_eq!((sale.price, sale.size, sale.fee), (dec!(0.999), dec!(10), dec!(0.02)));
+        assert_eq!(sale.tags, [REDEMPTION_TAG]);
+        assert_eq!(sale.strategy_name.as_deref(), Some("llm"));
+
+        // Nothing left to redeem
+        assert!(redeemer.run_once(&markets).await.unwrap().is_empty());
+        assert_eq!(client.calls.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_landed_transaction_is_not_resent() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, markets) = setup(&dir).await;
+        // Sent fine, but waiting for it fails twice
+        let client = MockRedemption::losing_confirmations(0, 2);
+        let redeemer = Redeemer::new(db.clone(), Arc::new(Notifier::disabled()), config(true)).with_client(client.clone());
+
+        let redemptions = redeemer.run_once(&markets).await.unwrap();
+        assert_eq!(redemptions.len(), 1);
+        assert_eq!(client.calls.lock().len(), 1);
+        assert_eq!(*client.confirmations.lock(), ["0xfeed1"; 3]);
+        assert_eq!(redemptions[0].receipt.tx_hash, "0xfeed1");
+
+        // Still unconfirmed when the attempts run out: the next check
+        // confirms the same transaction
+        let dir = tempfile::tempdir().unwrap();
+        let (db, markets) = setup(&dir).await;
+        let client = MockRedemption::losing_confirmations(0, 3);
+        let redeemer = Redeemer::new(db.clone(), Arc::new(Notifier::disabled()), config(true)).with_client(client.clone());
+        assert!(redeemer.run_once(&markets).await.unwrap().is_empty());
+        assert_eq!(redeemer.run_once(&markets).await.unwrap().len(), 1);
+        assert_eq!(client.calls.lock().len(), 1);
+        assert_eq!(*client.confirmations.lock(), ["0xfeed1"; 4]);
+    }
+
+    #[tokio::test]
+    async fn test_winnings_stay_pending_until_redeemed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, markets) = setup(&dir).await;
+
+        // Tracked but not redeemed without auto_redeem
+        let client = MockRedemption::new(0);
+        let manual = Redeemer::new(db.clone(), Arc::new(Notifier::disabled()), config(false)).with_client(client.clone());
+        assert!(manual.run_once(&markets).await.unwrap().is_empty());
+        assert_eq!(manual.pending_value(), dec!(10));
+        assert!(client.calls.lock().is_empty());
+
+        // Every attempt failing leaves the winnings pending and the position open
+        let client = MockRedemption::new(usize::MAX);
+        let failing = Redeemer::new(db.clone(), Arc::new(Notifier::disabled()), config(true)).with_client(client.clone());
+        assert!(failing.run_once(&markets).await.unwrap().is_empty());
+        assert_eq!(client.calls.lock().len(), 3);
+        assert_eq!(failing.pending_value(), dec!(10));
+        assert_eq!(db.get_open_positions().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_other_accounts_positions_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, markets) = setup(&dir).await;
+        let mut trade = buy("m1", "m1-yes", dec!(4));
+        trade.account_id = Some("fund".to_string());
+        db.save_trade(&trade).await.unwrap();
+
+        let redeemer = Redeemer::new(db, Arc::new(Notifier::disabled()), config(false)).with_account("main");
+        redeemer.run_once(&markets).await.unwrap();
+        assert_eq!(redeemer.pending_value(), dec!(10));
+    }
+}
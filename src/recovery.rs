@@ -0,0 +1,289 @@
+//! Crash recovery
+//!
+//! Indicators built up in memory (crypto price history, regime detector
+//! bars, executor position caches, market maker VPIN buckets) are lost when
+//! the process dies, and the bot trades blind until they warm up again.
+//! [`StateSnapshot`] captures them; [`SnapshotWriter`] writes it to disk in
+//! the background, through a temporary file renamed over the old snapshot so
+//! a crash mid-write never leaves a torn file. On startup the snapshot is
+//! loaded unless it was written by a different [`SNAPSHOT_VERSION`].
+//!
+//! Components also checkpoint their [`StrategyState`] to the database every
+//! [`STATE_SAVE_EVERY_CYCLES`] scans (see `Database::save_state`). Those
+//! checkpoints are restored over the snapshot unless they are older than
+//! [`MAX_STATE_AGE_SECS`], past which the indicators would be stale anyway
+//! and are re-warmed from scratch.
+
+use crate::error::{BotError, Result};
+use crate::orderbook::VpinState;
+use crate::regime::RegimeDetectorState;
+use crate::strategy::crypto_hf::CryptoTrackerState;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+
+/// Bump whenever a field of [`StateSnapshot`] (or of the state it holds)
+/// changes shape; snapshots of other versions are ignored
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Key of the dry-run simulated account in [`StateSnapshot::positions`]
+pub const SIMULATED_ACCOUNT_ID: &str = "simulation";
+
+/// Scans between database checkpoints of each [`StrategyState`]
+pub const STATE_SAVE_EVERY_CYCLES: u64 = 5;
+
+/// Checkpoints older than this are discarded on restore
+pub const MAX_STATE_AGE_SECS: i64 = 3600;
+
+/// In-memory state a component checkpoints to the `strategy_states` table
+pub trait StrategyState: Serialize + for<'de> Deserialize<'de> {
+    /// Row the state is stored under; one per state type
+    const KEY: &'static str;
+}
+
+impl StrategyState for CryptoTrackerState {
+    const KEY: &'static str = "crypto_tracker";
+}
+
+impl StrategyState for RegimeDetectorState {
+    const KEY: &'static str = "regime_detector";
+}
+
+/// Runtime state restored after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub version: u32,
+    pub taken_at: DateTime<Utc>,
+    pub crypto: CryptoTrackerState,
+    pub regime: Option<RegimeDetectorState>,
+    /// Executor position caches (token ID to size) by account ID
+    pub positions: HashMap<String, HashMap<String, Decimal>>,
+    /// Market maker VPIN buckets by market ID
+    pub vpin: HashMap<String, VpinState>,
+}
+
+impl StateSnapshot {
+    /// Empty snapshot of the current version
+    pub fn new(taken_at: DateTime<Utc>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            taken_at,
+            crypto: CryptoTrackerState::default(),
+            regime: None,
+            positions: HashMap::new(),
+            vpin: HashMap::new(),
+        }
+    }
+
+    /// Write to `path` atomically: to a sibling temporary file first, then
+    /// renamed into place
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec(self)?;
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| BotError::Internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let tmp = tmp_path(path);
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(|e| BotError::Internal(format!("Failed to write state snapshot: {}", e)))?;
+        tokio::fs::rename(&tmp, path)
+            .await
+            .map_err(|e| BotError::Internal(format!("Failed to replace state snapshot: {}", e)))?;
+        Ok(())
+    }
+
+    /// Snapshot at `path`; `None` when there is none, it can't be parsed or
+    /// it was written by another [`SNAPSHOT_VERSION`]
+    pub async fn load(path: &Path) -> Option<Self> {
+        let content = tokio::fs::read(path).await.ok()?;
+        let value: serde_json::Value = match serde_json::from_slice(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable state snapshot {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        let version = value.get("version").and_then(|v| v.as_u64());
+        if version != Some(SNAPSHOT_VERSION as u64) {
+            tracing::warn!(
+                "Ignoring state snapshot {} of version {:?} (expected {})",
+                path.display(),
+                version,
+                SNAPSHOT_VERSION
+            );
+            return None;
+        }
+        match serde_json::from_value(value) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                tracing::warn!("Ignoring state snapshot {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Writes snapshots off the trading loop. Only the latest snapshot matters,
+/// so one submitted while a write is in flight replaces any still waiting.
+pub struct SnapshotWriter {
+    tx: watch::Sender<Option<StateSnapshot>>,
+}
+
+impl SnapshotWriter {
+    /// Spawn the background task writing to `path`
+    pub fn spawn(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let (tx, mut rx) = watch::channel(None::<StateSnapshot>);
+        tokio::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let Some(snapshot) = rx.borrow_and_update().clone() else {
+                    continue;
+                };
+                if let Err(e) = snapshot.save(&path).await {
+                    tracing::warn!("Failed to save state snapshot: {}", e);
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queue `snapshot` for writing; never waits on disk
+    pub fn submit(&self, snapshot: StateSnapshot) {
+        self.tx.send_replace(Some(snapshot));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{OrderBookAnalyzer, TradeFlow, TradeSide};
+    use crate::regime::{PriceBar, RegimeConfig, RegimeDetector};
+    use crate::strategy::crypto_hf::{CryptoPriceTracker, PricePoint};
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn bar(i: i64) -> PriceBar {
+        let open = dec!(100) + Decimal::from(i);
+        let close = open + if i % 3 == 0 { dec!(-0.4) } else { dec!(1.1) };
+        PriceBar {
+            timestamp: Utc::now() - Duration::hours(100 - i),
+            open,
+            high: open.max(close) + dec!(0.5),
+            low: open.min(close) - dec!(0.5),
+            close,
+            volume: dec!(1000),
+        }
+    }
+
+    fn populated() -> (StateSnapshot, RegimeDetector) {
+        let mut detector = RegimeDetector::new(RegimeConfig::default());
+        for i in 0..60 {
+            detector.update(bar(i));
+        }
+        assert!(detector.current_regime().is_some());
+
+        let mut tracker = CryptoPriceTracker::new();
+        for i in 0..30 {
+            tracker.record_price("BTC", PricePoint {
+                price: dec!(60000) + Decimal::from(i),
+                timestamp: Utc::now() - Duration::minutes(30 - i),
+            });
+        }
+
+        let mut analyzer = OrderBookAnalyzer::new();
+        for i in 0..25u64 {
+            analyzer.process_trade(TradeFlow {
+                timestamp_ms: i,
+                price: dec!(0.5),
+                quantity: dec!(300),
+                side: Some(if i % 4 == 0 { TradeSide::Sell } else { TradeSide::Buy }),
+            });
+        }
+
+        let mut snapshot = StateSnapshot::new(Utc::now());
+        snapshot.crypto = tracker.state();
+        snapshot.regime = Some(detector.state());
+        snapshot.positions.insert("default".to_string(), HashMap::from([("tok".to_string(), dec!(42))]));
+        snapshot.vpin.insert("mkt".to_string(), analyzer.vpin_state());
+        (snapshot, detector)
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip_resumes_warm() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("snapshot.json");
+        let (snapshot, mut original) = populated();
+        snapshot.save(&path).await.unwrap();
+        assert!(!tmp_path(&path).exists());
+
+        let loaded = StateSnapshot::load(&path).await.expect("snapshot should load");
+        assert_eq!(loaded.positions["default"]["tok"], dec!(42));
+
+        let mut tracker = CryptoPriceTracker::new();
+        tracker.restore(loaded.crypto);
+        assert_eq!(tracker.history_len("BTC"), 30);
+        assert_eq!(tracker.current_price("BTC"), Some(dec!(60029)));
+
+        let mut analyzer = OrderBookAnalyzer::new();
+        analyzer.restore_vpin(loaded.vpin["mkt"].clone());
+        assert_eq!(loaded.vpin["mkt"].bucket_count(), 6);
+        assert!(analyzer.calculate_vpin().is_some());
+
+        // The restored detector classifies the very next bar exactly as the
+        // one that never stopped, where a cold one still needs min_bars
+        let mut restored = RegimeDetector::from_state(RegimeConfig::default(), loaded.regime.unwrap());
+        let next = bar(60);
+        let resumed = restored.update(next.clone()).expect("restored detector should be warm");
+        let expected = original.update(next.clone()).unwrap();
+        assert_eq!(resumed.regime, expected.regime);
+        assert_eq!(resumed.adx, expected.adx);
+        assert_eq!(resumed.atr, expected.atr);
+        assert!(RegimeDetector::new(RegimeConfig::default()).update(next).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_of_other_version_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+        assert!(StateSnapshot::load(&path).await.is_none());
+
+        let mut snapshot = StateSnapshot::new(Utc::now());
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        snapshot.save(&path).await.unwrap();
+        assert!(StateSnapshot::load(&path).await.is_none());
+
+        tokio::fs::write(&path, br#"{"version": 1, "crypto": 7}"#).await.unwrap();
+        assert!(StateSnapshot::load(&path).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_writer_saves_latest_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+        let writer = SnapshotWriter::spawn(&path);
+
+        let mut snapshot = StateSnapshot::new(Utc::now());
+        snapshot.positions.insert("default".to_string(), HashMap::new());
+        writer.submit(snapshot);
+
+        for _ in 0..100 {
+            if let Some(loaded) = StateSnapshot::load(&path).await {
+                assert!(loaded.positions.contains_key("default"));
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("snapshot was never written");
+    }
+}
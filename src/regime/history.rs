@@ -0,0 +1,215 @@
+//! Bulk regime classification of historical bars
+//!
+//! Regime-conditional models need the regime of every past period. The
+//! detector is run over the bars in order, exactly as it would have run
+//! live, and the detections are summarized into time spent per regime and
+//! the points where the regime changed.
+
+use super::{MarketRegime, PriceBar, RegimeDetection, RegimeDetector};
+use crate::error::{BotError, Result};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A change of the (smoothed) regime between two consecutive bars
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegimeTransition {
+    pub from: MarketRegime,
+    pub to: MarketRegime,
+    /// Timestamp of the first bar in the new regime
+    pub timestamp: DateTime<Utc>,
+    /// Detection confidence on that bar
+    pub confidence_after: Decimal,
+}
+
+/// Regime of every historical bar past the warm-up
+#[derive(Debug, Clone)]
+pub struct RegimeHistory {
+    /// Detections in bar order, keyed by bar timestamp
+    pub detections: Vec<(DateTime<Utc>, RegimeDetection)>,
+    /// Time spent in each regime; a bar lasts until the next one, and the
+    /// last bar as long as the one before it
+    pub time_in_regime: HashMap<MarketRegime, Duration>,
+    pub transitions: Vec<RegimeTransition>,
+    /// Regime of the most bars; ties go to the one with more time
+    pub most_common_regime: MarketRegime,
+    /// Index into the analyzed bars of each detection
+    bar_indices: Vec<usize>,
+}
+
+impl RegimeHistory {
+    /// Indices into the analyzed bars of those classified as `regime`,
+    /// e.g. to pick the matching rows of a returns matrix
+    pub fn filter_by_regime(&self, regime: MarketRegime) -> Vec<usize> {
+        self.detections
+            .iter()
+            .zip(&self.bar_indices)
+            .filter(|((_, d), _)| d.regime == regime)
+            .map(|(_, &i)| i)
+            .collect()
+    }
+
+    /// Number of bars classified as each regime
+    pub fn bar_counts(&self) -> HashMap<MarketRegime, usize> {
+        let mut counts = HashMap::new();
+        for (_, detection) in &self.detections {
+            *counts.entry(detection.regime).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Time covered by all detections
+    pub fn total_time(&self) -> Duration {
+        self.time_in_regime.values().fold(Duration::zero(), |acc, d| acc + *d)
+    }
+}
+
+impl RegimeDetector {
+    /// Classify historical bars, oldest first, by feeding them through
+    /// [`update`](Self::update) from a clean state. The detector keeps the
+    /// state of the last bar afterwards.
+    ///
+    /// Fails when the bars are out of order or too few to get past the
+    /// `min_bars` warm-up.
+    pub fn analyze_historical(&mut self, bars: &[PriceBar]) -> Result<RegimeHistory> {
+        if let Some(i) = (1..bars.len()).find(|&i| bars[i].timestamp <= bars[i - 1].timestamp) {
+            return Err(BotError::Strategy(format!(
+                "bars must be in chronological order (bar {} at {} follows {})",
+                i,
+                bars[i].timestamp,
+                bars[i - 1].timestamp
+            )));
+        }
+
+        self.reset();
+        let mut detections = Vec::new();
+        let mut bar_indices = Vec::new();
+        for (i, bar) in bars.iter().enumerate() {
+            if let Some(detection) = self.update(bar.clone()) {
+                detections.push((bar.timestamp, detection));
+                bar_indices.push(i);
+            }
+        }
+        if detections.is_empty() {
+            return Err(BotError::Strategy(format!(
+                "need at least {} bars to classify regimes, got {}",
+                self.config.min_bars,
+                bars.len()
+            )));
+        }
+
+        let mut time_in_regime: HashMap<MarketRegime, Duration> = HashMap::new();
+        let mut transitions = Vec::new();
+        for (i, (timestamp, detection)) in detections.iter().enumerate() {
+            let duration = match (detections.get(i + 1), i.checked_sub(1).map(|p| &detections[p])) {
+                (Some((next, _)), _) => *next - *timestamp,
+                (None, Some((prev, _))) => *timestamp - *prev,
+                (None, None) => Duration::zero(),
+            };
+            *time_in_regime.entry(detection.regime).or_insert_with(Duration::zero) += duration;
+
+            if let Some((_, prev)) = i.checked_sub(1).map(|p| &detections[p]) {
+                if prev.regime != detection.regime {
+                    transitions.push(RegimeTransition {
+                        from: prev.regime,
+                        to: detection.regime,
+                        timestamp: *timestamp,
+                        confidence_after: detection.confidence,
+                    });
+                }
+            }
+        }
+
+        let mut history = RegimeHistory {
+            detections,
+            time_in_regime,
+            transitions,
+            most_common_regime: MarketRegime::Unknown,
+            bar_indices,
+        };
+        let counts = history.bar_counts();
+        history.most_common_regime = counts
+            .iter()
+            .max_by_key(|(regime, count)| (**count, history.time_in_regime.get(*regime).copied()))
+            .map(|(regime, _)| *regime)
+            .unwrap_or(MarketRegime::Unknown);
+
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::RegimeConfig;
+    use rust_decimal_macros::dec;
+
+    /// Hourly bars: a steady climb, then a flat, tight range
+    fn trend_then_range() -> Vec<PriceBar> {
+        let start = Utc::now() - Duration::hours(200);
+        let mut bars = Vec::new();
+        let mut price = dec!(100);
+        for i in 0..160 {
+            let open = price;
+            if i < 80 {
+                price += dec!(1);
+            } else {
+                price = dec!(180) + if i % 2 == 0 { dec!(0.1) } else { dec!(-0.1) };
+            }
+            bars.push(PriceBar {
+                timestamp: start + Duration::hours(i),
+                open,
+                high: open.max(price) + dec!(0.2),
+                low: open.min(price) - dec!(0.2),
+                close: price,
+                volume: dec!(1000),
+            });
+        }
+        bars
+    }
+
+    #[test]
+    fn test_analyze_historical_summarizes_regimes() {
+        let bars = trend_then_range();
+        let mut detector = RegimeDetector::new(RegimeConfig::default());
+        let history = detector.analyze_historical(&bars).unwrap();
+
+        // Every bar past the warm-up is classified
+        let warmup = RegimeConfig::default().min_bars - 1;
+        assert_eq!(history.detections.len(), bars.len() - warmup);
+        assert_eq!(history.detections[0].0, bars[warmup].timestamp);
+
+        // Time adds up to the span of the classified bars plus one bar
+        assert_eq!(history.total_time(), Duration::hours((bars.len() - warmup) as i64));
+
+        // Per-regime indices partition the classified bars
+        let mut indices: Vec<usize> = history
+            .bar_counts()
+            .keys()
+            .flat_map(|r| history.filter_by_regime(*r))
+            .collect();
+        indices.sort();
+        assert_eq!(indices, (warmup..bars.len()).collect::<Vec<_>>());
+        assert_eq!(history.filter_by_regime(MarketRegime::BullishTrend).first(), Some(&warmup));
+
+        // The climb reads as a bullish trend and the regime changes afterwards
+        assert_eq!(history.detections[0].1.regime, MarketRegime::BullishTrend);
+        assert!(!history.transitions.is_empty());
+        let first = &history.transitions[0];
+        assert_eq!(first.from, MarketRegime::BullishTrend);
+        assert!(first.timestamp > bars[80].timestamp);
+        let counts = history.bar_counts();
+        assert!(counts.values().all(|c| *c <= counts[&history.most_common_regime]));
+    }
+
+    #[test]
+    fn test_analyze_historical_rejects_bad_input() {
+        let mut detector = RegimeDetector::new(RegimeConfig::default());
+        let bars = trend_then_range();
+        assert!(detector.analyze_historical(&bars[..10]).is_err());
+
+        let mut shuffled = bars.clone();
+        shuffled.swap(30, 31);
+        assert!(detector.analyze_historical(&shuffled).is_err());
+    }
+}
@@ -12,6 +12,12 @@
 //! - Hurst Exponent for mean reversion vs trend-following
 //! - Price distribution analysis
 //! - Volume profile analysis
+//!
+//! [`RegimeDetector::analyze_historical`] classifies a whole history at once.
+
+mod history;
+
+pub use history::{RegimeHistory, RegimeTransition};
 
 use chrono::{DateTime, Duration, Utc};
 use rust_decimal::{Decimal, MathematicalOps};
@@ -137,6 +143,18 @@ pub struct RegimeStrategy {
     pub prefer_longs: bool,
 }
 
+/// How true ranges are averaged into the ATR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtrMethod {
+    /// Simple average of the last `atr_period` true ranges
+    Sma,
+    /// Wilder's smoothing, `(prev * (n - 1) + tr) / n`, seeded with the
+    /// simple average of the first `atr_period` true ranges
+    #[default]
+    WilderEma,
+}
+
 /// Configuration for regime detection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegimeConfig {
@@ -154,6 +172,9 @@ pub struct RegimeConfig {
     pub adx_period: usize,
     /// Lookback period for ATR calculation
     pub atr_period: usize,
+    /// ATR smoothing method
+    #[serde(default)]
+    pub atr_method: AtrMethod,
     /// Lookback period for Hurst exponent
     pub hurst_period: usize,
     /// Regime change smoothing (bars)
@@ -172,6 +193,7 @@ impl Default for RegimeConfig {
             min_bars: 20,
             adx_period: 14,
             atr_period: 14,
+            atr_method: AtrMethod::default(),
             hurst_period: 100,
             smoothing_period: 3,
             use_hurst: true,
@@ -180,7 +202,7 @@ impl Default for RegimeConfig {
 }
 
 /// Price bar for regime calculation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceBar {
     pub timestamp: DateTime<Utc>,
     pub open: Decimal,
@@ -219,6 +241,18 @@ pub struct RegimeDetection {
     pub strategy: RegimeStrategy,
 }
 
+/// Everything a [`RegimeDetector`] has accumulated, so a restarted bot
+/// resumes with warm indicators instead of waiting for `min_bars` again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimeDetectorState {
+    bars: Vec<PriceBar>,
+    atr_history: Vec<Decimal>,
+    wilder_atr: Option<Decimal>,
+    wilder_seed: (Decimal, usize),
+    regime_history: Vec<MarketRegime>,
+    last_detection: Option<RegimeDetection>,
+}
+
 /// Market Regime Detector
 pub struct RegimeDetector {
     config: RegimeConfig,
@@ -226,6 +260,10 @@ pub struct RegimeDetector {
     bars: VecDeque<PriceBar>,
     /// Historical ATR values for percentile calculation
     atr_history: VecDeque<Decimal>,
+    /// Wilder-smoothed ATR, once `atr_period` true ranges have been seen
+    wilder_atr: Option<Decimal>,
+    /// Sum and count of the true ranges seeding the Wilder ATR
+    wilder_seed: (Decimal, usize),
     /// Recent regime detections for smoothing
     regime_history: VecDeque<MarketRegime>,
     /// Last detection result
@@ -239,6 +277,8 @@ impl RegimeDetector {
             config,
             bars: VecDeque::new(),
             atr_history: VecDeque::new(),
+            wilder_atr: None,
+            wilder_seed: (Decimal::ZERO, 0),
             regime_history: VecDeque::new(),
             last_detection: None,
         }
@@ -246,6 +286,10 @@ impl RegimeDetector {
 
     /// Add a new price bar and detect regime
     pub fn update(&mut self, bar: PriceBar) -> Option<RegimeDetection> {
+        if let Some(prev) = self.bars.back() {
+            let tr = Self::true_range(&bar, prev);
+            self.update_wilder_atr(tr);
+        }
         self.bars.push_back(bar);
 
         // Keep only necessary history
@@ -374,8 +418,39 @@ impl RegimeDetector {
         (adx, plus_di, minus_di)
     }
 
-    /// Calculate ATR (Average True Range)
+    /// True range of a bar given the one before it
+    fn true_range(current: &PriceBar, prev: &PriceBar) -> Decimal {
+        (current.high - current.low)
+            .max(Decimal::abs(&(current.high - prev.close)))
+            .max(Decimal::abs(&(current.low - prev.close)))
+    }
+
+    /// Fold the latest true range into the Wilder ATR
+    fn update_wilder_atr(&mut self, tr: Decimal) {
+        let period = Decimal::from(self.config.atr_period.max(1));
+        match self.wilder_atr {
+            Some(atr) => self.wilder_atr = Some((atr * (period - dec!(1)) + tr) / period),
+            None => {
+                let (sum, count) = &mut self.wilder_seed;
+                *sum += tr;
+                *count += 1;
+                if *count >= self.config.atr_period.max(1) {
+                    self.wilder_atr = Some(*sum / period);
+                }
+            }
+        }
+    }
+
+    /// Calculate ATR (Average True Range) with the configured method
     fn calculate_atr(&self) -> Decimal {
+        match self.config.atr_method {
+            AtrMethod::Sma => self.calculate_sma_atr(),
+            AtrMethod::WilderEma => self.wilder_atr.unwrap_or(dec!(0)),
+        }
+    }
+
+    /// Simple average of the last `atr_period` true ranges
+    fn calculate_sma_atr(&self) -> Decimal {
         let period = self.config.atr_period;
         if self.bars.len() < period + 1 {
             return dec!(0);
@@ -386,13 +461,7 @@ impl RegimeDetector {
 
         for i in 1..=period {
             let idx = bars.len() - period - 1 + i;
-            let current = &bars[idx];
-            let prev = &bars[idx - 1];
-
-            let tr = (current.high - current.low)
-                .max(Decimal::abs(&(current.high - prev.close)))
-                .max(Decimal::abs(&(current.low - prev.close)));
-            tr_sum += tr;
+            tr_sum += Self::true_range(bars[idx], bars[idx - 1]);
         }
 
         tr_sum / Decimal::from(period)
@@ -702,15 +771,47 @@ impl RegimeDetector {
         }
     }
 
+    /// Snapshot of the accumulated bars and indicator state
+    pub fn state(&self) -> RegimeDetectorState {
+        RegimeDetectorState {
+            bars: self.bars.iter().cloned().collect(),
+            atr_history: self.atr_history.iter().copied().collect(),
+            wilder_atr: self.wilder_atr,
+            wilder_seed: self.wilder_seed,
+            regime_history: self.regime_history.iter().copied().collect(),
+            last_detection: self.last_detection.clone(),
+        }
+    }
+
+    /// Detector resuming from a [`state`](Self::state) snapshot
+    pub fn from_state(config: RegimeConfig, state: RegimeDetectorState) -> Self {
+        Self {
+            config,
+            bars: state.bars.into(),
+            atr_history: state.atr_history.into(),
+            wilder_atr: state.wilder_atr,
+            wilder_seed: state.wilder_seed,
+            regime_history: state.regime_history.into(),
+            last_detection: state.last_detection,
+        }
+    }
+
     /// Reset detector state
     pub fn reset(&mut self) {
         self.bars.clear();
         self.atr_history.clear();
+        self.wilder_atr = None;
+        self.wilder_seed = (Decimal::ZERO, 0);
         self.regime_history.clear();
         self.last_detection = None;
     }
 }
 
+/// Short-term bars rolled into one medium-term bar by [`MultiTimeframeRegime::update`]
+pub const MEDIUM_TERM_BARS: usize = 4;
+/// Short-term bars rolled into one long-term bar by [`MultiTimeframeRegime::update`]
+pub const LONG_TERM_BARS: usize = 16;
+
 /// Multi-timeframe regime analysis
 pub struct MultiTimeframeRegime {
     /// Short-term detector (e.g., 15m)
@@ -719,6 +820,9 @@ pub struct MultiTimeframeRegime {
     pub medium_term: RegimeDetector,
     /// Long-term detector (e.g., 4h)
     pub long_term: RegimeDetector,
+    /// Medium- and long-term bars being built from short-term ones
+    pending: [Option<PriceBar>; 2],
+    short_bars: usize,
 }
 
 impl MultiTimeframeRegime {
@@ -728,6 +832,37 @@ impl MultiTimeframeRegime {
             short_term: RegimeDetector::new(RegimeConfig::default()),
             medium_term: RegimeDetector::new(RegimeConfig::default()),
             long_term: RegimeDetector::new(RegimeConfig::default()),
+            pending: [None, None],
+            short_bars: 0,
+        }
+    }
+
+    /// Feed a short-term bar, rolling every [`MEDIUM_TERM_BARS`] of them
+    /// into a medium-term bar and every [`LONG_TERM_BARS`] into a long-term one
+    pub fn update(&mut self, bar: PriceBar) {
+        self.short_term.update(bar.clone());
+        self.short_bars += 1;
+        let timeframes = [
+            (MEDIUM_TERM_BARS, &mut self.medium_term),
+            (LONG_TERM_BARS, &mut self.long_term),
+        ];
+        for ((every, detector), pending) in timeframes.into_iter().zip(self.pending.iter_mut()) {
+            let rolled = match pending.take() {
+                Some(p) => PriceBar {
+                    timestamp: bar.timestamp,
+                    open: p.open,
+                    high: p.high.max(bar.high),
+                    low: p.low.min(bar.low),
+                    close: bar.close,
+                    volume: p.volume + bar.volume,
+                },
+                None => bar.clone(),
+            };
+            if self.short_bars.is_multiple_of(every) {
+                detector.update(rolled);
+            } else {
+                *pending = Some(rolled);
+            }
         }
     }
 
@@ -815,6 +950,54 @@ pub struct RegimeConsensus {
     pub long_regime: MarketRegime,
 }
 
+impl RegimeConsensus {
+    /// Position size multiplier under the default [`ConsensusSizingConfig`]
+    pub fn size_multiplier(&self) -> Decimal {
+        self.size_multiplier_with(&ConsensusSizingConfig::default())
+    }
+
+    /// Position size multiplier for this consensus: a crisis on any
+    /// timeframe overrides the alignment, and full alignment only scales
+    /// up with enough confidence
+    pub fn size_multiplier_with(&self, config: &ConsensusSizingConfig) -> Decimal {
+        if self.primary_regime == MarketRegime::Crisis {
+            return config.crisis;
+        }
+        match self.alignment {
+            RegimeAlignment::FullyAligned if self.confidence >= config.min_aligned_confidence => config.fully_aligned,
+            RegimeAlignment::FullyAligned | RegimeAlignment::PartiallyAligned => config.partially_aligned,
+            RegimeAlignment::Conflicting => config.conflicting,
+        }
+    }
+}
+
+/// Size multipliers per multi-timeframe consensus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsensusSizingConfig {
+    /// All timeframes agree with at least `min_aligned_confidence`
+    pub fully_aligned: Decimal,
+    pub min_aligned_confidence: Decimal,
+    /// Some timeframes agree, or all do with less confidence
+    pub partially_aligned: Decimal,
+    /// No two adjacent timeframes agree
+    pub conflicting: Decimal,
+    /// A crisis on any timeframe
+    pub crisis: Decimal,
+}
+
+impl Default for ConsensusSizingConfig {
+    fn default() -> Self {
+        Self {
+            fully_aligned: dec!(1.25),
+            min_aligned_confidence: dec!(0.7),
+            partially_aligned: dec!(1.0),
+            conflicting: dec!(0.6),
+            crisis: dec!(0.05),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1021,6 +1204,50 @@ mod tests {
         assert!(detection.atr > dec!(0), "ATR should be positive");
     }
 
+    #[test]
+    fn test_atr_methods_diverge_after_volatility_jump() {
+        let bar = |i: i64, half_range: Decimal| PriceBar {
+            timestamp: Utc::now() - Duration::hours(100 - i),
+            open: dec!(100),
+            high: dec!(100) + half_range,
+            low: dec!(100) - half_range,
+            close: dec!(100),
+            volume: dec!(1000),
+        };
+        let detector = |atr_method| RegimeDetector::new(RegimeConfig { atr_method, ..Default::default() });
+        let mut sma = detector(AtrMethod::Sma);
+        let mut wilder = detector(AtrMethod::WilderEma);
+
+        // Calm bars: both settle on the constant true range
+        for i in 0..30 {
+            sma.update(bar(i, dec!(1)));
+            wilder.update(bar(i, dec!(1)));
+        }
+        assert_eq!(sma.current_regime().unwrap().atr, dec!(2));
+        assert_eq!(wilder.current_regime().unwrap().atr, dec!(2));
+
+        // A full period of wide bars: the SMA has fully caught up, Wilder's
+        // smoothing still carries weight from the calm bars
+        for i in 30..44 {
+            sma.update(bar(i, dec!(5)));
+            wilder.update(bar(i, dec!(5)));
+        }
+        let sma_atr = sma.current_regime().unwrap().atr;
+        let wilder_atr = wilder.current_regime().unwrap().atr;
+        assert_eq!(sma_atr, dec!(10));
+        assert!(wilder_atr > dec!(2) && wilder_atr < sma_atr, "wilder ATR {}", wilder_atr);
+        // 10 - 8 * (13/14)^14
+        assert!((wilder_atr - dec!(7.166)).abs() < dec!(0.01), "wilder ATR {}", wilder_atr);
+
+        // Back to calm: the SMA drops straight back, Wilder decays slowly
+        sma.update(bar(44, dec!(1)));
+        wilder.update(bar(44, dec!(1)));
+        let sma_atr = sma.current_regime().unwrap().atr;
+        let wilder_atr = wilder.current_regime().unwrap().atr;
+        assert!(sma_atr > dec!(0) && wilder_atr > dec!(0));
+        assert!(wilder_atr < sma_atr);
+    }
+
     #[test]
     fn test_regime_smoothing() {
         let config = RegimeConfig {
@@ -1090,6 +1317,46 @@ mod tests {
         assert!(mtf.long_term.current_regime().is_none());
     }
 
+    #[test]
+    fn test_multi_timeframe_rolls_bars_up() {
+        let mut mtf = MultiTimeframeRegime::new();
+        let bars = create_trending_bars(LONG_TERM_BARS * 2, true);
+        for bar in bars.iter().cloned() {
+            mtf.update(bar);
+        }
+        assert_eq!(mtf.short_term.state().bars.len(), 32);
+        assert_eq!(mtf.long_term.state().bars.len(), 2);
+        let medium = mtf.medium_term.state().bars;
+        assert_eq!(medium.len(), 8);
+        let first = &medium[0];
+        assert_eq!((first.open, first.close), (bars[0].open, bars[3].close));
+        assert_eq!(first.high, bars[..4].iter().map(|b| b.high).max().unwrap());
+        assert_eq!(first.volume, dec!(4000));
+    }
+
+    #[test]
+    fn test_consensus_size_multiplier() {
+        let consensus = |primary_regime, alignment, confidence| RegimeConsensus {
+            primary_regime,
+            confidence,
+            alignment,
+            short_regime: primary_regime,
+            medium_regime: primary_regime,
+            long_regime: primary_regime,
+        };
+        let aligned = consensus(MarketRegime::BullishTrend, RegimeAlignment::FullyAligned, dec!(0.9));
+        assert!(aligned.size_multiplier() > Decimal::ONE);
+        let unsure = consensus(MarketRegime::BullishTrend, RegimeAlignment::FullyAligned, dec!(0.5));
+        assert_eq!(unsure.size_multiplier(), Decimal::ONE);
+        let conflicting = consensus(MarketRegime::Ranging, RegimeAlignment::Conflicting, dec!(0.9));
+        assert!(conflicting.size_multiplier() < Decimal::ONE);
+        let crisis = consensus(MarketRegime::Crisis, RegimeAlignment::Conflicting, dec!(0.9));
+        assert!(crisis.size_multiplier() < dec!(0.1));
+
+        let config = ConsensusSizingConfig { fully_aligned: dec!(2), ..Default::default() };
+        assert_eq!(aligned.size_multiplier_with(&config), dec!(2));
+    }
+
     #[test]
     fn test_detector_reset() {
         let mut detector = RegimeDetector::new(RegimeConfig::default());
@@ -0,0 +1,261 @@
+//! Benchmarks for the monthly report
+//!
+//! A month's return means little on its own: [`hold_usdc`] is what doing
+//! nothing earned, and [`simulate_favorite`] is what the laziest active
+//! strategy earned — buying the favorite of every market the bot scanned,
+//! replayed from the stored daily price snapshots.
+
+use crate::fees::{FeeModel, FeeRole};
+use crate::storage::PriceSnapshot;
+use crate::types::Side;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Name of the cash benchmark
+pub const HOLD_USDC: &str = "Hold USDC";
+
+/// Name of the buy-the-favorite benchmark
+pub const BUY_FAVORITE: &str = "Buy favorite";
+
+/// Equity path of one benchmark over the period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub starting_equity: Decimal,
+    pub ending_equity: Decimal,
+    /// `ending / starting - 1`
+    pub total_return: Decimal,
+    /// Largest peak-to-trough fall of the daily equity, as a fraction of the peak
+    pub max_drawdown: Decimal,
+    /// Markets a position was taken in
+    pub markets: usize,
+    /// Equity at the end of each day with snapshots
+    pub equity_curve: Vec<(NaiveDate, Decimal)>,
+}
+
+impl BenchmarkResult {
+    fn from_curve(name: &str, starting_equity: Decimal, markets: usize, equity_curve: Vec<(NaiveDate, Decimal)>) -> Self {
+        let ending_equity = equity_curve.last().map_or(starting_equity, |(_, equity)| *equity);
+        let mut peak = starting_equity;
+        let mut max_drawdown = Decimal::ZERO;
+        for (_, equity) in &equity_curve {
+            peak = peak.max(*equity);
+            if peak > Decimal::ZERO {
+                max_drawdown = max_drawdown.max((peak - equity) / peak);
+            }
+        }
+        Self {
+            name: name.to_string(),
+            starting_equity,
+            ending_equity,
+            total_return: if starting_equity > Decimal::ZERO {
+                ending_equity / starting_equity - Decimal::ONE
+            } else {
+                Decimal::ZERO
+            },
+            max_drawdown,
+            markets,
+            equity_curve,
+        }
+    }
+}
+
+/// Keeping the whole bankroll in USDC: no return, no drawdown
+pub fn hold_usdc(starting_equity: Decimal) -> BenchmarkResult {
+    BenchmarkResult::from_curve(HOLD_USDC, starting_equity, 0, Vec::new())
+}
+
+/// A favorite bought at a market's first snapshot
+struct FavoritePosition {
+    /// The favorite is YES
+    yes: bool,
+    shares: Decimal,
+    /// Latest price of the favorite side
+    mark: Decimal,
+}
+
+/// Buy the favorite (YES at 50c and up, NO below) of every market in
+/// `snapshots` at its first snapshot, with an equal share of
+/// `starting_equity` each, paying taker fees, and hold to the end.
+///
+/// Positions are marked to each day's snapshot. A market in `resolutions`
+/// (YES won or not) pays out from its last snapshot's day on, since the bot
+/// stops scanning markets once they close; unresolved ones stay at their
+/// last price. Stakes of markets not yet seen, or priced at 0 or 1, sit in
+/// USDC.
+pub fn simulate_favorite(
+    snapshots: &[PriceSnapshot],
+    resolutions: &HashMap<String, bool>,
+    starting_equity: Decimal,
+    fees: &dyn FeeModel,
+) -> BenchmarkResult {
+    let mut days: BTreeMap<NaiveDate, Vec<&PriceSnapshot>> = BTreeMap::new();
+    let mut last_day: HashMap<&str, NaiveDate> = HashMap::new();
+    for snapshot in snapshots {
+        let day = snapshot.taken_at.date_naive();
+        days.entry(day).or_default().push(snapshot);
+        let last = last_day.entry(snapshot.market_id.as_str()).or_insert(day);
+        *last = (*last).max(day);
+    }
+    if last_day.is_empty() {
+        return BenchmarkResult::from_curve(BUY_FAVORITE, starting_equity, 0, Vec::new());
+    }
+    let stake = starting_equity / Decimal::from(last_day.len());
+
+    let mut cash = starting_equity;
+    let mut positions: HashMap<&str, FavoritePosition> = HashMap::new();
+    let mut skipped: HashSet<&str> = HashSet::new();
+    let mut equity_curve = Vec::with_capacity(days.len());
+    for (day, mut day_snapshots) in days {
+        day_snapshots.sort_by_key(|s| s.taken_at);
+        for snapshot in day_snapshots {
+            let market_id = snapshot.market_id.as_str();
+            if let Some(position) = positions.get_mut(market_id) {
+                position.mark = if position.yes { snapshot.yes_price } else { Decimal::ONE - snapshot.yes_price };
+                continue;
+            }
+            if skipped.contains(market_id) {
+                continue;
+            }
+            let yes = snapshot.yes_price >= dec!(0.5);
+            let price = if yes { snapshot.yes_price } else { Decimal::ONE - snapshot.yes_price };
+            if price <= Decimal::ZERO || price >= Decimal::ONE {
+                skipped.insert(market_id);
+                continue;
+            }
+            let fee = fees.fee_per_share(market_id, Side::Buy, price, FeeRole::Taker);
+            cash -= stake;
+            positions.insert(market_id, FavoritePosition { yes, shares: stake / (price + fee), mark: price });
+        }
+
+        for (market_id, position) in positions.iter_mut() {
+            if last_day.get(market_id) != Some(&day) {
+                continue;
+            }
+            if let Some(&yes_won) = resolutions.get(*market_id) {
+                position.mark = if yes_won == position.yes { Decimal::ONE } else { Decimal::ZERO };
+            }
+        }
+
+        let equity = cash + positions.values().map(|p| p.shares * p.mark).sum::<Decimal>();
+        equity_curve.push((day, equity));
+    }
+
+    BenchmarkResult::from_curve(BUY_FAVORITE, starting_equity, positions.len(), equity_curve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FeeSchedule;
+    use crate::fees::PolymarketFees;
+    use chrono::{TimeZone, Utc};
+
+    fn snapshot(market_id: &str, day: u32, yes_price: Decimal) -> PriceSnapshot {
+        PriceSnapshot {
+            market_id: market_id.to_string(),
+            yes_price,
+            taken_at: Utc.with_ymd_and_hms(2024, 6, day, 12, 0, 0).unwrap(),
+        }
+    }
+
+    fn fees(taker_fee_bps: Decimal) -> PolymarketFees {
+        PolymarketFees::new(FeeSchedule {
+            taker_fee_bps,
+            maker_fee_bps: Decimal::ZERO,
+        })
+    }
+
+    #[test]
+    fn test_hold_usdc_is_flat() {
+        let result = hold_usdc(dec!(1000));
+        assert_eq!(result.ending_equity, dec!(1000));
+        assert_eq!(result.total_return, Decimal::ZERO);
+        assert_eq!(result.max_drawdown, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_no_snapshots_matches_holding_usdc() {
+        let result = simulate_favorite(&[], &HashMap::new(), dec!(1000), &fees(Decimal::ZERO));
+        assert_eq!(result.name, BUY_FAVORITE);
+        assert_eq!(result.ending_equity, dec!(1000));
+        assert_eq!(result.markets, 0);
+    }
+
+    #[test]
+    fn test_favorite_pays_out_at_resolution() {
+        let snapshots = [snapshot("a", 1, dec!(0.80)), snapshot("a", 2, dec!(0.90))];
+        let resolutions = HashMap::from([("a".to_string(), true)]);
+        let result = simulate_favorite(&snapshots, &resolutions, dec!(100), &fees(Decimal::ZERO));
+        // 125 shares at 80c, marked at 80c on day 1 and paid $1 on day 2
+        assert_eq!(result.equity_curve, vec![
+            (NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), dec!(100)),
+            (NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(), dec!(125)),
+        ]);
+        assert_eq!(result.total_return, dec!(0.25));
+        assert_eq!(result.markets, 1);
+    }
+
+    #[test]
+    fn test_buys_no_below_fifty_cents_and_marks_to_market() {
+        // NO is the favorite at 70c, then slides to 56c without resolving
+        let snapshots = [
+            snapshot("a", 1, dec!(0.30)),
+            snapshot("a", 2, dec!(0.44)),
+            snapshot("a", 3, dec!(0.40)),
+        ];
+        let result = simulate_favorite(&snapshots, &HashMap::new(), dec!(70), &fees(Decimal::ZERO));
+        let equity: Vec<Decimal> = result.equity_curve.iter().map(|(_, e)| *e).collect();
+        assert_eq!(equity, vec![dec!(70), dec!(56), dec!(60)]);
+        assert_eq!(result.max_drawdown, dec!(0.2));
+        assert_eq!(result.total_return, dec!(60) / dec!(70) - Decimal::ONE);
+    }
+
+    #[test]
+    fn test_equal_weight_with_an_upset_and_a_late_market() {
+        let snapshots = [
+            snapshot("fav", 1, dec!(0.80)),
+            snapshot("upset", 1, dec!(0.20)),
+            snapshot("upset", 2, dec!(0.25)),
+            snapshot("late", 3, dec!(0.50)),
+            snapshot("fav", 3, dec!(0.95)),
+        ];
+        let resolutions = HashMap::from([
+            ("fav".to_string(), true),
+            ("upset".to_string(), true),
+            ("late".to_string(), false),
+        ]);
+        let result = simulate_favorite(&snapshots, &resolutions, dec!(300), &fees(Decimal::ZERO));
+        let equity: Vec<Decimal> = result.equity_curve.iter().map(|(_, e)| *e).collect();
+        // $100 each: 125 shares each of fav YES and upset NO, and $100
+        // waiting in USDC for the late market
+        assert_eq!(equity[0], dec!(300));
+        // Upset resolves YES on its last day: its NO shares are worthless
+        assert_eq!(equity[1], dec!(200));
+        // Late YES (the tie goes to YES) loses, fav pays $1 a share
+        assert_eq!(equity[2], dec!(125));
+        assert_eq!(result.markets, 3);
+        assert_eq!(result.max_drawdown, dec!(175) / dec!(300));
+    }
+
+    #[test]
+    fn test_taker_fees_reduce_shares_bought() {
+        // 500 bps of min(p, 1 - p): 1c a share at 80c
+        let snapshots = [snapshot("a", 1, dec!(0.80))];
+        let resolutions = HashMap::from([("a".to_string(), true)]);
+        let result = simulate_favorite(&snapshots, &resolutions, dec!(81), &fees(dec!(500)));
+        assert_eq!(result.ending_equity, dec!(100));
+    }
+
+    #[test]
+    fn test_settled_prices_are_not_bought() {
+        let snapshots = [snapshot("done", 1, Decimal::ONE), snapshot("a", 1, dec!(0.50))];
+        let result = simulate_favorite(&snapshots, &HashMap::new(), dec!(100), &fees(Decimal::ZERO));
+        assert_eq!(result.markets, 1);
+        // The settled market's half stays in USDC
+        assert_eq!(result.ending_equity, dec!(100));
+    }
+}
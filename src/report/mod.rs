@@ -0,0 +1,266 @@
+//! Monthly performance report
+//!
+//! [`MonthlyReport::build`] gathers a calendar month from the database:
+//! return on the starting equity, Sharpe and Sortino of the daily returns,
+//! drawdown, hit rate, fees, LLM spend and attribution by strategy and
+//! market category, next to the [`benchmark`]s the month is judged against.
+//! The Telegram summary is `notify::monthly_report`; the full report is
+//! written as JSON by [`MonthlyReport::write_json`]. The daily report is
+//! timed by [`DailySchedule`].
+
+pub mod benchmark;
+pub mod schedule;
+
+pub use benchmark::{hold_usdc, simulate_favorite, BenchmarkResult};
+pub use schedule::DailySchedule;
+
+use crate::error::{BotError, Result};
+use crate::fees::FeeModel;
+use crate::monitor::RollingPerformance;
+use crate::storage::{Database, LlmUsageTotals, PortfolioSnapshot, TagPerformance};
+use crate::types::{Trade, TradeStatus};
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory the JSON reports are written to by default
+pub const REPORT_DIR: &str = "reports";
+
+/// Trades on markets without a cached category
+const UNCATEGORIZED: &str = "uncategorized";
+
+/// Tag prefix naming the strategy a trade executed
+const STRATEGY_TAG: &str = "strategy:";
+
+/// One calendar month of trading
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthlyReport {
+    /// e.g. "2024-06"
+    pub period: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Portfolio value at the first snapshot of the month
+    pub starting_equity: Decimal,
+    /// Portfolio value at the last snapshot of the month
+    pub ending_equity: Decimal,
+    /// `ending / starting - 1`
+    pub monthly_return: Decimal,
+    /// Close-to-close return of each day after the first
+    pub daily_returns: Vec<(NaiveDate, Decimal)>,
+    /// Annualized; `None` with too few daily returns
+    pub sharpe: Option<Decimal>,
+    pub sortino: Option<Decimal>,
+    pub max_drawdown: Decimal,
+    /// Closing fills made in the month
+    pub closes: usize,
+    pub wins: usize,
+    /// Share of closing fills that made money after fees
+    pub hit_rate: Decimal,
+    pub avg_win: Option<Decimal>,
+    pub avg_loss: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    /// Fees paid on the month's fills
+    pub fees: Decimal,
+    pub llm_usage: LlmUsageTotals,
+    /// `None` unless the LLM token prices are configured
+    pub llm_spend: Option<Decimal>,
+    /// Performance per `strategy:` tag, credited to the entries' strategy
+    pub by_strategy: Vec<TagPerformance>,
+    /// Closing P&L per market category
+    pub by_category: Vec<TagPerformance>,
+    pub benchmarks: Vec<BenchmarkResult>,
+}
+
+/// First and first-after-last instants of the month `period` ("2024-06")
+pub fn month_range(period: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", period.trim()), "%Y-%m-%d").ok()?;
+    let next = first.checked_add_months(Months::new(1))?;
+    Some((first.and_hms_opt(0, 0, 0)?.and_utc(), next.and_hms_opt(0, 0, 0)?.and_utc()))
+}
+
+impl MonthlyReport {
+    /// Report on `period` ("2024-06"). The favorite benchmark pays `fees`
+    /// and starts from the month's starting equity; `llm_prices` are USD
+    /// per million input and output tokens.
+    pub async fn build(
+        db: &Database,
+        period: &str,
+        fees: &dyn FeeModel,
+        llm_prices: Option<(Decimal, Decimal)>,
+    ) -> Result<Self> {
+        let (from, to) = month_range(period)
+            .ok_or_else(|| BotError::Config(format!("Invalid period '{}', expected e.g. 2024-06", period)))?;
+
+        let history = db.get_portfolio_history(from, to).await?;
+        let closing_values = daily_closing_values(&history);
+        let starting_equity = history.first().map_or(Decimal::ZERO, |s| s.value);
+        let ending_equity = history.last().map_or(Decimal::ZERO, |s| s.value);
+        let daily_returns = daily_returns(&closing_values);
+        let mut performance = RollingPerformance::new(daily_returns.len().max(1), Decimal::ZERO);
+        for (_, r) in &daily_returns {
+            performance.update(*r);
+        }
+
+        let closing_fills = db.get_closing_fills_between(from, to).await?;
+        let (wins, losses): (Vec<Decimal>, Vec<Decimal>) =
+            closing_fills.iter().map(|(_, pnl)| *pnl).partition(|pnl| *pnl > Decimal::ZERO);
+        let mean = |xs: &[Decimal]| (!xs.is_empty()).then(|| xs.iter().sum::<Decimal>() / Decimal::from(xs.len()));
+
+        let fees_paid = db
+            .get_trades_since(from)
+            .await?
+            .iter()
+            .filter(|t| t.status == TradeStatus::Filled && t.timestamp < to)
+            .map(|t| t.fee)
+            .sum();
+
+        let llm_usage = db.get_llm_usage(from.date_naive(), to.date_naive()).await?;
+        let by_strategy = db
+            .performance_by_tag(from, to)
+            .await?
+            .into_iter()
+            .filter_map(|p| {
+                let strategy = p.tag.strip_prefix(STRATEGY_TAG)?.to_string();
+                Some(TagPerformance { tag: strategy, ..p })
+            })
+            .collect();
+
+        let mut categories = BTreeMap::new();
+        for (trade, _) in &closing_fills {
+            if categories.contains_key(&trade.market_id) {
+                continue;
+            }
+            let category = db
+                .get_cached_market(&trade.market_id)
+                .await?
+                .and_then(|m| m.category)
+                .unwrap_or_else(|| UNCATEGORIZED.to_string());
+            categories.insert(trade.market_id.clone(), category);
+        }
+        let by_category = attribute_closes(&closing_fills, |trade| categories[&trade.market_id].clone());
+
+        let snapshots = db.get_price_snapshots(from, to).await?;
+        let resolutions = db.get_resolved_outcomes().await?;
+        let benchmarks = vec![
+            hold_usdc(starting_equity),
+            simulate_favorite(&snapshots, &resolutions, starting_equity, fees),
+        ];
+
+        Ok(Self {
+            period: period.trim().to_string(),
+            from,
+            to,
+            starting_equity,
+            ending_equity,
+            monthly_return: if starting_equity > Decimal::ZERO {
+                ending_equity / starting_equity - Decimal::ONE
+            } else {
+                Decimal::ZERO
+            },
+            sharpe: performance.sharpe(),
+            sortino: performance.sortino(),
+            max_drawdown: performance.max_drawdown(),
+            daily_returns,
+            closes: closing_fills.len(),
+            wins: wins.len(),
+            hit_rate: if closing_fills.is_empty() {
+                Decimal::ZERO
+            } else {
+                Decimal::from(wins.len()) / Decimal::from(closing_fills.len())
+            },
+            avg_win: mean(&wins),
+            avg_loss: mean(&losses),
+            realized_pnl: closing_fills.iter().map(|(_, pnl)| *pnl).sum(),
+            fees: fees_paid,
+            llm_spend: llm_prices.map(|(input, output)| llm_usage.cost(input, output)),
+            llm_usage,
+            by_strategy,
+            by_category,
+            benchmarks,
+        })
+    }
+
+    /// Write the report to `dir/<period>.json`, creating `dir`
+    pub fn write_json(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(format!("{}.json", self.period));
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(&path, serde_json::to_vec_pretty(self).unwrap_or_default()))
+            .map_err(|e| BotError::Internal(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(path)
+    }
+}
+
+/// Last portfolio value of each UTC day, oldest first
+fn daily_closing_values(history: &[PortfolioSnapshot]) -> Vec<(NaiveDate, Decimal)> {
+    let mut by_day: BTreeMap<NaiveDate, Decimal> = BTreeMap::new();
+    for snapshot in history {
+        by_day.insert(snapshot.created_at.date_naive(), snapshot.value);
+    }
+    by_day.into_iter().collect()
+}
+
+/// Return of each day over the previous day's close
+fn daily_returns(closing_values: &[(NaiveDate, Decimal)]) -> Vec<(NaiveDate, Decimal)> {
+    closing_values
+        .windows(2)
+        .filter(|pair| pair[0].1 > Decimal::ZERO)
+        .map(|pair| (pair[1].0, pair[1].1 / pair[0].1 - Decimal::ONE))
+        .collect()
+}
+
+/// Closing fills summed per `key`, sorted by key
+fn attribute_closes(closing_fills: &[(Trade, Decimal)], key: impl Fn(&Trade) -> String) -> Vec<TagPerformance> {
+    let mut by_key: BTreeMap<String, TagPerformance> = BTreeMap::new();
+    for (trade, pnl) in closing_fills {
+        let tag = key(trade);
+        let line = by_key.entry(tag.clone()).or_insert_with(|| TagPerformance { tag, ..Default::default() });
+        line.trades += 1;
+        line.closes += 1;
+        if *pnl > Decimal::ZERO {
+            line.wins += 1;
+        }
+        line.realized_pnl += pnl;
+    }
+    by_key.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(day: u32, hour: u32, value: Decimal) -> PortfolioSnapshot {
+        PortfolioSnapshot {
+            created_at: Utc.with_ymd_and_hms(2024, 6, day, hour, 0, 0).unwrap(),
+            value,
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            n_open_positions: 0,
+            balance: value,
+        }
+    }
+
+    #[test]
+    fn test_month_range() {
+        let (from, to) = month_range("2024-12").unwrap();
+        assert_eq!(from, Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert!(month_range("2024-13").is_none());
+        assert!(month_range("June").is_none());
+    }
+
+    #[test]
+    fn test_daily_returns_use_each_days_last_value() {
+        let history = [
+            snapshot(1, 1, dec!(90)),
+            snapshot(1, 23, dec!(100)),
+            snapshot(2, 12, dec!(110)),
+            snapshot(4, 12, dec!(99)),
+        ];
+        let returns: Vec<Decimal> = daily_returns(&daily_closing_values(&history)).into_iter().map(|(_, r)| r).collect();
+        assert_eq!(returns, vec![dec!(0.1), dec!(-0.1)]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Once-a-day trigger for the daily report and risk reset
+//!
+//! [`DailySchedule`] fires on the first check after each UTC midnight. Time
+//! comes from a [`Clock`], so tests move a `ManualClock` across midnight
+//! instead of waiting for one.
+
+use crate::clock::Clock;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fires once per UTC day
+#[derive(Debug)]
+pub struct DailySchedule {
+    clock: Arc<dyn Clock>,
+    /// How often [`next`](Self::next) looks at the clock
+    check_every: Duration,
+    /// Day of the last firing, or of creation
+    day: NaiveDate,
+}
+
+impl DailySchedule {
+    /// Schedule whose first firing is the next UTC midnight
+    pub fn new(clock: Arc<dyn Clock>, check_every: Duration) -> Self {
+        let day = clock.now().date_naive();
+        Self { clock, check_every, day }
+    }
+
+    /// Whether a new UTC day has begun since the last firing; true once per
+    /// day, however often it's asked
+    pub fn due(&mut self) -> bool {
+        let today = self.clock.now().date_naive();
+        if today <= self.day {
+            return false;
+        }
+        self.day = today;
+        true
+    }
+
+    /// Wait until the next UTC day begins; returns the time it was noticed
+    pub async fn next(&mut self) -> DateTime<Utc> {
+        loop {
+            self.clock.sleep(self.check_every).await;
+            if self.due() {
+                return self.clock.now();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::risk::DailyPnlTracker;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_midnight_triggers_one_rollover_and_one_report() {
+        let clock = Arc::new(ManualClock::new(Utc.with_ymd_and_hms(2024, 3, 9, 23, 50, 0).unwrap()));
+        let mut schedule = DailySchedule::new(clock.clone(), Duration::from_secs(60));
+        let mut tracker = DailyPnlTracker::new(dec!(0.10)).with_clock(clock.clone());
+        tracker.record_pnl(dec!(-40));
+
+        let (mut rollovers, mut reports) = (0, 0);
+        // 23:50 to 00:20, a minute at a time
+        for _ in 0..30 {
+            clock.advance(60 * 1000);
+            rollovers += tracker.roll_over() as u32;
+            reports += schedule.due() as u32;
+        }
+
+        assert_eq!((rollovers, reports), (1, 1));
+        assert_eq!(tracker.current_pnl(), dec!(0));
+        assert_eq!(tracker.state().date, "2024-03-10");
+    }
+
+    #[tokio::test]
+    async fn test_next_waits_for_midnight() {
+        let clock = Arc::new(ManualClock::new(Utc.with_ymd_and_hms(2024, 3, 9, 23, 58, 0).unwrap()));
+        let mut schedule = DailySchedule::new(clock.clone(), Duration::from_secs(60));
+        let fired = tokio::spawn(async move { schedule.next().await });
+
+        for _ in 0..2 {
+            tokio::task::yield_now().await;
+            assert!(!fired.is_finished());
+            clock.advance(60 * 1000);
+        }
+        let at = tokio::time::timeout(Duration::from_secs(5), fired).await.unwrap().unwrap();
+        assert_eq!(at, Utc.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap());
+    }
+}
@@ -0,0 +1,102 @@
+//! Consecutive API failure tracking
+//!
+//! A run of failed exchange or database calls usually means an outage, not
+//! a bad order. After `max_consecutive` failures in a row trading pauses for
+//! `pause`; the next successful call clears the run.
+
+use crate::error::BotError;
+use std::time::{Duration, Instant};
+
+/// Counts API failures since the last success
+#[derive(Debug, Clone)]
+pub struct ApiFailureTracker {
+    max_consecutive: u32,
+    pause: Duration,
+    consecutive: u32,
+    last_failure: Option<Instant>,
+}
+
+impl ApiFailureTracker {
+    /// `max_consecutive` of 0 never pauses
+    pub fn new(max_consecutive: u32, pause: Duration) -> Self {
+        Self {
+            max_consecutive,
+            pause,
+            consecutive: 0,
+            last_failure: None,
+        }
+    }
+
+    /// Count `error` if it is an API failure; other errors leave the run as is
+    pub fn record_failure(&mut self, error: &BotError) {
+        self.record_failure_at(error, Instant::now());
+    }
+
+    fn record_failure_at(&mut self, error: &BotError, now: Instant) {
+        if error.is_api_failure() {
+            self.consecutive += 1;
+            self.last_failure = Some(now);
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive = 0;
+        self.last_failure = None;
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive
+    }
+
+    /// Whether the failure run is long and recent enough to pause trading
+    pub fn is_paused(&self) -> bool {
+        self.is_paused_at(Instant::now())
+    }
+
+    fn is_paused_at(&self, now: Instant) -> bool {
+        if self.max_consecutive == 0 || self.consecutive < self.max_consecutive {
+            return false;
+        }
+        self.last_failure
+            .is_some_and(|at| now.saturating_duration_since(at) < self.pause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeout() -> BotError {
+        BotError::Api("timeout".to_string())
+    }
+
+    #[test]
+    fn test_pauses_after_consecutive_failures_until_pause_expires() {
+        let mut tracker = ApiFailureTracker::new(3, Duration::from_secs(60));
+        let start = Instant::now();
+
+        tracker.record_failure_at(&timeout(), start);
+        tracker.record_failure_at(&timeout(), start);
+        assert!(!tracker.is_paused_at(start));
+
+        tracker.record_failure_at(&timeout(), start);
+        assert!(tracker.is_paused_at(start + Duration::from_secs(59)));
+        assert!(!tracker.is_paused_at(start + Duration::from_secs(60)));
+
+        tracker.record_success();
+        assert_eq!(tracker.consecutive_failures(), 0);
+        assert!(!tracker.is_paused_at(start));
+    }
+
+    #[test]
+    fn test_ignores_non_api_errors_and_zero_limit() {
+        let mut tracker = ApiFailureTracker::new(1, Duration::from_secs(60));
+        tracker.record_failure(&BotError::RiskLimit("max positions".to_string()));
+        assert!(!tracker.is_paused());
+
+        let mut disabled = ApiFailureTracker::new(0, Duration::from_secs(60));
+        disabled.record_failure(&timeout());
+        assert_eq!(disabled.consecutive_failures(), 1);
+        assert!(!disabled.is_paused());
+    }
+}
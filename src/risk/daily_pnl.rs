@@ -1,8 +1,10 @@
 //! Daily P&L Tracking with Loss Limits
 
-use chrono::Utc;
+use crate::clock::{Clock, SystemClock};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Tracks daily profit and loss with automatic reset
 #[derive(Debug, Clone)]
@@ -13,6 +15,8 @@ pub struct DailyPnlTracker {
     max_loss_pct: Decimal,
     /// Starting balance for the day (set on first trade)
     starting_balance: Option<Decimal>,
+    /// Where the day comes from
+    clock: Arc<dyn Clock>,
 }
 
 /// Serializable state for persistence
@@ -29,8 +33,13 @@ pub struct DailyPnlState {
 
 impl DailyPnlState {
     pub fn new() -> Self {
+        Self::starting(Utc::now())
+    }
+
+    /// Empty state for the UTC day of `now`
+    pub fn starting(now: DateTime<Utc>) -> Self {
         Self {
-            date: Utc::now().format("%Y-%m-%d").to_string(),
+            date: now.format("%Y-%m-%d").to_string(),
             realized_pnl: Decimal::ZERO,
             trade_count: 0,
             winning_trades: 0,
@@ -42,8 +51,12 @@ impl DailyPnlState {
 
     /// Check if this state is for the current day
     pub fn is_current_day(&self) -> bool {
-        let today = Utc::now().format("%Y-%m-%d").to_string();
-        self.date == today
+        self.is_day_of(Utc::now())
+    }
+
+    /// Check if this state is for the UTC day of `now`
+    pub fn is_day_of(&self, now: DateTime<Utc>) -> bool {
+        self.date == now.format("%Y-%m-%d").to_string()
     }
 }
 
@@ -60,9 +73,17 @@ impl DailyPnlTracker {
             state: DailyPnlState::new(),
             max_loss_pct,
             starting_balance: None,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Tell days apart by `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.state = DailyPnlState::starting(clock.now());
+        self.clock = clock;
+        self
+    }
+
     /// Set the starting balance for percentage calculations
     pub fn set_starting_balance(&mut self, balance: Decimal) {
         if self.starting_balance.is_none() {
@@ -72,8 +93,8 @@ impl DailyPnlTracker {
 
     /// Record a trade's P&L
     pub fn record_pnl(&mut self, pnl: Decimal) {
-        self.check_and_reset_day();
-        
+        self.roll_over();
+
         self.state.realized_pnl += pnl;
         self.state.trade_count += 1;
 
@@ -130,14 +151,14 @@ impl DailyPnlTracker {
 
     /// Restore from persisted state
     pub fn restore_state(&mut self, state: DailyPnlState) {
-        if state.is_current_day() {
+        if state.is_day_of(self.clock.now()) {
             self.state = state;
         }
     }
 
     /// Reset for a new day
     pub fn reset(&mut self) {
-        self.state = DailyPnlState::new();
+        self.state = DailyPnlState::starting(self.clock.now());
         self.starting_balance = None;
     }
 
@@ -149,11 +170,13 @@ impl DailyPnlTracker {
         Some(self.state.winning_trades as f64 / self.state.trade_count as f64 * 100.0)
     }
 
-    /// Check if we've crossed into a new day and reset if needed
-    fn check_and_reset_day(&mut self) {
-        if !self.state.is_current_day() {
-            self.reset();
+    /// Reset if we've crossed into a new day; returns whether we did
+    pub fn roll_over(&mut self) -> bool {
+        if self.state.is_day_of(self.clock.now()) {
+            return false;
         }
+        self.reset();
+        true
     }
 }
 
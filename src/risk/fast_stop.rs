@@ -0,0 +1,323 @@
+//! Fast stop for crypto Up/Down positions
+//!
+//! A 15-minute Up/Down market can go from winning to dead in 30 seconds,
+//! well inside one scan interval. [`FastStopMonitor`] is fed every Binance
+//! tick and re-estimates, for each watched position, the chance that the
+//! window closes on our side of its opening price: `Φ(ln(S/K) / (σ√τ))` for
+//! Up, its complement for Down. A position is exited when that estimate
+//! falls below `min_probability`, or when the underlying moves
+//! `adverse_move_bps` against it within `adverse_window_secs`.
+
+use crate::config::FastStopConfig;
+use crate::utils::normal_cdf;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Which side of the opening price a position wins on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpDown {
+    Up,
+    Down,
+}
+
+/// An open crypto Up/Down position under watch
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastStopPosition {
+    pub market_id: String,
+    /// Token held
+    pub token_id: String,
+    /// Underlying ticker (`BTC`, ...)
+    pub asset: String,
+    pub direction: UpDown,
+    /// Underlying price when the market's window opened
+    pub strike: Decimal,
+    /// When the window closes and the market resolves
+    pub expires_at: DateTime<Utc>,
+    /// Annualized realized volatility of the underlying
+    pub volatility: Decimal,
+    /// When the position was opened; earlier ticks don't count as adverse
+    /// moves against it
+    pub opened_at: DateTime<Utc>,
+}
+
+impl FastStopPosition {
+    /// Chance the window closes on this position's side given the
+    /// underlying at `price`, assuming driftless log-normal moves
+    pub fn resolution_probability(&self, price: Decimal, now: DateTime<Utc>) -> Decimal {
+        let remaining = (self.expires_at - now).num_milliseconds() as f64 / 1000.0;
+        let (Some(price), Some(strike), Some(vol)) = (price.to_f64(), self.strike.to_f64(), self.volatility.to_f64())
+        else {
+            return Decimal::ONE;
+        };
+        if price <= 0.0 || strike <= 0.0 {
+            return Decimal::ONE;
+        }
+        let spread = vol * (remaining.max(0.0) / SECONDS_PER_YEAR).sqrt();
+        let up = if spread > 0.0 {
+            normal_cdf((price / strike).ln() / spread)
+        } else if price >= strike {
+            1.0
+        } else {
+            0.0
+        };
+        let probability = match self.direction {
+            UpDown::Up => up,
+            UpDown::Down => 1.0 - up,
+        };
+        Decimal::from_f64(probability).unwrap_or(Decimal::ONE).round_dp(6)
+    }
+}
+
+/// Why a fast stop fired
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FastStopReason {
+    /// Resolution probability fell below the floor
+    ProbabilityFloor,
+    /// The underlying moved this many bps against the position within the
+    /// adverse window
+    AdverseMove { bps: Decimal },
+}
+
+/// A position to exit now
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastStopExit {
+    pub position: FastStopPosition,
+    pub reason: FastStopReason,
+    /// Resolution probability at the triggering tick
+    pub probability: Decimal,
+    /// Underlying price at the triggering tick
+    pub underlying: Decimal,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Watches crypto Up/Down positions tick by tick
+#[derive(Debug, Clone)]
+pub struct FastStopMonitor {
+    config: FastStopConfig,
+    /// Watched positions by token ID
+    positions: HashMap<String, FastStopPosition>,
+    /// Ticks within the adverse window, per asset
+    ticks: HashMap<String, VecDeque<(DateTime<Utc>, Decimal)>>,
+}
+
+impl FastStopMonitor {
+    pub fn new(config: FastStopConfig) -> Self {
+        Self {
+            config,
+            positions: HashMap::new(),
+            ticks: HashMap::new(),
+        }
+    }
+
+    /// Start watching a position, replacing any earlier one on its token
+    pub fn watch(&mut self, position: FastStopPosition) {
+        self.positions.insert(position.token_id.clone(), position);
+    }
+
+    /// Stop watching the position on `token_id`
+    pub fn unwatch(&mut self, token_id: &str) -> Option<FastStopPosition> {
+        self.positions.remove(token_id)
+    }
+
+    pub fn is_watching(&self, token_id: &str) -> bool {
+        self.positions.contains_key(token_id)
+    }
+
+    /// Feed one trade tick of `symbol` (`BTCUSDT` or `BTC`); returns the
+    /// positions to exit, which stop being watched. Positions past their
+    /// window are dropped without an exit.
+    pub fn on_tick(&mut self, symbol: &str, price: Decimal, at: DateTime<Utc>) -> Vec<FastStopExit> {
+        let asset = symbol.to_uppercase();
+        let asset = asset.strip_suffix("USDT").unwrap_or(&asset).to_string();
+        let window = Duration::seconds(self.config.adverse_window_secs as i64);
+        let ticks = self.ticks.entry(asset.clone()).or_default();
+        ticks.push_back((at, price));
+        while ticks.front().is_some_and(|(t, _)| *t < at - window) {
+            ticks.pop_front();
+        }
+
+        self.positions.retain(|_, p| p.expires_at > at);
+        let mut exits = Vec::new();
+        for position in self.positions.values().filter(|p| p.asset == asset) {
+            let probability = position.resolution_probability(price, at);
+            let reason = if probability < self.config.min_probability {
+                Some(FastStopReason::ProbabilityFloor)
+            } else {
+                adverse_move_bps(ticks, position, price)
+                    .filter(|bps| *bps >= self.config.adverse_move_bps)
+                    .map(|bps| FastStopReason::AdverseMove { bps })
+            };
+            if let Some(reason) = reason {
+                exits.push(FastStopExit {
+                    position: position.clone(),
+                    reason,
+                    probability,
+                    underlying: price,
+                    triggered_at: at,
+                });
+            }
+        }
+        for exit in &exits {
+            self.positions.remove(&exit.position.token_id);
+        }
+        exits
+    }
+}
+
+/// Largest move against `position` from a tick in the window (after it was
+/// opened) to `price`, in basis points
+fn adverse_move_bps(ticks: &VecDeque<(DateTime<Utc>, Decimal)>, position: &FastStopPosition, price: Decimal) -> Option<Decimal> {
+    let prices = ticks.iter().filter(|(t, _)| *t >= position.opened_at).map(|(_, p)| *p);
+    let reference = match position.direction {
+        UpDown::Up => prices.max()?,
+        UpDown::Down => prices.min()?,
+    };
+    if reference <= Decimal::ZERO {
+        return None;
+    }
+    let against = match position.direction {
+        UpDown::Up => reference - price,
+        UpDown::Down => price - reference,
+    };
+    Some((against / reference * Decimal::from(10_000)).max(Decimal::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn monitor() -> FastStopMonitor {
+        FastStopMonitor::new(FastStopConfig {
+            enabled: true,
+            min_probability: dec!(0.20),
+            adverse_move_bps: dec!(30),
+            adverse_window_secs: 30,
+        })
+    }
+
+    fn position(direction: UpDown, start: DateTime<Utc>) -> FastStopPosition {
+        FastStopPosition {
+            market_id: "m1".to_string(),
+            token_id: "up".to_string(),
+            asset: "BTC".to_string(),
+            direction,
+            strike: dec!(100000),
+            expires_at: start + Duration::minutes(10),
+            volatility: dec!(0.50),
+            opened_at: start,
+        }
+    }
+
+    /// Feed `(seconds after start, price)` ticks; returns the exits with the
+    /// second they fired at
+    fn replay(monitor: &mut FastStopMonitor, start: DateTime<Utc>, ticks: &[(i64, Decimal)]) -> Vec<(i64, FastStopExit)> {
+        ticks
+            .iter()
+            .flat_map(|(secs, price)| {
+                monitor
+                    .on_tick("BTCUSDT", *price, start + Duration::seconds(*secs))
+                    .into_iter()
+                    .map(move |exit| (*secs, exit))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolution_probability() {
+        let start = Utc::now();
+        let up = position(UpDown::Up, start);
+        assert_eq!(up.resolution_probability(dec!(100000), start), dec!(0.5));
+        // 0.2% above the strike with 10 minutes of 50% vol left (σ√τ ≈ 0.0022)
+        let p = up.resolution_probability(dec!(100200), start);
+        assert!(p > dec!(0.81) && p < dec!(0.83), "{}", p);
+        // Less time left makes the same distance more decisive
+        assert!(up.resolution_probability(dec!(100200), start + Duration::minutes(9)) > dec!(0.99));
+        assert_eq!(up.resolution_probability(dec!(99000), start + Duration::minutes(10)), Decimal::ZERO);
+
+        let down = position(UpDown::Down, start);
+        let q = down.resolution_probability(dec!(100200), start);
+        assert!((q + p - Decimal::ONE).abs() < dec!(0.000002), "{}", q);
+    }
+
+    #[test]
+    fn test_steady_ticks_do_not_fire() {
+        let start = Utc::now();
+        let mut monitor = monitor();
+        monitor.watch(position(UpDown::Up, start));
+
+        // Noise of a few bps around a price above the strike
+        let ticks: Vec<(i64, Decimal)> = (0..120)
+            .map(|i| (i * 2, dec!(100500) + Decimal::from((i % 5) * 10) - dec!(20)))
+            .collect();
+        assert!(replay(&mut monitor, start, &ticks).is_empty());
+        assert!(monitor.is_watching("up"));
+
+        // A 40 bps slide spread over two minutes never moves 30 bps within 30s
+        let slide: Vec<(i64, Decimal)> =
+            (0..=24).map(|i| (300 + i * 5, dec!(100500) - Decimal::from(i * 16))).collect();
+        assert!(replay(&mut monitor, start, &slide).is_empty());
+    }
+
+    #[test]
+    fn test_sharp_adverse_move_fires_once() {
+        let start = Utc::now();
+        let mut monitor = monitor();
+        monitor.watch(position(UpDown::Up, start));
+        // Other assets' ticks are ignored
+        assert!(monitor.on_tick("ETHUSDT", dec!(1), start).is_empty());
+
+        let ticks = [
+            (0, dec!(100300)),
+            (5, dec!(100320)),
+            (10, dec!(100250)),
+            (15, dec!(100100)),
+            // 35 bps below the 100320 high 15s earlier
+            (20, dec!(99969)),
+            (25, dec!(99900)),
+        ];
+        let exits = replay(&mut monitor, start, &ticks);
+        assert_eq!(exits.len(), 1);
+        let (secs, exit) = &exits[0];
+        assert_eq!(*secs, 20);
+        match exit.reason {
+            FastStopReason::AdverseMove { bps } => assert!(bps > dec!(34.9) && bps < dec!(35.1), "{}", bps),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(exit.underlying, dec!(99969));
+        assert!(!monitor.is_watching("up"));
+    }
+
+    #[test]
+    fn test_probability_floor_fires_near_expiry() {
+        let start = Utc::now();
+        let mut monitor = monitor();
+        monitor.watch(position(UpDown::Down, start));
+
+        // Drifting a few bps above the strike is harmless early, fatal late
+        let early = replay(&mut monitor, start, &[(0, dec!(100000)), (20, dec!(100040))]);
+        assert!(early.is_empty());
+        let late = replay(&mut monitor, start, &[(570, dec!(100030)), (580, dec!(100040))]);
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].1.reason, FastStopReason::ProbabilityFloor);
+        assert!(late[0].1.probability < dec!(0.20));
+    }
+
+    #[test]
+    fn test_ticks_before_entry_and_after_expiry_are_ignored() {
+        let start = Utc::now();
+        let mut monitor = monitor();
+        // The price was higher before we bought Up; that drop isn't ours
+        replay(&mut monitor, start, &[(0, dec!(100600))]);
+        monitor.watch(position(UpDown::Up, start + Duration::seconds(10)));
+        assert!(replay(&mut monitor, start, &[(10, dec!(100300)), (15, dec!(100290))]).is_empty());
+
+        // Past the window the market has resolved: no exit, no longer watched
+        assert!(replay(&mut monitor, start, &[(700, dec!(90000))]).is_empty());
+        assert!(!monitor.is_watching("up"));
+    }
+}
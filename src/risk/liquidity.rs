@@ -0,0 +1,214 @@
+//! Cost of a forced liquidation of the open positions
+//!
+//! Each position is unwound in child orders against its book, the book
+//! assumed to refill between steps. `urgency` in (0, 1] is the fraction of
+//! the position sold per step: 1 dumps it in one order, 0.25 takes four
+//! steps. Faster means larger child orders walking deeper into the book.
+//! Positions whose book is unavailable fall back to an impact estimated off
+//! their market's liquidity.
+
+use crate::orderbook::{OrderBookAnalyzer, TradeSide};
+use crate::types::{Side, Trade};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Liquidation cost, in percent of the portfolio, worth alerting about
+pub const LIQUIDATION_ALERT_PCT: Decimal = dec!(5);
+
+/// Impact never exceeds the whole price
+const MAX_IMPACT_BPS: Decimal = dec!(10000);
+
+/// Minutes between child orders
+const DEFAULT_STEP_MINS: u64 = 5;
+
+/// What unwinding the positions at once would cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidationReport {
+    pub total_slippage_usdc: Decimal,
+    /// Positions are unwound in parallel, so the slowest one sets the pace
+    pub time_to_liquidate_mins: u64,
+    /// Market losing the most to slippage; empty without positions
+    pub worst_market: String,
+    /// Slippage in percent of the positions' value
+    pub liquidation_cost_pct: Decimal,
+}
+
+/// Simulates forced liquidations of a set of positions
+#[derive(Debug, Clone)]
+pub struct LiquidityStressTester {
+    /// Market liquidity in USDC, by market ID, for positions without a book
+    market_liquidity: HashMap<String, Decimal>,
+    step_mins: u64,
+}
+
+impl Default for LiquidityStressTester {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl LiquidityStressTester {
+    pub fn new(market_liquidity: HashMap<String, Decimal>) -> Self {
+        Self {
+            market_liquidity,
+            step_mins: DEFAULT_STEP_MINS,
+        }
+    }
+
+    pub fn with_step_mins(mut self, step_mins: u64) -> Self {
+        self.step_mins = step_mins;
+        self
+    }
+
+    /// Unwind `positions` against their books (`token_id -> analyzer`,
+    /// `None` when the book could not be fetched) at `urgency`
+    pub fn simulate_liquidation(
+        &self,
+        positions: &[Trade],
+        order_books: &HashMap<String, Option<OrderBookAnalyzer>>,
+        urgency: f64,
+    ) -> LiquidationReport {
+        let urgency = Decimal::from_f64(urgency)
+            .unwrap_or(Decimal::ONE)
+            .clamp(dec!(0.01), Decimal::ONE);
+
+        let mut slippage_by_market: HashMap<&str, Decimal> = HashMap::new();
+        let mut value = Decimal::ZERO;
+        let mut steps = 0u64;
+        for position in positions.iter().filter(|p| p.size > Decimal::ZERO) {
+            let child_size = position.size * urgency;
+            let position_steps = (position.size / child_size).ceil().to_u64().unwrap_or(1);
+            // Positions are closed by trading against them
+            let side = match position.side {
+                Side::Buy => TradeSide::Sell,
+                Side::Sell => TradeSide::Buy,
+            };
+            let impact_bps = order_books
+                .get(&position.token_id)
+                .and_then(|book| book.as_ref()?.estimate_price_impact(side, child_size))
+                .unwrap_or_else(|| self.liquidity_based_impact(&position.market_id, child_size * position.price))
+                .clamp(Decimal::ZERO, MAX_IMPACT_BPS);
+
+            let notional = position.size * position.price;
+            value += notional;
+            steps = steps.max(position_steps);
+            *slippage_by_market.entry(&position.market_id).or_default() += notional * impact_bps / MAX_IMPACT_BPS;
+        }
+
+        let total_slippage_usdc: Decimal = slippage_by_market.values().sum();
+        let worst_market = slippage_by_market
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(market, _)| market.to_string())
+            .unwrap_or_default();
+        LiquidationReport {
+            total_slippage_usdc,
+            time_to_liquidate_mins: steps * self.step_mins,
+            worst_market,
+            liquidation_cost_pct: if value.is_zero() {
+                Decimal::ZERO
+            } else {
+                total_slippage_usdc / value * Decimal::ONE_HUNDRED
+            },
+        }
+    }
+
+    /// Impact in bps of an order of `notional` USDC on a market without a
+    /// book: its share of the market's liquidity, times 100. Unknown
+    /// liquidity is taken as no liquidity at all.
+    fn liquidity_based_impact(&self, market_id: &str, notional: Decimal) -> Decimal {
+        match self.market_liquidity.get(market_id) {
+            Some(&liquidity) if liquidity > Decimal::ZERO => notional / liquidity * Decimal::ONE_HUNDRED,
+            _ => MAX_IMPACT_BPS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{BookLevel, OrderBookSnapshot};
+    use crate::types::TradeStatus;
+
+    fn position(market_id: &str, token_id: &str, size: Decimal, price: Decimal) -> Trade {
+        Trade {
+            id: format!("t-{}", token_id),
+            order_id: format!("o-{}", token_id),
+            token_id: token_id.to_string(),
+            market_id: market_id.to_string(),
+            side: Side::Buy,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            timestamp: chrono::Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        }
+    }
+
+    /// 100 shares bid at 0.50, 100 at 0.45, 100 at 0.40
+    fn book() -> OrderBookAnalyzer {
+        let mut analyzer = OrderBookAnalyzer::new();
+        analyzer.process_snapshot(OrderBookSnapshot {
+            timestamp_ms: 1000,
+            bids: [dec!(0.50), dec!(0.45), dec!(0.40)]
+                .map(|price| BookLevel { price, quantity: dec!(100) })
+                .to_vec(),
+            asks: vec![BookLevel { price: dec!(0.52), quantity: dec!(300) }],
+            last_trade_price: None,
+            last_trade_side: None,
+        });
+        analyzer
+    }
+
+    #[test]
+    fn test_urgency_trades_time_for_slippage() {
+        let tester = LiquidityStressTester::default();
+        let positions = [position("m1", "yes", dec!(200), dec!(0.50))];
+        let books = HashMap::from([("yes".to_string(), Some(book()))]);
+
+        // All 200 at once: average 0.475, 500bps below the best bid
+        let dump = tester.simulate_liquidation(&positions, &books, 1.0);
+        assert_eq!(dump.total_slippage_usdc, dec!(5));
+        assert_eq!(dump.liquidation_cost_pct, dec!(5));
+        assert_eq!(dump.time_to_liquidate_mins, 5);
+        assert_eq!(dump.worst_market, "m1");
+
+        // Two children of 100 each fill at the best bid
+        let patient = tester.simulate_liquidation(&positions, &books, 0.5);
+        assert_eq!(patient.total_slippage_usdc, Decimal::ZERO);
+        assert_eq!(patient.time_to_liquidate_mins, 10);
+    }
+
+    #[test]
+    fn test_missing_books_fall_back_to_market_liquidity() {
+        let tester = LiquidityStressTester::new(HashMap::from([("thin".to_string(), dec!(1000))]));
+        let positions = [
+            position("deep", "yes", dec!(100), dec!(0.50)),
+            position("thin", "no", dec!(400), dec!(0.50)),
+            position("unknown", "other", dec!(10), dec!(0.50)),
+        ];
+        let books = HashMap::from([("yes".to_string(), Some(book())), ("no".to_string(), None)]);
+
+        let report = tester.simulate_liquidation(&positions, &books, 1.0);
+        // $200 of a $1000 market moves it 20bps: $0.40; the unknown market
+        // loses everything: $5
+        assert_eq!(report.total_slippage_usdc, dec!(5.4));
+        assert_eq!(report.worst_market, "unknown");
+        assert_eq!(report.liquidation_cost_pct.round_dp(2), dec!(2.12));
+
+        let empty = tester.simulate_liquidation(&[], &books, 1.0);
+        assert_eq!(empty, LiquidationReport {
+            total_slippage_usdc: Decimal::ZERO,
+            time_to_liquidate_mins: 0,
+            worst_market: String::new(),
+            liquidation_cost_pct: Decimal::ZERO,
+        });
+    }
+}
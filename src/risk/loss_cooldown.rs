@@ -0,0 +1,143 @@
+//! Post-loss cooldown per market
+//!
+//! Right after a losing close the model often still sees the same edge, and
+//! re-entering on that noise compounds the loss. A realized loss on a market
+//! blocks new entries there for `duration`, unless a signal's edge reaches
+//! `override_edge`. Wins never start a cooldown, and other markets are
+//! unaffected.
+
+use crate::clock::{Clock, SystemClock};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Markets in cooldown after a realized loss, keyed by market ID
+#[derive(Debug, Clone)]
+pub struct LossCooldown {
+    duration: Duration,
+    override_edge: Option<Decimal>,
+    until: HashMap<String, Instant>,
+    clock: Arc<dyn Clock>,
+}
+
+impl LossCooldown {
+    /// A zero `duration` never blocks
+    pub fn new(duration: Duration, override_edge: Option<Decimal>) -> Self {
+        Self {
+            duration,
+            override_edge,
+            until: HashMap::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Time cooldowns by `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Record the realized P&L of a close on `market_id`; only losses count
+    pub fn record_close(&mut self, market_id: &str, pnl: Decimal) {
+        self.record_close_at(market_id, pnl, self.clock.now_instant());
+    }
+
+    fn record_close_at(&mut self, market_id: &str, pnl: Decimal, now: Instant) {
+        if pnl >= Decimal::ZERO || self.duration.is_zero() {
+            return;
+        }
+        self.until.insert(market_id.to_string(), now + self.duration);
+    }
+
+    /// Whether an entry on `market_id` with `edge` is blocked by a cooldown
+    pub fn blocks(&self, market_id: &str, edge: Decimal) -> bool {
+        self.blocks_at(market_id, edge, self.clock.now_instant())
+    }
+
+    fn blocks_at(&self, market_id: &str, edge: Decimal, now: Instant) -> bool {
+        if self.override_edge.is_some_and(|threshold| edge.abs() >= threshold) {
+            return false;
+        }
+        self.remaining_at(market_id, now).is_some()
+    }
+
+    /// Time left on the cooldown of `market_id`, if any
+    pub fn remaining(&self, market_id: &str) -> Option<Duration> {
+        self.remaining_at(market_id, self.clock.now_instant())
+    }
+
+    fn remaining_at(&self, market_id: &str, now: Instant) -> Option<Duration> {
+        self.until
+            .get(market_id)
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Forget expired cooldowns
+    pub fn prune(&mut self) {
+        let now = self.clock.now_instant();
+        self.until.retain(|_, until| *until > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn cooldown() -> LossCooldown {
+        LossCooldown::new(Duration::from_secs(30 * 60), None)
+    }
+
+    #[test]
+    fn test_loss_blocks_reentry_until_expiry() {
+        let mut cooldown = cooldown();
+        let start = Instant::now();
+
+        cooldown.record_close_at("m1", dec!(-12.5), start);
+        assert!(cooldown.blocks_at("m1", dec!(0.10), start));
+        assert!(cooldown.blocks_at("m1", dec!(0.10), start + Duration::from_secs(29 * 60)));
+        assert!(!cooldown.blocks_at("m1", dec!(0.10), start + Duration::from_secs(30 * 60)));
+    }
+
+    #[test]
+    fn test_wins_and_other_markets_are_unaffected() {
+        let mut cooldown = cooldown();
+        let start = Instant::now();
+
+        cooldown.record_close_at("m1", dec!(-1), start);
+        cooldown.record_close_at("m2", dec!(8), start);
+        assert!(!cooldown.blocks_at("m2", dec!(0.10), start));
+        assert!(!cooldown.blocks_at("m3", dec!(0.10), start));
+
+        let mut disabled = LossCooldown::new(Duration::ZERO, None);
+        disabled.record_close_at("m1", dec!(-1), start);
+        assert!(!disabled.blocks_at("m1", dec!(0.10), start));
+    }
+
+    #[test]
+    fn test_strong_edge_overrides_cooldown() {
+        let mut cooldown = LossCooldown::new(Duration::from_secs(600), Some(dec!(0.15)));
+        let start = Instant::now();
+
+        cooldown.record_close_at("m1", dec!(-3), start);
+        assert!(cooldown.blocks_at("m1", dec!(0.10), start));
+        assert!(!cooldown.blocks_at("m1", dec!(0.15), start));
+        assert!(!cooldown.blocks_at("m1", dec!(-0.20), start));
+    }
+
+    #[test]
+    fn test_cooldown_follows_injected_clock() {
+        let clock = Arc::new(crate::clock::ManualClock::new(chrono::Utc::now()));
+        let mut cooldown = cooldown().with_clock(clock.clone());
+
+        cooldown.record_close("m1", dec!(-5));
+        clock.advance(29 * 60 * 1000);
+        assert_eq!(cooldown.remaining("m1"), Some(Duration::from_secs(60)));
+        clock.advance(60 * 1000);
+        assert!(!cooldown.blocks("m1", dec!(0.10)));
+        cooldown.prune();
+        assert!(cooldown.until.is_empty());
+    }
+}
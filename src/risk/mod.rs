@@ -9,6 +9,12 @@
 //! - Liquidity monitoring
 //! - Enhanced correlation risk analysis
 //! - Trailing stop loss with profit locking
+//! - Per-market cooldown after a realized loss
+//! - Tick-driven fast stop for crypto Up/Down positions
+//! - Portfolio stress tests under historical crisis scenarios
+//! - Regime cap on open positions, with optional rotation out of the weakest
+//! - Cost of a forced liquidation of the open positions
+//! - Size ramp-up for new strategies and market categories
 
 mod daily_pnl;
 mod volatility_sizer;
@@ -18,6 +24,13 @@ mod black_swan;
 mod liquidity_monitor;
 mod correlation_risk;
 mod trailing_stop;
+mod api_failures;
+mod position_queue;
+mod loss_cooldown;
+mod fast_stop;
+mod ramp;
+pub mod stress;
+pub mod liquidity;
 
 #[cfg(test)]
 mod tests;
@@ -43,9 +56,22 @@ pub use trailing_stop::{
     TrailingStopManager, TrailingStopConfig, TrailingStopState,
     TrailingStopAction, TrailingStopSummary, TrailingMode, ExitReason
 };
-
+pub use api_failures::ApiFailureTracker;
+pub use position_queue::{OpenPositionCounts, PositionLimits, PositionQueue, QueuedSignal};
+pub use loss_cooldown::LossCooldown;
+pub use ramp::{RampPolicy, RampReason, RampState, RampUpdate};
+pub use fast_stop::{FastStopExit, FastStopMonitor, FastStopPosition, FastStopReason, UpDown};
+pub use liquidity::{LiquidationReport, LiquidityStressTester, LIQUIDATION_ALERT_PCT};
+pub use stress::{BuiltinScenario, PortfolioStressTester, StressPortfolio, StressResult, StressScenario};
+
+use crate::clock::Clock;
 use crate::config::RiskConfig;
-use crate::types::{Market, Position, Signal};
+use crate::error::BotError;
+use crate::regime::{MarketRegime, RegimeConsensus};
+use crate::storage::DEFAULT_STRATEGY_NAME;
+use crate::types::{Market, Position, Signal, Trade};
+use std::collections::HashMap;
+use std::sync::Arc;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
@@ -60,6 +86,14 @@ pub struct RiskManager {
     pub liquidity_monitor: LiquidityMonitor,
     pub correlation_risk: CorrelationRiskManager,
     pub trailing_stop: TrailingStopManager,
+    pub api_failures: ApiFailureTracker,
+    pub loss_cooldown: LossCooldown,
+    /// Latest detected regime, whose `max_positions` caps open positions
+    regime: Option<MarketRegime>,
+    /// Latest consensus of the regime across timeframes
+    regime_consensus: Option<RegimeConsensus>,
+    /// Size ramp of new strategies and market categories, if enabled
+    ramp: Option<RampPolicy>,
 }
 
 impl RiskManager {
@@ -80,10 +114,125 @@ impl RiskManager {
             liquidity_monitor: LiquidityMonitor::new(liquidity_config),
             correlation_risk: CorrelationRiskManager::new(correlation_risk_config),
             trailing_stop: TrailingStopManager::new(trailing_stop_config),
+            api_failures: ApiFailureTracker::new(
+                config.max_consecutive_api_failures,
+                std::time::Duration::from_secs(config.api_failure_pause_secs),
+            ),
+            loss_cooldown: LossCooldown::new(
+                std::time::Duration::from_secs(config.loss_cooldown_mins * 60),
+                config.loss_cooldown_override_edge,
+            ),
+            regime: None,
+            regime_consensus: None,
+            ramp: None,
             config,
         }
     }
 
+    /// Time the daily P&L rollover and loss cooldowns by `clock` instead of
+    /// the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.pnl_tracker = self.pnl_tracker.with_clock(clock.clone());
+        self.loss_cooldown = self.loss_cooldown.with_clock(clock);
+        self
+    }
+
+    /// Follow `regime`'s cap on open positions
+    pub fn set_regime(&mut self, regime: MarketRegime) {
+        self.regime = Some(regime);
+    }
+
+    /// Follow the multi-timeframe consensus in sizing; `None` until every
+    /// timeframe has a regime
+    pub fn set_regime_consensus(&mut self, consensus: Option<RegimeConsensus>) {
+        self.regime_consensus = consensus;
+    }
+
+    /// Position size multiplier of the detected regime times that of the
+    /// multi-timeframe consensus; 1 for whichever is unknown
+    pub fn regime_size_multiplier(&self) -> Decimal {
+        let regime = self
+            .regime
+            .map_or(Decimal::ONE, |r| r.strategy_recommendation().position_size_multiplier);
+        let consensus = self
+            .regime_consensus
+            .as_ref()
+            .map_or(Decimal::ONE, |c| c.size_multiplier_with(&self.config.regime_consensus_sizing));
+        regime * consensus
+    }
+
+    /// Ramp position sizes of new strategies and market categories up
+    /// under `ramp`
+    pub fn set_ramp(&mut self, ramp: RampPolicy) {
+        self.ramp = Some(ramp);
+    }
+
+    pub fn ramp(&self) -> Option<&RampPolicy> {
+        self.ramp.as_ref()
+    }
+
+    /// Size multiplier of `signal`'s strategy and `market`'s category ramp;
+    /// 1 without a ramp
+    pub fn ramp_multiplier(&self, signal: &Signal, market: &Market) -> Decimal {
+        self.ramp.as_ref().map_or(Decimal::ONE, |ramp| {
+            ramp.multiplier(
+                signal.strategy_name.as_deref().unwrap_or(DEFAULT_STRATEGY_NAME),
+                market.market_category().name(),
+            )
+        })
+    }
+
+    /// Count a close by `strategy` in `category` towards their ramps;
+    /// returns the ramps' new states, nothing without a ramp
+    pub fn record_ramp_close(&mut self, strategy: &str, category: &str, pnl: Decimal) -> Vec<RampUpdate> {
+        self.ramp
+            .as_mut()
+            .map(|ramp| ramp.record_close(strategy, category, pnl))
+            .unwrap_or_default()
+    }
+
+    /// Open positions allowed in the current regime, if one is known
+    pub fn regime_max_positions(&self) -> Option<usize> {
+        self.regime.map(|r| r.strategy_recommendation().max_positions)
+    }
+
+    /// Whether `signal` may open a position next to `open` (open positions
+    /// as trades carrying their entry edge) under the regime's cap. Adding
+    /// to a held token takes no new slot; a token held on several accounts
+    /// is one position. At the cap, with `regime_rotation_min_edge_gain`
+    /// set, a signal whose edge beats the weakest position's by that much
+    /// rotates it out.
+    pub fn check_position_limit(&self, signal: &Signal, open: &[Trade]) -> PositionLimitCheck {
+        let (Some(regime), Some(max)) = (self.regime, self.regime_max_positions()) else {
+            return PositionLimitCheck::Allowed;
+        };
+        // Weakest entry edge per held token
+        let mut held: HashMap<&str, (&Trade, Decimal)> = HashMap::new();
+        for trade in open {
+            let edge = trade.edge.unwrap_or_default().abs();
+            held.entry(&trade.token_id)
+                .and_modify(|weakest| if edge < weakest.1 { *weakest = (trade, edge) })
+                .or_insert((trade, edge));
+        }
+        if held.contains_key(signal.token_id.as_str()) || held.len() < max {
+            return PositionLimitCheck::Allowed;
+        }
+
+        let weakest = held.values().min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.token_id.cmp(&b.0.token_id)));
+        if let (Some(gain), Some((trade, edge))) = (self.config.regime_rotation_min_edge_gain, weakest) {
+            if signal.edge.abs() - edge >= gain {
+                return PositionLimitCheck::Rotate {
+                    market_id: trade.market_id.clone(),
+                    token_id: trade.token_id.clone(),
+                    edge: *edge,
+                };
+            }
+        }
+        PositionLimitCheck::Blocked {
+            reason: format!("{:?} regime allows at most {} open positions", regime, max),
+        }
+    }
+
     /// Check if trading is allowed based on all risk constraints
     pub fn can_trade(&self) -> RiskCheckResult {
         // Check daily loss limit
@@ -100,6 +249,16 @@ impl RiskManager {
             };
         }
 
+        // Check for an ongoing API outage
+        if self.api_failures.is_paused() {
+            return RiskCheckResult::Blocked {
+                reason: format!(
+                    "{} consecutive API failures",
+                    self.api_failures.consecutive_failures()
+                ),
+            };
+        }
+
         RiskCheckResult::Allowed
     }
 
@@ -126,6 +285,11 @@ impl RiskManager {
             return None;
         }
 
+        // No re-entry right after a loss unless the edge is strong enough
+        if self.in_loss_cooldown(signal) {
+            return None;
+        }
+
         // Check liquidity
         if !self.liquidity_monitor.is_tradeable(&market.id) {
             return None;
@@ -155,6 +319,12 @@ impl RiskManager {
         let liquidity_multiplier = self.liquidity_monitor.get_size_multiplier(&market.id);
         let size_after_liq = size_after_bs * liquidity_multiplier;
 
+        // Apply the regime's and the timeframes' consensus multipliers
+        let size_after_regime = size_after_liq * self.regime_size_multiplier();
+
+        // Apply the strategy's and market category's ramp-up
+        let size_after_ramp = size_after_regime * self.ramp_multiplier(signal, market);
+
         // Check correlation - reduce size if highly correlated with existing positions
         let position_infos: Vec<PositionInfo> = current_positions
             .iter()
@@ -171,7 +341,7 @@ impl RiskManager {
         
         let correlation_multiplier = self.correlation_risk
             .get_size_multiplier(&market.id, &position_infos);
-        let final_size = size_after_liq * correlation_multiplier;
+        let final_size = size_after_ramp * correlation_multiplier;
 
         // Ensure minimum viable size
         if final_size < Decimal::ONE {
@@ -186,6 +356,28 @@ impl RiskManager {
         self.pnl_tracker.record_pnl(pnl);
     }
 
+    /// Record the realized P&L of closing a position on `market_id`; a loss
+    /// starts that market's cooldown
+    pub fn record_close(&mut self, market_id: &str, pnl: Decimal) {
+        self.loss_cooldown.record_close(market_id, pnl);
+        self.loss_cooldown.prune();
+    }
+
+    /// Whether `signal` would re-enter a market still cooling down from a loss
+    pub fn in_loss_cooldown(&self, signal: &Signal) -> bool {
+        self.loss_cooldown.blocks(&signal.market_id, signal.edge)
+    }
+
+    /// Record a successful exchange call, ending any API failure run
+    pub fn record_api_success(&mut self) {
+        self.api_failures.record_success();
+    }
+
+    /// Record a failed call; only outage-type errors count towards the pause
+    pub fn record_api_failure(&mut self, error: &BotError) {
+        self.api_failures.record_failure(error);
+    }
+
     /// Update volatility data for a market
     pub fn update_volatility(&mut self, market_id: &str, price: Decimal) {
         self.volatility_sizer.add_price_point(market_id, price);
@@ -305,6 +497,16 @@ pub enum RiskCheckResult {
     Blocked { reason: String },
 }
 
+/// Whether a new entry fits under the regime's open position cap
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionLimitCheck {
+    Allowed,
+    /// At the cap, but the signal beats this open position (entered at
+    /// `edge`), which should be closed to make room
+    Rotate { market_id: String, token_id: String, edge: Decimal },
+    Blocked { reason: String },
+}
+
 /// Comprehensive risk state
 #[derive(Debug, Clone)]
 pub struct RiskState {
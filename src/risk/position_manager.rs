@@ -243,6 +243,18 @@ mod tests {
             max_daily_loss_pct: dec!(0.10),     // 10%
             min_balance_reserve: dec!(100),     // $100
             max_open_positions: 10,
+            signal_dedup_ttl_secs: 900,
+            max_total_kelly: Decimal::ONE,
+            max_consecutive_api_failures: 5,
+            api_failure_pause_secs: 300,
+            max_positions_per_strategy: std::collections::HashMap::new(),
+            queued_signal_ttl_secs: 1800,
+            max_queued_signals: 20,
+            loss_cooldown_mins: 30,
+            loss_cooldown_override_edge: None,
+            regime_rotation_min_edge_gain: None,
+            max_heat_budget: Decimal::ZERO,
+            regime_consensus_sizing: Default::default(),
         }
     }
 
@@ -0,0 +1,424 @@
+//! Concurrent position limits with a queue for held-back signals
+//!
+//! A volatile scan can produce more signals than there are free position
+//! slots. Slots are limited globally (`max_open_positions`, tightened by the
+//! regime's `max_positions`) and per strategy, and shrink with the drawdown
+//! tier. Signals that find no slot wait in a [`PositionQueue`] ranked by net
+//! edge; when slots free up the best one still worth trading at current
+//! prices is released, as long as it is younger than the freshness window.
+
+use crate::config::RiskConfig;
+use crate::regime::RegimeStrategy;
+use crate::storage::{OpenPosition, DEFAULT_STRATEGY_NAME};
+use crate::strategy::DrawdownTier;
+use crate::types::{Side, Signal};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Strategy a signal or position counts against
+fn strategy_of(name: Option<&str>) -> &str {
+    name.unwrap_or(DEFAULT_STRATEGY_NAME)
+}
+
+/// Tokens currently held, overall and per strategy
+#[derive(Debug, Clone, Default)]
+pub struct OpenPositionCounts {
+    tokens: HashSet<String>,
+    by_strategy: HashMap<String, HashSet<String>>,
+}
+
+impl OpenPositionCounts {
+    /// Count positions across accounts; a token held on several accounts
+    /// is one position
+    pub fn from_positions(positions: &[OpenPosition]) -> Self {
+        let mut counts = Self::default();
+        for p in positions {
+            counts.add(strategy_of(p.strategy_name.as_deref()), &p.token_id);
+        }
+        counts
+    }
+
+    /// Record a position opened by `strategy`
+    pub fn add(&mut self, strategy: &str, token_id: &str) {
+        self.tokens.insert(token_id.to_string());
+        self.by_strategy
+            .entry(strategy.to_string())
+            .or_default()
+            .insert(token_id.to_string());
+    }
+
+    pub fn total(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn for_strategy(&self, strategy: &str) -> usize {
+        self.by_strategy.get(strategy).map_or(0, HashSet::len)
+    }
+
+    pub fn holds(&self, token_id: &str) -> bool {
+        self.tokens.contains(token_id)
+    }
+}
+
+/// Maximum concurrent positions, overall and per strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionLimits {
+    pub global: usize,
+    pub per_strategy: HashMap<String, usize>,
+}
+
+impl PositionLimits {
+    /// Limits from the risk config, tightened by the regime's position cap
+    /// when a regime is known
+    pub fn new(config: &RiskConfig, regime: Option<&RegimeStrategy>) -> Self {
+        let global = regime.map_or(config.max_open_positions, |r| r.max_positions.min(config.max_open_positions));
+        Self {
+            global,
+            per_strategy: config.max_positions_per_strategy.clone(),
+        }
+    }
+
+    /// Scale every limit for the drawdown tier: half the slots when
+    /// `Reduced`, a quarter at the `Floor`, keeping at least one wherever a
+    /// limit allowed any
+    pub fn for_drawdown(&self, tier: DrawdownTier) -> Self {
+        let scale = |limit: usize| match tier {
+            DrawdownTier::Normal => limit,
+            DrawdownTier::Reduced => (limit / 2).max(limit.min(1)),
+            DrawdownTier::Floor => (limit / 4).max(limit.min(1)),
+        };
+        Self {
+            global: scale(self.global),
+            per_strategy: self.per_strategy.iter().map(|(name, limit)| (name.clone(), scale(*limit))).collect(),
+        }
+    }
+
+    /// Whether `signal` may trade now. Adding to a token already held takes
+    /// no new slot.
+    pub fn has_slot(&self, open: &OpenPositionCounts, signal: &Signal) -> bool {
+        if open.holds(&signal.token_id) {
+            return true;
+        }
+        let strategy = strategy_of(signal.strategy_name.as_deref());
+        open.total() < self.global
+            && self
+                .per_strategy
+                .get(strategy)
+                .is_none_or(|limit| open.for_strategy(strategy) < *limit)
+    }
+}
+
+/// A signal waiting for a free position slot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedSignal {
+    pub signal: Signal,
+    pub question: String,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QueuedSignal {
+    /// Edge left after discounting the models' disagreement on it
+    pub fn net_edge(&self) -> Decimal {
+        net_edge(&self.signal)
+    }
+
+    pub fn is_fresh(&self, now: DateTime<Utc>, ttl: Duration) -> bool {
+        now - self.queued_at < ttl
+    }
+}
+
+fn net_edge(signal: &Signal) -> Decimal {
+    signal.edge.abs() - signal.epistemic_uncertainty
+}
+
+/// Signals held back by position limits, best net edge first, at most one
+/// per market
+#[derive(Debug, Clone)]
+pub struct PositionQueue {
+    entries: Vec<QueuedSignal>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl PositionQueue {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            ttl,
+            capacity,
+        }
+    }
+
+    pub fn from_config(config: &RiskConfig) -> Self {
+        Self::new(
+            Duration::seconds(config.queued_signal_ttl_secs as i64),
+            config.max_queued_signals,
+        )
+    }
+
+    /// Queue a signal, replacing any older one for the same market. Returns
+    /// false when the queue is full of better signals.
+    pub fn push(&mut self, signal: Signal, question: &str, now: DateTime<Utc>) -> bool {
+        self.entries.retain(|q| q.signal.market_id != signal.market_id);
+        let market_id = signal.market_id.clone();
+        self.insert(QueuedSignal {
+            signal,
+            question: question.to_string(),
+            queued_at: now,
+        });
+        self.entries.truncate(self.capacity);
+        self.entries.iter().any(|q| q.signal.market_id == market_id)
+    }
+
+    /// Put back entries loaded from storage, dropping duplicates and
+    /// anything over capacity
+    pub fn restore(&mut self, entries: Vec<QueuedSignal>) {
+        for entry in entries {
+            if self.entries.iter().all(|q| q.signal.market_id != entry.signal.market_id) {
+                self.insert(entry);
+            }
+        }
+        self.entries.truncate(self.capacity);
+    }
+
+    fn insert(&mut self, entry: QueuedSignal) {
+        // Ties keep the earlier signal first
+        let at = self.entries.partition_point(|q| q.net_edge() >= entry.net_edge());
+        self.entries.insert(at, entry);
+    }
+
+    /// Drop signals older than the freshness window, returning them
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<QueuedSignal> {
+        let (fresh, stale) = self.entries.drain(..).partition(|q| q.is_fresh(now, self.ttl));
+        self.entries = fresh;
+        stale
+    }
+
+    /// Take the best fresh signal that has a slot under `limits` and still
+    /// clears `min_edge` at the price `current_price` reports for its token.
+    /// Signals whose edge is gone are dropped; those without a current
+    /// price, or without a slot for their strategy, stay queued.
+    pub fn pop_ready(
+        &mut self,
+        now: DateTime<Utc>,
+        limits: &PositionLimits,
+        open: &OpenPositionCounts,
+        min_edge: Decimal,
+        current_price: impl Fn(&Signal) -> Option<Decimal>,
+    ) -> Option<QueuedSignal> {
+        self.expire(now);
+        let mut i = 0;
+        while i < self.entries.len() {
+            let queued = &self.entries[i];
+            if !limits.has_slot(open, &queued.signal) {
+                i += 1;
+                continue;
+            }
+            let Some(price) = current_price(&queued.signal) else {
+                i += 1;
+                continue;
+            };
+            let mut entry = self.entries.remove(i);
+            if reprice(&mut entry.signal, price, min_edge) {
+                return Some(entry);
+            }
+            tracing::debug!("Dropping queued signal for {}: edge gone at {}", entry.signal.market_id, price);
+        }
+        None
+    }
+
+    /// Queued signals, best first
+    pub fn entries(&self) -> &[QueuedSignal] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+/// Re-derive the signal's edge at `price`; false if it no longer points the
+/// same way or falls below `min_edge` net of model disagreement
+fn reprice(signal: &mut Signal, price: Decimal, min_edge: Decimal) -> bool {
+    let edge = signal.model_probability - price;
+    let same_side = match signal.side {
+        Side::Buy => edge > Decimal::ZERO,
+        Side::Sell => edge < Decimal::ZERO,
+    };
+    signal.market_probability = price;
+    signal.edge = edge;
+    same_side && net_edge(signal) >= min_edge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn signal(market_id: &str, strategy: &str, model: Decimal, market: Decimal) -> Signal {
+        let edge = model - market;
+        Signal {
+            market_id: market_id.to_string(),
+            token_id: format!("{}-yes", market_id),
+            side: if edge > Decimal::ZERO { Side::Buy } else { Side::Sell },
+            model_probability: model,
+            market_probability: market,
+            edge,
+            confidence: dec!(0.8),
+            suggested_size: dec!(0.02),
+            timestamp: Utc::now(),
+            strategy_name: Some(strategy.to_string()),
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    fn limits(global: usize, per_strategy: &[(&str, usize)]) -> PositionLimits {
+        PositionLimits {
+            global,
+            per_strategy: per_strategy.iter().map(|(n, l)| (n.to_string(), *l)).collect(),
+        }
+    }
+
+    fn open(positions: &[(&str, &str)]) -> OpenPositionCounts {
+        let mut counts = OpenPositionCounts::default();
+        for (strategy, token) in positions {
+            counts.add(strategy, token);
+        }
+        counts
+    }
+
+    #[test]
+    fn test_queue_ranks_by_net_edge() {
+        let now = Utc::now();
+        let mut queue = PositionQueue::new(Duration::minutes(30), 3);
+
+        let mut uncertain = signal("a", "llm", dec!(0.70), dec!(0.50));
+        uncertain.epistemic_uncertainty = dec!(0.12);
+        assert!(queue.push(uncertain, "A?", now));
+        assert!(queue.push(signal("b", "llm", dec!(0.60), dec!(0.50)), "B?", now));
+        assert!(queue.push(signal("c", "llm", dec!(0.20), dec!(0.35)), "C?", now));
+
+        // Net edges: a 0.08, b 0.10, c 0.15
+        let order: Vec<_> = queue.entries().iter().map(|q| q.signal.market_id.as_str()).collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+
+        // Full: a weaker signal is refused, a stronger one evicts the weakest
+        assert!(!queue.push(signal("d", "llm", dec!(0.56), dec!(0.50)), "D?", now));
+        assert!(queue.push(signal("e", "llm", dec!(0.80), dec!(0.50)), "E?", now));
+        let order: Vec<_> = queue.entries().iter().map(|q| q.signal.market_id.as_str()).collect();
+        assert_eq!(order, vec!["e", "c", "b"]);
+
+        // A newer signal for a queued market replaces it
+        assert!(queue.push(signal("b", "llm", dec!(0.90), dec!(0.50)), "B?", now));
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.entries()[0].signal.market_id, "b");
+
+        let price = |s: &Signal| Some(s.market_probability);
+        let none_open = OpenPositionCounts::default();
+        let released = queue.pop_ready(now, &limits(5, &[]), &none_open, dec!(0.05), price).unwrap();
+        assert_eq!(released.signal.market_id, "b");
+    }
+
+    #[test]
+    fn test_stale_and_repriced_signals_are_dropped() {
+        let now = Utc::now();
+        let mut queue = PositionQueue::new(Duration::minutes(30), 10);
+        queue.push(signal("old", "llm", dec!(0.90), dec!(0.50)), "Old?", now - Duration::minutes(31));
+        queue.push(signal("moved", "llm", dec!(0.70), dec!(0.50)), "Moved?", now);
+        queue.push(signal("fine", "llm", dec!(0.60), dec!(0.50)), "Fine?", now - Duration::minutes(29));
+        queue.push(signal("unpriced", "llm", dec!(0.65), dec!(0.50)), "Unpriced?", now);
+
+        // "moved" has rallied to the model's price; "unpriced" isn't in this scan
+        let price = |s: &Signal| match s.market_id.as_str() {
+            "moved" => Some(dec!(0.68)),
+            "fine" => Some(dec!(0.52)),
+            "old" => Some(dec!(0.50)),
+            _ => None,
+        };
+        let none_open = OpenPositionCounts::default();
+        let released = queue.pop_ready(now, &limits(5, &[]), &none_open, dec!(0.05), price).unwrap();
+        assert_eq!(released.signal.market_id, "fine");
+        assert_eq!(released.signal.market_probability, dec!(0.52));
+        assert_eq!(released.signal.edge, dec!(0.08));
+
+        assert!(queue.pop_ready(now, &limits(5, &[]), &none_open, dec!(0.05), price).is_none());
+        let left: Vec<_> = queue.entries().iter().map(|q| q.signal.market_id.as_str()).collect();
+        assert_eq!(left, vec!["unpriced"]);
+        assert_eq!(queue.expire(now + Duration::minutes(30)).len(), 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_global_and_strategy_limits() {
+        let limits = limits(3, &[("crypto_hf", 1)]);
+        let held = open(&[("llm", "x-yes"), ("crypto_hf", "y-yes")]);
+
+        assert!(limits.has_slot(&held, &signal("a", "llm", dec!(0.7), dec!(0.5))));
+        assert!(!limits.has_slot(&held, &signal("b", "crypto_hf", dec!(0.7), dec!(0.5))));
+        // Adding to a held token needs no slot
+        assert!(limits.has_slot(&held, &signal("y", "crypto_hf", dec!(0.7), dec!(0.5))));
+
+        let full = open(&[("llm", "x-yes"), ("llm", "z-yes"), ("crypto_hf", "y-yes")]);
+        assert!(!limits.has_slot(&full, &signal("a", "llm", dec!(0.7), dec!(0.5))));
+
+        // A queued crypto signal waits while an llm one behind it trades
+        let now = Utc::now();
+        let mut queue = PositionQueue::new(Duration::minutes(30), 10);
+        queue.push(signal("b", "crypto_hf", dec!(0.9), dec!(0.5)), "B?", now);
+        queue.push(signal("a", "llm", dec!(0.7), dec!(0.5)), "A?", now);
+        let price = |s: &Signal| Some(s.market_probability);
+        let released = queue.pop_ready(now, &limits, &held, dec!(0.05), price).unwrap();
+        assert_eq!(released.signal.market_id, "a");
+        assert_eq!(queue.entries()[0].signal.market_id, "b");
+    }
+
+    #[test]
+    fn test_drawdown_tiers_shrink_slots() {
+        let config = RiskConfig {
+            max_open_positions: 10,
+            max_positions_per_strategy: [("llm".to_string(), 4)].into(),
+            ..RiskConfig::default()
+        };
+        let regime = crate::regime::MarketRegime::Volatile.strategy_recommendation();
+        let normal = PositionLimits::new(&config, Some(&regime));
+        assert_eq!(normal.global, 5);
+        assert_eq!(PositionLimits::new(&config, None).global, 10);
+
+        let reduced = normal.for_drawdown(DrawdownTier::Reduced);
+        assert_eq!((reduced.global, reduced.per_strategy["llm"]), (2, 2));
+        let floor = normal.for_drawdown(DrawdownTier::Floor);
+        assert_eq!((floor.global, floor.per_strategy["llm"]), (1, 1));
+        assert_eq!(normal.for_drawdown(DrawdownTier::Normal), normal);
+
+        // Two positions open: the Reduced tier queues what Normal would trade,
+        // and the queued signal is released once the drawdown recovers
+        let held = open(&[("llm", "x-yes"), ("llm", "y-yes")]);
+        let now = Utc::now();
+        let mut queue = PositionQueue::new(Duration::minutes(30), 10);
+        let candidate = signal("a", "llm", dec!(0.7), dec!(0.5));
+        assert!(normal.has_slot(&held, &candidate));
+        assert!(!reduced.has_slot(&held, &candidate));
+        queue.push(candidate, "A?", now);
+
+        let price = |s: &Signal| Some(s.market_probability);
+        assert!(queue.pop_ready(now, &reduced, &held, dec!(0.05), price).is_none());
+        assert!(queue.pop_ready(now, &floor, &held, dec!(0.05), price).is_none());
+        assert_eq!(queue.len(), 1);
+        assert!(queue.pop_ready(now, &normal, &held, dec!(0.05), price).is_some());
+    }
+}
@@ -0,0 +1,261 @@
+//! Position size ramp-up for new strategies and market categories
+//!
+//! A strategy or market category without a track record trades at a
+//! fraction of the full size. The fraction grows by a step for every batch
+//! of closed trades won often enough, and a losing streak sets it back. A
+//! signal is sized by the lower of its strategy's and its category's
+//! fraction.
+
+use crate::config::{RampConfig, RampSetback};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Ramp progress of one strategy or market category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RampState {
+    /// Fraction of the full size cap
+    pub level: Decimal,
+    /// Closed trades since the level last changed or was last reviewed
+    pub trades: u32,
+    /// Of which won
+    pub wins: u32,
+    /// Losing trades in a row
+    pub losing_streak: u32,
+}
+
+impl RampState {
+    pub fn new(level: Decimal) -> Self {
+        Self { level, trades: 0, wins: 0, losing_streak: 0 }
+    }
+}
+
+/// Why a ramp level changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RampReason {
+    /// A batch of trades won often enough
+    Performance,
+    /// Too many losses in a row
+    LosingStreak,
+}
+
+/// A ramp's state after a close, and its level change if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct RampUpdate {
+    /// `strategy:<name>` or `category:<name>`
+    pub key: String,
+    pub state: RampState,
+    /// Previous level and why it changed
+    pub change: Option<(Decimal, RampReason)>,
+}
+
+impl RampUpdate {
+    /// "strategy:llm size ramp 25% → 35% after 10 trades"
+    pub fn describe(&self, config: &RampConfig) -> Option<String> {
+        let (from, reason) = self.change?;
+        let reason = match reason {
+            RampReason::Performance => format!("after {} trades", config.trades_per_step),
+            RampReason::LosingStreak => format!("after {} losses in a row", config.losing_streak),
+        };
+        Some(format!(
+            "{} size ramp {}% → {}% {}",
+            self.key,
+            (from * Decimal::ONE_HUNDRED).round_dp(0),
+            (self.state.level * Decimal::ONE_HUNDRED).round_dp(0),
+            reason
+        ))
+    }
+}
+
+/// Size ramps of strategies and market categories
+#[derive(Debug, Clone)]
+pub struct RampPolicy {
+    pub config: RampConfig,
+    states: HashMap<String, RampState>,
+}
+
+impl RampPolicy {
+    pub fn new(config: RampConfig) -> Self {
+        Self { config, states: HashMap::new() }
+    }
+
+    /// Resume from persisted states
+    pub fn with_states(mut self, states: HashMap<String, RampState>) -> Self {
+        self.states = states;
+        self
+    }
+
+    pub fn strategy_key(strategy: &str) -> String {
+        format!("strategy:{}", strategy)
+    }
+
+    pub fn category_key(category: &str) -> String {
+        format!("category:{}", category)
+    }
+
+    fn initial_level(&self) -> Decimal {
+        self.config.initial_fraction.clamp(Decimal::ZERO, Decimal::ONE)
+    }
+
+    /// Current fraction for `key`; the initial fraction when it hasn't traded
+    pub fn level(&self, key: &str) -> Decimal {
+        self.states.get(key).map_or_else(|| self.initial_level(), |s| s.level)
+    }
+
+    /// Size multiplier of a trade by `strategy` in `category`
+    pub fn multiplier(&self, strategy: &str, category: &str) -> Decimal {
+        self.level(&Self::strategy_key(strategy))
+            .min(self.level(&Self::category_key(category)))
+    }
+
+    /// Levels of everything that has traded, by key
+    pub fn levels(&self) -> Vec<(String, Decimal)> {
+        let mut levels: Vec<_> = self.states.iter().map(|(k, s)| (k.clone(), s.level)).collect();
+        levels.sort_by(|a, b| a.0.cmp(&b.0));
+        levels
+    }
+
+    /// Record the realized P&L of a close by `strategy` in `category`;
+    /// returns both ramps' new states, to be persisted
+    pub fn record_close(&mut self, strategy: &str, category: &str, pnl: Decimal) -> Vec<RampUpdate> {
+        [Self::strategy_key(strategy), Self::category_key(category)]
+            .into_iter()
+            .map(|key| {
+                let initial = self.initial_level();
+                let state = self.states.entry(key.clone()).or_insert_with(|| RampState::new(initial));
+                let change = advance(state, &self.config, initial, pnl > Decimal::ZERO);
+                RampUpdate { key, state: state.clone(), change }
+            })
+            .collect()
+    }
+}
+
+/// Count a closed trade; returns the previous level if it changed
+fn advance(state: &mut RampState, config: &RampConfig, initial: Decimal, won: bool) -> Option<(Decimal, RampReason)> {
+    let from = state.level;
+    state.trades += 1;
+    if won {
+        state.wins += 1;
+        state.losing_streak = 0;
+    } else {
+        state.losing_streak += 1;
+    }
+
+    if config.losing_streak > 0 && state.losing_streak >= config.losing_streak {
+        state.level = match config.on_losing_streak {
+            RampSetback::Reset => initial,
+            RampSetback::StepDown => (state.level - config.step).max(initial),
+        };
+        *state = RampState::new(state.level);
+        return (state.level != from).then_some((from, RampReason::LosingStreak));
+    }
+
+    if config.trades_per_step > 0 && state.trades >= config.trades_per_step {
+        let win_rate = Decimal::from(state.wins) / Decimal::from(state.trades);
+        if win_rate >= config.min_win_rate {
+            state.level = (state.level + config.step).min(Decimal::ONE);
+        }
+        state.trades = 0;
+        state.wins = 0;
+        return (state.level != from).then_some((from, RampReason::Performance));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn policy() -> RampPolicy {
+        RampPolicy::new(RampConfig::default())
+    }
+
+    #[test]
+    fn test_ramps_up_with_acceptable_win_rate() {
+        let mut ramp = policy();
+        assert_eq!(ramp.multiplier("llm", "crypto"), dec!(0.25));
+
+        // 5 wins in 10 (50% ≥ 45%) → one step up for both
+        for i in 0..10 {
+            let pnl = if i % 2 == 0 { dec!(5) } else { dec!(-5) };
+            ramp.record_close("llm", "crypto", pnl);
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.35));
+        assert_eq!(ramp.level("category:crypto"), dec!(0.35));
+
+        // 4 wins in 10 (40%) holds the level
+        for i in 0..10 {
+            let pnl = if i % 5 < 2 { dec!(5) } else { dec!(-5) };
+            ramp.record_close("llm", "crypto", pnl);
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.35));
+
+        // Never past the full size
+        for _ in 0..100 {
+            ramp.record_close("llm", "crypto", dec!(5));
+        }
+        assert_eq!(ramp.multiplier("llm", "crypto"), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_multiplier_is_lower_of_strategy_and_category() {
+        let mut ramp = policy();
+        for _ in 0..10 {
+            ramp.record_close("llm", "crypto", dec!(5));
+        }
+        // A seasoned strategy in a new category is held to the category's level
+        assert_eq!(ramp.multiplier("llm", "crypto"), dec!(0.35));
+        assert_eq!(ramp.multiplier("llm", "politics"), dec!(0.25));
+        assert_eq!(ramp.multiplier("copy", "crypto"), dec!(0.25));
+    }
+
+    #[test]
+    fn test_losing_streak_resets_or_steps_down() {
+        let mut ramp = policy();
+        for _ in 0..30 {
+            ramp.record_close("llm", "crypto", dec!(5));
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.55));
+
+        for _ in 0..4 {
+            ramp.record_close("llm", "crypto", dec!(-5));
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.55));
+        let updates = ramp.record_close("llm", "crypto", dec!(-5));
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.25));
+        assert_eq!(updates[0].change, Some((dec!(0.55), RampReason::LosingStreak)));
+        assert_eq!(updates[0].state, RampState::new(dec!(0.25)));
+        assert_eq!(
+            updates[0].describe(&ramp.config).as_deref(),
+            Some("strategy:llm size ramp 55% → 25% after 5 losses in a row")
+        );
+
+        let mut ramp = RampPolicy::new(RampConfig { on_losing_streak: RampSetback::StepDown, ..Default::default() });
+        for _ in 0..20 {
+            ramp.record_close("llm", "crypto", dec!(5));
+        }
+        for _ in 0..5 {
+            ramp.record_close("llm", "crypto", dec!(-5));
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.35));
+        for _ in 0..10 {
+            ramp.record_close("llm", "crypto", dec!(-5));
+        }
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.25));
+    }
+
+    #[test]
+    fn test_win_breaks_losing_streak() {
+        let mut ramp = policy();
+        for _ in 0..10 {
+            ramp.record_close("llm", "crypto", dec!(5));
+        }
+        for _ in 0..4 {
+            ramp.record_close("llm", "crypto", dec!(-5));
+        }
+        ramp.record_close("llm", "crypto", dec!(5));
+        ramp.record_close("llm", "crypto", dec!(-5));
+        assert_eq!(ramp.level("strategy:llm"), dec!(0.35));
+    }
+}
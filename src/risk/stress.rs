@@ -0,0 +1,348 @@
+//! Portfolio stress tests under historical crisis scenarios
+//!
+//! A scenario shocks the prices of the categories it hits, scales their
+//! volatility and pulls every correlation toward 1, the way correlations
+//! converge in a crash. [`PortfolioStressTester`] reports what each
+//! scenario does to a weighted portfolio: the loss from the shocks alone,
+//! and a 99% value at risk under the stressed covariance on top of it.
+
+use crate::portfolio::{PortfolioError, PortfolioOptimizer};
+use crate::storage::{LadderPosition, PriceSnapshot};
+use crate::types::MarketCategory;
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap};
+
+/// One-sided 99% quantile of the standard normal
+const Z_99: Decimal = dec!(2.326);
+
+/// Shocks of a crisis scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct StressScenario {
+    pub name: String,
+    /// Price shock per category, e.g. -0.5 for a 50% drop. The keys are the
+    /// categories the scenario hits, a zero shock included; only their
+    /// volatility is scaled.
+    pub asset_shocks: HashMap<MarketCategory, Decimal>,
+    /// Volatility multiple for the categories hit
+    pub vol_multiplier: Decimal,
+    /// Fraction of the way every correlation moves toward 1
+    pub correlation_shock: Decimal,
+}
+
+/// Scenarios modelled on past crises
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinScenario {
+    /// March 2020: stocks -30%, crypto -50%, everything moving together
+    Covid2020,
+    /// The 2022 crypto bear market: crypto -70%
+    Crypto2022,
+    /// A contested US election: politics volatility tripled
+    USElection,
+}
+
+impl BuiltinScenario {
+    pub const ALL: [Self; 3] = [Self::Covid2020, Self::Crypto2022, Self::USElection];
+
+    pub fn scenario(self) -> StressScenario {
+        let (name, shocks, vol_multiplier, correlation_shock) = match self {
+            // Polymarket has no stock markets; economics and finance
+            // markets stand in for them
+            Self::Covid2020 => (
+                "Covid scenario",
+                vec![(MarketCategory::Economics, dec!(-0.30)), (MarketCategory::Crypto, dec!(-0.50))],
+                dec!(2),
+                dec!(0.5),
+            ),
+            Self::Crypto2022 => ("Crypto crash", vec![(MarketCategory::Crypto, dec!(-0.70))], dec!(2), dec!(0.3)),
+            Self::USElection => ("US election", vec![(MarketCategory::Politics, Decimal::ZERO)], dec!(3), Decimal::ZERO),
+        };
+        StressScenario {
+            name: name.to_string(),
+            asset_shocks: shocks.into_iter().collect(),
+            vol_multiplier,
+            correlation_shock,
+        }
+    }
+}
+
+impl StressScenario {
+    /// Every built-in scenario
+    pub fn builtin() -> Vec<Self> {
+        BuiltinScenario::ALL.iter().map(|s| s.scenario()).collect()
+    }
+}
+
+/// What a scenario does to a portfolio, in percent of its value
+#[derive(Debug, Clone, PartialEq)]
+pub struct StressResult {
+    pub scenario_name: String,
+    /// Loss from the shocks; negative for a gain
+    pub portfolio_loss_pct: Decimal,
+    /// 99% value at risk under the stressed covariance, shocks included
+    pub var_99: Decimal,
+    /// Largest loss of a single asset
+    pub max_single_loss: Decimal,
+}
+
+/// Runs stress scenarios against portfolios of categorized assets
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioStressTester {
+    /// Category of each optimizer symbol; missing ones are `Other`
+    categories: HashMap<String, MarketCategory>,
+}
+
+impl PortfolioStressTester {
+    pub fn new(categories: HashMap<String, MarketCategory>) -> Self {
+        Self { categories }
+    }
+
+    /// Each scenario's effect on a portfolio of `weights` over the
+    /// optimizer's symbols, with the optimizer's covariance as the
+    /// unstressed one (the VaR horizon is its period)
+    pub fn run(&self, weights: &[Decimal], optimizer: &PortfolioOptimizer, scenarios: &[StressScenario]) -> Vec<StressResult> {
+        scenarios.iter().map(|s| self.run_scenario(weights, optimizer, s)).collect()
+    }
+
+    fn run_scenario(&self, weights: &[Decimal], optimizer: &PortfolioOptimizer, scenario: &StressScenario) -> StressResult {
+        let covariance = optimizer.covariance_matrix();
+        let correlation = optimizer.correlation_matrix();
+        let n = optimizer.symbols().len().min(weights.len());
+        let shocks: Vec<Option<Decimal>> = optimizer.symbols()[..n]
+            .iter()
+            .map(|symbol| {
+                let category = self.categories.get(symbol).copied().unwrap_or_default();
+                scenario.asset_shocks.get(&category).copied()
+            })
+            .collect();
+
+        let losses: Vec<Decimal> = (0..n).map(|i| -weights[i] * shocks[i].unwrap_or_default()).collect();
+        let loss: Decimal = losses.iter().sum();
+
+        let vols: Vec<Decimal> = (0..n)
+            .map(|i| {
+                let vol = covariance[i][i].max(Decimal::ZERO).sqrt().unwrap_or_default();
+                if shocks[i].is_some() {
+                    vol * scenario.vol_multiplier
+                } else {
+                    vol
+                }
+            })
+            .collect();
+        let correlation_shock = scenario.correlation_shock.clamp(Decimal::ZERO, Decimal::ONE);
+        let mut variance = Decimal::ZERO;
+        for i in 0..n {
+            for j in 0..n {
+                let rho = if i == j {
+                    Decimal::ONE
+                } else {
+                    correlation[i][j] + correlation_shock * (Decimal::ONE - correlation[i][j])
+                };
+                variance += weights[i] * weights[j] * rho * vols[i] * vols[j];
+            }
+        }
+        let std = variance.max(Decimal::ZERO).sqrt().unwrap_or_default();
+
+        let pct = |x: Decimal| (x * Decimal::ONE_HUNDRED).round_dp(4);
+        StressResult {
+            scenario_name: scenario.name.clone(),
+            portfolio_loss_pct: pct(loss),
+            var_99: pct(loss + Z_99 * std),
+            max_single_loss: pct(losses.iter().copied().fold(Decimal::ZERO, Decimal::max)),
+        }
+    }
+}
+
+/// Open positions ready to stress test
+pub struct StressPortfolio {
+    pub weights: Vec<Decimal>,
+    pub optimizer: PortfolioOptimizer,
+    pub tester: PortfolioStressTester,
+}
+
+impl StressPortfolio {
+    /// Open positions weighted by cost, one asset per market and side, with
+    /// the covariance of their daily returns over the days `snapshots` has
+    /// a price for every market. A NO position moves with 1 - the YES price.
+    pub fn from_positions(positions: &[LadderPosition], snapshots: &[PriceSnapshot]) -> Result<Self, PortfolioError> {
+        // (market, holds NO) -> (category, cost)
+        let mut assets: BTreeMap<(String, bool), (MarketCategory, Decimal)> = BTreeMap::new();
+        for p in positions {
+            let holds_no = p.market.as_ref().is_some_and(|m| {
+                m.outcomes
+                    .iter()
+                    .any(|o| o.token_id == p.position.token_id && o.outcome.eq_ignore_ascii_case("no"))
+            });
+            let category = p.market.as_ref().map(|m| m.market_category()).unwrap_or_default();
+            let asset = assets.entry((p.position.market_id.clone(), holds_no)).or_insert((category, Decimal::ZERO));
+            asset.1 += p.position.size * p.position.avg_price;
+        }
+        let total: Decimal = assets.values().map(|(_, cost)| *cost).sum();
+        if total <= Decimal::ZERO {
+            return Err(PortfolioError::InsufficientData { required: 1, actual: 0 });
+        }
+
+        let mut by_day: BTreeMap<NaiveDate, HashMap<&str, Decimal>> = BTreeMap::new();
+        for s in snapshots {
+            by_day.entry(s.taken_at.date_naive()).or_default().insert(&s.market_id, s.yes_price);
+        }
+        let prices: Vec<Vec<Decimal>> = by_day
+            .values()
+            .filter_map(|day| {
+                assets
+                    .keys()
+                    .map(|(market_id, holds_no)| {
+                        let yes = *day.get(market_id.as_str())?;
+                        Some(if *holds_no { Decimal::ONE - yes } else { yes })
+                    })
+                    .collect()
+            })
+            .collect();
+        let returns: Vec<Vec<Decimal>> = prices
+            .windows(2)
+            .map(|pair| {
+                pair[0]
+                    .iter()
+                    .zip(&pair[1])
+                    .map(|(before, after)| if before.is_zero() { Decimal::ZERO } else { after / before - Decimal::ONE })
+                    .collect()
+            })
+            .collect();
+
+        let symbols: Vec<String> = assets
+            .keys()
+            .map(|(market_id, holds_no)| if *holds_no { format!("{}:no", market_id) } else { market_id.clone() })
+            .collect();
+        let optimizer = PortfolioOptimizer::from_returns(symbols.clone(), &returns, Decimal::ZERO, 1)?;
+        Ok(Self {
+            weights: assets.values().map(|(_, cost)| cost / total).collect(),
+            tester: PortfolioStressTester::new(symbols.into_iter().zip(assets.values().map(|(category, _)| *category)).collect()),
+            optimizer,
+        })
+    }
+
+    pub fn run(&self, scenarios: &[StressScenario]) -> Vec<StressResult> {
+        self.tester.run(&self.weights, &self.optimizer, scenarios)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::OpenPosition;
+    use crate::types::{Market, Outcome};
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Uncorrelated crypto (20% vol) and politics (10% vol) markets
+    fn optimizer() -> PortfolioOptimizer {
+        PortfolioOptimizer::from_statistics(
+            vec!["btc".to_string(), "election".to_string()],
+            vec![Decimal::ZERO, Decimal::ZERO],
+            vec![vec![dec!(0.04), Decimal::ZERO], vec![Decimal::ZERO, dec!(0.01)]],
+            Decimal::ZERO,
+        )
+        .unwrap()
+    }
+
+    fn tester() -> PortfolioStressTester {
+        PortfolioStressTester::new(HashMap::from([
+            ("btc".to_string(), MarketCategory::Crypto),
+            ("election".to_string(), MarketCategory::Politics),
+        ]))
+    }
+
+    #[test]
+    fn test_builtin_scenarios() {
+        let results = tester().run(&[dec!(0.5), dec!(0.5)], &optimizer(), &StressScenario::builtin());
+        let names: Vec<&str> = results.iter().map(|r| r.scenario_name.as_str()).collect();
+        assert_eq!(names, ["Covid scenario", "Crypto crash", "US election"]);
+
+        // Half the portfolio in crypto
+        assert_eq!(results[0].portfolio_loss_pct, dec!(25));
+        assert_eq!(results[1].portfolio_loss_pct, dec!(35));
+        assert_eq!(results[1].max_single_loss, dec!(35));
+        assert!(results[1].var_99 > results[1].portfolio_loss_pct);
+
+        // No shock, but politics at 30% vol: 2.326 * sqrt(0.25 * 0.04 + 0.25 * 0.09)
+        let election = &results[2];
+        assert_eq!(election.portfolio_loss_pct, Decimal::ZERO);
+        assert!((election.var_99 - dec!(41.93)).abs() < dec!(0.01));
+    }
+
+    #[test]
+    fn test_correlation_shock_raises_var() {
+        let scenario = |correlation_shock| StressScenario {
+            name: "test".to_string(),
+            asset_shocks: HashMap::new(),
+            vol_multiplier: Decimal::ONE,
+            correlation_shock,
+        };
+        let results = tester().run(&[dec!(0.5), dec!(0.5)], &optimizer(), &[scenario(Decimal::ZERO), scenario(Decimal::ONE)]);
+
+        // Fully correlated: 2.326 * (0.5 * 0.2 + 0.5 * 0.1)
+        assert!(results[1].var_99 > results[0].var_99);
+        assert!((results[1].var_99 - dec!(34.89)).abs() < dec!(0.01));
+        assert_eq!(results[0].max_single_loss, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_portfolio_from_positions() {
+        let market = |id: &str, category: &str| Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: dec!(0.5) },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: dec!(0.5) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: Some(category.to_string()),
+            order_constraints: None,
+        };
+        let position = |market: Market, token: &str, size: Decimal| LadderPosition {
+            position: OpenPosition {
+                account_id: None,
+                market_id: market.id.clone(),
+                token_id: format!("{}-{}", market.id, token),
+                size,
+                avg_price: dec!(0.5),
+                strategy_name: None,
+            },
+            market: Some(market),
+        };
+        let positions = vec![
+            position(market("btc", "Crypto"), "yes", dec!(300)),
+            position(market("election", "Politics"), "no", dec!(100)),
+        ];
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let snapshots: Vec<PriceSnapshot> = (0..15)
+            .flat_map(|day| {
+                let wiggle = if day % 2 == 0 { dec!(0.05) } else { Decimal::ZERO };
+                [("btc", dec!(0.5) + wiggle), ("election", dec!(0.4) - wiggle)].map(|(id, price)| PriceSnapshot {
+                    market_id: id.to_string(),
+                    yes_price: price,
+                    taken_at: start + Duration::days(day),
+                })
+            })
+            .collect();
+
+        let portfolio = StressPortfolio::from_positions(&positions, &snapshots).unwrap();
+        assert_eq!(portfolio.optimizer.symbols(), ["btc", "election:no"]);
+        assert_eq!(portfolio.weights, [dec!(0.75), dec!(0.25)]);
+        // Holding NO, the election moves with BTC
+        assert!(portfolio.optimizer.correlation_matrix()[0][1] > dec!(0.9));
+
+        let crash = portfolio.run(&[BuiltinScenario::Crypto2022.scenario()]);
+        assert_eq!(crash[0].portfolio_loss_pct, dec!(52.5));
+
+        // Too little history
+        assert!(StressPortfolio::from_positions(&positions, &snapshots[..10]).is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 use super::*;
 use crate::config::RiskConfig;
+use crate::storage::DEFAULT_STRATEGY_NAME;
 use crate::types::{Market, Outcome, Position, Side, Signal};
 use chrono::Utc;
 use rust_decimal::Decimal;
@@ -14,6 +15,18 @@ fn test_risk_config() -> RiskConfig {
         max_daily_loss_pct: dec!(0.10),
         min_balance_reserve: dec!(100),
         max_open_positions: 5,
+        signal_dedup_ttl_secs: 900,
+        max_total_kelly: Decimal::ONE,
+        max_consecutive_api_failures: 5,
+        api_failure_pause_secs: 300,
+        max_positions_per_strategy: std::collections::HashMap::new(),
+        queued_signal_ttl_secs: 1800,
+        max_queued_signals: 20,
+        loss_cooldown_mins: 30,
+        loss_cooldown_override_edge: None,
+        regime_rotation_min_edge_gain: None,
+        max_heat_budget: Decimal::ZERO,
+        regime_consensus_sizing: Default::default(),
     }
 }
 
@@ -39,6 +52,10 @@ fn test_market() -> Market {
         ],
         active: true,
         closed: false,
+        event_id: None,
+        neg_risk: false,
+        category: None,
+        order_constraints: None,
     }
 }
 
@@ -53,6 +70,13 @@ fn test_signal() -> Signal {
         confidence: dec!(0.75),
         suggested_size: dec!(50),
         timestamp: Utc::now(),
+        strategy_name: None,
+        epistemic_uncertainty: Decimal::ZERO,
+        trace_id: Default::default(),
+        tags: Vec::new(),
+        posterior_variance: Decimal::ZERO,
+        valid_until: None,
+        signal_score: None,
     }
 }
 
@@ -151,6 +175,161 @@ fn test_risk_manager_respects_position_limit() {
     assert!(size.is_none());
 }
 
+#[test]
+fn test_risk_manager_loss_cooldown_blocks_reentry() {
+    let mut manager = RiskManager::new(test_risk_config());
+    let market = test_market();
+    let signal = test_signal();
+
+    manager.record_close(&market.id, dec!(5));
+    assert!(manager.calculate_position_size(&signal, &market, dec!(1000), &[]).is_some());
+
+    manager.record_close(&market.id, dec!(-5));
+    assert!(manager.in_loss_cooldown(&signal));
+    assert!(manager.calculate_position_size(&signal, &market, dec!(1000), &[]).is_none());
+
+    let other = Signal { market_id: "test-market-2".to_string(), ..test_signal() };
+    let other_market = Market { id: "test-market-2".to_string(), ..test_market() };
+    assert!(!manager.in_loss_cooldown(&other));
+    assert!(manager.calculate_position_size(&other, &other_market, dec!(1000), &[]).is_some());
+}
+
+fn open_trade(n: usize, edge: Decimal) -> crate::types::Trade {
+    crate::types::Trade {
+        id: format!("trade-{}", n),
+        order_id: format!("order-{}", n),
+        token_id: format!("token-{}", n),
+        market_id: format!("market-{}", n),
+        side: Side::Buy,
+        price: dec!(0.40),
+        size: dec!(50),
+        fee: Decimal::ZERO,
+        timestamp: Utc::now(),
+        status: Default::default(),
+        strategy_name: None,
+        account_id: None,
+        trace_id: Default::default(),
+        tags: Vec::new(),
+        edge: Some(edge),
+    }
+}
+
+#[test]
+fn test_regime_caps_open_positions() {
+    use crate::regime::MarketRegime;
+
+    let mut manager = RiskManager::new(test_risk_config());
+    let signal = test_signal();
+    let two_open = vec![open_trade(1, dec!(0.08)), open_trade(2, dec!(0.12))];
+    let fifteen_open: Vec<_> = (1..=14).map(|n| open_trade(n, dec!(0.10))).collect();
+
+    // No regime detected yet: no cap
+    assert_eq!(manager.check_position_limit(&signal, &fifteen_open), PositionLimitCheck::Allowed);
+
+    manager.set_regime(MarketRegime::Crisis);
+    assert_eq!(manager.regime_max_positions(), Some(2));
+    assert_eq!(manager.check_position_limit(&signal, &two_open[..1]), PositionLimitCheck::Allowed);
+    // A third entry is rejected...
+    assert_eq!(
+        manager.check_position_limit(&signal, &two_open),
+        PositionLimitCheck::Blocked { reason: "Crisis regime allows at most 2 open positions".to_string() }
+    );
+    // ...but adding to a held token takes no new slot
+    let held = Signal { token_id: "token-2".to_string(), ..test_signal() };
+    assert_eq!(manager.check_position_limit(&held, &two_open), PositionLimitCheck::Allowed);
+
+    manager.set_regime(MarketRegime::Ranging);
+    assert_eq!(manager.check_position_limit(&signal, &two_open), PositionLimitCheck::Allowed);
+    assert_eq!(manager.check_position_limit(&signal, &fifteen_open), PositionLimitCheck::Allowed);
+    let mut full = fifteen_open.clone();
+    full.push(open_trade(15, dec!(0.10)));
+    assert!(matches!(manager.check_position_limit(&signal, &full), PositionLimitCheck::Blocked { .. }));
+}
+
+#[test]
+fn test_regime_cap_rotates_out_the_weakest_position() {
+    use crate::regime::MarketRegime;
+
+    let config = RiskConfig { regime_rotation_min_edge_gain: Some(dec!(0.05)), ..test_risk_config() };
+    let mut manager = RiskManager::new(config);
+    manager.set_regime(MarketRegime::Crisis);
+    let open = vec![open_trade(1, dec!(0.12)), open_trade(2, dec!(0.04))];
+
+    // 0.10 beats the weakest 0.04 by 0.06
+    assert_eq!(
+        manager.check_position_limit(&test_signal(), &open),
+        PositionLimitCheck::Rotate { market_id: "market-2".to_string(), token_id: "token-2".to_string(), edge: dec!(0.04) }
+    );
+    // A short sell's edge counts by its size
+    let strong_sell = Signal { side: Side::Sell, edge: dec!(-0.10), ..test_signal() };
+    assert!(matches!(manager.check_position_limit(&strong_sell, &open), PositionLimitCheck::Rotate { .. }));
+    // 0.08 beats it by less than the required gain
+    let weak = Signal { edge: dec!(0.08), ..test_signal() };
+    assert!(matches!(manager.check_position_limit(&weak, &open), PositionLimitCheck::Blocked { .. }));
+}
+
+#[test]
+fn test_regime_consensus_scales_position_size() {
+    use crate::regime::{MarketRegime, RegimeAlignment, RegimeConsensus};
+
+    let mut manager = RiskManager::new(test_risk_config());
+    assert_eq!(manager.regime_size_multiplier(), Decimal::ONE);
+    let signal = test_signal();
+    let market = test_market();
+    let base = manager.calculate_position_size(&signal, &market, dec!(10000), &[]).unwrap();
+
+    // Bullish trend (1.2) confirmed on every timeframe (1.25)
+    manager.set_regime(MarketRegime::BullishTrend);
+    manager.set_regime_consensus(Some(RegimeConsensus {
+        primary_regime: MarketRegime::BullishTrend,
+        confidence: dec!(0.9),
+        alignment: RegimeAlignment::FullyAligned,
+        short_regime: MarketRegime::BullishTrend,
+        medium_regime: MarketRegime::BullishTrend,
+        long_regime: MarketRegime::BullishTrend,
+    }));
+    assert_eq!(manager.regime_size_multiplier(), dec!(1.5));
+    let aligned = manager.calculate_position_size(&signal, &market, dec!(10000), &[]).unwrap();
+    assert_eq!(aligned, base * dec!(1.5));
+
+    manager.set_regime_consensus(None);
+    assert_eq!(manager.regime_size_multiplier(), dec!(1.2));
+}
+
+#[test]
+fn test_ramp_scales_position_size_after_regime() {
+    use crate::config::RampConfig;
+    use crate::regime::MarketRegime;
+
+    let mut manager = RiskManager::new(test_risk_config());
+    let signal = test_signal();
+    let market = test_market();
+    let base = manager.calculate_position_size(&signal, &market, dec!(10000), &[]).unwrap();
+
+    // Without a ramp nothing changes
+    assert_eq!(manager.ramp_multiplier(&signal, &market), Decimal::ONE);
+    assert!(manager.record_ramp_close("main", "other", dec!(5)).is_empty());
+
+    // The ramp multiplies with the regime multiplier
+    manager.set_regime(MarketRegime::BullishTrend);
+    manager.set_ramp(RampPolicy::new(RampConfig::default()));
+    assert_eq!(manager.ramp_multiplier(&signal, &market), dec!(0.25));
+    let ramped = manager.calculate_position_size(&signal, &market, dec!(10000), &[]).unwrap();
+    assert_eq!(ramped, base * dec!(1.2) * dec!(0.25));
+
+    // A close of the signal's strategy and category steps both ramps
+    let category = market.market_category().name();
+    for _ in 0..10 {
+        manager.record_ramp_close(DEFAULT_STRATEGY_NAME, category, dec!(5));
+    }
+    assert_eq!(manager.ramp_multiplier(&signal, &market), dec!(0.35));
+
+    // ...and comes before the minimum size check: ramped below $1 is no trade
+    manager.set_ramp(RampPolicy::new(RampConfig { initial_fraction: dec!(0.001), ..Default::default() }));
+    assert!(base * dec!(1.2) * dec!(0.001) < Decimal::ONE);
+    assert_eq!(manager.calculate_position_size(&signal, &market, dec!(10000), &[]), None);
+}
+
 #[test]
 fn test_risk_manager_volatility_adjustment() {
     let mut manager = RiskManager::new(test_risk_config());
@@ -8,6 +8,8 @@
 //! - Adaptive routing based on market conditions
 //! - Child order management and aggregation
 //! - Execution quality feedback loop
+//! - Polymarket CLOB as a live venue ([`PolymarketVenue`])
+//! - Intraday volume profiles for slicing orders over time ([`VolumeCurve`])
 //!
 //! # Example
 //! ```ignore
@@ -21,10 +23,20 @@
 //! let child_orders = router.route(&parent)?;
 //! ```
 
+mod polymarket;
+mod volume_curve;
+
+pub use polymarket::{PolymarketVenue, POLYMARKET_VENUE_ID};
+pub use volume_curve::{CurveInterpolation, VolumeCurve};
+
+use crate::clock::{Clock, IdGenerator, SystemClock, UuidGenerator};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Order side
@@ -555,6 +567,14 @@ pub struct RoutingConfig {
     pub retry_on_failure: bool,
     /// Stale data threshold (ms)
     pub stale_threshold_ms: u64,
+    /// Half-life of a venue's reliability score while it gets no feedback;
+    /// idle venues drift toward a neutral 0.5 (0 = never decay)
+    #[serde(default = "default_reliability_half_life_secs")]
+    pub reliability_half_life_secs: u64,
+}
+
+fn default_reliability_half_life_secs() -> u64 {
+    24 * 60 * 60
 }
 
 impl Default for RoutingConfig {
@@ -568,6 +588,7 @@ impl Default for RoutingConfig {
             max_slippage_bps: dec!(50),
             retry_on_failure: true,
             stale_threshold_ms: 5000,
+            reliability_half_life_secs: default_reliability_half_life_secs(),
         }
     }
 }
@@ -638,6 +659,21 @@ pub struct ExecutionFeedback {
     pub error: Option<String>,
 }
 
+/// A venue the router's child orders can actually be sent to
+#[async_trait]
+pub trait VenueExecutor: Send + Sync {
+    /// Venue definition to register with the router, with current fees and
+    /// latency
+    fn venue(&self) -> Venue;
+
+    /// Current book of `symbol` on this venue
+    async fn liquidity(&self, symbol: &str) -> crate::error::Result<VenueLiquidity>;
+
+    /// Place `child` and report how it went; failures are reported in the
+    /// feedback so the router learns from them
+    async fn execute(&self, child: &ChildOrder) -> ExecutionFeedback;
+}
+
 /// Venue performance metrics (for adaptive scoring)
 #[derive(Debug, Clone, Default)]
 pub struct VenueMetrics {
@@ -656,12 +692,17 @@ pub struct VenueMetrics {
     /// Recent reliability (0-1)
     pub recent_reliability: f64,
     /// Last updated
-    pub last_updated: Option<Instant>,
+    pub last_updated: Option<DateTime<Utc>>,
 }
 
 impl VenueMetrics {
     /// Update metrics with execution feedback
     pub fn update(&mut self, feedback: &ExecutionFeedback) {
+        self.update_at(feedback, Utc::now());
+    }
+
+    /// Update metrics with execution feedback received at `at`
+    pub fn update_at(&mut self, feedback: &ExecutionFeedback, at: DateTime<Utc>) {
         self.total_orders += 1;
 
         if feedback.success {
@@ -690,7 +731,22 @@ impl VenueMetrics {
         let success_weight = if feedback.success { 1.0 } else { 0.0 };
         self.recent_reliability = self.recent_reliability * 0.95 + success_weight * 0.05;
 
-        self.last_updated = Some(Instant::now());
+        self.last_updated = Some(at);
+    }
+
+    /// Reliability to score the venue by at `now`: `recent_reliability`
+    /// decayed toward 0.5 by the time since the last feedback, so old data
+    /// counts for less. Venues without feedback are not decayed.
+    pub fn effective_reliability(&self, now: DateTime<Utc>, half_life: Duration) -> f64 {
+        let Some(last_updated) = self.last_updated else {
+            return self.recent_reliability;
+        };
+        if half_life.is_zero() {
+            return self.recent_reliability;
+        }
+        let idle = (now - last_updated).to_std().unwrap_or_default();
+        let weight = 0.5f64.powf(idle.as_secs_f64() / half_life.as_secs_f64());
+        0.5 + (self.recent_reliability - 0.5) * weight
     }
 
     /// Get fill rate
@@ -715,6 +771,10 @@ pub struct SmartOrderRouter {
     metrics: HashMap<String, VenueMetrics>,
     /// Order counter for sequencing
     order_counter: u32,
+    /// Timestamps for orders and decisions
+    clock: Arc<dyn Clock>,
+    /// Ids for orders created by the router
+    ids: Arc<dyn IdGenerator>,
 }
 
 impl SmartOrderRouter {
@@ -726,9 +786,51 @@ impl SmartOrderRouter {
             liquidity: HashMap::new(),
             metrics: HashMap::new(),
             order_counter: 0,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidGenerator),
         }
     }
 
+    /// Use a custom clock (e.g. a `ManualClock` for reproducible runs)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Use a custom id source (e.g. a `SequentialIdGenerator` for reproducible runs)
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Create a parent order stamped with the router's clock and id source
+    pub fn new_parent_order(
+        &self,
+        symbol: &str,
+        side: Side,
+        quantity: Decimal,
+        order_type: OrderType,
+    ) -> ParentOrder {
+        let mut order = ParentOrder::new(symbol, side, quantity, order_type);
+        order.id = self.ids.next_id();
+        order.created_at = self.clock.now_millis();
+        order
+    }
+
+    /// Create a child order stamped with the router's clock and id source
+    fn child_order(
+        &self,
+        parent: &ParentOrder,
+        venue_id: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> ChildOrder {
+        let mut child = ChildOrder::new(&parent.id, venue_id, &parent.symbol, parent.side, quantity, price);
+        child.id = self.ids.next_id();
+        child.created_at = self.clock.now_millis();
+        child
+    }
+
     /// Register a venue
     pub fn register_venue(&mut self, venue: Venue) {
         let venue_id = venue.id.clone();
@@ -759,7 +861,7 @@ impl SmartOrderRouter {
     /// Record execution feedback
     pub fn record_feedback(&mut self, feedback: ExecutionFeedback) {
         if let Some(metrics) = self.metrics.get_mut(&feedback.venue_id) {
-            metrics.update(&feedback);
+            metrics.update_at(&feedback, self.clock.now());
         }
     }
 
@@ -877,9 +979,10 @@ impl SmartOrderRouter {
                 (max_latency - venue.latency_ms as f64) / latency_range;
             score.latency_score = latency_normalized.clamp(0.0, 1.0) * 100.0;
 
-            // Reliability score from metrics
+            // Reliability score from metrics, faded while the venue is idle
             if let Some(metrics) = self.metrics.get(&venue.id) {
-                score.reliability_score = metrics.recent_reliability * 100.0;
+                let half_life = Duration::from_secs(self.config.reliability_half_life_secs);
+                score.reliability_score = metrics.effective_reliability(self.clock.now(), half_life) * 100.0;
             }
 
             score.calculate_total(weights);
@@ -887,7 +990,13 @@ impl SmartOrderRouter {
         }
 
         // Sort by total score descending
-        scores.sort_by(|a, b| b.total_score.partial_cmp(&a.total_score).unwrap());
+        // (ties broken by venue ID so routing doesn't depend on map order)
+        scores.sort_by(|a, b| {
+            b.total_score
+                .partial_cmp(&a.total_score)
+                .unwrap()
+                .then_with(|| a.venue_id.cmp(&b.venue_id))
+        });
         scores
     }
 
@@ -915,14 +1024,7 @@ impl SmartOrderRouter {
         let price = best.expected_price.unwrap_or(dec!(0));
 
         self.order_counter += 1;
-        let mut child = ChildOrder::new(
-            &order.id,
-            &best.venue_id,
-            &order.symbol,
-            order.side,
-            qty,
-            price,
-        );
+        let mut child = self.child_order(order, &best.venue_id, qty, price);
         child.sequence = self.order_counter;
         child.expected_fee = venue.taker_fee;
 
@@ -970,14 +1072,7 @@ impl SmartOrderRouter {
             let price = score.expected_price.unwrap_or(dec!(0));
 
             self.order_counter += 1;
-            let mut child = ChildOrder::new(
-                &order.id,
-                &score.venue_id,
-                &order.symbol,
-                order.side,
-                qty,
-                price,
-            );
+            let mut child = self.child_order(order, &score.venue_id, qty, price);
             child.sequence = self.order_counter;
             child.expected_fee = venue.taker_fee;
 
@@ -1039,14 +1134,7 @@ impl SmartOrderRouter {
             let price = score.expected_price.unwrap_or(dec!(0));
 
             self.order_counter += 1;
-            let mut child = ChildOrder::new(
-                &order.id,
-                &score.venue_id,
-                &order.symbol,
-                order.side,
-                qty,
-                price,
-            );
+            let mut child = self.child_order(order, &score.venue_id, qty, price);
             child.sequence = self.order_counter;
             child.expected_fee = venue.taker_fee;
 
@@ -1111,14 +1199,7 @@ impl SmartOrderRouter {
             let price = score.expected_price.unwrap_or(dec!(0));
 
             self.order_counter += 1;
-            let mut child = ChildOrder::new(
-                &order.id,
-                &score.venue_id,
-                &order.symbol,
-                order.side,
-                qty,
-                price,
-            );
+            let mut child = self.child_order(order, &score.venue_id, qty, price);
             child.sequence = self.order_counter;
             child.expected_fee = venue.taker_fee;
 
@@ -1166,14 +1247,7 @@ impl SmartOrderRouter {
             let price = score.expected_price.unwrap_or(dec!(0));
 
             self.order_counter += 1;
-            let mut child = ChildOrder::new(
-                &order.id,
-                &score.venue_id,
-                &order.symbol,
-                order.side,
-                qty,
-                price,
-            );
+            let mut child = self.child_order(order, &score.venue_id, qty, price);
             child.sequence = self.order_counter;
             child.expected_fee = venue.taker_fee;
 
@@ -1263,10 +1337,7 @@ impl SmartOrderRouter {
             expected_avg_price,
             expected_slippage_bps: slippage_bps,
             coverage,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64,
+            timestamp: self.clock.now_millis(),
             compute_time_us: compute_time.as_micros() as u64,
         }
     }
@@ -1914,4 +1985,93 @@ mod tests {
         // Compute time should be recorded
         assert!(decision.compute_time_us > 0);
     }
+
+    #[test]
+    fn test_idle_venue_reliability_decays_toward_neutral() {
+        use crate::clock::ManualClock;
+        use chrono::TimeZone;
+
+        let start = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = Arc::new(ManualClock::new(start));
+        let mut router = setup_test_router().with_clock(clock.clone());
+        let fill = |venue: &str| ExecutionFeedback {
+            child_id: "1".to_string(),
+            venue_id: venue.to_string(),
+            requested_qty: dec!(1),
+            filled_qty: dec!(1),
+            requested_price: dec!(100),
+            actual_price: dec!(100),
+            actual_slippage_bps: dec!(0),
+            latency_ms: 10,
+            success: true,
+            error: None,
+        };
+
+        // Both venues build the same track record
+        for _ in 0..100 {
+            router.record_feedback(fill("binance"));
+            router.record_feedback(fill("okx"));
+        }
+        let raw = router.get_metrics("binance").unwrap().recent_reliability;
+        assert!(raw > 0.9);
+
+        // A week later only OKX has traded again
+        clock.advance(7 * 24 * 60 * 60 * 1000);
+        router.record_feedback(fill("okx"));
+
+        let half_life = Duration::from_secs(router.config().reliability_half_life_secs);
+        let now = clock.now();
+        let idle = router.get_metrics("binance").unwrap();
+        let active = router.get_metrics("okx").unwrap();
+        let idle_score = idle.effective_reliability(now, half_life);
+        assert!((idle_score - 0.5).abs() < 0.01, "idle venue at {}", idle_score);
+        assert!(active.effective_reliability(now, half_life) > 0.9);
+        // The counters behind the score are untouched
+        assert_eq!(idle.recent_reliability, raw);
+        assert_eq!(idle.successful_fills, 100);
+
+        // Half-life 0 turns decay off
+        assert_eq!(idle.effective_reliability(now, Duration::ZERO), raw);
+
+        // Scoring uses the decayed value
+        let order = router.new_parent_order("BTC-USDT", Side::Buy, dec!(1), OrderType::Market);
+        let decision = router.route(&order);
+        let score = |venue: &str| {
+            decision.venue_scores.iter().find(|s| s.venue_id == venue).unwrap().reliability_score
+        };
+        assert!(score("okx") > score("binance"));
+        assert!((score("binance") - 50.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_injected_clock_and_ids_are_reproducible() {
+        use crate::clock::{ManualClock, SequentialIdGenerator};
+        use chrono::TimeZone;
+
+        let run = || {
+            let start = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+            let mut router = setup_test_router()
+                .with_clock(Arc::new(ManualClock::stepping(start, 5)))
+                .with_id_generator(Arc::new(SequentialIdGenerator::new("sor")));
+            router.set_config(RoutingConfig {
+                algorithm: RoutingAlgorithm::ProRata,
+                ..RoutingConfig::default()
+            });
+
+            let order = router.new_parent_order("BTC-USDT", Side::Buy, dec!(10), OrderType::Market);
+            let decision = router.route(&order);
+            let stamps: Vec<(String, u64)> = decision
+                .child_orders
+                .iter()
+                .map(|c| (c.id.clone(), c.created_at))
+                .collect();
+            (order.created_at, decision.parent_id, decision.timestamp, stamps)
+        };
+
+        let first = run();
+        assert_eq!(first, run());
+        assert_eq!(first.1, "sor-1");
+        assert!(first.3.len() > 1);
+        assert_eq!(first.3[0].0, "sor-2");
+    }
 }
@@ -0,0 +1,216 @@
+//! Polymarket CLOB as a routing venue
+//!
+//! [`PolymarketVenue`] adapts a CLOB client to [`VenueExecutor`]: symbols are
+//! outcome token IDs, liquidity comes from the token's order book, and child
+//! orders become CLOB orders (GTC for maker children, FOK at the child's
+//! price otherwise). Fees follow the bot's [`FeeModel`], and the reported
+//! latency tracks the round trips of the orders actually placed.
+
+use super::{BookLevel, ChildOrder, ExecutionFeedback, Side, Venue, VenueExecutor, VenueLiquidity};
+use crate::client::mock::ClobClientTrait;
+use crate::error::Result;
+use crate::executor::classify_order_status;
+use crate::fees::{FeeModel, FeeRole};
+use crate::types::{Market, Order, OrderType, TradeStatus};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Venue ID the CLOB is registered under
+pub const POLYMARKET_VENUE_ID: &str = "polymarket";
+
+/// Latency assumed until the first order has been timed
+const INITIAL_LATENCY_MS: u64 = 250;
+
+/// Book levels per side in a liquidity snapshot
+const BOOK_DEPTH: usize = 10;
+
+/// The Polymarket CLOB behind a [`VenueExecutor`]
+pub struct PolymarketVenue<C: ClobClientTrait> {
+    clob: C,
+    fees: Arc<dyn FeeModel>,
+    /// token_id -> market_id, for per-market fee rates
+    markets: RwLock<HashMap<String, String>>,
+    /// Moving average of order round trips
+    latency_ms: AtomicU64,
+}
+
+impl<C: ClobClientTrait> PolymarketVenue<C> {
+    pub fn new(clob: C, fees: Arc<dyn FeeModel>) -> Self {
+        Self {
+            clob,
+            fees,
+            markets: RwLock::new(HashMap::new()),
+            latency_ms: AtomicU64::new(INITIAL_LATENCY_MS),
+        }
+    }
+
+    /// Price fees of `market`'s tokens at its own rates
+    pub fn register_market(&self, market: &Market) {
+        let mut markets = self.markets.write();
+        for outcome in &market.outcomes {
+            markets.insert(outcome.token_id.clone(), market.id.clone());
+        }
+    }
+
+    /// Fee as a fraction of notional for one share of `token_id` at `price`
+    pub fn fee_rate(&self, token_id: &str, price: Decimal, is_maker: bool) -> Decimal {
+        if price <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let market_id = self.markets.read().get(token_id).cloned().unwrap_or_default();
+        let role = if is_maker { FeeRole::Maker } else { FeeRole::Taker };
+        self.fees.fee_per_share(&market_id, crate::types::Side::Buy, price, role) / price
+    }
+
+    fn record_latency(&self, sample_ms: u64) {
+        let previous = self.latency_ms.load(Ordering::Relaxed);
+        self.latency_ms.store((previous * 4 + sample_ms) / 5, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl<C: ClobClientTrait> VenueExecutor for PolymarketVenue<C> {
+    /// Fees are quoted at 50c, where the fee curve is steepest relative to
+    /// the price of a share
+    fn venue(&self) -> Venue {
+        Venue::new(POLYMARKET_VENUE_ID, "Polymarket")
+            .with_fees(self.fee_rate("", dec!(0.5), true), self.fee_rate("", dec!(0.5), false))
+            .with_latency(self.latency_ms.load(Ordering::Relaxed))
+    }
+
+    async fn liquidity(&self, symbol: &str) -> Result<VenueLiquidity> {
+        let book = self.clob.get_order_book(symbol).await?;
+        let levels = |levels: &[crate::client::clob::OrderBookLevel]| -> Vec<BookLevel> {
+            levels
+                .iter()
+                .map(|l| BookLevel { price: l.price, quantity: l.size })
+                .collect()
+        };
+        let mut bids = levels(&book.bids);
+        let mut asks = levels(&book.asks);
+        bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+        asks.sort_by_key(|l| l.price);
+        bids.truncate(BOOK_DEPTH);
+        asks.truncate(BOOK_DEPTH);
+
+        let mut liquidity = VenueLiquidity::new(POLYMARKET_VENUE_ID, symbol);
+        liquidity.best_bid = bids.first().copied();
+        liquidity.best_ask = asks.first().copied();
+        liquidity.bid_depth = bids;
+        liquidity.ask_depth = asks;
+        liquidity.timestamp = chrono::Utc::now().timestamp_millis() as u64;
+        Ok(liquidity)
+    }
+
+    async fn execute(&self, child: &ChildOrder) -> ExecutionFeedback {
+        let order = Order {
+            token_id: child.symbol.clone(),
+            side: match child.side {
+                Side::Buy => crate::types::Side::Buy,
+                Side::Sell => crate::types::Side::Sell,
+            },
+            price: child.price,
+            size: child.quantity,
+            order_type: if child.is_maker { OrderType::GTC } else { OrderType::FOK },
+        };
+        let mut feedback = ExecutionFeedback {
+            child_id: child.id.clone(),
+            venue_id: POLYMARKET_VENUE_ID.to_string(),
+            requested_qty: child.quantity,
+            filled_qty: Decimal::ZERO,
+            requested_price: child.price,
+            actual_price: child.price,
+            actual_slippage_bps: Decimal::ZERO,
+            latency_ms: 0,
+            success: false,
+            error: None,
+        };
+
+        let started = Instant::now();
+        let placed = self.clob.place_order(&order).await;
+        feedback.latency_ms = started.elapsed().as_millis() as u64;
+        self.record_latency(feedback.latency_ms);
+
+        let status = match placed {
+            Ok(status) => status,
+            Err(e) => {
+                feedback.error = Some(e.to_string());
+                return feedback;
+            }
+        };
+        match classify_order_status(&status) {
+            Some(TradeStatus::Filled) => {
+                feedback.success = true;
+                feedback.filled_qty = status.filled_size;
+                feedback.actual_price = status.avg_price.unwrap_or(child.price);
+            }
+            // A resting maker child is placed, not yet filled
+            Some(_) => feedback.success = true,
+            None => feedback.error = Some(format!("order {} {}", status.order_id, status.status)),
+        }
+        if child.price > Decimal::ZERO {
+            let slippage = (feedback.actual_price - child.price) / child.price * dec!(10000);
+            feedback.actual_slippage_bps = match child.side {
+                Side::Buy => slippage,
+                Side::Sell => -slippage,
+            };
+        }
+        feedback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::clob::{OrderBook, OrderBookLevel};
+    use crate::client::mock::MockClobClient;
+    use crate::config::FeeSchedule;
+    use crate::fees::PolymarketFees;
+
+    fn venue(clob: MockClobClient) -> PolymarketVenue<MockClobClient> {
+        let fees = PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: dec!(200),
+            maker_fee_bps: dec!(-50),
+        });
+        PolymarketVenue::new(clob, Arc::new(fees))
+    }
+
+    #[tokio::test]
+    async fn test_liquidity_sorts_and_caps_the_book() {
+        let mut clob = MockClobClient::new();
+        clob.set_order_book(
+            "yes",
+            OrderBook {
+                // The API lists bids from the worst price up
+                bids: (1..=15).map(|i| OrderBookLevel { price: Decimal::new(i, 2), size: dec!(100) }).collect(),
+                asks: vec![
+                    OrderBookLevel { price: dec!(0.60), size: dec!(50) },
+                    OrderBookLevel { price: dec!(0.58), size: dec!(20) },
+                ],
+            },
+        );
+        let liquidity = venue(clob).liquidity("yes").await.unwrap();
+        assert_eq!(liquidity.venue_id, POLYMARKET_VENUE_ID);
+        assert_eq!(liquidity.best_bid.unwrap().price, dec!(0.15));
+        assert_eq!(liquidity.best_ask.unwrap().price, dec!(0.58));
+        assert_eq!(liquidity.bid_depth.len(), BOOK_DEPTH);
+        assert_eq!(liquidity.bid_depth.last().unwrap().price, dec!(0.06));
+    }
+
+    #[test]
+    fn test_venue_reports_fee_rates() {
+        let venue = venue(MockClobClient::new());
+        let definition = venue.venue();
+        assert_eq!(definition.taker_fee, dec!(0.02));
+        assert_eq!(definition.maker_fee, dec!(-0.005));
+        assert_eq!(definition.latency_ms, INITIAL_LATENCY_MS);
+        // Cheap shares pay the same fee per share, so more per dollar
+        assert_eq!(venue.fee_rate("yes", dec!(0.90), false), dec!(0.02) * dec!(0.10) / dec!(0.90));
+    }
+}
@@ -0,0 +1,226 @@
+//! Intraday volume profile for schedule-based slicing
+//!
+//! A VWAP-style schedule sizes its slices by how much of the day's volume
+//! usually trades in each. Rather than a dense per-slice vector,
+//! [`VolumeCurve`] takes a handful of (time of day, relative volume)
+//! control points, e.g. an opening spike, a midday lull and a closing
+//! spike, and interpolates between them, piecewise-linearly or with a
+//! monotone cubic that doesn't overshoot the points.
+
+use crate::error::{BotError, Result};
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// Curve samples averaged into each slice's weight
+const SAMPLES_PER_SLICE: usize = 16;
+
+/// How a [`VolumeCurve`] fills in between its control points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveInterpolation {
+    /// Straight lines between points
+    #[default]
+    Linear,
+    /// Smooth, and monotone between each pair of points (Fritsch-Carlson),
+    /// so it never leaves the range of its neighbours
+    MonotoneCubic,
+}
+
+/// Relative volume by time of day, interpolated from control points
+#[derive(Debug, Clone)]
+pub struct VolumeCurve {
+    /// (seconds since midnight, relative volume), by time
+    points: Vec<(f64, f64)>,
+    /// Curve slope at each point, for the cubic
+    slopes: Vec<f64>,
+    interpolation: CurveInterpolation,
+}
+
+fn seconds(time: NaiveTime) -> f64 {
+    time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1e9
+}
+
+impl VolumeCurve {
+    /// Curve through `points`; volumes must be finite and non-negative,
+    /// times distinct. Flat before the first point and after the last.
+    pub fn new(points: &[(NaiveTime, f64)], interpolation: CurveInterpolation) -> Result<Self> {
+        if points.is_empty() {
+            return Err(BotError::Config("volume curve needs at least one point".into()));
+        }
+        if let Some((time, volume)) = points.iter().find(|(_, v)| !v.is_finite() || *v < 0.0) {
+            return Err(BotError::Config(format!("volume curve point at {} has volume {}", time, volume)));
+        }
+        let mut points: Vec<(f64, f64)> = points.iter().map(|&(t, v)| (seconds(t), v)).collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if points.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(BotError::Config("volume curve has two points at the same time".into()));
+        }
+
+        let slopes = match interpolation {
+            CurveInterpolation::Linear => Vec::new(),
+            CurveInterpolation::MonotoneCubic => monotone_slopes(&points),
+        };
+        Ok(Self { points, slopes, interpolation })
+    }
+
+    /// Relative volume at `time`
+    pub fn volume_at(&self, time: NaiveTime) -> f64 {
+        self.value(seconds(time))
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if x <= first.0 {
+            return first.1;
+        }
+        if x >= last.0 {
+            return last.1;
+        }
+        // Segment k spans points k and k + 1
+        let k = self.points.partition_point(|p| p.0 <= x) - 1;
+        let ((x0, y0), (x1, y1)) = (self.points[k], self.points[k + 1]);
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let y = match self.interpolation {
+            CurveInterpolation::Linear => y0 + (y1 - y0) * t,
+            CurveInterpolation::MonotoneCubic => {
+                let (t2, t3) = (t * t, t * t * t);
+                (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                    + (t3 - 2.0 * t2 + t) * h * self.slopes[k]
+                    + (-2.0 * t3 + 3.0 * t2) * y1
+                    + (t3 - t2) * h * self.slopes[k + 1]
+            }
+        };
+        y.max(0.0)
+    }
+
+    /// Weights of `slices` equal slices from `start` to `end` (past
+    /// midnight when `end` isn't after `start`): each slice's share of the
+    /// curve's volume, non-negative and summing to 1. Equal weights where
+    /// the curve has no volume.
+    pub fn slice_weights(&self, start: NaiveTime, end: NaiveTime, slices: usize) -> Vec<f64> {
+        if slices == 0 {
+            return Vec::new();
+        }
+        let from = seconds(start);
+        let mut span = seconds(end) - from;
+        if span <= 0.0 {
+            span += SECS_PER_DAY;
+        }
+        let width = span / slices as f64;
+
+        let volumes: Vec<f64> = (0..slices)
+            .map(|i| {
+                (0..SAMPLES_PER_SLICE)
+                    .map(|j| {
+                        let x = from + width * (i as f64 + (j as f64 + 0.5) / SAMPLES_PER_SLICE as f64);
+                        self.value(x % SECS_PER_DAY)
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        let total: f64 = volumes.iter().sum();
+        if total <= 0.0 {
+            return vec![1.0 / slices as f64; slices];
+        }
+        volumes.into_iter().map(|v| v / total).collect()
+    }
+}
+
+/// Slopes at each point that keep a cubic Hermite curve monotone between
+/// every pair of points (Fritsch-Butland); flat at local extrema
+fn monotone_slopes(points: &[(f64, f64)]) -> Vec<f64> {
+    let n = points.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let h: Vec<f64> = points.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    let d: Vec<f64> = points.windows(2).zip(&h).map(|(w, h)| (w[1].1 - w[0].1) / h).collect();
+
+    let mut slopes = vec![0.0; n];
+    slopes[0] = d[0];
+    slopes[n - 1] = d[n - 2];
+    for k in 1..n - 1 {
+        if d[k - 1] * d[k] > 0.0 {
+            let (h0, h1) = (h[k - 1], h[k]);
+            slopes[k] = 3.0 * (h0 + h1) / ((2.0 * h1 + h0) / d[k - 1] + (h1 + 2.0 * h0) / d[k]);
+        }
+    }
+    slopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// Opening spike, midday lull, closing spike
+    fn u_shape() -> Vec<(NaiveTime, f64)> {
+        vec![(at(9, 30), 3.0), (at(10, 30), 1.2), (at(12, 30), 0.6), (at(15, 0), 1.0), (at(16, 0), 2.5)]
+    }
+
+    #[test]
+    fn test_control_points_reproduced_exactly() {
+        for interpolation in [CurveInterpolation::Linear, CurveInterpolation::MonotoneCubic] {
+            let curve = VolumeCurve::new(&u_shape(), interpolation).unwrap();
+            for (time, volume) in u_shape() {
+                assert_eq!(curve.volume_at(time), volume, "{:?} at {}", interpolation, time);
+            }
+            // Flat outside the points
+            assert_eq!(curve.volume_at(at(8, 0)), 3.0);
+            assert_eq!(curve.volume_at(at(17, 0)), 2.5);
+        }
+    }
+
+    #[test]
+    fn test_linear_and_monotone_between_points() {
+        let linear = VolumeCurve::new(&u_shape(), CurveInterpolation::Linear).unwrap();
+        assert!((linear.volume_at(at(10, 0)) - 2.1).abs() < 1e-12);
+
+        // The cubic stays within each pair of points
+        let cubic = VolumeCurve::new(&u_shape(), CurveInterpolation::MonotoneCubic).unwrap();
+        let points = u_shape();
+        for pair in points.windows(2) {
+            let (lo, hi) = (pair[0].1.min(pair[1].1), pair[0].1.max(pair[1].1));
+            let (from, to) = (seconds(pair[0].0), seconds(pair[1].0));
+            for i in 0..=100 {
+                let v = cubic.value(from + (to - from) * i as f64 / 100.0);
+                assert!((lo - 1e-12..=hi + 1e-12).contains(&v), "{} outside {}..{}", v, lo, hi);
+            }
+        }
+    }
+
+    #[test]
+    fn test_slice_weights_normalize() {
+        for interpolation in [CurveInterpolation::Linear, CurveInterpolation::MonotoneCubic] {
+            let curve = VolumeCurve::new(&u_shape(), interpolation).unwrap();
+            let weights = curve.slice_weights(at(9, 30), at(16, 0), 13);
+            assert_eq!(weights.len(), 13);
+            assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert!(weights.iter().all(|w| *w >= 0.0));
+            // Heavier at the open and close than at midday
+            assert!(weights[0] > weights[6] && weights[12] > weights[6]);
+        }
+
+        // Across midnight, and with no volume at all
+        let curve = VolumeCurve::new(&u_shape(), CurveInterpolation::Linear).unwrap();
+        assert!((curve.slice_weights(at(22, 0), at(2, 0), 8).iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        let empty = VolumeCurve::new(&[(at(9, 0), 0.0), (at(17, 0), 0.0)], CurveInterpolation::MonotoneCubic).unwrap();
+        assert_eq!(empty.slice_weights(at(9, 0), at(17, 0), 4), vec![0.25; 4]);
+    }
+
+    #[test]
+    fn test_rejects_bad_points() {
+        assert!(VolumeCurve::new(&[], CurveInterpolation::Linear).is_err());
+        assert!(VolumeCurve::new(&[(at(9, 0), -1.0)], CurveInterpolation::Linear).is_err());
+        assert!(VolumeCurve::new(&[(at(9, 0), 1.0), (at(9, 0), 2.0)], CurveInterpolation::Linear).is_err());
+        // Unsorted points are fine
+        let curve = VolumeCurve::new(&[(at(12, 0), 1.0), (at(9, 0), 3.0)], CurveInterpolation::Linear).unwrap();
+        assert!((curve.volume_at(at(10, 30)) - 2.0).abs() < 1e-12);
+    }
+}
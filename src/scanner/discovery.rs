@@ -0,0 +1,282 @@
+//! New market discovery
+//!
+//! The main scan only covers the highest-volume markets, but new markets
+//! often misprice in their first hours, before they get there.
+//! [`MarketDiscovery`] polls for markets created within `max_age_hours`
+//! above the volume and liquidity floors, gives each one LLM first look
+//! (stored, so a market is analyzed at discovery only once), and sends a
+//! Telegram digest of the largest absolute edges. Markets whose edge reaches
+//! `auto_add_edge` go on a [`Watchlist`] the main scan includes.
+
+use crate::client::{GammaClient, MarketFilter};
+use crate::config::DiscoveryConfig;
+use crate::error::Result;
+use crate::model::EnsembleModel;
+use crate::notify::Notifier;
+use crate::storage::{Database, DiscoveredMarket};
+use crate::types::Market;
+use chrono::{Duration, Utc};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Market IDs added to the scan set by discovery
+#[derive(Debug, Clone, Default)]
+pub struct Watchlist(Arc<RwLock<Vec<String>>>);
+
+impl Watchlist {
+    pub fn add(&self, market_id: &str) {
+        let mut ids = self.0.write();
+        if !ids.iter().any(|id| id == market_id) {
+            ids.push(market_id.to_string());
+        }
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.0.read().clone()
+    }
+
+    /// Current state of watched markets not already in `scanned`; markets
+    /// that closed or can no longer be fetched leave the watchlist
+    pub async fn fetch(&self, gamma: &GammaClient, scanned: &[Market]) -> Vec<Market> {
+        let mut markets = Vec::new();
+        for id in self.ids() {
+            if scanned.iter().any(|m| m.id == id) {
+                continue;
+            }
+            match gamma.get_market(&id).await {
+                Ok(market) if market.active && !market.closed => markets.push(market),
+                Ok(_) => self.remove(&id),
+                Err(e) => {
+                    tracing::warn!("Dropping discovered market {} from the scan: {}", id, e);
+                    self.remove(&id);
+                }
+            }
+        }
+        markets
+    }
+
+    fn remove(&self, market_id: &str) {
+        self.0.write().retain(|id| id != market_id);
+    }
+}
+
+/// Polls for new markets and takes a first look at each
+pub struct MarketDiscovery {
+    gamma: GammaClient,
+    model: Arc<EnsembleModel>,
+    db: Arc<Database>,
+    notifier: Option<Arc<Notifier>>,
+    config: DiscoveryConfig,
+    watchlist: Watchlist,
+}
+
+impl MarketDiscovery {
+    pub fn new(gamma: GammaClient, model: Arc<EnsembleModel>, db: Arc<Database>, config: DiscoveryConfig) -> Self {
+        Self {
+            gamma,
+            model,
+            db,
+            notifier: None,
+            config,
+            watchlist: Watchlist::default(),
+        }
+    }
+
+    /// Send a digest of each poll's discoveries
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Markets auto-added to the scan set
+    pub fn watchlist(&self) -> Watchlist {
+        self.watchlist.clone()
+    }
+
+    /// Poll every `interval_secs` in the background
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(self.config.interval_secs));
+            loop {
+                interval.tick().await;
+                match self.poll().await {
+                    Ok(found) if !found.is_empty() => {
+                        tracing::info!("🆕 Discovered {} new markets", found.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Market discovery failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Fetch markets created within `max_age_hours` and analyze the new ones
+    pub async fn poll(&self) -> Result<Vec<DiscoveredMarket>> {
+        let filter = MarketFilter {
+            min_liquidity: self.config.min_liquidity,
+            min_volume: self.config.min_volume,
+            ..MarketFilter::default()
+        };
+        let since = Utc::now() - Duration::hours(self.config.max_age_hours);
+        let candidates = self.gamma.get_markets_created_since(since, &filter, 500).await?;
+        self.analyze(candidates).await
+    }
+
+    /// First look at up to `max_markets` of `candidates` not seen before,
+    /// largest absolute edge first. Markets no model could price are left
+    /// for the next poll.
+    pub async fn analyze(&self, candidates: Vec<Market>) -> Result<Vec<DiscoveredMarket>> {
+        let mut found = Vec::new();
+        for market in candidates {
+            if found.len() >= self.config.max_markets {
+                break;
+            }
+            let Some(market_price) = market.yes_price() else {
+                continue;
+            };
+            if self.db.is_market_discovered(&market.id).await? {
+                continue;
+            }
+            let prediction = self.model.predict(&market).await?;
+            if prediction.confidence.is_zero() {
+                tracing::debug!("No first look at {}: {}", market.id, prediction.reasoning);
+                continue;
+            }
+
+            let discovered = DiscoveredMarket {
+                market_id: market.id.clone(),
+                question: market.question.clone(),
+                discovered_at: Utc::now(),
+                volume: market.volume,
+                liquidity: market.liquidity,
+                market_price,
+                model_probability: prediction.probability,
+                confidence: prediction.confidence,
+                reasoning: prediction.reasoning,
+            };
+            self.db.save_discovered_market(&discovered).await?;
+            found.push(discovered);
+        }
+        found.sort_by_key(|m| std::cmp::Reverse(m.edge().abs()));
+
+        let added: Vec<String> = match self.config.auto_add_edge {
+            Some(threshold) => found
+                .iter()
+                .filter(|m| m.edge().abs() >= threshold)
+                .map(|m| m.market_id.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+        for id in &added {
+            self.watchlist.add(id);
+        }
+
+        if let Some(notifier) = &self.notifier {
+            let digest = &found[..found.len().min(self.config.digest_size)];
+            if !digest.is_empty() {
+                if let Err(e) = notifier.markets_discovered(digest, &added).await {
+                    tracing::warn!("Failed to send discovery digest: {}", e);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Prediction, ProbabilityModel};
+    use crate::types::Outcome;
+    use async_trait::async_trait;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    /// Prices every market at a fixed probability and counts its calls
+    struct FixedModel {
+        probability: Decimal,
+        calls: Arc<RwLock<usize>>,
+    }
+
+    #[async_trait]
+    impl ProbabilityModel for FixedModel {
+        async fn predict(&self, _market: &Market) -> Result<Prediction> {
+            *self.calls.write() += 1;
+            Ok(Prediction {
+                probability: self.probability,
+                confidence: dec!(0.7),
+                reasoning: "fixed".to_string(),
+                fallback_used: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    fn market(id: &str, yes_price: Decimal) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("Will {} happen?", id),
+            description: None,
+            end_date: None,
+            volume: dec!(50000),
+            liquidity: dec!(10000),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    async fn discovery(config: DiscoveryConfig) -> (MarketDiscovery, Arc<RwLock<usize>>, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::connect(dir.path().join("test.db")).await.unwrap());
+        let calls = Arc::new(RwLock::new(0));
+        let mut model = EnsembleModel::new();
+        model.add_model(Box::new(FixedModel { probability: dec!(0.60), calls: calls.clone() }), Decimal::ONE);
+        let gamma = GammaClient::new("http://127.0.0.1:9").unwrap();
+        (MarketDiscovery::new(gamma, Arc::new(model), db, config), calls, dir)
+    }
+
+    #[tokio::test]
+    async fn test_new_markets_are_analyzed_once_by_edge() {
+        let (discovery, calls, _dir) = discovery(DiscoveryConfig::default()).await;
+        let candidates = vec![market("small", dec!(0.55)), market("large", dec!(0.30)), market("mid", dec!(0.75))];
+
+        let found = discovery.analyze(candidates.clone()).await.unwrap();
+        let ids: Vec<&str> = found.iter().map(|m| m.market_id.as_str()).collect();
+        assert_eq!(ids, ["large", "mid", "small"]);
+        assert_eq!(found[0].edge(), dec!(0.30));
+        assert_eq!(*calls.read(), 3);
+
+        // Already stored: not analyzed again
+        assert!(discovery.analyze(candidates).await.unwrap().is_empty());
+        assert_eq!(*calls.read(), 3);
+        let stored = discovery.db.get_discovered_markets(Utc::now() - Duration::hours(1)).await.unwrap();
+        assert_eq!(stored.len(), 3);
+        assert!(discovery.watchlist().ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_large_edges_join_the_watchlist() {
+        let config = DiscoveryConfig {
+            auto_add_edge: Some(dec!(0.15)),
+            max_markets: 2,
+            ..DiscoveryConfig::default()
+        };
+        let (discovery, _, _dir) = discovery(config).await;
+        let candidates = vec![market("small", dec!(0.55)), market("large", dec!(0.30)), market("capped", dec!(0.10))];
+
+        let found = discovery.analyze(candidates).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(discovery.watchlist().ids(), ["large"]);
+    }
+}
@@ -8,9 +8,13 @@ mod continuous;
 mod cross_price_arb;
 mod crypto_market;
 mod crypto15m_monitor;
+mod discovery;
 mod indicators;
 mod negative_risk;
+mod pinned;
+mod pipeline;
 mod realtime;
+mod step;
 
 pub use arbitrage_loop::{ArbitrageLoop, ArbitrageLoopConfig, LoopStats};
 pub use continuous::ContinuousScanner;
@@ -26,8 +30,12 @@ pub use indicators::{
     RSI, StochRSI, StochRSIResult, SignalType, analyze_signal,
     SpikeDetector, SpikeConfig, SpikeEvent, SpikeType,
 };
-pub use negative_risk::NegativeRiskScanner;
+pub use discovery::{MarketDiscovery, Watchlist};
+pub use pinned::PinnedMarkets;
+pub use pipeline::{analyze_markets, DEFAULT_ANALYSIS_TIMEOUT_SECS, DEFAULT_MAX_CONCURRENT_ANALYSES};
+pub use negative_risk::{best_basket, Basket, NegativeRiskOpp, NegativeRiskScanner};
 pub use realtime::RealtimeArbitrageScanner;
+pub use step::{execute_or_rest, execute_signal, fetch_balances, record_execution};
 pub use cross_price_arb::{
     CrossPriceScanner, CrossPriceConfig, CrossPriceOpp,
     CrossPricePaperTrader, CompletedTrade as CrossPriceCompletedTrade,
@@ -1,10 +1,15 @@
 //! Negative Risk Market Scanner
 //!
 //! Scans multi-outcome markets (e.g., "Who will win the election?")
-//! for arbitrage opportunities where sum of all Yes prices < 1.0
+//! for arbitrage opportunities across the whole event:
 //!
-//! Strategy: Buy Yes on ALL candidates. One must win, guaranteeing $1 payout.
-//! If total cost < $1, profit = $1 - total_cost
+//! - YES basket: buy Yes on ALL candidates. One must win, guaranteeing $1
+//!   payout. If total cost < $1, profit = $1 - total_cost
+//! - NO basket: buy No on ALL N candidates. All but one resolve No,
+//!   guaranteeing $(N-1). If total cost < N-1, profit = N-1 - total_cost
+//!
+//! Either basket is only riskless when every market of the event is bought,
+//! so events with an unpriced market are skipped.
 
 use super::{ScannerConfig};
 use crate::client::clob::ClobClient;
@@ -25,11 +30,15 @@ pub struct NegativeRiskOpp {
     pub event_title: String,
     /// All outcomes with prices
     pub outcomes: Vec<OutcomeInfo>,
+    /// Which side to buy on every market
+    pub basket: Basket,
     /// Sum of all Yes prices
     pub total_yes_price: Decimal,
-    /// Arbitrage value (1.0 - total_yes_price)
+    /// Sum of all No prices
+    pub total_no_price: Decimal,
+    /// Guaranteed payout minus basket cost, per basket
     pub arbitrage_value: Decimal,
-    /// Maximum size (min of all ask sizes)
+    /// Maximum size (min of the basket's ask sizes)
     pub max_size: u32,
     /// Net profit after fees
     pub net_profit: Decimal,
@@ -44,6 +53,36 @@ pub struct OutcomeInfo {
     pub token_id: String,
     pub yes_price: Decimal,
     pub yes_ask_size: u32,
+    pub no_token_id: String,
+    pub no_price: Decimal,
+    pub no_ask_size: u32,
+}
+
+/// Side bought on every market of the event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basket {
+    /// One Yes per market, pays $1
+    Yes,
+    /// One No per market, pays $(N-1)
+    No,
+}
+
+/// Pick the more profitable basket from each market's (yes_ask, no_ask).
+/// Returns the basket and its edge (guaranteed payout minus cost).
+pub fn best_basket(asks: &[(Decimal, Decimal)]) -> Option<(Basket, Decimal)> {
+    if asks.len() < 2 {
+        return None;
+    }
+    let total_yes: Decimal = asks.iter().map(|(yes, _)| *yes).sum();
+    let total_no: Decimal = asks.iter().map(|(_, no)| *no).sum();
+    let yes_edge = Decimal::ONE - total_yes;
+    let no_edge = Decimal::from(asks.len() as u32 - 1) - total_no;
+
+    Some(if no_edge > yes_edge {
+        (Basket::No, no_edge)
+    } else {
+        (Basket::Yes, yes_edge)
+    })
 }
 
 /// API response for negative risk events
@@ -113,8 +152,9 @@ impl NegativeRiskScanner {
             match self.check_event(&event).await {
                 Ok(Some(opp)) => {
                     info!(
-                        "[NegRisk] Found opportunity: {} value={:.2}%, profit=${:.4}",
+                        "[NegRisk] Found opportunity: {} ({:?} basket) value={:.2}%, profit=${:.4}",
                         opp.event_title,
+                        opp.basket,
                         opp.arbitrage_value * dec!(100),
                         opp.net_profit
                     );
@@ -155,10 +195,8 @@ impl NegativeRiskScanner {
             return Ok(None);
         }
 
-        // Get prices for each outcome
+        // Get prices for each outcome; the basket needs every market
         let mut outcomes = Vec::new();
-        let mut total_yes_price = dec!(0);
-        let mut min_ask_size: u32 = u32::MAX;
 
         for market in &detail.markets {
             // Parse token IDs
@@ -168,24 +206,16 @@ impl NegativeRiskScanner {
                 .and_then(|s| serde_json::from_str(s).ok())
                 .unwrap_or_default();
 
-            if token_ids.is_empty() {
-                continue;
-            }
-
-            let yes_token_id = &token_ids[0];
-
-            // Get orderbook for Yes token
-            let book = match self.clob.get_order_book(yes_token_id).await {
-                Ok(b) => b,
-                Err(_) => continue,
+            let [yes_token_id, no_token_id] = token_ids.as_slice() else {
+                return Ok(None);
             };
 
-            if book.asks.is_empty() {
-                continue;
-            }
-
-            let yes_price = book.asks[0].price;
-            let yes_size: u32 = book.asks[0].size.try_into().unwrap_or(0);
+            // Best ask for each side
+            let yes_book = self.clob.get_order_book(yes_token_id).await?;
+            let no_book = self.clob.get_order_book(no_token_id).await?;
+            let (Some(yes_ask), Some(no_ask)) = (yes_book.asks.first(), no_book.asks.first()) else {
+                return Ok(None);
+            };
 
             // Extract outcome name from question
             let name = extract_outcome_name(&market.question);
@@ -193,26 +223,31 @@ impl NegativeRiskScanner {
             outcomes.push(OutcomeInfo {
                 name,
                 token_id: yes_token_id.clone(),
-                yes_price,
-                yes_ask_size: yes_size,
+                yes_price: yes_ask.price,
+                yes_ask_size: yes_ask.size.try_into().unwrap_or(0),
+                no_token_id: no_token_id.clone(),
+                no_price: no_ask.price,
+                no_ask_size: no_ask.size.try_into().unwrap_or(0),
             });
-
-            total_yes_price += yes_price;
-            min_ask_size = min_ask_size.min(yes_size);
         }
 
-        // Need at least 2 outcomes
-        if outcomes.len() < 2 {
+        let asks: Vec<_> = outcomes.iter().map(|o| (o.yes_price, o.no_price)).collect();
+        let Some((basket, arbitrage_value)) = best_basket(&asks) else {
             return Ok(None);
-        }
-
-        // Check for arbitrage
-        let arbitrage_value = dec!(1) - total_yes_price;
+        };
         if arbitrage_value <= self.config.min_spread {
             return Ok(None);
         }
 
         // Check liquidity
+        let min_ask_size = outcomes
+            .iter()
+            .map(|o| match basket {
+                Basket::Yes => o.yes_ask_size,
+                Basket::No => o.no_ask_size,
+            })
+            .min()
+            .unwrap_or(0);
         if min_ask_size < self.config.min_liquidity {
             return Ok(None);
         }
@@ -228,11 +263,16 @@ impl NegativeRiskScanner {
             return Ok(None);
         }
 
+        let total_yes_price = asks.iter().map(|(yes, _)| *yes).sum();
+        let total_no_price = asks.iter().map(|(_, no)| *no).sum();
+
         Ok(Some(NegativeRiskOpp {
             event_id: detail.id,
             event_title: detail.title,
             outcomes,
+            basket,
             total_yes_price,
+            total_no_price,
             arbitrage_value,
             max_size: min_ask_size,
             net_profit,
@@ -273,4 +313,22 @@ mod tests {
 
         assert!(total > dec!(1));
     }
+
+    #[test]
+    fn test_best_basket_yes() {
+        let asks = [(dec!(0.30), dec!(0.72)), (dec!(0.30), dec!(0.72)), (dec!(0.32), dec!(0.70))];
+        assert_eq!(best_basket(&asks), Some((Basket::Yes, dec!(0.08))));
+    }
+
+    #[test]
+    fn test_best_basket_no() {
+        // NO asks sum to 1.90 for a guaranteed 2.00
+        let asks = [(dec!(0.36), dec!(0.62)), (dec!(0.36), dec!(0.62)), (dec!(0.36), dec!(0.66))];
+        assert_eq!(best_basket(&asks), Some((Basket::No, dec!(0.10))));
+    }
+
+    #[test]
+    fn test_best_basket_needs_two_markets() {
+        assert_eq!(best_basket(&[(dec!(0.5), dec!(0.4))]), None);
+    }
 }
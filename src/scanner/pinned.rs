@@ -0,0 +1,143 @@
+//! Markets the operator always wants scanned
+//!
+//! A thesis trade can sit in a market the top-N and liquidity filters never
+//! pick. [`PinnedMarkets`] starts from `[markets] watchlist` and changes at
+//! runtime with `/watch` and `/unwatch`; the main scan fetches every pinned
+//! market on its own, past the scan filters, and trades it under the same
+//! risk checks as any other.
+
+use crate::client::GammaClient;
+use crate::types::Market;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Market IDs scanned whatever the scan filters say
+#[derive(Debug, Clone, Default)]
+pub struct PinnedMarkets(Arc<RwLock<Vec<String>>>);
+
+impl PinnedMarkets {
+    pub fn new(market_ids: &[String]) -> Self {
+        let pinned = Self::default();
+        for id in market_ids {
+            pinned.add(id);
+        }
+        pinned
+    }
+
+    /// Pin a market; `false` when it already was
+    pub fn add(&self, market_id: &str) -> bool {
+        let mut ids = self.0.write();
+        if ids.iter().any(|id| id == market_id) {
+            return false;
+        }
+        ids.push(market_id.to_string());
+        true
+    }
+
+    /// Unpin a market; `false` when it was not pinned
+    pub fn remove(&self, market_id: &str) -> bool {
+        let mut ids = self.0.write();
+        let before = ids.len();
+        ids.retain(|id| id != market_id);
+        ids.len() != before
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.0.read().clone()
+    }
+
+    /// Current state of pinned markets not already in `scanned`. Closed
+    /// markets are unpinned; ones that fail to fetch stay pinned for the
+    /// next scan.
+    pub async fn fetch(&self, gamma: &GammaClient, scanned: &[Market]) -> Vec<Market> {
+        let mut markets = Vec::new();
+        for id in self.ids() {
+            if scanned.iter().any(|m| m.id == id) {
+                continue;
+            }
+            match gamma.get_market(&id).await {
+                Ok(market) if market.closed => {
+                    tracing::info!("Unpinning closed market {}", id);
+                    self.remove(&id);
+                }
+                Ok(market) => markets.push(market),
+                Err(e) => tracing::warn!("Failed to fetch pinned market {}: {}", id, e),
+            }
+        }
+        markets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MarketFilter;
+    use rust_decimal_macros::dec;
+
+    fn gamma_market(id: &str, liquidity: &str, closed: bool) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "question": format!("Market {}?", id),
+            "liquidity": liquidity,
+            "volume": "50000",
+            "active": true,
+            "closed": closed,
+        })
+    }
+
+    /// Gamma serving `markets` from `/markets` and `/markets/{id}`
+    async fn mock_gamma(markets: Vec<serde_json::Value>) -> String {
+        use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+
+        let list = markets.clone();
+        let app = Router::new()
+            .route("/markets", get(move || async move { Json(serde_json::Value::Array(list)) }))
+            .route(
+                "/markets/{id}",
+                get(move |Path(id): Path<String>| async move {
+                    markets
+                        .iter()
+                        .find(|m| m["id"] == id.as_str())
+                        .map(|m| Json(m.clone()))
+                        .ok_or(StatusCode::NOT_FOUND)
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_pinned_markets_bypass_the_liquidity_floor() {
+        let url = mock_gamma(vec![
+            gamma_market("liquid", "20000", false),
+            gamma_market("thin-pinned", "500", false),
+            gamma_market("thin", "500", false),
+            gamma_market("resolved", "500", true),
+        ])
+        .await;
+        let gamma = GammaClient::new(&url).unwrap();
+        let pinned = PinnedMarkets::new(&["thin-pinned".to_string(), "liquid".to_string(), "resolved".to_string()]);
+
+        let filter = MarketFilter { min_liquidity: dec!(10000), ..MarketFilter::default() };
+        let mut scanned = gamma.get_markets_filtered(&filter, 20).await.unwrap();
+        scanned.extend(pinned.fetch(&gamma, &scanned).await);
+
+        // "liquid" is scanned once, "thin" stays below the floor
+        let ids: Vec<&str> = scanned.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["liquid", "thin-pinned"]);
+        assert_eq!(pinned.ids(), ["thin-pinned", "liquid"]);
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let pinned = PinnedMarkets::new(&["a".to_string(), "a".to_string()]);
+        assert_eq!(pinned.ids(), ["a"]);
+        assert!(pinned.add("b"));
+        assert!(!pinned.add("b"));
+        assert!(pinned.remove("a"));
+        assert!(!pinned.remove("a"));
+        assert_eq!(pinned.ids(), ["b"]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Bounded-concurrency market analysis
+//!
+//! Model calls dominate a scan: analyzed one after another, a single slow
+//! LLM response stalls every market behind it. [`analyze_markets`] runs up
+//! to `max_concurrent` analyses at once and gives each a deadline, so a hung
+//! market costs one slot for at most `timeout` instead of the whole batch.
+//! Results come back in input order for the caller to apply sequentially.
+
+use crate::error::{BotError, Result};
+use crate::types::Market;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+
+/// Analyses run at once by default
+pub const DEFAULT_MAX_CONCURRENT_ANALYSES: usize = 4;
+
+/// Seconds a single market's analysis may take by default
+pub const DEFAULT_ANALYSIS_TIMEOUT_SECS: u64 = 60;
+
+/// Run `analyze` on each market, at most `max_concurrent` at a time (at
+/// least one), failing any that takes longer than `timeout`. The results are
+/// in the order of `markets`, whatever order the analyses finish in.
+pub async fn analyze_markets<'a, T, F, Fut>(
+    markets: impl IntoIterator<Item = &'a Market>,
+    max_concurrent: usize,
+    timeout: Duration,
+    analyze: F,
+) -> Vec<(&'a Market, Result<T>)>
+where
+    F: Fn(&'a Market) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let analyze = &analyze;
+    let markets: Vec<&'a Market> = markets.into_iter().collect();
+    let mut results: Vec<Option<Result<T>>> = markets.iter().map(|_| None).collect();
+    let mut pending = markets.iter().copied().enumerate();
+    // An explicit queue rather than `buffer_unordered`, whose closure isn't
+    // general enough over lifetimes for `async_trait` callers
+    let mut in_flight = FuturesUnordered::new();
+    loop {
+        while in_flight.len() < max_concurrent.max(1) {
+            let Some((index, market)) = pending.next() else {
+                break;
+            };
+            in_flight.push(async move {
+                let result = match tokio::time::timeout(timeout, analyze(market)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(BotError::Api(format!(
+                        "analysis of {} timed out after {}s",
+                        market.id,
+                        timeout.as_secs_f64()
+                    ))),
+                };
+                (index, result)
+            });
+        }
+        let Some((index, result)) = in_flight.next().await else {
+            break;
+        };
+        results[index] = Some(result);
+    }
+    markets
+        .into_iter()
+        .zip(results)
+        .filter_map(|(market, result)| Some((market, result?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn market(id: &str) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: Vec::new(),
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_markets_are_analyzed_concurrently_in_order() {
+        let markets: Vec<Market> = (0..8).map(|i| market(&format!("m{}", i))).collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let start = tokio::time::Instant::now();
+
+        let results = analyze_markets(&markets, 4, Duration::from_secs(5), |market| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                peak.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                // Later markets answer sooner, so completion order is reversed
+                let index: u64 = market.id[1..].parse().unwrap();
+                tokio::time::sleep(Duration::from_millis(200 - 10 * index)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(market.id.clone())
+            }
+        })
+        .await;
+        let elapsed = start.elapsed();
+
+        // 8 analyses of ~200ms, 4 at a time: about 400ms rather than 1.6s
+        assert!(elapsed >= Duration::from_millis(300), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(800), "{:?}", elapsed);
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        let ids: Vec<String> = results.into_iter().map(|(_, r)| r.unwrap()).collect();
+        assert_eq!(ids, ["m0", "m1", "m2", "m3", "m4", "m5", "m6", "m7"]);
+    }
+
+    #[tokio::test]
+    async fn test_hung_market_times_out_without_blocking_the_batch() {
+        let markets = vec![market("hung"), market("a"), market("b")];
+        let start = tokio::time::Instant::now();
+
+        let results = analyze_markets(&markets, 2, Duration::from_millis(200), |market| async move {
+            if market.id == "hung" {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        })
+        .await;
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(matches!(&results[0].1, Err(BotError::Api(msg)) if msg.contains("timed out")));
+        assert!(results[1].1.is_ok() && results[2].1.is_ok());
+        assert_eq!(results[2].0.id, "b");
+    }
+}
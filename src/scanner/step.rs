@@ -0,0 +1,116 @@
+//! Steps of the main scan loop
+//!
+//! Each scan fetches the accounts' balances, then trades the scan's
+//! signals. The API health both steps observe is recorded with the
+//! [`RiskManager`], whose failure streaks pause trading, and failures are
+//! reported through the [`Notifier`].
+
+use crate::client::mock::ClobClientTrait;
+use crate::config::TwoPhaseConfig;
+use crate::error::Result;
+use crate::executor::Executor;
+use crate::notify::Notifier;
+use crate::risk::RiskManager;
+use crate::strategy::RestingOrderStrategy;
+use crate::types::{Signal, Trade};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::Mutex;
+
+/// Balance of each `(account id, client)`, leaving out the accounts whose
+/// balance can't be fetched; each fetch is recorded with `risk` and each
+/// failure reported to `notifier`, if given
+pub async fn fetch_balances<'a, C>(
+    clients: &[(&'a str, &'a C)],
+    risk: &Mutex<RiskManager>,
+    notifier: Option<&Notifier>,
+) -> HashMap<String, Decimal>
+where
+    C: ClobClientTrait + ?Sized,
+{
+    let mut balances = HashMap::new();
+    for (account, client) in clients {
+        match client.get_balance().await {
+            Ok(balance) => {
+                risk.lock().await.record_api_success();
+                balances.insert(account.to_string(), balance);
+            }
+            Err(e) => {
+                tracing::error!("Failed to get balance of {}: {}", account, e);
+                risk.lock().await.record_api_failure(&e);
+                if let Some(notifier) = notifier {
+                    let context = if clients.len() > 1 {
+                        format!("Balance fetch ({})", account)
+                    } else {
+                        "Balance fetch".to_string()
+                    };
+                    let _ = notifier.error(&context, &e.to_string()).await;
+                }
+            }
+        }
+    }
+    balances
+}
+
+/// Execute a signal, probing liquidity first when two-phase execution is
+/// configured and the order is large and only opens a position
+pub async fn execute_signal<C: ClobClientTrait>(
+    executor: &Executor<C>,
+    two_phase: Option<&TwoPhaseConfig>,
+    signal: &Signal,
+    balance: Decimal,
+) -> Result<Vec<Trade>> {
+    let size_usd = signal.suggested_size * balance;
+    let Some(config) = two_phase.filter(|c| size_usd >= c.min_size_for_probe_usdc) else {
+        return executor.execute(signal, balance).await;
+    };
+    let plan = executor.plan_order(signal, size_usd / signal.market_probability).await?;
+    if plan.reduce.is_some() {
+        return executor.execute(signal, balance).await;
+    }
+    tracing::info!(
+        "Probing liquidity for ${:.0} order on {} ({:.0}% first)",
+        size_usd,
+        signal.market_id,
+        config.probe_fraction * Decimal::from(100)
+    );
+    Ok(executor.execute_two_phase(signal, balance).await?.into_iter().collect())
+}
+
+/// Rest a signal as a limit order when its book is too wide to cross,
+/// otherwise execute it
+pub async fn execute_or_rest<C: ClobClientTrait>(
+    executor: &Executor<C>,
+    two_phase: Option<&TwoPhaseConfig>,
+    resting: Option<&mut RestingOrderStrategy>,
+    signal: &Signal,
+    balance: Decimal,
+) -> Result<Vec<Trade>> {
+    if let Some(resting) = resting {
+        if let Some(trades) = resting.place(executor, signal, balance).await? {
+            return Ok(trades);
+        }
+    }
+    execute_signal(executor, two_phase, signal, balance).await
+}
+
+/// Await an execution, recording its outcome with `risk` and reporting a
+/// failure to `notifier`, if given
+pub async fn record_execution<T>(
+    execution: impl Future<Output = Result<T>>,
+    risk: &Mutex<RiskManager>,
+    notifier: Option<&Notifier>,
+) -> Result<T> {
+    let result = execution.await;
+    match &result {
+        Ok(_) => risk.lock().await.record_api_success(),
+        Err(e) => {
+            risk.lock().await.record_api_failure(e);
+            if let Some(notifier) = notifier {
+                let _ = notifier.error("Trade execution", &e.to_string()).await;
+            }
+        }
+    }
+    result
+}
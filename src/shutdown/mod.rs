@@ -0,0 +1,446 @@
+//! Graceful shutdown handling
+//!
+//! Listens for SIGINT/SIGTERM and unwinds the bot before exit:
+//! - Flags `BotState::shutdown_requested` so the trading loop stops opening positions
+//! - Cancels all resting CLOB orders
+//! - Optionally flattens open positions at market, retrying closes that
+//!   don't fill until the account is flat or the timeout elapses
+//! - Sends a Telegram summary and checkpoints the portfolio to the database
+
+use crate::client::mock::ClobClientTrait;
+use crate::config::ShutdownConfig;
+use crate::executor::Executor;
+use crate::notify::Notifier;
+use crate::storage::{Database, HoldingsSnapshot};
+use crate::telegram::BotState;
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Pause between attempts to close the positions left open
+const CLOSE_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shutdown coordinator; the cleanup sequence runs at most once
+pub struct GracefulShutdown {
+    /// Close open positions at market on shutdown
+    pub close_positions: bool,
+    /// Maximum time to spend closing positions
+    pub timeout_secs: u64,
+    triggered: AtomicBool,
+}
+
+/// Outcome of the shutdown sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShutdownReport {
+    pub cancelled_orders: u32,
+    pub closed_positions: u32,
+    pub final_balance: Decimal,
+}
+
+impl ShutdownReport {
+    /// Telegram summary message
+    pub fn message(&self) -> String {
+        format!(
+            "🔴 Bot shutdown complete. Cancelled {} orders, closed {} positions. Final balance: ${:.2}",
+            self.cancelled_orders, self.closed_positions, self.final_balance
+        )
+    }
+}
+
+impl GracefulShutdown {
+    pub fn new(close_positions: bool, timeout_secs: u64) -> Self {
+        Self {
+            close_positions,
+            timeout_secs,
+            triggered: AtomicBool::new(false),
+        }
+    }
+
+    pub fn from_config(config: &ShutdownConfig) -> Self {
+        Self::new(config.close_positions, config.timeout_secs)
+    }
+
+    /// Wait for SIGINT or SIGTERM, returning the signal name
+    pub async fn wait_for_signal() -> &'static str {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            match signal(SignalKind::terminate()) {
+                Ok(mut sigterm) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => "SIGINT",
+                        _ = sigterm.recv() => "SIGTERM",
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to register SIGTERM handler: {}", e);
+                    let _ = tokio::signal::ctrl_c().await;
+                    "SIGINT"
+                }
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+            "SIGINT"
+        }
+    }
+
+    /// Claim the shutdown; returns false if it was already triggered
+    pub fn trigger(&self) -> bool {
+        self.triggered
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Whether shutdown has been triggered
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Run the shutdown sequence for every account's executor.
+    ///
+    /// Returns `None` if shutdown was already triggered elsewhere. Individual
+    /// step failures are logged and do not abort the remaining steps. The
+    /// report sums all accounts.
+    pub async fn run<C: ClobClientTrait>(
+        &self,
+        state: &RwLock<BotState>,
+        executors: &[Arc<Executor<C>>],
+        notifier: &Notifier,
+        db: &Database,
+    ) -> Option<ShutdownReport> {
+        if !self.trigger() {
+            return None;
+        }
+
+        state.write().await.shutdown_requested = true;
+        tracing::info!("Shutdown requested, cleaning up...");
+
+        let mut report = ShutdownReport {
+            cancelled_orders: 0,
+            closed_positions: 0,
+            final_balance: Decimal::ZERO,
+        };
+        for executor in executors {
+            let account = self.unwind(executor, db).await;
+            report.cancelled_orders += account.cancelled_orders;
+            report.closed_positions += account.closed_positions;
+            report.final_balance += account.final_balance;
+        }
+
+        if let Err(e) = notifier.send(&report.message()).await {
+            tracing::warn!("Failed to send shutdown notification: {}", e);
+        }
+
+        tracing::info!(
+            "Shutdown complete: {} orders cancelled, {} positions closed",
+            report.cancelled_orders,
+            report.closed_positions
+        );
+
+        Some(report)
+    }
+
+    /// Cancel, optionally flatten and checkpoint one account
+    async fn unwind<C: ClobClientTrait>(&self, executor: &Executor<C>, db: &Database) -> ShutdownReport {
+        let cancelled_orders = match executor.clob.cancel_all_orders().await {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!("Failed to cancel open orders: {}", e);
+                0
+            }
+        };
+
+        let closed_positions = if self.close_positions {
+            self.close_until_flat(executor).await
+        } else {
+            0
+        };
+
+        let final_balance = executor.clob.get_balance().await.unwrap_or(Decimal::ZERO);
+
+        let snapshot = HoldingsSnapshot {
+            timestamp: chrono::Utc::now(),
+            balance: final_balance,
+            positions: executor.get_positions().await,
+            label: "shutdown".to_string(),
+            account_id: executor.account_id().map(str::to_string),
+        };
+        if let Err(e) = db.save_holdings_snapshot(&snapshot).await {
+            tracing::warn!("Failed to save shutdown checkpoint: {}", e);
+        }
+
+        ShutdownReport {
+            cancelled_orders,
+            closed_positions,
+            final_balance,
+        }
+    }
+
+    /// Close positions at market until none are left or the timeout
+    /// elapses, returning the number closed. Fill-or-kill closes that find
+    /// too little depth are killed, so what's still held is retried.
+    async fn close_until_flat<C: ClobClientTrait>(&self, executor: &Executor<C>) -> u32 {
+        let mut closed = 0;
+        let close = async {
+            loop {
+                match executor.close_all_positions_at_market().await {
+                    Ok(n) => closed += n,
+                    Err(e) => tracing::warn!("Failed to close positions: {}", e),
+                }
+                if executor.get_positions().await.values().all(|size| size.is_zero()) {
+                    break;
+                }
+                tokio::time::sleep(CLOSE_RETRY_INTERVAL).await;
+            }
+        };
+
+        if tokio::time::timeout(Duration::from_secs(self.timeout_secs), close)
+            .await
+            .is_err()
+        {
+            let open = executor.get_positions().await.len();
+            tracing::warn!(
+                "Timed out after {}s closing positions, {} still open",
+                self.timeout_secs,
+                open
+            );
+        }
+        closed
+    }
+}
+
+impl Default for GracefulShutdown {
+    fn default() -> Self {
+        Self::from_config(&ShutdownConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RiskConfig;
+    use crate::testing::{MockClob, Scenario};
+    use crate::types::{Order, OrderType, Side};
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    /// Executor holding `positions` on `clob`, as restored after a restart
+    async fn holding(clob: Arc<MockClob>, positions: &[(&str, Decimal)]) -> Arc<Executor<Arc<MockClob>>> {
+        let executor = Executor::new(clob, RiskConfig::default());
+        executor
+            .restore_positions(positions.iter().map(|(token, size)| (token.to_string(), *size)).collect())
+            .await;
+        Arc::new(executor)
+    }
+
+    async fn unwind(
+        shutdown: &GracefulShutdown,
+        executor: &Arc<Executor<Arc<MockClob>>>,
+        db: &Database,
+    ) -> (ShutdownReport, RwLock<BotState>) {
+        let state = RwLock::new(BotState::default());
+        let report = shutdown
+            .run(&state, std::slice::from_ref(executor), &Notifier::disabled(), db)
+            .await
+            .unwrap();
+        (report, state)
+    }
+
+    #[tokio::test]
+    async fn test_run_cancels_resting_orders() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let clob = Arc::new(
+            Scenario::new()
+                .default_book(&[(dec!(0.40), dec!(100))], &[(dec!(0.60), dec!(100))])
+                .build(),
+        );
+        let resting = clob
+            .submit(&Order {
+                token_id: "t1".to_string(),
+                side: Side::Buy,
+                price: dec!(0.45),
+                size: dec!(10),
+                order_type: OrderType::GTC,
+            })
+            .unwrap();
+        let executor = holding(clob.clone(), &[]).await;
+
+        let shutdown = GracefulShutdown::new(true, 5);
+        let (report, state) = unwind(&shutdown, &executor, &db).await;
+
+        assert!(state.read().await.shutdown_requested);
+        assert_eq!(report.cancelled_orders, 1);
+        assert_eq!(report.closed_positions, 0);
+        assert_eq!(clob.order(&resting.order_id).unwrap().status, "CANCELLED");
+        assert!(clob.fills().is_empty());
+
+        // Only the first caller unwinds
+        let state = RwLock::new(BotState::default());
+        assert!(shutdown.run(&state, &[executor], &Notifier::disabled(), &db).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_closes_positions_at_market() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let clob = Arc::new(
+            Scenario::new()
+                .balance(dec!(500))
+                .position("t1", dec!(20))
+                .default_book(&[(dec!(0.40), dec!(100))], &[(dec!(0.60), dec!(100))])
+                .build(),
+        );
+        let executor = holding(clob.clone(), &[("t1", dec!(20))]).await;
+
+        let (report, _) = unwind(&GracefulShutdown::new(true, 5), &executor, &db).await;
+
+        assert_eq!(report.closed_positions, 1);
+        let fills = clob.fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].side, fills[0].price, fills[0].size), (Side::Sell, dec!(0.40), dec!(20)));
+        assert!(executor.get_positions().await.is_empty());
+        assert_eq!(report.final_balance, clob.balance());
+
+        let snapshot = db.latest_holdings_snapshot().await.unwrap().unwrap();
+        assert_eq!(snapshot.label, "shutdown");
+        assert!(snapshot.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_leaves_positions_open_when_not_closing() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let clob = Arc::new(
+            Scenario::new()
+                .position("t1", dec!(20))
+                .default_book(&[(dec!(0.40), dec!(100))], &[(dec!(0.60), dec!(100))])
+                .build(),
+        );
+        let executor = holding(clob.clone(), &[("t1", dec!(20))]).await;
+
+        let (report, _) = unwind(&GracefulShutdown::new(false, 5), &executor, &db).await;
+
+        assert_eq!(report.closed_positions, 0);
+        assert!(clob.fills().is_empty());
+        let snapshot = db.latest_holdings_snapshot().await.unwrap().unwrap();
+        assert_eq!(snapshot.positions, HashMap::from([("t1".to_string(), dec!(20))]));
+    }
+
+    #[tokio::test]
+    async fn test_run_checkpoints_positions_it_could_not_close() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        // t2's bid is too thin for a fill-or-kill close of the whole position
+        let clob = Arc::new(
+            Scenario::new()
+                .position("t1", dec!(20))
+                .position("t2", dec!(50))
+                .book("t1", &[(dec!(0.40), dec!(100))], &[(dec!(0.60), dec!(100))])
+                .book("t2", &[(dec!(0.30), dec!(10))], &[(dec!(0.70), dec!(100))])
+                .build(),
+        );
+        let executor = holding(clob.clone(), &[("t1", dec!(20)), ("t2", dec!(50))]).await;
+
+        let (report, _) = unwind(&GracefulShutdown::new(true, 1), &executor, &db).await;
+
+        assert_eq!(report.closed_positions, 1);
+        let fills = clob.fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].token_id, "t1");
+        let left = HashMap::from([("t2".to_string(), dec!(50))]);
+        assert_eq!(executor.get_positions().await, left);
+        let snapshot = db.latest_holdings_snapshot().await.unwrap().unwrap();
+        assert_eq!(snapshot.positions, left);
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_closes_until_the_book_deepens() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let clob = Arc::new(
+            Scenario::new()
+                .position("t1", dec!(50))
+                .book("t1", &[(dec!(0.30), dec!(10))], &[(dec!(0.70), dec!(100))])
+                .book_at(1, "t1", &[(dec!(0.35), dec!(100))], &[(dec!(0.70), dec!(100))])
+                .build(),
+        );
+        let executor = holding(clob.clone(), &[("t1", dec!(50))]).await;
+
+        // The first close is killed; liquidity arrives before the retry
+        let market = clob.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            market.advance(1);
+        });
+        let (report, _) = unwind(&GracefulShutdown::new(true, 10), &executor, &db).await;
+
+        assert_eq!(report.closed_positions, 1);
+        let fills = clob.fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].price, fills[0].size), (dec!(0.35), dec!(50)));
+        assert!(executor.get_positions().await.is_empty());
+    }
+
+    #[test]
+    fn test_trigger_only_once() {
+        let shutdown = GracefulShutdown::new(true, 10);
+        assert!(!shutdown.is_triggered());
+        assert!(shutdown.trigger());
+        assert!(shutdown.is_triggered());
+        assert!(!shutdown.trigger());
+    }
+
+    #[test]
+    fn test_trigger_concurrent() {
+        let shutdown = Arc::new(GracefulShutdown::new(false, 5));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let s = shutdown.clone();
+                std::thread::spawn(move || s.trigger())
+            })
+            .collect();
+
+        let wins = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|won| *won)
+            .count();
+        assert_eq!(wins, 1);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = ShutdownConfig {
+            close_positions: false,
+            timeout_secs: 45,
+        };
+        let shutdown = GracefulShutdown::from_config(&config);
+        assert!(!shutdown.close_positions);
+        assert_eq!(shutdown.timeout_secs, 45);
+
+        let default = GracefulShutdown::default();
+        assert!(default.close_positions);
+        assert_eq!(default.timeout_secs, 30);
+    }
+
+    #[test]
+    fn test_report_message() {
+        let report = ShutdownReport {
+            cancelled_orders: 3,
+            closed_positions: 2,
+            final_balance: dec!(1234.5),
+        };
+        assert_eq!(
+            report.message(),
+            "🔴 Bot shutdown complete. Cancelled 3 orders, closed 2 positions. Final balance: $1234.50"
+        );
+    }
+}
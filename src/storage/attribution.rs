@@ -0,0 +1,114 @@
+//! Performance attributed to trade tags
+//!
+//! Trades carry the tags of the signal they executed (strategy, model,
+//! prompt hash, experiment). [`Database::performance_by_tag`] counts each
+//! fill toward its own tags and credits the P&L of a closing fill to the
+//! tags of the entries it closed, so a prompt edit or experiment shows up as
+//! its own line.
+
+use super::Database;
+use crate::error::Result;
+use crate::strategy::market_maker::MmPnl;
+use crate::types::Side;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Trading results of the trades carrying one tag
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct TagPerformance {
+    pub tag: String,
+    /// Filled trades carrying the tag
+    pub trades: usize,
+    /// Closing fills of positions entered under the tag
+    pub closes: usize,
+    /// Closing fills that made money after fees
+    pub wins: usize,
+    /// Realized P&L after fees of those closing fills
+    pub realized_pnl: Decimal,
+    /// Mean signal edge of the trades that recorded one
+    pub avg_edge: Option<Decimal>,
+}
+
+impl TagPerformance {
+    /// Share of closing fills that made money; zero without any
+    pub fn win_rate(&self) -> Decimal {
+        if self.closes == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.wins) / Decimal::from(self.closes)
+    }
+}
+
+#[derive(Default)]
+struct TagTotals {
+    performance: TagPerformance,
+    edge_sum: Decimal,
+    edges: usize,
+}
+
+impl Database {
+    /// Performance per tag of the filled trades between `from` and `to`,
+    /// sorted by tag; untagged trades are left out
+    pub async fn performance_by_tag(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<TagPerformance>> {
+        let mut by_tag: BTreeMap<String, TagTotals> = BTreeMap::new();
+        // (account, token_id) -> average-cost book and the tags of its entries
+        let mut books: HashMap<(Option<String>, String), (MmPnl, Vec<String>)> = HashMap::new();
+
+        // Earlier trades still build the books so closes in the window are
+        // priced against their real entries
+        for trade in self.get_filled_trades().await? {
+            let (book, entry_tags) = books
+                .entry((trade.account_id.clone(), trade.token_id.clone()))
+                .or_insert_with(|| (MmPnl::new(&trade.token_id), Vec::new()));
+            let flat = book.inventory.is_zero();
+            let closing = !flat && book.inventory.is_sign_negative() != (trade.side == Side::Sell);
+            let realized_before = book.realized_pnl;
+            book.record_fill(trade.side, trade.price, trade.size);
+            let pnl = book.realized_pnl - realized_before - trade.fee;
+
+            let in_window = trade.timestamp >= from && trade.timestamp < to;
+            if in_window {
+                for tag in &trade.tags {
+                    let totals = by_tag.entry(tag.clone()).or_default();
+                    totals.performance.trades += 1;
+                    if let Some(edge) = trade.edge {
+                        totals.edge_sum += edge;
+                        totals.edges += 1;
+                    }
+                }
+                if closing {
+                    for tag in entry_tags.iter() {
+                        let totals = by_tag.entry(tag.clone()).or_default();
+                        totals.performance.closes += 1;
+                        if pnl > Decimal::ZERO {
+                            totals.performance.wins += 1;
+                        }
+                        totals.performance.realized_pnl += pnl;
+                    }
+                }
+            }
+
+            if !closing {
+                if flat {
+                    entry_tags.clear();
+                }
+                for tag in trade.tags {
+                    if !entry_tags.contains(&tag) {
+                        entry_tags.push(tag);
+                    }
+                }
+            }
+        }
+
+        Ok(by_tag
+            .into_iter()
+            .map(|(tag, totals)| TagPerformance {
+                tag,
+                avg_edge: (totals.edges > 0).then(|| totals.edge_sum / Decimal::from(totals.edges)),
+                ..totals.performance
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,180 @@
+//! Trades copied from followed traders and how they turned out
+//!
+//! A row is stored for every copy signal. Once the leader closes the
+//! position the row is settled with the leader's realized P&L scaled to the
+//! size we copied, and the settled rows of each leader feed
+//! `CopyTrader::recalibrate`.
+
+use super::Database;
+use crate::error::Result;
+use crate::strategy::copy_trade::{ClosedPosition, CopySignal, LeaderOutcomes};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A copied position and, once the leader closed it, its realized P&L
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopiedTrade {
+    /// Row ID (0 until stored)
+    pub id: i64,
+    /// `TopTrader::key` of the leader
+    pub trader_key: String,
+    pub market_id: String,
+    pub token_id: String,
+    /// The leader's position size when we copied it
+    pub trader_size: Decimal,
+    pub copied_size: Decimal,
+    pub copied_at: DateTime<Utc>,
+    /// Realized P&L of the copy; `None` while the leader holds
+    pub pnl: Option<Decimal>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl CopiedTrade {
+    /// Open copy of a signal's position
+    pub fn from_signal(signal: &CopySignal) -> Self {
+        Self {
+            id: 0,
+            trader_key: signal.trader.key().to_string(),
+            market_id: signal.market_id.clone(),
+            token_id: signal.token_id.clone(),
+            trader_size: signal.trader_size,
+            copied_size: signal.suggested_size,
+            copied_at: signal.timestamp,
+            pnl: None,
+            closed_at: None,
+        }
+    }
+
+    /// Settle against the leader's closed positions: the first close of
+    /// this market after we copied it, its P&L scaled by our share of the
+    /// leader's size. Returns whether it settled.
+    pub fn settle(&mut self, closed: &[ClosedPosition]) -> bool {
+        let Some(close) = closed
+            .iter()
+            .filter(|c| c.market_id == self.market_id && c.closed_at >= self.copied_at)
+            .min_by_key(|c| c.closed_at)
+        else {
+            return false;
+        };
+        let share = if self.trader_size > Decimal::ZERO {
+            self.copied_size / self.trader_size
+        } else {
+            Decimal::ZERO
+        };
+        self.pnl = Some((close.realized_pnl * share).round_dp(4));
+        self.closed_at = Some(close.closed_at);
+        true
+    }
+}
+
+impl Database {
+    /// Store an open copied trade
+    pub async fn record_copied_trade(&self, trade: &CopiedTrade) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO copied_trades (trader_key, market_id, token_id, trader_size, copied_size, copied_at, pnl, closed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&trade.trader_key)
+        .bind(&trade.market_id)
+        .bind(&trade.token_id)
+        .bind(trade.trader_size.to_string())
+        .bind(trade.copied_size.to_string())
+        .bind(trade.copied_at.to_rfc3339())
+        .bind(trade.pnl.map(|p| p.to_string()))
+        .bind(trade.closed_at.map(|t| t.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Copied trades whose leader still holds, oldest first
+    pub async fn get_open_copied_trades(&self) -> Result<Vec<CopiedTrade>> {
+        let rows = sqlx::query_as::<_, CopiedTradeRow>(
+            r#"
+            SELECT id, trader_key, market_id, token_id, trader_size, copied_size, copied_at, pnl, closed_at
+            FROM copied_trades
+            WHERE pnl IS NULL
+            ORDER BY copied_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Store the result of a settled copied trade
+    pub async fn settle_copied_trade(&self, trade: &CopiedTrade) -> Result<()> {
+        sqlx::query("UPDATE copied_trades SET pnl = ?, closed_at = ? WHERE id = ?")
+            .bind(trade.pnl.map(|p| p.to_string()))
+            .bind(trade.closed_at.map(|t| t.to_rfc3339()))
+            .bind(trade.id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Settled copied-trade results per leader
+    pub async fn get_copy_leader_outcomes(&self) -> Result<Vec<LeaderOutcomes>> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT trader_key, pnl FROM copied_trades WHERE pnl IS NOT NULL ORDER BY trader_key")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut outcomes: HashMap<String, LeaderOutcomes> = HashMap::new();
+        for (trader_key, pnl) in rows {
+            let Ok(pnl) = pnl.parse::<Decimal>() else {
+                continue;
+            };
+            let outcome = outcomes.entry(trader_key.clone()).or_insert_with(|| LeaderOutcomes {
+                trader_key,
+                ..Default::default()
+            });
+            outcome.trades += 1;
+            if pnl > Decimal::ZERO {
+                outcome.wins += 1;
+            }
+            outcome.total_pnl += pnl;
+        }
+
+        let mut outcomes: Vec<_> = outcomes.into_values().collect();
+        outcomes.sort_by(|a, b| a.trader_key.cmp(&b.trader_key));
+        Ok(outcomes)
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CopiedTradeRow {
+    id: i64,
+    trader_key: String,
+    market_id: String,
+    token_id: String,
+    trader_size: String,
+    copied_size: String,
+    copied_at: String,
+    pnl: Option<String>,
+    closed_at: Option<String>,
+}
+
+impl TryFrom<CopiedTradeRow> for CopiedTrade {
+    type Error = anyhow::Error;
+
+    fn try_from(row: CopiedTradeRow) -> std::result::Result<Self, Self::Error> {
+        Ok(CopiedTrade {
+            id: row.id,
+            trader_key: row.trader_key,
+            market_id: row.market_id,
+            token_id: row.token_id,
+            trader_size: row.trader_size.parse()?,
+            copied_size: row.copied_size.parse()?,
+            copied_at: row.copied_at.parse()?,
+            pnl: row.pnl.map(|p| p.parse()).transpose()?,
+            closed_at: row.closed_at.map(|t| t.parse()).transpose()?,
+        })
+    }
+}
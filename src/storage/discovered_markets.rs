@@ -0,0 +1,68 @@
+//! First-look predictions of newly discovered markets; a stored market is
+//! never analyzed at discovery again
+
+use super::Database;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// The model's first look at a new market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveredMarket {
+    pub market_id: String,
+    pub question: String,
+    pub discovered_at: DateTime<Utc>,
+    pub volume: Decimal,
+    pub liquidity: Decimal,
+    /// YES price when discovered
+    pub market_price: Decimal,
+    /// Model probability of YES
+    pub model_probability: Decimal,
+    pub confidence: Decimal,
+    pub reasoning: String,
+}
+
+impl DiscoveredMarket {
+    /// Model probability minus market price; positive means YES looks cheap
+    pub fn edge(&self) -> Decimal {
+        self.model_probability - self.market_price
+    }
+}
+
+impl Database {
+    /// Whether `market_id` already had its first look
+    pub async fn is_market_discovered(&self, market_id: &str) -> Result<bool> {
+        let row: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM discovered_markets WHERE market_id = ?")
+            .bind(market_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    /// Store a first-look prediction, keeping an earlier one for the same market
+    pub async fn save_discovered_market(&self, market: &DiscoveredMarket) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO discovered_markets (market_id, data, discovered_at) VALUES (?, ?, ?)")
+            .bind(&market.market_id)
+            .bind(serde_json::to_string(market)?)
+            .bind(market.discovered_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Markets discovered at or after `since`, oldest first; unreadable
+    /// rows are skipped
+    pub async fn get_discovered_markets(&self, since: DateTime<Utc>) -> Result<Vec<DiscoveredMarket>> {
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT data FROM discovered_markets WHERE discovered_at >= ? ORDER BY discovered_at",
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().filter_map(|data| serde_json::from_str(data).ok()).collect())
+    }
+}
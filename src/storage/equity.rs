@@ -0,0 +1,108 @@
+//! Portfolio value time series, one row per scan cycle, for equity curves
+//! and drawdown history
+
+use super::Database;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Portfolio value at one scan cycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub created_at: DateTime<Utc>,
+    /// Cash plus open positions at market
+    pub value: Decimal,
+    /// Realized P&L of all filled trades so far, net of fees
+    pub realized_pnl: Decimal,
+    /// Mark-to-market P&L of the open positions
+    pub unrealized_pnl: Decimal,
+    pub n_open_positions: u32,
+    /// Cash balance
+    pub balance: Decimal,
+}
+
+impl Database {
+    /// Record the portfolio value now
+    pub async fn save_portfolio_snapshot(
+        &self,
+        value: Decimal,
+        realized_pnl: Decimal,
+        unrealized_pnl: Decimal,
+        n_open_positions: u32,
+        balance: Decimal,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO portfolio_history
+                (created_at, value, realized_pnl, unrealized_pnl, n_open_positions, balance)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(value.to_string())
+        .bind(realized_pnl.to_string())
+        .bind(unrealized_pnl.to_string())
+        .bind(n_open_positions as i64)
+        .bind(balance.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Snapshots taken in `[from, to]`, oldest first
+    pub async fn get_portfolio_history(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PortfolioSnapshot>> {
+        let rows = sqlx::query_as::<_, PortfolioHistoryRow>(
+            r#"
+            SELECT created_at, value, realized_pnl, unrealized_pnl, n_open_positions, balance
+            FROM portfolio_history
+            WHERE created_at >= ? AND created_at <= ?
+            ORDER BY id
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Total portfolio value over every snapshot, oldest first
+    pub async fn compute_equity_curve(&self) -> Result<Vec<(DateTime<Utc>, Decimal)>> {
+        let rows = sqlx::query_as::<_, (String, String)>("SELECT created_at, value FROM portfolio_history ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(created_at, value)| Some((created_at.parse().ok()?, value.parse().ok()?)))
+            .collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PortfolioHistoryRow {
+    created_at: String,
+    value: String,
+    realized_pnl: String,
+    unrealized_pnl: String,
+    n_open_positions: i64,
+    balance: String,
+}
+
+impl TryFrom<PortfolioHistoryRow> for PortfolioSnapshot {
+    type Error = anyhow::Error;
+
+    fn try_from(row: PortfolioHistoryRow) -> std::result::Result<Self, Self::Error> {
+        Ok(PortfolioSnapshot {
+            created_at: row.created_at.parse()?,
+            value: row.value.parse()?,
+            realized_pnl: row.realized_pnl.parse()?,
+            unrealized_pnl: row.unrealized_pnl.parse()?,
+            n_open_positions: row.n_open_positions.try_into()?,
+            balance: row.balance.parse()?,
+        })
+    }
+}
@@ -0,0 +1,48 @@
+//! Fingerprints of executed signals, so a restart or a second ingestion
+//! path can't trade the same signal twice
+
+use super::Database;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+
+impl Database {
+    /// Whether a signal with this fingerprint was executed and hasn't expired
+    pub async fn is_signal_executed(&self, fingerprint: &str, now: DateTime<Utc>) -> Result<bool> {
+        let row: Option<(i64,)> = sqlx::query_as(
+            "SELECT 1 FROM executed_signals WHERE fingerprint = ? AND expires_at > ?",
+        )
+        .bind(fingerprint)
+        .bind(now.to_rfc3339())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+
+    /// Remember an executed signal until `expires_at`, dropping expired entries
+    pub async fn record_executed_signal(
+        &self,
+        fingerprint: &str,
+        executed_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM executed_signals WHERE expires_at <= ?")
+            .bind(executed_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO executed_signals (fingerprint, executed_at, expires_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(fingerprint)
+        .bind(executed_at.to_rfc3339())
+        .bind(expires_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+//! Hourly snapshots of the per-source ingestion counters, so their history
+//! survives restarts
+
+use super::Database;
+use crate::error::Result;
+use crate::ingester::SourceStats;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+impl Database {
+    /// Record the counters as of `at`, one row per source in its hour;
+    /// later snapshots in the same hour replace earlier ones
+    pub async fn save_ingest_stats(&self, stats: &[SourceStats], at: DateTime<Utc>) -> Result<()> {
+        let hour = at.duration_trunc(Duration::hours(1)).unwrap_or(at);
+        for s in stats {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO ingest_stats
+                    (hour, source, received, passed_prefilter, sent_to_llm, contributed, errors, last_message_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(hour.to_rfc3339())
+            .bind(&s.source)
+            .bind(s.received as i64)
+            .bind(s.passed_prefilter as i64)
+            .bind(s.sent_to_llm as i64)
+            .bind(s.contributed as i64)
+            .bind(s.errors as i64)
+            .bind(s.last_message_at.map(|t| t.to_rfc3339()))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Each source's most recent snapshot, by name
+    pub async fn get_latest_ingest_stats(&self) -> Result<Vec<SourceStats>> {
+        let rows = sqlx::query_as::<_, (String, i64, i64, i64, i64, i64, Option<String>)>(
+            r#"
+            SELECT source, received, passed_prefilter, sent_to_llm, contributed, errors, last_message_at
+            FROM ingest_stats s
+            WHERE hour = (SELECT MAX(hour) FROM ingest_stats WHERE source = s.source)
+            ORDER BY source
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(source, received, passed_prefilter, sent_to_llm, contributed, errors, last_message_at)| SourceStats {
+                source,
+                received: received as u64,
+                passed_prefilter: passed_prefilter as u64,
+                sent_to_llm: sent_to_llm as u64,
+                contributed: contributed as u64,
+                errors: errors as u64,
+                last_message_at: last_message_at.and_then(|t| t.parse().ok()),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,246 @@
+//! Trading journal: operator notes and auto-recorded events
+//!
+//! Entries are interleaved with trades to give a single audit timeline.
+
+use super::{Database, TradeRow};
+use crate::error::Result;
+use crate::types::Trade;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a journal entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalKind {
+    /// Free-form note from `/note`
+    Note,
+    /// Trading paused (manually or by a risk limit)
+    Pause,
+    /// Trading resumed
+    Resume,
+    /// Risk parameter changed via `/setrisk`
+    RiskChange,
+    /// Account drawdown crossed into a different sizing tier
+    DrawdownTier,
+    /// Market regime transition
+    RegimeChange,
+    /// Strategy switched between live, shadow and off
+    StrategyMode,
+    /// A strategy's or market category's size ramp changed level
+    Ramp,
+}
+
+impl JournalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalKind::Note => "note",
+            JournalKind::Pause => "pause",
+            JournalKind::Resume => "resume",
+            JournalKind::RiskChange => "risk_change",
+            JournalKind::DrawdownTier => "drawdown_tier",
+            JournalKind::RegimeChange => "regime_change",
+            JournalKind::StrategyMode => "strategy_mode",
+            JournalKind::Ramp => "ramp",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "note" => Some(JournalKind::Note),
+            "pause" => Some(JournalKind::Pause),
+            "resume" => Some(JournalKind::Resume),
+            "risk_change" => Some(JournalKind::RiskChange),
+            "drawdown_tier" => Some(JournalKind::DrawdownTier),
+            "regime_change" => Some(JournalKind::RegimeChange),
+            "strategy_mode" => Some(JournalKind::StrategyMode),
+            "ramp" => Some(JournalKind::Ramp),
+            _ => None,
+        }
+    }
+}
+
+/// A timestamped journal entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+    pub kind: JournalKind,
+    /// Market the entry refers to, if any
+    pub market_id: Option<String>,
+    pub text: String,
+    /// Whether an operator caused the entry (vs. the bot recording it)
+    pub manual: bool,
+}
+
+impl JournalEntry {
+    /// Entry recorded by the bot itself
+    pub fn auto(kind: JournalKind, text: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            kind,
+            market_id: None,
+            text: text.into(),
+            manual: false,
+        }
+    }
+
+    /// Entry caused by an operator command
+    pub fn manual(kind: JournalKind, text: impl Into<String>) -> Self {
+        Self {
+            manual: true,
+            ..Self::auto(kind, text)
+        }
+    }
+
+    /// Operator note, optionally about one market
+    pub fn note(market_id: Option<String>, text: impl Into<String>) -> Self {
+        Self {
+            market_id,
+            ..Self::manual(JournalKind::Note, text)
+        }
+    }
+
+    /// Regime transition, e.g. from a `RegimeDetector::get_transition()`
+    pub fn regime_change(from: impl std::fmt::Debug, to: impl std::fmt::Debug) -> Self {
+        Self::auto(JournalKind::RegimeChange, format!("Regime {:?} → {:?}", from, to))
+    }
+}
+
+/// One line of the merged audit timeline
+#[derive(Debug, Clone)]
+pub enum TimelineEntry {
+    Trade(Box<Trade>),
+    Journal(JournalEntry),
+}
+
+impl TimelineEntry {
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            TimelineEntry::Trade(t) => t.timestamp,
+            TimelineEntry::Journal(j) => j.timestamp,
+        }
+    }
+
+    /// Single-line rendering for the CLI
+    pub fn describe(&self) -> String {
+        let time = self.timestamp().format("%Y-%m-%d %H:%M:%S");
+        match self {
+            TimelineEntry::Trade(t) => format!(
+                "{}  TRADE   {:?} {} @ {} on {}",
+                time, t.side, t.size, t.price, t.market_id
+            ),
+            TimelineEntry::Journal(j) => {
+                let market = j.market_id.as_deref().map(|m| format!(" [{}]", m)).unwrap_or_default();
+                format!("{}  {:<7} {}{}", time, j.kind.as_str().to_uppercase(), j.text, market)
+            }
+        }
+    }
+}
+
+/// Merge trades and journal entries chronologically, trades first on ties
+pub fn merge_timeline(trades: Vec<Trade>, journal: Vec<JournalEntry>) -> Vec<TimelineEntry> {
+    let mut timeline: Vec<TimelineEntry> = trades
+        .into_iter()
+        .map(|t| TimelineEntry::Trade(Box::new(t)))
+        .chain(journal.into_iter().map(TimelineEntry::Journal))
+        .collect();
+    // Stable sort keeps trades ahead of journal entries with the same timestamp
+    timeline.sort_by_key(|e| e.timestamp());
+    timeline
+}
+
+impl Database {
+    /// Append a journal entry
+    pub async fn add_journal_entry(&self, entry: &JournalEntry) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO journal (timestamp, kind, market_id, text, manual)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(entry.timestamp.to_rfc3339())
+        .bind(entry.kind.as_str())
+        .bind(&entry.market_id)
+        .bind(&entry.text)
+        .bind(entry.manual)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Journal entries at or after `since`, oldest first
+    pub async fn get_journal_since(&self, since: DateTime<Utc>) -> Result<Vec<JournalEntry>> {
+        let rows = sqlx::query_as::<_, JournalRow>(
+            r#"
+            SELECT timestamp, kind, market_id, text, manual
+            FROM journal
+            WHERE timestamp >= ?
+            ORDER BY timestamp ASC, id ASC
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Trades at or after `since`, oldest first
+    pub async fn get_trades_since(&self, since: DateTime<Utc>) -> Result<Vec<Trade>> {
+        let rows = sqlx::query_as::<_, TradeRow>(
+            r#"
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
+            FROM trades
+            WHERE timestamp >= ?
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Trades and journal entries since `since`, interleaved chronologically
+    pub async fn get_timeline(&self, since: DateTime<Utc>) -> Result<Vec<TimelineEntry>> {
+        let trades = self.get_trades_since(since).await?;
+        let journal = self.get_journal_since(since).await?;
+        Ok(merge_timeline(trades, journal))
+    }
+
+    /// Number of operator interventions (notes, pauses, risk changes) since `since`
+    pub async fn count_manual_interventions(&self, since: DateTime<Utc>) -> Result<usize> {
+        let (count,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM journal WHERE manual = 1 AND timestamp >= ?")
+                .bind(since.to_rfc3339())
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(count as usize)
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct JournalRow {
+    timestamp: String,
+    kind: String,
+    market_id: Option<String>,
+    text: String,
+    manual: bool,
+}
+
+impl TryFrom<JournalRow> for JournalEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(row: JournalRow) -> std::result::Result<Self, Self::Error> {
+        Ok(JournalEntry {
+            timestamp: row.timestamp.parse()?,
+            kind: JournalKind::parse(&row.kind)
+                .ok_or_else(|| anyhow::anyhow!("unknown journal kind: {}", row.kind))?,
+            market_id: row.market_id,
+            text: row.text,
+            manual: row.manual,
+        })
+    }
+}
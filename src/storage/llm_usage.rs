@@ -0,0 +1,67 @@
+//! LLM calls and tokens per UTC day, for the spend line of the monthly
+//! report
+
+use super::Database;
+use crate::error::Result;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// LLM usage summed over a range of days
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct LlmUsageTotals {
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl LlmUsageTotals {
+    /// Cost at the given prices per million tokens
+    pub fn cost(&self, input_per_mtok: Decimal, output_per_mtok: Decimal) -> Decimal {
+        let mtok = Decimal::from(1_000_000);
+        (Decimal::from(self.input_tokens) * input_per_mtok + Decimal::from(self.output_tokens) * output_per_mtok) / mtok
+    }
+}
+
+impl Database {
+    /// Add usage to `day`'s totals
+    pub async fn record_llm_usage(&self, day: NaiveDate, usage: LlmUsageTotals) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO llm_usage (day, calls, input_tokens, output_tokens) VALUES (?, ?, ?, ?)
+            ON CONFLICT(day) DO UPDATE SET
+                calls = calls + excluded.calls,
+                input_tokens = input_tokens + excluded.input_tokens,
+                output_tokens = output_tokens + excluded.output_tokens
+            "#,
+        )
+        .bind(day.to_string())
+        .bind(usage.calls as i64)
+        .bind(usage.input_tokens as i64)
+        .bind(usage.output_tokens as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Usage summed over the days in `[from, to)`
+    pub async fn get_llm_usage(&self, from: NaiveDate, to: NaiveDate) -> Result<LlmUsageTotals> {
+        let (calls, input_tokens, output_tokens) = sqlx::query_as::<_, (i64, i64, i64)>(
+            r#"
+            SELECT COALESCE(SUM(calls), 0), COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0)
+            FROM llm_usage WHERE day >= ? AND day < ?
+            "#,
+        )
+        .bind(from.to_string())
+        .bind(to.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(LlmUsageTotals {
+            calls: calls as u64,
+            input_tokens: input_tokens as u64,
+            output_tokens: output_tokens as u64,
+        })
+    }
+}
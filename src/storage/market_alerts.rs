@@ -0,0 +1,60 @@
+//! Per-market alert rules added from Telegram
+
+use super::Database;
+use crate::error::Result;
+use crate::monitor::{MarketAlert, MarketAlertRule};
+use chrono::{DateTime, Utc};
+
+impl Database {
+    /// Store `rule`, returning its ID
+    pub async fn add_market_alert(&self, rule: &MarketAlertRule) -> Result<i64> {
+        let result = sqlx::query("INSERT INTO market_alerts (data, created_at) VALUES (?, ?)")
+            .bind(serde_json::to_string(rule)?)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Every stored rule, oldest first; unreadable rows are skipped
+    pub async fn get_market_alerts(&self) -> Result<Vec<MarketAlert>> {
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>)>(
+            "SELECT id, data, last_fired FROM market_alerts ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, data, last_fired)| {
+                Some(MarketAlert {
+                    id,
+                    rule: serde_json::from_str(&data).ok()?,
+                    last_fired: last_fired.and_then(|at| at.parse().ok()),
+                })
+            })
+            .collect())
+    }
+
+    /// Delete rule `id`; false when there is none
+    pub async fn delete_market_alert(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM market_alerts WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Start the cooldown of rule `id` at `at`
+    pub async fn mark_market_alert_fired(&self, id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE market_alerts SET last_fired = ? WHERE id = ?")
+            .bind(at.to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
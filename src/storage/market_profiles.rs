@@ -0,0 +1,133 @@
+//! Order book samples and the per-market profiles aggregated from them
+//!
+//! Samples are kept for the profile window and pruned at each refresh;
+//! a profile is one JSON row per market, rewritten daily.
+
+use super::Database;
+use crate::analysis::market_profile::{BookSample, MarketProfile};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+impl Database {
+    pub async fn save_book_sample(&self, sample: &BookSample) -> Result<()> {
+        sqlx::query("INSERT INTO book_samples (market_id, taken_at, spread, top_depth, mm_activity) VALUES (?, ?, ?, ?, ?)")
+            .bind(&sample.market_id)
+            .bind(sample.taken_at.to_rfc3339())
+            .bind(sample.spread.to_string())
+            .bind(sample.top_depth.to_string())
+            .bind(sample.mm_activity.map(|level| serde_json::to_string(&level)).transpose()?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Samples of `market_id` taken since `since`, oldest first
+    pub async fn get_book_samples(&self, market_id: &str, since: DateTime<Utc>) -> Result<Vec<BookSample>> {
+        let rows = sqlx::query_as::<_, BookSampleRow>(
+            r#"
+            SELECT market_id, taken_at, spread, top_depth, mm_activity
+            FROM book_samples
+            WHERE market_id = ? AND taken_at >= ?
+            ORDER BY taken_at
+            "#,
+        )
+        .bind(market_id)
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(BookSampleRow::into_sample).collect())
+    }
+
+    /// Markets with a sample since `since`
+    pub async fn sampled_markets(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT DISTINCT market_id FROM book_samples WHERE taken_at >= ? ORDER BY market_id")
+            .bind(since.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Drop samples taken before `before`
+    pub async fn prune_book_samples(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM book_samples WHERE taken_at < ?")
+            .bind(before.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Seconds from placement to fill of our resting orders in `market_id`
+    /// placed since `since`
+    pub async fn get_fill_latencies(&self, market_id: &str, since: DateTime<Utc>) -> Result<Vec<i64>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT timestamp, filled_at FROM trades WHERE market_id = ? AND timestamp >= ? AND filled_at IS NOT NULL",
+        )
+        .bind(market_id)
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|(placed, filled)| {
+                let placed = DateTime::parse_from_rfc3339(placed).ok()?;
+                let filled = DateTime::parse_from_rfc3339(filled).ok()?;
+                Some((filled - placed).num_seconds().max(0))
+            })
+            .collect())
+    }
+
+    pub async fn save_market_profile(&self, profile: &MarketProfile) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO market_profiles (market_id, data, updated_at) VALUES (?, ?, ?)")
+            .bind(&profile.market_id)
+            .bind(serde_json::to_string(profile)?)
+            .bind(profile.updated_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn load_market_profile(&self, market_id: &str) -> Result<Option<MarketProfile>> {
+        let data: Option<String> = sqlx::query_scalar("SELECT data FROM market_profiles WHERE market_id = ?")
+            .bind(market_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(data.and_then(|data| serde_json::from_str(&data).ok()))
+    }
+
+    /// Every stored profile; unreadable rows are skipped
+    pub async fn load_market_profiles(&self) -> Result<Vec<MarketProfile>> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT data FROM market_profiles ORDER BY market_id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().filter_map(|data| serde_json::from_str(data).ok()).collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BookSampleRow {
+    market_id: String,
+    taken_at: String,
+    spread: String,
+    top_depth: String,
+    mm_activity: Option<String>,
+}
+
+impl BookSampleRow {
+    fn into_sample(self) -> Option<BookSample> {
+        Some(BookSample {
+            taken_at: DateTime::parse_from_rfc3339(&self.taken_at).ok()?.with_timezone(&Utc),
+            spread: Decimal::from_str(&self.spread).ok()?,
+            top_depth: Decimal::from_str(&self.top_depth).ok()?,
+            mm_activity: self.mm_activity.and_then(|level| serde_json::from_str(&level).ok()),
+            market_id: self.market_id,
+        })
+    }
+}
@@ -2,17 +2,108 @@
 
 pub mod history;
 pub mod cache;
+pub mod journal;
+pub mod signal_outcomes;
+pub mod executed_signals;
+pub mod shadow_trades;
+pub mod positions;
+pub mod queued_signals;
+pub mod equity;
+pub mod discovered_markets;
+pub mod predictions;
+pub mod trace;
+pub mod market_alerts;
+pub mod attribution;
+pub mod price_snapshots;
+pub mod llm_usage;
+pub mod outbox;
+pub mod raw_signals;
+pub mod ingest_stats;
+pub mod resting_orders;
+pub mod copied_trades;
+pub mod ramp;
+pub mod market_profiles;
+pub mod strategy_states;
 
 #[cfg(test)]
 mod tests;
 
+use crate::config::DEFAULT_ACCOUNT_ID;
 use crate::error::Result;
 use crate::monitor::PerformanceStats;
-use crate::types::Trade;
+use crate::strategy::copy_trade::TopTrader;
+use crate::strategy::market_maker::MmPnl;
+use crate::types::{Market, Side, Trade, TradeStatus};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::path::Path;
 
+pub use journal::{JournalEntry, JournalKind, TimelineEntry};
+pub use signal_outcomes::SignalOutcome;
+pub use shadow_trades::{ShadowPnl, ShadowTrade};
+pub use positions::{LadderPosition, OpenPosition};
+pub use equity::PortfolioSnapshot;
+pub use discovered_markets::DiscoveredMarket;
+pub use predictions::{AccuracyReport, CalibrationBin, StoredPrediction};
+pub use trace::{TraceEvent, TraceStage};
+pub use attribution::TagPerformance;
+pub use price_snapshots::PriceSnapshot;
+pub use llm_usage::LlmUsageTotals;
+pub use outbox::OutboxMessage;
+pub use copied_trades::CopiedTrade;
+
+/// Point-in-time record of account balance and open positions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingsSnapshot {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub balance: Decimal,
+    /// token_id -> signed position size
+    pub positions: std::collections::HashMap<String, Decimal>,
+    /// Why the snapshot was taken (e.g. "shutdown")
+    pub label: String,
+    /// Account the snapshot is of
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+/// Scored copy-trading leaderboard at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardSnapshot {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Best score first
+    pub traders: Vec<TopTrader>,
+}
+
+/// Realized P&L attributed to one strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrategyPnl {
+    pub strategy: String,
+    /// Filled trades
+    pub trades: usize,
+    /// Traded notional
+    pub volume: Decimal,
+    pub fees: Decimal,
+    /// Realized P&L from round trips (average cost), net of fees
+    pub realized_pnl: Decimal,
+}
+
+/// Realized P&L of one account
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccountPnl {
+    pub account: String,
+    /// Filled trades
+    pub trades: usize,
+    /// Traded notional
+    pub volume: Decimal,
+    pub fees: Decimal,
+    /// Realized P&L from round trips (average cost), net of fees
+    pub realized_pnl: Decimal,
+}
+
+/// Strategy name reported for trades saved without one (single-strategy mode)
+pub const DEFAULT_STRATEGY_NAME: &str = "main";
+
 /// Database for storing trades and state
 pub struct Database {
     pool: SqlitePool,
@@ -47,13 +138,47 @@ impl Database {
                 price TEXT NOT NULL,
                 size TEXT NOT NULL,
                 fee TEXT NOT NULL,
-                timestamp TEXT NOT NULL
+                timestamp TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'filled',
+                strategy TEXT,
+                account TEXT,
+                trace_id TEXT,
+                tags TEXT,
+                edge TEXT
             )
             "#,
         )
         .execute(&self.pool)
         .await?;
 
+        // Databases created before the status column existed; fails harmlessly otherwise
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN status TEXT NOT NULL DEFAULT 'filled'")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN strategy TEXT")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN account TEXT")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN trace_id TEXT")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN tags TEXT")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN edge TEXT")
+            .execute(&self.pool)
+            .await;
+        // When a resting order filled, for fill latency
+        let _ = sqlx::query("ALTER TABLE trades ADD COLUMN filled_at TEXT")
+            .execute(&self.pool)
+            .await;
+        // Tags are a JSON array; the index serves tag lookups by exact set
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_trades_tags ON trades(tags)")
+            .execute(&self.pool)
+            .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS positions (
@@ -80,6 +205,425 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS market_events (
+                market_id TEXT PRIMARY KEY,
+                event_id TEXT NOT NULL,
+                neg_risk INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS portfolio_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                balance TEXT NOT NULL,
+                positions TEXT NOT NULL,
+                label TEXT NOT NULL,
+                account TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        let _ = sqlx::query("ALTER TABLE portfolio_snapshots ADD COLUMN account TEXT")
+            .execute(&self.pool)
+            .await;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS portfolio_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                created_at TEXT NOT NULL,
+                value TEXT NOT NULL,
+                realized_pnl TEXT NOT NULL,
+                unrealized_pnl TEXT NOT NULL,
+                n_open_positions INTEGER NOT NULL,
+                balance TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS leaderboard_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                traders TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS mm_pnl (
+                market_id TEXT PRIMARY KEY,
+                inventory TEXT NOT NULL,
+                avg_cost TEXT NOT NULL,
+                realized_pnl TEXT NOT NULL,
+                fills INTEGER NOT NULL,
+                volume TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS journal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                market_id TEXT,
+                text TEXT NOT NULL,
+                manual INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS signal_outcomes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                author TEXT NOT NULL,
+                token TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                entry_price TEXT NOT NULL,
+                signal_at TEXT NOT NULL,
+                resolve_at TEXT NOT NULL,
+                exit_price TEXT,
+                hit INTEGER
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS executed_signals (
+                fingerprint TEXT PRIMARY KEY,
+                executed_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS shadow_trades (
+                id TEXT PRIMARY KEY,
+                strategy TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                token_id TEXT NOT NULL,
+                side TEXT NOT NULL,
+                price TEXT NOT NULL,
+                size TEXT NOT NULL,
+                opened_at TEXT NOT NULL,
+                mark_price TEXT NOT NULL,
+                marked_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS queued_signals (
+                market_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                queued_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS health_checks (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                checked_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS discovered_markets (
+                market_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                discovered_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS trace_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trace_id TEXT NOT NULL,
+                stage TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                detail TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS predictions (
+                market_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                predicted_at TEXT NOT NULL,
+                outcome INTEGER
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS market_alerts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                data TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_fired TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS price_snapshots (
+                market_id TEXT NOT NULL,
+                day TEXT NOT NULL,
+                yes_price TEXT NOT NULL,
+                taken_at TEXT NOT NULL,
+                PRIMARY KEY (market_id, day)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                day TEXT PRIMARY KEY,
+                calls INTEGER NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS outbox (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                hash TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                parse_mode TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TEXT NOT NULL,
+                delivered_at TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_outbox_hash ON outbox(hash, created_at)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS raw_signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                author TEXT NOT NULL,
+                author_trust REAL NOT NULL,
+                timestamp TEXT NOT NULL,
+                metadata TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_raw_signals_timestamp ON raw_signals(timestamp)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS llm_extractions (
+                content_hash TEXT PRIMARY KEY,
+                response TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ingest_stats (
+                hour TEXT NOT NULL,
+                source TEXT NOT NULL,
+                received INTEGER NOT NULL,
+                passed_prefilter INTEGER NOT NULL,
+                sent_to_llm INTEGER NOT NULL,
+                contributed INTEGER NOT NULL,
+                errors INTEGER NOT NULL,
+                last_message_at TEXT,
+                PRIMARY KEY (hour, source)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS resting_orders (
+                order_id TEXT PRIMARY KEY,
+                market_id TEXT NOT NULL,
+                data TEXT NOT NULL,
+                placed_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS copied_trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trader_key TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                token_id TEXT NOT NULL,
+                trader_size TEXT NOT NULL,
+                copied_size TEXT NOT NULL,
+                copied_at TEXT NOT NULL,
+                pnl TEXT,
+                closed_at TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ramp_state (
+                key TEXT PRIMARY KEY,
+                level TEXT NOT NULL,
+                trades INTEGER NOT NULL,
+                wins INTEGER NOT NULL,
+                losing_streak INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS book_samples (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                market_id TEXT NOT NULL,
+                taken_at TEXT NOT NULL,
+                spread TEXT NOT NULL,
+                top_depth TEXT NOT NULL,
+                mm_activity TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_book_samples_market ON book_samples(market_id, taken_at)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS market_profiles (
+                market_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS strategy_states (
+                key TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                saved_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Write a row and read it back, for health checks
+    pub async fn health_round_trip(&self) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query("INSERT OR REPLACE INTO health_checks (id, checked_at) VALUES (1, ?)")
+            .bind(&now)
+            .execute(&self.pool)
+            .await?;
+
+        let (checked_at,): (String,) = sqlx::query_as("SELECT checked_at FROM health_checks WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await?;
+        if checked_at != now {
+            return Err(crate::error::BotError::Internal("health check row not read back".into()));
+        }
         Ok(())
     }
 
@@ -87,8 +631,8 @@ impl Database {
     pub async fn save_trade(&self, trade: &Trade) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO trades (id, order_id, token_id, market_id, side, price, size, fee, timestamp)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO trades (id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&trade.id)
@@ -100,17 +644,339 @@ impl Database {
         .bind(trade.size.to_string())
         .bind(trade.fee.to_string())
         .bind(trade.timestamp.to_rfc3339())
+        .bind(trade.status.as_str())
+        .bind(&trade.strategy_name)
+        .bind(&trade.account_id)
+        .bind((!trade.trace_id.is_nil()).then(|| trade.trace_id.to_string()))
+        .bind(if trade.tags.is_empty() { None } else { Some(serde_json::to_string(&trade.tags)?) })
+        .bind(trade.edge.map(|e| e.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update the fill status of a trade by exchange order ID
+    pub async fn update_trade_status(&self, order_id: &str, status: TradeStatus) -> Result<()> {
+        sqlx::query("UPDATE trades SET status = ? WHERE order_id = ?")
+            .bind(status.as_str())
+            .bind(order_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get trades whose orders are still resting on the book
+    pub async fn get_resting_trades(&self) -> Result<Vec<Trade>> {
+        let rows = sqlx::query_as::<_, TradeRow>(
+            r#"
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
+            FROM trades
+            WHERE status = 'resting'
+            ORDER BY timestamp DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Save a holdings snapshot
+    pub async fn save_holdings_snapshot(&self, snapshot: &HoldingsSnapshot) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO portfolio_snapshots (timestamp, balance, positions, label, account)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(snapshot.timestamp.to_rfc3339())
+        .bind(snapshot.balance.to_string())
+        .bind(serde_json::to_string(&snapshot.positions)?)
+        .bind(&snapshot.label)
+        .bind(&snapshot.account_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the most recent holdings snapshot
+    pub async fn latest_holdings_snapshot(&self) -> Result<Option<HoldingsSnapshot>> {
+        let row = sqlx::query_as::<_, SnapshotRow>(
+            r#"
+            SELECT timestamp, balance, positions, label, account
+            FROM portfolio_snapshots
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|r| r.try_into().ok()))
+    }
+
+    /// Save a copy-trading leaderboard snapshot
+    pub async fn save_leaderboard_snapshot(&self, snapshot: &LeaderboardSnapshot) -> Result<()> {
+        sqlx::query("INSERT INTO leaderboard_snapshots (timestamp, traders) VALUES (?, ?)")
+            .bind(snapshot.timestamp.to_rfc3339())
+            .bind(serde_json::to_string(&snapshot.traders)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the most recent leaderboard snapshot
+    pub async fn latest_leaderboard_snapshot(&self) -> Result<Option<LeaderboardSnapshot>> {
+        let row = sqlx::query_as::<_, LeaderboardRow>(
+            r#"
+            SELECT timestamp, traders
+            FROM leaderboard_snapshots
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|r| r.try_into().ok()))
+    }
+
+    /// Save (upsert) market-making P&L for a market
+    pub async fn save_mm_pnl(&self, pnl: &MmPnl) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO mm_pnl (market_id, inventory, avg_cost, realized_pnl, fills, volume, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&pnl.market_id)
+        .bind(pnl.inventory.to_string())
+        .bind(pnl.avg_cost.to_string())
+        .bind(pnl.realized_pnl.to_string())
+        .bind(pnl.fills as i64)
+        .bind(pnl.volume.to_string())
+        .bind(chrono::Utc::now().to_rfc3339())
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// Get market-making P&L for every market
+    pub async fn get_mm_pnl(&self) -> Result<Vec<MmPnl>> {
+        let rows = sqlx::query_as::<_, MmPnlRow>(
+            r#"
+            SELECT market_id, inventory, avg_cost, realized_pnl, fills, volume
+            FROM mm_pnl
+            ORDER BY market_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Record which event a market belongs to (no-op for standalone markets)
+    pub async fn save_market_event(&self, market: &Market) -> Result<()> {
+        let Some(event_id) = &market.event_id else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO market_events (market_id, event_id, neg_risk, updated_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(&market.id)
+        .bind(event_id)
+        .bind(market.neg_risk)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Market IDs grouped under an event, sorted
+    pub async fn get_event_markets(&self, event_id: &str) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT market_id FROM market_events WHERE event_id = ? ORDER BY market_id",
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Event IDs of all known negRisk events, sorted
+    pub async fn get_neg_risk_events(&self) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT event_id FROM market_events WHERE neg_risk = 1 ORDER BY event_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Realized P&L per strategy over all filled trades, sorted by strategy name
+    pub async fn get_pnl_by_strategy(&self) -> Result<Vec<StrategyPnl>> {
+        let by_strategy = self
+            .realized_pnl_by(|trade| {
+                trade
+                    .strategy_name
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_STRATEGY_NAME.to_string())
+            })
+            .await?;
+
+        Ok(by_strategy
+            .into_iter()
+            .map(|(strategy, pnl)| StrategyPnl { strategy, ..pnl })
+            .collect())
+    }
+
+    /// Realized P&L per account over all filled trades, sorted by account ID;
+    /// trades saved without an account count towards the default one
+    pub async fn get_pnl_by_account(&self) -> Result<Vec<AccountPnl>> {
+        let by_account = self
+            .realized_pnl_by(|trade| {
+                trade
+                    .account_id
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_ACCOUNT_ID.to_string())
+            })
+            .await?;
+
+        Ok(by_account
+            .into_iter()
+            .map(|(account, pnl)| AccountPnl {
+                account,
+                trades: pnl.trades,
+                volume: pnl.volume,
+                fees: pnl.fees,
+                realized_pnl: pnl.realized_pnl,
+            })
+            .collect())
+    }
+
+    /// Realized P&L of filled trades grouped by `key`, with a separate
+    /// average-cost book per group and token
+    async fn realized_pnl_by(
+        &self,
+        key: impl Fn(&Trade) -> String,
+    ) -> Result<std::collections::BTreeMap<String, StrategyPnl>> {
+        let mut by_key: std::collections::BTreeMap<String, StrategyPnl> = Default::default();
+        // (group, token_id) -> average-cost book
+        let mut books: std::collections::HashMap<(String, String), MmPnl> = Default::default();
+
+        for trade in self.get_filled_trades().await? {
+            let group = key(&trade);
+
+            let book = books
+                .entry((group.clone(), trade.token_id.clone()))
+                .or_insert_with(|| MmPnl::new(&trade.token_id));
+            let realized_before = book.realized_pnl;
+            book.record_fill(trade.side, trade.price, trade.size);
+
+            let pnl = by_key.entry(group).or_default();
+            pnl.trades += 1;
+            pnl.volume += trade.price * trade.size;
+            pnl.fees += trade.fee;
+            pnl.realized_pnl += book.realized_pnl - realized_before - trade.fee;
+        }
+
+        Ok(by_key)
+    }
+
+    /// Number of most recent closing fills in a row that lost money after
+    /// fees, over all accounts
+    pub async fn get_consecutive_losses(&self) -> Result<u32> {
+        let mut streak = 0;
+        for (_, pnl) in self.closing_fills().await? {
+            if pnl < Decimal::ZERO {
+                streak += 1;
+            } else {
+                streak = 0;
+            }
+        }
+        Ok(streak)
+    }
+
+    /// Realized P&L after fees of the filled trade `trade_id`; `None` when
+    /// it opened or added to a position rather than closing one
+    pub async fn get_closing_pnl(&self, trade_id: &str) -> Result<Option<Decimal>> {
+        Ok(self
+            .closing_fills()
+            .await?
+            .into_iter()
+            .find(|(trade, _)| trade.id == trade_id)
+            .map(|(_, pnl)| pnl))
+    }
+
+    /// Closing fills made in `[from, to)` with the P&L each realized after
+    /// fees, priced against entries made at any time
+    pub async fn get_closing_fills_between(&self, from: chrono::DateTime<chrono::Utc>, to: chrono::DateTime<chrono::Utc>) -> Result<Vec<(Trade, Decimal)>> {
+        Ok(self
+            .closing_fills()
+            .await?
+            .into_iter()
+            .filter(|(trade, _)| trade.timestamp >= from && trade.timestamp < to)
+            .collect())
+    }
+
+    /// Filled trades that reduced a position, oldest first, with the P&L
+    /// each realized after fees on its account's average-cost book
+    async fn closing_fills(&self) -> Result<Vec<(Trade, Decimal)>> {
+        let mut books: std::collections::HashMap<(Option<String>, String), MmPnl> = Default::default();
+        let mut closing_fills = Vec::new();
+
+        for trade in self.get_filled_trades().await? {
+            let book = books
+                .entry((trade.account_id.clone(), trade.token_id.clone()))
+                .or_insert_with(|| MmPnl::new(&trade.token_id));
+            let closing = !book.inventory.is_zero()
+                && book.inventory.is_sign_negative() != (trade.side == Side::Sell);
+            let realized_before = book.realized_pnl;
+            book.record_fill(trade.side, trade.price, trade.size);
+
+            if closing {
+                let pnl = book.realized_pnl - realized_before - trade.fee;
+                closing_fills.push((trade, pnl));
+            }
+        }
+
+        Ok(closing_fills)
+    }
+
+    /// Filled trades, oldest first
+    async fn get_filled_trades(&self) -> Result<Vec<Trade>> {
+        let rows = sqlx::query_as::<_, TradeRow>(
+            r#"
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
+            FROM trades
+            WHERE status = 'filled'
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| Trade::try_from(r).ok()).collect())
+    }
+
     /// Get recent trades
     pub async fn get_recent_trades(&self, limit: i64) -> Result<Vec<Trade>> {
         let rows = sqlx::query_as::<_, TradeRow>(
             r#"
-            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
             FROM trades
             ORDER BY timestamp DESC
             LIMIT ?
@@ -125,11 +991,16 @@ impl Database {
 
     /// Get daily performance stats
     pub async fn get_daily_stats(&self) -> Result<PerformanceStats> {
+        self.get_account_daily_stats(None).await
+    }
+
+    /// Daily performance stats of one account, or of all accounts for `None`
+    pub async fn get_account_daily_stats(&self, account: Option<&str>) -> Result<PerformanceStats> {
         let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
         
         let rows = sqlx::query_as::<_, TradeRow>(
             r#"
-            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
             FROM trades
             WHERE timestamp LIKE ?
             ORDER BY timestamp DESC
@@ -139,7 +1010,11 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
 
-        let trades: Vec<Trade> = rows.into_iter().filter_map(|r| r.try_into().ok()).collect();
+        let trades: Vec<Trade> = rows
+            .into_iter()
+            .filter_map(|r| Trade::try_from(r).ok())
+            .filter(|t| account.is_none_or(|a| t.account_id.as_deref().unwrap_or(DEFAULT_ACCOUNT_ID) == a))
+            .collect();
         
         let total_trades = trades.len();
         // Note: PnL calculation requires position tracking - simplified here
@@ -168,6 +1043,12 @@ struct TradeRow {
     size: String,
     fee: String,
     timestamp: String,
+    status: String,
+    strategy: Option<String>,
+    account: Option<String>,
+    trace_id: Option<String>,
+    tags: Option<String>,
+    edge: Option<String>,
 }
 
 impl TryFrom<TradeRow> for Trade {
@@ -190,6 +1071,81 @@ impl TryFrom<TradeRow> for Trade {
             size: row.size.parse()?,
             fee: row.fee.parse()?,
             timestamp: row.timestamp.parse()?,
+            status: if row.status == TradeStatus::Resting.as_str() {
+                TradeStatus::Resting
+            } else {
+                TradeStatus::Filled
+            },
+            strategy_name: row.strategy,
+            account_id: row.account,
+            trace_id: row.trace_id.and_then(|id| id.parse().ok()).unwrap_or_default(),
+            tags: row.tags.and_then(|t| serde_json::from_str(&t).ok()).unwrap_or_default(),
+            edge: row.edge.and_then(|e| e.parse().ok()),
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SnapshotRow {
+    timestamp: String,
+    balance: String,
+    positions: String,
+    label: String,
+    account: Option<String>,
+}
+
+impl TryFrom<SnapshotRow> for HoldingsSnapshot {
+    type Error = anyhow::Error;
+
+    fn try_from(row: SnapshotRow) -> std::result::Result<Self, Self::Error> {
+        Ok(HoldingsSnapshot {
+            timestamp: row.timestamp.parse()?,
+            balance: row.balance.parse()?,
+            positions: serde_json::from_str(&row.positions)?,
+            label: row.label,
+            account_id: row.account,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct LeaderboardRow {
+    timestamp: String,
+    traders: String,
+}
+
+impl TryFrom<LeaderboardRow> for LeaderboardSnapshot {
+    type Error = anyhow::Error;
+
+    fn try_from(row: LeaderboardRow) -> std::result::Result<Self, Self::Error> {
+        Ok(LeaderboardSnapshot {
+            timestamp: row.timestamp.parse()?,
+            traders: serde_json::from_str(&row.traders)?,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct MmPnlRow {
+    market_id: String,
+    inventory: String,
+    avg_cost: String,
+    realized_pnl: String,
+    fills: i64,
+    volume: String,
+}
+
+impl TryFrom<MmPnlRow> for MmPnl {
+    type Error = anyhow::Error;
+
+    fn try_from(row: MmPnlRow) -> std::result::Result<Self, Self::Error> {
+        Ok(MmPnl {
+            market_id: row.market_id,
+            inventory: row.inventory.parse()?,
+            avg_cost: row.avg_cost.parse()?,
+            realized_pnl: row.realized_pnl.parse()?,
+            fills: row.fills as u32,
+            volume: row.volume.parse()?,
         })
     }
 }
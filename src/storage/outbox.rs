@@ -0,0 +1,139 @@
+//! Outbound Telegram notifications waiting for delivery, kept across
+//! restarts
+
+use super::Database;
+use crate::error::Result;
+use crate::notify::Priority;
+use chrono::{DateTime, Utc};
+
+/// A queued notification
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxMessage {
+    pub id: i64,
+    pub priority: Priority,
+    pub text: String,
+    pub parse_mode: String,
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+}
+
+impl Database {
+    /// Queue a message for delivery unless one with the same hash was
+    /// queued since `dedup_since`; returns whether it was queued
+    pub async fn enqueue_notification(
+        &self,
+        hash: &str,
+        priority: Priority,
+        text: &str,
+        parse_mode: &str,
+        now: DateTime<Utc>,
+        dedup_since: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO outbox (hash, priority, text, parse_mode, created_at, attempts, next_attempt_at)
+            SELECT ?, ?, ?, ?, ?, 0, ?
+            WHERE NOT EXISTS (SELECT 1 FROM outbox WHERE hash = ? AND created_at >= ?)
+            "#,
+        )
+        .bind(hash)
+        .bind(priority.rank())
+        .bind(text)
+        .bind(parse_mode)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .bind(hash)
+        .bind(dedup_since.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Undelivered messages due by `now`, most important first, then oldest
+    pub async fn due_notifications(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<OutboxMessage>> {
+        let rows = sqlx::query_as::<_, (i64, i64, String, String, i64)>(
+            r#"
+            SELECT id, priority, text, parse_mode, attempts FROM outbox
+            WHERE delivered_at IS NULL AND next_attempt_at <= ?
+            ORDER BY priority DESC, id
+            LIMIT ?
+            "#,
+        )
+        .bind(now.to_rfc3339())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, priority, text, parse_mode, attempts)| OutboxMessage {
+                id,
+                priority: Priority::from_rank(priority),
+                text,
+                parse_mode,
+                attempts: attempts as u32,
+            })
+            .collect())
+    }
+
+    pub async fn mark_notification_delivered(&self, id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE outbox SET delivered_at = ? WHERE id = ?")
+            .bind(at.to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt and when to try again
+    pub async fn reschedule_notification(&self, id: i64, attempts: u32, next_attempt_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE outbox SET attempts = ?, next_attempt_at = ? WHERE id = ?")
+            .bind(attempts as i64)
+            .bind(next_attempt_at.to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Give up on a message
+    pub async fn drop_notification(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM outbox WHERE id = ?").bind(id).execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Give up on the undelivered messages less important than `priority`;
+    /// returns how many were dropped
+    pub async fn drop_notifications_below(&self, priority: Priority) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM outbox WHERE delivered_at IS NULL AND priority < ?")
+            .bind(priority.rank())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Forget messages delivered before `before`, once they no longer
+    /// matter for deduplication
+    pub async fn prune_delivered_notifications(&self, before: DateTime<Utc>) -> Result<()> {
+        sqlx::query("DELETE FROM outbox WHERE delivered_at IS NOT NULL AND delivered_at < ?")
+            .bind(before.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Messages waiting for delivery
+    pub async fn pending_notification_count(&self) -> Result<u64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM outbox WHERE delivered_at IS NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as u64)
+    }
+}
@@ -0,0 +1,145 @@
+//! Open positions rebuilt from fills, joined with cached market metadata
+
+use super::Database;
+use crate::error::Result;
+use crate::strategy::market_maker::MmPnl;
+use crate::types::{Market, Side, Trade};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// Shares held of one token by one account
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenPosition {
+    pub account_id: Option<String>,
+    pub market_id: String,
+    pub token_id: String,
+    pub size: Decimal,
+    /// Average cost of the shares held
+    pub avg_price: Decimal,
+    /// Strategy of the latest buy into the position
+    pub strategy_name: Option<String>,
+}
+
+/// An open position with its market as last cached, if it has been
+#[derive(Debug, Clone)]
+pub struct LadderPosition {
+    pub position: OpenPosition,
+    pub market: Option<Market>,
+}
+
+impl Database {
+    /// Cache a market's metadata, replacing what was stored for it
+    pub async fn cache_market(&self, market: &Market) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO market_cache (id, data, updated_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(&market.id)
+        .bind(serde_json::to_string(market)?)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cached metadata of a market
+    pub async fn get_cached_market(&self, market_id: &str) -> Result<Option<Market>> {
+        let data = sqlx::query_scalar::<_, String>("SELECT data FROM market_cache WHERE id = ?")
+            .bind(market_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(data.and_then(|d| serde_json::from_str(&d).ok()))
+    }
+
+    /// Tokens held, per account, from the filled trades; sorted by market
+    pub async fn get_open_positions(&self) -> Result<Vec<OpenPosition>> {
+        // (market, token, account) -> average-cost book and latest buyer
+        type Key = (String, String, Option<String>);
+        let mut books: BTreeMap<Key, (MmPnl, Option<String>)> = BTreeMap::new();
+        for trade in self.get_filled_trades().await? {
+            let (book, strategy) = books
+                .entry((trade.market_id.clone(), trade.token_id.clone(), trade.account_id.clone()))
+                .or_insert_with(|| (MmPnl::new(&trade.market_id), None));
+            book.record_fill(trade.side, trade.price, trade.size);
+            if trade.side == Side::Buy {
+                *strategy = trade.strategy_name.clone();
+            }
+        }
+
+        Ok(books
+            .into_iter()
+            .filter(|(_, (book, _))| book.inventory > Decimal::ZERO)
+            .map(|((market_id, token_id, account_id), (book, strategy_name))| OpenPosition {
+                account_id,
+                market_id,
+                token_id,
+                size: book.inventory,
+                avg_price: book.avg_cost,
+                strategy_name,
+            })
+            .collect())
+    }
+
+    /// Open positions as trades holding their size at average cost, carrying
+    /// the strategy, tags and edge of the latest buy into them
+    pub async fn get_open_position_trades(&self) -> Result<Vec<Trade>> {
+        type Key = (String, String, Option<String>);
+        let mut books: BTreeMap<Key, (MmPnl, Option<Trade>)> = BTreeMap::new();
+        for trade in self.get_filled_trades().await? {
+            let (book, latest_buy) = books
+                .entry((trade.market_id.clone(), trade.token_id.clone(), trade.account_id.clone()))
+                .or_insert_with(|| (MmPnl::new(&trade.market_id), None));
+            book.record_fill(trade.side, trade.price, trade.size);
+            if trade.side == Side::Buy {
+                *latest_buy = Some(trade);
+            }
+        }
+
+        Ok(books
+            .into_values()
+            .filter(|(book, _)| book.inventory > Decimal::ZERO)
+            .filter_map(|(book, latest_buy)| {
+                Some(Trade {
+                    price: book.avg_cost,
+                    size: book.inventory,
+                    fee: Decimal::ZERO,
+                    ..latest_buy?
+                })
+            })
+            .collect())
+    }
+
+    /// Markets with open positions, sorted
+    pub async fn get_open_position_markets(&self) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = self
+            .get_open_positions()
+            .await?
+            .into_iter()
+            .map(|p| p.market_id)
+            .collect();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    /// Open positions joined with the cached metadata of their markets
+    pub async fn get_ladder_positions(&self) -> Result<Vec<LadderPosition>> {
+        let mut markets: BTreeMap<String, Option<Market>> = BTreeMap::new();
+        let mut ladder = Vec::new();
+        for position in self.get_open_positions().await? {
+            if !markets.contains_key(&position.market_id) {
+                let market = self.get_cached_market(&position.market_id).await?;
+                markets.insert(position.market_id.clone(), market);
+            }
+            ladder.push(LadderPosition {
+                market: markets[&position.market_id].clone(),
+                position,
+            });
+        }
+
+        Ok(ladder)
+    }
+}
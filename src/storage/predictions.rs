@@ -0,0 +1,185 @@
+//! Model predictions scored against how their markets resolved
+//!
+//! The first prediction the bot makes on a market is stored (later ones
+//! drift toward the market price as resolution nears and say little about
+//! the model). Once the market resolves the row records the outcome, and
+//! [`Database::accuracy_report`] scores the resolved predictions.
+
+use super::Database;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Probabilities are clamped this far from 0 and 1 for log-loss
+const LOG_LOSS_EPSILON: Decimal = dec!(0.001);
+
+/// Number of equal-width predicted-probability bins in the calibration table
+const CALIBRATION_BINS: u32 = 10;
+
+/// The model's probability of YES for a market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPrediction {
+    pub market_id: String,
+    pub predicted_at: DateTime<Utc>,
+    /// Model probability of YES
+    pub probability: Decimal,
+    /// YES price when predicted
+    pub market_price: Decimal,
+    pub confidence: Decimal,
+    /// Hash of the prompt template the prediction was made with
+    #[serde(default)]
+    pub prompt_hash: Option<String>,
+}
+
+/// Predictions in one probability bin and how often YES actually won
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationBin {
+    pub lower: Decimal,
+    pub upper: Decimal,
+    pub count: usize,
+    pub mean_predicted: Decimal,
+    pub realized_frequency: Decimal,
+}
+
+/// Scores of the resolved predictions made in a time range
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AccuracyReport {
+    pub markets: usize,
+    /// Mean squared error of the model probability (0 is perfect, 0.25 a coin flip)
+    pub brier_score: Decimal,
+    /// Brier score of the market price at prediction time, for comparison
+    pub market_brier_score: Decimal,
+    pub log_loss: Decimal,
+    /// Non-empty bins, lowest probability first
+    pub calibration: Vec<CalibrationBin>,
+}
+
+impl AccuracyReport {
+    /// Score `(prediction, YES won)` pairs
+    pub fn from_resolved(resolved: &[(StoredPrediction, bool)]) -> Self {
+        if resolved.is_empty() {
+            return Self::default();
+        }
+        let n = Decimal::from(resolved.len());
+        let outcome = |won: bool| if won { Decimal::ONE } else { Decimal::ZERO };
+
+        let brier_score = resolved
+            .iter()
+            .map(|(p, won)| (p.probability - outcome(*won)).powi(2))
+            .sum::<Decimal>()
+            / n;
+        let market_brier_score = resolved
+            .iter()
+            .map(|(p, won)| (p.market_price - outcome(*won)).powi(2))
+            .sum::<Decimal>()
+            / n;
+        let log_loss = -resolved
+            .iter()
+            .map(|(p, won)| {
+                let probability = p.probability.clamp(LOG_LOSS_EPSILON, Decimal::ONE - LOG_LOSS_EPSILON);
+                if *won { probability.ln() } else { (Decimal::ONE - probability).ln() }
+            })
+            .sum::<Decimal>()
+            / n;
+
+        let width = Decimal::ONE / Decimal::from(CALIBRATION_BINS);
+        let calibration = (0..CALIBRATION_BINS)
+            .filter_map(|bin| {
+                let lower = width * Decimal::from(bin);
+                let upper = lower + width;
+                let last = bin == CALIBRATION_BINS - 1;
+                let members: Vec<_> = resolved
+                    .iter()
+                    .filter(|(p, _)| p.probability >= lower && (p.probability < upper || last))
+                    .collect();
+                if members.is_empty() {
+                    return None;
+                }
+                let count = Decimal::from(members.len());
+                Some(CalibrationBin {
+                    lower,
+                    upper,
+                    count: members.len(),
+                    mean_predicted: members.iter().map(|(p, _)| p.probability).sum::<Decimal>() / count,
+                    realized_frequency: Decimal::from(members.iter().filter(|(_, won)| *won).count()) / count,
+                })
+            })
+            .collect();
+
+        Self {
+            markets: resolved.len(),
+            brier_score,
+            market_brier_score,
+            log_loss,
+            calibration,
+        }
+    }
+}
+
+impl Database {
+    /// Store a prediction unless its market already has one
+    pub async fn save_prediction(&self, prediction: &StoredPrediction) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO predictions (market_id, data, predicted_at) VALUES (?, ?, ?)")
+            .bind(&prediction.market_id)
+            .bind(serde_json::to_string(prediction)?)
+            .bind(prediction.predicted_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Markets with a prediction still waiting on the outcome
+    pub async fn get_unresolved_prediction_markets(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT market_id FROM predictions WHERE outcome IS NULL")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    /// Record whether YES won on `market_id`
+    pub async fn resolve_prediction(&self, market_id: &str, yes_won: bool) -> Result<()> {
+        sqlx::query("UPDATE predictions SET outcome = ? WHERE market_id = ?")
+            .bind(yes_won)
+            .bind(market_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether YES won, per market with a resolved prediction
+    pub async fn get_resolved_outcomes(&self) -> Result<HashMap<String, bool>> {
+        let rows = sqlx::query_as::<_, (String, bool)>("SELECT market_id, outcome FROM predictions WHERE outcome IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Score the predictions made within `range` whose markets have resolved
+    pub async fn accuracy_report(&self, range: Range<DateTime<Utc>>) -> Result<AccuracyReport> {
+        let rows = sqlx::query_as::<_, (String, bool)>(
+            r#"
+            SELECT data, outcome FROM predictions
+            WHERE outcome IS NOT NULL AND predicted_at >= ? AND predicted_at < ?
+            ORDER BY predicted_at
+            "#,
+        )
+        .bind(range.start.to_rfc3339())
+        .bind(range.end.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let resolved: Vec<(StoredPrediction, bool)> = rows
+            .iter()
+            .filter_map(|(data, won)| Some((serde_json::from_str(data).ok()?, *won)))
+            .collect();
+        Ok(AccuracyReport::from_resolved(&resolved))
+    }
+}
@@ -0,0 +1,74 @@
+//! Daily YES price of every scanned market
+//!
+//! One row per market and UTC day, overwritten by each scan so it ends up
+//! holding the day's last price. Benchmarks replay these instead of
+//! fetching price history from the API.
+
+use super::Database;
+use crate::error::Result;
+use crate::types::Market;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A market's YES price at one scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceSnapshot {
+    pub market_id: String,
+    pub yes_price: Decimal,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl Database {
+    /// Record the YES price of each market with one as today's snapshot
+    pub async fn save_price_snapshots(&self, markets: &[Market], now: DateTime<Utc>) -> Result<()> {
+        let day = now.format("%Y-%m-%d").to_string();
+        let mut tx = self.pool.begin().await?;
+        for market in markets {
+            let Some(yes_price) = market.yes_price() else {
+                continue;
+            };
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO price_snapshots (market_id, day, yes_price, taken_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&market.id)
+            .bind(&day)
+            .bind(yes_price.to_string())
+            .bind(now.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Snapshots taken in `[from, to)`, oldest first
+    pub async fn get_price_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PriceSnapshot>> {
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT market_id, yes_price, taken_at FROM price_snapshots
+            WHERE taken_at >= ? AND taken_at < ?
+            ORDER BY taken_at, market_id
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(market_id, yes_price, taken_at)| {
+                Some(PriceSnapshot {
+                    market_id,
+                    yes_price: yes_price.parse().ok()?,
+                    taken_at: taken_at.parse().ok()?,
+                })
+            })
+            .collect())
+    }
+}
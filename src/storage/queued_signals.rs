@@ -0,0 +1,33 @@
+//! Signals waiting for a free position slot, kept across restarts
+
+use super::Database;
+use crate::error::Result;
+use crate::risk::QueuedSignal;
+
+impl Database {
+    /// Replace the stored queue with `entries`
+    pub async fn save_position_queue(&self, entries: &[QueuedSignal]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM queued_signals").execute(&mut *tx).await?;
+        for entry in entries {
+            sqlx::query("INSERT INTO queued_signals (market_id, data, queued_at) VALUES (?, ?, ?)")
+                .bind(&entry.signal.market_id)
+                .bind(serde_json::to_string(entry)?)
+                .bind(entry.queued_at.to_rfc3339())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// The stored queue, oldest first; unreadable rows are skipped
+    pub async fn load_position_queue(&self) -> Result<Vec<QueuedSignal>> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT data FROM queued_signals ORDER BY queued_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().filter_map(|data| serde_json::from_str(data).ok()).collect())
+    }
+}
@@ -0,0 +1,64 @@
+//! Size ramp progress of strategies and market categories
+//!
+//! One row per `RampPolicy` key, rewritten after every close so the ramp
+//! resumes where it was after a restart.
+
+use super::Database;
+use crate::error::Result;
+use crate::risk::RampState;
+use std::collections::HashMap;
+
+impl Database {
+    /// Store the ramp state of `key` (`strategy:<name>` or `category:<name>`)
+    pub async fn save_ramp_state(&self, key: &str, state: &RampState) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO ramp_state (key, level, trades, wins, losing_streak, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(key)
+        .bind(state.level.to_string())
+        .bind(state.trades as i64)
+        .bind(state.wins as i64)
+        .bind(state.losing_streak as i64)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// All stored ramp states, by key
+    pub async fn load_ramp_states(&self) -> Result<HashMap<String, RampState>> {
+        let rows = sqlx::query_as::<_, RampStateRow>(
+            "SELECT key, level, trades, wins, losing_streak FROM ramp_state",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some((
+                    row.key,
+                    RampState {
+                        level: row.level.parse().ok()?,
+                        trades: row.trades as u32,
+                        wins: row.wins as u32,
+                        losing_streak: row.losing_streak as u32,
+                    },
+                ))
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RampStateRow {
+    key: String,
+    level: String,
+    trades: i64,
+    wins: i64,
+    losing_streak: i64,
+}
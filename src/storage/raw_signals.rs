@@ -0,0 +1,82 @@
+//! The raw signal stream and the LLM extractions made from it, kept so the
+//! signal processor can be replayed offline with other parameters
+
+use super::Database;
+use crate::error::Result;
+use crate::ingester::RawSignal;
+use chrono::{DateTime, Utc};
+
+impl Database {
+    /// Record a message as it arrived from its source
+    pub async fn record_raw_signal(&self, signal: &RawSignal) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO raw_signals (source, source_id, content, author, author_trust, timestamp, metadata)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&signal.source)
+        .bind(&signal.source_id)
+        .bind(&signal.content)
+        .bind(&signal.author)
+        .bind(signal.author_trust)
+        .bind(signal.timestamp.to_rfc3339())
+        .bind(signal.metadata.as_ref().map(|m| m.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Messages that arrived in `[from, to)`, in arrival order
+    pub async fn get_raw_signals(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RawSignal>> {
+        let rows = sqlx::query_as::<_, (String, String, String, String, f64, String, Option<String>)>(
+            r#"
+            SELECT source, source_id, content, author, author_trust, timestamp, metadata FROM raw_signals
+            WHERE timestamp >= ? AND timestamp < ?
+            ORDER BY timestamp, id
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(source, source_id, content, author, author_trust, timestamp, metadata)| {
+                Some(RawSignal {
+                    source,
+                    source_id,
+                    content,
+                    author,
+                    author_trust,
+                    timestamp: timestamp.parse().ok()?,
+                    metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+                    trace_id: Default::default(),
+                })
+            })
+            .collect())
+    }
+
+    /// The LLM's extraction from a message, by content hash
+    pub async fn get_llm_extraction(&self, content_hash: &str) -> Result<Option<String>> {
+        let response = sqlx::query_scalar::<_, String>("SELECT response FROM llm_extractions WHERE content_hash = ?")
+            .bind(content_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(response)
+    }
+
+    pub async fn save_llm_extraction(&self, content_hash: &str, response: &str, now: DateTime<Utc>) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO llm_extractions (content_hash, response, created_at) VALUES (?, ?, ?)")
+            .bind(content_hash)
+            .bind(response)
+            .bind(now.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Resting limit orders and the signals that placed them, so a restart
+//! re-adopts them
+
+use super::Database;
+use crate::error::Result;
+use crate::strategy::resting::RestingOrder;
+
+impl Database {
+    /// Record an order resting on the book
+    pub async fn save_resting_order(&self, order: &RestingOrder) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO resting_orders (order_id, market_id, data, placed_at) VALUES (?, ?, ?, ?)")
+            .bind(&order.trade.order_id)
+            .bind(&order.signal.market_id)
+            .bind(serde_json::to_string(order)?)
+            .bind(order.placed_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stop tracking an order that left the book: its trade is marked
+    /// filled, or deleted when the order was cancelled unfilled
+    pub async fn close_resting_order(&self, order_id: &str, filled: bool) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM resting_orders WHERE order_id = ?")
+            .bind(order_id)
+            .execute(&mut *tx)
+            .await?;
+        if filled {
+            sqlx::query("UPDATE trades SET status = 'filled', filled_at = ? WHERE order_id = ? AND status = 'resting'")
+                .bind(chrono::Utc::now().to_rfc3339())
+                .bind(order_id)
+                .execute(&mut *tx)
+                .await?;
+        } else {
+            sqlx::query("DELETE FROM trades WHERE order_id = ? AND status = 'resting'")
+                .bind(order_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Orders still resting, oldest first; unreadable rows are skipped
+    pub async fn load_resting_orders(&self) -> Result<Vec<RestingOrder>> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT data FROM resting_orders ORDER BY placed_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().filter_map(|data| serde_json::from_str(data).ok()).collect())
+    }
+}
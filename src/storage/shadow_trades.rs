@@ -0,0 +1,155 @@
+//! Paper fills of shadow-mode strategies, marked to later prices for
+//! counterfactual P&L
+
+use super::Database;
+use crate::error::Result;
+use crate::types::Side;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A fill a shadow strategy would have had
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowTrade {
+    pub id: String,
+    pub strategy: String,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    /// Simulated fill price
+    pub price: Decimal,
+    pub size: Decimal,
+    pub opened_at: DateTime<Utc>,
+    /// Latest observed price of the token
+    pub mark_price: Decimal,
+    pub marked_at: DateTime<Utc>,
+}
+
+impl ShadowTrade {
+    /// Counterfactual P&L at the latest mark
+    pub fn pnl(&self) -> Decimal {
+        match self.side {
+            Side::Buy => (self.mark_price - self.price) * self.size,
+            Side::Sell => (self.price - self.mark_price) * self.size,
+        }
+    }
+}
+
+/// Counterfactual P&L of one shadow strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShadowPnl {
+    pub strategy: String,
+    pub trades: usize,
+    /// Notional at the simulated fill prices
+    pub volume: Decimal,
+    /// Mark-to-market P&L of all shadow fills
+    pub pnl: Decimal,
+}
+
+impl Database {
+    /// Store a shadow fill
+    pub async fn save_shadow_trade(&self, trade: &ShadowTrade) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO shadow_trades
+                (id, strategy, market_id, token_id, side, price, size, opened_at, mark_price, marked_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&trade.id)
+        .bind(&trade.strategy)
+        .bind(&trade.market_id)
+        .bind(&trade.token_id)
+        .bind(format!("{:?}", trade.side))
+        .bind(trade.price.to_string())
+        .bind(trade.size.to_string())
+        .bind(trade.opened_at.to_rfc3339())
+        .bind(trade.mark_price.to_string())
+        .bind(trade.marked_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark every shadow fill in `token_id` to `price`
+    pub async fn mark_shadow_trades(&self, token_id: &str, price: Decimal, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE shadow_trades SET mark_price = ?, marked_at = ? WHERE token_id = ?")
+            .bind(price.to_string())
+            .bind(at.to_rfc3339())
+            .bind(token_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Shadow fills, oldest first
+    pub async fn get_shadow_trades(&self) -> Result<Vec<ShadowTrade>> {
+        let rows = sqlx::query_as::<_, ShadowTradeRow>(
+            r#"
+            SELECT id, strategy, market_id, token_id, side, price, size, opened_at, mark_price, marked_at
+            FROM shadow_trades
+            ORDER BY opened_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Counterfactual P&L per shadow strategy, by strategy name
+    pub async fn get_shadow_pnl(&self) -> Result<Vec<ShadowPnl>> {
+        let mut by_strategy: BTreeMap<String, ShadowPnl> = BTreeMap::new();
+        for trade in self.get_shadow_trades().await? {
+            let pnl = by_strategy.entry(trade.strategy.clone()).or_insert_with(|| ShadowPnl {
+                strategy: trade.strategy.clone(),
+                ..Default::default()
+            });
+            pnl.trades += 1;
+            pnl.volume += trade.price * trade.size;
+            pnl.pnl += trade.pnl();
+        }
+
+        Ok(by_strategy.into_values().collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ShadowTradeRow {
+    id: String,
+    strategy: String,
+    market_id: String,
+    token_id: String,
+    side: String,
+    price: String,
+    size: String,
+    opened_at: String,
+    mark_price: String,
+    marked_at: String,
+}
+
+impl TryFrom<ShadowTradeRow> for ShadowTrade {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ShadowTradeRow) -> std::result::Result<Self, Self::Error> {
+        Ok(ShadowTrade {
+            id: row.id,
+            strategy: row.strategy,
+            market_id: row.market_id,
+            token_id: row.token_id,
+            side: match row.side.as_str() {
+                "Buy" => Side::Buy,
+                "Sell" => Side::Sell,
+                other => anyhow::bail!("unknown side: {}", other),
+            },
+            price: row.price.parse()?,
+            size: row.size.parse()?,
+            opened_at: row.opened_at.parse()?,
+            mark_price: row.mark_price.parse()?,
+            marked_at: row.marked_at.parse()?,
+        })
+    }
+}
@@ -0,0 +1,192 @@
+//! Outcomes of ingested signals, for learning author trust
+//!
+//! Each author behind an aggregated signal gets a row when the signal is
+//! emitted. Once the signal's timeframe has passed the row is resolved: did
+//! the token move in the predicted direction?
+
+use super::Database;
+use crate::error::Result;
+use crate::ingester::SignalDirection;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+/// One author's call and, once resolved, whether it was right
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalOutcome {
+    /// Row ID (0 until stored)
+    pub id: i64,
+    pub author: String,
+    pub token: String,
+    pub direction: SignalDirection,
+    pub entry_price: Decimal,
+    pub signal_at: DateTime<Utc>,
+    /// When the signal's timeframe ends
+    pub resolve_at: DateTime<Utc>,
+    pub exit_price: Option<Decimal>,
+    /// Whether the price moved the predicted way; `None` while pending
+    pub hit: Option<bool>,
+}
+
+impl SignalOutcome {
+    /// Pending outcome for a directional call over `timeframe` ("15m", "4h", "1d")
+    pub fn pending(
+        author: impl Into<String>,
+        token: impl Into<String>,
+        direction: SignalDirection,
+        timeframe: &str,
+        entry_price: Decimal,
+        signal_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: 0,
+            author: author.into(),
+            token: token.into(),
+            direction,
+            entry_price,
+            signal_at,
+            resolve_at: signal_at + parse_timeframe(timeframe),
+            exit_price: None,
+            hit: None,
+        }
+    }
+
+    /// Score the call against the price at the end of its timeframe
+    pub fn resolve(&mut self, exit_price: Decimal) {
+        self.exit_price = Some(exit_price);
+        self.hit = Some(match self.direction {
+            SignalDirection::Bullish => exit_price > self.entry_price,
+            SignalDirection::Bearish => exit_price < self.entry_price,
+            SignalDirection::Neutral => exit_price == self.entry_price,
+        });
+    }
+}
+
+/// "5m" / "4h" / "1d" as a duration; unknown formats count as one hour
+pub fn parse_timeframe(timeframe: &str) -> Duration {
+    let timeframe = timeframe.trim().to_lowercase();
+    let Some(unit) = timeframe.chars().last() else {
+        return Duration::hours(1);
+    };
+    let amount: Option<i64> = timeframe[..timeframe.len() - unit.len_utf8()].parse().ok();
+    match (amount, unit) {
+        (Some(n), 'm') if n > 0 => Duration::minutes(n),
+        (Some(n), 'h') if n > 0 => Duration::hours(n),
+        (Some(n), 'd') if n > 0 => Duration::days(n),
+        (Some(n), 'w') if n > 0 => Duration::weeks(n),
+        _ => Duration::hours(1),
+    }
+}
+
+fn direction_str(direction: SignalDirection) -> &'static str {
+    match direction {
+        SignalDirection::Bullish => "bullish",
+        SignalDirection::Bearish => "bearish",
+        SignalDirection::Neutral => "neutral",
+    }
+}
+
+impl Database {
+    /// Store a pending signal outcome
+    pub async fn record_signal_outcome(&self, outcome: &SignalOutcome) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO signal_outcomes (author, token, direction, entry_price, signal_at, resolve_at, exit_price, hit)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&outcome.author)
+        .bind(&outcome.token)
+        .bind(direction_str(outcome.direction))
+        .bind(outcome.entry_price.to_string())
+        .bind(outcome.signal_at.to_rfc3339())
+        .bind(outcome.resolve_at.to_rfc3339())
+        .bind(outcome.exit_price.map(|p| p.to_string()))
+        .bind(outcome.hit)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Pending outcomes whose timeframe has ended by `now`
+    pub async fn get_due_signal_outcomes(&self, now: DateTime<Utc>) -> Result<Vec<SignalOutcome>> {
+        let rows = sqlx::query_as::<_, SignalOutcomeRow>(
+            r#"
+            SELECT id, author, token, direction, entry_price, signal_at, resolve_at, exit_price, hit
+            FROM signal_outcomes
+            WHERE hit IS NULL AND resolve_at <= ?
+            ORDER BY resolve_at ASC
+            "#,
+        )
+        .bind(now.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+
+    /// Store the result of a resolved outcome
+    pub async fn resolve_signal_outcome(&self, outcome: &SignalOutcome) -> Result<()> {
+        sqlx::query("UPDATE signal_outcomes SET exit_price = ?, hit = ? WHERE id = ?")
+            .bind(outcome.exit_price.map(|p| p.to_string()))
+            .bind(outcome.hit)
+            .bind(outcome.id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Resolved outcomes of signals made at or after `since`, oldest first
+    pub async fn get_resolved_signal_outcomes(&self, since: DateTime<Utc>) -> Result<Vec<SignalOutcome>> {
+        let rows = sqlx::query_as::<_, SignalOutcomeRow>(
+            r#"
+            SELECT id, author, token, direction, entry_price, signal_at, resolve_at, exit_price, hit
+            FROM signal_outcomes
+            WHERE hit IS NOT NULL AND signal_at >= ?
+            ORDER BY signal_at ASC
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(|r| r.try_into().ok()).collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SignalOutcomeRow {
+    id: i64,
+    author: String,
+    token: String,
+    direction: String,
+    entry_price: String,
+    signal_at: String,
+    resolve_at: String,
+    exit_price: Option<String>,
+    hit: Option<bool>,
+}
+
+impl TryFrom<SignalOutcomeRow> for SignalOutcome {
+    type Error = anyhow::Error;
+
+    fn try_from(row: SignalOutcomeRow) -> std::result::Result<Self, Self::Error> {
+        Ok(SignalOutcome {
+            id: row.id,
+            author: row.author,
+            token: row.token,
+            direction: match row.direction.as_str() {
+                "bullish" => SignalDirection::Bullish,
+                "bearish" => SignalDirection::Bearish,
+                "neutral" => SignalDirection::Neutral,
+                other => anyhow::bail!("unknown signal direction: {}", other),
+            },
+            entry_price: row.entry_price.parse()?,
+            signal_at: row.signal_at.parse()?,
+            resolve_at: row.resolve_at.parse()?,
+            exit_price: row.exit_price.map(|p| p.parse()).transpose()?,
+            hit: row.hit,
+        })
+    }
+}
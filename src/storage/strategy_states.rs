@@ -0,0 +1,60 @@
+//! Database checkpoints of in-memory strategy state, one row per
+//! [`StrategyState`] type
+//!
+//! States are stored as JSON like the rest of the database: decimals
+//! deserialize through `deserialize_any`, which compact binary formats
+//! don't support.
+
+use super::Database;
+use crate::error::Result;
+use crate::recovery::{StrategyState, MAX_STATE_AGE_SECS};
+use chrono::{DateTime, Duration, Utc};
+
+impl Database {
+    /// Checkpoint `state`, replacing the previous one of its type
+    pub async fn save_state<T: StrategyState>(&self, state: &T) -> Result<()> {
+        self.save_state_at(state, Utc::now()).await
+    }
+
+    pub(crate) async fn save_state_at<T: StrategyState>(&self, state: &T, saved_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO strategy_states (key, data, saved_at) VALUES (?, ?, ?)")
+            .bind(T::KEY)
+            .bind(serde_json::to_string(state)?)
+            .bind(saved_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The last checkpoint of `T`; `None` when there is none, it can't be
+    /// read, or it is older than [`MAX_STATE_AGE_SECS`] and the state
+    /// should be warmed up afresh
+    pub async fn load_state<T: StrategyState>(&self) -> Result<Option<T>> {
+        let row = sqlx::query_as::<_, (String, String)>("SELECT data, saved_at FROM strategy_states WHERE key = ?")
+            .bind(T::KEY)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some((data, saved_at)) = row else {
+            return Ok(None);
+        };
+
+        let age = DateTime::parse_from_rfc3339(&saved_at)
+            .map(|saved_at| Utc::now() - saved_at.with_timezone(&Utc))
+            .unwrap_or(Duration::MAX);
+        if age > Duration::seconds(MAX_STATE_AGE_SECS) {
+            tracing::info!("Discarding {} state saved {}m ago, re-warming", T::KEY, age.num_minutes());
+            return Ok(None);
+        }
+        match serde_json::from_str(&data) {
+            Ok(state) => {
+                tracing::info!("Restored {} state saved {}s ago", T::KEY, age.num_seconds());
+                Ok(Some(state))
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable {} state: {}", T::KEY, e);
+                Ok(None)
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{Trade, Side};
+    use crate::types::{Trade, TradeStatus, Side};
     use chrono::Utc;
     use rust_decimal_macros::dec;
 
@@ -18,6 +18,12 @@ mod tests {
             size: dec!(100),
             fee: dec!(0.50),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
         
         assert_eq!(trade.id, "trade1");
@@ -37,6 +43,12 @@ mod tests {
             size: dec!(200),
             fee: dec!(1.00),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
         
         let json = serde_json::to_string(&trade).unwrap();
@@ -107,4 +119,1026 @@ mod tests {
         let net_profit = gross_profit - fee;
         assert_eq!(net_profit, dec!(45));
     }
+
+    #[tokio::test]
+    async fn test_holdings_snapshot_roundtrip() {
+        use crate::storage::{Database, HoldingsSnapshot};
+        use std::collections::HashMap;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        assert!(db.latest_holdings_snapshot().await.unwrap().is_none());
+
+        let mut positions = HashMap::new();
+        positions.insert("token1".to_string(), dec!(150));
+        positions.insert("token2".to_string(), dec!(-20));
+        let snapshot = HoldingsSnapshot {
+            timestamp: Utc::now(),
+            balance: dec!(1042.50),
+            positions,
+            label: "shutdown".to_string(),
+            account_id: None,
+        };
+        db.save_holdings_snapshot(&snapshot).await.unwrap();
+
+        let loaded = db.latest_holdings_snapshot().await.unwrap().unwrap();
+        assert_eq!(loaded.balance, dec!(1042.50));
+        assert_eq!(loaded.positions.get("token2"), Some(&dec!(-20)));
+        assert_eq!(loaded.label, "shutdown");
+    }
+
+    #[tokio::test]
+    async fn test_mm_pnl_upsert() {
+        use crate::storage::Database;
+        use crate::strategy::market_maker::MmPnl;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let mut pnl = MmPnl::new("m1");
+        pnl.record_fill(Side::Buy, dec!(0.48), dec!(10));
+        db.save_mm_pnl(&pnl).await.unwrap();
+        pnl.record_fill(Side::Sell, dec!(0.52), dec!(10));
+        db.save_mm_pnl(&pnl).await.unwrap();
+
+        let loaded = db.get_mm_pnl().await.unwrap();
+        assert_eq!(loaded, vec![pnl]);
+        assert_eq!(loaded[0].realized_pnl, dec!(0.40));
+    }
+
+    #[tokio::test]
+    async fn test_pnl_by_strategy() {
+        use crate::storage::{Database, DEFAULT_STRATEGY_NAME};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let trade = |id: &str, side: Side, price, strategy: Option<&str>| Trade {
+            id: id.to_string(),
+            order_id: format!("order-{}", id),
+            token_id: "token1".to_string(),
+            market_id: "m1".to_string(),
+            side,
+            price,
+            size: dec!(10),
+            fee: dec!(0.05),
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: strategy.map(str::to_string),
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+
+        // Same token, separate books per strategy
+        db.save_trade(&trade("1", Side::Buy, dec!(0.40), Some("llm"))).await.unwrap();
+        db.save_trade(&trade("2", Side::Buy, dec!(0.60), Some("momentum"))).await.unwrap();
+        db.save_trade(&trade("3", Side::Sell, dec!(0.50), Some("llm"))).await.unwrap();
+        db.save_trade(&trade("4", Side::Sell, dec!(0.50), Some("momentum"))).await.unwrap();
+        db.save_trade(&trade("5", Side::Buy, dec!(0.50), None)).await.unwrap();
+
+        let pnl = db.get_pnl_by_strategy().await.unwrap();
+        let names: Vec<_> = pnl.iter().map(|p| p.strategy.as_str()).collect();
+        assert_eq!(names, vec!["llm", DEFAULT_STRATEGY_NAME, "momentum"]);
+
+        assert_eq!(pnl[0].trades, 2);
+        assert_eq!(pnl[0].realized_pnl, dec!(0.90)); // +1.00 - 0.10 fees
+        assert_eq!(pnl[1].realized_pnl, dec!(-0.05)); // open position, fee only
+        assert_eq!(pnl[2].realized_pnl, dec!(-1.10));
+
+        let recent = db.get_recent_trades(10).await.unwrap();
+        assert!(recent.iter().any(|t| t.strategy_name.as_deref() == Some("llm")));
+    }
+
+    #[tokio::test]
+    async fn test_pnl_by_account() {
+        use crate::config::DEFAULT_ACCOUNT_ID;
+        use crate::storage::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let trade = |id: &str, side: Side, price, account: Option<&str>| Trade {
+            id: id.to_string(),
+            order_id: format!("order-{}", id),
+            token_id: "token1".to_string(),
+            market_id: "m1".to_string(),
+            side,
+            price,
+            size: dec!(10),
+            fee: dec!(0.05),
+            timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: Some("llm".to_string()),
+            account_id: account.map(str::to_string),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+
+        // Same token and strategy, separate books per account
+        db.save_trade(&trade("1", Side::Buy, dec!(0.40), Some("personal"))).await.unwrap();
+        db.save_trade(&trade("2", Side::Buy, dec!(0.60), Some("fund"))).await.unwrap();
+        db.save_trade(&trade("3", Side::Sell, dec!(0.50), Some("personal"))).await.unwrap();
+        db.save_trade(&trade("4", Side::Buy, dec!(0.50), None)).await.unwrap();
+
+        let pnl = db.get_pnl_by_account().await.unwrap();
+        let names: Vec<_> = pnl.iter().map(|p| p.account.as_str()).collect();
+        assert_eq!(names, vec![DEFAULT_ACCOUNT_ID, "fund", "personal"]);
+        assert_eq!(pnl[1].trades, 1);
+        assert_eq!(pnl[1].realized_pnl, dec!(-0.05));
+        assert_eq!(pnl[2].trades, 2);
+        assert_eq!(pnl[2].realized_pnl, dec!(0.90));
+
+        assert_eq!(db.get_account_daily_stats(Some("personal")).await.unwrap().total_trades, 2);
+        assert_eq!(db.get_account_daily_stats(Some(DEFAULT_ACCOUNT_ID)).await.unwrap().total_trades, 1);
+        assert_eq!(db.get_daily_stats().await.unwrap().total_trades, 4);
+
+        let recent = db.get_recent_trades(10).await.unwrap();
+        assert!(recent.iter().any(|t| t.account_id.as_deref() == Some("fund")));
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_losses() {
+        use crate::storage::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let start = Utc::now();
+        let trade = |n: i64, side: Side, price, token: &str| Trade {
+            id: n.to_string(),
+            order_id: format!("order-{}", n),
+            token_id: token.to_string(),
+            market_id: "m1".to_string(),
+            side,
+            price,
+            size: dec!(10),
+            fee: dec!(0.05),
+            timestamp: start + chrono::Duration::seconds(n),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+
+        assert_eq!(db.get_consecutive_losses().await.unwrap(), 0);
+
+        // Win, then two losses; opening fills don't count
+        for t in [
+            trade(1, Side::Buy, dec!(0.40), "a"),
+            trade(2, Side::Sell, dec!(0.50), "a"),
+            trade(3, Side::Buy, dec!(0.60), "b"),
+            trade(4, Side::Buy, dec!(0.50), "c"),
+            trade(5, Side::Sell, dec!(0.55), "b"),
+            trade(6, Side::Sell, dec!(0.45), "c"),
+        ] {
+            db.save_trade(&t).await.unwrap();
+        }
+        assert_eq!(db.get_consecutive_losses().await.unwrap(), 2);
+        assert_eq!(db.get_closing_pnl("3").await.unwrap(), None);
+        assert_eq!(db.get_closing_pnl("2").await.unwrap(), Some(dec!(0.95)));
+        assert_eq!(db.get_closing_pnl("6").await.unwrap(), Some(dec!(-0.55)));
+
+        db.save_trade(&trade(7, Side::Buy, dec!(0.30), "a")).await.unwrap();
+        db.save_trade(&trade(8, Side::Sell, dec!(0.40), "a")).await.unwrap();
+        assert_eq!(db.get_consecutive_losses().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_market_event_grouping() {
+        use crate::storage::Database;
+        use crate::types::Market;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let market = |id: &str, event_id: Option<&str>, neg_risk| Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: dec!(0),
+            liquidity: dec!(0),
+            outcomes: vec![],
+            active: true,
+            closed: false,
+            event_id: event_id.map(str::to_string),
+            neg_risk,
+            category: None,
+            order_constraints: None,
+        };
+
+        db.save_market_event(&market("m2", Some("e1"), true)).await.unwrap();
+        db.save_market_event(&market("m1", Some("e1"), true)).await.unwrap();
+        db.save_market_event(&market("m3", Some("e2"), false)).await.unwrap();
+        db.save_market_event(&market("m4", None, false)).await.unwrap();
+        // Re-saving is an upsert
+        db.save_market_event(&market("m1", Some("e1"), true)).await.unwrap();
+
+        assert_eq!(db.get_event_markets("e1").await.unwrap(), vec!["m1", "m2"]);
+        assert_eq!(db.get_event_markets("e2").await.unwrap(), vec!["m3"]);
+        assert_eq!(db.get_neg_risk_events().await.unwrap(), vec!["e1"]);
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_snapshot_roundtrip() {
+        use crate::storage::{Database, LeaderboardSnapshot};
+        use crate::strategy::copy_trade::TopTrader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        assert!(db.latest_leaderboard_snapshot().await.unwrap().is_none());
+
+        let trader = TopTrader {
+            username: "alice".to_string(),
+            address: Some("0xabc".to_string()),
+            win_rate: 0.7,
+            total_profit: dec!(1200),
+            weight: 1.0,
+            updated_at: Utc::now(),
+            trades: 40,
+            volume_usdc: dec!(50000),
+            sharpe_30d: 0.4,
+            performance_score: 0.70,
+        };
+        db.save_leaderboard_snapshot(&LeaderboardSnapshot { timestamp: Utc::now(), traders: vec![] })
+            .await
+            .unwrap();
+        db.save_leaderboard_snapshot(&LeaderboardSnapshot { timestamp: Utc::now(), traders: vec![trader] })
+            .await
+            .unwrap();
+
+        let latest = db.latest_leaderboard_snapshot().await.unwrap().unwrap();
+        assert_eq!(latest.traders.len(), 1);
+        assert_eq!(latest.traders[0].username, "alice");
+        assert_eq!(latest.traders[0].trades, 40);
+        assert_eq!(latest.traders[0].volume_usdc, dec!(50000));
+    }
+
+    #[tokio::test]
+    async fn test_journal_timeline_interleaves_trades() {
+        use crate::storage::{Database, JournalEntry, JournalKind, TimelineEntry};
+        use crate::types::{Side, Trade, TradeStatus};
+        use chrono::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let start = Utc::now() - Duration::hours(3);
+
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "tok".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.4),
+            size: dec!(10),
+            fee: dec!(0),
+            timestamp: start + Duration::hours(1),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        db.save_trade(&trade).await.unwrap();
+
+        let mut note = JournalEntry::note(Some("m1".to_string()), "entering on news");
+        note.timestamp = start + Duration::minutes(30);
+        let mut pause = JournalEntry::manual(JournalKind::Pause, "paused");
+        pause.timestamp = start + Duration::hours(2);
+        let mut tier = JournalEntry::auto(JournalKind::DrawdownTier, "Normal → Reduced");
+        tier.timestamp = start + Duration::hours(2) + Duration::minutes(5);
+        let mut old = JournalEntry::note(None, "before the window");
+        old.timestamp = start - Duration::days(1);
+        for entry in [&pause, &note, &tier, &old] {
+            db.add_journal_entry(entry).await.unwrap();
+        }
+
+        let timeline = db.get_timeline(start).await.unwrap();
+        let kinds: Vec<String> = timeline
+            .iter()
+            .map(|e| match e {
+                TimelineEntry::Trade(t) => t.id.clone(),
+                TimelineEntry::Journal(j) => j.kind.as_str().to_string(),
+            })
+            .collect();
+        assert_eq!(kinds, vec!["note", "t1", "pause", "drawdown_tier"]);
+
+        match &timeline[0] {
+            TimelineEntry::Journal(j) => assert_eq!(j, &note),
+            other => panic!("expected note, got {:?}", other),
+        }
+
+        // Auto-recorded tier changes are not interventions
+        assert_eq!(db.count_manual_interventions(start).await.unwrap(), 2);
+        assert_eq!(db.count_manual_interventions(start - Duration::days(2)).await.unwrap(), 3);
+    }
+    #[tokio::test]
+    async fn test_signal_outcome_lifecycle() {
+        use crate::ingester::SignalDirection;
+        use crate::storage::{Database, SignalOutcome};
+        use chrono::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let now = Utc::now();
+
+        let short = SignalOutcome::pending("alice", "BTC", SignalDirection::Bullish, "15m", dec!(60000), now - Duration::hours(1));
+        let long = SignalOutcome::pending("bob", "ETH", SignalDirection::Bearish, "1d", dec!(3000), now - Duration::hours(1));
+        assert_eq!(long.resolve_at, now + Duration::hours(23));
+        db.record_signal_outcome(&short).await.unwrap();
+        db.record_signal_outcome(&long).await.unwrap();
+
+        // Only the 15m call is due
+        let mut due = db.get_due_signal_outcomes(now).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].author, "alice");
+        assert!(db.get_resolved_signal_outcomes(now - Duration::days(1)).await.unwrap().is_empty());
+
+        due[0].resolve(dec!(60500));
+        db.resolve_signal_outcome(&due[0]).await.unwrap();
+
+        let resolved = db.get_resolved_signal_outcomes(now - Duration::days(1)).await.unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].hit, Some(true));
+        assert_eq!(resolved[0].exit_price, Some(dec!(60500)));
+        assert!(db.get_due_signal_outcomes(now).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ladder_positions_join_cached_markets() {
+        use crate::storage::Database;
+        use crate::types::{Market, Outcome};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+
+        let start = Utc::now();
+        let trade = |n: i64, market: &str, token: &str, side: Side, size, account: Option<&str>| Trade {
+            id: n.to_string(),
+            order_id: format!("order-{}", n),
+            token_id: token.to_string(),
+            market_id: market.to_string(),
+            side,
+            price: dec!(0.40),
+            size,
+            fee: dec!(0),
+            timestamp: start + chrono::Duration::seconds(n),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: account.map(str::to_string),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        for t in [
+            trade(1, "m1", "yes1", Side::Buy, dec!(30), None),
+            trade(2, "m1", "yes1", Side::Sell, dec!(10), None),
+            trade(3, "m1", "yes1", Side::Buy, dec!(5), Some("fund")),
+            // Fully closed
+            trade(4, "m2", "yes2", Side::Buy, dec!(10), None),
+            trade(5, "m2", "yes2", Side::Sell, dec!(10), None),
+        ] {
+            db.save_trade(&t).await.unwrap();
+        }
+
+        let mut market = Market {
+            id: "m1".to_string(),
+            question: "Will it happen?".to_string(),
+            description: None,
+            end_date: Some(start + chrono::Duration::days(10)),
+            volume: dec!(1000),
+            liquidity: dec!(500),
+            outcomes: vec![Outcome { token_id: "yes1".to_string(), outcome: "Yes".to_string(), price: dec!(0.5) }],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+        db.cache_market(&market).await.unwrap();
+
+        let positions = db.get_ladder_positions().await.unwrap();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].position.account_id, None);
+        assert_eq!(positions[0].position.size, dec!(20));
+        assert_eq!(positions[0].position.avg_price, dec!(0.40));
+        assert_eq!(positions[1].position.account_id.as_deref(), Some("fund"));
+        assert!(positions.iter().all(|p| p.market.as_ref().map(|m| m.id.as_str()) == Some("m1")));
+        assert_eq!(db.get_open_position_markets().await.unwrap(), vec!["m1"]);
+
+        // A refresh replaces the cached end date
+        market.end_date = Some(start + chrono::Duration::days(40));
+        db.cache_market(&market).await.unwrap();
+        let cached = db.get_cached_market("m1").await.unwrap().unwrap();
+        assert_eq!(cached.end_date, market.end_date);
+        assert!(db.get_cached_market("m2").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_position_queue_survives_restart() {
+        use crate::risk::PositionQueue;
+        use crate::storage::Database;
+        use crate::types::Signal;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        let now = Utc::now();
+        let signal = |market: &str, edge| Signal {
+            market_id: market.to_string(),
+            token_id: format!("{}-yes", market),
+            side: Side::Buy,
+            model_probability: dec!(0.5) + edge,
+            market_probability: dec!(0.5),
+            edge,
+            confidence: dec!(0.8),
+            suggested_size: dec!(0.02),
+            timestamp: now,
+            strategy_name: Some("llm".to_string()),
+            epistemic_uncertainty: dec!(0),
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: dec!(0),
+            valid_until: None,
+            signal_score: None,
+        };
+
+        let mut queue = PositionQueue::new(chrono::Duration::minutes(30), 10);
+        queue.push(signal("a", dec!(0.08)), "A?", now - chrono::Duration::minutes(5));
+        queue.push(signal("b", dec!(0.20)), "B?", now);
+        {
+            let db = Database::connect(&path).await.unwrap();
+            db.save_position_queue(queue.entries()).await.unwrap();
+        }
+
+        let db = Database::connect(&path).await.unwrap();
+        let mut restored = PositionQueue::new(chrono::Duration::minutes(30), 10);
+        restored.restore(db.load_position_queue().await.unwrap());
+        assert_eq!(restored.entries(), queue.entries());
+
+        db.save_position_queue(&[]).await.unwrap();
+        assert!(db.load_position_queue().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_history_and_equity_curve() {
+        use crate::storage::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        assert!(db.compute_equity_curve().await.unwrap().is_empty());
+
+        let before = Utc::now();
+        db.save_portfolio_snapshot(dec!(1000), dec!(0), dec!(0), 0, dec!(1000)).await.unwrap();
+        db.save_portfolio_snapshot(dec!(1012.5), dec!(2.5), dec!(10), 3, dec!(940)).await.unwrap();
+        let after = Utc::now();
+
+        let history = db.get_portfolio_history(before, after).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].value, dec!(1012.5));
+        assert_eq!(history[1].unrealized_pnl, dec!(10));
+        assert_eq!(history[1].n_open_positions, 3);
+        assert_eq!(history[1].balance, dec!(940));
+        assert!(history[0].created_at <= history[1].created_at);
+
+        let curve = db.compute_equity_curve().await.unwrap();
+        assert_eq!(curve.iter().map(|(_, v)| *v).collect::<Vec<_>>(), vec![dec!(1000), dec!(1012.5)]);
+        assert_eq!(curve[1].0, history[1].created_at);
+
+        assert!(db
+            .get_portfolio_history(after + chrono::Duration::seconds(1), after + chrono::Duration::hours(1))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_accuracy_report_scores_resolved_predictions() {
+        use crate::storage::{Database, StoredPrediction};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let now = Utc::now();
+        let predict = |market_id: &str, probability| StoredPrediction {
+            market_id: market_id.to_string(),
+            predicted_at: now,
+            probability,
+            market_price: dec!(0.5),
+            confidence: dec!(0.7),
+            prompt_hash: None,
+        };
+
+        // (0.8 - 1)² + (0.3 - 0)² + (0.6 - 0)² = 0.04 + 0.09 + 0.36
+        db.save_prediction(&predict("a", dec!(0.8))).await.unwrap();
+        db.save_prediction(&predict("b", dec!(0.3))).await.unwrap();
+        db.save_prediction(&predict("c", dec!(0.6))).await.unwrap();
+        db.save_prediction(&predict("open", dec!(0.9))).await.unwrap();
+        // Only the first prediction of a market counts
+        db.save_prediction(&predict("a", dec!(0.1))).await.unwrap();
+        db.resolve_prediction("a", true).await.unwrap();
+        db.resolve_prediction("b", false).await.unwrap();
+        db.resolve_prediction("c", false).await.unwrap();
+        assert_eq!(db.get_unresolved_prediction_markets().await.unwrap(), vec!["open".to_string()]);
+
+        let range = now - chrono::Duration::days(1)..now + chrono::Duration::days(1);
+        let report = db.accuracy_report(range).await.unwrap();
+        assert_eq!(report.markets, 3);
+        assert_eq!(report.brier_score, dec!(0.49) / dec!(3));
+        assert_eq!(report.market_brier_score, dec!(0.25));
+        assert!(report.log_loss > rust_decimal::Decimal::ZERO);
+
+        let bins: Vec<_> = report.calibration.iter().map(|b| (b.lower, b.count, b.realized_frequency)).collect();
+        assert_eq!(bins, vec![(dec!(0.3), 1, dec!(0)), (dec!(0.6), 1, dec!(0)), (dec!(0.8), 1, dec!(1))]);
+
+        let before = now - chrono::Duration::days(2)..now - chrono::Duration::days(1);
+        assert_eq!(db.accuracy_report(before).await.unwrap().markets, 0);
+    }
+
+    #[tokio::test]
+    async fn test_trace_follows_signal_to_trade() {
+        use crate::storage::{Database, TraceEvent, TraceStage};
+        use crate::types::{Side, TraceId, Trade, TradeStatus};
+        use chrono::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let trace_id = TraceId::new();
+        let start = Utc::now() - Duration::minutes(10);
+
+        let stages = [
+            (TraceStage::RawSignal, "twitter 1 by @alice: BTC breaking out"),
+            (TraceStage::ParsedSignal, "BTC Bullish"),
+            (TraceStage::Signal, "BUY yes on m1"),
+        ];
+        for (i, (stage, detail)) in stages.iter().enumerate() {
+            let event = TraceEvent { timestamp: start + Duration::minutes(i as i64), ..TraceEvent::new(trace_id, *stage, *detail) };
+            db.record_trace_event(&event).await.unwrap();
+        }
+        // Another trace and the nil trace stay out
+        db.record_trace_event(&TraceEvent::new(TraceId::new(), TraceStage::Risk, "other")).await.unwrap();
+        db.record_trace_event(&TraceEvent::new(TraceId::default(), TraceStage::Risk, "untraced")).await.unwrap();
+
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "tok".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.4),
+            size: dec!(10),
+            fee: dec!(0),
+            timestamp: start + Duration::minutes(5),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id,
+            tags: Vec::new(),
+            edge: None,
+        };
+        db.save_trade(&trade).await.unwrap();
+        assert_eq!(db.get_trade("t1").await.unwrap().unwrap().trace_id, trace_id);
+        assert!(db.get_trade("missing").await.unwrap().is_none());
+
+        let events = db.get_trace_events(trace_id).await.unwrap();
+        let stages: Vec<TraceStage> = events.iter().map(|e| e.stage).collect();
+        assert_eq!(
+            stages,
+            vec![TraceStage::RawSignal, TraceStage::ParsedSignal, TraceStage::Signal, TraceStage::Trade]
+        );
+        assert!(events[3].detail.contains("trade t1"));
+        assert!(db.get_trace_events(TraceId::default()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_market_alert_crud() {
+        use crate::monitor::MarketAlertRule;
+        use crate::storage::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let rule: MarketAlertRule = "517310 price > 0.70 30m".parse().unwrap();
+
+        let first = db.add_market_alert(&rule).await.unwrap();
+        let second = db.add_market_alert(&"m2 hours_left < 6".parse().unwrap()).await.unwrap();
+        let alerts = db.get_market_alerts().await.unwrap();
+        assert_eq!(alerts.iter().map(|a| a.id).collect::<Vec<_>>(), vec![first, second]);
+        assert_eq!(alerts[0].rule, rule);
+        assert!(alerts[0].last_fired.is_none());
+
+        let fired_at = Utc::now();
+        db.mark_market_alert_fired(first, fired_at).await.unwrap();
+        let alerts = db.get_market_alerts().await.unwrap();
+        assert_eq!(alerts[0].last_fired.map(|at| at.timestamp()), Some(fired_at.timestamp()));
+
+        assert!(db.delete_market_alert(first).await.unwrap());
+        assert!(!db.delete_market_alert(first).await.unwrap());
+        assert_eq!(db.get_market_alerts().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_performance_by_tag() {
+        use crate::storage::Database;
+        use crate::types::{Side, Trade, TradeStatus};
+        use chrono::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let start = Utc::now() - Duration::days(10);
+        let fill = |id: &str, side: Side, price, days, tags: &[&str], edge| Trade {
+            id: id.to_string(),
+            order_id: format!("o-{}", id),
+            token_id: "tok".to_string(),
+            market_id: "m1".to_string(),
+            side,
+            price,
+            size: dec!(10),
+            fee: dec!(0),
+            timestamp: start + Duration::days(days),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            edge,
+        };
+
+        // Entered before the window under the old prompt, closed inside it
+        db.save_trade(&fill("t1", Side::Buy, dec!(0.40), 0, &["strategy:llm", "prompt:aaaa"], Some(dec!(0.10)))).await.unwrap();
+        db.save_trade(&fill("t2", Side::Sell, dec!(0.50), 3, &[], None)).await.unwrap();
+        // A losing round trip under the new prompt
+        db.save_trade(&fill("t3", Side::Buy, dec!(0.60), 4, &["strategy:llm", "prompt:bbbb"], Some(dec!(0.20)))).await.unwrap();
+        db.save_trade(&fill("t4", Side::Sell, dec!(0.55), 5, &["strategy:llm"], Some(dec!(0.30)))).await.unwrap();
+
+        let stored = db.get_trade("t3").await.unwrap().unwrap();
+        assert_eq!(stored.tags, vec!["strategy:llm", "prompt:bbbb"]);
+        assert_eq!(stored.edge, Some(dec!(0.20)));
+
+        let report = db.performance_by_tag(start + Duration::days(2), Utc::now()).await.unwrap();
+        let tags: Vec<&str> = report.iter().map(|p| p.tag.as_str()).collect();
+        assert_eq!(tags, vec!["prompt:aaaa", "prompt:bbbb", "strategy:llm"]);
+
+        let old_prompt = &report[0];
+        assert_eq!((old_prompt.trades, old_prompt.closes, old_prompt.wins), (0, 1, 1));
+        assert_eq!(old_prompt.realized_pnl, dec!(1.0));
+        assert!(old_prompt.avg_edge.is_none());
+
+        let new_prompt = &report[1];
+        assert_eq!((new_prompt.trades, new_prompt.closes, new_prompt.wins), (1, 1, 0));
+        assert_eq!(new_prompt.realized_pnl, dec!(-0.5));
+        assert_eq!(new_prompt.avg_edge, Some(dec!(0.20)));
+
+        let llm = &report[2];
+        assert_eq!((llm.trades, llm.closes, llm.wins), (2, 2, 1));
+        assert_eq!(llm.realized_pnl, dec!(0.5));
+        assert_eq!(llm.win_rate(), dec!(0.5));
+        assert_eq!(llm.avg_edge, Some(dec!(0.25)));
+    }
+
+    #[tokio::test]
+    async fn test_price_snapshots_and_llm_usage() {
+        use crate::storage::{Database, LlmUsageTotals};
+        use crate::types::{Market, Outcome};
+        use chrono::{Duration, TimeZone};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let market = |yes: rust_decimal::Decimal| Market {
+            id: "m1".to_string(),
+            question: "m1?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(0),
+            liquidity: dec!(0),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: yes },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: dec!(1) - yes },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+
+        // The day's last scan wins
+        let morning = Utc.with_ymd_and_hms(2024, 6, 3, 8, 0, 0).unwrap();
+        db.save_price_snapshots(&[market(dec!(0.60))], morning).await.unwrap();
+        db.save_price_snapshots(&[market(dec!(0.65))], morning + Duration::hours(10)).await.unwrap();
+        db.save_price_snapshots(&[market(dec!(0.70))], morning + Duration::days(1)).await.unwrap();
+        let snapshots = db.get_price_snapshots(morning, morning + Duration::days(2)).await.unwrap();
+        let prices: Vec<_> = snapshots.iter().map(|s| s.yes_price).collect();
+        assert_eq!(prices, vec![dec!(0.65), dec!(0.70)]);
+
+        let day = morning.date_naive();
+        let usage = LlmUsageTotals { calls: 2, input_tokens: 1_000, output_tokens: 200 };
+        db.record_llm_usage(day, usage).await.unwrap();
+        db.record_llm_usage(day, usage).await.unwrap();
+        db.record_llm_usage(day + Duration::days(30), usage).await.unwrap();
+        let june = db.get_llm_usage(day, day + Duration::days(7)).await.unwrap();
+        assert_eq!(june, LlmUsageTotals { calls: 4, input_tokens: 2_000, output_tokens: 400 });
+        // $3 / $15 per million tokens
+        assert_eq!(june.cost(dec!(3), dec!(15)), dec!(0.012));
+    }
+
+    #[tokio::test]
+    async fn test_raw_signals_and_extractions_round_trip() {
+        use crate::ingester::RawSignal;
+        use crate::storage::Database;
+        use chrono::{Duration, TimeZone};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let message = |source_id: &str, minutes: i64| RawSignal {
+            source: "telegram".to_string(),
+            source_id: source_id.to_string(),
+            content: format!("message {}", source_id),
+            author: "@alice".to_string(),
+            author_trust: 0.7,
+            timestamp: start + Duration::minutes(minutes),
+            metadata: Some(serde_json::json!({"channel": "alpha"})),
+            trace_id: Default::default(),
+        };
+        db.record_raw_signal(&message("2", 5)).await.unwrap();
+        db.record_raw_signal(&message("1", 0)).await.unwrap();
+        db.record_raw_signal(&message("3", 60)).await.unwrap();
+
+        let replayed = db.get_raw_signals(start, start + Duration::minutes(30)).await.unwrap();
+        let ids: Vec<_> = replayed.iter().map(|m| m.source_id.as_str()).collect();
+        assert_eq!(ids, ["1", "2"]);
+        assert_eq!(replayed[0].author_trust, 0.7);
+        assert_eq!(replayed[0].metadata, Some(serde_json::json!({"channel": "alpha"})));
+
+        assert_eq!(db.get_llm_extraction("abc").await.unwrap(), None);
+        db.save_llm_extraction("abc", r#"{"token": null}"#, start).await.unwrap();
+        assert_eq!(db.get_llm_extraction("abc").await.unwrap().as_deref(), Some(r#"{"token": null}"#));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_stats_latest_snapshot_per_source() {
+        use crate::ingester::SourceStats;
+        use crate::storage::Database;
+        use chrono::{Duration, TimeZone};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 12, 10, 0).unwrap();
+        let stats = |source: &str, received: u64| SourceStats {
+            source: source.to_string(),
+            received,
+            passed_prefilter: received / 2,
+            errors: 1,
+            last_message_at: Some(start),
+            ..Default::default()
+        };
+        assert!(db.get_latest_ingest_stats().await.unwrap().is_empty());
+
+        db.save_ingest_stats(&[stats("telegram", 10), stats("twitter_rss", 0)], start).await.unwrap();
+        // Later in the same hour, then the next hour for one source only
+        db.save_ingest_stats(&[stats("telegram", 12)], start + Duration::minutes(40)).await.unwrap();
+        db.save_ingest_stats(&[stats("telegram", 20)], start + Duration::minutes(60)).await.unwrap();
+
+        let latest = db.get_latest_ingest_stats().await.unwrap();
+        assert_eq!(latest, vec![stats("telegram", 20), stats("twitter_rss", 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_resting_orders_survive_restart_and_close() {
+        use crate::storage::Database;
+        use crate::strategy::resting::RestingOrder;
+        use crate::types::{Signal, Trade, TradeStatus};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        let now = Utc::now();
+        let order = |order_id: &str| {
+            let signal = Signal {
+                market_id: format!("m-{}", order_id),
+                token_id: "yes".to_string(),
+                side: Side::Buy,
+                model_probability: dec!(0.6),
+                market_probability: dec!(0.5),
+                edge: dec!(0.1),
+                confidence: dec!(0.8),
+                suggested_size: dec!(0.02),
+                timestamp: now,
+                strategy_name: Some("llm".to_string()),
+                epistemic_uncertainty: dec!(0),
+                trace_id: Default::default(),
+                tags: Vec::new(),
+                posterior_variance: dec!(0),
+                valid_until: None,
+                signal_score: None,
+            };
+            RestingOrder {
+                trade: Trade {
+                    id: format!("t-{}", order_id),
+                    order_id: order_id.to_string(),
+                    token_id: "yes".to_string(),
+                    market_id: signal.market_id.clone(),
+                    side: Side::Buy,
+                    price: dec!(0.58),
+                    size: dec!(10),
+                    fee: dec!(0),
+                    timestamp: now,
+                    status: TradeStatus::Resting,
+                    strategy_name: signal.strategy_name.clone(),
+                    account_id: None,
+                    trace_id: signal.trace_id,
+                    tags: Vec::new(),
+                    edge: Some(signal.edge),
+                },
+                signal,
+                price: dec!(0.58),
+                placed_at: now,
+            }
+        };
+        let (filled, cancelled) = (order("o1"), order("o2"));
+        {
+            let db = Database::connect(&path).await.unwrap();
+            for order in [&filled, &cancelled] {
+                db.save_trade(&order.trade).await.unwrap();
+                db.save_resting_order(order).await.unwrap();
+            }
+        }
+
+        let db = Database::connect(&path).await.unwrap();
+        let restored = db.load_resting_orders().await.unwrap();
+        assert_eq!(restored.len(), 2);
+        let mut ids: Vec<_> = restored.iter().map(|o| (o.trade.order_id.as_str(), o.price, o.placed_at)).collect();
+        ids.sort();
+        assert_eq!(ids, [("o1", filled.price, filled.placed_at), ("o2", cancelled.price, cancelled.placed_at)]);
+
+        db.close_resting_order("o1", true).await.unwrap();
+        db.close_resting_order("o2", false).await.unwrap();
+        assert!(db.load_resting_orders().await.unwrap().is_empty());
+        assert!(db.get_resting_trades().await.unwrap().is_empty());
+        let trades = db.get_recent_trades(10).await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].order_id.as_str(), trades[0].status), ("o1", TradeStatus::Filled));
+    }
+
+    #[tokio::test]
+    async fn test_ramp_state_survives_restart() {
+        use crate::risk::RampState;
+        use crate::storage::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        let state = RampState { level: dec!(0.35), trades: 3, wins: 2, losing_streak: 1 };
+        {
+            let db = Database::connect(&path).await.unwrap();
+            assert!(db.load_ramp_states().await.unwrap().is_empty());
+            db.save_ramp_state("strategy:llm", &RampState::new(dec!(0.25))).await.unwrap();
+            db.save_ramp_state("strategy:llm", &state).await.unwrap();
+            db.save_ramp_state("category:crypto", &RampState::new(dec!(0.25))).await.unwrap();
+        }
+
+        let db = Database::connect(&path).await.unwrap();
+        let states = db.load_ramp_states().await.unwrap();
+        assert_eq!(states.len(), 2);
+        assert_eq!(states["strategy:llm"], state);
+        assert_eq!(states["category:crypto"], RampState::new(dec!(0.25)));
+    }
+
+    #[tokio::test]
+    async fn test_market_profile_aggregation() {
+        use crate::analysis::market_profile::{BookSample, MarketProfiles};
+        use crate::config::MarketProfileConfig;
+        use crate::orderbook::MmActivityLevel;
+        use crate::storage::Database;
+        use crate::types::TraceId;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let now = Utc::now();
+        let sample = |market_id: &str, minutes_ago: i64, spread| BookSample {
+            market_id: market_id.to_string(),
+            taken_at: now - chrono::Duration::minutes(minutes_ago),
+            spread,
+            top_depth: dec!(80),
+            mm_activity: Some(MmActivityLevel::Low),
+        };
+        // A sample from before the window, and three in it
+        db.save_book_sample(&sample("m1", 8 * 24 * 60, dec!(0.50))).await.unwrap();
+        for (minutes_ago, spread) in [(30, dec!(0.06)), (20, dec!(0.04)), (10, dec!(0.05))] {
+            db.save_book_sample(&sample("m1", minutes_ago, spread)).await.unwrap();
+        }
+        db.save_book_sample(&sample("m2", 5, dec!(0.01))).await.unwrap();
+
+        let since = now - chrono::Duration::days(7);
+        let samples = db.get_book_samples("m1", since).await.unwrap();
+        assert_eq!(samples.iter().map(|s| s.spread).collect::<Vec<_>>(), [dec!(0.06), dec!(0.04), dec!(0.05)]);
+        assert_eq!(samples[0], sample("m1", 30, dec!(0.06)));
+        assert_eq!(db.sampled_markets(since).await.unwrap(), ["m1", "m2"]);
+
+        // A resting order on m1 placed 90s ago fills now
+        let trade = Trade {
+            id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            token_id: "yes".to_string(),
+            market_id: "m1".to_string(),
+            side: Side::Buy,
+            price: dec!(0.40),
+            size: dec!(10),
+            fee: dec!(0),
+            timestamp: now - chrono::Duration::seconds(90),
+            status: TradeStatus::Resting,
+            strategy_name: None,
+            account_id: None,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            edge: None,
+        };
+        db.save_trade(&trade).await.unwrap();
+        assert!(db.get_fill_latencies("m1", since).await.unwrap().is_empty());
+        db.close_resting_order("o1", true).await.unwrap();
+        let latencies = db.get_fill_latencies("m1", since).await.unwrap();
+        assert_eq!(latencies.len(), 1);
+        assert!((90..=95).contains(&latencies[0]));
+
+        let profiles = MarketProfiles::new(MarketProfileConfig { min_samples: 3, ..Default::default() });
+        assert_eq!(profiles.refresh(&db, now).await.unwrap(), 2);
+        let profile = db.load_market_profile("m1").await.unwrap().unwrap();
+        assert_eq!(profile.median_spread, dec!(0.05));
+        assert_eq!(profile.median_top_depth, dec!(80));
+        assert_eq!(profile.samples, 3);
+        assert_eq!(profile.median_fill_latency_secs, Some(latencies[0]));
+        assert_eq!(profile.mm_activity, Some(MmActivityLevel::Low));
+        assert_eq!(profiles.get("m1"), Some(profile));
+        // The stale sample is pruned
+        assert_eq!(db.get_book_samples("m1", now - chrono::Duration::days(30)).await.unwrap().len(), 3);
+
+        // A restart picks the profiles up again
+        let restarted = MarketProfiles::new(MarketProfileConfig::default());
+        assert_eq!(restarted.load(&db).await.unwrap(), 2);
+        assert_eq!(restarted.get("m2").unwrap().median_spread, dec!(0.01));
+        assert!(db.load_market_profile("m3").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_state_checkpoints() {
+        use crate::recovery::StrategyState;
+        use crate::regime::{PriceBar, RegimeConfig, RegimeDetector, RegimeDetectorState};
+        use crate::storage::Database;
+        use crate::strategy::crypto_hf::{CryptoPriceTracker, CryptoTrackerState, PricePoint};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        assert!(db.load_state::<CryptoTrackerState>().await.unwrap().is_none());
+
+        let mut tracker = CryptoPriceTracker::new();
+        for i in 0..10 {
+            tracker.record_price("BTC", PricePoint {
+                price: dec!(60000.5) + rust_decimal::Decimal::from(i),
+                timestamp: Utc::now() - chrono::Duration::minutes(10 - i),
+            });
+        }
+        let mut detector = RegimeDetector::new(RegimeConfig::default());
+        for i in 0..5 {
+            let open = dec!(100) + rust_decimal::Decimal::from(i);
+            detector.update(PriceBar {
+                timestamp: Utc::now() - chrono::Duration::hours(5 - i),
+                open,
+                high: open + dec!(1),
+                low: open - dec!(1),
+                close: open + dec!(0.5),
+                volume: dec!(1000),
+            });
+        }
+        db.save_state(&tracker.state()).await.unwrap();
+        db.save_state(&detector.state()).await.unwrap();
+        // A newer checkpoint replaces the old one
+        tracker.record_price("BTC", PricePoint { price: dec!(61000), timestamp: Utc::now() });
+        db.save_state(&tracker.state()).await.unwrap();
+
+        let mut restored = CryptoPriceTracker::new();
+        restored.restore(db.load_state().await.unwrap().unwrap());
+        assert_eq!(restored.history_len("BTC"), 11);
+        assert_eq!(restored.current_price("BTC"), Some(dec!(61000)));
+        let regime: RegimeDetectorState = db.load_state().await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::to_value(&regime).unwrap(),
+            serde_json::to_value(detector.state()).unwrap()
+        );
+
+        // Checkpoints over an hour old are discarded, unreadable ones ignored
+        let stale = Utc::now() - chrono::Duration::minutes(61);
+        db.save_state_at(&detector.state(), stale).await.unwrap();
+        assert!(db.load_state::<RegimeDetectorState>().await.unwrap().is_none());
+        sqlx::query("UPDATE strategy_states SET data = '{}' WHERE key = ?")
+            .bind(CryptoTrackerState::KEY)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert!(db.load_state::<CryptoTrackerState>().await.unwrap().is_none());
+    }
 }
@@ -0,0 +1,164 @@
+//! Pipeline traces of signals
+//!
+//! Each stage a traced signal passes (raw signal, aggregated signal, trading
+//! signal, risk decision) records an event under its [`TraceId`]; trades
+//! carry the ID themselves. [`Database::get_trace_events`] merges both into
+//! one timeline.
+
+use super::{Database, TradeRow};
+use crate::error::Result;
+use crate::types::{Side, TraceId, Trade};
+use chrono::{DateTime, Utc};
+
+/// Pipeline stage of a trace event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceStage {
+    RawSignal,
+    ParsedSignal,
+    Signal,
+    Risk,
+    Trade,
+}
+
+impl TraceStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceStage::RawSignal => "raw_signal",
+            TraceStage::ParsedSignal => "parsed_signal",
+            TraceStage::Signal => "signal",
+            TraceStage::Risk => "risk",
+            TraceStage::Trade => "trade",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "raw_signal" => Some(TraceStage::RawSignal),
+            "parsed_signal" => Some(TraceStage::ParsedSignal),
+            "signal" => Some(TraceStage::Signal),
+            "risk" => Some(TraceStage::Risk),
+            "trade" => Some(TraceStage::Trade),
+            _ => None,
+        }
+    }
+}
+
+/// One step of a signal through the pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub trace_id: TraceId,
+    pub stage: TraceStage,
+    pub timestamp: DateTime<Utc>,
+    pub detail: String,
+}
+
+impl TraceEvent {
+    /// Event happening now
+    pub fn new(trace_id: TraceId, stage: TraceStage, detail: impl Into<String>) -> Self {
+        Self {
+            trace_id,
+            stage,
+            timestamp: Utc::now(),
+            detail: detail.into(),
+        }
+    }
+
+    fn of_trade(trade: &Trade) -> Self {
+        Self {
+            trace_id: trade.trace_id,
+            stage: TraceStage::Trade,
+            timestamp: trade.timestamp,
+            detail: format!(
+                "{} {:.2} @ {:.4} of {} ({}, trade {})",
+                match trade.side {
+                    Side::Buy => "BUY",
+                    Side::Sell => "SELL",
+                },
+                trade.size,
+                trade.price,
+                trade.token_id,
+                trade.status.as_str(),
+                trade.id
+            ),
+        }
+    }
+}
+
+impl Database {
+    /// Store a trace event; events of the nil trace are dropped
+    pub async fn record_trace_event(&self, event: &TraceEvent) -> Result<()> {
+        if event.trace_id.is_nil() {
+            return Ok(());
+        }
+        sqlx::query("INSERT INTO trace_events (trace_id, stage, timestamp, detail) VALUES (?, ?, ?, ?)")
+            .bind(event.trace_id.to_string())
+            .bind(event.stage.as_str())
+            .bind(event.timestamp.to_rfc3339())
+            .bind(&event.detail)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Every recorded stage of `trace_id` and the trades it led to, oldest
+    /// first
+    pub async fn get_trace_events(&self, trace_id: TraceId) -> Result<Vec<TraceEvent>> {
+        if trace_id.is_nil() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT stage, timestamp, detail FROM trace_events WHERE trace_id = ? ORDER BY id",
+        )
+        .bind(trace_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        let mut events: Vec<TraceEvent> = rows
+            .into_iter()
+            .filter_map(|(stage, timestamp, detail)| {
+                Some(TraceEvent {
+                    trace_id,
+                    stage: TraceStage::parse(&stage)?,
+                    timestamp: timestamp.parse().ok()?,
+                    detail,
+                })
+            })
+            .collect();
+
+        let trades = sqlx::query_as::<_, TradeRow>(
+            r#"
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
+            FROM trades
+            WHERE trace_id = ?
+            "#,
+        )
+        .bind(trace_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        events.extend(
+            trades
+                .into_iter()
+                .filter_map(|r| Trade::try_from(r).ok())
+                .map(|t| TraceEvent::of_trade(&t)),
+        );
+
+        events.sort_by_key(|e| e.timestamp);
+        Ok(events)
+    }
+
+    /// Trade by ID
+    pub async fn get_trade(&self, trade_id: &str) -> Result<Option<Trade>> {
+        let row = sqlx::query_as::<_, TradeRow>(
+            r#"
+            SELECT id, order_id, token_id, market_id, side, price, size, fee, timestamp, status, strategy, account, trace_id, tags, edge
+            FROM trades
+            WHERE id = ?
+            "#,
+        )
+        .bind(trade_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|r| r.try_into().ok()))
+    }
+}
@@ -492,6 +492,13 @@ impl BacktestStrategy for MomentumStrategy {
                 confidence: dec!(0.7),
                 suggested_size: dec!(100),
                 timestamp: candle.timestamp,
+                strategy_name: None,
+                epistemic_uncertainty: Decimal::ZERO,
+                trace_id: Default::default(),
+                tags: Vec::new(),
+                posterior_variance: Decimal::ZERO,
+                valid_until: None,
+                signal_score: None,
             })
         } else if momentum < -self.threshold && position.is_some() {
             Some(Signal {
@@ -504,6 +511,13 @@ impl BacktestStrategy for MomentumStrategy {
                 confidence: dec!(0.7),
                 suggested_size: dec!(100),
                 timestamp: candle.timestamp,
+                strategy_name: None,
+                epistemic_uncertainty: Decimal::ZERO,
+                trace_id: Default::default(),
+                tags: Vec::new(),
+                posterior_variance: Decimal::ZERO,
+                valid_until: None,
+                signal_score: None,
             })
         } else {
             None
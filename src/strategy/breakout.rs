@@ -0,0 +1,373 @@
+//! Volatility-squeeze breakout strategy
+//!
+//! A market that ranges in a narrow band for a while tends to break out of
+//! it strongly and keep going. [`VolumeBreakoutStrategy`] watches Bollinger
+//! Band width for a squeeze (bands under half their usual width for several
+//! bars) and then trades the first close outside the bands: a close above
+//! the upper band on heavy volume buys, a close below the lower band sells.
+//!
+//! [`BollingerBreakoutStrategy`] is the plain version for prediction
+//! markets: any close outside the bands is traded in its direction, with
+//! `sigmoid(z)` of the close's distance from the middle band as the model
+//! probability.
+
+use super::crypto_hf::{CryptoHfStrategy, CryptoPriceTracker};
+use super::momentum::{fetch_histories, signal_from_z, yes_outcome};
+use super::Strategy;
+use crate::client::GammaClient;
+use crate::types::{Market, Side, Signal, TraceId};
+use chrono::Utc;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Band width, relative to its average, below which the bands are squeezed
+const SQUEEZE_RATIO: Decimal = dec!(0.5);
+
+/// Portfolio fraction of a full-strength breakout
+const MAX_POSITION_FRACTION: Decimal = dec!(0.05);
+
+/// Probability shift the model expects from a full-strength breakout
+const MAX_EDGE: Decimal = dec!(0.10);
+
+/// Trades breakouts from a Bollinger Band squeeze
+#[derive(Debug, Clone)]
+pub struct VolumeBreakoutStrategy {
+    /// Bars in the moving average and standard deviation
+    pub period: usize,
+    /// Band half-width in standard deviations
+    pub bb_multiplier: Decimal,
+    /// Consecutive squeezed bars required before a breakout counts
+    pub min_squeeze_bars: usize,
+    /// Breakout volume required, as a multiple of the average volume
+    pub volume_confirmation_multiplier: Decimal,
+}
+
+impl Default for VolumeBreakoutStrategy {
+    fn default() -> Self {
+        Self {
+            period: 20,
+            bb_multiplier: dec!(2),
+            min_squeeze_bars: 5,
+            volume_confirmation_multiplier: dec!(1.5),
+        }
+    }
+}
+
+/// Bollinger Bands of one bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bands {
+    middle: Decimal,
+    std: Decimal,
+    upper: Decimal,
+    lower: Decimal,
+}
+
+impl Bands {
+    fn width(&self) -> Decimal {
+        self.upper - self.lower
+    }
+}
+
+impl VolumeBreakoutStrategy {
+    /// Bars of history used: the first band's window, then enough band
+    /// widths to average the squeeze against
+    pub fn lookback(&self) -> usize {
+        2 * self.period + self.min_squeeze_bars + 1
+    }
+
+    /// Signal on the market's YES (or Up) token when the last bar of
+    /// `price_history` breaks out of a squeeze. Each bar is compared with
+    /// the bands of the `period` bars before it. `volume_history` is
+    /// aligned with `price_history`; buys need its last bar above the
+    /// average volume times `volume_confirmation_multiplier`.
+    pub fn generate(&self, market: &Market, price_history: &[Decimal], volume_history: &[Decimal]) -> Option<Signal> {
+        if self.period < 2 || price_history.len() < self.period + self.min_squeeze_bars + 2 {
+            return None;
+        }
+        let bars = price_history.len();
+        let current = bars - 1;
+        let bands: Vec<Bands> = (self.period..bars)
+            .map(|bar| bands(&price_history[bar - self.period..bar], self.bb_multiplier))
+            .collect();
+        let (latest, history) = bands.split_last()?;
+
+        let avg_width = history.iter().map(Bands::width).sum::<Decimal>() / Decimal::from(history.len());
+        let squeezed = history.len() > self.min_squeeze_bars
+            && history
+                .iter()
+                .rev()
+                .take(self.min_squeeze_bars)
+                .all(|b| b.width() < avg_width * SQUEEZE_RATIO);
+        if !squeezed {
+            return None;
+        }
+
+        let close = price_history[current];
+        let (side, excursion) = if close > latest.upper {
+            let volumes = volume_history.get(current - self.period..=current)?;
+            let (volume, window) = volumes.split_last()?;
+            let avg_volume = window.iter().sum::<Decimal>() / Decimal::from(window.len());
+            if *volume <= avg_volume * self.volume_confirmation_multiplier {
+                tracing::debug!("Breakout on {} lacks volume ({} vs avg {})", market.id, volume, avg_volume);
+                return None;
+            }
+            (Side::Buy, close - latest.upper)
+        } else if close < latest.lower {
+            (Side::Sell, latest.lower - close)
+        } else {
+            return None;
+        };
+
+        // Distance past the band in standard deviations, a band's
+        // half-width past it being full strength
+        let strength = if latest.std.is_zero() {
+            Decimal::ONE
+        } else {
+            (excursion / latest.std / self.bb_multiplier.max(Decimal::ONE)).min(Decimal::ONE)
+        };
+
+        let outcome = market
+            .outcomes
+            .iter()
+            .find(|o| matches!(o.outcome.to_lowercase().as_str(), "yes" | "up"))?;
+        let market_probability = outcome.price;
+        let shift = strength * MAX_EDGE;
+        let model_probability = match side {
+            Side::Buy => market_probability + shift,
+            Side::Sell => market_probability - shift,
+        }
+        .clamp(dec!(0.01), dec!(0.99));
+
+        Some(Signal {
+            market_id: market.id.clone(),
+            token_id: outcome.token_id.clone(),
+            side,
+            model_probability,
+            market_probability,
+            edge: model_probability - market_probability,
+            confidence: strength,
+            suggested_size: strength * MAX_POSITION_FRACTION,
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            posterior_variance: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            valid_until: None,
+            signal_score: None,
+        })
+    }
+
+    /// [`generate`](Self::generate) on the tracker's recent bars of the
+    /// asset behind a crypto Up/Down market; `None` for other markets
+    pub fn generate_crypto(&self, market: &Market, tracker: &CryptoPriceTracker) -> Option<Signal> {
+        let info = CryptoHfStrategy::is_crypto_hf_market(market)?;
+        let (closes, volumes) = tracker.recent_bars(&info.asset, self.lookback())?;
+        self.generate(market, &closes, &volumes)
+    }
+}
+
+/// Trades closes outside the Bollinger Bands in their direction
+#[derive(Debug, Clone)]
+pub struct BollingerBreakoutStrategy {
+    /// Bars in the moving average and standard deviation
+    pub period: usize,
+    /// Band half-width in standard deviations
+    pub multiplier: Decimal,
+    /// YES token ID -> prices, oldest first
+    history: HashMap<String, Vec<Decimal>>,
+}
+
+impl Default for BollingerBreakoutStrategy {
+    fn default() -> Self {
+        Self::new(20, dec!(2))
+    }
+}
+
+impl BollingerBreakoutStrategy {
+    pub fn new(period: usize, multiplier: Decimal) -> Self {
+        Self {
+            period,
+            multiplier,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Use `prices` (oldest first) of `token_id` in the next `generate` round
+    pub fn set_price_history(&mut self, token_id: &str, prices: Vec<Decimal>) {
+        self.history.insert(token_id.to_string(), prices);
+    }
+
+    /// Fetch the price history of every market's YES token
+    pub async fn refresh(&mut self, gamma: &GammaClient, markets: &[Market]) {
+        self.history.extend(fetch_histories(gamma, markets).await);
+    }
+
+    /// Signal on the market's YES token when the last close of `prices` is
+    /// outside the bands of the `period` bars before it
+    pub fn signal(&self, market: &Market, prices: &[Decimal]) -> Option<Signal> {
+        if self.period < 2 || prices.len() <= self.period {
+            return None;
+        }
+        let (close, earlier) = prices.split_last()?;
+        let bands = bands(&earlier[earlier.len() - self.period..], self.multiplier);
+        let side = if *close > bands.upper {
+            Side::Buy
+        } else if *close < bands.lower {
+            Side::Sell
+        } else {
+            return None;
+        };
+        if bands.std.is_zero() {
+            return None;
+        }
+        signal_from_z(market, side, (close - bands.middle) / bands.std)
+    }
+}
+
+impl Strategy for BollingerBreakoutStrategy {
+    fn generate(&self, markets: &[Market], _budget: Decimal) -> Vec<Signal> {
+        markets
+            .iter()
+            .filter_map(|market| {
+                let prices = self.history.get(&yes_outcome(market)?.token_id)?;
+                self.signal(market, prices)
+            })
+            .collect()
+    }
+}
+
+fn bands(window: &[Decimal], multiplier: Decimal) -> Bands {
+    let n = Decimal::from(window.len());
+    let middle = window.iter().sum::<Decimal>() / n;
+    let variance = window.iter().map(|p| (p - middle) * (p - middle)).sum::<Decimal>() / n;
+    let std = variance.sqrt().unwrap_or(Decimal::ZERO);
+    Bands {
+        middle,
+        std,
+        upper: middle + multiplier * std,
+        lower: middle - multiplier * std,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+
+    fn market(yes_price: Decimal) -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: "Breakout?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(10000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn strategy() -> VolumeBreakoutStrategy {
+        VolumeBreakoutStrategy { period: 5, bb_multiplier: dec!(2), min_squeeze_bars: 3, volume_confirmation_multiplier: dec!(1.5) }
+    }
+
+    /// A wide range, then a tight one long enough to squeeze the bands,
+    /// then `last`
+    fn prices(last: Decimal) -> Vec<Decimal> {
+        let mut prices: Vec<Decimal> = [0.30, 0.50, 0.35, 0.55, 0.30, 0.50, 0.32, 0.52, 0.34, 0.50]
+            .iter()
+            .map(|p| Decimal::try_from(*p).unwrap())
+            .collect();
+        prices.extend([dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41)]);
+        prices.push(last);
+        prices
+    }
+
+    fn volumes(last: Decimal) -> Vec<Decimal> {
+        let mut volumes = vec![dec!(100); 20];
+        volumes.push(last);
+        volumes
+    }
+
+    #[test]
+    fn test_buys_a_high_volume_breakout_after_a_squeeze() {
+        let signal = strategy().generate(&market(dec!(0.45)), &prices(dec!(0.45)), &volumes(dec!(300))).unwrap();
+
+        assert_eq!(signal.side, Side::Buy);
+        assert_eq!(signal.token_id, "yes");
+        assert!(signal.edge > Decimal::ZERO);
+        // Far past the band: full strength
+        assert_eq!(signal.confidence, Decimal::ONE);
+        assert_eq!(signal.suggested_size, MAX_POSITION_FRACTION);
+    }
+
+    #[test]
+    fn test_upside_breakout_needs_volume_confirmation() {
+        assert!(strategy().generate(&market(dec!(0.45)), &prices(dec!(0.45)), &volumes(dec!(120))).is_none());
+        assert!(strategy().generate(&market(dec!(0.45)), &prices(dec!(0.45)), &[]).is_none());
+    }
+
+    #[test]
+    fn test_sells_a_downside_breakout() {
+        let signal = strategy().generate(&market(dec!(0.36)), &prices(dec!(0.36)), &volumes(dec!(50))).unwrap();
+
+        assert_eq!(signal.side, Side::Sell);
+        assert!(signal.edge < Decimal::ZERO);
+        assert!(signal.model_probability < signal.market_probability);
+    }
+
+    #[test]
+    fn test_size_scales_with_distance_past_the_band() {
+        // Just past the upper band (0.406 + 2 * 0.0049) is a weak breakout
+        let signal = strategy().generate(&market(dec!(0.418)), &prices(dec!(0.418)), &volumes(dec!(300))).unwrap();
+
+        assert!(signal.confidence > Decimal::ZERO && signal.confidence < dec!(0.5));
+        assert!(signal.suggested_size < MAX_POSITION_FRACTION / dec!(2));
+    }
+
+    #[test]
+    fn test_no_signal_without_a_squeeze_or_breakout() {
+        // Inside the bands
+        assert!(strategy().generate(&market(dec!(0.405)), &prices(dec!(0.405)), &volumes(dec!(300))).is_none());
+
+        // A close outside the bands of a market that never ranged tightly
+        let wide: Vec<Decimal> = (0..21).map(|i| if i % 2 == 0 { dec!(0.30) } else { dec!(0.50) }).chain([dec!(0.70)]).collect();
+        assert!(strategy().generate(&market(dec!(0.70)), &wide, &[dec!(100); 22]).is_none());
+
+        // Too little history
+        assert!(strategy().generate(&market(dec!(0.45)), &prices(dec!(0.45))[12..], &volumes(dec!(300))[12..]).is_none());
+    }
+
+    #[test]
+    fn test_bollinger_breakout_trades_closes_outside_the_bands() {
+        // Bands of 0.40/0.42 alternating: middle 0.41, std 0.01
+        let range: Vec<Decimal> = (0..6).map(|i| if i % 2 == 0 { dec!(0.40) } else { dec!(0.42) }).collect();
+        let mut strategy = BollingerBreakoutStrategy::new(6, dec!(2));
+
+        strategy.set_price_history("yes", [range.clone(), vec![dec!(0.44)]].concat());
+        let signals = strategy.generate(&[market(dec!(0.44))], dec!(1000));
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].side, Side::Buy);
+        assert_eq!(signals[0].model_probability, super::super::momentum::sigmoid(dec!(3)));
+
+        strategy.set_price_history("yes", [range.clone(), vec![dec!(0.38)]].concat());
+        let signals = strategy.generate(&[market(dec!(0.38))], dec!(1000));
+        assert_eq!(signals[0].side, Side::Sell);
+
+        // Inside the bands, or too little history
+        strategy.set_price_history("yes", [range.clone(), vec![dec!(0.425)]].concat());
+        assert!(strategy.generate(&[market(dec!(0.425))], dec!(1000)).is_empty());
+        strategy.set_price_history("yes", range[..4].to_vec());
+        assert!(strategy.generate(&[market(dec!(0.42))], dec!(1000)).is_empty());
+    }
+}
@@ -8,7 +8,7 @@
 
 use crate::config::{RiskConfig, StrategyConfig};
 use crate::model::Prediction;
-use crate::types::{Market, Side, Signal};
+use crate::types::{Market, Side, Signal, TraceId};
 use chrono::Utc;
 use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
@@ -144,6 +144,13 @@ impl CompoundStrategy {
             confidence: prediction.confidence,
             suggested_size,
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         })
     }
 
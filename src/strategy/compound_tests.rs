@@ -16,6 +16,18 @@ mod tests {
             model_update_interval_secs: 900,
             compound_enabled: true,
             compound_sqrt_scaling: true,
+            modes: std::collections::HashMap::new(),
+            high_epistemic_threshold: dec!(0.15),
+            accounts: std::collections::HashMap::new(),
+            experiment: None,
+            experiments: Default::default(),
+            bayesian_prior_strength: None,
+            regime_map: Default::default(),
+            regime_confidence_threshold: dec!(0.60),
+            max_concurrent_analyses: 4,
+            analysis_timeout_secs: 60,
+            seasonality: Default::default(),
+            scoring: Default::default(),
         };
         
         let risk = RiskConfig {
@@ -24,6 +36,18 @@ mod tests {
             max_daily_loss_pct: dec!(0.12),
             min_balance_reserve: dec!(25),
             max_open_positions: 12,
+            signal_dedup_ttl_secs: 900,
+            max_total_kelly: dec!(1),
+            max_consecutive_api_failures: 5,
+            api_failure_pause_secs: 300,
+            max_positions_per_strategy: std::collections::HashMap::new(),
+            queued_signal_ttl_secs: 1800,
+            max_queued_signals: 20,
+            loss_cooldown_mins: 30,
+            loss_cooldown_override_edge: None,
+            regime_rotation_min_edge_gain: None,
+            max_heat_budget: rust_decimal::Decimal::ZERO,
+            regime_consensus_sizing: Default::default(),
         };
         
         (strategy, risk)
@@ -1,9 +1,14 @@
 //! Copy trading - follow top traders
 //!
 //! Monitor successful traders' positions and copy their trades.
+//! Traders can also be discovered from the leaderboard (`TraderDiscovery`).
+//! Each followed trader's weight is recalibrated from the realized results
+//! of the trades we copied from them (`CopyTrader::recalibrate`).
 
+use crate::client::PolymarketClient;
 use crate::error::Result;
-use crate::types::{Side, Signal};
+use crate::types::{Side, Signal, TraceId};
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rust_decimal::Decimal;
@@ -25,6 +30,26 @@ pub struct TopTrader {
     pub weight: f64,
     /// Last updated
     pub updated_at: DateTime<Utc>,
+    /// Closed positions in the discovery lookback window
+    #[serde(default)]
+    pub trades: u32,
+    /// Traded volume (USDC) in the discovery lookback window
+    #[serde(default)]
+    pub volume_usdc: Decimal,
+    /// Sharpe ratio of daily realized P&L over the last 30 days
+    #[serde(default)]
+    pub sharpe_30d: f64,
+    /// Leaderboard ranking score (see `performance_score`)
+    #[serde(default)]
+    pub performance_score: f64,
+}
+
+impl TopTrader {
+    /// Identity used to match traders across leaderboard snapshots and
+    /// to key their copied-trade outcomes
+    pub fn key(&self) -> &str {
+        self.address.as_deref().unwrap_or(&self.username)
+    }
 }
 
 /// Copy trade monitor
@@ -39,6 +64,8 @@ pub struct CopyTrader {
     /// Minimum trader profit to follow a trade
     #[allow(dead_code)]
     min_trader_profit: Decimal,
+    /// Traders whose weight falls below this are no longer copied
+    min_weight: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -57,11 +84,12 @@ struct Position {
 impl CopyTrader {
     pub fn new() -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             traders: Vec::new(),
             known_positions: HashMap::new(),
             copy_ratio: 0.5,  // Copy 50% of their position
             min_trader_profit: Decimal::new(1000, 0),  // $1000 minimum profit
+            min_weight: 0.0,
         }
     }
 
@@ -70,6 +98,16 @@ impl CopyTrader {
         self
     }
 
+    pub fn with_min_weight(mut self, min_weight: f64) -> Self {
+        self.min_weight = min_weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Whether copying `trader` is paused for underperforming
+    pub fn is_paused(&self, trader: &TopTrader) -> bool {
+        trader.weight < self.min_weight
+    }
+
     /// Add a trader to follow
     pub fn add_trader(&mut self, trader: TopTrader) {
         // Remove if already exists
@@ -77,6 +115,53 @@ impl CopyTrader {
         self.traders.push(trader);
     }
 
+    /// Traders currently followed
+    pub fn traders(&self) -> &[TopTrader] {
+        &self.traders
+    }
+
+    /// Apply a follow-list update from leaderboard discovery
+    pub fn apply_follow_update(&mut self, update: &FollowListUpdate) {
+        self.traders.retain(|t| !update.removed.iter().any(|r| r.key() == t.key()));
+        for trader in &update.added {
+            self.add_trader(trader.clone());
+        }
+    }
+
+    /// Update followed traders' win rate, profit and weight from the
+    /// realized results of the trades copied from them.
+    ///
+    /// Traders with fewer than `min_trades` settled copies keep their
+    /// current stats. The weight is the win rate relative to
+    /// `COPY_BREAKEVEN_WIN_RATE` (capped at 1), halved when the copies lost
+    /// money overall. Returns the traders this paused.
+    pub fn recalibrate(&mut self, outcomes: &[LeaderOutcomes], min_trades: u32) -> Vec<TopTrader> {
+        let min_weight = self.min_weight;
+        let mut paused = Vec::new();
+        for trader in &mut self.traders {
+            let Some(outcome) = outcomes.iter().find(|o| o.trader_key == trader.key()) else {
+                continue;
+            };
+            if outcome.trades == 0 || outcome.trades < min_trades {
+                continue;
+            }
+
+            let was_paused = trader.weight < min_weight;
+            trader.win_rate = outcome.wins as f64 / outcome.trades as f64;
+            trader.total_profit = outcome.total_pnl;
+            trader.weight = (trader.win_rate / COPY_BREAKEVEN_WIN_RATE).min(1.0);
+            if outcome.total_pnl < Decimal::ZERO {
+                trader.weight *= 0.5;
+            }
+            trader.updated_at = Utc::now();
+
+            if trader.weight < min_weight && !was_paused {
+                paused.push(trader.clone());
+            }
+        }
+        paused
+    }
+
     /// Add trader by username (will need to resolve address)
     pub async fn add_trader_by_username(&mut self, username: &str) -> Result<()> {
         // Try to get trader info from Polymarket
@@ -88,6 +173,10 @@ impl CopyTrader {
             total_profit: Decimal::ZERO,
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         
         self.add_trader(trader);
@@ -109,9 +198,14 @@ impl CopyTrader {
                             .entry(address.clone())
                             .or_default();
 
+                        // Paused traders' positions are still tracked, so
+                        // unpausing doesn't copy everything they opened meanwhile
+                        let paused = trader.weight < self.min_weight;
+                        let ratio = Decimal::try_from(self.copy_ratio * trader.weight).unwrap_or(Decimal::new(5, 1));
+
                         for pos in positions {
                             // Check if this is a new position
-                            if !known.contains_key(&pos.market_id) {
+                            if !known.contains_key(&pos.market_id) && !paused {
                                 // New position - generate copy signal
                                 signals.push(CopySignal {
                                     trader: trader.clone(),
@@ -119,7 +213,7 @@ impl CopyTrader {
                                     token_id: pos.token_id.clone(),
                                     side: pos.side,
                                     trader_size: pos.size,
-                                    suggested_size: pos.size * Decimal::try_from(self.copy_ratio).unwrap_or(Decimal::new(5, 1)),
+                                    suggested_size: pos.size * ratio,
                                     timestamp: Utc::now(),
                                 });
 
@@ -209,6 +303,19 @@ impl CopyTrader {
         positions
     }
 
+    /// A trader's closed positions from the data API, most recent first
+    pub async fn get_closed_positions(&self, data_api_url: &str, address: &str) -> Result<Vec<ClosedPosition>> {
+        let data: serde_json::Value = self
+            .http
+            .get(format!("{}/closed-positions", data_api_url))
+            .query(&[("user", address), ("limit", "500")])
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(parse_closed_positions(&data))
+    }
+
     /// Resolve username to wallet address via Polymarket API
     pub async fn resolve_address(&self, username: &str) -> Result<Option<String>> {
         // Try Polymarket's user lookup
@@ -274,6 +381,10 @@ impl CopyTrader {
                         total_profit: profit,
                         weight: 1.0,
                         updated_at: Utc::now(),
+                        trades: 0,
+                        volume_usdc: Decimal::ZERO,
+                        sharpe_30d: 0.0,
+                        performance_score: 0.0,
                     });
                 }
             }
@@ -283,6 +394,286 @@ impl CopyTrader {
     }
 }
 
+/// Most traders added or removed by a single follow-list update (each)
+pub const MAX_FOLLOW_CHANGES: usize = 2;
+
+/// Ranking score for a leaderboard trader.
+///
+/// `win_rate * 0.4 + sharpe_30d * 0.3 + normalized_volume * 0.3`, with the
+/// Sharpe ratio clamped to [-1, 1] so a handful of lucky days can't dominate.
+pub fn performance_score(win_rate: f64, sharpe_30d: f64, normalized_volume: f64) -> f64 {
+    win_rate * 0.4 + sharpe_30d.clamp(-1.0, 1.0) * 0.3 + normalized_volume * 0.3
+}
+
+/// A leaderboard entry before per-trader stats are fetched
+#[derive(Debug, Clone)]
+pub struct LeaderboardEntry {
+    pub username: String,
+    pub address: String,
+    pub volume_usdc: Decimal,
+    pub pnl: Decimal,
+}
+
+/// Win rate at which a copied leader keeps full weight
+pub const COPY_BREAKEVEN_WIN_RATE: f64 = 0.5;
+
+/// Realized results of the trades copied from one leader
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LeaderOutcomes {
+    /// `TopTrader::key` of the leader
+    pub trader_key: String,
+    /// Settled copied trades
+    pub trades: u32,
+    /// Settled copied trades that made money
+    pub wins: u32,
+    /// Realized P&L of the copies (USDC)
+    pub total_pnl: Decimal,
+}
+
+/// A closed position from a trader's history
+#[derive(Debug, Clone)]
+pub struct ClosedPosition {
+    /// Condition ID of the market
+    pub market_id: String,
+    pub realized_pnl: Decimal,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// Leaderboard-based trader discovery
+pub struct TraderDiscovery;
+
+impl TraderDiscovery {
+    /// Fetch the leaderboard and score each trader on their closed positions
+    /// over the last `lookback_days`. Traders below `min_trades` or
+    /// `min_volume_usdc` are dropped; the rest are sorted by score, best first.
+    pub async fn fetch_leaderboard(
+        client: &PolymarketClient,
+        min_trades: u32,
+        min_volume_usdc: Decimal,
+        lookback_days: u32,
+    ) -> Result<Vec<TopTrader>> {
+        let http = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        let period = match lookback_days {
+            0..=1 => "DAY",
+            2..=7 => "WEEK",
+            8..=31 => "MONTH",
+            _ => "ALL",
+        };
+
+        let url = format!("{}/v1/leaderboard", client.data_api_url());
+        let data: serde_json::Value = http
+            .get(&url)
+            .query(&[("timePeriod", period), ("orderBy", "PNL"), ("limit", "50")])
+            .send()
+            .await?
+            .json()
+            .await?;
+        let entries = parse_leaderboard_entries(&data);
+
+        let mut history = HashMap::new();
+        for entry in entries.iter().filter(|e| e.volume_usdc >= min_volume_usdc) {
+            let url = format!("{}/closed-positions", client.data_api_url());
+            let resp = http
+                .get(&url)
+                .query(&[("user", entry.address.as_str()), ("limit", "500")])
+                .send()
+                .await;
+            match resp {
+                Ok(resp) => match resp.json::<serde_json::Value>().await {
+                    Ok(data) => {
+                        history.insert(entry.address.clone(), parse_closed_positions(&data));
+                    }
+                    Err(e) => tracing::debug!("Bad closed positions for {}: {}", entry.address, e),
+                },
+                Err(e) => tracing::debug!("Failed to fetch closed positions for {}: {}", entry.address, e),
+            }
+        }
+
+        Ok(score_leaderboard(&entries, &history, min_trades, min_volume_usdc, lookback_days, Utc::now()))
+    }
+}
+
+/// Parse `/v1/leaderboard` entries
+pub fn parse_leaderboard_entries(data: &serde_json::Value) -> Vec<LeaderboardEntry> {
+    data.as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| {
+                    let address = item.get("proxyWallet")?.as_str()?.to_string();
+                    let username = item
+                        .get("userName")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .unwrap_or_else(|| address.chars().take(10).collect());
+                    Some(LeaderboardEntry {
+                        username,
+                        address,
+                        volume_usdc: json_decimal(item.get("vol")),
+                        pnl: json_decimal(item.get("pnl")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse `/closed-positions` results
+pub fn parse_closed_positions(data: &serde_json::Value) -> Vec<ClosedPosition> {
+    data.as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| {
+                    let ts = item.get("timestamp")?.as_i64()?;
+                    Some(ClosedPosition {
+                        market_id: item
+                            .get("conditionId")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        realized_pnl: json_decimal(item.get("realizedPnl")),
+                        closed_at: DateTime::from_timestamp(ts, 0)?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Numbers arrive as either JSON numbers or strings
+fn json_decimal(value: Option<&serde_json::Value>) -> Decimal {
+    match value {
+        Some(serde_json::Value::String(s)) => s.parse().unwrap_or(Decimal::ZERO),
+        Some(v) => v.as_f64().and_then(|f| Decimal::try_from(f).ok()).unwrap_or(Decimal::ZERO),
+        None => Decimal::ZERO,
+    }
+}
+
+/// Turn leaderboard entries and their closed positions into scored traders
+pub fn score_leaderboard(
+    entries: &[LeaderboardEntry],
+    history: &HashMap<String, Vec<ClosedPosition>>,
+    min_trades: u32,
+    min_volume_usdc: Decimal,
+    lookback_days: u32,
+    now: DateTime<Utc>,
+) -> Vec<TopTrader> {
+    let since = now - chrono::Duration::days(lookback_days as i64);
+    let qualified: Vec<(&LeaderboardEntry, Vec<&ClosedPosition>)> = entries
+        .iter()
+        .filter(|e| e.volume_usdc >= min_volume_usdc)
+        .filter_map(|e| {
+            let closed: Vec<_> = history
+                .get(&e.address)?
+                .iter()
+                .filter(|p| p.closed_at >= since)
+                .collect();
+            (closed.len() as u32 >= min_trades).then_some((e, closed))
+        })
+        .collect();
+
+    let max_volume = qualified
+        .iter()
+        .map(|(e, _)| e.volume_usdc)
+        .max()
+        .unwrap_or(Decimal::ZERO);
+
+    let mut traders: Vec<TopTrader> = qualified
+        .into_iter()
+        .map(|(entry, closed)| {
+            let wins = closed.iter().filter(|p| p.realized_pnl > Decimal::ZERO).count();
+            let win_rate = if closed.is_empty() { 0.0 } else { wins as f64 / closed.len() as f64 };
+            let sharpe_30d = daily_sharpe(&closed, now, 30);
+            let normalized_volume = if max_volume > Decimal::ZERO {
+                (entry.volume_usdc / max_volume).try_into().unwrap_or(0.0)
+            } else {
+                0.0
+            };
+
+            TopTrader {
+                username: entry.username.clone(),
+                address: Some(entry.address.clone()),
+                win_rate,
+                total_profit: entry.pnl,
+                weight: 1.0,
+                updated_at: now,
+                trades: closed.len() as u32,
+                volume_usdc: entry.volume_usdc,
+                sharpe_30d,
+                performance_score: performance_score(win_rate, sharpe_30d, normalized_volume),
+            }
+        })
+        .collect();
+
+    traders.sort_by(|a, b| b.performance_score.total_cmp(&a.performance_score));
+    traders
+}
+
+/// Sharpe ratio of daily realized P&L over the last `days` days
+/// (days without closes count as zero)
+fn daily_sharpe(closed: &[&ClosedPosition], now: DateTime<Utc>, days: i64) -> f64 {
+    let mut daily = vec![0.0; days as usize];
+    for p in closed {
+        let age = (now - p.closed_at).num_days();
+        if (0..days).contains(&age) {
+            daily[age as usize] += f64::try_from(p.realized_pnl).unwrap_or(0.0);
+        }
+    }
+
+    let n = daily.len() as f64;
+    let mean = daily.iter().sum::<f64>() / n;
+    let var = daily.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n;
+    if var > 0.0 { mean / var.sqrt() } else { 0.0 }
+}
+
+/// Changes to the follow list from one discovery round
+#[derive(Debug, Clone, Default)]
+pub struct FollowListUpdate {
+    pub added: Vec<TopTrader>,
+    pub removed: Vec<TopTrader>,
+}
+
+impl FollowListUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Decide which traders to follow and unfollow given a fresh leaderboard.
+///
+/// Leaderboard traders scoring above `add_threshold` are added (best first);
+/// followed traders the leaderboard now scores below `remove_threshold` are
+/// removed (worst first). Followed traders missing from the leaderboard are
+/// kept. At most `MAX_FOLLOW_CHANGES` additions and removals each.
+pub fn plan_follow_update(
+    following: &[TopTrader],
+    leaderboard: &[TopTrader],
+    add_threshold: f64,
+    remove_threshold: f64,
+) -> FollowListUpdate {
+    let followed = |t: &TopTrader| following.iter().any(|f| f.key() == t.key());
+
+    let mut removed: Vec<TopTrader> = leaderboard
+        .iter()
+        .filter(|t| followed(t) && t.performance_score < remove_threshold)
+        .cloned()
+        .collect();
+    removed.sort_by(|a, b| a.performance_score.total_cmp(&b.performance_score));
+    removed.truncate(MAX_FOLLOW_CHANGES);
+
+    let mut added: Vec<TopTrader> = leaderboard
+        .iter()
+        .filter(|t| !followed(t) && t.performance_score > add_threshold)
+        .cloned()
+        .collect();
+    added.sort_by(|a, b| b.performance_score.total_cmp(&a.performance_score));
+    added.truncate(MAX_FOLLOW_CHANGES);
+
+    FollowListUpdate { added, removed }
+}
+
 /// Signal to copy a trader's position
 #[derive(Debug, Clone)]
 pub struct CopySignal {
@@ -308,6 +699,13 @@ impl CopySignal {
             confidence: Decimal::try_from(self.trader.win_rate).unwrap_or(Decimal::new(7, 1)),
             suggested_size: self.suggested_size,
             timestamp: self.timestamp,
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         }
     }
 }
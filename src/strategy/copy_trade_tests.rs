@@ -4,6 +4,7 @@
 mod tests {
     use super::super::copy_trade::*;
     use chrono::Utc;
+    use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
 
     #[test]
@@ -15,6 +16,10 @@ mod tests {
             total_profit: dec!(10000),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         assert_eq!(trader.username, "testuser");
         assert_eq!(trader.win_rate, 0.65);
@@ -30,6 +35,10 @@ mod tests {
             total_profit: dec!(5000),
             weight: 0.8,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         assert!(trader.address.is_none());
     }
@@ -43,6 +52,10 @@ mod tests {
             total_profit: dec!(15000),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         let json = serde_json::to_string(&trader).unwrap();
         assert!(json.contains("\"username\":\"user\""));
@@ -102,6 +115,10 @@ mod tests {
             total_profit: dec!(1000),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         copy_trader.add_trader(trader);
     }
@@ -117,6 +134,10 @@ mod tests {
             total_profit: dec!(500),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         copy_trader.add_trader(trader1);
         
@@ -127,6 +148,10 @@ mod tests {
             total_profit: dec!(1000),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         copy_trader.add_trader(trader2);
         // Second add should replace, not duplicate
@@ -179,6 +204,10 @@ enabled = true
             total_profit: dec!(5000),
             weight: 1.0,
             updated_at: Utc::now(),
+            trades: 0,
+            volume_usdc: Decimal::ZERO,
+            sharpe_30d: 0.0,
+            performance_score: 0.0,
         };
         
         let copy_signal = CopySignal {
@@ -196,4 +225,204 @@ enabled = true
         assert_eq!(signal.token_id, "token1");
         assert_eq!(signal.suggested_size, dec!(500));
     }
+
+    // ========== Leaderboard Discovery ==========
+
+    fn scored(username: &str, score: f64) -> TopTrader {
+        TopTrader {
+            username: username.to_string(),
+            address: Some(format!("0x{}", username)),
+            win_rate: 0.5,
+            total_profit: Decimal::ZERO,
+            weight: 1.0,
+            updated_at: Utc::now(),
+            trades: 30,
+            volume_usdc: dec!(20000),
+            sharpe_30d: 0.0,
+            performance_score: score,
+        }
+    }
+
+    #[test]
+    fn test_performance_score_weights() {
+        assert!((performance_score(0.6, 0.5, 1.0) - 0.69).abs() < 1e-9);
+        // Sharpe is clamped to [-1, 1]
+        assert_eq!(performance_score(0.0, 5.0, 0.0), performance_score(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_parse_leaderboard_entries() {
+        let data = serde_json::json!([
+            { "rank": "1", "proxyWallet": "0xaaa", "userName": "alice", "vol": 120000.5, "pnl": 8000 },
+            { "rank": "2", "proxyWallet": "0xbbbbbbbbbbbb", "userName": "", "vol": "900", "pnl": "-12.5" },
+            { "rank": "3", "userName": "nowallet" }
+        ]);
+        let entries = parse_leaderboard_entries(&data);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].username, "alice");
+        assert_eq!(entries[0].volume_usdc, dec!(120000.5));
+        // Anonymous traders are named after their wallet
+        assert_eq!(entries[1].username, "0xbbbbbbbb");
+        assert_eq!(entries[1].pnl, dec!(-12.5));
+    }
+
+    #[test]
+    fn test_score_leaderboard_filters_and_ranks() {
+        use std::collections::HashMap;
+
+        let now = Utc::now();
+        let entry = |name: &str, vol| LeaderboardEntry {
+            username: name.to_string(),
+            address: format!("0x{}", name),
+            volume_usdc: vol,
+            pnl: dec!(0),
+        };
+        let closes = |wins: usize, losses: usize| -> Vec<ClosedPosition> {
+            (0..wins + losses)
+                .map(|i| ClosedPosition {
+                    market_id: format!("m{}", i),
+                    realized_pnl: if i < wins { dec!(10) } else { dec!(-10) },
+                    closed_at: now - chrono::Duration::days((i % 20) as i64),
+                })
+                .collect()
+        };
+
+        let entries = vec![
+            entry("alice", dec!(100000)),
+            entry("bob", dec!(50000)),
+            entry("carol", dec!(500)),   // below volume floor
+            entry("dave", dec!(80000)),  // too few trades
+        ];
+        let mut history = HashMap::new();
+        history.insert("0xalice".to_string(), closes(18, 2));
+        history.insert("0xbob".to_string(), closes(5, 15));
+        history.insert("0xcarol".to_string(), closes(20, 0));
+        history.insert("0xdave".to_string(), closes(3, 0));
+        // Old closes fall outside the lookback
+        history.get_mut("0xdave").unwrap().extend((0..20).map(|_| ClosedPosition {
+            market_id: "old".to_string(),
+            realized_pnl: dec!(10),
+            closed_at: now - chrono::Duration::days(90),
+        }));
+
+        let traders = score_leaderboard(&entries, &history, 10, dec!(10000), 30, now);
+        let names: Vec<_> = traders.iter().map(|t| t.username.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+        assert_eq!(traders[0].trades, 20);
+        assert!((traders[0].win_rate - 0.9).abs() < 1e-9);
+        assert!(traders[0].sharpe_30d > 0.0);
+        assert!(traders[1].sharpe_30d < 0.0);
+        assert!(traders[0].performance_score > traders[1].performance_score);
+    }
+
+    #[test]
+    fn test_follow_update_respects_thresholds_and_cap() {
+        let following = vec![scored("old1", 0.0), scored("old2", 0.0), scored("old3", 0.0), scored("manual", 0.0)];
+        let leaderboard = vec![
+            scored("new1", 0.9),
+            scored("new2", 0.8),
+            scored("new3", 0.7),
+            scored("meh", 0.5),
+            scored("old1", 0.25),
+            scored("old2", 0.1),
+            scored("old3", 0.2),
+        ];
+
+        let update = plan_follow_update(&following, &leaderboard, 0.6, 0.3);
+        let added: Vec<_> = update.added.iter().map(|t| t.username.as_str()).collect();
+        let removed: Vec<_> = update.removed.iter().map(|t| t.username.as_str()).collect();
+        assert_eq!(added, vec!["new1", "new2"]);
+        assert_eq!(removed, vec!["old2", "old3"]);
+
+        let mut copy_trader = CopyTrader::new();
+        for trader in following {
+            copy_trader.add_trader(trader);
+        }
+        copy_trader.apply_follow_update(&update);
+        let now: Vec<_> = copy_trader.traders().iter().map(|t| t.username.as_str()).collect();
+        // Traders absent from the leaderboard are kept
+        assert_eq!(now, vec!["old1", "manual", "new1", "new2"]);
+    }
+
+    #[test]
+    fn test_follow_update_empty_when_nothing_qualifies() {
+        let update = plan_follow_update(&[scored("a", 0.5)], &[scored("a", 0.5), scored("b", 0.4)], 0.6, 0.3);
+        assert!(update.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_losing_leader_is_paused_after_recalibration() {
+        use std::collections::HashMap;
+        use crate::storage::{CopiedTrade, Database};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path().join("test.db")).await.unwrap();
+        let mut copy_trader = CopyTrader::new().with_min_weight(0.3);
+        copy_trader.add_trader(scored("loser", 0.8));
+        copy_trader.add_trader(scored("winner", 0.8));
+        let now = Utc::now();
+
+        // Copy six positions from each, the loser closes five at a loss
+        let mut closed = HashMap::new();
+        for (name, losses) in [("loser", 5), ("winner", 1)] {
+            let trader = copy_trader.traders().iter().find(|t| t.username == name).unwrap().clone();
+            let mut closes = Vec::new();
+            for i in 0..6 {
+                let signal = CopySignal {
+                    trader: trader.clone(),
+                    market_id: format!("m{}", i),
+                    token_id: format!("t{}", i),
+                    side: crate::types::Side::Buy,
+                    trader_size: dec!(200),
+                    suggested_size: dec!(100),
+                    timestamp: now - chrono::Duration::days(10),
+                };
+                db.record_copied_trade(&CopiedTrade::from_signal(&signal)).await.unwrap();
+                closes.push(ClosedPosition {
+                    market_id: format!("m{}", i),
+                    realized_pnl: if i < losses { dec!(-40) } else { dec!(20) },
+                    closed_at: now - chrono::Duration::days(1),
+                });
+            }
+            closed.insert(trader.key().to_string(), closes);
+        }
+
+        // Too few settled copies leave the weights alone
+        let mut open = db.get_open_copied_trades().await.unwrap();
+        assert_eq!(open.len(), 12);
+        for trade in open.iter_mut().take(2) {
+            assert!(trade.settle(&closed[&trade.trader_key]));
+            db.settle_copied_trade(trade).await.unwrap();
+        }
+        let outcomes = db.get_copy_leader_outcomes().await.unwrap();
+        assert!(copy_trader.recalibrate(&outcomes, 5).is_empty());
+        assert!(copy_trader.traders().iter().all(|t| t.weight == 1.0));
+
+        for trade in open.iter_mut().skip(2) {
+            assert!(trade.settle(&closed[&trade.trader_key]));
+            db.settle_copied_trade(trade).await.unwrap();
+        }
+        assert!(db.get_open_copied_trades().await.unwrap().is_empty());
+        let outcomes = db.get_copy_leader_outcomes().await.unwrap();
+        let loser = outcomes.iter().find(|o| o.trader_key == "0xloser").unwrap();
+        assert_eq!((loser.trades, loser.wins), (6, 1));
+        // Half of the leader's P&L, as we copied half their size
+        assert_eq!(loser.total_pnl, dec!(-90));
+
+        let paused = copy_trader.recalibrate(&outcomes, 5);
+        let names: Vec<_> = paused.iter().map(|t| t.username.as_str()).collect();
+        assert_eq!(names, vec!["loser"]);
+
+        let loser = &copy_trader.traders()[0];
+        assert!((loser.win_rate - 1.0 / 6.0).abs() < 1e-9);
+        assert_eq!(loser.total_profit, dec!(-90));
+        assert!(loser.weight < 0.3);
+        assert!(copy_trader.is_paused(loser));
+        let winner = &copy_trader.traders()[1];
+        assert_eq!(winner.weight, 1.0);
+        assert!(!copy_trader.is_paused(winner));
+
+        // Already paused traders aren't reported again
+        assert!(copy_trader.recalibrate(&outcomes, 5).is_empty());
+    }
 }
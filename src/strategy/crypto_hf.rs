@@ -3,14 +3,20 @@
 //! Trades 15-minute BTC/ETH/SOL/XRP Up/Down markets based on
 //! real-time price momentum.
 
+use crate::config::FeeConfig;
 use crate::error::Result;
+use crate::fees::{FeeModel, FeeRole, PolymarketFees};
+use crate::risk::{FastStopPosition, UpDown};
 use crate::strategy::trend_detector::{PriceBar, TrendDetector, TrendSignal};
-use crate::types::{Market, Side, Signal};
+use crate::types::{Market, Side, Signal, TraceId};
+use crate::utils::ema::ExponentialMovingAverage;
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use serde::Deserialize;
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
 
 /// Crypto price tracker with full OHLCV data
 pub struct CryptoPriceTracker {
@@ -28,14 +34,40 @@ pub struct CryptoPriceTracker {
     max_history: usize,
     // Trend detector
     trend_detector: TrendDetector,
+    // Fast and slow price EMAs per ticker, and their values before the
+    // latest sample, for crossover detection
+    fast_ema: HashMap<String, ExponentialMovingAverage>,
+    slow_ema: HashMap<String, ExponentialMovingAverage>,
+    previous_emas: HashMap<String, (Decimal, Decimal)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricePoint {
     pub price: Decimal,
     pub timestamp: DateTime<Utc>,
 }
 
+/// Price history of every tracked asset, keyed by ticker (`BTC`, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CryptoTrackerState {
+    pub assets: BTreeMap<String, AssetHistory>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetHistory {
+    pub prices: Vec<PricePoint>,
+    pub bars: Vec<PriceBar>,
+}
+
+const TRACKED_ASSETS: [&str; 4] = ["BTC", "ETH", "SOL", "XRP"];
+
+/// Samples averaged by the fast and slow price EMAs
+const FAST_EMA_PERIOD: u32 = 5;
+const SLOW_EMA_PERIOD: u32 = 20;
+
+/// Confidence of an EMA crossover the trend detector doesn't confirm
+const EMA_CROSSOVER_CONFIDENCE: Decimal = dec!(0.65);
+
 #[derive(Debug, Deserialize)]
 struct BinancePrice {
     #[allow(dead_code)]
@@ -53,6 +85,14 @@ pub struct CryptoHfStrategy {
     pub certainty_threshold: Decimal,
     /// Maximum position size in USD
     pub max_position_usd: Decimal,
+    /// EWMA decay for the realized-vol estimate (RiskMetrics uses 0.94)
+    pub vol_lambda: f64,
+    /// Annualized vol above which the required edge widens proportionally
+    pub normal_volatility: Decimal,
+    /// Annualized vol above which no trades are taken
+    pub max_volatility: Decimal,
+    /// Taker fees netted off the edge (Up/Down markets' rates by default)
+    pub fees: Arc<dyn FeeModel>,
 }
 
 impl Default for CryptoHfStrategy {
@@ -62,6 +102,10 @@ impl Default for CryptoHfStrategy {
             entry_minutes_before_close: 3,       // Enter 3 mins before close
             certainty_threshold: dec!(0.85),     // 85% certainty
             max_position_usd: dec!(20),          // $20 max per trade
+            vol_lambda: 0.94,
+            normal_volatility: dec!(0.60),       // 60% annualized
+            max_volatility: dec!(1.50),          // 150% annualized
+            fees: Arc::new(PolymarketFees::new(FeeConfig::default().crypto_updown)),
         }
     }
 }
@@ -69,7 +113,7 @@ impl Default for CryptoHfStrategy {
 impl CryptoPriceTracker {
     pub fn new() -> Self {
         Self {
-            http: reqwest::Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             btc_prices: VecDeque::with_capacity(500),
             eth_prices: VecDeque::with_capacity(500),
             sol_prices: VecDeque::with_capacity(500),
@@ -80,6 +124,9 @@ impl CryptoPriceTracker {
             xrp_bars: VecDeque::with_capacity(500),
             max_history: 500,  // ~8 hours of 1-minute data
             trend_detector: TrendDetector::new(),
+            fast_ema: HashMap::new(),
+            slow_ema: HashMap::new(),
+            previous_emas: HashMap::new(),
         }
     }
 
@@ -137,6 +184,10 @@ impl CryptoPriceTracker {
             }
         }
         
+        for asset in TRACKED_ASSETS {
+            self.rebuild_emas(asset);
+        }
+
         tracing::info!("Initialized crypto price history: BTC={}, ETH={}, SOL={}, XRP={} bars",
             self.btc_bars.len(), self.eth_bars.len(), 
             self.sol_bars.len(), self.xrp_bars.len());
@@ -167,23 +218,185 @@ impl CryptoPriceTracker {
                 timestamp: Utc::now(),
             };
             
-            let queue = match symbol {
-                "BTCUSDT" => &mut self.btc_prices,
-                "ETHUSDT" => &mut self.eth_prices,
-                "SOLUSDT" => &mut self.sol_prices,
-                "XRPUSDT" => &mut self.xrp_prices,
-                _ => continue,
-            };
-            
-            if queue.len() >= self.max_history {
-                queue.pop_front();
-            }
-            queue.push_back(point);
+            self.record_price(symbol, point);
         }
         
         Ok(())
     }
 
+    /// Append a price sample, dropping the oldest beyond `max_history`, and
+    /// fold it into the asset's EMAs
+    pub fn record_price(&mut self, asset: &str, point: PricePoint) {
+        let Some(ticker) = ticker(asset) else {
+            return;
+        };
+        self.update_emas(ticker, point.price);
+        let max_history = self.max_history;
+        let Some(queue) = self.history_mut(ticker).map(|(prices, _)| prices) else {
+            return;
+        };
+
+        if queue.len() >= max_history {
+            queue.pop_front();
+        }
+        queue.push_back(point);
+    }
+
+    fn update_emas(&mut self, ticker: &str, price: Decimal) {
+        let fast = self
+            .fast_ema
+            .entry(ticker.to_string())
+            .or_insert_with(|| ExponentialMovingAverage::with_period(FAST_EMA_PERIOD));
+        let previous_fast = fast.current();
+        fast.update(price);
+        let slow = self
+            .slow_ema
+            .entry(ticker.to_string())
+            .or_insert_with(|| ExponentialMovingAverage::with_period(SLOW_EMA_PERIOD));
+        let previous_slow = slow.current();
+        slow.update(price);
+        if let (Some(fast), Some(slow)) = (previous_fast, previous_slow) {
+            self.previous_emas.insert(ticker.to_string(), (fast, slow));
+        }
+    }
+
+    /// Recompute the EMAs of `ticker` from its price history
+    fn rebuild_emas(&mut self, ticker: &str) {
+        self.fast_ema.remove(ticker);
+        self.slow_ema.remove(ticker);
+        self.previous_emas.remove(ticker);
+        let prices: Vec<Decimal> = self.prices(ticker).map_or_else(Vec::new, |q| q.iter().map(|p| p.price).collect());
+        for price in prices {
+            self.update_emas(ticker, price);
+        }
+    }
+
+    /// Current fast and slow EMAs of `symbol`
+    pub fn emas(&self, symbol: &str) -> Option<(Decimal, Decimal)> {
+        let ticker = ticker(symbol)?;
+        Some((self.fast_ema.get(ticker)?.current()?, self.slow_ema.get(ticker)?.current()?))
+    }
+
+    /// `Buy` when the latest sample took the fast EMA above the slow one,
+    /// `Sell` when it took it below; `None` without a crossover or before
+    /// the slow EMA has seen `SLOW_EMA_PERIOD` samples
+    pub fn get_ema_signal(&self, symbol: &str) -> Option<Side> {
+        let ticker = ticker(symbol)?;
+        if self.history_len(ticker) < SLOW_EMA_PERIOD as usize {
+            return None;
+        }
+        let (fast, slow) = self.emas(ticker)?;
+        let (previous_fast, previous_slow) = *self.previous_emas.get(ticker)?;
+        if fast > slow && previous_fast < previous_slow {
+            Some(Side::Buy)
+        } else if fast < slow && previous_fast > previous_slow {
+            Some(Side::Sell)
+        } else {
+            None
+        }
+    }
+
+    /// Copy of the price history, for crash-recovery snapshots
+    pub fn state(&self) -> CryptoTrackerState {
+        let assets = TRACKED_ASSETS
+            .iter()
+            .filter_map(|&asset| {
+                let (prices, bars) = self.history(asset)?;
+                Some((asset.to_string(), AssetHistory {
+                    prices: prices.iter().cloned().collect(),
+                    bars: bars.iter().cloned().collect(),
+                }))
+            })
+            .collect();
+        CryptoTrackerState { assets }
+    }
+
+    /// Replace the history of every asset in `state` (newest `max_history`
+    /// entries), e.g. with the snapshot taken before a restart
+    pub fn restore(&mut self, state: CryptoTrackerState) {
+        let max_history = self.max_history;
+        for (asset, history) in state.assets {
+            let Some((prices, bars)) = self.history_mut(&asset) else {
+                continue;
+            };
+            let skip = history.prices.len().saturating_sub(max_history);
+            *prices = history.prices.into_iter().skip(skip).collect();
+            let skip = history.bars.len().saturating_sub(max_history);
+            *bars = history.bars.into_iter().skip(skip).collect();
+            if let Some(ticker) = ticker(&asset) {
+                self.rebuild_emas(ticker);
+            }
+        }
+    }
+
+    /// Number of price samples held for `asset`
+    pub fn history_len(&self, asset: &str) -> usize {
+        self.prices(asset).map_or(0, |p| p.len())
+    }
+
+    fn history(&self, asset: &str) -> Option<(&VecDeque<PricePoint>, &VecDeque<PriceBar>)> {
+        match asset.to_uppercase().as_str() {
+            "BTC" | "BTCUSDT" => Some((&self.btc_prices, &self.btc_bars)),
+            "ETH" | "ETHUSDT" => Some((&self.eth_prices, &self.eth_bars)),
+            "SOL" | "SOLUSDT" => Some((&self.sol_prices, &self.sol_bars)),
+            "XRP" | "XRPUSDT" => Some((&self.xrp_prices, &self.xrp_bars)),
+            _ => None,
+        }
+    }
+
+    fn history_mut(&mut self, asset: &str) -> Option<(&mut VecDeque<PricePoint>, &mut VecDeque<PriceBar>)> {
+        match asset.to_uppercase().as_str() {
+            "BTC" | "BTCUSDT" => Some((&mut self.btc_prices, &mut self.btc_bars)),
+            "ETH" | "ETHUSDT" => Some((&mut self.eth_prices, &mut self.eth_bars)),
+            "SOL" | "SOLUSDT" => Some((&mut self.sol_prices, &mut self.sol_bars)),
+            "XRP" | "XRPUSDT" => Some((&mut self.xrp_prices, &mut self.xrp_bars)),
+            _ => None,
+        }
+    }
+
+    fn prices(&self, asset: &str) -> Option<&VecDeque<PricePoint>> {
+        match asset.to_uppercase().as_str() {
+            "BTC" | "BTCUSDT" => Some(&self.btc_prices),
+            "ETH" | "ETHUSDT" => Some(&self.eth_prices),
+            "SOL" | "SOLUSDT" => Some(&self.sol_prices),
+            "XRP" | "XRPUSDT" => Some(&self.xrp_prices),
+            _ => None,
+        }
+    }
+
+    /// Annualized standard deviation of log returns over the last `window`
+    /// samples. Returns across a gap in the history (a missed interval) are
+    /// skipped rather than counted as one large move.
+    pub fn realized_volatility(&self, symbol: &str, window: usize) -> Option<Decimal> {
+        let queue = self.prices(symbol)?;
+        let start = queue.len().saturating_sub(window);
+        let points: Vec<&PricePoint> = queue.iter().skip(start).collect();
+        let (returns, interval_secs) = log_returns(&points)?;
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        annualize(variance, interval_secs)
+    }
+
+    /// EWMA realized volatility (annualized) over the full history:
+    /// `σ²_t = λ·σ²_{t-1} + (1 - λ)·r²_t`. Gaps are skipped as in
+    /// `realized_volatility`.
+    pub fn ewma_volatility(&self, symbol: &str, lambda: f64) -> Option<Decimal> {
+        let queue = self.prices(symbol)?;
+        let points: Vec<&PricePoint> = queue.iter().collect();
+        let (returns, interval_secs) = log_returns(&points)?;
+        let (first, rest) = returns.split_first()?;
+
+        let variance = rest
+            .iter()
+            .fold(first * first, |var, r| lambda * var + (1.0 - lambda) * r * r);
+        annualize(variance, interval_secs)
+    }
+
     /// Calculate momentum over last N minutes
     pub fn calculate_momentum(&self, asset: &str, minutes: u32) -> Option<Decimal> {
         let queue = match asset.to_uppercase().as_str() {
@@ -227,6 +440,23 @@ impl CryptoPriceTracker {
         
         queue.back().map(|p| p.price)
     }
+
+    /// Closes and volumes of the last `count` klines of `asset`, oldest
+    /// first
+    pub fn recent_bars(&self, asset: &str, count: usize) -> Option<(Vec<Decimal>, Vec<Decimal>)> {
+        let (_, bars) = self.history(asset)?;
+        let recent = bars.iter().skip(bars.len().saturating_sub(count));
+        Some(recent.map(|b| (b.close, b.volume)).unzip())
+    }
+
+    /// Last recorded price of `asset` at or before `at`
+    pub fn price_at(&self, asset: &str, at: DateTime<Utc>) -> Option<Decimal> {
+        self.prices(asset)?
+            .iter()
+            .rev()
+            .find(|p| p.timestamp <= at)
+            .map(|p| p.price)
+    }
     
     /// 使用多指标趋势检测器分析资产
     pub fn analyze_trend(&self, asset: &str) -> Option<TrendSignal> {
@@ -250,6 +480,52 @@ impl CryptoPriceTracker {
     }
 }
 
+/// Ticker of `asset` (`BTC` for `btc` or `BTCUSDT`), if tracked
+fn ticker(asset: &str) -> Option<&'static str> {
+    let asset = asset.to_uppercase();
+    let asset = asset.strip_suffix("USDT").unwrap_or(&asset);
+    TRACKED_ASSETS.iter().copied().find(|t| *t == asset)
+}
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Log returns between consecutive samples plus the sampling interval
+/// (median spacing, in seconds). Pairs spaced more than 1.5 intervals apart
+/// straddle a gap and are skipped.
+fn log_returns(points: &[&PricePoint]) -> Option<(Vec<f64>, f64)> {
+    let mut spacings: Vec<i64> = points
+        .windows(2)
+        .map(|w| (w[1].timestamp - w[0].timestamp).num_milliseconds())
+        .filter(|ms| *ms > 0)
+        .collect();
+    if spacings.is_empty() {
+        return None;
+    }
+    spacings.sort_unstable();
+    let interval_ms = spacings[spacings.len() / 2];
+
+    let returns = points
+        .windows(2)
+        .filter(|w| {
+            let ms = (w[1].timestamp - w[0].timestamp).num_milliseconds();
+            ms > 0 && ms * 2 <= interval_ms * 3
+        })
+        .filter_map(|w| {
+            let prev = f64::try_from(w[0].price).ok()?;
+            let next = f64::try_from(w[1].price).ok()?;
+            (prev > 0.0 && next > 0.0).then(|| (next / prev).ln())
+        })
+        .collect();
+
+    Some((returns, interval_ms as f64 / 1000.0))
+}
+
+/// Per-interval variance to annualized standard deviation
+fn annualize(variance: f64, interval_secs: f64) -> Option<Decimal> {
+    let vol = (variance * SECONDS_PER_YEAR / interval_secs).sqrt();
+    vol.is_finite().then(|| Decimal::try_from(vol).ok()).flatten()
+}
+
 impl CryptoHfStrategy {
     /// Check if this is a crypto Up/Down market and parse time window
     pub fn is_crypto_hf_market(market: &Market) -> Option<CryptoMarketInfo> {
@@ -321,37 +597,42 @@ impl CryptoHfStrategy {
         tracker: &CryptoPriceTracker,
     ) -> Option<Signal> {
         let info = Self::is_crypto_hf_market(market)?;
-        
-        // 使用多指标趋势检测器分析
-        let trend_signal = match tracker.analyze_trend(&info.asset) {
-            Some(s) => s,
+
+        // EMA crossover is the primary signal; a lone tick only moves the
+        // fast EMA a third of the way, so noise rarely flips it
+        let direction = match tracker.get_ema_signal(&info.asset) {
+            Some(Side::Buy) => "Up",
+            Some(Side::Sell) => "Down",
             None => {
-                tracing::debug!("Crypto {}: 数据不足，无法分析趋势", info.asset);
+                tracing::debug!("Crypto {}: no EMA crossover", info.asset);
                 return None;
             }
         };
-        
-        tracing::info!("Crypto {} ({}min市场): 趋势={:?} 置信度={:.1}% | {}",
-            info.asset, info.duration_minutes, 
-            trend_signal.trend, trend_signal.confidence * dec!(100),
-            trend_signal.reason);
-        
-        // 检查是否应该交易
-        if !tracker.trend_detector.should_trade(&trend_signal) {
-            tracing::debug!("Crypto {}: 置信度不足 ({:.1}% < 65%)，不交易",
-                info.asset, trend_signal.confidence * dec!(100));
-            return None;
+
+        // The trend detector sizes the trade, and vetoes crossovers it
+        // confidently contradicts
+        let trend_signal = tracker.analyze_trend(&info.asset);
+        if let Some(trend) = &trend_signal {
+            tracing::info!("Crypto {} ({}min市场): 趋势={:?} 置信度={:.1}% | {}",
+                info.asset, info.duration_minutes,
+                trend.trend, trend.confidence * dec!(100),
+                trend.reason);
         }
-        
-        // 获取建议方向
-        let direction = match trend_signal.suggested_direction() {
-            Some(d) => d,
-            None => {
-                tracing::debug!("Crypto {}: 趋势中性，不交易", info.asset);
+        let confirmation = trend_signal.filter(|t| tracker.trend_detector.should_trade(t));
+        let (confidence, size_factor) = match &confirmation {
+            Some(trend) if trend.suggested_direction() == Some(direction) => {
+                (trend.confidence, trend.position_size_factor())
+            }
+            Some(trend) => {
+                tracing::debug!("Crypto {}: EMA crossover {} contradicts trend {:?}, skipping",
+                    info.asset, direction, trend.trend);
                 return None;
             }
+            None => (EMA_CROSSOVER_CONFIDENCE, dec!(0.5)),
         };
-        
+        tracing::info!("Crypto {}: EMA crossover {} (confidence {:.1}%)",
+            info.asset, direction, confidence * dec!(100));
+
         // Get current market prices
         let up_price = market.outcomes.iter()
             .find(|o| o.outcome.to_lowercase() == "up")
@@ -368,7 +649,7 @@ impl CryptoHfStrategy {
                 .token_id.clone();
             
             // 基于置信度计算模型概率
-            let prob = dec!(0.5) + (trend_signal.confidence * dec!(0.4));
+            let prob = dec!(0.5) + (confidence * dec!(0.4));
             
             (Side::Buy, token, prob, up_price)
         } else {
@@ -376,19 +657,31 @@ impl CryptoHfStrategy {
                 .find(|o| o.outcome.to_lowercase() == "down")?
                 .token_id.clone();
             
-            let prob = dec!(0.5) + (trend_signal.confidence * dec!(0.4));
+            let prob = dec!(0.5) + (confidence * dec!(0.4));
             
             (Side::Buy, token, prob, down_price)
         };
         
         // 计算 edge，扣除手续费后的净收益
-        // Polymarket 手续费: ~2% taker fee
-        let fee_rate = dec!(0.02);
+        // Polymarket 手续费随价格变化: 50c 附近最高，接近 1c/99c 时趋近于零
+        let fee_rate = self.fees.fee_per_share(&market.id, side, market_prob, FeeRole::Taker);
         let gross_edge = model_prob - market_prob;
         let net_edge = gross_edge - fee_rate;
         
+        // Realized-vol gate: sit out vol spikes, demand more edge when elevated
+        let vol = tracker.ewma_volatility(&info.asset, self.vol_lambda);
+        if let Some(vol) = vol.filter(|v| *v > self.max_volatility) {
+            tracing::debug!("Crypto {}: realized vol {:.0}% above {:.0}%, skipping",
+                info.asset, vol * dec!(100), self.max_volatility * dec!(100));
+            return None;
+        }
+        let vol_factor = vol
+            .filter(|v| *v > self.normal_volatility && self.normal_volatility > Decimal::ZERO)
+            .map(|v| v / self.normal_volatility)
+            .unwrap_or(Decimal::ONE);
+
         // 最小净 edge 要求 (扣费后还要有 3% 利润空间)
-        let min_net_edge = dec!(0.03);
+        let min_net_edge = dec!(0.03) * vol_factor;
         
         if net_edge < min_net_edge {
             tracing::debug!("Crypto {}: 净edge不足 ({:.1}% < {:.1}%)，毛edge={:.1}%，手续费={:.1}%",
@@ -403,7 +696,6 @@ impl CryptoHfStrategy {
         
         // 根据置信度和 edge 调整仓位
         let base_size = self.max_position_usd;
-        let size_factor = trend_signal.position_size_factor(); // 强趋势=1.0, 弱趋势=0.5
         let size = (base_size * size_factor).min(dec!(20));
         
         Some(Signal {
@@ -413,9 +705,51 @@ impl CryptoHfStrategy {
             model_probability: model_prob,
             market_probability: market_prob,
             edge: net_edge, // 使用净 edge
-            confidence: trend_signal.confidence,
+            confidence,
             suggested_size: size / dec!(100), // As fraction of portfolio
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        })
+    }
+
+    /// The fast stop's view of a position opened on `signal`: the window's
+    /// opening price of the underlying as strike and its EWMA volatility.
+    /// `None` outside crypto Up/Down markets, before the window opens or
+    /// without price history to take the strike from.
+    pub fn fast_stop_position(
+        &self,
+        market: &Market,
+        signal: &Signal,
+        tracker: &CryptoPriceTracker,
+        now: DateTime<Utc>,
+    ) -> Option<FastStopPosition> {
+        let info = Self::is_crypto_hf_market(market)?;
+        let expires_at = market.end_date?;
+        let opens_at = expires_at - chrono::Duration::minutes(info.duration_minutes as i64);
+        if opens_at > now || expires_at <= now {
+            return None;
+        }
+        let outcome = market.outcomes.iter().find(|o| o.token_id == signal.token_id)?;
+        let direction = match outcome.outcome.to_lowercase().as_str() {
+            "up" => UpDown::Up,
+            "down" => UpDown::Down,
+            _ => return None,
+        };
+        Some(FastStopPosition {
+            market_id: market.id.clone(),
+            token_id: signal.token_id.clone(),
+            strike: tracker.price_at(&info.asset, opens_at)?,
+            volatility: tracker.ewma_volatility(&info.asset, self.vol_lambda)?,
+            asset: info.asset,
+            direction,
+            expires_at,
+            opened_at: now,
         })
     }
 }
@@ -476,6 +810,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
@@ -495,6 +833,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
@@ -514,6 +856,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
@@ -533,6 +879,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
@@ -552,6 +902,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
@@ -567,6 +921,222 @@ mod tests {
         assert_eq!(strategy.max_position_usd, dec!(20));
     }
 
+    /// EMA signal after each of `prices`, recorded as one-minute BTC samples
+    fn ema_signals(tracker: &mut CryptoPriceTracker, prices: &[f64]) -> Vec<Option<Side>> {
+        let start = Utc::now();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                tracker.record_price("BTCUSDT", PricePoint {
+                    price: Decimal::try_from(*p).unwrap(),
+                    timestamp: start + chrono::Duration::minutes(i as i64),
+                });
+                tracker.get_ema_signal("BTC")
+            })
+            .collect()
+    }
+
+    fn downtrend(from: f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| from - i as f64).collect()
+    }
+
+    #[test]
+    fn test_ema_crossover_signals_once_per_reversal() {
+        let mut tracker = CryptoPriceTracker::new();
+        let mut prices = downtrend(60000.0, 30);
+        prices.extend((1..=20).map(|i| 59971.0 + 5.0 * i as f64));
+        let signals = ema_signals(&mut tracker, &prices);
+
+        let buys: Vec<usize> = signals.iter().enumerate().filter(|(_, s)| **s == Some(Side::Buy)).map(|(i, _)| i).collect();
+        assert_eq!(buys.len(), 1);
+        assert!(buys[0] > 30, "crossed at {}", buys[0]);
+        assert!(signals.iter().all(|s| *s != Some(Side::Sell)));
+        let (fast, slow) = tracker.emas("BTC").unwrap();
+        assert!(fast > slow);
+
+        // And back down
+        let last = *prices.last().unwrap();
+        let signals = ema_signals(&mut tracker, &downtrend(last - 5.0, 20));
+        assert_eq!(signals.iter().filter(|s| **s == Some(Side::Sell)).count(), 1);
+        assert!(signals.iter().all(|s| *s != Some(Side::Buy)));
+    }
+
+    #[test]
+    fn test_single_tick_spike_is_not_a_crossover() {
+        let mut tracker = CryptoPriceTracker::new();
+        let mut prices = downtrend(60000.0, 30);
+        prices.push(59985.0);
+        prices.extend(downtrend(59969.0, 10));
+        assert!(ema_signals(&mut tracker, &prices).iter().all(Option::is_none));
+
+        // Too little history for the slow EMA
+        let mut fresh = CryptoPriceTracker::new();
+        assert!(ema_signals(&mut fresh, &[100.0, 90.0, 80.0, 120.0, 140.0]).iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_emas_are_rebuilt_on_restore() {
+        let mut tracker = CryptoPriceTracker::new();
+        ema_signals(&mut tracker, &downtrend(60000.0, 40));
+
+        let mut restored = CryptoPriceTracker::new();
+        restored.restore(tracker.state());
+        assert_eq!(restored.emas("BTC"), tracker.emas("BTC"));
+    }
+
+    #[test]
+    fn test_signal_requires_ema_crossover() {
+        let market = Market {
+            id: "btc-updown".to_string(),
+            question: "Bitcoin Up or Down - January 28, 10:45PM-11:00PM ET".to_string(),
+            description: None,
+            end_date: None,
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: vec![
+                Outcome { token_id: "up".to_string(), outcome: "Up".to_string(), price: dec!(0.50) },
+                Outcome { token_id: "down".to_string(), outcome: "Down".to_string(), price: dec!(0.50) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+        let strategy = CryptoHfStrategy::default();
+        let mut tracker = CryptoPriceTracker::new();
+        let prices = downtrend(60000.0, 30);
+        ema_signals(&mut tracker, &prices);
+        assert!(strategy.generate_signal(&market, &tracker).is_none());
+
+        let mut rising = 59971.0;
+        while tracker.get_ema_signal("BTC").is_none() {
+            rising += 5.0;
+            ema_signals(&mut tracker, &[rising]);
+        }
+        let signal = strategy.generate_signal(&market, &tracker).expect("crossover should signal");
+        assert_eq!(signal.token_id, "up");
+        assert_eq!(signal.confidence, EMA_CROSSOVER_CONFIDENCE);
+
+        // The crossover is spent on the next sample
+        ema_signals(&mut tracker, &[rising + 5.0]);
+        assert!(strategy.generate_signal(&market, &tracker).is_none());
+    }
+
+    /// One-minute BTC samples starting at `start`, from `prices`
+    fn tracker_with(prices: &[f64], start: DateTime<Utc>) -> CryptoPriceTracker {
+        let mut tracker = CryptoPriceTracker::new();
+        for (i, p) in prices.iter().enumerate() {
+            tracker.record_price("BTC", PricePoint {
+                price: Decimal::try_from(*p).unwrap(),
+                timestamp: start + chrono::Duration::minutes(i as i64),
+            });
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_fast_stop_position_takes_strike_at_window_open() {
+        let start = Utc::now() - chrono::Duration::minutes(20);
+        let prices: Vec<f64> = (0..20).map(|i| 60000.0 + (i % 3) as f64 * 10.0 + i as f64).collect();
+        let tracker = tracker_with(&prices, start);
+        let opens_at = start + chrono::Duration::minutes(10);
+        let mut market = Market {
+            id: "btc-updown".to_string(),
+            question: "Bitcoin Up or Down - January 28, 10:45PM-11:00PM ET".to_string(),
+            description: None,
+            end_date: Some(opens_at + chrono::Duration::minutes(15)),
+            volume: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+            outcomes: vec![
+                Outcome { token_id: "up".to_string(), outcome: "Up".to_string(), price: dec!(0.50) },
+                Outcome { token_id: "down".to_string(), outcome: "Down".to_string(), price: dec!(0.50) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        };
+        let signal = Signal {
+            market_id: market.id.clone(),
+            token_id: "down".to_string(),
+            side: Side::Buy,
+            model_probability: dec!(0.60),
+            market_probability: dec!(0.50),
+            edge: dec!(0.10),
+            confidence: dec!(0.5),
+            suggested_size: dec!(0.01),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        };
+        let strategy = CryptoHfStrategy::default();
+
+        let now = Utc::now();
+        let position = strategy.fast_stop_position(&market, &signal, &tracker, now).unwrap();
+        assert_eq!(position.direction, UpDown::Down);
+        assert_eq!(position.asset, "BTC");
+        // The sample taken as the window opened, ten minutes in
+        assert_eq!(position.strike, dec!(60020));
+        assert!(position.volatility > Decimal::ZERO);
+
+        // Not yet open, or already resolved
+        market.end_date = Some(now + chrono::Duration::minutes(20));
+        assert!(strategy.fast_stop_position(&market, &signal, &tracker, now).is_none());
+        market.end_date = Some(now);
+        assert!(strategy.fast_stop_position(&market, &signal, &tracker, now).is_none());
+    }
+
+    #[test]
+    fn test_realized_vol_constant_returns_near_zero() {
+        let prices: Vec<f64> = (0..60).map(|i| 50_000.0 * 1.001f64.powi(i)).collect();
+        let tracker = tracker_with(&prices, Utc::now());
+
+        let vol = tracker.realized_volatility("BTC", 30).unwrap();
+        assert!(vol < dec!(0.001), "vol = {}", vol);
+        let ewma = tracker.ewma_volatility("BTC", 0.94).unwrap();
+        // EWMA assumes zero-mean returns, so a steady drift still registers
+        assert!(ewma > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_realized_vol_volatile_series_positive() {
+        let prices: Vec<f64> = (0..60).map(|i| if i % 2 == 0 { 50_000.0 } else { 51_000.0 }).collect();
+        let tracker = tracker_with(&prices, Utc::now());
+
+        // ±2% every minute annualizes to well over 100%
+        let vol = tracker.realized_volatility("BTC", 30).unwrap();
+        assert!(vol > dec!(10), "vol = {}", vol);
+        let ewma = tracker.ewma_volatility("BTC", 0.94).unwrap();
+        assert!(ewma > dec!(10), "ewma = {}", ewma);
+        assert!(tracker.realized_volatility("DOGE", 30).is_none());
+    }
+
+    #[test]
+    fn test_realized_vol_skips_gaps() {
+        let start = Utc::now();
+        let mut tracker = tracker_with(&[100.0; 20], start);
+        // Feed resumes an hour later 10% higher
+        for i in 0..20 {
+            tracker.record_price("BTC", PricePoint {
+                price: dec!(110),
+                timestamp: start + chrono::Duration::minutes(80 + i),
+            });
+        }
+
+        assert_eq!(tracker.realized_volatility("BTC", 40).unwrap(), Decimal::ZERO);
+        assert_eq!(tracker.ewma_volatility("BTC", 0.94).unwrap(), Decimal::ZERO);
+    }
+
     #[test]
     fn test_crypto_price_tracker_new() {
         let tracker = CryptoPriceTracker::new();
@@ -616,6 +1186,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market).unwrap();
@@ -635,6 +1209,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market).unwrap();
@@ -654,6 +1232,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market).unwrap();
@@ -685,6 +1267,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let info = CryptoHfStrategy::is_crypto_hf_market(&market);
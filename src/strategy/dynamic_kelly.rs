@@ -55,6 +55,30 @@ impl Default for DynamicKellyConfig {
     }
 }
 
+/// Sizing tier implied by the account drawdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawdownTier {
+    /// Below `drawdown_reduction_start`: full size
+    Normal,
+    /// Between start and full: size scaled down linearly
+    Reduced,
+    /// At or beyond `drawdown_reduction_full`: minimum size
+    Floor,
+}
+
+impl DynamicKellyConfig {
+    /// Tier for a drawdown from peak (e.g. 0.05 = 5%)
+    pub fn drawdown_tier(&self, drawdown: Decimal) -> DrawdownTier {
+        if drawdown <= self.drawdown_reduction_start {
+            DrawdownTier::Normal
+        } else if drawdown >= self.drawdown_reduction_full {
+            DrawdownTier::Floor
+        } else {
+            DrawdownTier::Reduced
+        }
+    }
+}
+
 /// A single trade result for streak tracking
 #[derive(Debug, Clone)]
 pub struct TradeResult {
@@ -506,6 +530,15 @@ mod tests {
         println!("Drawdown mult at 5%: {}", result.adjustments.drawdown_multiplier);
     }
 
+    #[test]
+    fn test_drawdown_tier() {
+        let config = DynamicKellyConfig::default();
+        assert_eq!(config.drawdown_tier(dec!(0)), DrawdownTier::Normal);
+        assert_eq!(config.drawdown_tier(dec!(0.03)), DrawdownTier::Normal);
+        assert_eq!(config.drawdown_tier(dec!(0.05)), DrawdownTier::Reduced);
+        assert_eq!(config.drawdown_tier(dec!(0.08)), DrawdownTier::Floor);
+    }
+
     #[test]
     fn test_high_volatility_reduces_size() {
         let kelly = make_kelly();
@@ -0,0 +1,1056 @@
+//! Market making (liquidity provision) on allowlisted markets
+//!
+//! Quotes two-sided limit orders around a fair value (model prediction
+//! blended with the book mid), skews quotes against accumulated inventory,
+//! and re-quotes on a fast loop independent of the main scan interval.
+//!
+//! The ask side is worked as a sell of held YES inventory when available,
+//! otherwise as a bid on the NO token at `1 - ask` (economically equivalent
+//! on a binary market). All accounting is in YES-equivalent shares.
+//!
+//! Quoting stops and resting quotes are pulled when:
+//! - The detected regime is `Crisis`
+//! - Flow toxicity (VPIN) exceeds the configured limit
+//! - The market is close to resolution
+//! - A severe microstructure event (flash crash, quote stuffing, spoofing)
+//!   was seen in its book; quoting resumes after [`MICROSTRUCTURE_PAUSE`]
+
+use crate::analysis::market_profile::BookSample;
+use crate::client::mock::ClobClientTrait;
+use crate::client::OrderBook;
+use crate::config::MarketMakerConfig;
+use crate::error::Result;
+use crate::executor::classify_order_status;
+use crate::notify::Notifier;
+use crate::orderbook::microstructure::SPOOF_WINDOW_MS;
+use crate::orderbook::{OrderBookAnalyzer, OrderBookAnalyzerConfig, OrderBookSnapshot, TradeFlow, VpinState};
+use crate::regime::MarketRegime;
+use crate::storage::Database;
+use crate::types::{Order, OrderStatus, OrderType, Side, TradeStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+
+/// Minimum price increment on Polymarket
+const TICK: Decimal = dec!(0.01);
+
+/// How long a market isn't quoted after a severe microstructure event
+pub const MICROSTRUCTURE_PAUSE: Duration = Duration::from_secs(60);
+
+/// Per-market market-making P&L (YES-equivalent accounting)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MmPnl {
+    pub market_id: String,
+    /// Net YES-equivalent inventory (shares, negative = short)
+    pub inventory: Decimal,
+    /// Average cost of the open inventory
+    pub avg_cost: Decimal,
+    /// Realized P&L from round trips
+    pub realized_pnl: Decimal,
+    /// Number of fills
+    pub fills: u32,
+    /// Traded notional
+    pub volume: Decimal,
+}
+
+impl MmPnl {
+    pub fn new(market_id: &str) -> Self {
+        Self {
+            market_id: market_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Record a YES-equivalent fill
+    pub fn record_fill(&mut self, side: Side, price: Decimal, size: Decimal) {
+        let signed = match side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+
+        // Portion of the fill that reduces existing inventory
+        let closing = if self.inventory.is_sign_negative() != signed.is_sign_negative() && !self.inventory.is_zero() {
+            size.min(self.inventory.abs())
+        } else {
+            Decimal::ZERO
+        };
+
+        if !closing.is_zero() {
+            let per_share = match side {
+                Side::Buy => self.avg_cost - price,  // covering a short
+                Side::Sell => price - self.avg_cost, // selling a long
+            };
+            self.realized_pnl += per_share * closing;
+        }
+
+        let opening = size - closing;
+        let remaining = if signed.is_sign_negative() {
+            self.inventory - closing
+        } else {
+            self.inventory + closing
+        };
+        if opening.is_zero() {
+            self.inventory = remaining;
+        } else {
+            let opened = if signed.is_sign_negative() { -opening } else { opening };
+            let new_inventory = remaining + opened;
+            self.avg_cost = if remaining.is_zero() {
+                price
+            } else {
+                (self.avg_cost * remaining.abs() + price * opening) / new_inventory.abs()
+            };
+            self.inventory = new_inventory;
+        }
+
+        if self.inventory.is_zero() {
+            self.avg_cost = Decimal::ZERO;
+        }
+        self.fills += 1;
+        self.volume += price * size;
+    }
+
+    /// Unrealized P&L marked at `mark`
+    pub fn unrealized_pnl(&self, mark: Decimal) -> Decimal {
+        (mark - self.avg_cost) * self.inventory
+    }
+}
+
+/// A two-sided quote in YES-equivalent prices
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub fair_value: Decimal,
+    pub bid: Option<(Decimal, Decimal)>, // (price, size)
+    pub ask: Option<(Decimal, Decimal)>,
+}
+
+/// Why quoting was halted
+#[derive(Debug, Clone, PartialEq)]
+pub enum KillReason {
+    Crisis,
+    ToxicFlow { vpin: Decimal },
+    NearClose { minutes_left: i64 },
+}
+
+impl std::fmt::Display for KillReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillReason::Crisis => write!(f, "crisis regime"),
+            KillReason::ToxicFlow { vpin } => write!(f, "toxic flow (VPIN {:.2})", vpin),
+            KillReason::NearClose { minutes_left } => write!(f, "{} minutes to close", minutes_left),
+        }
+    }
+}
+
+/// Quote computation and kill rules
+pub struct MarketMakerStrategy {
+    config: MarketMakerConfig,
+}
+
+impl MarketMakerStrategy {
+    pub fn new(config: MarketMakerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &MarketMakerConfig {
+        &self.config
+    }
+
+    /// Whether the market is on the allowlist
+    pub fn is_allowed(&self, market_id: &str) -> bool {
+        self.config.markets.iter().any(|m| m == market_id)
+    }
+
+    /// Blend the model probability with the mid, weighted by model confidence
+    pub fn fair_value(&self, mid: Decimal, model: Option<(Decimal, Decimal)>) -> Decimal {
+        match model {
+            Some((probability, confidence)) => {
+                let w = (self.config.model_weight * confidence).clamp(Decimal::ZERO, Decimal::ONE);
+                w * probability + (Decimal::ONE - w) * mid
+            }
+            None => mid,
+        }
+    }
+
+    /// Compute quotes around `fair_value`, skewed against `inventory` and kept
+    /// from crossing the book
+    pub fn compute_quote(
+        &self,
+        fair_value: Decimal,
+        inventory: Decimal,
+        best_bid: Option<Decimal>,
+        best_ask: Option<Decimal>,
+    ) -> Quote {
+        let half = self.config.spread / Decimal::TWO;
+        let inv_ratio = if self.config.max_inventory > Decimal::ZERO {
+            (inventory / self.config.max_inventory).clamp(-Decimal::ONE, Decimal::ONE)
+        } else {
+            Decimal::ZERO
+        };
+        // Long inventory pushes both quotes down to attract sellers less and buyers more
+        let center = fair_value - self.config.skew_factor * half * inv_ratio;
+
+        let min_price = TICK;
+        let max_price = Decimal::ONE - TICK;
+
+        let mut bid = (center - half)
+            .round_dp_with_strategy(2, RoundingStrategy::ToNegativeInfinity)
+            .clamp(min_price, max_price);
+        let mut ask = (center + half)
+            .round_dp_with_strategy(2, RoundingStrategy::ToPositiveInfinity)
+            .clamp(min_price, max_price);
+
+        // Stay passive
+        if let Some(a) = best_ask {
+            bid = bid.min(a - TICK);
+        }
+        if let Some(b) = best_bid {
+            ask = ask.max(b + TICK);
+        }
+        if bid >= ask {
+            ask = (bid + TICK).min(max_price);
+        }
+
+        let bid_size = self.config.quote_size.min(self.config.max_inventory - inventory);
+        let ask_size = self.config.quote_size.min(self.config.max_inventory + inventory);
+
+        Quote {
+            fair_value,
+            bid: (bid_size > Decimal::ZERO && bid >= min_price && bid < ask).then_some((bid, bid_size)),
+            ask: (ask_size > Decimal::ZERO && ask <= max_price && ask > bid).then_some((ask, ask_size)),
+        }
+    }
+
+    /// Whether resting quotes should be replaced with `new`
+    pub fn needs_requote(&self, current: Option<&Quote>, new: &Quote) -> bool {
+        let Some(current) = current else {
+            return new.bid.is_some() || new.ask.is_some();
+        };
+        let tol = self.config.requote_tolerance;
+        let side_moved = |a: Option<(Decimal, Decimal)>, b: Option<(Decimal, Decimal)>| match (a, b) {
+            (Some((pa, _)), Some((pb, _))) => (pa - pb).abs() > tol,
+            (None, None) => false,
+            _ => true,
+        };
+
+        (current.fair_value - new.fair_value).abs() > tol
+            || side_moved(current.bid, new.bid)
+            || side_moved(current.ask, new.ask)
+    }
+
+    /// Hard stop conditions
+    pub fn kill_check(
+        &self,
+        regime: Option<MarketRegime>,
+        vpin: Option<Decimal>,
+        end_date: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<KillReason> {
+        if regime == Some(MarketRegime::Crisis) {
+            return Some(KillReason::Crisis);
+        }
+        if let Some(vpin) = vpin.filter(|v| *v > self.config.max_vpin) {
+            return Some(KillReason::ToxicFlow { vpin });
+        }
+        if let Some(end) = end_date {
+            let minutes_left = (end - now).num_minutes();
+            if minutes_left < self.config.min_minutes_to_close {
+                return Some(KillReason::NearClose { minutes_left });
+            }
+        }
+        None
+    }
+}
+
+/// Market data the engine needs for one allowlisted market
+#[derive(Debug, Clone)]
+pub struct MmMarketInput {
+    pub market_id: String,
+    pub yes_token: String,
+    pub no_token: Option<String>,
+    pub end_date: Option<DateTime<Utc>>,
+    /// (probability, confidence) from the ensemble model
+    pub model: Option<(Decimal, Decimal)>,
+}
+
+/// Inputs shared between the main scan loop and the re-quote loop
+#[derive(Default)]
+pub struct MarketMakerInputs {
+    markets: RwLock<HashMap<String, MmMarketInput>>,
+    regime: RwLock<Option<MarketRegime>>,
+    trades: RwLock<HashMap<String, Vec<TradeFlow>>>,
+    /// VPIN buckets per market, published by the engine after each cycle
+    vpin: RwLock<HashMap<String, VpinState>>,
+}
+
+impl MarketMakerInputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update market data (called from the main scan loop)
+    pub async fn update_market(&self, input: MmMarketInput) {
+        self.markets.write().await.insert(input.market_id.clone(), input);
+    }
+
+    /// Update the detected market regime
+    pub async fn set_regime(&self, regime: MarketRegime) {
+        *self.regime.write().await = Some(regime);
+    }
+
+    /// Feed a public trade for VPIN estimation
+    pub async fn record_trade(&self, market_id: &str, trade: TradeFlow) {
+        self.trades
+            .write()
+            .await
+            .entry(market_id.to_string())
+            .or_default()
+            .push(trade);
+    }
+
+    /// Latest VPIN buckets of every quoted market
+    pub async fn vpin_states(&self) -> HashMap<String, VpinState> {
+        self.vpin.read().await.clone()
+    }
+
+    /// Seed VPIN buckets (e.g. from a crash-recovery snapshot); picked up
+    /// when the engine first quotes each market
+    pub async fn restore_vpin(&self, states: HashMap<String, VpinState>) {
+        *self.vpin.write().await = states;
+    }
+}
+
+/// A resting quote on the CLOB
+#[derive(Debug, Clone)]
+struct LiveQuote {
+    order_id: String,
+    /// YES-equivalent side and price
+    side: Side,
+    price: Decimal,
+    size: Decimal,
+    /// Shares of the order already booked as filled
+    matched: Decimal,
+}
+
+impl LiveQuote {
+    /// Book the order's matched size beyond what was already booked;
+    /// returns whether anything was
+    fn book_matched(&mut self, status: &OrderStatus, pnl: &mut MmPnl) -> bool {
+        let new = status.filled_size.min(self.size) - self.matched;
+        if new <= Decimal::ZERO {
+            return false;
+        }
+        pnl.record_fill(self.side, self.price, new);
+        self.matched += new;
+        true
+    }
+}
+
+struct MmMarketState {
+    bid: Option<LiveQuote>,
+    ask: Option<LiveQuote>,
+    fair_value: Option<Decimal>,
+    pnl: MmPnl,
+    analyzer: OrderBookAnalyzer,
+    /// Not quoted until then, after a severe microstructure event
+    paused_until: Option<DateTime<Utc>>,
+    /// Snapshot time of the last event acted on
+    last_event_ms: u64,
+}
+
+impl MmMarketState {
+    fn new(market_id: &str, config: &MarketMakerConfig) -> Self {
+        Self {
+            bid: None,
+            ask: None,
+            fair_value: None,
+            pnl: MmPnl::new(market_id),
+            analyzer: OrderBookAnalyzer::with_config(OrderBookAnalyzerConfig {
+                vpin_mode: config.vpin_mode,
+                vpin_bucket_secs: config.vpin_bucket_secs,
+                ..Default::default()
+            }),
+            paused_until: None,
+            last_event_ms: 0,
+        }
+    }
+
+    fn current_quote(&self) -> Option<Quote> {
+        Some(Quote {
+            fair_value: self.fair_value?,
+            bid: self.bid.as_ref().map(|q| (q.price, q.size)),
+            ask: self.ask.as_ref().map(|q| (q.price, q.size)),
+        })
+    }
+}
+
+/// Fast re-quote loop for the market maker
+pub struct MarketMakerEngine<C: ClobClientTrait> {
+    clob: C,
+    strategy: MarketMakerStrategy,
+    inputs: Arc<MarketMakerInputs>,
+    markets: Mutex<HashMap<String, MmMarketState>>,
+    db: Option<Arc<Database>>,
+    notifier: Option<Arc<Notifier>>,
+}
+
+impl<C: ClobClientTrait> MarketMakerEngine<C> {
+    pub fn new(clob: C, config: MarketMakerConfig, inputs: Arc<MarketMakerInputs>) -> Self {
+        Self {
+            clob,
+            strategy: MarketMakerStrategy::new(config),
+            inputs,
+            markets: Mutex::new(HashMap::new()),
+            db: None,
+            notifier: None,
+        }
+    }
+
+    /// Persist per-market P&L to the database on every fill
+    pub fn with_database(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Alert the operator when a market is paused for a microstructure event
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    pub fn clob(&self) -> &C {
+        &self.clob
+    }
+
+    /// Run the re-quote loop forever
+    pub async fn run(self: Arc<Self>) {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.strategy.config().requote_interval_ms));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_cycle().await {
+                tracing::warn!("Market maker cycle failed: {}", e);
+            }
+        }
+    }
+
+    /// Current P&L for every quoted market
+    pub async fn pnl(&self) -> Vec<MmPnl> {
+        self.markets.lock().await.values().map(|s| s.pnl.clone()).collect()
+    }
+
+    /// Pull every resting quote
+    pub async fn cancel_all(&self) {
+        let mut markets = self.markets.lock().await;
+        for state in markets.values_mut() {
+            self.cancel_quotes(state).await;
+        }
+    }
+
+    /// One pass over all allowlisted markets: detect fills, check kill
+    /// conditions and re-quote where needed
+    pub async fn run_cycle(&self) -> Result<()> {
+        let inputs: Vec<MmMarketInput> = self
+            .inputs
+            .markets
+            .read()
+            .await
+            .values()
+            .filter(|m| self.strategy.is_allowed(&m.market_id))
+            .cloned()
+            .collect();
+        let regime = *self.inputs.regime.read().await;
+        let mut trades = std::mem::take(&mut *self.inputs.trades.write().await);
+
+        let open: HashMap<String, OrderStatus> = self
+            .clob
+            .get_open_orders()
+            .await?
+            .into_iter()
+            .map(|o| (o.order_id.clone(), o))
+            .collect();
+
+        let mut markets = self.markets.lock().await;
+        let mut vpin = self.inputs.vpin.write().await;
+        for input in inputs {
+            let state = markets.entry(input.market_id.clone()).or_insert_with(|| {
+                let mut state = MmMarketState::new(&input.market_id, self.strategy.config());
+                if let Some(restored) = vpin.get(&input.market_id) {
+                    state.analyzer.restore_vpin(restored.clone());
+                }
+                state
+            });
+
+            for trade in trades.remove(&input.market_id).unwrap_or_default() {
+                state.analyzer.process_trade(trade);
+            }
+            vpin.insert(input.market_id.clone(), state.analyzer.vpin_state());
+
+            if self.reconcile_quotes(state, &open).await {
+                self.persist(&state.pnl).await;
+            }
+
+            let book = match self.clob.get_order_book(&input.yes_token).await {
+                Ok(b) => b,
+                Err(e) => {
+                    tracing::debug!("MM book fetch failed for {}: {}", input.market_id, e);
+                    continue;
+                }
+            };
+            let Some(mid) = book.midpoint() else {
+                continue;
+            };
+
+            let now = Utc::now();
+            let snapshot = OrderBookSnapshot::from_book(&book, now.timestamp_millis().max(0) as u64);
+            let crossed = snapshot.is_crossed();
+            state.analyzer.process_snapshot(snapshot);
+            if crossed {
+                tracing::debug!("Skipping crossed book on {}", input.market_id);
+                continue;
+            }
+            self.sample_book(&input.market_id, &book, state, now).await;
+            self.check_microstructure(&input.market_id, state, now).await;
+            if state.paused_until.is_some_and(|until| now < until) {
+                continue;
+            }
+
+            let vpin = state.analyzer.calculate_vpin().map(|v| v.vpin);
+            if let Some(reason) = self.strategy.kill_check(regime, vpin, input.end_date, Utc::now()) {
+                if state.bid.is_some() || state.ask.is_some() {
+                    tracing::warn!("Market maker halted on {}: {}", input.market_id, reason);
+                    self.cancel_quotes(state).await;
+                }
+                continue;
+            }
+
+            let fair = self.strategy.fair_value(mid, input.model);
+            let quote = self
+                .strategy
+                .compute_quote(fair, state.pnl.inventory, book.best_bid(), book.best_ask());
+
+            if !self.strategy.needs_requote(state.current_quote().as_ref(), &quote) {
+                continue;
+            }
+
+            let fills_before = state.pnl.fills;
+            self.apply_quote(&input, state, &quote).await;
+            state.fair_value = Some(fair);
+            if state.pnl.fills != fills_before {
+                self.persist(&state.pnl).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_quote(&self, input: &MmMarketInput, state: &mut MmMarketState, quote: &Quote) {
+        // Bid: buy YES
+        let bid_order = quote.bid.map(|(price, size)| Order {
+            token_id: input.yes_token.clone(),
+            side: Side::Buy,
+            price,
+            size,
+            order_type: OrderType::GTC,
+        });
+        let live = state.bid.take();
+        state.bid = self
+            .work_side(live, bid_order, Side::Buy, quote.bid, &mut state.pnl)
+            .await;
+
+        // Ask: sell held YES, else buy NO at the complement
+        let ask_order = quote.ask.and_then(|(price, size)| {
+            if state.pnl.inventory >= size {
+                Some(Order {
+                    token_id: input.yes_token.clone(),
+                    side: Side::Sell,
+                    price,
+                    size,
+                    order_type: OrderType::GTC,
+                })
+            } else {
+                input.no_token.as_ref().map(|no| Order {
+                    token_id: no.clone(),
+                    side: Side::Buy,
+                    price: Decimal::ONE - price,
+                    size,
+                    order_type: OrderType::GTC,
+                })
+            }
+        });
+        let live = state.ask.take();
+        state.ask = self
+            .work_side(live, ask_order, Side::Sell, quote.ask, &mut state.pnl)
+            .await;
+    }
+
+    /// Book fills on the market's quotes from the exchange: open quotes by
+    /// their matched size so far, the rest by looking the order up and
+    /// dropping it once it's done; returns whether anything was booked
+    async fn reconcile_quotes(&self, state: &mut MmMarketState, open: &HashMap<String, OrderStatus>) -> bool {
+        let mut filled = false;
+        for slot in [&mut state.bid, &mut state.ask] {
+            let Some(quote) = slot.as_mut() else {
+                continue;
+            };
+            if let Some(status) = open.get(&quote.order_id) {
+                filled |= quote.book_matched(status, &mut state.pnl);
+                continue;
+            }
+            match self.clob.get_order(&quote.order_id).await {
+                Ok(status) => {
+                    filled |= quote.book_matched(&status, &mut state.pnl);
+                    if classify_order_status(&status) != Some(TradeStatus::Resting) {
+                        *slot = None;
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to look up quote {}: {}", quote.order_id, e),
+            }
+        }
+        filled
+    }
+
+    /// Book whatever a pulled or replaced quote matched since it was last
+    /// reconciled
+    async fn settle(&self, mut quote: LiveQuote, pnl: &mut MmPnl) {
+        match self.clob.get_order(&quote.order_id).await {
+            Ok(status) => {
+                quote.book_matched(&status, pnl);
+            }
+            Err(e) => tracing::warn!("Failed to look up quote {}: {}", quote.order_id, e),
+        }
+    }
+
+    /// Place, replace or cancel one side; returns the resulting resting quote
+    async fn work_side(
+        &self,
+        live: Option<LiveQuote>,
+        order: Option<Order>,
+        yes_side: Side,
+        yes_quote: Option<(Decimal, Decimal)>,
+        pnl: &mut MmPnl,
+    ) -> Option<LiveQuote> {
+        let (order, (price, size)) = match (order, yes_quote) {
+            (Some(o), Some(q)) => (o, q),
+            _ => {
+                if let Some(live) = live {
+                    if let Err(e) = self.clob.cancel_order(&live.order_id).await {
+                        tracing::warn!("Failed to cancel quote {}: {}", live.order_id, e);
+                        return Some(live);
+                    }
+                    self.settle(live, pnl).await;
+                }
+                return None;
+            }
+        };
+
+        let result = match &live {
+            Some(l) => self.clob.replace_order(&l.order_id, &order).await,
+            None => self.clob.place_order(&order).await,
+        };
+
+        let status = match result {
+            Ok(status) => status,
+            Err(e) => {
+                // The old quote may still be resting; keep tracking it so the
+                // next cycle reconciles or replaces it
+                tracing::warn!("Failed to place quote: {}", e);
+                return live;
+            }
+        };
+        if let Some(live) = live {
+            self.settle(live, pnl).await;
+        }
+
+        // Book what matched on arrival; only a resting remainder stays quoted
+        let mut quote = LiveQuote {
+            order_id: status.order_id.clone(),
+            side: yes_side,
+            price,
+            size,
+            matched: Decimal::ZERO,
+        };
+        quote.book_matched(&status, pnl);
+        (classify_order_status(&status) == Some(TradeStatus::Resting)).then_some(quote)
+    }
+
+    /// Pull the market's quotes and pause it on a new severe microstructure
+    /// event in its book
+    async fn check_microstructure(&self, market_id: &str, state: &mut MmMarketState, now: DateTime<Utc>) {
+        let Some(event) = state
+            .analyzer
+            .detect_microstructure_events(SPOOF_WINDOW_MS)
+            .into_iter()
+            .filter(|e| e.is_severe() && e.timestamp_ms > state.last_event_ms)
+            .max_by(|a, b| a.severity.total_cmp(&b.severity))
+        else {
+            return;
+        };
+        state.last_event_ms = event.timestamp_ms;
+        state.paused_until = Some(now + MICROSTRUCTURE_PAUSE);
+        tracing::warn!(
+            "Microstructure event on {}: {} (severity {:.2}), pausing for {}s",
+            market_id,
+            event.event_type,
+            event.severity,
+            MICROSTRUCTURE_PAUSE.as_secs()
+        );
+        self.cancel_quotes(state).await;
+
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send(&crate::notify::microstructure_event(event.event_type, market_id)).await {
+                tracing::warn!("Failed to send microstructure alert: {}", e);
+            }
+        }
+    }
+
+    async fn cancel_quotes(&self, state: &mut MmMarketState) {
+        let fills_before = state.pnl.fills;
+        for q in [state.bid.take(), state.ask.take()].into_iter().flatten() {
+            if let Err(e) = self.clob.cancel_order(&q.order_id).await {
+                tracing::warn!("Failed to cancel quote {}: {}", q.order_id, e);
+            }
+            self.settle(q, &mut state.pnl).await;
+        }
+        state.fair_value = None;
+        if state.pnl.fills != fills_before {
+            self.persist(&state.pnl).await;
+        }
+    }
+
+    /// Record the top of `book` for the market's profile
+    async fn sample_book(&self, market_id: &str, book: &OrderBook, state: &MmMarketState, now: DateTime<Utc>) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let mm_activity = state.analyzer.analyze_market_makers().map(|a| a.activity_level);
+        let Some(sample) = BookSample::from_book(market_id, book, mm_activity, now) else {
+            return;
+        };
+        if let Err(e) = db.save_book_sample(&sample).await {
+            tracing::warn!("Failed to save book sample for {}: {}", market_id, e);
+        }
+    }
+
+    async fn persist(&self, pnl: &MmPnl) {
+        if let Some(db) = &self.db {
+            if let Err(e) = db.save_mm_pnl(pnl).await {
+                tracing::warn!("Failed to save MM P&L for {}: {}", pnl.market_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::MockClobClient;
+    use crate::client::{OrderBook, OrderBookLevel};
+    use crate::clock::ManualClock;
+    use crate::paper::PaperBroker;
+    use crate::testing::{Fault, FaultScript, FaultyClob, OrderScript, Scenario};
+
+    fn config() -> MarketMakerConfig {
+        MarketMakerConfig {
+            enabled: true,
+            markets: vec!["m1".to_string()],
+            spread: dec!(0.04),
+            quote_size: dec!(10),
+            max_inventory: dec!(20),
+            skew_factor: Decimal::ONE,
+            model_weight: dec!(0.5),
+            requote_tolerance: dec!(0.01),
+            requote_interval_ms: 100,
+            min_minutes_to_close: 60,
+            max_vpin: dec!(0.7),
+            vpin_mode: Default::default(),
+            vpin_bucket_secs: 300,
+        }
+    }
+
+    #[test]
+    fn test_fair_value_blend() {
+        let strategy = MarketMakerStrategy::new(config());
+        assert_eq!(strategy.fair_value(dec!(0.50), None), dec!(0.50));
+        // weight = 0.5 * 0.8 = 0.4
+        assert_eq!(strategy.fair_value(dec!(0.50), Some((dec!(0.60), dec!(0.8)))), dec!(0.54));
+    }
+
+    #[test]
+    fn test_quote_symmetric_when_flat() {
+        let strategy = MarketMakerStrategy::new(config());
+        let q = strategy.compute_quote(dec!(0.50), Decimal::ZERO, None, None);
+        assert_eq!(q.bid, Some((dec!(0.48), dec!(10))));
+        assert_eq!(q.ask, Some((dec!(0.52), dec!(10))));
+    }
+
+    #[test]
+    fn test_inventory_skews_quotes_down() {
+        let strategy = MarketMakerStrategy::new(config());
+        // Half of max inventory long: shift by 0.5 * half-spread = 0.01
+        let q = strategy.compute_quote(dec!(0.50), dec!(10), None, None);
+        assert_eq!(q.bid, Some((dec!(0.47), dec!(10))));
+        assert_eq!(q.ask, Some((dec!(0.51), dec!(10))));
+    }
+
+    #[test]
+    fn test_max_inventory_stops_bidding() {
+        let strategy = MarketMakerStrategy::new(config());
+        let q = strategy.compute_quote(dec!(0.50), dec!(20), None, None);
+        assert!(q.bid.is_none());
+        assert!(q.ask.is_some());
+
+        let q = strategy.compute_quote(dec!(0.50), dec!(-20), None, None);
+        assert!(q.bid.is_some());
+        assert!(q.ask.is_none());
+    }
+
+    #[test]
+    fn test_quotes_stay_passive() {
+        let strategy = MarketMakerStrategy::new(config());
+        let q = strategy.compute_quote(dec!(0.50), Decimal::ZERO, Some(dec!(0.52)), Some(dec!(0.53)));
+        assert_eq!(q.bid.unwrap().0, dec!(0.48));
+        assert_eq!(q.ask.unwrap().0, dec!(0.53));
+    }
+
+    #[test]
+    fn test_needs_requote_tolerance() {
+        let strategy = MarketMakerStrategy::new(config());
+        let a = strategy.compute_quote(dec!(0.50), Decimal::ZERO, None, None);
+        let b = strategy.compute_quote(dec!(0.505), Decimal::ZERO, None, None);
+        let c = strategy.compute_quote(dec!(0.53), Decimal::ZERO, None, None);
+
+        assert!(strategy.needs_requote(None, &a));
+        assert!(!strategy.needs_requote(Some(&a), &a));
+        assert!(!strategy.needs_requote(Some(&a), &b));
+        assert!(strategy.needs_requote(Some(&a), &c));
+
+        let mut one_sided = a.clone();
+        one_sided.ask = None;
+        assert!(strategy.needs_requote(Some(&one_sided), &a));
+    }
+
+    #[test]
+    fn test_kill_conditions() {
+        let strategy = MarketMakerStrategy::new(config());
+        let now = Utc::now();
+        let far = Some(now + chrono::Duration::days(10));
+
+        assert_eq!(strategy.kill_check(Some(MarketRegime::Ranging), Some(dec!(0.2)), far, now), None);
+        assert_eq!(
+            strategy.kill_check(Some(MarketRegime::Crisis), None, far, now),
+            Some(KillReason::Crisis)
+        );
+        assert_eq!(
+            strategy.kill_check(None, Some(dec!(0.9)), far, now),
+            Some(KillReason::ToxicFlow { vpin: dec!(0.9) })
+        );
+        assert!(matches!(
+            strategy.kill_check(None, None, Some(now + chrono::Duration::minutes(30)), now),
+            Some(KillReason::NearClose { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pnl_round_trip() {
+        let mut pnl = MmPnl::new("m1");
+        pnl.record_fill(Side::Buy, dec!(0.48), dec!(10));
+        pnl.record_fill(Side::Buy, dec!(0.50), dec!(10));
+        assert_eq!(pnl.inventory, dec!(20));
+        assert_eq!(pnl.avg_cost, dec!(0.49));
+
+        pnl.record_fill(Side::Sell, dec!(0.53), dec!(10));
+        assert_eq!(pnl.inventory, dec!(10));
+        assert_eq!(pnl.realized_pnl, dec!(0.40));
+        assert_eq!(pnl.unrealized_pnl(dec!(0.50)), dec!(0.10));
+
+        // Flip through flat to short
+        pnl.record_fill(Side::Sell, dec!(0.55), dec!(15));
+        assert_eq!(pnl.inventory, dec!(-5));
+        assert_eq!(pnl.avg_cost, dec!(0.55));
+        assert_eq!(pnl.realized_pnl, dec!(1.00));
+
+        pnl.record_fill(Side::Buy, dec!(0.50), dec!(5));
+        assert_eq!(pnl.inventory, Decimal::ZERO);
+        assert_eq!(pnl.realized_pnl, dec!(1.25));
+        assert_eq!(pnl.fills, 5);
+    }
+
+    fn paper_engine(bid: Decimal, ask: Decimal) -> (MarketMakerEngine<PaperBroker<MockClobClient>>, Arc<MarketMakerInputs>) {
+        let mut data = MockClobClient::new();
+        data.set_order_book("yes", OrderBook {
+            bids: vec![OrderBookLevel { price: bid, size: dec!(100) }],
+            asks: vec![OrderBookLevel { price: ask, size: dec!(100) }],
+        });
+        let inputs = Arc::new(MarketMakerInputs::new());
+        let broker = PaperBroker::new(data, dec!(1000));
+        (MarketMakerEngine::new(broker, config(), inputs.clone()), inputs)
+    }
+
+    fn input() -> MmMarketInput {
+        MmMarketInput {
+            market_id: "m1".to_string(),
+            yes_token: "yes".to_string(),
+            no_token: Some("no".to_string()),
+            end_date: Some(Utc::now() + chrono::Duration::days(30)),
+            model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_quotes_two_sides_on_paper() {
+        let (engine, inputs) = paper_engine(dec!(0.40), dec!(0.60));
+        inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+
+        let open = engine.clob().get_open_orders().await.unwrap();
+        assert_eq!(open.len(), 2);
+
+        // Second cycle with an unchanged book leaves quotes alone
+        engine.run_cycle().await.unwrap();
+        assert_eq!(engine.clob().get_open_orders().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_engine_ignores_unlisted_and_halts_on_crisis() {
+        let (engine, inputs) = paper_engine(dec!(0.40), dec!(0.60));
+        let mut other = input();
+        other.market_id = "m2".to_string();
+        inputs.update_market(other).await;
+        engine.run_cycle().await.unwrap();
+        assert!(engine.clob().get_open_orders().await.unwrap().is_empty());
+
+        inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+        assert_eq!(engine.clob().get_open_orders().await.unwrap().len(), 2);
+
+        inputs.set_regime(MarketRegime::Crisis).await;
+        engine.run_cycle().await.unwrap();
+        assert!(engine.clob().get_open_orders().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engine_attributes_fills() {
+        let (engine, inputs) = paper_engine(dec!(0.40), dec!(0.60));
+        inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+
+        // Someone sells through our 0.48 bid
+        engine.clob().on_book("yes", OrderBook {
+            bids: vec![OrderBookLevel { price: dec!(0.40), size: dec!(100) }],
+            asks: vec![OrderBookLevel { price: dec!(0.48), size: dec!(100) }],
+        });
+        engine.run_cycle().await.unwrap();
+
+        let pnl = engine.pnl().await;
+        assert_eq!(pnl.len(), 1);
+        assert_eq!(pnl[0].inventory, dec!(10));
+        assert_eq!(pnl[0].avg_cost, dec!(0.48));
+        assert_eq!(pnl[0].fills, 1);
+    }
+
+    #[tokio::test]
+    async fn test_flash_crash_pulls_quotes_and_pauses_market() {
+        use crate::orderbook::BookLevel;
+
+        let (engine, inputs) = paper_engine(dec!(0.40), dec!(0.60));
+        inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+        assert_eq!(engine.clob().get_open_orders().await.unwrap().len(), 2);
+
+        let snapshot = |timestamp_ms, bid, ask| OrderBookSnapshot {
+            timestamp_ms,
+            bids: vec![BookLevel { price: bid, quantity: dec!(100) }],
+            asks: vec![BookLevel { price: ask, quantity: dec!(100) }],
+            last_trade_price: None,
+            last_trade_side: None,
+        };
+        let now = Utc::now();
+        let mut markets = engine.markets.lock().await;
+        let state = markets.get_mut("m1").unwrap();
+        state.analyzer.process_snapshot(snapshot(1_000, dec!(0.40), dec!(0.60)));
+        state.analyzer.process_snapshot(snapshot(1_050, dec!(0.30), dec!(0.40)));
+        engine.check_microstructure("m1", state, now).await;
+
+        assert_eq!(state.paused_until, Some(now + MICROSTRUCTURE_PAUSE));
+        assert!(state.bid.is_none() && state.ask.is_none());
+        // The same event doesn't extend the pause
+        engine.check_microstructure("m1", state, now + chrono::Duration::seconds(30)).await;
+        assert_eq!(state.paused_until, Some(now + MICROSTRUCTURE_PAUSE));
+        drop(markets);
+        assert!(engine.clob().get_open_orders().await.unwrap().is_empty());
+
+        // Still paused on the next cycle
+        engine.run_cycle().await.unwrap();
+        assert!(engine.clob().get_open_orders().await.unwrap().is_empty());
+    }
+
+    fn mock_scenario() -> Scenario {
+        Scenario::new().default_book(&[(dec!(0.40), dec!(100))], &[(dec!(0.60), dec!(100))])
+    }
+
+    #[tokio::test]
+    async fn test_engine_books_only_matched_size() {
+        // The bid fills 4 of 10 shares, then is cancelled outside the engine
+        let clob = mock_scenario()
+            .next_order(OrderScript::new().rests_for(1).fills(dec!(0.4)))
+            .build();
+        let engine = MarketMakerEngine::new(clob, config(), Arc::new(MarketMakerInputs::new()));
+        engine.inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+        let bid_id = engine.markets.lock().await["m1"].bid.as_ref().unwrap().order_id.clone();
+
+        engine.clob().advance(2);
+        engine.run_cycle().await.unwrap();
+        let pnl = engine.pnl().await;
+        assert_eq!(pnl[0].inventory, dec!(4));
+        assert_eq!(pnl[0].fills, 1);
+
+        engine.clob().cancel_order(&bid_id).await.unwrap();
+        engine.run_cycle().await.unwrap();
+        let pnl = engine.pnl().await;
+        assert_eq!(pnl[0].inventory, dec!(4));
+        assert_eq!(pnl[0].fills, 1);
+        // The cancelled quote was dropped and the bid re-quoted
+        let bid = engine.markets.lock().await["m1"].bid.as_ref().unwrap().order_id.clone();
+        assert_ne!(bid, bid_id);
+    }
+
+    #[tokio::test]
+    async fn test_failed_replace_keeps_tracking_the_resting_quote() {
+        let start = Utc::now();
+        let clock = Arc::new(ManualClock::new(start));
+        let script = FaultScript::new(clock.clone()).during(
+            Fault::ServerError(502),
+            start + chrono::Duration::minutes(1),
+            chrono::Duration::minutes(1),
+        );
+        let clob = FaultyClob::new(mock_scenario().build(), script);
+        let engine = MarketMakerEngine::new(clob, config(), Arc::new(MarketMakerInputs::new()));
+        engine.inputs.update_market(input()).await;
+        engine.run_cycle().await.unwrap();
+
+        let mut markets = engine.markets.lock().await;
+        let state = markets.get_mut("m1").unwrap();
+        let live = state.bid.take().unwrap();
+        let order = Order {
+            token_id: "yes".to_string(),
+            side: Side::Buy,
+            price: dec!(0.45),
+            size: dec!(10),
+            order_type: OrderType::GTC,
+        };
+        clock.advance(90_000);
+        let kept = engine
+            .work_side(Some(live.clone()), Some(order), Side::Buy, Some((dec!(0.45), dec!(10))), &mut state.pnl)
+            .await
+            .unwrap();
+        assert_eq!(kept.order_id, live.order_id);
+        assert_eq!(kept.price, live.price);
+        assert_eq!(engine.clob().inner.order(&live.order_id).unwrap().status, "OPEN");
+    }
+}
@@ -1,5 +1,6 @@
 //! Trading strategy implementation
 
+pub mod breakout;
 pub mod compound;
 pub mod copy_trade;
 pub mod crypto_hf;
@@ -14,6 +15,13 @@ pub mod arbitrage;
 pub mod volatility_adaptive;
 pub mod signal_aggregator;
 pub mod performance_monitor;
+pub mod market_maker;
+pub mod momentum;
+pub mod portfolio;
+pub mod selection;
+pub mod resting;
+pub mod shadow;
+pub mod signal_scorer;
 
 #[cfg(test)]
 mod tests;
@@ -22,22 +30,36 @@ mod compound_tests;
 #[cfg(test)]
 mod copy_trade_tests;
 
+pub use breakout::{BollingerBreakoutStrategy, VolumeBreakoutStrategy};
 pub use compound::CompoundStrategy;
 pub use copy_trade::{CopyTrader, CopySignal, TopTrader, CopyTradeConfig};
 pub use market_quality::{MarketQualityScorer, MarketQuality, MarketMetrics, QualityAssessment, QualityScorerConfig};
 pub use daily_risk::{DailyRiskLimiter, DailyRiskConfig, RiskState, RiskCheckResult, RiskBudget};
-pub use dynamic_kelly::{DynamicKelly, DynamicKellyConfig, KellyResult, KellyStats, MarketContext};
+pub use dynamic_kelly::{DrawdownTier, DynamicKelly, DynamicKellyConfig, KellyResult, KellyStats, MarketContext};
 pub use enhanced_filter::{EnhancedSignalFilter, EnhancedFilterConfig, SignalCandidate, FilterDecision, CategoryStats, PositionDirection};
 pub use arbitrage::{ArbitrageDetector, ArbitrageOpportunity, ArbitrageConfig, ArbitrageType, MarketData as ArbMarketData};
 pub use volatility_adaptive::{VolatilityAdaptiveExits, VolatilityTracker, VolatilityRegime, AdaptiveParams, AtrTrailingStop};
 pub use signal_aggregator::{SignalAggregator, AggregatedDecision, SignalSource, SignalType, SignalDirection, AggregatorConfig, SignalBuilder};
 pub use performance_monitor::{PerformanceMonitor, PerformanceMetrics, CompletedTrade, MarketRegime, PerformanceAlert, AlertType, AlertSeverity, MonitorConfig, RegimeAdjustments};
+pub use momentum::MomentumStrategy;
+pub use market_maker::{MarketMakerEngine, MarketMakerInputs, MarketMakerStrategy, MmMarketInput, MmPnl, Quote, KillReason};
+pub use resting::{RestingAction, RestingOrder, RestingOrderStrategy, RestingUpdate};
+pub use portfolio::{MultiStrategyPortfolio, PredictionStrategy, Strategy, StrategyAllocation};
+pub use selection::{AutoStrategySelector, StrategySwitch};
+pub use shadow::{Routing, ShadowRouter, StrategyModes};
+pub use signal_scorer::{Grade, ScorerWeights, SignalScore, SignalScorer};
 
+use crate::analysis::market_profile::MarketProfiles;
+use crate::analysis::seasonality::SeasonalityModel;
 use crate::config::{RiskConfig, StrategyConfig};
-use crate::model::Prediction;
-use crate::types::{Market, Side, Signal};
+use crate::fees::{FeeModel, PolymarketFees};
+use crate::ml::BayesianUpdater;
+use crate::model::{Prediction, UncertainPrediction};
+use crate::types::{Market, Side, Signal, TraceId};
 use chrono::Utc;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, MathematicalOps};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 pub use crypto_hf::{CryptoHfStrategy, CryptoPriceTracker};
 
@@ -45,21 +67,104 @@ pub use crypto_hf::{CryptoHfStrategy, CryptoPriceTracker};
 pub struct SignalGenerator {
     config: StrategyConfig,
     risk_config: RiskConfig,
+    /// Fees charged to take a signal, netted off its edge
+    fees: Arc<dyn FeeModel>,
+    /// Signals sized down for high model disagreement
+    high_uncertainty_signals_total: AtomicU64,
+    /// Per-market posteriors the model probability is taken from, when enabled
+    bayesian: Option<parking_lot::Mutex<BayesianUpdater>>,
+    /// Time-of-day volatility pattern positions are sized against, when
+    /// enabled
+    seasonality: Option<SeasonalityModel>,
+    /// Per-market profiles that raise `min_edge` on wide-spread markets
+    profiles: Option<Arc<MarketProfiles>>,
 }
 
 impl SignalGenerator {
     pub fn new(config: StrategyConfig, risk_config: RiskConfig) -> Self {
-        Self { config, risk_config }
+        Self {
+            config,
+            risk_config,
+            fees: Arc::new(PolymarketFees::default()),
+            high_uncertainty_signals_total: AtomicU64::new(0),
+            bayesian: None,
+            seasonality: None,
+            profiles: None,
+        }
+    }
+
+    /// Trade on the mean of a per-market Beta posterior fed by model
+    /// predictions and market prices, sizing down as its spread grows
+    pub fn with_bayesian(mut self, updater: BayesianUpdater) -> Self {
+        self.bayesian = Some(parking_lot::Mutex::new(updater));
+        self
+    }
+
+    /// Size positions by the inverse of `model`'s expected volatility at
+    /// the time of the signal
+    pub fn with_seasonality(mut self, model: SeasonalityModel) -> Self {
+        self.seasonality = Some(model);
+        self
+    }
+
+    /// Require more edge on markets whose profile shows a wide spread
+    pub fn with_market_profiles(mut self, profiles: Arc<MarketProfiles>) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Require edges to clear `fees` as well as `min_edge` (no fees by default)
+    pub fn with_fees(mut self, fees: Arc<dyn FeeModel>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Generate a signal from an ensemble prediction, halving its size when
+    /// the models disagree by more than `high_epistemic_threshold`
+    pub fn generate_with_uncertainty(&self, market: &Market, prediction: &UncertainPrediction) -> Option<Signal> {
+        let mut signal = self.generate(market, &prediction.prediction())?;
+        signal.epistemic_uncertainty = prediction.epistemic;
+        if self.is_high_uncertainty(&signal) {
+            signal.suggested_size *= Decimal::new(5, 1);
+            self.high_uncertainty_signals_total.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(signal)
+    }
+
+    /// Whether the models behind a signal disagree strongly
+    pub fn is_high_uncertainty(&self, signal: &Signal) -> bool {
+        signal.epistemic_uncertainty > self.config.high_epistemic_threshold
+    }
+
+    /// Signals sized down for model disagreement so far
+    pub fn high_uncertainty_signals_total(&self) -> u64 {
+        self.high_uncertainty_signals_total.load(Ordering::Relaxed)
     }
 
     /// Generate trading signal from market and prediction
     pub fn generate(&self, market: &Market, prediction: &Prediction) -> Option<Signal> {
         let market_prob = market.yes_price()?;
-        let model_prob = prediction.probability;
+        let (model_prob, posterior_variance) = match &self.bayesian {
+            Some(bayesian) => {
+                let mut bayesian = bayesian.lock();
+                bayesian.update_from_price(&market.id, market_prob);
+                let posterior = bayesian.update_from_model(&market.id, prediction.probability, prediction.confidence);
+                (posterior.mean(), posterior.variance())
+            }
+            None => (prediction.probability, Decimal::ZERO),
+        };
         let edge = model_prob - market_prob;
 
-        // Check if edge is significant
-        if edge.abs() < self.config.min_edge {
+        // Check if edge is significant once the round trip's taker fees
+        // are paid, closing at the model's price
+        let side = if edge > Decimal::ZERO { Side::Buy } else { Side::Sell };
+        let fee = self.fees.round_trip_per_share(&market.id, side, market_prob, model_prob);
+        let net_edge = edge.abs() - fee;
+        let min_edge = match &self.profiles {
+            Some(profiles) => profiles.min_edge(&market.id, self.config.min_edge),
+            None => self.config.min_edge,
+        };
+        if net_edge <= Decimal::ZERO || net_edge < min_edge {
             return None;
         }
 
@@ -78,12 +183,13 @@ impl SignalGenerator {
             .find(|o| o.outcome.to_lowercase() == "yes")
             .map(|o| o.token_id.clone())?;
 
-        let (side, token_id, effective_prob) = if edge > Decimal::ZERO {
-            (Side::Buy, yes_token, model_prob)
+        // Sized on the edge net of fees
+        let (token_id, effective_prob) = if edge > Decimal::ZERO {
+            (yes_token, model_prob - fee)
         } else {
             // Sell Yes when overpriced
             // For Kelly calculation, we're betting on "not Yes" at price (1 - market_prob)
-            (Side::Sell, yes_token, Decimal::ONE - model_prob)
+            (yes_token, Decimal::ONE - model_prob - fee)
         };
 
         // Calculate position size using Kelly criterion
@@ -92,7 +198,17 @@ impl SignalGenerator {
         } else {
             Decimal::ONE - market_prob // Selling Yes = buying at (1 - price)
         };
-        let suggested_size = self.calculate_kelly_size(effective_prob, market_price, prediction.confidence);
+        let mut suggested_size = self.calculate_kelly_size(effective_prob, market_price, prediction.confidence);
+        if self.bayesian.is_some() {
+            // A Beta's std dev is at most 0.5: a certain posterior keeps the
+            // full Kelly size, a maximally vague one sizes to zero
+            let std_dev = posterior_variance.sqrt().unwrap_or(Decimal::ZERO);
+            suggested_size *= (Decimal::ONE - Decimal::TWO * std_dev).max(Decimal::ZERO);
+        }
+        let now = Utc::now();
+        if let Some(seasonality) = &self.seasonality {
+            suggested_size *= seasonality.size_factor(now);
+        }
 
         Some(Signal {
             market_id: market.id.clone(),
@@ -100,10 +216,17 @@ impl SignalGenerator {
             side,
             model_probability: model_prob,
             market_probability: market_prob,
-            edge,
+            edge: if edge > Decimal::ZERO { net_edge } else { -net_edge },
             confidence: prediction.confidence,
             suggested_size,
-            timestamp: Utc::now(),
+            timestamp: now,
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            posterior_variance,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            valid_until: None,
+            signal_score: None,
         })
     }
 
@@ -0,0 +1,249 @@
+//! Price momentum strategy
+//!
+//! A rule-based signal source that doesn't depend on the LLM.
+//! [`MomentumStrategy`] measures the change of a market's YES price over the
+//! last `lookback` bars against the same change at every earlier bar. A move
+//! more than `z_threshold` standard deviations out of the ordinary is traded
+//! in its direction, and `sigmoid(z)` is the model probability.
+
+use super::Strategy;
+use crate::client::GammaClient;
+use crate::types::{Market, Side, Signal, TraceId};
+use chrono::Utc;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Portfolio fraction of a full-strength signal
+const MAX_POSITION_FRACTION: Decimal = dec!(0.05);
+
+/// Beyond this |z| the sigmoid is 0 or 1 for all purposes
+const MAX_Z: Decimal = dec!(20);
+
+/// Trades unusually strong moves in their direction
+#[derive(Debug, Clone)]
+pub struct MomentumStrategy {
+    /// Bars the price change is measured over
+    pub lookback: usize,
+    /// |z| a change must exceed to be traded
+    pub z_threshold: Decimal,
+    /// YES token ID -> prices, oldest first
+    history: HashMap<String, Vec<Decimal>>,
+}
+
+impl Default for MomentumStrategy {
+    fn default() -> Self {
+        Self::new(6, dec!(2))
+    }
+}
+
+impl MomentumStrategy {
+    pub fn new(lookback: usize, z_threshold: Decimal) -> Self {
+        Self {
+            lookback,
+            z_threshold,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Use `prices` (oldest first) of `token_id` in the next `generate` round
+    pub fn set_price_history(&mut self, token_id: &str, prices: Vec<Decimal>) {
+        self.history.insert(token_id.to_string(), prices);
+    }
+
+    /// Fetch the price history of every market's YES token
+    pub async fn refresh(&mut self, gamma: &GammaClient, markets: &[Market]) {
+        self.history.extend(fetch_histories(gamma, markets).await);
+    }
+
+    /// z-score of the latest `lookback`-bar change against the earlier ones;
+    /// `None` without at least two earlier changes or when they're all equal
+    pub fn z_score(&self, prices: &[Decimal]) -> Option<Decimal> {
+        if self.lookback == 0 || prices.len() < self.lookback + 3 {
+            return None;
+        }
+        let changes: Vec<Decimal> = prices.windows(self.lookback + 1).map(|w| w[self.lookback] - w[0]).collect();
+        let (change, earlier) = changes.split_last()?;
+        z_score(*change, earlier)
+    }
+
+    /// Signal on the market's YES token when its latest move is out of the
+    /// ordinary
+    pub fn signal(&self, market: &Market, prices: &[Decimal]) -> Option<Signal> {
+        let z = self.z_score(prices)?;
+        let side = if z > self.z_threshold {
+            Side::Buy
+        } else if z < -self.z_threshold {
+            Side::Sell
+        } else {
+            return None;
+        };
+        signal_from_z(market, side, z)
+    }
+}
+
+impl Strategy for MomentumStrategy {
+    fn generate(&self, markets: &[Market], _budget: Decimal) -> Vec<Signal> {
+        markets
+            .iter()
+            .filter_map(|market| {
+                let prices = self.history.get(&yes_outcome(market)?.token_id)?;
+                self.signal(market, prices)
+            })
+            .collect()
+    }
+}
+
+/// `(value - mean) / std` of `window`; `None` for fewer than two values or
+/// no spread
+pub(crate) fn z_score(value: Decimal, window: &[Decimal]) -> Option<Decimal> {
+    if window.len() < 2 {
+        return None;
+    }
+    let n = Decimal::from(window.len());
+    let mean = window.iter().sum::<Decimal>() / n;
+    let variance = window.iter().map(|c| (c - mean) * (c - mean)).sum::<Decimal>() / n;
+    let std = variance.sqrt()?;
+    if std.is_zero() {
+        return None;
+    }
+    Some((value - mean) / std)
+}
+
+/// `1 / (1 + e^-z)`
+pub(crate) fn sigmoid(z: Decimal) -> Decimal {
+    let z = z.clamp(-MAX_Z, MAX_Z);
+    Decimal::ONE / (Decimal::ONE + (-z).exp())
+}
+
+pub(crate) fn yes_outcome(market: &Market) -> Option<&crate::types::Outcome> {
+    market
+        .outcomes
+        .iter()
+        .find(|o| matches!(o.outcome.to_lowercase().as_str(), "yes" | "up"))
+}
+
+/// Signal on the YES token with `sigmoid(z)` as the model probability;
+/// `None` when that doesn't put the edge on `side`
+pub(crate) fn signal_from_z(market: &Market, side: Side, z: Decimal) -> Option<Signal> {
+    let outcome = yes_outcome(market)?;
+    let market_probability = outcome.price;
+    let model_probability = sigmoid(z).clamp(dec!(0.01), dec!(0.99));
+    let edge = model_probability - market_probability;
+    if (side == Side::Buy) != (edge > Decimal::ZERO) || edge.is_zero() {
+        return None;
+    }
+    let confidence = ((model_probability - dec!(0.5)).abs() * dec!(2)).min(Decimal::ONE);
+
+    Some(Signal {
+        market_id: market.id.clone(),
+        token_id: outcome.token_id.clone(),
+        side,
+        model_probability,
+        market_probability,
+        edge,
+        confidence,
+        suggested_size: confidence * MAX_POSITION_FRACTION,
+        timestamp: Utc::now(),
+        strategy_name: None,
+        epistemic_uncertainty: Decimal::ZERO,
+        posterior_variance: Decimal::ZERO,
+        trace_id: TraceId::new(),
+        tags: Vec::new(),
+        valid_until: None,
+        signal_score: None,
+    })
+}
+
+/// Price histories of the markets' YES tokens; markets whose history can't
+/// be fetched are left out
+pub(crate) async fn fetch_histories(gamma: &GammaClient, markets: &[Market]) -> HashMap<String, Vec<Decimal>> {
+    let mut histories = HashMap::new();
+    for token_id in markets.iter().filter_map(yes_outcome).map(|o| &o.token_id) {
+        match gamma.get_price_history(token_id).await {
+            Ok(prices) => {
+                histories.insert(token_id.clone(), prices);
+            }
+            Err(e) => tracing::debug!("No price history for {}: {}", token_id, e),
+        }
+    }
+    histories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+
+    fn market(yes_price: Decimal) -> Market {
+        Market {
+            id: "m1".to_string(),
+            question: "Momentum?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(10000),
+            outcomes: vec![
+                Outcome { token_id: "yes".to_string(), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: "no".to_string(), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    #[test]
+    fn test_z_score_of_known_series() {
+        // Changes -1, 1, -1, 1 have mean 0 and std 1
+        assert_eq!(z_score(dec!(3), &[dec!(-1), dec!(1), dec!(-1), dec!(1)]), Some(dec!(3)));
+        // Mean 2, std 1
+        assert_eq!(z_score(dec!(0.5), &[dec!(1), dec!(3), dec!(1), dec!(3)]), Some(dec!(-1.5)));
+        assert_eq!(z_score(dec!(1), &[dec!(2), dec!(2), dec!(2)]), None);
+        assert_eq!(z_score(dec!(1), &[dec!(2)]), None);
+
+        // One-bar changes of 0.40 0.41 0.40 0.41 0.40 0.43: earlier changes
+        // ±0.01 (mean 0, std 0.01), latest +0.03
+        let strategy = MomentumStrategy::new(1, dec!(2));
+        let prices = [dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.43)];
+        assert_eq!(strategy.z_score(&prices), Some(dec!(3)));
+        assert_eq!(MomentumStrategy::new(5, dec!(2)).z_score(&prices), None);
+    }
+
+    #[test]
+    fn test_sigmoid() {
+        assert_eq!(sigmoid(Decimal::ZERO), dec!(0.5));
+        assert!((sigmoid(dec!(3)) - dec!(0.9526)).abs() < dec!(0.0001));
+        assert!((sigmoid(dec!(3)) + sigmoid(dec!(-3)) - Decimal::ONE).abs() < dec!(0.000001));
+        assert!(sigmoid(dec!(1000)) > dec!(0.999));
+    }
+
+    #[test]
+    fn test_trades_strong_moves_in_their_direction() {
+        let mut strategy = MomentumStrategy::new(1, dec!(2));
+        strategy.set_price_history("yes", vec![dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.43)]);
+        let signals = strategy.generate(&[market(dec!(0.43))], dec!(1000));
+        assert_eq!(signals.len(), 1);
+        let signal = &signals[0];
+        assert_eq!((signal.side, signal.token_id.as_str()), (Side::Buy, "yes"));
+        assert_eq!(signal.model_probability, sigmoid(dec!(3)));
+        assert_eq!(signal.edge, signal.model_probability - dec!(0.43));
+
+        strategy.set_price_history("yes", vec![dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.37)]);
+        let signals = strategy.generate(&[market(dec!(0.37))], dec!(1000));
+        assert_eq!(signals[0].side, Side::Sell);
+        assert!(signals[0].edge < Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_no_signal_for_ordinary_moves() {
+        let mut strategy = MomentumStrategy::new(1, dec!(2));
+        strategy.set_price_history("yes", vec![dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41), dec!(0.40), dec!(0.41)]);
+        assert!(strategy.generate(&[market(dec!(0.41))], dec!(1000)).is_empty());
+        // No history for this market
+        assert!(MomentumStrategy::default().generate(&[market(dec!(0.41))], dec!(1000)).is_empty());
+    }
+}
@@ -0,0 +1,341 @@
+//! Multi-strategy portfolio
+//!
+//! Runs several strategies side by side, each against its own slice of the
+//! balance. Strategies size their signals against their budget; the portfolio
+//! rescales them to fractions of the total balance (what the executor
+//! expects), caps each strategy at its position limit and tags every signal
+//! with the strategy that produced it.
+//!
+//! When two strategies disagree on a market, the signal with the higher
+//! `edge * confidence` wins; on a tie neither trades.
+
+use crate::model::Prediction;
+use crate::types::{Market, Side, Signal};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::SignalGenerator;
+
+/// A signal source that can run inside a `MultiStrategyPortfolio`
+pub trait Strategy: Send + Sync {
+    /// Generate signals for `markets`, with `suggested_size` as a fraction of `budget`
+    fn generate(&self, markets: &[Market], budget: Decimal) -> Vec<Signal>;
+}
+
+/// Budget and limits for one strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyAllocation {
+    pub name: String,
+    /// Share of the total balance this strategy trades with
+    pub budget_fraction: Decimal,
+    /// Maximum open positions for this strategy
+    pub max_positions: usize,
+}
+
+/// Concurrent strategies with separate risk budgets
+#[derive(Default)]
+pub struct MultiStrategyPortfolio {
+    pub strategies: Vec<(Box<dyn Strategy>, StrategyAllocation)>,
+    /// Strategy name -> currently open positions
+    open_positions: HashMap<String, usize>,
+}
+
+impl MultiStrategyPortfolio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a strategy with its allocation
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy>, allocation: StrategyAllocation) -> Self {
+        self.strategies.push((strategy, allocation));
+        self
+    }
+
+    /// Update how many positions a strategy currently holds
+    pub fn set_open_positions(&mut self, name: &str, count: usize) {
+        self.open_positions.insert(name.to_string(), count);
+    }
+
+    /// Sum of all budget fractions (should not exceed 1)
+    pub fn total_allocation(&self) -> Decimal {
+        self.strategies.iter().map(|(_, a)| a.budget_fraction).sum()
+    }
+
+    /// Run every strategy against its budget and return the surviving
+    /// signals tagged with their strategy name.
+    ///
+    /// `suggested_size` on returned signals is a fraction of `total_balance`.
+    pub fn generate_all(&self, markets: &[Market], total_balance: Decimal) -> Vec<(Signal, String)> {
+        let mut candidates = Vec::new();
+
+        for (strategy, allocation) in &self.strategies {
+            let budget = total_balance * allocation.budget_fraction;
+            let open = self.open_positions.get(&allocation.name).copied().unwrap_or(0);
+            let slots = allocation.max_positions.saturating_sub(open);
+            if slots == 0 || budget <= Decimal::ZERO {
+                continue;
+            }
+
+            let mut signals = strategy.generate(markets, budget);
+            // Keep the strongest signals when over the position limit
+            signals.sort_by_key(|s| std::cmp::Reverse(score(s)));
+            signals.truncate(slots);
+
+            for mut signal in signals {
+                signal.suggested_size *= allocation.budget_fraction;
+                signal.strategy_name = Some(allocation.name.clone());
+                candidates.push((signal, allocation.name.clone()));
+            }
+        }
+
+        resolve_conflicts(candidates, markets)
+    }
+}
+
+/// Conflict-resolution score
+fn score(signal: &Signal) -> Decimal {
+    signal.edge.abs() * signal.confidence
+}
+
+/// Drop opposing signals on the same market, keeping the higher-scoring side
+/// (or neither on a tie). Signals that agree are all kept. Buying YES and
+/// selling NO are both bullish.
+fn resolve_conflicts(candidates: Vec<(Signal, String)>, markets: &[Market]) -> Vec<(Signal, String)> {
+    let no_tokens: HashMap<&str, &str> = markets
+        .iter()
+        .flat_map(|m| m.outcomes.iter().map(move |o| (m.id.as_str(), o)))
+        .filter(|(_, o)| o.outcome.eq_ignore_ascii_case("no"))
+        .map(|(market_id, o)| (market_id, o.token_id.as_str()))
+        .collect();
+    let is_bullish = |signal: &Signal| {
+        let on_no = no_tokens.get(signal.market_id.as_str()) == Some(&signal.token_id.as_str());
+        (signal.side == Side::Buy) != on_no
+    };
+
+    // market_id -> best score per direction (bullish, bearish)
+    let mut best: HashMap<&str, (Option<Decimal>, Option<Decimal>)> = HashMap::new();
+    for (signal, _) in &candidates {
+        let entry = best.entry(signal.market_id.as_str()).or_default();
+        let slot = if is_bullish(signal) { &mut entry.0 } else { &mut entry.1 };
+        *slot = Some(slot.map_or(score(signal), |s| s.max(score(signal))));
+    }
+
+    let winners: HashMap<String, Option<bool>> = best
+        .into_iter()
+        .map(|(market_id, (bull, bear))| {
+            let winner = match (bull, bear) {
+                (Some(_), None) => Some(true),
+                (None, Some(_)) => Some(false),
+                (Some(b), Some(s)) if b > s => Some(true),
+                (Some(b), Some(s)) if s > b => Some(false),
+                _ => None,
+            };
+            (market_id.to_string(), winner)
+        })
+        .collect();
+
+    candidates
+        .into_iter()
+        .filter(|(signal, name)| match winners.get(&signal.market_id).copied().flatten() {
+            Some(bullish) if bullish == is_bullish(signal) => true,
+            Some(_) => {
+                tracing::debug!("Dropping {} signal on {}: outscored by opposing strategy", name, signal.market_id);
+                false
+            }
+            None => {
+                tracing::debug!("Dropping {} signal on {}: tied with opposing strategy", name, signal.market_id);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Model-driven strategy: `SignalGenerator` over the latest predictions
+pub struct PredictionStrategy {
+    generator: SignalGenerator,
+    /// market_id -> latest prediction
+    predictions: HashMap<String, Prediction>,
+}
+
+impl PredictionStrategy {
+    pub fn new(generator: SignalGenerator) -> Self {
+        Self {
+            generator,
+            predictions: HashMap::new(),
+        }
+    }
+
+    /// Record a prediction for the next `generate` round
+    pub fn set_prediction(&mut self, market_id: &str, prediction: Prediction) {
+        self.predictions.insert(market_id.to_string(), prediction);
+    }
+}
+
+impl Strategy for PredictionStrategy {
+    fn generate(&self, markets: &[Market], _budget: Decimal) -> Vec<Signal> {
+        markets
+            .iter()
+            .filter_map(|market| {
+                let prediction = self.predictions.get(&market.id)?;
+                self.generator.generate(market, prediction)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Outcome;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    /// Returns fixed signals regardless of input
+    struct FixedStrategy(Vec<Signal>);
+
+    impl Strategy for FixedStrategy {
+        fn generate(&self, _markets: &[Market], _budget: Decimal) -> Vec<Signal> {
+            self.0.clone()
+        }
+    }
+
+    fn market(id: &str) -> Market {
+        Market {
+            id: id.to_string(),
+            question: format!("{}?", id),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(5000),
+            outcomes: vec![
+                Outcome { token_id: format!("{}-yes", id), outcome: "Yes".to_string(), price: dec!(0.50) },
+                Outcome { token_id: format!("{}-no", id), outcome: "No".to_string(), price: dec!(0.50) },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    fn signal(market_id: &str, token: &str, side: Side, edge: Decimal, confidence: Decimal) -> Signal {
+        Signal {
+            market_id: market_id.to_string(),
+            token_id: format!("{}-{}", market_id, token),
+            side,
+            model_probability: dec!(0.50) + edge,
+            market_probability: dec!(0.50),
+            edge,
+            confidence,
+            suggested_size: dec!(0.10),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    fn allocation(name: &str, budget_fraction: Decimal, max_positions: usize) -> StrategyAllocation {
+        StrategyAllocation { name: name.to_string(), budget_fraction, max_positions }
+    }
+
+    #[test]
+    fn test_signals_scaled_and_tagged() {
+        let portfolio = MultiStrategyPortfolio::new().with_strategy(
+            Box::new(FixedStrategy(vec![signal("m1", "yes", Side::Buy, dec!(0.10), dec!(0.8))])),
+            allocation("momentum", dec!(0.25), 5),
+        );
+
+        let out = portfolio.generate_all(&[market("m1")], dec!(1000));
+        assert_eq!(out.len(), 1);
+        let (signal, name) = &out[0];
+        assert_eq!(name, "momentum");
+        assert_eq!(signal.strategy_name.as_deref(), Some("momentum"));
+        // 10% of a 25% budget is 2.5% of the total balance
+        assert_eq!(signal.suggested_size, dec!(0.025));
+    }
+
+    #[test]
+    fn test_conflict_keeps_higher_score() {
+        let portfolio = MultiStrategyPortfolio::new()
+            .with_strategy(
+                Box::new(FixedStrategy(vec![signal("m1", "yes", Side::Buy, dec!(0.10), dec!(0.9))])),
+                allocation("llm", dec!(0.5), 5),
+            )
+            .with_strategy(
+                // Buying NO is bearish
+                Box::new(FixedStrategy(vec![signal("m1", "no", Side::Buy, dec!(0.05), dec!(0.9))])),
+                allocation("copy_trade", dec!(0.5), 5),
+            );
+
+        let out = portfolio.generate_all(&[market("m1")], dec!(1000));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].1, "llm");
+    }
+
+    #[test]
+    fn test_conflict_tie_emits_neither() {
+        let portfolio = MultiStrategyPortfolio::new()
+            .with_strategy(
+                Box::new(FixedStrategy(vec![signal("m1", "yes", Side::Buy, dec!(0.10), dec!(0.5))])),
+                allocation("llm", dec!(0.5), 5),
+            )
+            .with_strategy(
+                Box::new(FixedStrategy(vec![
+                    signal("m1", "yes", Side::Sell, dec!(-0.10), dec!(0.5)),
+                    signal("m2", "yes", Side::Buy, dec!(0.10), dec!(0.5)),
+                ])),
+                allocation("momentum", dec!(0.5), 5),
+            );
+
+        let out = portfolio.generate_all(&[market("m1"), market("m2")], dec!(1000));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0.market_id, "m2");
+    }
+
+    #[test]
+    fn test_agreeing_strategies_both_kept() {
+        let portfolio = MultiStrategyPortfolio::new()
+            .with_strategy(
+                Box::new(FixedStrategy(vec![signal("m1", "yes", Side::Buy, dec!(0.10), dec!(0.9))])),
+                allocation("llm", dec!(0.5), 5),
+            )
+            .with_strategy(
+                // Selling NO agrees with buying YES
+                Box::new(FixedStrategy(vec![signal("m1", "no", Side::Sell, dec!(0.02), dec!(0.5))])),
+                allocation("momentum", dec!(0.5), 5),
+            );
+
+        assert_eq!(portfolio.generate_all(&[market("m1")], dec!(1000)).len(), 2);
+    }
+
+    #[test]
+    fn test_max_positions_keeps_strongest() {
+        let mut portfolio = MultiStrategyPortfolio::new().with_strategy(
+            Box::new(FixedStrategy(vec![
+                signal("m1", "yes", Side::Buy, dec!(0.05), dec!(0.8)),
+                signal("m2", "yes", Side::Buy, dec!(0.20), dec!(0.8)),
+                signal("m3", "yes", Side::Buy, dec!(0.10), dec!(0.8)),
+            ])),
+            allocation("llm", dec!(1), 3),
+        );
+        portfolio.set_open_positions("llm", 1);
+
+        let markets = [market("m1"), market("m2"), market("m3")];
+        let ids: Vec<_> = portfolio
+            .generate_all(&markets, dec!(1000))
+            .into_iter()
+            .map(|(s, _)| s.market_id)
+            .collect();
+        assert_eq!(ids, vec!["m2", "m3"]);
+
+        portfolio.set_open_positions("llm", 3);
+        assert!(portfolio.generate_all(&markets, dec!(1000)).is_empty());
+    }
+}
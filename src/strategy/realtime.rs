@@ -3,14 +3,16 @@
 //! Combines Binance price stream with Polymarket orderbook for better predictions.
 
 use crate::error::Result;
-use crate::types::{Market, Side, Signal};
-use chrono::Utc;
+use crate::monitor::Heartbeat;
+use crate::notify::Notifier;
+use crate::types::{Market, Side, Signal, TraceId};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, info, warn};
 
 /// Real-time price data from Binance
@@ -34,6 +36,8 @@ pub struct RealtimeEngine {
     min_momentum: Decimal,
     /// Signal output channel
     signal_tx: mpsc::Sender<Signal>,
+    /// Every price update, as (symbol, price, received at)
+    ticks: broadcast::Sender<(String, Decimal, DateTime<Utc>)>,
 }
 
 impl RealtimeEngine {
@@ -43,12 +47,21 @@ impl RealtimeEngine {
             history: Arc::new(RwLock::new(HashMap::new())),
             min_momentum: dec!(0.02), // 0.02% minimum
             signal_tx,
+            ticks: broadcast::channel(1024).0,
         }
     }
 
+    /// Receive every price update as it arrives, for consumers that can't
+    /// wait for the next scan
+    pub fn subscribe_ticks(&self) -> broadcast::Receiver<(String, Decimal, DateTime<Utc>)> {
+        self.ticks.subscribe()
+    }
+
     /// Update price from WebSocket stream
     pub async fn update_price(&self, symbol: &str, price: Decimal) {
         let now = Instant::now();
+        // No subscribers is fine
+        let _ = self.ticks.send((symbol.to_string(), price, Utc::now()));
         
         // Update history
         {
@@ -164,6 +177,13 @@ impl RealtimeEngine {
             confidence: dec!(0.7),
             suggested_size: dec!(0.1), // 10% of portfolio
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: TraceId::new(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         })
     }
 
@@ -183,8 +203,63 @@ impl RealtimeEngine {
     }
 }
 
-/// Start Binance WebSocket and feed into engine
-pub async fn start_binance_feed(engine: Arc<RealtimeEngine>) -> Result<()> {
+/// Symbol and price of a combined-stream trade message, or `None` for
+/// anything that isn't a trade with a positive price
+pub fn parse_binance_trade(text: &str) -> Option<(String, Decimal)> {
+    let data = serde_json::from_str::<serde_json::Value>(text).ok()?;
+    let trade = data.get("data")?;
+    let symbol = trade["s"].as_str().filter(|s| !s.is_empty())?;
+    let price = trade["p"].as_str()?.parse::<Decimal>().ok()?;
+    (price > Decimal::ZERO).then(|| (symbol.to_string(), price))
+}
+
+/// Unparseable trades in a row after which the feed is reported broken
+pub const UNPARSEABLE_TRADES_ALERT: usize = 3;
+
+/// Binance trade messages into the engine's prices, reporting a run of
+/// unparseable ones to `notifier` once
+pub struct BinanceTradeFeed {
+    engine: Arc<RealtimeEngine>,
+    notifier: Option<Arc<Notifier>>,
+    unparseable: usize,
+}
+
+impl BinanceTradeFeed {
+    pub fn new(engine: Arc<RealtimeEngine>) -> Self {
+        Self { engine, notifier: None, unparseable: 0 }
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Update the engine with the trade in `text`, if it parses
+    pub async fn handle_message(&mut self, text: &str) -> Option<(String, Decimal)> {
+        let Some((symbol, price)) = parse_binance_trade(text) else {
+            self.unparseable += 1;
+            if self.unparseable == UNPARSEABLE_TRADES_ALERT {
+                warn!("Binance feed: {} unparseable trades in a row", UNPARSEABLE_TRADES_ALERT);
+                if let Some(notifier) = &self.notifier {
+                    let detail = format!("{} unparseable trades in a row", UNPARSEABLE_TRADES_ALERT);
+                    let _ = notifier.error("Binance feed", &detail).await;
+                }
+            }
+            return None;
+        };
+        self.unparseable = 0;
+        self.engine.update_price(&symbol, price).await;
+        Some((symbol, price))
+    }
+}
+
+/// Start Binance WebSocket and feed into engine, beating `heartbeat` on
+/// every message and reporting unparseable trades to `notifier`
+pub async fn start_binance_feed(
+    engine: Arc<RealtimeEngine>,
+    heartbeat: Option<Arc<Heartbeat>>,
+    notifier: Option<Arc<Notifier>>,
+) -> Result<()> {
     use futures_util::StreamExt;
     use tokio_tungstenite::connect_async;
 
@@ -193,6 +268,10 @@ pub async fn start_binance_feed(engine: Arc<RealtimeEngine>) -> Result<()> {
     let url = format!("wss://stream.binance.com:9443/stream?streams={}", streams.join("/"));
 
     info!("🔌 Connecting to Binance WebSocket...");
+    let mut feed = BinanceTradeFeed::new(engine);
+    if let Some(notifier) = notifier {
+        feed = feed.with_notifier(notifier);
+    }
     
     loop {
         match connect_async(&url).await {
@@ -201,17 +280,11 @@ pub async fn start_binance_feed(engine: Arc<RealtimeEngine>) -> Result<()> {
                 let (_, mut read) = ws_stream.split();
                 
                 while let Some(msg) = read.next().await {
+                    if let Some(heartbeat) = &heartbeat {
+                        heartbeat.beat();
+                    }
                     if let Ok(tokio_tungstenite::tungstenite::Message::Text(text)) = msg {
-                        if let Ok(data) = serde_json::from_str::<serde_json::Value>(&text) {
-                            if let Some(trade) = data.get("data") {
-                                let symbol = trade["s"].as_str().unwrap_or("");
-                                if let Some(price) = trade["p"].as_str()
-                                    .and_then(|p| p.parse::<Decimal>().ok()) 
-                                {
-                                    engine.update_price(symbol, price).await;
-                                }
-                            }
-                        }
+                        feed.handle_message(&text).await;
                     }
                 }
                 warn!("WebSocket disconnected, reconnecting...");
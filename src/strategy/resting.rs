@@ -0,0 +1,494 @@
+//! Resting limit orders on illiquid markets
+//!
+//! On thin markets crossing a wide spread gives away most of the edge. When
+//! the spread is wider than `min_spread`, a signal is worked instead as a
+//! limit order `margin` inside fair value (the model probability): below it
+//! for buys, above it for sells. Every scan each resting order is reviewed
+//! against the market's latest fair value:
+//! - Repriced when its target price moved more than `reprice_tolerance`
+//! - Cancelled when fair value crossed its price (no edge left) or it has
+//!   rested longer than `max_age_secs`
+//! - Booked as filled once it is no longer open on the exchange
+//!
+//! Orders are persisted with the signal that placed them, so a restart
+//! re-adopts them instead of leaving them unmanaged on the book.
+
+use crate::client::mock::ClobClientTrait;
+use crate::config::RestingOrderConfig;
+use crate::error::Result;
+use crate::executor::{Executor, OrderType};
+use crate::storage::Database;
+use crate::types::{Side, Signal, Trade, TradeStatus};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Minimum price increment on Polymarket
+const TICK: Decimal = dec!(0.01);
+
+/// Tag of the trades resting orders place, telling their fills from taker trades
+pub const RESTING_TAG: &str = "order:resting";
+
+/// A limit order working a signal on the book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestingOrder {
+    /// Signal the order was placed for
+    pub signal: Signal,
+    /// The order as placed: token, side, size, order ID and account. Sells
+    /// are worked as bids on the complement token.
+    pub trade: Trade,
+    /// Limit price in the signal token's terms
+    pub price: Decimal,
+    /// When the first order for the signal was placed; repricing keeps it
+    pub placed_at: DateTime<Utc>,
+}
+
+impl RestingOrder {
+    /// `price` (in the signal token's terms) on the token the order works
+    pub fn order_price(&self, price: Decimal) -> Decimal {
+        if self.trade.token_id == self.signal.token_id {
+            price
+        } else {
+            Decimal::ONE - price
+        }
+    }
+
+    /// Edge left at the limit price against `fair_value`
+    pub fn edge(&self, fair_value: Decimal) -> Decimal {
+        match self.signal.side {
+            Side::Buy => fair_value - self.price,
+            Side::Sell => self.price - fair_value,
+        }
+    }
+}
+
+/// What to do with a resting order at a review
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestingAction {
+    Keep,
+    Reprice { price: Decimal },
+    Cancel { reason: String },
+}
+
+/// A change to a resting order found at a review
+#[derive(Debug, Clone)]
+pub enum RestingUpdate {
+    /// The order left the book and is booked as filled
+    Filled { order: RestingOrder, trade: Box<Trade> },
+    /// The order was moved to `order.price`
+    Repriced { from: Decimal, order: RestingOrder },
+    Cancelled { order: RestingOrder, reason: String },
+}
+
+/// Places, reprices and cancels resting orders
+pub struct RestingOrderStrategy {
+    config: RestingOrderConfig,
+    /// Orders on the book, by order ID
+    orders: HashMap<String, RestingOrder>,
+    db: Option<Arc<Database>>,
+}
+
+impl RestingOrderStrategy {
+    pub fn new(config: RestingOrderConfig) -> Self {
+        Self {
+            config,
+            orders: HashMap::new(),
+            db: None,
+        }
+    }
+
+    /// Persist orders as they are placed, repriced, filled or cancelled
+    pub fn with_database(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Take over orders persisted before a restart
+    pub fn restore(&mut self, orders: Vec<RestingOrder>) {
+        for order in orders {
+            self.orders.insert(order.trade.order_id.clone(), order);
+        }
+    }
+
+    /// Orders on the book
+    pub fn orders(&self) -> impl Iterator<Item = &RestingOrder> {
+        self.orders.values()
+    }
+
+    /// Whether a signal should rest rather than cross a book quoted
+    /// `best_bid`/`best_ask`; a book missing a side counts as wide
+    pub fn should_rest(&self, signal: &Signal, best_bid: Option<Decimal>, best_ask: Option<Decimal>) -> bool {
+        let has_edge = match signal.side {
+            Side::Buy => signal.edge > Decimal::ZERO,
+            Side::Sell => signal.edge < Decimal::ZERO,
+        };
+        let wide = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => ask - bid > self.config.min_spread,
+            _ => true,
+        };
+        self.config.enabled && has_edge && wide
+    }
+
+    /// Limit price `margin` inside `fair_value` on the tick, in the signal
+    /// token's terms; `None` when it falls off the price range
+    pub fn limit_price(&self, side: Side, fair_value: Decimal) -> Option<Decimal> {
+        let price = match side {
+            Side::Buy => (fair_value - self.config.margin).round_dp_with_strategy(2, RoundingStrategy::ToNegativeInfinity),
+            Side::Sell => (fair_value + self.config.margin).round_dp_with_strategy(2, RoundingStrategy::ToPositiveInfinity),
+        };
+        (TICK..=Decimal::ONE - TICK).contains(&price).then_some(price)
+    }
+
+    /// Review `order` against its market's latest fair value, if the model
+    /// produced one this scan
+    pub fn decide(&self, order: &RestingOrder, fair_value: Option<Decimal>, now: DateTime<Utc>) -> RestingAction {
+        let age = now - order.placed_at;
+        if age > Duration::seconds(self.config.max_age_secs as i64) {
+            return RestingAction::Cancel {
+                reason: format!("aged out after {}h", age.num_hours()),
+            };
+        }
+        let Some(fair_value) = fair_value else {
+            return RestingAction::Keep;
+        };
+        if order.edge(fair_value) <= Decimal::ZERO {
+            return RestingAction::Cancel {
+                reason: format!(
+                    "edge gone: fair value {:.1}% vs limit {:.1}%",
+                    fair_value * Decimal::ONE_HUNDRED,
+                    order.price * Decimal::ONE_HUNDRED
+                ),
+            };
+        }
+        match self.limit_price(order.signal.side, fair_value) {
+            Some(price) if (price - order.price).abs() > self.config.reprice_tolerance => RestingAction::Reprice { price },
+            _ => RestingAction::Keep,
+        }
+    }
+
+    /// Work `signal` as a resting order on `executor` when its book is too
+    /// wide to cross. Returns the trades placed, empty when an order is
+    /// already resting on the market, or `None` when the signal should be
+    /// executed normally. Signals that would first reduce inventory held
+    /// the other way are left to normal execution.
+    pub async fn place<C: ClobClientTrait>(
+        &mut self,
+        executor: &Executor<C>,
+        signal: &Signal,
+        portfolio_value: Decimal,
+    ) -> Result<Option<Vec<Trade>>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let account_id = executor.account_id();
+        let resting = self
+            .orders
+            .values()
+            .any(|o| o.signal.market_id == signal.market_id && o.trade.account_id.as_deref() == account_id);
+        if resting {
+            return Ok(Some(Vec::new()));
+        }
+
+        let book = executor.clob.get_order_book(&signal.token_id).await?;
+        if !self.should_rest(signal, book.best_bid(), book.best_ask()) {
+            return Ok(None);
+        }
+        let Some(price) = self.limit_price(signal.side, signal.model_probability) else {
+            return Ok(None);
+        };
+        let size_shares = signal.suggested_size * portfolio_value / signal.market_probability;
+        if executor.plan_order(signal, size_shares).await?.reduce.is_some() {
+            return Ok(None);
+        }
+
+        // The strategy, not the signal's TTL, decides when the order goes
+        let mut signal = signal.clone();
+        signal.valid_until = None;
+        signal.tags.push(RESTING_TAG.to_string());
+        tracing::info!(
+            "📌 Resting {:?} on {} @ {:.2} (fair value {:.2}, spread {:?}-{:?})",
+            signal.side,
+            signal.market_id,
+            price,
+            signal.model_probability,
+            book.best_bid(),
+            book.best_ask()
+        );
+        let trades = executor
+            .execute_with_type(&signal, portfolio_value, OrderType::Limit { price })
+            .await?;
+        let now = Utc::now();
+        for trade in trades.iter().filter(|t| t.status == TradeStatus::Resting) {
+            let order = RestingOrder {
+                signal: signal.clone(),
+                trade: trade.clone(),
+                price,
+                placed_at: now,
+            };
+            self.track(order).await;
+        }
+        Ok(Some(trades))
+    }
+
+    /// Review the orders resting on `executor`'s account against the fair
+    /// value of their markets (`market_id -> probability`): book those that
+    /// left the book as filled, then reprice or cancel the rest as needed
+    pub async fn review<C: ClobClientTrait>(
+        &mut self,
+        executor: &Executor<C>,
+        fair_values: &HashMap<String, Decimal>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<RestingUpdate>> {
+        let account_id = executor.account_id();
+        let mut ours: Vec<String> = self
+            .orders
+            .values()
+            .filter(|o| o.trade.account_id.as_deref() == account_id)
+            .map(|o| o.trade.order_id.clone())
+            .collect();
+        if ours.is_empty() {
+            return Ok(Vec::new());
+        }
+        ours.sort();
+        let open = executor.open_order_ids().await?;
+
+        let mut updates = Vec::new();
+        for order_id in ours {
+            let Some(order) = self.orders.remove(&order_id) else {
+                continue;
+            };
+            if !open.contains(&order_id) {
+                let trade = executor.record_resting_fill(&order.trade).await;
+                self.close(&order_id, true).await;
+                updates.push(RestingUpdate::Filled { order, trade: Box::new(trade) });
+                continue;
+            }
+
+            match self.decide(&order, fair_values.get(&order.signal.market_id).copied(), now) {
+                RestingAction::Keep => {
+                    self.orders.insert(order_id, order);
+                }
+                RestingAction::Cancel { reason } => {
+                    if let Err(e) = executor.cancel_order(&order_id).await {
+                        tracing::warn!("Failed to cancel resting order {}: {}", order_id, e);
+                        self.orders.insert(order_id, order);
+                        continue;
+                    }
+                    self.close(&order_id, false).await;
+                    updates.push(RestingUpdate::Cancelled { order, reason });
+                }
+                RestingAction::Reprice { price } => {
+                    let replaced = executor.replace_order(&order.trade, order.order_price(price)).await;
+                    self.close(&order_id, false).await;
+                    let replacement = match replaced {
+                        Ok(Some(trade)) => trade,
+                        Ok(None) => {
+                            let reason = "replacement not accepted".to_string();
+                            updates.push(RestingUpdate::Cancelled { order, reason });
+                            continue;
+                        }
+                        Err(e) => {
+                            // Whether the old order survived is unknown; make sure it's gone
+                            let _ = executor.cancel_order(&order_id).await;
+                            let reason = format!("reprice failed: {}", e);
+                            updates.push(RestingUpdate::Cancelled { order, reason });
+                            continue;
+                        }
+                    };
+                    if let Some(db) = &self.db {
+                        if let Err(e) = db.save_trade(&replacement).await {
+                            tracing::warn!("Failed to save repriced order {}: {}", replacement.order_id, e);
+                        }
+                    }
+                    let from = order.price;
+                    let repriced = RestingOrder { trade: replacement.clone(), price, ..order };
+                    if replacement.status == TradeStatus::Filled {
+                        updates.push(RestingUpdate::Filled { order: repriced, trade: Box::new(replacement) });
+                    } else {
+                        self.track(repriced.clone()).await;
+                        updates.push(RestingUpdate::Repriced { from, order: repriced });
+                    }
+                }
+            }
+        }
+        Ok(updates)
+    }
+
+    async fn track(&mut self, order: RestingOrder) {
+        if let Some(db) = &self.db {
+            if let Err(e) = db.save_resting_order(&order).await {
+                tracing::warn!("Failed to save resting order {}: {}", order.trade.order_id, e);
+            }
+        }
+        self.orders.insert(order.trade.order_id.clone(), order);
+    }
+
+    async fn close(&self, order_id: &str, filled: bool) {
+        if let Some(db) = &self.db {
+            if let Err(e) = db.close_resting_order(order_id, filled).await {
+                tracing::warn!("Failed to close resting order {}: {}", order_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RiskConfig;
+    use crate::testing::Scenario;
+
+    fn config() -> RestingOrderConfig {
+        RestingOrderConfig {
+            enabled: true,
+            min_spread: dec!(0.05),
+            margin: dec!(0.02),
+            reprice_tolerance: dec!(0.01),
+            max_age_secs: 3600,
+        }
+    }
+
+    fn signal(side: Side, model_probability: Decimal) -> Signal {
+        Signal {
+            market_id: "m1".to_string(),
+            token_id: "yes".to_string(),
+            side,
+            model_probability,
+            market_probability: dec!(0.50),
+            edge: model_probability - dec!(0.50),
+            confidence: dec!(0.8),
+            suggested_size: dec!(0.05),
+            timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            posterior_variance: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    fn resting(side: Side, price: Decimal, placed_at: DateTime<Utc>) -> RestingOrder {
+        let signal = signal(side, dec!(0.60));
+        RestingOrder {
+            trade: Trade {
+                id: "t1".to_string(),
+                order_id: "o1".to_string(),
+                token_id: signal.token_id.clone(),
+                market_id: signal.market_id.clone(),
+                side,
+                price,
+                size: dec!(10),
+                fee: Decimal::ZERO,
+                timestamp: placed_at,
+                status: TradeStatus::Resting,
+                strategy_name: None,
+                account_id: None,
+                trace_id: signal.trace_id,
+                tags: Vec::new(),
+                edge: Some(signal.edge),
+            },
+            signal,
+            price,
+            placed_at,
+        }
+    }
+
+    #[test]
+    fn test_rests_only_on_wide_books() {
+        let strategy = RestingOrderStrategy::new(config());
+        let buy = signal(Side::Buy, dec!(0.60));
+        assert!(strategy.should_rest(&buy, Some(dec!(0.40)), Some(dec!(0.50))));
+        assert!(!strategy.should_rest(&buy, Some(dec!(0.47)), Some(dec!(0.50))));
+        assert!(strategy.should_rest(&buy, None, Some(dec!(0.50))));
+
+        let disabled = RestingOrderStrategy::new(RestingOrderConfig { enabled: false, ..config() });
+        assert!(!disabled.should_rest(&buy, Some(dec!(0.40)), Some(dec!(0.50))));
+    }
+
+    #[test]
+    fn test_limit_price_sits_inside_fair_value() {
+        let strategy = RestingOrderStrategy::new(config());
+        assert_eq!(strategy.limit_price(Side::Buy, dec!(0.605)), Some(dec!(0.58)));
+        assert_eq!(strategy.limit_price(Side::Sell, dec!(0.405)), Some(dec!(0.43)));
+        assert_eq!(strategy.limit_price(Side::Buy, dec!(0.02)), None);
+        assert_eq!(strategy.limit_price(Side::Sell, dec!(0.985)), None);
+    }
+
+    #[test]
+    fn test_review_reprices_cancels_and_ages_out() {
+        let strategy = RestingOrderStrategy::new(config());
+        let now = Utc::now();
+        let order = resting(Side::Buy, dec!(0.58), now - Duration::minutes(10));
+
+        assert_eq!(strategy.decide(&order, None, now), RestingAction::Keep);
+        assert_eq!(strategy.decide(&order, Some(dec!(0.605)), now), RestingAction::Keep);
+        assert_eq!(strategy.decide(&order, Some(dec!(0.64)), now), RestingAction::Reprice { price: dec!(0.62) });
+        assert_eq!(strategy.decide(&order, Some(dec!(0.585)), now), RestingAction::Reprice { price: dec!(0.56) });
+        assert!(matches!(strategy.decide(&order, Some(dec!(0.58)), now), RestingAction::Cancel { .. }));
+
+        let sell = resting(Side::Sell, dec!(0.43), now);
+        assert!(matches!(strategy.decide(&sell, Some(dec!(0.45)), now), RestingAction::Cancel { .. }));
+        assert_eq!(strategy.decide(&sell, Some(dec!(0.41)), now), RestingAction::Keep);
+
+        let old = resting(Side::Buy, dec!(0.58), now - Duration::hours(2));
+        assert!(matches!(strategy.decide(&old, Some(dec!(0.60)), now), RestingAction::Cancel { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_resting_order_lifecycle() {
+        // A 40/70 book, too wide to cross, until sellers come down to 61
+        let clob = Scenario::new()
+            .balance(dec!(10000))
+            .book("yes", &[(dec!(0.40), dec!(500))], &[(dec!(0.70), dec!(500))])
+            .book_at(2, "yes", &[(dec!(0.40), dec!(500))], &[(dec!(0.61), dec!(500))])
+            .build();
+        let executor = Executor::new(clob, RiskConfig::default());
+        let mut strategy = RestingOrderStrategy::new(config());
+        let buy = signal(Side::Buy, dec!(0.60));
+
+        let trades = strategy.place(&executor, &buy, dec!(1000)).await.unwrap().unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].status, TradeStatus::Resting);
+        assert_eq!(trades[0].price, dec!(0.58));
+        assert!(trades[0].tags.iter().any(|t| t == RESTING_TAG));
+        // One order per market
+        assert!(strategy.place(&executor, &buy, dec!(1000)).await.unwrap().unwrap().is_empty());
+
+        // Fair value rises: the bid follows it
+        let fair = HashMap::from([("m1".to_string(), dec!(0.64))]);
+        let updates = strategy.review(&executor, &fair, Utc::now()).await.unwrap();
+        let [RestingUpdate::Repriced { from, order }] = updates.as_slice() else {
+            panic!("expected a reprice, got {:?}", updates);
+        };
+        assert_eq!((*from, order.price), (dec!(0.58), dec!(0.62)));
+        executor.clob.advance(1);
+        assert!(strategy.review(&executor, &fair, Utc::now()).await.unwrap().is_empty());
+        assert!(executor.get_positions().await.is_empty());
+
+        executor.clob.advance(1);
+        let updates = strategy.review(&executor, &fair, Utc::now()).await.unwrap();
+        let [RestingUpdate::Filled { trade, .. }] = updates.as_slice() else {
+            panic!("expected a fill, got {:?}", updates);
+        };
+        assert_eq!(trade.status, TradeStatus::Filled);
+        assert_eq!(trade.price, dec!(0.62));
+        assert_eq!(executor.get_positions().await.get("yes"), Some(&trade.size));
+        assert_eq!(strategy.orders().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tight_book_is_left_to_normal_execution() {
+        let clob = Scenario::new()
+            .balance(dec!(10000))
+            .book("yes", &[(dec!(0.49), dec!(500))], &[(dec!(0.51), dec!(500))])
+            .build();
+        let executor = Executor::new(clob, RiskConfig::default());
+        let mut strategy = RestingOrderStrategy::new(config());
+        assert!(strategy.place(&executor, &signal(Side::Buy, dec!(0.60)), dec!(1000)).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Regime-driven strategy selection
+//!
+//! Trend followers make money in trends and bleed in ranges; mean reversion
+//! is the other way around. [`AutoStrategySelector`] maps each detected
+//! market regime to the strategy configured for it in `[strategy.regime_map]`
+//! and switches to it once the detector is confident enough, falling back to
+//! the default strategy otherwise.
+
+use crate::regime::{MarketRegime, RegimeDetection};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::Strategy;
+
+/// Name the selector reports while no mapped strategy is active
+pub const DEFAULT_STRATEGY: &str = "default";
+
+/// The active strategy changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategySwitch {
+    pub from: String,
+    pub to: String,
+    pub regime: MarketRegime,
+    pub confidence: Decimal,
+}
+
+/// Picks the strategy mapped to the current regime
+pub struct AutoStrategySelector {
+    pub mappings: HashMap<MarketRegime, String>,
+    pub strategies: HashMap<String, Box<dyn Strategy>>,
+    /// Detections at or below this confidence fall back to the default
+    pub confidence_threshold: Decimal,
+    /// Strategy chosen by the last `update`
+    active: String,
+}
+
+impl AutoStrategySelector {
+    pub fn new(mappings: HashMap<MarketRegime, String>, confidence_threshold: Decimal) -> Self {
+        Self {
+            mappings,
+            strategies: HashMap::new(),
+            confidence_threshold,
+            active: DEFAULT_STRATEGY.to_string(),
+        }
+    }
+
+    /// Register the implementation behind a strategy name
+    pub fn with_strategy(mut self, name: impl Into<String>, strategy: Box<dyn Strategy>) -> Self {
+        self.strategies.insert(name.into(), strategy);
+        self
+    }
+
+    /// Name of the strategy mapped to a confident `regime`
+    pub fn select_name(&self, regime: &RegimeDetection) -> Option<&str> {
+        if regime.confidence <= self.confidence_threshold {
+            return None;
+        }
+        self.mappings.get(&regime.regime).map(String::as_str)
+    }
+
+    /// Strategy mapped to a confident `regime`; `None` means use the default
+    pub fn select(&self, regime: &RegimeDetection) -> Option<&dyn Strategy> {
+        let name = self.select_name(regime)?;
+        self.strategies.get(name).map(|s| s.as_ref())
+    }
+
+    /// Currently active strategy name, [`DEFAULT_STRATEGY`] when none is
+    pub fn active(&self) -> &str {
+        &self.active
+    }
+
+    /// Follow a new detection; the switch when the active strategy changed
+    pub fn update(&mut self, regime: &RegimeDetection) -> Option<StrategySwitch> {
+        let selected = self.select_name(regime).unwrap_or(DEFAULT_STRATEGY).to_string();
+        if selected == self.active {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.active, selected.clone());
+        Some(StrategySwitch {
+            from,
+            to: selected,
+            regime: regime.regime,
+            confidence: regime.confidence,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Market, Signal};
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    struct Idle;
+
+    impl Strategy for Idle {
+        fn generate(&self, _markets: &[Market], _budget: Decimal) -> Vec<Signal> {
+            Vec::new()
+        }
+    }
+
+    fn detection(regime: MarketRegime, confidence: Decimal) -> RegimeDetection {
+        RegimeDetection {
+            regime,
+            confidence,
+            adx: Decimal::ZERO,
+            plus_di: Decimal::ZERO,
+            minus_di: Decimal::ZERO,
+            atr: Decimal::ZERO,
+            atr_percentile: Decimal::ZERO,
+            hurst: None,
+            volatility_ratio: Decimal::ONE,
+            trend_strength: Decimal::ZERO,
+            timestamp: Utc::now(),
+            strategy: regime.strategy_recommendation(),
+        }
+    }
+
+    fn selector() -> AutoStrategySelector {
+        let mappings = HashMap::from([
+            (MarketRegime::BullishTrend, "momentum".to_string()),
+            (MarketRegime::Ranging, "mean_reversion".to_string()),
+        ]);
+        AutoStrategySelector::new(mappings, dec!(0.6)).with_strategy("momentum", Box::new(Idle))
+    }
+
+    #[test]
+    fn test_select_requires_confident_mapped_regime() {
+        let selector = selector();
+        assert!(selector.select(&detection(MarketRegime::BullishTrend, dec!(0.82))).is_some());
+        assert!(selector.select(&detection(MarketRegime::BullishTrend, dec!(0.6))).is_none());
+        assert!(selector.select(&detection(MarketRegime::Crisis, dec!(0.9))).is_none());
+        // Mapped but not registered: the name is known, the strategy isn't
+        let ranging = detection(MarketRegime::Ranging, dec!(0.9));
+        assert_eq!(selector.select_name(&ranging), Some("mean_reversion"));
+        assert!(selector.select(&ranging).is_none());
+    }
+
+    #[test]
+    fn test_update_reports_each_switch_once() {
+        let mut selector = selector();
+        assert_eq!(selector.active(), DEFAULT_STRATEGY);
+        assert!(selector.update(&detection(MarketRegime::BullishTrend, dec!(0.5))).is_none());
+
+        let switch = selector.update(&detection(MarketRegime::BullishTrend, dec!(0.82))).unwrap();
+        assert_eq!((switch.from.as_str(), switch.to.as_str()), (DEFAULT_STRATEGY, "momentum"));
+        assert_eq!(switch.confidence, dec!(0.82));
+        assert!(selector.update(&detection(MarketRegime::BullishTrend, dec!(0.9))).is_none());
+
+        let switch = selector.update(&detection(MarketRegime::Ranging, dec!(0.7))).unwrap();
+        assert_eq!((switch.from.as_str(), switch.to.as_str()), ("momentum", "mean_reversion"));
+        // Losing confidence falls back to the default
+        let switch = selector.update(&detection(MarketRegime::Ranging, dec!(0.3))).unwrap();
+        assert_eq!(switch.to, DEFAULT_STRATEGY);
+        assert_eq!(selector.active(), DEFAULT_STRATEGY);
+    }
+}
@@ -0,0 +1,272 @@
+//! Shadow mode: run strategies without trading
+//!
+//! Every strategy has a [`StrategyMode`]. Live signals go to the real
+//! executor as before; shadow signals are filled by a separate executor
+//! trading a [`PaperBroker`] against live order books, so the simulated fill
+//! is what a market order would have got at the time. The fills land in
+//! `shadow_trades` and are marked to each scan's prices, giving a
+//! counterfactual P&L to compare against the live strategies before
+//! promoting one.
+
+use crate::client::mock::ClobClientTrait;
+use crate::config::{RiskConfig, StrategyMode};
+use crate::error::Result;
+use crate::executor::Executor;
+use crate::paper::PaperBroker;
+use crate::storage::{Database, ShadowTrade, DEFAULT_STRATEGY_NAME};
+use crate::types::{Market, Signal, TradeStatus};
+use chrono::Utc;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Cash in the shadow paper account; large so shadow fills are never
+/// limited by it (sizing follows the real balance)
+const SHADOW_ACCOUNT_BALANCE: i64 = 1_000_000_000;
+
+/// Current mode of each strategy, changeable at runtime
+#[derive(Debug, Default)]
+pub struct StrategyModes {
+    modes: RwLock<HashMap<String, StrategyMode>>,
+}
+
+impl StrategyModes {
+    pub fn new(modes: HashMap<String, StrategyMode>) -> Self {
+        Self {
+            modes: RwLock::new(modes),
+        }
+    }
+
+    /// Mode of `strategy`; strategies without an entry are live
+    pub fn mode(&self, strategy: &str) -> StrategyMode {
+        self.modes.read().get(strategy).copied().unwrap_or_default()
+    }
+
+    /// Switch a strategy's mode, returning the previous one
+    pub fn set(&self, strategy: &str, mode: StrategyMode) -> StrategyMode {
+        self.modes
+            .write()
+            .insert(strategy.to_string(), mode)
+            .unwrap_or_default()
+    }
+
+    /// Strategies with an explicit mode, by name
+    pub fn all(&self) -> Vec<(String, StrategyMode)> {
+        let mut modes: Vec<_> = self.modes.read().iter().map(|(s, m)| (s.clone(), *m)).collect();
+        modes.sort_by(|a, b| a.0.cmp(&b.0));
+        modes
+    }
+}
+
+/// What happened to a routed signal
+#[derive(Debug, Clone, PartialEq)]
+pub enum Routing {
+    /// The strategy is live; the caller should execute the signal
+    Live,
+    /// Paper-filled into `shadow_trades`
+    Shadowed(Vec<ShadowTrade>),
+    /// The strategy is off
+    Dropped,
+}
+
+/// Diverts signals of shadow and disabled strategies away from live trading
+pub struct ShadowRouter<D: ClobClientTrait> {
+    modes: Arc<StrategyModes>,
+    executor: Executor<PaperBroker<D>>,
+    db: Arc<Database>,
+}
+
+impl<D: ClobClientTrait> ShadowRouter<D> {
+    /// Shadow fills are priced off order books from `data`
+    pub fn new(modes: Arc<StrategyModes>, data: D, risk_config: RiskConfig, db: Arc<Database>) -> Self {
+        let broker = PaperBroker::new(data, Decimal::from(SHADOW_ACCOUNT_BALANCE));
+        Self {
+            modes,
+            executor: Executor::new(broker, risk_config),
+            db,
+        }
+    }
+
+    pub fn modes(&self) -> &Arc<StrategyModes> {
+        &self.modes
+    }
+
+    /// Route a signal by its strategy's mode. Shadow signals are executed
+    /// against the paper account and stored; live ones are left to the caller.
+    pub async fn route(&self, signal: &Signal, market: &Market, portfolio_value: Decimal) -> Result<Routing> {
+        let strategy = signal.strategy_name.as_deref().unwrap_or(DEFAULT_STRATEGY_NAME);
+        match self.modes.mode(strategy) {
+            StrategyMode::Live => Ok(Routing::Live),
+            StrategyMode::Off => Ok(Routing::Dropped),
+            StrategyMode::Shadow => {
+                self.executor.register_market(market).await;
+                let now = Utc::now();
+                let mut shadow_trades = Vec::new();
+                for trade in self.executor.execute(signal, portfolio_value).await? {
+                    if trade.status != TradeStatus::Filled {
+                        continue;
+                    }
+                    let shadow = ShadowTrade {
+                        id: trade.id,
+                        strategy: strategy.to_string(),
+                        market_id: trade.market_id,
+                        token_id: trade.token_id,
+                        side: trade.side,
+                        price: trade.price,
+                        size: trade.size,
+                        opened_at: now,
+                        mark_price: trade.price,
+                        marked_at: now,
+                    };
+                    self.db.save_shadow_trade(&shadow).await?;
+                    shadow_trades.push(shadow);
+                }
+                Ok(Routing::Shadowed(shadow_trades))
+            }
+        }
+    }
+
+    /// Mark shadow fills to the outcome prices of freshly scanned markets
+    pub async fn mark(&self, markets: &[Market]) -> Result<()> {
+        let now = Utc::now();
+        for outcome in markets.iter().flat_map(|m| &m.outcomes) {
+            self.db.mark_shadow_trades(&outcome.token_id, outcome.price, now).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockClob, Scenario};
+    use crate::types::Side;
+    use rust_decimal_macros::dec;
+
+    fn book() -> Scenario {
+        Scenario::new().balance(dec!(100000)).default_book(
+            &[(dec!(0.54), dec!(5000))],
+            &[(dec!(0.56), dec!(5000))],
+        )
+    }
+
+    fn signal(strategy: &str) -> Signal {
+        Signal {
+            market_id: "m1".to_string(),
+            token_id: "t1".to_string(),
+            side: Side::Buy,
+            model_probability: dec!(0.70),
+            market_probability: dec!(0.55),
+            edge: dec!(0.15),
+            confidence: dec!(0.80),
+            suggested_size: dec!(0.05),
+            timestamp: Utc::now(),
+            strategy_name: Some(strategy.to_string()),
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
+        }
+    }
+
+    fn market(yes_price: Decimal) -> Market {
+        use crate::types::Outcome;
+        Market {
+            id: "m1".to_string(),
+            question: "Will it happen?".to_string(),
+            description: None,
+            end_date: None,
+            volume: dec!(10000),
+            liquidity: dec!(5000),
+            outcomes: vec![
+                Outcome { token_id: "t1".to_string(), outcome: "Yes".to_string(), price: yes_price },
+                Outcome { token_id: "t2".to_string(), outcome: "No".to_string(), price: Decimal::ONE - yes_price },
+            ],
+            active: true,
+            closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
+        }
+    }
+
+    async fn router(modes: HashMap<String, StrategyMode>) -> (ShadowRouter<MockClob>, Arc<Database>, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::connect(dir.path().join("test.db")).await.unwrap());
+        let modes = Arc::new(StrategyModes::new(modes));
+        let router = ShadowRouter::new(modes, book().build(), RiskConfig::default(), db.clone());
+        (router, db, dir)
+    }
+
+    /// Trade a signal the way the scan loop does
+    async fn dispatch(router: &ShadowRouter<MockClob>, live: &Executor<MockClob>, signal: &Signal) -> Routing {
+        let routing = router.route(signal, &market(dec!(0.55)), dec!(1000)).await.unwrap();
+        if routing == Routing::Live {
+            live.execute(signal, dec!(1000)).await.unwrap();
+        }
+        routing
+    }
+
+    #[tokio::test]
+    async fn test_shadow_signals_never_reach_live_executor() {
+        let modes = HashMap::from([
+            ("candidate".to_string(), StrategyMode::Shadow),
+            ("retired".to_string(), StrategyMode::Off),
+        ]);
+        let (router, db, _dir) = router(modes).await;
+        let live = Executor::new(book().build(), RiskConfig::default());
+
+        let routing = dispatch(&router, &live, &signal("candidate")).await;
+        let Routing::Shadowed(shadow) = routing else {
+            panic!("expected shadow fill, got {:?}", routing);
+        };
+        assert_eq!(shadow.len(), 1);
+        assert_eq!(shadow[0].price, dec!(0.56));
+        assert_eq!(dispatch(&router, &live, &signal("retired")).await, Routing::Dropped);
+
+        assert!(live.clob.fills().is_empty());
+        assert!(live.get_positions().await.is_empty());
+        let stored = db.get_shadow_trades().await.unwrap();
+        assert_eq!(stored, shadow);
+
+        // Live strategies still trade for real
+        assert_eq!(dispatch(&router, &live, &signal("llm")).await, Routing::Live);
+        assert_eq!(live.clob.fills().len(), 1);
+        assert_eq!(db.get_shadow_trades().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_counterfactual_pnl_follows_later_prices() {
+        let modes = HashMap::from([("candidate".to_string(), StrategyMode::Shadow)]);
+        let (router, db, _dir) = router(modes).await;
+
+        let Routing::Shadowed(shadow) = router.route(&signal("candidate"), &market(dec!(0.55)), dec!(1000)).await.unwrap() else {
+            panic!("expected shadow fill");
+        };
+        let size = shadow[0].size;
+
+        router.mark(&[market(dec!(0.66))]).await.unwrap();
+        let pnl = db.get_shadow_pnl().await.unwrap();
+        assert_eq!(pnl.len(), 1);
+        assert_eq!(pnl[0].strategy, "candidate");
+        assert_eq!(pnl[0].trades, 1);
+        assert_eq!(pnl[0].pnl, (dec!(0.66) - dec!(0.56)) * size);
+    }
+
+    #[test]
+    fn test_promotion_switches_mode_at_runtime() {
+        let modes = StrategyModes::new(HashMap::from([("candidate".to_string(), StrategyMode::Shadow)]));
+        assert_eq!(modes.mode("candidate"), StrategyMode::Shadow);
+        assert_eq!(modes.mode("unlisted"), StrategyMode::Live);
+
+        assert_eq!(modes.set("candidate", StrategyMode::Live), StrategyMode::Shadow);
+        assert_eq!(modes.mode("candidate"), StrategyMode::Live);
+        assert_eq!(modes.all(), vec![("candidate".to_string(), StrategyMode::Live)]);
+        assert_eq!("SHADOW".parse::<StrategyMode>(), Ok(StrategyMode::Shadow));
+        assert!("paper".parse::<StrategyMode>().is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Multi-factor signal scoring
+//!
+//! Edge alone ignores how hard a market is to trade, how sure the model
+//! is and whether the crowd and the BTC regime agree. [`SignalScorer`]
+//! rates a signal from 0 to 1 on each of those dimensions, combines them
+//! with [`ScorerWeights`] and grades the result; only signals graded at
+//! least `[strategy.scoring] min_grade` are executed.
+
+use crate::analysis::sentiment::SentimentScore;
+use crate::orderbook::OrderBookAnalysis;
+use crate::regime::{MarketRegime, RegimeDetection};
+use crate::types::{Market, Side, Signal};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Edge (absolute) that scores full marks
+const FULL_EDGE: f64 = 0.10;
+
+/// Market liquidity (USDC) that scores full marks
+const FULL_LIQUIDITY: f64 = 50_000.0;
+
+/// Spread (bps) at which the liquidity score reaches zero
+const MAX_SPREAD_BPS: f64 = 1_000.0;
+
+/// Model disagreement (std dev of ensemble probabilities) at which the
+/// source quality reaches zero
+const MAX_EPISTEMIC_UNCERTAINTY: f64 = 0.25;
+
+/// Score components, in the order they're reported
+pub const SCORE_COMPONENTS: [&str; 6] =
+    ["edge", "liquidity_score", "confidence", "sentiment", "regime_alignment", "source_quality"];
+
+/// Weight of each score component; they should sum to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScorerWeights {
+    pub edge: f64,
+    pub liquidity_score: f64,
+    pub confidence: f64,
+    pub sentiment: f64,
+    pub regime_alignment: f64,
+    pub source_quality: f64,
+}
+
+impl Default for ScorerWeights {
+    fn default() -> Self {
+        Self {
+            edge: 0.30,
+            liquidity_score: 0.15,
+            confidence: 0.20,
+            sentiment: 0.10,
+            regime_alignment: 0.10,
+            source_quality: 0.15,
+        }
+    }
+}
+
+impl ScorerWeights {
+    pub fn sum(&self) -> f64 {
+        self.edge + self.liquidity_score + self.confidence + self.sentiment + self.regime_alignment + self.source_quality
+    }
+
+    /// Scaled to sum to 1; the defaults when nothing is weighted
+    pub fn normalized(&self) -> Self {
+        let sum = self.sum();
+        if sum <= 0.0 || !sum.is_finite() {
+            return Self::default();
+        }
+        Self {
+            edge: self.edge / sum,
+            liquidity_score: self.liquidity_score / sum,
+            confidence: self.confidence / sum,
+            sentiment: self.sentiment / sum,
+            regime_alignment: self.regime_alignment / sum,
+            source_quality: self.source_quality / sum,
+        }
+    }
+
+    fn get(&self, component: &str) -> f64 {
+        match component {
+            "edge" => self.edge,
+            "liquidity_score" => self.liquidity_score,
+            "confidence" => self.confidence,
+            "sentiment" => self.sentiment,
+            "regime_alignment" => self.regime_alignment,
+            "source_quality" => self.source_quality,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Letter grade of a signal score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    /// A from 0.8, B from 0.7, C from 0.6, D from 0.5, F below
+    pub fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 0.8 => Self::A,
+            s if s >= 0.7 => Self::B,
+            s if s >= 0.6 => Self::C,
+            s if s >= 0.5 => Self::D,
+            _ => Self::F,
+        }
+    }
+
+    /// Lowest score with this grade
+    pub fn min_score(self) -> f64 {
+        match self {
+            Self::A => 0.8,
+            Self::B => 0.7,
+            Self::C => 0.6,
+            Self::D => 0.5,
+            Self::F => 0.0,
+        }
+    }
+
+    /// Whether this grade is `min` or better
+    pub fn at_least(self, min: Grade) -> bool {
+        self.min_score() >= min.min_score()
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A signal's weighted score, its components (each 0-1) and grade
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalScore {
+    pub total: f64,
+    pub components: HashMap<String, f64>,
+    pub grade: Grade,
+}
+
+impl SignalScore {
+    /// Components in [`SCORE_COMPONENTS`] order
+    pub fn breakdown(&self) -> Vec<(&'static str, f64)> {
+        SCORE_COMPONENTS
+            .iter()
+            .filter_map(|&name| self.components.get(name).map(|&v| (name, v)))
+            .collect()
+    }
+}
+
+/// Scores signals on several dimensions before execution
+#[derive(Debug, Clone, Default)]
+pub struct SignalScorer {
+    pub weights: ScorerWeights,
+}
+
+impl SignalScorer {
+    /// Scorer with `weights` scaled to sum to 1
+    pub fn new(weights: ScorerWeights) -> Self {
+        Self { weights: weights.normalized() }
+    }
+
+    /// Score `signal` on `market`. Missing inputs score a neutral 0.5 for
+    /// sentiment and regime; without a book the liquidity score rests on
+    /// the market's liquidity alone. The regime and sentiment are read with
+    /// YES as the bullish side of the market.
+    pub fn score(
+        &self,
+        signal: &Signal,
+        market: &Market,
+        regime: Option<&RegimeDetection>,
+        sentiment: Option<&SentimentScore>,
+        ob: Option<&OrderBookAnalysis>,
+    ) -> SignalScore {
+        // +1 when the signal profits from YES rising, -1 when from it falling
+        let buys_yes = market
+            .outcomes
+            .iter()
+            .find(|o| o.token_id == signal.token_id)
+            .is_none_or(|o| o.outcome.eq_ignore_ascii_case("yes"));
+        let direction = if buys_yes == (signal.side == Side::Buy) { 1.0 } else { -1.0 };
+
+        let mut components = HashMap::new();
+        components.insert("edge".to_string(), edge_score(signal));
+        components.insert("liquidity_score".to_string(), liquidity_score(market, ob));
+        components.insert("confidence".to_string(), to_f64(signal.confidence).clamp(0.0, 1.0));
+        components.insert("sentiment".to_string(), sentiment_score(sentiment, direction));
+        components.insert("regime_alignment".to_string(), regime_score(regime, direction));
+        components.insert("source_quality".to_string(), source_quality(signal));
+
+        let total = SCORE_COMPONENTS
+            .iter()
+            .map(|&name| components[name] * self.weights.get(name))
+            .sum::<f64>()
+            .clamp(0.0, 1.0);
+        let score = SignalScore { total, components, grade: Grade::from_score(total) };
+
+        tracing::trace!(
+            trace_id = %signal.trace_id,
+            market_id = %signal.market_id,
+            "Signal score {:.3} ({}): {}",
+            score.total,
+            score.grade,
+            score
+                .breakdown()
+                .iter()
+                .map(|(name, value)| format!("{}={:.2}", name, value))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        score
+    }
+}
+
+fn to_f64(value: rust_decimal::Decimal) -> f64 {
+    f64::try_from(value).unwrap_or(0.0)
+}
+
+fn edge_score(signal: &Signal) -> f64 {
+    (to_f64(signal.edge).abs() / FULL_EDGE).min(1.0)
+}
+
+/// Depth from the market's liquidity (square root, so thin markets aren't
+/// zeroed), cut by a wide spread and by toxic (high VPIN) flow
+fn liquidity_score(market: &Market, ob: Option<&OrderBookAnalysis>) -> f64 {
+    let mut score = (to_f64(market.liquidity).max(0.0) / FULL_LIQUIDITY).sqrt().min(1.0);
+    if let Some(ob) = ob {
+        if let Some(imbalance) = &ob.imbalance {
+            score *= 1.0 - (to_f64(imbalance.spread_bps).max(0.0) / MAX_SPREAD_BPS).min(1.0);
+        }
+        if let Some(vpin) = &ob.vpin {
+            score *= 1.0 - to_f64(vpin.vpin).clamp(0.0, 1.0) / 2.0;
+        }
+    }
+    score
+}
+
+fn sentiment_score(sentiment: Option<&SentimentScore>, direction: f64) -> f64 {
+    match sentiment {
+        Some(s) => (0.5 + 0.5 * to_f64(s.score) * direction).clamp(0.0, 1.0),
+        None => 0.5,
+    }
+}
+
+/// Trends favour signals in their direction; volatile and crisis regimes
+/// count against every signal
+fn regime_score(regime: Option<&RegimeDetection>, direction: f64) -> f64 {
+    let Some(detection) = regime else {
+        return 0.5;
+    };
+    let confidence = to_f64(detection.confidence).clamp(0.0, 1.0);
+    let score = match detection.regime {
+        MarketRegime::BullishTrend => 0.5 + 0.5 * confidence * direction,
+        MarketRegime::BearishTrend => 0.5 - 0.5 * confidence * direction,
+        MarketRegime::Volatile => 0.5 - 0.2 * confidence,
+        MarketRegime::Crisis => 0.5 - 0.4 * confidence,
+        MarketRegime::Ranging | MarketRegime::Unknown => 0.5,
+    };
+    score.clamp(0.0, 1.0)
+}
+
+/// How much the ensemble's models agree
+fn source_quality(signal: &Signal) -> f64 {
+    1.0 - (to_f64(signal.epistemic_uncertainty).max(0.0) / MAX_EPISTEMIC_UNCERTAINTY).min(1.0)
+}
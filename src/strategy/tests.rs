@@ -18,6 +18,18 @@ mod tests {
             model_update_interval_secs: 3600,
             compound_enabled: false,
             compound_sqrt_scaling: false,
+            modes: std::collections::HashMap::new(),
+            high_epistemic_threshold: dec!(0.15),
+            accounts: std::collections::HashMap::new(),
+            experiment: None,
+            experiments: Default::default(),
+            bayesian_prior_strength: None,
+            regime_map: Default::default(),
+            regime_confidence_threshold: dec!(0.60),
+            max_concurrent_analyses: 4,
+            analysis_timeout_secs: 60,
+            seasonality: Default::default(),
+            scoring: Default::default(),
         };
         
         let risk = RiskConfig {
@@ -26,6 +38,18 @@ mod tests {
             max_daily_loss_pct: dec!(0.1),
             min_balance_reserve: dec!(100),
             max_open_positions: 10,
+            signal_dedup_ttl_secs: 900,
+            max_total_kelly: Decimal::ONE,
+            max_consecutive_api_failures: 5,
+            api_failure_pause_secs: 300,
+            max_positions_per_strategy: std::collections::HashMap::new(),
+            queued_signal_ttl_secs: 1800,
+            max_queued_signals: 20,
+            loss_cooldown_mins: 30,
+            loss_cooldown_override_edge: None,
+            regime_rotation_min_edge_gain: None,
+            max_heat_budget: Decimal::ZERO,
+            regime_consensus_sizing: Default::default(),
         };
         
         (strategy, risk)
@@ -53,6 +77,10 @@ mod tests {
                     price: Decimal::ONE - yes_price,
                 },
             ],
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -67,6 +95,7 @@ mod tests {
             probability: dec!(0.55),
             confidence: dec!(0.70),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = signal_gen.generate(&market, &prediction);
@@ -77,6 +106,167 @@ mod tests {
         assert!(s.edge > dec!(0.10), "Edge should be > 10%");
     }
 
+    #[test]
+    fn test_high_model_disagreement_halves_size() {
+        use crate::model::UncertainPrediction;
+
+        let (strategy_config, risk_config) = make_test_config();
+        let signal_gen = SignalGenerator::new(strategy_config, risk_config);
+        let market = make_test_market(dec!(0.40));
+        let prediction = |epistemic| UncertainPrediction {
+            mean: dec!(0.55),
+            std_dev: epistemic,
+            epistemic,
+            aleatoric: dec!(0),
+            credible_interval_95: (dec!(0.4), dec!(0.7)),
+            confidence: dec!(0.70),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+
+        let calm = signal_gen.generate_with_uncertainty(&market, &prediction(dec!(0.05))).unwrap();
+        assert_eq!(calm.epistemic_uncertainty, dec!(0.05));
+        assert!(!signal_gen.is_high_uncertainty(&calm));
+        assert_eq!(signal_gen.high_uncertainty_signals_total(), 0);
+
+        let split = signal_gen.generate_with_uncertainty(&market, &prediction(dec!(0.20))).unwrap();
+        assert!(signal_gen.is_high_uncertainty(&split));
+        assert_eq!(split.suggested_size, calm.suggested_size * dec!(0.5));
+        assert_eq!(signal_gen.high_uncertainty_signals_total(), 1);
+    }
+
+    #[test]
+    fn test_bayesian_posterior_drives_probability_and_size() {
+        use crate::ml::BayesianUpdater;
+        use rust_decimal::MathematicalOps;
+
+        let (strategy_config, risk_config) = make_test_config();
+        let plain = SignalGenerator::new(strategy_config.clone(), risk_config.clone());
+        let bayesian = SignalGenerator::new(strategy_config, risk_config).with_bayesian(BayesianUpdater::new(dec!(20)));
+        let market = make_test_market(dec!(0.40));
+        let prediction = Prediction {
+            probability: dec!(0.80),
+            confidence: dec!(0.90),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+
+        let raw = plain.generate(&market, &prediction).unwrap();
+        assert_eq!(raw.model_probability, dec!(0.80));
+        assert_eq!(raw.posterior_variance, Decimal::ZERO);
+
+        // Uniform prior + price 0.40 (weight 1) + model 0.80 (weight 18):
+        // Beta(15.8, 5.2)
+        let first = bayesian.generate(&market, &prediction).unwrap();
+        assert_eq!(first.model_probability, dec!(15.8) / dec!(21));
+        assert_eq!(first.edge, first.model_probability - dec!(0.40));
+        assert!(first.posterior_variance > Decimal::ZERO);
+        let scale = Decimal::ONE - Decimal::TWO * first.posterior_variance.sqrt().unwrap();
+        assert_eq!(first.suggested_size, raw.suggested_size * scale);
+        assert!(first.suggested_size < raw.suggested_size);
+
+        // Every scan narrows the posterior and sizes back up
+        let second = bayesian.generate(&market, &prediction).unwrap();
+        assert!(second.posterior_variance < first.posterior_variance);
+        assert!(second.suggested_size > first.suggested_size);
+    }
+
+    #[test]
+    fn test_seasonality_sizes_for_constant_risk() {
+        use crate::analysis::seasonality::SeasonalityModel;
+
+        let (strategy_config, risk_config) = make_test_config();
+        let plain = SignalGenerator::new(strategy_config.clone(), risk_config.clone());
+        let market = make_test_market(dec!(0.40));
+        let prediction = Prediction {
+            probability: dec!(0.60),
+            confidence: dec!(0.80),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+        let raw = plain.generate(&market, &prediction).unwrap();
+
+        // Twice the average volatility at every hour: half the size
+        let busy = SeasonalityModel { hourly_vol_multipliers: [dec!(2); 24], ..SeasonalityModel::default() };
+        let seasonal = SignalGenerator::new(strategy_config.clone(), risk_config.clone()).with_seasonality(busy);
+        assert_eq!(seasonal.generate(&market, &prediction).unwrap().suggested_size, raw.suggested_size * dec!(0.5));
+
+        let global = SeasonalityModel::global();
+        let seasonal = SignalGenerator::new(strategy_config, risk_config).with_seasonality(global.clone());
+        let signal = seasonal.generate(&market, &prediction).unwrap();
+        assert_eq!(signal.suggested_size, raw.suggested_size * global.size_factor(signal.timestamp));
+    }
+
+    #[test]
+    fn test_edge_must_clear_fees() {
+        use crate::config::FeeSchedule;
+        use crate::fees::PolymarketFees;
+        use std::sync::Arc;
+
+        // 6% edge at 50c against a 3% taker fee
+        let market = make_test_market(dec!(0.50));
+        let prediction = Prediction {
+            probability: dec!(0.56),
+            confidence: dec!(0.70),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+        let (strategy_config, risk_config) = make_test_config();
+        let free = SignalGenerator::new(strategy_config.clone(), risk_config.clone());
+        assert!(free.generate(&market, &prediction).is_some());
+
+        let fees = Arc::new(PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: dec!(600),
+            maker_fee_bps: Decimal::ZERO,
+        }));
+        let charged = SignalGenerator::new(strategy_config, risk_config).with_fees(fees);
+        assert!(charged.generate(&market, &prediction).is_none());
+
+        // Near the extremes the same rate costs far less: 7% edge at 88c pays 0.72c
+        let market = make_test_market(dec!(0.88));
+        let prediction = Prediction {
+            probability: dec!(0.95),
+            confidence: dec!(0.70),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+        assert!(charged.generate(&market, &prediction).is_some());
+    }
+
+    #[test]
+    fn test_marginal_edge_below_round_trip_fees_is_rejected() {
+        use crate::config::FeeSchedule;
+        use crate::fees::PolymarketFees;
+        use std::sync::Arc;
+
+        // 7% gross edge at 50c; at 400 bps the entry costs 2c and the exit
+        // at 57c another 1.72c, leaving 3.28c against a 5% minimum
+        let market = make_test_market(dec!(0.50));
+        let prediction = Prediction {
+            probability: dec!(0.57),
+            confidence: dec!(0.70),
+            reasoning: "Test".to_string(),
+            fallback_used: None,
+        };
+        let (strategy_config, risk_config) = make_test_config();
+        let fees = Arc::new(PolymarketFees::new(FeeSchedule {
+            taker_fee_bps: dec!(400),
+            maker_fee_bps: Decimal::ZERO,
+        }));
+        let charged = SignalGenerator::new(strategy_config.clone(), risk_config.clone()).with_fees(fees.clone());
+        assert!(charged.generate(&market, &prediction).is_none());
+
+        // A wider edge clears the hurdle, sized on what's left of it
+        let prediction = Prediction { probability: dec!(0.65), ..prediction };
+        let free = SignalGenerator::new(strategy_config, risk_config);
+        let gross = free.generate(&market, &prediction).unwrap();
+        let net = charged.generate(&market, &prediction).unwrap();
+        assert_eq!(gross.edge, dec!(0.15));
+        // 2c to enter and 1.4c to exit at 65c
+        assert_eq!(net.edge, dec!(0.116));
+        assert!(net.suggested_size < gross.suggested_size);
+    }
+
     #[test]
     fn test_no_signal_without_edge() {
         let (strategy_config, risk_config) = make_test_config();
@@ -88,6 +278,7 @@ mod tests {
             probability: dec!(0.52),
             confidence: dec!(0.70),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = signal_gen.generate(&market, &prediction);
@@ -105,6 +296,7 @@ mod tests {
             probability: dec!(0.60),
             confidence: dec!(0.50), // Below threshold
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = signal_gen.generate(&market, &prediction);
@@ -122,6 +314,7 @@ mod tests {
             probability: dec!(0.55),
             confidence: dec!(0.75),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = signal_gen.generate(&market, &prediction);
@@ -142,6 +335,7 @@ mod tests {
             probability: dec!(0.60),
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = signal_gen.generate(&market, &prediction).unwrap();
@@ -166,11 +360,16 @@ mod tests {
             outcomes: vec![], // No outcomes
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         let prediction = Prediction {
             probability: dec!(0.70),
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -188,6 +387,7 @@ mod tests {
             probability: dec!(0.55), // Exactly 5% edge
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -204,6 +404,7 @@ mod tests {
             probability: dec!(0.80), // 60% edge!
             confidence: dec!(0.90),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -224,6 +425,7 @@ mod tests {
             probability: dec!(0.20), // -60% edge
             confidence: dec!(0.90),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -245,6 +447,7 @@ mod tests {
             probability: dec!(0.55),
             confidence: dec!(0.75),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction).unwrap();
@@ -261,6 +464,7 @@ mod tests {
             probability: dec!(0.55),
             confidence: dec!(0.75),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction).unwrap();
@@ -277,6 +481,7 @@ mod tests {
             probability: dec!(0.60),
             confidence: dec!(0.85),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction).unwrap();
@@ -295,6 +500,7 @@ mod tests {
             probability: dec!(0.50), // Zero edge
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -311,6 +517,7 @@ mod tests {
             probability: dec!(0.20),
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
@@ -328,10 +535,89 @@ mod tests {
             probability: dec!(0.80),
             confidence: dec!(0.80),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
 
         let signal = signal_gen.generate(&market, &prediction);
         assert!(signal.is_some());
         assert_eq!(signal.unwrap().side, Side::Sell);
     }
+
+    #[test]
+    fn test_grade_boundaries() {
+        use crate::strategy::Grade;
+
+        let grades: Vec<_> = [0.85, 0.8, 0.75, 0.65, 0.55, 0.3].iter().map(|&s| Grade::from_score(s)).collect();
+        assert_eq!(grades, vec![Grade::A, Grade::A, Grade::B, Grade::C, Grade::D, Grade::F]);
+        assert!(Grade::A.at_least(Grade::B));
+        assert!(Grade::B.at_least(Grade::B));
+        assert!(!Grade::C.at_least(Grade::B));
+        assert!(Grade::F.at_least(Grade::F));
+    }
+
+    #[test]
+    fn test_signal_scorer_weighs_regime_sentiment_and_liquidity() {
+        use crate::analysis::sentiment::SentimentScore;
+        use crate::regime::{MarketRegime, RegimeDetection};
+        use crate::strategy::{Grade, ScorerWeights, SignalScorer};
+        use chrono::Utc;
+
+        let (strategy_config, risk_config) = make_test_config();
+        let signal_gen = SignalGenerator::new(strategy_config, risk_config);
+        let market = make_test_market(dec!(0.40));
+        let prediction = Prediction { probability: dec!(0.55), confidence: dec!(0.70), reasoning: "Test".to_string(), fallback_used: None };
+        let signal = signal_gen.generate(&market, &prediction).unwrap();
+
+        // Weights are scaled to sum to 1
+        let doubled = ScorerWeights { edge: 0.6, liquidity_score: 0.3, confidence: 0.4, sentiment: 0.2, regime_alignment: 0.2, source_quality: 0.3 };
+        let scorer = SignalScorer::new(doubled);
+        assert!((scorer.weights.edge - 0.3).abs() < 1e-9);
+        assert!((scorer.weights.sum() - 1.0).abs() < 1e-9);
+
+        // Full edge, deep market, agreeing models; neutral without regime or sentiment
+        let score = scorer.score(&signal, &market, None, None, None);
+        assert_eq!(score.components["edge"], 1.0);
+        assert_eq!(score.components["liquidity_score"], 1.0);
+        assert!((score.components["confidence"] - 0.7).abs() < 1e-9);
+        assert_eq!(score.components["sentiment"], 0.5);
+        assert_eq!(score.components["regime_alignment"], 0.5);
+        assert_eq!(score.components["source_quality"], 1.0);
+        assert!((score.total - 0.84).abs() < 1e-9);
+        assert_eq!(score.grade, Grade::A);
+        let names: Vec<_> = score.breakdown().iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["edge", "liquidity_score", "confidence", "sentiment", "regime_alignment", "source_quality"]);
+
+        // A confident bearish regime and crowd work against buying YES
+        let bearish = RegimeDetection {
+            regime: MarketRegime::BearishTrend,
+            confidence: dec!(1),
+            adx: dec!(30),
+            plus_di: dec!(10),
+            minus_di: dec!(30),
+            atr: dec!(1),
+            atr_percentile: dec!(50),
+            hurst: None,
+            volatility_ratio: dec!(1),
+            trend_strength: dec!(60),
+            timestamp: Utc::now(),
+            strategy: MarketRegime::BearishTrend.strategy_recommendation(),
+        };
+        let gloomy = SentimentScore { score: dec!(-1), sources: 12, oldest: Utc::now() };
+        let score = scorer.score(&signal, &market, Some(&bearish), Some(&gloomy), None);
+        assert_eq!(score.components["regime_alignment"], 0.0);
+        assert_eq!(score.components["sentiment"], 0.0);
+        assert!((score.total - 0.74).abs() < 1e-9);
+        assert_eq!(score.grade, Grade::B);
+
+        // ... and favour selling it; a thin market drags the score down
+        let thin = Market { liquidity: dec!(500), ..market.clone() };
+        let sell = Signal { side: Side::Sell, ..signal.clone() };
+        let score = scorer.score(&sell, &thin, Some(&bearish), Some(&gloomy), None);
+        assert_eq!(score.components["regime_alignment"], 1.0);
+        assert_eq!(score.components["sentiment"], 1.0);
+        assert!((score.components["liquidity_score"] - 0.1).abs() < 1e-9);
+        assert_eq!(score.grade, Grade::A);
+        let score = scorer.score(&signal, &thin, Some(&bearish), Some(&gloomy), None);
+        assert_eq!(score.grade, Grade::C);
+    }
 }
@@ -4,6 +4,7 @@
 
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 /// 趋势方向
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -28,7 +29,7 @@ pub struct TrendSignal {
 }
 
 /// 价格数据点（带成交量）
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceBar {
     pub open: Decimal,
     pub high: Decimal,
@@ -1,19 +1,57 @@
 //! Telegram bot for receiving commands
 //!
-//! Supports commands like /status, /markets, /pause, /resume, /buy, /sell
+//! Supports commands like /status, /markets, /book, /analyze, /grade, /pause, /resume, /buy, /sell, /rebalance, /note, /alert, /sources
+//!
+//! Updates arrive by long-polling `getUpdates`, or in webhook mode are
+//! pushed by Telegram to a local HTTP endpoint checked against a secret token.
 
 #[cfg(test)]
 mod tests;
 
-use crate::client::PolymarketClient;
-use crate::config::Config;
-use crate::error::Result;
-use crate::storage::Database;
+use crate::analysis::dossier;
+use crate::accounts::Accounts;
+use crate::client::{OrderBook, PolymarketClient};
+use crate::config::{Config, StrategyMode, TelegramBotMode, DEFAULT_ACCOUNT_ID};
+use crate::error::{BotError, Result};
+use crate::executor::Executor;
+use crate::fees::PolymarketFees;
+use crate::ingester::{AuthorTrust, IngestStats, SignalMapper};
+use crate::monitor::{HealthChecker, MarketAlertRule};
+use crate::orderbook::{OrderBookAnalysis, OrderBookAnalyzer, OrderBookSnapshot};
+use crate::portfolio::rebalance::{preview_message, PortfolioRebalancer};
+use crate::portfolio::CapitalLadder;
+use crate::scanner::PinnedMarkets;
+use crate::storage::{AccountPnl, Database, JournalEntry, JournalKind};
+use crate::strategy::{SignalScore, StrategyModes};
+use crate::utils::ascii_chart::progress_bar;
+use crate::utils::{http_client, HTTP_TIMEOUT};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+/// Callback data for the rebalance confirmation keyboard
+const REBALANCE_CONFIRM: &str = "rebalance:confirm";
+const REBALANCE_CANCEL: &str = "rebalance:cancel";
+
+/// Header Telegram sends the webhook secret token in
+const SECRET_TOKEN_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+/// Whether `token` is the webhook secret, compared in constant time so the
+/// response timing gives away neither the secret nor its length
+fn secret_token_matches(token: &[u8], secret: &str) -> bool {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mac = |message: &[u8]| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(message);
+        mac
+    };
+    mac(token).verify_slice(&mac(secret.as_bytes()).finalize().into_bytes()).is_ok()
+}
 
 /// Telegram bot for receiving commands
 pub struct TelegramBot {
@@ -22,6 +60,16 @@ pub struct TelegramBot {
     chat_id: String,
     last_update_id: RwLock<i64>,
     command_tx: mpsc::Sender<BotCommand>,
+    mode: TelegramBotMode,
+    webhook_requests: WebhookCounters,
+}
+
+/// Webhook requests by outcome
+#[derive(Debug, Default)]
+struct WebhookCounters {
+    ok: AtomicU64,
+    invalid_token: AtomicU64,
+    parse_error: AtomicU64,
 }
 
 /// Commands that can be sent to the trading bot
@@ -31,8 +79,10 @@ pub enum BotCommand {
     Pause,
     /// Resume trading
     Resume,
-    /// Get current status
+    /// Get current status, summed over accounts
     Status,
+    /// Status of one account
+    AccountStatus { account: String },
     /// List top markets
     Markets { limit: usize },
     /// Manual buy order
@@ -41,10 +91,56 @@ pub enum BotCommand {
     Sell { market_id: String, amount: Decimal },
     /// Get today's PnL
     Pnl,
+    /// Realized PnL of one account
+    AccountPnl { account: String },
     /// Get open positions
     Positions,
     /// Set risk parameter
     SetRisk { param: String, value: Decimal },
+    /// Locked capital by expected resolution date
+    Ladder,
+    /// Signals waiting for a free position slot
+    Queue,
+    /// Show proposed rebalance trades
+    RebalancePreview,
+    /// Execute rebalance trades (after keyboard confirmation)
+    RebalanceConfirm,
+    /// Add a journal note, optionally about one market
+    Note { market_id: Option<String>, text: String },
+    /// List signal authors with learned trust
+    Authors,
+    /// Per-source signal ingestion counts
+    Sources,
+    /// Compare shadow strategies with live ones
+    Shadow,
+    /// Switch a strategy between live, shadow and off
+    SetStrategyMode { strategy: String, mode: StrategyMode },
+    /// Order book analysis of a market's Yes token
+    Book { market_id: String },
+    /// Dossier of a market: book, history, our exposure, comparables, edge
+    Analyze { market_id: String },
+    /// Score breakdown of the latest signal on a market
+    Grade { market_id: String },
+    /// Pipeline trace behind a trade
+    Trace { trade_id: String },
+    /// Microstructure profile of a market
+    Profile { market_id: String },
+    /// Pinned markets, scanned whatever the scan filters say
+    Watchlist,
+    /// Pin a market to the scan
+    Watch { market_id: String },
+    /// Unpin a market
+    Unwatch { market_id: String },
+    /// Map a signal token to a market
+    AddMapping { token: String, market_id: String },
+    /// Add a per-market alert rule
+    AddAlert { rule: MarketAlertRule },
+    /// List per-market alert rules
+    ListAlerts,
+    /// Delete a per-market alert rule
+    DeleteAlert { id: i64 },
+    /// Probe every dependency
+    Health,
     /// Help
     Help,
 }
@@ -55,6 +151,19 @@ pub struct BotState {
     pub paused: bool,
     pub daily_pnl: Decimal,
     pub daily_loss_limit_hit: bool,
+    /// Set once a shutdown signal has been received
+    pub shutdown_requested: bool,
+    /// Portfolio heat as a fraction of the heat budget, once measured with
+    /// a budget set
+    pub heat_utilization: Option<Decimal>,
+    /// Latest signal score per market, for /grade
+    pub signal_scores: std::collections::HashMap<String, SignalScore>,
+    /// Size ramp level of each strategy and market category that has
+    /// traded, with a ramp configured
+    pub ramp_levels: Vec<(String, Decimal)>,
+    /// Resolved winnings not yet redeemed into USDC; not part of the
+    /// balance
+    pub pending_redemption: Decimal,
 }
 
 impl Default for BotState {
@@ -63,6 +172,11 @@ impl Default for BotState {
             paused: false,
             daily_pnl: Decimal::ZERO,
             daily_loss_limit_hit: false,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         }
     }
 }
@@ -71,6 +185,14 @@ impl Default for BotState {
 struct TelegramUpdate {
     update_id: i64,
     message: Option<TelegramMessage>,
+    callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    id: String,
+    data: Option<String>,
+    message: Option<TelegramMessage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -106,16 +228,68 @@ struct SendMessageRequest {
     chat_id: String,
     text: String,
     parse_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+#[derive(Debug, Serialize)]
+struct InlineKeyboardMarkup {
+    inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+#[derive(Debug, Serialize)]
+struct InlineKeyboardButton {
+    text: String,
+    callback_data: String,
+}
+
+impl InlineKeyboardMarkup {
+    fn rebalance_confirm() -> Self {
+        Self {
+            inline_keyboard: vec![vec![
+                InlineKeyboardButton {
+                    text: "✅ Confirm".to_string(),
+                    callback_data: REBALANCE_CONFIRM.to_string(),
+                },
+                InlineKeyboardButton {
+                    text: "❌ Cancel".to_string(),
+                    callback_data: REBALANCE_CANCEL.to_string(),
+                },
+            ]],
+        }
+    }
 }
 
 impl TelegramBot {
     pub fn new(bot_token: String, chat_id: String, command_tx: mpsc::Sender<BotCommand>) -> Self {
         Self {
-            http: Client::new(),
+            http: http_client(HTTP_TIMEOUT),
             bot_token,
             chat_id,
             last_update_id: RwLock::new(0),
             command_tx,
+            mode: TelegramBotMode::default(),
+            webhook_requests: WebhookCounters::default(),
+        }
+    }
+
+    /// Receive updates in `mode` (long-polling by default)
+    pub fn with_mode(mut self, mode: TelegramBotMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Listen for commands in the configured mode; returns once the
+    /// listener is set up, which for polling is never
+    pub async fn start(self: Arc<Self>) -> Result<()> {
+        match self.mode.clone() {
+            TelegramBotMode::Polling { .. } => {
+                self.start_polling().await;
+                Ok(())
+            }
+            TelegramBotMode::Webhook { public_url, listen_port, secret_token } => {
+                self.start_webhook(&public_url, listen_port, &secret_token).await
+            }
         }
     }
 
@@ -127,18 +301,12 @@ impl TelegramBot {
             match self.poll_updates().await {
                 Ok(updates) => {
                     for update in updates {
-                        if let Some(msg) = update.message {
-                            // Only process messages from authorized chat
-                            if msg.chat.id.to_string() == self.chat_id {
-                                if let Some(text) = msg.text {
-                                    self.handle_message(&text).await;
-                                }
-                            }
-                        }
-                        
+                        let update_id = update.update_id;
+                        self.dispatch(update).await;
+
                         // Update offset
                         let mut last_id = self.last_update_id.write().await;
-                        *last_id = update.update_id + 1;
+                        *last_id = update_id + 1;
                     }
                 }
                 Err(e) => {
@@ -151,16 +319,136 @@ impl TelegramBot {
         }
     }
 
+    /// Register `{public_url}/webhook` with Telegram and serve it on
+    /// `listen_port` in the background
+    pub async fn start_webhook(self: Arc<Self>, public_url: &str, listen_port: u16, secret_token: &str) -> Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/setWebhook", self.bot_token);
+        let body = serde_json::json!({
+            "url": format!("{}/webhook", public_url.trim_end_matches('/')),
+            "secret_token": secret_token,
+            "drop_pending_updates": true,
+            "allowed_updates": ["message", "callback_query"],
+        });
+        let response: serde_json::Value = self.http.post(&url).json(&body).send().await?.json().await?;
+        if response["ok"] != true {
+            return Err(BotError::Api(format!("setWebhook failed: {}", response["description"])));
+        }
+
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], listen_port));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| BotError::Internal(format!("Failed to bind webhook port {}: {}", listen_port, e)))?;
+        let app = self.webhook_router(secret_token.to_string());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("Telegram webhook server stopped: {}", e);
+            }
+        });
+        tracing::info!("Telegram webhook listening on {} for {}/webhook", addr, public_url);
+        Ok(())
+    }
+
+    /// `POST /webhook`: updates carrying `secret_token` are dispatched
+    fn webhook_router(self: Arc<Self>, secret_token: String) -> axum::Router {
+        use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, routing::post};
+
+        let handler = move |State(bot): State<Arc<Self>>, headers: HeaderMap, body: Bytes| {
+            let secret_token = secret_token.clone();
+            async move {
+                let authorized = headers
+                    .get(SECRET_TOKEN_HEADER)
+                    .is_some_and(|token| secret_token_matches(token.as_bytes(), &secret_token));
+                if !authorized {
+                    bot.webhook_requests.invalid_token.fetch_add(1, Ordering::Relaxed);
+                    return StatusCode::FORBIDDEN;
+                }
+                match serde_json::from_slice::<TelegramUpdate>(&body) {
+                    Ok(update) => {
+                        bot.webhook_requests.ok.fetch_add(1, Ordering::Relaxed);
+                        // Answer right away; replies go out on their own
+                        tokio::spawn(async move { bot.dispatch(update).await });
+                        StatusCode::OK
+                    }
+                    Err(e) => {
+                        // Acknowledged anyway, or Telegram would keep redelivering it
+                        bot.webhook_requests.parse_error.fetch_add(1, Ordering::Relaxed);
+                        tracing::warn!("Unreadable Telegram webhook update: {}", e);
+                        StatusCode::OK
+                    }
+                }
+            }
+        };
+        axum::Router::new().route("/webhook", post(handler)).with_state(self)
+    }
+
+    /// Remove the webhook on shutdown, so updates queue up for the next start
+    pub async fn shutdown(&self) {
+        if !matches!(self.mode, TelegramBotMode::Webhook { .. }) {
+            return;
+        }
+        let url = format!("https://api.telegram.org/bot{}/deleteWebhook", self.bot_token);
+        if let Err(e) = self.http.post(&url).send().await {
+            tracing::warn!("Failed to delete Telegram webhook: {}", e);
+        }
+    }
+
+    /// Webhook request counts in Prometheus text format
+    pub fn prometheus_counters(&self) -> String {
+        let mut text = "# HELP polymarket_bot_webhook_requests_total Telegram webhook requests by outcome\n\
+                        # TYPE polymarket_bot_webhook_requests_total counter\n"
+            .to_string();
+        for (result, count) in [
+            ("ok", &self.webhook_requests.ok),
+            ("invalid_token", &self.webhook_requests.invalid_token),
+            ("parse_error", &self.webhook_requests.parse_error),
+        ] {
+            text += &format!(
+                "polymarket_bot_webhook_requests_total{{result=\"{}\"}} {}\n",
+                result,
+                count.load(Ordering::Relaxed)
+            );
+        }
+        text
+    }
+
+    /// Handle an update from the authorized chat; others are ignored
+    async fn dispatch(&self, update: TelegramUpdate) {
+        if let Some(msg) = update.message {
+            // Only process messages from authorized chat
+            if msg.chat.id.to_string() == self.chat_id {
+                if let Some(text) = msg.text {
+                    self.handle_message(&text).await;
+                }
+            }
+        }
+
+        if let Some(query) = update.callback_query {
+            let authorized = query
+                .message
+                .as_ref()
+                .is_some_and(|m| m.chat.id.to_string() == self.chat_id);
+            if authorized {
+                self.handle_callback(&query).await;
+            }
+        }
+    }
+
     async fn poll_updates(&self) -> Result<Vec<TelegramUpdate>> {
         let last_id = *self.last_update_id.read().await;
-        
+        let timeout_secs = match self.mode {
+            TelegramBotMode::Polling { timeout_secs } => timeout_secs,
+            TelegramBotMode::Webhook { .. } => 0,
+        };
+
         let url = format!(
-            "https://api.telegram.org/bot{}/getUpdates?offset={}&timeout=30",
-            self.bot_token, last_id
+            "https://api.telegram.org/bot{}/getUpdates?offset={}&timeout={}",
+            self.bot_token, last_id, timeout_secs
         );
 
+        // Telegram holds a long poll open for up to timeout_secs
         let response: GetUpdatesResponse = self.http
             .get(&url)
+            .timeout(std::time::Duration::from_secs(timeout_secs) + HTTP_TIMEOUT)
             .send()
             .await?
             .json()
@@ -188,9 +476,13 @@ impl TelegramBot {
             "start" | "help" => {
                 self.send_help().await;
             }
-            "status" => {
+            "status" if args.is_empty() => {
                 let _ = self.command_tx.send(BotCommand::Status).await;
             }
+            "status" => {
+                let account = args.split_whitespace().next().unwrap_or_default().to_string();
+                let _ = self.command_tx.send(BotCommand::AccountStatus { account }).await;
+            }
             "markets" => {
                 let limit = args.parse().unwrap_or(5);
                 let _ = self.command_tx.send(BotCommand::Markets { limit }).await;
@@ -203,9 +495,13 @@ impl TelegramBot {
                 let _ = self.command_tx.send(BotCommand::Resume).await;
                 self.reply("▶️ Trading resumed").await;
             }
-            "pnl" => {
+            "pnl" if args.is_empty() => {
                 let _ = self.command_tx.send(BotCommand::Pnl).await;
             }
+            "pnl" => {
+                let account = args.split_whitespace().next().unwrap_or_default().to_string();
+                let _ = self.command_tx.send(BotCommand::AccountPnl { account }).await;
+            }
             "positions" | "pos" => {
                 let _ = self.command_tx.send(BotCommand::Positions).await;
             }
@@ -230,12 +526,146 @@ impl TelegramBot {
                     self.reply("❌ Usage: /setrisk <param> <value>\nParams: max_position, max_daily_loss, kelly_fraction").await;
                 }
             }
+            "note" => {
+                if let Some((market_id, text)) = parse_note_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Note { market_id, text }).await;
+                    self.reply("📝 Noted").await;
+                } else {
+                    self.reply("❌ Usage: /note [market_id] <text>").await;
+                }
+            }
+            "authors" => {
+                let _ = self.command_tx.send(BotCommand::Authors).await;
+            }
+            "sources" => {
+                let _ = self.command_tx.send(BotCommand::Sources).await;
+            }
+            "health" => {
+                let _ = self.command_tx.send(BotCommand::Health).await;
+            }
+            "ladder" => {
+                let _ = self.command_tx.send(BotCommand::Ladder).await;
+            }
+            "queue" => {
+                let _ = self.command_tx.send(BotCommand::Queue).await;
+            }
+            "shadow" if args.is_empty() => {
+                let _ = self.command_tx.send(BotCommand::Shadow).await;
+            }
+            "shadow" | "promote" | "disable" => {
+                let strategy = args.split_whitespace().next().unwrap_or_default().to_string();
+                let mode = match cmd {
+                    "shadow" => StrategyMode::Shadow,
+                    "promote" => StrategyMode::Live,
+                    _ => StrategyMode::Off,
+                };
+                if strategy.is_empty() {
+                    self.reply(&format!("❌ Usage: /{} &lt;strategy&gt;", cmd)).await;
+                } else {
+                    let _ = self.command_tx.send(BotCommand::SetStrategyMode { strategy, mode }).await;
+                }
+            }
+            "book" => {
+                if let Some(market_id) = parse_book_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Book { market_id }).await;
+                } else {
+                    self.reply("❌ Usage: /book &lt;market_id&gt;").await;
+                }
+            }
+            "analyze" => {
+                if let Some(market_id) = parse_book_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Analyze { market_id }).await;
+                } else {
+                    self.reply("❌ Usage: /analyze &lt;market_id&gt;").await;
+                }
+            }
+            "grade" => {
+                if let Some(market_id) = parse_book_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Grade { market_id }).await;
+                } else {
+                    self.reply("❌ Usage: /grade &lt;market_id&gt;").await;
+                }
+            }
+            "watch" if args.trim().is_empty() => {
+                let _ = self.command_tx.send(BotCommand::Watchlist).await;
+            }
+            "watch" | "unwatch" => match (cmd, parse_book_args(args)) {
+                ("watch", Some(market_id)) => {
+                    let _ = self.command_tx.send(BotCommand::Watch { market_id }).await;
+                }
+                (_, Some(market_id)) => {
+                    let _ = self.command_tx.send(BotCommand::Unwatch { market_id }).await;
+                }
+                (_, None) => self.reply("❌ Usage: /unwatch &lt;market_id&gt;").await,
+            },
+            "addmapping" => {
+                let mut parts = args.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some(token), Some(market_id)) => {
+                        let command = BotCommand::AddMapping { token: token.to_string(), market_id: market_id.to_string() };
+                        let _ = self.command_tx.send(command).await;
+                    }
+                    _ => self.reply("❌ Usage: /addmapping &lt;token&gt; &lt;market_id&gt;").await,
+                }
+            }
+            "trace" => {
+                if let Some(trade_id) = parse_book_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Trace { trade_id }).await;
+                } else {
+                    self.reply("❌ Usage: /trace &lt;trade_id&gt;").await;
+                }
+            }
+            "profile" => {
+                if let Some(market_id) = parse_book_args(args) {
+                    let _ = self.command_tx.send(BotCommand::Profile { market_id }).await;
+                } else {
+                    self.reply("❌ Usage: /profile &lt;market_id&gt;").await;
+                }
+            }
+            "alert" => match parse_alert_args(args) {
+                Ok(command) => {
+                    let _ = self.command_tx.send(command).await;
+                }
+                Err(e) => {
+                    self.reply(&format!("❌ {}", crate::notify::escape_html(&e))).await;
+                }
+            },
+            "rebalance" => match args {
+                "" | "preview" => {
+                    let _ = self.command_tx.send(BotCommand::RebalancePreview).await;
+                }
+                "confirm" => {
+                    self.reply_with_keyboard(
+                        "⚖️ Execute the proposed rebalance trades?",
+                        InlineKeyboardMarkup::rebalance_confirm(),
+                    )
+                    .await;
+                }
+                _ => {
+                    self.reply("❌ Usage: /rebalance preview | /rebalance confirm").await;
+                }
+            },
             _ => {
                 self.reply(&format!("❓ Unknown command: /{}\nUse /help for available commands", cmd)).await;
             }
         }
     }
 
+    async fn handle_callback(&self, query: &CallbackQuery) {
+        self.answer_callback(&query.id).await;
+
+        match query.data.as_deref() {
+            Some(REBALANCE_CONFIRM) => {
+                let _ = self.command_tx.send(BotCommand::RebalanceConfirm).await;
+                self.reply("⚖️ Rebalancing...").await;
+            }
+            Some(REBALANCE_CANCEL) => {
+                self.reply("Rebalance cancelled").await;
+            }
+            _ => {}
+        }
+    }
+
     fn parse_trade_args(&self, args: &str) -> Option<(String, Decimal)> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         if parts.len() >= 2 {
@@ -262,10 +692,19 @@ impl TelegramBot {
         let help_text = r#"🤖 <b>Polymarket Bot Commands</b>
 
 <b>Status</b>
-/status - Account balance & bot status
-/pnl - Today's profit/loss
+/status [account] - Balance & bot status, per account when several
+/pnl [account] - Today's profit/loss
 /positions - Open positions
 /markets [n] - Top n markets (default 5)
+/book &lt;market_id&gt; - Order book imbalance, spread and toxicity
+/analyze &lt;market_id&gt; - Market dossier: history, exposure, comparables, edge by size
+/grade &lt;market_id&gt; - Score breakdown and grade of the market's latest signal
+/health - Latency and status of every dependency
+/trace &lt;trade_id&gt; - Signal pipeline behind a trade
+/profile &lt;market_id&gt; - Spread, depth, quote rate and fill latency profile
+/watch - Markets pinned to the scan
+/watch &lt;market_id&gt; - Always scan a market, below the liquidity floor too
+/unwatch &lt;market_id&gt; - Unpin a market
 
 <b>Trading</b>
 /buy &lt;market_id&gt; &lt;amount&gt; - Manual buy
@@ -273,6 +712,33 @@ impl TelegramBot {
 /pause - Pause auto-trading
 /resume - Resume auto-trading
 
+<b>Portfolio</b>
+/ladder - Locked capital by resolution date
+/queue - Signals waiting for a position slot
+/rebalance preview - Show trades to restore target weights
+/rebalance confirm - Execute rebalance (asks for confirmation)
+
+<b>Alerts</b>
+/alert add &lt;market_id&gt; &lt;metric&gt; &lt;op&gt; &lt;value&gt; [cooldown] - e.g. /alert add 517310 price &gt; 0.70 30m
+   metrics: price, spread, volume_delta, pnl, hours_left
+/alert list - Market alerts
+/alert delete &lt;id&gt; - Remove a market alert
+
+<b>Journal</b>
+/note &lt;text&gt; - Add a journal entry
+/note &lt;market_id&gt; &lt;text&gt; - Journal entry about a market
+
+<b>Signals</b>
+/authors - Signal authors with learned trust
+/sources - Messages per signal source and when each last heard anything
+/addmapping &lt;token&gt; &lt;market_id&gt; - Map a signal token to a market
+
+<b>Strategies</b>
+/shadow - Shadow strategies' counterfactual P&amp;L vs live
+/shadow &lt;strategy&gt; - Paper-trade a strategy in shadow
+/promote &lt;strategy&gt; - Trade a strategy live
+/disable &lt;strategy&gt; - Turn a strategy off
+
 <b>Risk</b>
 /setrisk max_position 0.05 - Max 5% per position
 /setrisk max_daily_loss 0.10 - Max 10% daily loss
@@ -293,12 +759,50 @@ impl TelegramBot {
             chat_id: self.chat_id.clone(),
             text: text.to_string(),
             parse_mode: "HTML".to_string(),
+            reply_markup: None,
         };
 
         if let Err(e) = self.http.post(&url).json(&request).send().await {
             tracing::error!("Failed to send Telegram reply: {}", e);
         }
     }
+
+    async fn reply_with_keyboard(&self, text: &str, keyboard: InlineKeyboardMarkup) {
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            self.bot_token
+        );
+
+        let request = SendMessageRequest {
+            chat_id: self.chat_id.clone(),
+            text: text.to_string(),
+            parse_mode: "HTML".to_string(),
+            reply_markup: Some(keyboard),
+        };
+
+        if let Err(e) = self.http.post(&url).json(&request).send().await {
+            tracing::error!("Failed to send Telegram reply: {}", e);
+        }
+    }
+
+    async fn answer_callback(&self, callback_query_id: &str) {
+        let url = format!(
+            "https://api.telegram.org/bot{}/answerCallbackQuery",
+            self.bot_token
+        );
+
+        let body = serde_json::json!({ "callback_query_id": callback_query_id });
+        if let Err(e) = self.http.post(&url).json(&body).send().await {
+            tracing::error!("Failed to answer Telegram callback: {}", e);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::monitor::MetricsSource for TelegramBot {
+    async fn prometheus_text(&self) -> String {
+        self.prometheus_counters()
+    }
 }
 
 /// Command handler that processes commands from Telegram
@@ -306,6 +810,15 @@ pub struct CommandHandler {
     pub state: Arc<RwLock<BotState>>,
     notifier: crate::notify::Notifier,
     config: Config,
+    rebalancer: Option<Mutex<PortfolioRebalancer>>,
+    executor: Option<Arc<Executor>>,
+    author_trust: Option<Arc<AuthorTrust>>,
+    ingest_stats: Option<Arc<IngestStats>>,
+    strategy_modes: Option<Arc<StrategyModes>>,
+    accounts: Option<Arc<Accounts>>,
+    health: Option<Arc<HealthChecker>>,
+    pinned_markets: Option<PinnedMarkets>,
+    signal_mapper: Option<Arc<SignalMapper>>,
 }
 
 impl CommandHandler {
@@ -314,22 +827,86 @@ impl CommandHandler {
             state: Arc::new(RwLock::new(BotState::default())),
             notifier,
             config,
+            rebalancer: None,
+            executor: None,
+            author_trust: None,
+            ingest_stats: None,
+            strategy_modes: None,
+            accounts: None,
+            health: None,
+            pinned_markets: None,
+            signal_mapper: None,
         }
     }
 
+    /// Enable /rebalance, executing through `executor`
+    pub fn with_rebalancer(mut self, rebalancer: PortfolioRebalancer, executor: Arc<Executor>) -> Self {
+        self.rebalancer = Some(Mutex::new(rebalancer));
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Enable /shadow, /promote and /disable
+    pub fn with_strategy_modes(mut self, modes: Arc<StrategyModes>) -> Self {
+        self.strategy_modes = Some(modes);
+        self
+    }
+
+    /// Break /status and /pnl down by account and enable `/status <account>`
+    pub fn with_accounts(mut self, accounts: Arc<Accounts>) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    /// Enable /watch and /unwatch
+    pub fn with_pinned_markets(mut self, pinned: PinnedMarkets) -> Self {
+        self.pinned_markets = Some(pinned);
+        self
+    }
+
+    /// Enable /health
+    pub fn with_health(mut self, checker: Arc<HealthChecker>) -> Self {
+        self.health = Some(checker);
+        self
+    }
+
+    /// Enable /addmapping
+    pub fn with_signal_mapper(mut self, mapper: Arc<SignalMapper>) -> Self {
+        self.signal_mapper = Some(mapper);
+        self
+    }
+
+    /// Enable /authors
+    pub fn with_author_trust(mut self, trust: Arc<AuthorTrust>) -> Self {
+        self.author_trust = Some(trust);
+        self
+    }
+
+    /// Enable /sources
+    pub fn with_ingest_stats(mut self, stats: Arc<IngestStats>) -> Self {
+        self.ingest_stats = Some(stats);
+        self
+    }
+
     pub async fn handle(&self, cmd: BotCommand, client: &PolymarketClient, db: &Database) {
         match cmd {
             BotCommand::Pause => {
-                let mut state = self.state.write().await;
-                state.paused = true;
+                self.state.write().await.paused = true;
+                self.journal(db, JournalEntry::manual(JournalKind::Pause, "Trading paused via /pause")).await;
             }
             BotCommand::Resume => {
-                let mut state = self.state.write().await;
-                state.paused = false;
-                state.daily_loss_limit_hit = false;
+                {
+                    let mut state = self.state.write().await;
+                    state.paused = false;
+                    state.daily_loss_limit_hit = false;
+                }
+                self.journal(db, JournalEntry::manual(JournalKind::Resume, "Trading resumed via /resume")).await;
             }
             BotCommand::Status => {
-                self.send_status(client).await;
+                self.send_status(client, None).await;
+            }
+            BotCommand::AccountStatus { account } => {
+                self.send_status(client, Some(&account)).await;
             }
             BotCommand::Markets { limit } => {
                 self.send_markets(client, limit).await;
@@ -337,6 +914,9 @@ impl CommandHandler {
             BotCommand::Pnl => {
                 self.send_pnl(db).await;
             }
+            BotCommand::AccountPnl { account } => {
+                self.send_account_pnl(db, &account).await;
+            }
             BotCommand::Positions => {
                 self.send_positions(client).await;
             }
@@ -348,32 +928,294 @@ impl CommandHandler {
             }
             BotCommand::SetRisk { param, value } => {
                 self.set_risk_param(&param, value).await;
+                let entry = JournalEntry::manual(JournalKind::RiskChange, format!("{} = {}", param, value));
+                self.journal(db, entry).await;
+            }
+            BotCommand::RebalancePreview => {
+                self.send_rebalance_preview(client).await;
+            }
+            BotCommand::RebalanceConfirm => {
+                self.execute_rebalance(client).await;
+            }
+            BotCommand::Note { market_id, text } => {
+                self.journal(db, JournalEntry::note(market_id, text)).await;
+            }
+            BotCommand::Authors => {
+                let text = match &self.author_trust {
+                    Some(trust) => crate::notify::author_roster(&trust.roster()),
+                    None => "👥 Signal ingestion is not enabled".to_string(),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Sources => {
+                let text = match &self.ingest_stats {
+                    Some(stats) => crate::notify::ingest_sources(&stats.snapshot(), chrono::Utc::now()),
+                    None => "📡 Signal ingestion is not enabled".to_string(),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Ladder => {
+                let text = match db.get_ladder_positions().await {
+                    Ok(positions) => crate::notify::capital_ladder(&CapitalLadder::build(&positions, chrono::Utc::now())),
+                    Err(e) => format!("❌ Failed to load positions: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Queue => {
+                let text = match db.load_position_queue().await {
+                    Ok(mut entries) => {
+                        entries.sort_by_key(|q| std::cmp::Reverse(q.net_edge()));
+                        crate::notify::position_queue(&entries, chrono::Utc::now())
+                    }
+                    Err(e) => format!("❌ Failed to load signal queue: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Health => {
+                let text = match &self.health {
+                    Some(checker) => crate::notify::health_table(&checker.check_all().await),
+                    None => "🩺 Health checks are not enabled".to_string(),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Shadow => {
+                let live = db.get_pnl_by_strategy().await.unwrap_or_default();
+                let shadow = db.get_shadow_pnl().await.unwrap_or_default();
+                let mut text = crate::notify::shadow_comparison(&live, &shadow);
+                if let Some(modes) = &self.strategy_modes {
+                    for (strategy, mode) in modes.all() {
+                        text.push_str(&format!("\n{}: {}", strategy, mode));
+                    }
+                }
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::SetStrategyMode { strategy, mode } => {
+                let Some(modes) = &self.strategy_modes else {
+                    let _ = self.notifier.send("❌ Strategy modes are not available").await;
+                    return;
+                };
+                let previous = modes.set(&strategy, mode);
+                let change = format!("{}: {} → {}", strategy, previous, mode);
+                self.journal(db, JournalEntry::manual(JournalKind::StrategyMode, change.clone())).await;
+                let _ = self
+                    .notifier
+                    .send(&format!("🔀 Strategy {}\nSet [strategy.modes] to keep it after a restart", change))
+                    .await;
+            }
+            BotCommand::Book { market_id } => {
+                self.send_book(client, &market_id).await;
+            }
+            BotCommand::Analyze { market_id } => {
+                self.send_dossier(client, db, &market_id).await;
+            }
+            BotCommand::Grade { market_id } => {
+                let text = match self.state.read().await.signal_scores.get(&market_id) {
+                    Some(score) => crate::notify::signal_grade(&market_id, score),
+                    None => format!(
+                        "🎓 No scored signal for <code>{}</code> yet",
+                        crate::notify::escape_html(&market_id)
+                    ),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::AddAlert { rule } => {
+                let text = match db.add_market_alert(&rule).await {
+                    Ok(id) => format!(
+                        "🔔 Alert #{} added: <code>{}</code> {}",
+                        id,
+                        crate::notify::escape_html(&rule.market_id),
+                        crate::notify::escape_html(&rule.condition())
+                    ),
+                    Err(e) => format!("❌ Failed to add alert: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::ListAlerts => {
+                let text = match db.get_market_alerts().await {
+                    Ok(alerts) => crate::notify::market_alert_list(&alerts),
+                    Err(e) => format!("❌ Failed to load alerts: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::DeleteAlert { id } => {
+                let text = match db.delete_market_alert(id).await {
+                    Ok(true) => format!("🔕 Alert #{} deleted", id),
+                    Ok(false) => format!("❌ No alert #{}", id),
+                    Err(e) => format!("❌ Failed to delete alert: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Trace { trade_id } => {
+                let text = match db.get_trade(&trade_id).await {
+                    Ok(Some(trade)) if trade.trace_id.is_nil() => {
+                        format!("🔍 Trade <code>{}</code> was not traced", trade_id)
+                    }
+                    Ok(Some(trade)) => match db.get_trace_events(trade.trace_id).await {
+                        Ok(events) => crate::notify::trace_report(&trade_id, &events),
+                        Err(e) => format!("❌ Failed to load trace: {}", e),
+                    },
+                    Ok(None) => format!("❌ Trade <code>{}</code> not found", trade_id),
+                    Err(e) => format!("❌ Failed to load trade: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Profile { market_id } => {
+                let text = match db.load_market_profile(&market_id).await {
+                    Ok(Some(profile)) => crate::notify::market_profile_report(&profile),
+                    Ok(None) => format!(
+                        "❌ No profile for <code>{}</code> yet",
+                        crate::notify::escape_html(&market_id)
+                    ),
+                    Err(e) => format!("❌ Failed to load profile: {}", e),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Watchlist => {
+                let text = match &self.pinned_markets {
+                    Some(pinned) if pinned.ids().is_empty() => "📌 No markets pinned".to_string(),
+                    Some(pinned) => {
+                        let ids: Vec<String> = pinned
+                            .ids()
+                            .iter()
+                            .map(|id| format!("<code>{}</code>", crate::notify::escape_html(id)))
+                            .collect();
+                        format!("📌 <b>Pinned markets</b>\n{}", ids.join("\n"))
+                    }
+                    None => "❌ The watchlist is not available".to_string(),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Watch { market_id } => {
+                let Some(pinned) = &self.pinned_markets else {
+                    let _ = self.notifier.send("❌ The watchlist is not available").await;
+                    return;
+                };
+                let text = match client.gamma.get_market(&market_id).await {
+                    Ok(market) if market.closed => {
+                        format!("❌ Market <code>{}</code> is closed", crate::notify::escape_html(&market_id))
+                    }
+                    Ok(market) => {
+                        pinned.add(&market.id);
+                        format!(
+                            "📌 Scanning <code>{}</code> every cycle: {}\nAdd it to [markets] watchlist to keep it after a restart",
+                            crate::notify::escape_html(&market.id),
+                            crate::notify::escape_html(&market.question)
+                        )
+                    }
+                    Err(e) => format!(
+                        "❌ Market <code>{}</code> not found: {}",
+                        crate::notify::escape_html(&market_id),
+                        crate::notify::escape_html(&e.to_string())
+                    ),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::Unwatch { market_id } => {
+                let text = match &self.pinned_markets {
+                    Some(pinned) if pinned.remove(&market_id) => {
+                        format!("📍 Unpinned <code>{}</code>", crate::notify::escape_html(&market_id))
+                    }
+                    Some(_) => format!("❌ <code>{}</code> is not pinned", crate::notify::escape_html(&market_id)),
+                    None => "❌ The watchlist is not available".to_string(),
+                };
+                let _ = self.notifier.send(&text).await;
+            }
+            BotCommand::AddMapping { token, market_id } => {
+                let Some(mapper) = &self.signal_mapper else {
+                    let _ = self.notifier.send("❌ Signal mapping is not available").await;
+                    return;
+                };
+                let text = match client.gamma.get_market(&market_id).await {
+                    Ok(market) if market.closed => {
+                        format!("❌ Market <code>{}</code> is closed", crate::notify::escape_html(&market_id))
+                    }
+                    Ok(market) => {
+                        mapper.add_literal(&token, &market.id);
+                        format!(
+                            "🔗 Signals on <code>{}</code> now map to <code>{}</code>: {}\nAdd a [[ingester.mapping.rules]] entry to keep it after a restart",
+                            crate::notify::escape_html(&token.to_uppercase()),
+                            crate::notify::escape_html(&market.id),
+                            crate::notify::escape_html(&market.question)
+                        )
+                    }
+                    Err(e) => format!(
+                        "❌ Market <code>{}</code> not found: {}",
+                        crate::notify::escape_html(&market_id),
+                        crate::notify::escape_html(&e.to_string())
+                    ),
+                };
+                let _ = self.notifier.send(&text).await;
             }
             BotCommand::Help => {}
         }
     }
 
-    async fn send_status(&self, client: &PolymarketClient) {
-        let balance = client.clob.get_balance().await.unwrap_or(Decimal::ZERO);
-        let open_orders = client.clob.get_open_orders().await.unwrap_or_default();
-        let state = self.state.read().await;
+    async fn journal(&self, db: &Database, entry: JournalEntry) {
+        if let Err(e) = db.add_journal_entry(&entry).await {
+            tracing::warn!("Failed to write journal entry: {}", e);
+        }
+    }
 
-        let status_emoji = if state.paused { "⏸" } else { "▶️" };
-        let status_text = if state.paused { "PAUSED" } else { "RUNNING" };
+    async fn send_rebalance_preview(&self, client: &PolymarketClient) {
+        let Some(rebalancer) = &self.rebalancer else {
+            let _ = self.notifier.send("⚖️ Rebalancing is not configured").await;
+            return;
+        };
 
-        let text = format!(
-            "💰 <b>Account Status</b>\n\n\
-            Status: {} {}\n\
-            Balance: <code>${:.2}</code> USDC\n\
-            Open Orders: {}\n\
-            Daily PnL: <code>{:+.2}</code>",
-            status_emoji, status_text,
-            balance,
-            open_orders.len(),
-            state.daily_pnl,
-        );
+        let rebalancer = rebalancer.lock().await;
+        match rebalancer.preview(client).await {
+            Ok((trades, _)) => {
+                let (plan, _) = rebalancer.execution_plan(trades);
+                let _ = self.notifier.send(&preview_message(&plan)).await;
+            }
+            Err(e) => {
+                let _ = self.notifier.error("Rebalance preview", &e.to_string()).await;
+            }
+        }
+    }
 
-        let _ = self.notifier.send(&text).await;
+    async fn execute_rebalance(&self, client: &PolymarketClient) {
+        let (Some(rebalancer), Some(executor)) = (&self.rebalancer, &self.executor) else {
+            let _ = self.notifier.send("⚖️ Rebalancing is not configured").await;
+            return;
+        };
+
+        match rebalancer.lock().await.rebalance(executor, client).await {
+            Ok(report) => {
+                let _ = self.notifier.send(&report.message()).await;
+            }
+            Err(e) => {
+                let _ = self.notifier.error("Rebalance", &e.to_string()).await;
+            }
+        }
+    }
+
+    async fn send_status(&self, client: &PolymarketClient, account: Option<&str>) {
+        let clients: Vec<(&str, &PolymarketClient)> = match (&self.accounts, account) {
+            (Some(accounts), None) => accounts.iter().map(|a| (a.id.as_str(), a.client.as_ref())).collect(),
+            (Some(accounts), Some(id)) => match accounts.get(id) {
+                Some(a) => vec![(a.id.as_str(), a.client.as_ref())],
+                None => {
+                    let _ = self.notifier.send(&unknown_account(id, &accounts.ids())).await;
+                    return;
+                }
+            },
+            (None, None) => vec![(DEFAULT_ACCOUNT_ID, client)],
+            (None, Some(id)) => {
+                let _ = self.notifier.send(&unknown_account(id, &[DEFAULT_ACCOUNT_ID])).await;
+                return;
+            }
+        };
+
+        let mut balances = Vec::new();
+        for (id, client) in clients {
+            let balance = client.clob.get_balance().await.unwrap_or(Decimal::ZERO);
+            let open_orders = client.clob.get_open_orders().await.map(|o| o.len()).unwrap_or(0);
+            balances.push((id.to_string(), balance, open_orders));
+        }
+        let state = self.state.read().await;
+
+        let _ = self.notifier.send(&status_message(&state, &balances, account.is_some())).await;
     }
 
     async fn send_markets(&self, client: &PolymarketClient, limit: usize) {
@@ -403,19 +1245,95 @@ impl CommandHandler {
         }
     }
 
-    async fn send_pnl(&self, _db: &Database) {
+    async fn send_book(&self, client: &PolymarketClient, market_id: &str) {
+        let market = match client.gamma.get_market(market_id).await {
+            Ok(market) => market,
+            Err(e) => {
+                let _ = self.notifier.error("Market fetch", &e.to_string()).await;
+                return;
+            }
+        };
+        let Some(yes) = market.outcomes.iter().find(|o| o.outcome.eq_ignore_ascii_case("yes")) else {
+            let _ = self.notifier.send("❌ Market has no Yes outcome").await;
+            return;
+        };
+
+        match client.clob.get_order_book(&yes.token_id).await {
+            Ok(book) => {
+                let analysis = analyze_book(&book);
+                let _ = self.notifier.send(&crate::notify::book_analysis(&market.question, &analysis)).await;
+            }
+            Err(e) => {
+                let _ = self.notifier.error("Order book fetch", &e.to_string()).await;
+            }
+        }
+    }
+
+    async fn send_dossier(&self, client: &PolymarketClient, db: &Database, market_id: &str) {
+        let fees = PolymarketFees::from_config(&self.config.fees.clone().unwrap_or_default());
+        let now = chrono::Utc::now();
+        match dossier::gather(market_id, &client.gamma, &client.clob, Some(db), now).await {
+            Ok(builder) => {
+                let dossier = builder.fees(Arc::new(fees)).build(now);
+                let _ = self.notifier.send(&crate::notify::market_dossier(&dossier)).await;
+            }
+            Err(e) => {
+                let _ = self.notifier.error("Market dossier", &e.to_string()).await;
+            }
+        }
+    }
+
+    async fn send_pnl(&self, db: &Database) {
         let state = self.state.read().await;
         let emoji = if state.daily_pnl >= Decimal::ZERO { "📈" } else { "📉" };
         
-        let text = format!(
+        let mut text = format!(
             "{} <b>Today's PnL</b>\n\n\
             PnL: <code>{:+.2}</code> USDC",
             emoji, state.daily_pnl
         );
+        if self.accounts.as_ref().is_some_and(|a| a.len() > 1) {
+            let by_account = db.get_pnl_by_account().await.unwrap_or_default();
+            text.push_str(&crate::notify::account_attribution(&by_account));
+        }
         
         let _ = self.notifier.send(&text).await;
     }
 
+    async fn send_account_pnl(&self, db: &Database, account: &str) {
+        let ids = match &self.accounts {
+            Some(accounts) => accounts.ids(),
+            None => vec![DEFAULT_ACCOUNT_ID],
+        };
+        let Some(id) = ids.iter().find(|id| id.eq_ignore_ascii_case(account)) else {
+            let _ = self.notifier.send(&unknown_account(account, &ids)).await;
+            return;
+        };
+
+        let pnl = db
+            .get_pnl_by_account()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| p.account == *id)
+            .unwrap_or_else(|| AccountPnl {
+                account: id.to_string(),
+                ..Default::default()
+            });
+        let today = db.get_account_daily_stats(Some(id)).await.unwrap_or_default();
+        let emoji = if pnl.realized_pnl >= Decimal::ZERO { "📈" } else { "📉" };
+
+        let text = format!(
+            "{} <b>PnL: {}</b>\n\n\
+            Realized: <code>{:+.2}</code> USDC\n\
+            Fees: <code>{:.2}</code> over {} trades\n\
+            Trades today: {}",
+            emoji, id, pnl.realized_pnl, pnl.fees, pnl.trades, today.total_trades,
+        );
+
+        let _ = self.notifier.send(&text).await;
+    }
+
     async fn send_positions(&self, client: &PolymarketClient) {
         match client.clob.get_positions().await {
             Ok(positions) => {
@@ -503,4 +1421,117 @@ impl CommandHandler {
     pub async fn is_paused(&self) -> bool {
         self.state.read().await.paused
     }
+
+    pub async fn is_shutting_down(&self) -> bool {
+        self.state.read().await.shutdown_requested
+    }
+}
+
+/// `/status` reply; `balances` holds (account, balance, open orders) and
+/// is broken down when there are several accounts
+pub(crate) fn status_message(state: &BotState, balances: &[(String, Decimal, usize)], named: bool) -> String {
+    let status_emoji = if state.paused { "⏸" } else { "▶️" };
+    let status_text = if state.paused { "PAUSED" } else { "RUNNING" };
+    let title = match balances {
+        [(id, _, _)] if named => format!("Account Status: {}", id),
+        _ => "Account Status".to_string(),
+    };
+    let balance: Decimal = balances.iter().map(|(_, b, _)| *b).sum();
+    let open_orders: usize = balances.iter().map(|(_, _, n)| n).sum();
+
+    let mut text = format!(
+        "💰 <b>{}</b>\n\n\
+        Status: {} {}\n\
+        Balance: <code>${:.2}</code> USDC\n\
+        Open Orders: {}\n\
+        Daily PnL: <code>{:+.2}</code>",
+        title,
+        status_emoji, status_text,
+        balance,
+        open_orders,
+        state.daily_pnl,
+    );
+    if state.pending_redemption > Decimal::ZERO {
+        text.push_str(&format!("\nPending redemption: <code>${:.2}</code>", state.pending_redemption));
+    }
+    if let Some(heat) = state.heat_utilization {
+        text.push_str(&format!(
+            "\n🌡️ Heat: {:.0}% {}",
+            heat * Decimal::ONE_HUNDRED,
+            progress_bar(heat, 10)
+        ));
+    }
+    if !state.ramp_levels.is_empty() {
+        let levels: Vec<String> = state
+            .ramp_levels
+            .iter()
+            .map(|(key, level)| format!("{} {}%", crate::notify::escape_html(key), (level * Decimal::ONE_HUNDRED).round_dp(0)))
+            .collect();
+        text.push_str(&format!("\n📈 Size ramp: {}", levels.join(", ")));
+    }
+    if balances.len() > 1 {
+        text.push_str("\n\n<b>By Account</b>");
+        for (id, balance, open_orders) in balances {
+            text.push_str(&format!("\n{}: <code>${:.2}</code> ({} open orders)", id, balance, open_orders));
+        }
+    }
+    text
+}
+
+fn unknown_account(account: &str, known: &[&str]) -> String {
+    format!("❌ Unknown account: {}\nAccounts: {}", account, known.join(", "))
+}
+
+/// Market ID of `/book <market_id>`
+pub(crate) fn parse_book_args(args: &str) -> Option<String> {
+    args.split_whitespace().next().map(str::to_string)
+}
+
+/// `/alert add <rule>`, `/alert list` or `/alert delete <id>`; errors are
+/// the reply explaining what's wrong
+pub(crate) fn parse_alert_args(args: &str) -> std::result::Result<BotCommand, String> {
+    const USAGE: &str = "Usage: /alert add <market_id> <metric> <op> <value> [cooldown] | /alert list | /alert delete <id>";
+    let (action, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+    match (action, rest.trim()) {
+        ("add", rule) => rule
+            .parse::<MarketAlertRule>()
+            .map(|rule| BotCommand::AddAlert { rule })
+            .map_err(|e| format!("Invalid alert: {}", e)),
+        ("list", "") => Ok(BotCommand::ListAlerts),
+        ("delete", id) => id
+            .parse()
+            .map(|id| BotCommand::DeleteAlert { id })
+            .map_err(|_| format!("Invalid alert ID `{}`", id)),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// Full analysis of a freshly fetched book; a single snapshot carries no
+/// trade flow, so VPIN is only available once trades have been fed in
+pub(crate) fn analyze_book(book: &OrderBook) -> OrderBookAnalysis {
+    let mut analyzer = OrderBookAnalyzer::new();
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    analyzer.process_snapshot(OrderBookSnapshot::from_book(book, now_ms));
+    analyzer.get_full_analysis()
+}
+
+/// Split `/note` arguments into an optional leading market ID and the note
+/// text. The first word counts as a market ID when it is numeric (Gamma ID)
+/// or a 0x condition ID and more text follows.
+pub(crate) fn parse_note_args(args: &str) -> Option<(Option<String>, String)> {
+    let args = args.trim();
+    if args.is_empty() {
+        return None;
+    }
+
+    if let Some((first, rest)) = args.split_once(char::is_whitespace) {
+        let is_market_id = first.chars().all(|c| c.is_ascii_digit())
+            || (first.starts_with("0x") && first.len() > 2 && first[2..].chars().all(|c| c.is_ascii_hexdigit()));
+        let rest = rest.trim();
+        if is_market_id && !rest.is_empty() {
+            return Some((Some(first.to_string()), rest.to_string()));
+        }
+    }
+
+    Some((None, args.to_string()))
 }
@@ -12,6 +12,7 @@ mod tests {
         assert!(!state.paused);
         assert_eq!(state.daily_pnl, Decimal::ZERO);
         assert!(!state.daily_loss_limit_hit);
+        assert!(!state.shutdown_requested);
     }
 
     #[test]
@@ -20,6 +21,11 @@ mod tests {
             paused: true,
             daily_pnl: dec!(100),
             daily_loss_limit_hit: false,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         };
         assert!(state.paused);
     }
@@ -30,6 +36,11 @@ mod tests {
             paused: false,
             daily_pnl: dec!(-500),
             daily_loss_limit_hit: true,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         };
         assert!(state.daily_loss_limit_hit);
         assert!(state.daily_pnl < Decimal::ZERO);
@@ -41,6 +52,11 @@ mod tests {
             paused: true,
             daily_pnl: dec!(250),
             daily_loss_limit_hit: false,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         };
         let cloned = state.clone();
         assert_eq!(state.paused, cloned.paused);
@@ -178,6 +194,11 @@ mod tests {
             paused: false,
             daily_pnl: dec!(1500),
             daily_loss_limit_hit: false,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         };
         assert!(state.daily_pnl > Decimal::ZERO);
     }
@@ -188,7 +209,232 @@ mod tests {
             paused: false,
             daily_pnl: dec!(-300),
             daily_loss_limit_hit: false,
+            shutdown_requested: false,
+            heat_utilization: None,
+            signal_scores: Default::default(),
+            ramp_levels: Vec::new(),
+            pending_redemption: Decimal::ZERO,
         };
         assert!(state.daily_pnl < Decimal::ZERO);
     }
+
+    #[test]
+    fn test_rebalance_confirm_keyboard() {
+        use super::super::{InlineKeyboardMarkup, REBALANCE_CANCEL, REBALANCE_CONFIRM};
+
+        let json = serde_json::to_value(InlineKeyboardMarkup::rebalance_confirm()).unwrap();
+        let row = &json["inline_keyboard"][0];
+        assert_eq!(row[0]["callback_data"], REBALANCE_CONFIRM);
+        assert_eq!(row[1]["callback_data"], REBALANCE_CANCEL);
+    }
+
+    #[test]
+    fn test_callback_update_deserializes() {
+        let update: super::super::TelegramUpdate = serde_json::from_str(
+            r#"{"update_id": 7, "callback_query": {"id": "cb1", "data": "rebalance:confirm",
+                "message": {"message_id": 1, "chat": {"id": 42}}}}"#,
+        )
+        .unwrap();
+        let query = update.callback_query.unwrap();
+        assert_eq!(query.data.as_deref(), Some("rebalance:confirm"));
+        assert_eq!(query.message.unwrap().chat.id, 42);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_checks_secret_token() {
+        use super::super::TelegramBot;
+        use crate::config::TelegramBotMode;
+        use std::sync::Arc;
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        let bot = Arc::new(TelegramBot::new("123:abc".to_string(), "42".to_string(), tx).with_mode(
+            TelegramBotMode::Webhook {
+                public_url: "https://bot.example.com".to_string(),
+                listen_port: 0,
+                secret_token: "s3cret".to_string(),
+            },
+        ));
+        let app = bot.clone().webhook_router("s3cret".to_string());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // A message from another chat: accepted, then ignored
+        let update = r#"{"update_id": 1, "message": {"message_id": 1, "chat": {"id": 7}, "text": "/status"}}"#;
+        let http = reqwest::Client::new();
+        let post = |token: &'static str, body: &'static str| {
+            http.post(&url)
+                .header("X-Telegram-Bot-Api-Secret-Token", token)
+                .body(body)
+                .send()
+        };
+        assert_eq!(post("wrong", update).await.unwrap().status(), 403);
+        assert_eq!(post("s3cre", update).await.unwrap().status(), 403);
+        assert_eq!(post("s3cret", update).await.unwrap().status(), 200);
+        assert_eq!(post("s3cret", "not json").await.unwrap().status(), 200);
+        assert_eq!(http.post(&url).body(update).send().await.unwrap().status(), 403);
+
+        let counters = bot.prometheus_counters();
+        assert!(counters.contains("# TYPE polymarket_bot_webhook_requests_total counter"));
+        assert!(counters.contains("polymarket_bot_webhook_requests_total{result=\"ok\"} 1\n"));
+        assert!(counters.contains("polymarket_bot_webhook_requests_total{result=\"invalid_token\"} 3\n"));
+        assert!(counters.contains("polymarket_bot_webhook_requests_total{result=\"parse_error\"} 1\n"));
+    }
+
+    #[test]
+    fn test_parse_note_args() {
+        use super::super::parse_note_args;
+
+        assert_eq!(
+            parse_note_args("fed pricing in a cut, trimming size"),
+            Some((None, "fed pricing in a cut, trimming size".to_string()))
+        );
+        assert_eq!(
+            parse_note_args("517310 thin book, skip"),
+            Some((Some("517310".to_string()), "thin book, skip".to_string()))
+        );
+        assert_eq!(
+            parse_note_args("0xabc123 watch resolution source"),
+            Some((Some("0xabc123".to_string()), "watch resolution source".to_string()))
+        );
+        // A lone ID is the note itself
+        assert_eq!(parse_note_args("517310"), Some((None, "517310".to_string())));
+        assert_eq!(parse_note_args("   "), None);
+    }
+
+    #[test]
+    fn test_parse_book_args() {
+        use super::super::parse_book_args;
+
+        assert_eq!(parse_book_args("517310"), Some("517310".to_string()));
+        assert_eq!(parse_book_args("  0xabc123  extra"), Some("0xabc123".to_string()));
+        assert_eq!(parse_book_args(""), None);
+    }
+
+    #[test]
+    fn test_parse_alert_args() {
+        use super::super::{parse_alert_args, BotCommand};
+        use crate::monitor::AlertMetric;
+
+        match parse_alert_args("add 517310 price > 0.70 30m") {
+            Ok(BotCommand::AddAlert { rule }) => {
+                assert_eq!(rule.market_id, "517310");
+                assert_eq!(rule.metric, AlertMetric::Price);
+                assert_eq!(rule.cooldown_secs, 1800);
+            }
+            other => panic!("expected an alert, got {:?}", other),
+        }
+        assert!(matches!(parse_alert_args("list"), Ok(BotCommand::ListAlerts)));
+        assert!(matches!(parse_alert_args(" delete  7 "), Ok(BotCommand::DeleteAlert { id: 7 })));
+
+        let err = parse_alert_args("add 517310 price ~ 0.70").unwrap_err();
+        assert!(err.starts_with("Invalid alert: unknown comparator"), "{}", err);
+        assert_eq!(parse_alert_args("delete seven").unwrap_err(), "Invalid alert ID `seven`");
+        for args in ["", "remove 7", "list all"] {
+            assert!(parse_alert_args(args).unwrap_err().starts_with("Usage: /alert add"), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_book_reply_shows_imbalance() {
+        use super::super::analyze_book;
+        use crate::client::{OrderBook, OrderBookLevel};
+
+        let level = |price, size| OrderBookLevel { price, size };
+        // Worst-first as the API returns them; 1500 bid vs 500 ask is +0.50
+        let book = OrderBook {
+            bids: vec![level(dec!(0.50), dec!(1000)), level(dec!(0.52), dec!(500))],
+            asks: vec![level(dec!(0.56), dec!(300)), level(dec!(0.54), dec!(200))],
+        };
+
+        let analysis = analyze_book(&book);
+        let imbalance = analysis.imbalance.as_ref().unwrap();
+        assert_eq!(imbalance.simple_imbalance, dec!(0.5));
+        assert_eq!(imbalance.best_bid, dec!(0.52));
+        assert_eq!(imbalance.best_ask, dec!(0.54));
+
+        let text = crate::notify::book_analysis("Will <b>BTC</b> close above 100k? ".repeat(4).trim(), &analysis);
+        assert!(text.contains("Imbalance: <code>+0.50</code>"), "{}", text);
+        assert!(text.contains("Toxicity: n/a"));
+        assert!(text.contains("Icebergs: none detected"));
+        assert!(text.contains("&lt;b&gt;BTC"));
+        assert!(text.contains("..."));
+
+        // A crossed book is rejected rather than analyzed
+        let crossed = OrderBook {
+            bids: vec![level(dec!(0.55), dec!(100))],
+            asks: vec![level(dec!(0.54), dec!(100))],
+        };
+        let analysis = analyze_book(&crossed);
+        assert!(analysis.imbalance.is_none());
+        assert_eq!(analysis.invalid_snapshots, 1);
+        let text = crate::notify::book_analysis("Crossed?", &analysis);
+        assert!(text.contains("Imbalance: n/a (crossed or locked book)"), "{}", text);
+    }
+
+    #[test]
+    fn test_status_message_breaks_down_accounts() {
+        use super::super::status_message;
+
+        let state = BotState::default();
+        let balances = vec![
+            ("personal".to_string(), dec!(1200), 2),
+            ("fund".to_string(), dec!(5000.5), 1),
+        ];
+        let text = status_message(&state, &balances, false);
+        assert!(text.contains("Balance: <code>$6200.50</code>"));
+        assert!(text.contains("Open Orders: 3"));
+        assert!(text.contains("personal: <code>$1200.00</code> (2 open orders)"));
+        assert!(text.contains("fund: <code>$5000.50</code> (1 open orders)"));
+
+        // One named account: no breakdown
+        let text = status_message(&state, &balances[1..], true);
+        assert!(text.contains("Account Status: fund"));
+        assert!(!text.contains("By Account"));
+
+        // Single-account configs read as before
+        let text = status_message(&state, &[("default".to_string(), dec!(100), 0)], false);
+        assert!(text.starts_with("💰 <b>Account Status</b>"));
+        assert!(!text.contains("By Account"));
+        assert!(!text.contains("Heat"));
+    }
+
+    #[test]
+    fn test_status_message_shows_heat() {
+        use super::super::status_message;
+
+        let state = BotState {
+            heat_utilization: Some(dec!(0.68)),
+            ..BotState::default()
+        };
+        let text = status_message(&state, &[("default".to_string(), dec!(100), 0)], false);
+        assert!(text.contains("\n🌡️ Heat: 68% ███████░░░"));
+    }
+
+    #[test]
+    fn test_status_message_shows_ramp_levels() {
+        use super::super::status_message;
+
+        let state = BotState {
+            ramp_levels: vec![("category:crypto".to_string(), dec!(0.35)), ("strategy:llm".to_string(), dec!(1))],
+            ..BotState::default()
+        };
+        let text = status_message(&state, &[("default".to_string(), dec!(100), 0)], false);
+        assert!(text.contains("\n📈 Size ramp: category:crypto 35%, strategy:llm 100%"));
+    }
+
+    #[test]
+    fn test_status_message_shows_pending_redemption() {
+        use super::super::status_message;
+
+        let balances = [("default".to_string(), dec!(100), 0)];
+        assert!(!status_message(&BotState::default(), &balances, false).contains("Pending redemption"));
+        let state = BotState {
+            pending_redemption: dec!(42.5),
+            ..BotState::default()
+        };
+        let text = status_message(&state, &balances, false);
+        assert!(text.contains("Balance: <code>$100.00</code>"));
+        assert!(text.contains("\nPending redemption: <code>$42.50</code>"));
+    }
 }
@@ -92,6 +92,7 @@ impl BenchmarkSuite {
             probability: dec!(0.65),
             confidence: dec!(0.80),
             reasoning: "Benchmark".to_string(),
+            fallback_used: None,
         };
 
         for market in &markets {
@@ -113,6 +114,7 @@ impl BenchmarkSuite {
             probability: dec!(0.60),
             confidence: dec!(0.75),
             reasoning: "Analysis".to_string(),
+            fallback_used: None,
         };
 
         for _ in 0..iterations {
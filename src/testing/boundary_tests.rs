@@ -120,6 +120,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         assert_eq!(market.yes_price(), Some(dec!(0.50)));
@@ -201,6 +205,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         assert_eq!(market.yes_price(), Some(dec!(0.001)));
@@ -310,6 +318,7 @@ mod tests {
             probability: dec!(0.501),  // Tiny edge
             confidence: dec!(0.90),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = generator.generate(&market, &prediction);
@@ -328,6 +337,7 @@ mod tests {
             probability: dec!(0.70),
             confidence: dec!(0.30),   // Low confidence
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = generator.generate(&market, &prediction);
@@ -346,6 +356,7 @@ mod tests {
             probability: dec!(0.70),
             confidence: dec!(1.0),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = generator.generate(&market, &prediction);
@@ -369,12 +380,17 @@ mod tests {
             outcomes: vec![],  // Empty outcomes
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let prediction = Prediction {
             probability: dec!(0.70),
             confidence: dec!(0.90),
             reasoning: "Test".to_string(),
+            fallback_used: None,
         };
         
         let signal = generator.generate(&market, &prediction);
@@ -431,6 +447,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         assert!(market.arbitrage_opportunity().is_none());
@@ -451,6 +471,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         assert!(market.arbitrage_opportunity().is_none());
@@ -471,6 +495,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         
         let arb = market.arbitrage_opportunity();
@@ -492,6 +520,13 @@ mod tests {
             confidence: dec!(0.60), // Exactly at threshold
             suggested_size: dec!(0.05),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         assert!(signal.is_tradeable(dec!(0.05), dec!(0.60)));
@@ -510,6 +545,13 @@ mod tests {
             confidence: dec!(0.80),
             suggested_size: dec!(0.05),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         };
         
         // Negative edge should use abs() in is_tradeable
@@ -600,6 +642,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 }
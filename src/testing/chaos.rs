@@ -0,0 +1,251 @@
+//! Fault injection for resilience tests
+//!
+//! [`ChaosInjector`] decides, call by call, whether to fail and how; the
+//! wrappers here put it in front of a CLOB client or a probability model so
+//! the rest of the bot sees timeouts, API errors, locked databases and rate
+//! limits exactly as it would from the real dependency.
+
+use crate::client::mock::ClobClientTrait;
+use crate::client::OrderBook;
+use crate::error::{BotError, Result};
+use crate::model::{Prediction, ProbabilityModel};
+use crate::types::{Market, Order, OrderStatus, Position};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// A kind of failure to inject
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorType {
+    NetworkTimeout,
+    /// Non-success HTTP status
+    ApiError(u16),
+    DatabaseLock,
+    RateLimited { retry_after_secs: u64 },
+}
+
+/// An injected failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChaosError {
+    pub error_type: ErrorType,
+}
+
+impl fmt::Display for ChaosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error_type {
+            ErrorType::NetworkTimeout => write!(f, "network timeout (injected)"),
+            ErrorType::ApiError(status) => write!(f, "HTTP {} (injected)", status),
+            ErrorType::DatabaseLock => write!(f, "database is locked (injected)"),
+            ErrorType::RateLimited { retry_after_secs } => {
+                write!(f, "rate limited for {}s (injected)", retry_after_secs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChaosError {}
+
+/// Injected failures surface as the error the real dependency would return
+impl From<ChaosError> for BotError {
+    fn from(err: ChaosError) -> Self {
+        match err.error_type {
+            ErrorType::NetworkTimeout | ErrorType::ApiError(_) => BotError::Api(err.to_string()),
+            ErrorType::DatabaseLock => BotError::Database(sqlx::Error::Protocol(err.to_string())),
+            ErrorType::RateLimited { retry_after_secs } => BotError::RateLimited { retry_after_secs },
+        }
+    }
+}
+
+/// Fails a share of calls with one of the configured error types
+pub struct ChaosInjector {
+    /// Chance of failing each call, 0.0 to 1.0
+    pub failure_rate: f64,
+    /// Delay added before every call
+    pub latency_inject_ms: u64,
+    /// Failures to pick from, uniformly; empty means network timeouts
+    pub error_types: Vec<ErrorType>,
+    rng: Mutex<StdRng>,
+    injected: AtomicU64,
+}
+
+impl ChaosInjector {
+    pub fn new(failure_rate: f64, latency_inject_ms: u64, error_types: Vec<ErrorType>) -> Self {
+        Self {
+            failure_rate,
+            latency_inject_ms,
+            error_types,
+            rng: Mutex::new(StdRng::from_os_rng()),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Make the failure sequence reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Roll for a failure on one call
+    pub fn maybe_inject(&self) -> Option<ChaosError> {
+        let mut rng = self.rng.lock();
+        if rng.random::<f64>() >= self.failure_rate {
+            return None;
+        }
+        let error_type = if self.error_types.is_empty() {
+            ErrorType::NetworkTimeout
+        } else {
+            self.error_types[rng.random_range(0..self.error_types.len())].clone()
+        };
+        self.injected.fetch_add(1, Ordering::Relaxed);
+        Some(ChaosError { error_type })
+    }
+
+    /// Failures injected so far
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    /// Run before each proxied call: add the latency, then maybe fail
+    async fn before_call(&self) -> Result<()> {
+        if self.latency_inject_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.latency_inject_ms)).await;
+        }
+        match self.maybe_inject() {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A CLOB client whose calls go through a [`ChaosInjector`] first
+///
+/// Wraps any client, including the live one, so an executor can be run
+/// against a flaky exchange.
+pub struct ChaosPolymarketClient<C: ClobClientTrait> {
+    pub inner: C,
+    pub injector: ChaosInjector,
+}
+
+impl<C: ClobClientTrait> ChaosPolymarketClient<C> {
+    pub fn new(inner: C, injector: ChaosInjector) -> Self {
+        Self { inner, injector }
+    }
+}
+
+#[async_trait]
+impl<C: ClobClientTrait> ClobClientTrait for ChaosPolymarketClient<C> {
+    async fn get_balance(&self) -> Result<Decimal> {
+        self.injector.before_call().await?;
+        self.inner.get_balance().await
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        self.injector.before_call().await?;
+        self.inner.get_order_book(token_id).await
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        self.injector.before_call().await?;
+        self.inner.place_order(order).await
+    }
+
+    async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        self.injector.before_call().await?;
+        self.inner.place_order_until(order, expiration).await
+    }
+
+    fn supports_gtd(&self) -> bool {
+        self.inner.supports_gtd()
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.injector.before_call().await?;
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn replace_order(&self, order_id: &str, order: &Order) -> Result<OrderStatus> {
+        self.injector.before_call().await?;
+        self.inner.replace_order(order_id, order).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        self.injector.before_call().await?;
+        self.inner.get_open_orders().await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.injector.before_call().await?;
+        self.inner.get_order(order_id).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        self.injector.before_call().await?;
+        self.inner.get_positions().await
+    }
+
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        self.injector.before_call().await?;
+        self.inner.get_token_holdings(token_id).await
+    }
+}
+
+/// A probability model whose predictions go through a [`ChaosInjector`]
+/// first, e.g. an LLM provider that is down
+pub struct ChaosModel<M: ProbabilityModel> {
+    pub inner: M,
+    pub injector: ChaosInjector,
+}
+
+impl<M: ProbabilityModel> ChaosModel<M> {
+    pub fn new(inner: M, injector: ChaosInjector) -> Self {
+        Self { inner, injector }
+    }
+}
+
+#[async_trait]
+impl<M: ProbabilityModel> ProbabilityModel for ChaosModel<M> {
+    async fn predict(&self, market: &Market) -> Result<Prediction> {
+        self.injector.before_call().await?;
+        self.inner.predict(market).await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_rate_bounds() {
+        let never = ChaosInjector::new(0.0, 0, vec![ErrorType::DatabaseLock]).with_seed(1);
+        assert!((0..1000).all(|_| never.maybe_inject().is_none()));
+
+        let always = ChaosInjector::new(1.0, 0, vec![ErrorType::ApiError(503)]).with_seed(1);
+        for _ in 0..100 {
+            assert_eq!(always.maybe_inject().unwrap().error_type, ErrorType::ApiError(503));
+        }
+        assert_eq!(always.injected(), 100);
+    }
+
+    #[test]
+    fn test_injected_errors_map_to_bot_errors() {
+        let rate_limited: BotError = ChaosError {
+            error_type: ErrorType::RateLimited { retry_after_secs: 30 },
+        }
+        .into();
+        assert!(matches!(rate_limited, BotError::RateLimited { retry_after_secs: 30 }));
+
+        for error_type in [ErrorType::NetworkTimeout, ErrorType::ApiError(500), ErrorType::DatabaseLock] {
+            let err: BotError = ChaosError { error_type }.into();
+            assert!(err.is_api_failure(), "{}", err);
+        }
+    }
+}
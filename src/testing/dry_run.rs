@@ -89,6 +89,18 @@ impl DryRunSimulator {
             model_update_interval_secs: 900,
             compound_enabled: true,
             compound_sqrt_scaling: true,
+            modes: std::collections::HashMap::new(),
+            high_epistemic_threshold: Decimal::new(15, 2),
+            accounts: std::collections::HashMap::new(),
+            experiment: None,
+            experiments: Default::default(),
+            bayesian_prior_strength: None,
+            regime_map: Default::default(),
+            regime_confidence_threshold: dec!(0.60),
+            max_concurrent_analyses: 4,
+            analysis_timeout_secs: 60,
+            seasonality: Default::default(),
+            scoring: Default::default(),
         };
         let risk_config = RiskConfig::default();
         
@@ -146,6 +158,7 @@ impl DryRunSimulator {
                 probability: model_prob,
                 confidence: dec!(0.80),  // High confidence
                 reasoning: "Dry run simulation".to_string(),
+                fallback_used: None,
             };
 
             // Generate signal
@@ -404,6 +404,7 @@ impl EnhancedDryRun {
             probability: prob,
             confidence: dec!(0.5) + self.random() * dec!(0.5),
             reasoning: "Dry run simulation".to_string(),
+            fallback_used: None,
         }
     }
 
@@ -0,0 +1,514 @@
+//! Scripted outages for resilience tests
+//!
+//! Where [`ChaosInjector`](super::ChaosInjector) fails calls at random, a
+//! [`FaultScript`] fails them on a timetable read from a [`Clock`]: "the
+//! CLOB times out from 12:05 to 12:15". The wrappers here put a script in
+//! front of the Gamma and CLOB clients, a notifier backend and the raw
+//! Binance trade stream, so a test can drive the scan loop with a
+//! [`ManualClock`](crate::clock::ManualClock) and check what the bot does
+//! during the outage and after it clears.
+
+use crate::client::mock::{ClobClientTrait, GammaClientTrait};
+use crate::client::OrderBook;
+use crate::clock::Clock;
+use crate::error::{BotError, Result};
+use crate::monitor::PerformanceStats;
+use crate::notify::NotifierBackend;
+use crate::types::{Market, Order, OrderStatus, Position, Signal, Trade};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A way for a dependency to misbehave
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// The request never gets an answer
+    Timeout,
+    /// Non-success HTTP status
+    ServerError(u16),
+    /// The response body doesn't parse
+    MalformedJson,
+    /// Timestamps in responses are off by this much
+    ClockSkew(Duration),
+    /// The last good response is served again
+    Stale,
+}
+
+impl Fault {
+    /// The error a call hitting this fault returns, if it fails outright
+    fn error(&self) -> Option<BotError> {
+        match self {
+            Fault::Timeout => Some(BotError::Api("request timed out (injected)".to_string())),
+            Fault::ServerError(status) => Some(BotError::Api(format!("HTTP {} (injected)", status))),
+            Fault::MalformedJson => Some(
+                serde_json::from_str::<serde_json::Value>("{\"data\": [")
+                    .expect_err("truncated JSON never parses")
+                    .into(),
+            ),
+            Fault::ClockSkew(_) | Fault::Stale => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FaultWindow {
+    fault: Fault,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+/// When each fault is active, on a given clock
+pub struct FaultScript {
+    clock: Arc<dyn Clock>,
+    windows: Vec<FaultWindow>,
+    injected: AtomicU64,
+}
+
+impl FaultScript {
+    /// A script with no faults
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            windows: Vec::new(),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Inject `fault` for `duration` from `start`; the first window that
+    /// covers the current time wins where windows overlap
+    pub fn during(mut self, fault: Fault, start: DateTime<Utc>, duration: Duration) -> Self {
+        self.windows.push(FaultWindow {
+            fault,
+            start,
+            end: start + duration,
+        });
+        self
+    }
+
+    /// The fault active now, if any
+    pub fn active(&self) -> Option<Fault> {
+        let now = self.clock.now();
+        self.windows
+            .iter()
+            .find(|w| w.start <= now && now < w.end)
+            .map(|w| w.fault.clone())
+    }
+
+    /// Calls a fault was injected into so far
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    /// Run before each proxied call: fail it, or hand back the fault the
+    /// response should be bent by
+    fn before_call(&self) -> Result<Option<Fault>> {
+        let Some(fault) = self.active() else {
+            return Ok(None);
+        };
+        self.injected.fetch_add(1, Ordering::Relaxed);
+        match fault.error() {
+            Some(err) => Err(err),
+            None => Ok(Some(fault)),
+        }
+    }
+}
+
+/// Last good responses, keyed by request, for stale windows
+struct LastGood<V> {
+    responses: Mutex<HashMap<String, V>>,
+}
+
+impl<V: Clone> LastGood<V> {
+    fn new() -> Self {
+        Self {
+            responses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The response to serve instead of asking, while stale
+    fn stale(&self, key: &str, fault: &Option<Fault>) -> Option<V> {
+        match fault {
+            Some(Fault::Stale) => self.responses.lock().get(key).cloned(),
+            _ => None,
+        }
+    }
+
+    fn remember(&self, key: &str, value: V) -> V {
+        self.responses.lock().insert(key.to_string(), value.clone());
+        value
+    }
+}
+
+/// A CLOB client that fails on a [`FaultScript`]'s timetable
+///
+/// While stale, order books are the last ones fetched; clock skew leaves
+/// CLOB responses alone, they carry no timestamps the bot reads.
+pub struct FaultyClob<C: ClobClientTrait> {
+    pub inner: C,
+    pub script: FaultScript,
+    books: LastGood<OrderBook>,
+}
+
+impl<C: ClobClientTrait> FaultyClob<C> {
+    pub fn new(inner: C, script: FaultScript) -> Self {
+        Self {
+            inner,
+            script,
+            books: LastGood::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ClobClientTrait> ClobClientTrait for FaultyClob<C> {
+    async fn get_balance(&self) -> Result<Decimal> {
+        self.script.before_call()?;
+        self.inner.get_balance().await
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        let fault = self.script.before_call()?;
+        if let Some(book) = self.books.stale(token_id, &fault) {
+            return Ok(book);
+        }
+        let book = self.inner.get_order_book(token_id).await?;
+        Ok(self.books.remember(token_id, book))
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        self.script.before_call()?;
+        self.inner.place_order(order).await
+    }
+
+    async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        self.script.before_call()?;
+        self.inner.place_order_until(order, expiration).await
+    }
+
+    fn supports_gtd(&self) -> bool {
+        self.inner.supports_gtd()
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.script.before_call()?;
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn replace_order(&self, order_id: &str, order: &Order) -> Result<OrderStatus> {
+        self.script.before_call()?;
+        self.inner.replace_order(order_id, order).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        self.script.before_call()?;
+        self.inner.get_open_orders().await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.script.before_call()?;
+        self.inner.get_order(order_id).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        self.script.before_call()?;
+        self.inner.get_positions().await
+    }
+
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        self.script.before_call()?;
+        self.inner.get_token_holdings(token_id).await
+    }
+}
+
+/// A Gamma client that fails on a [`FaultScript`]'s timetable
+///
+/// While stale, every call returns the last good answer to the same
+/// question; clock skew shifts market end dates.
+pub struct FaultyGamma<G: GammaClientTrait> {
+    pub inner: G,
+    pub script: FaultScript,
+    lists: LastGood<Vec<Market>>,
+    markets: LastGood<Market>,
+}
+
+impl<G: GammaClientTrait> FaultyGamma<G> {
+    pub fn new(inner: G, script: FaultScript) -> Self {
+        Self {
+            inner,
+            script,
+            lists: LastGood::new(),
+            markets: LastGood::new(),
+        }
+    }
+}
+
+fn skew_market(mut market: Market, fault: &Option<Fault>) -> Market {
+    if let Some(Fault::ClockSkew(skew)) = fault {
+        market.end_date = market.end_date.map(|end| end + *skew);
+    }
+    market
+}
+
+#[async_trait]
+impl<G: GammaClientTrait> GammaClientTrait for FaultyGamma<G> {
+    async fn get_top_markets(&self, limit: usize) -> Result<Vec<Market>> {
+        let fault = self.script.before_call()?;
+        let key = format!("top:{}", limit);
+        let markets = match self.lists.stale(&key, &fault) {
+            Some(markets) => markets,
+            None => self.lists.remember(&key, self.inner.get_top_markets(limit).await?),
+        };
+        Ok(markets.into_iter().map(|m| skew_market(m, &fault)).collect())
+    }
+
+    async fn get_market(&self, market_id: &str) -> Result<Market> {
+        let fault = self.script.before_call()?;
+        let market = match self.markets.stale(market_id, &fault) {
+            Some(market) => market,
+            None => self.markets.remember(market_id, self.inner.get_market(market_id).await?),
+        };
+        Ok(skew_market(market, &fault))
+    }
+
+    async fn get_crypto_markets(&self) -> Result<Vec<Market>> {
+        let fault = self.script.before_call()?;
+        let markets = match self.lists.stale("crypto", &fault) {
+            Some(markets) => markets,
+            None => self.lists.remember("crypto", self.inner.get_crypto_markets().await?),
+        };
+        Ok(markets.into_iter().map(|m| skew_market(m, &fault)).collect())
+    }
+}
+
+/// A notifier backend, e.g. Telegram, that fails on a [`FaultScript`]'s
+/// timetable; stale and skewed windows deliver as normal
+pub struct FaultyNotifier<B: NotifierBackend> {
+    pub inner: B,
+    pub script: FaultScript,
+}
+
+impl<B: NotifierBackend> FaultyNotifier<B> {
+    pub fn new(inner: B, script: FaultScript) -> Self {
+        Self { inner, script }
+    }
+}
+
+#[async_trait]
+impl<B: NotifierBackend> NotifierBackend for FaultyNotifier<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn signal_found(&self, signal: &Signal, market_question: &str) -> Result<()> {
+        self.script.before_call()?;
+        self.inner.signal_found(signal, market_question).await
+    }
+
+    async fn trade_executed(&self, trade: &Trade, market_question: &str) -> Result<()> {
+        self.script.before_call()?;
+        self.inner.trade_executed(trade, market_question).await
+    }
+
+    async fn daily_report(&self, stats: &PerformanceStats, balance: Decimal) -> Result<()> {
+        self.script.before_call()?;
+        self.inner.daily_report(stats, balance).await
+    }
+
+    async fn error(&self, context: &str, error: &str) -> Result<()> {
+        self.script.before_call()?;
+        self.inner.error(context, error).await
+    }
+}
+
+/// A notifier backend that keeps what it is sent, for asserting on alerts
+#[derive(Debug, Default)]
+pub struct RecordingNotifier {
+    errors: Mutex<Vec<(String, String)>>,
+    trades: AtomicU64,
+    signals: AtomicU64,
+}
+
+impl RecordingNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Error alerts received, as (context, error)
+    pub fn errors(&self) -> Vec<(String, String)> {
+        self.errors.lock().clone()
+    }
+
+    pub fn trades(&self) -> u64 {
+        self.trades.load(Ordering::Relaxed)
+    }
+
+    pub fn signals(&self) -> u64 {
+        self.signals.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl NotifierBackend for RecordingNotifier {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn signal_found(&self, _signal: &Signal, _market_question: &str) -> Result<()> {
+        self.signals.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn trade_executed(&self, _trade: &Trade, _market_question: &str) -> Result<()> {
+        self.trades.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn daily_report(&self, _stats: &PerformanceStats, _balance: Decimal) -> Result<()> {
+        Ok(())
+    }
+
+    async fn error(&self, context: &str, error: &str) -> Result<()> {
+        self.errors.lock().push((context.to_string(), error.to_string()));
+        Ok(())
+    }
+}
+
+/// The Binance trade stream as it would arrive over the socket, bent by a
+/// [`FaultScript`]
+///
+/// Timeouts and server errors drop the message; malformed JSON replaces it
+/// with garbage (cut-off JSON, negative or non-numeric prices, missing
+/// payload); stale repeats the last good message; clock skew shifts the
+/// trade time.
+pub struct FaultyBinanceFeed {
+    pub script: FaultScript,
+    last: Mutex<Option<String>>,
+}
+
+impl FaultyBinanceFeed {
+    pub fn new(script: FaultScript) -> Self {
+        Self {
+            script,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// The message the bot receives in place of `message`, if any
+    pub fn deliver(&self, message: &str) -> Option<String> {
+        let fault = match self.script.before_call() {
+            Ok(fault) => fault,
+            Err(BotError::Json(_)) => return Some(self.garbage(message)),
+            Err(_) => return None,
+        };
+        match fault {
+            None => {
+                *self.last.lock() = Some(message.to_string());
+                Some(message.to_string())
+            }
+            Some(Fault::Stale) => Some(self.last.lock().clone().unwrap_or_else(|| message.to_string())),
+            Some(Fault::ClockSkew(skew)) => Some(skew_trade_time(message, skew)),
+            Some(_) => None,
+        }
+    }
+
+    fn garbage(&self, message: &str) -> String {
+        let with_price = |price: &str| {
+            serde_json::from_str::<serde_json::Value>(message)
+                .map(|mut v| {
+                    v["data"]["p"] = serde_json::Value::String(price.to_string());
+                    v.to_string()
+                })
+                .unwrap_or_default()
+        };
+        match self.script.injected() % 4 {
+            0 => message.chars().take(message.len() / 2).collect(),
+            1 => with_price("-0.01"),
+            2 => with_price("NaN"),
+            _ => "{\"stream\":\"btcusdt@trade\"}".to_string(),
+        }
+    }
+}
+
+/// Shift the "T" trade time of a combined-stream trade message
+fn skew_trade_time(message: &str, skew: Duration) -> String {
+    match serde_json::from_str::<serde_json::Value>(message) {
+        Ok(mut v) => {
+            if let Some(time) = v["data"]["T"].as_i64() {
+                v["data"]["T"] = (time + skew.num_milliseconds()).into();
+            }
+            v.to_string()
+        }
+        Err(_) => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::testing::Scenario;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_faults_are_active_only_inside_their_window() {
+        let clock = Arc::new(ManualClock::new(start()));
+        let script = FaultScript::new(clock.clone())
+            .during(Fault::ServerError(503), start() + Duration::minutes(5), Duration::minutes(10));
+
+        assert_eq!(script.active(), None);
+        clock.advance(5 * 60_000);
+        assert_eq!(script.active(), Some(Fault::ServerError(503)));
+        clock.advance(10 * 60_000 - 1);
+        assert_eq!(script.active(), Some(Fault::ServerError(503)));
+        clock.advance(1);
+        assert_eq!(script.active(), None);
+    }
+
+    #[test]
+    fn test_injected_faults_map_to_bot_errors() {
+        assert!(Fault::Timeout.error().unwrap().is_api_failure());
+        assert!(Fault::ServerError(502).error().unwrap().is_api_failure());
+        assert!(matches!(Fault::MalformedJson.error(), Some(BotError::Json(_))));
+        assert!(Fault::Stale.error().is_none());
+        assert!(Fault::ClockSkew(Duration::seconds(30)).error().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stale_clob_serves_last_good_book() {
+        let clock = Arc::new(ManualClock::new(start()));
+        let script = FaultScript::new(clock.clone()).during(Fault::Stale, start() + Duration::minutes(1), Duration::minutes(1));
+        let mock = Scenario::new()
+            .book("t0", &[(dec!(0.40), dec!(100))], &[(dec!(0.42), dec!(100))])
+            .build();
+        let clob = FaultyClob::new(mock, script);
+
+        let fresh = clob.get_order_book("t0").await.unwrap();
+        assert_eq!(fresh.best_bid(), Some(dec!(0.40)));
+
+        clock.advance(60_000);
+        let stale = clob.get_order_book("t0").await.unwrap();
+        assert_eq!(stale.best_bid(), Some(dec!(0.40)));
+        assert_eq!(clob.script.injected(), 1);
+    }
+
+    #[test]
+    fn test_binance_feed_skews_trade_time() {
+        let clock = Arc::new(ManualClock::new(start()));
+        let script = FaultScript::new(clock).during(Fault::ClockSkew(Duration::seconds(-90)), start(), Duration::minutes(1));
+        let feed = FaultyBinanceFeed::new(script);
+
+        let message = r#"{"stream":"btcusdt@trade","data":{"s":"BTCUSDT","p":"60000.00","T":1767268800000}}"#;
+        let delivered: serde_json::Value = serde_json::from_str(&feed.deliver(message).unwrap()).unwrap();
+        assert_eq!(delivered["data"]["T"], 1767268800000i64 - 90_000);
+        assert_eq!(delivered["data"]["p"], "60000.00");
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Utilities for generating test data
 
-use crate::types::{Market, Outcome, Signal, Side, Order, OrderType, Trade};
+use crate::types::{Market, Outcome, Signal, Side, Order, OrderType, Trade, TradeStatus};
 use chrono::{Utc, Duration};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -44,6 +44,10 @@ impl TestDataGenerator {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -71,6 +75,10 @@ impl TestDataGenerator {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -95,6 +103,13 @@ impl TestDataGenerator {
             confidence: dec!(0.75),
             suggested_size: dec!(0.03),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         }
     }
 
@@ -123,6 +138,12 @@ impl TestDataGenerator {
             size: Decimal::from(50),
             fee: dec!(0.50),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         }
     }
 
@@ -125,12 +125,17 @@ impl IntegrationTestHarness {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
 
         let prediction = crate::model::Prediction {
             probability: dec!(0.65),
             confidence: dec!(0.80),
             reasoning: "Test prediction".to_string(),
+            fallback_used: None,
         };
 
         match self.signal_gen.generate(&market, &prediction) {
@@ -0,0 +1,740 @@
+//! Scriptable mock exchange for execution tests
+//!
+//! `MockClob` implements `ClobClientTrait` over a virtual clock. Order books
+//! can be scheduled to change at given ticks, and individual orders can be
+//! scripted to rest, fill in pieces or be rejected, so a test reads like the
+//! market it describes:
+//!
+//! ```ignore
+//! let clob = Scenario::new()
+//!     .book("t1", &[(dec!(0.54), dec!(500))], &[(dec!(0.56), dec!(500))])
+//!     .next_order(OrderScript::new().rests_for(2).fills(dec!(0.4)))
+//!     .book_at(3, "t1", &[(dec!(0.50), dec!(500))], &[(dec!(0.52), dec!(500))])
+//!     .build();
+//! ```
+//!
+//! Unscripted orders match against the book: the crossing part fills at
+//! once level by level (so large orders fill partially), FOK orders that
+//! can't fill completely are killed, and the rest rests. Each tick a resting
+//! order fills if the book has moved through its price, or with
+//! `fill_probability` if it sits at the near touch.
+//!
+//! The synchronous [`MockClob::submit`] and [`MockClob::advance`] let the
+//! backtester drive the same model without an async runtime.
+
+use crate::client::mock::ClobClientTrait;
+use crate::client::{OrderBook, OrderBookLevel};
+use crate::error::{BotError, Result};
+use crate::types::{Order, OrderStatus, OrderType, Position, Side};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Why the exchange refuses an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    InsufficientBalance,
+    PriceOutOfRange,
+}
+
+/// One step of an order's scripted life
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderStep {
+    /// Refuse the order at placement
+    Reject(Rejection),
+    /// Do nothing for this many ticks
+    Rest(u64),
+    /// Fill this fraction of the original size at the order's price
+    Fill(Decimal),
+}
+
+/// Scripted behavior for one order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderScript {
+    steps: Vec<OrderStep>,
+}
+
+impl OrderScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rejected(mut self, reason: Rejection) -> Self {
+        self.steps.push(OrderStep::Reject(reason));
+        self
+    }
+
+    pub fn rests_for(mut self, ticks: u64) -> Self {
+        self.steps.push(OrderStep::Rest(ticks));
+        self
+    }
+
+    pub fn fills(mut self, fraction: Decimal) -> Self {
+        self.steps.push(OrderStep::Fill(fraction));
+        self
+    }
+}
+
+/// A fill on the mock exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockFill {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub tick: u64,
+}
+
+/// Declarative setup for a [`MockClob`]
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    balance: Decimal,
+    books: HashMap<String, OrderBook>,
+    default_book: Option<OrderBook>,
+    book_events: Vec<(u64, String, OrderBook)>,
+    scripts: VecDeque<OrderScript>,
+    positions: HashMap<String, Decimal>,
+    fill_probability: f64,
+    latency: Duration,
+    seed: u64,
+    gtd: bool,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            balance: dec!(1000),
+            books: HashMap::new(),
+            default_book: None,
+            book_events: Vec::new(),
+            scripts: VecDeque::new(),
+            positions: HashMap::new(),
+            fill_probability: 0.0,
+            latency: Duration::ZERO,
+            seed: 0,
+            gtd: true,
+        }
+    }
+}
+
+fn levels(levels: &[(Decimal, Decimal)]) -> Vec<OrderBookLevel> {
+    levels
+        .iter()
+        .map(|&(price, size)| OrderBookLevel { price, size })
+        .collect()
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starting cash (default $1000)
+    pub fn balance(mut self, balance: Decimal) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    /// Book for a token, best level first: `(price, size)`
+    pub fn book(mut self, token_id: &str, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> Self {
+        self.books.insert(
+            token_id.to_string(),
+            OrderBook { bids: levels(bids), asks: levels(asks) },
+        );
+        self
+    }
+
+    /// Book quoted for tokens without their own
+    pub fn default_book(mut self, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> Self {
+        self.default_book = Some(OrderBook { bids: levels(bids), asks: levels(asks) });
+        self
+    }
+
+    /// Replace a token's book when the clock reaches `tick`
+    pub fn book_at(
+        mut self,
+        tick: u64,
+        token_id: &str,
+        bids: &[(Decimal, Decimal)],
+        asks: &[(Decimal, Decimal)],
+    ) -> Self {
+        self.book_events.push((
+            tick,
+            token_id.to_string(),
+            OrderBook { bids: levels(bids), asks: levels(asks) },
+        ));
+        self
+    }
+
+    /// Script the next order placed (scripts apply to orders in placement order)
+    pub fn next_order(mut self, script: OrderScript) -> Self {
+        self.scripts.push_back(script);
+        self
+    }
+
+    /// Reject the next order placed
+    pub fn reject_next(self, reason: Rejection) -> Self {
+        self.next_order(OrderScript::new().rejected(reason))
+    }
+
+    /// Shares already held of a token
+    pub fn position(mut self, token_id: &str, shares: Decimal) -> Self {
+        self.positions.insert(token_id.to_string(), shares);
+        self
+    }
+
+    /// Chance per tick that an unscripted order resting at the near touch fills
+    pub fn fill_probability(mut self, p: f64) -> Self {
+        self.fill_probability = p;
+        self
+    }
+
+    /// Delay applied to every call through `ClobClientTrait`
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Seed for the fill-probability draws
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Act as a venue without GTD orders: expirations are ignored
+    pub fn without_gtd(mut self) -> Self {
+        self.gtd = false;
+        self
+    }
+
+    pub fn build(self) -> MockClob {
+        let mut book_events = self.book_events;
+        book_events.sort_by_key(|(tick, _, _)| *tick);
+
+        MockClob {
+            latency: self.latency,
+            gtd: self.gtd,
+            fill_probability: self.fill_probability,
+            default_book: self.default_book,
+            state: Mutex::new(ClobState {
+                tick: 0,
+                balance: self.balance,
+                books: self.books,
+                book_events: book_events.into(),
+                scripts: self.scripts,
+                positions: self.positions,
+                orders: Vec::new(),
+                fills: Vec::new(),
+                rng: StdRng::seed_from_u64(self.seed),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MockOrderState {
+    order_id: String,
+    order: Order,
+    filled: Decimal,
+    /// Notional filled, for the average price
+    filled_notional: Decimal,
+    cancelled: bool,
+    script: VecDeque<OrderStep>,
+    /// Scripted steps wait until this tick
+    resume_at: u64,
+    scripted: bool,
+    /// GTD expiry (unix seconds) the order was placed with
+    expiration: Option<i64>,
+}
+
+impl MockOrderState {
+    fn remaining(&self) -> Decimal {
+        self.order.size - self.filled
+    }
+
+    fn is_open(&self) -> bool {
+        !self.cancelled && self.remaining() > Decimal::ZERO
+    }
+
+    fn status(&self) -> OrderStatus {
+        let status = if self.cancelled {
+            "CANCELLED"
+        } else if self.remaining().is_zero() {
+            "FILLED"
+        } else {
+            "OPEN"
+        };
+        OrderStatus {
+            order_id: self.order_id.clone(),
+            status: status.to_string(),
+            filled_size: self.filled,
+            remaining_size: self.remaining(),
+            avg_price: (!self.filled.is_zero()).then(|| self.filled_notional / self.filled),
+        }
+    }
+}
+
+struct ClobState {
+    tick: u64,
+    balance: Decimal,
+    books: HashMap<String, OrderBook>,
+    book_events: VecDeque<(u64, String, OrderBook)>,
+    scripts: VecDeque<OrderScript>,
+    positions: HashMap<String, Decimal>,
+    orders: Vec<MockOrderState>,
+    fills: Vec<MockFill>,
+    rng: StdRng,
+}
+
+impl ClobState {
+    fn fill(&mut self, index: usize, price: Decimal, size: Decimal) {
+        let tick = self.tick;
+        let order = &mut self.orders[index];
+        let size = size.min(order.remaining());
+        if size <= Decimal::ZERO {
+            return;
+        }
+        order.filled += size;
+        order.filled_notional += price * size;
+
+        let (token_id, side, order_id) = (order.order.token_id.clone(), order.order.side, order.order_id.clone());
+        let position = self.positions.entry(token_id.clone()).or_default();
+        match side {
+            Side::Buy => {
+                *position += size;
+                self.balance -= price * size;
+            }
+            Side::Sell => {
+                *position -= size;
+                self.balance += price * size;
+            }
+        }
+        self.fills.push(MockFill { order_id, token_id, side, price, size, tick });
+    }
+
+    /// Run an order's script up to the current tick
+    fn run_script(&mut self, index: usize) {
+        loop {
+            let order = &mut self.orders[index];
+            if !order.is_open() || order.resume_at > self.tick {
+                return;
+            }
+            match order.script.pop_front() {
+                Some(OrderStep::Rest(ticks)) => order.resume_at = self.tick + ticks,
+                Some(OrderStep::Fill(fraction)) => {
+                    let (price, size) = (order.order.price, order.order.size * fraction);
+                    self.fill(index, price, size);
+                }
+                // Rejections only apply at placement
+                Some(OrderStep::Reject(_)) => {}
+                None => return,
+            }
+        }
+    }
+
+    /// Fill an unscripted order against the book, consuming liquidity
+    fn match_against_book(&mut self, index: usize, book: &mut OrderBook) {
+        let (side, limit) = (self.orders[index].order.side, self.orders[index].order.price);
+        let levels = match side {
+            Side::Buy => &mut book.asks,
+            Side::Sell => &mut book.bids,
+        };
+        for level in levels.iter_mut() {
+            let crosses = match side {
+                Side::Buy => level.price <= limit,
+                Side::Sell => level.price >= limit,
+            };
+            if !crosses || self.orders[index].remaining().is_zero() {
+                break;
+            }
+            let size = level.size.min(self.orders[index].remaining());
+            self.fill(index, level.price, size);
+            level.size -= size;
+        }
+        levels.retain(|l| l.size > Decimal::ZERO);
+    }
+}
+
+/// Scriptable mock CLOB on a virtual clock
+pub struct MockClob {
+    latency: Duration,
+    gtd: bool,
+    fill_probability: f64,
+    default_book: Option<OrderBook>,
+    state: Mutex<ClobState>,
+}
+
+impl MockClob {
+    /// Current virtual tick
+    pub fn now(&self) -> u64 {
+        self.state.lock().tick
+    }
+
+    pub fn balance(&self) -> Decimal {
+        self.state.lock().balance
+    }
+
+    /// All fills so far
+    pub fn fills(&self) -> Vec<MockFill> {
+        self.state.lock().fills.clone()
+    }
+
+    /// Status of an order by ID
+    pub fn order(&self, order_id: &str) -> Option<OrderStatus> {
+        let state = self.state.lock();
+        state.orders.iter().find(|o| o.order_id == order_id).map(MockOrderState::status)
+    }
+
+    /// GTD expiry (unix seconds) an order was placed with
+    pub fn expiration(&self, order_id: &str) -> Option<i64> {
+        let state = self.state.lock();
+        state.orders.iter().find(|o| o.order_id == order_id).and_then(|o| o.expiration)
+    }
+
+    /// Shares held of a token
+    pub fn holdings(&self, token_id: &str) -> Decimal {
+        self.state.lock().positions.get(token_id).copied().unwrap_or(Decimal::ZERO)
+    }
+
+    /// Current book for a token
+    pub fn book(&self, token_id: &str) -> Option<OrderBook> {
+        self.book_for(&self.state.lock(), token_id)
+    }
+
+    fn book_for(&self, state: &ClobState, token_id: &str) -> Option<OrderBook> {
+        state.books.get(token_id).or(self.default_book.as_ref()).cloned()
+    }
+
+    /// Place an order without the simulated latency
+    pub fn submit(&self, order: &Order) -> Result<OrderStatus> {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        let script = state.scripts.pop_front();
+
+        let rejection = match script.as_ref().and_then(|s| s.steps.first()) {
+            Some(OrderStep::Reject(reason)) => Some(*reason),
+            _ if order.price <= Decimal::ZERO || order.price >= Decimal::ONE => Some(Rejection::PriceOutOfRange),
+            _ if order.side == Side::Buy && order.price * order.size > state.balance => {
+                Some(Rejection::InsufficientBalance)
+            }
+            _ => None,
+        };
+        match rejection {
+            Some(Rejection::InsufficientBalance) => {
+                return Err(BotError::InsufficientBalance {
+                    required: order.price * order.size,
+                    available: state.balance,
+                })
+            }
+            Some(Rejection::PriceOutOfRange) => {
+                return Err(BotError::OrderRejected(format!("price {} out of range", order.price)))
+            }
+            None => {}
+        }
+
+        let order_id = format!("mock_{}", state.orders.len() + 1);
+        let index = state.orders.len();
+        let resume_at = state.tick;
+        state.orders.push(MockOrderState {
+            order_id,
+            order: order.clone(),
+            filled: Decimal::ZERO,
+            filled_notional: Decimal::ZERO,
+            cancelled: false,
+            script: script.as_ref().map(|s| s.steps.iter().copied().collect()).unwrap_or_default(),
+            resume_at,
+            scripted: script.is_some(),
+            expiration: None,
+        });
+
+        if script.is_some() {
+            state.run_script(index);
+        } else if let Some(mut book) = self.book_for(state, &order.token_id) {
+            if order.order_type == OrderType::FOK {
+                let available: Decimal = match order.side {
+                    Side::Buy => book.asks.iter().filter(|l| l.price <= order.price).map(|l| l.size).sum(),
+                    Side::Sell => book.bids.iter().filter(|l| l.price >= order.price).map(|l| l.size).sum(),
+                };
+                if available >= order.size {
+                    state.match_against_book(index, &mut book);
+                }
+            } else {
+                state.match_against_book(index, &mut book);
+            }
+            state.books.insert(order.token_id.clone(), book);
+        }
+
+        // Fill-or-kill never rests
+        if order.order_type == OrderType::FOK && state.orders[index].is_open() {
+            state.orders[index].cancelled = true;
+        }
+
+        Ok(state.orders[index].status())
+    }
+
+    /// Move the virtual clock forward, applying scheduled book changes and
+    /// filling resting orders
+    pub fn advance(&self, ticks: u64) {
+        for _ in 0..ticks {
+            let mut guard = self.state.lock();
+            let state = &mut *guard;
+            state.tick += 1;
+            let tick = state.tick;
+
+            while state.book_events.front().is_some_and(|(at, _, _)| *at <= tick) {
+                let (_, token_id, book) = state.book_events.pop_front().unwrap();
+                state.books.insert(token_id, book);
+            }
+
+            for index in 0..state.orders.len() {
+                if !state.orders[index].is_open() {
+                    continue;
+                }
+                if state.orders[index].scripted {
+                    state.run_script(index);
+                    continue;
+                }
+
+                let token_id = state.orders[index].order.token_id.clone();
+                let Some(mut book) = self.book_for(state, &token_id) else { continue };
+                state.match_against_book(index, &mut book);
+
+                // Passive fill at the near touch
+                let (side, price) = (state.orders[index].order.side, state.orders[index].order.price);
+                let at_touch = match side {
+                    Side::Buy => book.best_bid().is_none_or(|b| price >= b),
+                    Side::Sell => book.best_ask().is_none_or(|a| price <= a),
+                };
+                if state.orders[index].is_open() && at_touch && self.fill_probability > 0.0 {
+                    let draw: f64 = state.rng.random();
+                    if draw < self.fill_probability {
+                        let size = state.orders[index].remaining();
+                        state.fill(index, price, size);
+                    }
+                }
+                state.books.insert(token_id, book);
+            }
+        }
+    }
+
+    async fn simulate_latency(&self) {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ClobClientTrait for MockClob {
+    async fn get_balance(&self) -> Result<Decimal> {
+        self.simulate_latency().await;
+        Ok(self.balance())
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        self.simulate_latency().await;
+        self.book(token_id)
+            .ok_or_else(|| BotError::MarketNotFound(token_id.to_string()))
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<OrderStatus> {
+        self.simulate_latency().await;
+        self.submit(order)
+    }
+
+    async fn place_order_until(&self, order: &Order, expiration: i64) -> Result<OrderStatus> {
+        self.simulate_latency().await;
+        let status = self.submit(order)?;
+        if self.gtd {
+            let mut state = self.state.lock();
+            if let Some(placed) = state.orders.iter_mut().find(|o| o.order_id == status.order_id) {
+                placed.expiration = Some(expiration);
+            }
+        }
+        Ok(status)
+    }
+
+    fn supports_gtd(&self) -> bool {
+        self.gtd
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.state.lock();
+        if let Some(order) = state.orders.iter_mut().find(|o| o.order_id == order_id) {
+            order.cancelled = true;
+        }
+        Ok(())
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderStatus>> {
+        self.simulate_latency().await;
+        let state = self.state.lock();
+        Ok(state
+            .orders
+            .iter()
+            .filter(|o| o.is_open())
+            .map(MockOrderState::status)
+            .collect())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.simulate_latency().await;
+        self.order(order_id)
+            .ok_or_else(|| BotError::Execution(format!("Unknown order {}", order_id)))
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        self.simulate_latency().await;
+        let state = self.state.lock();
+        Ok(state
+            .positions
+            .iter()
+            .filter(|(_, size)| !size.is_zero())
+            .map(|(token_id, size)| Position {
+                token_id: token_id.clone(),
+                market_id: String::new(),
+                side: if *size > Decimal::ZERO { Side::Buy } else { Side::Sell },
+                size: size.abs(),
+                avg_entry_price: Decimal::ZERO,
+                current_price: Decimal::ZERO,
+                unrealized_pnl: Decimal::ZERO,
+            })
+            .collect())
+    }
+
+    async fn get_token_holdings(&self, token_id: &str) -> Result<Decimal> {
+        self.simulate_latency().await;
+        Ok(self.holdings(token_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: Side, price: Decimal, size: Decimal, order_type: OrderType) -> Order {
+        Order { token_id: "t1".to_string(), side, price, size, order_type }
+    }
+
+    fn t1_book() -> Scenario {
+        Scenario::new().book(
+            "t1",
+            &[(dec!(0.54), dec!(100)), (dec!(0.53), dec!(200))],
+            &[(dec!(0.56), dec!(100)), (dec!(0.57), dec!(200))],
+        )
+    }
+
+    #[test]
+    fn test_crossing_order_walks_the_book() {
+        let clob = t1_book().build();
+        let status = clob.submit(&order(Side::Buy, dec!(0.57), dec!(150), OrderType::GTC)).unwrap();
+
+        assert_eq!(status.status, "FILLED");
+        // 100 @ 0.56 + 50 @ 0.57
+        assert_eq!(status.avg_price, Some(dec!(84.5) / dec!(150)));
+        assert_eq!(clob.holdings("t1"), dec!(150));
+        assert_eq!(clob.book("t1").unwrap().best_ask(), Some(dec!(0.57)));
+    }
+
+    #[test]
+    fn test_partial_fill_rests_remainder_and_fok_is_killed() {
+        let clob = t1_book().build();
+        let status = clob.submit(&order(Side::Buy, dec!(0.56), dec!(150), OrderType::GTC)).unwrap();
+        assert_eq!(status.status, "OPEN");
+        assert_eq!(status.filled_size, dec!(100));
+        assert_eq!(status.remaining_size, dec!(50));
+
+        let status = clob.submit(&order(Side::Sell, dec!(0.54), dec!(500), OrderType::FOK)).unwrap();
+        assert_eq!(status.status, "CANCELLED");
+        assert_eq!(status.filled_size, dec!(0));
+    }
+
+    #[test]
+    fn test_scripted_rest_partial_fill_then_book_moves_away() {
+        let clob = t1_book()
+            .next_order(OrderScript::new().rests_for(2).fills(dec!(0.4)))
+            .book_at(3, "t1", &[(dec!(0.48), dec!(100))], &[(dec!(0.50), dec!(100))])
+            .build();
+
+        let status = clob.submit(&order(Side::Sell, dec!(0.55), dec!(100), OrderType::GTC)).unwrap();
+        assert_eq!(status.status, "OPEN");
+        assert_eq!(status.filled_size, dec!(0));
+
+        clob.advance(1);
+        assert_eq!(clob.order(&status.order_id).unwrap().filled_size, dec!(0));
+        clob.advance(1);
+        let after = clob.order(&status.order_id).unwrap();
+        assert_eq!(after.filled_size, dec!(40));
+        assert_eq!(after.avg_price, Some(dec!(0.55)));
+
+        clob.advance(5);
+        assert_eq!(clob.order(&status.order_id).unwrap().filled_size, dec!(40));
+        assert_eq!(clob.book("t1").unwrap().best_bid(), Some(dec!(0.48)));
+        assert_eq!(clob.fills()[0].tick, 2);
+    }
+
+    #[test]
+    fn test_resting_order_fills_when_book_trades_through() {
+        let clob = t1_book()
+            .book_at(2, "t1", &[(dec!(0.50), dec!(100))], &[(dec!(0.52), dec!(300))])
+            .build();
+
+        let status = clob.submit(&order(Side::Buy, dec!(0.53), dec!(100), OrderType::GTC)).unwrap();
+        clob.advance(1);
+        assert_eq!(clob.order(&status.order_id).unwrap().status, "OPEN");
+        clob.advance(1);
+        let after = clob.order(&status.order_id).unwrap();
+        assert_eq!(after.status, "FILLED");
+        assert_eq!(after.avg_price, Some(dec!(0.52)));
+    }
+
+    #[test]
+    fn test_fill_probability_at_touch() {
+        let clob = t1_book().fill_probability(1.0).build();
+        let status = clob.submit(&order(Side::Buy, dec!(0.55), dec!(10), OrderType::GTC)).unwrap();
+        assert_eq!(status.status, "OPEN");
+        clob.advance(1);
+        assert_eq!(clob.order(&status.order_id).unwrap().status, "FILLED");
+
+        // Behind the touch never fills passively
+        let clob = t1_book().fill_probability(1.0).build();
+        let status = clob.submit(&order(Side::Buy, dec!(0.50), dec!(10), OrderType::GTC)).unwrap();
+        clob.advance(10);
+        assert_eq!(clob.order(&status.order_id).unwrap().status, "OPEN");
+    }
+
+    #[test]
+    fn test_rejections() {
+        let clob = t1_book().reject_next(Rejection::InsufficientBalance).build();
+        assert!(matches!(
+            clob.submit(&order(Side::Buy, dec!(0.56), dec!(1), OrderType::GTC)),
+            Err(BotError::InsufficientBalance { .. })
+        ));
+        // Scripts are consumed in order; the next one is unscripted
+        assert!(clob.submit(&order(Side::Buy, dec!(0.56), dec!(1), OrderType::GTC)).is_ok());
+
+        assert!(matches!(
+            clob.submit(&order(Side::Buy, dec!(1.2), dec!(1), OrderType::GTC)),
+            Err(BotError::OrderRejected(_))
+        ));
+        assert!(matches!(
+            clob.submit(&order(Side::Buy, dec!(0.56), dec!(10000), OrderType::GTC)),
+            Err(BotError::InsufficientBalance { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_latency_applies_to_trait_calls() {
+        let clob = t1_book().latency(Duration::from_millis(20)).build();
+        let start = std::time::Instant::now();
+        clob.get_balance().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
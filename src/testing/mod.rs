@@ -8,6 +8,9 @@
 //! - Performance benchmarks
 //! - Test data generators
 //! - Boundary condition tests
+//! - Scriptable mock CLOB for execution tests
+//! - Fault injection for resilience tests
+//! - Scripted outages on a test clock
 
 pub mod dry_run;
 pub mod integration;
@@ -15,6 +18,9 @@ pub mod generators;
 pub mod benchmarks;
 pub mod enhanced_dry_run;
 pub mod optimized_simulator;
+pub mod mock_clob;
+pub mod chaos;
+pub mod faults;
 
 #[cfg(test)]
 mod boundary_tests;
@@ -26,3 +32,6 @@ pub use integration::IntegrationTestHarness;
 pub use generators::TestDataGenerator;
 pub use enhanced_dry_run::{EnhancedDryRun, EnhancedDryRunConfig, EnhancedSimResult};
 pub use optimized_simulator::EnhancedDryRunSimulator;
+pub use mock_clob::{MockClob, MockFill, OrderScript, OrderStep, Rejection, Scenario};
+pub use chaos::{ChaosError, ChaosInjector, ChaosModel, ChaosPolymarketClient, ErrorType};
+pub use faults::{Fault, FaultScript, FaultyBinanceFeed, FaultyClob, FaultyGamma, FaultyNotifier, RecordingNotifier};
@@ -462,6 +462,7 @@ impl EnhancedDryRunSimulator {
             probability: prob,
             confidence,
             reasoning: "Optimized dry run simulation".to_string(),
+            fallback_used: None,
         }
     }
     
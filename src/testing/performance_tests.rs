@@ -65,6 +65,7 @@ mod tests {
                 probability: prob,
                 confidence: dec!(0.80),
                 reasoning: "Test".to_string(),
+                fallback_used: None,
             };
             let _ = generator.generate(&market, &prediction);
         }
@@ -135,6 +136,7 @@ mod tests {
                     probability: dec!(0.65),
                     confidence: dec!(0.80),
                     reasoning: "Test".to_string(),
+                    fallback_used: None,
                 };
                 let _ = generator.generate(market, &prediction);
             }
@@ -171,6 +173,7 @@ mod tests {
                     probability: dec!(0.65),
                     confidence: dec!(0.80),
                     reasoning: "Test".to_string(),
+                    fallback_used: None,
                 };
                 
                 if let Some(signal) = generator.generate(market, &prediction) {
@@ -306,6 +309,7 @@ mod tests {
                     probability: dec!(0.65),
                     confidence: dec!(0.80),
                     reasoning: "Test".to_string(),
+                    fallback_used: None,
                 };
                 let _ = generator.generate(&market, &prediction);
                 count += 2;
@@ -381,6 +385,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -408,6 +416,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 }
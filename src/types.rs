@@ -16,6 +16,28 @@ pub struct Market {
     pub outcomes: Vec<Outcome>,
     pub active: bool,
     pub closed: bool,
+    /// Gamma event this market belongs to
+    #[serde(default)]
+    pub event_id: Option<String>,
+    /// Part of a negRisk event: exactly one of the event's markets resolves
+    /// YES, and a NO share converts into YES on every other market
+    #[serde(default)]
+    pub neg_risk: bool,
+    /// Gamma category, e.g. "Crypto" or "Politics"
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Exchange limits on orders, when Gamma reports them
+    #[serde(default)]
+    pub order_constraints: Option<OrderConstraints>,
+}
+
+/// Price and size limits the CLOB enforces on a market's orders
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderConstraints {
+    /// Price increment, e.g. 0.01
+    pub tick_size: Decimal,
+    /// Smallest accepted order, in shares
+    pub min_order_size: Decimal,
 }
 
 /// An outcome (Yes/No) in a market
@@ -26,6 +48,54 @@ pub struct Outcome {
     pub price: Decimal,  // 0.00 - 1.00
 }
 
+/// Broad market category, from Gamma's free-form category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum MarketCategory {
+    Crypto,
+    Politics,
+    Sports,
+    Economics,
+    Culture,
+    #[default]
+    Other,
+}
+
+impl MarketCategory {
+    /// Category of a Gamma category name, `Other` when unknown or missing
+    pub fn from_gamma(category: Option<&str>) -> Self {
+        let Some(category) = category else {
+            return Self::Other;
+        };
+        let category = category.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| category.contains(w));
+        if has(&["crypto", "bitcoin", "ethereum"]) {
+            Self::Crypto
+        } else if has(&["politic", "election", "geopolitic"]) {
+            Self::Politics
+        } else if has(&["sport", "nba", "nfl", "soccer", "football"]) {
+            Self::Sports
+        } else if has(&["econom", "business", "finance"]) {
+            Self::Economics
+        } else if has(&["culture", "entertainment", "music", "movie"]) {
+            Self::Culture
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Lowercase name, as used in config keys
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Crypto => "crypto",
+            Self::Politics => "politics",
+            Self::Sports => "sports",
+            Self::Economics => "economics",
+            Self::Culture => "culture",
+            Self::Other => "other",
+        }
+    }
+}
+
 /// Order side
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -65,18 +135,74 @@ pub struct OrderStatus {
     pub avg_price: Option<Decimal>,
 }
 
+/// Correlates a signal's log lines and records from ingestion to the
+/// trades it leads to. The nil ID (the default) marks records made before
+/// tracing or outside a traced pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TraceId(pub uuid::Uuid);
+
+impl TraceId {
+    /// A fresh random ID
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn is_nil(&self) -> bool {
+        self.0.is_nil()
+    }
+}
+
+impl std::fmt::Display for TraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for TraceId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        uuid::Uuid::parse_str(s).map(Self)
+    }
+}
+
 /// A trading signal generated by the strategy
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Signal {
     pub market_id: String,
     pub token_id: String,
     pub side: Side,
     pub model_probability: Decimal,
     pub market_probability: Decimal,
+    /// Model minus market probability, net of the round trip's fees where
+    /// the strategy charges them
     pub edge: Decimal,
     pub confidence: Decimal,
     pub suggested_size: Decimal,
     pub timestamp: DateTime<Utc>,
+    /// Strategy that produced the signal, when running several at once
+    pub strategy_name: Option<String>,
+    /// Disagreement between the ensemble's models (std dev of their
+    /// probabilities); zero when not measured
+    pub epistemic_uncertainty: Decimal,
+    /// Variance of the Bayesian posterior the model probability was taken
+    /// from; zero when not measured
+    #[serde(default)]
+    pub posterior_variance: Decimal,
+    #[serde(default)]
+    pub trace_id: TraceId,
+    /// Attribution tags (strategy, model, prompt hash, experiment) copied
+    /// onto the trades the signal produces
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the edge is considered stale: the signal is not traded after
+    /// it, and its resting orders expire then. `None` never expires
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
+    /// Multi-factor score (0-1) from the `SignalScorer`, once scored
+    #[serde(default)]
+    pub signal_score: Option<f64>,
 }
 
 /// Portfolio position
@@ -91,6 +217,26 @@ pub struct Position {
     pub unrealized_pnl: Decimal,
 }
 
+/// Whether a trade record has filled or is resting on the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeStatus {
+    /// Matched against the book
+    #[default]
+    Filled,
+    /// Accepted by the exchange, waiting for a counterparty
+    Resting,
+}
+
+impl TradeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeStatus::Filled => "filled",
+            TradeStatus::Resting => "resting",
+        }
+    }
+}
+
 /// Trade execution record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -103,6 +249,23 @@ pub struct Trade {
     pub size: Decimal,
     pub fee: Decimal,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub status: TradeStatus,
+    /// Strategy the trade is attributed to
+    #[serde(default)]
+    pub strategy_name: Option<String>,
+    /// Account the trade was placed on
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// Trace of the signal the trade executed
+    #[serde(default)]
+    pub trace_id: TraceId,
+    /// Attribution tags of the signal the trade executed
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Edge of the signal the trade executed
+    #[serde(default)]
+    pub edge: Option<Decimal>,
 }
 
 impl Market {
@@ -122,6 +285,27 @@ impl Market {
             .map(|o| o.price)
     }
 
+    /// Broad category of the market
+    pub fn market_category(&self) -> MarketCategory {
+        MarketCategory::from_gamma(self.category.as_deref())
+    }
+
+    /// Whether YES won, once the market has closed with its outcome
+    /// prices settled at 0 and 1; `None` while it is still undecided
+    pub fn resolved_yes(&self) -> Option<bool> {
+        if !self.closed {
+            return None;
+        }
+        let yes = self.yes_price()?;
+        if yes >= Decimal::new(99, 2) {
+            Some(true)
+        } else if yes <= Decimal::new(1, 2) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
     /// Check if there's an arbitrage opportunity (Yes + No < 1)
     pub fn arbitrage_opportunity(&self) -> Option<Decimal> {
         let yes = self.yes_price()?;
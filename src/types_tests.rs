@@ -147,6 +147,12 @@ mod tests {
             size: dec!(100),
             fee: dec!(0.50),
             timestamp: Utc::now(),
+            status: TradeStatus::Filled,
+            strategy_name: None,
+            account_id: None,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            edge: None,
         };
         assert_eq!(trade.fee, dec!(0.50));
     }
@@ -175,6 +181,16 @@ mod tests {
         assert_eq!(status.filled_size, dec!(100));
     }
 
+    #[test]
+    fn test_market_category_from_gamma() {
+        assert_eq!(MarketCategory::from_gamma(Some("Crypto")), MarketCategory::Crypto);
+        assert_eq!(MarketCategory::from_gamma(Some("US-current-affairs Politics")), MarketCategory::Politics);
+        assert_eq!(MarketCategory::from_gamma(Some("NBA Playoffs")), MarketCategory::Sports);
+        assert_eq!(MarketCategory::from_gamma(Some("Pop Culture")), MarketCategory::Culture);
+        assert_eq!(MarketCategory::from_gamma(Some("Science")), MarketCategory::Other);
+        assert_eq!(MarketCategory::from_gamma(None), MarketCategory::Other);
+    }
+
     #[test]
     fn test_market_empty_outcomes() {
         let market = Market {
@@ -187,6 +203,10 @@ mod tests {
             outcomes: vec![],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         };
         assert_eq!(market.yes_price(), None);
         assert_eq!(market.no_price(), None);
@@ -216,6 +236,10 @@ mod tests {
             ],
             active: true,
             closed: false,
+            event_id: None,
+            neg_risk: false,
+            category: None,
+            order_constraints: None,
         }
     }
 
@@ -230,6 +254,13 @@ mod tests {
             confidence,
             suggested_size: dec!(100),
             timestamp: Utc::now(),
+            strategy_name: None,
+            epistemic_uncertainty: Decimal::ZERO,
+            trace_id: Default::default(),
+            tags: Vec::new(),
+            posterior_variance: Decimal::ZERO,
+            valid_until: None,
+            signal_score: None,
         }
     }
 }
@@ -1,7 +1,40 @@
 //! Shared utility functions
 
+pub mod ascii_chart;
+pub mod ema;
+
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use std::time::Duration;
+
+/// Timeout of API requests
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout of LLM completions, which can take a while to generate
+pub const LLM_HTTP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// HTTP client whose requests give up after `timeout`, so a server that
+/// never answers cannot hang the task waiting on it
+pub fn http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(timeout.min(Duration::from_secs(10)))
+        .timeout(timeout)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+/// Standard normal CDF (Abramowitz and Stegun 7.1.26 erf, error below 1.5e-7)
+pub fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
 
 /// Approximate square root using Newton's method
 /// 
@@ -0,0 +1,66 @@
+//! Text charts for chat messages and terminal output
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One block character per value, scaled from the lowest to the highest.
+/// Longer series are downsampled to `width` characters, each the last value
+/// of its stretch; a flat series is drawn at mid height.
+pub fn sparkline(values: &[Decimal], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    let sampled: Vec<Decimal> = if values.len() <= width {
+        values.to_vec()
+    } else {
+        (1..=width).map(|i| values[i * values.len() / width - 1]).collect()
+    };
+
+    let min = sampled.iter().copied().min().unwrap_or_default();
+    let max = sampled.iter().copied().max().unwrap_or_default();
+    let top = Decimal::from(BARS.len() - 1);
+    sampled
+        .iter()
+        .map(|v| {
+            if max == min {
+                return BARS[BARS.len() / 2 - 1];
+            }
+            let level = ((*v - min) / (max - min) * top).round();
+            BARS[level.to_usize().unwrap_or(0).min(BARS.len() - 1)]
+        })
+        .collect()
+}
+
+/// `width` characters, the first `fraction` of them filled (rounded);
+/// fractions past 1 fill the whole bar
+pub fn progress_bar(fraction: Decimal, width: usize) -> String {
+    let filled = (fraction * Decimal::from(width)).round().to_usize().unwrap_or(0).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_sparkline_scales_and_downsamples() {
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[dec!(1), dec!(2), dec!(3), dec!(4), dec!(5), dec!(6), dec!(7), dec!(8)], 10), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[dec!(5), dec!(5)], 10), "▄▄");
+
+        // 100 rising values into 4 characters: the last of each quarter
+        let rising: Vec<Decimal> = (0..100).map(Decimal::from).collect();
+        assert_eq!(sparkline(&rising, 4), "▁▃▆█");
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(dec!(0.68), 10), "███████░░░");
+        assert_eq!(progress_bar(Decimal::ZERO, 4), "░░░░");
+        assert_eq!(progress_bar(dec!(1.7), 4), "████");
+        assert_eq!(progress_bar(dec!(-0.2), 4), "░░░░");
+    }
+}
@@ -0,0 +1,59 @@
+//! Exponential moving average
+
+use rust_decimal::Decimal;
+
+/// EMA seeded with the first value it sees
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialMovingAverage {
+    /// Weight of each new value, in (0, 1]
+    pub alpha: Decimal,
+    pub value: Option<Decimal>,
+}
+
+impl ExponentialMovingAverage {
+    pub fn new(alpha: Decimal) -> Self {
+        Self { alpha, value: None }
+    }
+
+    /// The usual `2 / (period + 1)` smoothing for an EMA over `period` samples
+    pub fn with_period(period: u32) -> Self {
+        Self::new(Decimal::TWO / Decimal::from(period + 1))
+    }
+
+    /// Fold in `price` and return the new average
+    pub fn update(&mut self, price: Decimal) -> Decimal {
+        let value = match self.value {
+            Some(prev) => prev + self.alpha * (price - prev),
+            None => price,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    pub fn current(&self) -> Option<Decimal> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_ema_smooths_toward_new_values() {
+        let mut ema = ExponentialMovingAverage::new(dec!(0.5));
+        assert_eq!(ema.current(), None);
+        assert_eq!(ema.update(dec!(100)), dec!(100));
+        assert_eq!(ema.update(dec!(110)), dec!(105));
+        assert_eq!(ema.update(dec!(110)), dec!(107.5));
+        assert_eq!(ema.current(), Some(dec!(107.5)));
+    }
+
+    #[test]
+    fn test_period_alpha() {
+        assert_eq!(ExponentialMovingAverage::with_period(5).alpha.round_dp(3), dec!(0.333));
+        assert_eq!(ExponentialMovingAverage::with_period(20).alpha.round_dp(3), dec!(0.095));
+        assert_eq!(ExponentialMovingAverage::with_period(1).alpha, Decimal::ONE);
+    }
+}